@@ -2,6 +2,7 @@
 //!
 //! Reads AI-related settings from `~/.casparian_flow/config.toml`
 
+use casparian_protocol::LlmSettings;
 use serde::Deserialize;
 use std::path::Path;
 
@@ -54,6 +55,10 @@ pub struct AiConfig {
     /// LLM provider: "llamacpp" (default) or "disabled"
     #[serde(default)]
     pub provider: AiProvider,
+
+    /// Remote/local LLM providers for MCP schema inference (`[ai.llm]`)
+    #[serde(default)]
+    pub llm: LlmSettings,
 }
 
 fn default_enabled() -> bool {
@@ -65,6 +70,7 @@ impl Default for AiConfig {
         Self {
             enabled: default_enabled(),
             provider: AiProvider::default(),
+            llm: LlmSettings::default(),
         }
     }
 }
@@ -137,6 +143,35 @@ mod tests {
         assert_eq!(config.provider, AiProvider::Llamacpp);
     }
 
+    #[test]
+    fn test_load_llm_providers() {
+        let temp = TempDir::new().unwrap();
+        let config_path = temp.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+            [ai.llm]
+            requests_per_minute = 10
+            session_token_budget = 20000
+
+            [[ai.llm.providers]]
+            provider = "anthropic"
+
+            [[ai.llm.providers]]
+            provider = "ollama"
+            endpoint = "http://localhost:11434"
+            model = "llama3"
+            "#,
+        )
+        .unwrap();
+
+        let config = load_ai_config(&config_path).unwrap();
+        assert_eq!(config.llm.providers.len(), 2);
+        assert_eq!(config.llm.requests_per_minute, 10);
+        assert_eq!(config.llm.session_token_budget, 20000);
+        assert_eq!(config.llm.providers[1].model, "llama3");
+    }
+
     #[test]
     fn test_nonexistent_file() {
         let temp = TempDir::new().unwrap();
@@ -0,0 +1,183 @@
+//! `casparian audit` command - query the control-plane audit tape.
+//!
+//! Routing rule changes, plugin publishes and rollbacks, approval decisions
+//! and parser config updates are appended to the audit tape next to the
+//! state store, with the actor and before/after snapshots.
+//!
+//! # Usage
+//!
+//! ```bash
+//! casparian audit                                  # last 50 entries
+//! casparian audit --entity plugin:sales_parser     # one plugin's history
+//! casparian audit --entity routing_rule --since 2026-01-01 --until 2026-02-01
+//! casparian audit --actor alice --json
+//! ```
+
+use anyhow::Result;
+use casparian_sentinel::db::{audit, AuditEntry, AuditLog, AuditQuery};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Args;
+
+use super::config;
+use super::error::HelpfulError;
+use super::output::print_table;
+
+/// Arguments for the `audit` command
+#[derive(Debug, Args)]
+pub struct AuditArgs {
+    /// Filter by entity: KIND or KIND:ID (kinds: routing_rule, plugin, approval, config, artifact)
+    #[arg(long)]
+    pub entity: Option<String>,
+
+    /// Filter by actor
+    #[arg(long)]
+    pub actor: Option<String>,
+
+    /// Only entries at or after this time (RFC 3339 or YYYY-MM-DD)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only entries before this time (RFC 3339 or YYYY-MM-DD)
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Show at most this many of the most recent entries
+    #[arg(long, default_value = "50")]
+    pub limit: usize,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: AuditArgs) -> Result<()> {
+    let query = build_query(&args)?;
+    let log = AuditLog::for_sqlite(&config::state_store_path());
+    let entries = log.query(&query)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No audit entries found");
+        if let Some(path) = log.path() {
+            println!("  Audit tape: {}", path.display());
+        }
+        return Ok(());
+    }
+
+    let rows = entries.iter().map(entry_row).collect();
+    print_table(&["TIME", "ENTITY", "ID", "ACTION", "ACTOR", "CHANGE"], rows);
+    Ok(())
+}
+
+fn build_query(args: &AuditArgs) -> Result<AuditQuery> {
+    let mut query = AuditQuery::new().limit(args.limit);
+    if let Some(entity) = &args.entity {
+        let (kind, id) = match entity.split_once(':') {
+            Some((kind, id)) => (kind, Some(id)),
+            None => (entity.as_str(), None),
+        };
+        let kinds = [
+            audit::entity::ROUTING_RULE,
+            audit::entity::PLUGIN,
+            audit::entity::APPROVAL,
+            audit::entity::CONFIG,
+            audit::entity::ARTIFACT,
+        ];
+        if !kinds.contains(&kind) {
+            return Err(HelpfulError::new(format!("Unknown entity kind '{}'", kind))
+                .with_suggestion(format!("TRY: One of {}", kinds.join(", ")))
+                .into());
+        }
+        query = query.entity_kind(kind);
+        if let Some(id) = id {
+            query = query.entity_id(id);
+        }
+    }
+    if let Some(actor) = &args.actor {
+        query = query.actor(actor);
+    }
+    if let Some(since) = &args.since {
+        query = query.since(parse_time(since, "--since")?);
+    }
+    if let Some(until) = &args.until {
+        query = query.until(parse_time(until, "--until")?);
+    }
+    Ok(query)
+}
+
+/// Parse an RFC 3339 timestamp, or a date (midnight UTC).
+fn parse_time(raw: &str, flag: &str) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight").and_utc());
+    }
+    Err(
+        HelpfulError::new(format!("Invalid {} time '{}'", flag, raw))
+            .with_suggestion("TRY: Use 2026-01-31 or 2026-01-31T12:00:00Z")
+            .into(),
+    )
+}
+
+fn entry_row(entry: &AuditEntry) -> Vec<String> {
+    let record = &entry.record;
+    vec![
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+        record.entity_kind.clone(),
+        record.entity_id.clone(),
+        record.action.clone(),
+        record.actor.clone(),
+        change_summary(record.before.as_ref(), record.after.as_ref()),
+    ]
+}
+
+/// Top-level fields that differ between the snapshots, e.g. "enabled: true -> false".
+fn change_summary(before: Option<&serde_json::Value>, after: Option<&serde_json::Value>) -> String {
+    let (Some(serde_json::Value::Object(before)), Some(serde_json::Value::Object(after))) =
+        (before, after)
+    else {
+        return String::new();
+    };
+    let mut changes = Vec::new();
+    for (key, new) in after {
+        let old = before.get(key).unwrap_or(&serde_json::Value::Null);
+        if old != new {
+            changes.push(format!("{}: {} -> {}", key, old, new));
+        }
+    }
+    for key in before.keys().filter(|key| !after.contains_key(*key)) {
+        changes.push(format!("{}: {} -> null", key, before[key]));
+    }
+    changes.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_time_accepts_date_and_rfc3339() {
+        let date = parse_time("2026-01-31", "--since").unwrap();
+        assert_eq!(date.to_rfc3339(), "2026-01-31T00:00:00+00:00");
+        let time = parse_time("2026-01-31T12:00:00+02:00", "--since").unwrap();
+        assert_eq!(time.to_rfc3339(), "2026-01-31T10:00:00+00:00");
+        assert!(parse_time("yesterday", "--since").is_err());
+    }
+
+    #[test]
+    fn test_change_summary_lists_changed_fields() {
+        let before = json!({ "tag": "sales", "enabled": true });
+        let after = json!({ "tag": "sales", "enabled": false });
+        assert_eq!(
+            change_summary(Some(&before), Some(&after)),
+            "enabled: true -> false"
+        );
+        assert_eq!(change_summary(None, Some(&after)), "");
+    }
+}
@@ -0,0 +1,218 @@
+//! `casparian compaction` command - coalesce small Parquet output files.
+//!
+//! Policies are set per output topic (output name); the `*` topic applies to
+//! outputs without their own policy. The Sentinel compacts topics whose
+//! policy is met every few minutes; `run` compacts one topic now.
+//!
+//! # Usage
+//!
+//! ```bash
+//! casparian compaction set orders --min-files 32 --target-size 512MB
+//! casparian compaction set '*' --min-size 1GB
+//! casparian compaction list
+//! casparian compaction run orders --dry-run
+//! casparian compaction run --json            # every topic whose policy is met
+//! ```
+
+use anyhow::Result;
+use casparian_db::DbConnection;
+use casparian_sentinel::db::compaction::{self, CompactionPolicy, DEFAULT_POLICY_TOPIC};
+use casparian_sentinel::{compact_topic, run_due_compactions, CompactOptions, CompactionReport};
+use clap::Subcommand;
+
+use super::config;
+use super::error::HelpfulError;
+use super::output::{format_size, parse_size, print_table};
+
+#[derive(Debug, Subcommand)]
+pub enum CompactionAction {
+    /// Set the compaction policy of an output topic ('*' for the default)
+    Set {
+        topic: String,
+        /// Compact once a directory holds this many small files
+        #[arg(long)]
+        min_files: Option<u32>,
+        /// Compact once a directory's small files add up to this, e.g. 1GB
+        #[arg(long)]
+        min_size: Option<String>,
+        /// Size of compacted files, e.g. 256MB
+        #[arg(long)]
+        target_size: Option<String>,
+    },
+    /// List compaction policies
+    List {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Remove the compaction policy of an output topic
+    Remove { topic: String },
+    /// Compact a topic now, or every topic whose policy is met
+    Run {
+        topic: Option<String>,
+        /// Report the files that would be compacted without rewriting them
+        #[arg(long)]
+        dry_run: bool,
+        /// Size of compacted files, e.g. 256MB
+        #[arg(long)]
+        target_size: Option<String>,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run(action: CompactionAction) -> Result<()> {
+    let conn = connect_db()?;
+    compaction::init_schema(&conn)?;
+
+    match action {
+        CompactionAction::Set {
+            topic,
+            min_files,
+            min_size,
+            target_size,
+        } => {
+            let policy = CompactionPolicy {
+                topic,
+                min_files,
+                min_bytes: parse_size_arg(min_size.as_deref(), "--min-size")?,
+                target_file_bytes: parse_size_arg(target_size.as_deref(), "--target-size")?,
+            };
+            compaction::set_policy(&conn, &policy)?;
+            println!("Compaction policy set for {}", topic_label(&policy.topic));
+            Ok(())
+        }
+        CompactionAction::List { json } => list_policies(&conn, json),
+        CompactionAction::Remove { topic } => {
+            if !compaction::remove_policy(&conn, &topic)? {
+                return Err(
+                    HelpfulError::new(format!("No compaction policy for '{}'", topic))
+                        .with_suggestion("TRY: casparian compaction list")
+                        .into(),
+                );
+            }
+            println!("Compaction policy removed for {}", topic_label(&topic));
+            Ok(())
+        }
+        CompactionAction::Run {
+            topic,
+            dry_run,
+            target_size,
+            json,
+        } => {
+            let report = match topic {
+                Some(topic) => {
+                    let options = CompactOptions {
+                        dry_run,
+                        target_file_bytes: parse_size_arg(target_size.as_deref(), "--target-size")?,
+                    };
+                    compact_topic(&conn, &topic, &options)?
+                }
+                None => {
+                    if dry_run || target_size.is_some() {
+                        return Err(
+                            HelpfulError::new("--dry-run and --target-size need a topic")
+                                .with_suggestion("TRY: casparian compaction run <TOPIC> --dry-run")
+                                .into(),
+                        );
+                    }
+                    run_due_compactions(&conn)?
+                }
+            };
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_report(&report);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn connect_db() -> Result<DbConnection> {
+    let db_path = config::state_store_path();
+    DbConnection::open_from_url(&format!("sqlite:{}", db_path.display())).map_err(|e| {
+        HelpfulError::new("Failed to connect to database")
+            .with_context(format!("Database: {}", db_path.display()))
+            .with_suggestion(format!("Error: {}", e))
+            .into()
+    })
+}
+
+fn parse_size_arg(raw: Option<&str>, flag: &str) -> Result<Option<u64>> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    let bytes = parse_size(raw).map_err(|e| {
+        HelpfulError::new(format!("Invalid {} '{}'", flag, raw))
+            .with_context(e)
+            .with_suggestion("TRY: Use a size like 500MB or 10GB")
+    })?;
+    Ok(Some(bytes))
+}
+
+fn topic_label(topic: &str) -> String {
+    if topic == DEFAULT_POLICY_TOPIC {
+        "all outputs (default)".to_string()
+    } else {
+        format!("'{}'", topic)
+    }
+}
+
+fn list_policies(conn: &DbConnection, json: bool) -> Result<()> {
+    let policies = compaction::list_policies(conn)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&policies)?);
+        return Ok(());
+    }
+    if policies.is_empty() {
+        println!("No compaction policies; outputs are only compacted on request");
+        println!("  TRY: casparian compaction set '*' --min-files 32");
+        return Ok(());
+    }
+    let limit = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let rows = policies
+        .iter()
+        .map(|policy| {
+            vec![
+                policy.topic.clone(),
+                limit(policy.min_files.map(|n| n.to_string())),
+                limit(policy.min_bytes.map(format_size)),
+                limit(policy.target_file_bytes.map(format_size)),
+            ]
+        })
+        .collect();
+    print_table(&["TOPIC", "MIN FILES", "MIN SIZE", "TARGET SIZE"], rows);
+    Ok(())
+}
+
+fn print_report(report: &CompactionReport) {
+    if report.directories.is_empty() {
+        println!("Nothing to compact");
+    } else {
+        let rows = report
+            .directories
+            .iter()
+            .flat_map(|dir| {
+                dir.groups.iter().map(move |group| {
+                    vec![
+                        dir.output_name.clone(),
+                        group.sources.len().to_string(),
+                        group.rows().to_string(),
+                        format_size(group.bytes()),
+                        group.target.display().to_string(),
+                    ]
+                })
+            })
+            .collect();
+        print_table(&["OUTPUT", "FILES", "ROWS", "SIZE", "COMPACTED FILE"], rows);
+        let verb = if report.dry_run {
+            "Would compact"
+        } else {
+            "Compacted"
+        };
+        println!("{} {} small file(s)", verb, report.files_compacted());
+    }
+    for error in &report.errors {
+        eprintln!("Compaction failed: {}", error);
+    }
+}
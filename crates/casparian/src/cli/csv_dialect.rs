@@ -0,0 +1,118 @@
+//! CSV dialect options shared by `parser csv-dialect` and `rule csv-dialect`
+
+use casparian_protocol::{CsvDialect, TextEncoding};
+use clap::Args;
+
+/// Dialect fields to set; unset fields keep their current value
+#[derive(Args, Debug, Clone, Default)]
+pub struct CsvDialectArgs {
+    /// Field delimiter (e.g. ';', '|', or 'tab')
+    #[arg(long, value_parser = parse_dialect_char)]
+    pub delimiter: Option<char>,
+    /// Quote character
+    #[arg(long, value_parser = parse_dialect_char)]
+    pub quote: Option<char>,
+    /// Escape character inside quotes (default: quotes are doubled)
+    #[arg(long, value_parser = parse_dialect_char)]
+    pub escape: Option<char>,
+    /// Lines before the header row (titles, export banners)
+    #[arg(long)]
+    pub skip_rows: Option<usize>,
+    /// Text encoding (utf-8, utf-16le, utf-16be, latin-1, windows-1252)
+    #[arg(long)]
+    pub encoding: Option<TextEncoding>,
+    /// Field value read as null; repeat for several (replaces the list)
+    #[arg(long = "null")]
+    pub null_tokens: Option<Vec<String>>,
+}
+
+impl CsvDialectArgs {
+    /// Whether no field is set.
+    pub fn is_empty(&self) -> bool {
+        self.delimiter.is_none()
+            && self.quote.is_none()
+            && self.escape.is_none()
+            && self.skip_rows.is_none()
+            && self.encoding.is_none()
+            && self.null_tokens.is_none()
+    }
+
+    /// `base` with the fields given on the command line replaced.
+    pub fn apply(&self, base: CsvDialect) -> CsvDialect {
+        CsvDialect {
+            delimiter: self.delimiter.unwrap_or(base.delimiter),
+            quote: self.quote.unwrap_or(base.quote),
+            escape: self.escape.or(base.escape),
+            skip_rows: self.skip_rows.unwrap_or(base.skip_rows),
+            encoding: self.encoding.unwrap_or(base.encoding),
+            null_tokens: self.null_tokens.clone().unwrap_or(base.null_tokens),
+        }
+    }
+}
+
+/// Parse a single dialect character; `tab` and `\t` name the tab.
+pub fn parse_dialect_char(value: &str) -> Result<char, String> {
+    match value {
+        "tab" | "\\t" | "\t" => return Ok('\t'),
+        _ => {}
+    }
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(format!("Expected a single character, got '{}'", value)),
+    }
+}
+
+/// Print a dialect as indented `label: value` lines.
+pub fn print_dialect(dialect: &CsvDialect) {
+    let show = |c: char| match c {
+        '\t' => "tab".to_string(),
+        c => format!("'{}'", c),
+    };
+    let nulls: Vec<String> = dialect
+        .null_tokens
+        .iter()
+        .map(|token| format!("\"{}\"", token))
+        .collect();
+    println!("  Delimiter:        {}", show(dialect.delimiter));
+    println!("  Quote:            {}", show(dialect.quote));
+    println!(
+        "  Escape:           {}",
+        dialect
+            .escape
+            .map(show)
+            .unwrap_or_else(|| "(doubled quotes)".to_string())
+    );
+    println!("  Skip rows:        {}", dialect.skip_rows);
+    println!("  Encoding:         {}", dialect.encoding);
+    println!("  Null values:      {}", nulls.join(", "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_keeps_unset_fields() {
+        let base = CsvDialect {
+            delimiter: ';',
+            skip_rows: 2,
+            ..CsvDialect::default()
+        };
+        let args = CsvDialectArgs {
+            encoding: Some(TextEncoding::Latin1),
+            null_tokens: Some(vec!["NA".to_string()]),
+            ..CsvDialectArgs::default()
+        };
+        assert!(!args.is_empty());
+        let dialect = args.apply(base);
+        assert_eq!(dialect.delimiter, ';');
+        assert_eq!(dialect.skip_rows, 2);
+        assert_eq!(dialect.encoding, TextEncoding::Latin1);
+        assert_eq!(dialect.null_tokens, vec!["NA"]);
+
+        assert_eq!(parse_dialect_char("tab"), Ok('\t'));
+        assert_eq!(parse_dialect_char(";"), Ok(';'));
+        assert!(parse_dialect_char(";;").is_err());
+    }
+}
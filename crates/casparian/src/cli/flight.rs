@@ -0,0 +1,55 @@
+//! `casparian flight` command - serve job outputs over Arrow Flight.
+//!
+//! BI tools and Python clients fetch completed outputs as RecordBatches by
+//! `<job_id>/<output_name>` (or `<output_name>` for the latest job) instead of
+//! reading Parquet files off disk.
+//!
+//! # Usage
+//!
+//! ```bash
+//! casparian flight                       # listen on 127.0.0.1:8815
+//! casparian flight --addr 0.0.0.0:8815   # expose to the network
+//! ```
+
+use anyhow::{Context, Result};
+use casparian_flight::StateStore;
+use clap::Args;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use super::config;
+use super::error::HelpfulError;
+
+/// Arguments for the `flight` command
+#[derive(Debug, Args)]
+pub struct FlightArgs {
+    /// Address to listen on
+    #[arg(long, default_value = casparian_flight::DEFAULT_FLIGHT_ADDR)]
+    pub addr: String,
+}
+
+pub fn run(args: FlightArgs) -> Result<()> {
+    let addr: SocketAddr = args.addr.parse().map_err(|_| {
+        HelpfulError::new(format!("Invalid listen address '{}'", args.addr)).with_suggestion(
+            format!(
+                "TRY: casparian flight --addr {}",
+                casparian_flight::DEFAULT_FLIGHT_ADDR
+            ),
+        )
+    })?;
+    let state_store = StateStore::open(&config::state_store_url())?;
+    state_store.artifacts().init_schema()?;
+
+    println!("Serving job outputs over Arrow Flight at grpc://{}", addr);
+    println!("  Ticket: <job_id>/<output_name>, or <output_name> for the latest job");
+    println!("  Press Ctrl+C to stop");
+
+    let runtime = tokio::runtime::Runtime::new().context("Failed to start async runtime")?;
+    runtime.block_on(casparian_flight::serve(
+        addr,
+        Arc::new(state_store),
+        async {
+            let _ = tokio::signal::ctrl_c().await;
+        },
+    ))
+}
@@ -1,18 +1,25 @@
 //! Job command - Manage individual jobs
 //!
-//! Commands for showing, retrying, and cancelling individual jobs.
+//! Commands for showing, retrying, verifying, exporting, and cancelling
+//! individual jobs.
 //!
 //! WS4-05: Cancel requires Control API; no direct DB fallback.
 
 use crate::cli::error::HelpfulError;
-use crate::cli::jobs::{column_exists, get_db_path, table_exists, Job};
-use crate::cli::output::format_number_signed;
+use crate::cli::jobs::{
+    column_exists, get_db_path, parse_result_summary, result_summary_select, table_exists, Job,
+};
+use crate::cli::output::{format_number, format_number_signed};
 use casparian_db::{DbConnection, DbValue};
-use casparian_protocol::{JobId, JobStatus, ProcessingStatus};
-use casparian_sentinel::{ControlClient, DEFAULT_CONTROL_ADDR};
+use casparian_protocol::types::{ArtifactDiscrepancy, OutputColumnStats};
+use casparian_protocol::{JobDiagnostics, JobId, JobStatus, ProcessingStatus, RetryAttempt};
+use casparian_sentinel::db::StateStore;
+use casparian_sentinel::{
+    export_artifact, ControlClient, ExportFormat, ExportOptions, DEFAULT_CONTROL_ADDR,
+};
 use clap::Subcommand;
 use serde::Serialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Subcommands for job management
@@ -54,6 +61,29 @@ pub enum JobAction {
         /// Job ID to cancel
         id: String,
     },
+    /// Check a completed job's reported outputs against its sinks
+    Verify {
+        /// Job ID to verify
+        id: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Copy a job output to a local file, checked against the job's receipt
+    Export {
+        /// Job ID that wrote the output
+        id: String,
+        /// Output name
+        output: String,
+        /// Destination file (must not exist)
+        dest: PathBuf,
+        /// File format: original, or csv (Parquet outputs only)
+        #[arg(long, default_value = "original")]
+        format: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 /// Detailed job information including failure details
@@ -62,6 +92,11 @@ pub struct JobDetails {
     pub job: Job,
     pub failure: Option<JobFailure>,
     pub timeline: JobTimeline,
+    /// Failed attempts, oldest first
+    pub retries: Vec<RetryAttempt>,
+    /// Worker diagnostics of the latest attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<JobDiagnostics>,
 }
 
 /// Job failure details
@@ -100,6 +135,14 @@ pub fn run(action: JobAction) -> anyhow::Result<()> {
         JobAction::Retry { id } => run_retry(&db_path, &id),
         JobAction::RetryAll { topic } => run_retry_all(&db_path, topic.as_deref()),
         JobAction::Cancel { id } => run_cancel(&id),
+        JobAction::Verify { id, json } => run_verify(&id, json),
+        JobAction::Export {
+            id,
+            output,
+            dest,
+            format,
+            json,
+        } => run_export(&db_path, &id, &output, &dest, &format, json),
     }
 }
 
@@ -130,18 +173,28 @@ fn run_show(db_path: &PathBuf, id: &str, json: bool) -> anyhow::Result<()> {
 
     // Build timeline
     let timeline = build_timeline(&job);
+    let retries = get_retry_history(&conn, job_id)?;
+    let diagnostics = get_job_diagnostics(&conn, job_id)?;
 
     let details = JobDetails {
         job: job.clone(),
         failure: failure.clone(),
         timeline: timeline.clone(),
+        retries,
+        diagnostics,
     };
 
     if json {
         let output = serde_json::to_string_pretty(&details)?;
         println!("{}", output);
     } else {
-        print_job_details(&job, &failure, &timeline);
+        print_job_details(
+            &job,
+            &failure,
+            &timeline,
+            &details.retries,
+            details.diagnostics.as_ref(),
+        );
     }
 
     Ok(())
@@ -304,8 +357,134 @@ fn run_cancel(id: &str) -> anyhow::Result<()> {
     run_cancel_via_api(&client, job_id)
 }
 
+/// Verify a job's receipt via the Control API (the Sentinel records the result).
+fn run_verify(id: &str, json: bool) -> anyhow::Result<()> {
+    let job_id: JobId = id.parse().map_err(|_| {
+        HelpfulError::new(format!("Invalid job ID: '{}'", id))
+            .with_context("Job ID must be a positive integer")
+    })?;
+
+    let client = require_control_client()?;
+    let verification = client.verify_job(job_id).map_err(|e| {
+        HelpfulError::new(format!("Failed to verify job {}", job_id))
+            .with_context(format!("Control API error: {}", e))
+    })?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&verification)?);
+        return Ok(());
+    }
+
+    println!("JOB #{}: {}", job_id, verification.status);
+    println!(
+        "  Checked {} artifacts, skipped {}",
+        verification.checked, verification.skipped
+    );
+    print_discrepancies(&verification.discrepancies);
+    Ok(())
+}
+
+/// Export a job output, verifying it against the receipt. The export is
+/// recorded in the audit log.
+fn run_export(
+    db_path: &PathBuf,
+    id: &str,
+    output: &str,
+    dest: &Path,
+    format: &str,
+    json: bool,
+) -> anyhow::Result<()> {
+    let job_id: JobId = id.parse().map_err(|_| {
+        HelpfulError::new(format!("Invalid job ID: '{}'", id))
+            .with_context("Job ID must be a positive integer")
+    })?;
+    let format: ExportFormat = format
+        .parse()
+        .map_err(|e: String| HelpfulError::new(e).with_suggestion("TRY: --format csv"))?;
+
+    let state_store = StateStore::open(&db_url_for_path(db_path))?;
+    let job_id_db = job_id.to_i64().map_err(|err| anyhow::anyhow!(err))?;
+    let options = ExportOptions {
+        format,
+        actor: None,
+    };
+    let report = export_artifact(&state_store, job_id_db, output, dest, &options).map_err(|e| {
+        HelpfulError::new(format!("Failed to export '{}' of job {}", output, job_id))
+            .with_context(e.to_string())
+            .with_suggestion(format!(
+                "TRY: casparian job show {}   # List the job's outputs",
+                job_id
+            ))
+    })?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "Exported {} of job #{} to {}",
+        output,
+        job_id,
+        report.dest.display()
+    );
+    if let Some(rows) = report.rows {
+        println!("  Rows:   {}", format_number(rows));
+    }
+    println!("  Bytes:  {}", format_number(report.bytes));
+    println!("  Hash:   {}", report.dest_hash);
+    if !report.receipt_hash_checked {
+        println!("  Note:   the receipt has no file hash; only the copy was checked");
+    }
+    Ok(())
+}
+
+fn print_discrepancies(discrepancies: &[ArtifactDiscrepancy]) {
+    for discrepancy in discrepancies {
+        let rows = match (discrepancy.reported_rows, discrepancy.actual_rows) {
+            (Some(reported), Some(actual)) => {
+                format!(" (reported {}, found {})", reported, actual)
+            }
+            _ => String::new(),
+        };
+        println!(
+            "  {}: {}{}",
+            discrepancy.output_name, discrepancy.reason, rows
+        );
+        println!("    {}", discrepancy.sink_uri);
+    }
+}
+
+fn print_column_stats(outputs: &[OutputColumnStats]) {
+    for output in outputs {
+        println!("  {}", output.output_name);
+        let width = output
+            .columns
+            .iter()
+            .map(|column| column.column_name.len())
+            .max()
+            .unwrap_or(0);
+        for column in &output.columns {
+            let mut line = format!(
+                "    {:<width$}  nulls {} ({:.1}%)",
+                column.column_name,
+                format_number(column.null_count),
+                column.null_pct(),
+                width = width
+            );
+            if let Some(distinct) = column.distinct_estimate {
+                line.push_str(&format!("  distinct ~{}", format_number(distinct)));
+            }
+            if let (Some(min), Some(max)) = (&column.min, &column.max) {
+                line.push_str(&format!("  range {} .. {}", min, max));
+            }
+            println!("{}", line);
+        }
+    }
+}
+
 /// Require a working Control API connection for mutations.
-fn require_control_client() -> anyhow::Result<ControlClient> {
+pub(crate) fn require_control_client() -> anyhow::Result<ControlClient> {
     // Check for explicit address override
     let addr = std::env::var("CASPARIAN_CONTROL_ADDR")
         .unwrap_or_else(|_| DEFAULT_CONTROL_ADDR.to_string());
@@ -314,7 +493,7 @@ fn require_control_client() -> anyhow::Result<ControlClient> {
     if std::env::var("CASPARIAN_CONTROL_DISABLED").is_ok() {
         return Err(
             HelpfulError::new("Control API is disabled (CASPARIAN_CONTROL_DISABLED set)")
-                .with_context("Job cancellation requires the Control API")
+                .with_context("This command requires the Control API")
                 .with_suggestion("Remove CASPARIAN_CONTROL_DISABLED or start sentinel normally")
                 .into(),
         );
@@ -433,12 +612,14 @@ fn get_job_by_id(conn: &DbConnection, job_id: JobId) -> anyhow::Result<Option<Jo
             q.end_time,
             q.error_message,
             q.result_summary,
-            q.retry_count{quarantine_select}
+            q.retry_count,
+            {result_select}{quarantine_select}
         FROM cf_processing_queue q
         LEFT JOIN scout_files sf ON sf.id = q.file_id
         {quarantine_join}
         WHERE q.id = ?
         "#,
+        result_select = result_summary_select(conn)?,
         quarantine_select = quarantine_select,
         quarantine_join = quarantine_join
     );
@@ -465,7 +646,8 @@ fn get_job_by_id(conn: &DbConnection, job_id: JobId) -> anyhow::Result<Option<Jo
                 error_message: r.get(7).ok(),
                 result_summary: r.get(8).ok(),
                 retry_count: r.get(9)?,
-                quarantine_rows: r.get(10).ok(),
+                quarantine_rows: r.get(11).ok(),
+                result: parse_result_summary(r.get::<Option<String>>(10).ok().flatten()),
             })
         }
         None => None,
@@ -511,6 +693,58 @@ fn get_job_failure(conn: &DbConnection, job_id: JobId) -> anyhow::Result<Option<
     }))
 }
 
+/// Get the failed attempts recorded for a job
+fn get_retry_history(conn: &DbConnection, job_id: JobId) -> anyhow::Result<Vec<RetryAttempt>> {
+    let job_id_db = job_id.to_i64().map_err(|err| anyhow::anyhow!(err))?;
+    if !table_exists(conn, "cf_job_retries")? {
+        return Ok(Vec::new());
+    }
+
+    let rows = conn.query_all(
+        r#"
+            SELECT attempt, error_class, error_message, failed_at, retry_at
+            FROM cf_job_retries
+            WHERE job_id = ?
+            ORDER BY attempt ASC, id ASC
+            "#,
+        &[DbValue::from(job_id_db)],
+    )?;
+
+    rows.iter()
+        .map(|r| -> anyhow::Result<RetryAttempt> {
+            let attempt: i64 = r.get(0)?;
+            Ok(RetryAttempt {
+                attempt: u32::try_from(attempt).unwrap_or(0),
+                error_class: r.get(1)?,
+                error_message: r.get(2).unwrap_or_default(),
+                failed_at: r.get(3)?,
+                retry_at: r.get(4).ok(),
+            })
+        })
+        .collect()
+}
+
+/// Get the diagnostics the worker reported for the job's latest attempt
+fn get_job_diagnostics(
+    conn: &DbConnection,
+    job_id: JobId,
+) -> anyhow::Result<Option<JobDiagnostics>> {
+    let job_id_db = job_id.to_i64().map_err(|err| anyhow::anyhow!(err))?;
+    if !table_exists(conn, "cf_job_diagnostics")? {
+        return Ok(None);
+    }
+
+    let row = conn.query_optional(
+        "SELECT diagnostics_json FROM cf_job_diagnostics WHERE job_id = ?",
+        &[DbValue::from(job_id_db)],
+    )?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let json: String = row.get(0)?;
+    Ok(serde_json::from_str(&json).ok())
+}
+
 /// Build timeline from job data
 fn build_timeline(job: &Job) -> JobTimeline {
     let duration_secs = match (&job.claim_time, &job.end_time) {
@@ -536,7 +770,13 @@ fn build_timeline(job: &Job) -> JobTimeline {
 }
 
 /// Print formatted job details
-fn print_job_details(job: &Job, failure: &Option<JobFailure>, timeline: &JobTimeline) {
+fn print_job_details(
+    job: &Job,
+    failure: &Option<JobFailure>,
+    timeline: &JobTimeline,
+    retries: &[RetryAttempt],
+    diagnostics: Option<&JobDiagnostics>,
+) {
     println!("JOB #{}", job.id);
     println!();
     println!("FILE:      {}", job.file_path);
@@ -560,6 +800,56 @@ fn print_job_details(job: &Job, failure: &Option<JobFailure>, timeline: &JobTime
         println!("  Duration:  {}", format_duration(secs));
     }
 
+    if let Some(diagnostics) = diagnostics {
+        if !diagnostics.stage_timings.is_empty() {
+            let stages: Vec<String> = diagnostics
+                .stage_timings
+                .iter()
+                .map(|timing| format!("{} {}ms", timing.stage, timing.duration_ms))
+                .collect();
+            println!("  Stages:    {}", stages.join(", "));
+        }
+        if let Some(category) = diagnostics.error_category {
+            println!();
+            println!("FAILURE CATEGORY: {}", category);
+            if let Some(stage) = diagnostics.failed_stage {
+                println!("  Stage:     {}", stage);
+            }
+            if let Some(ref class) = diagnostics.error_class {
+                println!("  Class:     {}", class);
+            }
+        }
+        if let Some(ref verification) = diagnostics.verification {
+            println!();
+            println!("RECEIPT: {}", verification.status);
+            print_discrepancies(&verification.discrepancies);
+        }
+        if !diagnostics.column_stats.is_empty() {
+            println!();
+            println!("COLUMN STATS:");
+            print_column_stats(&diagnostics.column_stats);
+        }
+    }
+
+    if !retries.is_empty() {
+        println!();
+        println!("RETRY HISTORY:");
+        for attempt in retries {
+            let next = match attempt.retry_at {
+                Some(retry_at) => format!("retry at {}", format_millis(retry_at)),
+                None => "not retried".to_string(),
+            };
+            println!(
+                "  #{} {}  [{}] {} ({})",
+                attempt.attempt,
+                format_millis(attempt.failed_at),
+                attempt.error_class,
+                attempt.error_message,
+                next
+            );
+        }
+    }
+
     if let Some(ref f) = failure {
         println!();
         println!("ERROR:");
@@ -593,6 +883,32 @@ fn print_job_details(job: &Job, failure: &Option<JobFailure>, timeline: &JobTime
         println!();
         println!("RESULT:    {}", summary);
     }
+    if let Some(ref result) = job.result {
+        if !result.artifacts.is_empty() {
+            println!();
+            println!("ARTIFACTS:");
+            for artifact in &result.artifacts {
+                let rows = artifact
+                    .rows
+                    .map(|rows| format!(" ({} rows)", format_number(rows)))
+                    .unwrap_or_default();
+                println!(
+                    "  {:<11} {} -> {}{}",
+                    artifact.kind.as_str().to_lowercase(),
+                    artifact.name,
+                    artifact.uri.as_deref().unwrap_or("-"),
+                    rows
+                );
+            }
+        }
+        if !result.warnings.is_empty() {
+            println!();
+            println!("WARNINGS:");
+            for warning in &result.warnings {
+                println!("  {}", warning);
+            }
+        }
+    }
 
     // Suggestions
     println!();
@@ -615,6 +931,13 @@ fn print_job_details(job: &Job, failure: &Option<JobFailure>, timeline: &JobTime
     println!("  casparian jobs                    # View all jobs");
 }
 
+/// Format unix millis for display
+fn format_millis(millis: i64) -> String {
+    chrono::DateTime::<chrono::Utc>::from_timestamp_millis(millis)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| millis.to_string())
+}
+
 /// Format a datetime string for display
 fn format_datetime(dt_str: &str) -> String {
     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(dt_str) {
@@ -665,6 +988,7 @@ mod tests {
             result_summary: None,
             retry_count: 0,
             quarantine_rows: None,
+            result: None,
         };
 
         let timeline = build_timeline(&job);
@@ -6,7 +6,7 @@ use crate::cli::config;
 use crate::cli::error::HelpfulError;
 use crate::cli::output::{format_number_signed, print_table_colored};
 use casparian_db::{DbConnection, DbValue};
-use casparian_protocol::{JobId, ProcessingStatus};
+use casparian_protocol::{JobId, JobResultSummary, ProcessingStatus};
 use chrono::SecondsFormat;
 use comfy_table::Color;
 use serde::Serialize;
@@ -54,6 +54,9 @@ pub struct Job {
     pub result_summary: Option<String>,
     pub retry_count: i32,
     pub quarantine_rows: Option<i64>,
+    /// Structured result (artifacts, counters, warnings) of a concluded job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<JobResultSummary>,
 }
 
 /// Queue statistics
@@ -235,6 +238,23 @@ pub(crate) fn column_exists(
     Ok(conn.column_exists(table, column)?)
 }
 
+/// Select expression for the structured result column (NULL on queues
+/// created before it existed).
+pub(crate) fn result_summary_select(conn: &DbConnection) -> anyhow::Result<&'static str> {
+    Ok(
+        if column_exists(conn, "cf_processing_queue", "result_summary_json")? {
+            "q.result_summary_json"
+        } else {
+            "NULL AS result_summary_json"
+        },
+    )
+}
+
+/// Parse a `result_summary_json` value; malformed JSON is treated as absent.
+pub(crate) fn parse_result_summary(raw: Option<String>) -> Option<JobResultSummary> {
+    raw.and_then(|json| serde_json::from_str(&json).ok())
+}
+
 fn get_queue_stats(conn: &DbConnection) -> anyhow::Result<QueueStats> {
     if !table_exists(conn, "cf_processing_queue")? {
         return Ok(QueueStats::default());
@@ -363,6 +383,7 @@ fn get_jobs(
     } else {
         ", NULL as quarantine_rows"
     };
+    let result_select = result_summary_select(conn)?;
     let quarantine_join = if has_quarantine_column {
         ""
     } else if has_quarantine_table {
@@ -393,7 +414,8 @@ fn get_jobs(
                 q.end_time,
                 q.error_message,
                 q.result_summary,
-                q.retry_count{quarantine_select}
+                q.retry_count,
+                {result_select}{quarantine_select}
             FROM cf_processing_queue q
             LEFT JOIN scout_files sf ON sf.id = q.file_id
             {quarantine_join}
@@ -403,6 +425,7 @@ fn get_jobs(
             LIMIT ?
             "#,
             status_placeholders,
+            result_select = result_select,
             quarantine_select = quarantine_select,
             quarantine_join = quarantine_join
         )
@@ -419,7 +442,8 @@ fn get_jobs(
                 q.end_time,
                 q.error_message,
                 q.result_summary,
-                q.retry_count{quarantine_select}
+                q.retry_count,
+                {result_select}{quarantine_select}
             FROM cf_processing_queue q
             LEFT JOIN scout_files sf ON sf.id = q.file_id
             {quarantine_join}
@@ -428,6 +452,7 @@ fn get_jobs(
             LIMIT ?
             "#,
             status_placeholders,
+            result_select = result_select,
             quarantine_select = quarantine_select,
             quarantine_join = quarantine_join
         )
@@ -466,13 +491,14 @@ fn get_jobs(
                 error_message: row.get::<Option<String>>(7).ok().flatten(),
                 result_summary: row.get::<Option<String>>(8).ok().flatten(),
                 retry_count: row.get(9)?,
-                quarantine_rows: row.get::<Option<i64>>(10).ok().flatten().and_then(|value| {
+                quarantine_rows: row.get::<Option<i64>>(11).ok().flatten().and_then(|value| {
                     if value > 0 {
                         Some(value)
                     } else {
                         None
                     }
                 }),
+                result: parse_result_summary(row.get::<Option<String>>(10).ok().flatten()),
             })
         })
         .collect::<anyhow::Result<Vec<_>>>()?;
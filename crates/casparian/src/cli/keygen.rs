@@ -0,0 +1,220 @@
+//! `casparian keygen` command - generate CurveZMQ key pairs.
+//!
+//! The Sentinel and each worker need a key pair to encrypt their ZMQ
+//! traffic. Keys are written to ~/.casparian_flow/keys as `{name}.key`
+//! (owner-only) and `{name}.pub`.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # On the Sentinel host
+//! casparian keygen --name sentinel
+//! casparian sentinel --curve
+//!
+//! # On each worker host
+//! casparian keygen --name worker
+//! # ...append the printed public key to the Sentinel's keys/authorized_workers
+//! casparian worker --connect tcp://sentinel:5555 --server-key '<sentinel public key>'
+//!
+//! # Publisher signing key (Ed25519) for `casparian publish`
+//! casparian keygen --publisher --name alice
+//! # ...copy keys/publishers/alice.pub to the Sentinel's keys/publishers/
+//! ```
+
+use anyhow::Result;
+use casparian_protocol::keys::{self, CurveKeyPair};
+use casparian_security::keyring;
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::error::HelpfulError;
+
+/// Arguments for the `keygen` command
+#[derive(Debug, Args)]
+pub struct KeygenArgs {
+    /// Key pair name ("sentinel" for the Sentinel, anything else for workers)
+    #[arg(long, default_value = keys::WORKER_KEY_NAME)]
+    pub name: String,
+
+    /// Directory to write keys to (default: ~/.casparian_flow/keys)
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// Overwrite an existing key pair
+    #[arg(long)]
+    pub force: bool,
+
+    /// Generate an Ed25519 publisher signing key (keys/publishers) instead
+    /// of a CURVE key pair
+    #[arg(long)]
+    pub publisher: bool,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct KeygenOutput {
+    name: String,
+    public_key: String,
+    secret_key_path: PathBuf,
+    public_key_path: PathBuf,
+}
+
+pub fn run(args: KeygenArgs) -> Result<()> {
+    let mut dir = args.dir.clone().unwrap_or_else(keys::keys_dir);
+    if args.publisher {
+        dir = keyring::publishers_dir(&dir);
+    }
+    let secret_path = keys::secret_key_path(&dir, &args.name);
+    if secret_path.exists() && !args.force {
+        return Err(HelpfulError::new(format!(
+            "Key pair '{}' already exists: {}",
+            args.name,
+            secret_path.display()
+        ))
+        .with_context("Overwriting a key pair locks out peers that trust the old public key")
+        .with_suggestions([
+            format!(
+                "TRY: Print the existing public key: cat {}",
+                keys::public_key_path(&dir, &args.name).display()
+            ),
+            format!(
+                "TRY: Replace it: casparian keygen --name {} --force",
+                args.name
+            ),
+        ])
+        .into());
+    }
+    if args.publisher {
+        return run_publisher(&args, &dir);
+    }
+
+    let generated = zmq::CurveKeyPair::new().map_err(|err| {
+        HelpfulError::new(format!("Failed to generate CURVE key pair: {}", err))
+            .with_context("libzmq must be built with CURVE (libsodium) support")
+            .with_suggestion(
+                "TRY: Check support with: python -c 'import zmq; print(zmq.has(\"curve\"))'",
+            )
+    })?;
+    let pair = CurveKeyPair {
+        public_key: zmq::z85_encode(&generated.public_key)?,
+        secret_key: zmq::z85_encode(&generated.secret_key)?,
+    };
+    keys::save_keypair(&dir, &args.name, &pair)?;
+
+    let output = KeygenOutput {
+        name: args.name.clone(),
+        public_key: pair.public_key,
+        secret_key_path: secret_path,
+        public_key_path: keys::public_key_path(&dir, &args.name),
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Generated CURVE key pair '{}'", output.name);
+    println!("  Secret: {}", output.secret_key_path.display());
+    println!("  Public: {}", output.public_key_path.display());
+    println!();
+    println!("Public key: {}", output.public_key);
+    println!();
+    if output.name == keys::SENTINEL_KEY_NAME {
+        println!("TRY: Start the Sentinel with encryption: casparian sentinel --curve");
+        println!(
+            "TRY: Start workers with: casparian worker --server-key '{}'",
+            output.public_key
+        );
+    } else {
+        println!(
+            "TRY: Authorize this worker on the Sentinel host: echo '{}' >> {}",
+            output.public_key,
+            keys::keys_dir()
+                .join(keys::AUTHORIZED_WORKERS_FILE)
+                .display()
+        );
+    }
+    Ok(())
+}
+
+/// Generate an Ed25519 key that `casparian publish` signs deploys with.
+fn run_publisher(args: &KeygenArgs, dir: &std::path::Path) -> Result<()> {
+    let public_key = keyring::generate_publisher_key(dir, &args.name)?;
+    let output = KeygenOutput {
+        name: args.name.clone(),
+        public_key,
+        secret_key_path: keys::secret_key_path(dir, &args.name),
+        public_key_path: keys::public_key_path(dir, &args.name),
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Generated publisher key '{}'", output.name);
+    println!("  Secret: {}", output.secret_key_path.display());
+    println!("  Public: {}", output.public_key_path.display());
+    println!();
+    println!(
+        "TRY: Trust it on the Sentinel host: copy {} to {}",
+        output.public_key_path.display(),
+        keyring::publishers_dir(&keys::keys_dir()).display()
+    );
+    println!(
+        "TRY: Publish signed: casparian publish <file> --version <v> --signer {}",
+        output.name
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_keygen_refuses_to_overwrite_without_force() {
+        let dir = tempdir().unwrap();
+        let args = |force| KeygenArgs {
+            name: "worker".to_string(),
+            dir: Some(dir.path().to_path_buf()),
+            force,
+            publisher: false,
+            json: true,
+        };
+
+        std::fs::write(keys::secret_key_path(dir.path(), "worker"), "existing").unwrap();
+        let err = run(args(false)).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{}", err);
+        assert_eq!(
+            std::fs::read_to_string(keys::secret_key_path(dir.path(), "worker")).unwrap(),
+            "existing"
+        );
+    }
+
+    #[test]
+    fn test_keygen_publisher_key_is_trusted_by_keyring() {
+        let dir = tempdir().unwrap();
+        run(KeygenArgs {
+            name: "alice".to_string(),
+            dir: Some(dir.path().to_path_buf()),
+            force: false,
+            publisher: true,
+            json: true,
+        })
+        .unwrap();
+
+        let publishers = keyring::publishers_dir(dir.path());
+        let signing_key = keyring::load_signing_key(&publishers, "alice")
+            .unwrap()
+            .unwrap();
+        let signature = keyring::sign_artifact(&signing_key, "abc123");
+        keyring::Keyring::load(&publishers)
+            .unwrap()
+            .verify("alice", "abc123", &signature)
+            .unwrap();
+    }
+}
@@ -1,12 +1,18 @@
 //! MCP (Model Context Protocol) CLI commands
 //!
-//! Provides the `casparian mcp serve` command for running the MCP server.
+//! Provides the `casparian mcp serve` command for running the MCP server,
+//! plus approval commands and the auto-approval rules (`casparian mcp rules`).
 
 use anyhow::Result;
+use casparian_db::DbConnection;
+use casparian_sentinel::db::approval_policy::{self, ApprovalRule, RuleCondition};
 use clap::Subcommand;
 use std::path::PathBuf;
 use tracing::info;
 
+use super::error::HelpfulError;
+use super::output::print_table;
+
 #[derive(Subcommand, Debug)]
 pub enum McpAction {
     /// Start the MCP server (stdio transport)
@@ -38,6 +44,18 @@ pub enum McpAction {
         #[arg(long, default_value = "10000")]
         max_rows: usize,
 
+        /// Reject queries whose unfiltered full scans exceed this many rows (default: 50M)
+        #[arg(long, default_value = "50000000")]
+        max_scan_rows: u64,
+
+        /// Reject queries estimated to scan more bytes than this (default: 4GB)
+        #[arg(long, default_value = "4294967296")]
+        max_scan_bytes: u64,
+
+        /// Cancel queries that run longer than this (default: 30s)
+        #[arg(long, default_value = "30000")]
+        query_timeout_ms: u64,
+
         /// Audit log file path (default: ~/.casparian_flow/mcp_audit.ndjson)
         #[arg(long)]
         audit_log: Option<PathBuf>,
@@ -57,6 +75,10 @@ pub enum McpAction {
         /// Allow MCP to open the DB in write mode when Control API is unavailable
         #[arg(long)]
         standalone_db_writer: bool,
+
+        /// Return source paths and artifact URIs unredacted (default: hashed)
+        #[arg(long)]
+        allow_plaintext_uris: bool,
     },
 
     /// Approve a pending MCP operation
@@ -98,6 +120,42 @@ pub enum McpAction {
         #[arg(long)]
         standalone_db_writer: bool,
     },
+
+    /// Manage rules that approve low-risk operations automatically
+    ///
+    /// Rules are checked in the order they were added; the first match
+    /// approves the request and is recorded in the approval audit log.
+    Rules {
+        #[command(subcommand)]
+        action: ApprovalRuleAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ApprovalRuleAction {
+    /// Add or replace an approval rule
+    ///
+    /// Without --max-files or --additive-schema the rule approves every run
+    /// of the given plugins.
+    Add {
+        name: String,
+        /// Plugin the rule covers (repeatable; default: all plugins)
+        #[arg(long = "plugin")]
+        plugins: Vec<String>,
+        /// Approve runs over at most this many files
+        #[arg(long, conflicts_with = "additive_schema")]
+        max_files: Option<u64>,
+        /// Approve schema promotions that only add nullable columns
+        #[arg(long)]
+        additive_schema: bool,
+    },
+    /// List approval rules in evaluation order
+    List {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Remove an approval rule
+    Remove { name: String },
 }
 
 pub fn run(action: McpAction) -> Result<()> {
@@ -106,20 +164,28 @@ pub fn run(action: McpAction) -> Result<()> {
             allow_paths,
             max_output_bytes,
             max_rows,
+            max_scan_rows,
+            max_scan_bytes,
+            query_timeout_ms,
             audit_log,
             database,
             output,
             control_addr,
             standalone_db_writer,
+            allow_plaintext_uris,
         } => run_serve(
             allow_paths,
             max_output_bytes,
             max_rows,
+            max_scan_rows,
+            max_scan_bytes,
+            query_timeout_ms,
             audit_log,
             database,
             output,
             control_addr,
             standalone_db_writer,
+            allow_plaintext_uris,
         ),
         McpAction::Approve {
             approval_id,
@@ -133,6 +199,7 @@ pub fn run(action: McpAction) -> Result<()> {
             control_addr,
             standalone_db_writer,
         } => run_list(all, json, control_addr, standalone_db_writer),
+        McpAction::Rules { action } => run_rules(action),
     }
 }
 
@@ -140,11 +207,15 @@ fn run_serve(
     allow_paths: Vec<PathBuf>,
     max_output_bytes: usize,
     max_rows: usize,
+    max_scan_rows: u64,
+    max_scan_bytes: u64,
+    query_timeout_ms: u64,
     audit_log: Option<PathBuf>,
     database: Option<PathBuf>,
     _output: Option<PathBuf>,
     control_addr: Option<String>,
     standalone_db_writer: bool,
+    allow_plaintext_uris: bool,
 ) -> Result<()> {
     use super::config;
     use casparian_mcp::{McpServer, McpServerConfig};
@@ -161,17 +232,35 @@ fn run_serve(
         Some(audit_log.unwrap_or_else(|| config::casparian_home().join("mcp_audit.ndjson")));
 
     let db_path = database.unwrap_or_else(config::state_store_path);
+    let mut llm = match casparian::ai::config::load_default_ai_config() {
+        Ok(ai) if ai.enabled => ai.llm,
+        Ok(_) => Default::default(),
+        Err(e) => {
+            tracing::warn!("Ignoring [ai] config: {}", e);
+            Default::default()
+        }
+    };
+    // CI and bug reproduction: answer LLM calls only from a recording
+    if let Some(dir) = std::env::var_os("CASPARIAN_LLM_REPLAY_DIR") {
+        llm.cache = casparian_protocol::LlmCacheMode::Replay;
+        llm.cache_dir = Some(PathBuf::from(dir));
+    }
     let mcp_config = McpServerConfig {
         server_name: "casparian-mcp".to_string(),
         server_version: env!("CARGO_PKG_VERSION").to_string(),
         allowed_paths,
         max_response_bytes: max_output_bytes,
         max_rows,
+        max_scan_rows,
+        max_scan_bytes,
+        query_timeout_ms,
         audit_log_path,
         db_path,
         query_catalog_path: config::query_catalog_path(),
         control_addr,
         standalone_db_writer,
+        allow_plaintext_uris,
+        llm,
     };
 
     info!("Starting MCP server (stdio)");
@@ -198,12 +287,13 @@ fn run_approve(
     };
 
     let id = ApprovalId::from_string(&approval_id);
+    let actor = std::env::var("USER").ok();
 
     if reject {
-        manager.reject(&id, Some("Rejected via CLI".to_string()))?;
+        manager.reject(&id, Some("Rejected via CLI".to_string()), actor.as_deref())?;
         println!("Rejected approval: {}", approval_id);
     } else {
-        manager.approve(&id)?;
+        manager.approve(&id, actor.as_deref(), None)?;
         println!("Approved: {}", approval_id);
 
         // Get the approval to show what was approved
@@ -284,6 +374,88 @@ fn run_list(
     Ok(())
 }
 
+fn run_rules(action: ApprovalRuleAction) -> Result<()> {
+    use super::config;
+
+    let db_path = config::state_store_path();
+    let conn = DbConnection::open_sqlite(&db_path).map_err(|e| {
+        HelpfulError::new("Failed to connect to database")
+            .with_context(format!("Database: {}", db_path.display()))
+            .with_suggestion(format!("Error: {}", e))
+    })?;
+    approval_policy::init_schema(&conn)?;
+
+    match action {
+        ApprovalRuleAction::Add {
+            name,
+            plugins,
+            max_files,
+            additive_schema,
+        } => {
+            let condition = match (max_files, additive_schema) {
+                (Some(max_files), _) => RuleCondition::SmallRun { max_files },
+                (None, true) => RuleCondition::AdditiveSchema,
+                (None, false) => RuleCondition::AnyRun,
+            };
+            let rule = ApprovalRule {
+                name,
+                plugins,
+                condition,
+            };
+            approval_policy::set_rule(&conn, &rule).map_err(|e| {
+                HelpfulError::new(format!("Invalid approval rule '{}'", rule.name))
+                    .with_context(e.to_string())
+                    .with_suggestion(
+                        "TRY: Pass --plugin, --max-files or --additive-schema to narrow the rule",
+                    )
+            })?;
+            println!("Approval rule '{}' saved", rule.name);
+            Ok(())
+        }
+        ApprovalRuleAction::List { json } => {
+            let rules = approval_policy::list_rules(&conn)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rules)?);
+                return Ok(());
+            }
+            if rules.is_empty() {
+                println!("No approval rules; every operation needs a human approval");
+                println!("  TRY: casparian mcp rules add small-runs --max-files 10");
+                return Ok(());
+            }
+            let rows = rules
+                .iter()
+                .map(|rule| {
+                    let condition = match rule.condition {
+                        RuleCondition::AnyRun => "any run".to_string(),
+                        RuleCondition::SmallRun { max_files } => {
+                            format!("runs of <= {} files", max_files)
+                        }
+                        RuleCondition::AdditiveSchema => "additive schema changes".to_string(),
+                    };
+                    let plugins = if rule.plugins.is_empty() {
+                        "*".to_string()
+                    } else {
+                        rule.plugins.join(", ")
+                    };
+                    vec![rule.name.clone(), plugins, condition]
+                })
+                .collect();
+            print_table(&["RULE", "PLUGINS", "APPROVES"], rows);
+            Ok(())
+        }
+        ApprovalRuleAction::Remove { name } => {
+            if !approval_policy::remove_rule(&conn, &name)? {
+                return Err(HelpfulError::new(format!("No approval rule '{}'", name))
+                    .with_suggestion("TRY: casparian mcp rules list")
+                    .into());
+            }
+            println!("Approval rule '{}' removed", name);
+            Ok(())
+        }
+    }
+}
+
 fn truncate(s: &str, max: usize) -> String {
     if s.len() <= max {
         s.to_string()
@@ -92,11 +92,47 @@ pub mod config;
 pub mod context;
 
 // Support
+pub mod csv_dialect;
 pub mod support_bundle;
 
+// Transport security
+pub mod keygen;
+
 // Tape recording and playback
 pub mod tape;
 
+// Intent session export/import
+pub mod session;
+
+// Control-plane audit log
+pub mod audit;
+
+// Retention policies and output/log garbage collection
+pub mod retention;
+
+// Compaction of small Parquet outputs
+pub mod compaction;
+
+// Live dashboard of a running Sentinel
+pub mod top;
+
+// Arrow Flight server for job outputs
+#[cfg(feature = "flight")]
+pub mod flight;
+#[cfg(not(feature = "flight"))]
+pub mod flight {
+    #[derive(Debug, clap::Args)]
+    pub struct FlightArgs {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8815")]
+        pub addr: String,
+    }
+
+    pub fn run(_args: FlightArgs) -> anyhow::Result<()> {
+        anyhow::bail!("flight requires the `flight` feature")
+    }
+}
+
 // Re-exports are used by the scan, preview, and resource modules
 #[allow(unused_imports)]
 pub use error::HelpfulError;
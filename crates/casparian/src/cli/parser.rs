@@ -5,14 +5,21 @@
 //! - `parser show <name>` - Show parser details
 //! - `parser test <file.py> --input <data>` - Test a parser against a file
 //! - `parser unpublish <name>` - Remove parser from active duty
+//! - `parser rollback <name> <version>` - Re-activate a previous version
 //! - `parser backtest <name> [--limit N]` - Run parser against all files for its topic
+//! - `parser retry-policy <name> [--max-attempts N ...]` - Show or set the retry policy
+//! - `parser limits <name> [--timeout-secs N ...]` - Show or set resource limits
+//! - `parser csv-dialect <name> [--delimiter C ...]` - Show or set the CSV dialect
 
 use crate::cli::config;
+use crate::cli::csv_dialect::{print_dialect, CsvDialectArgs};
 use crate::cli::error::HelpfulError;
-use crate::cli::output::{print_table, print_table_colored};
+use crate::cli::job::require_control_client;
+use crate::cli::output::{format_size, parse_size, print_table, print_table_colored};
 use anyhow::Context;
 use casparian_db::{DbConnection, DbValue};
-use casparian_protocol::PluginStatus;
+use casparian_protocol::{retry, ErrorCategory, PluginStatus, ResourceLimits, RetryPolicy};
+use casparian_sentinel::db::{audit, AuditLog};
 use chrono::{DateTime, Utc};
 use clap::Subcommand;
 use comfy_table::Color;
@@ -59,6 +66,16 @@ pub enum ParserAction {
         /// Parser name
         name: String,
     },
+    /// Roll a parser back to a previously active version
+    Rollback {
+        /// Parser name
+        name: String,
+        /// Version to re-activate
+        version: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Run parser against all files for its topic
     Backtest {
         /// Parser name
@@ -83,6 +100,82 @@ pub enum ParserAction {
         #[arg(long)]
         json: bool,
     },
+    /// Show or set how failed jobs of a parser are retried
+    #[command(name = "retry-policy")]
+    RetryPolicy {
+        /// Parser name
+        name: String,
+        /// Total attempts, including the first run (1 disables retries)
+        #[arg(long)]
+        max_attempts: Option<u32>,
+        /// Exponential backoff base in seconds (retry N waits base^N)
+        #[arg(long)]
+        backoff_base_secs: Option<u64>,
+        /// Random extra delay as a fraction of the backoff (0.0 - 1.0)
+        #[arg(long)]
+        jitter: Option<f64>,
+        /// Error classes or categories to retry (comma-separated, e.g. transient,timeout)
+        #[arg(long, value_delimiter = ',')]
+        retry_on: Option<Vec<String>>,
+        /// Remove the parser's policy and use the default
+        #[arg(long, conflicts_with_all = ["max_attempts", "backoff_base_secs", "jitter", "retry_on"])]
+        reset: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show or set the CPU, memory, wall-clock, decompression, and scratch limits of a parser's jobs
+    Limits {
+        /// Parser name
+        name: String,
+        /// Wall-clock limit per job in seconds; the plugin is killed when exceeded
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+        /// CPU time limit of the plugin process in seconds
+        #[arg(long)]
+        max_cpu_secs: Option<u64>,
+        /// Memory limit of the plugin process (e.g. 512MB, 2GB)
+        #[arg(long, value_parser = parse_size)]
+        max_memory: Option<u64>,
+        /// Largest decompressed size of a compressed (gzip/zstd/bzip2) input (e.g. 20GB)
+        #[arg(long, value_parser = parse_size)]
+        max_decompressed: Option<u64>,
+        /// Size quota of the job's scratch directory (e.g. 5GB)
+        #[arg(long, value_parser = parse_size)]
+        max_scratch: Option<u64>,
+        /// Remove all limits
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "timeout_secs",
+                "max_cpu_secs",
+                "max_memory",
+                "max_decompressed",
+                "max_scratch"
+            ]
+        )]
+        reset: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show or set how a parser's CSV inputs are read (overridden by rule dialects)
+    #[command(name = "csv-dialect")]
+    CsvDialect {
+        /// Parser name
+        name: String,
+        #[command(flatten)]
+        dialect: CsvDialectArgs,
+        /// Remove the parser's dialect and use the detected one
+        #[arg(
+            long,
+            conflicts_with_all = ["delimiter", "quote", "escape", "skip_rows", "encoding", "null_tokens"]
+        )]
+        reset: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 // ============================================================================
@@ -183,9 +276,60 @@ fn run_with_action(action: ParserAction) -> anyhow::Result<()> {
             json,
         } => cmd_test(&parser, &input, rows, json),
         ParserAction::Unpublish { name } => cmd_unpublish(&name),
+        ParserAction::Rollback {
+            name,
+            version,
+            json,
+        } => cmd_rollback(&name, &version, json),
         ParserAction::Backtest { name, limit, json } => cmd_backtest(&name, limit, json),
         ParserAction::Resume { name } => cmd_resume(&name),
         ParserAction::Health { name, json } => cmd_health(&name, json),
+        ParserAction::RetryPolicy {
+            name,
+            max_attempts,
+            backoff_base_secs,
+            jitter,
+            retry_on,
+            reset,
+            json,
+        } => cmd_retry_policy(
+            &name,
+            RetryPolicyUpdate {
+                max_attempts,
+                backoff_base_secs,
+                jitter,
+                retry_on,
+                reset,
+            },
+            json,
+        ),
+        ParserAction::Limits {
+            name,
+            timeout_secs,
+            max_cpu_secs,
+            max_memory,
+            max_decompressed,
+            max_scratch,
+            reset,
+            json,
+        } => cmd_limits(
+            &name,
+            ResourceLimits {
+                timeout_secs,
+                max_cpu_secs,
+                max_memory_bytes: max_memory,
+                max_decompressed_bytes: max_decompressed,
+                max_scratch_bytes: max_scratch,
+            },
+            reset,
+            json,
+        ),
+        ParserAction::CsvDialect {
+            name,
+            dialect,
+            reset,
+            json,
+        } => cmd_csv_dialect(&name, &dialect, reset, json),
     }
 }
 
@@ -613,6 +757,37 @@ fn cmd_unpublish(name: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Re-activate a previous parser version via the Control API, so connected
+/// workers are notified and the rollback is audited.
+fn cmd_rollback(name: &str, version: &str, json_output: bool) -> anyhow::Result<()> {
+    let client = require_control_client()?;
+    let actor = std::env::var("USER").ok();
+    let rollback = client
+        .rollback_plugin(name, version, actor.as_deref())
+        .map_err(|e| {
+            HelpfulError::new(format!(
+                "Failed to roll back parser '{}' to {}",
+                name, version
+            ))
+            .with_context(e.to_string())
+            .with_suggestion("TRY: casparian parser ls  (list versions and their status)")
+        })?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&rollback)?);
+        return Ok(());
+    }
+
+    println!(
+        "Rolled back parser '{}': {} -> {}",
+        rollback.plugin_name,
+        rollback.from_version.as_deref().unwrap_or("(none active)"),
+        rollback.to_version
+    );
+    println!("Workers notified: {}", rollback.workers_notified);
+    Ok(())
+}
+
 /// Run backtest against all files for a parser's topic
 fn cmd_backtest(name: &str, limit: Option<usize>, json_output: bool) -> anyhow::Result<()> {
     let _ = (limit, json_output);
@@ -800,6 +975,279 @@ fn cmd_health(name: &str, json_output: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Requested changes to a parser's retry policy; unset fields keep their
+/// current value.
+struct RetryPolicyUpdate {
+    max_attempts: Option<u32>,
+    backoff_base_secs: Option<u64>,
+    jitter: Option<f64>,
+    retry_on: Option<Vec<String>>,
+    reset: bool,
+}
+
+impl RetryPolicyUpdate {
+    fn is_empty(&self) -> bool {
+        !self.reset
+            && self.max_attempts.is_none()
+            && self.backoff_base_secs.is_none()
+            && self.jitter.is_none()
+            && self.retry_on.is_none()
+    }
+}
+
+fn cmd_retry_policy(
+    name: &str,
+    update: RetryPolicyUpdate,
+    json_output: bool,
+) -> anyhow::Result<()> {
+    let conn = connect_db()?;
+    let queue = casparian_sentinel::JobQueue::new(conn);
+    queue.init_error_handling_schema()?;
+
+    let configured = queue.get_retry_policy(name)?;
+    let (policy, is_default) = if update.is_empty() {
+        match configured {
+            Some(policy) => (policy, false),
+            None => (RetryPolicy::default(), true),
+        }
+    } else if update.reset {
+        queue.set_retry_policy(name, None)?;
+        audit_config_change(name, "retry_policy", configured.as_ref(), None);
+        (RetryPolicy::default(), true)
+    } else {
+        let mut policy = configured.clone().unwrap_or_default();
+        if let Some(max_attempts) = update.max_attempts {
+            policy.max_attempts = max_attempts;
+        }
+        if let Some(base) = update.backoff_base_secs {
+            policy.backoff_base_secs = base;
+        }
+        if let Some(jitter) = update.jitter {
+            policy.jitter = jitter;
+        }
+        if let Some(classes) = update.retry_on {
+            policy.retryable_errors = classes
+                .into_iter()
+                .map(|class| class.trim().to_ascii_lowercase())
+                .filter(|class| !class.is_empty())
+                .collect();
+        }
+        policy.validate().map_err(|e| {
+            HelpfulError::new("Invalid retry policy")
+                .with_context(e)
+                .with_suggestion(format!(
+                    "TRY: --retry-on accepts error classes ({}) or categories ({})",
+                    retry::ERROR_CLASSES.join(", "),
+                    ErrorCategory::ALL
+                        .iter()
+                        .map(|category| category.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+        })?;
+        queue.set_retry_policy(name, Some(&policy))?;
+        audit_config_change(name, "retry_policy", configured.as_ref(), Some(&policy));
+        (policy, false)
+    };
+
+    if json_output {
+        let result = serde_json::json!({
+            "parser_name": name,
+            "default": is_default,
+            "policy": policy,
+        });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    println!("Retry Policy: {}", name);
+    println!("========================================");
+    if is_default {
+        println!("  (default policy)");
+    }
+    println!("  Max attempts:     {}", policy.max_attempts);
+    println!("  Backoff base:     {}s", policy.backoff_base_secs);
+    println!("  Jitter:           {:.0}%", policy.jitter * 100.0);
+    println!(
+        "  Retry on:         {}",
+        if policy.retryable_errors.is_empty() {
+            "(nothing)".to_string()
+        } else {
+            policy.retryable_errors.join(", ")
+        }
+    );
+    let delays: Vec<String> = (1..policy.max_attempts)
+        .take(5)
+        .map(|retry| format!("{}s", policy.backoff_ms(retry, 0.0) / 1_000))
+        .collect();
+    if !delays.is_empty() {
+        println!("  Retry delays:     {}", delays.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Record a per-parser config change (`<parser>/<setting>`) on the audit tape.
+fn audit_config_change<T: Serialize>(
+    name: &str,
+    setting: &str,
+    before: Option<&T>,
+    after: Option<&T>,
+) {
+    let action = if after.is_some() { "updated" } else { "reset" };
+    AuditLog::for_sqlite(&get_db_path()).record(
+        audit::entity::CONFIG,
+        &format!("{}/{}", name, setting),
+        action,
+        None,
+        before.and_then(audit::snapshot),
+        after.and_then(audit::snapshot),
+    );
+}
+
+/// Show or update a parser's resource limits. Limits given in `update`
+/// replace the configured ones; unset fields keep their current value.
+fn cmd_limits(
+    name: &str,
+    update: ResourceLimits,
+    reset: bool,
+    json_output: bool,
+) -> anyhow::Result<()> {
+    let conn = connect_db()?;
+    let queue = casparian_sentinel::JobQueue::new(conn);
+    queue.init_error_handling_schema()?;
+
+    let configured = queue.get_resource_limits(name)?.unwrap_or_default();
+    let limits = if reset {
+        queue.set_resource_limits(name, None)?;
+        audit_config_change(name, "resource_limits", Some(&configured), None);
+        ResourceLimits::default()
+    } else if update.is_unlimited() {
+        configured
+    } else {
+        let limits = ResourceLimits {
+            timeout_secs: update.timeout_secs.or(configured.timeout_secs),
+            max_cpu_secs: update.max_cpu_secs.or(configured.max_cpu_secs),
+            max_memory_bytes: update.max_memory_bytes.or(configured.max_memory_bytes),
+            max_decompressed_bytes: update
+                .max_decompressed_bytes
+                .or(configured.max_decompressed_bytes),
+            max_scratch_bytes: update.max_scratch_bytes.or(configured.max_scratch_bytes),
+        };
+        limits.validate().map_err(|e| {
+            HelpfulError::new("Invalid resource limits")
+                .with_context(e)
+                .with_suggestion("TRY: Use --reset to remove all limits for this parser")
+        })?;
+        queue.set_resource_limits(name, Some(&limits))?;
+        audit_config_change(name, "resource_limits", Some(&configured), Some(&limits));
+        limits
+    };
+
+    if json_output {
+        let result = serde_json::json!({
+            "parser_name": name,
+            "unlimited": limits.is_unlimited(),
+            "limits": limits,
+        });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    let show = |value: Option<String>| value.unwrap_or_else(|| "(unlimited)".to_string());
+    println!("Resource Limits: {}", name);
+    println!("========================================");
+    println!(
+        "  Wall-clock:       {}",
+        show(limits.timeout_secs.map(|secs| format!("{}s", secs)))
+    );
+    println!(
+        "  CPU time:         {}",
+        show(limits.max_cpu_secs.map(|secs| format!("{}s", secs)))
+    );
+    println!(
+        "  Memory:           {}",
+        show(limits.max_memory_bytes.map(format_size))
+    );
+    println!(
+        "  Decompressed:     {}{}",
+        format_size(limits.decompressed_limit()),
+        if limits.max_decompressed_bytes.is_none() {
+            " (default)"
+        } else {
+            ""
+        }
+    );
+    println!(
+        "  Scratch:          {}",
+        show(limits.max_scratch_bytes.map(format_size))
+    );
+    if !limits.is_unlimited() {
+        println!();
+        println!("Jobs exceeding a limit are killed and fail without retry.");
+    }
+
+    Ok(())
+}
+
+/// Show or update a parser's CSV dialect. Fields given in `update` replace
+/// the configured ones; unset fields keep their current value.
+fn cmd_csv_dialect(
+    name: &str,
+    update: &CsvDialectArgs,
+    reset: bool,
+    json_output: bool,
+) -> anyhow::Result<()> {
+    let conn = connect_db()?;
+    let queue = casparian_sentinel::JobQueue::new(conn);
+    queue.init_error_handling_schema()?;
+
+    let configured = queue.get_csv_dialect(name)?;
+    let dialect = if reset {
+        queue.set_csv_dialect(name, None)?;
+        audit_config_change(name, "csv_dialect", configured.as_ref(), None);
+        None
+    } else if update.is_empty() {
+        configured
+    } else {
+        let dialect = update.apply(configured.clone().unwrap_or_default());
+        dialect.validate().map_err(|e| {
+            HelpfulError::new("Invalid CSV dialect")
+                .with_context(e)
+                .with_suggestion("TRY: Use --reset to fall back to the detected dialect")
+        })?;
+        queue.set_csv_dialect(name, Some(&dialect))?;
+        audit_config_change(name, "csv_dialect", configured.as_ref(), Some(&dialect));
+        Some(dialect)
+    };
+
+    if json_output {
+        let result = serde_json::json!({
+            "parser_name": name,
+            "configured": dialect.is_some(),
+            "dialect": dialect,
+        });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    println!("CSV Dialect: {}", name);
+    println!("========================================");
+    match &dialect {
+        Some(dialect) => print_dialect(dialect),
+        None => {
+            println!("  (not configured)");
+            println!();
+            println!("Files are read with the dialect scout detected, or the parser's defaults.");
+            return Ok(());
+        }
+    }
+    println!();
+    println!("Tagging rules with a CSV dialect take precedence over this one.");
+
+    Ok(())
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -4,26 +4,31 @@ use crate::cli::config;
 use crate::cli::context;
 use crate::cli::error::HelpfulError;
 use anyhow::{Context, Result};
+use casparian::scout::incremental::{self, IncrementalRead};
 use casparian::scout::{SourceId, WorkspaceId};
 use casparian::storage::{
     PipelineStore, Pipeline, SelectionFilters, SelectionResolution, WatermarkField,
 };
 use casparian::telemetry::TelemetryRecorder;
 use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::naming::expand_namespace;
 use casparian_protocol::telemetry as protocol_telemetry;
-use casparian_protocol::types::SchemaDefinition;
+use casparian_protocol::types::{JobPriority, SchemaDefinition};
 use casparian_protocol::{
     defaults, materialization_key, output_target_key, schema_hash, table_name_with_schema,
-    PipelineRunStatus, ProcessingStatus, SchemaColumnSpec, SinkConfig, SinkMode,
+    PipelineRunStatus, ProcessingStatus, RuntimeKind, SchemaColumnSpec, SchemaEvolution,
+    SinkConfig, SinkMode,
 };
 use casparian_schema::approval::derive_scope_id;
 use casparian_schema::{SchemaContract, SchemaStorage};
+use casparian_sentinel::db::job_ids;
 use casparian_sentinel::ExpectedOutputs;
+use casparian_sinks::template::validate_sink_uri_template;
 use chrono::{TimeZone, Utc};
 use clap::Subcommand;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tracing::warn;
 use uuid::Uuid;
@@ -45,6 +50,9 @@ pub enum PipelineAction {
         /// Preview only (no DB writes)
         #[arg(long)]
         dry_run: bool,
+        /// Reprocess files whose outputs are already materialized
+        #[arg(long)]
+        force: bool,
     },
     /// Backfill a pipeline over a date range (inclusive)
     Backfill {
@@ -59,6 +67,9 @@ pub enum PipelineAction {
         /// Preview only (no DB writes)
         #[arg(long)]
         dry_run: bool,
+        /// Reprocess files whose outputs are already materialized
+        #[arg(long)]
+        force: bool,
     },
 }
 
@@ -69,10 +80,12 @@ pub fn run(action: PipelineAction, telemetry: Option<TelemetryRecorder>) -> Resu
             name,
             logical_date,
             dry_run,
+            force,
         } => run_pipeline(
             &name,
             logical_date,
             dry_run,
+            force,
             telemetry,
             Some("pipeline_run"),
         )
@@ -82,11 +95,13 @@ pub fn run(action: PipelineAction, telemetry: Option<TelemetryRecorder>) -> Resu
             start,
             end,
             dry_run,
+            force,
         } => backfill_pipeline(
             &name,
             &start,
             &end,
             dry_run,
+            force,
             telemetry,
             Some("pipeline_backfill"),
         )
@@ -141,8 +156,17 @@ struct SelectionConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct RunConfig {
     parser: String,
+    /// Pin runs to this parser version instead of the latest active one.
+    #[serde(default)]
+    parser_version: Option<String>,
     #[serde(default)]
     output: Option<String>,
+    /// Queue lane for the run's jobs (LOW, NORMAL, HIGH or URGENT).
+    #[serde(default)]
+    priority: JobPriority,
+    /// Only parse lines appended since the previous run (native parsers).
+    #[serde(default)]
+    incremental: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -274,6 +298,7 @@ fn run_pipeline(
     name: &str,
     logical_date: Option<String>,
     dry_run: bool,
+    force: bool,
     telemetry: Option<TelemetryRecorder>,
     telemetry_kind: Option<&str>,
 ) -> Result<PipelineRunMetrics> {
@@ -382,8 +407,9 @@ fn run_pipeline(
             enqueue_jobs(
                 &conn,
                 &run_id,
-                &spec.pipeline.run.parser,
+                &spec.pipeline.run,
                 &resolution.file_ids,
+                force,
             )?
         };
 
@@ -451,6 +477,7 @@ fn backfill_pipeline(
     start: &str,
     end: &str,
     dry_run: bool,
+    force: bool,
     telemetry: Option<TelemetryRecorder>,
     telemetry_kind: Option<&str>,
 ) -> Result<()> {
@@ -465,6 +492,7 @@ fn backfill_pipeline(
             name,
             Some(date_str),
             dry_run,
+            force,
             telemetry.clone(),
             telemetry_kind,
         )?;
@@ -709,9 +737,18 @@ fn table_exists(conn: &DbConnection, table: &str) -> Result<bool> {
 struct ParserManifest {
     version: String,
     fingerprint: String,
+    runtime_kind: RuntimeKind,
 }
 
-fn load_parser_manifest(conn: &DbConnection, parser: &str) -> Result<ParserManifest> {
+/// Latest active manifest for `parser`, or exactly `pinned_version` when set.
+///
+/// A pinned version may be superseded (e.g. after a newer publish) but must
+/// have been active at some point.
+fn load_parser_manifest(
+    conn: &DbConnection,
+    parser: &str,
+    pinned_version: Option<&str>,
+) -> Result<ParserManifest> {
     if !table_exists(conn, "cf_plugin_manifest")? {
         return Err(anyhow::anyhow!(
             "Plugin registry table missing; publish '{}' before running pipelines",
@@ -719,26 +756,51 @@ fn load_parser_manifest(conn: &DbConnection, parser: &str) -> Result<ParserManif
         ));
     }
 
-    let row = conn.query_optional(
-        r#"
-        SELECT version, artifact_hash
-        FROM cf_plugin_manifest
-        WHERE plugin_name = ? AND status IN (?, ?)
-        ORDER BY created_at DESC
-        LIMIT 1
-        "#,
-        &[
-            DbValue::from(parser),
-            DbValue::from(casparian_protocol::PluginStatus::Active.as_str()),
-            DbValue::from(casparian_protocol::PluginStatus::Deployed.as_str()),
-        ],
-    )?;
+    let row = match pinned_version {
+        Some(version) => conn.query_optional(
+            r#"
+            SELECT version, artifact_hash, runtime_kind
+            FROM cf_plugin_manifest
+            WHERE plugin_name = ? AND version = ? AND status IN (?, ?, ?)
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            &[
+                DbValue::from(parser),
+                DbValue::from(version),
+                DbValue::from(casparian_protocol::PluginStatus::Active.as_str()),
+                DbValue::from(casparian_protocol::PluginStatus::Deployed.as_str()),
+                DbValue::from(casparian_protocol::PluginStatus::Superseded.as_str()),
+            ],
+        )?,
+        None => conn.query_optional(
+            r#"
+            SELECT version, artifact_hash, runtime_kind
+            FROM cf_plugin_manifest
+            WHERE plugin_name = ? AND status IN (?, ?)
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            &[
+                DbValue::from(parser),
+                DbValue::from(casparian_protocol::PluginStatus::Active.as_str()),
+                DbValue::from(casparian_protocol::PluginStatus::Deployed.as_str()),
+            ],
+        )?,
+    };
 
     let Some(row) = row else {
-        return Err(anyhow::anyhow!(
-            "Parser '{}' not found in registry (publish it first)",
-            parser
-        ));
+        return Err(match pinned_version {
+            Some(version) => anyhow::anyhow!(
+                "Parser '{}' version '{}' not found in registry (or never activated)",
+                parser,
+                version
+            ),
+            None => anyhow::anyhow!(
+                "Parser '{}' not found in registry (publish it first)",
+                parser
+            ),
+        });
     };
 
     let version: String = row.get_by_name("version")?;
@@ -748,10 +810,15 @@ fn load_parser_manifest(conn: &DbConnection, parser: &str) -> Result<ParserManif
     } else {
         artifact_hash
     };
+    let runtime_kind: String = row.get_by_name("runtime_kind")?;
+    let runtime_kind = runtime_kind
+        .parse::<RuntimeKind>()
+        .map_err(|err| anyhow::anyhow!(err))?;
 
     Ok(ParserManifest {
         version,
         fingerprint,
+        runtime_kind,
     })
 }
 
@@ -773,6 +840,7 @@ fn schema_definition_from_contract(
             data_type: col.data_type.clone(),
             nullable: col.nullable,
             format: col.format.clone(),
+            constraints: col.constraints.clone(),
         })
         .collect();
 
@@ -822,7 +890,8 @@ fn load_sink_configs(
     if table_exists(conn, "cf_topic_config")? {
         let rows = conn.query_all(
             r#"
-            SELECT topic_name, uri, mode, quarantine_allow, quarantine_max_pct, quarantine_max_count, quarantine_dir
+            SELECT namespace, topic_name, uri, mode, quarantine_allow, quarantine_max_pct,
+                   quarantine_max_count, quarantine_dir
             FROM cf_topic_config
             WHERE plugin_name = ?
             ORDER BY id ASC
@@ -860,10 +929,17 @@ fn load_sink_configs(
                 has_quarantine = true;
             }
 
+            let namespace: String = row.get_by_name("namespace")?;
+            let uri = expand_namespace(&row.get_by_name::<String>("uri")?, &namespace);
+            validate_sink_uri_template(&uri)?;
+            let schema_evolution =
+                SchemaEvolution::from_sink_uri(&uri).map_err(|e| anyhow::anyhow!(e))?;
+
             sinks.push(SinkConfig {
                 topic: row.get_by_name("topic_name")?,
-                uri: row.get_by_name("uri")?,
+                uri,
                 mode,
+                schema_evolution,
                 quarantine_config: if has_quarantine {
                     Some(quarantine_config)
                 } else {
@@ -879,6 +955,7 @@ fn load_sink_configs(
             topic: defaults::DEFAULT_SINK_TOPIC.to_string(),
             uri: defaults::DEFAULT_SINK_URI.to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         });
@@ -1050,15 +1127,29 @@ impl PipelineStoreHandle {
 fn enqueue_jobs(
     conn: &DbConnection,
     run_id: &str,
-    parser: &str,
+    run: &RunConfig,
     file_ids: &[i64],
+    force: bool,
 ) -> Result<EnqueueSummary> {
     if file_ids.is_empty() {
         return Ok(EnqueueSummary::default());
     }
     ensure_queue_schema(conn)?;
 
-    let manifest = load_parser_manifest(conn, parser)?;
+    let parser = run.parser.as_str();
+    let pinned_version = run.parser_version.as_deref();
+    let manifest = load_parser_manifest(conn, parser, pinned_version)?;
+    let scout = if run.incremental {
+        if manifest.runtime_kind != RuntimeKind::NativeExec {
+            anyhow::bail!(
+                "Parser '{}' is not a native parser; incremental runs need one",
+                parser
+            );
+        }
+        Some(casparian::scout::Database::from_conn(conn.clone())?)
+    } else {
+        None
+    };
     let sinks = load_sink_configs(conn, parser, &manifest.version)?;
     let output_targets = output_target_keys_for_sinks(conn, &sinks, parser, &manifest.version)?;
 
@@ -1085,9 +1176,11 @@ fn enqueue_jobs(
 
     let existing_keys = load_existing_materialization_keys(conn, &all_keys)?;
 
+    // One reservation for the whole run; IDs of skipped files go unused
+    let mut reserved = job_ids::reserve_job_ids(conn, file_ids.len() as u64)?;
     let mut summary = EnqueueSummary::default();
     for file_id in file_ids {
-        let should_enqueue = if output_targets.is_empty() {
+        let should_enqueue = if force || output_targets.is_empty() {
             true
         } else if let Some(keys) = keys_by_file.get(file_id) {
             !keys.iter().all(|key| existing_keys.contains(key))
@@ -1102,18 +1195,58 @@ fn enqueue_jobs(
                     file_id
                 )
             })?;
+
+            // Incremental: queue only the lines appended since the last run
+            let mut range = None;
+            if let Some(scout) = &scout {
+                let size = file_meta
+                    .get(file_id)
+                    .map_or(0, |(_, size)| u64::try_from(*size).unwrap_or(0));
+                let previous = if force {
+                    None
+                } else {
+                    scout.get_incremental_state(*file_id)?
+                };
+                match incremental::plan_read(Path::new(input_file), size, previous.as_ref())? {
+                    IncrementalRead::UpToDate => {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    IncrementalRead::Range { start, end, next } => {
+                        range = Some((start, end, next));
+                    }
+                }
+            }
+            let (range_start, range_end) = match &range {
+                Some((start, end, _)) => (Some(i64::try_from(*start)?), Some(i64::try_from(*end)?)),
+                None => (None, None),
+            };
+
+            let job_id = reserved
+                .next()
+                .context("Job ID reservation exhausted")?
+                .to_i64()?;
             conn.execute(
-                "INSERT INTO cf_processing_queue (file_id, input_file, pipeline_run_id, plugin_name, status, priority) VALUES (?, ?, ?, ?, ?, 0)",
+                "INSERT INTO cf_processing_queue (id, file_id, input_file, pipeline_run_id, plugin_name, pinned_version, status, priority, force_rerun, range_start, range_end) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 &[
+                    DbValue::from(job_id),
                     DbValue::from(*file_id),
                     DbValue::from(input_file.as_str()),
                     DbValue::from(run_id),
                     DbValue::from(parser),
+                    DbValue::from(pinned_version),
                     DbValue::from(ProcessingStatus::Queued.as_str()),
+                    DbValue::from(run.priority.value()),
+                    DbValue::from(force),
+                    DbValue::from(range_start),
+                    DbValue::from(range_end),
                 ],
             )
 
             .context("Failed to enqueue job")?;
+            if let (Some(scout), Some((_, _, next))) = (&scout, &range) {
+                scout.set_incremental_state(*file_id, next)?;
+            }
             summary.queued += 1;
         } else {
             summary.skipped += 1;
@@ -1194,6 +1327,7 @@ mod tests {
             topic: "orders".to_string(),
             uri: "parquet://./output".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -1219,6 +1353,7 @@ mod tests {
             topic: "*".to_string(), // Default sink
             uri: "parquet://./output".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -1239,6 +1374,7 @@ mod tests {
             topic: "output".to_string(), // Another default sink variant
             uri: "parquet://./data".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -1259,6 +1395,7 @@ mod tests {
             topic: "*".to_string(),
             uri: "parquet://./output".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -1279,6 +1416,7 @@ mod tests {
             topic: "*".to_string(),
             uri: "parquet://./output".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -1305,6 +1443,7 @@ mod tests {
                 topic: "custom_output".to_string(), // Explicit
                 uri: "parquet://./custom".to_string(),
                 mode: SinkMode::Append,
+                schema_evolution: SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             },
@@ -1312,6 +1451,7 @@ mod tests {
                 topic: "*".to_string(), // Default - will expand
                 uri: "parquet://./output".to_string(),
                 mode: SinkMode::Append,
+                schema_evolution: SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             },
@@ -1332,6 +1472,7 @@ mod tests {
             topic: "*".to_string(),
             uri: "parquet://./output_v1".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -1340,6 +1481,7 @@ mod tests {
             topic: "*".to_string(),
             uri: "parquet://./output_v2".to_string(), // Different URI
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -1362,6 +1504,7 @@ mod tests {
             topic: "*".to_string(),
             uri: "parquet://./output".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -1370,6 +1513,7 @@ mod tests {
             topic: "*".to_string(),
             uri: "parquet://./output".to_string(),
             mode: SinkMode::Replace, // Different mode
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
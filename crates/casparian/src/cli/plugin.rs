@@ -14,6 +14,7 @@ use casparian::trust::{
     load_default_trust_config, PublicKeyBase64, SignerId, TrustConfig, TrustMode,
 };
 use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::capabilities::normalize_requirements;
 use casparian_protocol::{PluginStatus, RuntimeKind, SchemaDefinition};
 use casparian_schema::approval::derive_scope_id;
 use casparian_schema::{
@@ -85,6 +86,8 @@ struct BundleManifest {
     platform_os: Option<String>,
     #[serde(default)]
     platform_arch: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    requirements: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -311,6 +314,7 @@ fn cmd_verify(target: &str, os: Option<String>, arch: Option<String>) -> Result<
         entrypoint: String::new(),
         platform_os: platform_os.clone(),
         platform_arch: platform_arch.clone(),
+        requirements: Vec::new(),
     };
     let bundle_root = install_path(&manifest)?;
 
@@ -475,7 +479,7 @@ fn load_manifest(path: &Path) -> Result<BundleManifest> {
     }
     let content =
         fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
-    let manifest: BundleManifest =
+    let mut manifest: BundleManifest =
         toml::from_str(&content).context("Failed to parse casparian.toml")?;
 
     if manifest.name.trim().is_empty() {
@@ -490,6 +494,8 @@ fn load_manifest(path: &Path) -> Result<BundleManifest> {
     if manifest.entrypoint.trim().is_empty() {
         anyhow::bail!("Manifest field 'entrypoint' must be non-empty");
     }
+    manifest.requirements = normalize_requirements(&manifest.requirements)
+        .map_err(|err| anyhow::anyhow!("Manifest field 'requirements': {}", err))?;
 
     Ok(manifest)
 }
@@ -704,6 +710,11 @@ fn insert_manifest(
     let signer_value = signer_id
         .map(|id| DbValue::from(id.as_str()))
         .unwrap_or(DbValue::Null);
+    let system_requirements = if manifest.requirements.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&manifest.requirements)?)
+    };
 
     conn.execute(
         r#"
@@ -750,7 +761,9 @@ fn insert_manifest(
             ),
             DbValue::Null,
             DbValue::Null,
-            DbValue::Null,
+            system_requirements
+                .map(DbValue::from)
+                .unwrap_or(DbValue::Null),
         ],
     )?;
     Ok(())
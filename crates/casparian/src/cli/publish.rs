@@ -3,6 +3,7 @@
 use anyhow::Result;
 use crate::publish::prepare_publish;
 use casparian_protocol::types::DeployCommand;
+use casparian_protocol::stream::{split_payload, DEFAULT_STREAM_CHUNK_SIZE};
 use casparian_protocol::{JobId, Message, OpCode};
 use zmq::Context;
 
@@ -36,6 +37,8 @@ pub fn run_publish(
     });
 
     // Construct DeployCommand
+    let system_requirements = (!artifact.manifest.requirements.is_empty())
+        .then(|| artifact.manifest.requirements.clone());
     let deploy_cmd = DeployCommand {
         plugin_name: plugin_name.clone(),
         version: version.clone(),
@@ -49,27 +52,45 @@ pub fn run_publish(
         publisher_name,
         publisher_email: email,
         azure_oid: None,
-        system_requirements: None,
+        system_requirements,
+        signer_id: None,
+        signature: None,
+        namespace: None,
     };
 
     // 7. Send via ZMQ DEALER to Sentinel
     let sentinel_addr = addr.unwrap_or_else(crate::get_default_ipc_addr);
     tracing::info!("Connecting to Sentinel at {}", sentinel_addr);
 
+    // A pipe:// Sentinel is reached through a local relay
+    let pipe_link = casparian_pipe::is_pipe_addr(&sentinel_addr)
+        .then(|| casparian_pipe::PipeLink::connect(&sentinel_addr))
+        .transpose()?;
+    let connect_addr = pipe_link
+        .as_ref()
+        .map_or(sentinel_addr.as_str(), |link| link.endpoint());
+
     let context = Context::new();
     let socket = context.socket(zmq::DEALER)?;
-    socket.connect(&sentinel_addr)?;
+    socket.connect(connect_addr)?;
     tracing::info!("✓ Connected to Sentinel");
 
     // Serialize payload
     let payload = serde_json::to_vec(&deploy_cmd)?;
 
-    // Create protocol message
-    let msg = Message::new(OpCode::Deploy, JobId::new(0), payload)?;
-    let (header_bytes, payload_bytes) = msg.pack()?;
+    // Create protocol message(s); large artifacts are streamed in chunks
+    let messages = split_payload(
+        OpCode::Deploy,
+        JobId::new(0),
+        payload,
+        DEFAULT_STREAM_CHUNK_SIZE,
+    )?;
 
-    // Send message (multipart)
-    socket.send_multipart(vec![header_bytes, payload_bytes], 0)?;
+    // Send message(s) (multipart)
+    for msg in &messages {
+        let (header_bytes, payload_bytes) = msg.pack()?;
+        socket.send_multipart(vec![header_bytes, payload_bytes], 0)?;
+    }
     tracing::info!("✓ Sent deployment request");
 
     // 8. Await ACK/ERR response
@@ -0,0 +1,229 @@
+//! `casparian retention` command - retention policies and garbage collection.
+//!
+//! Policies are set per output topic (output name); the `*` topic applies to
+//! outputs without their own policy. `gc` deletes expired output files and
+//! old job logs, and compacts the logs of finished jobs.
+//!
+//! # Usage
+//!
+//! ```bash
+//! casparian retention set orders --keep-versions 5 --keep-days 30
+//! casparian retention set '*' --max-size 50GB
+//! casparian retention list
+//! casparian retention gc --dry-run
+//! casparian retention gc --log-keep-days 14 --json
+//! ```
+
+use anyhow::Result;
+use casparian_db::DbConnection;
+use casparian_sentinel::db::retention::{self, RetentionPolicy, DEFAULT_POLICY_TOPIC};
+use casparian_sentinel::{collect_garbage, GcOptions, GcReport};
+use clap::Subcommand;
+
+use super::config;
+use super::error::HelpfulError;
+use super::output::{format_size, parse_size, print_table};
+
+#[derive(Debug, Subcommand)]
+pub enum RetentionAction {
+    /// Set the retention policy of an output topic ('*' for the default)
+    Set {
+        topic: String,
+        /// Delete outputs older than this many days
+        #[arg(long)]
+        keep_days: Option<u32>,
+        /// Keep at most this many outputs (newest first)
+        #[arg(long)]
+        keep_versions: Option<u32>,
+        /// Keep at most this much output, e.g. 10GB
+        #[arg(long)]
+        max_size: Option<String>,
+    },
+    /// List retention policies
+    List {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Remove the retention policy of an output topic
+    Remove { topic: String },
+    /// Delete expired outputs and job logs
+    Gc {
+        /// Report what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Delete the logs of jobs that logged nothing for this many days
+        #[arg(long)]
+        log_keep_days: Option<u32>,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run(action: RetentionAction) -> Result<()> {
+    let conn = connect_db()?;
+    retention::init_schema(&conn)?;
+
+    match action {
+        RetentionAction::Set {
+            topic,
+            keep_days,
+            keep_versions,
+            max_size,
+        } => {
+            let max_bytes = match max_size {
+                Some(raw) => Some(parse_size(&raw).map_err(|e| {
+                    HelpfulError::new(format!("Invalid --max-size '{}'", raw))
+                        .with_context(e)
+                        .with_suggestion("TRY: Use a size like 500MB or 10GB")
+                })?),
+                None => None,
+            };
+            let policy = RetentionPolicy {
+                topic,
+                keep_days,
+                keep_versions,
+                max_bytes,
+            };
+            if policy.is_empty() {
+                return Err(HelpfulError::new("No retention limit given")
+                    .with_suggestion("TRY: Pass --keep-days, --keep-versions or --max-size")
+                    .into());
+            }
+            retention::set_policy(&conn, &policy)?;
+            println!("Retention policy set for {}", topic_label(&policy.topic));
+            Ok(())
+        }
+        RetentionAction::List { json } => list_policies(&conn, json),
+        RetentionAction::Remove { topic } => {
+            if !retention::remove_policy(&conn, &topic)? {
+                return Err(
+                    HelpfulError::new(format!("No retention policy for '{}'", topic))
+                        .with_suggestion("TRY: casparian retention list")
+                        .into(),
+                );
+            }
+            println!("Retention policy removed for {}", topic_label(&topic));
+            Ok(())
+        }
+        RetentionAction::Gc {
+            dry_run,
+            log_keep_days,
+            json,
+        } => {
+            let options = GcOptions {
+                dry_run,
+                log_keep_days,
+                now_millis: None,
+            };
+            let report = collect_garbage(&conn, &options)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_report(&report);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn connect_db() -> Result<DbConnection> {
+    let db_path = config::state_store_path();
+    DbConnection::open_from_url(&format!("sqlite:{}", db_path.display())).map_err(|e| {
+        HelpfulError::new("Failed to connect to database")
+            .with_context(format!("Database: {}", db_path.display()))
+            .with_suggestion(format!("Error: {}", e))
+            .into()
+    })
+}
+
+fn topic_label(topic: &str) -> String {
+    if topic == DEFAULT_POLICY_TOPIC {
+        "all outputs (default)".to_string()
+    } else {
+        format!("'{}'", topic)
+    }
+}
+
+fn list_policies(conn: &DbConnection, json: bool) -> Result<()> {
+    let policies = retention::list_policies(conn)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&policies)?);
+        return Ok(());
+    }
+    if policies.is_empty() {
+        println!("No retention policies; outputs and logs are kept forever");
+        println!("  TRY: casparian retention set '*' --keep-versions 10");
+        return Ok(());
+    }
+    let limit = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let rows = policies
+        .iter()
+        .map(|policy| {
+            vec![
+                policy.topic.clone(),
+                limit(policy.keep_days.map(|days| days.to_string())),
+                limit(policy.keep_versions.map(|n| n.to_string())),
+                limit(policy.max_bytes.map(format_size)),
+            ]
+        })
+        .collect();
+    print_table(&["TOPIC", "KEEP DAYS", "KEEP VERSIONS", "MAX SIZE"], rows);
+    Ok(())
+}
+
+fn print_report(report: &GcReport) {
+    let verb = if report.dry_run {
+        "Would delete"
+    } else {
+        "Deleted"
+    };
+    if report.expired.is_empty() {
+        println!("No expired outputs");
+    } else {
+        let rows = report
+            .expired
+            .iter()
+            .map(|artifact| {
+                vec![
+                    artifact.job_id.to_string(),
+                    artifact.output_name.clone(),
+                    artifact.reason.to_string(),
+                    format_size(artifact.bytes),
+                    artifact.uri.clone(),
+                ]
+            })
+            .collect();
+        print_table(&["JOB", "OUTPUT", "REASON", "SIZE", "URI"], rows);
+        println!(
+            "{} {} output(s), {}",
+            verb,
+            report.expired.len(),
+            format_size(report.bytes_freed)
+        );
+    }
+    for artifact in &report.protected {
+        println!(
+            "Kept {} (job {}): retained outputs were produced from it",
+            artifact.uri, artifact.job_id
+        );
+    }
+    if report.logs_expired.jobs > 0 {
+        println!(
+            "{} logs of {} job(s), {}",
+            verb,
+            report.logs_expired.jobs,
+            format_size(report.logs_expired.bytes)
+        );
+    }
+    if report.logs_compacted > 0 {
+        let verb = if report.dry_run {
+            "Would compact"
+        } else {
+            "Compacted"
+        };
+        println!("{} logs of {} job(s)", verb, report.logs_compacted);
+    }
+    for error in &report.errors {
+        eprintln!("Failed to delete {}", error);
+    }
+}
@@ -3,13 +3,16 @@
 //! Data-oriented design: structs for data, functions for behavior.
 
 use crate::cli::config::state_store_path;
+use crate::cli::csv_dialect::{print_dialect, CsvDialectArgs};
 use crate::cli::error::HelpfulError;
 use crate::cli::output::print_table;
 use crate::cli::workspace;
-use casparian::scout::{Database, TaggingRule, TaggingRuleId, WorkspaceId};
+use casparian::scout::{
+    Database, RuleMatchMode, RulePattern, TaggingRule, TaggingRuleId, WorkspaceId,
+};
 use casparian_db::DbValue;
+use casparian_protocol::TextEncoding;
 use clap::Subcommand;
-use glob::Pattern;
 
 /// Subcommands for rule management
 #[derive(Subcommand, Debug, Clone)]
@@ -44,16 +47,44 @@ pub enum RuleAction {
     },
     /// Test a rule against a path
     Test { id: String, path: String },
+    /// Show or set how overlapping rules combine (first-match, all-match)
+    Mode { mode: Option<String> },
+    /// Show or set how CSV files tagged by a rule are read
+    #[command(name = "csv-dialect")]
+    CsvDialect {
+        id: String,
+        #[command(flatten)]
+        dialect: CsvDialectArgs,
+        /// Remove the rule's dialect
+        #[arg(
+            long,
+            conflicts_with_all = ["delimiter", "quote", "escape", "skip_rows", "encoding", "null_tokens"]
+        )]
+        reset: bool,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show or set the text encoding of files tagged by a rule
+    Encoding {
+        id: String,
+        /// utf-8, utf-16le, utf-16be, latin-1, or windows-1252
+        encoding: Option<TextEncoding>,
+        /// Remove the override; the worker detects the encoding
+        #[arg(long, conflicts_with = "encoding")]
+        reset: bool,
+        #[arg(long)]
+        json: bool,
+    },
 }
 
-/// Validate a glob pattern
-fn validate_pattern(pattern: &str) -> Result<Pattern, HelpfulError> {
-    Pattern::new(pattern).map_err(|e| {
+/// Validate a rule pattern (globs joined by `;`, `!` prefix to exclude)
+fn validate_pattern(pattern: &str) -> Result<RulePattern, HelpfulError> {
+    RulePattern::parse(pattern).map_err(|e| {
         HelpfulError::new(format!("Invalid glob pattern: {}", e))
             .with_context(format!("Pattern: {}", pattern))
-            .with_suggestion("TRY: Examples: *.csv, sales/**/*.json, data_????.log")
+            .with_suggestion("TRY: Examples: *.csv, sales/**/*.json, **/logs/*.evtx;!**/archive/**")
             .with_suggestion(
-                "TRY: Use * for any characters, ** for directories, ? for single character",
+                "TRY: * and ? stay within a directory, ** spans directories, ! excludes",
             )
     })
 }
@@ -67,7 +98,7 @@ fn find_rule<'a>(rules: &'a [TaggingRule], input: &str) -> Option<&'a TaggingRul
 
 /// Count how many files in the database match a pattern
 fn count_matching_files(db: &Database, workspace_id: &WorkspaceId, pattern: &str) -> u64 {
-    let pat = match Pattern::new(pattern) {
+    let pat = match RulePattern::parse(pattern) {
         Ok(p) => p,
         Err(_) => return 0,
     };
@@ -88,7 +119,7 @@ fn count_matching_files(db: &Database, workspace_id: &WorkspaceId, pattern: &str
             .unwrap_or_default();
         for file in files {
             // Match against relative path
-            if pat.matches(&file.rel_path) {
+            if pat.is_match(&file.rel_path) {
                 matched += 1;
             }
         }
@@ -126,6 +157,19 @@ fn run_with_action(action: RuleAction) -> anyhow::Result<()> {
         RuleAction::Show { id, json } => show_rule(&db, &workspace_id, &id, json),
         RuleAction::Remove { id, force } => remove_rule(&db, &workspace_id, &id, force),
         RuleAction::Test { id, path } => test_rule(&db, &workspace_id, &id, &path),
+        RuleAction::Mode { mode } => rule_mode(&db, &workspace_id, mode.as_deref()),
+        RuleAction::CsvDialect {
+            id,
+            dialect,
+            reset,
+            json,
+        } => rule_csv_dialect(&db, &workspace_id, &id, &dialect, reset, json),
+        RuleAction::Encoding {
+            id,
+            encoding,
+            reset,
+            json,
+        } => rule_encoding(&db, &workspace_id, &id, encoding, reset, json),
     }
 }
 
@@ -298,7 +342,7 @@ fn show_rule(
     if matched > 0 {
         println!();
         println!("SAMPLE MATCHES (first 5):");
-        let pat = Pattern::new(&rule.pattern).unwrap();
+        let pat = validate_pattern(&rule.pattern)?;
         let sources = db.list_sources(workspace_id).unwrap_or_default();
         let mut count = 0;
         for source in sources {
@@ -306,7 +350,7 @@ fn show_rule(
                 .list_files_by_source(&source.id, 1000)
                 .unwrap_or_default();
             for file in files {
-                if pat.matches(&file.rel_path) {
+                if pat.is_match(&file.rel_path) {
                     println!("  {}", file.rel_path);
                     count += 1;
                     if count >= 5 {
@@ -399,7 +443,7 @@ fn test_rule(
 
     let pat = validate_pattern(&rule.pattern)?;
 
-    if pat.matches(path) {
+    if pat.is_match(path) {
         println!("MATCH: '{}' matches pattern '{}'", path, rule.pattern);
         println!("  Would be tagged as: {}", rule.tag);
     } else {
@@ -415,6 +459,117 @@ fn test_rule(
     Ok(())
 }
 
+fn rule_mode(db: &Database, workspace_id: &WorkspaceId, mode: Option<&str>) -> anyhow::Result<()> {
+    let Some(raw) = mode else {
+        let current = db.get_rule_match_mode(workspace_id)?;
+        println!("Rule match mode: {}", current);
+        return Ok(());
+    };
+
+    let mode = RuleMatchMode::parse(raw).ok_or_else(|| {
+        HelpfulError::new(format!("Unknown rule match mode: {}", raw))
+            .with_suggestion("TRY: first-match (highest-priority rule wins)")
+            .with_suggestion("TRY: all-match (every matching rule adds its topic)")
+    })?;
+    db.set_rule_match_mode(workspace_id, mode)
+        .map_err(|e| HelpfulError::new(format!("Failed to set rule match mode: {}", e)))?;
+    println!("Rule match mode set to {}", mode);
+    Ok(())
+}
+
+fn rule_csv_dialect(
+    db: &Database,
+    workspace_id: &WorkspaceId,
+    id: &str,
+    update: &CsvDialectArgs,
+    reset: bool,
+    json: bool,
+) -> anyhow::Result<()> {
+    let rules = db.list_tagging_rules(workspace_id)?;
+    let rule = find_rule(&rules, id).ok_or_else(|| {
+        HelpfulError::new(format!("Rule not found: {}", id))
+            .with_suggestion("TRY: Use 'casparian rule ls' to see available rules")
+    })?;
+
+    let configured = db.get_rule_csv_dialect(&rule.id)?;
+    let dialect = if reset {
+        db.set_rule_csv_dialect(&rule.id, None)?;
+        None
+    } else if update.is_empty() {
+        configured
+    } else {
+        let dialect = update.apply(configured.unwrap_or_default());
+        dialect.validate().map_err(|e| {
+            HelpfulError::new("Invalid CSV dialect")
+                .with_context(e)
+                .with_suggestion("TRY: Use --reset to remove the rule's dialect")
+        })?;
+        db.set_rule_csv_dialect(&rule.id, Some(&dialect))
+            .map_err(|e| HelpfulError::new(format!("Failed to set CSV dialect: {}", e)))?;
+        Some(dialect)
+    };
+
+    if json {
+        let output = serde_json::json!({
+            "id": rule.id,
+            "pattern": rule.pattern,
+            "dialect": dialect,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("CSV DIALECT: {}", rule.pattern);
+    match &dialect {
+        Some(dialect) => print_dialect(dialect),
+        None => println!("  (not configured; the parser's or the detected dialect applies)"),
+    }
+    Ok(())
+}
+
+fn rule_encoding(
+    db: &Database,
+    workspace_id: &WorkspaceId,
+    id: &str,
+    update: Option<TextEncoding>,
+    reset: bool,
+    json: bool,
+) -> anyhow::Result<()> {
+    let rules = db.list_tagging_rules(workspace_id)?;
+    let rule = find_rule(&rules, id).ok_or_else(|| {
+        HelpfulError::new(format!("Rule not found: {}", id))
+            .with_suggestion("TRY: Use 'casparian rule ls' to see available rules")
+    })?;
+
+    let encoding = if reset {
+        db.set_rule_input_encoding(&rule.id, None)?;
+        None
+    } else if let Some(encoding) = update {
+        db.set_rule_input_encoding(&rule.id, Some(encoding))
+            .map_err(|e| HelpfulError::new(format!("Failed to set encoding: {}", e)))?;
+        Some(encoding)
+    } else {
+        db.get_rule_input_encoding(&rule.id)?
+    };
+
+    if json {
+        let output = serde_json::json!({
+            "id": rule.id,
+            "pattern": rule.pattern,
+            "encoding": encoding,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("ENCODING: {}", rule.pattern);
+    match encoding {
+        Some(encoding) => println!("  {}", encoding),
+        None => println!("  (not configured; the worker detects it from the file)"),
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -436,20 +591,24 @@ mod tests {
 
     #[test]
     fn test_pattern_matching() {
-        let pat = Pattern::new("*.csv").unwrap();
-        assert!(pat.matches("test.csv"));
-        // Note: glob's default MatchOptions allows * to match /
-        // To enforce strict path matching, use MatchOptions { require_literal_separator: true, .. }
-        assert!(!pat.matches("test.json"));
+        let pat = RulePattern::parse("*.csv").unwrap();
+        assert!(pat.is_match("test.csv"));
+        assert!(!pat.is_match("test.json"));
+
+        // * stays within a directory; ! excludes
+        let pat = RulePattern::parse("data/*.csv;!data/tmp_*").unwrap();
+        assert!(pat.is_match("data/test.csv"));
+        assert!(!pat.is_match("data/nested/test.csv"));
+        assert!(!pat.is_match("data/tmp_test.csv"));
     }
 
     #[test]
     fn test_recursive_pattern_matching() {
-        let pat = Pattern::new("**/*.csv").unwrap();
-        assert!(pat.matches("test.csv"));
-        assert!(pat.matches("data/test.csv"));
-        assert!(pat.matches("data/nested/test.csv"));
-        assert!(!pat.matches("test.json"));
+        let pat = RulePattern::parse("**/*.csv").unwrap();
+        assert!(pat.is_match("test.csv"));
+        assert!(pat.is_match("data/test.csv"));
+        assert!(pat.is_match("data/nested/test.csv"));
+        assert!(!pat.is_match("test.json"));
     }
 
     #[test]
@@ -13,6 +13,13 @@
 //! casparian run parser.py input.csv --sink parquet://./output/
 //! casparian run parser.py input.csv --sink duckdb:///data.db
 //!
+//! # Per-output sinks (other outputs go to --sink)
+//! casparian run parser.py input.csv \
+//!     --output-sink events=duckdb:///data.db --output-mode events=replace
+//!
+//! # Placeholders resolved per output ({plugin} is the parser file stem)
+//! casparian run parser.py input.csv --sink 'parquet://./output/{plugin}/{date:yyyy-MM-dd}/'
+//!
 //! # Dry run
 //! casparian run parser.py input.csv --whatif
 //! ```
@@ -30,8 +37,11 @@ use uuid::Uuid;
 
 use crate::cli::error::HelpfulError;
 use casparian::runner::{DevRunner, LogDestination, ParserRef};
+use casparian_protocol::types::SinkMode;
 use casparian_security::signing::sha256;
-use casparian_sinks::{plan_outputs, write_output_plan, OutputDescriptor};
+use casparian_sinks::template::{validate_sink_uri_template, TemplateVars};
+use casparian_sinks::{plan_outputs, write_routed_output_plan, OutputDescriptor, OutputRoutes};
+use std::collections::BTreeMap;
 
 /// Arguments for the `run` command
 #[derive(Debug, Args)]
@@ -48,6 +58,14 @@ pub struct RunArgs {
     #[arg(long, short, default_value_t = defaults::DEFAULT_SINK_URI.to_string())]
     pub sink: String,
 
+    /// Send one output to its own sink: NAME=URI (repeatable)
+    #[arg(long = "output-sink", value_name = "NAME=URI")]
+    pub output_sinks: Vec<String>,
+
+    /// Sink mode for one output: NAME=append|replace|error|upsert (repeatable)
+    #[arg(long = "output-mode", value_name = "NAME=MODE")]
+    pub output_modes: Vec<String>,
+
     /// Force re-processing even if already processed
     #[arg(long)]
     pub force: bool,
@@ -79,6 +97,8 @@ struct RunResult {
     parser: PathBuf,
     input: PathBuf,
     sink: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    output_sinks: BTreeMap<String, String>,
     whatif: bool,
     batches: usize,
     total_rows: usize,
@@ -113,12 +133,23 @@ pub fn cmd_run(args: RunArgs, telemetry: Option<TelemetryRecorder>) -> Result<()
         );
     }
 
+    let routes = build_output_routes(&args)?;
+    let output_sinks: BTreeMap<String, String> = routes
+        .routed_outputs()
+        .filter_map(|name| {
+            routes
+                .resolve(name)
+                .map(|route| (name.to_string(), route.uri))
+        })
+        .collect();
+
     if args.whatif {
         if args.json {
             let result = RunResult {
                 parser: args.parser.clone(),
                 input: args.input.clone(),
                 sink: args.sink.clone(),
+                output_sinks: output_sinks.clone(),
                 whatif: true,
                 batches: 0,
                 total_rows: 0,
@@ -131,6 +162,9 @@ pub fn cmd_run(args: RunArgs, telemetry: Option<TelemetryRecorder>) -> Result<()
             println!("Running parser: {}", args.parser.display());
             println!("Input file: {}", args.input.display());
             println!("Sink: {}", args.sink);
+            for (name, uri) in &output_sinks {
+                println!("  {} -> {}", name, uri);
+            }
             println!();
             println!("[whatif] Would process file - no output written");
         }
@@ -141,6 +175,9 @@ pub fn cmd_run(args: RunArgs, telemetry: Option<TelemetryRecorder>) -> Result<()
         println!("Running parser: {}", args.parser.display());
         println!("Input file: {}", args.input.display());
         println!("Sink: {}", args.sink);
+        for (name, uri) in &output_sinks {
+            println!("  {} -> {}", name, uri);
+        }
     }
 
     let telemetry_start = Instant::now();
@@ -214,7 +251,16 @@ pub fn cmd_run(args: RunArgs, telemetry: Option<TelemetryRecorder>) -> Result<()
                 .collect();
 
             let outputs = plan_outputs(&descriptors, &result.output_batches, "output")?;
-            let output_artifacts = write_output_plan(&args.sink, &outputs, "dev", None)?;
+            check_routed_outputs(&routes, &outputs)?;
+            let plugin_name = args
+                .parser
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let routes = routes
+                .clone()
+                .with_template_vars(TemplateVars::new(plugin_name));
+            let output_artifacts = write_routed_output_plan(&routes, &outputs, "dev", None)?;
             for artifact in output_artifacts {
                 let name = artifact.name;
                 let uri = artifact.uri;
@@ -236,6 +282,7 @@ pub fn cmd_run(args: RunArgs, telemetry: Option<TelemetryRecorder>) -> Result<()
             parser: args.parser.clone(),
             input: args.input.clone(),
             sink: args.sink.clone(),
+            output_sinks: output_sinks.clone(),
             whatif: false,
             batches,
             total_rows,
@@ -303,6 +350,76 @@ pub fn cmd_run(args: RunArgs, telemetry: Option<TelemetryRecorder>) -> Result<()
     }
 }
 
+/// Parse `--output-sink` / `--output-mode` into routes over the `--sink` default.
+fn build_output_routes(args: &RunArgs) -> Result<OutputRoutes> {
+    check_sink_template(&args.sink)?;
+    let mut routes = OutputRoutes::new(Some(args.sink.clone()));
+    for value in &args.output_sinks {
+        let (name, uri) =
+            split_output_assignment(value, "--output-sink", "events=duckdb:///data.db")?;
+        check_sink_template(uri)?;
+        routes = routes.route(name, uri, None);
+    }
+    for value in &args.output_modes {
+        let (name, mode) = split_output_assignment(value, "--output-mode", "events=replace")?;
+        let mode = mode.parse::<SinkMode>().map_err(|err| {
+            HelpfulError::new(format!("Invalid --output-mode '{}': {}", value, err))
+                .with_suggestion("TRY: Use one of: append, replace, error, upsert")
+        })?;
+        routes = routes.with_mode(name, mode)?;
+    }
+    Ok(routes)
+}
+
+/// Catch placeholder typos before the parser runs.
+fn check_sink_template(uri: &str) -> Result<()> {
+    validate_sink_uri_template(uri).map_err(|err| {
+        HelpfulError::new(err.to_string())
+            .with_suggestion(
+                "TRY: Use {plugin}, {tag}, {output}, {job_id}, {namespace} or {date:yyyy-MM-dd}",
+            )
+            .into()
+    })
+}
+
+fn split_output_assignment<'a>(
+    value: &'a str,
+    flag: &str,
+    example: &str,
+) -> Result<(&'a str, &'a str)> {
+    match value.split_once('=') {
+        Some((name, target)) if !name.trim().is_empty() && !target.trim().is_empty() => {
+            Ok((name.trim(), target.trim()))
+        }
+        _ => Err(HelpfulError::new(format!("Invalid {} '{}'", flag, value))
+            .with_context("Expected NAME=VALUE")
+            .with_suggestion(format!("TRY: {} {}", flag, example))
+            .into()),
+    }
+}
+
+/// A route for an output the parser did not produce is almost always a typo.
+fn check_routed_outputs(
+    routes: &OutputRoutes,
+    outputs: &[casparian_sinks::OutputPlan],
+) -> Result<()> {
+    let produced: Vec<&str> = outputs.iter().map(|output| output.name()).collect();
+    let unknown: Vec<&str> = routes
+        .routed_outputs()
+        .filter(|name| !produced.contains(name))
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(HelpfulError::new(format!(
+        "Parser has no output named [{}]",
+        unknown.join(", ")
+    ))
+    .with_context(format!("Parser outputs: {}", produced.join(", ")))
+    .with_suggestion("TRY: Match --output-sink/--output-mode names to the parser's outputs")
+    .into())
+}
+
 pub(crate) fn ensure_dev_venv(source: &str) -> Result<Option<PathBuf>> {
     if std::env::var("VIRTUAL_ENV").is_ok() {
         return Ok(None);
@@ -0,0 +1,119 @@
+//! Session command for Casparian CLI
+//!
+//! Packages intent-pipeline sessions for support cases:
+//! - `export` - Write a session (tape, state history, proposals, redacted
+//!   samples, backtest reports) to a zip bundle
+//! - `import` - Unpack a bundle read-only into a separate sessions directory
+
+use crate::cli::config;
+use anyhow::{Context, Result};
+use casparian_mcp::intent::{ExportOptions, SessionArchive, SessionId, SessionStore};
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand, Debug)]
+pub enum SessionCommands {
+    /// Export a session to a zip bundle
+    Export {
+        /// Session ID
+        session_id: String,
+
+        /// Output zip path (default: session-{id}.zip)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Tape recorded during the session, included as-is
+        #[arg(long)]
+        tape: Option<PathBuf>,
+
+        /// Sessions directory (default: CASP_SESSION_DIR or ~/.casparian_flow/sessions)
+        #[arg(long)]
+        sessions_dir: Option<PathBuf>,
+    },
+
+    /// Import a session bundle read-only for inspection
+    Import {
+        /// Path to the bundle written by `session export`
+        bundle: PathBuf,
+
+        /// Sessions directory to unpack into (default: ~/.casparian_flow/imported_sessions)
+        #[arg(long)]
+        into: Option<PathBuf>,
+    },
+}
+
+pub fn run_session_command(cmd: SessionCommands) -> Result<()> {
+    match cmd {
+        SessionCommands::Export {
+            session_id,
+            output,
+            tape,
+            sessions_dir,
+        } => export_session(&session_id, output, tape, sessions_dir),
+        SessionCommands::Import { bundle, into } => import_session(&bundle, into),
+    }
+}
+
+fn export_session(
+    session_id: &str,
+    output: Option<PathBuf>,
+    tape: Option<PathBuf>,
+    sessions_dir: Option<PathBuf>,
+) -> Result<()> {
+    let session_id: SessionId = session_id
+        .parse()
+        .with_context(|| format!("Invalid session ID: {}", session_id))?;
+    let store = match sessions_dir {
+        Some(dir) => SessionStore::with_root(dir),
+        None => SessionStore::new(),
+    };
+    let bundle = store.get_session(session_id).with_context(|| {
+        format!(
+            "Session {} not found in {}",
+            session_id,
+            store.root().display()
+        )
+    })?;
+
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("session-{}.zip", session_id)));
+    let options = ExportOptions {
+        tape,
+        ..Default::default()
+    };
+    let info = bundle
+        .export(&output, &options)
+        .with_context(|| format!("Failed to export session to {}", output.display()))?;
+
+    println!("Exported session {} to {}", session_id, output.display());
+    println!("  State:   {}", info.state);
+    println!("  Files:   {}", info.files.len());
+    println!(
+        "  Samples: {} (redaction: {:?})",
+        info.sample_count, info.redaction_mode
+    );
+    if let Some(tape) = &info.tape {
+        println!("  Tape:    {}", tape);
+    }
+    Ok(())
+}
+
+fn import_session(bundle: &Path, into: Option<PathBuf>) -> Result<()> {
+    let archive = SessionArchive::open(bundle)
+        .with_context(|| format!("Failed to open session bundle: {}", bundle.display()))?;
+    let root = into.unwrap_or_else(|| config::casparian_home().join("imported_sessions"));
+    let session = archive
+        .unpack(&root)
+        .with_context(|| format!("Failed to import session into {}", root.display()))?;
+
+    let info = archive.info();
+    println!("Imported session {} (read-only)", info.session_id);
+    println!("  Exported: {}", info.exported_at.to_rfc3339());
+    println!("  State:    {}", info.state);
+    println!("  Location: {}", session.session_dir().display());
+    println!();
+    println!(
+        "To inspect it with the MCP tools, set CASP_SESSION_DIR={}",
+        root.display()
+    );
+    Ok(())
+}
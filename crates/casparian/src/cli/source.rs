@@ -7,7 +7,10 @@ use crate::cli::context;
 use crate::cli::error::HelpfulError;
 use crate::cli::output::{format_size, print_table};
 use crate::cli::workspace;
-use casparian::scout::{Database, Scanner, Source, SourceId, SourceType, WorkspaceId};
+use casparian::scout::{
+    Database, ScanCancelToken, ScanConfig, Scanner, Source, SourceId, SourceType, SourceWatcher,
+    WatchEvent, WatchMode, WorkspaceId,
+};
 use casparian_db::DbValue;
 use clap::Subcommand;
 use std::path::PathBuf;
@@ -74,6 +77,22 @@ pub enum SourceAction {
         /// Source name or ID
         name: String,
     },
+    /// Watch sources and keep discovered files in sync until interrupted
+    Watch {
+        name: Option<String>,
+        #[arg(long)]
+        all: bool,
+        /// Tag files discovered while watching
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Set how a source is watched (auto, realtime, poll)
+    SetWatchMode {
+        /// Source name or ID
+        name: String,
+        /// auto (realtime on local disks, polling on network mounts), realtime, or poll
+        mode: String,
+    },
 }
 
 /// Source statistics for display
@@ -260,6 +279,12 @@ fn run_with_action(action: SourceAction) -> anyhow::Result<()> {
             set_exec_path(&db, &workspace_id, &name, &exec_path)
         }
         SourceAction::ClearExecPath { name } => clear_exec_path(&db, &workspace_id, &name),
+        SourceAction::Watch { name, all, tag } => {
+            watch_sources(&db, &workspace_id, name, all, tag.as_deref())
+        }
+        SourceAction::SetWatchMode { name, mode } => {
+            set_watch_mode(&db, &workspace_id, &name, &mode)
+        }
         SourceAction::Use { .. } => unreachable!(), // Handled above
     }
 }
@@ -480,6 +505,7 @@ fn show_source(
     };
 
     let stats = get_source_stats(conn, workspace_id, &source.id);
+    let watch_mode = db.get_watch_mode(&source.id)?;
 
     if json {
         let mut output = serde_json::json!({
@@ -490,6 +516,7 @@ fn show_source(
             "source_type": format!("{:?}", source.source_type),
             "enabled": source.enabled,
             "poll_interval_secs": source.poll_interval_secs,
+            "watch_mode": watch_mode.as_str(),
             "files": stats.file_count,
             "size": stats.total_size,
         });
@@ -528,6 +555,7 @@ fn show_source(
     println!("  Type:     {:?}", source.source_type);
     println!("  Enabled:  {}", if source.enabled { "yes" } else { "no" });
     println!("  Poll:     {}s", source.poll_interval_secs);
+    println!("  Watch:    {}", watch_mode);
     println!();
     println!("  Files:    {}", stats.file_count);
     println!("  Size:     {}", format_size(stats.total_size));
@@ -701,6 +729,112 @@ fn sync_sources(
     Ok(())
 }
 
+fn set_watch_mode(
+    db: &Database,
+    workspace_id: &WorkspaceId,
+    name: &str,
+    mode: &str,
+) -> anyhow::Result<()> {
+    let mode = WatchMode::parse(mode).ok_or_else(|| {
+        let valid: Vec<&str> = WatchMode::ALL.iter().map(|m| m.as_str()).collect();
+        HelpfulError::new(format!("Invalid watch mode: {}", mode))
+            .with_suggestion(format!("TRY: One of {}", valid.join(", ")))
+    })?;
+
+    let sources = db.list_sources(workspace_id)?;
+    let source = find_source(&sources, name).ok_or_else(|| {
+        HelpfulError::new(format!("Source not found: {}", name))
+            .with_suggestion("TRY: Use 'casparian source ls' to list sources".to_string())
+    })?;
+
+    db.set_watch_mode(&source.id, mode)
+        .map_err(|e| HelpfulError::new(format!("Failed to update source: {}", e)))?;
+
+    println!("Watch mode for '{}' set to {}", source.name, mode);
+    Ok(())
+}
+
+fn watch_sources(
+    db: &Database,
+    workspace_id: &WorkspaceId,
+    name: Option<String>,
+    all: bool,
+    tag: Option<&str>,
+) -> anyhow::Result<()> {
+    let sources = db.list_sources(workspace_id)?;
+    let to_watch: Vec<Source> = match (name, all) {
+        (_, true) => sources.into_iter().filter(|s| s.enabled).collect(),
+        (Some(name), false) => match find_source(&sources, &name) {
+            Some(source) => vec![source.clone()],
+            None => {
+                return Err(HelpfulError::new(format!("Source not found: {}", name))
+                    .with_suggestion("TRY: Use 'casparian source ls' to see available sources")
+                    .into());
+            }
+        },
+        (None, false) => {
+            return Err(HelpfulError::new("No source specified")
+                .with_suggestion("TRY: casparian source watch <name>")
+                .with_suggestion("TRY: casparian source watch --all")
+                .into());
+        }
+    };
+
+    if to_watch.is_empty() {
+        println!("No enabled sources to watch.");
+        return Ok(());
+    }
+
+    let names: std::collections::HashMap<SourceId, String> = to_watch
+        .iter()
+        .map(|s| (s.id.clone(), s.name.clone()))
+        .collect();
+    let label = |id: &SourceId| names.get(id).cloned().unwrap_or_else(|| id.to_string());
+
+    println!(
+        "Watching {} source(s). Press Ctrl+C to stop.",
+        to_watch.len()
+    );
+    let watcher = SourceWatcher::new(db.clone(), ScanConfig::default());
+    watcher.run(
+        &to_watch,
+        tag,
+        ScanCancelToken::new(),
+        |event| match event {
+            WatchEvent::Watching { source_id, mode } => {
+                println!("  {}: {}", label(source_id), mode);
+            }
+            WatchEvent::FellBackToPoll { source_id, reason } => {
+                println!(
+                    "  {}: realtime unavailable ({}), polling instead",
+                    label(source_id),
+                    reason
+                );
+            }
+            WatchEvent::Synced {
+                source_id,
+                full_scan,
+                stats,
+            } => {
+                if *full_scan || stats.files_new + stats.files_changed + stats.files_deleted > 0 {
+                    println!(
+                        "  {}: {} new, {} changed, {} deleted{}",
+                        label(source_id),
+                        stats.files_new,
+                        stats.files_changed,
+                        stats.files_deleted,
+                        if *full_scan { " (full scan)" } else { "" }
+                    );
+                }
+            }
+            WatchEvent::Failed { source_id, message } => {
+                eprintln!("  {}: error: {}", label(source_id), message);
+            }
+        },
+    )?;
+    Ok(())
+}
+
 /// Set or show the default source context
 fn use_source(name: Option<String>, clear: bool) -> anyhow::Result<()> {
     // Handle --clear flag
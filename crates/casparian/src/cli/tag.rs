@@ -8,10 +8,10 @@ use crate::cli::error::HelpfulError;
 use crate::cli::output::format_size;
 use crate::cli::workspace;
 use casparian::scout::{
-    match_rules_to_files, patterns, Database, FileStatus, RuleApplyFile, RuleApplyRule, TagSource,
-    TaggingRuleId, TaggingSummary, WorkspaceId,
+    match_rules_to_files, patterns, ContentDetection, Database, FileStatus, RuleApplyFile,
+    RuleApplyRule, RuleMatchMode, TagSource, TaggingRuleId, TaggingSummary, WorkspaceId,
 };
-use casparian_db::{DbConnection, DbValue};
+use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
 use chrono::Utc;
 use std::path::PathBuf;
 
@@ -131,7 +131,7 @@ fn load_untagged_files(
 ) -> Result<Vec<RuleApplyFile>, HelpfulError> {
     let rows = conn
         .query_all(
-            "SELECT f.id, f.path, f.rel_path, f.size \
+            "SELECT f.id, f.path, f.rel_path, f.size, f.detected_type, f.detection_confidence \
              FROM scout_files f \
              LEFT JOIN scout_file_tags t \
                 ON t.file_id = f.id AND t.workspace_id = f.workspace_id \
@@ -163,6 +163,7 @@ fn load_untagged_files(
             size: row
                 .get_by_name("size")
                 .map_err(|e| HelpfulError::new(format!("Failed to read file size: {}", e)))?,
+            detection: read_detection(&row)?,
         };
         files.push(file);
     }
@@ -178,7 +179,7 @@ fn get_file_by_path(
 ) -> Result<Option<RuleApplyFile>, HelpfulError> {
     let row = conn
         .query_optional(
-            "SELECT id, path, rel_path, size \
+            "SELECT id, path, rel_path, size, detected_type, detection_confidence \
              FROM scout_files \
              WHERE workspace_id = ? AND path = ?",
             &[DbValue::from(workspace_id.to_string()), DbValue::from(path)],
@@ -203,11 +204,26 @@ fn get_file_by_path(
         size: row
             .get_by_name("size")
             .map_err(|e| HelpfulError::new(format!("Failed to read file size: {}", e)))?,
+        detection: read_detection(&row)?,
     };
 
     Ok(Some(file))
 }
 
+/// Sniffed content type from the `detected_type` / `detection_confidence` columns
+fn read_detection(row: &UnifiedDbRow) -> Result<Option<ContentDetection>, HelpfulError> {
+    let detected_type: Option<String> = row
+        .get_by_name("detected_type")
+        .map_err(|e| HelpfulError::new(format!("Failed to read detected_type: {}", e)))?;
+    let confidence: Option<String> = row
+        .get_by_name("detection_confidence")
+        .map_err(|e| HelpfulError::new(format!("Failed to read detection_confidence: {}", e)))?;
+    Ok(ContentDetection::from_columns(
+        detected_type.as_deref(),
+        confidence.as_deref(),
+    ))
+}
+
 /// Apply tag to a file in the database
 fn apply_tag(
     conn: &DbConnection,
@@ -461,6 +477,10 @@ fn run_apply_rules(dry_run: bool, no_queue: bool) -> anyhow::Result<()> {
             .into());
     }
 
+    // Sniff content types so files with wrong or missing extensions can match
+    db.detect_content_types(&workspace_id)
+        .map_err(|e| HelpfulError::new(format!("Failed to detect file content types: {}", e)))?;
+
     // Load untagged files
     let files = load_untagged_files(conn, &workspace_id)?;
     let total_files = count_all_files(conn, &workspace_id)?;
@@ -474,7 +494,8 @@ fn run_apply_rules(dry_run: bool, no_queue: bool) -> anyhow::Result<()> {
     }
 
     // Match files to rules
-    let (matches, mut summary) = match_rules_to_files(&files, &rules).map_err(|e| {
+    let mode = db.get_rule_match_mode(&workspace_id)?;
+    let (matches, mut summary) = match_rules_to_files(&files, &rules, mode).map_err(|e| {
         HelpfulError::new(format!("Failed to match rules: {}", e))
             .with_context("One or more rule patterns are invalid")
     })?;
@@ -544,7 +565,11 @@ fn run_apply_rules(dry_run: bool, no_queue: bool) -> anyhow::Result<()> {
         }
 
         println!();
-        println!("Applied tags to {} files.", applied);
+        if mode == RuleMatchMode::AllMatch {
+            println!("Applied {} tags ({}).", applied, mode);
+        } else {
+            println!("Applied tags to {} files.", applied);
+        }
 
         if !no_queue {
             println!();
@@ -666,7 +691,9 @@ mod tests {
                 first_seen_at BIGINT,
                 last_seen_at BIGINT,
                 processed_at BIGINT,
-                sentinel_job_id BIGINT
+                sentinel_job_id BIGINT,
+                detected_type TEXT,
+                detection_confidence TEXT
             );
 
             CREATE TABLE scout_file_tags (
@@ -3,15 +3,21 @@
 //! Provides commands for working with session tape recordings:
 //! - `explain` - Summarize what happened in a recorded session
 //! - `validate` - Check tape format and schema version
+//! - `replay` - Re-run a recorded command and report where its domain events diverge
 
+use crate::cli::config;
 use anyhow::{Context, Result};
-use casparian_tape::{EnvelopeV1, EventName, SCHEMA_VERSION};
+use casparian_tape::{
+    compare_domain_events, DivergenceReport, EnvelopeV1, EventName, TapeReader, SCHEMA_VERSION,
+    VOLATILE_FIELDS,
+};
 use clap::Subcommand;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 #[derive(Subcommand, Debug)]
 pub enum TapeCommands {
@@ -30,6 +36,21 @@ pub enum TapeCommands {
         /// Path to the tape file
         tape_file: PathBuf,
     },
+
+    /// Re-run a recorded command against a scratch copy of the Casparian home
+    /// and compare its domain events to the recording
+    Replay {
+        /// Path to the recorded tape file
+        tape_file: PathBuf,
+
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        /// The recorded command, e.g. `-- pipeline run nightly --date 2026-01-01`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
 }
 
 /// Summary of a job from the tape
@@ -71,6 +92,11 @@ pub fn run_tape_command(cmd: TapeCommands) -> Result<()> {
     match cmd {
         TapeCommands::Explain { tape_file, format } => explain_tape(&tape_file, &format),
         TapeCommands::Validate { tape_file } => validate_tape(&tape_file),
+        TapeCommands::Replay {
+            tape_file,
+            format,
+            command,
+        } => replay_tape(&tape_file, &format, &command),
     }
 }
 
@@ -114,6 +140,9 @@ fn process_event(envelope: &EnvelopeV1, summary: &mut TapeSummary) {
         EventName::TapeStopped => {
             // Nothing special to do
         }
+        EventName::Audit(_) => {
+            // Audit records live on the audit tape (`casparian audit`)
+        }
         EventName::UICommand(name) => {
             summary.commands.push(name.clone());
         }
@@ -404,6 +433,175 @@ fn validate_tape(tape_file: &PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Re-run `command` with its own tape and diff the domain events against the
+/// recording. The command runs with `CASPARIAN_HOME` pointed at a scratch copy
+/// of the current home, so anything it writes is discarded.
+fn replay_tape(tape_file: &Path, format: &str, command: &[String]) -> Result<()> {
+    let recorded = read_envelopes(tape_file)?;
+
+    let scratch = tempfile::tempdir().context("Failed to create replay directory")?;
+    let home = scratch.path().join("home");
+    let source_home = config::casparian_home();
+    if source_home.is_dir() {
+        copy_home(&source_home, &home)?;
+    } else {
+        fs::create_dir_all(&home)
+            .with_context(|| format!("Failed to create {}", home.display()))?;
+    }
+
+    let replay_file = scratch.path().join("replay.tape");
+    let exe = std::env::current_exe().context("Failed to locate the casparian executable")?;
+    let status = Command::new(exe)
+        .arg("--tape")
+        .arg(&replay_file)
+        .args(command)
+        .env("CASPARIAN_HOME", &home)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to re-run the recorded command")?;
+    let replayed = read_envelopes(&replay_file)?;
+
+    let recorded_commands = command_names(&recorded);
+    let replayed_commands = command_names(&replayed);
+    if recorded_commands != replayed_commands {
+        anyhow::bail!(
+            "Tape recorded command(s) [{}] but the replay ran [{}]; pass the recorded command after `--`",
+            recorded_commands.join(", "),
+            replayed_commands.join(", ")
+        );
+    }
+
+    let report = compare_domain_events(&recorded, &replayed, VOLATILE_FIELDS);
+    if format == "json" {
+        let output = serde_json::json!({
+            "tape_file": tape_file.display().to_string(),
+            "command": command,
+            "exit_code": status.code(),
+            "report": report,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print_replay_text(tape_file, command, status.code(), &report);
+    }
+
+    if !report.is_match() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn read_envelopes(tape_file: &Path) -> Result<Vec<EnvelopeV1>> {
+    TapeReader::open(tape_file)
+        .with_context(|| format!("Failed to open tape file: {}", tape_file.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to read tape file: {}", tape_file.display()))
+}
+
+fn command_names(envelopes: &[EnvelopeV1]) -> Vec<String> {
+    envelopes
+        .iter()
+        .filter_map(|envelope| match &envelope.event_name {
+            EventName::UICommand(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Copy the Casparian home for a replay, leaving out logs.
+fn copy_home(src: &Path, dst: &Path) -> Result<()> {
+    fs::create_dir_all(dst).with_context(|| format!("Failed to create {}", dst.display()))?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        if entry.file_name() == "logs" {
+            continue;
+        }
+        copy_entry(&entry.path(), &dst.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+fn copy_entry(from: &Path, to: &Path) -> Result<()> {
+    let file_type = fs::symlink_metadata(from)?.file_type();
+    if file_type.is_dir() {
+        fs::create_dir_all(to).with_context(|| format!("Failed to create {}", to.display()))?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_entry(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else if file_type.is_file() {
+        fs::copy(from, to)
+            .with_context(|| format!("Failed to copy {} to {}", from.display(), to.display()))?;
+    }
+    Ok(())
+}
+
+fn print_replay_text(
+    tape_file: &Path,
+    command: &[String],
+    exit_code: Option<i32>,
+    report: &DivergenceReport,
+) {
+    println!("=== Tape Replay ===\n");
+    println!("Tape: {}", tape_file.display());
+    println!("Command: {}", command.join(" "));
+    match exit_code {
+        Some(code) => println!("Exit code: {}", code),
+        None => println!("Exit code: (terminated by signal)"),
+    }
+    println!(
+        "Domain events: {} recorded, {} replayed",
+        report.recorded_events, report.replayed_events
+    );
+
+    let Some(divergence) = &report.divergence else {
+        println!("\nResult: MATCH ✓");
+        return;
+    };
+
+    println!(
+        "\nFirst divergence at domain event #{}:",
+        divergence.index + 1
+    );
+    println!(
+        "  recorded: {}",
+        describe_event(
+            divergence.recorded_event.as_deref(),
+            divergence.recorded_seq
+        )
+    );
+    println!(
+        "  replayed: {}",
+        describe_event(
+            divergence.replayed_event.as_deref(),
+            divergence.replayed_seq
+        )
+    );
+    for field in &divergence.fields {
+        println!(
+            "  {}: {} -> {}",
+            field.path,
+            describe_value(field.recorded.as_ref()),
+            describe_value(field.replayed.as_ref())
+        );
+    }
+    println!("\nResult: DIVERGED ✗");
+}
+
+fn describe_event(name: Option<&str>, seq: Option<u64>) -> String {
+    match (name, seq) {
+        (Some(name), Some(seq)) => format!("{} (seq {})", name, seq),
+        _ => "(no event)".to_string(),
+    }
+}
+
+fn describe_value(value: Option<&Value>) -> String {
+    value
+        .map(Value::to_string)
+        .unwrap_or_else(|| "(absent)".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -452,4 +650,39 @@ mod tests {
         let result = explain_tape(&tape.path().to_path_buf(), "json");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_read_envelopes_lists_recorded_commands() {
+        let tape = create_test_tape(&[
+            r#"{"schema_version":1,"event_id":"e1","seq":0,"timestamp":"2026-01-01T00:00:00Z","correlation_id":null,"parent_id":null,"event_name":{"type":"tape_started"},"payload":{}}"#,
+            r#"{"schema_version":1,"event_id":"e2","seq":1,"timestamp":"2026-01-01T00:00:01Z","correlation_id":"c1","parent_id":null,"event_name":{"type":"ui_command","name":"Pipeline"},"payload":{}}"#,
+            r#"{"schema_version":1,"event_id":"e3","seq":2,"timestamp":"2026-01-01T00:00:02Z","correlation_id":"r1","parent_id":null,"event_name":{"type":"domain_event","name":"run.start"},"payload":{"run_id":"r1"}}"#,
+        ]);
+
+        let envelopes = read_envelopes(tape.path()).unwrap();
+        assert_eq!(envelopes.len(), 3);
+        assert_eq!(command_names(&envelopes), vec!["Pipeline".to_string()]);
+    }
+
+    #[test]
+    fn test_copy_home_skips_logs() {
+        let src = tempfile::tempdir().unwrap();
+        std::fs::write(src.path().join("state.sqlite"), b"db").unwrap();
+        std::fs::create_dir_all(src.path().join("logs")).unwrap();
+        std::fs::write(src.path().join("logs").join("casparian.log"), b"log").unwrap();
+        std::fs::create_dir_all(src.path().join("plugins").join("p")).unwrap();
+        std::fs::write(
+            src.path().join("plugins").join("p").join("parser.py"),
+            b"py",
+        )
+        .unwrap();
+
+        let dst = tempfile::tempdir().unwrap();
+        let home = dst.path().join("home");
+        copy_home(src.path(), &home).unwrap();
+
+        assert!(home.join("state.sqlite").is_file());
+        assert!(home.join("plugins").join("p").join("parser.py").is_file());
+        assert!(!home.join("logs").exists());
+    }
 }
@@ -0,0 +1,509 @@
+//! `casparian top` command - live dashboard of a running Sentinel.
+//!
+//! Polls the Control API for what the Deck shows: worker health, queue
+//! depth, in-flight jobs, recent failures and per-plugin metrics. Plugin
+//! throughput is the change in completed jobs and rows written between two
+//! polls, so it reads zero until the second refresh.
+//!
+//! # Usage
+//!
+//! ```bash
+//! casparian top
+//! casparian top --refresh 5 --failures 20
+//! ```
+//!
+//! Keys: `q`/Esc quit, `r` refresh now.
+
+use anyhow::Result;
+use casparian_protocol::metrics::PluginMetricsReport;
+use casparian_protocol::types::WorkerHealth;
+use casparian_protocol::ProcessingStatus;
+use casparian_sentinel::{
+    ControlClient, JobInfo, QueueStatsInfo, WorkerHealthReport, DEFAULT_CONTROL_ADDR,
+};
+use clap::Args;
+use crossterm::event::{KeyCode, KeyModifiers};
+use crossterm::{
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table};
+use ratatui::{backend::CrosstermBackend, prelude::*, Terminal};
+use std::collections::HashMap;
+use std::io::stdout;
+use std::time::{Duration, Instant};
+
+use super::error::HelpfulError;
+use super::output::format_number;
+use super::tui::event::{Event, EventHandler};
+
+/// Running jobs fetched per refresh.
+const RUNNING_LIMIT: i64 = 50;
+
+#[derive(Debug, Args)]
+pub struct TopArgs {
+    /// Control API address of the Sentinel
+    #[arg(long, env = "CASPARIAN_CONTROL_ADDR", default_value = DEFAULT_CONTROL_ADDR)]
+    pub control_addr: String,
+    /// Seconds between refreshes
+    #[arg(long, default_value_t = 2)]
+    pub refresh: u64,
+    /// Recent failures to show
+    #[arg(long, default_value_t = 10)]
+    pub failures: usize,
+}
+
+/// One poll of the Control API.
+struct Snapshot {
+    taken_at: Instant,
+    queue: QueueStatsInfo,
+    health: WorkerHealthReport,
+    running: Vec<JobInfo>,
+    failures: Vec<JobInfo>,
+    plugins: PluginMetricsReport,
+}
+
+/// Per-plugin counters and throughput between two polls.
+#[derive(Debug, Clone, PartialEq)]
+struct PluginRate {
+    plugin: String,
+    completed: u64,
+    failed: u64,
+    rows_written: u64,
+    jobs_per_min: f64,
+    rows_per_sec: f64,
+    mean_ms: Option<f64>,
+    failure_rate: f64,
+}
+
+struct Top {
+    addr: String,
+    failures: usize,
+    /// Dropped after a failed request; a timed-out REQ socket cannot be reused
+    client: Option<ControlClient>,
+    snapshot: Option<Snapshot>,
+    rates: Vec<PluginRate>,
+    error: Option<String>,
+}
+
+pub fn run(args: TopArgs) -> Result<()> {
+    let client = connect(&args.control_addr)?;
+    if !client.ping().unwrap_or(false) {
+        return Err(HelpfulError::new(format!(
+            "Control API did not respond at {}",
+            args.control_addr
+        ))
+        .with_suggestion("Start sentinel (Control API is on by default)")
+        .into());
+    }
+    let mut top = Top {
+        addr: args.control_addr,
+        failures: args.failures,
+        client: Some(client),
+        snapshot: None,
+        rates: Vec::new(),
+        error: None,
+    };
+    top.refresh();
+
+    enable_raw_mode()?;
+    let mut stdout = stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_loop(
+        &mut terminal,
+        &mut top,
+        Duration::from_secs(args.refresh.max(1)),
+    );
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+fn run_loop<B: Backend>(
+    terminal: &mut Terminal<B>,
+    top: &mut Top,
+    refresh: Duration,
+) -> Result<()> {
+    let events = EventHandler::new(Duration::from_millis(250));
+    let mut last_refresh = Instant::now();
+    loop {
+        terminal.draw(|frame| draw(frame, top))?;
+        match events.next() {
+            Event::Key(key) => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(())
+                }
+                KeyCode::Char('r') => {
+                    top.refresh();
+                    last_refresh = Instant::now();
+                }
+                _ => {}
+            },
+            Event::Tick | Event::Resize(_, _) => {}
+        }
+        if last_refresh.elapsed() >= refresh {
+            top.refresh();
+            last_refresh = Instant::now();
+        }
+    }
+}
+
+fn connect(addr: &str) -> Result<ControlClient> {
+    ControlClient::connect_with_timeout(addr, Duration::from_secs(1)).map_err(|e| {
+        HelpfulError::new(format!("Control API unavailable at {}", addr))
+            .with_context(format!("Connection error: {}", e))
+            .with_suggestion("Start sentinel (Control API is on by default)")
+            .into()
+    })
+}
+
+impl Top {
+    fn refresh(&mut self) {
+        let client = match self.client.take() {
+            Some(client) => client,
+            None => match connect(&self.addr) {
+                Ok(client) => client,
+                Err(err) => {
+                    self.error = Some(err.to_string());
+                    return;
+                }
+            },
+        };
+        match fetch(&client, self.failures) {
+            Ok(snapshot) => {
+                let previous = self
+                    .snapshot
+                    .as_ref()
+                    .map(|prev| (&prev.plugins, snapshot.taken_at - prev.taken_at));
+                self.rates = plugin_rates(previous, &snapshot.plugins);
+                self.snapshot = Some(snapshot);
+                self.client = Some(client);
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+}
+
+fn fetch(client: &ControlClient, failures: usize) -> Result<Snapshot> {
+    Ok(Snapshot {
+        taken_at: Instant::now(),
+        queue: client.get_queue_stats()?,
+        health: client.get_worker_health()?,
+        running: client.list_jobs(Some(ProcessingStatus::Running), Some(RUNNING_LIMIT), None)?,
+        failures: client.list_jobs(Some(ProcessingStatus::Failed), Some(failures as i64), None)?,
+        plugins: client.get_plugin_metrics()?,
+    })
+}
+
+/// Plugin counters of `current` with throughput since `previous`, busiest
+/// first.
+fn plugin_rates(
+    previous: Option<(&PluginMetricsReport, Duration)>,
+    current: &PluginMetricsReport,
+) -> Vec<PluginRate> {
+    let before: HashMap<&str, (u64, u64)> = previous
+        .map(|(report, _)| {
+            report
+                .plugins
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.key.as_str(),
+                        (entry.jobs.completed, entry.jobs.rows_written),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let secs = previous.map_or(0.0, |(_, elapsed)| elapsed.as_secs_f64());
+    let mut rates: Vec<PluginRate> = current
+        .plugins
+        .iter()
+        .map(|entry| {
+            let (completed, rows) = before
+                .get(entry.key.as_str())
+                .copied()
+                .unwrap_or((entry.jobs.completed, entry.jobs.rows_written));
+            let (jobs_per_min, rows_per_sec) = if secs > 0.0 {
+                (
+                    entry.jobs.completed.saturating_sub(completed) as f64 * 60.0 / secs,
+                    entry.jobs.rows_written.saturating_sub(rows) as f64 / secs,
+                )
+            } else {
+                (0.0, 0.0)
+            };
+            PluginRate {
+                plugin: entry.key.clone(),
+                completed: entry.jobs.completed,
+                failed: entry.jobs.failed,
+                rows_written: entry.jobs.rows_written,
+                jobs_per_min,
+                rows_per_sec,
+                mean_ms: entry.jobs.latency.mean_ms(),
+                failure_rate: entry.failure_rate,
+            }
+        })
+        .collect();
+    rates.sort_by(|a, b| {
+        b.rows_per_sec
+            .total_cmp(&a.rows_per_sec)
+            .then(b.completed.cmp(&a.completed))
+            .then(a.plugin.cmp(&b.plugin))
+    });
+    rates
+}
+
+fn draw(frame: &mut Frame, top: &Top) {
+    let [header, middle, plugins, failures, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Percentage(35),
+        Constraint::Percentage(30),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [workers, running] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(middle);
+
+    draw_header(frame, top, header);
+    if let Some(snapshot) = &top.snapshot {
+        draw_workers(frame, &snapshot.health, workers);
+        draw_running(frame, &snapshot.running, running);
+        draw_failures(frame, &snapshot.failures, failures);
+    }
+    draw_plugins(frame, &top.rates, plugins);
+    frame.render_widget(
+        Paragraph::new(" q quit  r refresh").style(Style::default().fg(Color::DarkGray)),
+        footer,
+    );
+}
+
+fn draw_header(frame: &mut Frame, top: &Top, area: Rect) {
+    let mut lines = Vec::new();
+    let status = match (&top.snapshot, &top.error) {
+        (_, Some(error)) => Span::styled(
+            format!("{} - {}", top.addr, error),
+            Style::default().fg(Color::Red),
+        ),
+        (Some(snapshot), None) => Span::raw(format!(
+            "{} - refreshed {}s ago",
+            top.addr,
+            snapshot.taken_at.elapsed().as_secs()
+        )),
+        (None, None) => Span::raw(top.addr.clone()),
+    };
+    lines.push(Line::from(status));
+    if let Some(snapshot) = &top.snapshot {
+        let queue = &snapshot.queue;
+        let pulse = &snapshot.health.pulse;
+        lines.push(Line::from(format!(
+            "Queue: {} queued, {} running, {} failed, {} completed   Workers: {} healthy, {} degraded, {} unreachable, {} flapping",
+            format_number(queue.queued.max(0) as u64),
+            format_number(queue.running.max(0) as u64),
+            format_number(queue.failed.max(0) as u64),
+            format_number(queue.completed.max(0) as u64),
+            pulse.workers_healthy,
+            pulse.workers_degraded,
+            pulse.workers_unreachable,
+            pulse.workers_flapping,
+        )));
+    }
+    let block = Block::default()
+        .borders(Borders::BOTTOM)
+        .title(" casparian top ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_workers(frame: &mut Frame, report: &WorkerHealthReport, area: Rect) {
+    let rows = report.workers.iter().map(|worker| {
+        let color = match worker.health {
+            WorkerHealth::Healthy if !worker.flapping => Color::Green,
+            WorkerHealth::Healthy | WorkerHealth::Degraded => Color::Yellow,
+            WorkerHealth::Unreachable | WorkerHealth::Evicted => Color::Red,
+        };
+        let health = if worker.flapping {
+            format!("{}*", worker.health)
+        } else {
+            worker.health.to_string()
+        };
+        Row::new(vec![
+            worker.worker_id.clone(),
+            health,
+            worker.status.to_string(),
+            worker
+                .current_job_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            format!("{:.0}s", worker.seconds_since_seen),
+        ])
+        .style(Style::default().fg(color))
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(12),
+            Constraint::Length(12),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header_row(&["WORKER", "HEALTH", "STATUS", "JOB", "SEEN"]))
+    .block(titled(format!("Workers ({})", report.workers.len())));
+    frame.render_widget(table, area);
+}
+
+fn draw_running(frame: &mut Frame, jobs: &[JobInfo], area: Rect) {
+    let rows = jobs.iter().map(|job| {
+        Row::new(vec![
+            job.id.to_string(),
+            job.plugin_name.clone(),
+            job.priority.to_string(),
+            job.updated_at.clone().unwrap_or_default(),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Min(16),
+            Constraint::Length(5),
+            Constraint::Length(20),
+        ],
+    )
+    .header(header_row(&["JOB", "PLUGIN", "PRIO", "UPDATED"]))
+    .block(titled(format!("In flight ({})", jobs.len())));
+    frame.render_widget(table, area);
+}
+
+fn draw_plugins(frame: &mut Frame, rates: &[PluginRate], area: Rect) {
+    let rows = rates.iter().map(|rate| {
+        Row::new(vec![
+            rate.plugin.clone(),
+            format!("{:.1}", rate.jobs_per_min),
+            format!("{:.0}", rate.rows_per_sec),
+            format_number(rate.completed),
+            format_number(rate.failed),
+            format_number(rate.rows_written),
+            rate.mean_ms
+                .map(|ms| format!("{:.0}ms", ms))
+                .unwrap_or_else(|| "-".to_string()),
+            format!("{:.1}%", rate.failure_rate * 100.0),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(16),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(12),
+            Constraint::Length(9),
+            Constraint::Length(7),
+        ],
+    )
+    .header(header_row(&[
+        "PLUGIN",
+        "JOBS/MIN",
+        "ROWS/S",
+        "COMPLETED",
+        "FAILED",
+        "ROWS",
+        "MEAN",
+        "FAIL %",
+    ]))
+    .block(titled("Plugins".to_string()));
+    frame.render_widget(table, area);
+}
+
+fn draw_failures(frame: &mut Frame, jobs: &[JobInfo], area: Rect) {
+    let rows = jobs.iter().map(|job| {
+        Row::new(vec![
+            job.id.to_string(),
+            job.plugin_name.clone(),
+            job.updated_at.clone().unwrap_or_default(),
+            job.error_message
+                .as_deref()
+                .and_then(|message| message.lines().next())
+                .unwrap_or("")
+                .to_string(),
+        ])
+        .style(Style::default().fg(Color::Red))
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(20),
+            Constraint::Length(20),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header_row(&["JOB", "PLUGIN", "FAILED AT", "ERROR"]))
+    .block(titled("Recent failures".to_string()));
+    frame.render_widget(table, area);
+}
+
+fn header_row(cells: &[&'static str]) -> Row<'static> {
+    Row::new(cells.to_vec()).style(
+        Style::default()
+            .add_modifier(Modifier::BOLD)
+            .fg(Color::Cyan),
+    )
+}
+
+fn titled(title: String) -> Block<'static> {
+    Block::default().borders(Borders::TOP).title(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::metrics::{BreakdownEntry, JobBreakdown};
+
+    fn report(entries: &[(&str, u64, u64)]) -> PluginMetricsReport {
+        PluginMetricsReport {
+            plugins: entries
+                .iter()
+                .map(|(key, completed, rows)| {
+                    BreakdownEntry::new(
+                        key.to_string(),
+                        JobBreakdown {
+                            completed: *completed,
+                            rows_written: *rows,
+                            ..JobBreakdown::default()
+                        },
+                    )
+                })
+                .collect(),
+            tags: Vec::new(),
+            namespaces: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_plugin_rates_between_polls() {
+        let first = report(&[("orders", 10, 1_000), ("trades", 4, 400)]);
+        let rates = plugin_rates(None, &first);
+        assert!(rates.iter().all(|rate| rate.rows_per_sec == 0.0));
+
+        let second = report(&[("orders", 12, 1_500), ("trades", 10, 2_400), ("fx", 1, 10)]);
+        let rates = plugin_rates(Some((&first, Duration::from_secs(10))), &second);
+        let names: Vec<&str> = rates.iter().map(|rate| rate.plugin.as_str()).collect();
+        assert_eq!(names, vec!["trades", "orders", "fx"]);
+        assert_eq!(rates[0].rows_per_sec, 200.0);
+        assert_eq!(rates[0].jobs_per_min, 36.0);
+        assert_eq!(rates[1].rows_per_sec, 50.0);
+        // A plugin first seen in this poll has no rate yet
+        assert_eq!(rates[2].rows_per_sec, 0.0);
+    }
+}
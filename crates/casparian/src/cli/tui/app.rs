@@ -14,7 +14,7 @@ use casparian_mcp::intent::{
 };
 use casparian_protocol::{
     Approval as ProtoApproval, ApprovalOperation, ApprovalStatus as ProtoApprovalStatus,
-    JobStatus as ProtocolJobStatus, ProcessingStatus,
+    JobResultSummary, JobStatus as ProtocolJobStatus, ProcessingStatus,
 };
 use casparian_sentinel::{
     ApiStorage, ControlClient, JobInfo as ControlJobInfo, ScoutRuleInfo, ScoutSourceInfo,
@@ -3807,6 +3807,18 @@ impl App {
             }
         }
 
+        // Continuous Chrome trace of every frame, for long sessions
+        #[cfg(feature = "profiling")]
+        if let Ok(path) = std::env::var("CASPARIAN_PROFILE_TRACE") {
+            if let Err(err) = app.profiler.start_chrome_trace(&path) {
+                app.set_global_status_for(
+                    format!("Profiler trace {} not started: {}", path, err),
+                    true,
+                    Duration::from_secs(8),
+                );
+            }
+        }
+
         app
     }
 
@@ -5949,6 +5961,11 @@ impl App {
             .unwrap_or_default();
 
         let id = i64::try_from(job.id).expect("job id out of range for i64");
+        let output_path = job
+            .result
+            .as_ref()
+            .and_then(|result| result.output_uris().next())
+            .map(str::to_string);
         JobInfo {
             id,
             file_id: if job.file_id == 0 {
@@ -5970,7 +5987,7 @@ impl App {
             items_total: 0,
             items_processed: 0,
             items_failed: 0,
-            output_path: None,
+            output_path,
             output_size_bytes: None,
             backtest: None,
             failures,
@@ -8136,6 +8153,14 @@ impl App {
                         .map_err(|err| format!("Jobs schema check failed: {}", err))?;
                 let has_quarantine_table = App::table_exists(&conn, "cf_quarantine")
                     .map_err(|err| format!("Jobs schema check failed: {}", err))?;
+                let result_select =
+                    if App::column_exists(&conn, "cf_processing_queue", "result_summary_json")
+                        .map_err(|err| format!("Jobs schema check failed: {}", err))?
+                    {
+                        "q.result_summary_json"
+                    } else {
+                        "NULL AS result_summary_json"
+                    };
 
                 let (quarantine_select, quarantine_join) = if has_quarantine_column {
                     ("q.quarantine_rows", "")
@@ -8170,7 +8195,8 @@ impl App {
                     q.pipeline_run_id,
                     pr.logical_date,
                     pr.selection_snapshot_hash,
-                    {quarantine_select}
+                    {quarantine_select},
+                    {result_select}
                 FROM cf_processing_queue q
                 LEFT JOIN cf_pipeline_runs pr ON pr.id = q.pipeline_run_id
                 {quarantine_join}
@@ -8191,6 +8217,7 @@ impl App {
                 "#,
                         quarantine_select = quarantine_select,
                         quarantine_join = quarantine_join,
+                        result_select = result_select,
                         running = ProcessingStatus::Running.as_str(),
                         staged = ProcessingStatus::Staged.as_str(),
                         dispatching = ProcessingStatus::Dispatching.as_str(),
@@ -8214,7 +8241,8 @@ impl App {
                     q.result_summary,
                     q.error_message,
                     q.completion_status,
-                    {quarantine_select}
+                    {quarantine_select},
+                    {result_select}
                 FROM cf_processing_queue q
                 {quarantine_join}
                 ORDER BY
@@ -8234,6 +8262,7 @@ impl App {
                 "#,
                         quarantine_select = quarantine_select,
                         quarantine_join = quarantine_join,
+                        result_select = result_select,
                         running = ProcessingStatus::Running.as_str(),
                         staged = ProcessingStatus::Staged.as_str(),
                         dispatching = ProcessingStatus::Dispatching.as_str(),
@@ -8288,6 +8317,13 @@ impl App {
                         } else {
                             (None, None, None, row.get(9).ok())
                         };
+                    let result_json: Option<String> = row
+                        .get(if has_pipeline_runs { 13 } else { 10 })
+                        .ok()
+                        .flatten();
+                    let output_path = result_json
+                        .and_then(|json| serde_json::from_str::<JobResultSummary>(&json).ok())
+                        .and_then(|result| result.output_uris().next().map(str::to_string));
 
                     // Map queue status + completion_status to UI status
                     let status =
@@ -8327,7 +8363,7 @@ impl App {
                         items_total: 0,
                         items_processed: if result_summary.is_some() { 1 } else { 0 },
                         items_failed: if error_message.is_some() { 1 } else { 0 },
-                        output_path: None,
+                        output_path,
                         output_size_bytes: None,
                         backtest: None,
                         failures,
@@ -115,6 +115,11 @@ pub fn run(args: TuiArgs, telemetry: Option<TelemetryRecorder>) -> Result<()> {
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
+    #[cfg(feature = "profiling")]
+    if let Err(err) = app.profiler.finish_chrome_trace() {
+        eprintln!("Failed to write profiler trace: {}", err);
+    }
+
     let record_result = if let Some(recorder) = recorder {
         recorder.finish(&app)
     } else {
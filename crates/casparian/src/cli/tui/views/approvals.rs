@@ -181,13 +181,14 @@ impl App {
 
         // Call backend to persist the approval
         let approval_id_owned = approval_id.to_string();
+        let actor = std::env::var("USER").ok();
         if self.control_connected {
             if let Some(control_addr) = self.control_addr.clone() {
                 std::thread::spawn(move || {
                     if let Ok(client) =
                         ControlClient::connect_with_timeout(&control_addr, Duration::from_millis(500))
                     {
-                        let _ = client.approve(&approval_id_owned);
+                        let _ = client.approve(&approval_id_owned, actor.as_deref(), None);
                     }
                 });
             }
@@ -204,7 +205,7 @@ impl App {
                         tracing::error!("Failed to init schema for approval: {}", e);
                         return;
                     }
-                    if let Err(e) = storage.approve(&approval_id_owned, None) {
+                    if let Err(e) = storage.approve(&approval_id_owned, actor.as_deref(), None) {
                         tracing::error!("Failed to approve {}: {}", approval_id_owned, e);
                     }
                 }
@@ -230,6 +231,7 @@ impl App {
         // Call backend to persist the rejection
         let approval_id_owned = approval_id.to_string();
         let reason_owned = reason.clone();
+        let actor = std::env::var("USER").ok();
         if self.control_connected {
             if let Some(control_addr) = self.control_addr.clone() {
                 std::thread::spawn(move || {
@@ -237,7 +239,7 @@ impl App {
                         ControlClient::connect_with_timeout(&control_addr, Duration::from_millis(500))
                     {
                         let reason = reason_owned.as_deref().unwrap_or("");
-                        let _ = client.reject(&approval_id_owned, reason);
+                        let _ = client.reject(&approval_id_owned, reason, actor.as_deref());
                     }
                 });
             }
@@ -254,7 +256,11 @@ impl App {
                         tracing::error!("Failed to init schema for rejection: {}", e);
                         return;
                     }
-                    if let Err(e) = storage.reject(&approval_id_owned, None, reason_owned.as_deref()) {
+                    if let Err(e) = storage.reject(
+                        &approval_id_owned,
+                        actor.as_deref(),
+                        reason_owned.as_deref(),
+                    ) {
                         tracing::error!("Failed to reject {}: {}", approval_id_owned, e);
                     }
                 }
@@ -55,9 +55,11 @@
 
 use anyhow::Result;
 use casparian::telemetry::TelemetryRecorder;
-use casparian_sentinel::{Sentinel, SentinelArgs, SentinelConfig};
-use casparian_tape::{EventName, TapeWriter};
-use casparian_worker::{bridge, Worker, WorkerArgs, WorkerConfig};
+use casparian_sentinel::{
+    SchedulingPolicy, SecurityConfig, Sentinel, SentinelArgs, SentinelConfig, WorkerHealthConfig,
+};
+use casparian_tape::{EventName, RedactionMode, RedactionPolicy, TapeWriter, TapeWriterConfig};
+use casparian_worker::{bridge, Worker, WorkerArgs, WorkerCommand, WorkerConfig};
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 use std::process::ExitCode;
@@ -385,7 +387,7 @@ enum Commands {
     // === Existing Server Commands ===
     /// Start both Sentinel and Worker in one process (Split-Runtime)
     Start {
-        /// ZMQ bind/connect address (default: IPC socket)
+        /// ZMQ or grpc:// bind/connect address (default: IPC socket)
         #[arg(long)]
         addr: Option<String>,
 
@@ -417,6 +419,10 @@ enum Commands {
         /// Disable the Control API entirely.
         #[arg(long)]
         no_control_api: bool,
+
+        /// Dev mode: accept plugin deploys without a publisher signature
+        #[arg(long, env = "CASPARIAN_ALLOW_UNSIGNED_DEPLOYS")]
+        allow_unsigned_deploys: bool,
     },
 
     /// Start only the Sentinel (Control Plane)
@@ -451,6 +457,15 @@ enum Commands {
         /// Publisher email (optional)
         #[arg(long)]
         email: Option<String>,
+
+        /// Publisher key to sign with, from ~/.casparian_flow/keys/publishers
+        /// (default: the publisher name, if a key exists)
+        #[arg(long)]
+        signer: Option<String>,
+
+        /// Tenant namespace that owns the plugin (default: "default")
+        #[arg(long)]
+        namespace: Option<String>,
     },
 
     /// Show current configuration and paths
@@ -460,6 +475,9 @@ enum Commands {
         json: bool,
     },
 
+    /// Live dashboard of workers, queue depth, in-flight jobs and plugin throughput
+    Top(cli::top::TopArgs),
+
     /// Interactive TUI for chat and monitoring
     Tui {
         #[command(flatten)]
@@ -502,41 +520,48 @@ enum Commands {
     /// Export a support bundle (zip) with tapes and metadata for debugging
     SupportBundle(cli::support_bundle::SupportBundleArgs),
 
-    /// Work with session tape recordings (explain, validate)
+    /// Generate a CurveZMQ key pair for encrypted Sentinel/Worker transport
+    Keygen(cli::keygen::KeygenArgs),
+
+    /// Query the audit log of control-plane changes (rules, plugins, approvals, config)
+    Audit(cli::audit::AuditArgs),
+
+    /// Manage retention policies and garbage-collect expired outputs and logs
+    Retention {
+        #[command(subcommand)]
+        action: cli::retention::RetentionAction,
+    },
+
+    /// Coalesce small Parquet output files and manage compaction policies
+    Compaction {
+        #[command(subcommand)]
+        action: cli::compaction::CompactionAction,
+    },
+
+    /// Serve completed job outputs over Arrow Flight (requires the `flight` feature)
+    Flight(cli::flight::FlightArgs),
+
+    /// Work with session tape recordings (explain, validate, replay)
     Tape {
         #[command(subcommand)]
         command: cli::tape::TapeCommands,
     },
+
+    /// Export or import intent sessions as zip bundles for support cases
+    Session {
+        #[command(subcommand)]
+        command: cli::session::SessionCommands,
+    },
 }
 
-/// Get the default IPC address for the current platform.
+/// Get the default local address for the current platform.
 ///
-/// Uses user-specific paths to avoid collisions on multi-user systems:
-/// - **Unix**: `$XDG_RUNTIME_DIR/casparian.sock` or `/tmp/casparian_{uid}.sock`
-/// - **Windows**: `ipc://casparian_{username}` (named pipe scoped to user)
+/// Uses user-specific names to avoid collisions on multi-user systems:
+/// - **Unix**: `ipc://` at `$XDG_RUNTIME_DIR/casparian.sock` or
+///   `/tmp/casparian_{uid}.sock`
+/// - **Windows**: the named pipe `pipe://casparian-{USERNAME}`
 fn get_default_ipc_addr() -> String {
-    #[cfg(windows)]
-    {
-        // Windows named pipes are global - scope to username to avoid collision
-        let username = std::env::var("USERNAME")
-            .or_else(|_| std::env::var("USER"))
-            .unwrap_or_else(|_| "default".to_string());
-        format!("ipc://casparian_{}", username)
-    }
-    #[cfg(not(windows))]
-    {
-        // Try XDG_RUNTIME_DIR first (standard on Linux for user-specific sockets)
-        if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
-            let socket_path = std::path::Path::new(&runtime_dir).join("casparian.sock");
-            return format!("ipc://{}", socket_path.display());
-        }
-
-        // Fallback to temp_dir with user ID to avoid collision on shared systems
-        let temp_dir = std::env::temp_dir();
-        let uid = unsafe { libc::getuid() };
-        let socket_path = temp_dir.join(format!("casparian_{}.sock", uid));
-        format!("ipc://{}", socket_path.display())
-    }
+    casparian_protocol::endpoint::default_local_addr("casparian")
 }
 
 /// Sentinel handle for shutdown coordination
@@ -582,6 +607,10 @@ fn command_wants_json(command: &Commands) -> bool {
         Commands::Config { json } => *json,
         Commands::Run(args) => args.json,
         Commands::SupportBundle(args) => args.json,
+        Commands::Keygen(args) => args.json,
+        Commands::Audit(args) => args.json,
+        Commands::Retention { action } => retention_action_wants_json(action),
+        Commands::Compaction { action } => compaction_action_wants_json(action),
         Commands::Parser { action } => parser_action_wants_json(action),
         Commands::Plugin { action } => plugin_action_wants_json(action),
         Commands::Rule { action } => rule_action_wants_json(action),
@@ -605,6 +634,22 @@ fn parser_action_wants_json(action: &cli::parser::ParserAction) -> bool {
     }
 }
 
+fn retention_action_wants_json(action: &cli::retention::RetentionAction) -> bool {
+    match action {
+        cli::retention::RetentionAction::List { json } => *json,
+        cli::retention::RetentionAction::Gc { json, .. } => *json,
+        _ => false,
+    }
+}
+
+fn compaction_action_wants_json(action: &cli::compaction::CompactionAction) -> bool {
+    match action {
+        cli::compaction::CompactionAction::List { json } => *json,
+        cli::compaction::CompactionAction::Run { json, .. } => *json,
+        _ => false,
+    }
+}
+
 fn plugin_action_wants_json(action: &cli::plugin::PluginAction) -> bool {
     match action {
         cli::plugin::PluginAction::List { json } => *json,
@@ -654,6 +699,7 @@ fn worker_action_wants_json(action: &cli::worker::WorkerAction) -> bool {
 fn job_action_wants_json(action: &cli::job::JobAction) -> bool {
     match action {
         cli::job::JobAction::Show { json, .. } => *json,
+        cli::job::JobAction::Verify { json, .. } => *json,
         _ => false,
     }
 }
@@ -817,6 +863,7 @@ fn run_command(cli: Cli, telemetry: Option<TelemetryRecorder>) -> Result<()> {
             venvs_dir,
             control_addr,
             no_control_api,
+            allow_unsigned_deploys,
         } => {
             // Use config module for defaults
             let state_store_url = resolve_state_store_url(state_store);
@@ -832,6 +879,7 @@ fn run_command(cli: Cli, telemetry: Option<TelemetryRecorder>) -> Result<()> {
                 venvs_dir,
                 control_addr,
                 no_control_api,
+                allow_unsigned_deploys,
             )
         }
 
@@ -845,8 +893,11 @@ fn run_command(cli: Cli, telemetry: Option<TelemetryRecorder>) -> Result<()> {
             addr,
             publisher,
             email,
-        } => run_publish(file, version, addr, publisher, email),
+            signer,
+            namespace,
+        } => run_publish(file, version, addr, publisher, email, signer, namespace),
         Commands::Config { json } => cli::config::run(cli::config::ConfigArgs { json }),
+        Commands::Top(args) => cli::top::run(args),
         Commands::Tui { args } => cli::tui::run(args, telemetry),
         Commands::TuiSnapshots { args } => cli::tui::snapshot_export::run(args),
         Commands::TuiStateGraph { args } => cli::tui::state_graph::run(args),
@@ -854,7 +905,13 @@ fn run_command(cli: Cli, telemetry: Option<TelemetryRecorder>) -> Result<()> {
         Commands::TuiFlow { command } => cli::tui::flow_runner::run(command),
         Commands::Mcp { action } => cli::mcp::run(action),
         Commands::SupportBundle(args) => cli::support_bundle::run(args),
+        Commands::Keygen(args) => cli::keygen::run(args),
+        Commands::Audit(args) => cli::audit::run(args),
+        Commands::Retention { action } => cli::retention::run(action),
+        Commands::Compaction { action } => cli::compaction::run(action),
+        Commands::Flight(args) => cli::flight::run(args),
         Commands::Tape { command } => cli::tape::run_tape_command(command),
+        Commands::Session { command } => cli::session::run_session_command(command),
     }
 }
 
@@ -863,7 +920,7 @@ fn main() -> ExitCode {
     let cli = Cli::parse();
 
     // Initialize logging - suppress stdout logs in TUI mode to avoid corrupting display
-    let is_tui_mode = matches!(cli.command, Commands::Tui { .. });
+    let is_tui_mode = matches!(cli.command, Commands::Tui { .. } | Commands::Top(_));
     let json_mode = command_wants_json(&cli.command);
     let default_filter = "casparian=info,casparian_sentinel=info,casparian_worker=info";
     let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
@@ -909,16 +966,16 @@ fn main() -> ExitCode {
     registry.init();
 
     // Create tape writer if --tape specified
-    let tape_writer = cli
-        .tape
-        .as_ref()
-        .and_then(|path| match TapeWriter::new(path) {
+    let tape_writer = cli.tape.as_ref().and_then(|path| {
+        let config = TapeWriterConfig::default().with_redaction(tape_redaction_policy());
+        match TapeWriter::with_config(path, config) {
             Ok(w) => Some(Arc::new(w)),
             Err(e) => {
                 eprintln!("Warning: Failed to create tape file: {}", e);
                 None
             }
-        });
+        }
+    });
 
     let telemetry = tape_writer.as_ref().and_then(|writer| {
         TelemetryRecorder::new(writer.clone())
@@ -933,7 +990,7 @@ fn main() -> ExitCode {
     let command_name = get_command_name(&cli.command);
     let correlation_id = uuid::Uuid::new_v4().to_string();
     let command_event_id = if let Some(ref writer) = tape_writer {
-        let payload = build_command_payload(&cli.command, writer);
+        let payload = build_command_payload(&cli.command);
         match writer.emit(
             EventName::UICommand(command_name.clone()),
             Some(&correlation_id),
@@ -1017,6 +1074,7 @@ fn get_command_name(cmd: &Commands) -> String {
         Commands::Worker { .. } => "Worker".to_string(),
         Commands::Publish { .. } => "Publish".to_string(),
         Commands::Config { .. } => "Config".to_string(),
+        Commands::Top(_) => "Top".to_string(),
         Commands::Tui { .. } => "Tui".to_string(),
         Commands::TuiSnapshots { .. } => "TuiSnapshots".to_string(),
         Commands::TuiStateGraph { .. } => "TuiStateGraph".to_string(),
@@ -1024,30 +1082,46 @@ fn get_command_name(cmd: &Commands) -> String {
         Commands::TuiFlow { .. } => "TuiFlow".to_string(),
         Commands::Mcp { .. } => "Mcp".to_string(),
         Commands::SupportBundle(_) => "SupportBundle".to_string(),
+        Commands::Keygen(_) => "Keygen".to_string(),
+        Commands::Audit(_) => "Audit".to_string(),
+        Commands::Retention { .. } => "Retention".to_string(),
+        Commands::Compaction { .. } => "Compaction".to_string(),
+        Commands::Flight(_) => "Flight".to_string(),
         Commands::Tape { .. } => "Tape".to_string(),
+        Commands::Session { .. } => "Session".to_string(),
         Commands::Start { .. } => "Start".to_string(),
     }
 }
 
-/// Build redacted payload for tape recording
-fn build_command_payload(cmd: &Commands, writer: &TapeWriter) -> serde_json::Value {
+/// Redaction for the CLI tape: path arguments of commands are hashed.
+fn tape_redaction_policy() -> RedactionPolicy {
+    RedactionPolicy::new()
+        .with_rule("path", RedactionMode::Hash)
+        .with_rule("file", RedactionMode::Hash)
+        .with_rule("parser", RedactionMode::Hash)
+        .with_rule("input", RedactionMode::Hash)
+        .with_rule("output_path", RedactionMode::Hash)
+}
+
+/// Build the payload for tape recording (redacted by `tape_redaction_policy`)
+fn build_command_payload(cmd: &Commands) -> serde_json::Value {
     match cmd {
         Commands::Scan { path, tag, .. } => {
             serde_json::json!({
-                "path_hash": writer.redact_string(&path.display().to_string()),
+                "path": path.display().to_string(),
                 "tag": tag,
             })
         }
         Commands::Preview { file, rows, .. } => {
             serde_json::json!({
-                "file_hash": writer.redact_string(&file.display().to_string()),
+                "file": file.display().to_string(),
                 "rows": rows,
             })
         }
         Commands::Run(args) => {
             serde_json::json!({
-                "parser_hash": writer.redact_string(&args.parser.display().to_string()),
-                "input_hash": writer.redact_string(&args.input.display().to_string()),
+                "parser": args.parser.display().to_string(),
+                "input": args.input.display().to_string(),
             })
         }
         Commands::Config { .. } => {
@@ -1082,7 +1156,7 @@ fn build_command_payload(cmd: &Commands, writer: &TapeWriter) -> serde_json::Val
         }
         Commands::SupportBundle(args) => {
             serde_json::json!({
-                "output_path_hash": writer.redact_string(&args.output.display().to_string()),
+                "output_path": args.output.display().to_string(),
             })
         }
         // For other commands, just record the command type without details
@@ -1100,6 +1174,7 @@ fn run_unified(
     venvs_dir: Option<std::path::PathBuf>,
     control_addr: Option<String>,
     no_control_api: bool,
+    allow_unsigned_deploys: bool,
 ) -> Result<()> {
     // Ensure config directories exist
     cli::config::ensure_casparian_home()?;
@@ -1167,6 +1242,20 @@ fn run_unified(
             max_workers: 1,
             control_addr,
             query_catalog_path,
+            scheduling_policy: SchedulingPolicy::default(),
+            metrics_addr: None,
+            http_api_addr: None,
+            security: SecurityConfig::disabled(),
+            allow_unsigned_deploys,
+            dispatch_batch_size: 1,
+            worker_health: WorkerHealthConfig::default(),
+            priority_aging_secs: casparian_sentinel::DEFAULT_PRIORITY_AGING_SECS,
+            preempt_low_priority: false,
+            read_only: false,
+            rehash: false,
+            schema_drift_threshold: casparian_sentinel::DEFAULT_SCHEMA_DRIFT_THRESHOLD,
+            worker_pool: None,
+            config_path: None,
         };
 
         let mut sentinel = Sentinel::bind(config)?;
@@ -1189,14 +1278,11 @@ fn run_unified(
         "rust-{}",
         &uuid::Uuid::new_v4().to_string()[..8] // First 8 hex chars of UUID
     );
-    let worker_config = WorkerConfig {
-        sentinel_addr: addr,
-        parquet_root: output,
-        worker_id,
-        shim_path,
-        capabilities: vec!["*".to_string()],
-        venvs_dir,
-    };
+    let mut worker_config = WorkerConfig::new(addr, output, worker_id, shim_path)
+        .with_capabilities(vec!["*".to_string()]);
+    if let Some(venvs_dir) = venvs_dir {
+        worker_config = worker_config.with_venvs_dir(venvs_dir);
+    }
 
     // Wait for Sentinel to be ready
     ready_rx
@@ -1295,6 +1381,9 @@ fn looks_like_url(raw: &str) -> bool {
 
 /// Run Sentinel standalone (for distributed deployment)
 fn run_sentinel_standalone(args: SentinelArgs) -> Result<()> {
+    let security = args.security_config()?;
+    let worker_health = args.worker_health_config();
+    let worker_pool = args.worker_pool_config(std::env::current_exe()?, &["worker"])?;
     let shutdown_flag = Arc::new(AtomicBool::new(false));
     let shutdown_flag_handler = shutdown_flag.clone();
 
@@ -1332,6 +1421,20 @@ fn run_sentinel_standalone(args: SentinelArgs) -> Result<()> {
         query_catalog_path: args
             .query_catalog
             .unwrap_or_else(cli::config::query_catalog_path),
+        scheduling_policy: args.scheduling_policy,
+        metrics_addr: args.metrics_addr,
+        http_api_addr: args.http_api_addr,
+        security,
+        allow_unsigned_deploys: args.allow_unsigned_deploys,
+        dispatch_batch_size: args.dispatch_batch_size,
+        worker_health,
+        priority_aging_secs: args.priority_aging_secs,
+        preempt_low_priority: args.preempt_low_priority,
+        read_only: args.read_only,
+        rehash: args.rehash,
+        schema_drift_threshold: args.schema_drift_threshold,
+        worker_pool,
+        config_path: args.config,
     };
     let mut sentinel = Sentinel::bind(config)?;
 
@@ -1349,6 +1452,24 @@ fn run_sentinel_standalone(args: SentinelArgs) -> Result<()> {
 
 /// Run Worker standalone (for distributed deployment)
 fn run_worker_standalone(args: WorkerArgs) -> Result<()> {
+    let settings = args.settings()?;
+    if args.print_config {
+        print!("{}", settings.to_toml()?);
+        return Ok(());
+    }
+    if let Some(WorkerCommand::Healthcheck { addr }) = &args.command {
+        let Some(addr) = addr.as_deref().or(settings.health.listen.as_deref()) else {
+            anyhow::bail!("No health endpoint: pass --addr or set health.listen");
+        };
+        let report = casparian_worker::health::healthcheck(addr)?;
+        println!(
+            "Worker {} is healthy ({} running jobs)",
+            report.worker_id,
+            report.jobs.len()
+        );
+        return Ok(());
+    }
+
     let shutdown_flag = Arc::new(AtomicBool::new(false));
     let shutdown_flag_handler = shutdown_flag.clone();
 
@@ -1366,23 +1487,14 @@ fn run_worker_standalone(args: WorkerArgs) -> Result<()> {
         });
     }
 
-    // Materialize embedded bridge shim to disk (single binary distribution)
-    let shim_path = bridge::materialize_bridge_shim().map_err(|e| anyhow::anyhow!(e))?;
-    let worker_id = args.worker_id.unwrap_or_else(|| {
+    let worker_id = settings.worker_id.clone().unwrap_or_else(|| {
         format!(
             "rust-{}",
             &uuid::Uuid::new_v4().to_string()[..8] // First 8 hex chars of UUID
         )
     });
-
-    let config = WorkerConfig {
-        sentinel_addr: args.connect,
-        parquet_root: args.output,
-        worker_id,
-        shim_path,
-        capabilities: vec!["*".to_string()],
-        venvs_dir: None, // Use default ~/.casparian_flow/venvs
-    };
+    // Materializes the embedded bridge shim unless runtime.shim_path is set
+    let config = settings.into_config(worker_id)?;
 
     let (worker, worker_handle) = Worker::connect(config).map_err(|e| anyhow::anyhow!(e))?;
 
@@ -1404,9 +1516,12 @@ fn run_publish(
     addr: Option<String>,
     publisher: Option<String>,
     email: Option<String>,
+    signer: Option<String>,
+    namespace: Option<String>,
 ) -> Result<()> {
     use casparian::prepare_publish;
     use casparian_protocol::types::DeployCommand;
+    use casparian_protocol::stream::{split_payload, DEFAULT_STREAM_CHUNK_SIZE};
     use casparian_protocol::{JobId, Message, OpCode};
     use zmq::Context;
 
@@ -1430,7 +1545,12 @@ fn run_publish(
             .unwrap_or_else(|_| "unknown".to_string())
     });
 
+    let (signer_id, signature) =
+        sign_deploy(&artifact.artifact_hash, signer.as_deref(), &publisher_name)?;
+
     // 6. Construct DeployCommand
+    let system_requirements = (!artifact.manifest.requirements.is_empty())
+        .then(|| artifact.manifest.requirements.clone());
     let deploy_cmd = DeployCommand {
         plugin_name: plugin_name.clone(),
         version: version.clone(),
@@ -1444,34 +1564,52 @@ fn run_publish(
         publisher_name,
         publisher_email: email,
         azure_oid: None,
-        system_requirements: None,
+        system_requirements,
+        signer_id,
+        signature,
+        namespace,
     };
 
     // 7. Send via ZMQ DEALER to Sentinel
     let sentinel_addr = addr.unwrap_or_else(get_default_ipc_addr);
     info!("Connecting to Sentinel at {}", sentinel_addr);
 
+    // A pipe:// Sentinel is reached through a local relay
+    let pipe_link = casparian_pipe::is_pipe_addr(&sentinel_addr)
+        .then(|| casparian_pipe::PipeLink::connect(&sentinel_addr))
+        .transpose()?;
+    let connect_addr = pipe_link
+        .as_ref()
+        .map_or(sentinel_addr.as_str(), |link| link.endpoint());
+
     let context = Context::new();
     let socket = context
         .socket(zmq::DEALER)
         .map_err(|e| anyhow::anyhow!("Failed to create DEALER socket: {}", e))?;
     socket
-        .connect(&sentinel_addr)
+        .connect(connect_addr)
         .map_err(|e| anyhow::anyhow!("Failed to connect to sentinel: {}", e))?;
     info!("✓ Connected to Sentinel");
 
     // Serialize payload
     let payload = serde_json::to_vec(&deploy_cmd)?;
 
-    // Create protocol message
-    let msg = Message::new(OpCode::Deploy, JobId::new(0), payload)?;
-    let (header_bytes, payload_bytes) = msg.pack()?;
-
-    // Send message (multipart)
-    let frames = [header_bytes.as_slice(), payload_bytes.as_slice()];
-    socket
-        .send_multipart(&frames, 0)
-        .map_err(|e| anyhow::anyhow!("ZMQ send error: {}", e))?;
+    // Create protocol message(s); large artifacts are streamed in chunks
+    let messages = split_payload(
+        OpCode::Deploy,
+        JobId::new(0),
+        payload,
+        DEFAULT_STREAM_CHUNK_SIZE,
+    )?;
+
+    // Send message(s) (multipart)
+    for msg in &messages {
+        let (header_bytes, payload_bytes) = msg.pack()?;
+        let frames = [header_bytes.as_slice(), payload_bytes.as_slice()];
+        socket
+            .send_multipart(&frames, 0)
+            .map_err(|e| anyhow::anyhow!("ZMQ send error: {}", e))?;
+    }
     info!("✓ Sent deployment request");
 
     // 8. Await ACK/ERR response
@@ -1499,6 +1637,12 @@ fn run_publish(
 
             if deploy_response.success {
                 println!("✅ Deployed plugin '{}' v{}", plugin_name, version);
+                match deploy_response.signer_id {
+                    Some(signer_id) if deploy_response.signature_verified => {
+                        println!("   Signature verified (signer: {})", signer_id)
+                    }
+                    _ => println!("   Unsigned (accepted by a Sentinel in dev mode)"),
+                }
                 Ok(())
             } else {
                 anyhow::bail!("Deployment failed: {}", deploy_response.message)
@@ -1516,6 +1660,53 @@ fn run_publish(
     }
 }
 
+/// Sign an artifact hash with a publisher key from ~/.casparian_flow/keys/publishers.
+///
+/// An explicit `--signer` must have a key; otherwise the publisher name's key
+/// is used if present, and the deploy goes out unsigned if not.
+fn sign_deploy(
+    artifact_hash: &str,
+    signer: Option<&str>,
+    publisher_name: &str,
+) -> Result<(Option<String>, Option<String>)> {
+    use casparian_security::keyring::{
+        load_signing_key, publishers_dir, sign_artifact, validate_signer_id,
+    };
+
+    let dir = publishers_dir(&casparian_protocol::keys::keys_dir());
+    let (signer_id, signing_key) = match signer {
+        Some(signer_id) => {
+            let key = load_signing_key(&dir, signer_id)?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No signing key for '{}' in {} (create one with: casparian keygen --publisher --name {})",
+                    signer_id,
+                    dir.display(),
+                    signer_id
+                )
+            })?;
+            (signer_id, Some(key))
+        }
+        None if validate_signer_id(publisher_name).is_ok() => {
+            (publisher_name, load_signing_key(&dir, publisher_name)?)
+        }
+        None => (publisher_name, None),
+    };
+
+    match signing_key {
+        Some(key) => Ok((
+            Some(signer_id.to_string()),
+            Some(sign_artifact(&key, artifact_hash)),
+        )),
+        None => {
+            warn!(
+                "No publisher key for '{}'; deploying unsigned. Create one with: casparian keygen --publisher --name <id>",
+                signer_id
+            );
+            Ok((None, None))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1523,13 +1714,14 @@ mod tests {
     #[test]
     fn test_default_ipc_addr_format() {
         let addr = get_default_ipc_addr();
-        assert!(
-            addr.starts_with("ipc://"),
-            "IPC address should start with ipc://"
-        );
 
         #[cfg(not(windows))]
         {
+            assert!(
+                addr.starts_with("ipc://"),
+                "IPC address should start with ipc://"
+            );
+
             // Should contain casparian in the socket name
             assert!(
                 addr.contains("casparian"),
@@ -1561,15 +1753,14 @@ mod tests {
 
         #[cfg(windows)]
         {
+            // A per-user named pipe
+            let name = casparian_protocol::endpoint::pipe_name(&addr).unwrap();
             assert!(
-                addr.starts_with("ipc://casparian_"),
-                "Windows should start with ipc://casparian_"
+                name.starts_with("casparian-"),
+                "Windows should use a casparian named pipe: {}",
+                addr
             );
-            // Should contain username
-            let username = std::env::var("USERNAME")
-                .or_else(|_| std::env::var("USER"))
-                .unwrap_or_else(|_| "default".to_string());
-            assert_eq!(addr, format!("ipc://casparian_{}", username));
+            casparian_protocol::endpoint::validate_ipc_addr(&addr).unwrap();
         }
     }
 }
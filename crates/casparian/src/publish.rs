@@ -4,12 +4,16 @@
 //! Used by both CLI (`casparian publish`) and Tauri UI.
 
 use anyhow::{Context, Result};
-use casparian_protocol::{DataType, RuntimeKind, SchemaColumnSpec, SchemaDefinition};
+use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::capabilities::normalize_requirements;
+use casparian_protocol::{
+    ColumnConstraint, DataType, RuntimeKind, SchemaColumnSpec, SchemaDefinition,
+};
 use casparian_security::signing::{compute_artifact_hash, sha256};
-use casparian_security::{Gatekeeper, GatekeeperProfile};
+use casparian_security::{Gatekeeper, GatekeeperPolicy, GatekeeperPolicyConfig, GatekeeperProfile};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Result of analyzing a plugin file
@@ -35,6 +39,9 @@ pub struct PluginAnalysis {
     pub handler_methods: Vec<String>,
     /// Detected topic registrations (from configure method)
     pub detected_topics: Vec<String>,
+    /// Effective Gatekeeper policy the plugin was checked against
+    #[serde(default)]
+    pub gatekeeper_policy: GatekeeperPolicy,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -43,10 +50,15 @@ pub enum GatekeeperMode {
     Strict,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Default Gatekeeper policy file under the Casparian home.
+pub const GATEKEEPER_POLICY_FILENAME: &str = "gatekeeper_policy.toml";
+
+#[derive(Debug, Clone)]
 pub struct GatekeeperOptions {
     pub mode: GatekeeperMode,
     pub profile: GatekeeperProfile,
+    /// Operator policy file (TOML or JSON); `None` enforces the profile as-is
+    pub policy_path: Option<PathBuf>,
 }
 
 impl GatekeeperOptions {
@@ -62,6 +74,13 @@ impl GatekeeperOptions {
                 _ => None,
             })
             .unwrap_or(GatekeeperProfile::Standard);
+        let policy_path = std::env::var_os("CASPARIAN_GATEKEEPER_POLICY")
+            .map(PathBuf::from)
+            .or_else(|| {
+                let default_path =
+                    casparian_protocol::paths::casparian_home().join(GATEKEEPER_POLICY_FILENAME);
+                default_path.is_file().then_some(default_path)
+            });
 
         Self {
             mode: if strict {
@@ -70,6 +89,18 @@ impl GatekeeperOptions {
                 GatekeeperMode::Warn
             },
             profile,
+            policy_path,
+        }
+    }
+
+    /// Effective policy for `plugin_name`. A configured policy file that
+    /// cannot be read or parsed is an error, never a silent fallback.
+    pub fn resolve_policy(&self, plugin_name: &str) -> Result<GatekeeperPolicy> {
+        match &self.policy_path {
+            Some(path) => {
+                Ok(GatekeeperPolicyConfig::load(path)?.resolve(plugin_name, self.profile))
+            }
+            None => Ok(GatekeeperPolicy::for_profile(self.profile)),
         }
     }
 }
@@ -133,6 +164,10 @@ pub struct PluginManifest {
     pub platform_os: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub platform_arch: Option<String>,
+    /// Capability tags a worker must advertise to run this plugin
+    /// (e.g. "python3.11", "duckdb", "gpu")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requirements: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -150,6 +185,8 @@ struct ColumnSpecInput {
     pub nullable: bool,
     #[serde(default)]
     pub format: Option<String>,
+    #[serde(default)]
+    pub constraints: Vec<ColumnConstraint>,
 }
 
 /// Analyze a plugin file without deploying it
@@ -187,7 +224,8 @@ pub fn analyze_plugin_with_options(
     let source_hash = sha256(source_code.as_bytes());
 
     // 4. Validate with Gatekeeper (Real AST parsing)
-    let gatekeeper = Gatekeeper::with_profile(options.profile);
+    let gatekeeper_policy = options.resolve_policy(&plugin_name)?;
+    let gatekeeper = Gatekeeper::with_policy(gatekeeper_policy.clone());
     let (is_valid, validation_errors, validation_warnings) = match gatekeeper.analyze(&source_code)
     {
         Ok(report) => match options.mode {
@@ -238,6 +276,7 @@ pub fn analyze_plugin_with_options(
         env_hash,
         handler_methods,
         detected_topics,
+        gatekeeper_policy,
     })
 }
 
@@ -307,7 +346,8 @@ fn load_manifest(plugin_dir: &Path) -> Result<(PluginManifest, String)> {
 
     let content = std::fs::read_to_string(&manifest_path)
         .with_context(|| format!("Failed to read manifest: {:?}", manifest_path))?;
-    let manifest: PluginManifest = toml::from_str(&content).context("Failed to parse manifest")?;
+    let mut manifest: PluginManifest =
+        toml::from_str(&content).context("Failed to parse manifest")?;
 
     if manifest.name.trim().is_empty() {
         anyhow::bail!("Manifest field 'name' must be non-empty");
@@ -358,6 +398,9 @@ fn load_manifest(plugin_dir: &Path) -> Result<(PluginManifest, String)> {
         }
     }
 
+    manifest.requirements = normalize_requirements(&manifest.requirements)
+        .map_err(|err| anyhow::anyhow!("Manifest field 'requirements': {}", err))?;
+
     let manifest_json =
         serde_json::to_string(&manifest).context("Failed to serialize manifest JSON")?;
 
@@ -471,6 +514,7 @@ fn extract_outputs_manifest(path: &Path) -> Result<BTreeMap<String, SchemaDefini
                 data_type: column.data_type,
                 nullable: column.nullable,
                 format: column.format,
+                constraints: column.constraints,
             });
         }
 
@@ -511,6 +555,9 @@ pub struct PublishReceipt {
     pub routing_rule_id: Option<i64>,
     /// ID of created/updated topic config (if any)
     pub topic_config_id: Option<i64>,
+    /// Effective Gatekeeper policy the plugin passed
+    #[serde(default)]
+    pub gatekeeper_policy: Option<GatekeeperPolicy>,
 }
 
 /// Prepare a plugin for publishing (validates and generates lockfile)
@@ -599,6 +646,7 @@ pub fn prepare_publish_with_options(
         manifest_json,
         schema_artifacts_json,
         detected_topics: analysis.detected_topics,
+        gatekeeper_policy: analysis.gatekeeper_policy,
     })
 }
 
@@ -615,6 +663,220 @@ pub struct PreparedArtifact {
     pub manifest_json: String,
     pub schema_artifacts_json: String,
     pub detected_topics: Vec<String>,
+    pub gatekeeper_policy: GatekeeperPolicy,
+}
+
+/// One stored version of a plugin from `cf_plugin_manifest`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginVersionSource {
+    pub version: String,
+    pub source_code: String,
+    pub source_hash: String,
+    pub env_hash: Option<String>,
+}
+
+/// What changed between two versions of a plugin, for review before approval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginVersionDiff {
+    pub plugin_name: String,
+    pub from_version: String,
+    pub to_version: String,
+    pub source_changed: bool,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Line diff of the source, including unchanged lines for context
+    pub source_diff: Vec<SourceDiffLine>,
+    pub env_hash: ValueChange,
+    pub topics: ListChange,
+    pub handler_methods: ListChange,
+}
+
+/// Before/after value of a single field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueChange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub changed: bool,
+}
+
+/// Items added to or removed from a list, in source order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListChange {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLineKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+impl DiffLineKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffLineKind::Unchanged => "unchanged",
+            DiffLineKind::Added => "added",
+            DiffLineKind::Removed => "removed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceDiffLine {
+    pub kind: DiffLineKind,
+    /// Line number in the older version (removed/unchanged lines)
+    pub from_line: Option<usize>,
+    /// Line number in the newer version (added/unchanged lines)
+    pub to_line: Option<usize>,
+    pub text: String,
+}
+
+/// Load one version of a plugin. With several platform variants of the same
+/// version, the most recently published one wins.
+pub fn load_plugin_version(
+    conn: &DbConnection,
+    plugin_name: &str,
+    version: &str,
+) -> Result<PluginVersionSource> {
+    let row = conn
+        .query_optional(
+            r#"
+            SELECT version, source_code, source_hash, env_hash
+            FROM cf_plugin_manifest
+            WHERE plugin_name = ? AND version = ?
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            &[DbValue::from(plugin_name), DbValue::from(version)],
+        )?
+        .ok_or_else(|| {
+            anyhow::anyhow!("Plugin {}@{} not found in registry", plugin_name, version)
+        })?;
+    let env_hash: Option<String> = row.get_by_name("env_hash")?;
+    Ok(PluginVersionSource {
+        version: row.get_by_name("version")?,
+        source_code: row.get_by_name("source_code")?,
+        source_hash: row.get_by_name("source_hash")?,
+        env_hash: env_hash.filter(|hash| !hash.trim().is_empty()),
+    })
+}
+
+/// Diff two published versions of `plugin_name` (`from_version` -> `to_version`).
+pub fn diff_plugin_versions(
+    conn: &DbConnection,
+    plugin_name: &str,
+    from_version: &str,
+    to_version: &str,
+) -> Result<PluginVersionDiff> {
+    let from = load_plugin_version(conn, plugin_name, from_version)?;
+    let to = load_plugin_version(conn, plugin_name, to_version)?;
+    Ok(diff_plugin_sources(plugin_name, &from, &to))
+}
+
+/// Diff two plugin versions already in memory.
+pub fn diff_plugin_sources(
+    plugin_name: &str,
+    from: &PluginVersionSource,
+    to: &PluginVersionSource,
+) -> PluginVersionDiff {
+    let source_diff = diff_lines(&from.source_code, &to.source_code);
+    let count = |kind: DiffLineKind| source_diff.iter().filter(|line| line.kind == kind).count();
+    PluginVersionDiff {
+        plugin_name: plugin_name.to_string(),
+        from_version: from.version.clone(),
+        to_version: to.version.clone(),
+        source_changed: from.source_hash != to.source_hash,
+        lines_added: count(DiffLineKind::Added),
+        lines_removed: count(DiffLineKind::Removed),
+        source_diff,
+        env_hash: ValueChange {
+            changed: from.env_hash != to.env_hash,
+            from: from.env_hash.clone(),
+            to: to.env_hash.clone(),
+        },
+        topics: diff_lists(
+            &detect_topic_registrations(&from.source_code),
+            &detect_topic_registrations(&to.source_code),
+        ),
+        handler_methods: diff_lists(
+            &detect_handler_methods(&from.source_code),
+            &detect_handler_methods(&to.source_code),
+        ),
+    }
+}
+
+fn diff_lists(from: &[String], to: &[String]) -> ListChange {
+    let mut change = ListChange::default();
+    for item in to {
+        if from.contains(item) {
+            change.unchanged.push(item.clone());
+        } else {
+            change.added.push(item.clone());
+        }
+    }
+    change.removed = from
+        .iter()
+        .filter(|item| !to.contains(item))
+        .cloned()
+        .collect();
+    change
+}
+
+/// Line diff via longest common subsequence. Plugin sources are a few
+/// hundred lines, so the quadratic table is cheap.
+fn diff_lines(from: &str, to: &str) -> Vec<SourceDiffLine> {
+    let old: Vec<&str> = from.lines().collect();
+    let new: Vec<&str> = to.lines().collect();
+
+    // lcs[i][j] = LCS length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let line = |kind, from_line, to_line, text: &str| SourceDiffLine {
+        kind,
+        from_line,
+        to_line,
+        text: text.to_string(),
+    };
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(line(
+                DiffLineKind::Unchanged,
+                Some(i + 1),
+                Some(j + 1),
+                old[i],
+            ));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(line(DiffLineKind::Removed, Some(i + 1), None, old[i]));
+            i += 1;
+        } else {
+            diff.push(line(DiffLineKind::Added, None, Some(j + 1), new[j]));
+            j += 1;
+        }
+    }
+    for (idx, text) in old.iter().enumerate().skip(i) {
+        diff.push(line(DiffLineKind::Removed, Some(idx + 1), None, text));
+    }
+    for (idx, text) in new.iter().enumerate().skip(j) {
+        diff.push(line(DiffLineKind::Added, None, Some(idx + 1), text));
+    }
+    diff
 }
 
 #[cfg(test)]
@@ -651,6 +913,63 @@ class Handler:
         assert_eq!(topics, vec!["output", "errors"]);
     }
 
+    #[test]
+    fn test_diff_plugin_sources() {
+        let version = |version: &str, source: &str, env_hash: Option<&str>| PluginVersionSource {
+            version: version.to_string(),
+            source_code: source.to_string(),
+            source_hash: sha256(source.as_bytes()),
+            env_hash: env_hash.map(str::to_string),
+        };
+        let v1 = version(
+            "1.0.0",
+            r#"class Handler:
+    def configure(self, context, config):
+        self.out = context.register_topic("events")
+    def execute(self, file_path):
+        pass
+"#,
+            Some("env-a"),
+        );
+        let v2 = version(
+            "1.1.0",
+            r#"class Handler:
+    def configure(self, context, config):
+        self.out = context.register_topic("events")
+        self.notes = context.register_topic("annotations")
+    def execute(self, file_path):
+        return None
+    def on_error(self, error):
+        raise error
+"#,
+            Some("env-a"),
+        );
+
+        let diff = diff_plugin_sources("demo", &v1, &v2);
+        assert!(diff.source_changed);
+        assert!(!diff.env_hash.changed);
+        assert_eq!(diff.topics.added, vec!["annotations"]);
+        assert_eq!(diff.topics.unchanged, vec!["events"]);
+        assert!(diff.topics.removed.is_empty());
+        assert_eq!(diff.handler_methods.added, vec!["on_error"]);
+        assert_eq!(diff.lines_removed, 1);
+        assert_eq!(diff.lines_added, 4);
+        let removed = diff
+            .source_diff
+            .iter()
+            .find(|line| line.kind == DiffLineKind::Removed)
+            .unwrap();
+        assert_eq!(removed.text.trim(), "pass");
+        assert_eq!(removed.from_line, Some(5));
+
+        let same = diff_plugin_sources("demo", &v1, &v1);
+        assert!(!same.source_changed);
+        assert!(same
+            .source_diff
+            .iter()
+            .all(|line| line.kind == DiffLineKind::Unchanged));
+    }
+
     #[test]
     fn test_analyze_plugin_valid() {
         let temp_dir = TempDir::new().unwrap();
@@ -703,6 +1022,7 @@ class Handler:
             GatekeeperOptions {
                 mode: GatekeeperMode::Warn,
                 profile: GatekeeperProfile::Standard,
+                policy_path: None,
             },
         )
         .unwrap();
@@ -731,6 +1051,7 @@ def parse(path):
             GatekeeperOptions {
                 mode: GatekeeperMode::Strict,
                 profile: GatekeeperProfile::Standard,
+                policy_path: None,
             },
         )
         .unwrap();
@@ -738,4 +1059,51 @@ def parse(path):
         assert!(!analysis.validation_errors.is_empty());
         assert!(analysis.validation_warnings.is_empty());
     }
+
+    #[test]
+    fn test_analyze_plugin_applies_policy_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let plugin_path = temp_dir.path().join("evtx_parser.py");
+        std::fs::write(
+            &plugin_path,
+            r#"
+import subprocess
+
+def parse(path):
+    eval(path)
+"#,
+        )
+        .unwrap();
+        let policy_path = temp_dir.path().join("policy.toml");
+        std::fs::write(
+            &policy_path,
+            r#"
+[defaults]
+banned_calls = ["eval"]
+
+[plugins.evtx_parser]
+allow_imports = ["subprocess"]
+"#,
+        )
+        .unwrap();
+
+        let analysis = analyze_plugin_with_options(
+            &plugin_path,
+            GatekeeperOptions {
+                mode: GatekeeperMode::Strict,
+                profile: GatekeeperProfile::Standard,
+                policy_path: Some(policy_path),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            analysis.validation_errors,
+            vec!["Banned call: 'eval' (matches 'eval')".to_string()]
+        );
+        assert!(analysis
+            .gatekeeper_policy
+            .allowed_modules
+            .contains("subprocess"));
+        assert_eq!(analysis.gatekeeper_policy.banned_calls, vec!["eval"]);
+    }
 }
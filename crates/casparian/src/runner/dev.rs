@@ -5,6 +5,7 @@
 
 use super::{ExecutionResult, LogDestination, ParserRef};
 use anyhow::{Context, Result};
+use casparian_protocol::types::ResourceLimits;
 use casparian_protocol::JobId;
 use casparian_worker::cancel::CancellationToken;
 use std::path::Path;
@@ -60,6 +61,9 @@ impl DevRunner {
             shim_path,
             inherit_stdio,
             cancel_token: CancellationToken::new(),
+            limits: ResourceLimits::default(),
+            csv_dialect: None,
+            scratch_dir: None,
         };
 
         // Execute with terminal output (logs captured by bridge)
@@ -5,6 +5,7 @@
 
 use crate::high_failure::{FailureHistoryEntry, FileInfo, HighFailureError, HighFailureTable};
 use crate::metrics::{FailureCategory, IterationMetrics};
+use crate::progress::{BacktestEvent, BacktestHandle, BacktestStatus, ViolationSummary};
 use crate::ScopeId;
 use serde::{Deserialize, Serialize};
 
@@ -73,6 +74,13 @@ pub enum BacktestResult {
         reason: String,
     },
 
+    /// Backtest was cancelled through its `BacktestHandle`
+    Cancelled {
+        metrics: IterationMetrics,
+        files_tested: usize,
+        files_remaining: usize,
+    },
+
     /// Backtest had an error
     Error { files_tested: usize, error: String },
 }
@@ -83,6 +91,7 @@ impl BacktestResult {
         match self {
             BacktestResult::Complete { metrics, .. } => metrics.pass_rate,
             BacktestResult::EarlyStopped { metrics, .. } => metrics.pass_rate,
+            BacktestResult::Cancelled { metrics, .. } => metrics.pass_rate,
             BacktestResult::Error { .. } => 0.0,
         }
     }
@@ -96,6 +105,11 @@ impl BacktestResult {
     pub fn is_early_stopped(&self) -> bool {
         matches!(self, BacktestResult::EarlyStopped { .. })
     }
+
+    /// Whether the backtest was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, BacktestResult::Cancelled { .. })
+    }
 }
 
 /// File test result
@@ -123,14 +137,46 @@ pub fn backtest_with_failfast<P: ParserRunner>(
     parser_version: usize,
     iteration: usize,
     config: &FailFastConfig,
+) -> Result<BacktestResult, HighFailureError> {
+    backtest_with_handle(
+        parser,
+        files,
+        high_failure_table,
+        scope_id,
+        parser_version,
+        iteration,
+        config,
+        &BacktestHandle::detached(),
+    )
+}
+
+/// Run a fail-fast backtest, streaming per-file progress to `handle` and
+/// stopping before the next file once the handle is cancelled.
+#[allow(clippy::too_many_arguments)]
+pub fn backtest_with_handle<P: ParserRunner>(
+    parser: &P,
+    files: &[FileInfo],
+    high_failure_table: &HighFailureTable,
+    scope_id: &ScopeId,
+    parser_version: usize,
+    iteration: usize,
+    config: &FailFastConfig,
+    handle: &BacktestHandle,
 ) -> Result<BacktestResult, HighFailureError> {
     // Get files in optimal order
     let ordered_files = high_failure_table.get_backtest_order(files, scope_id)?;
 
     let total_files = ordered_files.len();
+    handle.emit(BacktestEvent::Started {
+        iteration,
+        parser_version,
+        total_files,
+    });
     if total_files == 0 {
+        let metrics = IterationMetrics::new(iteration, parser_version);
+        emit_finished(handle, &metrics, BacktestStatus::Complete);
         return Ok(BacktestResult::Complete {
-            metrics: IterationMetrics::new(iteration, parser_version),
+            metrics,
             high_failure_pass_rate: 1.0,
             remaining_pass_rate: 1.0,
         });
@@ -145,10 +191,21 @@ pub fn backtest_with_failfast<P: ParserRunner>(
     let start_time = std::time::Instant::now();
 
     for (idx, file) in ordered_files.iter().enumerate() {
+        if handle.is_cancelled() {
+            metrics.duration_ms = start_time.elapsed().as_millis() as u64;
+            metrics.finalize();
+            emit_finished(handle, &metrics, BacktestStatus::Cancelled);
+            return Ok(BacktestResult::Cancelled {
+                metrics,
+                files_tested: idx,
+                files_remaining: total_files - idx,
+            });
+        }
+
         // Run parser on file
         let result = parser.run(&file.path);
 
-        if result.passed {
+        let violation = if result.passed {
             metrics.record_pass();
             high_failure_table.record_success(&file.path, scope_id)?;
 
@@ -157,6 +214,7 @@ pub fn backtest_with_failfast<P: ParserRunner>(
             } else {
                 remaining_passed += 1;
             }
+            None
         } else {
             let category = result.category.unwrap_or(FailureCategory::Unknown);
             let error_msg = result.error.as_deref().unwrap_or("Unknown error");
@@ -165,7 +223,21 @@ pub fn backtest_with_failfast<P: ParserRunner>(
             // Record failure in high-failure table
             let entry = FailureHistoryEntry::new(iteration, parser_version, category, error_msg);
             high_failure_table.record_failure(&file.path, scope_id, entry)?;
-        }
+            Some(ViolationSummary {
+                category,
+                message: error_msg.to_string(),
+            })
+        };
+
+        handle.emit(BacktestEvent::FileTested {
+            iteration,
+            index: idx + 1,
+            total_files,
+            file_path: file.path.clone(),
+            passed: result.passed,
+            is_high_failure: file.is_high_failure,
+            violation,
+        });
 
         // Track high-failure vs remaining
         if file.is_high_failure {
@@ -195,6 +267,7 @@ pub fn backtest_with_failfast<P: ParserRunner>(
                 if hf_pass_rate < config.high_failure_threshold {
                     metrics.duration_ms = start_time.elapsed().as_millis() as u64;
                     metrics.finalize();
+                    emit_finished(handle, &metrics, BacktestStatus::EarlyStopped);
 
                     return Ok(BacktestResult::EarlyStopped {
                         metrics,
@@ -214,6 +287,7 @@ pub fn backtest_with_failfast<P: ParserRunner>(
 
     metrics.duration_ms = start_time.elapsed().as_millis() as u64;
     metrics.finalize();
+    emit_finished(handle, &metrics, BacktestStatus::Complete);
 
     let high_failure_pass_rate = if high_failure_tested > 0 {
         high_failure_passed as f32 / high_failure_tested as f32
@@ -234,6 +308,17 @@ pub fn backtest_with_failfast<P: ParserRunner>(
     })
 }
 
+fn emit_finished(handle: &BacktestHandle, metrics: &IterationMetrics, status: BacktestStatus) {
+    handle.emit(BacktestEvent::Finished {
+        iteration: metrics.iteration,
+        status,
+        files_tested: metrics.files_tested,
+        files_passed: metrics.files_passed,
+        files_failed: metrics.files_failed,
+        pass_rate: metrics.pass_rate,
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -400,6 +485,133 @@ mod tests {
         assert!(result.is_complete());
     }
 
+    #[test]
+    fn test_backtest_streams_file_events() {
+        let table = create_test_table();
+        let scope_id = ScopeId::new();
+        let parser = MockParser {
+            failing_files: vec!["/path/b.csv".to_string()],
+        };
+        let config = FailFastConfig::no_early_stop();
+        let files = vec![
+            FileInfo::new("/path/a.csv", 100),
+            FileInfo::new("/path/b.csv", 100),
+        ];
+
+        let (handle, mut rx) = BacktestHandle::new();
+        let result =
+            backtest_with_handle(&parser, &files, &table, &scope_id, 1, 1, &config, &handle)
+                .unwrap();
+        assert!(result.is_complete());
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(events.len(), 4);
+        assert!(matches!(
+            events[0],
+            BacktestEvent::Started { total_files: 2, .. }
+        ));
+        let failed = events
+            .iter()
+            .find_map(|event| match event {
+                BacktestEvent::FileTested {
+                    file_path,
+                    passed: false,
+                    violation,
+                    ..
+                } => Some((file_path.clone(), violation.clone())),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(failed.0, "/path/b.csv");
+        assert_eq!(
+            failed.1,
+            Some(ViolationSummary {
+                category: FailureCategory::TypeMismatch,
+                message: "Mock failure".to_string(),
+            })
+        );
+        assert!(matches!(
+            events[3],
+            BacktestEvent::Finished {
+                status: BacktestStatus::Complete,
+                files_tested: 2,
+                files_passed: 1,
+                ..
+            }
+        ));
+    }
+
+    /// Cancels the shared handle after the first file.
+    struct CancellingParser {
+        handle: BacktestHandle,
+    }
+
+    impl ParserRunner for CancellingParser {
+        fn run(&self, file_path: &str) -> FileTestResult {
+            self.handle.cancel();
+            FileTestResult {
+                file_path: file_path.to_string(),
+                passed: true,
+                error: None,
+                category: None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_backtest_cancel_mid_run() {
+        let table = create_test_table();
+        let scope_id = ScopeId::new();
+        let (handle, mut rx) = BacktestHandle::new();
+        let parser = CancellingParser {
+            handle: handle.clone(),
+        };
+        let files = vec![
+            FileInfo::new("/path/a.csv", 100),
+            FileInfo::new("/path/b.csv", 100),
+            FileInfo::new("/path/c.csv", 100),
+        ];
+
+        let result = backtest_with_handle(
+            &parser,
+            &files,
+            &table,
+            &scope_id,
+            1,
+            1,
+            &FailFastConfig::default(),
+            &handle,
+        )
+        .unwrap();
+
+        match result {
+            BacktestResult::Cancelled {
+                files_tested,
+                files_remaining,
+                ..
+            } => {
+                assert_eq!(files_tested, 1);
+                assert_eq!(files_remaining, 2);
+            }
+            other => panic!("expected cancelled result, got {:?}", other),
+        }
+
+        let mut last = None;
+        while let Ok(event) = rx.try_recv() {
+            last = Some(event);
+        }
+        assert!(matches!(
+            last,
+            Some(BacktestEvent::Finished {
+                status: BacktestStatus::Cancelled,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_empty_files() {
         let table = create_test_table();
@@ -121,6 +121,12 @@ pub struct FileInfo {
     pub is_high_failure: bool,
     /// Number of consecutive failures (0 if not high-failure)
     pub consecutive_failures: usize,
+    /// Last modification time (Unix millis), for newest-first sampling
+    #[serde(default)]
+    pub modified_at: Option<i64>,
+    /// File tags, for stratified sampling by tag
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl FileInfo {
@@ -133,9 +139,23 @@ impl FileInfo {
             tested: false,
             is_high_failure: false,
             consecutive_failures: 0,
+            modified_at: None,
+            tags: Vec::new(),
         }
     }
 
+    /// Set the modification time (Unix millis)
+    pub fn with_modified_at(mut self, modified_at: i64) -> Self {
+        self.modified_at = Some(modified_at);
+        self
+    }
+
+    /// Set the file's tags
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
     /// Create from an existing file entry with high-failure info
     pub fn with_high_failure(mut self, consecutive_failures: usize) -> Self {
         self.is_high_failure = true;
@@ -3,9 +3,13 @@
 //! Runs multiple backtest iterations until a termination condition is met.
 //! Supports pass rate thresholds, plateau detection, and timeouts.
 
-use crate::failfast::{backtest_with_failfast, BacktestResult, FailFastConfig, ParserRunner};
+use crate::failfast::{
+    backtest_with_failfast, backtest_with_handle, BacktestResult, FailFastConfig, ParserRunner,
+};
 use crate::high_failure::{FileInfo, HighFailureError, HighFailureTable};
 use crate::metrics::{BacktestMetrics, IterationMetrics};
+use crate::progress::BacktestHandle;
+use crate::sampling::{SampleManifest, SamplingStrategy};
 use crate::ScopeId;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
@@ -30,6 +34,11 @@ pub struct IterationConfig {
 
     /// Fail-fast configuration
     pub failfast_config: FailFastConfig,
+
+    /// Corpus sampling per iteration: iteration N uses entry N-1, the last
+    /// entry applies to all later iterations (empty = every file)
+    #[serde(default)]
+    pub sampling: Vec<SamplingStrategy>,
 }
 
 impl Default for IterationConfig {
@@ -41,6 +50,7 @@ impl Default for IterationConfig {
             improvement_threshold: 0.01,
             plateau_window: 3,
             failfast_config: FailFastConfig::default(),
+            sampling: Vec::new(),
         }
     }
 }
@@ -55,6 +65,7 @@ impl IterationConfig {
             improvement_threshold: 0.05,
             plateau_window: 2,
             failfast_config: FailFastConfig::default(),
+            sampling: Vec::new(),
         }
     }
 
@@ -67,8 +78,18 @@ impl IterationConfig {
             improvement_threshold: 0.005,
             plateau_window: 5,
             failfast_config: FailFastConfig::default(),
+            sampling: Vec::new(),
         }
     }
+
+    /// Sampling strategy for `iteration` (1-based)
+    pub fn sampling_for(&self, iteration: usize) -> SamplingStrategy {
+        self.sampling
+            .get(iteration.saturating_sub(1))
+            .or(self.sampling.last())
+            .cloned()
+            .unwrap_or_default()
+    }
 }
 
 /// A single backtest iteration result
@@ -159,6 +180,11 @@ pub struct BacktestLoopResult {
 
     /// Final pass rate
     pub final_pass_rate: f32,
+
+    /// Files each sampled iteration tested (iterations that tested every
+    /// file are not recorded)
+    #[serde(default)]
+    pub samples: Vec<SampleManifest>,
 }
 
 impl BacktestLoopResult {
@@ -230,25 +256,72 @@ pub fn run_backtest_loop<P: MutableParser>(
     high_failure_table: &HighFailureTable,
     scope_id: &ScopeId,
     config: &IterationConfig,
+) -> Result<BacktestLoopResult, HighFailureError> {
+    run_backtest_loop_with_handle(
+        parser,
+        files,
+        high_failure_table,
+        scope_id,
+        config,
+        &BacktestHandle::detached(),
+    )
+}
+
+/// Run the backtest loop, streaming per-file progress to `handle`.
+///
+/// Cancelling the handle stops the current iteration before its next file
+/// and ends the loop with `TerminationReason::UserStopped`.
+pub fn run_backtest_loop_with_handle<P: MutableParser>(
+    parser: &mut P,
+    files: &[FileInfo],
+    high_failure_table: &HighFailureTable,
+    scope_id: &ScopeId,
+    config: &IterationConfig,
+    handle: &BacktestHandle,
 ) -> Result<BacktestLoopResult, HighFailureError> {
     let start_time = Instant::now();
     let mut iterations: Vec<BacktestIteration> = Vec::new();
     let mut metrics = BacktestMetrics::new();
+    let mut samples: Vec<SampleManifest> = Vec::new();
 
     loop {
         let iteration_num = iterations.len() + 1;
         let parser_version = parser.version();
 
+        if handle.is_cancelled() {
+            let final_pass_rate = iterations.last().map(|i| i.pass_rate).unwrap_or(0.0);
+            return Ok(BacktestLoopResult {
+                iterations,
+                metrics,
+                termination_reason: TerminationReason::UserStopped,
+                total_duration_ms: start_time.elapsed().as_millis() as u64,
+                final_pass_rate,
+                samples,
+            });
+        }
+
+        let strategy = config.sampling_for(iteration_num);
+        let sampled;
+        let iteration_files = if strategy == SamplingStrategy::All {
+            files
+        } else {
+            let (sample, manifest) = strategy.sample_with_manifest(files, iteration_num);
+            samples.push(manifest);
+            sampled = sample;
+            sampled.as_slice()
+        };
+
         // Run backtest
         // F-009: Pass files by reference instead of cloning
-        let result = backtest_with_failfast(
+        let result = backtest_with_handle(
             parser,
-            files,
+            iteration_files,
             high_failure_table,
             scope_id,
             parser_version,
             iteration_num,
             &config.failfast_config,
+            handle,
         )?;
 
         // Extract iteration info
@@ -271,6 +344,23 @@ pub fn run_backtest_loop<P: MutableParser>(
                 metrics.record_iteration(iter_metrics);
                 iter
             }
+            BacktestResult::Cancelled {
+                metrics: iter_metrics,
+                ..
+            } => {
+                let mut iter: BacktestIteration = iter_metrics.into();
+                iter.was_early_stopped = true;
+                metrics.record_iteration(iter_metrics);
+                iterations.push(iter);
+                return Ok(BacktestLoopResult {
+                    iterations,
+                    metrics,
+                    termination_reason: TerminationReason::UserStopped,
+                    total_duration_ms: start_time.elapsed().as_millis() as u64,
+                    final_pass_rate: iter.pass_rate,
+                    samples,
+                });
+            }
             BacktestResult::Error { error, .. } => {
                 return Ok(BacktestLoopResult {
                     iterations,
@@ -280,6 +370,7 @@ pub fn run_backtest_loop<P: MutableParser>(
                     },
                     total_duration_ms: start_time.elapsed().as_millis() as u64,
                     final_pass_rate: 0.0,
+                    samples,
                 });
             }
         };
@@ -296,6 +387,7 @@ pub fn run_backtest_loop<P: MutableParser>(
                 termination_reason: reason,
                 total_duration_ms: start_time.elapsed().as_millis() as u64,
                 final_pass_rate,
+                samples,
             });
         }
 
@@ -312,6 +404,7 @@ pub fn run_backtest_loop<P: MutableParser>(
                 },
                 total_duration_ms: start_time.elapsed().as_millis() as u64,
                 final_pass_rate,
+                samples,
             });
         }
     }
@@ -533,6 +626,86 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_loop_stops_when_cancelled() {
+        let table = create_test_table();
+        let scope_id = ScopeId::new();
+
+        let mut parser = TestParser {
+            version: 1,
+            failing_files: vec!["/path/a.csv".to_string()],
+            fix_one_per_iteration: true,
+        };
+        let files = vec![FileInfo::new("/path/a.csv", 100)];
+
+        let (handle, _rx) = BacktestHandle::new();
+        handle.cancel();
+        let result = run_backtest_loop_with_handle(
+            &mut parser,
+            &files,
+            &table,
+            &scope_id,
+            &IterationConfig::default(),
+            &handle,
+        )
+        .expect("run backtest loop");
+
+        assert_eq!(result.termination_reason, TerminationReason::UserStopped);
+        assert!(result.iterations.is_empty());
+    }
+
+    #[test]
+    fn test_loop_records_sample_manifests() {
+        let table = create_test_table();
+        let scope_id = ScopeId::new();
+
+        let mut parser = TestParser {
+            version: 1,
+            failing_files: vec!["/path/a.csv".to_string()],
+            fix_one_per_iteration: true,
+        };
+        let config = IterationConfig {
+            max_iterations: 3,
+            pass_rate_threshold: 1.0,
+            sampling: vec![SamplingStrategy::Random { size: 2, seed: 9 }],
+            ..Default::default()
+        };
+        let files: Vec<FileInfo> = (0..10)
+            .map(|i| FileInfo::new(format!("/path/{}.csv", i), 100))
+            .chain(std::iter::once(FileInfo::new("/path/a.csv", 100)))
+            .collect();
+
+        let result = run_backtest_loop(&mut parser, &files, &table, &scope_id, &config)
+            .expect("run backtest loop");
+
+        assert_eq!(result.samples.len(), result.iterations.len());
+        for (manifest, iteration) in result.samples.iter().zip(&result.iterations) {
+            assert_eq!(manifest.iteration, iteration.iteration);
+            assert_eq!(manifest.population, files.len());
+            let replayed = manifest.replay().sample(&files);
+            assert_eq!(
+                replayed.iter().map(|f| f.path.clone()).collect::<Vec<_>>(),
+                manifest.paths
+            );
+        }
+    }
+
+    #[test]
+    fn test_sampling_for_repeats_last_strategy() {
+        let newest = SamplingStrategy::NewestFirst { size: 5 };
+        let config = IterationConfig {
+            sampling: vec![newest.clone(), SamplingStrategy::All],
+            ..Default::default()
+        };
+        assert_eq!(config.sampling_for(1), newest);
+        assert_eq!(config.sampling_for(2), SamplingStrategy::All);
+        assert_eq!(config.sampling_for(7), SamplingStrategy::All);
+        assert_eq!(
+            IterationConfig::default().sampling_for(1),
+            SamplingStrategy::All
+        );
+    }
+
     #[test]
     fn test_single_backtest() {
         let table = create_test_table();
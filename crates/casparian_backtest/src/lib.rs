@@ -27,15 +27,24 @@
 //! - Plateau detected (no improvement for N iterations)
 //! - Timeout
 //! - User stopped
+//!
+//! # Sampling
+//!
+//! For large corpora each iteration can test a sample (random, stratified,
+//! newest-first) instead of every file; see [`sampling`].
 
 pub mod failfast;
 pub mod high_failure;
 pub mod ids;
 pub mod iteration;
 pub mod metrics;
+pub mod progress;
+pub mod sampling;
 
 pub use failfast::*;
 pub use high_failure::*;
 pub use ids::{FileId, IdParseError, ScopeId};
 pub use iteration::*;
 pub use metrics::*;
+pub use progress::*;
+pub use sampling::*;
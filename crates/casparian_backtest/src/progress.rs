@@ -0,0 +1,139 @@
+//! Live backtest progress and cancellation
+//!
+//! A `BacktestHandle` is passed into a backtest run. The run emits a
+//! `BacktestEvent` per tested file over a tokio mpsc channel and checks the
+//! handle's cancel flag before each file, so a UI (Deck, MCP server) can show
+//! live progress and stop a run early.
+//!
+//! The channel is unbounded and sending never blocks, so the backtest itself
+//! stays synchronous: consumers can `recv().await` inside a runtime or
+//! `blocking_recv()` from a plain thread.
+
+use crate::metrics::FailureCategory;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// How a backtest run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BacktestStatus {
+    /// All files were tested
+    Complete,
+    /// Stopped by the fail-fast threshold
+    EarlyStopped,
+    /// Stopped via `BacktestHandle::cancel`
+    Cancelled,
+}
+
+/// Why a file failed, in a form small enough to stream
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViolationSummary {
+    pub category: FailureCategory,
+    pub message: String,
+}
+
+/// Progress event emitted during a backtest run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BacktestEvent {
+    /// A run is starting
+    Started {
+        iteration: usize,
+        parser_version: usize,
+        total_files: usize,
+    },
+    /// One file was tested
+    FileTested {
+        iteration: usize,
+        /// 1-based position in test order
+        index: usize,
+        total_files: usize,
+        file_path: String,
+        passed: bool,
+        is_high_failure: bool,
+        violation: Option<ViolationSummary>,
+    },
+    /// The run ended
+    Finished {
+        iteration: usize,
+        status: BacktestStatus,
+        files_tested: usize,
+        files_passed: usize,
+        files_failed: usize,
+        pass_rate: f32,
+    },
+}
+
+/// Progress sink and cancel switch for a backtest run.
+///
+/// Clones share the same cancel flag and channel.
+#[derive(Debug, Clone, Default)]
+pub struct BacktestHandle {
+    cancelled: Arc<AtomicBool>,
+    events: Option<mpsc::UnboundedSender<BacktestEvent>>,
+}
+
+impl BacktestHandle {
+    /// Create a handle and the receiver for its progress events.
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<BacktestEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handle = Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            events: Some(tx),
+        };
+        (handle, rx)
+    }
+
+    /// A handle with no event channel (cancellation only).
+    pub fn detached() -> Self {
+        Self::default()
+    }
+
+    /// Request that the run stop before the next file.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Send an event; a dropped receiver is not an error.
+    pub(crate) fn emit(&self, event: BacktestEvent) {
+        if let Some(tx) = &self.events {
+            let _ = tx.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_clones_share_cancel_flag() {
+        let (handle, _rx) = BacktestHandle::new();
+        let clone = handle.clone();
+        assert!(!handle.is_cancelled());
+        clone.cancel();
+        assert!(handle.is_cancelled());
+    }
+
+    #[test]
+    fn test_emit_after_receiver_dropped() {
+        let (handle, rx) = BacktestHandle::new();
+        drop(rx);
+        handle.emit(BacktestEvent::Started {
+            iteration: 1,
+            parser_version: 1,
+            total_files: 0,
+        });
+        BacktestHandle::detached().emit(BacktestEvent::Started {
+            iteration: 1,
+            parser_version: 1,
+            total_files: 0,
+        });
+    }
+}
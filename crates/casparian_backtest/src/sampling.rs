@@ -0,0 +1,352 @@
+//! Corpus sampling for large backtests
+//!
+//! With 100k files a full pass per iteration is too slow, so each iteration
+//! can test a sample of the corpus instead. The sample is drawn before the
+//! fail-fast ordering, which then orders the sampled files as usual.
+//!
+//! Every draw is recorded as a `SampleManifest`. Sampling is deterministic
+//! (files are sorted by path before drawing and the PRNG is a fixed
+//! algorithm, not `rand`), and a manifest can be replayed exactly with
+//! `SampleManifest::replay`.
+
+use crate::high_failure::FileInfo;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Stratum key for stratified sampling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StratifyBy {
+    /// Parent directory of the file
+    Directory,
+    /// Order of magnitude of the file size (<10 B, <100 B, ...)
+    Size,
+    /// First tag of the file (`untagged` when it has none)
+    Tag,
+}
+
+/// How the files of one iteration are chosen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum SamplingStrategy {
+    /// Test every file
+    #[default]
+    All,
+    /// Uniform random sample
+    Random { size: usize, seed: u64 },
+    /// Random sample with each stratum represented in proportion to its size
+    /// (and at least once while `size` allows)
+    Stratified {
+        size: usize,
+        by: StratifyBy,
+        seed: u64,
+    },
+    /// The most recently modified files; files without `modified_at` last
+    NewestFirst { size: usize },
+    /// Exactly these paths (replay of a recorded sample)
+    Fixed { paths: Vec<String> },
+}
+
+/// The files one iteration tested and how they were chosen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleManifest {
+    pub iteration: usize,
+    pub strategy: SamplingStrategy,
+    /// Number of files sampled from
+    pub population: usize,
+    /// Sampled paths, sorted
+    pub paths: Vec<String>,
+}
+
+impl SampleManifest {
+    /// Strategy that selects exactly this sample again.
+    pub fn replay(&self) -> SamplingStrategy {
+        SamplingStrategy::Fixed {
+            paths: self.paths.clone(),
+        }
+    }
+}
+
+impl SamplingStrategy {
+    /// Draw a sample of `files`; the result is sorted by path.
+    pub fn sample(&self, files: &[FileInfo]) -> Vec<FileInfo> {
+        let mut sorted: Vec<&FileInfo> = files.iter().collect();
+        sorted.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut chosen: Vec<&FileInfo> = match self {
+            SamplingStrategy::All => sorted,
+            SamplingStrategy::Random { size, seed } => {
+                random_subset(sorted, *size, &mut SplitMix64::new(*seed))
+            }
+            SamplingStrategy::Stratified { size, by, seed } => {
+                stratified_subset(sorted, *size, *by, *seed)
+            }
+            SamplingStrategy::NewestFirst { size } => {
+                // Stable sort keeps path order among equal timestamps.
+                sorted.sort_by_key(|file| Reverse(file.modified_at));
+                sorted.truncate(*size);
+                sorted
+            }
+            SamplingStrategy::Fixed { paths } => {
+                let wanted: HashSet<&str> = paths.iter().map(String::as_str).collect();
+                sorted
+                    .into_iter()
+                    .filter(|file| wanted.contains(file.path.as_str()))
+                    .collect()
+            }
+        };
+        chosen.sort_by(|a, b| a.path.cmp(&b.path));
+        chosen.into_iter().cloned().collect()
+    }
+
+    /// Draw a sample and record it for `iteration`.
+    pub fn sample_with_manifest(
+        &self,
+        files: &[FileInfo],
+        iteration: usize,
+    ) -> (Vec<FileInfo>, SampleManifest) {
+        let sample = self.sample(files);
+        let manifest = SampleManifest {
+            iteration,
+            strategy: self.clone(),
+            population: files.len(),
+            paths: sample.iter().map(|file| file.path.clone()).collect(),
+        };
+        (sample, manifest)
+    }
+}
+
+/// Partial Fisher-Yates shuffle: `size` files chosen uniformly.
+fn random_subset<'a>(
+    mut files: Vec<&'a FileInfo>,
+    size: usize,
+    rng: &mut SplitMix64,
+) -> Vec<&'a FileInfo> {
+    let size = size.min(files.len());
+    for i in 0..size {
+        let j = i + rng.below(files.len() - i);
+        files.swap(i, j);
+    }
+    files.truncate(size);
+    files
+}
+
+fn stratified_subset(
+    files: Vec<&FileInfo>,
+    size: usize,
+    by: StratifyBy,
+    seed: u64,
+) -> Vec<&FileInfo> {
+    let total = files.len();
+    if size >= total {
+        return files;
+    }
+    let mut strata: BTreeMap<String, Vec<&FileInfo>> = BTreeMap::new();
+    for file in files {
+        strata.entry(stratum_key(file, by)).or_default().push(file);
+    }
+
+    let quotas = allocate(
+        &strata.values().map(Vec::len).collect::<Vec<_>>(),
+        size,
+        total,
+    );
+    // One PRNG for all strata, advanced in key order, so the draw depends
+    // only on the seed and the corpus.
+    let mut rng = SplitMix64::new(seed);
+    strata
+        .into_values()
+        .zip(quotas)
+        .flat_map(|(members, quota)| random_subset(members, quota, &mut rng))
+        .collect()
+}
+
+/// Split `size` across strata of the given lengths: proportionally with the
+/// largest remainders rounded up, and at least one each while `size` allows.
+fn allocate(lengths: &[usize], size: usize, total: usize) -> Vec<usize> {
+    let mut quotas = vec![0; lengths.len()];
+    if lengths.len() > size {
+        // Not every stratum fits: one file from each of the largest.
+        let mut order: Vec<usize> = (0..lengths.len()).collect();
+        order.sort_by(|&a, &b| lengths[b].cmp(&lengths[a]));
+        for index in order.into_iter().take(size) {
+            quotas[index] = 1;
+        }
+        return quotas;
+    }
+
+    let mut remainders = Vec::with_capacity(lengths.len());
+    for (index, &len) in lengths.iter().enumerate() {
+        let exact = size as f64 * len as f64 / total as f64;
+        quotas[index] = (exact.floor() as usize).clamp(1, len);
+        remainders.push((exact - exact.floor(), index));
+    }
+    remainders.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let mut assigned: usize = quotas.iter().sum();
+    // The minimum of one per stratum can overshoot; take back from the
+    // largest quotas first.
+    while assigned > size {
+        let index = (0..quotas.len())
+            .filter(|&index| quotas[index] > 1)
+            .max_by_key(|&index| quotas[index])
+            .expect("size >= number of strata");
+        quotas[index] -= 1;
+        assigned -= 1;
+    }
+    while assigned < size {
+        let before = assigned;
+        for &(_, index) in &remainders {
+            if assigned == size {
+                break;
+            }
+            if quotas[index] < lengths[index] {
+                quotas[index] += 1;
+                assigned += 1;
+            }
+        }
+        if assigned == before {
+            break;
+        }
+    }
+    quotas
+}
+
+fn stratum_key(file: &FileInfo, by: StratifyBy) -> String {
+    match by {
+        StratifyBy::Directory => Path::new(&file.path)
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        StratifyBy::Size => {
+            // Zero-padded so strata sort by size.
+            let magnitude = file.size.checked_ilog10().map_or(0, |digits| digits + 1);
+            format!("{:02}", magnitude)
+        }
+        StratifyBy::Tag => file
+            .tags
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "untagged".to_string()),
+    }
+}
+
+/// SplitMix64: tiny, fixed PRNG so a seed reproduces the same sample on
+/// every build.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound` (`bound > 0`).
+    fn below(&mut self, bound: usize) -> usize {
+        // Modulo bias is negligible for corpus-sized bounds.
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Vec<FileInfo> {
+        let mut files = Vec::new();
+        for i in 0..80 {
+            files.push(
+                FileInfo::new(format!("/data/big/{:03}.csv", i), 1_000_000)
+                    .with_modified_at(i)
+                    .with_tags(vec!["trades".to_string()]),
+            );
+        }
+        for i in 0..20 {
+            files.push(FileInfo::new(format!("/data/small/{:03}.csv", i), 50).with_modified_at(i));
+        }
+        files
+    }
+
+    fn paths(files: &[FileInfo]) -> Vec<&str> {
+        files.iter().map(|file| file.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_random_sample_is_reproducible() {
+        let files = corpus();
+        let strategy = SamplingStrategy::Random { size: 10, seed: 7 };
+        let first = strategy.sample(&files);
+        assert_eq!(first.len(), 10);
+
+        // Input order does not matter, the seed does.
+        let mut reversed = files.clone();
+        reversed.reverse();
+        assert_eq!(paths(&strategy.sample(&reversed)), paths(&first));
+        let other = SamplingStrategy::Random { size: 10, seed: 8 }.sample(&files);
+        assert_ne!(paths(&other), paths(&first));
+
+        let (sample, manifest) = strategy.sample_with_manifest(&files, 3);
+        assert_eq!(manifest.population, 100);
+        assert_eq!(manifest.iteration, 3);
+        assert_eq!(paths(&manifest.replay().sample(&files)), paths(&sample));
+    }
+
+    #[test]
+    fn test_stratified_sample_covers_every_stratum() {
+        let files = corpus();
+        for by in [StratifyBy::Directory, StratifyBy::Size, StratifyBy::Tag] {
+            let sample = SamplingStrategy::Stratified {
+                size: 10,
+                by,
+                seed: 1,
+            }
+            .sample(&files);
+            assert_eq!(sample.len(), 10);
+            let small = sample
+                .iter()
+                .filter(|file| file.path.starts_with("/data/small/"))
+                .count();
+            assert_eq!(small, 2, "{:?}", by);
+        }
+
+        // A tiny sample still takes one file from each stratum.
+        let sample = SamplingStrategy::Stratified {
+            size: 2,
+            by: StratifyBy::Directory,
+            seed: 1,
+        }
+        .sample(&files);
+        assert_eq!(sample.len(), 2);
+        assert!(sample[1].path.starts_with("/data/small/"));
+    }
+
+    #[test]
+    fn test_newest_first() {
+        let sample = SamplingStrategy::NewestFirst { size: 3 }.sample(&corpus());
+        assert_eq!(
+            paths(&sample),
+            vec![
+                "/data/big/077.csv",
+                "/data/big/078.csv",
+                "/data/big/079.csv"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_allocate_quotas() {
+        assert_eq!(allocate(&[80, 20], 10, 100), vec![8, 2]);
+        assert_eq!(allocate(&[98, 1, 1], 5, 100), vec![3, 1, 1]);
+        assert_eq!(allocate(&[5, 9, 1], 2, 15), vec![1, 1, 0]);
+        assert_eq!(allocate(&[2, 2], 4, 4), vec![2, 2]);
+    }
+}
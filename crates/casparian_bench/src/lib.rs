@@ -0,0 +1,23 @@
+//! End-to-end benchmarks of the scan → dispatch → sink pipeline.
+//!
+//! A run generates a reproducible corpus of CSV or JSONL files, scans it
+//! with Scout into a fresh state store, queues one job per file and lets a
+//! real Sentinel dispatch them to in-process bench workers, which decode
+//! each file and write it through `casparian_sinks`. The [`BenchReport`]
+//! holds scan and pipeline throughput (rows/s, MB/s), dispatch latency
+//! percentiles and per-file parse and sink-write timings as JSON, and
+//! [`compare`] checks a report against a previous release's.
+//!
+//! Plugin execution is not measured: bench workers stand in for the plugin
+//! runtime so results track the control plane and sinks only.
+
+pub mod corpus;
+pub mod pipeline;
+pub mod report;
+pub mod stats;
+mod worker;
+
+pub use corpus::{Corpus, CorpusFormat, CorpusSpec};
+pub use pipeline::{run, BenchConfig};
+pub use report::{compare, BenchReport, Regression, REPORT_SCHEMA_VERSION};
+pub use stats::LatencySummary;
@@ -0,0 +1,138 @@
+//! Casparian Flow end-to-end benchmark.
+//!
+//! Usage:
+//!     casparian-bench --files 1000 --file-bytes 65536 --format csv --output bench.json
+//!     casparian-bench --baseline previous.json --max-regression-pct 10
+
+use anyhow::Context;
+use casparian_bench::{BenchConfig, BenchReport, CorpusFormat, CorpusSpec};
+use clap::Parser;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "casparian-bench",
+    about = "End-to-end scan, dispatch and sink benchmark for Casparian Flow"
+)]
+struct Args {
+    /// Number of corpus files
+    #[arg(long, default_value_t = 200)]
+    files: usize,
+
+    /// Target size of each corpus file in bytes
+    #[arg(long, default_value_t = 64 * 1024)]
+    file_bytes: u64,
+
+    /// Corpus file format
+    #[arg(long, value_enum, default_value_t = CorpusFormat::Csv)]
+    format: CorpusFormat,
+
+    /// Seed of the corpus generator (same seed, same bytes)
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    /// Bench workers connected to the Sentinel (1-8)
+    #[arg(long, default_value_t = 4)]
+    workers: usize,
+
+    /// Max files per DISPATCH (1 = one file per dispatch)
+    #[arg(long, default_value_t = 1)]
+    dispatch_batch_size: usize,
+
+    /// Sink URI for job outputs (default: parquet:// under the work directory)
+    #[arg(long)]
+    sink: Option<String>,
+
+    /// Keep corpus, state store and outputs in this directory
+    #[arg(long)]
+    work_dir: Option<PathBuf>,
+
+    /// Fail if the pipeline takes longer than this many seconds
+    #[arg(long, default_value_t = 600)]
+    timeout_secs: u64,
+
+    /// Write the JSON report here instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Previous report to compare against; exits 1 on a regression
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Allowed slowdown against --baseline, in percent
+    #[arg(long, default_value_t = 10.0, requires = "baseline")]
+    max_regression_pct: f64,
+}
+
+fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "casparian_bench=info,casparian_sentinel=warn".into()),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    match run(Args::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Returns false when the report regressed against the baseline.
+fn run(args: Args) -> anyhow::Result<bool> {
+    let mut config = BenchConfig::new(CorpusSpec {
+        files: args.files,
+        file_bytes: args.file_bytes,
+        format: args.format,
+        seed: args.seed,
+    });
+    config.workers = args.workers;
+    config.dispatch_batch_size = args.dispatch_batch_size;
+    config.sink_uri = args.sink;
+    config.work_dir = args.work_dir;
+    config.timeout_secs = args.timeout_secs;
+
+    let report = casparian_bench::run(&config)?;
+    let json = serde_json::to_string_pretty(&report)?;
+    match &args.output {
+        Some(path) => std::fs::write(path, format!("{}\n", json))
+            .with_context(|| format!("Failed to write report to {}", path.display()))?,
+        None => println!("{}", json),
+    }
+    eprintln!(
+        "{} jobs ({} failed): {:.0} rows/s, {:.1} MB/s, dispatch p50 {:.2} ms / p99 {:.2} ms",
+        report.pipeline.jobs,
+        report.pipeline.failed,
+        report.pipeline.rows_per_sec,
+        report.pipeline.mb_per_sec,
+        report.pipeline.dispatch_latency.p50_ms,
+        report.pipeline.dispatch_latency.p99_ms
+    );
+
+    let Some(baseline_path) = args.baseline else {
+        return Ok(report.pipeline.failed == 0);
+    };
+    let baseline: BenchReport = serde_json::from_str(
+        &std::fs::read_to_string(&baseline_path)
+            .with_context(|| format!("Failed to read baseline {}", baseline_path.display()))?,
+    )
+    .with_context(|| format!("Invalid baseline report {}", baseline_path.display()))?;
+    let regressions = casparian_bench::compare(&baseline, &report, args.max_regression_pct)?;
+    for regression in &regressions {
+        eprintln!(
+            "REGRESSION {}: {:.3} -> {:.3} ({:.1}% worse, baseline {})",
+            regression.metric,
+            regression.baseline,
+            regression.current,
+            regression.change_pct,
+            baseline.casparian_version
+        );
+    }
+    Ok(regressions.is_empty() && report.pipeline.failed == 0)
+}
@@ -0,0 +1,418 @@
+//! scan → dispatch → sink run.
+//!
+//! 1. Generate the corpus and scan it into a fresh SQLite state store.
+//! 2. Deploy the bench plugin and queue one job per scanned file.
+//! 3. Start a Sentinel on that store and `workers` bench workers, and wait
+//!    until every file is concluded and the Sentinel has recorded it.
+
+use crate::corpus::{self, Corpus, CorpusSpec};
+use crate::report::{BenchReport, PipelineReport, ScanReport, REPORT_SCHEMA_VERSION};
+use crate::stats::{megabytes, per_sec, LatencySummary};
+use crate::worker::{BenchWorker, WorkerStats};
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::types::RuntimeKind;
+use casparian_protocol::{defaults, ProcessingStatus};
+use casparian_scout::{ScanConfig, Source, SourceId, SourceType};
+use casparian_sentinel::{
+    SchedulingPolicy, SecurityConfig, Sentinel, SentinelConfig, WorkerHealthConfig,
+};
+use casparian_state_store::{reserve_job_ids, PluginDeployRequest, StateStore};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Plugin name of the queued jobs.
+pub const BENCH_PLUGIN: &str = "casparian_bench";
+/// Sentinel hard cap on connected workers.
+const MAX_BENCH_WORKERS: usize = 8;
+/// How long to wait for the Sentinel to record the last CONCLUDE.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Benchmark parameters; serialized into the report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchConfig {
+    pub corpus: CorpusSpec,
+    /// Bench workers connected to the Sentinel
+    pub workers: usize,
+    /// Sentinel `--dispatch-batch-size`
+    pub dispatch_batch_size: usize,
+    /// Sink every job writes to (default: Parquet under the work directory)
+    pub sink_uri: Option<String>,
+    /// Give up if the pipeline has not finished after this long
+    pub timeout_secs: u64,
+    /// Keep corpus, state store and outputs here instead of a temp directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_dir: Option<PathBuf>,
+}
+
+impl BenchConfig {
+    /// One worker, unbatched dispatch, Parquet output.
+    pub fn new(corpus: CorpusSpec) -> Self {
+        Self {
+            corpus,
+            workers: 1,
+            dispatch_batch_size: 1,
+            sink_uri: None,
+            timeout_secs: 600,
+            work_dir: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_BENCH_WORKERS).contains(&self.workers) {
+            anyhow::bail!("workers must be between 1 and {}", MAX_BENCH_WORKERS);
+        }
+        if self.dispatch_batch_size == 0 {
+            anyhow::bail!("dispatch_batch_size must be at least 1");
+        }
+        if self.corpus.files == 0 {
+            anyhow::bail!("corpus needs at least one file");
+        }
+        Ok(())
+    }
+}
+
+/// Run the benchmark described by `config`.
+pub fn run(config: &BenchConfig) -> Result<BenchReport> {
+    config.validate()?;
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let (_temp_dir, work_dir) = match &config.work_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            (None, dir.clone())
+        }
+        None => {
+            let temp_dir = tempfile::TempDir::new()?;
+            let path = temp_dir.path().to_path_buf();
+            (Some(temp_dir), path)
+        }
+    };
+
+    info!(
+        "Generating {} {:?} files of {} bytes",
+        config.corpus.files, config.corpus.format, config.corpus.file_bytes
+    );
+    let corpus = corpus::generate(&config.corpus, &work_dir.join("corpus"))?;
+
+    let state_path = work_dir.join("state.sqlite");
+    for suffix in ["", "-wal", "-shm"] {
+        let path = PathBuf::from(format!("{}{}", state_path.display(), suffix));
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove old state store {}", path.display()))?;
+        }
+    }
+    let state_store_url = format!("sqlite:{}", state_path.display());
+    let store = StateStore::open(&state_store_url)?;
+    store.init()?;
+
+    info!("Scanning {}", corpus.root.display());
+    let (scan, files) = scan_corpus(&store, &corpus)?;
+    deploy_bench_plugin(&store)?;
+    let jobs = enqueue_jobs(&state_path, &files)?;
+
+    let sink_uri = config
+        .sink_uri
+        .clone()
+        .unwrap_or_else(|| format!("parquet://{}", work_dir.join("output").display()));
+    info!("Dispatching {} jobs to {} workers", jobs, config.workers);
+    let pipeline = run_pipeline(
+        config,
+        &work_dir,
+        &state_store_url,
+        &state_path,
+        jobs,
+        &sink_uri,
+    )?;
+
+    Ok(BenchReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        casparian_version: env!("CARGO_PKG_VERSION").to_string(),
+        started_at,
+        config: config.clone(),
+        corpus,
+        scan,
+        pipeline,
+    })
+}
+
+/// Scan the corpus as a local source; returns `(file_id, path)` per file.
+fn scan_corpus(store: &StateStore, corpus: &Corpus) -> Result<(ScanReport, Vec<(i64, String)>)> {
+    let session = store.session_bulk()?;
+    let scout = session.scout();
+    let workspace = scout.ensure_default_workspace()?;
+    let source = Source {
+        workspace_id: workspace.id,
+        id: SourceId::new(),
+        name: "Bench Corpus".to_string(),
+        source_type: SourceType::Local,
+        path: corpus.root.display().to_string(),
+        exec_path: None,
+        poll_interval_secs: 0,
+        enabled: true,
+    };
+    scout.upsert_source(&source)?;
+
+    let start = Instant::now();
+    let result = session
+        .scanner(ScanConfig::default())?
+        .scan_source(&source)?;
+    let elapsed = start.elapsed();
+    if let Some(error) = result.errors.first() {
+        anyhow::bail!("Scan failed on {}: {}", error.path, error.message);
+    }
+
+    let files: Vec<(i64, String)> = scout
+        .list_files_by_source(&source.id, corpus.files + 1)?
+        .into_iter()
+        .filter_map(|file| file.id.map(|id| (id, file.path)))
+        .collect();
+    if files.len() != corpus.files {
+        anyhow::bail!(
+            "Scan found {} files, the corpus has {}",
+            files.len(),
+            corpus.files
+        );
+    }
+
+    let stats = result.stats;
+    let report = ScanReport {
+        files: stats.files_persisted,
+        bytes: stats.bytes_scanned,
+        duration_ms: elapsed.as_secs_f64() * 1000.0,
+        files_per_sec: per_sec(stats.files_persisted as f64, elapsed),
+        mb_per_sec: per_sec(megabytes(stats.bytes_scanned), elapsed),
+    };
+    Ok((report, files))
+}
+
+fn deploy_bench_plugin(store: &StateStore) -> Result<()> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let source_code = "# Executed in-process by casparian-bench\n".to_string();
+    store.routing().deploy_plugin(PluginDeployRequest {
+        plugin_name: BENCH_PLUGIN.to_string(),
+        namespace: defaults::DEFAULT_NAMESPACE.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        runtime_kind: RuntimeKind::PythonShim,
+        entrypoint: "bench.py:parse".to_string(),
+        platform_os: None,
+        platform_arch: None,
+        source_hash: blake3::hash(source_code.as_bytes()).to_hex().to_string(),
+        source_code,
+        env_hash: String::new(),
+        artifact_hash: String::new(),
+        manifest_json: "{}".to_string(),
+        protocol_version: "1.0".to_string(),
+        schema_artifacts_json: "{}".to_string(),
+        outputs_json: "[]".to_string(),
+        signature_verified: false,
+        signer_id: None,
+        created_at: now,
+        deployed_at: now,
+        publisher_name: BENCH_PLUGIN.to_string(),
+        publisher_email: None,
+        azure_oid: None,
+        system_requirements_json: None,
+        lockfile_content: None,
+        contracts: Vec::new(),
+    })
+}
+
+fn enqueue_jobs(state_path: &Path, files: &[(i64, String)]) -> Result<u64> {
+    let conn = DbConnection::open_sqlite(state_path)?;
+    let job_ids = reserve_job_ids(&conn, files.len() as u64)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    conn.execute("BEGIN TRANSACTION", &[])?;
+    for ((file_id, path), job_id) in files.iter().zip(job_ids) {
+        conn.execute(
+            "INSERT INTO cf_processing_queue (id, file_id, input_file, plugin_name, status, priority, scheduled_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            &[
+                DbValue::from(job_id.to_i64()?),
+                DbValue::from(*file_id),
+                DbValue::from(path.as_str()),
+                DbValue::from(BENCH_PLUGIN),
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(0),
+                DbValue::from(now),
+            ],
+        )
+        .context("Failed to enqueue bench job")?;
+    }
+    conn.execute("COMMIT", &[])?;
+    Ok(files.len() as u64)
+}
+
+fn run_pipeline(
+    config: &BenchConfig,
+    work_dir: &Path,
+    state_store_url: &str,
+    state_path: &Path,
+    jobs: u64,
+    sink_uri: &str,
+) -> Result<PipelineReport> {
+    #[cfg(unix)]
+    let bind_addr = format!("ipc://{}", work_dir.join("sentinel.sock").display());
+    #[cfg(not(unix))]
+    let bind_addr = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        format!("tcp://127.0.0.1:{}", listener.local_addr()?.port())
+    };
+
+    let sentinel_config = SentinelConfig {
+        bind_addr: bind_addr.clone(),
+        state_store_url: state_store_url.to_string(),
+        max_workers: config.workers,
+        control_addr: None,
+        query_catalog_path: work_dir.join("query.duckdb"),
+        scheduling_policy: SchedulingPolicy::default(),
+        metrics_addr: None,
+        http_api_addr: None,
+        security: SecurityConfig::disabled(),
+        allow_unsigned_deploys: true,
+        dispatch_batch_size: config.dispatch_batch_size,
+        worker_health: WorkerHealthConfig::default(),
+        priority_aging_secs: casparian_sentinel::DEFAULT_PRIORITY_AGING_SECS,
+        preempt_low_priority: false,
+        read_only: false,
+        rehash: false,
+        schema_drift_threshold: casparian_sentinel::DEFAULT_SCHEMA_DRIFT_THRESHOLD,
+        worker_pool: None,
+        config_path: None,
+    };
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    let sentinel_thread = thread::spawn(move || -> Result<()> {
+        let mut sentinel = match Sentinel::bind(sentinel_config) {
+            Ok(sentinel) => sentinel,
+            Err(err) => {
+                let _ = ready_tx.send(Err(format!("{:#}", err)));
+                return Err(err);
+            }
+        };
+        let _ = ready_tx.send(Ok(()));
+        sentinel.run_with_shutdown(stop_rx)
+    });
+    match ready_rx.recv() {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => anyhow::bail!("Sentinel failed to start: {}", err),
+        Err(_) => anyhow::bail!("Sentinel thread exited during startup"),
+    }
+
+    let context = zmq::Context::new();
+    let files_done = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut worker_threads = Vec::with_capacity(config.workers);
+    for index in 0..config.workers {
+        let worker = BenchWorker::connect(
+            &context,
+            &bind_addr,
+            format!("bench-{}", index),
+            config.corpus.format,
+            sink_uri.to_string(),
+            files_done.clone(),
+        )?;
+        let stop = stop.clone();
+        worker_threads.push(thread::spawn(move || worker.run(&stop)));
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(config.timeout_secs);
+    let finished = wait_until(deadline, || files_done.load(Ordering::SeqCst) >= jobs);
+    // The Sentinel records CONCLUDEs asynchronously; let it catch up.
+    let conn = DbConnection::open_sqlite(state_path)?;
+    let mut counts = (0, 0, 0);
+    wait_until(Instant::now() + SETTLE_TIMEOUT, || {
+        match job_counts(&conn) {
+            Ok(current) => counts = current,
+            Err(err) => tracing::warn!("Failed to count bench jobs: {}", err),
+        }
+        counts.0 + counts.1 >= jobs
+    });
+
+    stop.store(true, Ordering::SeqCst);
+    let mut stats = Vec::with_capacity(worker_threads.len());
+    for handle in worker_threads {
+        match handle.join() {
+            Ok(result) => stats.push(result?),
+            Err(_) => anyhow::bail!("Bench worker panicked"),
+        }
+    }
+    let _ = stop_tx.send(());
+    match sentinel_thread.join() {
+        Ok(result) => result?,
+        Err(_) => anyhow::bail!("Sentinel thread panicked"),
+    }
+    if !finished {
+        anyhow::bail!(
+            "Pipeline did not finish within {}s: {} of {} files concluded ({} still pending)",
+            config.timeout_secs,
+            files_done.load(Ordering::SeqCst),
+            jobs,
+            counts.2
+        );
+    }
+
+    Ok(pipeline_report(jobs, counts, &stats))
+}
+
+/// `(completed, failed, pending)` bench jobs.
+fn job_counts(conn: &DbConnection) -> Result<(u64, u64, u64)> {
+    let rows = conn.query_all(
+        "SELECT status, COUNT(*) AS n FROM cf_processing_queue WHERE plugin_name = ? GROUP BY status",
+        &[DbValue::from(BENCH_PLUGIN)],
+    )?;
+    let mut counts = (0, 0, 0);
+    for row in rows {
+        let status: String = row.get_by_name("status")?;
+        let n = row.get_by_name::<i64>("n")?.max(0) as u64;
+        match status.parse::<ProcessingStatus>() {
+            Ok(ProcessingStatus::Completed) => counts.0 += n,
+            Ok(ProcessingStatus::Failed) => counts.1 += n,
+            _ => counts.2 += n,
+        }
+    }
+    Ok(counts)
+}
+
+fn wait_until(deadline: Instant, mut done: impl FnMut() -> bool) -> bool {
+    loop {
+        if done() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+fn pipeline_report(jobs: u64, counts: (u64, u64, u64), stats: &[WorkerStats]) -> PipelineReport {
+    let started = stats.iter().filter_map(|s| s.started).min();
+    let finished = stats.iter().filter_map(|s| s.finished).max();
+    let elapsed = match (started, finished) {
+        (Some(start), Some(end)) => end.saturating_duration_since(start),
+        _ => Duration::ZERO,
+    };
+    let rows = stats.iter().map(|s| s.rows).sum();
+    let bytes = stats.iter().map(|s| s.bytes).sum();
+    let collect = |pick: fn(&WorkerStats) -> &Vec<Duration>| -> Vec<Duration> {
+        stats.iter().flat_map(|s| pick(s).iter().copied()).collect()
+    };
+    PipelineReport {
+        jobs,
+        completed: counts.0,
+        failed: counts.1,
+        rows,
+        bytes,
+        duration_ms: elapsed.as_secs_f64() * 1000.0,
+        rows_per_sec: per_sec(rows as f64, elapsed),
+        mb_per_sec: per_sec(megabytes(bytes), elapsed),
+        dispatch_latency: LatencySummary::from_samples(&collect(|s| &s.dispatch_latency)),
+        parse: LatencySummary::from_samples(&collect(|s| &s.parse)),
+        sink_write: LatencySummary::from_samples(&collect(|s| &s.sink_write)),
+    }
+}
@@ -0,0 +1,230 @@
+//! Machine-readable benchmark report and release-to-release comparison.
+
+use crate::corpus::Corpus;
+use crate::pipeline::BenchConfig;
+use crate::stats::LatencySummary;
+use serde::{Deserialize, Serialize};
+
+/// Bumped when a field changes meaning; [`compare`] refuses mixed versions.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub schema_version: u32,
+    /// Version of the crates under test
+    pub casparian_version: String,
+    /// RFC 3339 start time
+    pub started_at: String,
+    pub config: BenchConfig,
+    pub corpus: Corpus,
+    pub scan: ScanReport,
+    pub pipeline: PipelineReport,
+}
+
+/// Scout scan of the corpus into the state store.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanReport {
+    pub files: u64,
+    pub bytes: u64,
+    pub duration_ms: f64,
+    pub files_per_sec: f64,
+    pub mb_per_sec: f64,
+}
+
+/// Dispatch of every scanned file through the Sentinel to the bench workers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineReport {
+    pub jobs: u64,
+    /// Jobs the Sentinel recorded as completed / failed
+    pub completed: u64,
+    pub failed: u64,
+    pub rows: u64,
+    pub bytes: u64,
+    /// First IDENTIFY to last CONCLUDE
+    pub duration_ms: f64,
+    pub rows_per_sec: f64,
+    pub mb_per_sec: f64,
+    /// Worker idle (IDENTIFY or CONCLUDE sent) to its next DISPATCH
+    pub dispatch_latency: LatencySummary,
+    /// Per file: read and decode into Arrow
+    pub parse: LatencySummary,
+    /// Per file: write to the sink and commit
+    pub sink_write: LatencySummary,
+}
+
+/// A metric that got worse than the allowed tolerance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Regression {
+    pub metric: String,
+    pub baseline: f64,
+    pub current: f64,
+    /// Positive = worse
+    pub change_pct: f64,
+}
+
+/// Metrics of `current` more than `max_regression_pct` percent worse than
+/// `baseline`. Throughputs regress when they drop, latencies when they grow.
+pub fn compare(
+    baseline: &BenchReport,
+    current: &BenchReport,
+    max_regression_pct: f64,
+) -> anyhow::Result<Vec<Regression>> {
+    if baseline.schema_version != current.schema_version {
+        anyhow::bail!(
+            "Baseline report has schema version {}, this build writes {}",
+            baseline.schema_version,
+            current.schema_version
+        );
+    }
+    if baseline.config.corpus != current.config.corpus {
+        anyhow::bail!("Baseline report was run on a different corpus spec");
+    }
+
+    let higher_is_better = [
+        (
+            "scan.files_per_sec",
+            baseline.scan.files_per_sec,
+            current.scan.files_per_sec,
+        ),
+        (
+            "scan.mb_per_sec",
+            baseline.scan.mb_per_sec,
+            current.scan.mb_per_sec,
+        ),
+        (
+            "pipeline.rows_per_sec",
+            baseline.pipeline.rows_per_sec,
+            current.pipeline.rows_per_sec,
+        ),
+        (
+            "pipeline.mb_per_sec",
+            baseline.pipeline.mb_per_sec,
+            current.pipeline.mb_per_sec,
+        ),
+    ];
+    let (base, cur) = (&baseline.pipeline, &current.pipeline);
+    let lower_is_better = [
+        (
+            "pipeline.dispatch_latency.p50_ms",
+            base.dispatch_latency.p50_ms,
+            cur.dispatch_latency.p50_ms,
+        ),
+        (
+            "pipeline.dispatch_latency.p99_ms",
+            base.dispatch_latency.p99_ms,
+            cur.dispatch_latency.p99_ms,
+        ),
+        (
+            "pipeline.sink_write.p50_ms",
+            base.sink_write.p50_ms,
+            cur.sink_write.p50_ms,
+        ),
+        (
+            "pipeline.sink_write.p99_ms",
+            base.sink_write.p99_ms,
+            cur.sink_write.p99_ms,
+        ),
+    ];
+
+    let mut regressions = Vec::new();
+    for (metric, before, after) in higher_is_better {
+        if before > 0.0 {
+            push_if_worse(
+                &mut regressions,
+                metric,
+                before,
+                after,
+                (before - after) / before,
+            );
+        }
+    }
+    for (metric, before, after) in lower_is_better {
+        if before > 0.0 {
+            push_if_worse(
+                &mut regressions,
+                metric,
+                before,
+                after,
+                (after - before) / before,
+            );
+        }
+    }
+    regressions.retain(|r| r.change_pct > max_regression_pct);
+    Ok(regressions)
+}
+
+fn push_if_worse(out: &mut Vec<Regression>, metric: &str, baseline: f64, current: f64, worse: f64) {
+    if worse > 0.0 {
+        out.push(Regression {
+            metric: metric.to_string(),
+            baseline,
+            current,
+            change_pct: worse * 100.0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corpus::{CorpusFormat, CorpusSpec};
+
+    fn report(rows_per_sec: f64, dispatch_p99_ms: f64) -> BenchReport {
+        let spec = CorpusSpec {
+            files: 10,
+            file_bytes: 1024,
+            format: CorpusFormat::Csv,
+            seed: 1,
+        };
+        BenchReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            casparian_version: "0.1.0".to_string(),
+            started_at: "2026-01-01T00:00:00Z".to_string(),
+            config: BenchConfig::new(spec),
+            corpus: Corpus {
+                root: "/tmp/corpus".into(),
+                files: 10,
+                bytes: 10_240,
+                rows: 400,
+            },
+            scan: ScanReport::default(),
+            pipeline: PipelineReport {
+                rows_per_sec,
+                dispatch_latency: LatencySummary {
+                    p99_ms: dispatch_p99_ms,
+                    ..LatencySummary::default()
+                },
+                ..PipelineReport::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_compare_flags_regressions_beyond_tolerance() {
+        let baseline = report(1000.0, 10.0);
+
+        let within = report(950.0, 10.5);
+        assert!(compare(&baseline, &within, 10.0).unwrap().is_empty());
+
+        let worse = report(800.0, 20.0);
+        let regressions = compare(&baseline, &worse, 10.0).unwrap();
+        let metrics: Vec<&str> = regressions.iter().map(|r| r.metric.as_str()).collect();
+        assert_eq!(
+            metrics,
+            ["pipeline.rows_per_sec", "pipeline.dispatch_latency.p99_ms"]
+        );
+        assert!((regressions[0].change_pct - 20.0).abs() < 1e-9);
+        assert!((regressions[1].change_pct - 100.0).abs() < 1e-9);
+
+        let better = report(2000.0, 1.0);
+        assert!(compare(&baseline, &better, 0.0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compare_rejects_other_corpus() {
+        let baseline = report(1000.0, 10.0);
+        let mut other = report(1000.0, 10.0);
+        other.config.corpus.files = 20;
+        assert!(compare(&baseline, &other, 10.0).is_err());
+    }
+}
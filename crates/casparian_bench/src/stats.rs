@@ -0,0 +1,88 @@
+//! Latency and throughput summaries.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Distribution of a set of timings, in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    /// Summarize `samples`; all fields are 0 without samples.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        sorted.sort_by(f64::total_cmp);
+        let mean_ms = sorted.iter().sum::<f64>() / sorted.len() as f64;
+        Self {
+            count: sorted.len(),
+            min_ms: sorted[0],
+            mean_ms,
+            p50_ms: percentile(&sorted, 50.0),
+            p90_ms: percentile(&sorted, 90.0),
+            p99_ms: percentile(&sorted, 99.0),
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Nearest-rank percentile of ascending `sorted` values.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    let rank = (pct / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// `amount` per second over `elapsed` (0 for an empty interval).
+pub fn per_sec(amount: f64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        amount / secs
+    } else {
+        0.0
+    }
+}
+
+/// Bytes as megabytes (10^6), the unit of every `mb_per_sec` field.
+pub fn megabytes(bytes: u64) -> f64 {
+    bytes as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_summary_percentiles() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let summary = LatencySummary::from_samples(&samples);
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.min_ms, 1.0);
+        assert_eq!(summary.p50_ms, 50.0);
+        assert_eq!(summary.p90_ms, 90.0);
+        assert_eq!(summary.p99_ms, 99.0);
+        assert_eq!(summary.max_ms, 100.0);
+        assert!((summary.mean_ms - 50.5).abs() < 1e-9);
+
+        let single = LatencySummary::from_samples(&[Duration::from_millis(7)]);
+        assert_eq!(single.p50_ms, 7.0);
+        assert_eq!(single.p99_ms, 7.0);
+        assert_eq!(LatencySummary::from_samples(&[]), LatencySummary::default());
+    }
+
+    #[test]
+    fn test_per_sec() {
+        assert_eq!(per_sec(500.0, Duration::from_millis(250)), 2000.0);
+        assert_eq!(per_sec(500.0, Duration::ZERO), 0.0);
+        assert_eq!(megabytes(2_500_000), 2.5);
+    }
+}
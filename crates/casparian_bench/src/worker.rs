@@ -0,0 +1,299 @@
+//! In-process bench worker.
+//!
+//! Speaks the worker protocol to the Sentinel like `casparian worker` does
+//! (IDENTIFY, DISPATCH_ACK, HEARTBEAT, CONCLUDE, batched dispatch), but
+//! instead of running the plugin it decodes the corpus file into Arrow with
+//! the fixed corpus schema and writes it to the bench sink. This keeps
+//! plugin runtimes out of the measurement and the bench independent of a
+//! Python environment.
+
+use crate::corpus::{self, CorpusFormat};
+use anyhow::{Context, Result};
+use arrow::record_batch::RecordBatch;
+use casparian_protocol::types::{
+    ArtifactV1, BatchFileReceipt, DispatchAckPayload, DispatchCommand, HeartbeatPayload,
+    HeartbeatStatus, IdentifyPayload, JobReceipt, JobStatus, SinkMode,
+};
+use casparian_protocol::{metrics, JobId, Message, OpCode, ProtocolFeatures, ProtocolVersionRange};
+use casparian_sinks::{OutputBatch, OutputPlan};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Output name of every bench job.
+pub const BENCH_OUTPUT: &str = "bench";
+const RECV_TIMEOUT_MS: i32 = 50;
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Timings collected by one worker.
+#[derive(Debug, Default)]
+pub(crate) struct WorkerStats {
+    pub dispatch_latency: Vec<Duration>,
+    pub parse: Vec<Duration>,
+    pub sink_write: Vec<Duration>,
+    pub rows: u64,
+    pub bytes: u64,
+    /// IDENTIFY sent
+    pub started: Option<Instant>,
+    /// Last CONCLUDE sent
+    pub finished: Option<Instant>,
+}
+
+pub(crate) struct BenchWorker {
+    socket: zmq::Socket,
+    worker_id: String,
+    format: CorpusFormat,
+    sink_uri: String,
+    /// Files concluded by every worker of the run
+    files_done: Arc<AtomicU64>,
+    stats: WorkerStats,
+}
+
+impl BenchWorker {
+    pub fn connect(
+        context: &zmq::Context,
+        addr: &str,
+        worker_id: String,
+        format: CorpusFormat,
+        sink_uri: String,
+        files_done: Arc<AtomicU64>,
+    ) -> Result<Self> {
+        let socket = context.socket(zmq::DEALER)?;
+        socket.set_identity(worker_id.as_bytes())?;
+        socket.set_rcvtimeo(RECV_TIMEOUT_MS)?;
+        socket.set_linger(0)?;
+        socket
+            .connect(addr)
+            .with_context(|| format!("Failed to connect bench worker to {}", addr))?;
+        Ok(Self {
+            socket,
+            worker_id,
+            format,
+            sink_uri,
+            files_done,
+            stats: WorkerStats::default(),
+        })
+    }
+
+    /// Process dispatches until `stop` is set.
+    pub fn run(mut self, stop: &AtomicBool) -> Result<WorkerStats> {
+        let identify = IdentifyPayload {
+            capabilities: vec!["*".to_string()],
+            worker_id: Some(self.worker_id.clone()),
+            protocol_versions: ProtocolVersionRange::SUPPORTED,
+            protocol_features: ProtocolFeatures::DISPATCH_ACK
+                .union(ProtocolFeatures::BATCH_DISPATCH),
+        };
+        self.send(OpCode::Identify, JobId::new(0), &identify)?;
+        let mut idle_since = Instant::now();
+        let mut last_heartbeat = idle_since;
+        self.stats.started = Some(idle_since);
+
+        while !stop.load(Ordering::SeqCst) {
+            if last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+                self.send_heartbeat()?;
+                last_heartbeat = Instant::now();
+            }
+            let parts = match self.socket.recv_multipart(0) {
+                Ok(parts) => parts,
+                Err(zmq::Error::EAGAIN) => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let (header, payload) = match parts.len() {
+                2 => (parts[0].clone(), parts[1].clone()),
+                3 if parts[0].is_empty() => (parts[1].clone(), parts[2].clone()),
+                count => {
+                    warn!("Expected 2 frames [header, payload], got {}", count);
+                    continue;
+                }
+            };
+            let msg = Message::unpack(&[header, payload])?;
+            match msg.header.opcode {
+                OpCode::Dispatch => {
+                    self.stats.dispatch_latency.push(idle_since.elapsed());
+                    let cmd: DispatchCommand = serde_json::from_slice(&msg.payload)?;
+                    self.handle_dispatch(msg.header.job_id, cmd)?;
+                    idle_since = Instant::now();
+                    last_heartbeat = idle_since;
+                    self.stats.finished = Some(idle_since);
+                }
+                OpCode::Heartbeat => {
+                    self.send_heartbeat()?;
+                    last_heartbeat = Instant::now();
+                }
+                other => debug!("Bench worker ignoring {:?}", other),
+            }
+        }
+        Ok(self.stats)
+    }
+
+    fn handle_dispatch(&mut self, job_id: JobId, cmd: DispatchCommand) -> Result<()> {
+        let leases = cmd
+            .lease_token
+            .clone()
+            .map(|token| (job_id, token))
+            .into_iter()
+            .chain(
+                cmd.batch
+                    .iter()
+                    .map(|file| (file.job_id, file.lease_token.clone())),
+            );
+        for (ack_job_id, lease_token) in leases {
+            let ack = DispatchAckPayload {
+                lease_token,
+                worker_id: Some(self.worker_id.clone()),
+            };
+            self.send(OpCode::DispatchAck, ack_job_id, &ack)?;
+        }
+
+        let mut receipt = self.process_file(job_id, &cmd.file_path, cmd.lease_token.clone());
+        for file in &cmd.batch {
+            let file_receipt =
+                self.process_file(file.job_id, &file.file_path, Some(file.lease_token.clone()));
+            receipt.batch_results.push(BatchFileReceipt {
+                job_id: file.job_id,
+                receipt: file_receipt,
+            });
+        }
+        self.send(OpCode::Conclude, job_id, &receipt)
+    }
+
+    fn process_file(
+        &mut self,
+        job_id: JobId,
+        path: &str,
+        lease_token: Option<String>,
+    ) -> JobReceipt {
+        let result = self.parse_and_write(job_id, path);
+        self.files_done.fetch_add(1, Ordering::SeqCst);
+        let (status, metrics, artifacts, source_hash, error_message) = match result {
+            Ok((rows, artifacts, source_hash)) => (
+                JobStatus::Success,
+                HashMap::from([(metrics::ROWS.to_string(), rows as i64)]),
+                artifacts,
+                Some(source_hash),
+                None,
+            ),
+            Err(err) => {
+                warn!("Bench job {} failed: {:#}", job_id, err);
+                (
+                    JobStatus::Failed,
+                    HashMap::new(),
+                    Vec::new(),
+                    None,
+                    Some(format!("{:#}", err)),
+                )
+            }
+        };
+        JobReceipt {
+            status,
+            metrics,
+            artifacts,
+            error_message,
+            diagnostics: None,
+            source_hash,
+            source_file: None,
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: None,
+            lease_token,
+            batch_results: Vec::new(),
+        }
+    }
+
+    fn parse_and_write(
+        &mut self,
+        job_id: JobId,
+        path: &str,
+    ) -> Result<(u64, Vec<ArtifactV1>, String)> {
+        let parse_start = Instant::now();
+        let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path))?;
+        let batches = decode(self.format, &data)?;
+        self.stats.parse.push(parse_start.elapsed());
+        let source_hash = blake3::hash(&data).to_hex().to_string();
+        let rows: u64 = batches.iter().map(|batch| batch.num_rows() as u64).sum();
+
+        let write_start = Instant::now();
+        let plan = OutputPlan::new(
+            BENCH_OUTPUT,
+            None,
+            batches
+                .into_iter()
+                .map(OutputBatch::from_record_batch)
+                .collect(),
+            SinkMode::Append,
+        );
+        let written =
+            casparian_sinks::write_output_plan(&self.sink_uri, &[plan], &job_id.to_string(), None)?;
+        self.stats.sink_write.push(write_start.elapsed());
+
+        self.stats.rows += rows;
+        self.stats.bytes += data.len() as u64;
+        let artifacts = written
+            .into_iter()
+            .map(|output| ArtifactV1::Output {
+                output_name: output.name,
+                sink_uri: output.uri,
+                table: None,
+                rows: Some(output.rows),
+                schema_hash: None,
+                content_hash: None,
+            })
+            .collect();
+        Ok((rows, artifacts, source_hash))
+    }
+
+    fn send_heartbeat(&self) -> Result<()> {
+        let heartbeat = HeartbeatPayload {
+            status: HeartbeatStatus::Idle,
+            active_job_count: 0,
+            active_job_ids: Vec::new(),
+            load: None,
+        };
+        self.send(OpCode::Heartbeat, JobId::new(0), &heartbeat)
+    }
+
+    fn send<T: serde::Serialize>(&self, opcode: OpCode, job_id: JobId, payload: &T) -> Result<()> {
+        let msg = Message::new(opcode, job_id, serde_json::to_vec(payload)?)?;
+        let (header, body) = msg.pack()?;
+        self.socket
+            .send_multipart([header.as_ref(), body.as_slice()], 0)?;
+        Ok(())
+    }
+}
+
+/// Decode a corpus file into Arrow batches.
+fn decode(format: CorpusFormat, data: &[u8]) -> Result<Vec<RecordBatch>> {
+    let schema = corpus::schema();
+    let batches = match format {
+        CorpusFormat::Csv => arrow::csv::ReaderBuilder::new(schema)
+            .with_header(true)
+            .build(Cursor::new(data))?
+            .collect::<Result<Vec<_>, _>>()?,
+        CorpusFormat::Jsonl => arrow::json::ReaderBuilder::new(schema)
+            .build(Cursor::new(data))?
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_corpus_rows() {
+        let csv =
+            b"id,name,value,ts\n1,abcdefgh,1.500,1704067200000\n2,ijklmnop,2.250,1704067200001\n";
+        let batches = decode(CorpusFormat::Csv, csv).unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+
+        let jsonl = b"{\"id\":1,\"name\":\"abcdefgh\",\"value\":1.5,\"ts\":1704067200000}\n";
+        let batches = decode(CorpusFormat::Jsonl, jsonl).unwrap();
+        assert_eq!(batches[0].num_rows(), 1);
+        assert_eq!(batches[0].schema(), corpus::schema());
+    }
+}
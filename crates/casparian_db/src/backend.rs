@@ -6,6 +6,7 @@
 
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{debug_span, info};
@@ -373,6 +374,31 @@ impl FromDbValue for Vec<u8> {
     }
 }
 
+/// Cancels the statement running on a connection.
+///
+/// Unlike [`DbConnection`], the handle is `Send + Sync`, so a watchdog thread
+/// can use it to enforce a query timeout.
+#[derive(Clone)]
+pub struct QueryInterruptHandle {
+    inner: InterruptInner,
+}
+
+#[derive(Clone)]
+enum InterruptInner {
+    DuckDb(Arc<duckdb::InterruptHandle>),
+    Sqlite(Arc<rusqlite::InterruptHandle>),
+}
+
+impl QueryInterruptHandle {
+    /// Interrupt the running statement; it fails with an "interrupted" error.
+    pub fn interrupt(&self) {
+        match &self.inner {
+            InterruptInner::DuckDb(handle) => handle.interrupt(),
+            InterruptInner::Sqlite(handle) => handle.interrupt(),
+        }
+    }
+}
+
 #[derive(Clone)]
 enum Inner {
     DuckDb {
@@ -575,6 +601,15 @@ impl DbConnection {
         self.access_mode == AccessMode::ReadWrite
     }
 
+    /// Handle for interrupting statements on this connection from another thread.
+    pub fn interrupt_handle(&self) -> QueryInterruptHandle {
+        let inner = match &self.inner {
+            Inner::DuckDb { conn, .. } => InterruptInner::DuckDb(conn.interrupt_handle()),
+            Inner::Sqlite { conn } => InterruptInner::Sqlite(Arc::new(conn.get_interrupt_handle())),
+        };
+        QueryInterruptHandle { inner }
+    }
+
     /// Get the backend name.
     pub fn backend_name(&self) -> &'static str {
         match self.inner {
@@ -1751,4 +1786,13 @@ mod tests {
         assert!(conn.column_exists("t", "id").unwrap());
         assert!(!conn.column_exists("t", "nope").unwrap());
     }
+
+    #[test]
+    fn interrupt_handle_on_idle_sqlite_connection_is_noop() {
+        let conn = DbConnection::open_sqlite(Path::new(":memory:")).unwrap();
+        conn.interrupt_handle().interrupt();
+
+        let one: i64 = conn.query_scalar("SELECT 1", &[]).unwrap();
+        assert_eq!(one, 1);
+    }
 }
@@ -25,7 +25,7 @@ pub mod sql_guard;
 
 pub use backend::{
     AccessMode, BackendError, DbConnection, DbRow as UnifiedDbRow, DbTimestamp, DbTimestampError,
-    DbTransaction, DbValue, FromDbValue,
+    DbTransaction, DbValue, FromDbValue, QueryInterruptHandle,
 };
 pub use dev::dev_allow_destructive_reset;
 pub use license::{License, LicenseError, LicenseTier};
@@ -0,0 +1,425 @@
+//! Job output datasets addressable over Flight.
+//!
+//! A dataset is one `output` artifact recorded in `cf_job_artifacts`. Only
+//! local Parquet outputs (`file://...parquet`) can be served; Hive-partitioned
+//! outputs (`file://<dir>/**/<file>.parquet`) are read file by file with the
+//! partition values added back as Utf8 columns.
+
+use anyhow::{bail, Context, Result};
+use arrow::array::{ArrayRef, RecordBatch, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use casparian_state_store::{JobArtifactRecord, StateStore};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Directory name Hive/Spark use for NULL partition values.
+const HIVE_NULL_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Rows per RecordBatch read from Parquet.
+const READ_BATCH_SIZE: usize = 8192;
+
+/// Names a dataset: `<job_id>/<output_name>`, or `<output_name>` for the most
+/// recent job that produced that output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetRef {
+    pub job_id: Option<i64>,
+    pub output_name: String,
+}
+
+impl DatasetRef {
+    /// Parse a ticket or command string.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        let path: Vec<String> = raw.split('/').map(str::to_string).collect();
+        Self::from_path(&path)
+    }
+
+    /// Parse a Flight descriptor path: `[job_id, output_name]` or `[output_name]`.
+    pub fn from_path(path: &[String]) -> Result<Self> {
+        match path {
+            [output_name] if !output_name.is_empty() => Ok(Self {
+                job_id: None,
+                output_name: output_name.clone(),
+            }),
+            [job_id, output_name] if !output_name.is_empty() => {
+                let job_id = job_id
+                    .parse::<i64>()
+                    .with_context(|| format!("Invalid job id '{}'", job_id))?;
+                Ok(Self {
+                    job_id: Some(job_id),
+                    output_name: output_name.clone(),
+                })
+            }
+            _ => bail!(
+                "Expected '<job_id>/<output_name>' or '<output_name>', got '{}'",
+                path.join("/")
+            ),
+        }
+    }
+}
+
+/// One Parquet file of a dataset, with the Hive partition values of its directory.
+#[derive(Debug, Clone)]
+struct ParquetPart {
+    path: PathBuf,
+    partitions: Vec<(String, Option<String>)>,
+}
+
+/// A resolved job output.
+#[derive(Debug, Clone)]
+pub struct OutputDataset {
+    pub job_id: i64,
+    pub output_name: String,
+    pub rows: Option<i64>,
+    parts: Vec<ParquetPart>,
+}
+
+impl OutputDataset {
+    /// Look up the output artifact a `DatasetRef` names.
+    pub fn resolve(state_store: &StateStore, dataset: &DatasetRef) -> Result<Self> {
+        let record = match dataset.job_id {
+            Some(job_id) => state_store
+                .artifacts()
+                .list_job_artifacts(job_id)?
+                .into_iter()
+                .find(|record| record.kind == "output" && record.name == dataset.output_name),
+            None => state_store
+                .artifacts()
+                .list_output_artifacts(Some(&dataset.output_name), 1)?
+                .into_iter()
+                .next(),
+        };
+        let Some(record) = record else {
+            match dataset.job_id {
+                Some(job_id) => bail!("Job {} has no output '{}'", job_id, dataset.output_name),
+                None => bail!("No completed job has output '{}'", dataset.output_name),
+            }
+        };
+        Self::from_artifact(&record)
+    }
+
+    /// Most recent servable outputs, optionally for one output name.
+    /// Artifacts that are not local Parquet files are skipped.
+    pub fn list(
+        state_store: &StateStore,
+        output_name: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Self>> {
+        let records = state_store
+            .artifacts()
+            .list_output_artifacts(output_name, limit)?;
+        Ok(records
+            .iter()
+            .filter_map(|record| match Self::from_artifact(record) {
+                Ok(dataset) => Some(dataset),
+                Err(err) => {
+                    tracing::debug!(
+                        "Skipping output '{}' of job {}: {}",
+                        record.name,
+                        record.job_id,
+                        err
+                    );
+                    None
+                }
+            })
+            .collect())
+    }
+
+    fn from_artifact(record: &JobArtifactRecord) -> Result<Self> {
+        let Some(path) = record.uri.strip_prefix("file://") else {
+            bail!(
+                "Output '{}' of job {} is not a local file: {}",
+                record.name,
+                record.job_id,
+                record.uri
+            );
+        };
+        let path = Path::new(path);
+        if path.extension().and_then(|ext| ext.to_str()) != Some("parquet") {
+            bail!(
+                "Output '{}' of job {} is not Parquet; only Parquet outputs can be served",
+                record.name,
+                record.job_id
+            );
+        }
+        Ok(Self {
+            job_id: record.job_id,
+            output_name: record.name.clone(),
+            rows: record.rows,
+            parts: find_parts(path)?,
+        })
+    }
+
+    /// Ticket that `do_get` accepts for this dataset.
+    pub fn ticket(&self) -> String {
+        format!("{}/{}", self.job_id, self.output_name)
+    }
+
+    /// Schema of the served batches: the Parquet schema plus partition columns.
+    pub fn schema(&self) -> Result<SchemaRef> {
+        let Some(first) = self.parts.first() else {
+            bail!(
+                "Output '{}' of job {} has no data files",
+                self.output_name,
+                self.job_id
+            );
+        };
+        let file = open(&first.path)?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+            .with_context(|| format!("Failed to read {}", first.path.display()))?;
+        let mut fields: Vec<Field> = builder
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone())
+            .collect();
+        for (key, _) in &first.partitions {
+            fields.push(Field::new(key, DataType::Utf8, true));
+        }
+        Ok(Arc::new(Schema::new(fields)))
+    }
+
+    /// Read every batch in file order. Stops at the first error from `on_batch`.
+    pub fn read(&self, mut on_batch: impl FnMut(RecordBatch) -> Result<()>) -> Result<()> {
+        let schema = self.schema()?;
+        for part in &self.parts {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(open(&part.path)?)
+                .with_context(|| format!("Failed to read {}", part.path.display()))?
+                .with_batch_size(READ_BATCH_SIZE)
+                .build()?;
+            for batch in reader {
+                let batch = batch?;
+                let mut columns = batch.columns().to_vec();
+                for field in schema.fields().iter().skip(batch.num_columns()) {
+                    let value = part
+                        .partitions
+                        .iter()
+                        .find(|(key, _)| key == field.name())
+                        .and_then(|(_, value)| value.as_deref());
+                    let column: ArrayRef =
+                        Arc::new(StringArray::from(vec![value; batch.num_rows()]));
+                    columns.push(column);
+                }
+                on_batch(
+                    RecordBatch::try_new(schema.clone(), columns).with_context(|| {
+                        format!("{} does not match the output schema", part.path.display())
+                    })?,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn open(path: &Path) -> Result<File> {
+    File::open(path).with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Data files for an artifact path. `<dir>/**/<name>` matches `<name>` in any
+/// partition directory below `<dir>`.
+fn find_parts(path: &Path) -> Result<Vec<ParquetPart>> {
+    let components: Vec<Component> = path.components().collect();
+    let Some(glob) = components
+        .iter()
+        .position(|component| component.as_os_str() == "**")
+    else {
+        if !path.is_file() {
+            bail!("Output file not found: {}", path.display());
+        }
+        return Ok(vec![ParquetPart {
+            path: path.to_path_buf(),
+            partitions: Vec::new(),
+        }]);
+    };
+    let base: PathBuf = components[..glob].iter().collect();
+    let file_name = path
+        .file_name()
+        .context("Partitioned output path has no file name")?;
+
+    let mut parts = Vec::new();
+    let mut pending = vec![base.clone()];
+    while let Some(dir) = pending.pop() {
+        let entries =
+            std::fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for entry in entries {
+            let entry_path = entry?.path();
+            if entry_path.is_dir() {
+                pending.push(entry_path);
+            } else if entry_path.file_name() == Some(file_name) {
+                let relative = entry_path
+                    .parent()
+                    .and_then(|parent| parent.strip_prefix(&base).ok())
+                    .unwrap_or(Path::new(""));
+                parts.push(ParquetPart {
+                    partitions: parse_partitions(relative),
+                    path: entry_path,
+                });
+            }
+        }
+    }
+    parts.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(parts)
+}
+
+/// `region=eu/day=2026-01-01` -> `[("region", "eu"), ("day", "2026-01-01")]`
+fn parse_partitions(relative_dir: &Path) -> Vec<(String, Option<String>)> {
+    relative_dir
+        .components()
+        .filter_map(|component| {
+            let segment = component.as_os_str().to_str()?;
+            let (key, value) = segment.split_once('=')?;
+            let value = (value != HIVE_NULL_PARTITION).then(|| unescape_partition_value(value));
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Reverse the sink's percent-encoding of partition directory values.
+fn unescape_partition_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+    use parquet::arrow::ArrowWriter;
+
+    fn write_parquet(path: &Path, values: &[i64]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "amount",
+            DataType::Int64,
+            false,
+        )]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(values.to_vec()))],
+        )
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    fn artifact(uri: String) -> JobArtifactRecord {
+        JobArtifactRecord {
+            job_id: 7,
+            kind: "output".to_string(),
+            name: "orders".to_string(),
+            uri,
+            table_name: None,
+            rows: Some(3),
+            content_hash: None,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_dataset_ref_parse() {
+        assert_eq!(
+            DatasetRef::parse("42/orders").unwrap(),
+            DatasetRef {
+                job_id: Some(42),
+                output_name: "orders".to_string()
+            }
+        );
+        assert_eq!(DatasetRef::parse("orders").unwrap().job_id, None);
+        assert!(DatasetRef::parse("x/orders").is_err());
+        assert!(DatasetRef::parse("1/2/orders").is_err());
+        assert!(DatasetRef::parse("").is_err());
+    }
+
+    #[test]
+    fn test_read_single_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders_abc.parquet");
+        write_parquet(&path, &[1, 2, 3]);
+
+        let dataset =
+            OutputDataset::from_artifact(&artifact(format!("file://{}", path.display()))).unwrap();
+        assert_eq!(dataset.ticket(), "7/orders");
+        let mut rows = 0;
+        dataset
+            .read(|batch| {
+                rows += batch.num_rows();
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(rows, 3);
+    }
+
+    #[test]
+    fn test_read_partitioned_adds_partition_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        write_parquet(&dir.path().join("region=eu/orders_abc.parquet"), &[1, 2]);
+        write_parquet(
+            &dir.path()
+                .join(format!("region={}/orders_abc.parquet", HIVE_NULL_PARTITION)),
+            &[3],
+        );
+        write_parquet(
+            &dir.path().join("region=us%2Fwest/orders_abc.parquet"),
+            &[4],
+        );
+
+        let uri = format!("file://{}/**/orders_abc.parquet", dir.path().display());
+        let dataset = OutputDataset::from_artifact(&artifact(uri)).unwrap();
+        let schema = dataset.schema().unwrap();
+        assert_eq!(schema.field(1).name(), "region");
+
+        let mut regions = Vec::new();
+        dataset
+            .read(|batch| {
+                let column = batch
+                    .column(1)
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .unwrap();
+                regions.extend(column.iter().map(|value| value.map(str::to_string)));
+                Ok(())
+            })
+            .unwrap();
+        regions.sort();
+        assert_eq!(
+            regions,
+            [
+                None,
+                Some("eu".to_string()),
+                Some("eu".to_string()),
+                Some("us/west".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_parquet_output_is_rejected() {
+        let err = OutputDataset::from_artifact(&artifact("file:///tmp/orders.csv".to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("only Parquet"), "{}", err);
+        let err = OutputDataset::from_artifact(&artifact("duckdb:///tmp/out.db".to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("not a local file"), "{}", err);
+    }
+}
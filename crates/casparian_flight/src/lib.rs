@@ -0,0 +1,48 @@
+//! Arrow Flight server for Casparian Flow job outputs.
+//!
+//! Serves completed output datasets straight from the data plane so BI tools
+//! and Python clients can fetch RecordBatches over gRPC without knowing where
+//! or how outputs are laid out on disk:
+//!
+//! ```python
+//! import pyarrow.flight as flight
+//! client = flight.connect("grpc://localhost:8815")
+//! table = client.do_get(flight.Ticket(b"42/orders")).read_all()   # job 42
+//! table = client.do_get(flight.Ticket(b"orders")).read_all()      # latest job
+//! ```
+//!
+//! Datasets are resolved through `cf_job_artifacts`; see [`dataset`] for
+//! which outputs can be served.
+
+pub mod dataset;
+mod service;
+
+pub use dataset::{DatasetRef, OutputDataset};
+pub use service::OutputFlightService;
+
+/// Re-exported so callers can open the store the service reads from.
+pub use casparian_state_store::StateStore;
+
+use anyhow::{Context, Result};
+use arrow_flight::flight_service_server::FlightServiceServer;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Default Flight listen address (8815 is the conventional Flight port).
+pub const DEFAULT_FLIGHT_ADDR: &str = "127.0.0.1:8815";
+
+/// Serve outputs until `shutdown` resolves.
+pub async fn serve(
+    addr: SocketAddr,
+    state_store: Arc<StateStore>,
+    shutdown: impl std::future::Future<Output = ()> + Send,
+) -> Result<()> {
+    tracing::info!("Arrow Flight server listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(FlightServiceServer::new(OutputFlightService::new(
+            state_store,
+        )))
+        .serve_with_shutdown(addr, shutdown)
+        .await
+        .with_context(|| format!("Arrow Flight server on {} failed", addr))
+}
@@ -0,0 +1,245 @@
+//! `FlightService` implementation: read-only access to job outputs.
+//!
+//! - `ListFlights`: recent outputs; `Criteria.expression` filters by output name
+//! - `GetFlightInfo` / `GetSchema`: descriptor path `[job_id, output]` or `[output]`
+//!   (or a CMD descriptor holding `job_id/output`)
+//! - `DoGet`: the ticket from `FlightInfo`, streamed as RecordBatches
+//!
+//! Writes, actions and exchanges are rejected.
+
+use crate::dataset::{DatasetRef, OutputDataset};
+use arrow::ipc::writer::IpcWriteOptions;
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_descriptor::DescriptorType;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaAsIpc, SchemaResult, Ticket,
+};
+use casparian_state_store::StateStore;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tonic::{Request, Response, Status, Streaming};
+
+/// Maximum outputs returned by `ListFlights`.
+const LIST_FLIGHTS_LIMIT: usize = 500;
+
+/// Batches buffered between the Parquet reader thread and the gRPC stream.
+const DO_GET_BUFFER_BATCHES: usize = 4;
+
+/// Serves completed job outputs recorded in the state store.
+#[derive(Clone)]
+pub struct OutputFlightService {
+    state_store: Arc<StateStore>,
+}
+
+impl OutputFlightService {
+    pub fn new(state_store: Arc<StateStore>) -> Self {
+        Self { state_store }
+    }
+
+    /// Run a state store / filesystem lookup off the async runtime.
+    async fn blocking<T: Send + 'static>(
+        &self,
+        op: impl FnOnce(&StateStore) -> anyhow::Result<T> + Send + 'static,
+    ) -> Result<T, Status> {
+        let state_store = self.state_store.clone();
+        tokio::task::spawn_blocking(move || op(&state_store))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .map_err(to_status)
+    }
+
+    async fn resolve(&self, dataset: DatasetRef) -> Result<OutputDataset, Status> {
+        self.blocking(move |state_store| OutputDataset::resolve(state_store, &dataset))
+            .await
+    }
+}
+
+fn to_status(err: anyhow::Error) -> Status {
+    let message = format!("{:#}", err);
+    if message.contains("has no output")
+        || message.contains("No completed job")
+        || message.contains("not found")
+    {
+        Status::not_found(message)
+    } else if message.contains("not Parquet") || message.contains("not a local file") {
+        Status::failed_precondition(message)
+    } else {
+        Status::internal(message)
+    }
+}
+
+fn descriptor_dataset(descriptor: &FlightDescriptor) -> Result<DatasetRef, Status> {
+    let parsed = if descriptor.r#type == DescriptorType::Cmd as i32 {
+        let cmd = std::str::from_utf8(&descriptor.cmd)
+            .map_err(|_| Status::invalid_argument("Descriptor command is not UTF-8"))?;
+        DatasetRef::parse(cmd)
+    } else {
+        DatasetRef::from_path(&descriptor.path)
+    };
+    parsed.map_err(|err| Status::invalid_argument(err.to_string()))
+}
+
+fn flight_info(dataset: &OutputDataset) -> anyhow::Result<FlightInfo> {
+    let schema = dataset.schema()?;
+    let descriptor = FlightDescriptor::new_path(vec![
+        dataset.job_id.to_string(),
+        dataset.output_name.clone(),
+    ]);
+    let endpoint = FlightEndpoint::new().with_ticket(Ticket::new(dataset.ticket()));
+    Ok(FlightInfo::new()
+        .try_with_schema(&schema)?
+        .with_descriptor(descriptor)
+        .with_endpoint(endpoint)
+        .with_total_records(dataset.rows.unwrap_or(-1)))
+}
+
+#[tonic::async_trait]
+impl FlightService for OutputFlightService {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("Handshake is not required"))
+    }
+
+    async fn list_flights(
+        &self,
+        request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        let expression = request.into_inner().expression;
+        let output_name = std::str::from_utf8(&expression)
+            .map_err(|_| Status::invalid_argument("Criteria expression is not UTF-8"))?
+            .trim()
+            .to_string();
+        let infos = self
+            .blocking(move |state_store| {
+                let filter = (!output_name.is_empty()).then_some(output_name.as_str());
+                let datasets = OutputDataset::list(state_store, filter, LIST_FLIGHTS_LIMIT)?;
+                Ok(datasets
+                    .iter()
+                    .filter_map(|dataset| flight_info(dataset).ok())
+                    .collect::<Vec<_>>())
+            })
+            .await?;
+        Ok(Response::new(
+            futures::stream::iter(infos.into_iter().map(Ok)).boxed(),
+        ))
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let dataset = descriptor_dataset(request.get_ref())?;
+        let info = self
+            .blocking(move |state_store| {
+                flight_info(&OutputDataset::resolve(state_store, &dataset)?)
+            })
+            .await?;
+        Ok(Response::new(info))
+    }
+
+    async fn poll_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<PollInfo>, Status> {
+        Err(Status::unimplemented(
+            "Use GetFlightInfo; outputs are already complete",
+        ))
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        let dataset = descriptor_dataset(request.get_ref())?;
+        let schema = self
+            .blocking(move |state_store| OutputDataset::resolve(state_store, &dataset)?.schema())
+            .await?;
+        let result: SchemaResult = SchemaAsIpc::new(&schema, &IpcWriteOptions::default())
+            .try_into()
+            .map_err(|err: arrow::error::ArrowError| Status::internal(err.to_string()))?;
+        Ok(Response::new(result))
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let ticket = std::str::from_utf8(&request.get_ref().ticket)
+            .map_err(|_| Status::invalid_argument("Ticket is not UTF-8"))?;
+        let dataset_ref =
+            DatasetRef::parse(ticket).map_err(|err| Status::invalid_argument(err.to_string()))?;
+        let dataset = self.resolve(dataset_ref).await?;
+        let schema = dataset.schema().map_err(to_status)?;
+        tracing::info!(
+            "Flight DoGet: job {} output '{}'",
+            dataset.job_id,
+            dataset.output_name
+        );
+
+        // Read on a blocking thread; a full channel applies backpressure and a
+        // dropped receiver (client went away) stops the reader.
+        let (tx, rx) = mpsc::channel(DO_GET_BUFFER_BATCHES);
+        tokio::task::spawn_blocking(move || {
+            let result = dataset.read(|batch| {
+                tx.blocking_send(Ok(batch))
+                    .map_err(|_| anyhow::anyhow!("Flight client disconnected"))
+            });
+            if let Err(err) = result {
+                let _ = tx.blocking_send(Err(FlightError::ExternalError(err.into())));
+            }
+        });
+        let batches = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|batch| (batch, rx))
+        });
+
+        let stream = FlightDataEncoderBuilder::new()
+            .with_schema(schema)
+            .build(batches)
+            .map_err(Status::from)
+            .boxed();
+        Ok(Response::new(stream))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("Outputs are read-only"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("No actions are supported"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Ok(Response::new(futures::stream::empty().boxed()))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("Outputs are read-only"))
+    }
+}
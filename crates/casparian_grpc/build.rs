@@ -0,0 +1,20 @@
+//! Generates the `Link` service stubs. Messages are hand-written prost types
+//! in `src/proto.rs`, so no `protoc` is needed.
+
+fn main() {
+    let exchange = tonic_build::manual::Method::builder()
+        .name("exchange")
+        .route_name("Exchange")
+        .input_type("crate::proto::Frame")
+        .output_type("crate::proto::Frame")
+        .codec_path("tonic::codec::ProstCodec")
+        .client_streaming()
+        .server_streaming()
+        .build();
+    let link = tonic_build::manual::Service::builder()
+        .name("Link")
+        .package("casparian.link")
+        .method(exchange)
+        .build();
+    tonic_build::manual::Builder::new().compile(&[link]);
+}
@@ -0,0 +1,800 @@
+//! Mapping between protocol messages and protobuf frames.
+//!
+//! Typed bodies are best effort: a payload that does not parse as the
+//! opcode's type is sent as raw bytes, so the relay never drops a message
+//! the peer could have handled. Struct conversions destructure every field,
+//! so a new protocol field fails to compile until it is mapped here.
+
+use crate::proto::{self, frame::Body, Frame};
+use anyhow::{anyhow, Context, Result};
+use casparian_protocol::types::{
+    BatchFile, BatchFileReceipt, ByteRange, DispatchCommand, HeartbeatPayload, HeartbeatStatus,
+    IdentifyPayload, JobReceipt, JobStatus, ResourceLimits, SourceFile, SourceFingerprint,
+    SourceHash, TextEncoding, WorkerLoad,
+};
+use casparian_protocol::{
+    Header, JobId, Message, OpCode, ProtocolFeatures, ProtocolVersionRange, MAX_PAYLOAD_SIZE,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+impl Frame {
+    /// Frame for `msg`, with a typed body when the payload parses.
+    pub fn from_message(msg: &Message) -> Self {
+        Self {
+            version: u32::from(msg.header.version),
+            opcode: u32::from(msg.header.opcode.as_u8()),
+            reserved: u32::from(msg.header.reserved),
+            job_id: msg.header.job_id.as_u64(),
+            body: Some(
+                typed_body(msg.header.opcode, &msg.payload)
+                    .unwrap_or_else(|| Body::Payload(msg.payload.clone())),
+            ),
+        }
+    }
+
+    /// Protocol message carried by this frame.
+    pub fn into_message(self) -> Result<Message> {
+        let version = u8::try_from(self.version).context("Frame version out of range")?;
+        let opcode = u8::try_from(self.opcode).context("Frame opcode out of range")?;
+        let opcode = OpCode::from_u8(opcode).map_err(|e| anyhow!(e))?;
+        let reserved = u16::try_from(self.reserved).context("Frame reserved out of range")?;
+        let payload = match self.body {
+            None => Vec::new(),
+            Some(Body::Payload(bytes)) => bytes,
+            Some(Body::Identify(identify)) => serde_json::to_vec(&IdentifyPayload::from(identify))?,
+            Some(Body::Dispatch(dispatch)) => {
+                serde_json::to_vec(&DispatchCommand::try_from(dispatch)?)?
+            }
+            Some(Body::Heartbeat(heartbeat)) => {
+                serde_json::to_vec(&HeartbeatPayload::try_from(heartbeat)?)?
+            }
+            Some(Body::Conclude(conclude)) => serde_json::to_vec(&JobReceipt::try_from(conclude)?)?,
+        };
+        if payload.len() > MAX_PAYLOAD_SIZE {
+            anyhow::bail!("Frame payload of {} bytes is too large", payload.len());
+        }
+        Ok(Message {
+            header: Header {
+                version,
+                opcode,
+                reserved,
+                job_id: JobId::new(self.job_id),
+                payload_len: payload.len() as u32,
+            },
+            payload,
+        })
+    }
+}
+
+fn typed_body(opcode: OpCode, payload: &[u8]) -> Option<Body> {
+    match opcode {
+        OpCode::Identify => serde_json::from_slice::<IdentifyPayload>(payload)
+            .ok()
+            .map(|identify| Body::Identify(identify.into())),
+        OpCode::Dispatch => serde_json::from_slice::<DispatchCommand>(payload)
+            .ok()
+            .and_then(|dispatch| proto::Dispatch::try_from(dispatch).ok())
+            .map(Body::Dispatch),
+        OpCode::Heartbeat => serde_json::from_slice::<HeartbeatPayload>(payload)
+            .ok()
+            .map(|heartbeat| Body::Heartbeat(heartbeat.into())),
+        OpCode::Conclude => serde_json::from_slice::<JobReceipt>(payload)
+            .ok()
+            .and_then(|receipt| proto::Conclude::try_from(receipt).ok())
+            .map(Body::Conclude),
+        _ => None,
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(Into::into)
+}
+
+fn from_json<T: DeserializeOwned>(json: &str, what: &str) -> Result<T> {
+    serde_json::from_str(json).with_context(|| format!("Invalid {} in frame", what))
+}
+
+fn parse_encoding(value: Option<String>) -> Result<Option<TextEncoding>> {
+    value
+        .map(|value| value.parse::<TextEncoding>().map_err(|e| anyhow!(e)))
+        .transpose()
+}
+
+impl From<IdentifyPayload> for proto::Identify {
+    fn from(identify: IdentifyPayload) -> Self {
+        let IdentifyPayload {
+            capabilities,
+            worker_id,
+            protocol_versions,
+            protocol_features,
+        } = identify;
+        Self {
+            capabilities,
+            worker_id,
+            protocol_min: u32::from(protocol_versions.min),
+            protocol_max: u32::from(protocol_versions.max),
+            protocol_features: protocol_features.bits(),
+        }
+    }
+}
+
+impl From<proto::Identify> for IdentifyPayload {
+    fn from(identify: proto::Identify) -> Self {
+        // A range that does not fit the header byte falls back to the v4
+        // default, which the Sentinel then negotiates as it would for ZMQ.
+        let protocol_versions = match (
+            u8::try_from(identify.protocol_min),
+            u8::try_from(identify.protocol_max),
+        ) {
+            (Ok(min), Ok(max)) => ProtocolVersionRange::new(min, max).unwrap_or_default(),
+            _ => ProtocolVersionRange::default(),
+        };
+        Self {
+            capabilities: identify.capabilities,
+            worker_id: identify.worker_id,
+            protocol_versions,
+            protocol_features: ProtocolFeatures::from_bits(identify.protocol_features),
+        }
+    }
+}
+
+impl TryFrom<DispatchCommand> for proto::Dispatch {
+    type Error = anyhow::Error;
+
+    fn try_from(cmd: DispatchCommand) -> Result<Self> {
+        let DispatchCommand {
+            plugin_name,
+            parser_version,
+            file_path,
+            sinks,
+            file_id,
+            lease_token,
+            runtime_kind,
+            entrypoint,
+            platform_os,
+            platform_arch,
+            signature_verified,
+            signer_id,
+            env_hash,
+            source_code,
+            artifact_hash,
+            lockfile_content,
+            limits,
+            batch,
+            priority,
+            byte_range,
+            tag,
+            checkpoint_key,
+            csv_dialect,
+            input_encoding,
+            cached_source_hash,
+            input_lineage,
+        } = cmd;
+        Ok(Self {
+            plugin_name,
+            parser_version,
+            file_path,
+            sinks_json: sinks.iter().map(to_json).collect::<Result<_>>()?,
+            file_id,
+            lease_token,
+            runtime_kind: runtime_kind.as_str().to_string(),
+            entrypoint,
+            platform_os,
+            platform_arch,
+            signature_verified,
+            signer_id,
+            env_hash,
+            source_code,
+            artifact_hash,
+            lockfile_content,
+            limits: (!limits.is_unlimited()).then(|| limits.into()),
+            batch: batch
+                .into_iter()
+                .map(proto::BatchFile::try_from)
+                .collect::<Result<_>>()?,
+            priority: priority.as_str().to_string(),
+            byte_range: byte_range.map(|range| proto::ByteRange {
+                start: range.start,
+                end: range.end,
+            }),
+            tag,
+            checkpoint_key,
+            csv_dialect_json: csv_dialect.as_ref().map(to_json).transpose()?,
+            input_encoding: input_encoding.map(|encoding| encoding.as_str().to_string()),
+            cached_source_hash: cached_source_hash.map(Into::into),
+            input_lineage_json: input_lineage.as_ref().map(to_json).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<proto::Dispatch> for DispatchCommand {
+    type Error = anyhow::Error;
+
+    fn try_from(dispatch: proto::Dispatch) -> Result<Self> {
+        Ok(Self {
+            plugin_name: dispatch.plugin_name,
+            parser_version: dispatch.parser_version,
+            file_path: dispatch.file_path,
+            sinks: dispatch
+                .sinks_json
+                .iter()
+                .map(|json| from_json(json, "sink config"))
+                .collect::<Result<_>>()?,
+            file_id: dispatch.file_id,
+            lease_token: dispatch.lease_token,
+            runtime_kind: dispatch
+                .runtime_kind
+                .parse()
+                .map_err(|e: String| anyhow!(e))?,
+            entrypoint: dispatch.entrypoint,
+            platform_os: dispatch.platform_os,
+            platform_arch: dispatch.platform_arch,
+            signature_verified: dispatch.signature_verified,
+            signer_id: dispatch.signer_id,
+            env_hash: dispatch.env_hash,
+            source_code: dispatch.source_code,
+            artifact_hash: dispatch.artifact_hash,
+            lockfile_content: dispatch.lockfile_content,
+            limits: dispatch.limits.map(Into::into).unwrap_or_default(),
+            batch: dispatch
+                .batch
+                .into_iter()
+                .map(BatchFile::try_from)
+                .collect::<Result<_>>()?,
+            priority: dispatch.priority.parse().map_err(|e: String| anyhow!(e))?,
+            byte_range: dispatch.byte_range.map(|range| ByteRange {
+                start: range.start,
+                end: range.end,
+            }),
+            tag: dispatch.tag,
+            checkpoint_key: dispatch.checkpoint_key,
+            csv_dialect: dispatch
+                .csv_dialect_json
+                .map(|json| from_json(&json, "CSV dialect"))
+                .transpose()?,
+            input_encoding: parse_encoding(dispatch.input_encoding)?,
+            cached_source_hash: dispatch
+                .cached_source_hash
+                .map(SourceHash::try_from)
+                .transpose()?,
+            input_lineage: dispatch
+                .input_lineage_json
+                .map(|json| from_json(&json, "lineage"))
+                .transpose()?,
+        })
+    }
+}
+
+impl From<ResourceLimits> for proto::ResourceLimits {
+    fn from(limits: ResourceLimits) -> Self {
+        let ResourceLimits {
+            timeout_secs,
+            max_cpu_secs,
+            max_memory_bytes,
+            max_decompressed_bytes,
+            max_scratch_bytes,
+        } = limits;
+        Self {
+            timeout_secs,
+            max_cpu_secs,
+            max_memory_bytes,
+            max_decompressed_bytes,
+            max_scratch_bytes,
+        }
+    }
+}
+
+impl From<proto::ResourceLimits> for ResourceLimits {
+    fn from(limits: proto::ResourceLimits) -> Self {
+        Self {
+            timeout_secs: limits.timeout_secs,
+            max_cpu_secs: limits.max_cpu_secs,
+            max_memory_bytes: limits.max_memory_bytes,
+            max_decompressed_bytes: limits.max_decompressed_bytes,
+            max_scratch_bytes: limits.max_scratch_bytes,
+        }
+    }
+}
+
+impl From<SourceFingerprint> for proto::SourceFingerprint {
+    fn from(fingerprint: SourceFingerprint) -> Self {
+        Self {
+            size: fingerprint.size,
+            mtime_ms: fingerprint.mtime_ms,
+        }
+    }
+}
+
+impl From<proto::SourceFingerprint> for SourceFingerprint {
+    fn from(fingerprint: proto::SourceFingerprint) -> Self {
+        Self {
+            size: fingerprint.size,
+            mtime_ms: fingerprint.mtime_ms,
+        }
+    }
+}
+
+impl From<SourceHash> for proto::SourceHash {
+    fn from(source_hash: SourceHash) -> Self {
+        Self {
+            hash: source_hash.hash,
+            fingerprint: Some(source_hash.fingerprint.into()),
+        }
+    }
+}
+
+impl TryFrom<proto::SourceHash> for SourceHash {
+    type Error = anyhow::Error;
+
+    fn try_from(source_hash: proto::SourceHash) -> Result<Self> {
+        Ok(Self {
+            hash: source_hash.hash,
+            fingerprint: source_hash
+                .fingerprint
+                .context("Source hash without a fingerprint")?
+                .into(),
+        })
+    }
+}
+
+impl From<SourceFile> for proto::SourceFile {
+    fn from(file: SourceFile) -> Self {
+        Self {
+            path: file.path,
+            fingerprint: Some(file.fingerprint.into()),
+        }
+    }
+}
+
+impl TryFrom<proto::SourceFile> for SourceFile {
+    type Error = anyhow::Error;
+
+    fn try_from(file: proto::SourceFile) -> Result<Self> {
+        Ok(Self {
+            path: file.path,
+            fingerprint: file
+                .fingerprint
+                .context("Source file without a fingerprint")?
+                .into(),
+        })
+    }
+}
+
+impl TryFrom<BatchFile> for proto::BatchFile {
+    type Error = anyhow::Error;
+
+    fn try_from(file: BatchFile) -> Result<Self> {
+        let BatchFile {
+            job_id,
+            file_id,
+            file_path,
+            lease_token,
+            tag,
+            csv_dialect,
+            input_encoding,
+            cached_source_hash,
+            input_lineage,
+        } = file;
+        Ok(Self {
+            job_id: job_id.as_u64(),
+            file_id,
+            file_path,
+            lease_token,
+            tag,
+            csv_dialect_json: csv_dialect.as_ref().map(to_json).transpose()?,
+            input_encoding: input_encoding.map(|encoding| encoding.as_str().to_string()),
+            cached_source_hash: cached_source_hash.map(Into::into),
+            input_lineage_json: input_lineage.as_ref().map(to_json).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<proto::BatchFile> for BatchFile {
+    type Error = anyhow::Error;
+
+    fn try_from(file: proto::BatchFile) -> Result<Self> {
+        Ok(Self {
+            job_id: JobId::new(file.job_id),
+            file_id: file.file_id,
+            file_path: file.file_path,
+            lease_token: file.lease_token,
+            tag: file.tag,
+            csv_dialect: file
+                .csv_dialect_json
+                .map(|json| from_json(&json, "CSV dialect"))
+                .transpose()?,
+            input_encoding: parse_encoding(file.input_encoding)?,
+            cached_source_hash: file
+                .cached_source_hash
+                .map(SourceHash::try_from)
+                .transpose()?,
+            input_lineage: file
+                .input_lineage_json
+                .map(|json| from_json(&json, "lineage"))
+                .transpose()?,
+        })
+    }
+}
+
+impl From<HeartbeatPayload> for proto::Heartbeat {
+    fn from(heartbeat: HeartbeatPayload) -> Self {
+        let HeartbeatPayload {
+            status,
+            active_job_count,
+            active_job_ids,
+            load,
+        } = heartbeat;
+        let status = match status {
+            HeartbeatStatus::Idle => proto::HeartbeatStatus::Idle,
+            HeartbeatStatus::Busy => proto::HeartbeatStatus::Busy,
+            HeartbeatStatus::Alive => proto::HeartbeatStatus::Alive,
+        };
+        Self {
+            status: status as i32,
+            active_job_count: active_job_count as u64,
+            active_job_ids: active_job_ids.into_iter().map(JobId::as_u64).collect(),
+            load: load.map(|load| {
+                let WorkerLoad {
+                    queue_depth,
+                    capacity,
+                    cpu_percent,
+                    memory_used_bytes,
+                    memory_total_bytes,
+                } = load;
+                proto::WorkerLoad {
+                    queue_depth: queue_depth as u64,
+                    capacity: capacity as u64,
+                    cpu_percent,
+                    memory_used_bytes,
+                    memory_total_bytes,
+                }
+            }),
+        }
+    }
+}
+
+impl TryFrom<proto::Heartbeat> for HeartbeatPayload {
+    type Error = anyhow::Error;
+
+    fn try_from(heartbeat: proto::Heartbeat) -> Result<Self> {
+        let status = match proto::HeartbeatStatus::try_from(heartbeat.status) {
+            Ok(proto::HeartbeatStatus::Idle) => HeartbeatStatus::Idle,
+            Ok(proto::HeartbeatStatus::Busy) => HeartbeatStatus::Busy,
+            Ok(proto::HeartbeatStatus::Alive) => HeartbeatStatus::Alive,
+            Ok(proto::HeartbeatStatus::Unspecified) | Err(_) => {
+                anyhow::bail!("Invalid heartbeat status {}", heartbeat.status)
+            }
+        };
+        Ok(Self {
+            status,
+            active_job_count: usize::try_from(heartbeat.active_job_count)?,
+            active_job_ids: heartbeat
+                .active_job_ids
+                .into_iter()
+                .map(JobId::new)
+                .collect(),
+            load: heartbeat
+                .load
+                .map(|load| -> Result<WorkerLoad> {
+                    Ok(WorkerLoad {
+                        queue_depth: usize::try_from(load.queue_depth)?,
+                        capacity: usize::try_from(load.capacity)?,
+                        cpu_percent: load.cpu_percent,
+                        memory_used_bytes: load.memory_used_bytes,
+                        memory_total_bytes: load.memory_total_bytes,
+                    })
+                })
+                .transpose()?,
+        })
+    }
+}
+
+impl TryFrom<JobReceipt> for proto::Conclude {
+    type Error = anyhow::Error;
+
+    fn try_from(receipt: JobReceipt) -> Result<Self> {
+        let JobReceipt {
+            status,
+            metrics,
+            artifacts,
+            error_message,
+            diagnostics,
+            source_hash,
+            source_file,
+            decompressed_hash,
+            source_encoding,
+            lineage,
+            lease_token,
+            batch_results,
+        } = receipt;
+        let status = match status {
+            JobStatus::Success => proto::JobStatus::Success,
+            JobStatus::PartialSuccess => proto::JobStatus::PartialSuccess,
+            JobStatus::CompletedWithWarnings => proto::JobStatus::CompletedWithWarnings,
+            JobStatus::Failed => proto::JobStatus::Failed,
+            JobStatus::Rejected => proto::JobStatus::Rejected,
+            JobStatus::Aborted => proto::JobStatus::Aborted,
+        };
+        Ok(Self {
+            status: status as i32,
+            metrics,
+            artifacts_json: artifacts.iter().map(to_json).collect::<Result<_>>()?,
+            error_message,
+            diagnostics_json: diagnostics.as_ref().map(to_json).transpose()?,
+            source_hash,
+            decompressed_hash,
+            source_encoding: source_encoding.map(|encoding| encoding.as_str().to_string()),
+            lease_token,
+            batch_results: batch_results
+                .into_iter()
+                .map(|result| {
+                    Ok(proto::BatchFileReceipt {
+                        job_id: result.job_id.as_u64(),
+                        receipt: Some(proto::Conclude::try_from(result.receipt)?),
+                    })
+                })
+                .collect::<Result<_>>()?,
+            source_file: source_file.map(Into::into),
+            lineage_json: lineage.as_ref().map(to_json).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<proto::Conclude> for JobReceipt {
+    type Error = anyhow::Error;
+
+    fn try_from(conclude: proto::Conclude) -> Result<Self> {
+        let status = match proto::JobStatus::try_from(conclude.status) {
+            Ok(proto::JobStatus::Success) => JobStatus::Success,
+            Ok(proto::JobStatus::PartialSuccess) => JobStatus::PartialSuccess,
+            Ok(proto::JobStatus::CompletedWithWarnings) => JobStatus::CompletedWithWarnings,
+            Ok(proto::JobStatus::Failed) => JobStatus::Failed,
+            Ok(proto::JobStatus::Rejected) => JobStatus::Rejected,
+            Ok(proto::JobStatus::Aborted) => JobStatus::Aborted,
+            Ok(proto::JobStatus::Unspecified) | Err(_) => {
+                anyhow::bail!("Invalid job status {}", conclude.status)
+            }
+        };
+        Ok(Self {
+            status,
+            metrics: conclude.metrics,
+            artifacts: conclude
+                .artifacts_json
+                .iter()
+                .map(|json| from_json(json, "artifact"))
+                .collect::<Result<_>>()?,
+            error_message: conclude.error_message,
+            diagnostics: conclude
+                .diagnostics_json
+                .map(|json| from_json(&json, "diagnostics"))
+                .transpose()?,
+            source_hash: conclude.source_hash,
+            source_file: conclude.source_file.map(SourceFile::try_from).transpose()?,
+            decompressed_hash: conclude.decompressed_hash,
+            source_encoding: parse_encoding(conclude.source_encoding)?,
+            lineage: conclude
+                .lineage_json
+                .map(|json| from_json(&json, "lineage"))
+                .transpose()?,
+            lease_token: conclude.lease_token,
+            batch_results: conclude
+                .batch_results
+                .into_iter()
+                .map(|result| {
+                    let receipt = result.receipt.context("Batch result without a receipt")?;
+                    Ok(BatchFileReceipt {
+                        job_id: JobId::new(result.job_id),
+                        receipt: JobReceipt::try_from(receipt)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::types::{
+        LineageChain, LineageFileType, LineageHop, RuntimeKind, SinkConfig,
+    };
+    use casparian_protocol::JobPriority;
+    use std::collections::HashMap;
+
+    fn round_trip(msg: &Message) -> (Frame, Message) {
+        let frame = Frame::from_message(msg);
+        let bytes = prost::Message::encode_to_vec(&frame);
+        let decoded: Frame = prost::Message::decode(bytes.as_slice()).unwrap();
+        (frame, decoded.into_message().unwrap())
+    }
+
+    fn json<T: Serialize>(value: &T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_identify_and_heartbeat_are_typed() {
+        let identify = IdentifyPayload {
+            capabilities: vec!["python".to_string()],
+            worker_id: Some("worker-1".to_string()),
+            protocol_versions: ProtocolVersionRange::SUPPORTED,
+            protocol_features: ProtocolFeatures::STREAMING,
+        };
+        let msg = Message::new(
+            OpCode::Identify,
+            JobId::new(0),
+            serde_json::to_vec(&identify).unwrap(),
+        )
+        .unwrap();
+        let (frame, back) = round_trip(&msg);
+        assert!(matches!(frame.body, Some(Body::Identify(_))));
+        let parsed: IdentifyPayload = serde_json::from_slice(&back.payload).unwrap();
+        assert_eq!(json(&parsed), json(&identify));
+
+        let heartbeat = HeartbeatPayload {
+            status: HeartbeatStatus::Busy,
+            active_job_count: 1,
+            active_job_ids: vec![JobId::new(7)],
+            load: Some(WorkerLoad {
+                queue_depth: 1,
+                capacity: 4,
+                cpu_percent: Some(12.5),
+                memory_used_bytes: None,
+                memory_total_bytes: None,
+            }),
+        };
+        let msg = Message::new(
+            OpCode::Heartbeat,
+            JobId::new(7),
+            serde_json::to_vec(&heartbeat).unwrap(),
+        )
+        .unwrap();
+        let (frame, back) = round_trip(&msg);
+        assert!(matches!(frame.body, Some(Body::Heartbeat(_))));
+        assert_eq!(back.header.job_id, JobId::new(7));
+        let parsed: HeartbeatPayload = serde_json::from_slice(&back.payload).unwrap();
+        assert_eq!(json(&parsed), json(&heartbeat));
+    }
+
+    #[test]
+    fn test_dispatch_and_conclude_are_typed() {
+        let dispatch = DispatchCommand {
+            plugin_name: "orders".to_string(),
+            parser_version: Some("1.0.0".to_string()),
+            file_path: "/data/orders.csv".to_string(),
+            sinks: vec![SinkConfig {
+                topic: "output".to_string(),
+                uri: "parquet://./output/".to_string(),
+                mode: Default::default(),
+                schema_evolution: Default::default(),
+                quarantine_config: None,
+                schema: None,
+            }],
+            file_id: 3,
+            lease_token: Some("lease".to_string()),
+            runtime_kind: RuntimeKind::PythonShim,
+            entrypoint: "orders.py:parse".to_string(),
+            platform_os: None,
+            platform_arch: None,
+            signature_verified: true,
+            signer_id: Some("ops".to_string()),
+            env_hash: None,
+            source_code: Some("def parse(): pass".to_string()),
+            artifact_hash: "abc".to_string(),
+            lockfile_content: None,
+            limits: ResourceLimits {
+                timeout_secs: Some(60),
+                ..Default::default()
+            },
+            batch: vec![BatchFile {
+                job_id: JobId::new(9),
+                file_id: 4,
+                file_path: "/data/orders-2.csv".to_string(),
+                lease_token: "lease-2".to_string(),
+                tag: None,
+                csv_dialect: None,
+                input_encoding: Some(TextEncoding::Latin1),
+                cached_source_hash: None,
+                input_lineage: None,
+            }],
+            priority: JobPriority::High,
+            byte_range: Some(ByteRange { start: 0, end: 10 }),
+            tag: Some("orders".to_string()),
+            checkpoint_key: None,
+            csv_dialect: None,
+            input_encoding: None,
+            cached_source_hash: Some(SourceHash {
+                hash: "hash".to_string(),
+                fingerprint: SourceFingerprint {
+                    size: 10,
+                    mtime_ms: 1_700_000_000_000,
+                },
+            }),
+            input_lineage: None,
+        };
+        let msg = Message::new(
+            OpCode::Dispatch,
+            JobId::new(8),
+            serde_json::to_vec(&dispatch).unwrap(),
+        )
+        .unwrap();
+        let (frame, back) = round_trip(&msg);
+        assert!(matches!(frame.body, Some(Body::Dispatch(_))));
+        let parsed: DispatchCommand = serde_json::from_slice(&back.payload).unwrap();
+        assert_eq!(json(&parsed), json(&dispatch));
+
+        let receipt = JobReceipt {
+            status: JobStatus::Failed,
+            metrics: HashMap::from([("rows".to_string(), 10)]),
+            artifacts: Vec::new(),
+            error_message: Some("boom".to_string()),
+            diagnostics: None,
+            source_hash: Some("hash".to_string()),
+            source_file: Some(SourceFile {
+                path: "/data/orders.csv".to_string(),
+                fingerprint: SourceFingerprint {
+                    size: 10,
+                    mtime_ms: 1_700_000_000_000,
+                },
+            }),
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: Some(LineageChain {
+                hops: vec![LineageHop {
+                    file_path: "/data/orders.csv".into(),
+                    file_type: LineageFileType::Original,
+                    offset: 0,
+                    row_number: 0,
+                    job_id: Some(JobId::new(8)),
+                    plugin_name: Some("orders".to_string()),
+                    parser_version: Some("1.0.0".to_string()),
+                    content_hash: Some("hash".to_string()),
+                }],
+            }),
+            lease_token: Some("lease".to_string()),
+            batch_results: vec![BatchFileReceipt {
+                job_id: JobId::new(9),
+                receipt: JobReceipt {
+                    status: JobStatus::Success,
+                    metrics: HashMap::new(),
+                    artifacts: Vec::new(),
+                    error_message: None,
+                    diagnostics: None,
+                    source_hash: None,
+                    source_file: None,
+                    decompressed_hash: None,
+                    source_encoding: None,
+                    lineage: None,
+                    lease_token: Some("lease-2".to_string()),
+                    batch_results: Vec::new(),
+                },
+            }],
+        };
+        let msg = Message::new(
+            OpCode::Conclude,
+            JobId::new(8),
+            serde_json::to_vec(&receipt).unwrap(),
+        )
+        .unwrap();
+        let (frame, back) = round_trip(&msg);
+        assert!(matches!(frame.body, Some(Body::Conclude(_))));
+        let parsed: JobReceipt = serde_json::from_slice(&back.payload).unwrap();
+        assert_eq!(json(&parsed), json(&receipt));
+    }
+
+    #[test]
+    fn test_other_payloads_pass_through() {
+        let msg = Message::new(OpCode::Abort, JobId::new(5), b"{}".to_vec()).unwrap();
+        let (frame, back) = round_trip(&msg);
+        assert!(matches!(frame.body, Some(Body::Payload(_))));
+        assert_eq!(back.payload, msg.payload);
+        assert_eq!(back.header.opcode, OpCode::Abort);
+
+        // A HEARTBEAT the typed mapping cannot read is relayed untouched.
+        let msg = Message::new(OpCode::Heartbeat, JobId::new(0), b"not json".to_vec()).unwrap();
+        let (frame, back) = round_trip(&msg);
+        assert!(matches!(frame.body, Some(Body::Payload(_))));
+        assert_eq!(back.payload, b"not json");
+    }
+}
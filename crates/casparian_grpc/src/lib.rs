@@ -0,0 +1,47 @@
+//! gRPC transport between workers and the Sentinel.
+//!
+//! An alternative to ZMQ for deployments where only HTTP/2 is allowed
+//! through. It carries the same IDENTIFY/DISPATCH/HEARTBEAT/CONCLUDE
+//! exchange (and every other opcode) over one bidirectional `Exchange`
+//! stream per worker, with protobuf mappings of the protocol types.
+//!
+//! Both ends bridge to the existing ZMQ event loops instead of replacing
+//! them: the Sentinel binds its ROUTER in-process and [`GrpcRelay`] forwards
+//! each stream to it, and the worker connects its DEALER to a [`GrpcLink`].
+//! The transport is selected by the `grpc://` scheme of the bind address.
+//! There is no transport security; CURVE is ZMQ-only.
+
+mod convert;
+mod link;
+pub mod proto;
+mod pump;
+mod relay;
+
+pub use link::GrpcLink;
+pub use relay::GrpcRelay;
+
+/// URI scheme selecting the gRPC transport (`grpc://0.0.0.0:7070`).
+pub const GRPC_SCHEME: &str = "grpc://";
+
+/// `host:port` of a `grpc://` URI, or None for other transports.
+pub fn grpc_authority(uri: &str) -> Option<&str> {
+    uri.strip_prefix(GRPC_SCHEME)
+        .map(|rest| rest.trim_end_matches('/'))
+        .filter(|authority| !authority.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grpc_authority() {
+        assert_eq!(grpc_authority("grpc://0.0.0.0:7070"), Some("0.0.0.0:7070"));
+        assert_eq!(
+            grpc_authority("grpc://sentinel:7070/"),
+            Some("sentinel:7070")
+        );
+        assert_eq!(grpc_authority("grpc://"), None);
+        assert_eq!(grpc_authority("tcp://127.0.0.1:5555"), None);
+    }
+}
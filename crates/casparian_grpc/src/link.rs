@@ -0,0 +1,159 @@
+//! Worker side: a local ROUTER the worker's DEALER connects to, relayed over
+//! one `Exchange` stream to the Sentinel.
+
+use crate::proto::link_client::LinkClient;
+use crate::proto::Frame;
+use anyhow::{Context, Result};
+use casparian_protocol::OpCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{info, warn};
+
+/// Frames buffered per direction.
+const STREAM_BUFFER: usize = 64;
+/// Delay between attempts to reach the Sentinel.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+static NEXT_LINK: AtomicUsize = AtomicUsize::new(0);
+
+/// Running connection to a `grpc://` Sentinel; closed on drop.
+///
+/// The stream is re-established when it drops. The worker's last IDENTIFY is
+/// replayed first on every new stream, since the Sentinel sees each stream
+/// as a new peer.
+pub struct GrpcLink {
+    endpoint: String,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl GrpcLink {
+    /// Start relaying to the Sentinel at `uri` (`grpc://host:port`).
+    pub fn connect(uri: &str, context: &zmq::Context) -> Result<Self> {
+        let authority =
+            crate::grpc_authority(uri).with_context(|| format!("Not a gRPC address: {}", uri))?;
+        let target = format!("http://{}", authority);
+        let channel = tonic::transport::Endpoint::from_shared(target.clone())
+            .with_context(|| format!("Invalid gRPC address: {}", uri))?;
+
+        let endpoint = format!(
+            "inproc://casparian-grpc-link-{}",
+            NEXT_LINK.fetch_add(1, Ordering::Relaxed)
+        );
+        let socket = context
+            .socket(zmq::ROUTER)
+            .context("Failed to create gRPC link socket")?;
+        socket.set_linger(0)?;
+        socket
+            .bind(&endpoint)
+            .with_context(|| format!("Failed to bind {}", endpoint))?;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .thread_name("worker-grpc")
+            .enable_all()
+            .build()
+            .context("Failed to start gRPC runtime")?;
+        let (to_zmq, from_grpc) = std::sync::mpsc::channel();
+        let (to_grpc, from_zmq) = mpsc::channel(STREAM_BUFFER);
+        std::thread::Builder::new()
+            .name("worker-grpc-pump".to_string())
+            .spawn(move || crate::pump::run(socket, true, from_grpc, to_grpc))
+            .context("Failed to spawn gRPC relay thread")?;
+
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("worker-grpc".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    tokio::select! {
+                        _ = relay(channel, from_zmq, to_zmq) => {}
+                        _ = shutdown_rx => {}
+                    }
+                });
+            })
+            .context("Failed to spawn gRPC thread")?;
+        info!("Connecting to Sentinel over gRPC at {}", target);
+        Ok(Self {
+            endpoint,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    /// Endpoint the worker's DEALER connects to instead of the Sentinel.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+}
+
+impl Drop for GrpcLink {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Relay frames until the pump stops, reconnecting whenever the stream drops.
+async fn relay(
+    channel: tonic::transport::Endpoint,
+    mut from_zmq: mpsc::Receiver<Frame>,
+    to_zmq: std::sync::mpsc::Sender<Frame>,
+) {
+    let identify = u32::from(OpCode::Identify.as_u8());
+    let mut last_identify: Option<Frame> = None;
+    loop {
+        let mut client = match LinkClient::connect(channel.clone()).await {
+            Ok(client) => client,
+            Err(err) => {
+                warn!("gRPC connect failed: {}", err);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        let (outbound, stream) = mpsc::channel(STREAM_BUFFER);
+        if let Some(frame) = last_identify.clone() {
+            let _ = outbound.send(frame).await;
+        }
+        let mut inbound = match client.exchange(ReceiverStream::new(stream)).await {
+            Ok(response) => response.into_inner(),
+            Err(status) => {
+                warn!("gRPC stream failed: {}", status);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        loop {
+            tokio::select! {
+                frame = from_zmq.recv() => {
+                    let Some(frame) = frame else { return };
+                    if frame.opcode == identify {
+                        last_identify = Some(frame.clone());
+                    }
+                    if outbound.send(frame).await.is_err() {
+                        break;
+                    }
+                }
+                frame = inbound.message() => match frame {
+                    Ok(Some(frame)) => {
+                        if to_zmq.send(frame).is_err() {
+                            return;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(status) => {
+                        warn!("gRPC stream to Sentinel ended: {}", status);
+                        break;
+                    }
+                },
+            }
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
@@ -0,0 +1,300 @@
+//! Protobuf messages of the `casparian.link.Link` service.
+//!
+//! Equivalent `.proto`:
+//!
+//! ```proto
+//! service Link {
+//!   rpc Exchange(stream Frame) returns (stream Frame);
+//! }
+//! ```
+//!
+//! A `Frame` is one protocol message: the binary header fields plus a typed
+//! body for IDENTIFY, DISPATCH, HEARTBEAT and CONCLUDE. Every other opcode,
+//! stream chunks and payloads of other protocol versions travel as the raw
+//! `payload` bytes. Nested documents (sink configs, CSV dialects, artifacts,
+//! diagnostics) keep their protocol JSON encoding.
+
+use std::collections::HashMap;
+
+/// One protocol message.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Frame {
+    /// Header protocol version
+    #[prost(uint32, tag = "1")]
+    pub version: u32,
+    /// `OpCode` byte
+    #[prost(uint32, tag = "2")]
+    pub opcode: u32,
+    /// Header `reserved` field
+    #[prost(uint32, tag = "3")]
+    pub reserved: u32,
+    #[prost(uint64, tag = "4")]
+    pub job_id: u64,
+    #[prost(oneof = "frame::Body", tags = "10, 11, 12, 13, 14")]
+    pub body: Option<frame::Body>,
+}
+
+pub mod frame {
+    #[allow(clippy::large_enum_variant)]
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Body {
+        #[prost(message, tag = "10")]
+        Identify(super::Identify),
+        #[prost(message, tag = "11")]
+        Dispatch(super::Dispatch),
+        #[prost(message, tag = "12")]
+        Heartbeat(super::Heartbeat),
+        #[prost(message, tag = "13")]
+        Conclude(super::Conclude),
+        /// Protocol payload bytes, for everything without a typed body
+        #[prost(bytes = "vec", tag = "14")]
+        Payload(Vec<u8>),
+    }
+}
+
+/// `IdentifyPayload`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Identify {
+    #[prost(string, repeated, tag = "1")]
+    pub capabilities: Vec<String>,
+    #[prost(string, optional, tag = "2")]
+    pub worker_id: Option<String>,
+    #[prost(uint32, tag = "3")]
+    pub protocol_min: u32,
+    #[prost(uint32, tag = "4")]
+    pub protocol_max: u32,
+    /// `ProtocolFeatures` bits
+    #[prost(uint32, tag = "5")]
+    pub protocol_features: u32,
+}
+
+/// `DispatchCommand`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Dispatch {
+    #[prost(string, tag = "1")]
+    pub plugin_name: String,
+    #[prost(string, optional, tag = "2")]
+    pub parser_version: Option<String>,
+    #[prost(string, tag = "3")]
+    pub file_path: String,
+    /// One `SinkConfig` JSON document per sink
+    #[prost(string, repeated, tag = "4")]
+    pub sinks_json: Vec<String>,
+    #[prost(int64, tag = "5")]
+    pub file_id: i64,
+    #[prost(string, optional, tag = "6")]
+    pub lease_token: Option<String>,
+    /// `RuntimeKind::as_str`
+    #[prost(string, tag = "7")]
+    pub runtime_kind: String,
+    #[prost(string, tag = "8")]
+    pub entrypoint: String,
+    #[prost(string, optional, tag = "9")]
+    pub platform_os: Option<String>,
+    #[prost(string, optional, tag = "10")]
+    pub platform_arch: Option<String>,
+    #[prost(bool, tag = "11")]
+    pub signature_verified: bool,
+    #[prost(string, optional, tag = "12")]
+    pub signer_id: Option<String>,
+    #[prost(string, optional, tag = "13")]
+    pub env_hash: Option<String>,
+    #[prost(string, optional, tag = "14")]
+    pub source_code: Option<String>,
+    #[prost(string, tag = "15")]
+    pub artifact_hash: String,
+    #[prost(string, optional, tag = "16")]
+    pub lockfile_content: Option<String>,
+    #[prost(message, optional, tag = "17")]
+    pub limits: Option<ResourceLimits>,
+    #[prost(message, repeated, tag = "18")]
+    pub batch: Vec<BatchFile>,
+    /// `JobPriority::as_str`
+    #[prost(string, tag = "19")]
+    pub priority: String,
+    #[prost(message, optional, tag = "20")]
+    pub byte_range: Option<ByteRange>,
+    #[prost(string, optional, tag = "21")]
+    pub tag: Option<String>,
+    #[prost(string, optional, tag = "22")]
+    pub checkpoint_key: Option<String>,
+    /// `CsvDialect` JSON
+    #[prost(string, optional, tag = "23")]
+    pub csv_dialect_json: Option<String>,
+    /// `TextEncoding::as_str`
+    #[prost(string, optional, tag = "24")]
+    pub input_encoding: Option<String>,
+    #[prost(message, optional, tag = "25")]
+    pub cached_source_hash: Option<SourceHash>,
+    /// `LineageChain` JSON
+    #[prost(string, optional, tag = "26")]
+    pub input_lineage_json: Option<String>,
+}
+
+/// `ResourceLimits`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResourceLimits {
+    #[prost(uint64, optional, tag = "1")]
+    pub timeout_secs: Option<u64>,
+    #[prost(uint64, optional, tag = "2")]
+    pub max_cpu_secs: Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    pub max_memory_bytes: Option<u64>,
+    #[prost(uint64, optional, tag = "4")]
+    pub max_decompressed_bytes: Option<u64>,
+    #[prost(uint64, optional, tag = "5")]
+    pub max_scratch_bytes: Option<u64>,
+}
+
+/// `BatchFile`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchFile {
+    #[prost(uint64, tag = "1")]
+    pub job_id: u64,
+    #[prost(int64, tag = "2")]
+    pub file_id: i64,
+    #[prost(string, tag = "3")]
+    pub file_path: String,
+    #[prost(string, tag = "4")]
+    pub lease_token: String,
+    #[prost(string, optional, tag = "5")]
+    pub tag: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub csv_dialect_json: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub input_encoding: Option<String>,
+    #[prost(message, optional, tag = "8")]
+    pub cached_source_hash: Option<SourceHash>,
+    /// `LineageChain` JSON
+    #[prost(string, optional, tag = "9")]
+    pub input_lineage_json: Option<String>,
+}
+
+/// `SourceFingerprint`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SourceFingerprint {
+    #[prost(uint64, tag = "1")]
+    pub size: u64,
+    #[prost(int64, tag = "2")]
+    pub mtime_ms: i64,
+}
+
+/// `SourceFile`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SourceFile {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(message, optional, tag = "2")]
+    pub fingerprint: Option<SourceFingerprint>,
+}
+
+/// `SourceHash`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SourceHash {
+    #[prost(string, tag = "1")]
+    pub hash: String,
+    #[prost(message, optional, tag = "2")]
+    pub fingerprint: Option<SourceFingerprint>,
+}
+
+/// `ByteRange`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ByteRange {
+    #[prost(uint64, tag = "1")]
+    pub start: u64,
+    #[prost(uint64, tag = "2")]
+    pub end: u64,
+}
+
+/// `HeartbeatPayload`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Heartbeat {
+    #[prost(enumeration = "HeartbeatStatus", tag = "1")]
+    pub status: i32,
+    #[prost(uint64, tag = "2")]
+    pub active_job_count: u64,
+    #[prost(uint64, repeated, tag = "3")]
+    pub active_job_ids: Vec<u64>,
+    #[prost(message, optional, tag = "4")]
+    pub load: Option<WorkerLoad>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum HeartbeatStatus {
+    Unspecified = 0,
+    Idle = 1,
+    Busy = 2,
+    Alive = 3,
+}
+
+/// `WorkerLoad`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WorkerLoad {
+    #[prost(uint64, tag = "1")]
+    pub queue_depth: u64,
+    #[prost(uint64, tag = "2")]
+    pub capacity: u64,
+    #[prost(float, optional, tag = "3")]
+    pub cpu_percent: Option<f32>,
+    #[prost(uint64, optional, tag = "4")]
+    pub memory_used_bytes: Option<u64>,
+    #[prost(uint64, optional, tag = "5")]
+    pub memory_total_bytes: Option<u64>,
+}
+
+/// `JobReceipt`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Conclude {
+    #[prost(enumeration = "JobStatus", tag = "1")]
+    pub status: i32,
+    #[prost(map = "string, int64", tag = "2")]
+    pub metrics: HashMap<String, i64>,
+    /// One `ArtifactV1` JSON document per artifact
+    #[prost(string, repeated, tag = "3")]
+    pub artifacts_json: Vec<String>,
+    #[prost(string, optional, tag = "4")]
+    pub error_message: Option<String>,
+    /// `JobDiagnostics` JSON
+    #[prost(string, optional, tag = "5")]
+    pub diagnostics_json: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub source_hash: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub decompressed_hash: Option<String>,
+    /// `TextEncoding::as_str`
+    #[prost(string, optional, tag = "8")]
+    pub source_encoding: Option<String>,
+    #[prost(string, optional, tag = "9")]
+    pub lease_token: Option<String>,
+    #[prost(message, repeated, tag = "10")]
+    pub batch_results: Vec<BatchFileReceipt>,
+    #[prost(message, optional, tag = "11")]
+    pub source_file: Option<SourceFile>,
+    /// `LineageChain` JSON
+    #[prost(string, optional, tag = "12")]
+    pub lineage_json: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum JobStatus {
+    Unspecified = 0,
+    Success = 1,
+    PartialSuccess = 2,
+    CompletedWithWarnings = 3,
+    Failed = 4,
+    Rejected = 5,
+    Aborted = 6,
+}
+
+/// `BatchFileReceipt`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchFileReceipt {
+    #[prost(uint64, tag = "1")]
+    pub job_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub receipt: Option<Conclude>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/casparian.link.Link.rs"));
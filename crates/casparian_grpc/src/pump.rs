@@ -0,0 +1,95 @@
+//! Moves frames between a ZMQ socket and a gRPC stream.
+
+use crate::proto::Frame;
+use casparian_protocol::Message;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, warn};
+
+/// How long the pump waits on the socket before checking for gRPC frames.
+const POLL_TIMEOUT_MS: i64 = 10;
+
+/// Relay between `socket` and a gRPC stream until either side closes.
+///
+/// `inbound` frames are sent on the socket; messages read from it go to
+/// `outbound`. A ROUTER socket (`routed`) serves a single local peer, whose
+/// identity is taken from the last message it sent.
+pub(crate) fn run(
+    socket: zmq::Socket,
+    routed: bool,
+    inbound: Receiver<Frame>,
+    outbound: Sender<Frame>,
+) {
+    let mut peer: Option<Vec<u8>> = None;
+    loop {
+        loop {
+            match inbound.try_recv() {
+                Ok(frame) => send_frame(&socket, peer.as_deref(), routed, frame),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+
+        match socket.poll(zmq::POLLIN, POLL_TIMEOUT_MS) {
+            Ok(0) => continue,
+            Ok(_) => {}
+            Err(err) => {
+                warn!("gRPC relay socket failed: {}", err);
+                return;
+            }
+        }
+        let mut parts = match socket.recv_multipart(0) {
+            Ok(parts) => parts,
+            Err(err) => {
+                warn!("gRPC relay receive failed: {}", err);
+                return;
+            }
+        };
+        if routed {
+            if parts.is_empty() {
+                continue;
+            }
+            peer = Some(parts.remove(0));
+        }
+        match Message::unpack(&parts) {
+            Ok(msg) => {
+                if outbound.blocking_send(Frame::from_message(&msg)).is_err() {
+                    return;
+                }
+            }
+            Err(err) => warn!("gRPC relay dropped malformed message: {}", err),
+        }
+    }
+}
+
+fn send_frame(socket: &zmq::Socket, peer: Option<&[u8]>, routed: bool, frame: Frame) {
+    let msg = match frame.into_message() {
+        Ok(msg) => msg,
+        Err(err) => {
+            warn!("gRPC relay dropped malformed frame: {}", err);
+            return;
+        }
+    };
+    let (header, body) = match msg.pack() {
+        Ok(parts) => parts,
+        Err(err) => {
+            warn!("gRPC relay failed to pack message: {}", err);
+            return;
+        }
+    };
+    let result = if routed {
+        let Some(peer) = peer else {
+            debug!(
+                "gRPC relay dropped {:?} before the worker connected",
+                msg.header.opcode
+            );
+            return;
+        };
+        socket.send_multipart([peer, header.as_slice(), body.as_slice()], 0)
+    } else {
+        socket.send_multipart([header.as_slice(), body.as_slice()], 0)
+    };
+    if let Err(err) = result {
+        warn!("gRPC relay send failed: {}", err);
+    }
+}
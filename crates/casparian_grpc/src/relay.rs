@@ -0,0 +1,158 @@
+//! Sentinel side: serves the `Link` service in front of the ROUTER socket.
+
+use crate::proto::link_server::{Link, LinkServer};
+use crate::proto::Frame;
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::thread::JoinHandle;
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{debug, info, warn};
+
+/// Frames buffered per direction of one worker stream.
+const STREAM_BUFFER: usize = 64;
+
+/// Running gRPC listener; stopped on drop.
+///
+/// Every `Exchange` stream gets its own DEALER connected to the Sentinel's
+/// ROUTER at `endpoint`, so each gRPC worker appears to the Sentinel as a
+/// separate ZMQ peer.
+pub struct GrpcRelay {
+    local_addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl GrpcRelay {
+    /// Bind `addr` (e.g. "0.0.0.0:7070") and relay to the ROUTER bound at
+    /// `endpoint` in `context`.
+    pub fn start(addr: &str, context: &zmq::Context, endpoint: &str) -> Result<Self> {
+        let bind_addr: SocketAddr = addr
+            .parse()
+            .with_context(|| format!("Invalid gRPC address: {}", addr))?;
+        let listener = std::net::TcpListener::bind(bind_addr)
+            .with_context(|| format!("Failed to bind gRPC on {}", addr))?;
+        listener
+            .set_nonblocking(true)
+            .context("Failed to configure gRPC listener")?;
+        let local_addr = listener
+            .local_addr()
+            .context("Failed to read gRPC address")?;
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("sentinel-grpc")
+            .enable_all()
+            .build()
+            .context("Failed to start gRPC runtime")?;
+        let service = LinkService {
+            context: context.clone(),
+            endpoint: endpoint.to_string(),
+        };
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("sentinel-grpc".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    let listener = match tokio::net::TcpListener::from_std(listener) {
+                        Ok(listener) => listener,
+                        Err(err) => {
+                            warn!("gRPC listener failed: {}", err);
+                            return;
+                        }
+                    };
+                    let result = tonic::transport::Server::builder()
+                        .add_service(LinkServer::new(service))
+                        .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                            let _ = shutdown_rx.await;
+                        })
+                        .await;
+                    if let Err(err) = result {
+                        warn!("gRPC server failed: {}", err);
+                    }
+                });
+            })
+            .context("Failed to spawn gRPC thread")?;
+        info!("gRPC transport listening on grpc://{}", local_addr);
+        Ok(Self {
+            local_addr,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for GrpcRelay {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+struct LinkService {
+    context: zmq::Context,
+    endpoint: String,
+}
+
+type FrameStream = Pin<Box<dyn Stream<Item = Result<Frame, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Link for LinkService {
+    type ExchangeStream = FrameStream;
+
+    async fn exchange(
+        &self,
+        request: Request<Streaming<Frame>>,
+    ) -> Result<Response<Self::ExchangeStream>, Status> {
+        let peer = request.remote_addr();
+        let socket = self
+            .context
+            .socket(zmq::DEALER)
+            .and_then(|socket| {
+                socket.set_linger(0)?;
+                socket.connect(&self.endpoint)?;
+                Ok(socket)
+            })
+            .map_err(|err| Status::unavailable(format!("Sentinel socket: {}", err)))?;
+
+        let (to_zmq, from_grpc) = std::sync::mpsc::channel();
+        let (to_grpc, from_zmq) = mpsc::channel(STREAM_BUFFER);
+        std::thread::Builder::new()
+            .name("sentinel-grpc-pump".to_string())
+            .spawn(move || crate::pump::run(socket, false, from_grpc, to_grpc))
+            .map_err(|err| Status::internal(format!("Failed to spawn relay: {}", err)))?;
+
+        let mut inbound = request.into_inner();
+        tokio::spawn(async move {
+            loop {
+                match inbound.message().await {
+                    Ok(Some(frame)) => {
+                        if to_zmq.send(frame).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(status) => {
+                        debug!("gRPC worker stream from {:?} ended: {}", peer, status);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(
+            ReceiverStream::new(from_zmq).map(Ok),
+        )))
+    }
+}
@@ -0,0 +1,72 @@
+//! End-to-end: a DEALER talks to a ROUTER through GrpcLink and GrpcRelay.
+
+use casparian_grpc::{GrpcLink, GrpcRelay};
+use casparian_protocol::types::{HeartbeatPayload, HeartbeatStatus, IdentifyPayload};
+use casparian_protocol::{JobId, Message, OpCode};
+
+fn recv(socket: &zmq::Socket) -> Vec<Vec<u8>> {
+    assert!(socket.poll(zmq::POLLIN, 10_000).unwrap() > 0, "timed out");
+    socket.recv_multipart(0).unwrap()
+}
+
+fn send(socket: &zmq::Socket, identity: Option<&[u8]>, opcode: OpCode, payload: Vec<u8>) {
+    let (header, body) = Message::new(opcode, JobId::new(1), payload)
+        .unwrap()
+        .pack()
+        .unwrap();
+    match identity {
+        Some(identity) => socket.send_multipart([identity, &header, &body], 0),
+        None => socket.send_multipart([&header, &body], 0),
+    }
+    .unwrap();
+}
+
+#[test]
+fn test_worker_and_sentinel_exchange_over_grpc() {
+    let context = zmq::Context::new();
+    let router = context.socket(zmq::ROUTER).unwrap();
+    router.bind("inproc://sentinel-test").unwrap();
+    let relay = GrpcRelay::start("127.0.0.1:0", &context, "inproc://sentinel-test").unwrap();
+
+    let uri = format!("grpc://{}", relay.local_addr());
+    let link = GrpcLink::connect(&uri, &context).unwrap();
+    let dealer = context.socket(zmq::DEALER).unwrap();
+    dealer.connect(link.endpoint()).unwrap();
+
+    let identify = IdentifyPayload {
+        capabilities: vec!["*".to_string()],
+        worker_id: Some("grpc-worker".to_string()),
+        protocol_versions: Default::default(),
+        protocol_features: Default::default(),
+    };
+    send(
+        &dealer,
+        None,
+        OpCode::Identify,
+        serde_json::to_vec(&identify).unwrap(),
+    );
+    let parts = recv(&router);
+    assert_eq!(parts.len(), 3);
+    let msg = Message::unpack(&parts[1..]).unwrap();
+    assert_eq!(msg.header.opcode, OpCode::Identify);
+    let received: IdentifyPayload = serde_json::from_slice(&msg.payload).unwrap();
+    assert_eq!(received.worker_id.as_deref(), Some("grpc-worker"));
+
+    let heartbeat = HeartbeatPayload {
+        status: HeartbeatStatus::Idle,
+        active_job_count: 0,
+        active_job_ids: Vec::new(),
+        load: None,
+    };
+    send(
+        &router,
+        Some(&parts[0]),
+        OpCode::Heartbeat,
+        serde_json::to_vec(&heartbeat).unwrap(),
+    );
+    let reply = Message::unpack(&recv(&dealer)).unwrap();
+    assert_eq!(reply.header.opcode, OpCode::Heartbeat);
+    assert_eq!(reply.header.job_id, JobId::new(1));
+    let received: HeartbeatPayload = serde_json::from_slice(&reply.payload).unwrap();
+    assert!(matches!(received.status, HeartbeatStatus::Idle));
+}
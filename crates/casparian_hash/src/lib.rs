@@ -0,0 +1,198 @@
+//! Streaming blake3 hashing of source files.
+//!
+//! [`hash_file`] hashes exactly the bytes on disk: the file is read in
+//! fixed-size chunks (never loaded whole), files of [`PARALLEL_THRESHOLD`]
+//! bytes or more are hashed chunk by chunk on the rayon pool, and the hash is
+//! rejected if the file's size or mtime changed while it was being read.
+//!
+//! The [`FileFingerprint`] returned with the hash is the cache key: a caller
+//! that kept `(path, fingerprint) -> hash` can skip rehashing while
+//! [`FileFingerprint::of`] still matches.
+
+use std::fs::{File, Metadata};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Read size for sequential hashing.
+const READ_CHUNK: usize = 1 << 20;
+/// Read size for parallel hashing; each chunk is split across the rayon pool.
+const PARALLEL_CHUNK: usize = 16 << 20;
+/// Files at least this large are hashed in parallel.
+pub const PARALLEL_THRESHOLD: u64 = 32 << 20;
+
+#[derive(Debug, thiserror::Error)]
+pub enum HashError {
+    #[error("failed to read '{path}': {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("'{path}' changed while it was being hashed")]
+    Changed { path: PathBuf },
+}
+
+/// Size and modification time identifying one generation of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileFingerprint {
+    pub size: u64,
+    /// Modification time, Unix milliseconds
+    pub mtime_ms: i64,
+}
+
+impl FileFingerprint {
+    pub fn of(path: &Path) -> Result<Self, HashError> {
+        let metadata = std::fs::metadata(path).map_err(|source| io_error(path, source))?;
+        Ok(Self::from_metadata(&metadata))
+    }
+
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        let mtime_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .and_then(|since| i64::try_from(since.as_millis()).ok())
+            .unwrap_or(0);
+        Self {
+            size: metadata.len(),
+            mtime_ms,
+        }
+    }
+}
+
+/// Hash of a file and the generation it was computed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHash {
+    /// Hex-encoded blake3 hash
+    pub hash: String,
+    pub fingerprint: FileFingerprint,
+}
+
+/// Hash the contents of `path`.
+pub fn hash_file(path: &Path) -> Result<FileHash, HashError> {
+    let file = File::open(path).map_err(|source| io_error(path, source))?;
+    let before =
+        FileFingerprint::from_metadata(&file.metadata().map_err(|source| io_error(path, source))?);
+    let (hash, bytes) = if before.size >= PARALLEL_THRESHOLD {
+        hash_parallel(file)
+    } else {
+        hash_reader(file)
+    }
+    .map_err(|source| io_error(path, source))?;
+    let after = FileFingerprint::of(path)?;
+    if bytes != before.size || after != before {
+        return Err(HashError::Changed {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(FileHash {
+        hash,
+        fingerprint: before,
+    })
+}
+
+/// Hash everything `reader` yields; returns the hex hash and the byte count.
+pub fn hash_reader<R: Read>(mut reader: R) -> io::Result<(String, u64)> {
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; READ_CHUNK];
+    let mut total = 0u64;
+    loop {
+        let read = read_full(&mut reader, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        total += read as u64;
+    }
+    Ok((hasher.finalize().to_hex().to_string(), total))
+}
+
+/// Like [`hash_reader`], hashing each large chunk on the rayon pool. The
+/// result is identical to sequential hashing.
+fn hash_parallel<R: Read>(mut reader: R) -> io::Result<(String, u64)> {
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; PARALLEL_CHUNK];
+    let mut total = 0u64;
+    loop {
+        let read = read_full(&mut reader, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update_rayon(&buffer[..read]);
+        total += read as u64;
+    }
+    Ok((hasher.finalize().to_hex().to_string(), total))
+}
+
+/// Fill `buffer` unless EOF comes first. blake3 requires whole chunks
+/// between updates to hash in parallel, so short reads are topped up.
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+fn io_error(path: &Path, source: io::Error) -> HashError {
+    HashError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn file_with(bytes: &[u8]) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(bytes).unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn test_hash_file_matches_one_shot_hash() {
+        let data: Vec<u8> = (0..3 * READ_CHUNK + 17).map(|i| (i % 251) as u8).collect();
+        let file = file_with(&data);
+        let hashed = hash_file(file.path()).unwrap();
+        assert_eq!(hashed.hash, blake3::hash(&data).to_hex().to_string());
+        assert_eq!(hashed.fingerprint.size, data.len() as u64);
+        assert_eq!(
+            hashed.fingerprint,
+            FileFingerprint::of(file.path()).unwrap()
+        );
+
+        let empty = file_with(b"");
+        assert_eq!(
+            hash_file(empty.path()).unwrap().hash,
+            blake3::hash(b"").to_hex().to_string()
+        );
+    }
+
+    #[test]
+    fn test_parallel_hash_matches_sequential() {
+        let data: Vec<u8> = (0..PARALLEL_CHUNK + 4096)
+            .map(|i| (i % 239) as u8)
+            .collect();
+        let (parallel, bytes) = hash_parallel(data.as_slice()).unwrap();
+        assert_eq!(bytes, data.len() as u64);
+        assert_eq!(parallel, hash_reader(data.as_slice()).unwrap().0);
+    }
+
+    #[test]
+    fn test_missing_file_is_an_io_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = hash_file(&dir.path().join("missing.csv")).unwrap_err();
+        assert!(matches!(err, HashError::Io { .. }));
+    }
+}
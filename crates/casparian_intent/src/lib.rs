@@ -438,6 +438,12 @@ pub enum StateMachineError {
 
     #[error("state is terminal: {0}")]
     TerminalState(IntentState),
+
+    #[error("no checkpoint for session {0}")]
+    CheckpointNotFound(SessionId),
+
+    #[error("persistence error: {0}")]
+    Persistence(#[source] PersistenceError),
 }
 
 /// State machine manager for a session.
@@ -519,6 +525,45 @@ impl StateMachine {
         self.history.push(transition.clone());
         transition
     }
+
+    /// Snapshot the current state and full history.
+    pub fn checkpoint(&self) -> StateMachineCheckpoint {
+        StateMachineCheckpoint {
+            current: self.current,
+            history: self.history.clone(),
+        }
+    }
+
+    /// Persist a checkpoint of this machine for `session_id`.
+    pub fn save<P: StateMachinePersistence + ?Sized>(
+        &self,
+        session_id: SessionId,
+        store: &P,
+    ) -> Result<(), StateMachineError> {
+        store
+            .save_checkpoint(session_id, &self.checkpoint())
+            .map_err(StateMachineError::Persistence)
+    }
+
+    /// Rebuild a machine from the last checkpoint saved for `session_id`.
+    pub fn resume<P: StateMachinePersistence + ?Sized>(
+        session_id: SessionId,
+        store: &P,
+    ) -> Result<Self, StateMachineError> {
+        let checkpoint = store
+            .load_checkpoint(session_id)
+            .map_err(StateMachineError::Persistence)?
+            .ok_or(StateMachineError::CheckpointNotFound(session_id))?;
+        Ok(Self::from_checkpoint(checkpoint))
+    }
+
+    /// Rebuild a machine from a checkpoint.
+    pub fn from_checkpoint(checkpoint: StateMachineCheckpoint) -> Self {
+        Self {
+            current: checkpoint.current,
+            history: checkpoint.history,
+        }
+    }
 }
 
 impl Default for StateMachine {
@@ -527,6 +572,36 @@ impl Default for StateMachine {
     }
 }
 
+// ============================================================================
+// Persistence - Checkpoint/resume across process restarts
+// ============================================================================
+
+/// Error returned by `StateMachinePersistence` implementations.
+pub type PersistenceError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A state machine's current state and full transition history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateMachineCheckpoint {
+    pub current: IntentState,
+    pub history: Vec<StateTransition>,
+}
+
+/// Durable storage for state machine checkpoints, keyed by session.
+///
+/// `save_checkpoint` replaces any previous checkpoint for the session.
+pub trait StateMachinePersistence {
+    fn save_checkpoint(
+        &self,
+        session_id: SessionId,
+        checkpoint: &StateMachineCheckpoint,
+    ) -> Result<(), PersistenceError>;
+
+    fn load_checkpoint(
+        &self,
+        session_id: SessionId,
+    ) -> Result<Option<StateMachineCheckpoint>, PersistenceError>;
+}
+
 // ============================================================================
 // Session - The core session record
 // ============================================================================
@@ -768,6 +843,60 @@ mod tests {
         assert!(matches!(result, Err(StateMachineError::TerminalState(_))));
     }
 
+    #[derive(Default)]
+    struct MemoryStore(
+        std::cell::RefCell<std::collections::HashMap<SessionId, StateMachineCheckpoint>>,
+    );
+
+    impl StateMachinePersistence for MemoryStore {
+        fn save_checkpoint(
+            &self,
+            session_id: SessionId,
+            checkpoint: &StateMachineCheckpoint,
+        ) -> Result<(), PersistenceError> {
+            self.0.borrow_mut().insert(session_id, checkpoint.clone());
+            Ok(())
+        }
+
+        fn load_checkpoint(
+            &self,
+            session_id: SessionId,
+        ) -> Result<Option<StateMachineCheckpoint>, PersistenceError> {
+            Ok(self.0.borrow().get(&session_id).cloned())
+        }
+    }
+
+    #[test]
+    fn test_state_machine_resume_from_checkpoint() {
+        let store = MemoryStore::default();
+        let session_id = SessionId::new();
+
+        let mut sm = StateMachine::new();
+        sm.transition(IntentState::ScanCorpus).unwrap();
+        sm.transition_with_reason(
+            IntentState::ProposeSelection,
+            Some("scan done".to_string()),
+            Some("agent".to_string()),
+        )
+        .unwrap();
+        sm.save(session_id, &store).unwrap();
+
+        let mut resumed = StateMachine::resume(session_id, &store).unwrap();
+        assert_eq!(resumed.current(), IntentState::ProposeSelection);
+        assert_eq!(resumed.history().len(), 2);
+        assert_eq!(resumed.history()[1].reason.as_deref(), Some("scan done"));
+        resumed
+            .transition(IntentState::AwaitingSelectionApproval)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_state_machine_resume_missing_checkpoint() {
+        let store = MemoryStore::default();
+        let result = StateMachine::resume(SessionId::new(), &store);
+        assert!(matches!(result, Err(StateMachineError::CheckpointNotFound(_))));
+    }
+
     #[test]
     fn test_state_machine_force_transition() {
         let mut sm = StateMachine::new();
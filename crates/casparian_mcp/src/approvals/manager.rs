@@ -7,7 +7,7 @@ use crate::types::ApprovalSummary;
 use anyhow::{Context, Result};
 use casparian_db::DbConnection;
 use casparian_protocol::{
-    ApiJobId as ProtocolJobId, Approval as ProtocolApproval,
+    ApiJobId as ProtocolJobId, Approval as ProtocolApproval, ApprovalAuditEntry,
     ApprovalOperation as ProtocolApprovalOperation, ApprovalStatus as ProtocolApprovalStatus,
 };
 use casparian_sentinel::{ApiStorage, ControlClient, DEFAULT_CONTROL_ADDR};
@@ -80,15 +80,19 @@ impl ApprovalManager {
     }
 
     /// Create a new approval request.
+    ///
+    /// The request comes back approved, with `auto_approved_by` set, when an
+    /// approval rule matches it.
     pub fn create_approval(
         &self,
         operation: ApprovalOperation,
         summary: ApprovalSummary,
     ) -> Result<ApprovalRequest> {
-        let approval = ApprovalRequest::new(operation, summary);
-        let protocol_op = to_protocol_operation(&approval.operation);
+        let mut approval = ApprovalRequest::new(operation, summary);
+        let protocol_op =
+            to_protocol_operation(&approval.operation, approval.summary.file_count as u64);
         let expires_in = approval.expires_at.signed_duration_since(Utc::now());
-        match &self.backend {
+        let auto_approved_by = match &self.backend {
             ApprovalBackend::Db { .. } => {
                 let storage = self.storage()?;
                 storage.create_approval(
@@ -96,8 +100,7 @@ impl ApprovalManager {
                     &protocol_op,
                     &approval.summary.description,
                     expires_in,
-                )?;
-                Ok(approval)
+                )?
             }
             ApprovalBackend::Control { .. } => {
                 let client = self.control_client()?;
@@ -106,10 +109,16 @@ impl ApprovalManager {
                     protocol_op,
                     &approval.summary.description,
                     expires_in.num_seconds(),
-                )?;
-                Ok(approval)
+                )?
             }
+        };
+        if auto_approved_by.is_some() {
+            approval.status = ApprovalStatus::Approved {
+                approved_at: Utc::now(),
+            };
+            approval.auto_approved_by = auto_approved_by;
         }
+        Ok(approval)
     }
 
     /// Get an approval by ID.
@@ -128,37 +137,62 @@ impl ApprovalManager {
         }
     }
 
-    /// Approve an approval request.
-    pub fn approve(&self, id: &ApprovalId) -> Result<bool> {
+    /// Approve an approval request, recording `actor` and `justification`
+    /// in the audit log.
+    pub fn approve(
+        &self,
+        id: &ApprovalId,
+        actor: Option<&str>,
+        justification: Option<&str>,
+    ) -> Result<bool> {
         match &self.backend {
             ApprovalBackend::Db { .. } => {
                 let storage = self.storage()?;
-                storage.approve(id.as_ref(), None)
+                storage.approve(id.as_ref(), actor, justification)
             }
             ApprovalBackend::Control { .. } => {
                 let client = self.control_client()?;
-                let (success, _message) = client.approve(id.as_ref())?;
+                let (success, _message) = client.approve(id.as_ref(), actor, justification)?;
                 Ok(success)
             }
         }
     }
 
     /// Reject an approval request.
-    pub fn reject(&self, id: &ApprovalId, reason: Option<String>) -> Result<bool> {
+    pub fn reject(
+        &self,
+        id: &ApprovalId,
+        reason: Option<String>,
+        actor: Option<&str>,
+    ) -> Result<bool> {
         match &self.backend {
             ApprovalBackend::Db { .. } => {
                 let storage = self.storage()?;
-                storage.reject(id.as_ref(), None, reason.as_deref())
+                storage.reject(id.as_ref(), actor, reason.as_deref())
             }
             ApprovalBackend::Control { .. } => {
                 let client = self.control_client()?;
                 let reason = reason.unwrap_or_else(|| "Rejected via MCP".to_string());
-                let (success, _message) = client.reject(id.as_ref(), &reason)?;
+                let (success, _message) = client.reject(id.as_ref(), &reason, actor)?;
                 Ok(success)
             }
         }
     }
 
+    /// List audit log entries for an approval, oldest first.
+    pub fn list_audit(&self, id: &ApprovalId) -> Result<Vec<ApprovalAuditEntry>> {
+        match &self.backend {
+            ApprovalBackend::Db { .. } => {
+                let storage = self.storage()?;
+                storage.list_approval_audit(id.as_ref())
+            }
+            ApprovalBackend::Control { .. } => {
+                let client = self.control_client()?;
+                client.list_approval_audit(id.as_ref())
+            }
+        }
+    }
+
     /// Set the job ID after approval is processed.
     pub fn set_job_id(&self, approval_id: &ApprovalId, job_id: String) -> Result<()> {
         let parsed: ProtocolJobId = job_id
@@ -241,7 +275,7 @@ impl ApprovalManager {
 // Conversion Helpers
 // ============================================================================
 
-fn to_protocol_operation(op: &ApprovalOperation) -> ProtocolApprovalOperation {
+fn to_protocol_operation(op: &ApprovalOperation, file_count: u64) -> ProtocolApprovalOperation {
     match op {
         ApprovalOperation::Run {
             plugin_ref,
@@ -260,7 +294,7 @@ fn to_protocol_operation(op: &ApprovalOperation) -> ProtocolApprovalOperation {
                 plugin_name,
                 plugin_version,
                 input_dir: input_dir.to_string_lossy().to_string(),
-                file_count: 0,
+                file_count,
                 output: Some(output.clone()),
             }
         }
@@ -364,6 +398,7 @@ fn from_protocol_approval(pa: ProtocolApproval) -> Result<ApprovalRequest> {
         expires_at,
         status,
         job_id: pa.job_id.map(|id| id.as_u64().to_string()),
+        auto_approved_by: None,
     })
 }
 
@@ -419,11 +454,15 @@ mod tests {
             .unwrap();
         let id = approval.approval_id.clone();
 
-        let approved = manager.approve(&id).unwrap();
+        let approved = manager.approve(&id, Some("alice"), None).unwrap();
         assert!(approved);
 
         let approval = manager.get_approval(&id).unwrap().unwrap();
         assert!(matches!(approval.status, ApprovalStatus::Approved { .. }));
+
+        let audit = manager.list_audit(&id).unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].actor.as_deref(), Some("alice"));
     }
 
     #[test]
@@ -436,7 +475,7 @@ mod tests {
         let id = approval.approval_id.clone();
 
         let rejected = manager
-            .reject(&id, Some("Not approved".to_string()))
+            .reject(&id, Some("Not approved".to_string()), None)
             .unwrap();
         assert!(rejected);
 
@@ -444,6 +483,40 @@ mod tests {
         assert!(matches!(approval.status, ApprovalStatus::Rejected { .. }));
     }
 
+    #[test]
+    fn test_auto_approve_by_rule() {
+        use crate::approvals::{approval_policy, ApprovalRule, RuleCondition, POLICY_ACTOR};
+
+        let (manager, _temp) = create_test_manager();
+        let storage = manager.storage().unwrap();
+        approval_policy::set_rule(
+            storage.connection(),
+            &ApprovalRule {
+                name: "small-runs".to_string(),
+                plugins: vec!["test_parser".to_string()],
+                condition: RuleCondition::SmallRun { max_files: 10 },
+            },
+        )
+        .unwrap();
+
+        let approval = manager
+            .create_approval(test_operation(), test_summary())
+            .unwrap();
+        assert!(matches!(approval.status, ApprovalStatus::Approved { .. }));
+        assert_eq!(approval.auto_approved_by.as_deref(), Some("small-runs"));
+
+        let audit = manager.list_audit(&approval.approval_id).unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].actor.as_deref(), Some(POLICY_ACTOR));
+        assert_eq!(audit[0].rule.as_deref(), Some("small-runs"));
+
+        let mut summary = test_summary();
+        summary.file_count = 11;
+        let approval = manager.create_approval(test_operation(), summary).unwrap();
+        assert!(matches!(approval.status, ApprovalStatus::Pending));
+        assert!(approval.auto_approved_by.is_none());
+    }
+
     #[test]
     fn test_list_pending() {
         let (manager, _temp) = create_test_manager();
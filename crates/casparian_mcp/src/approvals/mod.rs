@@ -15,6 +15,14 @@
 //! casparian mcp approve <id> --reject
 //! ```
 //!
+//! # Auto-approval
+//!
+//! Low-risk operations can skip the human: approval rules (see
+//! [`approval_policy`]) approve runs of trusted plugins, runs over few files
+//! and additive schema promotions as soon as they are requested. The
+//! Sentinel applies the same rules, and the audit log records the decision
+//! with actor `policy` and the matched rule.
+//!
 //! # Storage
 //!
 //! Approvals are stored in the state store via Sentinel's control plane (`cf_api_approvals`).
@@ -24,6 +32,9 @@ mod manager;
 #[cfg(test)]
 mod store;
 
+pub use casparian_sentinel::db::approval_policy::{
+    self, ApprovalRule, RuleCondition, POLICY_ACTOR,
+};
 pub use manager::ApprovalManager;
 
 use crate::types::{ApprovalSummary, PluginRef};
@@ -91,6 +102,10 @@ pub struct ApprovalRequest {
     /// Job ID created after approval (if applicable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub job_id: Option<String>,
+
+    /// Approval rule that approved the request when it was created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_approved_by: Option<String>,
 }
 
 impl ApprovalRequest {
@@ -105,6 +120,7 @@ impl ApprovalRequest {
             expires_at: now + chrono::Duration::hours(DEFAULT_EXPIRY_HOURS),
             status: ApprovalStatus::Pending,
             job_id: None,
+            auto_approved_by: None,
         }
     }
 
@@ -3,10 +3,12 @@
 //! Commands are sent from tool handlers to the Core thread.
 //! Each command includes a Responder channel for returning results.
 
+use super::event::BacktestIterationInfo;
 use crate::approvals::{ApprovalId, ApprovalOperation, ApprovalRequest};
 use crate::jobs::{Job, JobId, JobProgress, JobSpec};
 use crate::types::ApprovalSummary;
 use anyhow::Result;
+use casparian_protocol::ApprovalAuditEntry;
 use std::sync::mpsc::Sender;
 
 /// One-shot channel for returning results from Core
@@ -44,6 +46,13 @@ pub enum Command {
         respond: Responder<Result<()>>,
     },
 
+    /// Report one finished backtest iteration
+    ReportBacktestIteration {
+        id: JobId,
+        iteration: BacktestIterationInfo,
+        respond: Responder<Result<()>>,
+    },
+
     /// Complete a job successfully
     CompleteJob {
         id: JobId,
@@ -72,6 +81,13 @@ pub enum Command {
         respond: Responder<Result<Vec<Job>>>,
     },
 
+    /// Reserve job IDs for the per-file runs of a job
+    ReserveRunJobIds {
+        id: JobId,
+        count: u64,
+        respond: Responder<Result<Vec<casparian_protocol::JobId>>>,
+    },
+
     // ========================================================================
     // Approval Lifecycle Commands
     // ========================================================================
@@ -91,6 +107,8 @@ pub enum Command {
     /// Approve a pending request
     ApproveRequest {
         id: ApprovalId,
+        actor: Option<String>,
+        justification: Option<String>,
         respond: Responder<Result<bool>>,
     },
 
@@ -98,9 +116,16 @@ pub enum Command {
     RejectRequest {
         id: ApprovalId,
         reason: Option<String>,
+        actor: Option<String>,
         respond: Responder<Result<bool>>,
     },
 
+    /// List audit log entries for an approval
+    ListApprovalAudit {
+        id: ApprovalId,
+        respond: Responder<Result<Vec<ApprovalAuditEntry>>>,
+    },
+
     /// Set job ID on an approval (after job creation)
     SetApprovalJobId {
         approval_id: ApprovalId,
@@ -1,9 +1,26 @@
 //! Event types emitted by Core
 //!
 //! Events are broadcast to interested subscribers when state changes occur.
-//! This enables reactive patterns without polling.
+//! This enables reactive patterns without polling; the server forwards them
+//! to MCP clients subscribed to the affected resources (see
+//! [`crate::subscriptions`]).
 
+use crate::approvals::ApprovalId;
 use crate::jobs::{JobId, JobProgress};
+use serde::Serialize;
+
+/// Outcome of one backtest iteration (one input file).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BacktestIterationInfo {
+    /// 1-based index of the file just processed
+    pub iteration: u64,
+    pub files_total: u64,
+    /// Whether this file parsed successfully
+    pub passed: bool,
+    /// Running totals so far
+    pub files_passed: u64,
+    pub files_failed: u64,
+}
 
 /// Events emitted by the Core thread
 #[derive(Debug, Clone)]
@@ -31,11 +48,22 @@ pub enum Event {
 
     /// A job was cancelled
     JobCancelled { job_id: JobId },
+
+    /// A backtest job finished one input file
+    BacktestIteration {
+        job_id: JobId,
+        iteration: BacktestIterationInfo,
+    },
+
     // ========================================================================
-    // Approval Events (to be added in Commit 2)
+    // Approval Events
     // ========================================================================
-    // ApprovalCreated { approval_id: ApprovalId },
-    // ApprovalApproved { approval_id: ApprovalId },
-    // ApprovalRejected { approval_id: ApprovalId },
-    // ApprovalExpired { approval_id: ApprovalId },
+    /// An approval request was created
+    ApprovalCreated { approval_id: ApprovalId },
+
+    /// An approval request was approved
+    ApprovalApproved { approval_id: ApprovalId },
+
+    /// An approval request was rejected
+    ApprovalRejected { approval_id: ApprovalId },
 }
@@ -16,7 +16,7 @@ mod event;
 
 pub use casparian_worker::cancel::CancellationToken;
 pub use command::{Command, Responder};
-pub use event::Event;
+pub use event::{BacktestIterationInfo, Event};
 
 use crate::approvals::ApprovalManager;
 use crate::jobs::{Job, JobId, JobManager, JobProgress, JobSpec};
@@ -103,6 +103,19 @@ impl CoreHandle {
         })?
     }
 
+    /// Report one finished backtest iteration
+    pub fn report_backtest_iteration(
+        &self,
+        id: JobId,
+        iteration: BacktestIterationInfo,
+    ) -> Result<()> {
+        self.send_and_wait(|respond| Command::ReportBacktestIteration {
+            id,
+            iteration,
+            respond,
+        })?
+    }
+
     /// Complete a job successfully
     pub fn complete_job(&self, id: JobId, result: serde_json::Value) -> Result<()> {
         self.send_and_wait(|respond| Command::CompleteJob {
@@ -131,6 +144,15 @@ impl CoreHandle {
         })?
     }
 
+    /// Reserve job IDs for the per-file runs of a job
+    pub fn reserve_run_job_ids(
+        &self,
+        id: JobId,
+        count: u64,
+    ) -> Result<Vec<casparian_protocol::JobId>> {
+        self.send_and_wait(|respond| Command::ReserveRunJobIds { id, count, respond })?
+    }
+
     // ========================================================================
     // Approval Methods
     // ========================================================================
@@ -157,19 +179,43 @@ impl CoreHandle {
     }
 
     /// Approve a pending request
-    pub fn approve(&self, id: crate::approvals::ApprovalId) -> Result<bool> {
-        self.send_and_wait(|respond| Command::ApproveRequest { id, respond })?
+    pub fn approve(
+        &self,
+        id: crate::approvals::ApprovalId,
+        actor: Option<String>,
+        justification: Option<String>,
+    ) -> Result<bool> {
+        self.send_and_wait(|respond| Command::ApproveRequest {
+            id,
+            actor,
+            justification,
+            respond,
+        })?
     }
 
     /// Reject a pending request
-    pub fn reject(&self, id: crate::approvals::ApprovalId, reason: Option<String>) -> Result<bool> {
+    pub fn reject(
+        &self,
+        id: crate::approvals::ApprovalId,
+        reason: Option<String>,
+        actor: Option<String>,
+    ) -> Result<bool> {
         self.send_and_wait(|respond| Command::RejectRequest {
             id,
             reason,
+            actor,
             respond,
         })?
     }
 
+    /// List audit log entries for an approval
+    pub fn list_approval_audit(
+        &self,
+        id: crate::approvals::ApprovalId,
+    ) -> Result<Vec<casparian_protocol::ApprovalAuditEntry>> {
+        self.send_and_wait(|respond| Command::ListApprovalAudit { id, respond })?
+    }
+
     /// Set job ID on an approval
     pub fn set_approval_job_id(
         &self,
@@ -331,6 +377,18 @@ impl Core {
                 let _ = respond.send(result);
             }
 
+            Command::ReportBacktestIteration {
+                id,
+                iteration,
+                respond,
+            } => {
+                let _ = self.events.send(Event::BacktestIteration {
+                    job_id: id,
+                    iteration,
+                });
+                let _ = respond.send(Ok(()));
+            }
+
             Command::CompleteJob {
                 id,
                 result,
@@ -375,6 +433,11 @@ impl Core {
                 let _ = respond.send(result);
             }
 
+            Command::ReserveRunJobIds { id, count, respond } => {
+                let result = self.job_manager.reserve_run_job_ids(&id, count);
+                let _ = respond.send(result);
+            }
+
             // ====================================================================
             // Approval Commands
             // ====================================================================
@@ -384,6 +447,16 @@ impl Core {
                 respond,
             } => {
                 let result = self.approval_manager.create_approval(operation, summary);
+                if let Ok(ref approval) = result {
+                    let _ = self.events.send(Event::ApprovalCreated {
+                        approval_id: approval.approval_id.clone(),
+                    });
+                    if approval.auto_approved_by.is_some() {
+                        let _ = self.events.send(Event::ApprovalApproved {
+                            approval_id: approval.approval_id.clone(),
+                        });
+                    }
+                }
                 let _ = respond.send(result);
             }
 
@@ -392,17 +465,40 @@ impl Core {
                 let _ = respond.send(result);
             }
 
-            Command::ApproveRequest { id, respond } => {
-                let result = self.approval_manager.approve(&id);
+            Command::ApproveRequest {
+                id,
+                actor,
+                justification,
+                respond,
+            } => {
+                let result =
+                    self.approval_manager
+                        .approve(&id, actor.as_deref(), justification.as_deref());
+                if let Ok(true) = result {
+                    let _ = self
+                        .events
+                        .send(Event::ApprovalApproved { approval_id: id });
+                }
                 let _ = respond.send(result);
             }
 
             Command::RejectRequest {
                 id,
                 reason,
+                actor,
                 respond,
             } => {
-                let result = self.approval_manager.reject(&id, reason);
+                let result = self.approval_manager.reject(&id, reason, actor.as_deref());
+                if let Ok(true) = result {
+                    let _ = self
+                        .events
+                        .send(Event::ApprovalRejected { approval_id: id });
+                }
+                let _ = respond.send(result);
+            }
+
+            Command::ListApprovalAudit { id, respond } => {
+                let result = self.approval_manager.list_audit(&id);
                 let _ = respond.send(result);
             }
 
@@ -223,7 +223,8 @@ impl DbApprovalStore {
             // Update status if changed
             match &approval.status {
                 ApprovalStatus::Approved { .. } => {
-                    self.storage.approve(approval.approval_id.as_ref(), None)?;
+                    self.storage
+                        .approve(approval.approval_id.as_ref(), None, None)?;
                     if let Some(job_id) = &approval.job_id {
                         let parsed: u64 = job_id
                             .parse()
@@ -276,7 +277,7 @@ impl DbApprovalStore {
 
     /// Approve an approval request.
     pub fn approve(&self, id: &ApprovalId) -> Result<bool> {
-        self.storage.approve(id.as_ref(), None)
+        self.storage.approve(id.as_ref(), None, None)
     }
 
     /// Reject an approval request.
@@ -531,6 +532,7 @@ fn from_protocol_approval(pa: casparian_protocol::Approval) -> Result<ApprovalRe
         expires_at,
         status,
         job_id: pa.job_id.map(|id| id.as_u64().to_string()),
+        auto_approved_by: None,
     })
 }
 
@@ -0,0 +1,470 @@
+//! Session export/import for support cases.
+//!
+//! `SessionBundle::export` packages one session into a single zip: the
+//! manifest, the state-machine history, the decision log, all proposals
+//! (including schema intents), the backtest reports and iterations, the
+//! sample values found in those reports, and optionally the session's tape.
+//!
+//! Sample values are redacted on the way out (`ExportOptions::redaction`,
+//! hashed by default), both in the exported reports and in `samples.jsonl`.
+//! Tapes are already redacted by the tape writer and are copied as-is.
+//!
+//! `SessionArchive` reads an exported zip without touching any session
+//! store. `SessionArchive::unpack` materializes it as a session under a
+//! separate root with read-only files, so a maintainer can point the tools
+//! at it (`CASP_SESSION_DIR`) to reproduce an issue.
+//!
+//! Archive layout:
+//! ```text
+//! bundle.json            ArchiveInfo
+//! manifest.json
+//! state_history.jsonl
+//! approvals.jsonl
+//! proposals/*.json
+//! reports/*.json, reports/*.jsonl
+//! samples.jsonl          SampleRecord per redacted sample set
+//! tape/{file_name}       optional
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::session::{SessionBundle, SessionError, StateHistoryEntry};
+use super::types::{DecisionRecord, SessionId, SessionManifest};
+use crate::types::{RedactionMode, RedactionPolicy};
+
+/// Version of the archive layout written by `SessionBundle::export`.
+pub const SESSION_ARCHIVE_VERSION: u32 = 1;
+
+const ARCHIVE_INFO: &str = "bundle.json";
+const SAMPLES: &str = "samples.jsonl";
+const TAPE_DIR: &str = "tape";
+
+/// Files copied from the session directory, when present.
+const SESSION_FILES: &[&str] = &["manifest.json", "state_history.jsonl", "approvals.jsonl"];
+/// Session subdirectories copied in full.
+const SESSION_DIRS: &[&str] = &["proposals", "reports"];
+
+/// Options for `SessionBundle::export`.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Tape recorded during the session, stored as `tape/{file_name}`
+    pub tape: Option<PathBuf>,
+    /// Redaction applied to sample values
+    pub redaction: RedactionPolicy,
+}
+
+/// Contents summary stored as `bundle.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveInfo {
+    pub version: u32,
+    pub session_id: SessionId,
+    pub exported_at: DateTime<Utc>,
+    /// Session state at export time
+    pub state: String,
+    pub redaction_mode: RedactionMode,
+    /// Archive paths of the session files, sorted
+    pub files: Vec<String>,
+    /// Archive path of the tape, if one was included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tape: Option<String>,
+    /// Number of records in `samples.jsonl`
+    pub sample_count: usize,
+}
+
+/// Redacted sample values of one column, taken from a backtest report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleRecord {
+    /// Archive path of the report the values came from
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    pub values: Vec<String>,
+}
+
+impl SessionBundle {
+    /// Package the session into a zip at `path`.
+    pub fn export(
+        &self,
+        path: &Path,
+        options: &ExportOptions,
+    ) -> Result<ArchiveInfo, SessionError> {
+        let manifest = self.read_manifest()?;
+        let session_dir = self.session_dir();
+
+        let mut entries: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        for name in SESSION_FILES {
+            let source = session_dir.join(name);
+            if source.is_file() {
+                entries.insert(name.to_string(), fs::read(&source)?);
+            }
+        }
+        let mut samples = Vec::new();
+        for dir in SESSION_DIRS {
+            let source = session_dir.join(dir);
+            if !source.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(&source)? {
+                let entry = entry?;
+                if !entry.file_type()?.is_file() {
+                    continue;
+                }
+                let Some(file_name) = entry.file_name().to_str().map(String::from) else {
+                    continue;
+                };
+                // Skip in-flight atomic writes
+                if file_name.starts_with(".tmp_") {
+                    continue;
+                }
+                let name = format!("{}/{}", dir, file_name);
+                let mut content = fs::read(entry.path())?;
+                if *dir == "reports" {
+                    content = redact_report(&name, &content, &options.redaction, &mut samples)?;
+                }
+                entries.insert(name, content);
+            }
+        }
+
+        let file = fs::File::create(path)?;
+        let mut zip = ZipWriter::new(file);
+        let file_options =
+            SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        for (name, content) in &entries {
+            zip.start_file(name.as_str(), file_options)?;
+            zip.write_all(content)?;
+        }
+
+        let mut sample_lines = String::new();
+        for sample in &samples {
+            sample_lines.push_str(&serde_json::to_string(sample)?);
+            sample_lines.push('\n');
+        }
+        zip.start_file(SAMPLES, file_options)?;
+        zip.write_all(sample_lines.as_bytes())?;
+
+        let tape = match &options.tape {
+            Some(tape_path) => {
+                let file_name = tape_path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .ok_or_else(|| SessionError::InvalidPath(tape_path.display().to_string()))?;
+                let name = format!("{}/{}", TAPE_DIR, file_name);
+                zip.start_file(name.as_str(), file_options)?;
+                zip.write_all(&fs::read(tape_path)?)?;
+                Some(name)
+            }
+            None => None,
+        };
+
+        let info = ArchiveInfo {
+            version: SESSION_ARCHIVE_VERSION,
+            session_id: self.session_id,
+            exported_at: Utc::now(),
+            state: manifest.state,
+            redaction_mode: options.redaction.base.mode,
+            files: entries.into_keys().collect(),
+            tape,
+            sample_count: samples.len(),
+        };
+        zip.start_file(ARCHIVE_INFO, file_options)?;
+        zip.write_all(serde_json::to_string_pretty(&info)?.as_bytes())?;
+        zip.finish()?;
+
+        Ok(info)
+    }
+}
+
+/// Read-only view of an exported session archive.
+#[derive(Debug)]
+pub struct SessionArchive {
+    info: ArchiveInfo,
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+impl SessionArchive {
+    /// Load an archive written by `SessionBundle::export`.
+    pub fn open(path: &Path) -> Result<Self, SessionError> {
+        let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+        let mut entries = BTreeMap::new();
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index)?;
+            if file.is_dir() {
+                continue;
+            }
+            if file.enclosed_name().is_none() {
+                return Err(SessionError::InvalidArchive(format!(
+                    "unsafe entry path '{}'",
+                    file.name()
+                )));
+            }
+            let name = file.name().to_string();
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)?;
+            entries.insert(name, content);
+        }
+
+        let info: ArchiveInfo = match entries.remove(ARCHIVE_INFO) {
+            Some(content) => serde_json::from_slice(&content)?,
+            None => {
+                return Err(SessionError::InvalidArchive(format!(
+                    "missing {}",
+                    ARCHIVE_INFO
+                )))
+            }
+        };
+        if info.version > SESSION_ARCHIVE_VERSION {
+            return Err(SessionError::InvalidArchive(format!(
+                "archive version {} is newer than supported version {}",
+                info.version, SESSION_ARCHIVE_VERSION
+            )));
+        }
+
+        Ok(Self { info, entries })
+    }
+
+    pub fn info(&self) -> &ArchiveInfo {
+        &self.info
+    }
+
+    /// Raw content of an archive entry
+    pub fn file(&self, name: &str) -> Option<&[u8]> {
+        self.entries.get(name).map(Vec::as_slice)
+    }
+
+    pub fn manifest(&self) -> Result<SessionManifest, SessionError> {
+        let content = self
+            .file("manifest.json")
+            .ok_or_else(|| SessionError::InvalidArchive("missing manifest.json".to_string()))?;
+        Ok(serde_json::from_slice(content)?)
+    }
+
+    pub fn state_history(&self) -> Result<Vec<StateHistoryEntry>, SessionError> {
+        self.read_jsonl("state_history.jsonl")
+    }
+
+    pub fn decisions(&self) -> Result<Vec<DecisionRecord>, SessionError> {
+        self.read_jsonl("approvals.jsonl")
+    }
+
+    pub fn samples(&self) -> Result<Vec<SampleRecord>, SessionError> {
+        self.read_jsonl(SAMPLES)
+    }
+
+    /// Archive paths of the proposals, sorted
+    pub fn proposals(&self) -> Vec<&str> {
+        self.entries
+            .keys()
+            .filter(|name| name.starts_with("proposals/"))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Archive paths of the reports, sorted
+    pub fn reports(&self) -> Vec<&str> {
+        self.entries
+            .keys()
+            .filter(|name| name.starts_with("reports/"))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn tape(&self) -> Option<&[u8]> {
+        self.info.tape.as_deref().and_then(|name| self.file(name))
+    }
+
+    /// Write the session under `root` with read-only files.
+    ///
+    /// Fails if `root` already holds a session with the same id.
+    pub fn unpack(&self, root: &Path) -> Result<SessionBundle, SessionError> {
+        let bundle = SessionBundle::new(self.info.session_id, root.to_path_buf());
+        let session_dir = bundle.session_dir();
+        if session_dir.exists() {
+            return Err(SessionError::AlreadyExists(self.info.session_id));
+        }
+        bundle.init()?;
+        for (name, content) in &self.entries {
+            let target = session_dir.join(name);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, content)?;
+            let mut permissions = fs::metadata(&target)?.permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(&target, permissions)?;
+        }
+        Ok(bundle)
+    }
+
+    fn read_jsonl<T: DeserializeOwned>(&self, name: &str) -> Result<Vec<T>, SessionError> {
+        let Some(content) = self.file(name) else {
+            return Ok(vec![]);
+        };
+        let mut records = Vec::new();
+        for line in String::from_utf8_lossy(content).lines() {
+            if !line.trim().is_empty() {
+                records.push(serde_json::from_str(line)?);
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// Redact the `sample_values` of a report (JSON or JSONL) and collect them.
+fn redact_report(
+    name: &str,
+    content: &[u8],
+    redaction: &RedactionPolicy,
+    samples: &mut Vec<SampleRecord>,
+) -> Result<Vec<u8>, SessionError> {
+    if name.ends_with(".jsonl") {
+        let mut redacted = String::new();
+        for line in String::from_utf8_lossy(content).lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut value: Value = serde_json::from_str(line)?;
+            redact_samples(name, &mut value, redaction, samples);
+            redacted.push_str(&serde_json::to_string(&value)?);
+            redacted.push('\n');
+        }
+        return Ok(redacted.into_bytes());
+    }
+    if name.ends_with(".json") {
+        let mut value: Value = serde_json::from_slice(content)?;
+        redact_samples(name, &mut value, redaction, samples);
+        return Ok(serde_json::to_string_pretty(&value)?.into_bytes());
+    }
+    Ok(content.to_vec())
+}
+
+fn redact_samples(
+    source: &str,
+    value: &mut Value,
+    redaction: &RedactionPolicy,
+    samples: &mut Vec<SampleRecord>,
+) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Array(raw)) = map.get("sample_values") {
+                let raw: Vec<String> = raw
+                    .iter()
+                    .map(|item| match item {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect();
+                let values = redaction.redact_samples(&raw);
+                samples.push(SampleRecord {
+                    source: source.to_string(),
+                    column: map.get("column").and_then(Value::as_str).map(String::from),
+                    expected: map
+                        .get("expected")
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                    values: values.clone(),
+                });
+                map.insert(
+                    "sample_values".to_string(),
+                    Value::Array(values.into_iter().map(Value::String).collect()),
+                );
+            }
+            for (key, field) in map.iter_mut() {
+                if key != "sample_values" {
+                    redact_samples(source, field, redaction, samples);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact_samples(source, item, redaction, samples);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::session::SessionStore;
+    use crate::intent::state::IntentState;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_and_unpack_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SessionStore::with_root(temp_dir.path().join("sessions"));
+        let bundle = store.create_session("parse trades", None, None).unwrap();
+        bundle.update_state(IntentState::ProposeSelection).unwrap();
+        bundle
+            .write_report(
+                "backtest",
+                "job1",
+                &serde_json::json!({
+                    "top_k_violations": [{
+                        "violation_type": "type_mismatch",
+                        "example_contexts": [{
+                            "column": "price",
+                            "expected": "float64",
+                            "sample_values": ["4111-1111", "n/a"],
+                        }],
+                    }],
+                }),
+            )
+            .unwrap();
+        let tape_path = temp_dir.path().join("session.tape");
+        fs::write(&tape_path, "{}\n").unwrap();
+
+        let archive_path = temp_dir.path().join("session.zip");
+        let options = ExportOptions {
+            tape: Some(tape_path),
+            ..Default::default()
+        };
+        let info = bundle.export(&archive_path, &options).unwrap();
+        assert_eq!(info.state, IntentState::ProposeSelection.as_str());
+        assert_eq!(info.sample_count, 1);
+
+        let archive = SessionArchive::open(&archive_path).unwrap();
+        assert_eq!(archive.manifest().unwrap().intent_text, "parse trades");
+        let history = archive.state_history().unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].to, IntentState::ProposeSelection.as_str());
+        assert_eq!(archive.reports(), vec!["reports/backtest_job1.json"]);
+        assert_eq!(archive.tape(), Some(&b"{}\n"[..]));
+
+        let samples = archive.samples().unwrap();
+        assert_eq!(samples[0].column.as_deref(), Some("price"));
+        assert!(samples[0]
+            .values
+            .iter()
+            .all(|value| value.starts_with("[hash:")));
+        let report = String::from_utf8_lossy(archive.file("reports/backtest_job1.json").unwrap());
+        assert!(!report.contains("4111-1111"));
+
+        let import_root = temp_dir.path().join("imported");
+        let imported = archive.unpack(&import_root).unwrap();
+        assert_eq!(imported.session_id, bundle.session_id);
+        assert_eq!(imported.read_state_history().unwrap(), history);
+        let manifest_path = imported.session_dir().join("manifest.json");
+        assert!(fs::metadata(manifest_path)
+            .unwrap()
+            .permissions()
+            .readonly());
+        assert!(matches!(
+            archive.unpack(&import_root),
+            Err(SessionError::AlreadyExists(_))
+        ));
+    }
+}
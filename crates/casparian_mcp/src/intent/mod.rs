@@ -10,14 +10,16 @@
 //! - State machine for workflow progression
 
 pub mod confidence;
+pub mod export;
 pub mod fileset;
 pub mod session;
 pub mod state;
 pub mod types;
 
 pub use confidence::ConfidenceScore;
+pub use export::{ArchiveInfo, ExportOptions, SampleRecord, SessionArchive};
 pub use fileset::FileSetStore;
-pub use session::FileSetEntry;
+pub use session::{FileSetEntry, StateHistoryEntry};
 pub use session::{SessionBundle, SessionStore};
 pub use state::{IntentState, StateMachine, StateTransition};
 pub use types::ConfidenceLabel;
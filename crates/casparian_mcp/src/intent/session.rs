@@ -3,7 +3,7 @@
 //! Session bundles persist the full decision history and artifacts
 //! needed to reproduce outcomes.
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
@@ -35,6 +35,12 @@ pub enum SessionError {
 
     #[error("invalid session path: {0}")]
     InvalidPath(String),
+
+    #[error("zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("invalid session archive: {0}")]
+    InvalidArchive(String),
 }
 
 // ============================================================================
@@ -47,6 +53,7 @@ pub enum SessionError {
 /// ```text
 /// sessions/{session_id}/
 ///   manifest.json
+///   state_history.jsonl
 ///   corpora/
 ///     corpus_manifest.jsonl
 ///     filesets/
@@ -118,13 +125,56 @@ impl SessionBundle {
         Ok(manifest)
     }
 
-    /// Update the manifest state
+    /// Update the manifest state, recording the transition in the state history
     pub fn update_state(&self, state: IntentState) -> Result<(), SessionError> {
         let mut manifest = self.read_manifest()?;
-        manifest.state = state.as_str().to_string();
+        let to = state.as_str().to_string();
+        if manifest.state != to {
+            self.append_state_history(&StateHistoryEntry {
+                timestamp: Utc::now(),
+                from: manifest.state.clone(),
+                to: to.clone(),
+            })?;
+        }
+        manifest.state = to;
         self.write_manifest(&manifest)
     }
 
+    /// Path to the state history log
+    pub fn state_history_path(&self) -> PathBuf {
+        self.session_dir().join("state_history.jsonl")
+    }
+
+    fn append_state_history(&self, entry: &StateHistoryEntry) -> Result<(), SessionError> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.state_history_path())?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Read all state transitions, oldest first
+    pub fn read_state_history(&self) -> Result<Vec<StateHistoryEntry>, SessionError> {
+        let path = self.state_history_path();
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+
+        let file = fs::File::open(&path)?;
+        let reader = BufReader::new(file);
+        let mut entries = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+
+        Ok(entries)
+    }
+
     /// Add an artifact reference to the manifest
     pub fn add_artifact(&self, kind: &str, reference: &str) -> Result<(), SessionError> {
         let mut manifest = self.read_manifest()?;
@@ -535,6 +585,14 @@ impl Default for SessionStore {
 // Supporting Types
 // ============================================================================
 
+/// One state-machine transition of a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateHistoryEntry {
+    pub timestamp: DateTime<Utc>,
+    pub from: String,
+    pub to: String,
+}
+
 /// Entry in the corpus manifest
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CorpusEntry {
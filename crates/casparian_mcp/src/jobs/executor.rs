@@ -31,7 +31,7 @@
 //! to avoid holding locks during I/O operations.
 
 use super::{JobId, JobProgress, JobSpec, JobState};
-use crate::core::{CancellationToken, CoreHandle};
+use crate::core::{BacktestIterationInfo, CancellationToken, CoreHandle};
 use anyhow::{Context, Result};
 use serde_json::json;
 use std::collections::HashMap;
@@ -335,6 +335,9 @@ impl JobExecutor {
             }));
         }
 
+        // Each file runs under its own job ID, linked to this job
+        let run_job_ids = self.core.reserve_run_job_ids(*job_id, total_files as u64)?;
+
         // Update progress - starting
         self.update_progress(job_id, "scanning", 0, Some(total_files as u64), None);
 
@@ -369,14 +372,15 @@ impl JobExecutor {
             );
 
             // Run parser on file
-            let ctx = create_run_context(idx, &parser_path);
-            match runtime.run_file(&ctx, file_path, &cancel_token) {
+            let ctx = create_run_context(idx, run_job_ids[idx], &parser_path);
+            let file_passed = match runtime.run_file(&ctx, file_path, &cancel_token) {
                 Ok(outputs) => {
                     passed += 1;
                     for info in outputs.output_info {
                         let count = all_outputs.entry(info.name.clone()).or_insert(0);
                         *count += 1;
                     }
+                    true
                 }
                 Err(e) => {
                     failed += 1;
@@ -391,8 +395,21 @@ impl JobExecutor {
                         file_path.display(),
                         e
                     );
+                    false
                 }
-            }
+            };
+
+            // Push the iteration to subscribers (via CoreHandle - no locks)
+            let _ = self.core.report_backtest_iteration(
+                *job_id,
+                BacktestIterationInfo {
+                    iteration: idx as u64 + 1,
+                    files_total: total_files as u64,
+                    passed: file_passed,
+                    files_passed: passed as u64,
+                    files_failed: failed as u64,
+                },
+            );
         }
 
         // Final progress update
@@ -459,6 +476,9 @@ impl JobExecutor {
             }));
         }
 
+        // Each file runs under its own job ID, linked to this job
+        let run_job_ids = self.core.reserve_run_job_ids(*job_id, total_files as u64)?;
+
         self.update_progress(job_id, "starting", 0, Some(total_files as u64), None);
 
         let runtime = NativeSubprocessRuntime::new();
@@ -485,7 +505,7 @@ impl JobExecutor {
                 Some(&format!("Processing {}", file_path.display())),
             );
 
-            let ctx = create_run_context(idx, &parser_path);
+            let ctx = create_run_context(idx, run_job_ids[idx], &parser_path);
             match runtime.run_file(&ctx, file_path, &cancel_token) {
                 Ok(outputs) => {
                     processed += 1;
@@ -642,12 +662,9 @@ fn find_input_files(input_dir: &str) -> Result<Vec<PathBuf>> {
 
 fn create_run_context(
     file_idx: usize,
+    run_job_id: casparian_protocol::JobId,
     parser_path: &std::path::Path,
 ) -> casparian_worker::runtime::RunContext {
-    use casparian_protocol::JobId as ProtoJobId;
-
-    let proto_job_id = ProtoJobId::new(file_idx as u64);
-
     // Provide wildcard schema hash - backtest validates outputs but doesn't require
     // exact schema matching (that's what it's testing)
     let mut schema_hashes = HashMap::new();
@@ -655,12 +672,17 @@ fn create_run_context(
     schema_hashes.insert("*".to_string(), "backtest".to_string());
 
     casparian_worker::runtime::RunContext {
-        job_id: proto_job_id,
+        job_id: run_job_id,
         file_id: file_idx as i64,
         entrypoint: parser_path.to_string_lossy().to_string(),
         env_hash: None,
+        lockfile_content: None,
         source_code: None,
         schema_hashes,
+        limits: casparian_protocol::types::ResourceLimits::default(),
+        byte_range: None,
+        csv_dialect: None,
+        scratch_dir: None,
     }
 }
 
@@ -708,4 +730,37 @@ mod tests {
         // Shutdown core
         let _ = core.shutdown();
     }
+
+    #[test]
+    fn test_file_runs_are_linked_to_their_job() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let db_path = temp.path().join("test.sqlite");
+        let input_dir = temp.path().join("input");
+        std::fs::create_dir(&input_dir).unwrap();
+        std::fs::write(input_dir.join("a.csv"), "id\n1\n").unwrap();
+        std::fs::write(input_dir.join("b.csv"), "id\n2\n").unwrap();
+        // Not executable, so every file fails fast; the runs still get IDs
+        let parser = temp.path().join("parser");
+        std::fs::write(&parser, "").unwrap();
+
+        let (core, _events, _thread) = spawn_core(db_path.clone()).unwrap();
+        let (executor, _handle) = JobExecutor::new(core.clone());
+        let spec = JobSpec::Backtest {
+            plugin_ref: crate::types::PluginRef::path(&parser),
+            input_dir: input_dir.to_string_lossy().to_string(),
+            schemas: None,
+            redaction: None,
+        };
+        let job = core.create_job(spec, None).unwrap();
+        executor.execute_job(job.id);
+
+        let conn = casparian_db::DbConnection::open_sqlite(&db_path).unwrap();
+        let storage = casparian_sentinel::ApiStorage::new(conn);
+        let run_job_ids = storage.queue_jobs_for(job.id).unwrap();
+        assert_eq!(run_job_ids.len(), 2);
+        assert!(run_job_ids.iter().all(|id| id.as_u64() != job.id.as_u64()));
+        assert_eq!(storage.api_job_for(run_job_ids[0]).unwrap(), Some(job.id));
+
+        let _ = core.shutdown();
+    }
 }
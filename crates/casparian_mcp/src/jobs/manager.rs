@@ -334,6 +334,19 @@ impl JobManager {
         }
     }
 
+    /// Reserve one job ID per file a job runs, linked to the job so the runs
+    /// can be traced back to it.
+    pub fn reserve_run_job_ids(
+        &self,
+        id: &JobId,
+        count: u64,
+    ) -> Result<Vec<casparian_protocol::JobId>> {
+        match &self.backend {
+            JobBackend::Db { .. } => self.storage()?.reserve_queue_jobs(*id, count),
+            JobBackend::Control { .. } => self.control_client()?.reserve_api_job_ids(*id, count),
+        }
+    }
+
     /// List jobs with optional filter
     pub fn list_jobs(&self, status_filter: Option<&str>, limit: usize) -> Result<Vec<Job>> {
         let status = match status_filter {
@@ -353,7 +366,7 @@ impl JobManager {
             }
             JobBackend::Control { .. } => {
                 let client = self.control_client()?;
-                let jobs = client.list_api_jobs(status, Some(limit as i64), Some(0))?;
+                let jobs = client.list_api_jobs(status, None, Some(limit as i64), Some(0))?;
                 jobs.into_iter().map(from_protocol_job).collect()
             }
         }
@@ -9,7 +9,8 @@
 //! # Design
 //!
 //! Long-running operations (backtest, run) return immediately with a job_id.
-//! Clients poll for progress via `casparian_job_status`.
+//! Clients poll for progress via `casparian_job_status`, or subscribe to
+//! `casparian://jobs/{job_id}` to have progress pushed (see `crate::subscriptions`).
 //!
 //! # Concurrency
 //!
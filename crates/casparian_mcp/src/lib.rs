@@ -68,6 +68,7 @@ pub mod db_store;
 pub mod jobs;
 pub mod redaction;
 pub mod security;
+pub mod subscriptions;
 pub mod tools;
 
 // Sync Core - single-owner state management (Phase 1B)
@@ -76,6 +77,10 @@ pub mod core;
 // Intent Pipeline (non-brittle MCP orchestration)
 pub mod intent;
 
+// LLM client for assisted intent steps
+#[cfg(feature = "llm")]
+pub mod llm;
+
 // Re-exports for convenience
 pub use approvals::{ApprovalId, ApprovalManager, ApprovalRequest, ApprovalStatus};
 pub use db_store::{DbApprovalStore, DbJobStore};
@@ -89,6 +94,7 @@ pub use core::{
 pub use protocol::{ErrorCode, JsonRpcError, JsonRpcRequest, JsonRpcResponse};
 pub use security::{OutputBudget, PathAllowlist, SecurityConfig};
 pub use server::{McpServer, McpServerConfig};
+pub use subscriptions::{ResourceUri, Subscriptions};
 pub use types::{
     ApprovalDecision, ApprovalStatusFilter, ColumnDefinition, DataType, JobStatusFilter, PluginRef,
     RedactionPolicy, SchemaDefinition, SchemaMode, SimpleDataType, ViolationContext,
@@ -0,0 +1,144 @@
+//! Record/replay of LLM responses keyed by prompt hash.
+//!
+//! Each response is one JSON file, `<cache_dir>/<key>.json`, so a recorded
+//! directory can be checked in for CI or attached to a bug report.
+
+use std::path::{Path, PathBuf};
+
+use casparian_protocol::LlmCacheMode;
+use sha2::{Digest, Sha256};
+
+use super::{LlmError, LlmRequest, LlmResponse};
+
+/// Hex SHA-256 of the system and user prompt.
+pub fn prompt_key(request: &LlmRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.system.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(request.prompt.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+#[derive(Debug)]
+pub struct ResponseCache {
+    mode: LlmCacheMode,
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    /// `None` when caching is off; record and replay need `dir`.
+    pub fn new(mode: LlmCacheMode, dir: Option<&Path>) -> Result<Option<Self>, LlmError> {
+        if mode == LlmCacheMode::Off {
+            return Ok(None);
+        }
+        let dir = dir.ok_or_else(|| {
+            LlmError::Cache("cache_dir is required for record/replay".to_string())
+        })?;
+        match mode {
+            LlmCacheMode::Record => std::fs::create_dir_all(dir)
+                .map_err(|e| LlmError::Cache(format!("{}: {}", dir.display(), e)))?,
+            _ if !dir.is_dir() => {
+                return Err(LlmError::Cache(format!(
+                    "replay directory {} does not exist",
+                    dir.display()
+                )))
+            }
+            _ => {}
+        }
+        Ok(Some(Self {
+            mode,
+            dir: dir.to_path_buf(),
+        }))
+    }
+
+    pub fn mode(&self) -> LlmCacheMode {
+        self.mode
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Recorded response for `key`; a miss is `LlmError::ReplayMiss`.
+    pub fn load(&self, key: &str) -> Result<LlmResponse, LlmError> {
+        let path = self.path(key);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(LlmError::ReplayMiss {
+                    key: key.to_string(),
+                    dir: self.dir.clone(),
+                })
+            }
+            Err(e) => return Err(LlmError::Cache(format!("{}: {}", path.display(), e))),
+        };
+        serde_json::from_str(&content)
+            .map_err(|e| LlmError::Cache(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Save `response` under `key`, replacing an earlier recording.
+    pub fn store(&self, key: &str, response: &LlmResponse) -> Result<(), LlmError> {
+        let path = self.path(key);
+        let tmp = path.with_extension("json.tmp");
+        let content =
+            serde_json::to_string_pretty(response).map_err(|e| LlmError::Cache(e.to_string()))?;
+        std::fs::write(&tmp, content)
+            .and_then(|()| std::fs::rename(&tmp, &path))
+            .map_err(|e| LlmError::Cache(format!("{}: {}", path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::TokenUsage;
+
+    fn request(prompt: &str) -> LlmRequest {
+        LlmRequest {
+            system: "system".to_string(),
+            prompt: prompt.to_string(),
+            max_tokens: None,
+        }
+    }
+
+    #[test]
+    fn test_record_then_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = ResponseCache::new(LlmCacheMode::Record, Some(dir.path()))
+            .unwrap()
+            .unwrap();
+        let response = LlmResponse {
+            text: "{}".to_string(),
+            provider: "anthropic".to_string(),
+            model: "model".to_string(),
+            usage: TokenUsage {
+                input_tokens: 5,
+                output_tokens: 1,
+            },
+        };
+        let key = prompt_key(&request("a"));
+        assert_ne!(key, prompt_key(&request("b")));
+        recorder.store(&key, &response).unwrap();
+
+        let replay = ResponseCache::new(LlmCacheMode::Replay, Some(dir.path()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(replay.load(&key).unwrap(), response);
+        let miss = prompt_key(&request("b"));
+        assert!(matches!(
+            replay.load(&miss),
+            Err(LlmError::ReplayMiss { key, .. }) if key == miss
+        ));
+    }
+
+    #[test]
+    fn test_cache_modes() {
+        assert!(ResponseCache::new(LlmCacheMode::Off, None)
+            .unwrap()
+            .is_none());
+        assert!(ResponseCache::new(LlmCacheMode::Replay, None).is_err());
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        assert!(ResponseCache::new(LlmCacheMode::Replay, Some(&missing)).is_err());
+    }
+}
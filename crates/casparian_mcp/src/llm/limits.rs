@@ -0,0 +1,112 @@
+//! Request rate limits and per-session token budgets.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Sliding one-minute window of requests to one provider.
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_minute: u32,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    /// `per_minute` = 0 allows every request.
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// Take a slot for a request at `now`; false if the minute is used up.
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        if self.per_minute == 0 {
+            return true;
+        }
+        while let Some(&oldest) = self.sent.front() {
+            if now.duration_since(oldest) < WINDOW {
+                break;
+            }
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= self.per_minute as usize {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+/// Tokens spent per intent session against a shared limit.
+#[derive(Debug, Default)]
+pub struct TokenBudget {
+    limit: u64,
+    used: Mutex<HashMap<String, u64>>,
+}
+
+impl TokenBudget {
+    /// `limit` = 0 means unlimited.
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            used: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub fn used(&self, session: &str) -> u64 {
+        self.used
+            .lock()
+            .map(|used| used.get(session).copied().unwrap_or(0))
+            .unwrap_or(0)
+    }
+
+    /// Tokens `session` may still spend; `None` when unlimited.
+    pub fn remaining(&self, session: &str) -> Option<u64> {
+        (self.limit > 0).then(|| self.limit.saturating_sub(self.used(session)))
+    }
+
+    pub fn charge(&self, session: &str, tokens: u64) {
+        if let Ok(mut used) = self.used.lock() {
+            *used.entry(session.to_string()).or_insert(0) += tokens;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_window() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(2);
+        assert!(limiter.try_acquire(start));
+        assert!(limiter.try_acquire(start + Duration::from_secs(10)));
+        assert!(!limiter.try_acquire(start + Duration::from_secs(20)));
+        // The first request leaves the window after a minute.
+        assert!(limiter.try_acquire(start + Duration::from_secs(61)));
+        assert!(!limiter.try_acquire(start + Duration::from_secs(62)));
+
+        let mut unlimited = RateLimiter::new(0);
+        assert!((0..100).all(|_| unlimited.try_acquire(start)));
+    }
+
+    #[test]
+    fn test_token_budget() {
+        let budget = TokenBudget::new(100);
+        budget.charge("a", 60);
+        assert_eq!(budget.remaining("a"), Some(40));
+        assert_eq!(budget.remaining("b"), Some(100));
+        budget.charge("a", 60);
+        assert_eq!(budget.remaining("a"), Some(0));
+        assert_eq!(TokenBudget::new(0).remaining("a"), None);
+    }
+}
@@ -0,0 +1,471 @@
+//! LLM client for assisted intent steps (feature `llm`).
+//!
+//! `LlmClient` sends a prompt to the providers of `LlmSettings` in order:
+//! Anthropic, OpenAI, or a local Ollama endpoint. Each provider has its own
+//! requests-per-minute limit; a provider over its limit, misconfigured, or
+//! failing is skipped and the next one is tried. Each intent session has a
+//! token budget, charged with the usage the provider reports.
+//!
+//! Every request sent is recorded on the tape at `LlmSettings.tape_path`,
+//! correlated by session ID. Prompts and responses are hashed by the tape's
+//! redaction policy unless `capture_plaintext` is set; API keys never reach
+//! the tape.
+//!
+//! With `LlmSettings.cache` = record, every response is saved under
+//! `cache_dir` by prompt hash; with replay, responses come only from there
+//! and a missing one is an error, so intent flows run without a network.
+
+mod cache;
+mod limits;
+mod provider;
+pub mod schema;
+mod transport;
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use casparian_protocol::{LlmCacheMode, LlmConfig, LlmSettings};
+use casparian_tape::{EventName, RedactionMode, RedactionPolicy, TapeWriter, TapeWriterConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::warn;
+
+pub use cache::{prompt_key, ResponseCache};
+pub use limits::{RateLimiter, TokenBudget};
+pub use transport::{HttpTransport, LlmTransport, TransportError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LlmError {
+    #[error("No LLM providers configured")]
+    NoProviders,
+
+    #[error("Session token budget exhausted ({used} of {budget} tokens used)")]
+    BudgetExhausted { used: u64, budget: u64 },
+
+    #[error("All LLM providers failed: {}", .0.join("; "))]
+    AllProvidersFailed(Vec<String>),
+
+    #[error("LLM client unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("No recorded LLM response for prompt {key} in {dir} (replay mode)")]
+    ReplayMiss {
+        key: String,
+        dir: std::path::PathBuf,
+    },
+
+    #[error("LLM response cache error: {0}")]
+    Cache(String),
+}
+
+/// One prompt for the model.
+#[derive(Debug, Clone)]
+pub struct LlmRequest {
+    pub system: String,
+    pub prompt: String,
+    /// Completion limit; unset = the provider's `max_tokens`
+    pub max_tokens: Option<usize>,
+}
+
+/// Token counts reported by the provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// Completion from the provider that answered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmResponse {
+    pub text: String,
+    pub provider: String,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+pub struct LlmClient {
+    providers: Vec<LlmConfig>,
+    transport: Box<dyn LlmTransport>,
+    limiters: Vec<Mutex<RateLimiter>>,
+    budget: TokenBudget,
+    tape: Option<TapeWriter>,
+    cache: Option<ResponseCache>,
+}
+
+impl LlmClient {
+    /// Client sending requests over HTTP.
+    pub fn new(settings: &LlmSettings) -> Result<Self, LlmError> {
+        let transport = HttpTransport::new(Duration::from_secs(settings.timeout_secs))
+            .map_err(|e| LlmError::Unavailable(e.to_string()))?;
+        Self::with_transport(settings, Box::new(transport))
+    }
+
+    pub fn with_transport(
+        settings: &LlmSettings,
+        transport: Box<dyn LlmTransport>,
+    ) -> Result<Self, LlmError> {
+        let tape = match &settings.tape_path {
+            Some(path) => {
+                let config = TapeWriterConfig {
+                    redaction: capture_policy(settings.capture_plaintext),
+                    ..Default::default()
+                };
+                let tape = TapeWriter::with_config(path, config).map_err(|e| {
+                    LlmError::Unavailable(format!("LLM tape {}: {}", path.display(), e))
+                })?;
+                Some(tape)
+            }
+            None => None,
+        };
+        Ok(Self {
+            providers: settings.providers.clone(),
+            transport,
+            limiters: settings
+                .providers
+                .iter()
+                .map(|_| Mutex::new(RateLimiter::new(settings.requests_per_minute)))
+                .collect(),
+            budget: TokenBudget::new(settings.session_token_budget),
+            tape,
+            cache: ResponseCache::new(settings.cache, settings.cache_dir.as_deref())?,
+        })
+    }
+
+    /// Process-wide client, created from `settings` on first use. `None`
+    /// when no providers are configured and responses are not replayed.
+    pub fn shared(settings: &LlmSettings) -> Result<Option<&'static LlmClient>, LlmError> {
+        static SHARED: OnceLock<Result<LlmClient, String>> = OnceLock::new();
+        if settings.providers.is_empty() && settings.cache != LlmCacheMode::Replay {
+            return Ok(None);
+        }
+        match SHARED.get_or_init(|| LlmClient::new(settings).map_err(|e| e.to_string())) {
+            Ok(client) => Ok(Some(client)),
+            Err(message) => Err(LlmError::Unavailable(message.clone())),
+        }
+    }
+
+    /// Tokens `session` has spent.
+    pub fn tokens_used(&self, session: &str) -> u64 {
+        self.budget.used(session)
+    }
+
+    /// Complete `request` for `session`, falling back through the providers.
+    pub fn complete(&self, session: &str, request: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        let replaying = self
+            .cache
+            .as_ref()
+            .filter(|cache| cache.mode() == LlmCacheMode::Replay);
+        if self.providers.is_empty() && replaying.is_none() {
+            return Err(LlmError::NoProviders);
+        }
+        let remaining = self.budget.remaining(session);
+        if remaining == Some(0) {
+            return Err(LlmError::BudgetExhausted {
+                used: self.budget.used(session),
+                budget: self.budget.limit(),
+            });
+        }
+
+        let key = prompt_key(request);
+        if let Some(cache) = replaying {
+            let response = cache.load(&key)?;
+            let mut payload = call_payload(&key, &response.provider, &response.model, request);
+            payload["response"] = json!(response.text);
+            payload["usage"] = json!(response.usage);
+            self.record(
+                session,
+                EventName::DomainEvent("LlmResponseReplayed".to_string()),
+                payload,
+            );
+            self.budget.charge(session, response.usage.total());
+            return Ok(response);
+        }
+
+        let mut failures = Vec::new();
+        for (config, limiter) in self.providers.iter().zip(&self.limiters) {
+            let name = provider::provider_name(&config.provider);
+            let max_tokens = request.max_tokens.unwrap_or(config.max_tokens);
+            let max_tokens = match remaining {
+                Some(remaining) => max_tokens.min(usize::try_from(remaining).unwrap_or(usize::MAX)),
+                None => max_tokens,
+            };
+
+            let acquired = limiter
+                .lock()
+                .map(|mut limiter| limiter.try_acquire(Instant::now()))
+                .unwrap_or(false);
+            if !acquired {
+                failures.push(format!("{}: rate limited", name));
+                continue;
+            }
+            let http = match provider::build_request(config, request, max_tokens) {
+                Ok(http) => http,
+                Err(message) => {
+                    failures.push(format!("{}: {}", name, message));
+                    continue;
+                }
+            };
+
+            let started = Instant::now();
+            let result = self
+                .transport
+                .post_json(&http.url, &http.headers, &http.body)
+                .map_err(|e| e.to_string())
+                .and_then(|body| provider::parse_response(&config.provider, &body));
+
+            let mut payload = call_payload(&key, name, &config.model, request);
+            payload["latency_ms"] = json!(started.elapsed().as_millis() as u64);
+            match result {
+                Ok((text, usage)) => {
+                    payload["response"] = json!(text);
+                    payload["usage"] = json!(usage);
+                    self.record(
+                        session,
+                        EventName::DomainEvent("LlmRequestCompleted".to_string()),
+                        payload,
+                    );
+                    self.budget.charge(session, usage.total());
+                    let response = LlmResponse {
+                        text,
+                        provider: name.to_string(),
+                        model: config.model.clone(),
+                        usage,
+                    };
+                    if let Some(cache) = &self.cache {
+                        if let Err(err) = cache.store(&key, &response) {
+                            warn!("Failed to record LLM response: {}", err);
+                        }
+                    }
+                    return Ok(response);
+                }
+                Err(message) => {
+                    payload["error"] = json!(message);
+                    self.record(
+                        session,
+                        EventName::ErrorEvent("LlmRequestFailed".to_string()),
+                        payload,
+                    );
+                    failures.push(format!("{}: {}", name, message));
+                }
+            }
+        }
+        Err(LlmError::AllProvidersFailed(failures))
+    }
+
+    fn record(&self, session: &str, event: EventName, payload: Value) {
+        let Some(tape) = &self.tape else {
+            return;
+        };
+        if let Err(err) = tape.emit(event, Some(session), None, payload) {
+            warn!("Failed to record LLM request on tape: {}", err);
+        }
+    }
+}
+
+/// Tape payload common to every call; `cache_key` names the response file
+/// a replay would read.
+fn call_payload(key: &str, provider: &str, model: &str, request: &LlmRequest) -> Value {
+    json!({
+        "provider": provider,
+        "model": model,
+        "cache_key": key,
+        "prompt": {
+            "system": request.system,
+            "user": request.prompt,
+        },
+    })
+}
+
+/// Tape redaction for LLM calls: prompts and responses are hashed unless
+/// plaintext capture is on.
+fn capture_policy(capture_plaintext: bool) -> RedactionPolicy {
+    if capture_plaintext {
+        return RedactionPolicy::new();
+    }
+    RedactionPolicy::new()
+        .with_rule("prompt.*", RedactionMode::Hash)
+        .with_rule("response", RedactionMode::Hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::LlmProvider;
+    use casparian_tape::TapeReader;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Fails for the OpenAI endpoint, answers everything else.
+    struct FakeTransport {
+        calls: AtomicUsize,
+    }
+
+    impl LlmTransport for FakeTransport {
+        fn post_json(
+            &self,
+            url: &str,
+            _headers: &[(&'static str, String)],
+            _body: &Value,
+        ) -> Result<Value, TransportError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if url.contains("openai") {
+                return Err(TransportError {
+                    status: Some(503),
+                    message: "HTTP 503: overloaded".to_string(),
+                });
+            }
+            Ok(json!({
+                "message": { "content": "{\"amount\": \"float64\"}" },
+                "prompt_eval_count": 40,
+                "eval_count": 10,
+            }))
+        }
+    }
+
+    fn settings(tape_path: Option<std::path::PathBuf>) -> LlmSettings {
+        LlmSettings {
+            providers: vec![
+                LlmConfig {
+                    provider: LlmProvider::OpenAi,
+                    api_key: Some("sk-test".to_string()),
+                    model: "gpt-test".to_string(),
+                    ..Default::default()
+                },
+                LlmConfig {
+                    provider: LlmProvider::Ollama {
+                        endpoint: "http://localhost:11434".to_string(),
+                    },
+                    model: "local-test".to_string(),
+                    ..Default::default()
+                },
+            ],
+            requests_per_minute: 2,
+            session_token_budget: 100,
+            tape_path,
+            ..Default::default()
+        }
+    }
+
+    fn request() -> LlmRequest {
+        LlmRequest {
+            system: "You type columns.".to_string(),
+            prompt: "secret sample values".to_string(),
+            max_tokens: Some(64),
+        }
+    }
+
+    #[test]
+    fn test_fallback_budget_and_tape() {
+        let dir = tempfile::tempdir().unwrap();
+        let tape_path = dir.path().join("llm.tape");
+        let client = LlmClient::with_transport(
+            &settings(Some(tape_path.clone())),
+            Box::new(FakeTransport {
+                calls: AtomicUsize::new(0),
+            }),
+        )
+        .unwrap();
+
+        let response = client.complete("session-a", &request()).unwrap();
+        assert_eq!(response.provider, "ollama");
+        assert_eq!(response.model, "local-test");
+        assert_eq!(response.usage.total(), 50);
+        assert_eq!(client.tokens_used("session-a"), 50);
+        assert_eq!(client.tokens_used("session-b"), 0);
+
+        // Second call spends the rest of the budget; the third is refused.
+        client.complete("session-a", &request()).unwrap();
+        let err = client.complete("session-a", &request()).unwrap_err();
+        assert!(matches!(err, LlmError::BudgetExhausted { used: 100, .. }));
+
+        let text = std::fs::read_to_string(&tape_path).unwrap();
+        assert!(!text.contains("secret sample values"));
+        assert!(!text.contains("sk-test"));
+        let envelopes = TapeReader::open(&tape_path)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let names: Vec<_> = envelopes.iter().map(|e| e.event_name.clone()).collect();
+        assert_eq!(
+            names[1..],
+            [
+                EventName::ErrorEvent("LlmRequestFailed".to_string()),
+                EventName::DomainEvent("LlmRequestCompleted".to_string()),
+                EventName::ErrorEvent("LlmRequestFailed".to_string()),
+                EventName::DomainEvent("LlmRequestCompleted".to_string()),
+            ]
+        );
+        assert_eq!(envelopes[2].correlation_id.as_deref(), Some("session-a"));
+        assert_eq!(envelopes[2].payload["usage"]["output_tokens"], 10);
+    }
+
+    #[test]
+    fn test_rate_limited_providers_fail_over() {
+        let client = LlmClient::with_transport(
+            &LlmSettings {
+                session_token_budget: 0,
+                ..settings(None)
+            },
+            Box::new(FakeTransport {
+                calls: AtomicUsize::new(0),
+            }),
+        )
+        .unwrap();
+        client.complete("s", &request()).unwrap();
+        client.complete("s", &request()).unwrap();
+        // Both providers have used their two requests this minute.
+        match client.complete("s", &request()).unwrap_err() {
+            LlmError::AllProvidersFailed(failures) => {
+                assert_eq!(
+                    failures,
+                    vec!["openai: rate limited", "ollama: rate limited"]
+                );
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_record_then_replay_without_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = LlmClient::with_transport(
+            &LlmSettings {
+                cache: LlmCacheMode::Record,
+                cache_dir: Some(dir.path().to_path_buf()),
+                ..settings(None)
+            },
+            Box::new(FakeTransport {
+                calls: AtomicUsize::new(0),
+            }),
+        )
+        .unwrap();
+        let recorded = recorder.complete("s", &request()).unwrap();
+
+        // No providers configured: answers can only come from the recording.
+        let replay = LlmClient::with_transport(
+            &LlmSettings {
+                cache: LlmCacheMode::Replay,
+                cache_dir: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            Box::new(FakeTransport {
+                calls: AtomicUsize::new(0),
+            }),
+        )
+        .unwrap();
+        assert_eq!(replay.complete("s", &request()).unwrap(), recorded);
+        assert_eq!(replay.tokens_used("s"), 50);
+
+        let other = LlmRequest {
+            prompt: "unseen prompt".to_string(),
+            ..request()
+        };
+        let err = replay.complete("s", &other).unwrap_err();
+        assert!(matches!(err, LlmError::ReplayMiss { .. }));
+    }
+}
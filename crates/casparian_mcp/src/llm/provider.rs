@@ -0,0 +1,206 @@
+//! Request and response formats of each provider's API.
+
+use casparian_protocol::{LlmConfig, LlmProvider};
+use serde_json::{json, Value};
+
+use super::{LlmRequest, TokenUsage};
+
+const ANTHROPIC_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const OPENAI_URL: &str = "https://api.openai.com/v1/chat/completions";
+
+pub(crate) struct HttpRequest {
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Value,
+}
+
+pub(crate) fn provider_name(provider: &LlmProvider) -> &'static str {
+    match provider {
+        LlmProvider::Anthropic => "anthropic",
+        LlmProvider::OpenAi => "openai",
+        LlmProvider::Ollama { .. } => "ollama",
+        LlmProvider::None => "none",
+    }
+}
+
+/// API key from the config, else the provider's usual environment variable.
+fn api_key(config: &LlmConfig, env_var: &str) -> Result<String, String> {
+    config
+        .api_key
+        .clone()
+        .or_else(|| std::env::var(env_var).ok())
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| format!("no API key (set api_key or {})", env_var))
+}
+
+pub(crate) fn build_request(
+    config: &LlmConfig,
+    request: &LlmRequest,
+    max_tokens: usize,
+) -> Result<HttpRequest, String> {
+    match &config.provider {
+        LlmProvider::Anthropic => Ok(HttpRequest {
+            url: ANTHROPIC_URL.to_string(),
+            headers: vec![
+                ("x-api-key", api_key(config, "ANTHROPIC_API_KEY")?),
+                ("anthropic-version", ANTHROPIC_VERSION.to_string()),
+            ],
+            body: json!({
+                "model": config.model,
+                "max_tokens": max_tokens,
+                "temperature": config.temperature,
+                "system": request.system,
+                "messages": [{ "role": "user", "content": request.prompt }],
+            }),
+        }),
+        LlmProvider::OpenAi => Ok(HttpRequest {
+            url: OPENAI_URL.to_string(),
+            headers: vec![(
+                "authorization",
+                format!("Bearer {}", api_key(config, "OPENAI_API_KEY")?),
+            )],
+            body: json!({
+                "model": config.model,
+                "max_tokens": max_tokens,
+                "temperature": config.temperature,
+                "messages": [
+                    { "role": "system", "content": request.system },
+                    { "role": "user", "content": request.prompt },
+                ],
+            }),
+        }),
+        LlmProvider::Ollama { endpoint } => Ok(HttpRequest {
+            url: format!("{}/api/chat", endpoint.trim_end_matches('/')),
+            headers: Vec::new(),
+            body: json!({
+                "model": config.model,
+                "stream": false,
+                "options": {
+                    "temperature": config.temperature,
+                    "num_predict": max_tokens,
+                },
+                "messages": [
+                    { "role": "system", "content": request.system },
+                    { "role": "user", "content": request.prompt },
+                ],
+            }),
+        }),
+        LlmProvider::None => Err("provider 'none' does not serve requests".to_string()),
+    }
+}
+
+/// Completion text and token usage from a provider response.
+pub(crate) fn parse_response(
+    provider: &LlmProvider,
+    body: &Value,
+) -> Result<(String, TokenUsage), String> {
+    let count = |value: &Value| value.as_u64().unwrap_or(0);
+    let (text, usage) = match provider {
+        LlmProvider::Anthropic => {
+            let text = body["content"].as_array().map(|blocks| {
+                blocks
+                    .iter()
+                    .filter(|block| block["type"] == "text")
+                    .filter_map(|block| block["text"].as_str())
+                    .collect::<String>()
+            });
+            let usage = TokenUsage {
+                input_tokens: count(&body["usage"]["input_tokens"]),
+                output_tokens: count(&body["usage"]["output_tokens"]),
+            };
+            (text, usage)
+        }
+        LlmProvider::OpenAi => {
+            let text = body["choices"][0]["message"]["content"]
+                .as_str()
+                .map(str::to_string);
+            let usage = TokenUsage {
+                input_tokens: count(&body["usage"]["prompt_tokens"]),
+                output_tokens: count(&body["usage"]["completion_tokens"]),
+            };
+            (text, usage)
+        }
+        LlmProvider::Ollama { .. } => {
+            let text = body["message"]["content"].as_str().map(str::to_string);
+            let usage = TokenUsage {
+                input_tokens: count(&body["prompt_eval_count"]),
+                output_tokens: count(&body["eval_count"]),
+            };
+            (text, usage)
+        }
+        LlmProvider::None => return Err("provider 'none' does not serve requests".to_string()),
+    };
+    text.map(|text| (text, usage))
+        .ok_or_else(|| "response has no completion text".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> LlmRequest {
+        LlmRequest {
+            system: "system".to_string(),
+            prompt: "prompt".to_string(),
+            max_tokens: None,
+        }
+    }
+
+    #[test]
+    fn test_build_requests() {
+        let anthropic = LlmConfig {
+            provider: LlmProvider::Anthropic,
+            api_key: Some("key-a".to_string()),
+            ..Default::default()
+        };
+        let http = build_request(&anthropic, &request(), 128).unwrap();
+        assert_eq!(http.url, ANTHROPIC_URL);
+        assert!(http.headers.contains(&("x-api-key", "key-a".to_string())));
+        assert_eq!(http.body["system"], "system");
+        assert_eq!(http.body["max_tokens"], 128);
+
+        let ollama = LlmConfig {
+            provider: LlmProvider::Ollama {
+                endpoint: "http://localhost:11434/".to_string(),
+            },
+            model: "llama3".to_string(),
+            ..Default::default()
+        };
+        let http = build_request(&ollama, &request(), 64).unwrap();
+        assert_eq!(http.url, "http://localhost:11434/api/chat");
+        assert!(http.headers.is_empty());
+        assert_eq!(http.body["options"]["num_predict"], 64);
+        assert_eq!(http.body["messages"][1]["content"], "prompt");
+
+        let none = LlmConfig::default();
+        assert!(build_request(&none, &request(), 64).is_err());
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        let (text, usage) = parse_response(
+            &LlmProvider::Anthropic,
+            &json!({
+                "content": [{ "type": "text", "text": "hello" }],
+                "usage": { "input_tokens": 12, "output_tokens": 3 },
+            }),
+        )
+        .unwrap();
+        assert_eq!(text, "hello");
+        assert_eq!(usage.total(), 15);
+
+        let (text, usage) = parse_response(
+            &LlmProvider::OpenAi,
+            &json!({
+                "choices": [{ "message": { "content": "hi" } }],
+                "usage": { "prompt_tokens": 7, "completion_tokens": 2 },
+            }),
+        )
+        .unwrap();
+        assert_eq!(text, "hi");
+        assert_eq!(usage.input_tokens, 7);
+
+        assert!(parse_response(&LlmProvider::OpenAi, &json!({ "error": "x" })).is_err());
+    }
+}
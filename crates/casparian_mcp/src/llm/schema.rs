@@ -0,0 +1,122 @@
+//! Type suggestions for ambiguous schema columns.
+//!
+//! The model only picks among the candidates type inference already found;
+//! anything else in the answer is dropped, and the choice is still put to a
+//! human as the default option of the column's question.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::LlmRequest;
+
+/// Sample values sent per column.
+pub const MAX_SAMPLES: usize = 10;
+
+const SYSTEM_PROMPT: &str = "You choose column types for a data pipeline. \
+For each column, pick exactly one of its candidate types based on the column \
+name and sample values. Answer with a single JSON object mapping column name \
+to the chosen type, and nothing else.";
+
+/// A column type inference could not settle.
+#[derive(Debug, Clone)]
+pub struct AmbiguousColumn {
+    pub name: String,
+    pub candidates: Vec<String>,
+    pub samples: Vec<String>,
+}
+
+/// Prompt asking for one type per column.
+pub fn type_suggestion_request(columns: &[AmbiguousColumn]) -> LlmRequest {
+    let mut prompt = String::from("Columns:\n");
+    for column in columns {
+        let samples: Vec<&str> = column
+            .samples
+            .iter()
+            .take(MAX_SAMPLES)
+            .map(String::as_str)
+            .collect();
+        prompt.push_str(&format!(
+            "- name: {}\n  candidates: {}\n  samples: {}\n",
+            column.name,
+            column.candidates.join(", "),
+            serde_json::to_string(&samples).unwrap_or_default()
+        ));
+    }
+    LlmRequest {
+        system: SYSTEM_PROMPT.to_string(),
+        prompt,
+        // A short JSON object; the name and type per column fit in ~20 tokens.
+        max_tokens: Some(64 + 20 * columns.len()),
+    }
+}
+
+/// Chosen type per column from the model's answer, keeping only choices
+/// among the column's candidates.
+pub fn parse_type_suggestions(text: &str, columns: &[AmbiguousColumn]) -> BTreeMap<String, String> {
+    let mut suggestions = BTreeMap::new();
+    let (Some(start), Some(end)) = (text.find('{'), text.rfind('}')) else {
+        return suggestions;
+    };
+    if end < start {
+        return suggestions;
+    }
+    let Ok(Value::Object(answer)) = serde_json::from_str::<Value>(&text[start..=end]) else {
+        return suggestions;
+    };
+    for column in columns {
+        let Some(choice) = answer.get(&column.name).and_then(Value::as_str) else {
+            continue;
+        };
+        if let Some(candidate) = column
+            .candidates
+            .iter()
+            .find(|candidate| candidate.eq_ignore_ascii_case(choice.trim()))
+        {
+            suggestions.insert(column.name.clone(), candidate.clone());
+        }
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<AmbiguousColumn> {
+        vec![
+            AmbiguousColumn {
+                name: "is_active".to_string(),
+                candidates: vec!["boolean".to_string(), "int64".to_string()],
+                samples: vec!["1".to_string(), "0".to_string()],
+            },
+            AmbiguousColumn {
+                name: "day".to_string(),
+                candidates: vec!["date".to_string(), "string".to_string()],
+                samples: vec!["2024-01-15".to_string()],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_request_lists_candidates() {
+        let request = type_suggestion_request(&columns());
+        assert!(request
+            .prompt
+            .contains("- name: is_active\n  candidates: boolean, int64"));
+        assert!(request.prompt.contains(r#"samples: ["2024-01-15"]"#));
+        assert_eq!(request.max_tokens, Some(104));
+    }
+
+    #[test]
+    fn test_parse_keeps_candidates_only() {
+        let text = "Sure:\n```json\n{\"is_active\": \"Boolean\", \"day\": \"timestamp\", \
+                    \"other\": \"int64\"}\n```";
+        let suggestions = parse_type_suggestions(text, &columns());
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions["is_active"], "boolean");
+
+        assert!(parse_type_suggestions("no idea", &columns()).is_empty());
+        assert!(parse_type_suggestions("} {", &columns()).is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+//! HTTP transport for provider requests.
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+/// Failed provider request; `status` is set when the provider answered.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct TransportError {
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+/// Sends a JSON request and returns the JSON response.
+pub trait LlmTransport: Send + Sync {
+    fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&'static str, String)],
+        body: &Value,
+    ) -> Result<Value, TransportError>;
+}
+
+/// Error bodies are cut to this many characters in messages.
+const ERROR_BODY_CHARS: usize = 200;
+
+pub struct HttpTransport {
+    client: reqwest::blocking::Client,
+}
+
+impl HttpTransport {
+    pub fn new(timeout: Duration) -> Result<Self, TransportError> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| TransportError {
+                status: None,
+                message: format!("Failed to build HTTP client: {}", e),
+            })?;
+        Ok(Self { client })
+    }
+}
+
+impl LlmTransport for HttpTransport {
+    fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&'static str, String)],
+        body: &Value,
+    ) -> Result<Value, TransportError> {
+        let mut request = self.client.post(url).json(body);
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        let response = request.send().map_err(|e| TransportError {
+            status: e.status().map(|status| status.as_u16()),
+            message: e.to_string(),
+        })?;
+        let status = response.status();
+        let text = response.text().map_err(|e| TransportError {
+            status: Some(status.as_u16()),
+            message: e.to_string(),
+        })?;
+        if !status.is_success() {
+            let body: String = text.chars().take(ERROR_BODY_CHARS).collect();
+            return Err(TransportError {
+                status: Some(status.as_u16()),
+                message: format!("HTTP {}: {}", status.as_u16(), body),
+            });
+        }
+        serde_json::from_str(&text).map_err(|e| TransportError {
+            status: Some(status.as_u16()),
+            message: format!("Invalid JSON response: {}", e),
+        })
+    }
+}
@@ -102,6 +102,31 @@ impl JsonRpcResponse {
     }
 }
 
+/// JSON-RPC notification (server → client, no response expected)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcNotification {
+    /// Must be "2.0"
+    pub jsonrpc: String,
+
+    /// Method name
+    pub method: String,
+
+    /// Parameters (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcNotification {
+    /// Create a notification
+    pub fn new(method: impl Into<String>, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: method.into(),
+            params: Some(params),
+        }
+    }
+}
+
 /// Request ID (can be number or string per JSON-RPC spec)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(untagged)]
@@ -249,9 +274,9 @@ pub struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<ToolsCapability>,
 
-    /// Resources capability (not used in v1)
+    /// Resources capability (job and approval subscriptions)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub resources: Option<Value>,
+    pub resources: Option<ResourcesCapability>,
 
     /// Prompts capability (not used in v1)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -271,6 +296,19 @@ pub struct ToolsCapability {
     pub list_changed: bool,
 }
 
+/// Resources capability
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesCapability {
+    /// Whether clients may subscribe to resource updates
+    #[serde(default)]
+    pub subscribe: bool,
+
+    /// Whether the resource list may change
+    #[serde(default)]
+    pub list_changed: bool,
+}
+
 /// Server info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerInfo {
@@ -343,6 +381,61 @@ impl ContentBlock {
     }
 }
 
+/// Resource descriptor for resources/list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    /// Resource URI (e.g., "casparian://jobs")
+    pub uri: String,
+
+    /// Human-readable name
+    pub name: String,
+
+    /// Description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// MIME type of the contents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// Resources list result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourcesListResult {
+    /// Available resources
+    pub resources: Vec<Resource>,
+}
+
+/// Params for resources/read, resources/subscribe, and resources/unsubscribe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUriParams {
+    /// Resource URI
+    pub uri: String,
+}
+
+/// Contents of a resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceContents {
+    /// Resource URI
+    pub uri: String,
+
+    /// MIME type of `text`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+
+    /// Text contents
+    pub text: String,
+}
+
+/// Resources read result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceReadResult {
+    /// Resource contents
+    pub contents: Vec<ResourceContents>,
+}
+
 // ============================================================================
 // MCP Methods
 // ============================================================================
@@ -359,6 +452,16 @@ pub mod methods {
     pub const TOOLS_CALL: &str = "tools/call";
     /// Ping (keepalive)
     pub const PING: &str = "ping";
+    /// List available resources
+    pub const RESOURCES_LIST: &str = "resources/list";
+    /// Read a resource
+    pub const RESOURCES_READ: &str = "resources/read";
+    /// Subscribe to resource updates
+    pub const RESOURCES_SUBSCRIBE: &str = "resources/subscribe";
+    /// Cancel a resource subscription
+    pub const RESOURCES_UNSUBSCRIBE: &str = "resources/unsubscribe";
+    /// Notification that a subscribed resource changed (server → client)
+    pub const RESOURCES_UPDATED: &str = "notifications/resources/updated";
 }
 
 #[cfg(test)]
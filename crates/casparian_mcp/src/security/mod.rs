@@ -3,7 +3,7 @@
 //! Provides security controls for the MCP server:
 //! - Path allowlist: Validates file paths against configured roots
 //! - Output budget: Limits response sizes to prevent OOM
-//! - Redaction: Hashes/truncates sensitive sample values
+//! - Redaction: Hashes/truncates sensitive sample values, paths, and URIs
 //! - Audit logging: Records all tool invocations
 //!
 //! # Design Principles
@@ -19,6 +19,8 @@ pub use audit::AuditLog;
 pub use output_budget::OutputBudget;
 pub use path_allowlist::PathAllowlist;
 
+use crate::types::RedactionPolicy;
+
 /// Combined security configuration
 #[derive(Debug)]
 pub struct SecurityConfig {
@@ -30,6 +32,9 @@ pub struct SecurityConfig {
 
     /// Audit logging (optional)
     pub audit_log: Option<AuditLog>,
+
+    /// Return source paths and artifact URIs in plaintext instead of hashed
+    pub allow_plaintext_uris: bool,
 }
 
 impl SecurityConfig {
@@ -40,6 +45,17 @@ impl SecurityConfig {
     ) -> Result<std::path::PathBuf, SecurityError> {
         self.path_allowlist.validate(path)
     }
+
+    /// Redact a source path or artifact URI for tool output.
+    ///
+    /// Hashed with the default redaction policy unless plaintext is allowed.
+    pub fn redact_uri(&self, uri: &str) -> String {
+        if self.allow_plaintext_uris {
+            uri.to_string()
+        } else {
+            RedactionPolicy::default().redact(uri)
+        }
+    }
 }
 
 /// Security-related errors
@@ -60,6 +76,12 @@ pub enum SecurityError {
     #[error("Row count exceeds budget: {count} rows > {max} rows")]
     TooManyRows { count: usize, max: usize },
 
+    #[error("Full scan of {table} exceeds budget: ~{rows} rows > {max} rows (add a filter)")]
+    FullScanTooLarge { table: String, rows: u64, max: u64 },
+
+    #[error("Estimated scan exceeds budget: ~{bytes} bytes > {max} bytes")]
+    ScanTooLarge { bytes: u64, max: u64 },
+
     #[error("Audit log error: {0}")]
     AuditError(String),
 }
@@ -7,6 +7,8 @@
 //!
 //! - Max response size: 1MB
 //! - Max rows returned: 10,000
+//! - Max rows scanned by an unfiltered full scan: 50,000,000
+//! - Max bytes scanned per query (estimated): 4GB
 
 use super::SecurityError;
 
@@ -18,23 +20,38 @@ pub struct OutputBudget {
 
     /// Maximum number of rows
     max_rows: usize,
+
+    /// Maximum estimated rows read by an unfiltered full table scan
+    max_scan_rows: u64,
+
+    /// Maximum estimated bytes scanned by a query
+    max_scan_bytes: u64,
 }
 
+const DEFAULT_MAX_SCAN_ROWS: u64 = 50_000_000;
+const DEFAULT_MAX_SCAN_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
 impl OutputBudget {
     /// Create a new output budget
     pub fn new(max_bytes: usize, max_rows: usize) -> Self {
         Self {
             max_bytes,
             max_rows,
+            max_scan_rows: DEFAULT_MAX_SCAN_ROWS,
+            max_scan_bytes: DEFAULT_MAX_SCAN_BYTES,
         }
     }
 
+    /// Set the scan limits applied to query plans
+    pub fn with_scan_limits(mut self, max_scan_rows: u64, max_scan_bytes: u64) -> Self {
+        self.max_scan_rows = max_scan_rows;
+        self.max_scan_bytes = max_scan_bytes;
+        self
+    }
+
     /// Default budget (1MB, 10K rows)
     pub fn default_budget() -> Self {
-        Self {
-            max_bytes: 1024 * 1024,
-            max_rows: 10_000,
-        }
+        Self::new(1024 * 1024, 10_000)
     }
 
     /// Get max bytes limit
@@ -47,6 +64,41 @@ impl OutputBudget {
         self.max_rows
     }
 
+    /// Get max rows for an unfiltered full scan
+    pub fn max_scan_rows(&self) -> u64 {
+        self.max_scan_rows
+    }
+
+    /// Get max estimated bytes scanned per query
+    pub fn max_scan_bytes(&self) -> u64 {
+        self.max_scan_bytes
+    }
+
+    /// Check if an unfiltered full scan of `table` is within budget
+    pub fn check_full_scan(&self, table: &str, rows: u64) -> Result<(), SecurityError> {
+        if rows > self.max_scan_rows {
+            Err(SecurityError::FullScanTooLarge {
+                table: table.to_string(),
+                rows,
+                max: self.max_scan_rows,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check if the estimated bytes scanned by a query are within budget
+    pub fn check_scan_bytes(&self, bytes: u64) -> Result<(), SecurityError> {
+        if bytes > self.max_scan_bytes {
+            Err(SecurityError::ScanTooLarge {
+                bytes,
+                max: self.max_scan_bytes,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     /// Check if response size is within budget
     pub fn check_size(&self, size: usize) -> Result<(), SecurityError> {
         if size > self.max_bytes {
@@ -148,6 +200,29 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_scan_limits() {
+        let budget = OutputBudget::new(1000, 100).with_scan_limits(1_000, 64_000);
+
+        assert!(budget.check_full_scan("events", 1_000).is_ok());
+        assert!(matches!(
+            budget.check_full_scan("events", 1_001),
+            Err(SecurityError::FullScanTooLarge {
+                rows: 1_001,
+                max: 1_000,
+                ..
+            })
+        ));
+        assert!(budget.check_scan_bytes(64_000).is_ok());
+        assert!(matches!(
+            budget.check_scan_bytes(64_001),
+            Err(SecurityError::ScanTooLarge {
+                bytes: 64_001,
+                max: 64_000
+            })
+        ));
+    }
+
     #[test]
     fn test_enforce_size() {
         let budget = OutputBudget::new(100, 10);
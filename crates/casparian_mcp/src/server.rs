@@ -6,7 +6,12 @@
 //!
 //! The server runs in a single process, reading JSON-RPC requests from stdin
 //! and writing responses to stdout. Long-running operations return immediately
-//! with a job_id; progress is polled via separate tool calls.
+//! with a job_id; progress is polled via separate tool calls, or pushed to
+//! clients that subscribe to the job resource (see [`crate::subscriptions`]).
+//!
+//! Responses and notifications are written by a dedicated output thread that
+//! owns stdout and the subscription set, so pushed events never interleave
+//! with a response line.
 //!
 //! # Example
 //!
@@ -16,21 +21,26 @@
 //! server.run()?; // Blocking, no async runtime required
 //! ```
 
+use crate::approvals::ApprovalId;
 use crate::core::{spawn_core_with_config, CoreConfig, CoreHandle, Event};
 use crate::jobs::{JobExecutor, JobExecutorHandle};
 use crate::protocol::{
     methods, ContentBlock, InitializeParams, InitializeResult, JsonRpcError, JsonRpcRequest,
-    JsonRpcResponse, ServerCapabilities, ServerInfo, ToolCallParams, ToolCallResult,
-    ToolsCapability, ToolsListResult, JSONRPC_VERSION, MCP_PROTOCOL_VERSION,
+    JsonRpcResponse, Resource, ResourceContents, ResourceReadResult, ResourceUriParams,
+    ResourcesCapability, ResourcesListResult, ServerCapabilities, ServerInfo, ToolCallParams,
+    ToolCallResult, ToolsCapability, ToolsListResult, JSONRPC_VERSION, MCP_PROTOCOL_VERSION,
 };
 use crate::security::{AuditLog, OutputBudget, PathAllowlist, SecurityConfig};
+use crate::subscriptions::{ResourceUri, Subscriptions, APPROVALS_URI, JOBS_URI};
 use crate::tools::ToolRegistry;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use casparian_protocol::LlmSettings;
+use serde::Serialize;
 use serde_json::Value;
 use std::io::{BufRead, Write};
 use std::path::PathBuf;
-use std::sync::mpsc::Receiver;
-use std::thread::JoinHandle;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
 use tracing::{debug, error, info, warn};
 
 /// MCP Server configuration
@@ -51,6 +61,15 @@ pub struct McpServerConfig {
     /// Maximum rows returned from queries
     pub max_rows: usize,
 
+    /// Maximum estimated rows for an unfiltered full scan in a query
+    pub max_scan_rows: u64,
+
+    /// Maximum estimated bytes scanned by a query
+    pub max_scan_bytes: u64,
+
+    /// Queries running longer than this are cancelled
+    pub query_timeout_ms: u64,
+
     /// Path to audit log file
     pub audit_log_path: Option<PathBuf>,
 
@@ -65,6 +84,13 @@ pub struct McpServerConfig {
 
     /// Allow standalone DB writer mode (no Control API)
     pub standalone_db_writer: bool,
+
+    /// Return source paths and artifact URIs unredacted
+    pub allow_plaintext_uris: bool,
+
+    /// LLM providers and limits for assisted schema inference (used with the
+    /// `llm` feature)
+    pub llm: LlmSettings,
 }
 
 impl Default for McpServerConfig {
@@ -78,15 +104,37 @@ impl Default for McpServerConfig {
             allowed_paths: vec![std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))],
             max_response_bytes: 1024 * 1024, // 1MB
             max_rows: 10_000,
+            max_scan_rows: 50_000_000,
+            max_scan_bytes: 4 * 1024 * 1024 * 1024, // 4GB
+            query_timeout_ms: 30_000,
             audit_log_path: Some(casparian_dir.join("mcp_audit.ndjson")),
             db_path: casparian_dir.join("state.sqlite"),
             query_catalog_path: casparian_dir.join("query.duckdb"),
             control_addr: Some(casparian_sentinel::DEFAULT_CONTROL_ADDR.to_string()),
             standalone_db_writer: false,
+            allow_plaintext_uris: false,
+            llm: LlmSettings::default(),
         }
     }
 }
 
+/// Jobs returned when reading `casparian://jobs`
+const RESOURCE_JOB_LIST_LIMIT: usize = 100;
+
+/// Messages for the output thread
+enum Outbound {
+    /// Write a response
+    Response(JsonRpcResponse),
+    /// Start pushing events for a resource
+    Subscribe(ResourceUri),
+    /// Stop pushing events for a resource
+    Unsubscribe(ResourceUri),
+    /// Core event; pushed if it touches a subscribed resource
+    Event(Event),
+    /// Flush and exit
+    Shutdown,
+}
+
 /// MCP Server
 ///
 /// # Lock-Free Architecture (Phase 4)
@@ -99,9 +147,12 @@ pub struct McpServer {
     security: SecurityConfig,
     /// Handle to the Core thread for all state operations
     core: CoreHandle,
-    /// Event receiver for state change notifications (not used yet, for future TUI)
-    #[allow(dead_code)]
-    events: Receiver<Event>,
+    /// Event receiver for state change notifications (taken by `run`)
+    events: Option<Receiver<Event>>,
+    /// Channel to the output thread
+    output: Sender<Outbound>,
+    /// Output thread receiver (taken by `run`)
+    output_rx: Option<Receiver<Outbound>>,
     /// Core thread handle (joined on drop)
     #[allow(dead_code)]
     core_thread: JoinHandle<()>,
@@ -118,7 +169,8 @@ impl McpServer {
     pub fn new(config: McpServerConfig) -> Result<Self> {
         // Initialize security subsystem
         let path_allowlist = PathAllowlist::new(config.allowed_paths.clone());
-        let output_budget = OutputBudget::new(config.max_response_bytes, config.max_rows);
+        let output_budget = OutputBudget::new(config.max_response_bytes, config.max_rows)
+            .with_scan_limits(config.max_scan_rows, config.max_scan_bytes);
         let audit_log = config
             .audit_log_path
             .as_ref()
@@ -129,6 +181,7 @@ impl McpServer {
             path_allowlist,
             output_budget,
             audit_log,
+            allow_plaintext_uris: config.allow_plaintext_uris,
         };
 
         // Spawn the Core thread (owns JobManager, ApprovalManager - single owner, no locks)
@@ -149,11 +202,15 @@ impl McpServer {
         // Initialize tool registry
         let tools = ToolRegistry::new();
 
+        let (output, output_rx) = mpsc::channel();
+
         Ok(Self {
             config,
             security,
             core,
-            events,
+            events: Some(events),
+            output,
+            output_rx: Some(output_rx),
             core_thread,
             tools,
             executor_handle,
@@ -167,8 +224,30 @@ impl McpServer {
     /// This is a synchronous blocking loop - no async runtime required.
     pub fn run(&mut self) -> Result<()> {
         let stdin = std::io::stdin();
-        let stdout = std::io::stdout();
-        let mut stdout = stdout.lock();
+        let output_rx = self
+            .output_rx
+            .take()
+            .context("MCP server is already running")?;
+        let events = self
+            .events
+            .take()
+            .context("MCP server is already running")?;
+
+        // Forward Core events to the output thread, which pushes them to
+        // subscribers
+        let event_tx = self.output.clone();
+        thread::Builder::new()
+            .name("mcp-events".to_string())
+            .spawn(move || {
+                for event in events {
+                    if event_tx.send(Outbound::Event(event)).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        let output_thread = thread::Builder::new()
+            .name("mcp-output".to_string())
+            .spawn(move || output_loop(output_rx))?;
 
         info!("MCP server starting");
 
@@ -192,7 +271,7 @@ impl McpServer {
                             format!("Invalid JSON: {}", e),
                         ),
                     );
-                    self.write_response(&mut stdout, &response)?;
+                    self.send_output(Outbound::Response(response))?;
                     continue;
                 }
             };
@@ -216,11 +295,14 @@ impl McpServer {
             }
 
             // Write response
-            self.write_response(&mut stdout, &response)?;
+            self.send_output(Outbound::Response(response))?;
         }
 
         info!("MCP server shutting down");
-        Ok(())
+        let _ = self.output.send(Outbound::Shutdown);
+        output_thread
+            .join()
+            .map_err(|_| anyhow!("MCP output thread panicked"))?
     }
 
     /// Handle a single JSON-RPC request (synchronous)
@@ -259,6 +341,10 @@ impl McpServer {
             methods::PING => {
                 JsonRpcResponse::success(request.id, Value::Object(Default::default()))
             }
+            methods::RESOURCES_LIST => self.handle_resources_list(request),
+            methods::RESOURCES_READ => self.handle_resources_read(request),
+            methods::RESOURCES_SUBSCRIBE => self.handle_resources_subscribe(request, true),
+            methods::RESOURCES_UNSUBSCRIBE => self.handle_resources_subscribe(request, false),
             _ => JsonRpcResponse::error(
                 request.id,
                 JsonRpcError::new(
@@ -308,7 +394,10 @@ impl McpServer {
                 tools: Some(ToolsCapability {
                     list_changed: false,
                 }),
-                resources: None,
+                resources: Some(ResourcesCapability {
+                    subscribe: true,
+                    list_changed: false,
+                }),
                 prompts: None,
                 logging: None,
             },
@@ -477,14 +566,178 @@ impl McpServer {
         }
     }
 
-    /// Write a response to stdout
-    fn write_response<W: Write>(&self, writer: &mut W, response: &JsonRpcResponse) -> Result<()> {
-        let json = serde_json::to_string(response)?;
-        debug!("Sending: {}", json);
-        writeln!(writer, "{}", json)?;
-        writer.flush()?;
-        Ok(())
+    /// Handle resources/list request
+    fn handle_resources_list(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let resource = |uri: &str, name: &str, description: &str| Resource {
+            uri: uri.to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            mime_type: Some("application/json".to_string()),
+        };
+        let result = ResourcesListResult {
+            resources: vec![
+                resource(
+                    JOBS_URI,
+                    "Jobs",
+                    "Recent jobs. Subscribe to casparian://jobs/{job_id} for one job's progress.",
+                ),
+                resource(
+                    APPROVALS_URI,
+                    "Approvals",
+                    "Approval requests. Subscribe to casparian://approvals/{approval_id} for one.",
+                ),
+            ],
+        };
+
+        JsonRpcResponse::success(request.id, serde_json::to_value(result).unwrap())
     }
+
+    /// Handle resources/read request
+    fn handle_resources_read(&self, request: JsonRpcRequest) -> JsonRpcResponse {
+        let resource = match parse_resource_params(request.params) {
+            Ok(resource) => resource,
+            Err(error) => return JsonRpcResponse::error(request.id, error),
+        };
+
+        let contents = match &resource {
+            ResourceUri::Jobs => self
+                .core
+                .list_jobs(None, RESOURCE_JOB_LIST_LIMIT)
+                .and_then(|jobs| Ok(serde_json::to_value(jobs)?)),
+            ResourceUri::Job(job_id) => self.core.get_job(*job_id).and_then(|job| {
+                let job = job.ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+                Ok(serde_json::to_value(job)?)
+            }),
+            ResourceUri::Approvals => self
+                .core
+                .list_approvals(None)
+                .and_then(|approvals| Ok(serde_json::to_value(approvals)?)),
+            ResourceUri::Approval(approval_id) => self
+                .core
+                .get_approval(ApprovalId::from_string(approval_id))
+                .and_then(|approval| {
+                    let approval =
+                        approval.ok_or_else(|| anyhow!("Approval not found: {}", approval_id))?;
+                    Ok(serde_json::to_value(approval)?)
+                }),
+        };
+
+        match contents {
+            Ok(value) => {
+                let result = ResourceReadResult {
+                    contents: vec![ResourceContents {
+                        uri: resource.as_uri(),
+                        mime_type: Some("application/json".to_string()),
+                        text: value.to_string(),
+                    }],
+                };
+                JsonRpcResponse::success(request.id, serde_json::to_value(result).unwrap())
+            }
+            Err(e) => JsonRpcResponse::error(
+                request.id,
+                JsonRpcError::new(crate::protocol::ErrorCode::InvalidParams, e.to_string()),
+            ),
+        }
+    }
+
+    /// Handle resources/subscribe and resources/unsubscribe requests
+    fn handle_resources_subscribe(
+        &self,
+        request: JsonRpcRequest,
+        subscribe: bool,
+    ) -> JsonRpcResponse {
+        let resource = match parse_resource_params(request.params) {
+            Ok(resource) => resource,
+            Err(error) => return JsonRpcResponse::error(request.id, error),
+        };
+
+        // Queued ahead of the response, so events that follow the ack are pushed
+        let message = if subscribe {
+            info!("Subscribe: {}", resource.as_uri());
+            Outbound::Subscribe(resource)
+        } else {
+            info!("Unsubscribe: {}", resource.as_uri());
+            Outbound::Unsubscribe(resource)
+        };
+        if self.output.send(message).is_err() {
+            return JsonRpcResponse::error(
+                request.id,
+                JsonRpcError::new(
+                    crate::protocol::ErrorCode::InternalError,
+                    "Output thread stopped",
+                ),
+            );
+        }
+
+        JsonRpcResponse::success(request.id, Value::Object(Default::default()))
+    }
+
+    /// Queue a message for the output thread
+    fn send_output(&self, message: Outbound) -> Result<()> {
+        self.output
+            .send(message)
+            .map_err(|_| anyhow!("MCP output thread stopped"))
+    }
+}
+
+/// Parse `{ "uri": ... }` params into a known resource
+fn parse_resource_params(params: Option<Value>) -> std::result::Result<ResourceUri, JsonRpcError> {
+    let params: ResourceUriParams = params
+        .ok_or_else(|| {
+            JsonRpcError::new(
+                crate::protocol::ErrorCode::InvalidParams,
+                "Missing resource params",
+            )
+        })
+        .and_then(|p| {
+            serde_json::from_value(p).map_err(|e| {
+                JsonRpcError::new(
+                    crate::protocol::ErrorCode::InvalidParams,
+                    format!("Invalid resource params: {}", e),
+                )
+            })
+        })?;
+
+    ResourceUri::parse(&params.uri).ok_or_else(|| {
+        JsonRpcError::new(
+            crate::protocol::ErrorCode::InvalidParams,
+            format!("Unknown resource: {}", params.uri),
+        )
+    })
+}
+
+/// Output thread: owns stdout and the subscription set.
+fn output_loop(messages: Receiver<Outbound>) -> Result<()> {
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
+    let mut subscriptions = Subscriptions::new();
+
+    for message in messages {
+        match message {
+            Outbound::Response(response) => write_message(&mut stdout, &response)?,
+            Outbound::Subscribe(resource) => subscriptions.subscribe(&resource),
+            Outbound::Unsubscribe(resource) => {
+                subscriptions.unsubscribe(&resource);
+            }
+            Outbound::Event(event) => {
+                for notification in subscriptions.notifications(&event) {
+                    write_message(&mut stdout, &notification)?;
+                }
+            }
+            Outbound::Shutdown => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Write one JSON-RPC message line
+fn write_message<W: Write, T: Serialize>(writer: &mut W, message: &T) -> Result<()> {
+    let json = serde_json::to_string(message)?;
+    debug!("Sending: {}", json);
+    writeln!(writer, "{}", json)?;
+    writer.flush()?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -497,6 +750,7 @@ mod tests {
         assert_eq!(config.server_name, "casparian-mcp");
         assert_eq!(config.max_response_bytes, 1024 * 1024);
         assert_eq!(config.max_rows, 10_000);
+        assert_eq!(config.query_timeout_ms, 30_000);
         assert!(!config.standalone_db_writer);
     }
 }
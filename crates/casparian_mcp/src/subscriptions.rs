@@ -0,0 +1,243 @@
+//! Resource Subscriptions - Pushed Job and Approval Events
+//!
+//! Clients subscribe to resource URIs via `resources/subscribe`. When the
+//! Core emits an [`Event`] that touches a subscribed resource, the server
+//! pushes a `notifications/resources/updated` notification carrying the
+//! event, so assistants do not have to poll `casparian_job_status`.
+//!
+//! # Resources
+//!
+//! | URI                                   | Events                                    |
+//! |---------------------------------------|-------------------------------------------|
+//! | `casparian://jobs`                    | Every job event                           |
+//! | `casparian://jobs/{job_id}`           | State, progress, and backtest iterations  |
+//! | `casparian://approvals`               | Every approval state change               |
+//! | `casparian://approvals/{approval_id}` | State changes of one approval             |
+//!
+//! Only changes made through this server's Core are pushed; approvals
+//! decided from the CLI are picked up on the next `resources/read`.
+
+use crate::core::Event;
+use crate::jobs::JobId;
+use crate::protocol::{methods, JsonRpcNotification};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+/// All jobs
+pub const JOBS_URI: &str = "casparian://jobs";
+/// All approvals
+pub const APPROVALS_URI: &str = "casparian://approvals";
+
+/// A resource clients can read or subscribe to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    Jobs,
+    Job(JobId),
+    Approvals,
+    Approval(String),
+}
+
+impl ResourceUri {
+    /// Parse a resource URI. Returns None for unknown URIs.
+    pub fn parse(uri: &str) -> Option<Self> {
+        if uri == JOBS_URI {
+            return Some(Self::Jobs);
+        }
+        if uri == APPROVALS_URI {
+            return Some(Self::Approvals);
+        }
+        if let Some(id) = uri.strip_prefix(JOBS_URI).and_then(|s| s.strip_prefix('/')) {
+            return id.parse().ok().map(Self::Job);
+        }
+        if let Some(id) = uri
+            .strip_prefix(APPROVALS_URI)
+            .and_then(|s| s.strip_prefix('/'))
+        {
+            if !id.is_empty() && !id.contains('/') {
+                return Some(Self::Approval(id.to_string()));
+            }
+        }
+        None
+    }
+
+    /// Canonical URI string
+    pub fn as_uri(&self) -> String {
+        match self {
+            Self::Jobs => JOBS_URI.to_string(),
+            Self::Job(id) => format!("{}/{}", JOBS_URI, id),
+            Self::Approvals => APPROVALS_URI.to_string(),
+            Self::Approval(id) => format!("{}/{}", APPROVALS_URI, id),
+        }
+    }
+}
+
+/// The resources an event touches (most specific first) and the event
+/// payload pushed to subscribers.
+pub fn event_resources(event: &Event) -> (Vec<ResourceUri>, Value) {
+    let job = |job_id: &JobId, payload: Value| {
+        (vec![ResourceUri::Job(*job_id), ResourceUri::Jobs], payload)
+    };
+    let approval = |approval_id: &str, kind: &str| {
+        (
+            vec![
+                ResourceUri::Approval(approval_id.to_string()),
+                ResourceUri::Approvals,
+            ],
+            json!({ "type": kind, "approval_id": approval_id }),
+        )
+    };
+
+    match event {
+        Event::JobCreated { job_id } => {
+            job(job_id, json!({ "type": "job_created", "job_id": job_id }))
+        }
+        Event::JobStarted { job_id } => {
+            job(job_id, json!({ "type": "job_started", "job_id": job_id }))
+        }
+        Event::JobProgress { job_id, progress } => job(
+            job_id,
+            json!({ "type": "job_progress", "job_id": job_id, "progress": progress }),
+        ),
+        Event::JobCompleted { job_id } => {
+            job(job_id, json!({ "type": "job_completed", "job_id": job_id }))
+        }
+        Event::JobFailed { job_id, error } => job(
+            job_id,
+            json!({ "type": "job_failed", "job_id": job_id, "error": error }),
+        ),
+        Event::JobCancelled { job_id } => {
+            job(job_id, json!({ "type": "job_cancelled", "job_id": job_id }))
+        }
+        Event::BacktestIteration { job_id, iteration } => job(
+            job_id,
+            json!({ "type": "backtest_iteration", "job_id": job_id, "iteration": iteration }),
+        ),
+        Event::ApprovalCreated { approval_id } => {
+            approval(approval_id.as_ref(), "approval_created")
+        }
+        Event::ApprovalApproved { approval_id } => {
+            approval(approval_id.as_ref(), "approval_approved")
+        }
+        Event::ApprovalRejected { approval_id } => {
+            approval(approval_id.as_ref(), "approval_rejected")
+        }
+    }
+}
+
+/// The set of resource URIs the client is subscribed to.
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    uris: BTreeSet<String>,
+}
+
+impl Subscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe to a resource. Subscribing twice is a no-op.
+    pub fn subscribe(&mut self, resource: &ResourceUri) {
+        self.uris.insert(resource.as_uri());
+    }
+
+    /// Returns true if the client was subscribed.
+    pub fn unsubscribe(&mut self, resource: &ResourceUri) -> bool {
+        self.uris.remove(&resource.as_uri())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.uris.is_empty()
+    }
+
+    /// Notifications to push for an event: one per subscribed resource it
+    /// touches.
+    pub fn notifications(&self, event: &Event) -> Vec<JsonRpcNotification> {
+        if self.uris.is_empty() {
+            return Vec::new();
+        }
+        let (resources, payload) = event_resources(event);
+        resources
+            .iter()
+            .map(ResourceUri::as_uri)
+            .filter(|uri| self.uris.contains(uri))
+            .map(|uri| {
+                JsonRpcNotification::new(
+                    methods::RESOURCES_UPDATED,
+                    json!({ "uri": uri, "event": payload }),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approvals::ApprovalId;
+    use crate::core::BacktestIterationInfo;
+
+    #[test]
+    fn test_parse_resource_uri() {
+        assert_eq!(ResourceUri::parse(JOBS_URI), Some(ResourceUri::Jobs));
+        assert_eq!(
+            ResourceUri::parse("casparian://jobs/42"),
+            Some(ResourceUri::Job(JobId::new(42)))
+        );
+        assert_eq!(
+            ResourceUri::parse("casparian://approvals/abc"),
+            Some(ResourceUri::Approval("abc".to_string()))
+        );
+        assert_eq!(ResourceUri::parse("casparian://jobs/not-a-number"), None);
+        assert_eq!(ResourceUri::parse("casparian://approvals/"), None);
+        assert_eq!(ResourceUri::parse("file:///etc/passwd"), None);
+
+        let job = ResourceUri::Job(JobId::new(7));
+        assert_eq!(ResourceUri::parse(&job.as_uri()), Some(job));
+    }
+
+    #[test]
+    fn test_notifications_only_for_subscribed_resources() {
+        let mut subs = Subscriptions::new();
+        let event = Event::BacktestIteration {
+            job_id: JobId::new(7),
+            iteration: BacktestIterationInfo {
+                iteration: 1,
+                files_total: 3,
+                passed: true,
+                files_passed: 1,
+                files_failed: 0,
+            },
+        };
+        assert!(subs.notifications(&event).is_empty());
+
+        subs.subscribe(&ResourceUri::Job(JobId::new(7)));
+        subs.subscribe(&ResourceUri::Approvals);
+        let notifications = subs.notifications(&event);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].method, methods::RESOURCES_UPDATED);
+        let params = notifications[0].params.as_ref().unwrap();
+        assert_eq!(params["uri"], "casparian://jobs/7");
+        assert_eq!(params["event"]["type"], "backtest_iteration");
+        assert_eq!(params["event"]["iteration"]["files_total"], 3);
+
+        // Other jobs are not pushed
+        let other = Event::JobStarted {
+            job_id: JobId::new(8),
+        };
+        assert!(subs.notifications(&other).is_empty());
+
+        let approved = Event::ApprovalApproved {
+            approval_id: ApprovalId::from_string("abc"),
+        };
+        let notifications = subs.notifications(&approved);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            notifications[0].params.as_ref().unwrap()["uri"],
+            APPROVALS_URI
+        );
+
+        assert!(subs.unsubscribe(&ResourceUri::Approvals));
+        assert!(!subs.unsubscribe(&ResourceUri::Approvals));
+        assert!(subs.notifications(&approved).is_empty());
+    }
+}
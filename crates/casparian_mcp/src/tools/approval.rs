@@ -1,6 +1,7 @@
-//! Approval Tools - Approval Status and List
+//! Approval Tools - Approval Status, List, Decide, and Audit
 //!
-//! Tools for monitoring approval requests.
+//! Tools for monitoring and deciding approval requests. Every decision is
+//! recorded in the approval audit log with the deciding actor.
 
 use super::McpTool;
 use crate::approvals::{ApprovalId, ApprovalOperation, ApprovalStatus};
@@ -10,6 +11,7 @@ use crate::security::SecurityConfig;
 use crate::server::McpServerConfig;
 use crate::types::{ApprovalDecision, ApprovalStatusFilter};
 use anyhow::{anyhow, Result};
+use casparian_protocol::ListApprovalAuditResponse;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::info;
@@ -196,6 +198,9 @@ struct ApprovalDecideArgs {
     decision: ApprovalDecision,
     #[serde(default)]
     reason: Option<String>,
+    /// Who is deciding; recorded in the audit log
+    #[serde(default)]
+    actor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -233,7 +238,11 @@ impl McpTool for ApprovalDecideTool {
                 },
                 "reason": {
                     "type": "string",
-                    "description": "Optional reason (required for rejection)"
+                    "description": "Optional reason (required for rejection); recorded as the audit justification"
+                },
+                "actor": {
+                    "type": "string",
+                    "description": "Who is making the decision; recorded in the audit log"
                 }
             },
             "required": ["approval_id", "decision"]
@@ -309,7 +318,7 @@ impl McpTool for ApprovalDecideTool {
         // Apply decision via Core
         match args.decision {
             ApprovalDecision::Reject => {
-                core.reject(approval_id, args.reason.clone())?;
+                core.reject(approval_id, args.reason.clone(), args.actor.clone())?;
                 Ok(serde_json::to_value(ApprovalDecideResult {
                     approval_id: args.approval_id,
                     decision: args.decision.to_string(),
@@ -320,7 +329,7 @@ impl McpTool for ApprovalDecideTool {
             }
             ApprovalDecision::Approve => {
                 let operation = approval.operation.clone();
-                core.approve(approval_id.clone())?;
+                core.approve(approval_id.clone(), args.actor.clone(), args.reason.clone())?;
 
                 // Create job via Core
                 let (job_id, job_id_for_enqueue): (Option<String>, Option<crate::jobs::JobId>) =
@@ -375,3 +384,61 @@ impl McpTool for ApprovalDecideTool {
         }
     }
 }
+
+// ============================================================================
+// casparian_approval_audit
+// ============================================================================
+
+pub struct ApprovalAuditTool;
+
+#[derive(Debug, Deserialize)]
+struct ApprovalAuditArgs {
+    approval_id: String,
+}
+
+impl McpTool for ApprovalAuditTool {
+    fn name(&self) -> &'static str {
+        "casparian_approval_audit"
+    }
+
+    fn description(&self) -> &'static str {
+        "List the audit log (actor, time, decision, justification) for an approval request"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "approval_id": {
+                    "type": "string"
+                }
+            },
+            "required": ["approval_id"]
+        })
+    }
+
+    fn execute(
+        &self,
+        args: Value,
+        _security: &SecurityConfig,
+        core: &CoreHandle,
+        _config: &McpServerConfig,
+        _executor: &JobExecutorHandle,
+    ) -> Result<Value> {
+        let args: ApprovalAuditArgs =
+            serde_json::from_value(args).map_err(|e| anyhow!("Invalid arguments: {}", e))?;
+        let approval_id = ApprovalId::from_string(&args.approval_id);
+
+        if core.get_approval(approval_id.clone())?.is_none() {
+            return Err(anyhow!("Approval not found: {}", args.approval_id));
+        }
+        let entries = core.list_approval_audit(approval_id)?;
+
+        let result = ListApprovalAuditResponse {
+            approval_id: args.approval_id,
+            entries,
+        };
+
+        Ok(serde_json::to_value(result)?)
+    }
+}
@@ -0,0 +1,244 @@
+//! Catalog Tools - Dataset List, Dataset Describe
+//!
+//! Answer "which tables exist" from the dataset catalog the Sentinel fills on
+//! every CONCLUDE (`cf_datasets`), instead of listing output directories.
+//!
+//! Sink URIs and source roots are hashed per the default redaction policy
+//! unless the server runs with `--allow-plaintext-uris`.
+
+use super::McpTool;
+use crate::core::CoreHandle;
+use crate::jobs::JobExecutorHandle;
+use crate::security::SecurityConfig;
+use crate::server::McpServerConfig;
+use anyhow::{anyhow, Result};
+use casparian_db::DbConnection;
+use casparian_protocol::http_types::{DatasetDetail, DatasetSummary};
+use casparian_sentinel::dataset_catalog;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Open the state store read-only. Returns None if no dataset has been
+/// registered yet.
+fn open_catalog(config: &McpServerConfig) -> Result<Option<DbConnection>> {
+    let conn = DbConnection::open_sqlite_readonly(&config.db_path)
+        .map_err(|e| anyhow!("Failed to open state store: {}", e))?;
+    if !conn.table_exists("cf_datasets")? {
+        return Ok(None);
+    }
+    Ok(Some(conn))
+}
+
+fn redact_summary(mut summary: DatasetSummary, security: &SecurityConfig) -> DatasetSummary {
+    summary.sink_uri = security.redact_uri(&summary.sink_uri);
+    summary
+}
+
+// ============================================================================
+// casparian_dataset_list
+// ============================================================================
+
+pub struct DatasetListTool;
+
+#[derive(Debug, Deserialize)]
+struct DatasetListArgs {
+    #[serde(default)]
+    query: Option<String>,
+    #[serde(default)]
+    plugin_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DatasetListResult {
+    datasets: Vec<DatasetSummary>,
+    truncated: bool,
+    uris_redacted: bool,
+}
+
+impl McpTool for DatasetListTool {
+    fn name(&self) -> &'static str {
+        "casparian_dataset_list"
+    }
+
+    fn description(&self) -> &'static str {
+        "List or search the datasets (output tables) jobs have produced"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Match dataset, plugin, table or column names (case-insensitive)"
+                },
+                "plugin_name": {
+                    "type": "string",
+                    "description": "Only datasets produced by this plugin"
+                }
+            }
+        })
+    }
+
+    fn execute(
+        &self,
+        args: Value,
+        security: &SecurityConfig,
+        _core: &CoreHandle,
+        config: &McpServerConfig,
+        _executor: &JobExecutorHandle,
+    ) -> Result<Value> {
+        let args: DatasetListArgs = serde_json::from_value(args)?;
+        let max_rows = security.output_budget.max_rows();
+
+        // One extra row tells whether the listing was cut short
+        let records = match open_catalog(config)? {
+            Some(conn) => dataset_catalog::list(
+                &conn,
+                args.query.as_deref(),
+                args.plugin_name.as_deref(),
+                max_rows + 1,
+            )?,
+            None => Vec::new(),
+        };
+        let truncated = records.len() > max_rows;
+        let datasets = records
+            .iter()
+            .take(max_rows)
+            .map(|record| redact_summary(record.summary(), security))
+            .collect();
+
+        let result = DatasetListResult {
+            datasets,
+            truncated,
+            uris_redacted: !security.allow_plaintext_uris,
+        };
+        Ok(serde_json::to_value(result)?)
+    }
+}
+
+// ============================================================================
+// casparian_dataset_describe
+// ============================================================================
+
+pub struct DatasetDescribeTool;
+
+#[derive(Debug, Deserialize)]
+struct DatasetDescribeArgs {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DatasetDescribeResult {
+    name: String,
+    found: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dataset: Option<DatasetDetail>,
+    uris_redacted: bool,
+}
+
+fn redact_detail(mut detail: DatasetDetail, security: &SecurityConfig) -> DatasetDetail {
+    detail.summary = redact_summary(detail.summary, security);
+    detail.lineage_root = detail
+        .lineage_root
+        .as_deref()
+        .map(|root| security.redact_uri(root));
+    detail
+}
+
+impl McpTool for DatasetDescribeTool {
+    fn name(&self) -> &'static str {
+        "casparian_dataset_describe"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show a dataset's schema, row count, partitions, sink, and source root"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Dataset (output) name"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    fn execute(
+        &self,
+        args: Value,
+        security: &SecurityConfig,
+        _core: &CoreHandle,
+        config: &McpServerConfig,
+        _executor: &JobExecutorHandle,
+    ) -> Result<Value> {
+        let args: DatasetDescribeArgs = serde_json::from_value(args)?;
+
+        let record = match open_catalog(config)? {
+            Some(conn) => dataset_catalog::describe(&conn, &args.name)?,
+            None => None,
+        };
+        let dataset = record.map(|record| redact_detail(record.detail(), security));
+
+        let result = DatasetDescribeResult {
+            name: args.name,
+            found: dataset.is_some(),
+            dataset,
+            uris_redacted: !security.allow_plaintext_uris,
+        };
+        Ok(serde_json::to_value(result)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{OutputBudget, PathAllowlist};
+    use casparian_protocol::SinkMode;
+
+    fn security(allow_plaintext_uris: bool) -> SecurityConfig {
+        SecurityConfig {
+            path_allowlist: PathAllowlist::new(vec![]),
+            output_budget: OutputBudget::new(1024 * 1024, 100),
+            audit_log: None,
+            allow_plaintext_uris,
+        }
+    }
+
+    fn detail() -> DatasetDetail {
+        DatasetDetail {
+            summary: DatasetSummary {
+                name: "orders".to_string(),
+                plugin_name: "orders_parser".to_string(),
+                sink_uri: "parquet:///out/orders".to_string(),
+                row_count: 10,
+                byte_size: None,
+                last_updated: "2026-01-01T00:00:00Z".to_string(),
+                table_name: None,
+                partition_columns: Vec::new(),
+            },
+            sink_mode: SinkMode::Append,
+            columns: Vec::new(),
+            schema_hash: None,
+            lineage_root: Some("/data/orders".to_string()),
+            job_count: 1,
+            last_job_id: 1,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_uris_hashed_by_default() {
+        let redacted = redact_detail(detail(), &security(false));
+        assert!(redacted.summary.sink_uri.starts_with("[hash:"));
+        assert!(redacted.lineage_root.unwrap().starts_with("[hash:"));
+        assert_eq!(redacted.summary.name, "orders");
+
+        let plain = redact_detail(detail(), &security(true));
+        assert_eq!(plain.lineage_root.as_deref(), Some("/data/orders"));
+    }
+}
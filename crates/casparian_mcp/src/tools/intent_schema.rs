@@ -145,7 +145,7 @@ impl McpTool for SchemaInferIntentTool {
         args: Value,
         _security: &SecurityConfig,
         _core: &CoreHandle,
-        _config: &McpServerConfig,
+        config: &McpServerConfig,
         _executor: &JobExecutorHandle,
     ) -> anyhow::Result<Value> {
         let args: SchemaInferIntentArgs = serde_json::from_value(args)?;
@@ -154,11 +154,21 @@ impl McpTool for SchemaInferIntentTool {
         let bundle = session_store.get_session(args.session_id)?;
 
         // Infer types for parsed columns
+        let mut parsed = Vec::new();
+        for sample in &args.parser_output_sample {
+            parsed.push(infer_column_type(sample, ColumnSource::Parsed)?);
+        }
+
+        // Ask the model to break ties; the human still decides
+        #[cfg(feature = "llm")]
+        suggest_ambiguous_types(config, &args, &mut parsed)?;
+        #[cfg(not(feature = "llm"))]
+        let _ = config;
+
         let mut columns = Vec::new();
         let mut questions = Vec::new();
 
-        for sample in &args.parser_output_sample {
-            let (column, question) = infer_column_type(sample, ColumnSource::Parsed)?;
+        for (column, question) in parsed {
             columns.push(column);
             if let Some(q) = question {
                 questions.push(q);
@@ -459,6 +469,92 @@ fn infer_column_type(
     Ok((column, question))
 }
 
+/// Put the model's pick first among each ambiguous column's candidates and
+/// make it the question's default. Failures keep the heuristic result,
+/// except when replaying recorded responses, where they fail the call.
+#[cfg(feature = "llm")]
+fn suggest_ambiguous_types(
+    config: &McpServerConfig,
+    args: &SchemaInferIntentArgs,
+    parsed: &mut [(SchemaIntentColumn, Option<HumanQuestion>)],
+) -> anyhow::Result<()> {
+    use crate::llm::schema::{parse_type_suggestions, type_suggestion_request, AmbiguousColumn};
+    use crate::llm::LlmClient;
+    use casparian_protocol::LlmCacheMode;
+
+    let replaying = config.llm.cache == LlmCacheMode::Replay;
+
+    let ambiguous: Vec<AmbiguousColumn> = parsed
+        .iter()
+        .filter(|(_, question)| question.is_some())
+        .filter_map(|(column, _)| {
+            let sample = args
+                .parser_output_sample
+                .iter()
+                .find(|sample| sample.name == column.name)?;
+            Some(AmbiguousColumn {
+                name: column.name.clone(),
+                candidates: column.inference.candidates.clone(),
+                samples: sample
+                    .values
+                    .iter()
+                    .filter(|value| !value.is_null())
+                    .take(crate::llm::schema::MAX_SAMPLES)
+                    .map(|value| match value {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+    if ambiguous.is_empty() {
+        return Ok(());
+    }
+
+    let client = match LlmClient::shared(&config.llm) {
+        Ok(Some(client)) => client,
+        Ok(None) => return Ok(()),
+        Err(err) if replaying => return Err(err.into()),
+        Err(err) => {
+            tracing::warn!("Schema type suggestions skipped: {}", err);
+            return Ok(());
+        }
+    };
+    let session = args.session_id.to_string();
+    let response = match client.complete(&session, &type_suggestion_request(&ambiguous)) {
+        Ok(response) => response,
+        Err(err) if replaying => return Err(err.into()),
+        Err(err) => {
+            tracing::warn!("Schema type suggestions failed: {}", err);
+            return Ok(());
+        }
+    };
+
+    let suggestions = parse_type_suggestions(&response.text, &ambiguous);
+    for (column, question) in parsed.iter_mut() {
+        let Some(choice) = suggestions.get(&column.name) else {
+            continue;
+        };
+        let candidates = &mut column.inference.candidates;
+        if let Some(index) = candidates.iter().position(|c| c == choice) {
+            let chosen = candidates.remove(index);
+            candidates.insert(0, chosen);
+        }
+        column.declared_type = choice.clone();
+        column.inference.confidence = Confidence::medium(vec![format!(
+            "Suggested by {} ({})",
+            response.provider, response.model
+        )]);
+        if let Some(question) = question {
+            for option in &mut question.options {
+                option.default = option.option_id == *choice;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn analyze_value_types(values: &[&serde_json::Value]) -> (Vec<String>, InferenceMethod) {
     let mut could_be_int = true;
     let mut could_be_float = true;
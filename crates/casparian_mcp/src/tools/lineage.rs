@@ -0,0 +1,371 @@
+//! Lineage Tools - Lineage Trace, Artifact Provenance
+//!
+//! Answer "which source files produced this table" from the lineage hops the
+//! Sentinel records on every CONCLUDE (`cf_lineage_hops`).
+//!
+//! Source paths and artifact URIs are hashed per the default redaction policy
+//! unless the server runs with `--allow-plaintext-uris`.
+
+use super::McpTool;
+use crate::core::CoreHandle;
+use crate::jobs::JobExecutorHandle;
+use crate::security::SecurityConfig;
+use crate::server::McpServerConfig;
+use anyhow::{anyhow, Result};
+use casparian_db::DbConnection;
+use casparian_sentinel::{LineageRecord, LineageStorage};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Open the lineage store read-only. Returns None if nothing has been
+/// recorded yet.
+fn open_lineage(config: &McpServerConfig) -> Result<Option<LineageStorage>> {
+    let conn = DbConnection::open_sqlite_readonly(&config.db_path)
+        .map_err(|e| anyhow!("Failed to open state store: {}", e))?;
+    if !conn.table_exists("cf_lineage_hops")? {
+        return Ok(None);
+    }
+    Ok(Some(LineageStorage::new(conn)))
+}
+
+fn millis_to_rfc3339(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| millis.to_string())
+}
+
+// ============================================================================
+// casparian_lineage_trace
+// ============================================================================
+
+pub struct LineageTraceTool;
+
+#[derive(Debug, Deserialize)]
+struct LineageTraceArgs {
+    #[serde(default)]
+    table: Option<String>,
+    #[serde(default)]
+    artifact_uri: Option<String>,
+}
+
+/// A source file that contributed to the traced output.
+#[derive(Debug, Serialize)]
+struct LineageSource {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_file_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_hash: Option<String>,
+    job_ids: Vec<i64>,
+    plugins: Vec<String>,
+    artifact_count: usize,
+    rows: i64,
+    last_produced_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct LineageTraceResult {
+    target: String,
+    source_count: usize,
+    sources: Vec<LineageSource>,
+    truncated: bool,
+    uris_redacted: bool,
+}
+
+/// Group hops by source file. Hops without a known source are grouped
+/// together under an empty source.
+fn group_sources(records: &[LineageRecord], redact: &dyn Fn(&str) -> String) -> Vec<LineageSource> {
+    type SourceKey = (Option<String>, Option<String>);
+    let mut grouped: BTreeMap<SourceKey, (i64, LineageSource)> = BTreeMap::new();
+
+    for record in records {
+        let key = (record.source_hash.clone(), record.source_path.clone());
+        let (latest, source) = grouped.entry(key).or_insert_with(|| {
+            (
+                record.created_at,
+                LineageSource {
+                    source_file_id: record.source_file_id,
+                    source_path: record.source_path.as_deref().map(redact),
+                    source_hash: record.source_hash.clone(),
+                    job_ids: Vec::new(),
+                    plugins: Vec::new(),
+                    artifact_count: 0,
+                    rows: 0,
+                    last_produced_at: String::new(),
+                },
+            )
+        });
+        if !source.job_ids.contains(&record.job_id) {
+            source.job_ids.push(record.job_id);
+        }
+        if !source.plugins.contains(&record.plugin_name) {
+            source.plugins.push(record.plugin_name.clone());
+        }
+        source.artifact_count += 1;
+        source.rows += record.rows.unwrap_or(0);
+        *latest = (*latest).max(record.created_at);
+    }
+
+    let mut sources: Vec<(i64, LineageSource)> = grouped.into_values().collect();
+    sources.sort_by_key(|(latest, _)| Reverse(*latest));
+    sources
+        .into_iter()
+        .map(|(latest, mut source)| {
+            source.last_produced_at = millis_to_rfc3339(latest);
+            source
+        })
+        .collect()
+}
+
+impl McpTool for LineageTraceTool {
+    fn name(&self) -> &'static str {
+        "casparian_lineage_trace"
+    }
+
+    fn description(&self) -> &'static str {
+        "Find the source files that produced an output table or artifact"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "table": {
+                    "type": "string",
+                    "description": "Output table (or output name) to trace"
+                },
+                "artifact_uri": {
+                    "type": "string",
+                    "description": "Sink URI of a single artifact to trace"
+                }
+            }
+        })
+    }
+
+    fn execute(
+        &self,
+        args: Value,
+        security: &SecurityConfig,
+        _core: &CoreHandle,
+        config: &McpServerConfig,
+        _executor: &JobExecutorHandle,
+    ) -> Result<Value> {
+        let args: LineageTraceArgs = serde_json::from_value(args)?;
+        let (target, lookup) = match (args.table, args.artifact_uri) {
+            (Some(table), None) => (table.clone(), Lookup::Table(table)),
+            (None, Some(uri)) => (security.redact_uri(&uri), Lookup::Artifact(uri)),
+            _ => return Err(anyhow!("Provide exactly one of 'table' or 'artifact_uri'")),
+        };
+
+        let records = match open_lineage(config)? {
+            Some(lineage) => match lookup {
+                Lookup::Table(table) => lineage.lineage_for_table(&table)?,
+                Lookup::Artifact(uri) => lineage.lineage_for_artifact(&uri)?,
+            },
+            None => Vec::new(),
+        };
+
+        let mut sources = group_sources(&records, &|uri| security.redact_uri(uri));
+        let source_count = sources.len();
+        let max_rows = security.output_budget.max_rows();
+        let truncated = sources.len() > max_rows;
+        sources.truncate(max_rows);
+
+        let result = LineageTraceResult {
+            target,
+            source_count,
+            sources,
+            truncated,
+            uris_redacted: !security.allow_plaintext_uris,
+        };
+        Ok(serde_json::to_value(result)?)
+    }
+}
+
+enum Lookup {
+    Table(String),
+    Artifact(String),
+}
+
+// ============================================================================
+// casparian_artifact_provenance
+// ============================================================================
+
+pub struct ArtifactProvenanceTool;
+
+#[derive(Debug, Deserialize)]
+struct ArtifactProvenanceArgs {
+    artifact_uri: String,
+}
+
+/// One source → job → artifact hop that produced the artifact.
+#[derive(Debug, Serialize)]
+struct ProvenanceHop {
+    job_id: i64,
+    plugin_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parser_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_file_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_hash: Option<String>,
+    artifact_kind: String,
+    artifact_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    table_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rows: Option<i64>,
+    produced_at: String,
+}
+
+impl ProvenanceHop {
+    fn from_record(record: LineageRecord, redact: &dyn Fn(&str) -> String) -> Self {
+        Self {
+            job_id: record.job_id,
+            plugin_name: record.plugin_name,
+            parser_version: record.parser_version,
+            source_file_id: record.source_file_id,
+            source_path: record.source_path.as_deref().map(redact),
+            source_hash: record.source_hash,
+            artifact_kind: record.artifact_kind,
+            artifact_name: record.artifact_name,
+            table_name: record.table_name,
+            rows: record.rows,
+            produced_at: millis_to_rfc3339(record.created_at),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ArtifactProvenanceResult {
+    artifact_uri: String,
+    found: bool,
+    /// Most recent first
+    hops: Vec<ProvenanceHop>,
+    uris_redacted: bool,
+}
+
+impl McpTool for ArtifactProvenanceTool {
+    fn name(&self) -> &'static str {
+        "casparian_artifact_provenance"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show the jobs, parser versions, and source files that produced an artifact"
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "artifact_uri": {
+                    "type": "string",
+                    "description": "Sink URI of the artifact"
+                }
+            },
+            "required": ["artifact_uri"]
+        })
+    }
+
+    fn execute(
+        &self,
+        args: Value,
+        security: &SecurityConfig,
+        _core: &CoreHandle,
+        config: &McpServerConfig,
+        _executor: &JobExecutorHandle,
+    ) -> Result<Value> {
+        let args: ArtifactProvenanceArgs = serde_json::from_value(args)?;
+
+        let records = match open_lineage(config)? {
+            Some(lineage) => lineage.lineage_for_artifact(&args.artifact_uri)?,
+            None => Vec::new(),
+        };
+        let redact = |uri: &str| security.redact_uri(uri);
+        let hops: Vec<ProvenanceHop> = records
+            .into_iter()
+            .take(security.output_budget.max_rows())
+            .map(|record| ProvenanceHop::from_record(record, &redact))
+            .collect();
+
+        let result = ArtifactProvenanceResult {
+            artifact_uri: security.redact_uri(&args.artifact_uri),
+            found: !hops.is_empty(),
+            hops,
+            uris_redacted: !security.allow_plaintext_uris,
+        };
+        Ok(serde_json::to_value(result)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{OutputBudget, PathAllowlist};
+
+    fn hop(job_id: i64, source: &str, uri: &str, created_at: i64) -> LineageRecord {
+        LineageRecord {
+            job_id,
+            source_file_id: Some(job_id),
+            source_path: Some(format!("/data/{}", source)),
+            source_hash: Some(format!("hash_{}", source)),
+            decompressed_hash: None,
+            source_encoding: None,
+            plugin_name: "csv_parser".to_string(),
+            parser_version: Some("1.0.0".to_string()),
+            artifact_kind: "output".to_string(),
+            artifact_name: "trades".to_string(),
+            artifact_uri: uri.to_string(),
+            table_name: Some("trades".to_string()),
+            rows: Some(10),
+            created_at,
+            lineage: None,
+        }
+    }
+
+    fn security(allow_plaintext_uris: bool) -> SecurityConfig {
+        SecurityConfig {
+            path_allowlist: PathAllowlist::new(vec![]),
+            output_budget: OutputBudget::new(1024 * 1024, 100),
+            audit_log: None,
+            allow_plaintext_uris,
+        }
+    }
+
+    #[test]
+    fn test_group_sources() {
+        let records = vec![
+            hop(1, "a.csv", "parquet:///out/a_1.parquet", 1_000),
+            hop(1, "a.csv", "parquet:///out/a_2.parquet", 1_000),
+            hop(2, "b.csv", "parquet:///out/b.parquet", 2_000),
+        ];
+        let security = security(true);
+        let sources = group_sources(&records, &|uri| security.redact_uri(uri));
+
+        assert_eq!(sources.len(), 2);
+        // Most recently produced first
+        assert_eq!(sources[0].source_path.as_deref(), Some("/data/b.csv"));
+        assert_eq!(sources[1].job_ids, vec![1]);
+        assert_eq!(sources[1].artifact_count, 2);
+        assert_eq!(sources[1].rows, 20);
+    }
+
+    #[test]
+    fn test_uris_hashed_by_default() {
+        let security = security(false);
+        let record = hop(1, "a.csv", "parquet:///out/a.parquet", 1_000);
+        let provenance = ProvenanceHop::from_record(record, &|uri| security.redact_uri(uri));
+
+        let path = provenance.source_path.unwrap();
+        assert!(path.starts_with("[hash:"));
+        assert!(!path.contains("a.csv"));
+        assert!(security
+            .redact_uri("parquet:///out/a.parquet")
+            .starts_with("[hash:"));
+    }
+}
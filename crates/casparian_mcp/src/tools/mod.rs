@@ -9,7 +9,9 @@
 //! - **Preview**: preview (read-only)
 //! - **Jobs**: backtest_start, run_request, job_*
 //! - **Query**: query (read-only sandbox)
-//! - **Approvals**: approval_status, approval_list
+//! - **Lineage**: lineage_trace, artifact_provenance (URIs hashed by default)
+//! - **Catalog**: dataset_list, dataset_describe (URIs hashed by default)
+//! - **Approvals**: approval_status, approval_list, approval_decide, approval_audit
 //!
 //! # Human Gates
 //!
@@ -22,7 +24,9 @@ mod registry;
 // Tool implementations
 mod approval;
 mod backtest;
+mod catalog;
 mod job;
+mod lineage;
 mod plugins;
 mod preview;
 mod query;
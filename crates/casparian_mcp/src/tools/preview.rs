@@ -12,6 +12,7 @@ use crate::types::{
     ColumnDefinition, DataType, PluginRef, RedactionPolicy, SchemaDefinition, SimpleDataType,
 };
 use anyhow::{Context, Result};
+use casparian_protocol::types::ResourceLimits;
 use casparian_protocol::JobId as ProtoJobId;
 use casparian_worker::cancel::CancellationToken;
 use casparian_worker::native_runtime::NativeSubprocessRuntime;
@@ -344,8 +345,13 @@ fn create_run_context(file_idx: usize, parser_path: &Path) -> RunContext {
         file_id: file_idx as i64,
         entrypoint: parser_path.to_string_lossy().to_string(),
         env_hash: None,
+        lockfile_content: None,
         source_code: None,
         schema_hashes,
+        limits: ResourceLimits::default(),
+        byte_range: None,
+        csv_dialect: None,
+        scratch_dir: None,
     }
 }
 
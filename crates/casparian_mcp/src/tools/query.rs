@@ -2,6 +2,17 @@
 //!
 //! Runs SQL queries against output data in read-only mode.
 //! Only SELECT, WITH, and EXPLAIN are allowed.
+//!
+//! # Guardrails
+//!
+//! Before running a query, the planner's estimates (`EXPLAIN (FORMAT JSON)`)
+//! are checked against the [`OutputBudget`](crate::security::OutputBudget):
+//! unfiltered full scans over `max_scan_rows` and queries expected to scan
+//! more than `max_scan_bytes` are rejected. Plans that only stream rows
+//! (scan → projection → limit) are bounded by the row limit and pass.
+//!
+//! Queries that outlive the server timeout are interrupted on the DuckDB
+//! connection and fail with a timeout error.
 
 use super::McpTool;
 use crate::core::CoreHandle;
@@ -11,10 +22,19 @@ use crate::security::SecurityConfig;
 use crate::server::McpServerConfig;
 use crate::types::RedactionPolicy;
 use anyhow::{anyhow, Result};
-use casparian_db::{apply_row_limit, validate_read_only, DbConnection};
+use casparian_db::{apply_row_limit, validate_read_only, DbConnection, UnifiedDbRow};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::time::Instant;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Bytes assumed per scanned value when estimating scan size
+const ESTIMATED_BYTES_PER_VALUE: u64 = 8;
+
+/// Operators that pass rows through without buffering, so a LIMIT above
+/// them stops the scan early
+const STREAMING_OPERATORS: &[&str] = &["PROJECTION", "LIMIT", "STREAMING_LIMIT"];
 
 pub struct QueryTool;
 
@@ -25,6 +45,8 @@ struct QueryArgs {
     limit: usize,
     #[serde(default)]
     redaction: Option<RedactionPolicy>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
 }
 
 fn default_limit() -> usize {
@@ -45,6 +67,208 @@ struct QueryResult {
     row_count: usize,
     truncated: bool,
     elapsed_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plan: Option<PlanEstimate>,
+}
+
+/// A leaf of the query plan that reads data
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ScanEstimate {
+    table: String,
+    estimated_rows: u64,
+    columns: u64,
+    filtered: bool,
+}
+
+/// Planner estimates for a query
+#[derive(Debug, Default, Serialize)]
+struct PlanEstimate {
+    scans: Vec<ScanEstimate>,
+    estimated_rows_scanned: u64,
+    estimated_bytes_scanned: u64,
+    /// No blocking operators: the row limit bounds every scan
+    streaming: bool,
+}
+
+impl PlanEstimate {
+    /// Parse DuckDB's `EXPLAIN (FORMAT JSON)` output.
+    fn from_explain_json(plan_json: &str, row_limit: usize) -> Result<Self> {
+        let root: Value = serde_json::from_str(plan_json)
+            .map_err(|e| anyhow!("Failed to parse query plan: {}", e))?;
+        let mut plan = PlanEstimate {
+            streaming: true,
+            ..Default::default()
+        };
+        match &root {
+            Value::Array(nodes) => nodes.iter().for_each(|node| plan.walk(node)),
+            node => plan.walk(node),
+        }
+
+        if plan.streaming {
+            for scan in &mut plan.scans {
+                scan.estimated_rows = scan.estimated_rows.min(row_limit as u64);
+            }
+        }
+        plan.estimated_rows_scanned = plan.scans.iter().map(|s| s.estimated_rows).sum();
+        plan.estimated_bytes_scanned = plan
+            .scans
+            .iter()
+            .map(|s| {
+                s.estimated_rows
+                    .saturating_mul(s.columns.max(1))
+                    .saturating_mul(ESTIMATED_BYTES_PER_VALUE)
+            })
+            .fold(0, u64::saturating_add);
+        Ok(plan)
+    }
+
+    fn walk(&mut self, node: &Value) {
+        let name = node
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_uppercase();
+        let children = node
+            .get("children")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        if children.is_empty() {
+            self.scans
+                .push(scan_estimate(&name, node.get("extra_info")));
+        } else if !STREAMING_OPERATORS.contains(&name.as_str()) {
+            self.streaming = false;
+        }
+        for child in children {
+            self.walk(child);
+        }
+    }
+
+    /// Check the estimates against the scan budget
+    fn check(&self, security: &SecurityConfig) -> Result<()> {
+        let budget = &security.output_budget;
+        for scan in self.scans.iter().filter(|scan| !scan.filtered) {
+            budget.check_full_scan(&scan.table, scan.estimated_rows)?;
+        }
+        budget.check_scan_bytes(self.estimated_bytes_scanned)?;
+        Ok(())
+    }
+}
+
+/// Read a leaf operator's estimates. Newer DuckDB versions emit `extra_info`
+/// as an object; older ones emit a single string with an `EC: N` line.
+fn scan_estimate(name: &str, extra_info: Option<&Value>) -> ScanEstimate {
+    let mut scan = ScanEstimate {
+        table: name.to_string(),
+        estimated_rows: 0,
+        columns: 1,
+        filtered: false,
+    };
+    match extra_info {
+        Some(Value::Object(info)) => {
+            if let Some(table) = info
+                .get("Table")
+                .or_else(|| info.get("Function"))
+                .and_then(Value::as_str)
+            {
+                scan.table = table.to_string();
+            }
+            scan.estimated_rows = info
+                .get("Estimated Cardinality")
+                .and_then(value_as_u64)
+                .unwrap_or(0);
+            scan.columns = info.get("Projections").map(list_len).unwrap_or(1);
+            scan.filtered = info.get("Filters").is_some_and(|f| list_len(f) > 0);
+        }
+        Some(Value::String(info)) => {
+            for line in info.lines().map(str::trim) {
+                if let Some(ec) = line.strip_prefix("EC:") {
+                    scan.estimated_rows = ec.trim().parse().unwrap_or(0);
+                } else if line.starts_with("Filters:") {
+                    scan.filtered = true;
+                }
+            }
+        }
+        _ => {}
+    }
+    scan
+}
+
+fn value_as_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Number of entries in a plan field that is either a list or a
+/// newline-separated string
+fn list_len(value: &Value) -> u64 {
+    match value {
+        Value::Array(items) => items.len() as u64,
+        Value::String(s) => s.lines().filter(|line| !line.trim().is_empty()).count() as u64,
+        _ => 0,
+    }
+}
+
+fn is_explain(sql: &str) -> bool {
+    sql.split_whitespace()
+        .next()
+        .is_some_and(|word| word.eq_ignore_ascii_case("EXPLAIN"))
+}
+
+/// Run the plan through EXPLAIN and reject it if it is over the scan budget.
+fn estimate_plan(
+    conn: &DbConnection,
+    sql: &str,
+    row_limit: usize,
+    security: &SecurityConfig,
+) -> Result<PlanEstimate> {
+    let explain = conn
+        .query_all(&format!("EXPLAIN (FORMAT JSON) {}", sql), &[])
+        .map_err(|e| anyhow!("Query failed: {}", e))?;
+    let plan_json: String = explain
+        .first()
+        .ok_or_else(|| anyhow!("EXPLAIN returned no plan"))?
+        .get(1)?;
+    let plan = PlanEstimate::from_explain_json(&plan_json, row_limit)?;
+    plan.check(security)?;
+    Ok(plan)
+}
+
+/// Run a query, interrupting it on the connection if it outlives `timeout`.
+fn query_with_timeout(
+    conn: &DbConnection,
+    sql: &str,
+    timeout: Duration,
+) -> Result<Vec<UnifiedDbRow>> {
+    let interrupt = conn.interrupt_handle();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let watchdog = thread::Builder::new()
+        .name("mcp-query-watchdog".to_string())
+        .spawn(move || match done_rx.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => {
+                interrupt.interrupt();
+                true
+            }
+            _ => false,
+        })?;
+
+    let result = conn.query_all(sql, &[]);
+    let _ = done_tx.send(());
+    let timed_out = watchdog.join().unwrap_or(false);
+
+    match result {
+        Ok(rows) => Ok(rows),
+        Err(_) if timed_out => Err(anyhow!(
+            "Query exceeded timeout of {}ms and was cancelled",
+            timeout.as_millis()
+        )),
+        Err(e) => Err(anyhow!("Query failed: {}", e)),
+    }
 }
 
 impl McpTool for QueryTool {
@@ -74,6 +298,10 @@ impl McpTool for QueryTool {
                     "properties": {
                         "mode": { "type": "string", "enum": ["none", "truncate", "hash"], "default": "hash" }
                     }
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "description": "Cancel the query after this many milliseconds (capped by the server timeout)"
                 }
             },
             "required": ["sql"]
@@ -93,8 +321,11 @@ impl McpTool for QueryTool {
         // Validate SQL is read-only
         validate_read_only(&args.sql).map_err(|err| anyhow!(err))?;
 
-        // Enforce row limit
+        // Enforce row limit and timeout
         let limit = args.limit.min(security.output_budget.max_rows());
+        let timeout_ms = args
+            .timeout_ms
+            .map_or(config.query_timeout_ms, |t| t.min(config.query_timeout_ms));
 
         let start = Instant::now();
 
@@ -105,10 +336,15 @@ impl McpTool for QueryTool {
         // Add LIMIT to query if not present
         let sql = apply_row_limit(&args.sql, limit);
 
+        // Reject queries the planner expects to be over the scan budget
+        let plan = if is_explain(&sql) {
+            None
+        } else {
+            Some(estimate_plan(&conn, &sql, limit, security)?)
+        };
+
         // Execute query
-        let db_rows = conn
-            .query_all(&sql, &[])
-            .map_err(|e| anyhow!("Query failed: {}", e))?;
+        let db_rows = query_with_timeout(&conn, &sql, Duration::from_millis(timeout_ms))?;
 
         let elapsed_ms = start.elapsed().as_millis() as u64;
 
@@ -152,6 +388,7 @@ impl McpTool for QueryTool {
             row_count,
             truncated,
             elapsed_ms,
+            plan,
         };
 
         Ok(serde_json::to_value(result)?)
@@ -207,6 +444,7 @@ fn db_value_to_type_name(value: &casparian_db::DbValue) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::security::{OutputBudget, PathAllowlist};
 
     #[test]
     fn test_read_only_validation() {
@@ -280,6 +518,92 @@ mod tests {
         );
     }
 
+    fn security(max_scan_rows: u64, max_scan_bytes: u64) -> SecurityConfig {
+        SecurityConfig {
+            path_allowlist: PathAllowlist::new(vec![]),
+            output_budget: OutputBudget::new(1024 * 1024, 100)
+                .with_scan_limits(max_scan_rows, max_scan_bytes),
+            audit_log: None,
+            allow_plaintext_uris: false,
+        }
+    }
+
+    #[test]
+    fn test_plan_estimate_streaming_scan_bounded_by_limit() {
+        let plan_json = r#"[{
+            "name": "STREAMING_LIMIT",
+            "children": [{
+                "name": "SEQ_SCAN ",
+                "children": [],
+                "extra_info": {
+                    "Table": "events",
+                    "Projections": ["id", "ts"],
+                    "Estimated Cardinality": "90000000"
+                }
+            }]
+        }]"#;
+        let plan = PlanEstimate::from_explain_json(plan_json, 100).unwrap();
+
+        assert!(plan.streaming);
+        assert_eq!(plan.scans.len(), 1);
+        assert_eq!(plan.scans[0].table, "events");
+        assert_eq!(plan.scans[0].estimated_rows, 100);
+        assert_eq!(
+            plan.estimated_bytes_scanned,
+            100 * 2 * ESTIMATED_BYTES_PER_VALUE
+        );
+        assert!(plan.check(&security(1_000, 1024 * 1024)).is_ok());
+    }
+
+    #[test]
+    fn test_plan_estimate_rejects_unfiltered_full_scan() {
+        let plan_json = r#"[{
+            "name": "STREAMING_LIMIT",
+            "children": [{
+                "name": "ORDER_BY",
+                "children": [{
+                    "name": "SEQ_SCAN ",
+                    "children": [],
+                    "extra_info": {
+                        "Table": "events",
+                        "Projections": "id\nts\npayload",
+                        "Estimated Cardinality": "5000"
+                    }
+                }]
+            }]
+        }]"#;
+        let plan = PlanEstimate::from_explain_json(plan_json, 100).unwrap();
+
+        assert!(!plan.streaming);
+        assert_eq!(plan.scans[0].estimated_rows, 5000);
+        assert_eq!(plan.scans[0].columns, 3);
+        let err = plan.check(&security(1_000, u64::MAX)).unwrap_err();
+        assert!(err.to_string().contains("Full scan of events"));
+
+        // Same plan fails the byte budget even when the row threshold allows it
+        let err = plan.check(&security(10_000, 1_000)).unwrap_err();
+        assert!(err.to_string().contains("Estimated scan exceeds budget"));
+    }
+
+    #[test]
+    fn test_plan_estimate_filtered_scan_and_legacy_format() {
+        let plan_json = r#"{
+            "name": "HASH_GROUP_BY",
+            "children": [{
+                "name": "SEQ_SCAN",
+                "children": [],
+                "extra_info": "events\n[INFOSEPARATOR]\nid\n[INFOSEPARATOR]\nFilters: id>=10\n[INFOSEPARATOR]\nEC: 5000"
+            }]
+        }"#;
+        let plan = PlanEstimate::from_explain_json(plan_json, 100).unwrap();
+
+        assert_eq!(plan.scans[0].estimated_rows, 5000);
+        assert!(plan.scans[0].filtered);
+        assert!(plan.check(&security(1_000, u64::MAX)).is_ok());
+        assert!(is_explain("  explain SELECT 1"));
+        assert!(!is_explain("SELECT explain FROM t"));
+    }
+
     #[test]
     fn test_whitespace_handling() {
         // Leading/trailing whitespace should be handled
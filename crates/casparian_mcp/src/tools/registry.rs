@@ -30,6 +30,10 @@ impl ToolRegistry {
         registry.register(Box::new(scan::ScanTool));
         registry.register(Box::new(preview::PreviewTool));
         registry.register(Box::new(query::QueryTool));
+        registry.register(Box::new(lineage::LineageTraceTool));
+        registry.register(Box::new(lineage::ArtifactProvenanceTool));
+        registry.register(Box::new(catalog::DatasetListTool));
+        registry.register(Box::new(catalog::DatasetDescribeTool));
         registry.register(Box::new(backtest::BacktestStartTool));
         registry.register(Box::new(run::RunRequestTool));
         registry.register(Box::new(job::JobStatusTool));
@@ -38,6 +42,7 @@ impl ToolRegistry {
         registry.register(Box::new(approval::ApprovalStatusTool));
         registry.register(Box::new(approval::ApprovalListTool));
         registry.register(Box::new(approval::ApprovalDecideTool));
+        registry.register(Box::new(approval::ApprovalAuditTool));
 
         // Intent pipeline tools (§7.1-7.9)
         // Session lifecycle
@@ -144,6 +149,10 @@ mod tests {
         assert!(registry.has_tool("casparian_scan"));
         assert!(registry.has_tool("casparian_preview"));
         assert!(registry.has_tool("casparian_query"));
+        assert!(registry.has_tool("casparian_lineage_trace"));
+        assert!(registry.has_tool("casparian_artifact_provenance"));
+        assert!(registry.has_tool("casparian_dataset_list"));
+        assert!(registry.has_tool("casparian_dataset_describe"));
         assert!(registry.has_tool("casparian_backtest_start"));
         assert!(registry.has_tool("casparian_run_request"));
         assert!(registry.has_tool("casparian_job_status"));
@@ -152,6 +161,7 @@ mod tests {
         assert!(registry.has_tool("casparian_approval_status"));
         assert!(registry.has_tool("casparian_approval_list"));
         assert!(registry.has_tool("casparian_approval_decide"));
+        assert!(registry.has_tool("casparian_approval_audit"));
     }
 
     #[test]
@@ -1,7 +1,8 @@
 //! casparian_run_request - Request Parser Execution
 //!
 //! Creates an approval request for parser execution.
-//! Human must approve via CLI before the job runs.
+//! Human must approve via CLI before the job runs, unless an approval rule
+//! approves the request on creation; the job is then queued right away.
 
 use super::McpTool;
 use crate::approvals::ApprovalOperation;
@@ -15,6 +16,7 @@ use casparian_protocol::defaults;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::path::PathBuf;
+use tracing::info;
 use walkdir::WalkDir;
 
 pub struct RunRequestTool;
@@ -44,6 +46,11 @@ struct RunRequestResult {
     summary: RunRequestSummary,
     expires_at: String,
     approve_command: String,
+    /// Approval rule that approved the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_approved_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job_id: Option<String>,
 }
 
 impl McpTool for RunRequestTool {
@@ -101,7 +108,7 @@ impl McpTool for RunRequestTool {
         security: &SecurityConfig,
         core: &CoreHandle,
         _config: &McpServerConfig,
-        _executor: &JobExecutorHandle,
+        executor: &JobExecutorHandle,
     ) -> Result<Value> {
         let args: RunRequestArgs = serde_json::from_value(args)?;
         let _schemas = args.schemas.as_ref();
@@ -136,7 +143,7 @@ impl McpTool for RunRequestTool {
 
         // Create operation
         let operation = ApprovalOperation::Run {
-            plugin_ref: args.plugin_ref,
+            plugin_ref: args.plugin_ref.clone(),
             input_dir: PathBuf::from(&args.input_dir),
             output: output.clone(),
         };
@@ -144,9 +151,34 @@ impl McpTool for RunRequestTool {
         // Create approval request via Core
         let approval = core.create_approval(operation, summary.clone())?;
 
+        // Approved by a rule: queue the job as a human approval would
+        let job_id = if approval.auto_approved_by.is_some() {
+            let job_spec = crate::jobs::JobSpec::Run {
+                plugin_ref: args.plugin_ref,
+                input_dir: args.input_dir.clone(),
+                output_dir: Some(output),
+                schemas: None,
+            };
+            let job = core.create_job(job_spec, Some(approval.approval_id.to_string()))?;
+            executor.enqueue(job.id)?;
+            info!(
+                "Enqueued run job {} from auto-approved approval {}",
+                job.id, approval.approval_id
+            );
+            core.set_approval_job_id(approval.approval_id.clone(), job.id.to_string())?;
+            Some(job.id.to_string())
+        } else {
+            None
+        };
+        let status = if job_id.is_some() {
+            "approved"
+        } else {
+            "pending_approval"
+        };
+
         let result = RunRequestResult {
             approval_id: approval.approval_id.to_string(),
-            status: "pending_approval".to_string(),
+            status: status.to_string(),
             summary: RunRequestSummary {
                 description: summary.description,
                 file_count: summary.file_count,
@@ -155,6 +187,8 @@ impl McpTool for RunRequestTool {
             },
             expires_at: approval.expires_at.to_rfc3339(),
             approve_command: approval.approve_command(),
+            auto_approved_by: approval.auto_approved_by,
+            job_id,
         };
 
         Ok(serde_json::to_value(result)?)
@@ -315,6 +315,7 @@ impl ColumnDefinition {
             data_type: self.data_type.to_protocol()?,
             nullable: self.nullable,
             format: self.format.clone(),
+            constraints: Vec::new(),
         })
     }
 
@@ -478,7 +479,8 @@ impl TryFrom<ProtocolViolationType> for ViolationType {
             ProtocolViolationType::FormatMismatch => Ok(ViolationType::FormatMismatch),
             ProtocolViolationType::ColumnMissing
             | ProtocolViolationType::ColumnExtra
-            | ProtocolViolationType::ColumnOrderMismatch => Err(format!(
+            | ProtocolViolationType::ColumnOrderMismatch
+            | ProtocolViolationType::ConstraintViolated => Err(format!(
                 "Unsupported protocol ViolationType for MCP: {:?}",
                 value
             )),
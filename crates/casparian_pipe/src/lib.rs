@@ -0,0 +1,67 @@
+//! Named-pipe transport between workers and the Sentinel.
+//!
+//! The default local transport on Windows, where `\\.\pipe\` names are the
+//! native IPC primitive. libzmq has no named-pipe transport, so both ends
+//! splice the pipe onto ZMQ over loopback TCP and the ZMTP bytes pass through
+//! untouched (CURVE included): the Sentinel binds its ROUTER on an ephemeral
+//! loopback port behind a [`PipeRelay`], and the worker connects its DEALER to
+//! the loopback port of a [`PipeLink`]. The transport is selected by the
+//! `pipe://` scheme of the address.
+//!
+//! The loopback ports are reachable by other local users; use CURVE where
+//! that matters. Off Windows a Unix socket stands in for the pipe (see
+//! [`casparian_protocol::endpoint::pipe_path`]).
+
+mod link;
+mod relay;
+mod sys;
+
+pub use casparian_protocol::endpoint::PIPE_SCHEME;
+pub use link::PipeLink;
+pub use relay::PipeRelay;
+
+use anyhow::{Context, Result};
+use casparian_protocol::endpoint;
+use std::path::PathBuf;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tracing::debug;
+
+/// ZMQ endpoint the Sentinel binds behind a [`PipeRelay`].
+pub const LOOPBACK_ENDPOINT: &str = "tcp://127.0.0.1:*";
+
+/// Whether `addr` selects the named-pipe transport.
+pub fn is_pipe_addr(addr: &str) -> bool {
+    addr.starts_with(PIPE_SCHEME)
+}
+
+/// Validated pipe path of a `pipe://` URI.
+fn resolve(uri: &str) -> Result<PathBuf> {
+    let name = endpoint::pipe_name(uri).with_context(|| format!("Not a pipe address: {}", uri))?;
+    endpoint::validate_ipc_addr(uri)?;
+    Ok(endpoint::pipe_path(name))
+}
+
+/// Copy bytes both ways until either side closes.
+async fn splice<A, B>(mut a: A, mut b: B)
+where
+    A: AsyncRead + AsyncWrite + Unpin,
+    B: AsyncRead + AsyncWrite + Unpin,
+{
+    if let Err(err) = tokio::io::copy_bidirectional(&mut a, &mut b).await {
+        debug!("Pipe connection closed: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert!(is_pipe_addr("pipe://casparian"));
+        assert!(!is_pipe_addr("ipc:///tmp/casparian.sock"));
+        assert!(resolve("pipe://casparian").is_ok());
+        assert!(resolve("pipe://").is_err());
+        assert!(resolve("tcp://127.0.0.1:5555").is_err());
+    }
+}
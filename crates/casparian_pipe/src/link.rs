@@ -0,0 +1,103 @@
+//! Worker side: a loopback port the worker's DEALER connects to, relayed
+//! over the Sentinel's pipe.
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn};
+
+/// Pause after a failed accept so a persistent error does not spin.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Running connection to a `pipe://` Sentinel; closed on drop.
+///
+/// Each ZMQ connection to [`endpoint`](Self::endpoint) opens its own pipe
+/// client. A pipe that cannot be opened closes the connection, and ZMQ's
+/// reconnect retries it, so the link can start before the Sentinel.
+pub struct PipeLink {
+    endpoint: String,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PipeLink {
+    /// Start relaying to the Sentinel at `uri` (`pipe://name`).
+    pub fn connect(uri: &str) -> Result<Self> {
+        let path = crate::resolve(uri)?;
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").context("Failed to bind pipe link port")?;
+        listener
+            .set_nonblocking(true)
+            .context("Failed to configure pipe link port")?;
+        let local_addr = listener
+            .local_addr()
+            .context("Failed to read pipe link port")?;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .thread_name("worker-pipe")
+            .enable_all()
+            .build()
+            .context("Failed to start pipe runtime")?;
+        let listener = runtime
+            .block_on(async { TcpListener::from_std(listener) })
+            .context("Failed to register pipe link port")?;
+
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("worker-pipe".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    tokio::select! {
+                        _ = relay(listener, path) => {}
+                        _ = shutdown_rx => {}
+                    }
+                });
+            })
+            .context("Failed to spawn pipe thread")?;
+        info!("Connecting to Sentinel over pipe {}", uri);
+        Ok(Self {
+            endpoint: format!("tcp://{}", local_addr),
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    /// Endpoint the worker's DEALER connects to instead of the Sentinel.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+}
+
+impl Drop for PipeLink {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+async fn relay(listener: TcpListener, path: PathBuf) {
+    loop {
+        match listener.accept().await {
+            Ok((tcp, _)) => {
+                let path = path.clone();
+                tokio::spawn(async move {
+                    match crate::sys::connect(&path).await {
+                        Ok(pipe) => crate::splice(tcp, pipe).await,
+                        Err(err) => debug!("Pipe {} unavailable: {}", path.display(), err),
+                    }
+                });
+            }
+            Err(err) => {
+                warn!("Pipe link accept failed: {}", err);
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+            }
+        }
+    }
+}
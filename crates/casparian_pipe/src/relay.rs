@@ -0,0 +1,97 @@
+//! Sentinel side: serves the pipe in front of the ROUTER's loopback port.
+
+use crate::sys::Listener;
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+/// Pause after a failed accept so a persistent error does not spin.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Running pipe server; stopped on drop.
+///
+/// Every pipe client gets its own TCP connection to the ROUTER at `target`,
+/// so each worker appears to the Sentinel as a separate ZMQ peer.
+pub struct PipeRelay {
+    uri: String,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PipeRelay {
+    /// Serve `uri` (`pipe://name`) and relay to the ZMQ socket bound at
+    /// `target` (`tcp://127.0.0.1:port`, see `Socket::get_last_endpoint`).
+    pub fn start(uri: &str, target: &str) -> Result<Self> {
+        let path = crate::resolve(uri)?;
+        let target: SocketAddr = target
+            .strip_prefix("tcp://")
+            .and_then(|addr| addr.parse().ok())
+            .with_context(|| format!("Pipe relay target is not a TCP endpoint: {}", target))?;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .thread_name("sentinel-pipe")
+            .enable_all()
+            .build()
+            .context("Failed to start pipe runtime")?;
+        let listener = runtime
+            .block_on(async { Listener::bind(&path) })
+            .with_context(|| format!("Failed to bind pipe {}", path.display()))?;
+
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("sentinel-pipe".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    tokio::select! {
+                        _ = serve(listener, target) => {}
+                        _ = shutdown_rx => {}
+                    }
+                });
+            })
+            .context("Failed to spawn pipe thread")?;
+        info!("Pipe transport listening on {}", uri);
+        Ok(Self {
+            uri: uri.to_string(),
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+impl Drop for PipeRelay {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+async fn serve(mut listener: Listener, target: SocketAddr) {
+    loop {
+        match listener.accept().await {
+            Ok(pipe) => {
+                tokio::spawn(async move {
+                    match TcpStream::connect(target).await {
+                        Ok(tcp) => crate::splice(pipe, tcp).await,
+                        Err(err) => warn!("Pipe relay cannot reach {}: {}", target, err),
+                    }
+                });
+            }
+            Err(err) => {
+                warn!("Pipe accept failed: {}", err);
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Platform pipe primitives: Windows named pipes, or a Unix socket elsewhere.
+
+pub(crate) use imp::{connect, Listener};
+
+#[cfg(windows)]
+mod imp {
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, Instant};
+    use tokio::net::windows::named_pipe::{
+        ClientOptions, NamedPipeClient, NamedPipeServer, ServerOptions,
+    };
+
+    /// Every server instance is connected; retry until one frees up.
+    const ERROR_PIPE_BUSY: i32 = 231;
+    /// How long a client waits for a free pipe instance.
+    const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+    const BUSY_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+    /// Pipe server. One instance always waits for the next client, so a
+    /// connecting client never sees the pipe missing.
+    pub(crate) struct Listener {
+        path: PathBuf,
+        next: NamedPipeServer,
+    }
+
+    impl Listener {
+        /// Create the first instance; fails if another process owns the
+        /// name. Must run inside the runtime.
+        pub(crate) fn bind(path: &Path) -> io::Result<Self> {
+            let next = ServerOptions::new()
+                .first_pipe_instance(true)
+                .create(path)?;
+            Ok(Self {
+                path: path.to_path_buf(),
+                next,
+            })
+        }
+
+        pub(crate) async fn accept(&mut self) -> io::Result<NamedPipeServer> {
+            self.next.connect().await?;
+            let next = ServerOptions::new().create(&self.path)?;
+            Ok(std::mem::replace(&mut self.next, next))
+        }
+    }
+
+    pub(crate) async fn connect(path: &Path) -> io::Result<NamedPipeClient> {
+        let deadline = Instant::now() + BUSY_TIMEOUT;
+        loop {
+            match ClientOptions::new().open(path) {
+                Err(err)
+                    if err.raw_os_error() == Some(ERROR_PIPE_BUSY) && Instant::now() < deadline =>
+                {
+                    tokio::time::sleep(BUSY_RETRY_DELAY).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod imp {
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use tokio::net::{UnixListener, UnixStream};
+
+    /// Unix socket standing in for the pipe; unlinked on drop.
+    pub(crate) struct Listener {
+        path: PathBuf,
+        inner: UnixListener,
+    }
+
+    impl Listener {
+        /// Bind the socket. Must run inside the runtime.
+        pub(crate) fn bind(path: &Path) -> io::Result<Self> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let inner = UnixListener::bind(path)?;
+            Ok(Self {
+                path: path.to_path_buf(),
+                inner,
+            })
+        }
+
+        pub(crate) async fn accept(&mut self) -> io::Result<UnixStream> {
+            self.inner.accept().await.map(|(stream, _)| stream)
+        }
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    pub(crate) async fn connect(path: &Path) -> io::Result<UnixStream> {
+        UnixStream::connect(path).await
+    }
+}
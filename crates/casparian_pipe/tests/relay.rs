@@ -0,0 +1,94 @@
+//! End-to-end: a DEALER talks to a ROUTER through PipeLink and PipeRelay.
+
+use casparian_pipe::{PipeLink, PipeRelay, LOOPBACK_ENDPOINT};
+use casparian_protocol::types::IdentifyPayload;
+use casparian_protocol::{JobId, Message, OpCode};
+
+fn recv(socket: &zmq::Socket) -> Vec<Vec<u8>> {
+    assert!(socket.poll(zmq::POLLIN, 10_000).unwrap() > 0, "timed out");
+    socket.recv_multipart(0).unwrap()
+}
+
+fn pipe_uri(test: &str) -> String {
+    format!("pipe://casparian-{}-{}", test, std::process::id())
+}
+
+fn bind_router(context: &zmq::Context) -> (zmq::Socket, String) {
+    let router = context.socket(zmq::ROUTER).unwrap();
+    router.bind(LOOPBACK_ENDPOINT).unwrap();
+    let endpoint = router.get_last_endpoint().unwrap().unwrap();
+    (router, endpoint)
+}
+
+#[test]
+fn test_worker_and_sentinel_exchange_over_pipe() {
+    let context = zmq::Context::new();
+    let (router, target) = bind_router(&context);
+    let uri = pipe_uri("exchange");
+    let _relay = PipeRelay::start(&uri, &target).unwrap();
+
+    let link = PipeLink::connect(&uri).unwrap();
+    let dealer = context.socket(zmq::DEALER).unwrap();
+    dealer.connect(link.endpoint()).unwrap();
+
+    let identify = IdentifyPayload {
+        capabilities: vec!["*".to_string()],
+        worker_id: Some("pipe-worker".to_string()),
+        protocol_versions: Default::default(),
+        protocol_features: Default::default(),
+    };
+    let (header, body) = Message::new(
+        OpCode::Identify,
+        JobId::new(1),
+        serde_json::to_vec(&identify).unwrap(),
+    )
+    .unwrap()
+    .pack()
+    .unwrap();
+    dealer.send_multipart([&header, &body], 0).unwrap();
+
+    let parts = recv(&router);
+    assert_eq!(parts.len(), 3);
+    let msg = Message::unpack(&parts[1..]).unwrap();
+    assert_eq!(msg.header.opcode, OpCode::Identify);
+    let received: IdentifyPayload = serde_json::from_slice(&msg.payload).unwrap();
+    assert_eq!(received.worker_id.as_deref(), Some("pipe-worker"));
+
+    // Replies find their way back by identity
+    router
+        .send_multipart([&parts[0], &parts[1], &parts[2]], 0)
+        .unwrap();
+    let reply = recv(&dealer);
+    assert_eq!(reply, parts[1..].to_vec());
+}
+
+#[test]
+fn test_link_waits_for_a_late_relay() {
+    let context = zmq::Context::new();
+    let uri = pipe_uri("late");
+    let link = PipeLink::connect(&uri).unwrap();
+    let dealer = context.socket(zmq::DEALER).unwrap();
+    dealer.set_reconnect_ivl(50).unwrap();
+    dealer.connect(link.endpoint()).unwrap();
+    dealer.send("hello", 0).unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    let (router, target) = bind_router(&context);
+    let _relay = PipeRelay::start(&uri, &target).unwrap();
+
+    let parts = recv(&router);
+    assert_eq!(parts[1], b"hello");
+}
+
+#[test]
+fn test_second_relay_on_the_same_pipe_fails() {
+    let context = zmq::Context::new();
+    let (_router, target) = bind_router(&context);
+    let uri = pipe_uri("taken");
+    let relay = PipeRelay::start(&uri, &target).unwrap();
+    assert!(PipeRelay::start(&uri, &target).is_err());
+
+    // The name is free again once the first relay stops
+    drop(relay);
+    PipeRelay::start(&uri, &target).unwrap();
+}
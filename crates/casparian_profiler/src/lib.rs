@@ -38,12 +38,42 @@
 //! - `db.query` - Database queries
 //!
 //! **Reserved characters**: Zone names MUST NOT contain `:` or `,` (used in TSV export).
+//!
+//! ## Chrome Trace Export
+//!
+//! [`Profiler::export_chrome_trace`] renders the frame history as Chrome trace
+//! JSON, which loads in `chrome://tracing` and the Perfetto UI. Each frame is a
+//! slice; zones are laid out inside it, nested by their dotted names
+//! (`scanner.walk` under `scanner`). Zones only keep per-frame totals, so their
+//! offsets within the frame are synthetic; durations are exact.
+//!
+//! For sessions longer than the history, [`Profiler::start_chrome_trace`]
+//! appends every frame to a file as it ends:
+//!
+//! ```rust,ignore
+//! profiler.start_chrome_trace("tui-trace.json")?;
+//! // ... run the TUI ...
+//! profiler.finish_chrome_trace()?;
+//! ```
+//!
+//! ## Threads
+//!
+//! `Profiler` is single-threaded. Worker pipelines and the Sentinel event loop
+//! use [`SharedProfiler`], which accumulates zone totals from any thread and
+//! can mirror zones as `tracing` spans (`tracing` feature).
 
 use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 use std::time::Instant;
 
+mod shared;
+
+pub use shared::{SharedProfiler, SharedZoneGuard, ZoneStats};
+
 /// Number of frames to keep in history (30 seconds at 250ms tick rate)
 const FRAME_HISTORY: usize = 120;
 
@@ -68,10 +98,19 @@ struct ProfilerInner {
     zone_times: HashMap<&'static str, ZoneAccum>,
     /// When current frame started
     frame_start: Option<Instant>,
+    /// Trace timestamps are microseconds since this instant
+    epoch: Instant,
+    /// Frames ended since creation (numbers frames in traces)
+    frames_ended: u64,
+    /// Continuous Chrome trace output, if started
+    trace: Option<TraceStream>,
 }
 
 /// Record of a single frame
 struct FrameRecord {
+    number: u64,
+    /// Start, in microseconds since the profiler's epoch
+    start_us: f64,
     total_ms: f64,
     /// Zone timings: (name, milliseconds)
     zones: Vec<(&'static str, f64)>,
@@ -133,6 +172,9 @@ impl Profiler {
                 frame_times: VecDeque::with_capacity(FRAME_HISTORY),
                 zone_times: HashMap::new(),
                 frame_start: None,
+                epoch: Instant::now(),
+                frames_ended: 0,
+                trace: None,
             }),
         }
     }
@@ -152,6 +194,8 @@ impl Profiler {
         if let Some(start) = inner.frame_start.take() {
             let total = start.elapsed();
             let record = FrameRecord {
+                number: inner.frames_ended,
+                start_us: start.duration_since(inner.epoch).as_secs_f64() * 1_000_000.0,
                 total_ms: total.as_secs_f64() * 1000.0,
                 zones: inner
                     .zone_times
@@ -159,6 +203,10 @@ impl Profiler {
                     .map(|(k, v)| (*k, v.total_ns as f64 / 1_000_000.0))
                     .collect(),
             };
+            inner.frames_ended += 1;
+            if let Some(trace) = inner.trace.as_mut() {
+                trace.write_frame(&record, self.budget_ms);
+            }
             inner.frame_times.push_back(record);
             if inner.frame_times.len() > FRAME_HISTORY {
                 inner.frame_times.pop_front();
@@ -353,6 +401,178 @@ impl Profiler {
             String::new()
         }
     }
+
+    /// Export the frame history as Chrome trace JSON (oldest frame first).
+    ///
+    /// Open the result in `chrome://tracing` or <https://ui.perfetto.dev>.
+    /// Output format (one object per frame and zone):
+    /// ```text
+    /// {"traceEvents":[
+    /// {"name":"frame","cat":"frame","ph":"X","ts":0.0,"dur":205300.0,"pid":1,"tid":1,"args":{"frame":0,"budget_pct":82.1}},
+    /// {"name":"scanner.walk","cat":"zone","ph":"X","ts":0.0,"dur":120100.0,"pid":1,"tid":1},
+    /// ...
+    /// ],"displayTimeUnit":"ms"}
+    /// ```
+    pub fn export_chrome_trace(&self) -> String {
+        let inner = self.inner.borrow();
+        let mut events = vec![thread_name_event()];
+        for frame in &inner.frame_times {
+            events.extend(frame_events(frame, self.budget_ms));
+        }
+        format!(
+            "{{\"traceEvents\":[\n{}\n],\"displayTimeUnit\":\"ms\"}}\n",
+            events.join(",\n")
+        )
+    }
+
+    /// Append every frame to a Chrome trace file at `path` as it ends,
+    /// replacing the file. Frames already in the history are not written.
+    ///
+    /// The file is a JSON array flushed after each frame; a session that
+    /// exits without [`Profiler::finish_chrome_trace`] still loads, as the
+    /// trace viewers accept an array without its closing bracket.
+    pub fn start_chrome_trace(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(format!("[\n{}", thread_name_event()).as_bytes())?;
+        out.flush()?;
+        self.inner.borrow_mut().trace = Some(TraceStream {
+            out,
+            error: None,
+            closed: false,
+        });
+        Ok(())
+    }
+
+    /// Close the trace file started by [`Profiler::start_chrome_trace`].
+    ///
+    /// Returns the first write error since the trace started; frames after
+    /// it were not written. Does nothing when no trace is running.
+    pub fn finish_chrome_trace(&self) -> io::Result<()> {
+        let trace = self.inner.borrow_mut().trace.take();
+        match trace {
+            Some(mut trace) => trace.close(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Chrome trace file being written frame by frame.
+struct TraceStream {
+    out: BufWriter<File>,
+    /// First write error; nothing is written after it
+    error: Option<io::Error>,
+    closed: bool,
+}
+
+impl TraceStream {
+    fn write_frame(&mut self, frame: &FrameRecord, budget_ms: u64) {
+        if self.error.is_some() {
+            return;
+        }
+        let mut chunk = String::new();
+        for event in frame_events(frame, budget_ms) {
+            chunk.push_str(",\n");
+            chunk.push_str(&event);
+        }
+        if let Err(err) = self
+            .out
+            .write_all(chunk.as_bytes())
+            .and_then(|()| self.out.flush())
+        {
+            self.error = Some(err);
+        }
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.closed = true;
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.out.write_all(b"\n]\n")?;
+        self.out.flush()
+    }
+}
+
+impl Drop for TraceStream {
+    fn drop(&mut self) {
+        if !self.closed {
+            let _ = self.close();
+        }
+    }
+}
+
+/// Trace events (JSON objects) of one frame: the frame slice, then its zones.
+fn frame_events(frame: &FrameRecord, budget_ms: u64) -> Vec<String> {
+    let budget_pct = (frame.total_ms / budget_ms as f64) * 100.0;
+    let mut events = vec![format!(
+        "{{\"name\":\"frame\",\"cat\":\"frame\",\"ph\":\"X\",\"ts\":{:.1},\"dur\":{:.1},\"pid\":1,\"tid\":1,\"args\":{{\"frame\":{},\"budget_pct\":{:.1}}}}}",
+        frame.start_us,
+        frame.total_ms * 1000.0,
+        frame.number,
+        budget_pct
+    )];
+
+    // Parent of a zone: its longest dotted prefix that was also recorded
+    let parent = |name: &str| -> Option<&'static str> {
+        let mut prefix = name;
+        while let Some((head, _)) = prefix.rsplit_once('.') {
+            if let Some(found) = frame.zones.iter().find(|(zone, _)| *zone == head) {
+                return Some(found.0);
+            }
+            prefix = head;
+        }
+        None
+    };
+    let mut children: HashMap<Option<&'static str>, Vec<(&'static str, f64)>> = HashMap::new();
+    for (name, ms) in &frame.zones {
+        children.entry(parent(name)).or_default().push((*name, *ms));
+    }
+    for zones in children.values_mut() {
+        zones.sort_by(|a, b| a.0.cmp(b.0));
+    }
+    place_zones(&children, None, frame.start_us, &mut events);
+    events
+}
+
+/// Lay the children of `parent` end to end from `start_us`, recursively.
+fn place_zones(
+    children: &HashMap<Option<&'static str>, Vec<(&'static str, f64)>>,
+    parent: Option<&'static str>,
+    start_us: f64,
+    events: &mut Vec<String>,
+) {
+    let mut cursor = start_us;
+    for (name, ms) in children.get(&parent).into_iter().flatten() {
+        let dur = ms * 1000.0;
+        events.push(format!(
+            "{{\"name\":{},\"cat\":\"zone\",\"ph\":\"X\",\"ts\":{:.1},\"dur\":{:.1},\"pid\":1,\"tid\":1}}",
+            json_string(name),
+            cursor,
+            dur
+        ));
+        place_zones(children, Some(name), cursor, events);
+        cursor += dur;
+    }
+}
+
+fn thread_name_event() -> String {
+    "{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":1,\"args\":{\"name\":\"tui\"}}"
+        .to_string()
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
 }
 
 impl Default for Profiler {
@@ -519,6 +739,74 @@ mod tests {
         assert!(zones.contains("zone.scanner.walk="));
     }
 
+    #[test]
+    fn test_export_chrome_trace_nests_dotted_zones() {
+        let profiler = Profiler::new(250);
+
+        profiler.begin_frame();
+        {
+            let _scanner = profiler.zone("scanner");
+            let _walk = profiler.zone("scanner.walk");
+            thread::sleep(Duration::from_millis(2));
+        }
+        {
+            let _draw = profiler.zone("tui.draw");
+        }
+        profiler.end_frame();
+
+        let trace = profiler.export_chrome_trace();
+        assert!(trace.starts_with("{\"traceEvents\":["));
+        assert!(trace.contains("\"name\":\"frame\""));
+        assert!(trace.contains("\"args\":{\"frame\":0,"));
+
+        let frame = profiler.inner.borrow();
+        let events = frame_events(frame.frame_times.back().unwrap(), 250);
+        // frame, then scanner with scanner.walk at its start, then tui.draw after it
+        assert_eq!(events.len(), 4);
+        let ts = |event: &str| -> f64 {
+            let rest = &event[event.find("\"ts\":").unwrap() + 5..];
+            rest[..rest.find(',').unwrap()].parse().unwrap()
+        };
+        assert!(events[1].contains("\"name\":\"scanner\""));
+        assert!(events[2].contains("\"name\":\"scanner.walk\""));
+        assert!(events[3].contains("\"name\":\"tui.draw\""));
+        assert_eq!(ts(&events[1]), ts(&events[0]));
+        assert_eq!(ts(&events[2]), ts(&events[1]));
+        assert!(ts(&events[3]) >= ts(&events[1]) + 2000.0);
+    }
+
+    #[test]
+    fn test_continuous_chrome_trace() {
+        let path = std::env::temp_dir().join(format!(
+            "casparian_profiler_trace_{}.json",
+            std::process::id()
+        ));
+        let profiler = Profiler::new(250);
+        profiler.begin_frame();
+        profiler.end_frame();
+        profiler.start_chrome_trace(&path).unwrap();
+        for _ in 0..3 {
+            profiler.begin_frame();
+            {
+                let _z = profiler.zone("tui.draw");
+            }
+            profiler.end_frame();
+        }
+
+        // Readable before the trace is finished, without the closing bracket
+        let partial = std::fs::read_to_string(&path).unwrap();
+        assert!(partial.starts_with("[\n"));
+        assert!(!partial.contains("\"frame\":0,"));
+        assert_eq!(partial.matches("\"name\":\"frame\"").count(), 3);
+
+        profiler.finish_chrome_trace().unwrap();
+        let finished = std::fs::read_to_string(&path).unwrap();
+        assert!(finished.ends_with("\n]\n"));
+        assert!(finished.contains("\"frame\":3,"));
+        profiler.finish_chrome_trace().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_empty_profiler() {
         let profiler = Profiler::new(250);
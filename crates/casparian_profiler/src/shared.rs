@@ -0,0 +1,263 @@
+//! Thread-safe profiler for worker pipelines and the Sentinel event loop.
+//!
+//! [`Profiler`](crate::Profiler) is frame-oriented and single-threaded.
+//! [`SharedProfiler`] has no frames: it accumulates call count, total and
+//! maximum time per zone since creation (or the last [`SharedProfiler::reset`]),
+//! from any number of threads. Share it through an `Arc` or a `static`.
+//!
+//! Counters are atomics. Zones live in maps sharded by name, so threads
+//! timing different zones rarely touch the same lock, and a zone seen before
+//! only takes a shard's read lock.
+//!
+//! With the `tracing` feature every zone is also a `profiler.zone` span at
+//! TRACE level carrying `zone` and, when it closes, `elapsed_us` fields, so
+//! the same timings show up in logs when span events are enabled.
+//!
+//! ```rust,ignore
+//! static PROFILER: LazyLock<SharedProfiler> = LazyLock::new(SharedProfiler::new);
+//!
+//! {
+//!     let _zone = PROFILER.zone("worker.encode");
+//!     // ... encode a batch ...
+//! }
+//! let zones = PROFILER.zones();
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+/// Number of zone map shards
+const SHARDS: usize = 16;
+
+/// Zone profiler safe to share across threads.
+pub struct SharedProfiler {
+    shards: [RwLock<HashMap<&'static str, Arc<ZoneCounters>>>; SHARDS],
+}
+
+#[derive(Default)]
+struct ZoneCounters {
+    total_ns: AtomicU64,
+    calls: AtomicU64,
+    max_ns: AtomicU64,
+}
+
+impl ZoneCounters {
+    fn add(&self, elapsed_ns: u64) {
+        self.total_ns.fetch_add(elapsed_ns, AtomicOrdering::Relaxed);
+        self.calls.fetch_add(1, AtomicOrdering::Relaxed);
+        self.max_ns.fetch_max(elapsed_ns, AtomicOrdering::Relaxed);
+    }
+}
+
+/// Accumulated timings of one zone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneStats {
+    pub name: &'static str,
+    pub calls: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+}
+
+impl ZoneStats {
+    pub fn mean_ms(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.total_ms / self.calls as f64
+        }
+    }
+}
+
+/// RAII guard that times a zone of a [`SharedProfiler`]. Records elapsed
+/// time when dropped, on whichever thread drops it.
+pub struct SharedZoneGuard<'a> {
+    profiler: &'a SharedProfiler,
+    zone: &'static str,
+    start: Instant,
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+}
+
+impl Drop for SharedZoneGuard<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed().as_nanos() as u64;
+        #[cfg(feature = "tracing")]
+        self.span.record("elapsed_us", elapsed / 1_000);
+        self.profiler.record(self.zone, elapsed);
+    }
+}
+
+impl SharedProfiler {
+    pub fn new() -> Self {
+        Self {
+            shards: std::array::from_fn(|_| RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Time a named zone. Returns guard that records on drop.
+    ///
+    /// Same naming convention as [`Profiler::zone`](crate::Profiler::zone):
+    /// `{module}.{operation}`, e.g. `worker.encode`, `sentinel.dispatch`.
+    ///
+    /// With the `tracing` feature the guard holds an entered span and is
+    /// not `Send`; drop it before an `.await`.
+    pub fn zone(&self, name: &'static str) -> SharedZoneGuard<'_> {
+        SharedZoneGuard {
+            profiler: self,
+            zone: name,
+            start: Instant::now(),
+            #[cfg(feature = "tracing")]
+            span: tracing::trace_span!(
+                "profiler.zone",
+                zone = name,
+                elapsed_us = tracing::field::Empty
+            )
+            .entered(),
+        }
+    }
+
+    /// Add one call of `elapsed_ns` to a zone, for timings taken elsewhere.
+    pub fn record(&self, name: &'static str, elapsed_ns: u64) {
+        let shard = &self.shards[shard_index(name)];
+        let existing = shard
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned();
+        let counters = match existing {
+            Some(counters) => counters,
+            None => shard
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(name)
+                .or_default()
+                .clone(),
+        };
+        counters.add(elapsed_ns);
+    }
+
+    /// Timings of every zone, sorted by total time descending.
+    pub fn zones(&self) -> Vec<ZoneStats> {
+        let mut zones: Vec<ZoneStats> = self
+            .shards
+            .iter()
+            .flat_map(|shard| {
+                let shard = shard.read().unwrap_or_else(|e| e.into_inner());
+                shard
+                    .iter()
+                    .map(|(name, counters)| ZoneStats {
+                        name,
+                        calls: counters.calls.load(AtomicOrdering::Relaxed),
+                        total_ms: ns_to_ms(counters.total_ns.load(AtomicOrdering::Relaxed)),
+                        max_ms: ns_to_ms(counters.max_ns.load(AtomicOrdering::Relaxed)),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        zones.sort_by(|a, b| {
+            b.total_ms
+                .total_cmp(&a.total_ms)
+                .then_with(|| a.name.cmp(b.name))
+        });
+        zones
+    }
+
+    /// Forget all timings.
+    pub fn reset(&self) {
+        for shard in &self.shards {
+            shard.write().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+    }
+
+    /// Export zone totals as key=value lines, like
+    /// [`Profiler::export_zones`](crate::Profiler::export_zones).
+    ///
+    /// Output format (sorted by time descending):
+    /// ```text
+    /// zone.worker.encode=1520.4
+    /// zone.worker.write=310.9
+    /// ```
+    pub fn export_zones(&self) -> String {
+        self.zones()
+            .iter()
+            .map(|zone| format!("zone.{}={:.1}\n", zone.name, zone.total_ms))
+            .collect()
+    }
+}
+
+impl Default for SharedProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn shard_index(name: &str) -> usize {
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    hasher.finish() as usize % SHARDS
+}
+
+fn ns_to_ms(ns: u64) -> f64 {
+    ns as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_zones_from_many_threads() {
+        let profiler = Arc::new(SharedProfiler::new());
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let profiler = Arc::clone(&profiler);
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        let _zone = profiler.zone(if i % 2 == 0 {
+                            "worker.encode"
+                        } else {
+                            "worker.write"
+                        });
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let zones = profiler.zones();
+        assert_eq!(zones.len(), 2);
+        assert!(zones.iter().all(|zone| zone.calls == 400));
+        assert!(profiler.export_zones().contains("zone.worker.encode="));
+
+        profiler.reset();
+        assert!(profiler.zones().is_empty());
+    }
+
+    #[test]
+    fn test_zone_stats() {
+        let profiler = SharedProfiler::new();
+        profiler.record("sentinel.dispatch", 2_000_000);
+        profiler.record("sentinel.dispatch", 6_000_000);
+        {
+            let _zone = profiler.zone("sentinel.poll");
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let zones = profiler.zones();
+        assert_eq!(zones[0].name, "sentinel.poll");
+        assert!(zones[0].total_ms >= 10.0);
+        let dispatch = &zones[1];
+        assert_eq!(dispatch.calls, 2);
+        assert_eq!(dispatch.total_ms, 8.0);
+        assert_eq!(dispatch.max_ms, 6.0);
+        assert_eq!(dispatch.mean_ms(), 4.0);
+    }
+}
@@ -0,0 +1,114 @@
+//! Capability tags for routing jobs to compatible workers.
+//!
+//! Plugins declare the runtimes and features they need (`requirements` in the
+//! plugin manifest, e.g. `["python3.11", "duckdb", "gpu"]`); workers advertise
+//! what they support in IDENTIFY. A job is only dispatched to a worker that
+//! advertises every requirement of its plugin. A worker advertising `*`
+//! accepts everything, which keeps homogeneous pools working unchanged.
+//!
+//! Tags are compared case-insensitively.
+
+/// Capability that satisfies any requirement.
+pub const WILDCARD_CAPABILITY: &str = "*";
+
+/// Returns true if `tag` is a well-formed capability or requirement tag:
+/// non-empty ASCII letters, digits, `.`, `_`, `-`, `=`, `+`.
+pub fn is_valid_capability_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '=' | '+'))
+}
+
+/// Canonical form of a tag (trimmed, lowercase).
+pub fn normalize_capability_tag(tag: &str) -> String {
+    tag.trim().to_ascii_lowercase()
+}
+
+/// Validate and canonicalize a plugin's declared requirements: normalized,
+/// sorted, and de-duplicated. Errors name the first invalid tag.
+pub fn normalize_requirements(requirements: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized = Vec::with_capacity(requirements.len());
+    for requirement in requirements {
+        let tag = normalize_capability_tag(requirement);
+        if !is_valid_capability_tag(&tag) {
+            return Err(format!(
+                "invalid requirement '{}': use letters, digits, '.', '_', '-', '=', '+'",
+                requirement
+            ));
+        }
+        normalized.push(tag);
+    }
+    normalized.sort();
+    normalized.dedup();
+    Ok(normalized)
+}
+
+/// Requirements in `requirements` that `capabilities` does not provide.
+///
+/// Empty when the worker is compatible.
+pub fn unmet_requirements(capabilities: &[String], requirements: &[String]) -> Vec<String> {
+    if capabilities
+        .iter()
+        .any(|cap| cap.trim() == WILDCARD_CAPABILITY)
+    {
+        return Vec::new();
+    }
+    requirements
+        .iter()
+        .filter(|req| {
+            let req = normalize_capability_tag(req);
+            !capabilities
+                .iter()
+                .any(|cap| normalize_capability_tag(cap) == req)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns true if a worker with `capabilities` can run a plugin with `requirements`.
+pub fn satisfies_requirements(capabilities: &[String], requirements: &[String]) -> bool {
+    unmet_requirements(capabilities, requirements).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_wildcard_and_empty_requirements() {
+        assert!(satisfies_requirements(&tags(&["*"]), &tags(&["gpu"])));
+        assert!(satisfies_requirements(&tags(&["duckdb"]), &[]));
+        assert!(satisfies_requirements(&[], &[]));
+    }
+
+    #[test]
+    fn test_unmet_requirements() {
+        let worker = tags(&["python3.11", "DuckDB"]);
+        assert!(satisfies_requirements(&worker, &tags(&["duckdb"])));
+        assert_eq!(
+            unmet_requirements(&worker, &tags(&["python3.11", "gpu", "cuda12"])),
+            tags(&["gpu", "cuda12"])
+        );
+        assert_eq!(unmet_requirements(&[], &tags(&["gpu"])), tags(&["gpu"]));
+    }
+
+    #[test]
+    fn test_tag_validation() {
+        assert!(is_valid_capability_tag("python3.11"));
+        assert!(is_valid_capability_tag("cuda-12"));
+        assert!(!is_valid_capability_tag(""));
+        assert!(!is_valid_capability_tag("has space"));
+        assert!(!is_valid_capability_tag("*"));
+        assert_eq!(normalize_capability_tag(" GPU "), "gpu");
+        assert_eq!(
+            normalize_requirements(&tags(&["gpu", "DuckDB", "GPU"])).unwrap(),
+            tags(&["duckdb", "gpu"])
+        );
+        assert!(normalize_requirements(&tags(&["gpu", "*"])).is_err());
+    }
+}
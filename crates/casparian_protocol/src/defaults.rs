@@ -5,4 +5,7 @@ pub const DEFAULT_CONTROL_ADDR: &str = "tcp://127.0.0.1:5556";
 pub const DEFAULT_STATE_STORE_URL: &str = "sqlite:state.sqlite";
 pub const DEFAULT_SINK_TOPIC: &str = "output";
 pub const DEFAULT_SINK_URI: &str = "parquet://./output/";
+/// Namespace of plugins and sinks deployed without an explicit one.
+pub const DEFAULT_NAMESPACE: &str = "default";
 pub const CANCELLED_BY_USER_MESSAGE: &str = "Cancelled by user";
+pub const PREEMPTED_MESSAGE: &str = "Preempted by an urgent job";
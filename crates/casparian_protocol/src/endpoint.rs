@@ -0,0 +1,537 @@
+//! Local transport endpoints: default local addresses, `ipc://` and `pipe://`
+//! checks, stale socket cleanup and reachability diagnostics.
+//!
+//! Two local transports are supported. `ipc://path` is libzmq's own and is a
+//! Unix domain socket on every platform. `pipe://name` is a Windows named pipe
+//! (`\\.\pipe\name`); libzmq cannot speak it, so both ends relay it to ZMQ
+//! over loopback TCP (the `casparian_pipe` crate). Off Windows a `pipe://`
+//! address is served from a Unix socket instead, so the relays run everywhere.
+//!
+//! Defaults are per-user: a named pipe suffixed with the user name on
+//! Windows; the runtime dir or a uid-suffixed temp file on Unix. Linux
+//! abstract sockets (`ipc://@name`) have no file and skip the path checks.
+
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Scheme of ZMQ's local socket transport.
+pub const IPC_SCHEME: &str = "ipc://";
+
+/// Scheme of the named-pipe transport.
+pub const PIPE_SCHEME: &str = "pipe://";
+
+/// Longest pipe name Windows accepts after `\\.\pipe\` (256 in total).
+pub const MAX_PIPE_NAME_LEN: usize = 247;
+
+/// Longest socket path the OS accepts (`sun_path` without the NUL).
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+pub const MAX_IPC_PATH_LEN: usize = 103;
+#[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "freebsd")))]
+pub const MAX_IPC_PATH_LEN: usize = 107;
+
+/// How long a diagnostic TCP probe waits for a connection.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Error)]
+pub enum IpcError {
+    #[error("IPC address {0} has no path")]
+    EmptyPath(String),
+
+    #[error("IPC path {path} is {len} bytes; sockets are limited to {max}")]
+    PathTooLong {
+        path: PathBuf,
+        len: usize,
+        max: usize,
+    },
+
+    #[error("Invalid pipe name '{name}': {reason}")]
+    InvalidPipeName { name: String, reason: &'static str },
+
+    #[error("IPC path {0} exists and is not a socket")]
+    NotASocket(PathBuf),
+
+    #[error("Another process is already listening on {0}")]
+    InUse(PathBuf),
+
+    #[error("Failed to prepare IPC path {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Socket path of an `ipc://` address, or None for other transports.
+pub fn ipc_path(addr: &str) -> Option<&Path> {
+    addr.strip_prefix(IPC_SCHEME).map(Path::new)
+}
+
+/// Pipe name of a `pipe://` address, or None for other transports.
+pub fn pipe_name(addr: &str) -> Option<&str> {
+    addr.strip_prefix(PIPE_SCHEME)
+}
+
+/// Default per-user `ipc://` address for a socket called `name`.
+pub fn default_ipc_addr(name: &str) -> String {
+    format!("{}{}", IPC_SCHEME, default_ipc_path(name).display())
+}
+
+/// Default per-user local address for `name`: a named pipe on Windows, an
+/// `ipc://` socket elsewhere.
+pub fn default_local_addr(name: &str) -> String {
+    #[cfg(windows)]
+    {
+        // Pipe names are machine-wide; scope them to the user
+        let user = std::env::var("USERNAME").unwrap_or_else(|_| "default".to_string());
+        format!("{}{}-{}", PIPE_SCHEME, name, user)
+    }
+    #[cfg(not(windows))]
+    {
+        default_ipc_addr(name)
+    }
+}
+
+/// Where the named pipe `name` lives: `\\.\pipe\name` on Windows, and
+/// elsewhere the Unix socket the pipe relays use in its place.
+pub fn pipe_path(name: &str) -> PathBuf {
+    #[cfg(windows)]
+    {
+        PathBuf::from(format!(r"\\.\pipe\{}", name))
+    }
+    #[cfg(not(windows))]
+    {
+        default_ipc_path(&format!("{}.pipe", name))
+    }
+}
+
+#[cfg(unix)]
+fn default_ipc_path(name: &str) -> PathBuf {
+    // XDG_RUNTIME_DIR is already private to the user
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        let path = Path::new(&runtime_dir).join(format!("{}.sock", name));
+        if path_len(&path) <= MAX_IPC_PATH_LEN {
+            return path;
+        }
+    }
+    // Shared temp dirs need the uid to avoid collisions
+    let uid = unsafe { libc::getuid() };
+    let file_name = format!("{}_{}.sock", name, uid);
+    let path = std::env::temp_dir().join(&file_name);
+    if path_len(&path) <= MAX_IPC_PATH_LEN {
+        return path;
+    }
+    // macOS per-user temp dirs can be too long for a socket path
+    Path::new("/tmp").join(file_name)
+}
+
+#[cfg(windows)]
+fn default_ipc_path(name: &str) -> PathBuf {
+    let file_name = format!("{}.sock", name);
+    if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+        let path = Path::new(&local_app_data)
+            .join("casparian")
+            .join(&file_name);
+        if path_len(&path) <= MAX_IPC_PATH_LEN {
+            return path;
+        }
+    }
+    let user = std::env::var("USERNAME").unwrap_or_else(|_| "default".to_string());
+    std::env::temp_dir().join(format!("{}_{}.sock", name, user))
+}
+
+fn path_len(path: &Path) -> usize {
+    path.as_os_str().len()
+}
+
+fn is_abstract(path: &Path) -> bool {
+    path.as_os_str().to_string_lossy().starts_with('@')
+}
+
+/// Check the path of an `ipc://` address or the name of a `pipe://` one.
+/// Other transports pass.
+pub fn validate_ipc_addr(addr: &str) -> Result<(), IpcError> {
+    if let Some(name) = pipe_name(addr) {
+        return validate_pipe_name(name);
+    }
+    let Some(path) = ipc_path(addr) else {
+        return Ok(());
+    };
+    if path.as_os_str().is_empty() {
+        return Err(IpcError::EmptyPath(addr.to_string()));
+    }
+    let len = path_len(path);
+    if len > MAX_IPC_PATH_LEN {
+        return Err(IpcError::PathTooLong {
+            path: path.to_path_buf(),
+            len,
+            max: MAX_IPC_PATH_LEN,
+        });
+    }
+    Ok(())
+}
+
+fn validate_pipe_name(name: &str) -> Result<(), IpcError> {
+    let invalid = |reason| IpcError::InvalidPipeName {
+        name: name.to_string(),
+        reason,
+    };
+    if name.is_empty() {
+        return Err(invalid("empty"));
+    }
+    if name.contains(['\\', '/']) {
+        return Err(invalid("contains a path separator"));
+    }
+    if name.len() > MAX_PIPE_NAME_LEN {
+        return Err(invalid("longer than 247 bytes"));
+    }
+    #[cfg(not(windows))]
+    {
+        let path = pipe_path(name);
+        let len = path_len(&path);
+        if len > MAX_IPC_PATH_LEN {
+            return Err(IpcError::PathTooLong {
+                path,
+                len,
+                max: MAX_IPC_PATH_LEN,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Make an `ipc://` or `pipe://` address ready to bind: validate it, create
+/// the parent directory and remove a socket file left behind by a process
+/// that exited without unlinking it. A socket someone still listens on is not
+/// touched. Windows named pipes vanish with their server and need no cleanup.
+/// Other transports pass unchanged.
+pub fn prepare_ipc_bind(addr: &str) -> Result<(), IpcError> {
+    validate_ipc_addr(addr)?;
+    if let Some(name) = pipe_name(addr) {
+        #[cfg(not(windows))]
+        prepare_socket_path(&pipe_path(name))?;
+        #[cfg(windows)]
+        let _ = name;
+        return Ok(());
+    }
+    match ipc_path(addr) {
+        Some(path) => prepare_socket_path(path),
+        None => Ok(()),
+    }
+}
+
+fn prepare_socket_path(path: &Path) -> Result<(), IpcError> {
+    if is_abstract(path) {
+        return Ok(());
+    }
+    let io_err = |source| IpcError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(io_err(err)),
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if !metadata.file_type().is_socket() {
+            return Err(IpcError::NotASocket(path.to_path_buf()));
+        }
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(IpcError::InUse(path.to_path_buf()));
+        }
+    }
+    #[cfg(not(unix))]
+    if metadata.is_dir() {
+        return Err(IpcError::NotASocket(path.to_path_buf()));
+    }
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(io_err(err)),
+    }
+}
+
+/// Outcome of probing an endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointDiagnosis {
+    pub addr: String,
+    /// None when the transport cannot be probed without speaking ZMQ
+    pub reachable: Option<bool>,
+    /// What was found, phrased for an operator
+    pub detail: String,
+}
+
+impl EndpointDiagnosis {
+    fn new(addr: &str, reachable: Option<bool>, detail: impl Into<String>) -> Self {
+        Self {
+            addr: addr.to_string(),
+            reachable,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Probe whether something is listening on `addr`, explaining failures
+/// (missing or stale socket, permissions, refused TCP port). A reachable
+/// endpoint is not necessarily a Sentinel.
+pub fn diagnose_endpoint(addr: &str) -> EndpointDiagnosis {
+    if let Some(path) = ipc_path(addr) {
+        if let Err(err) = validate_ipc_addr(addr) {
+            return EndpointDiagnosis::new(addr, Some(false), err.to_string());
+        }
+        return diagnose_ipc(addr, path);
+    }
+    if let Some(name) = pipe_name(addr) {
+        if let Err(err) = validate_ipc_addr(addr) {
+            return EndpointDiagnosis::new(addr, Some(false), err.to_string());
+        }
+        return diagnose_pipe(addr, name);
+    }
+    let authority = addr
+        .strip_prefix("tcp://")
+        .or_else(|| addr.strip_prefix("grpc://"))
+        .map(|rest| rest.trim_end_matches('/'));
+    match authority {
+        Some(authority) => diagnose_tcp(addr, authority),
+        None => EndpointDiagnosis::new(
+            addr,
+            None,
+            format!(
+                "Cannot probe {}; expected ipc://, pipe://, tcp:// or grpc://",
+                addr
+            ),
+        ),
+    }
+}
+
+fn diagnose_ipc(addr: &str, path: &Path) -> EndpointDiagnosis {
+    if is_abstract(path) {
+        return EndpointDiagnosis::new(addr, None, "Abstract sockets cannot be probed");
+    }
+    match std::fs::symlink_metadata(path) {
+        Ok(_) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return EndpointDiagnosis::new(
+                addr,
+                Some(false),
+                format!("No socket at {}; is the Sentinel running?", path.display()),
+            );
+        }
+        Err(err) => {
+            return EndpointDiagnosis::new(
+                addr,
+                Some(false),
+                format!("Cannot read {}: {}", path.display(), err),
+            );
+        }
+    }
+    #[cfg(unix)]
+    {
+        match std::os::unix::net::UnixStream::connect(path) {
+            Ok(_) => EndpointDiagnosis::new(addr, Some(true), "Listening"),
+            Err(err) => {
+                let detail = match err.kind() {
+                    io::ErrorKind::ConnectionRefused => format!(
+                        "Stale socket at {}: nothing is listening; restart the Sentinel",
+                        path.display()
+                    ),
+                    io::ErrorKind::PermissionDenied => format!(
+                        "Permission denied on {}; the Sentinel runs as another user",
+                        path.display()
+                    ),
+                    _ => format!("Cannot connect to {}: {}", path.display(), err),
+                };
+                EndpointDiagnosis::new(addr, Some(false), detail)
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    EndpointDiagnosis::new(addr, None, format!("Socket file {} exists", path.display()))
+}
+
+#[cfg(windows)]
+fn diagnose_pipe(addr: &str, name: &str) -> EndpointDiagnosis {
+    /// Every instance of the pipe is connected; the server is up.
+    const ERROR_PIPE_BUSY: i32 = 231;
+
+    let path = pipe_path(name);
+    match std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&path)
+    {
+        Ok(_) => EndpointDiagnosis::new(addr, Some(true), "Listening"),
+        Err(err) if err.raw_os_error() == Some(ERROR_PIPE_BUSY) => {
+            EndpointDiagnosis::new(addr, Some(true), "Listening (all pipe instances busy)")
+        }
+        Err(err) => {
+            let detail = match err.kind() {
+                io::ErrorKind::NotFound => format!(
+                    "No named pipe at {}; is the Sentinel running?",
+                    path.display()
+                ),
+                io::ErrorKind::PermissionDenied => format!(
+                    "Permission denied on {}; the Sentinel runs as another user",
+                    path.display()
+                ),
+                _ => format!("Cannot open {}: {}", path.display(), err),
+            };
+            EndpointDiagnosis::new(addr, Some(false), detail)
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn diagnose_pipe(addr: &str, name: &str) -> EndpointDiagnosis {
+    diagnose_ipc(addr, &pipe_path(name))
+}
+
+fn diagnose_tcp(addr: &str, authority: &str) -> EndpointDiagnosis {
+    // A wildcard bind address is reached through loopback
+    let authority = authority
+        .strip_prefix("*:")
+        .or_else(|| authority.strip_prefix("0.0.0.0:"))
+        .map(|port| format!("127.0.0.1:{}", port))
+        .unwrap_or_else(|| authority.to_string());
+    let targets = match authority.to_socket_addrs() {
+        Ok(targets) => targets.collect::<Vec<_>>(),
+        Err(err) => {
+            return EndpointDiagnosis::new(
+                addr,
+                Some(false),
+                format!("Cannot resolve {}: {}", authority, err),
+            );
+        }
+    };
+    let mut last_err = None;
+    for target in targets {
+        match TcpStream::connect_timeout(&target, PROBE_TIMEOUT) {
+            Ok(_) => return EndpointDiagnosis::new(addr, Some(true), "Listening"),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    let detail = match last_err {
+        Some(err) if err.kind() == io::ErrorKind::ConnectionRefused => format!(
+            "Nothing is listening on {}; is the Sentinel running?",
+            authority
+        ),
+        Some(err) if err.kind() == io::ErrorKind::TimedOut => {
+            format!("Timed out connecting to {}; check firewalls", authority)
+        }
+        Some(err) => format!("Cannot connect to {}: {}", authority, err),
+        None => format!("{} resolved to no addresses", authority),
+    };
+    EndpointDiagnosis::new(addr, Some(false), detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_ipc_addr_is_valid() {
+        let addr = default_ipc_addr("casparian");
+        assert!(addr.starts_with(IPC_SCHEME));
+        assert!(addr.ends_with(".sock"));
+        validate_ipc_addr(&addr).unwrap();
+        assert!(ipc_path(&addr).unwrap().is_absolute());
+    }
+
+    #[test]
+    fn test_validate_ipc_addr() {
+        assert!(matches!(
+            validate_ipc_addr("ipc://"),
+            Err(IpcError::EmptyPath(_))
+        ));
+        let long = format!("ipc:///tmp/{}.sock", "x".repeat(MAX_IPC_PATH_LEN));
+        assert!(matches!(
+            validate_ipc_addr(&long),
+            Err(IpcError::PathTooLong { .. })
+        ));
+        validate_ipc_addr("tcp://127.0.0.1:5555").unwrap();
+    }
+
+    #[test]
+    fn test_validate_pipe_addr() {
+        validate_ipc_addr(&default_local_addr("casparian")).unwrap();
+        validate_ipc_addr("pipe://casparian-test").unwrap();
+        assert_eq!(pipe_name("pipe://casparian"), Some("casparian"));
+        assert_eq!(pipe_name("ipc:///tmp/casparian.sock"), None);
+        for bad in ["pipe://", "pipe://a\\b", "pipe://a/b"] {
+            assert!(
+                matches!(
+                    validate_ipc_addr(bad),
+                    Err(IpcError::InvalidPipeName { .. })
+                ),
+                "{}",
+                bad
+            );
+        }
+        let long = format!("pipe://{}", "x".repeat(MAX_PIPE_NAME_LEN + 1));
+        assert!(validate_ipc_addr(&long).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_prepare_ipc_bind_removes_stale_socket_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("sentinel.sock");
+        let addr = format!("ipc://{}", path.display());
+
+        // Missing parent directory is created
+        prepare_ipc_bind(&addr).unwrap();
+        assert!(path.parent().unwrap().is_dir());
+
+        // A live listener is left alone and reported
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        assert!(matches!(prepare_ipc_bind(&addr), Err(IpcError::InUse(_))));
+        assert_eq!(diagnose_endpoint(&addr).reachable, Some(true));
+
+        // Once the listener is gone the file is stale and removed
+        drop(listener);
+        let stale = diagnose_endpoint(&addr);
+        assert_eq!(stale.reachable, Some(false));
+        assert!(stale.detail.contains("Stale socket"), "{}", stale.detail);
+        prepare_ipc_bind(&addr).unwrap();
+        assert!(!path.exists());
+
+        let missing = diagnose_endpoint(&addr);
+        assert!(missing.detail.contains("No socket"), "{}", missing.detail);
+
+        // Regular files are never deleted
+        std::fs::write(&path, b"data").unwrap();
+        assert!(matches!(
+            prepare_ipc_bind(&addr),
+            Err(IpcError::NotASocket(_))
+        ));
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_diagnose_tcp() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let addr = format!("tcp://127.0.0.1:{}", port);
+        assert_eq!(diagnose_endpoint(&addr).reachable, Some(true));
+        assert_eq!(
+            diagnose_endpoint(&format!("tcp://*:{}", port)).reachable,
+            Some(true)
+        );
+
+        drop(listener);
+        let closed = diagnose_endpoint(&addr);
+        assert_eq!(closed.reachable, Some(false));
+        assert_eq!(diagnose_endpoint("inproc://x").reachable, None);
+    }
+}
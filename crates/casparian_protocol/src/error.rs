@@ -1,5 +1,7 @@
 //! Protocol error types
 
+use crate::version::ProtocolVersionRange;
+use crate::OpCode;
 use std::io;
 use thiserror::Error;
 
@@ -13,8 +15,20 @@ pub enum ProtocolError {
     #[error("Header too short: expected {expected} bytes, got {got}")]
     HeaderTooShort { expected: usize, got: usize },
 
-    #[error("Protocol version mismatch: expected {expected}, got {got}")]
-    VersionMismatch { expected: u8, got: u8 },
+    #[error("Unsupported protocol version {got}: this build speaks v{min}..=v{max}")]
+    UnsupportedVersion { got: u8, min: u8, max: u8 },
+
+    #[error("Invalid protocol version range: min {min} > max {max}")]
+    InvalidVersionRange { min: u8, max: u8 },
+
+    #[error("No common protocol version: local {local}, peer {peer}")]
+    NoCommonVersion {
+        local: ProtocolVersionRange,
+        peer: ProtocolVersionRange,
+    },
+
+    #[error("OpCode {opcode:?} is not supported at protocol version {version}")]
+    OpCodeUnsupported { opcode: OpCode, version: u8 },
 
     #[error("Invalid frame count: expected {expected}, got {got}")]
     InvalidFrameCount { expected: usize, got: usize },
@@ -25,6 +39,9 @@ pub enum ProtocolError {
     #[error("Payload too large: {size} bytes exceeds maximum {max} bytes")]
     PayloadTooLarge { size: usize, max: usize },
 
+    #[error("Stream error for job {job_id}: {reason}")]
+    Stream { job_id: u64, reason: String },
+
     #[error("JSON serialization error: {0}")]
     JsonError(#[from] serde_json::Error),
 
@@ -9,14 +9,14 @@ use std::fmt;
 
 use thiserror::Error;
 
-use crate::types::{DataType, ProcessingStatus, SchemaColumnSpec};
+use crate::types::{DataType, ProcessingStatus, SchemaColumnSpec, SinkMode, WorkerHealth};
 
 // ============================================================================
 // Event Types
 // ============================================================================
 
-/// Unique identifier for an event within a job.
-/// Monotonically increasing per job to ensure ordering.
+/// Unique identifier for an event.
+/// Monotonically increasing across all jobs, so it doubles as a stream cursor.
 pub type EventId = u64;
 
 /// Unique identifier for an API job (cf_api_jobs).
@@ -143,8 +143,39 @@ pub enum EventType {
     },
     /// Approval required (job is waiting)
     ApprovalRequired { approval_id: String },
+    /// Approval was approved or rejected
+    ApprovalDecided {
+        approval_id: String,
+        status: ApprovalStatus,
+    },
+    /// Worker registered with the Sentinel
+    WorkerJoined { worker_id: String },
+    /// Worker was dropped after missing heartbeats
+    WorkerLeft { worker_id: String },
+    /// Worker moved between heartbeat health states
+    WorkerHealthChanged {
+        worker_id: String,
+        from: WorkerHealth,
+        to: WorkerHealth,
+        /// Whether the worker is recovering too often to receive new jobs
+        flapping: bool,
+    },
+    /// A dataset's drift from its locked contract passed the alert threshold
+    SchemaDrift {
+        plugin_name: String,
+        /// Job whose input introduced the newest drift
+        job_id: i64,
+        /// Source columns no output carries, across the dataset's runs
+        unmapped_columns: Vec<String>,
+        /// `output.column` names that arrived as another accepted type
+        type_drifts: Vec<String>,
+    },
 }
 
+/// Job ID under which events not tied to a job (approvals without a job,
+/// worker membership) are recorded. API job IDs start at 1.
+pub const SYSTEM_EVENT_JOB_ID: ApiJobId = ApiJobId::new(0);
+
 /// Summary of violations for an event (aggregated).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ViolationSummary {
@@ -165,6 +196,8 @@ pub enum ViolationType {
     ColumnMissing,
     ColumnExtra,
     ColumnOrderMismatch,
+    /// Value broke a column constraint (not_null, unique, range, ...)
+    ConstraintViolated,
 }
 
 /// Event record stored in the database and returned by the API.
@@ -385,6 +418,9 @@ pub struct ApprovalDecision {
     pub decision: ApprovalDecisionType,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
+    /// Who is making the decision (user, service, or agent name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
 }
 
 /// Approval decision type.
@@ -395,6 +431,21 @@ pub enum ApprovalDecisionType {
     Reject,
 }
 
+/// Audit log entry recording one decision on an approval request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalAuditEntry {
+    pub approval_id: String,
+    pub decision: ApprovalDecisionType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+    pub decided_at: String, // RFC3339
+    /// Approval rule that made the decision; `None` for manual decisions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+}
+
 // ============================================================================
 // Query Types
 // ============================================================================
@@ -507,6 +558,10 @@ pub struct CreateJobResponse {
     pub approval_id: Option<String>,
 }
 
+/// Request header that makes POST /jobs safe to retry: repeating a request
+/// with the same key returns the original `CreateJobResponse`.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
 /// Response for GET /jobs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListJobsResponse {
@@ -535,10 +590,26 @@ pub struct ListApprovalsResponse {
 pub struct ApprovalDecideResponse {
     pub approval_id: String,
     pub status: ApprovalStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_by: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub job_id: Option<ApiJobId>,
 }
 
+/// Request for GET /approvals/{id}/audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListApprovalAudit {
+    pub approval_id: String,
+}
+
+/// Response for GET /approvals/{id}/audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListApprovalAuditResponse {
+    pub approval_id: String,
+    /// Decisions in the order they were recorded
+    pub entries: Vec<ApprovalAuditEntry>,
+}
+
 /// Response for GET /health
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthResponse {
@@ -555,7 +626,7 @@ pub struct VersionResponse {
 }
 
 /// Dataset summary for GET /datasets
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DatasetSummary {
     pub name: String,
     pub plugin_name: String,
@@ -564,6 +635,32 @@ pub struct DatasetSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub byte_size: Option<u64>,
     pub last_updated: String, // RFC3339
+    /// Table the last commit wrote to, for table sinks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
+    /// Hive partition columns of the sink (`partition_by`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partition_columns: Vec<String>,
+}
+
+/// Dataset detail for GET /datasets/{name}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetDetail {
+    #[serde(flatten)]
+    pub summary: DatasetSummary,
+    pub sink_mode: SinkMode,
+    /// Contract schema of the output, when the sink has one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<SchemaColumnSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_hash: Option<String>,
+    /// Source root the dataset's input files were scanned from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lineage_root: Option<String>,
+    /// Committed jobs that wrote to the dataset
+    pub job_count: u64,
+    pub last_job_id: i64,
+    pub created_at: String, // RFC3339
 }
 
 /// Response for GET /datasets
@@ -603,6 +700,10 @@ pub struct ControlPlaneDiscovery {
 // Error Response
 // ============================================================================
 
+/// `ErrorResponse::code` (HTTP 409) for an idempotency key reused with a
+/// different request body.
+pub const IDEMPOTENCY_KEY_CONFLICT: &str = "IDEMPOTENCY_KEY_CONFLICT";
+
 /// Standard error response for the API.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -625,6 +726,18 @@ impl ErrorResponse {
         self.details = Some(details);
         self
     }
+
+    /// Error for `key` reused with a request other than its first one.
+    pub fn idempotency_conflict(key: &str) -> Self {
+        Self::new(
+            format!(
+                "Idempotency key '{}' was already used with a different request",
+                key
+            ),
+            IDEMPOTENCY_KEY_CONFLICT,
+        )
+        .with_details(serde_json::json!({ "idempotency_key": key }))
+    }
 }
 
 #[cfg(test)]
@@ -709,6 +822,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_approval_audit_serialization() {
+        let response = ListApprovalAuditResponse {
+            approval_id: "appr-1".to_string(),
+            entries: vec![ApprovalAuditEntry {
+                approval_id: "appr-1".to_string(),
+                decision: ApprovalDecisionType::Reject,
+                actor: Some("alice".to_string()),
+                justification: None,
+                decided_at: "2026-01-01T00:00:00Z".to_string(),
+                rule: None,
+            }],
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"decision\":\"reject\""));
+        assert!(json.contains("\"actor\":\"alice\""));
+        assert!(!json.contains("justification"));
+        assert!(!json.contains("rule"));
+
+        let decision: ApprovalDecision = serde_json::from_str(r#"{"decision":"approve"}"#).unwrap();
+        assert!(decision.actor.is_none());
+    }
+
+    #[test]
+    fn test_dataset_detail_flattens_summary() {
+        let detail = DatasetDetail {
+            summary: DatasetSummary {
+                name: "orders".to_string(),
+                plugin_name: "orders_parser".to_string(),
+                sink_uri: "parquet:///out?partition_by=region".to_string(),
+                row_count: 12,
+                byte_size: None,
+                last_updated: "2026-01-01T00:00:00Z".to_string(),
+                table_name: None,
+                partition_columns: vec!["region".to_string()],
+            },
+            sink_mode: SinkMode::Append,
+            columns: Vec::new(),
+            schema_hash: None,
+            lineage_root: Some("/data/orders".to_string()),
+            job_count: 3,
+            last_job_id: 7,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let json = serde_json::to_string(&detail).unwrap();
+        assert!(json.contains("\"name\":\"orders\""));
+        assert!(json.contains("\"partition_columns\":[\"region\"]"));
+        assert!(json.contains("\"sink_mode\":\"append\""));
+        assert!(!json.contains("columns\":[]"));
+
+        let parsed: DatasetDetail = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, detail);
+
+        // Summaries from older servers have no catalog fields
+        let summary: DatasetSummary = serde_json::from_str(
+            r#"{"name":"a","plugin_name":"p","sink_uri":"parquet://x","row_count":1,"last_updated":"2026-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert!(summary.partition_columns.is_empty());
+    }
+
     #[test]
     fn test_query_request_defaults() {
         let req: QueryRequest = serde_json::from_str(r#"{"sql": "SELECT 1"}"#).unwrap();
@@ -0,0 +1,268 @@
+//! CURVE key storage for encrypted Sentinel <-> Worker transport.
+//!
+//! Keys are Z85-encoded 32-byte CurveZMQ keys (40 characters). Generation
+//! needs libzmq and happens in the CLI (`casparian keygen`); this module only
+//! stores and loads them so both sides share one on-disk layout:
+//!
+//! ```text
+//! ~/.casparian_flow/keys/
+//!   sentinel.key          # public + secret key (mode 0600)
+//!   sentinel.pub          # public key, hand this to workers
+//!   worker.key / .pub     # same, per worker
+//!   authorized_workers    # one worker public key per line, '#' comments
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::paths::casparian_home;
+
+/// Length of a Z85-encoded CURVE key.
+pub const Z85_KEY_LEN: usize = 40;
+pub const SENTINEL_KEY_NAME: &str = "sentinel";
+pub const WORKER_KEY_NAME: &str = "worker";
+pub const AUTHORIZED_WORKERS_FILE: &str = "authorized_workers";
+
+const Z85_ALPHABET: &str =
+    "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+#[derive(Debug, Error)]
+pub enum KeyError {
+    #[error("key file not found: {path}")]
+    NotFound { path: PathBuf },
+    #[error("failed to access {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid key in {path}: {reason}")]
+    Invalid { path: PathBuf, reason: String },
+}
+
+/// A CURVE key pair, Z85-encoded.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CurveKeyPair {
+    pub public_key: String,
+    pub secret_key: String,
+}
+
+impl fmt::Debug for CurveKeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CurveKeyPair")
+            .field("public_key", &self.public_key)
+            .field("secret_key", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Default key directory: ~/.casparian_flow/keys
+pub fn keys_dir() -> PathBuf {
+    casparian_home().join("keys")
+}
+
+pub fn is_valid_z85_key(key: &str) -> bool {
+    key.len() == Z85_KEY_LEN && key.chars().all(|c| Z85_ALPHABET.contains(c))
+}
+
+pub fn secret_key_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.key", name))
+}
+
+pub fn public_key_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.pub", name))
+}
+
+/// Write `{name}.key` (owner-only on Unix) and `{name}.pub` under `dir`.
+pub fn save_keypair(dir: &Path, name: &str, pair: &CurveKeyPair) -> Result<(), KeyError> {
+    for key in [&pair.public_key, &pair.secret_key] {
+        if !is_valid_z85_key(key) {
+            return Err(KeyError::Invalid {
+                path: secret_key_path(dir, name),
+                reason: "not a 40-character Z85 key".to_string(),
+            });
+        }
+    }
+    std::fs::create_dir_all(dir).map_err(|source| KeyError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+
+    let secret_path = secret_key_path(dir, name);
+    let secret = format!(
+        "# Casparian CURVE key pair '{}'. Keep this file private.\npublic-key = \"{}\"\nsecret-key = \"{}\"\n",
+        name, pair.public_key, pair.secret_key
+    );
+    write_private(&secret_path, &secret)?;
+
+    let public_path = public_key_path(dir, name);
+    let public = format!("public-key = \"{}\"\n", pair.public_key);
+    std::fs::write(&public_path, public).map_err(|source| KeyError::Io {
+        path: public_path,
+        source,
+    })
+}
+
+pub fn load_keypair(dir: &Path, name: &str) -> Result<CurveKeyPair, KeyError> {
+    let path = secret_key_path(dir, name);
+    let contents = read_key_file(&path)?;
+    Ok(CurveKeyPair {
+        public_key: key_field(&path, &contents, "public-key")?,
+        secret_key: key_field(&path, &contents, "secret-key")?,
+    })
+}
+
+pub fn load_public_key(dir: &Path, name: &str) -> Result<String, KeyError> {
+    let path = public_key_path(dir, name);
+    let contents = read_key_file(&path)?;
+    key_field(&path, &contents, "public-key")
+}
+
+/// Read an allow-list of public keys: one per line, anything after the key
+/// and lines starting with `#` are ignored.
+pub fn load_authorized_keys(path: &Path) -> Result<Vec<String>, KeyError> {
+    let contents = read_key_file(path)?;
+    let mut keys = Vec::new();
+    for (idx, line) in contents.lines().enumerate() {
+        // '#' is also a Z85 character, so a leading key wins over a comment.
+        let key = line.split_whitespace().next().unwrap_or("");
+        if key.is_empty() || (key.starts_with('#') && !is_valid_z85_key(key)) {
+            continue;
+        }
+        if !is_valid_z85_key(key) {
+            return Err(KeyError::Invalid {
+                path: path.to_path_buf(),
+                reason: format!("line {} is not a 40-character Z85 key", idx + 1),
+            });
+        }
+        keys.push(key.to_string());
+    }
+    Ok(keys)
+}
+
+fn read_key_file(path: &Path) -> Result<String, KeyError> {
+    std::fs::read_to_string(path).map_err(|source| {
+        if source.kind() == std::io::ErrorKind::NotFound {
+            KeyError::NotFound {
+                path: path.to_path_buf(),
+            }
+        } else {
+            KeyError::Io {
+                path: path.to_path_buf(),
+                source,
+            }
+        }
+    })
+}
+
+fn key_field(path: &Path, contents: &str, field: &str) -> Result<String, KeyError> {
+    let value = contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(name, _)| name.trim() == field)
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .ok_or_else(|| KeyError::Invalid {
+            path: path.to_path_buf(),
+            reason: format!("missing {}", field),
+        })?;
+    if !is_valid_z85_key(&value) {
+        return Err(KeyError::Invalid {
+            path: path.to_path_buf(),
+            reason: format!("{} is not a 40-character Z85 key", field),
+        });
+    }
+    Ok(value)
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> Result<(), KeyError> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let io_err = |source| KeyError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(io_err)?;
+    file.write_all(contents.as_bytes()).map_err(io_err)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> Result<(), KeyError> {
+    std::fs::write(path, contents).map_err(|source| KeyError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    // Test vectors from the ZMQ CURVE spec (RFC 26).
+    const PUBLIC: &str = "rq:rM>}U?@Lns47E1%kR.o@n%FcmmsL/@{H8]yf7";
+    const SECRET: &str = "JTKVSB%%)wK0E.X)V>+}o?pNmC{O&4W4b!Ni{Lh6";
+
+    fn pair() -> CurveKeyPair {
+        CurveKeyPair {
+            public_key: PUBLIC.to_string(),
+            secret_key: SECRET.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_z85_key_validation() {
+        assert!(is_valid_z85_key(PUBLIC));
+        assert!(!is_valid_z85_key("short"));
+        assert!(!is_valid_z85_key(&format!("{}~", &PUBLIC[..39])));
+    }
+
+    #[test]
+    fn test_keypair_round_trip() {
+        let dir = tempdir().unwrap();
+        save_keypair(dir.path(), "worker", &pair()).unwrap();
+
+        assert_eq!(load_keypair(dir.path(), "worker").unwrap(), pair());
+        assert_eq!(load_public_key(dir.path(), "worker").unwrap(), PUBLIC);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(secret_key_path(dir.path(), "worker"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_debug_redacts_secret() {
+        let rendered = format!("{:?}", pair());
+        assert!(rendered.contains(PUBLIC));
+        assert!(!rendered.contains(SECRET));
+    }
+
+    #[test]
+    fn test_authorized_keys_parsing() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(AUTHORIZED_WORKERS_FILE);
+        std::fs::write(&path, format!("# workers\n\n{}  # worker-1\n", PUBLIC)).unwrap();
+        assert_eq!(load_authorized_keys(&path).unwrap(), vec![PUBLIC]);
+
+        std::fs::write(&path, "not-a-key\n").unwrap();
+        let err = load_authorized_keys(&path).unwrap_err();
+        assert!(err.to_string().contains("line 1"), "{}", err);
+
+        let missing = load_keypair(dir.path(), "absent").unwrap_err();
+        assert!(matches!(missing, KeyError::NotFound { .. }));
+    }
+}
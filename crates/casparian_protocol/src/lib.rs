@@ -10,23 +10,37 @@
 //! [VER:1][OP:1][RES:2][JOB_ID:8][LEN:4]
 //! ```
 //!
-//! - VER (u8): Protocol version (0x04)
+//! - VER (u8): Protocol version (0x04); peers negotiate a common version in
+//!   IDENTIFY, see [`version`]
 //! - OP (u8): OpCode
 //! - RES (u16): Reserved for future use
 //! - JOB_ID (u64): Job ID (Q = unsigned long long, 8 bytes)
 //! - LEN (u32): Payload length in bytes (I = unsigned int, 4 bytes)
+//!
+//! Payloads larger than a single message (or that should not be buffered in
+//! full) are sent as STREAM_BEGIN / STREAM_CHUNK / STREAM_END sequences; see
+//! [`stream`].
 
+pub mod capabilities;
 pub mod config;
 pub mod defaults;
+pub mod endpoint;
 pub mod error;
 pub mod http_types;
 pub mod idempotency;
+pub mod keys;
 pub mod metrics;
 pub mod naming;
 pub mod paths;
+pub mod retry;
+pub mod stream;
 pub mod telemetry;
 pub mod types;
+pub mod version;
 
+pub use version::{
+    NegotiatedProtocol, ProtocolFeatures, ProtocolVersionRange, MIN_PROTOCOL_VERSION,
+};
 pub use paths::{
     casparian_home, default_logs_dir, default_query_catalog_path, default_state_store_path,
 };
@@ -35,9 +49,18 @@ pub use paths::{
 pub use types::{
     // Shredder types
     AnalysisResult,
+    ArtifactDiscrepancy,
     ArtifactKind,
     ArtifactV1,
+    BatchFile,
+    BatchFileReceipt,
+    ByteRange,
+    ColumnConstraint,
+    ColumnInference,
     ColumnOrderMismatch,
+    ColumnStats,
+    ConstraintViolations,
+    CsvDialect,
     // Canonical enums (use these everywhere)
     DataType,
     // Protocol types
@@ -46,29 +69,51 @@ pub use types::{
     DetectionConfidence,
     DispatchCommand,
     DispatchAckPayload,
+    ErrorCategory,
     ErrorPayload,
     HeartbeatPayload,
     HeartbeatStatus,
+    IdentifyAckPayload,
     IdentifyPayload,
+    InferredTypeCandidate,
     JobDiagnostics,
     JobId,
+    JobPriority,
     JobReceipt,
+    JobResultSummary,
+    JobStage,
     JobStatus,
     LineageBlock,
     LineageChain,
     LineageFileType,
     LineageHop,
+    LimitKind,
+    LlmCacheMode,
     LlmConfig,
     LlmProvider,
+    LlmSettings,
     ObservedColumn,
     ObservedDataType,
+    OutputColumnStats,
+    PipelineRun,
+    PipelineRunFailure,
+    PipelineRunJob,
+    PipelineRunStage,
     PipelineRunStatus,
     PluginStatus,
     ProcessingStatus,
     QuarantineConfig,
+    ReceiptVerification,
+    ReloadPayload,
+    ResourceLimits,
+    ResultArtifact,
     RuntimeKind,
+    ScheduleCatchUp,
+    ScheduleTarget,
     SchemaColumnSpec,
     SchemaDefinition,
+    SchemaDrift,
+    SchemaEvolution,
     SchemaMismatch,
     ShardMeta,
     ShredConfig,
@@ -76,7 +121,16 @@ pub use types::{
     ShredStrategy,
     SinkConfig,
     SinkMode,
+    SourceFile,
+    SourceFingerprint,
+    SourceHash,
+    StageTiming,
+    TextEncoding,
+    TypeDrift,
     TypeMismatch,
+    VerificationStatus,
+    WorkerHealth,
+    WorkerLoad,
     WorkerStatus,
 };
 
@@ -84,12 +138,15 @@ pub use idempotency::{
     materialization_key, output_target_key, schema_hash, table_name_with_schema,
 };
 pub use naming::{is_safe_output_id, safe_output_id};
+pub use capabilities::{satisfies_requirements, unmet_requirements, WILDCARD_CAPABILITY};
+pub use retry::{RetryAttempt, RetryPolicy};
 
 // Re-export HTTP API types
 pub use http_types::{
     ApiJobId,
     // Approval types
     Approval,
+    ApprovalAuditEntry,
     // API response types
     ApprovalDecideResponse,
     ApprovalDecision,
@@ -98,6 +155,7 @@ pub use http_types::{
     ApprovalStatus,
     ControlPlaneDiscovery,
     CreateJobResponse,
+    DatasetDetail,
     DatasetSummary,
     ErrorResponse,
     // Event types
@@ -112,6 +170,8 @@ pub use http_types::{
     JobProgress,
     JobResult,
     JobSpec,
+    ListApprovalAudit,
+    ListApprovalAuditResponse,
     ListApprovalsResponse,
     ListDatasetsResponse,
     ListEventsResponse,
@@ -128,13 +188,14 @@ pub use http_types::{
     VersionResponse,
     ViolationSummary,
     ViolationType,
+    SYSTEM_EVENT_JOB_ID,
 };
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use error::{ProtocolError, Result};
 use std::io::Cursor;
 
-/// Protocol version
+/// Protocol version written by this build (the highest it supports)
 pub const PROTOCOL_VERSION: u8 = 0x04;
 
 /// Header size in bytes
@@ -173,6 +234,14 @@ pub enum OpCode {
     // v5.0 Bridge Mode: Artifact Deployment
     Deploy = 10, // "Deploy this artifact (source + lockfile + signature)."
     Ack = 11,    // "Generic acknowledgment (used for DeployResponse, etc.)"
+
+    // Bidirectional (Chunked payload streaming)
+    StreamBegin = 12, // "A large payload for opcode X follows in chunks."
+    StreamChunk = 13, // "Next chunk of the payload."
+    StreamEnd = 14,   // "Payload complete; here is the digest."
+
+    // Worker -> Sentinel (Live log tail)
+    LogChunk = 15, // "New log output for job X."
 }
 
 impl OpCode {
@@ -190,6 +259,10 @@ impl OpCode {
             7 => Ok(OpCode::Reload),
             10 => Ok(OpCode::Deploy),
             11 => Ok(OpCode::Ack),
+            12 => Ok(OpCode::StreamBegin),
+            13 => Ok(OpCode::StreamChunk),
+            14 => Ok(OpCode::StreamEnd),
+            15 => Ok(OpCode::LogChunk),
             _ => Err(ProtocolError::InvalidOpCode(value)),
         }
     }
@@ -263,10 +336,12 @@ impl Header {
         let job_id = JobId::new(cursor.read_u64::<BigEndian>()?);
         let payload_len = cursor.read_u32::<BigEndian>()?;
 
-        if version != PROTOCOL_VERSION {
-            return Err(ProtocolError::VersionMismatch {
-                expected: PROTOCOL_VERSION,
+        let supported = ProtocolVersionRange::SUPPORTED;
+        if !supported.contains(version) {
+            return Err(ProtocolError::UnsupportedVersion {
                 got: version,
+                min: supported.min,
+                max: supported.max,
             });
         }
 
@@ -290,6 +365,8 @@ pub struct Message {
 }
 
 /// Maximum payload size (4GB - 1, the max value of u32)
+///
+/// Applies per message; streamed payloads are split into chunks below this.
 pub const MAX_PAYLOAD_SIZE: usize = u32::MAX as usize;
 
 impl Message {
@@ -363,6 +440,10 @@ mod tests {
             OpCode::DispatchAck,
             OpCode::Heartbeat,
             OpCode::Conclude,
+            OpCode::StreamBegin,
+            OpCode::StreamChunk,
+            OpCode::StreamEnd,
+            OpCode::LogChunk,
         ] {
             let header = Header::new(opcode, JobId::new(9999), 512);
             let packed = header.pack().unwrap();
@@ -377,7 +458,10 @@ mod tests {
         buf[0] = 0xFF; // Invalid version
 
         let result = Header::unpack(&buf);
-        assert!(matches!(result, Err(ProtocolError::VersionMismatch { .. })));
+        assert!(matches!(
+            result,
+            Err(ProtocolError::UnsupportedVersion { got: 0xFF, .. })
+        ));
     }
 
     #[test]
@@ -1,6 +1,11 @@
 //! Canonical metric keys for job receipts and telemetry.
 //!
 //! Use these constants/helpers everywhere to avoid stringly-typed drift.
+//! Also defines the per-plugin / per-tag breakdown types reported by the
+//! Sentinel (`GetPluginMetrics`).
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Total rows processed across outputs.
 pub const ROWS: &str = "rows";
@@ -14,6 +19,10 @@ pub const OUTPUT_COUNT: &str = "output_count";
 pub const IS_TRANSIENT: &str = "is_transient";
 /// Quarantine policy rejection flag.
 pub const QUARANTINE_REJECTED: &str = "quarantine_rejected";
+/// Milliseconds the Parquet/CSV sinks spent encoding, across outputs.
+pub const SINK_ENCODE_MS: &str = "sink_encode_ms";
+/// Milliseconds the Parquet/CSV sinks spent writing files, across outputs.
+pub const SINK_IO_MS: &str = "sink_io_ms";
 
 /// Per-output rows prefix.
 pub const ROWS_BY_OUTPUT_PREFIX: &str = "rows.";
@@ -76,3 +85,178 @@ pub fn parse_quarantine_rows_by_output(key: &str) -> Option<&str> {
 pub fn parse_lineage_unavailable_rows_by_output(key: &str) -> Option<&str> {
     key.strip_prefix(LINEAGE_UNAVAILABLE_ROWS_BY_OUTPUT_PREFIX)
 }
+
+// ============================================================================
+// Per-plugin / per-tag breakdown
+// ============================================================================
+
+/// Upper bounds (milliseconds) of the job latency histogram buckets.
+/// A final overflow bucket (`+Inf`) follows the last bound.
+pub const LATENCY_BUCKETS_MS: [u64; 10] = [
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000, 300_000,
+];
+
+/// How a concluded job is counted in the breakdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobOutcome {
+    Completed,
+    Failed,
+    Retried,
+    Rejected,
+    Aborted,
+}
+
+impl JobOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobOutcome::Completed => "completed",
+            JobOutcome::Failed => "failed",
+            JobOutcome::Retried => "retried",
+            JobOutcome::Rejected => "rejected",
+            JobOutcome::Aborted => "aborted",
+        }
+    }
+}
+
+impl fmt::Display for JobOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Job latency (claim to conclude) histogram over `LATENCY_BUCKETS_MS`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// Non-cumulative count per bucket; the last entry is the overflow bucket.
+    pub buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    pub count: u64,
+    pub sum_ms: u64,
+}
+
+impl LatencyHistogram {
+    pub fn observe(&mut self, latency_ms: u64) {
+        let index = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[index] += 1;
+        self.count += 1;
+        self.sum_ms = self.sum_ms.saturating_add(latency_ms);
+    }
+
+    /// Cumulative counts (Prometheus `le` semantics), overflow last.
+    pub fn cumulative(&self) -> Vec<u64> {
+        self.buckets
+            .iter()
+            .scan(0u64, |total, count| {
+                *total += count;
+                Some(*total)
+            })
+            .collect()
+    }
+
+    pub fn mean_ms(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum_ms as f64 / self.count as f64)
+        }
+    }
+}
+
+/// Job counters for one plugin, tag or namespace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobBreakdown {
+    pub completed: u64,
+    pub failed: u64,
+    pub retried: u64,
+    pub rejected: u64,
+    pub aborted: u64,
+    pub rows_written: u64,
+    pub latency: LatencyHistogram,
+}
+
+impl JobBreakdown {
+    pub fn record(&mut self, outcome: JobOutcome, rows: u64, latency_ms: Option<u64>) {
+        match outcome {
+            JobOutcome::Completed => self.completed += 1,
+            JobOutcome::Failed => self.failed += 1,
+            JobOutcome::Retried => self.retried += 1,
+            JobOutcome::Rejected => self.rejected += 1,
+            JobOutcome::Aborted => self.aborted += 1,
+        }
+        self.rows_written = self.rows_written.saturating_add(rows);
+        if let Some(latency_ms) = latency_ms {
+            self.latency.observe(latency_ms);
+        }
+    }
+
+    /// Total concluded jobs across all outcomes.
+    pub fn total(&self) -> u64 {
+        self.completed + self.failed + self.retried + self.rejected + self.aborted
+    }
+
+    /// Fraction of concluded jobs that failed (including retried attempts).
+    pub fn failure_rate(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            0.0
+        } else {
+            (self.failed + self.retried) as f64 / total as f64
+        }
+    }
+}
+
+/// Breakdown entry keyed by plugin name, tag or namespace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakdownEntry {
+    pub key: String,
+    #[serde(flatten)]
+    pub jobs: JobBreakdown,
+    pub failure_rate: f64,
+}
+
+impl BreakdownEntry {
+    pub fn new(key: String, jobs: JobBreakdown) -> Self {
+        let failure_rate = jobs.failure_rate();
+        Self {
+            key,
+            jobs,
+            failure_rate,
+        }
+    }
+}
+
+/// Per-plugin, per-tag and per-namespace job metrics since Sentinel start,
+/// sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginMetricsReport {
+    pub plugins: Vec<BreakdownEntry>,
+    pub tags: Vec<BreakdownEntry>,
+    #[serde(default)]
+    pub namespaces: Vec<BreakdownEntry>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_breakdown_histogram_and_failure_rate() {
+        let mut jobs = JobBreakdown::default();
+        jobs.record(JobOutcome::Completed, 10, Some(50));
+        jobs.record(JobOutcome::Completed, 5, Some(1_000));
+        jobs.record(JobOutcome::Failed, 0, Some(400_000));
+        jobs.record(JobOutcome::Retried, 0, None);
+
+        assert_eq!(jobs.total(), 4);
+        assert_eq!(jobs.rows_written, 15);
+        assert!((jobs.failure_rate() - 0.5).abs() < f64::EPSILON);
+        assert_eq!(jobs.latency.count, 3);
+        assert_eq!(jobs.latency.buckets[0], 1);
+        assert_eq!(jobs.latency.buckets[3], 1);
+        assert_eq!(jobs.latency.buckets[LATENCY_BUCKETS_MS.len()], 1);
+        assert_eq!(jobs.latency.cumulative().last().copied(), Some(3));
+    }
+}
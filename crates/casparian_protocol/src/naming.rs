@@ -43,6 +43,41 @@ pub fn safe_output_id(name: &str) -> String {
     format!("{}_{}", slug, &hash[..8])
 }
 
+/// Maximum length of a tenant namespace.
+pub const MAX_NAMESPACE_LEN: usize = 64;
+
+/// Placeholder in sink URIs replaced by the sink's namespace.
+pub const NAMESPACE_PLACEHOLDER: &str = "{namespace}";
+
+/// Expand `{namespace}` in a sink URI, e.g. `parquet:///out/{namespace}/`.
+pub fn expand_namespace(uri: &str, namespace: &str) -> String {
+    uri.replace(NAMESPACE_PLACEHOLDER, namespace)
+}
+
+/// Validate a tenant namespace.
+///
+/// Namespaces are substituted into sink paths (`{namespace}`), so they are
+/// restricted to lowercase ASCII letters, digits, `_` and `-`.
+pub fn validate_namespace(namespace: &str) -> Result<(), String> {
+    if namespace.is_empty() || namespace.len() > MAX_NAMESPACE_LEN {
+        return Err(format!(
+            "namespace must be 1-{} characters, got {}",
+            MAX_NAMESPACE_LEN,
+            namespace.len()
+        ));
+    }
+    if !namespace
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "namespace '{}' may only contain a-z, 0-9, '_' and '-'",
+            namespace
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,4 +109,18 @@ mod tests {
         let safe = safe_output_id("");
         assert!(is_safe(&safe));
     }
+
+    #[test]
+    fn validate_namespace_rejects_path_characters() {
+        assert!(validate_namespace("default").is_ok());
+        assert!(validate_namespace("team-risk_2").is_ok());
+        assert!(validate_namespace("").is_err());
+        assert!(validate_namespace("../etc").is_err());
+        assert!(validate_namespace("Finance").is_err());
+        assert!(validate_namespace(&"a".repeat(MAX_NAMESPACE_LEN + 1)).is_err());
+        assert_eq!(
+            expand_namespace("parquet:///out/{namespace}/trades", "risk"),
+            "parquet:///out/risk/trades"
+        );
+    }
 }
@@ -0,0 +1,202 @@
+//! Retry policy for failed jobs.
+//!
+//! Each plugin may configure a `RetryPolicy` (stored in `cf_plugin_config`);
+//! plugins without one use the default, which matches the historical
+//! behavior: up to 3 retries of transient errors after 4s, 16s, and 64s.
+//!
+//! Workers classify failures (`JobDiagnostics::error_class`) and categorize
+//! them (`JobDiagnostics::error_category`); a failure is retried only if its
+//! class or category is listed in `retryable_errors` and attempts remain.
+
+use crate::types::ErrorCategory;
+use serde::{Deserialize, Serialize};
+
+/// Failure that may succeed on retry (timeouts, resources busy).
+pub const ERROR_CLASS_TRANSIENT: &str = "transient";
+/// Failure that will not succeed on retry (invalid parser, schema violation).
+pub const ERROR_CLASS_PERMANENT: &str = "permanent";
+/// Failure talking to the plugin subprocess.
+pub const ERROR_CLASS_BRIDGE: &str = "bridge";
+/// Unclassified worker failure.
+pub const ERROR_CLASS_INTERNAL: &str = "internal";
+
+/// Known error classes.
+pub const ERROR_CLASSES: &[&str] = &[
+    ERROR_CLASS_TRANSIENT,
+    ERROR_CLASS_PERMANENT,
+    ERROR_CLASS_BRIDGE,
+    ERROR_CLASS_INTERNAL,
+];
+
+/// Total attempts (first run plus retries) under the default policy.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+/// Default exponential backoff base in seconds.
+pub const DEFAULT_BACKOFF_BASE_SECS: u64 = 4;
+/// Upper bound on a single backoff delay.
+pub const MAX_BACKOFF_SECS: u64 = 24 * 60 * 60;
+
+/// How failed jobs of a plugin are retried.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first run. 1 disables retries.
+    pub max_attempts: u32,
+    /// Retry `n` (1-based) waits `backoff_base_secs ^ n` seconds.
+    pub backoff_base_secs: u64,
+    /// Random extra delay as a fraction of the backoff (0.0 - 1.0).
+    #[serde(default)]
+    pub jitter: f64,
+    /// Error classes or categories that are retried; anything else fails
+    /// immediately.
+    pub retryable_errors: Vec<String>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff_base_secs: DEFAULT_BACKOFF_BASE_SECS,
+            jitter: 0.0,
+            retryable_errors: vec![ERROR_CLASS_TRANSIENT.to_string()],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Check that the policy is usable. Errors name the offending field.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 {
+            return Err("max_attempts must be at least 1".to_string());
+        }
+        if self.backoff_base_secs == 0 {
+            return Err("backoff_base_secs must be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(format!(
+                "jitter must be between 0.0 and 1.0, got {}",
+                self.jitter
+            ));
+        }
+        if let Some(class) = self.retryable_errors.iter().find(|class| {
+            !ERROR_CLASSES.contains(&class.as_str()) && class.parse::<ErrorCategory>().is_err()
+        }) {
+            let categories: Vec<&str> = ErrorCategory::ALL.iter().map(|c| c.as_str()).collect();
+            return Err(format!(
+                "unknown error class '{}' (expected one of: {}; or a category: {})",
+                class,
+                ERROR_CLASSES.join(", "),
+                categories.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns true if the policy lists the failure's class or category.
+    pub fn is_retryable(&self, error_class: &str, category: Option<ErrorCategory>) -> bool {
+        self.retryable_errors.iter().any(|class| {
+            class == error_class || category.is_some_and(|category| class == category.as_str())
+        })
+    }
+
+    /// Returns true if a job that has already been retried `retry_count`
+    /// times may be retried after failing with `error_class` / `category`.
+    pub fn should_retry(
+        &self,
+        error_class: &str,
+        category: Option<ErrorCategory>,
+        retry_count: u32,
+    ) -> bool {
+        retry_count.saturating_add(1) < self.max_attempts
+            && self.is_retryable(error_class, category)
+    }
+
+    /// Delay before retry `retry` (1-based), in milliseconds.
+    ///
+    /// `jitter_sample` is a uniform sample in `[0.0, 1.0)`; it adds up to
+    /// `jitter` of the backoff so retries of a batch of jobs spread out.
+    pub fn backoff_ms(&self, retry: u32, jitter_sample: f64) -> u64 {
+        let secs = self
+            .backoff_base_secs
+            .max(1)
+            .checked_pow(retry)
+            .unwrap_or(MAX_BACKOFF_SECS)
+            .min(MAX_BACKOFF_SECS);
+        let base_ms = secs * 1_000;
+        let jitter = self.jitter.clamp(0.0, 1.0) * jitter_sample.clamp(0.0, 1.0);
+        base_ms + (base_ms as f64 * jitter) as u64
+    }
+}
+
+/// One failed attempt of a job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryAttempt {
+    /// 1-based attempt number.
+    pub attempt: u32,
+    pub error_class: String,
+    pub error_message: String,
+    /// Unix millis when the attempt failed.
+    pub failed_at: i64,
+    /// Unix millis the retry was scheduled for; None if the job was not retried.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_matches_legacy_backoff() {
+        let policy = RetryPolicy::default();
+        assert!(policy.validate().is_ok());
+        assert_eq!(policy.backoff_ms(1, 0.5), 4_000);
+        assert_eq!(policy.backoff_ms(2, 0.5), 16_000);
+        assert_eq!(policy.backoff_ms(3, 0.5), 64_000);
+
+        assert!(policy.should_retry(ERROR_CLASS_TRANSIENT, None, 0));
+        assert!(policy.should_retry(ERROR_CLASS_TRANSIENT, None, 2));
+        assert!(!policy.should_retry(ERROR_CLASS_TRANSIENT, None, 3));
+        assert!(!policy.should_retry(ERROR_CLASS_PERMANENT, None, 0));
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            backoff_base_secs: 2,
+            jitter: 0.5,
+            retryable_errors: vec![ERROR_CLASS_BRIDGE.to_string()],
+        };
+        assert!(policy.should_retry(ERROR_CLASS_BRIDGE, None, 0));
+        assert!(!policy.should_retry(ERROR_CLASS_BRIDGE, None, 1));
+        assert!(!policy.should_retry(ERROR_CLASS_TRANSIENT, None, 0));
+        assert_eq!(policy.backoff_ms(1, 0.0), 2_000);
+        assert_eq!(policy.backoff_ms(1, 1.0), 3_000);
+        assert_eq!(policy.backoff_ms(64, 0.0), MAX_BACKOFF_SECS * 1_000);
+    }
+
+    #[test]
+    fn test_validate() {
+        let invalid = |f: fn(&mut RetryPolicy)| {
+            let mut policy = RetryPolicy::default();
+            f(&mut policy);
+            policy.validate().is_err()
+        };
+        assert!(invalid(|p| p.max_attempts = 0));
+        assert!(invalid(|p| p.backoff_base_secs = 0));
+        assert!(invalid(|p| p.jitter = 1.5));
+        assert!(invalid(|p| p.retryable_errors.push("flaky".to_string())));
+        assert!(!invalid(|p| p.retryable_errors.push("timeout".to_string())));
+    }
+
+    #[test]
+    fn test_retry_by_category() {
+        let policy = RetryPolicy {
+            retryable_errors: vec![ErrorCategory::Timeout.as_str().to_string()],
+            ..Default::default()
+        };
+        assert!(policy.validate().is_ok());
+        assert!(policy.should_retry(ERROR_CLASS_TRANSIENT, Some(ErrorCategory::Timeout), 0));
+        assert!(!policy.should_retry(ERROR_CLASS_TRANSIENT, Some(ErrorCategory::IoError), 0));
+        assert!(!policy.should_retry(ERROR_CLASS_TRANSIENT, None, 0));
+    }
+}
@@ -0,0 +1,443 @@
+//! Chunked payload streaming over the Split Plane Protocol.
+//!
+//! Large payloads (Deploy artifacts, big DispatchCommands) are sent as a
+//! sequence of ordinary messages sharing the same JOB_ID:
+//!
+//! ```text
+//! STREAM_BEGIN  payload = JSON StreamBegin { opcode, total_len }
+//! STREAM_CHUNK  payload = raw bytes (repeated, in order)
+//! STREAM_END    payload = JSON StreamEnd { total_len, chunk_count, blake3 }
+//! ```
+//!
+//! The receiver reassembles the chunks into a single `Message` carrying the
+//! original opcode. The blake3 digest and counts in `StreamEnd` are checked
+//! before the message is released, so truncated or reordered streams fail loud.
+//!
+//! Streaming removes the limit on the *sending* side only: [`StreamEncoder`]
+//! holds one chunk at a time. [`StreamAssembler`] still buffers the whole
+//! payload and releases it as one `Message`, so received payloads remain
+//! capped at [`MAX_PAYLOAD_SIZE`] (4 GiB) and must fit in memory. Every
+//! current receiver parses the payload as one JSON message, which needs it
+//! whole anyway.
+
+use crate::error::{ProtocolError, Result};
+use crate::{JobId, Message, OpCode, MAX_PAYLOAD_SIZE};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::Read;
+
+/// Default chunk size for streamed payloads (1 MiB).
+pub const DEFAULT_STREAM_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Payload of `OpCode::StreamBegin`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamBegin {
+    /// Opcode of the reassembled message (raw u8 on the wire).
+    pub opcode: u8,
+    /// Total payload length if known up front (absent when streaming a reader).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_len: Option<u64>,
+}
+
+/// Payload of `OpCode::StreamEnd`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamEnd {
+    pub total_len: u64,
+    pub chunk_count: u64,
+    /// Hex blake3 digest of the full payload.
+    pub blake3: String,
+}
+
+fn stream_error(job_id: JobId, reason: impl Into<String>) -> ProtocolError {
+    ProtocolError::Stream {
+        job_id: job_id.as_u64(),
+        reason: reason.into(),
+    }
+}
+
+fn control_message<T: Serialize>(opcode: OpCode, job_id: JobId, body: &T) -> Result<Message> {
+    Message::new(opcode, job_id, serde_json::to_vec(body)?)
+}
+
+/// Split an in-memory payload into messages.
+///
+/// Payloads that fit in one chunk are returned as a single plain message so
+/// small messages keep the existing wire format.
+pub fn split_payload(
+    opcode: OpCode,
+    job_id: JobId,
+    payload: Vec<u8>,
+    chunk_size: usize,
+) -> Result<Vec<Message>> {
+    if chunk_size == 0 {
+        return Err(stream_error(job_id, "chunk size must be non-zero"));
+    }
+    if payload.len() <= chunk_size {
+        return Ok(vec![Message::new(opcode, job_id, payload)?]);
+    }
+
+    let total_len = payload.len() as u64;
+    let mut messages = Vec::with_capacity(payload.len() / chunk_size + 3);
+    messages.push(control_message(
+        OpCode::StreamBegin,
+        job_id,
+        &StreamBegin {
+            opcode: opcode.as_u8(),
+            total_len: Some(total_len),
+        },
+    )?);
+    for chunk in payload.chunks(chunk_size) {
+        messages.push(Message::new(OpCode::StreamChunk, job_id, chunk.to_vec())?);
+    }
+    let chunk_count = (messages.len() - 1) as u64;
+    messages.push(control_message(
+        OpCode::StreamEnd,
+        job_id,
+        &StreamEnd {
+            total_len,
+            chunk_count,
+            blake3: blake3::hash(&payload).to_hex().to_string(),
+        },
+    )?);
+    Ok(messages)
+}
+
+/// Lazily stream a reader as BEGIN / CHUNK... / END messages.
+///
+/// Only one chunk is held in memory at a time; the digest is computed
+/// incrementally and emitted in the trailing `StreamEnd`.
+pub struct StreamEncoder<R: Read> {
+    reader: R,
+    opcode: OpCode,
+    job_id: JobId,
+    chunk_size: usize,
+    hasher: blake3::Hasher,
+    total_len: u64,
+    chunk_count: u64,
+    state: EncoderState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EncoderState {
+    Begin,
+    Chunks,
+    End,
+    Done,
+}
+
+impl<R: Read> StreamEncoder<R> {
+    pub fn new(opcode: OpCode, job_id: JobId, reader: R, chunk_size: usize) -> Result<Self> {
+        if chunk_size == 0 || chunk_size > MAX_PAYLOAD_SIZE {
+            return Err(stream_error(job_id, "chunk size out of range"));
+        }
+        Ok(Self {
+            reader,
+            opcode,
+            job_id,
+            chunk_size,
+            hasher: blake3::Hasher::new(),
+            total_len: 0,
+            chunk_count: 0,
+            state: EncoderState::Begin,
+        })
+    }
+
+    fn read_chunk(&mut self) -> Result<Vec<u8>> {
+        let mut buf = Vec::with_capacity(self.chunk_size);
+        (&mut self.reader)
+            .take(self.chunk_size as u64)
+            .read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn next_message(&mut self) -> Result<Option<Message>> {
+        match self.state {
+            EncoderState::Begin => {
+                self.state = EncoderState::Chunks;
+                let begin = StreamBegin {
+                    opcode: self.opcode.as_u8(),
+                    total_len: None,
+                };
+                control_message(OpCode::StreamBegin, self.job_id, &begin).map(Some)
+            }
+            EncoderState::Chunks => {
+                let chunk = self.read_chunk()?;
+                if chunk.is_empty() {
+                    self.state = EncoderState::End;
+                    return self.next_message();
+                }
+                self.hasher.update(&chunk);
+                self.total_len += chunk.len() as u64;
+                self.chunk_count += 1;
+                Message::new(OpCode::StreamChunk, self.job_id, chunk).map(Some)
+            }
+            EncoderState::End => {
+                self.state = EncoderState::Done;
+                let end = StreamEnd {
+                    total_len: self.total_len,
+                    chunk_count: self.chunk_count,
+                    blake3: self.hasher.finalize().to_hex().to_string(),
+                };
+                control_message(OpCode::StreamEnd, self.job_id, &end).map(Some)
+            }
+            EncoderState::Done => Ok(None),
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamEncoder<R> {
+    type Item = Result<Message>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_message() {
+            Ok(Some(msg)) => Some(Ok(msg)),
+            Ok(None) => None,
+            Err(err) => {
+                self.state = EncoderState::Done;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// In-flight stream being reassembled.
+struct PartialStream {
+    opcode: OpCode,
+    expected_len: Option<u64>,
+    payload: Vec<u8>,
+    hasher: blake3::Hasher,
+    chunk_count: u64,
+}
+
+/// Reassembles streamed messages, keyed by sender (e.g. worker identity + job id).
+///
+/// Non-stream messages pass straight through, so callers can route every
+/// received message through `push`. Each stream is buffered in memory until
+/// `STREAM_END`; streams over `max_payload` bytes (at most
+/// [`MAX_PAYLOAD_SIZE`]) are rejected.
+pub struct StreamAssembler<K> {
+    streams: HashMap<K, PartialStream>,
+    max_payload: usize,
+}
+
+impl<K: Eq + Hash> Default for StreamAssembler<K> {
+    fn default() -> Self {
+        Self::new(MAX_PAYLOAD_SIZE)
+    }
+}
+
+impl<K: Eq + Hash> StreamAssembler<K> {
+    /// Create an assembler that rejects streams larger than `max_payload` bytes.
+    pub fn new(max_payload: usize) -> Self {
+        Self {
+            streams: HashMap::new(),
+            max_payload,
+        }
+    }
+
+    /// Number of streams currently being reassembled.
+    pub fn pending(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Drop any partial stream for `key`.
+    pub fn discard(&mut self, key: &K) {
+        self.streams.remove(key);
+    }
+
+    /// Keep only partial streams whose key matches (e.g. drop a departed peer).
+    pub fn retain(&mut self, mut keep: impl FnMut(&K) -> bool) {
+        self.streams.retain(|key, _| keep(key));
+    }
+
+    /// Feed a received message.
+    ///
+    /// Returns `Some(message)` when a complete message is available (either a
+    /// plain message or a fully reassembled stream), `None` while a stream is
+    /// still in progress.
+    pub fn push(&mut self, key: K, msg: Message) -> Result<Option<Message>> {
+        let job_id = msg.header.job_id;
+        match msg.header.opcode {
+            OpCode::StreamBegin => {
+                let begin: StreamBegin = serde_json::from_slice(&msg.payload)?;
+                let opcode = OpCode::from_u8(begin.opcode)?;
+                if matches!(
+                    opcode,
+                    OpCode::StreamBegin | OpCode::StreamChunk | OpCode::StreamEnd
+                ) {
+                    return Err(stream_error(job_id, "nested streams are not allowed"));
+                }
+                if let Some(total) = begin.total_len {
+                    if total > self.max_payload as u64 {
+                        return Err(ProtocolError::PayloadTooLarge {
+                            size: usize::try_from(total).unwrap_or(usize::MAX),
+                            max: self.max_payload,
+                        });
+                    }
+                }
+                let capacity = begin
+                    .total_len
+                    .and_then(|len| usize::try_from(len).ok())
+                    .unwrap_or(0);
+                // A new BEGIN replaces any abandoned stream from the same sender.
+                self.streams.insert(
+                    key,
+                    PartialStream {
+                        opcode,
+                        expected_len: begin.total_len,
+                        payload: Vec::with_capacity(capacity),
+                        hasher: blake3::Hasher::new(),
+                        chunk_count: 0,
+                    },
+                );
+                Ok(None)
+            }
+            OpCode::StreamChunk => {
+                let max_payload = self.max_payload;
+                let Some(stream) = self.streams.get_mut(&key) else {
+                    return Err(stream_error(job_id, "chunk received without STREAM_BEGIN"));
+                };
+                let new_len = stream.payload.len() + msg.payload.len();
+                if new_len > max_payload {
+                    self.streams.remove(&key);
+                    return Err(ProtocolError::PayloadTooLarge {
+                        size: new_len,
+                        max: max_payload,
+                    });
+                }
+                stream.hasher.update(&msg.payload);
+                stream.payload.extend_from_slice(&msg.payload);
+                stream.chunk_count += 1;
+                Ok(None)
+            }
+            OpCode::StreamEnd => {
+                let Some(stream) = self.streams.remove(&key) else {
+                    return Err(stream_error(job_id, "STREAM_END received without STREAM_BEGIN"));
+                };
+                let end: StreamEnd = serde_json::from_slice(&msg.payload)?;
+                let got_len = stream.payload.len() as u64;
+                if got_len != end.total_len || stream.expected_len.is_some_and(|l| l != got_len)
+                {
+                    return Err(stream_error(
+                        job_id,
+                        format!(
+                            "length mismatch: expected {} bytes, got {}",
+                            end.total_len, got_len
+                        ),
+                    ));
+                }
+                if stream.chunk_count != end.chunk_count {
+                    return Err(stream_error(
+                        job_id,
+                        format!(
+                            "chunk count mismatch: expected {}, got {}",
+                            end.chunk_count, stream.chunk_count
+                        ),
+                    ));
+                }
+                let digest = stream.hasher.finalize().to_hex().to_string();
+                if digest != end.blake3 {
+                    return Err(stream_error(job_id, "blake3 digest mismatch"));
+                }
+                Message::new(stream.opcode, job_id, stream.payload).map(Some)
+            }
+            _ => Ok(Some(msg)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn roundtrip(messages: Vec<Message>) -> Message {
+        let mut assembler = StreamAssembler::default();
+        let mut out = None;
+        for msg in messages {
+            // Exercise the wire encoding too.
+            let (header, body) = msg.pack().unwrap();
+            let msg = Message::unpack(&[header, body]).unwrap();
+            if let Some(done) = assembler.push("peer", msg).unwrap() {
+                assert!(out.is_none(), "only one message should complete");
+                out = Some(done);
+            }
+        }
+        assert_eq!(assembler.pending(), 0);
+        out.expect("stream should complete")
+    }
+
+    #[test]
+    fn test_small_payload_is_not_chunked() {
+        let messages = split_payload(OpCode::Dispatch, JobId::new(1), payload(10), 64).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].header.opcode, OpCode::Dispatch);
+    }
+
+    #[test]
+    fn test_split_and_reassemble() {
+        let data = payload(1000);
+        let messages = split_payload(OpCode::Deploy, JobId::new(7), data.clone(), 64).unwrap();
+        assert_eq!(messages.first().unwrap().header.opcode, OpCode::StreamBegin);
+        assert_eq!(messages.last().unwrap().header.opcode, OpCode::StreamEnd);
+        assert_eq!(messages.len(), 16 + 2);
+
+        let msg = roundtrip(messages);
+        assert_eq!(msg.header.opcode, OpCode::Deploy);
+        assert_eq!(msg.header.job_id, JobId::new(7));
+        assert_eq!(msg.payload, data);
+    }
+
+    #[test]
+    fn test_encoder_streams_reader() {
+        let data = payload(300);
+        let messages: Vec<Message> =
+            StreamEncoder::new(OpCode::Dispatch, JobId::new(3), data.as_slice(), 128)
+                .unwrap()
+                .collect::<Result<_>>()
+                .unwrap();
+        assert_eq!(messages.len(), 3 + 2);
+
+        let msg = roundtrip(messages);
+        assert_eq!(msg.header.opcode, OpCode::Dispatch);
+        assert_eq!(msg.payload, data);
+    }
+
+    #[test]
+    fn test_dropped_chunk_is_rejected() {
+        let mut messages = split_payload(OpCode::Deploy, JobId::new(1), payload(256), 64).unwrap();
+        messages.remove(2);
+
+        let mut assembler = StreamAssembler::default();
+        let mut result = Ok(None);
+        for msg in messages {
+            result = assembler.push((), msg);
+            if result.is_err() {
+                break;
+            }
+        }
+        assert!(matches!(result, Err(ProtocolError::Stream { .. })));
+    }
+
+    #[test]
+    fn test_chunk_without_begin_is_rejected() {
+        let mut assembler = StreamAssembler::default();
+        let chunk = Message::new(OpCode::StreamChunk, JobId::new(1), vec![1, 2, 3]).unwrap();
+        assert!(matches!(
+            assembler.push((), chunk),
+            Err(ProtocolError::Stream { .. })
+        ));
+    }
+
+    #[test]
+    fn test_stream_over_limit_is_rejected() {
+        let messages = split_payload(OpCode::Deploy, JobId::new(1), payload(256), 64).unwrap();
+        let mut assembler = StreamAssembler::new(100);
+        let err = assembler.push((), messages[0].clone()).unwrap_err();
+        assert!(matches!(err, ProtocolError::PayloadTooLarge { .. }));
+    }
+}
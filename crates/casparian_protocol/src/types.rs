@@ -3,13 +3,15 @@
 use serde::de;
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::path::PathBuf;
 use std::str::FromStr;
 use thiserror::Error;
 use url::form_urlencoded;
 
+use crate::version::{ProtocolFeatures, ProtocolVersionRange};
+
 // ============================================================================
 // Canonical Enums (used across all crates)
 // ============================================================================
@@ -106,14 +108,25 @@ pub enum SinkMode {
     Replace,
     /// Error if data already exists
     Error,
+    /// Replace rows whose key columns match an output row, insert the rest
+    /// (table sinks only; the key comes from the sink URI)
+    Upsert,
 }
 
 impl SinkMode {
+    pub const ALL: &'static [SinkMode] = &[
+        SinkMode::Append,
+        SinkMode::Replace,
+        SinkMode::Error,
+        SinkMode::Upsert,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             SinkMode::Append => "append",
             SinkMode::Replace => "replace",
             SinkMode::Error => "error",
+            SinkMode::Upsert => "upsert",
         }
     }
 }
@@ -132,8 +145,71 @@ impl FromStr for SinkMode {
             "append" => Ok(SinkMode::Append),
             "replace" => Ok(SinkMode::Replace),
             "error" => Ok(SinkMode::Error),
+            "upsert" => Ok(SinkMode::Upsert),
+            _ => Err(format!(
+                "Invalid sink mode: '{}'. Expected: append, replace, error, or upsert",
+                s
+            )),
+        }
+    }
+}
+
+/// How an appending table sink reconciles output columns with an existing
+/// destination table. "Extra" columns are in the output but not the table;
+/// "missing" columns are in the table but not the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaEvolution {
+    /// Add extra columns to the table (ALTER TABLE ... ADD COLUMN); missing
+    /// columns are filled with NULL.
+    AddMissingColumns,
+    /// Reject extra columns; missing columns are filled with NULL (default)
+    #[default]
+    FailOnExtra,
+    /// Reject any difference between output and table columns.
+    Strict,
+}
+
+impl SchemaEvolution {
+    /// Sink URI query parameter carrying the mode (`?schema_evolution=strict`).
+    pub const URI_PARAM: &'static str = "schema_evolution";
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaEvolution::AddMissingColumns => "add_missing_columns",
+            SchemaEvolution::FailOnExtra => "fail_on_extra",
+            SchemaEvolution::Strict => "strict",
+        }
+    }
+
+    /// Read the mode from a sink URI's query string, defaulting when absent.
+    pub fn from_sink_uri(uri: &str) -> Result<Self, String> {
+        let Some((_, query)) = uri.split_once('?') else {
+            return Ok(Self::default());
+        };
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == Self::URI_PARAM)
+            .map(|(_, value)| value.parse())
+            .unwrap_or(Ok(Self::default()))
+    }
+}
+
+impl fmt::Display for SchemaEvolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for SchemaEvolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "add_missing_columns" => Ok(SchemaEvolution::AddMissingColumns),
+            "fail_on_extra" => Ok(SchemaEvolution::FailOnExtra),
+            "strict" => Ok(SchemaEvolution::Strict),
             _ => Err(format!(
-                "Invalid sink mode: '{}'. Expected: append, replace, or error",
+                "Invalid schema evolution: '{}'. Expected: add_missing_columns, fail_on_extra, or strict",
                 s
             )),
         }
@@ -252,6 +328,87 @@ impl FromStr for ProcessingStatus {
     }
 }
 
+// ============================================================================
+// Job Priority
+// ============================================================================
+
+/// Dispatch lane of a job. Stored in `cf_processing_queue.priority` as
+/// [`JobPriority::value`]; higher lanes are leased first, FIFO within a lane.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    /// May preempt a LOW job when every worker is busy (if enabled)
+    Urgent,
+}
+
+impl JobPriority {
+    pub const ALL: &'static [JobPriority] = &[
+        JobPriority::Low,
+        JobPriority::Normal,
+        JobPriority::High,
+        JobPriority::Urgent,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobPriority::Low => "LOW",
+            JobPriority::Normal => "NORMAL",
+            JobPriority::High => "HIGH",
+            JobPriority::Urgent => "URGENT",
+        }
+    }
+
+    /// Value stored in the queue's `priority` column. NORMAL is 0, the
+    /// column default, so rows written before lanes existed stay NORMAL.
+    pub fn value(&self) -> i32 {
+        match self {
+            JobPriority::Low => -10,
+            JobPriority::Normal => 0,
+            JobPriority::High => 10,
+            JobPriority::Urgent => 20,
+        }
+    }
+
+    /// Lane of a stored `priority` value; values between lanes round down.
+    pub fn from_value(value: i32) -> Self {
+        if value >= JobPriority::Urgent.value() {
+            JobPriority::Urgent
+        } else if value >= JobPriority::High.value() {
+            JobPriority::High
+        } else if value >= JobPriority::Normal.value() {
+            JobPriority::Normal
+        } else {
+            JobPriority::Low
+        }
+    }
+}
+
+impl fmt::Display for JobPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for JobPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "LOW" => Ok(JobPriority::Low),
+            "NORMAL" => Ok(JobPriority::Normal),
+            "HIGH" => Ok(JobPriority::High),
+            "URGENT" => Ok(JobPriority::Urgent),
+            _ => Err(format!("Invalid job priority: '{}'", s)),
+        }
+    }
+}
+
 // ============================================================================
 // Plugin Status (Canonical Definition)
 // ============================================================================
@@ -463,6 +620,41 @@ impl FromStr for WorkerStatus {
     }
 }
 
+/// Heartbeat-derived health of a connected worker, tracked by the Sentinel.
+///
+/// Progresses HEALTHY -> DEGRADED -> UNREACHABLE -> EVICTED as heartbeats stop
+/// arriving; any message from the worker returns it to HEALTHY. EVICTED is
+/// terminal: the worker is dropped and must re-register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WorkerHealth {
+    #[default]
+    Healthy,
+    /// Missed at least one heartbeat
+    Degraded,
+    /// Silent long enough that its jobs are at risk
+    Unreachable,
+    /// Dropped; its in-flight jobs are failed
+    Evicted,
+}
+
+impl WorkerHealth {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkerHealth::Healthy => "HEALTHY",
+            WorkerHealth::Degraded => "DEGRADED",
+            WorkerHealth::Unreachable => "UNREACHABLE",
+            WorkerHealth::Evicted => "EVICTED",
+        }
+    }
+}
+
+impl fmt::Display for WorkerHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 // ============================================================================
 // Data Types (Canonical Definition)
 // ============================================================================
@@ -959,6 +1151,20 @@ pub struct QuarantineConfig {
     /// Optional directory override for quarantine outputs (file-based sinks only).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quarantine_dir: Option<String>,
+    /// Collect per-column statistics (null count, min/max, distinct estimate)
+    /// for the output. Implied by `max_null_pct`.
+    #[serde(default)]
+    pub column_stats: bool,
+    /// Maximum percentage of nulls allowed in any single column before failing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_null_pct: Option<f64>,
+}
+
+impl QuarantineConfig {
+    /// Whether column statistics must be collected for this output.
+    pub fn collects_column_stats(&self) -> bool {
+        self.column_stats || self.max_null_pct.is_some()
+    }
 }
 
 impl Default for QuarantineConfig {
@@ -968,6 +1174,8 @@ impl Default for QuarantineConfig {
             max_quarantine_pct: 10.0,
             max_quarantine_count: None,
             quarantine_dir: None,
+            column_stats: false,
+            max_null_pct: None,
         }
     }
 }
@@ -980,12 +1188,55 @@ pub struct SinkConfig {
     pub uri: String,
     #[serde(default)]
     pub mode: SinkMode,
+    /// Column reconciliation when appending to an existing table.
+    #[serde(default)]
+    pub schema_evolution: SchemaEvolution,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quarantine_config: Option<QuarantineConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schema: Option<SchemaDefinition>,
 }
 
+/// True for the catch-all topics (`*` or `output`) that receive every output
+/// without its own sink config.
+pub fn is_default_sink_topic(topic: &str) -> bool {
+    topic == "*" || topic == crate::defaults::DEFAULT_SINK_TOPIC
+}
+
+/// Sink config for one output of a job.
+///
+/// `sinks` maps output names (topics) to sinks, so outputs of one job can go
+/// to different URIs with different modes. Resolution order: the output's own
+/// topic, then the default topic, then the only configured sink.
+pub fn select_sink_for_output<'a>(
+    sinks: &'a [SinkConfig],
+    output_name: &str,
+) -> Option<&'a SinkConfig> {
+    if let Some(exact) = sinks.iter().find(|sink| sink.topic == output_name) {
+        return Some(exact);
+    }
+    if let Some(default) = sinks.iter().find(|sink| is_default_sink_topic(&sink.topic)) {
+        return Some(default);
+    }
+    if sinks.len() == 1 {
+        return sinks.first();
+    }
+    None
+}
+
+/// Outputs in `outputs` that no sink in `sinks` would receive. With no sinks
+/// at all, everything goes to the worker's default sink.
+pub fn untargeted_outputs<'a>(sinks: &[SinkConfig], outputs: &[&'a str]) -> Vec<&'a str> {
+    if sinks.is_empty() {
+        return Vec::new();
+    }
+    outputs
+        .iter()
+        .copied()
+        .filter(|output| select_sink_for_output(sinks, output).is_none())
+        .collect()
+}
+
 /// Typed schema definition for an output.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SchemaDefinition {
@@ -997,8 +1248,16 @@ pub struct SchemaDefinition {
 pub enum SinkScheme {
     Parquet,
     Csv,
+    /// Newline-delimited JSON (`jsonl://dir`).
+    Jsonl,
+    /// Arrow IPC stream or file (`arrow://dir`, `?format=file` for the file format).
+    Arrow,
     Duckdb,
     File,
+    /// S3-compatible object store (`s3://bucket/prefix`).
+    S3,
+    /// PostgreSQL database (`postgres://user@host:port/db?table=...`).
+    Postgres,
 }
 
 impl SinkScheme {
@@ -1006,8 +1265,12 @@ impl SinkScheme {
         match self {
             SinkScheme::Parquet => "parquet",
             SinkScheme::Csv => "csv",
+            SinkScheme::Jsonl => "jsonl",
+            SinkScheme::Arrow => "arrow",
             SinkScheme::Duckdb => "duckdb",
             SinkScheme::File => "file",
+            SinkScheme::S3 => "s3",
+            SinkScheme::Postgres => "postgres",
         }
     }
 }
@@ -1019,8 +1282,12 @@ impl FromStr for SinkScheme {
         match s.to_lowercase().as_str() {
             "parquet" => Ok(SinkScheme::Parquet),
             "csv" => Ok(SinkScheme::Csv),
+            "jsonl" | "ndjson" => Ok(SinkScheme::Jsonl),
+            "arrow" => Ok(SinkScheme::Arrow),
             "duckdb" => Ok(SinkScheme::Duckdb),
             "file" => Ok(SinkScheme::File),
+            "s3" => Ok(SinkScheme::S3),
+            "postgres" | "postgresql" => Ok(SinkScheme::Postgres),
             other => Err(format!("Unsupported sink scheme: '{}'", other)),
         }
     }
@@ -1129,136 +1396,958 @@ pub struct DispatchCommand {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_code: Option<String>, // Plugin source code for subprocess execution
     pub artifact_hash: String, // SHA256(source + lockfile + manifest + schemas)
+    /// uv.lock content for `env_hash`, so the worker can build the env on a cache miss
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lockfile_content: Option<String>,
+
+    /// Resource limits enforced on the plugin process (unset = unlimited)
+    #[serde(default, skip_serializing_if = "ResourceLimits::is_unlimited")]
+    pub limits: ResourceLimits,
+
+    /// Additional files for the same plugin/sinks, run after `file_path` in
+    /// order (empty = single-file dispatch). Only sent to workers that
+    /// negotiated `ProtocolFeatures::BATCH_DISPATCH`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub batch: Vec<BatchFile>,
+    /// Queue lane the job was leased from
+    #[serde(default)]
+    pub priority: JobPriority,
+    /// Read only this part of `file_path` (incremental ingestion of a growing
+    /// file). Only sent to workers that negotiated `ProtocolFeatures::BYTE_RANGE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_range: Option<ByteRange>,
+    /// Tag of the input file, for `{tag}` in sink URIs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Materialization key of the input (file generation + parser
+    /// fingerprint). Parquet outputs checkpoint their part files under it so
+    /// a re-dispatch after a worker crash resumes instead of rewriting. Unset
+    /// for batched files and byte-range reads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_key: Option<String>,
+    /// How to read a CSV input; unset = parser defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv_dialect: Option<CsvDialect>,
+    /// Character encoding of a text input, overriding the worker's detection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_encoding: Option<TextEncoding>,
+    /// Hash of `file_path` from the Sentinel's cache. The worker uses it
+    /// instead of rehashing while the file still matches the fingerprint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_source_hash: Option<SourceHash>,
+    /// Lineage of the job that wrote `file_path`, when the input is an
+    /// earlier pipeline stage's artifact. The worker prepends its own hop
+    /// and returns the chain in the receipt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_lineage: Option<LineageChain>,
 }
 
-// ============================================================================
-// OpCode.CONCLUDE (Worker -> Sentinel)
-// ============================================================================
+/// Size and modification time identifying one generation of an input file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceFingerprint {
+    pub size: u64,
+    /// Modification time, Unix milliseconds
+    pub mtime_ms: i64,
+}
 
-/// Job completion status - type-safe enum instead of stringly-typed
+/// An input file as it was read by the worker.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum JobStatus {
-    Success,
-    PartialSuccess,
-    CompletedWithWarnings,
-    Failed,
-    Rejected, // Worker at capacity
-    Aborted,  // Cancelled by sentinel
+pub struct SourceFile {
+    pub path: String,
+    pub fingerprint: SourceFingerprint,
 }
 
-impl JobStatus {
-    pub const ALL: &'static [JobStatus] = &[
-        JobStatus::Success,
-        JobStatus::PartialSuccess,
-        JobStatus::CompletedWithWarnings,
-        JobStatus::Failed,
-        JobStatus::Rejected,
-        JobStatus::Aborted,
-    ];
+/// Blake3 hash of an input file and the generation it was computed from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceHash {
+    pub hash: String,
+    pub fingerprint: SourceFingerprint,
+}
 
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            JobStatus::Success => "SUCCESS",
-            JobStatus::PartialSuccess => "PARTIAL_SUCCESS",
-            JobStatus::CompletedWithWarnings => "COMPLETED_WITH_WARNINGS",
-            JobStatus::Failed => "FAILED",
-            JobStatus::Rejected => "REJECTED",
-            JobStatus::Aborted => "ABORTED",
-        }
+/// Half-open byte range `[start, end)` of an input file.
+///
+/// Both ends fall on line boundaries: `start` is where the previous read
+/// stopped and `end` follows the last complete line seen when the job was
+/// queued, so a line still being written is left for the next read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    pub fn len(&self) -> u64 {
+        self.end.saturating_sub(self.start)
     }
 
-    pub fn is_success(&self) -> bool {
-        matches!(
-            self,
-            JobStatus::Success | JobStatus::PartialSuccess | JobStatus::CompletedWithWarnings
-        )
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
+}
 
-    pub fn is_failure(&self) -> bool {
-        !self.is_success()
+/// One follow-on file in a batched DISPATCH. Each file is its own queue job
+/// with its own lease; the job in the message header covers `file_path`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchFile {
+    pub job_id: JobId,
+    pub file_id: i64,
+    pub file_path: String,
+    pub lease_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv_dialect: Option<CsvDialect>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_encoding: Option<TextEncoding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_source_hash: Option<SourceHash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_lineage: Option<LineageChain>,
+}
+
+/// Per-job resource limits for plugin execution.
+///
+/// CPU time and memory are enforced by the OS on the plugin process (rlimits
+/// on Unix, a job object on Windows); the wall-clock timeout is enforced by
+/// the worker, which kills the process when it expires.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// Wall-clock limit for running the plugin, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// CPU time limit of the plugin process, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cpu_secs: Option<u64>,
+    /// Memory (address space) limit of the plugin process, in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_bytes: Option<u64>,
+    /// Largest decompressed size of a compressed input, in bytes
+    /// (`DEFAULT_MAX_DECOMPRESSED_BYTES` when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_decompressed_bytes: Option<u64>,
+    /// Size quota of the job's scratch directory, in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_scratch_bytes: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.timeout_secs.is_none()
+            && self.max_cpu_secs.is_none()
+            && self.max_memory_bytes.is_none()
+            && self.max_decompressed_bytes.is_none()
+            && self.max_scratch_bytes.is_none()
     }
 
-    /// Convert JobStatus to ProcessingStatus for queue updates
-    pub fn to_processing_status(&self) -> ProcessingStatus {
+    /// Decompressed-size limit applied to compressed inputs.
+    pub fn decompressed_limit(&self) -> u64 {
+        self.max_decompressed_bytes
+            .unwrap_or(DEFAULT_MAX_DECOMPRESSED_BYTES)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout_secs == Some(0) {
+            return Err("timeout_secs must be greater than 0".to_string());
+        }
+        if self.max_cpu_secs == Some(0) {
+            return Err("max_cpu_secs must be greater than 0".to_string());
+        }
+        if self
+            .max_memory_bytes
+            .is_some_and(|bytes| bytes < MIN_MEMORY_LIMIT_BYTES)
+        {
+            return Err(format!(
+                "max_memory_bytes must be at least {} MiB",
+                MIN_MEMORY_LIMIT_BYTES / (1024 * 1024)
+            ));
+        }
+        if self.max_decompressed_bytes == Some(0) {
+            return Err("max_decompressed_bytes must be greater than 0".to_string());
+        }
+        if self.max_scratch_bytes == Some(0) {
+            return Err("max_scratch_bytes must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Smallest accepted memory limit; interpreters fail to start below this.
+pub const MIN_MEMORY_LIMIT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Environment variable carrying a job's `CsvDialect` (JSON) to the plugin.
+pub const CSV_DIALECT_ENV: &str = "CASPARIAN_CSV_DIALECT";
+
+/// Environment variable carrying the path of a job's scratch directory to
+/// the plugin. The worker also points `TMPDIR` there and removes the
+/// directory when the job ends.
+pub const SCRATCH_DIR_ENV: &str = "CASPARIAN_SCRATCH_DIR";
+
+/// Character encoding of a text input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    /// ISO-8859-1
+    #[serde(rename = "latin-1")]
+    Latin1,
+    #[serde(rename = "windows-1252")]
+    Windows1252,
+}
+
+impl TextEncoding {
+    pub const ALL: &'static [TextEncoding] = &[
+        TextEncoding::Utf8,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::Latin1,
+        TextEncoding::Windows1252,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
         match self {
-            JobStatus::Success | JobStatus::PartialSuccess | JobStatus::CompletedWithWarnings => {
-                ProcessingStatus::Completed
-            }
-            JobStatus::Failed => ProcessingStatus::Failed,
-            JobStatus::Aborted => ProcessingStatus::Aborted,
-            JobStatus::Rejected => ProcessingStatus::Queued, // Requeue on rejection
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Utf16Be => "utf-16be",
+            TextEncoding::Latin1 => "latin-1",
+            TextEncoding::Windows1252 => "windows-1252",
         }
     }
 }
 
-impl fmt::Display for JobStatus {
+impl fmt::Display for TextEncoding {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.as_str())
     }
 }
 
-impl FromStr for JobStatus {
+impl FromStr for TextEncoding {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_uppercase().as_str() {
-            "SUCCESS" => Ok(JobStatus::Success),
-            "PARTIAL_SUCCESS" => Ok(JobStatus::PartialSuccess),
-            "COMPLETED_WITH_WARNINGS" => Ok(JobStatus::CompletedWithWarnings),
-            "FAILED" => Ok(JobStatus::Failed),
-            "REJECTED" => Ok(JobStatus::Rejected),
-            "ABORTED" => Ok(JobStatus::Aborted),
-            _ => Err(format!("Invalid job status: '{}'", s)),
+        let normalized = s.trim().to_lowercase().replace('_', "-");
+        match normalized.as_str() {
+            "utf-8" | "utf8" => Ok(TextEncoding::Utf8),
+            "utf-16le" | "utf16le" => Ok(TextEncoding::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(TextEncoding::Utf16Be),
+            "latin-1" | "latin1" | "iso-8859-1" => Ok(TextEncoding::Latin1),
+            "windows-1252" | "cp1252" => Ok(TextEncoding::Windows1252),
+            _ => Err(format!(
+                "Invalid encoding: '{}'. Expected: utf-8, utf-16le, utf-16be, latin-1, \
+                 or windows-1252",
+                s
+            )),
         }
     }
 }
 
-// ============================================================================
-// Diagnostics (Worker -> Sentinel)
-// ============================================================================
-
-/// Structured diagnostics included with failures (optional).
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct JobDiagnostics {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub schema_mismatch: Option<SchemaMismatch>,
+/// What a Sentinel schedule does when its cron expression fires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduleTarget {
+    /// Start a scan of the source, picking up new and changed files.
+    RescanSource { source_id: String },
+    /// Open a processing window for files tagged by the rule. Their jobs
+    /// wait in the queue while the window is closed.
+    RuleWindow {
+        rule_id: String,
+        window_minutes: u32,
+    },
 }
 
-/// Mismatch between expected schema and observed output.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SchemaMismatch {
-    pub output_name: String,
-    pub expected_columns: Vec<SchemaColumnSpec>,
-    pub actual_columns: Vec<ObservedColumn>,
-    pub missing_columns: Vec<String>,
-    pub extra_columns: Vec<String>,
-    pub order_mismatches: Vec<ColumnOrderMismatch>,
-    pub type_mismatches: Vec<TypeMismatch>,
-}
+impl ScheduleTarget {
+    /// `cf_schedules.target_kind`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ScheduleTarget::RescanSource { .. } => "rescan_source",
+            ScheduleTarget::RuleWindow { .. } => "rule_window",
+        }
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct SchemaColumnSpec {
-    pub name: String,
-    pub data_type: DataType,
-    pub nullable: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub format: Option<String>,
+    /// Source ID or rule ID the schedule acts on.
+    pub fn target_id(&self) -> &str {
+        match self {
+            ScheduleTarget::RescanSource { source_id } => source_id,
+            ScheduleTarget::RuleWindow { rule_id, .. } => rule_id,
+        }
+    }
+
+    /// Window length in milliseconds (0 for targets without a window).
+    pub fn window_ms(&self) -> i64 {
+        match self {
+            ScheduleTarget::RescanSource { .. } => 0,
+            ScheduleTarget::RuleWindow { window_minutes, .. } => {
+                i64::from(*window_minutes) * 60_000
+            }
+        }
+    }
+}
+
+/// What a schedule does about fires it missed while the Sentinel was down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleCatchUp {
+    /// Drop missed fires and wait for the next one.
+    #[default]
+    Skip,
+    /// Fire once for all missed fires; a missed window opens for its full
+    /// length from now.
+    RunOnce,
+}
+
+impl ScheduleCatchUp {
+    pub const ALL: &'static [ScheduleCatchUp] = &[ScheduleCatchUp::Skip, ScheduleCatchUp::RunOnce];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduleCatchUp::Skip => "skip",
+            ScheduleCatchUp::RunOnce => "run_once",
+        }
+    }
+}
+
+impl fmt::Display for ScheduleCatchUp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ScheduleCatchUp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "skip" => Ok(ScheduleCatchUp::Skip),
+            "run_once" => Ok(ScheduleCatchUp::RunOnce),
+            _ => Err(format!(
+                "Invalid catch-up policy: '{}'. Expected: skip or run-once",
+                s
+            )),
+        }
+    }
+}
+
+/// How to read a delimited text (CSV) input.
+///
+/// Set per tagging rule or per plugin, or detected from the file head by
+/// scout. The Sentinel sends the resolved dialect with DISPATCH and the
+/// worker hands it to the plugin as `CSV_DIALECT_ENV`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvDialect {
+    #[serde(default = "default_csv_delimiter")]
+    pub delimiter: char,
+    #[serde(default = "default_csv_quote")]
+    pub quote: char,
+    /// Escape character inside quoted fields; unset means quotes are
+    /// escaped by doubling them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escape: Option<char>,
+    /// Lines before the header row (report titles, export banners)
+    #[serde(default)]
+    pub skip_rows: usize,
+    #[serde(default)]
+    pub encoding: TextEncoding,
+    /// Field values read as null
+    #[serde(default = "default_null_tokens")]
+    pub null_tokens: Vec<String>,
+}
+
+fn default_csv_delimiter() -> char {
+    ','
+}
+
+fn default_csv_quote() -> char {
+    '"'
+}
+
+fn default_null_tokens() -> Vec<String> {
+    vec![String::new()]
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: default_csv_delimiter(),
+            quote: default_csv_quote(),
+            escape: None,
+            skip_rows: 0,
+            encoding: TextEncoding::default(),
+            null_tokens: default_null_tokens(),
+        }
+    }
+}
+
+impl CsvDialect {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, ch) in [("delimiter", self.delimiter), ("quote", self.quote)]
+            .into_iter()
+            .chain(self.escape.map(|escape| ("escape", escape)))
+        {
+            if !ch.is_ascii() || ch == '\n' || ch == '\r' {
+                return Err(format!(
+                    "{} must be a single ASCII character other than a line break",
+                    name
+                ));
+            }
+        }
+        if self.delimiter == self.quote {
+            return Err("delimiter and quote must differ".to_string());
+        }
+        if self.escape == Some(self.delimiter) {
+            return Err("delimiter and escape must differ".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Decompressed-size limit for compressed inputs when a parser sets none;
+/// guards workers against decompression bombs.
+pub const DEFAULT_MAX_DECOMPRESSED_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+
+/// A resource limit a plugin process can exceed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitKind {
+    WallClock,
+    CpuTime,
+    Memory,
+    /// A compressed input inflated past `max_decompressed_bytes`
+    DecompressedSize,
+    /// The job's scratch directory grew past `max_scratch_bytes`
+    ScratchSpace,
+}
+
+impl LimitKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitKind::WallClock => "wall_clock",
+            LimitKind::CpuTime => "cpu_time",
+            LimitKind::Memory => "memory",
+            LimitKind::DecompressedSize => "decompressed_size",
+            LimitKind::ScratchSpace => "scratch_space",
+        }
+    }
+
+    /// Category reported for a job terminated for exceeding this limit.
+    pub fn error_category(&self) -> ErrorCategory {
+        match self {
+            LimitKind::WallClock => ErrorCategory::Timeout,
+            LimitKind::CpuTime
+            | LimitKind::Memory
+            | LimitKind::DecompressedSize
+            | LimitKind::ScratchSpace => ErrorCategory::ResourceLimit,
+        }
+    }
+}
+
+impl fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// ============================================================================
+// OpCode.CONCLUDE (Worker -> Sentinel)
+// ============================================================================
+
+/// Job completion status - type-safe enum instead of stringly-typed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobStatus {
+    Success,
+    PartialSuccess,
+    CompletedWithWarnings,
+    Failed,
+    Rejected, // Worker at capacity
+    Aborted,  // Cancelled by sentinel
+}
+
+impl JobStatus {
+    pub const ALL: &'static [JobStatus] = &[
+        JobStatus::Success,
+        JobStatus::PartialSuccess,
+        JobStatus::CompletedWithWarnings,
+        JobStatus::Failed,
+        JobStatus::Rejected,
+        JobStatus::Aborted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Success => "SUCCESS",
+            JobStatus::PartialSuccess => "PARTIAL_SUCCESS",
+            JobStatus::CompletedWithWarnings => "COMPLETED_WITH_WARNINGS",
+            JobStatus::Failed => "FAILED",
+            JobStatus::Rejected => "REJECTED",
+            JobStatus::Aborted => "ABORTED",
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Success | JobStatus::PartialSuccess | JobStatus::CompletedWithWarnings
+        )
+    }
+
+    pub fn is_failure(&self) -> bool {
+        !self.is_success()
+    }
+
+    /// Convert JobStatus to ProcessingStatus for queue updates
+    pub fn to_processing_status(&self) -> ProcessingStatus {
+        match self {
+            JobStatus::Success | JobStatus::PartialSuccess | JobStatus::CompletedWithWarnings => {
+                ProcessingStatus::Completed
+            }
+            JobStatus::Failed => ProcessingStatus::Failed,
+            JobStatus::Aborted => ProcessingStatus::Aborted,
+            JobStatus::Rejected => ProcessingStatus::Queued, // Requeue on rejection
+        }
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "SUCCESS" => Ok(JobStatus::Success),
+            "PARTIAL_SUCCESS" => Ok(JobStatus::PartialSuccess),
+            "COMPLETED_WITH_WARNINGS" => Ok(JobStatus::CompletedWithWarnings),
+            "FAILED" => Ok(JobStatus::Failed),
+            "REJECTED" => Ok(JobStatus::Rejected),
+            "ABORTED" => Ok(JobStatus::Aborted),
+            _ => Err(format!("Invalid job status: '{}'", s)),
+        }
+    }
+}
+
+// ============================================================================
+// Diagnostics (Worker -> Sentinel)
+// ============================================================================
+
+/// Structured diagnostics included with failures (optional).
+///
+/// Constraint violations are also reported on successful jobs whose
+/// violating rows were quarantined.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct JobDiagnostics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_mismatch: Option<SchemaMismatch>,
+    /// Per-output, per-column counts of rows that failed a column constraint.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraint_violations: Vec<ConstraintViolations>,
+    /// Failure class used for retry decisions (see [`crate::retry`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_class: Option<String>,
+    /// What kind of failure this was, for grouping failures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_category: Option<ErrorCategory>,
+    /// Stage the job was in when it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_stage: Option<JobStage>,
+    /// Wall time of each stage that ran, in execution order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stage_timings: Vec<StageTiming>,
+    /// Resource limit the plugin process was terminated for exceeding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_exceeded: Option<LimitKind>,
+    /// Sentinel cross-check of the reported artifacts against the sinks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<ReceiptVerification>,
+    /// Per-output column statistics, for outputs that collect them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub column_stats: Vec<OutputColumnStats>,
+    /// Peak bytes of Arrow batches the worker held for the job, for
+    /// capacity planning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_memory_bytes: Option<u64>,
+    /// High-water mark of the plugin's scratch directory, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scratch_peak_bytes: Option<u64>,
+    /// Input changes the locked contracts accepted without failing the job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_drift: Option<SchemaDrift>,
+}
+
+/// Drift between a job's input and the contracts of its outputs.
+///
+/// A contract fails the job when an output loses or renames a column; drift
+/// is what gets through: source columns no output carries (dropped on the
+/// way to the sinks) and contract columns whose values arrived as another
+/// type the contract still accepts (text cast to decimal, integers widened
+/// to floats).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SchemaDrift {
+    /// Source columns that no output emits.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unmapped_columns: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub type_drifts: Vec<TypeDrift>,
+}
+
+impl SchemaDrift {
+    pub fn is_empty(&self) -> bool {
+        self.unmapped_columns.is_empty() && self.type_drifts.is_empty()
+    }
+}
+
+/// Contract column whose values arrived as a different (accepted) type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TypeDrift {
+    pub output_name: String,
+    pub column: String,
+    pub expected: DataType,
+    pub actual: ObservedDataType,
+}
+
+/// Result of checking a receipt's artifacts against what the sinks hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VerificationStatus {
+    /// Every checkable artifact exists with the reported row count
+    Verified,
+    /// At least one artifact is missing or its row count differs
+    Suspect,
+    /// No artifact could be checked (remote sinks, locked databases)
+    Unverifiable,
+}
+
+impl VerificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerificationStatus::Verified => "VERIFIED",
+            VerificationStatus::Suspect => "SUSPECT",
+            VerificationStatus::Unverifiable => "UNVERIFIABLE",
+        }
+    }
+}
+
+impl fmt::Display for VerificationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Receipt verification outcome recorded in `JobDiagnostics::verification`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptVerification {
+    pub status: VerificationStatus,
+    /// Artifacts that were checked against their sink
+    pub checked: usize,
+    /// Artifacts whose sink cannot be checked from the Sentinel
+    pub skipped: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub discrepancies: Vec<ArtifactDiscrepancy>,
+    /// Unix millis
+    pub verified_at: i64,
+}
+
+/// One artifact whose sink state does not match the receipt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactDiscrepancy {
+    pub output_name: String,
+    pub sink_uri: String,
+    /// Rows reported in the receipt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reported_rows: Option<u64>,
+    /// Rows found in the sink (None when the artifact is missing)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_rows: Option<u64>,
+    pub reason: String,
+}
+
+/// Machine-readable failure taxonomy reported by workers.
+///
+/// Categories are finer than retry error classes: a retry policy may list
+/// either (e.g. retry `timeout` but not other transient failures).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Input file missing, unreadable, or a filesystem error
+    IoError,
+    /// Output did not match the declared schema
+    SchemaViolation,
+    /// Parser code raised or exited with an error
+    PluginException,
+    /// Parser or sink took too long
+    Timeout,
+    /// Sink target already exists or is locked by another writer
+    SinkConflict,
+    /// Sink write failed for another reason
+    SinkError,
+    /// Plugin blocked by the trust policy
+    TrustPolicy,
+    /// Invalid job configuration (entrypoint, sink, quarantine settings)
+    InvalidConfig,
+    /// Too many rows quarantined under the quarantine policy
+    QuarantineRejected,
+    /// Plugin process exceeded its CPU time or memory limit
+    ResourceLimit,
+    /// Unclassified worker failure
+    Internal,
+}
+
+impl ErrorCategory {
+    pub const ALL: &'static [ErrorCategory] = &[
+        ErrorCategory::IoError,
+        ErrorCategory::SchemaViolation,
+        ErrorCategory::PluginException,
+        ErrorCategory::Timeout,
+        ErrorCategory::SinkConflict,
+        ErrorCategory::SinkError,
+        ErrorCategory::TrustPolicy,
+        ErrorCategory::InvalidConfig,
+        ErrorCategory::QuarantineRejected,
+        ErrorCategory::ResourceLimit,
+        ErrorCategory::Internal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::IoError => "io_error",
+            ErrorCategory::SchemaViolation => "schema_violation",
+            ErrorCategory::PluginException => "plugin_exception",
+            ErrorCategory::Timeout => "timeout",
+            ErrorCategory::SinkConflict => "sink_conflict",
+            ErrorCategory::SinkError => "sink_error",
+            ErrorCategory::TrustPolicy => "trust_policy",
+            ErrorCategory::InvalidConfig => "invalid_config",
+            ErrorCategory::QuarantineRejected => "quarantine_rejected",
+            ErrorCategory::ResourceLimit => "resource_limit",
+            ErrorCategory::Internal => "internal",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ErrorCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        ErrorCategory::ALL
+            .iter()
+            .copied()
+            .find(|category| category.as_str() == lower)
+            .ok_or_else(|| format!("Invalid error category: '{}'", s))
+    }
+}
+
+/// Stage of job execution on the worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStage {
+    /// Trust checks, entrypoint resolution, runtime setup
+    Setup,
+    /// Running the parser over the input file
+    Execute,
+    /// Schema, constraint, and quarantine checks on the output
+    Validate,
+    /// Writing outputs to sinks
+    Write,
+}
+
+impl JobStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStage::Setup => "setup",
+            JobStage::Execute => "execute",
+            JobStage::Validate => "validate",
+            JobStage::Write => "write",
+        }
+    }
+}
+
+impl fmt::Display for JobStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Wall time spent in one stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: JobStage,
+    pub duration_ms: u64,
+}
+
+/// Constraint violations for one output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConstraintViolations {
+    pub output_name: String,
+    pub violations: Vec<crate::http_types::ViolationSummary>,
+}
+
+/// Column statistics for one output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutputColumnStats {
+    pub output_name: String,
+    pub columns: Vec<ColumnStats>,
+}
+
+/// Statistics for one output column, gathered while its batches were written.
+///
+/// `min`/`max` are rendered as text and omitted for types without a total
+/// order; `distinct_estimate` is a HyperLogLog estimate (~1.6% error).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ColumnStats {
+    pub column_name: String,
+    pub row_count: u64,
+    pub null_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distinct_estimate: Option<u64>,
+}
+
+impl ColumnStats {
+    /// Share of null values, as a percentage of rows.
+    pub fn null_pct(&self) -> f64 {
+        if self.row_count == 0 {
+            0.0
+        } else {
+            (self.null_count as f64 / self.row_count as f64) * 100.0
+        }
+    }
+}
+
+/// Mismatch between expected schema and observed output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaMismatch {
+    pub output_name: String,
+    pub expected_columns: Vec<SchemaColumnSpec>,
+    pub actual_columns: Vec<ObservedColumn>,
+    pub missing_columns: Vec<String>,
+    pub extra_columns: Vec<String>,
+    pub order_mismatches: Vec<ColumnOrderMismatch>,
+    pub type_mismatches: Vec<TypeMismatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaColumnSpec {
+    pub name: String,
+    pub data_type: DataType,
+    pub nullable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Row-level rules checked after type validation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<ColumnConstraint>,
+}
+
+/// Row-level rule on a column value, beyond its type and nullability.
+///
+/// Rows that break a constraint are quarantined like any other row error.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ColumnConstraint {
+    /// Value must not be null (even if the column type is nullable).
+    NotNull,
+    /// Value must not repeat within a job's output.
+    Unique,
+    /// Numeric value must lie within `[min, max]` (either bound optional).
+    Range {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+    /// Text value must match the regular expression (unanchored).
+    Regex { pattern: String },
+    /// Text value must be one of `values`.
+    Enum { values: Vec<String> },
+}
+
+impl ColumnConstraint {
+    pub const KINDS: &'static [&'static str] = &["not_null", "unique", "range", "regex", "enum"];
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ColumnConstraint::NotNull => "not_null",
+            ColumnConstraint::Unique => "unique",
+            ColumnConstraint::Range { .. } => "range",
+            ColumnConstraint::Regex { .. } => "regex",
+            ColumnConstraint::Enum { .. } => "enum",
+        }
+    }
+}
+
+impl fmt::Display for ColumnConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnConstraint::Range { min, max } => {
+                let bound = |value: &Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+                write!(f, "range[{}..{}]", bound(min), bound(max))
+            }
+            ColumnConstraint::Regex { pattern } => write!(f, "regex({})", pattern),
+            ColumnConstraint::Enum { values } => write!(f, "enum({})", values.join("|")),
+            other => write!(f, "{}", other.kind()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObservedColumn {
     pub name: String,
     pub data_type: ObservedDataType,
+    /// How the type was inferred from text values, when it was
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inference: Option<ColumnInference>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ObservedDataType {
     Canonical { data_type: DataType },
     Arrow { name: String },
 }
 
+/// Type inferred for a column from its values, for schema review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnInference {
+    pub data_type: DataType,
+    /// Confidence in `data_type` (0.0 - 1.0)
+    pub confidence: f64,
+    /// Every type at least one value parsed as, best fit first
+    #[serde(default)]
+    pub candidates: Vec<InferredTypeCandidate>,
+}
+
+/// How well one type fits a column's values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferredTypeCandidate {
+    pub data_type: DataType,
+    /// Non-null values that parse as this type
+    pub matched: u64,
+    /// Share of non-null values that parse as this type (0.0 - 1.0)
+    pub confidence: f64,
+    /// Values that parse as this type
+    #[serde(default)]
+    pub samples: Vec<String>,
+    /// Values that do not
+    #[serde(default)]
+    pub counterexamples: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnOrderMismatch {
     pub index: usize,
@@ -1283,6 +2372,17 @@ pub enum ArtifactKind {
     Other,
 }
 
+impl ArtifactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtifactKind::Output => "OUTPUT",
+            ArtifactKind::Quarantine => "QUARANTINE",
+            ArtifactKind::Log => "LOG",
+            ArtifactKind::Other => "OTHER",
+        }
+    }
+}
+
 /// Typed artifact record (v1).
 ///
 /// Structured to keep invalid states out of core logic.
@@ -1298,6 +2398,9 @@ pub enum ArtifactV1 {
         rows: Option<u64>,
         #[serde(skip_serializing_if = "Option::is_none")]
         schema_hash: Option<String>,
+        /// Hex blake3 hash of the written file, for single-file local sinks
+        #[serde(skip_serializing_if = "Option::is_none")]
+        content_hash: Option<String>,
     },
     Quarantine {
         output_name: String,
@@ -1318,23 +2421,378 @@ pub enum ArtifactV1 {
     },
 }
 
-/// Payload for OpCode.CONCLUDE.
-/// Worker -> Sentinel: "Job finished. Here are the results."
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JobReceipt {
-    pub status: JobStatus,
-    pub metrics: HashMap<String, i64>, // e.g., {"rows": 1500, "size_bytes": 42000}
-    pub artifacts: Vec<ArtifactV1>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error_message: Option<String>, // Populated if status is failure
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub diagnostics: Option<JobDiagnostics>,
-    /// Blake3 hash of the input file content. Used for tape replay determinism
-    /// and correlating outputs with specific input versions.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub source_hash: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub lease_token: Option<String>,
+/// Payload for OpCode.CONCLUDE.
+/// Worker -> Sentinel: "Job finished. Here are the results."
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobReceipt {
+    pub status: JobStatus,
+    pub metrics: HashMap<String, i64>, // e.g., {"rows": 1500, "size_bytes": 42000}
+    pub artifacts: Vec<ArtifactV1>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>, // Populated if status is failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<JobDiagnostics>,
+    /// Blake3 hash of the input file content. Used for tape replay determinism
+    /// and correlating outputs with specific input versions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_hash: Option<String>,
+    /// Path and generation of the input `source_hash` was computed from, so
+    /// the Sentinel can cache the hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<SourceFile>,
+    /// Blake3 hash of the decompressed content, set when the input was a
+    /// gzip/zstd/bzip2 file the worker decompressed for the parser.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decompressed_hash: Option<String>,
+    /// Encoding of a non-UTF-8 text input the worker transcoded to UTF-8
+    /// for the parser.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_encoding: Option<TextEncoding>,
+    /// Files the job's input was derived from: the job's own input first,
+    /// then the inputs of earlier pipeline stages, back to the raw source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<LineageChain>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lease_token: Option<String>,
+    /// Per-file receipts for a batched DISPATCH, one per `BatchFile` (the
+    /// header job's result is the top-level receipt itself).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub batch_results: Vec<BatchFileReceipt>,
+}
+
+/// Result of one follow-on file of a batched DISPATCH.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchFileReceipt {
+    pub job_id: JobId,
+    pub receipt: JobReceipt,
+}
+
+/// Structured result of a concluded job, stored as JSON on the queue row.
+///
+/// Built from the `JobReceipt` so readers (CLI, Deck) get artifact URIs and
+/// counters without parsing the free-text `result_summary`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JobResultSummary {
+    #[serde(default)]
+    pub artifacts: Vec<ResultArtifact>,
+    /// Receipt metrics (see [`crate::metrics`] for the keys)
+    #[serde(default)]
+    pub counters: BTreeMap<String, i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// One artifact in a [`JobResultSummary`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultArtifact {
+    pub kind: ArtifactKind,
+    /// Output name (log/other artifacts: the artifact name)
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+}
+
+impl JobResultSummary {
+    pub fn from_receipt(receipt: &JobReceipt) -> Self {
+        let artifacts = receipt
+            .artifacts
+            .iter()
+            .map(ResultArtifact::from_artifact)
+            .collect();
+        let counters = receipt
+            .metrics
+            .iter()
+            .map(|(key, value)| (key.clone(), *value))
+            .collect();
+
+        let mut warnings = Vec::new();
+        if let Some(rows) = receipt
+            .metrics
+            .get(crate::metrics::QUARANTINE_ROWS)
+            .filter(|rows| **rows > 0)
+        {
+            warnings.push(format!("{} row(s) quarantined", rows));
+        }
+        if let Some(diagnostics) = receipt.diagnostics.as_ref() {
+            if let Some(mismatch) = diagnostics.schema_mismatch.as_ref() {
+                warnings.push(format!(
+                    "Output '{}' does not match its schema contract",
+                    mismatch.output_name
+                ));
+            }
+            for output in &diagnostics.constraint_violations {
+                let count: u64 = output.violations.iter().map(|v| v.count).sum();
+                warnings.push(format!(
+                    "Output '{}': {} constraint violation(s)",
+                    output.output_name, count
+                ));
+            }
+            if let Some(verification) = diagnostics.verification.as_ref() {
+                for discrepancy in &verification.discrepancies {
+                    warnings.push(format!(
+                        "Output '{}' at {}: {}",
+                        discrepancy.output_name, discrepancy.sink_uri, discrepancy.reason
+                    ));
+                }
+            }
+        }
+
+        Self {
+            artifacts,
+            counters,
+            warnings,
+        }
+    }
+
+    /// Summary for a job concluded before the structured column existed.
+    ///
+    /// Old summaries were free text; one that names a file or URI is taken
+    /// as the job's output.
+    pub fn from_legacy(text: &str) -> Self {
+        let text = text.trim();
+        let looks_like_output = !text.contains(char::is_whitespace)
+            && (text.contains("://") || text.starts_with('/'))
+            && !text.ends_with('/');
+        let artifacts = if looks_like_output {
+            let name = text
+                .rsplit('/')
+                .next()
+                .and_then(|file| file.split('.').next())
+                .unwrap_or(text)
+                .to_string();
+            vec![ResultArtifact {
+                kind: ArtifactKind::Output,
+                name,
+                uri: Some(text.to_string()),
+                rows: None,
+            }]
+        } else {
+            Vec::new()
+        };
+        Self {
+            artifacts,
+            ..Self::default()
+        }
+    }
+
+    /// URIs of the job's output artifacts.
+    pub fn output_uris(&self) -> impl Iterator<Item = &str> {
+        self.artifacts
+            .iter()
+            .filter(|artifact| artifact.kind == ArtifactKind::Output)
+            .filter_map(|artifact| artifact.uri.as_deref())
+    }
+}
+
+impl ResultArtifact {
+    fn from_artifact(artifact: &ArtifactV1) -> Self {
+        match artifact {
+            ArtifactV1::Output {
+                output_name,
+                sink_uri,
+                rows,
+                ..
+            } => Self {
+                kind: ArtifactKind::Output,
+                name: output_name.clone(),
+                uri: Some(sink_uri.clone()),
+                rows: *rows,
+            },
+            ArtifactV1::Quarantine {
+                output_name,
+                sink_uri,
+                rows,
+                ..
+            } => Self {
+                kind: ArtifactKind::Quarantine,
+                name: output_name.clone(),
+                uri: Some(sink_uri.clone()),
+                rows: *rows,
+            },
+            ArtifactV1::Log { name, uri } => Self {
+                kind: ArtifactKind::Log,
+                name: name.clone(),
+                uri: Some(uri.clone()),
+                rows: None,
+            },
+            ArtifactV1::Other { name, uri } => Self {
+                kind: ArtifactKind::Other,
+                name: name.clone(),
+                uri: uri.clone(),
+                rows: None,
+            },
+        }
+    }
+}
+
+/// All jobs of one pipeline run as a DAG.
+///
+/// The run's root jobs are the ones enqueued for it; every downstream job a
+/// topic subscription chains from them joins the same run, linked to the job
+/// whose output it reads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineRun {
+    pub run_id: String,
+    /// Aggregate status over all jobs of the run
+    pub status: PipelineRunStatus,
+    /// First dispatch of any job (unix millis)
+    pub started_at: Option<i64>,
+    /// Last job end, once the run is terminal (unix millis)
+    pub completed_at: Option<i64>,
+    /// Job nodes, by id (upstream jobs before their downstream jobs)
+    pub jobs: Vec<PipelineRunJob>,
+    /// Jobs grouped by depth and plugin, shallowest first
+    pub stages: Vec<PipelineRunStage>,
+}
+
+/// One job node of a [`PipelineRun`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineRunJob {
+    pub job_id: i64,
+    pub plugin_name: String,
+    pub file_id: i64,
+    /// Job whose output this job reads (`None` for a root job)
+    pub upstream_job_id: Option<i64>,
+    /// Distance from the root job (root jobs are 0)
+    pub depth: u32,
+    pub status: ProcessingStatus,
+    pub queued_at: i64,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    pub error_message: Option<String>,
+}
+
+/// The jobs of one plugin at one depth of a [`PipelineRun`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineRunStage {
+    pub depth: u32,
+    pub plugin_name: String,
+    pub jobs: u64,
+    /// Queued, dispatching or running
+    pub active: u64,
+    /// Completed or skipped
+    pub completed: u64,
+    /// Failed or aborted
+    pub failed: u64,
+    /// First dispatch of a job of the stage
+    pub started_at: Option<i64>,
+    /// Last job end, once every job of the stage is terminal
+    pub finished_at: Option<i64>,
+    pub duration_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<PipelineRunFailure>,
+}
+
+/// A failed or aborted job of a [`PipelineRunStage`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineRunFailure {
+    pub job_id: i64,
+    pub file_id: i64,
+    pub error_message: Option<String>,
+}
+
+impl PipelineRun {
+    /// Build a run from its jobs: depths from the upstream links, stages,
+    /// and the aggregate status (same rules as the stored run status: any
+    /// failure fails the run, then any unfinished job keeps it running).
+    pub fn from_jobs(run_id: impl Into<String>, mut jobs: Vec<PipelineRunJob>) -> Self {
+        jobs.sort_by_key(|job| job.job_id);
+        let mut depths: HashMap<i64, u32> = HashMap::with_capacity(jobs.len());
+        for job in &mut jobs {
+            job.depth = job
+                .upstream_job_id
+                .and_then(|upstream| depths.get(&upstream))
+                .map_or(0, |depth| depth + 1);
+            depths.insert(job.job_id, job.depth);
+        }
+
+        let mut stages: Vec<PipelineRunStage> = Vec::new();
+        for job in &jobs {
+            let index = match stages
+                .iter()
+                .position(|stage| stage.depth == job.depth && stage.plugin_name == job.plugin_name)
+            {
+                Some(index) => index,
+                None => {
+                    stages.push(PipelineRunStage {
+                        depth: job.depth,
+                        plugin_name: job.plugin_name.clone(),
+                        jobs: 0,
+                        active: 0,
+                        completed: 0,
+                        failed: 0,
+                        started_at: None,
+                        finished_at: None,
+                        duration_ms: None,
+                        failures: Vec::new(),
+                    });
+                    stages.len() - 1
+                }
+            };
+            let stage = &mut stages[index];
+            stage.jobs += 1;
+            match job.status {
+                ProcessingStatus::Completed | ProcessingStatus::Skipped => stage.completed += 1,
+                ProcessingStatus::Failed | ProcessingStatus::Aborted => {
+                    stage.failed += 1;
+                    stage.failures.push(PipelineRunFailure {
+                        job_id: job.job_id,
+                        file_id: job.file_id,
+                        error_message: job.error_message.clone(),
+                    });
+                }
+                _ => stage.active += 1,
+            }
+            stage.started_at = min_some(stage.started_at, job.started_at);
+            stage.finished_at = stage.finished_at.max(job.finished_at);
+        }
+        for stage in &mut stages {
+            if stage.active > 0 {
+                stage.finished_at = None;
+            }
+            stage.duration_ms = match (stage.started_at, stage.finished_at) {
+                (Some(start), Some(end)) => Some(end.saturating_sub(start)),
+                _ => None,
+            };
+        }
+        stages.sort_by(|a, b| (a.depth, &a.plugin_name).cmp(&(b.depth, &b.plugin_name)));
+
+        let failed = stages.iter().any(|stage| stage.failed > 0);
+        let active = stages.iter().any(|stage| stage.active > 0);
+        let status = if failed {
+            PipelineRunStatus::Failed
+        } else if active {
+            PipelineRunStatus::Running
+        } else if jobs.is_empty() {
+            PipelineRunStatus::NoOp
+        } else {
+            PipelineRunStatus::Completed
+        };
+        let started_at = jobs.iter().filter_map(|job| job.started_at).min();
+        let completed_at = if status.is_terminal() {
+            jobs.iter().filter_map(|job| job.finished_at).max()
+        } else {
+            None
+        };
+
+        Self {
+            run_id: run_id.into(),
+            status,
+            started_at,
+            completed_at,
+            jobs,
+            stages,
+        }
+    }
+}
+
+fn min_some(a: Option<i64>, b: Option<i64>) -> Option<i64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
 }
 
 // ============================================================================
@@ -1350,13 +2808,42 @@ pub struct DispatchAckPayload {
     pub worker_id: Option<String>,
 }
 
+/// Payload for OpCode.LOG_CHUNK.
+/// Worker -> Sentinel: "New log output for job X." Sent while the job runs so
+/// the log can be tailed before CONCLUDE.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogChunkPayload {
+    /// Byte offset of `data` in the job's log file
+    pub offset: u64,
+    pub data: String,
+}
+
 /// Payload for OpCode.IDENTIFY.
-/// Worker -> Sentinel: Handshake with capabilities (informational only in v1).
+/// Worker -> Sentinel: Handshake with capabilities.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdentifyPayload {
-    pub capabilities: Vec<String>, // Informational; Sentinel assumes homogeneous worker pool
+    /// Runtime/feature tags this worker supports ("*" = any); the Sentinel
+    /// only dispatches jobs whose plugin requirements are all advertised.
+    /// See [`crate::capabilities`].
+    pub capabilities: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub worker_id: Option<String>, // Optional stable worker ID
+    /// Protocol versions the worker can speak (absent = v4 only).
+    #[serde(default)]
+    pub protocol_versions: ProtocolVersionRange,
+    /// Optional protocol features the worker implements (absent = none).
+    #[serde(default)]
+    pub protocol_features: ProtocolFeatures,
+}
+
+/// Payload for OpCode.ACK in reply to IDENTIFY.
+/// Sentinel -> Worker: "Registered; speak this version from now on."
+/// Workers send IDENTIFY at `MIN_PROTOCOL_VERSION` and switch to
+/// `protocol_version` once this arrives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentifyAckPayload {
+    pub protocol_version: u8,
+    pub protocol_features: ProtocolFeatures,
 }
 
 // ============================================================================
@@ -1392,6 +2879,40 @@ pub struct HeartbeatPayload {
     /// All active job IDs (for monitoring/debugging)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub active_job_ids: Vec<JobId>,
+    /// Resource usage snapshot used by the Sentinel scheduler for backpressure.
+    /// Absent from workers that do not report load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load: Option<WorkerLoad>,
+}
+
+/// Worker load reported in HEARTBEAT.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorkerLoad {
+    /// Jobs currently held by the worker (running or waiting to run).
+    pub queue_depth: usize,
+    /// Maximum jobs the worker will hold at once.
+    pub capacity: usize,
+    /// Host CPU utilisation, 0-100 (may exceed 100 on oversubscribed hosts).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_percent: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_used_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_total_bytes: Option<u64>,
+}
+
+impl WorkerLoad {
+    /// True when the worker cannot accept another job.
+    pub fn is_full(&self) -> bool {
+        self.queue_depth >= self.capacity
+    }
+
+    /// Host memory utilisation, 0-100, when both figures are known.
+    pub fn memory_percent(&self) -> Option<f32> {
+        let used = self.memory_used_bytes?;
+        let total = self.memory_total_bytes.filter(|total| *total > 0)?;
+        Some((used as f64 / total as f64 * 100.0) as f32)
+    }
 }
 
 fn is_zero(n: &usize) -> bool {
@@ -1409,6 +2930,31 @@ pub struct ErrorPayload {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub traceback: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<ErrorCategory>,
+}
+
+// ============================================================================
+// OpCode.RELOAD (Sentinel -> Worker)
+// ============================================================================
+
+/// Payload for OpCode.RELOAD.
+/// Sentinel -> Worker: "The active version of this plugin changed; drop
+/// anything cached for it."
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadPayload {
+    pub plugin_name: String,
+    /// Version that is now active.
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Environment of the active version (Python plugins); workers build it
+    /// ahead of the first job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_hash: Option<String>,
+    /// uv.lock content for `env_hash`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lockfile_content: Option<String>,
 }
 
 // ============================================================================
@@ -1435,8 +2981,21 @@ pub struct DeployCommand {
     pub publisher_email: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub azure_oid: Option<String>, // For enterprise mode
+    /// Capability tags a worker must advertise to run this plugin,
+    /// e.g. ["python3.11", "gpu"]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub system_requirements: Option<Vec<String>>, // e.g., ["glibc_2.31"]
+    pub system_requirements: Option<Vec<String>>,
+    /// Publisher key that signed `artifact_hash` (file stem in the keyring)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer_id: Option<String>,
+    /// Base64 Ed25519 signature over `artifact_hash`. Required unless the
+    /// Sentinel allows unsigned deploys (dev mode).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Tenant namespace that owns the plugin (default: `DEFAULT_NAMESPACE`).
+    /// A plugin name belongs to one namespace for its whole version history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
 }
 
 /// Response to a DEPLOY command.
@@ -1447,6 +3006,11 @@ pub struct DeployResponse {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub plugin_id: Option<i64>,
+    /// True if the artifact signature verified against the trusted keyring
+    #[serde(default)]
+    pub signature_verified: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer_id: Option<String>,
 }
 
 // ============================================================================
@@ -1492,6 +3056,40 @@ pub enum DetectionConfidence {
     Unknown,
 }
 
+impl DetectionConfidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DetectionConfidence::High => "HIGH",
+            DetectionConfidence::Medium => "MEDIUM",
+            DetectionConfidence::Low => "LOW",
+            DetectionConfidence::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl fmt::Display for DetectionConfidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for DetectionConfidence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "HIGH" => Ok(DetectionConfidence::High),
+            "MEDIUM" => Ok(DetectionConfidence::Medium),
+            "LOW" => Ok(DetectionConfidence::Low),
+            "UNKNOWN" => Ok(DetectionConfidence::Unknown),
+            _ => Err(format!(
+                "Invalid detection confidence: '{}'. Expected: high, medium, low, or unknown",
+                s
+            )),
+        }
+    }
+}
+
 /// Result of analyzing file head
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AnalysisResult {
@@ -1596,12 +3194,22 @@ pub struct LineageBlock {
 }
 
 /// Hop in a lineage chain (for multi-hop tracing)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LineageHop {
     pub file_path: PathBuf,
     pub file_type: LineageFileType,
     pub offset: u64,
     pub row_number: u64,
+    /// Job that read `file_path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<JobId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parser_version: Option<String>,
+    /// Blake3 hash of the file content the job read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
 }
 
 /// Type of file in lineage chain
@@ -1612,14 +3220,33 @@ pub enum LineageFileType {
     Shard,
     Freezer,
     ExtractedShard,
+    /// Output of an earlier pipeline stage
+    Artifact,
 }
 
 /// Full lineage chain from output back to source
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LineageChain {
     pub hops: Vec<LineageHop>,
 }
 
+impl LineageChain {
+    /// Chain of a job that read `hop`: that hop, then the chain of the job
+    /// that wrote its file (None for a raw source).
+    pub fn with_upstream(hop: LineageHop, upstream: Option<&LineageChain>) -> Self {
+        let mut hops = vec![hop];
+        if let Some(upstream) = upstream {
+            hops.extend(upstream.hops.iter().cloned());
+        }
+        Self { hops }
+    }
+
+    /// Raw source file the chain starts from.
+    pub fn source(&self) -> Option<&LineageHop> {
+        self.hops.last()
+    }
+}
+
 /// LLM provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(tag = "provider", rename_all = "snake_case")]
@@ -1659,6 +3286,72 @@ fn default_temperature() -> f32 {
     0.0
 }
 
+/// Settings for LLM-assisted features (schema inference).
+///
+/// Providers are tried in order: one that is rate limited or fails falls
+/// through to the next. No providers means the features run without an LLM.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmSettings {
+    #[serde(default)]
+    pub providers: Vec<LlmConfig>,
+    /// Requests per minute to each provider (0 = unlimited)
+    #[serde(default = "default_llm_requests_per_minute")]
+    pub requests_per_minute: u32,
+    /// Prompt + completion tokens one intent session may spend (0 = unlimited)
+    #[serde(default)]
+    pub session_token_budget: u64,
+    #[serde(default = "default_llm_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Tape recording every request and response; unset = not recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tape_path: Option<PathBuf>,
+    /// Record prompts and responses in plaintext; by default they are hashed
+    #[serde(default)]
+    pub capture_plaintext: bool,
+    /// Record/replay of responses keyed by prompt hash
+    #[serde(default)]
+    pub cache: LlmCacheMode,
+    /// Directory of cached responses; required unless `cache` is off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// How LLM responses are cached in `LlmSettings.cache_dir`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmCacheMode {
+    /// Always call the providers (default)
+    #[default]
+    Off,
+    /// Call the providers and save every response
+    Record,
+    /// Answer only from saved responses; a miss is an error and no provider
+    /// is ever called
+    Replay,
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            providers: Vec::new(),
+            requests_per_minute: default_llm_requests_per_minute(),
+            session_token_budget: 0,
+            timeout_secs: default_llm_timeout_secs(),
+            tape_path: None,
+            capture_plaintext: false,
+            cache: LlmCacheMode::default(),
+            cache_dir: None,
+        }
+    }
+}
+
+fn default_llm_requests_per_minute() -> u32 {
+    30
+}
+fn default_llm_timeout_secs() -> u64 {
+    60
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1669,6 +3362,7 @@ mod tests {
             topic: "output".to_string(),
             uri: "s3://bucket/key".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         };
@@ -1679,14 +3373,131 @@ mod tests {
         assert_eq!(sink, deserialized);
     }
 
+    #[test]
+    fn test_per_output_sink_selection() {
+        let sink = |topic: &str, uri: &str, mode| SinkConfig {
+            topic: topic.to_string(),
+            uri: uri.to_string(),
+            mode,
+            schema_evolution: SchemaEvolution::default(),
+            quarantine_config: None,
+            schema: None,
+        };
+        let sinks = vec![
+            sink("events", "duckdb:///tmp/events.duckdb", SinkMode::Append),
+            sink("annotations", "parquet:///tmp/out", SinkMode::Replace),
+        ];
+
+        let events = select_sink_for_output(&sinks, "events").unwrap();
+        assert_eq!(events.uri, "duckdb:///tmp/events.duckdb");
+        let annotations = select_sink_for_output(&sinks, "annotations").unwrap();
+        assert_eq!(annotations.mode, SinkMode::Replace);
+        assert_eq!(
+            untargeted_outputs(&sinks, &["events", "annotations", "metrics"]),
+            vec!["metrics"]
+        );
+
+        let mut with_default = sinks.clone();
+        with_default.push(sink("*", "parquet:///tmp/default", SinkMode::Append));
+        assert!(untargeted_outputs(&with_default, &["metrics"]).is_empty());
+        assert!(untargeted_outputs(&[], &["metrics"]).is_empty());
+    }
+
+    #[test]
+    fn test_job_priority_lanes() {
+        for priority in JobPriority::ALL {
+            assert_eq!(JobPriority::from_value(priority.value()), *priority);
+            assert_eq!(priority.as_str().parse::<JobPriority>().unwrap(), *priority);
+        }
+        assert_eq!(JobPriority::from_value(0), JobPriority::default());
+        assert_eq!(JobPriority::from_value(100), JobPriority::Urgent);
+        assert_eq!(JobPriority::from_value(5), JobPriority::Normal);
+        assert_eq!(JobPriority::from_value(-1), JobPriority::Low);
+        assert!(JobPriority::Urgent > JobPriority::Low);
+    }
+
+    #[test]
+    fn test_byte_range_len_and_serde() {
+        let range = ByteRange { start: 10, end: 25 };
+        assert_eq!(range.len(), 15);
+        assert!(ByteRange { start: 25, end: 10 }.is_empty());
+
+        let json = serde_json::json!({"start": 10, "end": 25});
+        assert_eq!(serde_json::from_value::<ByteRange>(json).unwrap(), range);
+    }
+
+    #[test]
+    fn test_csv_dialect_defaults_and_validation() {
+        let json = serde_json::json!({"delimiter": ";", "encoding": "windows-1252"});
+        let dialect: CsvDialect = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            dialect,
+            CsvDialect {
+                delimiter: ';',
+                encoding: TextEncoding::Windows1252,
+                ..CsvDialect::default()
+            }
+        );
+        assert_eq!(dialect.null_tokens, vec![String::new()]);
+        assert!(dialect.validate().is_ok());
+
+        assert_eq!("Latin1".parse::<TextEncoding>(), Ok(TextEncoding::Latin1));
+        assert!("ebcdic".parse::<TextEncoding>().is_err());
+        for encoding in TextEncoding::ALL {
+            assert_eq!(encoding.as_str().parse::<TextEncoding>(), Ok(*encoding));
+        }
+
+        let same = CsvDialect {
+            quote: ';',
+            ..dialect.clone()
+        };
+        assert!(same.validate().is_err());
+        let newline = CsvDialect {
+            delimiter: '\n',
+            ..dialect
+        };
+        assert!(newline.validate().is_err());
+    }
+
     #[test]
     fn test_sink_mode_from_str() {
         assert_eq!("append".parse::<SinkMode>().unwrap(), SinkMode::Append);
         assert_eq!("REPLACE".parse::<SinkMode>().unwrap(), SinkMode::Replace);
         assert_eq!("Error".parse::<SinkMode>().unwrap(), SinkMode::Error);
+        assert_eq!("upsert".parse::<SinkMode>().unwrap(), SinkMode::Upsert);
         assert!("invalid".parse::<SinkMode>().is_err());
     }
 
+    #[test]
+    fn test_schema_evolution_parsing() {
+        assert_eq!(
+            "add_missing_columns".parse::<SchemaEvolution>().unwrap(),
+            SchemaEvolution::AddMissingColumns
+        );
+        assert_eq!(
+            "STRICT".parse::<SchemaEvolution>().unwrap(),
+            SchemaEvolution::Strict
+        );
+        assert!("loose".parse::<SchemaEvolution>().is_err());
+
+        assert_eq!(
+            SchemaEvolution::from_sink_uri("duckdb:///tmp/out.duckdb").unwrap(),
+            SchemaEvolution::FailOnExtra
+        );
+        assert_eq!(
+            SchemaEvolution::from_sink_uri(
+                "duckdb:///tmp/out.duckdb?table=t&schema_evolution=add_missing_columns"
+            )
+            .unwrap(),
+            SchemaEvolution::AddMissingColumns
+        );
+        assert!(SchemaEvolution::from_sink_uri("duckdb:///tmp/out.duckdb?schema_evolution=x").is_err());
+
+        let json = r#"{"topic":"t","uri":"duckdb:///tmp/out.duckdb"}"#;
+        let sink: SinkConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(sink.schema_evolution, SchemaEvolution::FailOnExtra);
+    }
+
     #[test]
     fn test_worker_status_from_str() {
         assert_eq!(
@@ -1710,12 +3521,39 @@ mod tests {
         let payload = IdentifyPayload {
             capabilities: vec!["plugin_a".to_string(), "plugin_b".to_string()],
             worker_id: Some("worker-001".to_string()),
+            protocol_versions: ProtocolVersionRange::SUPPORTED,
+            protocol_features: ProtocolFeatures::SUPPORTED,
         };
 
         let json = serde_json::to_string(&payload).unwrap();
         let deserialized: IdentifyPayload = serde_json::from_str(&json).unwrap();
         assert_eq!(payload.capabilities, deserialized.capabilities);
         assert_eq!(payload.worker_id, deserialized.worker_id);
+        assert_eq!(payload.protocol_versions, deserialized.protocol_versions);
+        assert_eq!(payload.protocol_features, deserialized.protocol_features);
+    }
+
+    #[test]
+    fn test_identify_payload_without_negotiation_fields() {
+        let json = r#"{"capabilities":["*"],"worker_id":"old-worker"}"#;
+        let payload: IdentifyPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            payload.protocol_versions,
+            ProtocolVersionRange::exact(crate::MIN_PROTOCOL_VERSION)
+        );
+        assert_eq!(payload.protocol_features, ProtocolFeatures::NONE);
+    }
+
+    #[test]
+    fn test_identify_ack_payload_serialization() {
+        let payload = IdentifyAckPayload {
+            protocol_version: crate::MIN_PROTOCOL_VERSION,
+            protocol_features: ProtocolFeatures::DISPATCH_ACK,
+        };
+
+        let json = serde_json::to_string(&payload).unwrap();
+        let deserialized: IdentifyAckPayload = serde_json::from_str(&json).unwrap();
+        assert_eq!(payload, deserialized);
     }
 
     #[test]
@@ -1724,6 +3562,13 @@ mod tests {
             status: HeartbeatStatus::Busy,
             active_job_count: 3,
             active_job_ids: vec![JobId::new(12345), JobId::new(12346), JobId::new(12347)],
+            load: Some(WorkerLoad {
+                queue_depth: 3,
+                capacity: 4,
+                cpu_percent: Some(42.5),
+                memory_used_bytes: Some(1024),
+                memory_total_bytes: Some(4096),
+            }),
         };
 
         let json = serde_json::to_string(&payload).expect("serialize heartbeat");
@@ -1732,6 +3577,24 @@ mod tests {
         assert_eq!(payload.status, deserialized.status);
         assert_eq!(payload.active_job_count, deserialized.active_job_count);
         assert_eq!(payload.active_job_ids, deserialized.active_job_ids);
+        assert_eq!(payload.load, deserialized.load);
+    }
+
+    #[test]
+    fn test_heartbeat_without_load_deserializes() {
+        let payload: HeartbeatPayload =
+            serde_json::from_str(r#"{"status":"IDLE"}"#).expect("deserialize heartbeat");
+        assert!(payload.load.is_none());
+
+        let load = WorkerLoad {
+            queue_depth: 1,
+            capacity: 1,
+            cpu_percent: None,
+            memory_used_bytes: Some(512),
+            memory_total_bytes: Some(2048),
+        };
+        assert!(load.is_full());
+        assert_eq!(load.memory_percent(), Some(25.0));
     }
 
     #[test]
@@ -1770,7 +3633,12 @@ mod tests {
             error_message: None,
             diagnostics: None,
             source_hash: Some("abc123def456".to_string()),
+            source_file: None,
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: None,
             lease_token: None,
+            batch_results: Vec::new(),
         };
 
         let json = serde_json::to_string(&receipt).unwrap();
@@ -1795,7 +3663,12 @@ mod tests {
             error_message: None,
             diagnostics: None,
             source_hash: Some("abcd1234".to_string()),
+            source_file: None,
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: None,
             lease_token: None,
+            batch_results: Vec::new(),
         };
         let json = serde_json::to_string(&receipt_with_hash).unwrap();
         assert!(json.contains("source_hash"));
@@ -1809,12 +3682,110 @@ mod tests {
             error_message: Some("error".to_string()),
             diagnostics: None,
             source_hash: None,
+            source_file: None,
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: None,
             lease_token: None,
+            batch_results: Vec::new(),
         };
         let json = serde_json::to_string(&receipt_no_hash).unwrap();
         assert!(!json.contains("source_hash"));
     }
 
+    #[test]
+    fn test_job_result_summary_from_receipt() {
+        let receipt = JobReceipt {
+            status: JobStatus::CompletedWithWarnings,
+            metrics: HashMap::from([
+                (crate::metrics::ROWS.to_string(), 10),
+                (crate::metrics::QUARANTINE_ROWS.to_string(), 2),
+            ]),
+            artifacts: vec![
+                ArtifactV1::Output {
+                    output_name: "orders".to_string(),
+                    sink_uri: "parquet:///out/orders.parquet".to_string(),
+                    table: None,
+                    rows: Some(10),
+                    schema_hash: None,
+                    content_hash: None,
+                },
+                ArtifactV1::Quarantine {
+                    output_name: "orders".to_string(),
+                    sink_uri: "parquet:///out/orders_quarantine.parquet".to_string(),
+                    table: None,
+                    rows: Some(2),
+                },
+            ],
+            error_message: None,
+            diagnostics: None,
+            source_hash: None,
+            source_file: None,
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: None,
+            lease_token: None,
+            batch_results: Vec::new(),
+        };
+
+        let summary = JobResultSummary::from_receipt(&receipt);
+        assert_eq!(
+            summary.output_uris().collect::<Vec<_>>(),
+            vec!["parquet:///out/orders.parquet"]
+        );
+        assert_eq!(summary.artifacts[1].kind, ArtifactKind::Quarantine);
+        assert_eq!(summary.counters.get(crate::metrics::ROWS), Some(&10));
+        assert_eq!(summary.warnings, vec!["2 row(s) quarantined".to_string()]);
+
+        let json = serde_json::to_string(&summary).unwrap();
+        let decoded: JobResultSummary = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, summary);
+    }
+
+    #[test]
+    fn test_job_result_summary_from_legacy_text() {
+        let summary = JobResultSummary::from_legacy("/data/out/orders.parquet");
+        assert_eq!(summary.artifacts.len(), 1);
+        assert_eq!(summary.artifacts[0].name, "orders");
+        assert_eq!(
+            summary.output_uris().collect::<Vec<_>>(),
+            vec!["/data/out/orders.parquet"]
+        );
+        assert!(JobResultSummary::from_legacy("Success").artifacts.is_empty());
+        assert!(JobResultSummary::from_legacy("Skipped: outputs already materialized")
+            .artifacts
+            .is_empty());
+    }
+
+    #[test]
+    fn test_job_receipt_batch_results_round_trip() {
+        let json = r#"{"status":"SUCCESS","metrics":{},"artifacts":[]}"#;
+        let receipt: JobReceipt = serde_json::from_str(json).unwrap();
+        assert!(receipt.batch_results.is_empty());
+        assert!(!serde_json::to_string(&receipt)
+            .unwrap()
+            .contains("batch_results"));
+
+        let mut batched = receipt.clone();
+        batched.batch_results.push(BatchFileReceipt {
+            job_id: JobId::new(7),
+            receipt: JobReceipt {
+                status: JobStatus::Failed,
+                lease_token: Some("lease-7".to_string()),
+                ..receipt
+            },
+        });
+        let json = serde_json::to_string(&batched).unwrap();
+        let decoded: JobReceipt = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.batch_results.len(), 1);
+        assert_eq!(decoded.batch_results[0].job_id, JobId::new(7));
+        assert_eq!(decoded.batch_results[0].receipt.status, JobStatus::Failed);
+        assert_eq!(
+            decoded.batch_results[0].receipt.lease_token.as_deref(),
+            Some("lease-7")
+        );
+    }
+
     #[test]
     fn test_job_status_serialization() {
         // Test that JobStatus serializes to SCREAMING_SNAKE_CASE
@@ -2262,6 +4233,71 @@ mod tests {
         assert!(PipelineRunStatus::Completed.is_terminal());
     }
 
+    fn run_job(
+        job_id: i64,
+        plugin_name: &str,
+        upstream_job_id: Option<i64>,
+        status: ProcessingStatus,
+        times: Option<(i64, i64)>,
+    ) -> PipelineRunJob {
+        PipelineRunJob {
+            job_id,
+            plugin_name: plugin_name.to_string(),
+            file_id: 1,
+            upstream_job_id,
+            depth: 0,
+            status,
+            queued_at: 0,
+            started_at: times.map(|(start, _)| start),
+            finished_at: times.map(|(_, end)| end),
+            error_message: None,
+        }
+    }
+
+    #[test]
+    fn test_pipeline_run_from_jobs() {
+        let run = PipelineRun::from_jobs(
+            "run-1",
+            vec![
+                run_job(3, "load", Some(2), ProcessingStatus::Running, None),
+                run_job(1, "parse", None, ProcessingStatus::Completed, Some((10, 20))),
+                run_job(2, "enrich", Some(1), ProcessingStatus::Completed, Some((25, 40))),
+            ],
+        );
+        assert_eq!(run.status, PipelineRunStatus::Running);
+        assert_eq!(run.started_at, Some(10));
+        assert_eq!(run.completed_at, None);
+        let depths: Vec<(i64, u32)> = run.jobs.iter().map(|job| (job.job_id, job.depth)).collect();
+        assert_eq!(depths, vec![(1, 0), (2, 1), (3, 2)]);
+        let stages: Vec<(&str, Option<i64>)> = run
+            .stages
+            .iter()
+            .map(|stage| (stage.plugin_name.as_str(), stage.duration_ms))
+            .collect();
+        assert_eq!(
+            stages,
+            vec![("parse", Some(10)), ("enrich", Some(15)), ("load", None)]
+        );
+
+        let mut failed = run_job(3, "load", Some(2), ProcessingStatus::Failed, Some((41, 50)));
+        failed.error_message = Some("boom".to_string());
+        let run = PipelineRun::from_jobs(
+            "run-1",
+            vec![
+                run_job(1, "parse", None, ProcessingStatus::Completed, Some((10, 20))),
+                run_job(2, "enrich", Some(1), ProcessingStatus::Completed, Some((25, 40))),
+                failed,
+            ],
+        );
+        assert_eq!(run.status, PipelineRunStatus::Failed);
+        assert_eq!(run.completed_at, Some(50));
+        assert_eq!(run.stages[2].failed, 1);
+        assert_eq!(
+            run.stages[2].failures[0].error_message.as_deref(),
+            Some("boom")
+        );
+    }
+
     // ======================================================================
     // JobStatus enhanced method tests
     // ======================================================================
@@ -2337,4 +4373,75 @@ mod tests {
         assert_eq!(format!("{}", JobStatus::Success), "SUCCESS");
         assert_eq!(format!("{}", JobStatus::PartialSuccess), "PARTIAL_SUCCESS");
     }
+
+    #[test]
+    fn test_error_category_round_trip() {
+        for category in ErrorCategory::ALL {
+            assert_eq!(category.as_str().parse::<ErrorCategory>(), Ok(*category));
+            let json = serde_json::to_string(category).unwrap();
+            assert_eq!(json, format!("\"{}\"", category.as_str()));
+        }
+        assert_eq!(
+            "TIMEOUT".parse::<ErrorCategory>(),
+            Ok(ErrorCategory::Timeout)
+        );
+        assert!("flaky".parse::<ErrorCategory>().is_err());
+    }
+
+    #[test]
+    fn test_job_diagnostics_taxonomy_serialization() {
+        let diagnostics = JobDiagnostics {
+            error_class: Some("transient".to_string()),
+            error_category: Some(ErrorCategory::SinkConflict),
+            failed_stage: Some(JobStage::Write),
+            stage_timings: vec![
+                StageTiming {
+                    stage: JobStage::Setup,
+                    duration_ms: 3,
+                },
+                StageTiming {
+                    stage: JobStage::Write,
+                    duration_ms: 40,
+                },
+            ],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(json["error_category"], "sink_conflict");
+        assert_eq!(json["failed_stage"], "write");
+        assert_eq!(json["stage_timings"][1]["duration_ms"], 40);
+
+        // Older workers send diagnostics without the taxonomy fields
+        let legacy: JobDiagnostics =
+            serde_json::from_str(r#"{"error_class":"permanent"}"#).unwrap();
+        assert!(legacy.error_category.is_none());
+        assert!(legacy.stage_timings.is_empty());
+        assert!(legacy.schema_drift.is_none());
+    }
+
+    #[test]
+    fn test_schema_drift_serialization() {
+        let drift = SchemaDrift {
+            unmapped_columns: vec!["discount_code".to_string()],
+            type_drifts: vec![TypeDrift {
+                output_name: "orders".to_string(),
+                column: "amount".to_string(),
+                expected: DataType::Float64,
+                actual: ObservedDataType::Canonical {
+                    data_type: DataType::Int64,
+                },
+            }],
+        };
+        let diagnostics = JobDiagnostics {
+            schema_drift: Some(drift.clone()),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(json["schema_drift"]["unmapped_columns"][0], "discount_code");
+        assert_eq!(json["schema_drift"]["type_drifts"][0]["column"], "amount");
+
+        let parsed: JobDiagnostics = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.schema_drift, Some(drift));
+        assert!(SchemaDrift::default().is_empty());
+    }
 }
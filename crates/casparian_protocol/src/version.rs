@@ -0,0 +1,313 @@
+//! Protocol version negotiation.
+//!
+//! Each side advertises the range of header versions it can speak plus a
+//! feature bitmask (Worker -> Sentinel in IDENTIFY). The Sentinel picks the
+//! highest common version and the intersection of features and sends them
+//! back in an ACK (`IdentifyAckPayload`). Both sides then translate every
+//! message they send to the negotiated version. The worker sends IDENTIFY
+//! itself at `MIN_PROTOCOL_VERSION`, which every supported Sentinel can read.
+//! This lets a Sentinel and Worker one version apart interoperate during
+//! rolling upgrades.
+
+use crate::error::{ProtocolError, Result};
+use crate::{Message, OpCode, PROTOCOL_VERSION};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Oldest header version this build can read and write.
+pub const MIN_PROTOCOL_VERSION: u8 = 0x04;
+
+/// Inclusive range of protocol versions a peer can speak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolVersionRange {
+    pub min: u8,
+    pub max: u8,
+}
+
+impl ProtocolVersionRange {
+    /// Versions supported by this build.
+    pub const SUPPORTED: Self = Self {
+        min: MIN_PROTOCOL_VERSION,
+        max: PROTOCOL_VERSION,
+    };
+
+    pub fn new(min: u8, max: u8) -> Result<Self> {
+        if min > max {
+            return Err(ProtocolError::InvalidVersionRange { min, max });
+        }
+        Ok(Self { min, max })
+    }
+
+    /// Range for a peer that speaks exactly one version.
+    pub fn exact(version: u8) -> Self {
+        Self {
+            min: version,
+            max: version,
+        }
+    }
+
+    pub fn contains(&self, version: u8) -> bool {
+        (self.min..=self.max).contains(&version)
+    }
+
+    /// Highest version both ranges contain, if any.
+    pub fn negotiate(&self, peer: &ProtocolVersionRange) -> Option<u8> {
+        let min = self.min.max(peer.min);
+        let max = self.max.min(peer.max);
+        (min <= max).then_some(max)
+    }
+}
+
+impl Default for ProtocolVersionRange {
+    /// Peers that omit a range in IDENTIFY predate negotiation and speak v4 only.
+    fn default() -> Self {
+        Self::exact(MIN_PROTOCOL_VERSION)
+    }
+}
+
+impl fmt::Display for ProtocolVersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}..=v{}", self.min, self.max)
+    }
+}
+
+/// Optional protocol features, advertised as a bitmask in IDENTIFY.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProtocolFeatures(u32);
+
+impl ProtocolFeatures {
+    pub const NONE: Self = Self(0);
+    /// Understands STREAM_BEGIN / STREAM_CHUNK / STREAM_END.
+    pub const STREAMING: Self = Self(1 << 0);
+    /// Sends DISPATCH_ACK after accepting a dispatch lease.
+    pub const DISPATCH_ACK: Self = Self(1 << 1);
+    /// Runs DISPATCH file manifests (`DispatchCommand::batch`) and reports
+    /// per-file results in `JobReceipt::batch_results`.
+    pub const BATCH_DISPATCH: Self = Self(1 << 2);
+    /// Honors `DispatchCommand::byte_range`, reading only that part of the file.
+    pub const BYTE_RANGE: Self = Self(1 << 3);
+
+    /// Every feature implemented by this build.
+    pub const SUPPORTED: Self = Self(
+        Self::STREAMING.0 | Self::DISPATCH_ACK.0 | Self::BATCH_DISPATCH.0 | Self::BYTE_RANGE.0,
+    );
+
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersection(&self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn union(&self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Version and features agreed with a specific peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedProtocol {
+    pub version: u8,
+    pub features: ProtocolFeatures,
+}
+
+impl NegotiatedProtocol {
+    /// Agree on the highest common version and shared features.
+    pub fn negotiate(
+        local: ProtocolVersionRange,
+        local_features: ProtocolFeatures,
+        peer: ProtocolVersionRange,
+        peer_features: ProtocolFeatures,
+    ) -> Result<Self> {
+        let version = local
+            .negotiate(&peer)
+            .ok_or(ProtocolError::NoCommonVersion { local, peer })?;
+        Ok(Self {
+            version,
+            features: local_features.intersection(peer_features),
+        })
+    }
+
+    pub fn supports(&self, feature: ProtocolFeatures) -> bool {
+        self.features.contains(feature)
+    }
+}
+
+impl Default for NegotiatedProtocol {
+    /// The protocol spoken before a peer has identified itself.
+    fn default() -> Self {
+        Self {
+            version: MIN_PROTOCOL_VERSION,
+            features: ProtocolFeatures::NONE,
+        }
+    }
+}
+
+/// Minimum protocol version that carries `opcode`.
+pub fn opcode_min_version(opcode: OpCode) -> u8 {
+    match opcode {
+        OpCode::Unknown
+        | OpCode::Identify
+        | OpCode::Dispatch
+        | OpCode::DispatchAck
+        | OpCode::Abort
+        | OpCode::Heartbeat
+        | OpCode::Conclude
+        | OpCode::Err
+        | OpCode::Reload
+        | OpCode::Deploy
+        | OpCode::Ack
+        | OpCode::StreamBegin
+        | OpCode::StreamChunk
+        | OpCode::StreamEnd
+        | OpCode::LogChunk => MIN_PROTOCOL_VERSION,
+    }
+}
+
+/// Rewrite an outgoing message for a peer speaking `protocol`.
+///
+/// Fails if the opcode does not exist at the negotiated version, or if it is a
+/// stream opcode and the peer did not advertise `STREAMING`.
+pub fn downgrade(mut msg: Message, protocol: &NegotiatedProtocol) -> Result<Message> {
+    let opcode = msg.header.opcode;
+    let is_stream = matches!(
+        opcode,
+        OpCode::StreamBegin | OpCode::StreamChunk | OpCode::StreamEnd
+    );
+    if opcode_min_version(opcode) > protocol.version
+        || (is_stream && !protocol.supports(ProtocolFeatures::STREAMING))
+    {
+        return Err(ProtocolError::OpCodeUnsupported {
+            opcode,
+            version: protocol.version,
+        });
+    }
+    msg.header.version = protocol.version;
+    Ok(msg)
+}
+
+/// Normalize an incoming message from an older/newer peer to this build's version.
+///
+/// `Header::unpack` has already checked the version is within
+/// `ProtocolVersionRange::SUPPORTED`; the header layout is shared across the
+/// range, so only the version byte changes.
+pub fn upgrade(mut msg: Message) -> Message {
+    msg.header.version = PROTOCOL_VERSION;
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::JobId;
+
+    #[test]
+    fn test_negotiate_picks_highest_common_version() {
+        let sentinel = ProtocolVersionRange::new(4, 5).unwrap();
+        let worker = ProtocolVersionRange::new(5, 6).unwrap();
+        assert_eq!(sentinel.negotiate(&worker), Some(5));
+        assert_eq!(worker.negotiate(&sentinel), Some(5));
+
+        let old = ProtocolVersionRange::exact(4);
+        assert_eq!(sentinel.negotiate(&old), Some(4));
+
+        let too_new = ProtocolVersionRange::exact(7);
+        assert_eq!(sentinel.negotiate(&too_new), None);
+    }
+
+    #[test]
+    fn test_invalid_range_rejected() {
+        assert!(ProtocolVersionRange::new(5, 4).is_err());
+    }
+
+    #[test]
+    fn test_missing_range_defaults_to_v4() {
+        assert_eq!(
+            ProtocolVersionRange::default(),
+            ProtocolVersionRange::exact(MIN_PROTOCOL_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_features_intersect() {
+        let negotiated = NegotiatedProtocol::negotiate(
+            ProtocolVersionRange::SUPPORTED,
+            ProtocolFeatures::SUPPORTED,
+            ProtocolVersionRange::SUPPORTED,
+            ProtocolFeatures::DISPATCH_ACK,
+        )
+        .unwrap();
+        assert!(negotiated.supports(ProtocolFeatures::DISPATCH_ACK));
+        assert!(!negotiated.supports(ProtocolFeatures::STREAMING));
+    }
+
+    #[test]
+    fn test_batch_dispatch_requires_both_peers() {
+        let negotiated = NegotiatedProtocol::negotiate(
+            ProtocolVersionRange::SUPPORTED,
+            ProtocolFeatures::SUPPORTED,
+            ProtocolVersionRange::SUPPORTED,
+            ProtocolFeatures::STREAMING.union(ProtocolFeatures::DISPATCH_ACK),
+        )
+        .unwrap();
+        assert!(ProtocolFeatures::SUPPORTED.contains(ProtocolFeatures::BATCH_DISPATCH));
+        assert!(!negotiated.supports(ProtocolFeatures::BATCH_DISPATCH));
+    }
+
+    #[test]
+    fn test_no_common_version_is_error() {
+        let result = NegotiatedProtocol::negotiate(
+            ProtocolVersionRange::SUPPORTED,
+            ProtocolFeatures::SUPPORTED,
+            ProtocolVersionRange::exact(0x7F),
+            ProtocolFeatures::SUPPORTED,
+        );
+        assert!(matches!(result, Err(ProtocolError::NoCommonVersion { .. })));
+    }
+
+    #[test]
+    fn test_downgrade_rejects_streams_without_feature() {
+        let protocol = NegotiatedProtocol::default();
+        let msg = Message::new(OpCode::StreamChunk, JobId::new(1), vec![1, 2, 3]).unwrap();
+        assert!(matches!(
+            downgrade(msg, &protocol),
+            Err(ProtocolError::OpCodeUnsupported { .. })
+        ));
+
+        let msg = Message::new(OpCode::Dispatch, JobId::new(1), vec![]).unwrap();
+        let translated = downgrade(msg, &protocol).unwrap();
+        assert_eq!(translated.header.version, MIN_PROTOCOL_VERSION);
+        assert_eq!(upgrade(translated).header.version, PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn test_newer_worker_downgrades_to_older_sentinel() {
+        // Worker speaking up to v5 against a Sentinel that only knows v4
+        let worker = ProtocolVersionRange::new(MIN_PROTOCOL_VERSION, 5).unwrap();
+        let sentinel = ProtocolVersionRange::exact(MIN_PROTOCOL_VERSION);
+        let protocol = NegotiatedProtocol::negotiate(
+            sentinel,
+            ProtocolFeatures::SUPPORTED,
+            worker,
+            ProtocolFeatures::SUPPORTED,
+        )
+        .unwrap();
+        assert_eq!(protocol.version, MIN_PROTOCOL_VERSION);
+
+        let msg = Message::new(OpCode::Conclude, JobId::new(1), b"{}".to_vec()).unwrap();
+        let (header, body) = downgrade(msg, &protocol).unwrap().pack().unwrap();
+        assert_eq!(header[0], MIN_PROTOCOL_VERSION);
+        let unpacked = Message::unpack(&[header, body]).unwrap();
+        assert_eq!(unpacked.header.opcode, OpCode::Conclude);
+    }
+}
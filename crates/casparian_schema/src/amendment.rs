@@ -18,6 +18,10 @@
 //! 3. User reviews the proposal
 //! 4. User chooses an action (approve, modify, reject, etc.)
 //! 5. Contract is updated (if approved) with new version
+//!
+//! Proposals are persisted with [`SchemaStorage::save_amendment`];
+//! [`process_amendment`] applies an action to a stored proposal and records
+//! the [`AmendmentResult`].
 
 use crate::ids::{AmendmentId, ContractId, SchemaTimestamp};
 use crate::storage::{SchemaStorage, StorageError};
@@ -379,6 +383,23 @@ impl std::fmt::Display for SchemaChange {
     }
 }
 
+impl SchemaChange {
+    /// The column this change targets (`None` for reordering).
+    pub fn column_name(&self) -> Option<&str> {
+        match self {
+            SchemaChange::AddColumn { column, .. } => Some(&column.name),
+            SchemaChange::RemoveColumn { column_name }
+            | SchemaChange::ChangeType { column_name, .. }
+            | SchemaChange::ChangeNullability { column_name, .. }
+            | SchemaChange::AddDefaultValue { column_name, .. }
+            | SchemaChange::RemoveDefaultValue { column_name }
+            | SchemaChange::ChangeFormat { column_name, .. } => Some(column_name),
+            SchemaChange::RenameColumn { from, .. } => Some(from),
+            SchemaChange::ReorderColumns { .. } => None,
+        }
+    }
+}
+
 /// Sample value that caused a schema issue.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SampleValue {
@@ -445,6 +466,41 @@ pub enum AmendmentStatus {
     Superseded,
 }
 
+impl AmendmentStatus {
+    pub const ALL: &'static [AmendmentStatus] = &[
+        AmendmentStatus::Pending,
+        AmendmentStatus::Approved,
+        AmendmentStatus::Rejected,
+        AmendmentStatus::SeparatedSchema,
+        AmendmentStatus::FilesExcluded,
+        AmendmentStatus::Superseded,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AmendmentStatus::Pending => "pending",
+            AmendmentStatus::Approved => "approved",
+            AmendmentStatus::Rejected => "rejected",
+            AmendmentStatus::SeparatedSchema => "separated_schema",
+            AmendmentStatus::FilesExcluded => "files_excluded",
+            AmendmentStatus::Superseded => "superseded",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|status| status.as_str() == s)
+    }
+}
+
+impl std::fmt::Display for AmendmentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Action to take on an amendment proposal.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -796,6 +852,33 @@ pub fn approve_amendment(
     }
 }
 
+/// Apply an action to a stored proposal and record the result.
+///
+/// Deferring leaves the proposal pending. Approving supersedes every other
+/// pending proposal against the same contract, since they were computed
+/// against the old version.
+pub fn process_amendment(
+    storage: &SchemaStorage,
+    amendment_id: &AmendmentId,
+    action: AmendmentAction,
+    processed_by: impl Into<String>,
+) -> Result<AmendmentResult, AmendmentError> {
+    let proposal = storage
+        .get_amendment(amendment_id)?
+        .ok_or_else(|| AmendmentError::AmendmentNotFound(amendment_id.clone()))?;
+
+    let result = approve_amendment(storage, &proposal, action, processed_by)?;
+    if result.status == AmendmentStatus::Pending {
+        return Ok(result);
+    }
+
+    storage.record_amendment_result(amendment_id, &result)?;
+    if result.status == AmendmentStatus::Approved {
+        storage.supersede_pending_amendments(&proposal.contract_id, amendment_id)?;
+    }
+    Ok(result)
+}
+
 /// Compute the changes between two schemas.
 fn compute_schema_changes(from: &LockedSchema, to: &LockedSchema) -> Vec<SchemaChange> {
     let mut changes = Vec::new();
@@ -1107,6 +1190,75 @@ mod tests {
         assert!(updated.schemas[0].columns[1].nullable);
     }
 
+    #[test]
+    fn test_process_stored_amendment() {
+        let storage = create_test_storage();
+        let contract = create_test_contract(&storage);
+
+        let nullable = propose_nullability_amendment(&contract, "name", 10.0).unwrap();
+        let retype = propose_type_mismatch_amendment(
+            &contract,
+            "id",
+            DataType::Int64,
+            vec!["abc".to_string()],
+            DataType::String,
+        )
+        .unwrap();
+        storage.save_amendment(&nullable).unwrap();
+        storage.save_amendment(&retype).unwrap();
+        assert_eq!(storage.list_pending_amendments().unwrap().len(), 2);
+
+        // Deferring keeps the proposal pending
+        let deferred = process_amendment(
+            &storage,
+            &nullable.amendment_id,
+            AmendmentAction::Defer {
+                notes: "later".to_string(),
+            },
+            "reviewer",
+        )
+        .unwrap();
+        assert_eq!(deferred.status, AmendmentStatus::Pending);
+        assert_eq!(storage.list_pending_amendments().unwrap().len(), 2);
+
+        let result = process_amendment(
+            &storage,
+            &nullable.amendment_id,
+            AmendmentAction::ApproveAsProposed,
+            "reviewer",
+        )
+        .unwrap();
+        assert_eq!(result.contract.as_ref().unwrap().version, 2);
+
+        let stored = storage
+            .get_amendment(&nullable.amendment_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, AmendmentStatus::Approved);
+        let recorded = storage
+            .get_amendment_result(&nullable.amendment_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(recorded.processed_by, "reviewer");
+
+        // The other proposal was computed against version 1
+        let superseded = storage
+            .get_amendment(&retype.amendment_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(superseded.status, AmendmentStatus::Superseded);
+        assert!(storage.list_pending_amendments().unwrap().is_empty());
+
+        let err = process_amendment(
+            &storage,
+            &nullable.amendment_id,
+            AmendmentAction::ApproveAsProposed,
+            "reviewer",
+        )
+        .unwrap_err();
+        assert!(matches!(err, AmendmentError::AlreadyProcessed(_)));
+    }
+
     #[test]
     fn test_approve_with_modifications() {
         let storage = create_test_storage();
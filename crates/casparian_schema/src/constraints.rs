@@ -0,0 +1,188 @@
+//! Column constraints: row-level rules beyond names and types.
+//!
+//! A contract's types say what shape a value has; constraints say which values
+//! are acceptable. They are declared per column (`LockedColumn::constraints`),
+//! travel to the worker inside `SchemaColumnSpec`, and are enforced there on
+//! every written batch. Rows that break a constraint are quarantined and
+//! counted per column in the job's diagnostics.
+//!
+//! This module validates constraint *definitions* when a schema is built, so
+//! a bad regex or an inverted range fails at approval time rather than on the
+//! first job.
+
+use crate::{ColumnConstraint, DataType, LockedColumn};
+use regex::Regex;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ConstraintError {
+    #[error("column '{column}': range constraint needs at least one of min/max")]
+    EmptyRange { column: String },
+    #[error("column '{column}': range min {min} is greater than max {max}")]
+    InvertedRange { column: String, min: f64, max: f64 },
+    #[error("column '{column}': range constraint requires a numeric column, got {data_type}")]
+    RangeOnNonNumeric { column: String, data_type: String },
+    #[error("column '{column}': invalid regex '{pattern}': {message}")]
+    InvalidRegex {
+        column: String,
+        pattern: String,
+        message: String,
+    },
+    #[error("column '{column}': enum constraint must list at least one value")]
+    EmptyEnum { column: String },
+}
+
+/// Check that every constraint on `column` is well-formed for its type.
+pub fn validate_column_constraints(column: &LockedColumn) -> Result<(), ConstraintError> {
+    for constraint in &column.constraints {
+        validate_constraint(&column.name, &column.data_type, constraint)?;
+    }
+    Ok(())
+}
+
+pub fn validate_constraint(
+    column: &str,
+    data_type: &DataType,
+    constraint: &ColumnConstraint,
+) -> Result<(), ConstraintError> {
+    match constraint {
+        ColumnConstraint::NotNull | ColumnConstraint::Unique => Ok(()),
+        ColumnConstraint::Range { min, max } => {
+            if min.is_none() && max.is_none() {
+                return Err(ConstraintError::EmptyRange {
+                    column: column.to_string(),
+                });
+            }
+            if let (Some(min), Some(max)) = (min, max) {
+                if min > max {
+                    return Err(ConstraintError::InvertedRange {
+                        column: column.to_string(),
+                        min: *min,
+                        max: *max,
+                    });
+                }
+            }
+            if !is_numeric(data_type) {
+                return Err(ConstraintError::RangeOnNonNumeric {
+                    column: column.to_string(),
+                    data_type: data_type.to_string(),
+                });
+            }
+            Ok(())
+        }
+        ColumnConstraint::Regex { pattern } => {
+            Regex::new(pattern)
+                .map(|_| ())
+                .map_err(|err| ConstraintError::InvalidRegex {
+                    column: column.to_string(),
+                    pattern: pattern.clone(),
+                    message: err.to_string(),
+                })
+        }
+        ColumnConstraint::Enum { values } => {
+            if values.is_empty() {
+                return Err(ConstraintError::EmptyEnum {
+                    column: column.to_string(),
+                });
+            }
+            Ok(())
+        }
+    }
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int64 | DataType::Float64 | DataType::Decimal { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_constraints() {
+        let column = LockedColumn::required("amount", DataType::Float64)
+            .with_constraint(ColumnConstraint::Range {
+                min: Some(0.0),
+                max: None,
+            })
+            .with_constraint(ColumnConstraint::Unique);
+        assert!(validate_column_constraints(&column).is_ok());
+
+        let column = LockedColumn::optional("code", DataType::String)
+            .with_constraint(ColumnConstraint::Regex {
+                pattern: "^[A-Z]{3}$".to_string(),
+            })
+            .with_constraint(ColumnConstraint::Enum {
+                values: vec!["USD".to_string(), "EUR".to_string()],
+            });
+        assert!(validate_column_constraints(&column).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_constraints() {
+        let range = |min, max| ColumnConstraint::Range { min, max };
+        assert!(matches!(
+            validate_constraint("x", &DataType::Int64, &range(None, None)),
+            Err(ConstraintError::EmptyRange { .. })
+        ));
+        assert!(matches!(
+            validate_constraint("x", &DataType::Int64, &range(Some(5.0), Some(1.0))),
+            Err(ConstraintError::InvertedRange { .. })
+        ));
+        assert!(matches!(
+            validate_constraint("x", &DataType::String, &range(Some(0.0), None)),
+            Err(ConstraintError::RangeOnNonNumeric { .. })
+        ));
+        assert!(matches!(
+            validate_constraint(
+                "x",
+                &DataType::String,
+                &ColumnConstraint::Regex {
+                    pattern: "(".to_string()
+                }
+            ),
+            Err(ConstraintError::InvalidRegex { .. })
+        ));
+        assert!(matches!(
+            validate_constraint(
+                "x",
+                &DataType::String,
+                &ColumnConstraint::Enum { values: vec![] }
+            ),
+            Err(ConstraintError::EmptyEnum { .. })
+        ));
+    }
+
+    #[test]
+    fn test_constraint_serde_shape() {
+        let json =
+            r#"[{"kind":"not_null"},{"kind":"range","min":0.0},{"kind":"enum","values":["a"]}]"#;
+        let parsed: Vec<ColumnConstraint> = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed[0], ColumnConstraint::NotNull);
+        assert_eq!(
+            parsed[1],
+            ColumnConstraint::Range {
+                min: Some(0.0),
+                max: None
+            }
+        );
+        assert_eq!(parsed[2].kind(), "enum");
+    }
+
+    #[test]
+    fn test_constraints_change_content_hash() {
+        let plain = crate::LockedSchema::new(
+            "orders",
+            vec![LockedColumn::required("id", DataType::Int64)],
+        );
+        let unique = crate::LockedSchema::new(
+            "orders",
+            vec![LockedColumn::required("id", DataType::Int64)
+                .with_constraint(ColumnConstraint::Unique)],
+        );
+        assert_ne!(plain.content_hash, unique.content_hash);
+    }
+}
@@ -140,6 +140,9 @@ impl LockedSchema {
             data_type: &'a DataType,
             nullable: bool,
             format: Option<&'a str>,
+            // Omitted when empty so unconstrained schemas keep their hash.
+            #[serde(skip_serializing_if = "<[_]>::is_empty")]
+            constraints: &'a [ColumnConstraint],
         }
 
         #[derive(Serialize)]
@@ -155,6 +158,7 @@ impl LockedSchema {
                 data_type: &col.data_type,
                 nullable: col.nullable,
                 format: col.format.as_deref(),
+                constraints: &col.constraints,
             })
             .collect();
 
@@ -211,6 +215,10 @@ pub struct LockedColumn {
 
     /// Optional description for documentation
     pub description: Option<String>,
+
+    /// Row-level rules beyond type and nullability (see [`crate::constraints`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<ColumnConstraint>,
 }
 
 impl LockedColumn {
@@ -222,6 +230,7 @@ impl LockedColumn {
             nullable: false,
             format: None,
             description: None,
+            constraints: Vec::new(),
         }
     }
 
@@ -233,6 +242,7 @@ impl LockedColumn {
             nullable: true,
             format: None,
             description: None,
+            constraints: Vec::new(),
         }
     }
 
@@ -247,10 +257,16 @@ impl LockedColumn {
         self.description = Some(desc.to_string());
         self
     }
+
+    /// Add a row-level constraint
+    pub fn with_constraint(mut self, constraint: ColumnConstraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
 }
 
 /// Canonical data type used for schema contracts (shared across crates).
-pub use casparian_protocol::{DataType, QuarantineConfig};
+pub use casparian_protocol::{ColumnConstraint, DataType, QuarantineConfig};
 
 /// A schema contract violation - parser output doesn't match contract.
 ///
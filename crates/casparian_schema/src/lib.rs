@@ -21,17 +21,20 @@
 //! # Modules
 //!
 //! - [`contract`]: Core types for schema contracts (LockedSchema, LockedColumn, etc.)
+//! - [`constraints`]: Row-level column rules (not_null, unique, range, regex, enum)
 //! - [`storage`]: SQLite-backed persistence for contracts
 //! - [`approval`]: Workflow for approving schemas and creating contracts
 //! - [`amendment`]: Workflow for controlled schema evolution
 
 pub mod amendment;
 pub mod approval;
+pub mod constraints;
 pub mod contract;
 pub mod ids;
 pub mod output_specs;
 pub mod storage;
 
+pub use constraints::{validate_column_constraints, ConstraintError};
 pub use contract::*;
 pub use ids::{
     AmendmentId, ContractId, DiscoveryId, IdParseError, SchemaId, SchemaTimestamp,
@@ -1,4 +1,4 @@
-use crate::{LockedColumn, LockedSchema};
+use crate::{validate_column_constraints, ConstraintError, LockedColumn, LockedSchema};
 use casparian_protocol::SchemaDefinition;
 use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
@@ -18,6 +18,8 @@ pub enum SchemaSpecError {
     EmptyColumnName(String),
     #[error("Schema for '{0}' has duplicate column '{1}'")]
     DuplicateColumnName(String, String),
+    #[error("Schema for '{0}' has an invalid constraint: {1}")]
+    InvalidConstraint(String, ConstraintError),
     #[error("Failed to serialize outputs JSON: {0}")]
     Serialization(String),
 }
@@ -73,6 +75,9 @@ pub fn locked_schema_from_definition(
         if let Some(format) = &col.format {
             locked = locked.with_format(format);
         }
+        locked.constraints = col.constraints.clone();
+        validate_column_constraints(&locked)
+            .map_err(|err| SchemaSpecError::InvalidConstraint(output_name.to_string(), err))?;
         columns.push(locked);
     }
 
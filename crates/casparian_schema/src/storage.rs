@@ -2,7 +2,8 @@
 //!
 //! Database-backed persistence for schema contracts using casparian_db.
 
-use crate::ids::{ContractId, DiscoveryId, SchemaTimestamp};
+use crate::amendment::{AmendmentResult, AmendmentStatus, SchemaAmendmentProposal};
+use crate::ids::{AmendmentId, ContractId, DiscoveryId, SchemaTimestamp};
 use crate::{LockedSchema, QuarantineConfig, SchemaContract};
 use casparian_db::{BackendError, DbConnection, DbValue, UnifiedDbRow};
 use std::path::Path;
@@ -106,6 +107,19 @@ impl SchemaStorage {
 
             CREATE INDEX IF NOT EXISTS idx_schema_discovery_scope
                 ON schema_discovery_results(scope_id);
+
+            CREATE TABLE IF NOT EXISTS schema_amendments (
+                amendment_id TEXT PRIMARY KEY,
+                contract_id TEXT NOT NULL,
+                status TEXT NOT NULL,
+                proposal_json TEXT NOT NULL,
+                result_json TEXT,
+                created_at TEXT NOT NULL,
+                processed_at TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_schema_amendments_status
+                ON schema_amendments(status);
         "#,
             DiscoveryStatus::Pending.as_str(),
             status_values
@@ -406,6 +420,130 @@ impl SchemaStorage {
         )?;
         Ok(result > 0)
     }
+
+    // === Amendments ===
+
+    /// Save an amendment proposal (insert or replace).
+    pub fn save_amendment(&self, proposal: &SchemaAmendmentProposal) -> Result<(), StorageError> {
+        let proposal_json = serde_json::to_string(proposal)?;
+        self.conn.execute(
+            r#"
+                INSERT INTO schema_amendments
+                    (amendment_id, contract_id, status, proposal_json, created_at)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(amendment_id) DO UPDATE SET
+                    status = excluded.status,
+                    proposal_json = excluded.proposal_json
+                "#,
+            &[
+                DbValue::from(proposal.amendment_id.as_str()),
+                DbValue::from(proposal.contract_id.as_str()),
+                DbValue::from(proposal.status.as_str()),
+                DbValue::from(proposal_json),
+                DbValue::from(proposal.created_at.as_str()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get an amendment proposal by ID.
+    pub fn get_amendment(
+        &self,
+        amendment_id: &AmendmentId,
+    ) -> Result<Option<SchemaAmendmentProposal>, StorageError> {
+        let row = self.conn.query_optional(
+            "SELECT status, proposal_json FROM schema_amendments WHERE amendment_id = ?",
+            &[DbValue::from(amendment_id.as_str())],
+        )?;
+        row.map(row_to_amendment).transpose()
+    }
+
+    /// Pending amendment proposals, oldest first.
+    pub fn list_pending_amendments(&self) -> Result<Vec<SchemaAmendmentProposal>, StorageError> {
+        let rows = self.conn.query_all(
+            r#"
+                SELECT status, proposal_json
+                FROM schema_amendments
+                WHERE status = ?
+                ORDER BY created_at ASC
+                "#,
+            &[DbValue::from(AmendmentStatus::Pending.as_str())],
+        )?;
+        rows.into_iter().map(row_to_amendment).collect()
+    }
+
+    /// Record the outcome of processing an amendment.
+    pub fn record_amendment_result(
+        &self,
+        amendment_id: &AmendmentId,
+        result: &AmendmentResult,
+    ) -> Result<bool, StorageError> {
+        let result_json = serde_json::to_string(result)?;
+        let updated = self.conn.execute(
+            r#"
+                UPDATE schema_amendments
+                SET status = ?, result_json = ?, processed_at = ?
+                WHERE amendment_id = ?
+                "#,
+            &[
+                DbValue::from(result.status.as_str()),
+                DbValue::from(result_json),
+                DbValue::from(result.processed_at.as_str()),
+                DbValue::from(amendment_id.as_str()),
+            ],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Get the recorded outcome of a processed amendment.
+    pub fn get_amendment_result(
+        &self,
+        amendment_id: &AmendmentId,
+    ) -> Result<Option<AmendmentResult>, StorageError> {
+        let json: Option<String> = self
+            .conn
+            .query_optional(
+                "SELECT result_json FROM schema_amendments WHERE amendment_id = ?",
+                &[DbValue::from(amendment_id.as_str())],
+            )?
+            .map(|row| row.get_by_name("result_json"))
+            .transpose()?
+            .flatten();
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    /// Mark every other pending amendment of a contract as superseded.
+    pub fn supersede_pending_amendments(
+        &self,
+        contract_id: &ContractId,
+        except: &AmendmentId,
+    ) -> Result<usize, StorageError> {
+        let updated = self.conn.execute(
+            r#"
+                UPDATE schema_amendments
+                SET status = ?
+                WHERE contract_id = ? AND status = ? AND amendment_id != ?
+                "#,
+            &[
+                DbValue::from(AmendmentStatus::Superseded.as_str()),
+                DbValue::from(contract_id.as_str()),
+                DbValue::from(AmendmentStatus::Pending.as_str()),
+                DbValue::from(except.as_str()),
+            ],
+        )?;
+        Ok(updated as usize)
+    }
+}
+
+fn row_to_amendment(row: UnifiedDbRow) -> Result<SchemaAmendmentProposal, StorageError> {
+    let status_str: String = row.get_by_name("status")?;
+    let status = AmendmentStatus::parse(&status_str)
+        .ok_or_else(|| StorageError::Parse(format!("Invalid amendment status: {}", status_str)))?;
+    let proposal_json: String = row.get_by_name("proposal_json")?;
+    let mut proposal: SchemaAmendmentProposal = serde_json::from_str(&proposal_json)?;
+    // The column is authoritative; the JSON keeps the status at save time.
+    proposal.status = status;
+    Ok(proposal)
 }
 
 fn row_to_contract(row: UnifiedDbRow) -> Result<SchemaContract, StorageError> {
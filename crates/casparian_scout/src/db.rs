@@ -6,14 +6,17 @@
 //! - Tagging Rules: pattern → tag mappings
 //! - Files: discovered files with their tags and status
 
+use super::detect::{self, ContentDetection, ContentType};
 use super::error::{Result, ScoutError};
+use super::incremental::IncrementalState;
 use super::types::{
     BatchUpsertResult, DbStats, ExtractionLogStatus, ExtractionStatus, Extractor, FileStatus,
-    FileTag, ParserValidationStatus, ScannedFile, Source, SourceId, SourceType, TagSource,
-    TaggingRule, TaggingRuleId, UpsertResult, Workspace, WorkspaceId,
+    FileTag, ParserValidationStatus, RuleMatchMode, ScannedFile, Source, SourceId, SourceType,
+    TagSource, TaggingRule, TaggingRuleId, UpsertResult, WatchMode, Workspace, WorkspaceId,
 };
 use casparian_ai_types::DraftStatus;
 use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::{CsvDialect, DetectionConfidence, TextEncoding};
 #[cfg(feature = "duckdb")]
 use casparian_db::BackendError;
 use chrono::{DateTime, Utc};
@@ -62,6 +65,8 @@ CREATE TABLE IF NOT EXISTS scout_rules (
     tag TEXT NOT NULL,
     priority INTEGER NOT NULL DEFAULT 0,
     enabled INTEGER NOT NULL DEFAULT 1,
+    csv_dialect TEXT,                            -- JSON CsvDialect for matched files; NULL = detect
+    input_encoding TEXT,                         -- TextEncoding of matched files; NULL = detect
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
     UNIQUE(workspace_id, name)
@@ -107,6 +112,11 @@ CREATE TABLE IF NOT EXISTS scout_files (
     deleted_at INTEGER,
     processed_at INTEGER,
     sentinel_job_id INTEGER,
+    processed_offset BIGINT,                     -- bytes already queued for incremental reads
+    head_hash TEXT,                              -- hash of the first min(processed_offset, 4096) bytes
+    detected_type TEXT,                          -- content type sniffed from the file head (e.g., "csv", "evtx")
+    detection_confidence TEXT,                   -- HIGH, MEDIUM, LOW, UNKNOWN; NULL = not sniffed yet
+    csv_dialect TEXT,                            -- JSON CsvDialect sniffed for CSV files
     -- Extractor metadata (Phase 6)
     metadata_raw TEXT,                           -- JSON blob of extracted metadata
     extraction_status TEXT DEFAULT '__EXTRACTION_STATUS_DEFAULT__'     -- pending, extracted, timeout, crash, stale, error
@@ -522,6 +532,39 @@ fn now_millis() -> i64 {
     Utc::now().timestamp_millis()
 }
 
+/// Stored (size, mtime, content_hash) for a path, used to classify rescans.
+type ExistingFile = (i64, i64, Option<String>);
+
+/// Whether a rescanned file differs from its stored row. Content hashes
+/// (S3 ETags) only count when the scanner supplied one, so local scans that
+/// leave `content_hash` empty never flip a file to changed on that basis.
+fn file_changed(old_size: i64, old_mtime: i64, old_hash: Option<&str>, file: &ScannedFile) -> bool {
+    old_size != file.size as i64
+        || old_mtime != file.mtime
+        || file
+            .content_hash
+            .as_deref()
+            .is_some_and(|hash| old_hash != Some(hash))
+}
+
+/// SQL form of `file_changed` comparing the stored row alias `old` to the
+/// incoming row alias `new`.
+fn changed_sql(old: &str, new: &str) -> String {
+    format!(
+        "({old}.size != {new}.size OR {old}.mtime != {new}.mtime \
+         OR ({new}.content_hash IS NOT NULL \
+         AND COALESCE({old}.content_hash, '') != {new}.content_hash))"
+    )
+}
+
+fn rule_match_mode_key(workspace_id: &WorkspaceId) -> String {
+    format!("rule_match_mode:{}", workspace_id)
+}
+
+fn watch_mode_key(source_id: &SourceId) -> String {
+    format!("watch_mode:{}", source_id)
+}
+
 /// Convert a glob pattern to SQL LIKE pattern.
 ///
 /// # Examples
@@ -639,6 +682,11 @@ impl Database {
             "missing_scans",
             "status_before_delete",
             "deleted_at",
+            "processed_offset",
+            "head_hash",
+            "detected_type",
+            "detection_confidence",
+            "csv_dialect",
         ];
         let mut missing = Vec::new();
         for col in required_columns {
@@ -658,24 +706,28 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
             )));
         }
 
-        let required_source_columns = ["exec_path"];
-        let mut source_missing = Vec::new();
-        for col in required_source_columns {
-            if !column_exists(conn, "scout_sources", col)? {
-                source_missing.push(col);
+        let required_table_columns: [(&str, &[&str]); 2] = [
+            ("scout_sources", &["exec_path"]),
+            ("scout_rules", &["csv_dialect", "input_encoding"]),
+        ];
+        for (table, columns) in required_table_columns {
+            let mut table_missing = Vec::new();
+            for &col in columns {
+                if !column_exists(conn, table, col)? {
+                    table_missing.push(col);
+                }
             }
-        }
-
-        if source_missing.is_empty() {
-            return Ok(());
-        }
-
-        Err(ScoutError::Config(format!(
-            "Database schema for 'scout_sources' is missing columns: {}. \
+            if !table_missing.is_empty() {
+                return Err(ScoutError::Config(format!(
+                    "Database schema for '{}' is missing columns: {}. \
 Manual reset required. Delete the state store (default: ~/.casparian_flow/state.sqlite) \
 or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
-            source_missing.join(", ")
-        )))
+                    table,
+                    table_missing.join(", ")
+                )));
+            }
+        }
+        Ok(())
     }
 
     /// Create an in-memory database (for testing).
@@ -1441,6 +1493,76 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
         Ok(result > 0)
     }
 
+    /// Set (or clear, with None) the CSV dialect of files a rule tags.
+    /// Returns false if the rule does not exist.
+    pub fn set_rule_csv_dialect(
+        &self,
+        id: &TaggingRuleId,
+        dialect: Option<&CsvDialect>,
+    ) -> Result<bool> {
+        if let Some(dialect) = dialect {
+            dialect.validate().map_err(ScoutError::Config)?;
+        }
+        let json = dialect.map(serde_json::to_string).transpose()?;
+        let result = self.conn.execute(
+            "UPDATE scout_rules SET csv_dialect = ?, updated_at = ? WHERE id = ?",
+            &[
+                DbValue::from(json),
+                DbValue::from(now_millis()),
+                DbValue::from(id.to_string()),
+            ],
+        )?;
+        Ok(result > 0)
+    }
+
+    /// CSV dialect configured on a rule, if any.
+    pub fn get_rule_csv_dialect(&self, id: &TaggingRuleId) -> Result<Option<CsvDialect>> {
+        let json: Option<String> = self
+            .conn
+            .query_optional(
+                "SELECT csv_dialect FROM scout_rules WHERE id = ?",
+                &[DbValue::from(id.to_string())],
+            )?
+            .map(|row| row.get::<Option<String>>(0))
+            .transpose()?
+            .flatten();
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    /// Set (or clear, with None) the text encoding of files a rule tags,
+    /// overriding the worker's detection. Returns false if the rule does not
+    /// exist.
+    pub fn set_rule_input_encoding(
+        &self,
+        id: &TaggingRuleId,
+        encoding: Option<TextEncoding>,
+    ) -> Result<bool> {
+        let result = self.conn.execute(
+            "UPDATE scout_rules SET input_encoding = ?, updated_at = ? WHERE id = ?",
+            &[
+                DbValue::from(encoding.map(|encoding| encoding.as_str())),
+                DbValue::from(now_millis()),
+                DbValue::from(id.to_string()),
+            ],
+        )?;
+        Ok(result > 0)
+    }
+
+    /// Text encoding configured on a rule, if any.
+    pub fn get_rule_input_encoding(&self, id: &TaggingRuleId) -> Result<Option<TextEncoding>> {
+        let raw: Option<String> = self
+            .conn
+            .query_optional(
+                "SELECT input_encoding FROM scout_rules WHERE id = ?",
+                &[DbValue::from(id.to_string())],
+            )?
+            .map(|row| row.get::<Option<String>>(0))
+            .transpose()?
+            .flatten();
+        raw.map(|raw| raw.parse().map_err(ScoutError::Config))
+            .transpose()
+    }
+
     fn row_to_tagging_rule(row: &casparian_db::UnifiedDbRow) -> Result<TaggingRule> {
         let enabled: i64 = row.get(6)?;
         let id_raw: String = row.get(0)?;
@@ -1468,7 +1590,7 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
     pub fn upsert_file(&self, file: &ScannedFile) -> Result<UpsertResult> {
         // Check if file exists
         let existing = self.conn.query_optional(
-            "SELECT id, size, mtime, status FROM scout_files WHERE source_id = ? AND file_uid = ?",
+            "SELECT id, size, mtime, status, content_hash FROM scout_files WHERE source_id = ? AND file_uid = ?",
             &[
                 file.source_id.as_i64().into(),
                 DbValue::from(file.file_uid.as_str()),
@@ -1537,8 +1659,9 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                 let id: i64 = row.get(0)?;
                 let old_size: i64 = row.get(1)?;
                 let old_mtime: i64 = row.get(2)?;
+                let old_hash: Option<String> = row.get(4)?;
 
-                let changed = file.size as i64 != old_size || file.mtime != old_mtime;
+                let changed = file_changed(old_size, old_mtime, old_hash.as_deref(), file);
 
                 if changed {
                     // File changed - reset to pending, clear tag
@@ -1559,6 +1682,9 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                             status = ?,
                             error = NULL,
                             sentinel_job_id = NULL,
+                            detected_type = NULL,
+                            detection_confidence = NULL,
+                            csv_dialect = NULL,
                             last_seen_at = ?
                         WHERE id = ?
                         "#,
@@ -1683,9 +1809,10 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                     if let Some(existing) = existing.as_ref() {
                         for file in chunk {
                             let is_new = !existing.contains_key(&file.path);
-                            let is_changed = existing.get(&file.path).is_some_and(|(size, mtime)| {
-                                *size != file.size as i64 || *mtime != file.mtime
-                            });
+                            let is_changed =
+                                existing.get(&file.path).is_some_and(|(size, mtime, hash)| {
+                                    file_changed(*size, *mtime, hash.as_deref(), file)
+                                });
 
                             if is_new {
                                 chunk_new += 1;
@@ -1825,24 +1952,25 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                     tx.bulk_insert_rows("staging_scout_files", &STAGING_COLUMNS, &rows)?;
 
                     if compute_stats {
-                        let row = tx.query_one(
+                        let stats_sql = format!(
                             r#"
                             SELECT
                                 COALESCE(SUM(CASE WHEN target.path IS NULL THEN 1 ELSE 0 END), 0) AS new_count,
                                 COALESCE(SUM(CASE
                                     WHEN target.path IS NOT NULL
-                                     AND (target.size != source.size OR target.mtime != source.mtime)
+                                     AND {changed}
                                     THEN 1 ELSE 0 END), 0) AS changed_count,
                                 COALESCE(SUM(CASE
                                     WHEN target.path IS NOT NULL
-                                     AND target.size = source.size AND target.mtime = source.mtime
+                                     AND NOT {changed}
                                     THEN 1 ELSE 0 END), 0) AS unchanged_count
                             FROM staging_scout_files AS source
                             LEFT JOIN scout_files AS target
                               ON target.source_id = source.source_id AND target.file_uid = source.file_uid
                             "#,
-                            &[],
-                        )?;
+                            changed = changed_sql("target", "source"),
+                        );
+                        let row = tx.query_one(&stats_sql, &[])?;
 
                         let new_count: i64 = row.get(0)?;
                         let changed_count: i64 = row.get(1)?;
@@ -1864,7 +1992,7 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                         MERGE INTO scout_files AS target
                         USING staging_scout_files AS source
                         ON target.source_id = source.source_id AND target.file_uid = source.file_uid
-                        WHEN MATCHED AND (target.size != source.size OR target.mtime != source.mtime) THEN
+                        WHEN MATCHED AND {changed} THEN
                             UPDATE SET
                                 workspace_id = source.workspace_id,
                                 path = source.path,
@@ -1898,7 +2026,8 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                             VALUES (source.workspace_id, source.source_id, source.file_uid, source.path, source.rel_path, source.parent_path, source.name, source.extension, source.is_dir,
                                     source.size, source.mtime, source.content_hash, source.status, source.first_seen_at, source.last_seen_at)
                         "#,
-                        pending_status = pending_status
+                        pending_status = pending_status,
+                        changed = changed_sql("target", "source"),
                     );
 
                     tx.execute_batch(&merge_sql)?;
@@ -1938,7 +2067,7 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
         source_id: &SourceId,
         files: &[ScannedFile],
     ) -> std::result::Result<
-        std::collections::HashMap<String, (i64, i64)>,
+        std::collections::HashMap<String, ExistingFile>,
         casparian_db::BackendError,
     > {
         let mut existing = std::collections::HashMap::with_capacity(files.len());
@@ -1949,7 +2078,7 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
         for chunk in files.chunks(SELECT_CHUNK_SIZE) {
             let placeholders: String = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(",");
             let query = format!(
-                "SELECT path, size, mtime FROM scout_files WHERE source_id = ? AND path IN ({})",
+                "SELECT path, size, mtime, content_hash FROM scout_files WHERE source_id = ? AND path IN ({})",
                 placeholders
             );
 
@@ -1964,7 +2093,8 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                 let path: String = row.get(0)?;
                 let size: i64 = row.get(1)?;
                 let mtime: i64 = row.get(2)?;
-                existing.insert(path, (size, mtime));
+                let content_hash: Option<String> = row.get(3)?;
+                existing.insert(path, (size, mtime, content_hash));
             }
         }
 
@@ -2087,13 +2217,26 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                    extension = excluded.extension,
                    is_dir = excluded.is_dir,
                    status = CASE
-                       WHEN scout_files.size != excluded.size OR scout_files.mtime != excluded.mtime
+                       WHEN {changed}
                        THEN excluded.status
                        ELSE scout_files.status
                    END,
+                   detected_type = CASE
+                       WHEN {changed} THEN NULL
+                       ELSE scout_files.detected_type
+                   END,
+                   detection_confidence = CASE
+                       WHEN {changed} THEN NULL
+                       ELSE scout_files.detection_confidence
+                   END,
+                   csv_dialect = CASE
+                       WHEN {changed} THEN NULL
+                       ELSE scout_files.csv_dialect
+                   END,
                    last_seen_at = excluded.last_seen_at
             "#,
-            values
+            values,
+            changed = changed_sql("scout_files", "excluded"),
         );
 
         let mut params = Vec::with_capacity(files.len() * 15);
@@ -2124,15 +2267,50 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
         tx: &mut casparian_db::DbTransaction<'_>,
         files: &[ScannedFile],
         now: i64,
-        existing: Option<&std::collections::HashMap<String, (i64, i64)>>,
+        existing: Option<&std::collections::HashMap<String, ExistingFile>>,
         stats: &mut BatchUpsertResult,
     ) {
+        let upsert_sql = format!(
+            r#"INSERT INTO scout_files
+                   (workspace_id, source_id, file_uid, path, rel_path, parent_path, name, extension, is_dir, size, mtime, content_hash, status, first_seen_at, last_seen_at)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                   ON CONFLICT(source_id, path) DO UPDATE SET
+                       workspace_id = excluded.workspace_id,
+                       file_uid = excluded.file_uid,
+                       size = excluded.size,
+                       mtime = excluded.mtime,
+                       content_hash = excluded.content_hash,
+                       parent_path = excluded.parent_path,
+                       name = excluded.name,
+                       extension = excluded.extension,
+                       is_dir = excluded.is_dir,
+                       status = CASE
+                           WHEN {changed}
+                           THEN excluded.status
+                           ELSE scout_files.status
+                       END,
+                       detected_type = CASE
+                           WHEN {changed} THEN NULL
+                           ELSE scout_files.detected_type
+                       END,
+                       detection_confidence = CASE
+                           WHEN {changed} THEN NULL
+                           ELSE scout_files.detection_confidence
+                       END,
+                       csv_dialect = CASE
+                           WHEN {changed} THEN NULL
+                           ELSE scout_files.csv_dialect
+                       END,
+                       last_seen_at = excluded.last_seen_at"#,
+            changed = changed_sql("scout_files", "excluded"),
+        );
+
         for file in files {
             let classification = existing.map(|existing| {
                 let is_new = !existing.contains_key(&file.path);
-                let is_changed = existing
-                    .get(&file.path)
-                    .is_some_and(|(size, mtime)| *size != file.size as i64 || *mtime != file.mtime);
+                let is_changed = existing.get(&file.path).is_some_and(|(size, mtime, hash)| {
+                    file_changed(*size, *mtime, hash.as_deref(), file)
+                });
                 (is_new, is_changed)
             });
 
@@ -2154,28 +2332,7 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
                 now.into(),
             ];
 
-            let result = tx.execute(
-                r#"INSERT INTO scout_files
-                   (workspace_id, source_id, file_uid, path, rel_path, parent_path, name, extension, is_dir, size, mtime, content_hash, status, first_seen_at, last_seen_at)
-                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                   ON CONFLICT(source_id, path) DO UPDATE SET
-                       workspace_id = excluded.workspace_id,
-                       file_uid = excluded.file_uid,
-                       size = excluded.size,
-                       mtime = excluded.mtime,
-                       content_hash = excluded.content_hash,
-                       parent_path = excluded.parent_path,
-                       name = excluded.name,
-                       extension = excluded.extension,
-                       is_dir = excluded.is_dir,
-                       status = CASE
-                           WHEN scout_files.size != excluded.size OR scout_files.mtime != excluded.mtime
-                           THEN excluded.status
-                           ELSE scout_files.status
-                       END,
-                       last_seen_at = excluded.last_seen_at"#,
-                &params,
-            );
+            let result = tx.execute(&upsert_sql, &params);
 
             match result {
                 Ok(_) => {
@@ -2563,6 +2720,138 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
         Ok(())
     }
 
+    /// Incremental-read progress of a file (`None` if never read incrementally).
+    pub fn get_incremental_state(&self, file_id: i64) -> Result<Option<IncrementalState>> {
+        let row = self.conn.query_optional(
+            "SELECT processed_offset, head_hash FROM scout_files WHERE id = ?",
+            &[file_id.into()],
+        )?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let processed_offset: Option<i64> = row.get(0)?;
+        let head_hash: Option<String> = row.get(1)?;
+        Ok(match (processed_offset, head_hash) {
+            (Some(offset), Some(head_hash)) => Some(IncrementalState {
+                processed_offset: u64::try_from(offset).unwrap_or(0),
+                head_hash,
+            }),
+            _ => None,
+        })
+    }
+
+    /// Record that a file's bytes up to `state.processed_offset` are queued.
+    pub fn set_incremental_state(&self, file_id: i64, state: &IncrementalState) -> Result<()> {
+        let offset = i64::try_from(state.processed_offset)
+            .map_err(|_| ScoutError::Config("processed_offset exceeds i64".to_string()))?;
+        self.conn.execute(
+            "UPDATE scout_files SET processed_offset = ?, head_hash = ? WHERE id = ?",
+            &[
+                offset.into(),
+                state.head_hash.as_str().into(),
+                file_id.into(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Sniff the content type of every file in the workspace that has not been
+    /// sniffed since it last changed. Returns the number of files sniffed.
+    ///
+    /// Files that cannot be read are left for the next call.
+    pub fn detect_content_types(&self, workspace_id: &WorkspaceId) -> Result<usize> {
+        let rows = self.conn.query_all(
+            "SELECT id, path FROM scout_files \
+             WHERE workspace_id = ? AND is_dir = 0 AND status != ? \
+             AND detection_confidence IS NULL",
+            &[
+                workspace_id.to_string().into(),
+                FileStatus::Deleted.as_str().into(),
+            ],
+        )?;
+        let mut sniffed = 0;
+        for row in rows {
+            let id: i64 = row.get(0)?;
+            let path: String = row.get(1)?;
+            match detect::read_head(Path::new(&path)) {
+                Ok((head, complete)) => {
+                    let detection = detect::sniff(&head, complete);
+                    self.set_content_detection(id, detection.as_ref())?;
+                    if let Some(ContentType::Csv { .. }) = detection.map(|d| d.content_type) {
+                        let dialect = detect::detect_csv_dialect(&head, complete);
+                        self.set_csv_dialect(id, dialect.as_ref().map(|d| &d.dialect))?;
+                    }
+                    sniffed += 1;
+                }
+                Err(err) => {
+                    tracing::debug!(path = %path, error = %err, "Skipping content detection");
+                }
+            }
+        }
+        Ok(sniffed)
+    }
+
+    /// Record the sniffed content type of a file (`None` = not recognized).
+    pub fn set_content_detection(
+        &self,
+        file_id: i64,
+        detection: Option<&ContentDetection>,
+    ) -> Result<()> {
+        let (detected_type, confidence) = match detection {
+            Some(detection) => (
+                Some(detection.content_type.as_str()),
+                detection.confidence.as_str(),
+            ),
+            None => (None, DetectionConfidence::Unknown.as_str()),
+        };
+        self.conn.execute(
+            "UPDATE scout_files SET detected_type = ?, detection_confidence = ? WHERE id = ?",
+            &[detected_type.into(), confidence.into(), file_id.into()],
+        )?;
+        Ok(())
+    }
+
+    /// Record the sniffed CSV dialect of a file.
+    pub fn set_csv_dialect(&self, file_id: i64, dialect: Option<&CsvDialect>) -> Result<()> {
+        let json = dialect.map(serde_json::to_string).transpose()?;
+        self.conn.execute(
+            "UPDATE scout_files SET csv_dialect = ? WHERE id = ?",
+            &[json.into(), file_id.into()],
+        )?;
+        Ok(())
+    }
+
+    /// Sniffed CSV dialect of a file (`None` if not sniffed or not CSV).
+    pub fn get_csv_dialect(&self, file_id: i64) -> Result<Option<CsvDialect>> {
+        let json: Option<String> = self
+            .conn
+            .query_optional(
+                "SELECT csv_dialect FROM scout_files WHERE id = ?",
+                &[file_id.into()],
+            )?
+            .map(|row| row.get::<Option<String>>(0))
+            .transpose()?
+            .flatten();
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    /// Sniffed content type of a file (`None` if not sniffed or not recognized).
+    pub fn get_content_detection(&self, file_id: i64) -> Result<Option<ContentDetection>> {
+        let row = self.conn.query_optional(
+            "SELECT detected_type, detection_confidence FROM scout_files WHERE id = ?",
+            &[file_id.into()],
+        )?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let detected_type: Option<String> = row.get(0)?;
+        let confidence: Option<String> = row.get(1)?;
+        Ok(ContentDetection::from_columns(
+            detected_type.as_deref(),
+            confidence.as_deref(),
+        ))
+    }
+
     /// Mark files as deleted if not seen recently
     pub fn mark_deleted_files(
         &self,
@@ -2586,6 +2875,28 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
         Ok(result)
     }
 
+    /// Mark a file, or every file below a directory, as deleted.
+    pub fn mark_path_deleted(&self, source_id: &SourceId, path: &str) -> Result<u64> {
+        let dir_prefix = format!("{}/", path.trim_end_matches('/'));
+        let result = self.conn.execute(
+            r#"
+                UPDATE scout_files SET status = ?
+                WHERE source_id = ? AND status != ?
+                  AND (path = ? OR SUBSTR(path, 1, ?) = ?)
+                "#,
+            &[
+                FileStatus::Deleted.as_str().into(),
+                source_id.as_i64().into(),
+                FileStatus::Deleted.as_str().into(),
+                path.into(),
+                (dir_prefix.chars().count() as i64).into(),
+                dir_prefix.as_str().into(),
+            ],
+        )?;
+
+        Ok(result)
+    }
+
     fn row_to_file(row: &casparian_db::UnifiedDbRow) -> Result<ScannedFile> {
         use super::types::ExtractionStatus;
 
@@ -3417,6 +3728,47 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
         }
     }
 
+    /// Delete a setting value
+    pub fn delete_setting(&self, key: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM scout_settings WHERE key = ?", &[key.into()])?;
+        Ok(())
+    }
+
+    /// Tagging rule match mode for a workspace (defaults to first-match).
+    pub fn get_rule_match_mode(&self, workspace_id: &WorkspaceId) -> Result<RuleMatchMode> {
+        let key = rule_match_mode_key(workspace_id);
+        match self.get_setting(&key)? {
+            Some(raw) => RuleMatchMode::parse(&raw)
+                .ok_or_else(|| ScoutError::Config(format!("Invalid {} setting: '{}'", key, raw))),
+            None => Ok(RuleMatchMode::default()),
+        }
+    }
+
+    /// Set the tagging rule match mode for a workspace.
+    pub fn set_rule_match_mode(
+        &self,
+        workspace_id: &WorkspaceId,
+        mode: RuleMatchMode,
+    ) -> Result<()> {
+        self.set_setting(&rule_match_mode_key(workspace_id), mode.as_str())
+    }
+
+    /// Watch mode for a source (defaults to auto).
+    pub fn get_watch_mode(&self, source_id: &SourceId) -> Result<WatchMode> {
+        let key = watch_mode_key(source_id);
+        match self.get_setting(&key)? {
+            Some(raw) => WatchMode::parse(&raw)
+                .ok_or_else(|| ScoutError::Config(format!("Invalid {} setting: '{}'", key, raw))),
+            None => Ok(WatchMode::default()),
+        }
+    }
+
+    /// Set the watch mode for a source.
+    pub fn set_watch_mode(&self, source_id: &SourceId, mode: WatchMode) -> Result<()> {
+        self.set_setting(&watch_mode_key(source_id), mode.as_str())
+    }
+
     // ========================================================================
     // Extractor Operations
     // ========================================================================
@@ -3743,6 +4095,138 @@ mod tests {
         assert!(db.get_tagging_rule(&rule_id).unwrap().is_none());
     }
 
+    #[test]
+    fn test_watch_mode_setting() {
+        let db = create_test_db();
+        let source_id = SourceId::new();
+        assert_eq!(db.get_watch_mode(&source_id).unwrap(), WatchMode::Auto);
+
+        db.set_watch_mode(&source_id, WatchMode::Poll).unwrap();
+        assert_eq!(db.get_watch_mode(&source_id).unwrap(), WatchMode::Poll);
+        assert_eq!(
+            db.get_watch_mode(&SourceId::new()).unwrap(),
+            WatchMode::Auto
+        );
+    }
+
+    #[test]
+    fn test_rule_match_mode_setting() {
+        let db = create_test_db();
+        let workspace_id = default_workspace_id(&db);
+        assert_eq!(
+            db.get_rule_match_mode(&workspace_id).unwrap(),
+            RuleMatchMode::FirstMatch
+        );
+
+        db.set_rule_match_mode(&workspace_id, RuleMatchMode::AllMatch)
+            .unwrap();
+        assert_eq!(
+            db.get_rule_match_mode(&workspace_id).unwrap(),
+            RuleMatchMode::AllMatch
+        );
+        assert_eq!(
+            db.get_rule_match_mode(&WorkspaceId::new()).unwrap(),
+            RuleMatchMode::FirstMatch
+        );
+    }
+
+    #[test]
+    fn test_incremental_state_round_trip() {
+        let db = create_test_db();
+        let workspace_id = default_workspace_id(&db);
+        let source_id = SourceId::new();
+        db.upsert_source(&Source {
+            workspace_id,
+            id: source_id,
+            name: "Logs".to_string(),
+            source_type: SourceType::Local,
+            path: "/logs".to_string(),
+            exec_path: None,
+            poll_interval_secs: 30,
+            enabled: true,
+        })
+        .unwrap();
+        let file_uid = crate::file_uid::weak_uid_from_path_str("/logs/app.log");
+        let file = ScannedFile::new(
+            workspace_id,
+            source_id,
+            &file_uid,
+            "/logs/app.log",
+            "app.log",
+            120,
+            1,
+        );
+        let file_id = db.upsert_file(&file).unwrap().id;
+        assert_eq!(db.get_incremental_state(file_id).unwrap(), None);
+
+        let state = IncrementalState {
+            processed_offset: 100,
+            head_hash: "abc".to_string(),
+        };
+        db.set_incremental_state(file_id, &state).unwrap();
+        assert_eq!(db.get_incremental_state(file_id).unwrap(), Some(state));
+    }
+
+    #[test]
+    fn test_detect_content_types_records_and_resets() {
+        use crate::detect::ContentType;
+
+        let db = create_test_db();
+        let workspace_id = default_workspace_id(&db);
+        let dir = tempfile::tempdir().unwrap();
+        let source_id = SourceId::new();
+        db.upsert_source(&Source {
+            workspace_id,
+            id: source_id,
+            name: "Exports".to_string(),
+            source_type: SourceType::Local,
+            path: dir.path().display().to_string(),
+            exec_path: None,
+            poll_interval_secs: 30,
+            enabled: true,
+        })
+        .unwrap();
+
+        let upsert = |name: &str, contents: &str, mtime: i64| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            let path = path.display().to_string();
+            let file_uid = crate::file_uid::weak_uid_from_path_str(&path);
+            let file = ScannedFile::new(
+                workspace_id,
+                source_id,
+                &file_uid,
+                &path,
+                name,
+                contents.len() as u64,
+                mtime,
+            );
+            db.upsert_file(&file).unwrap().id
+        };
+        let csv_id = upsert("export", "a,b\n1,2\n3,4\n", 1);
+        let prose_id = upsert("notes.txt", "hello\n", 1);
+
+        assert_eq!(db.detect_content_types(&workspace_id).unwrap(), 2);
+        let detection = db.get_content_detection(csv_id).unwrap().unwrap();
+        assert_eq!(detection.content_type, ContentType::Csv { delimiter: b',' });
+        assert_eq!(detection.confidence, DetectionConfidence::Medium);
+        assert_eq!(
+            db.get_csv_dialect(csv_id).unwrap(),
+            Some(CsvDialect::default())
+        );
+        assert_eq!(db.get_content_detection(prose_id).unwrap(), None);
+        assert_eq!(db.get_csv_dialect(prose_id).unwrap(), None);
+
+        // Already sniffed: nothing to do until the file changes.
+        assert_eq!(db.detect_content_types(&workspace_id).unwrap(), 0);
+        upsert("export", "{\"a\": 1}\n{\"a\": 2}\n", 2);
+        assert_eq!(db.get_content_detection(csv_id).unwrap(), None);
+        assert_eq!(db.get_csv_dialect(csv_id).unwrap(), None);
+        assert_eq!(db.detect_content_types(&workspace_id).unwrap(), 1);
+        let detection = db.get_content_detection(csv_id).unwrap().unwrap();
+        assert_eq!(detection.content_type, ContentType::Ndjson);
+    }
+
     #[test]
     fn test_file_tagging() {
         let db = create_test_db();
@@ -0,0 +1,575 @@
+//! Content-based file type detection.
+//!
+//! Tagging rules match on paths, which fails for files with a wrong or
+//! missing extension (`logs/Security` that is really EVTX, `export.txt` that
+//! is really CSV). Sniffing the first `SNIFF_BYTES` of a file recognizes the
+//! common formats from their magic bytes or structure:
+//!
+//! - parquet (`PAR1`), EVTX (`ElfFile\0`) and gzip (`1f 8b`) by magic bytes
+//! - JSON vs NDJSON by parsing the document or its individual lines
+//! - CSV dialects by a delimiter that splits every sampled line into the
+//!   same number of fields
+//!
+//! The result is recorded per file together with a `DetectionConfidence`,
+//! and rule matching treats a file with a trusted detection as if it carried
+//! the detected type's extension (see `rule_apply`).
+//!
+//! For CSV the full `CsvDialect` is inferred as well (quote, escape, leading
+//! preamble rows, encoding, null tokens) and handed to the parser at
+//! dispatch when no rule or plugin configures one.
+
+use crate::error::Result;
+use casparian_protocol::{CsvDialect, DetectionConfidence, TextEncoding};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read from the start of a file for detection.
+pub const SNIFF_BYTES: usize = 8192;
+
+/// Lines sampled for NDJSON and CSV detection.
+const SAMPLE_LINES: usize = 50;
+
+/// CSV lines needed for a `High` confidence dialect.
+const CSV_HIGH_CONFIDENCE_LINES: usize = 5;
+
+/// Delimiters tried for CSV dialect detection, in order of preference.
+const CSV_DELIMITERS: &[u8] = b",\t;|";
+
+/// Quote characters tried for CSV dialect detection, in order of preference.
+const CSV_QUOTES: &[char] = &['"', '\''];
+
+/// Most preamble lines (titles, export banners) skipped before a CSV header.
+const CSV_MAX_SKIP_ROWS: usize = 10;
+
+/// Unquoted field values recognized as null markers.
+const CSV_NULL_TOKENS: &[&str] = &["NULL", "null", "\\N", "NA", "N/A"];
+
+/// A content type recognized by sniffing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+    /// Delimited text with the given field delimiter
+    Csv {
+        delimiter: u8,
+    },
+    /// A single JSON document
+    Json,
+    /// One JSON value per line
+    Ndjson,
+    Parquet,
+    Evtx,
+    Gzip,
+}
+
+impl ContentType {
+    /// Value stored in `scout_files.detected_type`.
+    pub fn as_str(&self) -> String {
+        match self {
+            ContentType::Csv { delimiter: b',' } => "csv".to_string(),
+            ContentType::Csv { delimiter: b'\t' } => "tsv".to_string(),
+            ContentType::Csv { delimiter } => format!("csv:{}", *delimiter as char),
+            ContentType::Json => "json".to_string(),
+            ContentType::Ndjson => "ndjson".to_string(),
+            ContentType::Parquet => "parquet".to_string(),
+            ContentType::Evtx => "evtx".to_string(),
+            ContentType::Gzip => "gzip".to_string(),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "csv" => Some(ContentType::Csv { delimiter: b',' }),
+            "tsv" => Some(ContentType::Csv { delimiter: b'\t' }),
+            "json" => Some(ContentType::Json),
+            "ndjson" => Some(ContentType::Ndjson),
+            "parquet" => Some(ContentType::Parquet),
+            "evtx" => Some(ContentType::Evtx),
+            "gzip" => Some(ContentType::Gzip),
+            _ => match value.strip_prefix("csv:")?.as_bytes() {
+                [delimiter] => Some(ContentType::Csv {
+                    delimiter: *delimiter,
+                }),
+                _ => None,
+            },
+        }
+    }
+
+    /// Canonical file extension (lowercase, without dot).
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ContentType::Csv { delimiter: b'\t' } => "tsv",
+            ContentType::Csv { .. } => "csv",
+            ContentType::Json => "json",
+            ContentType::Ndjson => "jsonl",
+            ContentType::Parquet => "parquet",
+            ContentType::Evtx => "evtx",
+            ContentType::Gzip => "gz",
+        }
+    }
+}
+
+/// Detected type of one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentDetection {
+    pub content_type: ContentType,
+    pub confidence: DetectionConfidence,
+}
+
+impl ContentDetection {
+    fn new(content_type: ContentType, confidence: DetectionConfidence) -> Self {
+        Self {
+            content_type,
+            confidence,
+        }
+    }
+
+    /// Rebuild from the `detected_type` / `detection_confidence` columns.
+    pub fn from_columns(
+        detected_type: Option<&str>,
+        confidence: Option<&str>,
+    ) -> Option<ContentDetection> {
+        Some(ContentDetection {
+            content_type: ContentType::parse(detected_type?)?,
+            confidence: confidence?.parse().ok()?,
+        })
+    }
+
+    /// Whether the detection is strong enough to tag by.
+    pub fn is_trusted(&self) -> bool {
+        matches!(
+            self.confidence,
+            DetectionConfidence::High | DetectionConfidence::Medium
+        )
+    }
+}
+
+/// CSV dialect inferred from the head of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvDialectDetection {
+    pub dialect: CsvDialect,
+    pub confidence: DetectionConfidence,
+    /// Share of sampled rows after the preamble with the header's field
+    /// count (0.0 - 1.0)
+    pub score: f64,
+}
+
+/// Sniff the head of the file at `path`.
+pub fn detect_path(path: &Path) -> Result<Option<ContentDetection>> {
+    let (head, complete) = read_head(path)?;
+    Ok(sniff(&head, complete))
+}
+
+/// Read the first `SNIFF_BYTES` of the file at `path`; the flag is true when
+/// that is the whole file.
+pub fn read_head(path: &Path) -> Result<(Vec<u8>, bool)> {
+    let file = File::open(path)?;
+    let mut head = Vec::with_capacity(SNIFF_BYTES + 1);
+    // One byte past the window tells whether the head is the whole file.
+    file.take(SNIFF_BYTES as u64 + 1).read_to_end(&mut head)?;
+    let complete = head.len() <= SNIFF_BYTES;
+    head.truncate(SNIFF_BYTES);
+    Ok((head, complete))
+}
+
+/// Detect the content type of `head`, the first bytes of a file.
+/// `complete` is true when `head` is the entire file.
+pub fn sniff(head: &[u8], complete: bool) -> Option<ContentDetection> {
+    if head.starts_with(b"PAR1") {
+        return Some(ContentDetection::new(
+            ContentType::Parquet,
+            DetectionConfidence::High,
+        ));
+    }
+    if head.starts_with(b"ElfFile\0") {
+        return Some(ContentDetection::new(
+            ContentType::Evtx,
+            DetectionConfidence::High,
+        ));
+    }
+    if head.starts_with(&[0x1f, 0x8b]) {
+        // Deflate (08) is the only compression method in use.
+        let confidence = if head.get(2) == Some(&0x08) {
+            DetectionConfidence::High
+        } else {
+            DetectionConfidence::Medium
+        };
+        return Some(ContentDetection::new(ContentType::Gzip, confidence));
+    }
+
+    let (text, encoding) = decode_text(head)?;
+    if text.trim_start().starts_with(['{', '[']) {
+        let lines = sample_lines(&text, complete);
+        sniff_json(&text, &lines, complete)
+    } else {
+        let detection = sniff_csv(&text, complete, encoding)?;
+        Some(ContentDetection::new(
+            ContentType::Csv {
+                delimiter: detection.dialect.delimiter as u8,
+            },
+            detection.confidence,
+        ))
+    }
+}
+
+/// Infer the CSV dialect of `head`, the first bytes of a file.
+/// `complete` is true when `head` is the entire file.
+pub fn detect_csv_dialect(head: &[u8], complete: bool) -> Option<CsvDialectDetection> {
+    let (text, encoding) = decode_text(head)?;
+    sniff_csv(&text, complete, encoding)
+}
+
+/// Decode `head` by its byte order mark, falling back to Windows-1252 when it
+/// is not UTF-8. Binary content (NUL bytes without a UTF-16 BOM) is `None`.
+fn decode_text(head: &[u8]) -> Option<(Cow<'_, str>, TextEncoding)> {
+    if let Some(rest) = head.strip_prefix(b"\xef\xbb\xbf") {
+        return Some((String::from_utf8_lossy(rest), TextEncoding::Utf8));
+    }
+    if let Some(rest) = head.strip_prefix(b"\xff\xfe") {
+        let text = decode_utf16(rest, u16::from_le_bytes);
+        return Some((Cow::Owned(text), TextEncoding::Utf16Le));
+    }
+    if let Some(rest) = head.strip_prefix(b"\xfe\xff") {
+        let text = decode_utf16(rest, u16::from_be_bytes);
+        return Some((Cow::Owned(text), TextEncoding::Utf16Be));
+    }
+    if head.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(head) {
+        Ok(text) => Some((Cow::Borrowed(text), TextEncoding::Utf8)),
+        // A multi-byte sequence cut off by the end of the window.
+        Err(err) if err.error_len().is_none() => Some((
+            String::from_utf8_lossy(&head[..err.valid_up_to()]),
+            TextEncoding::Utf8,
+        )),
+        // Only the ASCII delimiters matter for detection, so bytes are
+        // mapped one-to-one rather than through the full code page.
+        Err(_) => Some((
+            Cow::Owned(head.iter().map(|&byte| byte as char).collect()),
+            TextEncoding::Windows1252,
+        )),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Non-empty lines of `text`, without a trailing partial line.
+fn sample_lines(text: &str, complete: bool) -> Vec<&str> {
+    sample_lines_indexed(text, complete)
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect()
+}
+
+fn sniff_json(text: &str, lines: &[&str], complete: bool) -> Option<ContentDetection> {
+    let line_is_value = |line: &&str| {
+        serde_json::from_str::<serde_json::Value>(line)
+            .is_ok_and(|value| value.is_object() || value.is_array())
+    };
+    if lines.len() >= 2 && lines.iter().all(line_is_value) {
+        return Some(ContentDetection::new(
+            ContentType::Ndjson,
+            DetectionConfidence::High,
+        ));
+    }
+    if complete {
+        return serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .map(|_| ContentDetection::new(ContentType::Json, DetectionConfidence::High));
+    }
+    // Truncated: a lone complete line still looks like NDJSON, anything else
+    // is the start of a larger document.
+    let content_type = if lines.len() == 1 && line_is_value(&lines[0]) {
+        ContentType::Ndjson
+    } else {
+        ContentType::Json
+    };
+    Some(ContentDetection::new(
+        content_type,
+        DetectionConfidence::Medium,
+    ))
+}
+
+fn sniff_csv(text: &str, complete: bool, encoding: TextEncoding) -> Option<CsvDialectDetection> {
+    let lines = sample_lines_indexed(text, complete);
+    if lines.len() < 2 {
+        return None;
+    }
+
+    let mut best: Option<CsvCandidate> = None;
+    for &quote in CSV_QUOTES {
+        let escape = text.contains(&format!("\\{}", quote)).then_some('\\');
+        for &delimiter in CSV_DELIMITERS {
+            let dialect = CsvDialect {
+                delimiter: delimiter as char,
+                quote,
+                escape,
+                ..CsvDialect::default()
+            };
+            let Some(candidate) = CsvCandidate::evaluate(&lines, dialect) else {
+                continue;
+            };
+            // Later quotes and delimiters must do strictly better.
+            if best
+                .as_ref()
+                .map_or(true, |best| candidate.rank() > best.rank())
+            {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    let CsvCandidate {
+        mut dialect,
+        skip,
+        agreeing,
+        ..
+    } = best?;
+    let rows = &lines[skip..];
+    let confidence = if agreeing == rows.len() {
+        if rows.len() >= CSV_HIGH_CONFIDENCE_LINES {
+            DetectionConfidence::High
+        } else {
+            DetectionConfidence::Medium
+        }
+    } else if agreeing * 5 >= rows.len() * 4 {
+        DetectionConfidence::Low
+    } else {
+        return None;
+    };
+
+    dialect.skip_rows = rows[0].0;
+    dialect.encoding = encoding;
+    for &token in CSV_NULL_TOKENS {
+        let present = rows[1..].iter().any(|(_, line)| {
+            split_fields(line, &dialect)
+                .iter()
+                .any(|field| field.trim() == token)
+        });
+        if present {
+            dialect.null_tokens.push(token.to_string());
+        }
+    }
+    Some(CsvDialectDetection {
+        dialect,
+        confidence,
+        score: agreeing as f64 / rows.len() as f64,
+    })
+}
+
+/// One delimiter/quote combination tried by `sniff_csv`.
+struct CsvCandidate {
+    dialect: CsvDialect,
+    /// Sampled lines before the header
+    skip: usize,
+    /// Fields in the header
+    fields: usize,
+    /// Lines from the header on with the header's field count
+    agreeing: usize,
+}
+
+impl CsvCandidate {
+    fn evaluate(lines: &[(usize, &str)], dialect: CsvDialect) -> Option<Self> {
+        let counts: Vec<usize> = lines
+            .iter()
+            .map(|(_, line)| split_fields(line, &dialect).len())
+            .collect();
+        let skip = preamble_len(&counts);
+        let fields = counts[skip];
+        if fields < 2 {
+            return None;
+        }
+        let agreeing = counts[skip..]
+            .iter()
+            .filter(|&&count| count == fields)
+            .count();
+        Some(Self {
+            dialect,
+            skip,
+            fields,
+            agreeing,
+        })
+    }
+
+    fn rank(&self) -> (usize, usize) {
+        (self.agreeing, self.fields)
+    }
+}
+
+/// Leading lines with fewer fields than the most common count, when they
+/// are few enough to be a preamble rather than the data itself.
+fn preamble_len(counts: &[usize]) -> usize {
+    let mut frequency: Vec<(usize, usize)> = Vec::new();
+    for &count in counts {
+        match frequency.iter_mut().find(|(fields, _)| *fields == count) {
+            Some((_, seen)) => *seen += 1,
+            None => frequency.push((count, 1)),
+        }
+    }
+    let Some(&(mode, _)) = frequency
+        .iter()
+        .max_by_key(|&&(fields, seen)| (seen, fields))
+    else {
+        return 0;
+    };
+    let skip = counts.iter().take_while(|&&count| count < mode).count();
+    if skip <= CSV_MAX_SKIP_ROWS && skip * 2 < counts.len() && counts.len() - skip >= 2 {
+        skip
+    } else {
+        0
+    }
+}
+
+/// Non-empty lines of `text` with their line numbers, without a trailing
+/// partial line.
+fn sample_lines_indexed(text: &str, complete: bool) -> Vec<(usize, &str)> {
+    let body = if complete {
+        text
+    } else {
+        text.rfind('\n').map_or("", |end| &text[..end])
+    };
+    body.lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .take(SAMPLE_LINES)
+        .collect()
+}
+
+/// Raw fields of `line`, ignoring delimiters inside quotes. Quoted fields
+/// keep their quotes.
+fn split_fields<'a>(line: &'a str, dialect: &CsvDialect) -> Vec<&'a str> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quoted && Some(c) == dialect.escape {
+            escaped = true;
+        } else if c == dialect.quote {
+            quoted = !quoted;
+        } else if c == dialect.delimiter && !quoted {
+            fields.push(&line[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+    fields.push(&line[start..]);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(head: &[u8], complete: bool) -> Option<(String, DetectionConfidence)> {
+        sniff(head, complete).map(|d| (d.content_type.as_str(), d.confidence))
+    }
+
+    #[test]
+    fn test_magic_bytes() {
+        use DetectionConfidence::High;
+        assert_eq!(
+            detected(b"PAR1\x15\x04", false),
+            Some(("parquet".to_string(), High))
+        );
+        assert_eq!(
+            detected(b"ElfFile\0\0\0", false),
+            Some(("evtx".to_string(), High))
+        );
+        assert_eq!(
+            detected(&[0x1f, 0x8b, 0x08, 0x00], false),
+            Some(("gzip".to_string(), High))
+        );
+        assert_eq!(detected(b"\x7fELF\x02\x01\0", false), None);
+    }
+
+    #[test]
+    fn test_json_vs_ndjson() {
+        use DetectionConfidence::{High, Medium};
+        assert_eq!(
+            detected(b"{\n  \"a\": 1,\n  \"b\": [1, 2]\n}\n", true),
+            Some(("json".to_string(), High))
+        );
+        assert_eq!(
+            detected(b"{\"a\": 1}\n{\"a\": 2}\n{\"a\": 3}\n", true),
+            Some(("ndjson".to_string(), High))
+        );
+        assert_eq!(
+            detected(b"[\n  {\"a\": 1},\n  {\"a\": ", false),
+            Some(("json".to_string(), Medium))
+        );
+        assert_eq!(detected(b"{ not json", true), None);
+    }
+
+    #[test]
+    fn test_csv_dialects() {
+        use DetectionConfidence::{High, Low, Medium};
+        let comma = b"id,name,note\n1,a,\"x, y\"\n2,b,z\n3,c,z\n4,d,z\n";
+        assert_eq!(detected(comma, true), Some(("csv".to_string(), High)));
+        let tab = b"id\tname\n1\ta\n";
+        assert_eq!(detected(tab, true), Some(("tsv".to_string(), Medium)));
+        let semicolon = b"id;amount\n1;2,50\n2;3,75\n";
+        assert_eq!(
+            detected(semicolon, true),
+            Some(("csv:;".to_string(), Medium))
+        );
+        let ragged = b"a,b,c\n1,2,3\n1,2,3\n1,2,3\n1,2\n";
+        assert_eq!(detected(ragged, true), Some(("csv".to_string(), Low)));
+        assert_eq!(detected(b"just some prose.\nmore prose.\n", true), None);
+    }
+
+    #[test]
+    fn test_csv_dialect_detection() {
+        let european = "Export Kontoumsätze\n\nDatum;Betrag;Notiz\n01.02.2024;2,50;NULL\n\
+                        02.02.2024;3,75;\"a; b\"\n03.02.2024;1,00;x\n04.02.2024;9,99;y\n\
+                        05.02.2024;0,10;z\n";
+        let detection = detect_csv_dialect(european.as_bytes(), true).unwrap();
+        assert_eq!(detection.dialect.delimiter, ';');
+        assert_eq!(detection.dialect.quote, '"');
+        assert_eq!(detection.dialect.skip_rows, 2);
+        assert_eq!(detection.dialect.encoding, TextEncoding::Utf8);
+        assert_eq!(detection.dialect.null_tokens, vec!["", "NULL"]);
+        assert_eq!(detection.confidence, DetectionConfidence::High);
+        assert_eq!(detection.score, 1.0);
+
+        // Windows-1252 bytes (0xe4 = a-umlaut) and backslash-escaped quotes.
+        let latin = b"name,city\n\"M\xe4rz \\\"x\\\", y\",Bonn\nb,\\N\n";
+        let detection = detect_csv_dialect(latin, true).unwrap();
+        assert_eq!(detection.dialect.encoding, TextEncoding::Windows1252);
+        assert_eq!(detection.dialect.escape, Some('\\'));
+        assert_eq!(detection.dialect.null_tokens, vec!["", "\\N"]);
+        assert_eq!(detection.confidence, DetectionConfidence::Medium);
+
+        let utf16: Vec<u8> = [0xff, 0xfe]
+            .into_iter()
+            .chain("a|b\n1|2\n".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let detection = detect_csv_dialect(&utf16, true).unwrap();
+        assert_eq!(detection.dialect.delimiter, '|');
+        assert_eq!(detection.dialect.encoding, TextEncoding::Utf16Le);
+
+        let ragged = b"a,b,c\n1,2,3\n1,2,3\n1,2,3\n1,2\n";
+        assert_eq!(detect_csv_dialect(ragged, true).unwrap().score, 0.8);
+    }
+
+    #[test]
+    fn test_column_round_trip() {
+        for value in [
+            "csv", "tsv", "csv:|", "json", "ndjson", "parquet", "evtx", "gzip",
+        ] {
+            assert_eq!(ContentType::parse(value).unwrap().as_str(), value);
+        }
+        let detection = ContentDetection::from_columns(Some("csv:|"), Some("MEDIUM")).unwrap();
+        assert_eq!(detection.content_type, ContentType::Csv { delimiter: b'|' });
+        assert!(detection.is_trusted());
+        assert_eq!(ContentDetection::from_columns(None, Some("UNKNOWN")), None);
+    }
+}
@@ -0,0 +1,165 @@
+//! Incremental reads of growing, line-oriented files (logs).
+//!
+//! Scout remembers, per file, how many bytes have already been handed to a
+//! job (`processed_offset`) and a hash of the file's head (`head_hash`, over
+//! the first `min(processed_offset, HEAD_HASH_BYTES)` bytes). On the next
+//! read the head is hashed again: if it still matches and the file has not
+//! shrunk, only the appended bytes are read. Anything else (truncation,
+//! rotation, an in-place rewrite) falls back to reading the whole file.
+//!
+//! A read always ends just after the last newline, so a line that is still
+//! being written is picked up by the following read instead of being split.
+
+use crate::error::Result;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Bytes of the file head covered by `head_hash`.
+pub const HEAD_HASH_BYTES: u64 = 4096;
+
+/// Chunk size used when searching backwards for the last newline.
+const TAIL_SCAN_BYTES: u64 = 64 * 1024;
+
+/// Incremental-read progress of one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncrementalState {
+    /// Bytes already handed to a job (always on a line boundary)
+    pub processed_offset: u64,
+    /// Hash of the first `min(processed_offset, HEAD_HASH_BYTES)` bytes
+    pub head_hash: String,
+}
+
+/// What the next read of a file should cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncrementalRead {
+    /// No complete new line since the last read.
+    UpToDate,
+    /// Read `[start, end)`; `start` is 0 when the whole file must be re-read.
+    Range {
+        start: u64,
+        end: u64,
+        /// State to record once the range is queued
+        next: IncrementalState,
+    },
+}
+
+/// Decide which bytes of `path` (currently `size` bytes) the next read needs,
+/// given the progress recorded by the previous one.
+pub fn plan_read(
+    path: &Path,
+    size: u64,
+    previous: Option<&IncrementalState>,
+) -> Result<IncrementalRead> {
+    let mut file = File::open(path)?;
+    let start = match previous {
+        Some(state) if state.processed_offset <= size => {
+            let head_len = state.processed_offset.min(HEAD_HASH_BYTES);
+            if hash_head(&mut file, head_len)? == state.head_hash {
+                state.processed_offset
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    };
+
+    let Some(end) = last_line_end(&mut file, start, size)? else {
+        return Ok(IncrementalRead::UpToDate);
+    };
+    let head_hash = hash_head(&mut file, end.min(HEAD_HASH_BYTES))?;
+    Ok(IncrementalRead::Range {
+        start,
+        end,
+        next: IncrementalState {
+            processed_offset: end,
+            head_hash,
+        },
+    })
+}
+
+/// Hash of the first `len` bytes of `file`.
+fn hash_head(file: &mut File, len: u64) -> Result<String> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut head = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut head)?;
+    Ok(blake3::hash(&head).to_hex().to_string())
+}
+
+/// Offset just past the last `\n` in `[start, size)`, if any.
+fn last_line_end(file: &mut File, start: u64, size: u64) -> Result<Option<u64>> {
+    let mut chunk_end = size;
+    let mut buf = Vec::new();
+    while chunk_end > start {
+        let chunk_start = chunk_end.saturating_sub(TAIL_SCAN_BYTES).max(start);
+        buf.clear();
+        file.seek(SeekFrom::Start(chunk_start))?;
+        (&mut *file)
+            .take(chunk_end - chunk_start)
+            .read_to_end(&mut buf)?;
+        if let Some(pos) = buf.iter().rposition(|&byte| byte == b'\n') {
+            return Ok(Some(chunk_start + pos as u64 + 1));
+        }
+        chunk_end = chunk_start;
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn plan(path: &Path, previous: Option<&IncrementalState>) -> IncrementalRead {
+        let size = std::fs::metadata(path).unwrap().len();
+        plan_read(path, size, previous).unwrap()
+    }
+
+    fn next_state(read: IncrementalRead) -> (u64, u64, IncrementalState) {
+        match read {
+            IncrementalRead::Range { start, end, next } => (start, end, next),
+            IncrementalRead::UpToDate => panic!("expected a range"),
+        }
+    }
+
+    #[test]
+    fn test_appended_lines_resume_from_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "a\nb\npartial").unwrap();
+
+        let (start, end, state) = next_state(plan(&path, None));
+        assert_eq!((start, end), (0, 4));
+        assert_eq!(state.processed_offset, 4);
+
+        // Only the unfinished line so far: nothing new to read.
+        assert_eq!(plan(&path, Some(&state)), IncrementalRead::UpToDate);
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b" line\nc\n").unwrap();
+        let (start, end, state) = next_state(plan(&path, Some(&state)));
+        assert_eq!((start, end), (4, 19));
+        assert_eq!(plan(&path, Some(&state)), IncrementalRead::UpToDate);
+    }
+
+    #[test]
+    fn test_rewritten_or_truncated_file_is_read_in_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "first\nsecond\n").unwrap();
+        let (_, _, state) = next_state(plan(&path, None));
+
+        // Rotated: same length prefix, different content.
+        std::fs::write(&path, "FIRST\nsecond\nthird\n").unwrap();
+        let (start, end, _) = next_state(plan(&path, Some(&state)));
+        assert_eq!((start, end), (0, 19));
+
+        // Truncated below the recorded offset.
+        std::fs::write(&path, "x\n").unwrap();
+        let (start, end, _) = next_state(plan(&path, Some(&state)));
+        assert_eq!((start, end), (0, 2));
+    }
+}
@@ -4,28 +4,35 @@
 //! Standalone crate for scanning + tagging logic.
 
 pub mod db;
+pub mod detect;
 pub mod engine;
 pub mod error;
 pub mod extractor;
 pub mod file_uid;
+pub mod incremental;
 pub mod patterns;
 pub mod rule_apply;
+pub mod s3;
 pub mod scan_path;
 pub mod scanner;
 pub mod tagger;
 pub mod types;
+pub mod watcher;
 pub mod wire;
 
 // Re-exports for CLI usage
 pub use db::Database;
+pub use detect::{ContentDetection, ContentType, CsvDialectDetection};
 pub use engine::{InProcessEngine, ScanEngine, SubprocessEngine};
 pub use extractor::{BatchExtractor, ExtractorConfig, ExtractorResult, ExtractorRunner};
-pub use patterns::{build_matcher, matches, normalize_glob_pattern};
+pub use incremental::{IncrementalRead, IncrementalState};
+pub use patterns::{build_matcher, matches, normalize_glob_pattern, RulePattern};
 pub use rule_apply::{
     match_rules_to_files, RuleApplyFile, RuleApplyRule, RuleMatch, TaggingSummary,
 };
 pub use scanner::{ScanCancelToken, ScanConfig, ScanProgress, Scanner};
 pub use types::{
-    ExtractionStatus, Extractor, FileStatus, FileTag, ScannedFile, Source, SourceId, SourceType,
-    TagSource, TaggingRule, TaggingRuleId, Workspace, WorkspaceId,
+    ExtractionStatus, Extractor, FileStatus, FileTag, RuleMatchMode, ScannedFile, Source, SourceId,
+    SourceType, TagSource, TaggingRule, TaggingRuleId, WatchMode, Workspace, WorkspaceId,
 };
+pub use watcher::{SourceWatcher, WatchConfig, WatchEvent};
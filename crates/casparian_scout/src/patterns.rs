@@ -1,6 +1,19 @@
 //! Shared glob pattern normalization and matching helpers.
+//!
+//! Globs follow path semantics: `*` and `?` stay within one path segment and
+//! `**` spans any number of directories (`**/logs/*.evtx`).
+//!
+//! Tagging rule patterns may combine several globs separated by `;`. Globs
+//! prefixed with `!` are exclusions: a path matches the rule when it matches
+//! any include and no exclusion (`**/*.evtx;!**/archive/**`).
 
-use globset::{GlobBuilder, GlobMatcher};
+use globset::{GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+
+/// Separator between globs in a tagging rule pattern.
+pub const RULE_PATTERN_SEPARATOR: char = ';';
+
+/// Prefix marking an exclusion glob in a tagging rule pattern.
+pub const EXCLUDE_PREFIX: char = '!';
 
 /// Normalize a glob pattern for matching against relative paths.
 ///
@@ -26,17 +39,79 @@ pub fn normalize_glob_pattern(raw: &str) -> String {
 pub fn build_matcher(glob_pattern: &str) -> Result<GlobMatcher, String> {
     GlobBuilder::new(glob_pattern)
         .case_insensitive(true)
+        .literal_separator(true)
         .build()
         .map(|g| g.compile_matcher())
         .map_err(|_| "Invalid pattern".to_string())
 }
 
-/// Match a raw glob pattern against a path.
+/// Match a raw rule pattern (includes and `!` exclusions) against a path.
 pub fn matches(raw_pattern: &str, path: &str) -> Result<bool, String> {
-    let normalized = normalize_glob_pattern(raw_pattern);
-    let matcher = build_matcher(&normalized)?;
-    let candidate = path.trim_start_matches('/');
-    Ok(matcher.is_match(candidate))
+    Ok(RulePattern::parse(raw_pattern)?.is_match(path))
+}
+
+/// Compiled tagging rule pattern: any include matches and no exclusion does.
+#[derive(Debug, Clone)]
+pub struct RulePattern {
+    includes: GlobSet,
+    excludes: GlobSet,
+}
+
+impl RulePattern {
+    /// Parse `glob[;glob...][;!exclude...]`. A pattern with only exclusions
+    /// includes everything else.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let (includes, excludes) = split_rule_pattern(raw);
+        let includes = if includes.is_empty() {
+            vec!["*"]
+        } else {
+            includes
+        };
+        Ok(Self {
+            includes: build_glob_set(&includes)?,
+            excludes: build_glob_set(&excludes)?,
+        })
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        let candidate = path.trim_start_matches('/');
+        self.includes.is_match(candidate) && !self.excludes.is_match(candidate)
+    }
+}
+
+/// Split a rule pattern into include and exclusion globs (not yet normalized).
+pub fn split_rule_pattern(raw: &str) -> (Vec<&str>, Vec<&str>) {
+    let mut includes = Vec::new();
+    let mut excludes = Vec::new();
+    for part in raw.split(RULE_PATTERN_SEPARATOR).map(str::trim) {
+        match part.strip_prefix(EXCLUDE_PREFIX) {
+            Some(exclude) => {
+                let exclude = exclude.trim();
+                if !exclude.is_empty() {
+                    excludes.push(exclude);
+                }
+            }
+            None if !part.is_empty() => includes.push(part),
+            None => {}
+        }
+    }
+    (includes, excludes)
+}
+
+fn build_glob_set(raw_patterns: &[&str]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for raw in raw_patterns {
+        let normalized = normalize_glob_pattern(raw);
+        let glob = GlobBuilder::new(&normalized)
+            .case_insensitive(true)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| format!("Invalid pattern '{}': {}", raw, e))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Invalid pattern: {}", e))
 }
 
 #[cfg(test)]
@@ -60,4 +135,30 @@ mod tests {
         assert!(matches("**/*.json", "deep/nested/file.json").unwrap());
         assert!(!matches("*.csv", "data.json").unwrap());
     }
+
+    #[test]
+    fn single_star_stays_within_segment() {
+        assert!(matches("**/logs/*.evtx", "host1/logs/system.evtx").unwrap());
+        assert!(matches("**/logs/*.evtx", "logs/system.evtx").unwrap());
+        assert!(!matches("**/logs/*.evtx", "host1/logs/old/system.evtx").unwrap());
+        assert!(!matches("data/*.csv", "data/2024/file.csv").unwrap());
+        assert!(matches("data/**/*.csv", "data/2024/01/file.csv").unwrap());
+    }
+
+    #[test]
+    fn exclusions_veto_includes() {
+        let pattern = RulePattern::parse("**/*.evtx; !**/archive/**").unwrap();
+        assert!(pattern.is_match("host1/logs/system.evtx"));
+        assert!(!pattern.is_match("host1/archive/logs/system.evtx"));
+        assert!(!pattern.is_match("host1/logs/system.csv"));
+
+        let pattern = RulePattern::parse("*.csv;*.tsv").unwrap();
+        assert!(pattern.is_match("a/b.tsv"));
+
+        let only_excludes = RulePattern::parse("!*.tmp").unwrap();
+        assert!(only_excludes.is_match("a/b.csv"));
+        assert!(!only_excludes.is_match("a/b.tmp"));
+
+        assert!(RulePattern::parse("[invalid").is_err());
+    }
 }
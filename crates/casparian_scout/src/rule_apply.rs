@@ -1,9 +1,9 @@
 //! Shared rule-application helpers for tagging files.
 
+use super::detect::ContentDetection;
 use super::error::{Result, ScoutError};
-use super::patterns;
-use super::types::TaggingRuleId;
-use globset::GlobMatcher;
+use super::patterns::RulePattern;
+use super::types::{RuleMatchMode, TaggingRuleId};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -12,6 +12,24 @@ pub struct RuleApplyFile {
     pub path: String,
     pub rel_path: String,
     pub size: i64,
+    /// Sniffed content type, if any (see `detect`)
+    pub detection: Option<ContentDetection>,
+}
+
+impl RuleApplyFile {
+    /// Path with the detected type's extension appended, when a trusted
+    /// detection disagrees with the file's own extension. Rules are matched
+    /// against this too, so `**/*.evtx` tags an extensionless EVTX file.
+    fn detected_rel_path(&self) -> Option<String> {
+        let detection = self.detection.filter(ContentDetection::is_trusted)?;
+        let extension = detection.content_type.extension();
+        let name = self.rel_path.rsplit('/').next().unwrap_or(&self.rel_path);
+        let current = name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase());
+        if current.as_deref() == Some(extension) {
+            return None;
+        }
+        Some(format!("{}.{}", self.rel_path, extension))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -47,32 +65,42 @@ pub struct TaggingSummary {
 
 struct CompiledRule {
     rule: RuleApplyRule,
-    matcher: GlobMatcher,
+    pattern: RulePattern,
 }
 
-/// Match files to tagging rules (first match wins).
+/// Match files to tagging rules, evaluated by priority (highest first).
+///
+/// With `RuleMatchMode::FirstMatch` each file gets at most one match; with
+/// `RuleMatchMode::AllMatch` every matching rule contributes a match.
 pub fn match_rules_to_files(
     files: &[RuleApplyFile],
     rules: &[RuleApplyRule],
+    mode: RuleMatchMode,
 ) -> Result<(Vec<RuleMatch>, TaggingSummary)> {
     let mut compiled = Vec::with_capacity(rules.len());
     for rule in rules {
-        let normalized = patterns::normalize_glob_pattern(&rule.pattern);
-        let matcher = patterns::build_matcher(&normalized)
+        let pattern = RulePattern::parse(&rule.pattern)
             .map_err(|e| ScoutError::Pattern(format!("{}: {}", rule.pattern, e)))?;
         compiled.push(CompiledRule {
             rule: rule.clone(),
-            matcher,
+            pattern,
         });
     }
+    // Stable sort keeps caller order among equal priorities.
+    compiled.sort_by_key(|r| std::cmp::Reverse(r.rule.priority));
 
     let mut summary = TaggingSummary::default();
     let mut matches = Vec::new();
 
     for file in files {
+        let detected_rel_path = file.detected_rel_path();
         let mut matched = false;
         for compiled_rule in &compiled {
-            if compiled_rule.matcher.is_match(&file.rel_path) {
+            let is_match = compiled_rule.pattern.is_match(&file.rel_path)
+                || detected_rel_path
+                    .as_deref()
+                    .is_some_and(|path| compiled_rule.pattern.is_match(path));
+            if is_match {
                 let entry = summary
                     .matches
                     .entry(compiled_rule.rule.pattern.clone())
@@ -89,7 +117,9 @@ pub fn match_rules_to_files(
                     pattern: compiled_rule.rule.pattern.clone(),
                 });
                 matched = true;
-                break;
+                if mode == RuleMatchMode::FirstMatch {
+                    break;
+                }
             }
         }
         if !matched {
@@ -102,3 +132,103 @@ pub fn match_rules_to_files(
 
     Ok((matches, summary))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(id: i64, rel_path: &str) -> RuleApplyFile {
+        RuleApplyFile {
+            id,
+            path: format!("/data/{}", rel_path),
+            rel_path: rel_path.to_string(),
+            size: 10,
+            detection: None,
+        }
+    }
+
+    fn rule(pattern: &str, tag: &str, priority: i32) -> RuleApplyRule {
+        RuleApplyRule {
+            id: TaggingRuleId::new(),
+            pattern: pattern.to_string(),
+            tag: tag.to_string(),
+            priority,
+        }
+    }
+
+    #[test]
+    fn test_match_modes_and_exclusions() {
+        let files = vec![
+            file(1, "host1/logs/system.evtx"),
+            file(2, "host1/archive/logs/system.evtx"),
+            file(3, "notes.txt"),
+        ];
+        let rules = vec![
+            rule("**/*.evtx", "evtx", 10),
+            rule("**/logs/*.evtx;!**/archive/**", "live_logs", 20),
+        ];
+
+        let (first, summary) =
+            match_rules_to_files(&files, &rules, RuleMatchMode::FirstMatch).unwrap();
+        let tags: Vec<(i64, &str)> = first.iter().map(|m| (m.file_id, m.tag.as_str())).collect();
+        assert_eq!(tags, vec![(1, "live_logs"), (2, "evtx")]);
+        assert_eq!(summary.untagged, 1);
+
+        let (all, _) = match_rules_to_files(&files, &rules, RuleMatchMode::AllMatch).unwrap();
+        let tags: Vec<(i64, &str)> = all.iter().map(|m| (m.file_id, m.tag.as_str())).collect();
+        assert_eq!(tags, vec![(1, "live_logs"), (1, "evtx"), (2, "evtx")]);
+    }
+
+    #[test]
+    fn test_detected_type_matches_extension_rules() {
+        use crate::detect::ContentType;
+        use casparian_protocol::DetectionConfidence;
+
+        let detected = |id, rel_path, content_type, confidence| RuleApplyFile {
+            detection: Some(ContentDetection {
+                content_type,
+                confidence,
+            }),
+            ..file(id, rel_path)
+        };
+        let files = vec![
+            detected(
+                1,
+                "host1/logs/Security",
+                ContentType::Evtx,
+                DetectionConfidence::High,
+            ),
+            detected(
+                2,
+                "exports/report.txt",
+                ContentType::Csv { delimiter: b',' },
+                DetectionConfidence::Medium,
+            ),
+            detected(
+                3,
+                "exports/maybe.txt",
+                ContentType::Csv { delimiter: b',' },
+                DetectionConfidence::Low,
+            ),
+            detected(
+                4,
+                "archive/logs/old",
+                ContentType::Evtx,
+                DetectionConfidence::High,
+            ),
+        ];
+        let rules = vec![
+            rule("**/*.evtx;!archive/**", "evtx", 10),
+            rule("**/*.csv", "csv", 10),
+        ];
+
+        let (matches, summary) =
+            match_rules_to_files(&files, &rules, RuleMatchMode::FirstMatch).unwrap();
+        let tags: Vec<(i64, &str)> = matches
+            .iter()
+            .map(|m| (m.file_id, m.tag.as_str()))
+            .collect();
+        assert_eq!(tags, vec![(1, "evtx"), (2, "csv")]);
+        assert_eq!(summary.untagged, 2);
+    }
+}
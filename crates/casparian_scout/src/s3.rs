@@ -0,0 +1,398 @@
+//! S3 source listing.
+//!
+//! An S3 source's `path` is either `s3://bucket/prefix` or a bare key prefix
+//! inside the bucket named by `SourceType::S3`. The scanner lists objects
+//! page by page (ListObjectsV2) and persists each page like a walked batch:
+//!
+//! - `path` is the object URI, `rel_path` is the key below the prefix
+//! - `content_hash` carries the ETag, so an object rewritten with the same
+//!   size and mtime is still detected as changed
+//! - the continuation token is checkpointed in `scout_settings` after every
+//!   persisted page, so an interrupted scan resumes instead of relisting
+//!
+//! Credentials come from the source (`access_key`/`secret_key`), falling back
+//! to `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY`. The live client requires the
+//! `source-s3` feature; everything else here is backend-agnostic.
+
+use super::db::Database;
+use super::error::{Result, ScoutError};
+use super::file_uid::weak_uid_from_path_str;
+use super::types::{ScannedFile, Source, SourceId, SourceType};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// Bucket + key prefix for an S3 source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Location {
+    pub bucket: String,
+    pub prefix: String,
+}
+
+impl S3Location {
+    /// Resolve the bucket and prefix for an S3 source.
+    pub fn for_source(source: &Source) -> Result<Self> {
+        let SourceType::S3 { bucket, .. } = &source.source_type else {
+            return Err(ScoutError::Config(format!(
+                "Source '{}' is not an S3 source",
+                source.name
+            )));
+        };
+
+        let raw = source.path.trim();
+        let (bucket, prefix) = match raw.strip_prefix("s3://") {
+            Some(rest) => {
+                let rest = rest.trim_matches('/');
+                let (path_bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+                if !bucket.is_empty() && path_bucket != bucket {
+                    return Err(ScoutError::Config(format!(
+                        "Source path '{}' does not match configured bucket '{}'",
+                        raw, bucket
+                    )));
+                }
+                (path_bucket, prefix)
+            }
+            None => (bucket.as_str(), raw),
+        };
+
+        if bucket.is_empty() {
+            return Err(ScoutError::Config(format!(
+                "S3 source '{}' is missing a bucket name",
+                source.name
+            )));
+        }
+
+        Ok(Self {
+            bucket: bucket.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+        })
+    }
+
+    /// Prefix passed to ListObjectsV2.
+    ///
+    /// Ends with `/` so `data` does not also match keys under `database/`.
+    pub fn list_prefix(&self) -> String {
+        if self.prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", self.prefix)
+        }
+    }
+
+    /// Canonical `s3://` URI for an object key in this bucket.
+    pub fn object_uri(&self, key: &str) -> String {
+        format!("s3://{}/{}", self.bucket, key)
+    }
+}
+
+/// One listed object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Object {
+    pub key: String,
+    pub etag: Option<String>,
+    pub size: u64,
+    /// RFC 3339 timestamp as returned by the listing.
+    pub last_modified: String,
+}
+
+/// One ListObjectsV2 page.
+#[derive(Debug, Clone, Default)]
+pub struct S3ListPage {
+    pub objects: Vec<S3Object>,
+    /// Token for the next page; `None` on the last page.
+    pub next_token: Option<String>,
+}
+
+/// Minimal listing surface needed by the scanner.
+///
+/// Kept narrow so scans can be exercised without a live endpoint.
+pub trait ObjectLister {
+    fn list_page(&self, prefix: &str, continuation_token: Option<&str>) -> Result<S3ListPage>;
+}
+
+/// Build a live lister for an S3 source.
+#[cfg(feature = "source-s3")]
+pub fn connect(source: &Source) -> Result<Box<dyn ObjectLister>> {
+    let location = S3Location::for_source(source)?;
+    Ok(Box::new(RustS3Lister::connect(
+        &source.source_type,
+        &location,
+    )?))
+}
+
+/// Build a live lister for an S3 source.
+#[cfg(not(feature = "source-s3"))]
+pub fn connect(_source: &Source) -> Result<Box<dyn ObjectLister>> {
+    Err(ScoutError::Config(
+        "S3 source support is disabled (enable feature source-s3)".to_string(),
+    ))
+}
+
+#[cfg(feature = "source-s3")]
+struct RustS3Lister {
+    bucket: Box<s3::Bucket>,
+}
+
+#[cfg(feature = "source-s3")]
+impl RustS3Lister {
+    fn connect(source_type: &SourceType, location: &S3Location) -> Result<Self> {
+        let SourceType::S3 {
+            region,
+            access_key,
+            secret_key,
+            endpoint,
+            ..
+        } = source_type
+        else {
+            return Err(ScoutError::Config("Not an S3 source".to_string()));
+        };
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        let endpoint = endpoint.clone().or_else(|| env("AWS_ENDPOINT_URL"));
+        let region = match &endpoint {
+            Some(endpoint) => s3::Region::Custom {
+                region: region.clone(),
+                endpoint: endpoint.clone(),
+            },
+            None => region.parse().map_err(|e| {
+                ScoutError::Config(format!("Invalid S3 region '{}': {}", region, e))
+            })?,
+        };
+        let access_key = access_key.clone().or_else(|| env("AWS_ACCESS_KEY_ID"));
+        let secret_key = secret_key.clone().or_else(|| env("AWS_SECRET_ACCESS_KEY"));
+        let credentials = s3::creds::Credentials::new(
+            access_key.as_deref(),
+            secret_key.as_deref(),
+            None,
+            env("AWS_SESSION_TOKEN").as_deref(),
+            None,
+        )
+        .map_err(|e| ScoutError::Config(format!("Failed to resolve S3 credentials: {}", e)))?;
+
+        let mut bucket = s3::Bucket::new(&location.bucket, region, credentials).map_err(|e| {
+            ScoutError::Config(format!(
+                "Failed to configure S3 bucket '{}': {}",
+                location.bucket, e
+            ))
+        })?;
+        // Custom endpoints (MinIO etc.) almost always need path-style addressing.
+        if endpoint.is_some() {
+            bucket = bucket.with_path_style();
+        }
+        Ok(Self { bucket })
+    }
+}
+
+#[cfg(feature = "source-s3")]
+impl ObjectLister for RustS3Lister {
+    fn list_page(&self, prefix: &str, continuation_token: Option<&str>) -> Result<S3ListPage> {
+        let (result, _status) = self
+            .bucket
+            .list_page(
+                prefix.to_string(),
+                None,
+                continuation_token.map(str::to_string),
+                None,
+                None,
+            )
+            .map_err(|e| {
+                ScoutError::Io(std::io::Error::other(format!(
+                    "Failed to list s3://{}/{}: {}",
+                    self.bucket.name(),
+                    prefix,
+                    e
+                )))
+            })?;
+
+        let objects = result
+            .contents
+            .into_iter()
+            .map(|object| S3Object {
+                key: object.key,
+                etag: object.e_tag,
+                size: object.size,
+                last_modified: object.last_modified,
+            })
+            .collect();
+        let next_token = if result.is_truncated {
+            result.next_continuation_token
+        } else {
+            None
+        };
+        Ok(S3ListPage {
+            objects,
+            next_token,
+        })
+    }
+}
+
+/// Strip the quotes S3 wraps around ETags.
+pub fn normalize_etag(etag: &str) -> String {
+    etag.trim().trim_matches('"').to_string()
+}
+
+/// Convert a listed object to a `ScannedFile`.
+///
+/// Returns `None` for folder placeholder keys and keys outside the prefix.
+/// The file UID is derived from the object URI so it stays stable across
+/// rewrites; the ETag is stored as `content_hash` for change detection.
+pub fn object_to_scanned_file(
+    source: &Source,
+    location: &S3Location,
+    object: &S3Object,
+) -> Option<ScannedFile> {
+    if object.key.ends_with('/') {
+        return None;
+    }
+    let rel_path = object.key.strip_prefix(&location.list_prefix())?;
+    if rel_path.is_empty() {
+        return None;
+    }
+
+    let uri = location.object_uri(&object.key);
+    let mtime = DateTime::parse_from_rfc3339(&object.last_modified)
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or(0);
+    let mut file = ScannedFile::new(
+        source.workspace_id,
+        source.id,
+        &weak_uid_from_path_str(&uri),
+        &uri,
+        rel_path,
+        object.size,
+        mtime,
+    );
+    file.content_hash = object
+        .etag
+        .as_deref()
+        .map(normalize_etag)
+        .filter(|etag| !etag.is_empty());
+    Some(file)
+}
+
+/// Progress of an interrupted listing, stored in `scout_settings`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    /// Token for the first page not yet persisted.
+    pub continuation_token: String,
+    /// Start of the listing pass (ms); used for deleted-object detection.
+    pub started_at_ms: i64,
+    /// Objects persisted so far in this pass.
+    pub objects_seen: u64,
+}
+
+fn checkpoint_key(source_id: &SourceId) -> String {
+    format!("s3_scan_checkpoint:{}", source_id)
+}
+
+/// Load the checkpoint for a source, ignoring unreadable values.
+pub fn load_checkpoint(db: &Database, source_id: &SourceId) -> Result<Option<ScanCheckpoint>> {
+    let Some(raw) = db.get_setting(&checkpoint_key(source_id))? else {
+        return Ok(None);
+    };
+    match serde_json::from_str(&raw) {
+        Ok(checkpoint) => Ok(Some(checkpoint)),
+        Err(e) => {
+            tracing::warn!(source_id = %source_id, error = %e, "Ignoring unreadable S3 scan checkpoint");
+            Ok(None)
+        }
+    }
+}
+
+pub fn save_checkpoint(
+    db: &Database,
+    source_id: &SourceId,
+    checkpoint: &ScanCheckpoint,
+) -> Result<()> {
+    let raw = serde_json::to_string(checkpoint)?;
+    db.set_setting(&checkpoint_key(source_id), &raw)
+}
+
+pub fn clear_checkpoint(db: &Database, source_id: &SourceId) -> Result<()> {
+    db.delete_setting(&checkpoint_key(source_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::WorkspaceId;
+
+    fn s3_source(path: &str) -> Source {
+        Source {
+            workspace_id: WorkspaceId::new(),
+            id: SourceId::new(),
+            name: "bucket".to_string(),
+            source_type: SourceType::S3 {
+                region: "us-east-1".to_string(),
+                bucket: "data".to_string(),
+                access_key: None,
+                secret_key: None,
+                endpoint: None,
+            },
+            path: path.to_string(),
+            exec_path: None,
+            poll_interval_secs: 30,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_location_from_uri_and_prefix() {
+        let location = S3Location::for_source(&s3_source("s3://data/raw/2024/")).unwrap();
+        assert_eq!(location.bucket, "data");
+        assert_eq!(location.prefix, "raw/2024");
+        assert_eq!(location.list_prefix(), "raw/2024/");
+
+        let location = S3Location::for_source(&s3_source("raw")).unwrap();
+        assert_eq!(location.bucket, "data");
+        assert_eq!(location.list_prefix(), "raw/");
+
+        let location = S3Location::for_source(&s3_source("")).unwrap();
+        assert_eq!(location.list_prefix(), "");
+
+        assert!(S3Location::for_source(&s3_source("s3://other/raw")).is_err());
+    }
+
+    #[test]
+    fn test_object_to_scanned_file() {
+        let source = s3_source("s3://data/raw");
+        let location = S3Location::for_source(&source).unwrap();
+        let object = S3Object {
+            key: "raw/2024/orders.csv".to_string(),
+            etag: Some("\"abc123\"".to_string()),
+            size: 42,
+            last_modified: "2024-01-02T03:04:05.000Z".to_string(),
+        };
+
+        let file = object_to_scanned_file(&source, &location, &object).unwrap();
+        assert_eq!(file.path, "s3://data/raw/2024/orders.csv");
+        assert_eq!(file.rel_path, "2024/orders.csv");
+        assert_eq!(file.parent_path, "2024");
+        assert_eq!(file.extension.as_deref(), Some("csv"));
+        assert_eq!(file.content_hash.as_deref(), Some("abc123"));
+        assert_eq!(file.mtime, 1_704_164_645_000);
+
+        let marker = S3Object {
+            key: "raw/2024/".to_string(),
+            ..object
+        };
+        assert!(object_to_scanned_file(&source, &location, &marker).is_none());
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let db = Database::open_in_memory().unwrap();
+        let source_id = SourceId::new();
+        assert!(load_checkpoint(&db, &source_id).unwrap().is_none());
+
+        let checkpoint = ScanCheckpoint {
+            continuation_token: "token-2".to_string(),
+            started_at_ms: 1_000,
+            objects_seen: 10,
+        };
+        save_checkpoint(&db, &source_id, &checkpoint).unwrap();
+        assert_eq!(load_checkpoint(&db, &source_id).unwrap(), Some(checkpoint));
+
+        clear_checkpoint(&db, &source_id).unwrap();
+        assert!(load_checkpoint(&db, &source_id).unwrap().is_none());
+    }
+}
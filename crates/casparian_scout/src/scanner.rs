@@ -14,11 +14,12 @@
 use super::db::Database;
 use super::error::{Result, ScoutError};
 use super::file_uid::compute_file_uid;
+use super::s3::{self, ObjectLister, S3Location, ScanCheckpoint};
 use super::types::{ScanStats, ScannedFile, Source, SourceId, SourceType, WorkspaceId};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use ignore::WalkBuilder;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::mpsc;
 use std::sync::Arc;
@@ -494,6 +495,11 @@ impl Scanner {
         let _scan_guard = scan_span.enter();
         info!(source = %source.name, path = %source.path, "Starting streaming scan");
 
+        if let SourceType::S3 { .. } = source.source_type {
+            let lister = s3::connect(source)?;
+            return self.scan_object_store(source, lister.as_ref(), progress_tx, tag, cancel);
+        }
+
         let source_path = Path::new(&source.path);
         if !source_path.exists() {
             return Err(ScoutError::FileNotFound(source.path.clone()));
@@ -855,6 +861,291 @@ impl Scanner {
         })
     }
 
+    /// Scan an object-store source page by page.
+    ///
+    /// Each listed page is persisted before the next one is requested and the
+    /// continuation token is checkpointed, so an interrupted or failed scan
+    /// resumes from the last persisted page. Deleted objects are only marked
+    /// once a full listing pass completes.
+    pub(crate) fn scan_object_store(
+        &self,
+        source: &Source,
+        lister: &dyn ObjectLister,
+        progress_tx: Option<mpsc::Sender<ScanProgress>>,
+        tag: Option<&str>,
+        cancel: Option<ScanCancelToken>,
+    ) -> Result<ScanResult> {
+        let start = Instant::now();
+        let location = S3Location::for_source(source)?;
+        let list_prefix = location.list_prefix();
+
+        let (scan_start, mut token, mut objects_seen) =
+            match s3::load_checkpoint(&self.db, &source.id)? {
+                Some(checkpoint) => {
+                    info!(
+                        source = %source.name,
+                        objects_seen = checkpoint.objects_seen,
+                        "Resuming S3 listing from checkpoint"
+                    );
+                    let started_at = DateTime::from_timestamp_millis(checkpoint.started_at_ms)
+                        .unwrap_or_else(Utc::now);
+                    (
+                        started_at,
+                        Some(checkpoint.continuation_token),
+                        checkpoint.objects_seen,
+                    )
+                }
+                None => (Utc::now(), None, 0),
+            };
+
+        let mut stats = ScanStats::default();
+        loop {
+            if cancel.as_ref().is_some_and(|token| token.is_cancelled()) {
+                return Err(ScoutError::Cancelled);
+            }
+
+            let page = lister.list_page(&list_prefix, token.as_deref())?;
+            let batch: Vec<ScannedFile> = page
+                .objects
+                .iter()
+                .filter_map(|object| s3::object_to_scanned_file(source, &location, object))
+                .collect();
+            stats.files_discovered += batch.len() as u64;
+            stats.bytes_scanned += batch.iter().map(|file| file.size).sum::<u64>();
+
+            if !batch.is_empty() {
+                let batch_stats = Self::persist_batch_streaming(
+                    &self.db,
+                    &batch,
+                    tag,
+                    self.config.compute_stats,
+                )?;
+                stats.files_new += batch_stats.files_new;
+                stats.files_changed += batch_stats.files_changed;
+                stats.files_unchanged += batch_stats.files_unchanged;
+                stats.files_persisted += batch.len() as u64;
+                objects_seen += batch.len() as u64;
+            }
+
+            if let Some(tx) = progress_tx.as_ref() {
+                let elapsed = start.elapsed();
+                let _ = tx.send(ScanProgress {
+                    dirs_scanned: 0,
+                    files_found: stats.files_discovered as usize,
+                    files_persisted: stats.files_persisted as usize,
+                    current_dir: Some(location.object_uri(&list_prefix)),
+                    elapsed_ms: elapsed.as_millis() as u64,
+                    files_per_sec: stats.files_persisted as f64
+                        / elapsed.as_secs_f64().max(f64::EPSILON),
+                    stalled: false,
+                });
+            }
+
+            match page.next_token {
+                Some(next) => {
+                    s3::save_checkpoint(
+                        &self.db,
+                        &source.id,
+                        &ScanCheckpoint {
+                            continuation_token: next.clone(),
+                            started_at_ms: scan_start.timestamp_millis(),
+                            objects_seen,
+                        },
+                    )?;
+                    token = Some(next);
+                }
+                None => break,
+            }
+        }
+
+        s3::clear_checkpoint(&self.db, &source.id)?;
+        stats.files_deleted = self.db.mark_deleted_files(&source.id, scan_start)?;
+        stats.duration_ms = start.elapsed().as_millis() as u64;
+
+        if let Err(e) = self
+            .db
+            .update_source_file_count(&source.id, objects_seen as usize)
+        {
+            tracing::warn!(source_id = %source.id, error = %e, "Failed to update source file_count");
+        }
+        if let Err(e) = self.db.populate_folder_cache(&source.id) {
+            tracing::warn!(
+                source_id = %source.id,
+                error = %e,
+                "Failed to populate folder cache"
+            );
+        }
+
+        info!(
+            source = %source.name,
+            discovered = stats.files_discovered,
+            new = stats.files_new,
+            changed = stats.files_changed,
+            deleted = stats.files_deleted,
+            duration_ms = stats.duration_ms,
+            "S3 scan complete"
+        );
+        Ok(ScanResult {
+            stats,
+            errors: Vec::new(),
+        })
+    }
+
+    /// Rescan specific paths under a local source.
+    ///
+    /// Used by the watcher to push change notifications through the same
+    /// persist path as a full scan. Each path is re-stat'd: files are
+    /// upserted, directories are walked, and missing paths are marked
+    /// deleted. Paths outside the source or excluded by the scan config are
+    /// ignored.
+    pub fn scan_paths(
+        &self,
+        source: &Source,
+        paths: &[PathBuf],
+        tag: Option<&str>,
+    ) -> Result<ScanStats> {
+        let start = Instant::now();
+        let root = Path::new(&source.path);
+        let now = Utc::now();
+        let mut stats = ScanStats::default();
+        let mut batch = Vec::new();
+
+        for path in paths {
+            if self.is_excluded(root, path) {
+                continue;
+            }
+            match std::fs::metadata(path) {
+                Ok(metadata) if metadata.is_dir() => {
+                    stats.dirs_scanned += 1;
+                    let entries = walkdir::WalkDir::new(path)
+                        .follow_links(self.config.follow_symlinks)
+                        .into_iter()
+                        .filter_entry(|entry| !self.is_excluded(root, entry.path()));
+                    for entry in entries {
+                        let entry = match entry {
+                            Ok(entry) => entry,
+                            Err(e) => {
+                                stats.errors += 1;
+                                tracing::debug!(error = %e, "Failed to read watched directory entry");
+                                continue;
+                            }
+                        };
+                        if !entry.file_type().is_file() {
+                            continue;
+                        }
+                        match entry.metadata() {
+                            Ok(metadata) => batch.push(Self::scanned_file_for_path(
+                                source,
+                                root,
+                                entry.path(),
+                                &metadata,
+                                now,
+                            )),
+                            Err(_) => stats.errors += 1,
+                        }
+                    }
+                }
+                Ok(metadata) if metadata.is_file() => {
+                    batch.push(Self::scanned_file_for_path(
+                        source, root, path, &metadata, now,
+                    ));
+                }
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    let full_path = path.to_string_lossy();
+                    stats.files_deleted += self.db.mark_path_deleted(&source.id, &full_path)?;
+                }
+                Err(e) => {
+                    stats.errors += 1;
+                    tracing::debug!(path = %path.display(), error = %e, "Failed to stat watched path");
+                }
+            }
+        }
+
+        stats.files_discovered = batch.len() as u64;
+        for chunk in batch.chunks(self.config.batch_size.max(1)) {
+            let batch_stats =
+                Self::persist_batch_streaming(&self.db, chunk, tag, self.config.compute_stats)?;
+            stats.files_new += batch_stats.files_new;
+            stats.files_changed += batch_stats.files_changed;
+            stats.files_unchanged += batch_stats.files_unchanged;
+            stats.files_persisted += chunk.len() as u64;
+            stats.bytes_scanned += chunk.iter().map(|file| file.size).sum::<u64>();
+        }
+
+        if stats.files_new > 0 || stats.files_deleted > 0 {
+            if let Err(e) = self.db.populate_folder_cache(&source.id) {
+                tracing::warn!(
+                    source_id = %source.id,
+                    error = %e,
+                    "Failed to populate folder cache"
+                );
+            }
+        }
+
+        stats.duration_ms = start.elapsed().as_millis() as u64;
+        Ok(stats)
+    }
+
+    /// Whether a path falls outside `root` or matches the scan exclusions.
+    fn is_excluded(&self, root: &Path, path: &Path) -> bool {
+        let Ok(rel) = path.strip_prefix(root) else {
+            return true;
+        };
+        let path_str = path.to_string_lossy();
+        if self
+            .config
+            .exclude_path_patterns
+            .iter()
+            .any(|pattern| path_str.contains(pattern.as_str()))
+        {
+            return true;
+        }
+
+        let mut components = rel.components().peekable();
+        while let Some(component) = components.next() {
+            let name = component.as_os_str().to_string_lossy();
+            if !self.config.include_hidden && name.starts_with('.') {
+                return true;
+            }
+            let is_dir_component = components.peek().is_some();
+            if is_dir_component && self.config.exclude_dir_names.iter().any(|ex| *ex == name) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn scanned_file_for_path(
+        source: &Source,
+        root: &Path,
+        path: &Path,
+        metadata: &std::fs::Metadata,
+        now: DateTime<Utc>,
+    ) -> ScannedFile {
+        let rel_path = path
+            .strip_prefix(root)
+            .map(normalize_path_to_forward_slashes)
+            .unwrap_or_else(|_| normalize_path_to_forward_slashes(path));
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let uid = compute_file_uid(&source.source_type, path, metadata);
+        ScannedFile::from_parts_with_now(
+            source.workspace_id,
+            source.id,
+            uid.value,
+            path.to_string_lossy().into_owned(),
+            rel_path,
+            metadata.len(),
+            mtime,
+            now,
+        )
+    }
+
     /// Streaming parallel walk - sends batches to channel instead of collecting
     ///
     /// This is the GAP-006 fix: O(batch_size) memory instead of O(file_count).
@@ -1369,4 +1660,173 @@ mod tests {
         let pending = db.list_pending_files(&source.id, 10).unwrap();
         assert_eq!(pending.len(), 3);
     }
+
+    // ========================================================================
+    // S3 source tests
+    // ========================================================================
+
+    /// Serves fixed pages keyed by continuation token, optionally failing once.
+    struct FakeLister {
+        pages: Vec<s3::S3ListPage>,
+        fail_on_token: std::cell::RefCell<Option<String>>,
+        requested: std::cell::RefCell<Vec<Option<String>>>,
+    }
+
+    impl FakeLister {
+        fn new(pages: Vec<Vec<s3::S3Object>>) -> Self {
+            let count = pages.len();
+            let pages = pages
+                .into_iter()
+                .enumerate()
+                .map(|(idx, objects)| s3::S3ListPage {
+                    objects,
+                    next_token: (idx + 1 < count).then(|| format!("token-{}", idx + 1)),
+                })
+                .collect();
+            Self {
+                pages,
+                fail_on_token: std::cell::RefCell::new(None),
+                requested: std::cell::RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl ObjectLister for FakeLister {
+        fn list_page(&self, _prefix: &str, token: Option<&str>) -> Result<s3::S3ListPage> {
+            self.requested.borrow_mut().push(token.map(str::to_string));
+            if token.is_some() && self.fail_on_token.borrow().as_deref() == token {
+                self.fail_on_token.borrow_mut().take();
+                return Err(ScoutError::InvalidState("listing failed".to_string()));
+            }
+            let idx = match token {
+                None => 0,
+                Some(token) => token
+                    .strip_prefix("token-")
+                    .and_then(|idx| idx.parse::<usize>().ok())
+                    .unwrap(),
+            };
+            Ok(self.pages[idx].clone())
+        }
+    }
+
+    fn s3_object(key: &str, etag: &str) -> s3::S3Object {
+        s3::S3Object {
+            key: key.to_string(),
+            etag: Some(format!("\"{}\"", etag)),
+            size: 10,
+            last_modified: "2024-01-01T00:00:00.000Z".to_string(),
+        }
+    }
+
+    fn create_s3_env() -> (Database, Source) {
+        let db = Database::open_in_memory().unwrap();
+        let workspace_id = db.ensure_default_workspace().unwrap().id;
+        let source = Source {
+            workspace_id,
+            id: SourceId::new(),
+            name: "Bucket".to_string(),
+            source_type: SourceType::S3 {
+                region: "us-east-1".to_string(),
+                bucket: "data".to_string(),
+                access_key: None,
+                secret_key: None,
+                endpoint: None,
+            },
+            path: "s3://data/raw".to_string(),
+            exec_path: None,
+            poll_interval_secs: 30,
+            enabled: true,
+        };
+        db.upsert_source(&source).unwrap();
+        (db, source)
+    }
+
+    #[test]
+    fn test_s3_scan_lists_all_pages() {
+        let (db, source) = create_s3_env();
+        let lister = FakeLister::new(vec![
+            vec![s3_object("raw/a.csv", "a1"), s3_object("raw/sub/", "")],
+            vec![s3_object("raw/sub/b.csv", "b1")],
+        ]);
+
+        let scanner = Scanner::new(db.clone());
+        let result = scanner
+            .scan_object_store(&source, &lister, None, None, None)
+            .unwrap();
+        assert_eq!(result.stats.files_discovered, 2);
+        assert_eq!(result.stats.files_new, 2);
+
+        let files = db.list_pending_files(&source.id, 10).unwrap();
+        let file = files.iter().find(|f| f.rel_path == "sub/b.csv").unwrap();
+        assert_eq!(file.path, "s3://data/raw/sub/b.csv");
+        assert_eq!(file.content_hash.as_deref(), Some("b1"));
+        assert!(s3::load_checkpoint(&db, &source.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_s3_scan_detects_etag_change() {
+        let (db, source) = create_s3_env();
+        let scanner = Scanner::new(db.clone());
+
+        let lister = FakeLister::new(vec![vec![s3_object("raw/a.csv", "v1")]]);
+        scanner
+            .scan_object_store(&source, &lister, None, None, None)
+            .unwrap();
+
+        // Same size and LastModified, new content
+        let lister = FakeLister::new(vec![vec![s3_object("raw/a.csv", "v2")]]);
+        let result = scanner
+            .scan_object_store(&source, &lister, None, None, None)
+            .unwrap();
+        assert_eq!(result.stats.files_changed, 1);
+        assert_eq!(result.stats.files_unchanged, 0);
+    }
+
+    #[test]
+    fn test_s3_scan_resumes_from_checkpoint() {
+        let (db, source) = create_s3_env();
+        let scanner = Scanner::new(db.clone());
+
+        let lister = FakeLister::new(vec![
+            vec![
+                s3_object("raw/a.csv", "a1"),
+                s3_object("raw/gone.csv", "g1"),
+            ],
+            vec![s3_object("raw/b.csv", "b1")],
+        ]);
+        scanner
+            .scan_object_store(&source, &lister, None, None, None)
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+
+        // Second pass: gone.csv was removed, and page 2 fails the first time
+        let lister = FakeLister::new(vec![
+            vec![s3_object("raw/a.csv", "a1")],
+            vec![s3_object("raw/b.csv", "b1")],
+        ]);
+        *lister.fail_on_token.borrow_mut() = Some("token-1".to_string());
+        assert!(scanner
+            .scan_object_store(&source, &lister, None, None, None)
+            .is_err());
+        let checkpoint = s3::load_checkpoint(&db, &source.id).unwrap().unwrap();
+        assert_eq!(checkpoint.continuation_token, "token-1");
+        assert_eq!(checkpoint.objects_seen, 1);
+
+        lister.requested.borrow_mut().clear();
+        let result = scanner
+            .scan_object_store(&source, &lister, None, None, None)
+            .unwrap();
+        assert_eq!(
+            *lister.requested.borrow(),
+            vec![Some("token-1".to_string())]
+        );
+        assert_eq!(result.stats.files_deleted, 1);
+        assert!(s3::load_checkpoint(&db, &source.id).unwrap().is_none());
+
+        let deleted = db
+            .list_files_by_status(&source.workspace_id, FileStatus::Deleted, 10)
+            .unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].rel_path, "gone.csv");
+    }
 }
@@ -1,41 +1,69 @@
 //! File tagging based on patterns
 //!
-//! Matches files to tagging rules based on glob patterns.
-//! Returns the tag to assign to each file.
+//! Matches files to tagging rules based on glob patterns (with `!` exclusions,
+//! see `patterns::RulePattern`). Returns the tag(s) to assign to each file.
 
 use super::error::{Result, ScoutError};
-use super::types::{ScannedFile, TaggingRule, TaggingRuleId, WorkspaceId};
-use glob::Pattern;
+use super::patterns::RulePattern;
+use super::types::{RuleMatchMode, ScannedFile, TaggingRule, TaggingRuleId, WorkspaceId};
 
 /// Compiled tagging rule for efficient matching
 #[allow(dead_code)] // Used in tests
 struct CompiledRule {
     rule: TaggingRule,
-    pattern: Pattern,
+    pattern: RulePattern,
+}
+
+impl CompiledRule {
+    fn matches(&self, file: &ScannedFile) -> bool {
+        self.rule.workspace_id == file.workspace_id && self.pattern.is_match(&file.rel_path)
+    }
 }
 
 /// Tagger that matches files to tags based on patterns
 #[allow(dead_code)] // Used in tests
 pub struct Tagger {
     rules: Vec<CompiledRule>,
+    mode: RuleMatchMode,
 }
 
 #[allow(dead_code)] // Used in tests
 impl Tagger {
-    /// Create a new tagger with the given rules
-    /// Rules should be pre-sorted by priority (highest first)
+    /// Create a first-match tagger with the given rules.
+    /// Rules are evaluated by priority (highest first); ties keep input order.
     pub fn new(rules: Vec<TaggingRule>) -> Result<Self> {
+        Self::with_mode(rules, RuleMatchMode::FirstMatch)
+    }
+
+    /// Create a tagger with an explicit match mode.
+    pub fn with_mode(rules: Vec<TaggingRule>, mode: RuleMatchMode) -> Result<Self> {
         let compiled: Result<Vec<CompiledRule>> = rules
             .into_iter()
             .filter(|r| r.enabled)
             .map(|rule| {
-                let pattern = Pattern::new(&rule.pattern)
+                let pattern = RulePattern::parse(&rule.pattern)
                     .map_err(|e| ScoutError::Pattern(format!("{}: {}", rule.pattern, e)))?;
                 Ok(CompiledRule { rule, pattern })
             })
             .collect();
+        let mut rules = compiled?;
+        rules.sort_by_key(|r| std::cmp::Reverse(r.rule.priority));
+
+        Ok(Self { rules, mode })
+    }
 
-        Ok(Self { rules: compiled? })
+    pub fn mode(&self) -> RuleMatchMode {
+        self.mode
+    }
+
+    /// Rules whose tags apply to a file under the tagger's match mode
+    /// (at most one for first-match, every match for all-match).
+    pub fn rules_for_file(&self, file: &ScannedFile) -> Vec<&TaggingRule> {
+        let matching = self.rules.iter().filter(|cr| cr.matches(file));
+        match self.mode {
+            RuleMatchMode::FirstMatch => matching.take(1).map(|cr| &cr.rule).collect(),
+            RuleMatchMode::AllMatch => matching.map(|cr| &cr.rule).collect(),
+        }
     }
 
     /// Find the tag for a file based on matching rules
@@ -43,9 +71,7 @@ impl Tagger {
     pub fn get_tag(&self, file: &ScannedFile) -> Option<&str> {
         self.rules
             .iter()
-            .find(|cr| {
-                cr.rule.workspace_id == file.workspace_id && cr.pattern.matches(&file.rel_path)
-            })
+            .find(|cr| cr.matches(file))
             .map(|cr| cr.rule.tag.as_str())
     }
 
@@ -54,9 +80,7 @@ impl Tagger {
     pub fn get_tag_with_rule_id(&self, file: &ScannedFile) -> Option<(&str, TaggingRuleId)> {
         self.rules
             .iter()
-            .find(|cr| {
-                cr.rule.workspace_id == file.workspace_id && cr.pattern.matches(&file.rel_path)
-            })
+            .find(|cr| cr.matches(file))
             .map(|cr| (cr.rule.tag.as_str(), cr.rule.id))
     }
 
@@ -64,18 +88,14 @@ impl Tagger {
     pub fn match_file(&self, file: &ScannedFile) -> Vec<&TaggingRule> {
         self.rules
             .iter()
-            .filter(|cr| {
-                cr.rule.workspace_id == file.workspace_id && cr.pattern.matches(&file.rel_path)
-            })
+            .filter(|cr| cr.matches(file))
             .map(|cr| &cr.rule)
             .collect()
     }
 
     /// Check if any rule matches a file
     pub fn has_match(&self, file: &ScannedFile) -> bool {
-        self.rules.iter().any(|cr| {
-            cr.rule.workspace_id == file.workspace_id && cr.pattern.matches(&file.rel_path)
-        })
+        self.rules.iter().any(|cr| cr.matches(file))
     }
 
     /// Get all rules
@@ -246,6 +266,37 @@ mod tests {
         assert_eq!(tagger.get_tag(&file), Some("specific_data"));
     }
 
+    #[test]
+    fn test_exclusions_and_all_match_mode() {
+        let workspace_id = WorkspaceId::new();
+        let source_id = SourceId::new();
+        let rules = vec![
+            create_test_rule(TaggingRuleId::new(), &workspace_id, "**/*.evtx", "evtx", 10),
+            create_test_rule(
+                TaggingRuleId::new(),
+                &workspace_id,
+                "**/logs/*.evtx;!**/archive/**",
+                "live_logs",
+                20,
+            ),
+        ];
+
+        let first = Tagger::new(rules.clone()).unwrap();
+        let live = create_test_file(workspace_id, &source_id, "dc01/logs/security.evtx");
+        let archived = create_test_file(workspace_id, &source_id, "dc01/archive/logs/old.evtx");
+        assert_eq!(first.get_tag(&live), Some("live_logs"));
+        assert_eq!(first.get_tag(&archived), Some("evtx"));
+        assert_eq!(first.rules_for_file(&live).len(), 1);
+
+        let all = Tagger::with_mode(rules, RuleMatchMode::AllMatch).unwrap();
+        let tags: Vec<&str> = all
+            .rules_for_file(&live)
+            .iter()
+            .map(|r| r.tag.as_str())
+            .collect();
+        assert_eq!(tags, vec!["live_logs", "evtx"]);
+    }
+
     #[test]
     fn test_no_match() {
         let workspace_id = WorkspaceId::new();
@@ -356,9 +356,52 @@ pub enum SourceType {
         access_key: Option<String>,
         #[serde(default)]
         secret_key: Option<String>,
+        /// Custom endpoint for S3-compatible stores (MinIO, Ceph RGW)
+        #[serde(default)]
+        endpoint: Option<String>,
     },
 }
 
+/// How a source picks up changes between explicit scans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchMode {
+    /// Realtime for local disks, polling for network mounts and object stores.
+    #[default]
+    Auto,
+    /// Filesystem notifications (inotify/FSEvents/ReadDirectoryChangesW).
+    Realtime,
+    /// Full rescan every `poll_interval_secs`.
+    Poll,
+}
+
+impl WatchMode {
+    pub const ALL: &'static [WatchMode] = &[WatchMode::Auto, WatchMode::Realtime, WatchMode::Poll];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchMode::Auto => "auto",
+            WatchMode::Realtime => "realtime",
+            WatchMode::Poll => "poll",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "auto" => Some(WatchMode::Auto),
+            "realtime" | "notify" => Some(WatchMode::Realtime),
+            "poll" | "polling" => Some(WatchMode::Poll),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WatchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 // ============================================================================
 // Tagging Rule Types
 // ============================================================================
@@ -376,7 +419,9 @@ pub struct TaggingRule {
     pub name: String,
     /// Workspace ID this rule applies to
     pub workspace_id: WorkspaceId,
-    /// Glob pattern to match files (e.g., "*.csv", "data/**/*.json")
+    /// Glob pattern to match files (e.g., "*.csv", "data/**/*.json").
+    /// Several globs may be joined with `;`; globs prefixed with `!` exclude
+    /// (e.g., "**/logs/*.evtx;!**/archive/**"). See `patterns::RulePattern`.
     pub pattern: String,
     /// Tag to assign to matching files
     pub tag: String,
@@ -386,6 +431,42 @@ pub struct TaggingRule {
     pub enabled: bool,
 }
 
+/// How a workspace's tagging rules combine when several match one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleMatchMode {
+    /// Highest-priority matching rule assigns its tag; the rest are skipped.
+    #[default]
+    FirstMatch,
+    /// Every matching rule assigns its tag (in priority order).
+    AllMatch,
+}
+
+impl RuleMatchMode {
+    pub const ALL: &'static [RuleMatchMode] = &[RuleMatchMode::FirstMatch, RuleMatchMode::AllMatch];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleMatchMode::FirstMatch => "first_match",
+            RuleMatchMode::AllMatch => "all_match",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "first_match" | "first" => Some(RuleMatchMode::FirstMatch),
+            "all_match" | "all" => Some(RuleMatchMode::AllMatch),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RuleMatchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// How a tag was assigned to a file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -1049,6 +1130,29 @@ mod tests {
         assert_eq!(FileStatus::parse("Tagged"), Some(FileStatus::Tagged));
     }
 
+    #[test]
+    fn test_rule_match_mode_roundtrip() {
+        for mode in RuleMatchMode::ALL {
+            assert_eq!(RuleMatchMode::parse(mode.as_str()), Some(*mode));
+        }
+        assert_eq!(
+            RuleMatchMode::parse("all-match"),
+            Some(RuleMatchMode::AllMatch)
+        );
+        assert_eq!(RuleMatchMode::default(), RuleMatchMode::FirstMatch);
+        assert!(RuleMatchMode::parse("some").is_none());
+    }
+
+    #[test]
+    fn test_watch_mode_roundtrip() {
+        for mode in WatchMode::ALL {
+            assert_eq!(WatchMode::parse(mode.as_str()), Some(*mode));
+        }
+        assert_eq!(WatchMode::parse("Polling"), Some(WatchMode::Poll));
+        assert_eq!(WatchMode::default(), WatchMode::Auto);
+        assert!(WatchMode::parse("sometimes").is_none());
+    }
+
     #[test]
     fn test_tagging_rule_serialization() {
         let workspace_id = WorkspaceId::new();
@@ -0,0 +1,563 @@
+//! Realtime source watching
+//!
+//! Keeps sources in sync between explicit scans. Each source resolves to one
+//! of two strategies (see `WatchMode`):
+//!
+//! - **Realtime**: filesystem notifications from the `notify` crate
+//!   (inotify/FSEvents/ReadDirectoryChangesW). Events are coalesced per path
+//!   and flushed through `Scanner::scan_paths` once the source has been quiet
+//!   for `debounce` (or `max_delay` has passed under a steady stream of
+//!   events).
+//! - **Poll**: a full `Scanner` pass every `poll_interval_secs`.
+//!
+//! `WatchMode::Auto` picks realtime for local disks and polling for SMB/S3
+//! sources and network mounts, where notifications are unreliable. A source
+//! whose notification watch cannot be registered also falls back to polling.
+
+use super::db::Database;
+use super::error::Result;
+use super::scanner::{ScanCancelToken, ScanConfig, Scanner};
+use super::types::{ScanStats, Source, SourceId, SourceType, WatchMode};
+use notify::{EventKind, RecursiveMode, Watcher as _};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Poll interval used when a source has `poll_interval_secs = 0`.
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 30;
+
+/// Filesystem types treated as network mounts by `WatchMode::Auto`.
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "afs",
+    "9p",
+    "davfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+    "fuse.s3fs",
+    "fuse.gcsfuse",
+];
+
+/// Timing configuration for the watcher.
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    /// Quiet period after the last event before a source is flushed
+    pub debounce: Duration,
+    /// Upper bound on how long events may accumulate before a flush
+    pub max_delay: Duration,
+    /// How often the loop wakes to check cancellation, flushes, and polls
+    pub tick: Duration,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(500),
+            max_delay: Duration::from_secs(5),
+            tick: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Update reported by `SourceWatcher::run`.
+#[derive(Debug, Clone)]
+pub enum WatchEvent {
+    /// Source is being watched with the given (resolved) mode
+    Watching {
+        source_id: SourceId,
+        mode: WatchMode,
+    },
+    /// Realtime watching failed to start; the source is polled instead
+    FellBackToPoll { source_id: SourceId, reason: String },
+    /// Changes were persisted
+    Synced {
+        source_id: SourceId,
+        /// Whether this was a full scan (initial, poll, or rescan) or a path flush
+        full_scan: bool,
+        stats: ScanStats,
+    },
+    /// A scan or flush failed; watching continues
+    Failed {
+        source_id: SourceId,
+        message: String,
+    },
+}
+
+/// Resolve `WatchMode::Auto` to a concrete strategy for a source.
+pub fn effective_watch_mode(source: &Source, configured: WatchMode) -> WatchMode {
+    match (&source.source_type, configured) {
+        // Object stores have no notification channel we can use.
+        (SourceType::S3 { .. }, _) => WatchMode::Poll,
+        (SourceType::Smb { .. }, WatchMode::Auto) => WatchMode::Poll,
+        (SourceType::Local, WatchMode::Auto) => {
+            if is_network_mount(Path::new(&source.path)) {
+                WatchMode::Poll
+            } else {
+                WatchMode::Realtime
+            }
+        }
+        (_, mode) => mode,
+    }
+}
+
+/// Whether `path` lives on a network filesystem (Linux only).
+pub fn is_network_mount(path: &Path) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        match std::fs::read_to_string("/proc/mounts") {
+            Ok(mounts) => mount_fs_type(&path, &mounts)
+                .is_some_and(|fs_type| NETWORK_FS_TYPES.contains(&fs_type)),
+            Err(_) => false,
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Filesystem type of the longest mount point containing `path`.
+///
+/// `mounts` is in `/proc/mounts` format; spaces in mount points are `\040`.
+fn mount_fs_type<'a>(path: &Path, mounts: &'a str) -> Option<&'a str> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then_some((mount_point.len(), fs_type))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fs_type)| fs_type)
+}
+
+/// Paths touched since the last flush for one source.
+///
+/// Only the set of paths is kept: the flush re-stats each one, so a file
+/// created, modified, and removed within one window collapses to a single
+/// delete.
+#[derive(Debug, Default)]
+struct PendingChanges {
+    paths: HashSet<PathBuf>,
+    rescan: bool,
+    first_at: Option<Instant>,
+    last_at: Option<Instant>,
+}
+
+impl PendingChanges {
+    fn touch(&mut self, path: PathBuf, now: Instant) {
+        self.paths.insert(path);
+        self.mark(now);
+    }
+
+    fn request_rescan(&mut self, now: Instant) {
+        self.rescan = true;
+        self.mark(now);
+    }
+
+    fn mark(&mut self, now: Instant) {
+        self.first_at.get_or_insert(now);
+        self.last_at = Some(now);
+    }
+
+    fn is_due(&self, now: Instant, config: &WatchConfig) -> bool {
+        match (self.first_at, self.last_at) {
+            (Some(first), Some(last)) => {
+                now.duration_since(last) >= config.debounce
+                    || now.duration_since(first) >= config.max_delay
+            }
+            _ => false,
+        }
+    }
+
+    fn take(&mut self) -> (Vec<PathBuf>, bool) {
+        let mut paths: Vec<PathBuf> = self.paths.drain().collect();
+        paths.sort();
+        let rescan = std::mem::take(&mut self.rescan);
+        self.first_at = None;
+        self.last_at = None;
+        (paths, rescan)
+    }
+}
+
+struct WatchedSource {
+    source: Source,
+    mode: WatchMode,
+    poll_interval: Duration,
+    next_poll: Instant,
+    pending: PendingChanges,
+}
+
+/// Keeps a set of sources in sync until cancelled.
+pub struct SourceWatcher {
+    db: Database,
+    scan_config: ScanConfig,
+    config: WatchConfig,
+}
+
+impl SourceWatcher {
+    pub fn new(db: Database, scan_config: ScanConfig) -> Self {
+        Self {
+            db,
+            scan_config,
+            config: WatchConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: WatchConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Resolved watch mode for a source (stored setting, then `Auto` rules).
+    pub fn resolve_mode(&self, source: &Source) -> Result<WatchMode> {
+        let configured = self.db.get_watch_mode(&source.id)?;
+        Ok(effective_watch_mode(source, configured))
+    }
+
+    /// Watch `sources` until `cancel` fires.
+    ///
+    /// Each source gets a full scan first so changes made while nothing was
+    /// watching are picked up. Scan failures are reported through `on_event`
+    /// and do not stop the loop.
+    pub fn run(
+        &self,
+        sources: &[Source],
+        tag: Option<&str>,
+        cancel: ScanCancelToken,
+        mut on_event: impl FnMut(&WatchEvent),
+    ) -> Result<()> {
+        let scanner = Scanner::with_config(self.db.clone(), self.scan_config.clone());
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut notifier = None;
+        let mut watched = Vec::with_capacity(sources.len());
+
+        for source in sources {
+            let mut mode = self.resolve_mode(source)?;
+            if mode == WatchMode::Realtime {
+                if let Err(reason) = watch_path(&mut notifier, &event_tx, &source.path) {
+                    warn!(source = %source.name, %reason, "Realtime watch unavailable, polling");
+                    on_event(&WatchEvent::FellBackToPoll {
+                        source_id: source.id,
+                        reason,
+                    });
+                    mode = WatchMode::Poll;
+                }
+            }
+            info!(source = %source.name, mode = %mode, "Watching source");
+            on_event(&WatchEvent::Watching {
+                source_id: source.id,
+                mode,
+            });
+
+            let secs = match source.poll_interval_secs {
+                0 => DEFAULT_POLL_INTERVAL_SECS,
+                secs => secs,
+            };
+            let poll_interval = Duration::from_secs(secs);
+            self.full_scan(&scanner, source, tag, &cancel, &mut on_event);
+            watched.push(WatchedSource {
+                source: source.clone(),
+                mode,
+                poll_interval,
+                next_poll: Instant::now() + poll_interval,
+                pending: PendingChanges::default(),
+            });
+        }
+
+        while !cancel.is_cancelled() {
+            match event_rx.recv_timeout(self.config.tick) {
+                Ok(event) => record_event(&mut watched, event, Instant::now()),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+            // Drain whatever else arrived so bursts coalesce into one flush.
+            while let Ok(event) = event_rx.try_recv() {
+                record_event(&mut watched, event, Instant::now());
+            }
+
+            let now = Instant::now();
+            for entry in watched.iter_mut() {
+                if cancel.is_cancelled() {
+                    break;
+                }
+                match entry.mode {
+                    WatchMode::Poll => {
+                        if now >= entry.next_poll {
+                            self.full_scan(&scanner, &entry.source, tag, &cancel, &mut on_event);
+                            entry.next_poll = Instant::now() + entry.poll_interval;
+                        }
+                    }
+                    _ => {
+                        if !entry.pending.is_due(now, &self.config) {
+                            continue;
+                        }
+                        let (paths, rescan) = entry.pending.take();
+                        if rescan {
+                            self.full_scan(&scanner, &entry.source, tag, &cancel, &mut on_event);
+                        } else {
+                            match scanner.scan_paths(&entry.source, &paths, tag) {
+                                Ok(stats) => on_event(&WatchEvent::Synced {
+                                    source_id: entry.source.id,
+                                    full_scan: false,
+                                    stats,
+                                }),
+                                Err(e) => on_event(&WatchEvent::Failed {
+                                    source_id: entry.source.id,
+                                    message: e.to_string(),
+                                }),
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        drop(notifier);
+        Ok(())
+    }
+
+    fn full_scan(
+        &self,
+        scanner: &Scanner,
+        source: &Source,
+        tag: Option<&str>,
+        cancel: &ScanCancelToken,
+        on_event: &mut impl FnMut(&WatchEvent),
+    ) {
+        match scanner.scan_with_cancel(source, None, tag, Some(cancel.clone())) {
+            Ok(result) => on_event(&WatchEvent::Synced {
+                source_id: source.id,
+                full_scan: true,
+                stats: result.stats,
+            }),
+            Err(e) => {
+                if !cancel.is_cancelled() {
+                    on_event(&WatchEvent::Failed {
+                        source_id: source.id,
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Register a recursive watch, creating the shared notifier on first use.
+fn watch_path(
+    notifier: &mut Option<notify::RecommendedWatcher>,
+    event_tx: &mpsc::Sender<notify::Result<notify::Event>>,
+    path: &str,
+) -> std::result::Result<(), String> {
+    if notifier.is_none() {
+        let created = notify::recommended_watcher(event_tx.clone()).map_err(|e| e.to_string())?;
+        *notifier = Some(created);
+    }
+    match notifier.as_mut() {
+        Some(watcher) => watcher
+            .watch(Path::new(path), RecursiveMode::Recursive)
+            .map_err(|e| e.to_string()),
+        None => Err("notifier unavailable".to_string()),
+    }
+}
+
+/// Route a notification to the realtime source(s) whose root contains it.
+fn record_event(watched: &mut [WatchedSource], event: notify::Result<notify::Event>, now: Instant) {
+    let realtime = |entry: &&mut WatchedSource| entry.mode == WatchMode::Realtime;
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            // Errors (e.g. queue overflow) may have dropped events: rescan.
+            warn!(error = %e, "Filesystem watch error, scheduling rescan");
+            for entry in watched.iter_mut().filter(realtime) {
+                entry.pending.request_rescan(now);
+            }
+            return;
+        }
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+
+    for entry in watched.iter_mut().filter(realtime) {
+        let root = Path::new(&entry.source.path);
+        if event.need_rescan() {
+            entry.pending.request_rescan(now);
+            continue;
+        }
+        for path in event.paths.iter().filter(|path| path.starts_with(root)) {
+            entry.pending.touch(path.clone(), now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::WorkspaceId;
+
+    fn source(source_type: SourceType, path: &str) -> Source {
+        Source {
+            workspace_id: WorkspaceId::new(),
+            id: SourceId::new(),
+            name: "src".to_string(),
+            source_type,
+            path: path.to_string(),
+            exec_path: None,
+            poll_interval_secs: 30,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_effective_watch_mode() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let local = source(SourceType::Local, &tmp.path().to_string_lossy());
+        assert_eq!(
+            effective_watch_mode(&local, WatchMode::Poll),
+            WatchMode::Poll
+        );
+        assert_eq!(
+            effective_watch_mode(&local, WatchMode::Realtime),
+            WatchMode::Realtime
+        );
+
+        let smb = source(
+            SourceType::Smb {
+                username: None,
+                password: None,
+            },
+            "/mnt/share",
+        );
+        assert_eq!(effective_watch_mode(&smb, WatchMode::Auto), WatchMode::Poll);
+        assert_eq!(
+            effective_watch_mode(&smb, WatchMode::Realtime),
+            WatchMode::Realtime
+        );
+
+        let s3 = source(
+            SourceType::S3 {
+                region: "us-east-1".to_string(),
+                bucket: "data".to_string(),
+                access_key: None,
+                secret_key: None,
+                endpoint: None,
+            },
+            "s3://data",
+        );
+        assert_eq!(
+            effective_watch_mode(&s3, WatchMode::Realtime),
+            WatchMode::Poll
+        );
+    }
+
+    #[test]
+    fn test_mount_fs_type_picks_longest_mount() {
+        let mounts = "\
+/dev/sda1 / ext4 rw 0 0
+//nas/share /mnt/nas cifs rw 0 0
+server:/export /mnt/nas/nfs\\040data nfs4 rw 0 0
+";
+        assert_eq!(mount_fs_type(Path::new("/home/me"), mounts), Some("ext4"));
+        assert_eq!(
+            mount_fs_type(Path::new("/mnt/nas/reports"), mounts),
+            Some("cifs")
+        );
+        assert_eq!(
+            mount_fs_type(Path::new("/mnt/nas/nfs data/x.csv"), mounts),
+            Some("nfs4")
+        );
+        // Component-wise prefix: /mnt/nasty is not under /mnt/nas
+        assert_eq!(mount_fs_type(Path::new("/mnt/nasty"), mounts), Some("ext4"));
+    }
+
+    #[test]
+    fn test_pending_changes_debounce_and_coalesce() {
+        let config = WatchConfig {
+            debounce: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1_000),
+            tick: Duration::from_millis(10),
+        };
+        let start = Instant::now();
+        let mut pending = PendingChanges::default();
+        assert!(!pending.is_due(start, &config));
+
+        pending.touch(PathBuf::from("/data/a.csv"), start);
+        pending.touch(
+            PathBuf::from("/data/a.csv"),
+            start + Duration::from_millis(50),
+        );
+        pending.touch(
+            PathBuf::from("/data/b.csv"),
+            start + Duration::from_millis(90),
+        );
+        assert!(!pending.is_due(start + Duration::from_millis(150), &config));
+        assert!(pending.is_due(start + Duration::from_millis(190), &config));
+
+        let (paths, rescan) = pending.take();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("/data/a.csv"), PathBuf::from("/data/b.csv")]
+        );
+        assert!(!rescan);
+        assert!(!pending.is_due(start + Duration::from_secs(10), &config));
+
+        // A steady stream still flushes once max_delay has passed.
+        for ms in (0..1_000).step_by(50) {
+            pending.touch(
+                PathBuf::from("/data/c.csv"),
+                start + Duration::from_millis(ms),
+            );
+        }
+        assert!(pending.is_due(start + Duration::from_millis(1_000), &config));
+    }
+
+    #[test]
+    fn test_scan_paths_upserts_and_deletes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = Database::open_in_memory().unwrap();
+        let workspace_id = db.ensure_default_workspace().unwrap().id;
+        let mut src = source(SourceType::Local, &tmp.path().to_string_lossy());
+        src.workspace_id = workspace_id;
+        db.upsert_source(&src).unwrap();
+
+        let file = tmp.path().join("a.csv");
+        std::fs::write(&file, "a,b\n").unwrap();
+        std::fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        std::fs::write(tmp.path().join("sub/b.csv"), "c,d\n").unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        std::fs::write(tmp.path().join(".git/HEAD"), "ref").unwrap();
+
+        let scanner = Scanner::new(db.clone());
+        let stats = scanner
+            .scan_paths(
+                &src,
+                &[
+                    file.clone(),
+                    tmp.path().join("sub"),
+                    tmp.path().join(".git/HEAD"),
+                ],
+                None,
+            )
+            .unwrap();
+        assert_eq!(stats.files_new, 2);
+
+        std::fs::remove_file(&file).unwrap();
+        std::fs::remove_dir_all(tmp.path().join("sub")).unwrap();
+        let stats = scanner
+            .scan_paths(&src, &[file, tmp.path().join("sub")], None)
+            .unwrap();
+        assert_eq!(stats.files_deleted, 2);
+    }
+}
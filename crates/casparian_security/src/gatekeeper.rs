@@ -5,6 +5,9 @@
 //! - Import of banned modules
 //! - Use of restricted language features
 //!
+//! The rules come from a [`GatekeeperPolicy`]: a profile's built-in rules,
+//! optionally adjusted by an operator policy file (see [`crate::policy`]).
+//!
 //! **Design Philosophy:**
 //! Static analysis in Rust, not runtime validation in Python.
 //! Zero-dependency on Python interpreter for security checks.
@@ -12,28 +15,87 @@
 use anyhow::{Context, Result};
 use rustpython_ast::Visitor;
 use rustpython_parser::{ast, Parse};
-use std::collections::HashSet;
-
-/// Banned modules that plugins are not allowed to import (standard profile).
-const BANNED_MODULES: &[&str] = &[
-    "os",
-    "subprocess",
-    "sys",
-    "shutil",
-    "socket",
-    "ctypes",
-    "multiprocessing",
+use serde::{Deserialize, Serialize};
+
+use crate::policy::GatekeeperPolicy;
+
+/// Names of Python builtin functions, checked against a builtin whitelist.
+const PYTHON_BUILTINS: &[&str] = &[
+    "abs",
+    "aiter",
+    "all",
+    "anext",
+    "any",
+    "ascii",
+    "bin",
+    "bool",
+    "breakpoint",
+    "bytearray",
+    "bytes",
+    "callable",
+    "chr",
+    "classmethod",
+    "compile",
+    "complex",
+    "delattr",
+    "dict",
+    "dir",
+    "divmod",
+    "enumerate",
+    "eval",
+    "exec",
+    "filter",
+    "float",
+    "format",
+    "frozenset",
+    "getattr",
+    "globals",
+    "hasattr",
+    "hash",
+    "help",
+    "hex",
+    "id",
+    "input",
+    "int",
+    "isinstance",
+    "issubclass",
+    "iter",
+    "len",
+    "list",
+    "locals",
+    "map",
+    "max",
+    "memoryview",
+    "min",
+    "next",
+    "object",
+    "oct",
+    "open",
+    "ord",
+    "pow",
+    "print",
+    "property",
+    "range",
+    "repr",
+    "reversed",
+    "round",
+    "set",
+    "setattr",
+    "slice",
+    "sorted",
+    "staticmethod",
+    "str",
+    "sum",
+    "super",
+    "tuple",
+    "type",
+    "vars",
+    "zip",
     "__import__",
-    "importlib",
 ];
 
-/// Modules that trigger warnings (dfir profile).
-const DFIR_WARN_MODULES: &[&str] = &["subprocess", "socket", "multiprocessing"];
-
-/// Banned modules for dfir profile (kept as errors).
-const DFIR_BANNED_MODULES: &[&str] = &["shutil", "ctypes", "__import__", "importlib"];
-
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum GatekeeperProfile {
     Standard,
     Dfir,
@@ -47,8 +109,7 @@ pub struct GatekeeperReport {
 
 /// Gatekeeper validates Python source code for security violations
 pub struct Gatekeeper {
-    banned_modules: HashSet<String>,
-    warn_modules: HashSet<String>,
+    policy: GatekeeperPolicy,
 }
 
 impl Default for Gatekeeper {
@@ -65,16 +126,17 @@ impl Gatekeeper {
 
     /// Create a new Gatekeeper with a specific profile.
     pub fn with_profile(profile: GatekeeperProfile) -> Self {
-        match profile {
-            GatekeeperProfile::Standard => Self {
-                banned_modules: BANNED_MODULES.iter().map(|s| s.to_string()).collect(),
-                warn_modules: HashSet::new(),
-            },
-            GatekeeperProfile::Dfir => Self {
-                banned_modules: DFIR_BANNED_MODULES.iter().map(|s| s.to_string()).collect(),
-                warn_modules: DFIR_WARN_MODULES.iter().map(|s| s.to_string()).collect(),
-            },
-        }
+        Self::with_policy(GatekeeperPolicy::for_profile(profile))
+    }
+
+    /// Create a new Gatekeeper enforcing an effective policy.
+    pub fn with_policy(policy: GatekeeperPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// The rules this Gatekeeper enforces.
+    pub fn policy(&self) -> &GatekeeperPolicy {
+        &self.policy
     }
 
     /// Validate Python source code
@@ -101,7 +163,7 @@ impl Gatekeeper {
         let ast = ast::Suite::parse(source_code, "<plugin>")
             .context("Failed to parse Python source code")?;
 
-        let mut visitor = GatekeeperVisitor::new(&self.banned_modules, &self.warn_modules);
+        let mut visitor = GatekeeperVisitor::new(&self.policy);
         for stmt in ast {
             visitor.visit_stmt(stmt);
         }
@@ -114,22 +176,56 @@ impl Gatekeeper {
 }
 
 struct GatekeeperVisitor<'a> {
-    banned_modules: &'a HashSet<String>,
-    warn_modules: &'a HashSet<String>,
+    policy: &'a GatekeeperPolicy,
+    depth: usize,
+    depth_exceeded: bool,
     errors: Vec<String>,
     warnings: Vec<String>,
 }
 
 impl<'a> GatekeeperVisitor<'a> {
-    fn new(banned_modules: &'a HashSet<String>, warn_modules: &'a HashSet<String>) -> Self {
+    fn new(policy: &'a GatekeeperPolicy) -> Self {
         Self {
-            banned_modules,
-            warn_modules,
+            policy,
+            depth: 0,
+            depth_exceeded: false,
             errors: Vec::new(),
             warnings: Vec::new(),
         }
     }
 
+    fn enter_node(&mut self) {
+        self.depth += 1;
+        if let Some(max_depth) = self.policy.max_ast_depth {
+            if self.depth > max_depth && !self.depth_exceeded {
+                self.depth_exceeded = true;
+                self.errors
+                    .push(format!("AST depth exceeds policy limit of {}", max_depth));
+            }
+        }
+    }
+
+    fn check_call(&mut self, func: &ast::Expr) {
+        let Some(name) = call_name(func) else {
+            return;
+        };
+        if let Some(allowed) = &self.policy.allowed_builtins {
+            if PYTHON_BUILTINS.contains(&name.as_str()) && !allowed.contains(&name) {
+                self.errors
+                    .push(format!("Builtin not allowed by policy: '{}'", name));
+            }
+        }
+        if let Some(pattern) = self
+            .policy
+            .banned_calls
+            .iter()
+            .find(|pattern| glob_match(pattern, &name))
+        {
+            self.errors
+                .push(format!("Banned call: '{}' (matches '{}')", name, pattern));
+        }
+    }
+
     fn check_import(&mut self, module_name: &str, context: &str) {
         if let Some(level) = self.violation_level(module_name) {
             let message = match level {
@@ -157,10 +253,14 @@ impl<'a> GatekeeperVisitor<'a> {
     }
 
     fn violation_level(&self, module_name: &str) -> Option<ViolationLevel> {
-        if self.banned_modules.contains(module_name) {
+        let policy = self.policy;
+        if policy.allowed_modules.contains(module_name) {
+            return None;
+        }
+        if policy.banned_modules.contains(module_name) {
             return Some(ViolationLevel::Error);
         }
-        if self.warn_modules.contains(module_name) {
+        if policy.warn_modules.contains(module_name) {
             return Some(ViolationLevel::Warning);
         }
         let base = if module_name.ends_with(".__import__") {
@@ -168,10 +268,13 @@ impl<'a> GatekeeperVisitor<'a> {
         } else {
             module_name.split('.').next().unwrap_or(module_name)
         };
-        if self.banned_modules.contains(base) {
+        if policy.allowed_modules.contains(base) {
+            return None;
+        }
+        if policy.banned_modules.contains(base) {
             return Some(ViolationLevel::Error);
         }
-        if self.warn_modules.contains(base) {
+        if policy.warn_modules.contains(base) {
             return Some(ViolationLevel::Warning);
         }
         None
@@ -226,7 +329,52 @@ fn dynamic_import_name(func: &ast::Expr) -> Option<String> {
     }
 }
 
+/// Dotted name of a called function (`eval`, `os.path.join`), if the callee
+/// is a plain name or attribute chain.
+fn call_name(func: &ast::Expr) -> Option<String> {
+    match func {
+        ast::Expr::Name(name) => Some(name.id.to_string()),
+        ast::Expr::Attribute(attr) => {
+            let value = call_name(attr.value.as_ref())?;
+            Some(format!("{}.{}", value, attr.attr.as_str()))
+        }
+        _ => None,
+    }
+}
+
+/// Match `name` against a pattern where `*` matches any run of characters.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
 impl<'a> Visitor for GatekeeperVisitor<'a> {
+    fn visit_stmt(&mut self, node: ast::Stmt) {
+        self.enter_node();
+        self.generic_visit_stmt(node);
+        self.depth -= 1;
+    }
+
+    fn visit_expr(&mut self, node: ast::Expr) {
+        self.enter_node();
+        self.generic_visit_expr(node);
+        self.depth -= 1;
+    }
+
     fn visit_stmt_import(&mut self, node: ast::StmtImport) {
         for alias in &node.names {
             self.check_import(alias.name.as_str(), "import");
@@ -243,6 +391,7 @@ impl<'a> Visitor for GatekeeperVisitor<'a> {
 
     fn visit_expr_call(&mut self, node: ast::ExprCall) {
         self.check_dynamic_import(&node.func);
+        self.check_call(&node.func);
         self.generic_visit_expr_call(node);
     }
 }
@@ -386,6 +535,86 @@ def parse(file_path):
             .any(|msg| msg.contains("Warning import: 'import subprocess'")));
     }
 
+    fn policy_gatekeeper(toml: &str, plugin: &str) -> Gatekeeper {
+        let config = crate::policy::GatekeeperPolicyConfig::from_toml_str(toml).unwrap();
+        Gatekeeper::with_policy(config.resolve(plugin, GatekeeperProfile::Standard))
+    }
+
+    #[test]
+    fn test_policy_allows_and_denies_imports() {
+        let gatekeeper = policy_gatekeeper(
+            "[defaults]\nallow_imports = [\"sys\"]\ndeny_imports = [\"pickle\"]\n",
+            "p",
+        );
+        let report = gatekeeper
+            .analyze("import sys\nimport sys.path\nimport pickle\nimport os\n")
+            .unwrap();
+        assert_eq!(
+            report.errors,
+            vec![
+                "Banned import: 'import pickle'".to_string(),
+                "Banned import: 'import os'".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_policy_banned_calls_and_builtins() {
+        let gatekeeper = policy_gatekeeper(
+            r#"
+[defaults]
+allowed_builtins = ["len", "print"]
+banned_calls = ["*.unlink", "pd.read_*"]
+"#,
+            "p",
+        );
+        let code = r#"
+def parse(path):
+    print(len(path))
+    eval("1")
+    path.unlink()
+    pd.read_csv(path)
+    pd.DataFrame()
+"#;
+        let report = gatekeeper.analyze(code).unwrap();
+        assert_eq!(
+            report.errors,
+            vec![
+                "Builtin not allowed by policy: 'eval'".to_string(),
+                "Banned call: 'path.unlink' (matches '*.unlink')".to_string(),
+                "Banned call: 'pd.read_csv' (matches 'pd.read_*')".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_policy_max_ast_depth() {
+        let code = "def parse(x):\n    if x:\n        return [[[x]]]\n";
+        // FunctionDef > If > Return > List > List > List > Name
+        let report = policy_gatekeeper("[defaults]\nmax_ast_depth = 6\n", "p")
+            .analyze(code)
+            .unwrap();
+        assert_eq!(
+            report.errors,
+            vec!["AST depth exceeds policy limit of 6".to_string()]
+        );
+        let report = policy_gatekeeper("[defaults]\nmax_ast_depth = 7\n", "p")
+            .analyze(code)
+            .unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("eval", "eval"));
+        assert!(!glob_match("eval", "evaluate"));
+        assert!(glob_match("os.*", "os.system"));
+        assert!(glob_match("*.unlink", "path.unlink"));
+        assert!(!glob_match("*.unlink", "unlink"));
+        assert!(glob_match("a*b*c", "a-b-b-c"));
+        assert!(!glob_match("a*b*c", "a-c"));
+    }
+
     #[test]
     fn test_invalid_python_syntax() {
         let gatekeeper = Gatekeeper::new();
@@ -0,0 +1,189 @@
+//! Ed25519 publisher keys for signed deploys
+//!
+//! Publishers sign the artifact hash of a plugin with their Ed25519 key; the
+//! Sentinel only installs a deploy whose signature verifies against a key in
+//! its trusted keyring. Keys are base64 (32 bytes), one per file:
+//!
+//! ```text
+//! ~/.casparian_flow/keys/publishers/
+//!   alice.pub   # trusted public key for signer "alice"
+//!   alice.key   # alice's signing key (only on alice's machine, mode 0600)
+//! ```
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Keyring subdirectory under the keys directory
+pub const PUBLISHERS_DIR: &str = "publishers";
+
+/// Trusted publisher keyring: `<keys_dir>/publishers`
+pub fn publishers_dir(keys_dir: &Path) -> PathBuf {
+    keys_dir.join(PUBLISHERS_DIR)
+}
+
+/// Signer ids name key files, so they must be plain file stems.
+pub fn validate_signer_id(signer_id: &str) -> Result<()> {
+    let valid = !signer_id.is_empty()
+        && signer_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !signer_id.starts_with('.');
+    if !valid {
+        anyhow::bail!(
+            "Invalid signer id '{}': use letters, digits, '_', '-' or '.'",
+            signer_id
+        );
+    }
+    Ok(())
+}
+
+/// Generate a publisher key pair and write `<signer_id>.key` (owner-only on
+/// Unix) and `<signer_id>.pub` under `dir`. Returns the base64 public key.
+pub fn generate_publisher_key(dir: &Path, signer_id: &str) -> Result<String> {
+    validate_signer_id(signer_id)?;
+    let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+    let public_key = general_purpose::STANDARD.encode(signing_key.verifying_key().as_bytes());
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let secret_path = dir.join(format!("{}.key", signer_id));
+    std::fs::write(
+        &secret_path,
+        general_purpose::STANDARD.encode(signing_key.to_bytes()),
+    )
+    .with_context(|| format!("Failed to write {}", secret_path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&secret_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    let public_path = dir.join(format!("{}.pub", signer_id));
+    std::fs::write(&public_path, format!("{}\n", public_key))
+        .with_context(|| format!("Failed to write {}", public_path.display()))?;
+    Ok(public_key)
+}
+
+/// Load `<signer_id>.key` from `dir`, if this machine has one.
+pub fn load_signing_key(dir: &Path, signer_id: &str) -> Result<Option<SigningKey>> {
+    validate_signer_id(signer_id)?;
+    let path = dir.join(format!("{}.key", signer_id));
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let bytes: [u8; 32] = decode_fixed(raw.trim())
+        .with_context(|| format!("Invalid signing key in {}", path.display()))?;
+    Ok(Some(SigningKey::from_bytes(&bytes)))
+}
+
+/// Sign an artifact hash. Returns the base64 signature.
+pub fn sign_artifact(signing_key: &SigningKey, artifact_hash: &str) -> String {
+    general_purpose::STANDARD.encode(signing_key.sign(artifact_hash.as_bytes()).to_bytes())
+}
+
+/// Public keys of the publishers this machine trusts, keyed by signer id.
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    keys: BTreeMap<String, VerifyingKey>,
+}
+
+impl Keyring {
+    /// Load every `<signer_id>.pub` in `dir`. A missing directory is an empty
+    /// keyring; a malformed key file is an error.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut keys = BTreeMap::new();
+        if !dir.exists() {
+            return Ok(Self { keys });
+        }
+        let entries =
+            std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pub") {
+                continue;
+            }
+            let Some(signer_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let raw = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let bytes: [u8; 32] = decode_fixed(raw.trim())
+                .with_context(|| format!("Invalid public key in {}", path.display()))?;
+            let key = VerifyingKey::from_bytes(&bytes)
+                .map_err(|_| anyhow::anyhow!("Invalid public key in {}", path.display()))?;
+            keys.insert(signer_id.to_string(), key);
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, signer_id: &str) -> bool {
+        self.keys.contains_key(signer_id)
+    }
+
+    /// Verify `signature` (base64) over `artifact_hash` by a trusted signer.
+    pub fn verify(&self, signer_id: &str, artifact_hash: &str, signature: &str) -> Result<()> {
+        let key = self.keys.get(signer_id).ok_or_else(|| {
+            anyhow::anyhow!("Signer '{}' is not in the trusted keyring", signer_id)
+        })?;
+        let bytes: [u8; 64] = decode_fixed(signature.trim()).context("Invalid signature")?;
+        key.verify_strict(artifact_hash.as_bytes(), &Signature::from_bytes(&bytes))
+            .map_err(|_| {
+                anyhow::anyhow!("Signature by '{}' does not match the artifact", signer_id)
+            })
+    }
+}
+
+fn decode_fixed<const N: usize>(encoded: &str) -> Result<[u8; N]> {
+    let bytes = general_purpose::STANDARD
+        .decode(encoded)
+        .context("not valid base64")?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("expected {} bytes, got {}", N, bytes.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let temp = tempfile::tempdir().unwrap();
+        generate_publisher_key(temp.path(), "alice").unwrap();
+        let signing_key = load_signing_key(temp.path(), "alice").unwrap().unwrap();
+        let keyring = Keyring::load(temp.path()).unwrap();
+        assert!(keyring.contains("alice"));
+
+        let signature = sign_artifact(&signing_key, "abc123");
+        keyring.verify("alice", "abc123", &signature).unwrap();
+
+        // Tampered artifact, unknown signer, garbage signature
+        assert!(keyring.verify("alice", "abc124", &signature).is_err());
+        assert!(keyring.verify("bob", "abc123", &signature).is_err());
+        assert!(keyring.verify("alice", "abc123", "not base64!").is_err());
+    }
+
+    #[test]
+    fn test_missing_keyring_is_empty() {
+        let temp = tempfile::tempdir().unwrap();
+        let keyring = Keyring::load(&temp.path().join("missing")).unwrap();
+        assert!(keyring.is_empty());
+        assert!(load_signing_key(temp.path(), "alice").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_validate_signer_id() {
+        assert!(validate_signer_id("alice.smith-2").is_ok());
+        assert!(validate_signer_id("").is_err());
+        assert!(validate_signer_id("../alice").is_err());
+        assert!(validate_signer_id(".hidden").is_err());
+    }
+}
@@ -2,10 +2,16 @@
 //!
 //! Provides:
 //! - **Gatekeeper**: AST-based Python code validation
+//! - **Policy**: Operator-configurable Gatekeeper rules with per-plugin overrides
 //! - **Signing**: SHA256 hashing for content identity
+//! - **Keyring**: Ed25519 publisher keys for signed deploys
 
 pub mod gatekeeper;
+pub mod keyring;
+pub mod policy;
 pub mod signing;
 
 pub use gatekeeper::{Gatekeeper, GatekeeperProfile, GatekeeperReport};
+pub use keyring::Keyring;
+pub use policy::{GatekeeperPolicy, GatekeeperPolicyConfig, PolicyRules};
 pub use signing::sha256;
@@ -0,0 +1,270 @@
+//! Gatekeeper policy configuration
+//!
+//! Operators tune the Gatekeeper rules with a policy file (TOML, or JSON when
+//! the file ends in `.json`). The `[defaults]` table applies to every
+//! plugin; a `[plugins.<name>]` table layers overrides for one plugin on top:
+//!
+//! ```toml
+//! [defaults]
+//! profile = "standard"
+//! allow_imports = ["sys"]
+//! deny_imports = ["requests"]
+//! max_ast_depth = 200
+//! allowed_builtins = ["len", "range", "open", "print"]
+//! banned_calls = ["eval", "exec", "*.unlink"]
+//!
+//! [plugins.evtx_parser]
+//! profile = "dfir"
+//! allow_imports = ["subprocess"]
+//! ```
+//!
+//! Resolving a config for a plugin yields a [`GatekeeperPolicy`]: the
+//! effective rule set the Gatekeeper enforces, which is also recorded with
+//! the publish so reviewers can see what a plugin was checked against.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::gatekeeper::GatekeeperProfile;
+
+/// Banned modules that plugins are not allowed to import (standard profile).
+const BANNED_MODULES: &[&str] = &[
+    "os",
+    "subprocess",
+    "sys",
+    "shutil",
+    "socket",
+    "ctypes",
+    "multiprocessing",
+    "__import__",
+    "importlib",
+];
+
+/// Modules that trigger warnings (dfir profile).
+const DFIR_WARN_MODULES: &[&str] = &["subprocess", "socket", "multiprocessing"];
+
+/// Banned modules for dfir profile (kept as errors).
+const DFIR_BANNED_MODULES: &[&str] = &["shutil", "ctypes", "__import__", "importlib"];
+
+/// Effective Gatekeeper rules for one plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatekeeperPolicy {
+    pub profile: GatekeeperProfile,
+    /// Imports rejected as errors (a module also bans its submodules)
+    pub banned_modules: BTreeSet<String>,
+    /// Imports reported as warnings
+    pub warn_modules: BTreeSet<String>,
+    /// Imports always accepted, overriding the two lists above
+    pub allowed_modules: BTreeSet<String>,
+    /// Deepest statement/expression nesting accepted
+    pub max_ast_depth: Option<usize>,
+    /// When set, calls to builtins outside this list are rejected
+    pub allowed_builtins: Option<BTreeSet<String>>,
+    /// Call patterns rejected as errors (`*` matches any characters)
+    pub banned_calls: Vec<String>,
+}
+
+impl GatekeeperPolicy {
+    /// Built-in rules of a profile, before any policy file is applied.
+    pub fn for_profile(profile: GatekeeperProfile) -> Self {
+        let (banned, warn) = match profile {
+            GatekeeperProfile::Standard => (BANNED_MODULES, &[][..]),
+            GatekeeperProfile::Dfir => (DFIR_BANNED_MODULES, DFIR_WARN_MODULES),
+        };
+        Self {
+            profile,
+            banned_modules: banned.iter().map(|s| s.to_string()).collect(),
+            warn_modules: warn.iter().map(|s| s.to_string()).collect(),
+            allowed_modules: BTreeSet::new(),
+            max_ast_depth: None,
+            allowed_builtins: None,
+            banned_calls: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, rules: &PolicyRules) {
+        for module in &rules.allow_imports {
+            self.banned_modules.remove(module);
+            self.warn_modules.remove(module);
+            self.allowed_modules.insert(module.clone());
+        }
+        for module in &rules.warn_imports {
+            self.allowed_modules.remove(module);
+            self.banned_modules.remove(module);
+            self.warn_modules.insert(module.clone());
+        }
+        for module in &rules.deny_imports {
+            self.allowed_modules.remove(module);
+            self.warn_modules.remove(module);
+            self.banned_modules.insert(module.clone());
+        }
+        if rules.max_ast_depth.is_some() {
+            self.max_ast_depth = rules.max_ast_depth;
+        }
+        if let Some(builtins) = &rules.allowed_builtins {
+            self.allowed_builtins = Some(builtins.iter().cloned().collect());
+        }
+        for pattern in &rules.banned_calls {
+            if !self.banned_calls.contains(pattern) {
+                self.banned_calls.push(pattern.clone());
+            }
+        }
+    }
+}
+
+impl Default for GatekeeperPolicy {
+    fn default() -> Self {
+        Self::for_profile(GatekeeperProfile::Standard)
+    }
+}
+
+/// One layer of policy rules (the defaults or a plugin override).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyRules {
+    /// Profile to start from; overrides the caller's default
+    pub profile: Option<GatekeeperProfile>,
+    pub allow_imports: Vec<String>,
+    pub warn_imports: Vec<String>,
+    pub deny_imports: Vec<String>,
+    pub max_ast_depth: Option<usize>,
+    /// Builtin whitelist; replaces any whitelist from an earlier layer
+    pub allowed_builtins: Option<Vec<String>>,
+    /// Added to the banned-call patterns of earlier layers
+    pub banned_calls: Vec<String>,
+}
+
+/// Parsed Gatekeeper policy file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GatekeeperPolicyConfig {
+    /// Rules applied to every plugin
+    pub defaults: PolicyRules,
+    /// Per-plugin overrides, keyed by plugin name
+    pub plugins: BTreeMap<String, PolicyRules>,
+}
+
+impl GatekeeperPolicyConfig {
+    /// Load a policy file; `.json` files are parsed as JSON, anything else as TOML.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read Gatekeeper policy: {}", path.display()))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let parsed = if is_json {
+            Self::from_json_str(&contents)
+        } else {
+            Self::from_toml_str(&contents)
+        };
+        parsed.with_context(|| format!("Invalid Gatekeeper policy: {}", path.display()))
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    pub fn from_json_str(contents: &str) -> Result<Self> {
+        Ok(serde_json::from_str(contents)?)
+    }
+
+    /// Effective policy for `plugin_name`: the profile's built-in rules, then
+    /// `defaults`, then the plugin's override. A profile named in the
+    /// config replaces `default_profile`.
+    pub fn resolve(
+        &self,
+        plugin_name: &str,
+        default_profile: GatekeeperProfile,
+    ) -> GatekeeperPolicy {
+        let plugin_rules = self.plugins.get(plugin_name);
+        let profile = plugin_rules
+            .and_then(|rules| rules.profile)
+            .or(self.defaults.profile)
+            .unwrap_or(default_profile);
+
+        let mut policy = GatekeeperPolicy::for_profile(profile);
+        policy.apply(&self.defaults);
+        if let Some(rules) = plugin_rules {
+            policy.apply(rules);
+        }
+        policy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"
+[defaults]
+allow_imports = ["sys"]
+deny_imports = ["requests"]
+max_ast_depth = 50
+allowed_builtins = ["len", "print"]
+banned_calls = ["eval"]
+
+[plugins.evtx_parser]
+profile = "dfir"
+allow_imports = ["subprocess"]
+allowed_builtins = ["len", "open"]
+banned_calls = ["*.unlink"]
+"#;
+
+    #[test]
+    fn test_resolve_layers_defaults_and_plugin_rules() {
+        let config = GatekeeperPolicyConfig::from_toml_str(POLICY).unwrap();
+
+        let policy = config.resolve("csv_parser", GatekeeperProfile::Standard);
+        assert_eq!(policy.profile, GatekeeperProfile::Standard);
+        assert!(!policy.banned_modules.contains("sys"));
+        assert!(policy.allowed_modules.contains("sys"));
+        assert!(policy.banned_modules.contains("requests"));
+        assert!(policy.banned_modules.contains("os"));
+        assert_eq!(policy.max_ast_depth, Some(50));
+        assert_eq!(policy.banned_calls, vec!["eval".to_string()]);
+
+        let policy = config.resolve("evtx_parser", GatekeeperProfile::Standard);
+        assert_eq!(policy.profile, GatekeeperProfile::Dfir);
+        assert!(!policy.banned_modules.contains("os"));
+        assert!(!policy.warn_modules.contains("subprocess"));
+        assert!(policy.warn_modules.contains("socket"));
+        assert_eq!(
+            policy.allowed_builtins,
+            Some(["len", "open"].iter().map(|s| s.to_string()).collect())
+        );
+        assert_eq!(
+            policy.banned_calls,
+            vec!["eval".to_string(), "*.unlink".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_empty_config_matches_profile() {
+        let config = GatekeeperPolicyConfig::default();
+        assert_eq!(
+            config.resolve("any", GatekeeperProfile::Dfir),
+            GatekeeperPolicy::for_profile(GatekeeperProfile::Dfir)
+        );
+    }
+
+    #[test]
+    fn test_load_json_and_reject_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.json");
+        std::fs::write(
+            &path,
+            r#"{"defaults": {"deny_imports": ["pickle"]}, "plugins": {"p": {"max_ast_depth": 10}}}"#,
+        )
+        .unwrap();
+        let config = GatekeeperPolicyConfig::load(&path).unwrap();
+        assert_eq!(config.defaults.deny_imports, vec!["pickle".to_string()]);
+        assert_eq!(config.plugins["p"].max_ast_depth, Some(10));
+
+        let path = dir.path().join("policy.toml");
+        std::fs::write(&path, "[defaults]\ndeny_import = [\"pickle\"]\n").unwrap();
+        let err = GatekeeperPolicyConfig::load(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("deny_import"), "{:#}", err);
+    }
+}
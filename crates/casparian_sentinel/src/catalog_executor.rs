@@ -10,6 +10,8 @@ use tracing::{info, warn};
 pub struct CatalogIntent {
     pub view_name: String,
     pub parquet_glob: PathBuf,
+    /// Read `col=value/` directories as columns (partitioned Parquet sinks)
+    pub hive_partitioning: bool,
 }
 
 pub struct CatalogExecutor {
@@ -34,14 +36,17 @@ impl CatalogExecutor {
 }
 
 fn run_catalog_thread(query_catalog_path: PathBuf, rx: Receiver<CatalogIntent>) {
-    let mut pending: HashMap<String, PathBuf> = HashMap::new();
+    let mut pending: HashMap<String, (PathBuf, bool)> = HashMap::new();
     let mut last_flush = Instant::now();
     let flush_interval = Duration::from_millis(500);
 
     loop {
         match rx.recv_timeout(Duration::from_millis(250)) {
             Ok(intent) => {
-                pending.insert(intent.view_name, intent.parquet_glob);
+                pending.insert(
+                    intent.view_name,
+                    (intent.parquet_glob, intent.hive_partitioning),
+                );
             }
             Err(mpsc::RecvTimeoutError::Timeout) => {}
             Err(mpsc::RecvTimeoutError::Disconnected) => break,
@@ -62,7 +67,10 @@ fn run_catalog_thread(query_catalog_path: PathBuf, rx: Receiver<CatalogIntent>)
     }
 }
 
-fn apply_updates(query_catalog_path: &PathBuf, views: &HashMap<String, PathBuf>) -> anyhow::Result<()> {
+fn apply_updates(
+    query_catalog_path: &PathBuf,
+    views: &HashMap<String, (PathBuf, bool)>,
+) -> anyhow::Result<()> {
     if let Some(parent) = query_catalog_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
@@ -81,11 +89,16 @@ fn apply_updates(query_catalog_path: &PathBuf, views: &HashMap<String, PathBuf>)
         catalog_conn.execute("CREATE SCHEMA IF NOT EXISTS quarantine", &[])?;
     }
 
-    for (view_name, pattern) in views {
+    for (view_name, (pattern, hive_partitioning)) in views {
         let path_literal = escape_sql_literal(&pattern.to_string_lossy());
+        let options = if *hive_partitioning {
+            ", hive_partitioning = true"
+        } else {
+            ""
+        };
         let sql = format!(
-            "CREATE OR REPLACE VIEW {} AS SELECT * FROM parquet_scan('{}')",
-            view_name, path_literal
+            "CREATE OR REPLACE VIEW {} AS SELECT * FROM parquet_scan('{}'{})",
+            view_name, path_literal, options
         );
         catalog_conn.execute(&sql, &[])?;
     }
@@ -0,0 +1,350 @@
+//! Compaction of small Parquet outputs.
+//!
+//! Finds the local Parquet directories an output topic was written to (from
+//! its job artifacts), lets `casparian_sinks::compaction` coalesce the small
+//! files there, and records every replaced file in the state store, which
+//! also carries the job's lineage over to the compacted file.
+//!
+//! [`compact_topic`] compacts one topic on request. [`run_due_compactions`]
+//! compacts the topics whose policy (`casparian_state_store::compaction`) is
+//! met: at least `min_files` small files (16 by default), or `min_bytes` of
+//! them, in one directory. The sentinel runs it periodically.
+
+use anyhow::Result;
+use casparian_db::DbConnection;
+use casparian_sinks::compaction::{
+    self as files, CompactedFile, CompactionGroup, CompactionOptions, DEFAULT_TARGET_FILE_BYTES,
+};
+use casparian_state_store::compaction::{self, CompactionPolicy, CompactionRecord};
+use casparian_state_store::retention::{self, OutputArtifact};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Small files that trigger a policy without `min_files` or `min_bytes`.
+pub const DEFAULT_MIN_FILES: u32 = 16;
+
+/// What one compaction run does.
+#[derive(Debug, Clone, Default)]
+pub struct CompactOptions {
+    /// Report the groups only; rewrite nothing
+    pub dry_run: bool,
+    /// Overrides the policy's target file size
+    pub target_file_bytes: Option<u64>,
+}
+
+/// Compaction of one output directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryCompaction {
+    pub output_name: String,
+    pub dir: PathBuf,
+    /// Groups planned (on a dry run) or compacted
+    pub groups: Vec<CompactionGroup>,
+    pub compacted: Vec<CompactedFile>,
+}
+
+/// Outcome of a compaction run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompactionReport {
+    pub dry_run: bool,
+    pub directories: Vec<DirectoryCompaction>,
+    pub errors: Vec<String>,
+}
+
+impl CompactionReport {
+    /// Small files compacted (or, on a dry run, to compact).
+    pub fn files_compacted(&self) -> usize {
+        let dirs = self.directories.iter();
+        if self.dry_run {
+            dirs.flat_map(|dir| &dir.groups)
+                .map(|group| group.sources.len())
+                .sum()
+        } else {
+            dirs.flat_map(|dir| &dir.compacted)
+                .map(|file| file.sources.len())
+                .sum()
+        }
+    }
+}
+
+/// Compact the small files of `output_name` now, whatever its policy says.
+pub fn compact_topic(
+    conn: &DbConnection,
+    output_name: &str,
+    options: &CompactOptions,
+) -> Result<CompactionReport> {
+    compaction::init_schema(conn)?;
+    let policies = compaction::list_policies(conn)?;
+    let artifacts = retention::output_artifacts(conn)?;
+    let mut report = CompactionReport {
+        dry_run: options.dry_run,
+        ..CompactionReport::default()
+    };
+    let policy = compaction::policy_for(&policies, output_name);
+    let file_options = file_options(policy, options);
+    for dir in output_dirs(conn, &artifacts, output_name)? {
+        compact_dir(
+            conn,
+            &artifacts,
+            output_name,
+            &dir,
+            &file_options,
+            options.dry_run,
+            |_| true,
+            &mut report,
+        );
+    }
+    Ok(report)
+}
+
+/// Compact every topic whose policy is met.
+pub fn run_due_compactions(conn: &DbConnection) -> Result<CompactionReport> {
+    compaction::init_schema(conn)?;
+    let policies = compaction::list_policies(conn)?;
+    let mut report = CompactionReport::default();
+    if policies.is_empty() {
+        return Ok(report);
+    }
+    let artifacts = retention::output_artifacts(conn)?;
+    let topics: BTreeSet<&str> = artifacts
+        .iter()
+        .map(|artifact| artifact.output_name.as_str())
+        .collect();
+    let options = CompactOptions::default();
+    for topic in topics {
+        let Some(policy) = compaction::policy_for(&policies, topic) else {
+            continue;
+        };
+        let file_options = file_options(Some(policy), &options);
+        for dir in output_dirs(conn, &artifacts, topic)? {
+            compact_dir(
+                conn,
+                &artifacts,
+                topic,
+                &dir,
+                &file_options,
+                false,
+                |groups| policy_met(policy, groups),
+                &mut report,
+            );
+        }
+    }
+    Ok(report)
+}
+
+fn file_options(policy: Option<&CompactionPolicy>, options: &CompactOptions) -> CompactionOptions {
+    let target = options
+        .target_file_bytes
+        .or_else(|| policy.and_then(|policy| policy.target_file_bytes))
+        .unwrap_or(DEFAULT_TARGET_FILE_BYTES);
+    CompactionOptions::for_target(target)
+}
+
+fn policy_met(policy: &CompactionPolicy, groups: &[CompactionGroup]) -> bool {
+    let files: usize = groups.iter().map(|group| group.sources.len()).sum();
+    let bytes: u64 = groups.iter().map(CompactionGroup::bytes).sum();
+    if policy.min_files.is_none() && policy.min_bytes.is_none() {
+        return files >= DEFAULT_MIN_FILES as usize;
+    }
+    policy
+        .min_files
+        .is_some_and(|min_files| files >= min_files as usize)
+        || policy.min_bytes.is_some_and(|min_bytes| bytes >= min_bytes)
+}
+
+fn compact_dir(
+    conn: &DbConnection,
+    artifacts: &[OutputArtifact],
+    output_name: &str,
+    dir: &Path,
+    options: &CompactionOptions,
+    dry_run: bool,
+    due: impl Fn(&[CompactionGroup]) -> bool,
+    report: &mut CompactionReport,
+) {
+    if !dry_run {
+        if let Err(err) = files::recover_compactions(dir) {
+            report.errors.push(format!("{}: {}", dir.display(), err));
+            return;
+        }
+    }
+    let groups = match files::plan_compaction(dir, output_name, options) {
+        Ok(groups) => groups,
+        Err(err) => {
+            report.errors.push(format!("{}: {}", dir.display(), err));
+            return;
+        }
+    };
+    if groups.is_empty() || !due(&groups) {
+        return;
+    }
+
+    let mut compacted = Vec::new();
+    if !dry_run {
+        let artifact_uris = artifact_uris_by_file(artifacts, output_name);
+        for group in &groups {
+            let file = match files::compact_group(dir, group, options) {
+                Ok(file) => file,
+                Err(err) => {
+                    warn!("Compaction of {} failed: {}", group.target.display(), err);
+                    report
+                        .errors
+                        .push(format!("{}: {}", group.target.display(), err));
+                    continue;
+                }
+            };
+            if let Err(err) = record_compacted(conn, &artifact_uris, output_name, &file) {
+                report
+                    .errors
+                    .push(format!("{}: {}", file.path.display(), err));
+            }
+            compacted.push(file);
+        }
+        info!(
+            "Compacted {} small files of '{}' in {}",
+            compacted
+                .iter()
+                .map(|file| file.sources.len())
+                .sum::<usize>(),
+            output_name,
+            dir.display()
+        );
+    }
+    report.directories.push(DirectoryCompaction {
+        output_name: output_name.to_string(),
+        dir: dir.to_path_buf(),
+        groups,
+        compacted,
+    });
+}
+
+fn record_compacted(
+    conn: &DbConnection,
+    artifact_uris: &HashMap<String, String>,
+    output_name: &str,
+    file: &CompactedFile,
+) -> Result<()> {
+    let compacted_uri = format!("file://{}", file.path.display());
+    for source in &file.sources {
+        let name = source
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        compaction::record_compaction(
+            conn,
+            &CompactionRecord {
+                source_path: source.path.display().to_string(),
+                artifact_uri: artifact_uris.get(&name).cloned(),
+                compacted_uri: compacted_uri.clone(),
+                output_name: output_name.to_string(),
+                rows: source.rows,
+                bytes: source.bytes,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Job artifact URI by file name, for `dir/name` and `dir/**/name` URIs.
+fn artifact_uris_by_file(
+    artifacts: &[OutputArtifact],
+    output_name: &str,
+) -> HashMap<String, String> {
+    artifacts
+        .iter()
+        .filter(|artifact| artifact.output_name == output_name)
+        .filter_map(|artifact| {
+            let name = artifact.uri.rsplit('/').next()?;
+            Some((name.to_string(), artifact.uri.clone()))
+        })
+        .collect()
+}
+
+/// Local Parquet directories `output_name` was written to, including ones
+/// whose files were all compacted already.
+fn output_dirs(
+    conn: &DbConnection,
+    artifacts: &[OutputArtifact],
+    output_name: &str,
+) -> Result<Vec<PathBuf>> {
+    let mut dirs = BTreeSet::new();
+    let uris = artifacts
+        .iter()
+        .filter(|artifact| artifact.output_name == output_name)
+        .map(|artifact| artifact.uri.clone());
+    let compacted = compaction::list_compactions(conn, Some(output_name))?
+        .into_iter()
+        .map(|record| record.compacted_uri);
+    for uri in uris.chain(compacted) {
+        if let Some(dir) = parquet_dir(&uri) {
+            dirs.insert(dir);
+        }
+    }
+    Ok(dirs.into_iter().filter(|dir| dir.is_dir()).collect())
+}
+
+/// Output directory of a local Parquet artifact URI (the part before `/**/`
+/// for partitioned outputs).
+fn parquet_dir(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    if !path.ends_with(".parquet") {
+        return None;
+    }
+    match path.split_once("/**/") {
+        Some((dir, _)) => Some(PathBuf::from(dir)),
+        None => Path::new(path).parent().map(Path::to_path_buf),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_sinks::compaction::CompactionSource;
+
+    fn group(files: usize, bytes: u64) -> CompactionGroup {
+        CompactionGroup {
+            target: PathBuf::from("/out/trades_c.parquet"),
+            sources: (0..files)
+                .map(|i| CompactionSource {
+                    path: PathBuf::from(format!("/out/trades_{}.parquet", i)),
+                    bytes,
+                    rows: 1,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_policy_thresholds() {
+        let default = CompactionPolicy {
+            topic: "*".to_string(),
+            ..CompactionPolicy::default()
+        };
+        assert!(!policy_met(&default, &[group(15, 1)]));
+        assert!(policy_met(&default, &[group(10, 1), group(6, 1)]));
+
+        let by_size = CompactionPolicy {
+            topic: "trades".to_string(),
+            min_bytes: Some(1000),
+            ..CompactionPolicy::default()
+        };
+        assert!(!policy_met(&by_size, &[group(20, 10)]));
+        assert!(policy_met(&by_size, &[group(2, 500)]));
+    }
+
+    #[test]
+    fn test_parquet_dir() {
+        assert_eq!(
+            parquet_dir("file:///out/trades/trades_abc.parquet"),
+            Some(PathBuf::from("/out/trades"))
+        );
+        assert_eq!(
+            parquet_dir("file:///out/trades/**/trades_abc.parquet"),
+            Some(PathBuf::from("/out/trades"))
+        );
+        assert_eq!(parquet_dir("file:///out/trades/trades_abc.csv"), None);
+        assert_eq!(parquet_dir("duckdb:///out/db.duckdb#trades"), None);
+    }
+}
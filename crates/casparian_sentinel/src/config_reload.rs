@@ -0,0 +1,274 @@
+//! Hot reload of Sentinel settings and routing tables.
+//!
+//! Scheduling settings may live in a TOML file (`--config`). Every key is
+//! optional and overrides the matching command-line flag; removing a key
+//! restores the flag value. The Sentinel polls the file and the routing
+//! tables (topic configs in the state store) every
+//! [`CONFIG_RELOAD_TICK_SECS`] and applies a change as a whole between two
+//! passes of its event loop, so no dispatch sees half of it. An invalid file
+//! is logged and ignored; the previous settings stay in effect.
+//!
+//! Applied changes are recorded on the audit tape as
+//! [`CONFIG_RELOADED_ACTION`]. When the topic configs of a plugin change,
+//! every connected worker gets a RELOAD for it.
+//!
+//! ```toml
+//! max_workers = 6
+//! scheduling_policy = "least-loaded"
+//! dispatch_batch_size = 8
+//! priority_aging_secs = 300
+//! preempt_low_priority = true
+//! schema_drift_threshold = 2
+//!
+//! [worker_health]
+//! evict_after_secs = 90.0
+//! ```
+
+use anyhow::{Context, Result};
+use casparian_protocol::types::SinkConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::scheduler::SchedulingPolicy;
+use crate::worker_health::WorkerHealthConfig;
+
+/// How often the settings file and routing tables are checked (seconds).
+pub const CONFIG_RELOAD_TICK_SECS: f64 = 2.0;
+/// Audit action of an applied reload.
+pub const CONFIG_RELOADED_ACTION: &str = "config_reloaded";
+
+/// Settings the Sentinel applies without a restart.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReloadableSettings {
+    pub max_workers: usize,
+    pub scheduling_policy: SchedulingPolicy,
+    pub dispatch_batch_size: usize,
+    pub priority_aging_secs: u64,
+    pub preempt_low_priority: bool,
+    pub schema_drift_threshold: usize,
+    pub worker_health: WorkerHealthConfig,
+}
+
+impl ReloadableSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_workers == 0 {
+            anyhow::bail!("max_workers must be at least 1");
+        }
+        if self.dispatch_batch_size == 0 {
+            anyhow::bail!("dispatch_batch_size must be at least 1");
+        }
+        self.worker_health.validate()
+    }
+
+    /// These settings with the keys set in `file` replaced.
+    pub fn overlay(&self, file: &SettingsFile) -> Self {
+        let health = &file.worker_health;
+        let base = &self.worker_health;
+        Self {
+            max_workers: file.max_workers.unwrap_or(self.max_workers),
+            scheduling_policy: file.scheduling_policy.unwrap_or(self.scheduling_policy),
+            dispatch_batch_size: file.dispatch_batch_size.unwrap_or(self.dispatch_batch_size),
+            priority_aging_secs: file.priority_aging_secs.unwrap_or(self.priority_aging_secs),
+            preempt_low_priority: file
+                .preempt_low_priority
+                .unwrap_or(self.preempt_low_priority),
+            schema_drift_threshold: file
+                .schema_drift_threshold
+                .unwrap_or(self.schema_drift_threshold),
+            worker_health: WorkerHealthConfig {
+                degraded_after_secs: health
+                    .degraded_after_secs
+                    .unwrap_or(base.degraded_after_secs),
+                unreachable_after_secs: health
+                    .unreachable_after_secs
+                    .unwrap_or(base.unreachable_after_secs),
+                evict_after_secs: health.evict_after_secs.unwrap_or(base.evict_after_secs),
+                flap_window_secs: health.flap_window_secs.unwrap_or(base.flap_window_secs),
+                flap_threshold: health.flap_threshold.unwrap_or(base.flap_threshold),
+            },
+        }
+    }
+}
+
+/// Contents of the settings file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsFile {
+    pub max_workers: Option<usize>,
+    pub scheduling_policy: Option<SchedulingPolicy>,
+    pub dispatch_batch_size: Option<usize>,
+    pub priority_aging_secs: Option<u64>,
+    pub preempt_low_priority: Option<bool>,
+    pub schema_drift_threshold: Option<usize>,
+    #[serde(default)]
+    pub worker_health: WorkerHealthOverrides,
+}
+
+/// `[worker_health]` table of the settings file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkerHealthOverrides {
+    pub degraded_after_secs: Option<f64>,
+    pub unreachable_after_secs: Option<f64>,
+    pub evict_after_secs: Option<f64>,
+    pub flap_window_secs: Option<f64>,
+    pub flap_threshold: Option<usize>,
+}
+
+impl SettingsFile {
+    pub fn parse(raw: &str) -> Result<Self> {
+        Ok(toml::from_str(raw)?)
+    }
+}
+
+/// Polls the settings file for changes.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    /// Settings selected by flags, under the file
+    base: ReloadableSettings,
+    /// Contents at the last poll; None while the file does not exist
+    last_seen: Option<String>,
+}
+
+impl ConfigWatcher {
+    /// Watch `path` over the flag settings `base`. Returns the watcher and
+    /// the settings in effect now; an invalid file is an error at startup.
+    pub fn new(path: PathBuf, base: ReloadableSettings) -> Result<(Self, ReloadableSettings)> {
+        let last_seen = read_optional(&path)?;
+        let settings = resolve(&base, last_seen.as_deref())
+            .with_context(|| format!("Invalid Sentinel config {}", path.display()))?;
+        let watcher = Self {
+            path,
+            base,
+            last_seen,
+        };
+        Ok((watcher, settings))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The new settings if the file changed since the last poll.
+    pub fn poll(&mut self) -> Option<Result<ReloadableSettings>> {
+        let contents = match read_optional(&self.path) {
+            Ok(contents) => contents,
+            Err(err) => return Some(Err(err)),
+        };
+        if contents == self.last_seen {
+            return None;
+        }
+        self.last_seen = contents;
+        Some(
+            resolve(&self.base, self.last_seen.as_deref())
+                .with_context(|| format!("Invalid Sentinel config {}", self.path.display())),
+        )
+    }
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(raw) => Ok(Some(raw)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => {
+            Err(err).with_context(|| format!("Failed to read Sentinel config {}", path.display()))
+        }
+    }
+}
+
+fn resolve(base: &ReloadableSettings, raw: Option<&str>) -> Result<ReloadableSettings> {
+    let file = match raw {
+        Some(raw) => SettingsFile::parse(raw)?,
+        None => SettingsFile::default(),
+    };
+    let settings = base.overlay(&file);
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// Plugins whose sinks differ between two topic maps, by name.
+pub fn changed_plugins(
+    before: &HashMap<String, Vec<SinkConfig>>,
+    after: &HashMap<String, Vec<SinkConfig>>,
+) -> Vec<String> {
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| before.get(*name) != after.get(*name))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::types::{SchemaEvolution, SinkMode};
+
+    fn base() -> ReloadableSettings {
+        ReloadableSettings {
+            max_workers: 4,
+            scheduling_policy: SchedulingPolicy::RoundRobin,
+            dispatch_batch_size: 1,
+            priority_aging_secs: 600,
+            preempt_low_priority: false,
+            schema_drift_threshold: 0,
+            worker_health: WorkerHealthConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_watcher_applies_and_reverts_file_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sentinel.toml");
+        let (mut watcher, settings) = ConfigWatcher::new(path.clone(), base()).unwrap();
+        assert_eq!(settings, base());
+        assert!(watcher.poll().is_none());
+
+        std::fs::write(
+            &path,
+            "max_workers = 6\nscheduling_policy = \"least-loaded\"\n\n[worker_health]\nevict_after_secs = 90.0\n",
+        )
+        .unwrap();
+        let settings = watcher.poll().unwrap().unwrap();
+        assert_eq!(settings.max_workers, 6);
+        assert_eq!(settings.scheduling_policy, SchedulingPolicy::LeastLoaded);
+        assert_eq!(settings.worker_health.evict_after_secs, 90.0);
+        assert_eq!(settings.dispatch_batch_size, 1);
+        assert!(watcher.poll().is_none());
+
+        // Invalid files are reported once and leave the caller's settings alone
+        std::fs::write(&path, "max_worker = 6\n").unwrap();
+        assert!(watcher.poll().unwrap().is_err());
+        assert!(watcher.poll().is_none());
+        std::fs::write(&path, "[worker_health]\ndegraded_after_secs = 50.0\n").unwrap();
+        assert!(watcher.poll().unwrap().is_err());
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(watcher.poll().unwrap().unwrap(), base());
+    }
+
+    #[test]
+    fn test_changed_plugins() {
+        let sink = |uri: &str| SinkConfig {
+            topic: "output".to_string(),
+            uri: uri.to_string(),
+            mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
+            quarantine_config: None,
+            schema: None,
+        };
+        let before = HashMap::from([
+            ("a".to_string(), vec![sink("parquet:///out/a")]),
+            ("b".to_string(), vec![sink("parquet:///out/b")]),
+        ]);
+        let after = HashMap::from([
+            ("a".to_string(), vec![sink("parquet:///out/a")]),
+            ("b".to_string(), vec![sink("parquet:///out/b2")]),
+            ("c".to_string(), vec![sink("parquet:///out/c")]),
+        ]);
+        assert_eq!(changed_plugins(&before, &after), vec!["b", "c"]);
+        assert!(changed_plugins(&after, &after).is_empty());
+    }
+}
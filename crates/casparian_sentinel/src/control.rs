@@ -12,25 +12,43 @@
 //!
 //! # Supported Operations
 //!
-//! - `ListJobs` / `GetJob` / `CancelJob` / `GetQueueStats`
+//! - `ListJobs` / `GetJob` / `CancelJob` / `GetQueueStats` / `GetPluginMetrics`
+//! - `GetWorkerHealth`
+//! - `VerifyJob`
+//! - `GetPipelineRun`
+//! - `ListDeadLetters` / `RequeueDeadLetter` / `PurgeDeadLetters`
+//! - `RollbackPlugin`
+//! - `ListSubscriptions` / `SubscribePlugin` / `SetSubscriptionEnabled`
+//! - `ListSchedules` / `CreateSchedule` / `SetScheduleEnabled` / `DeleteSchedule`
 //! - `ListApprovals` / `CreateApproval` / `GetApproval` / `Approve` / `Reject`
-//! - `SetApprovalJobId` / `ExpireApprovals`
+//! - `SetApprovalJobId` / `ExpireApprovals` / `ListApprovalAudit`
 //! - `CreateApiJob` / `GetApiJob` / `ListApiJobs`
 //! - `UpdateApiJobStatus` / `UpdateApiJobProgress` / `UpdateApiJobResult` / `UpdateApiJobError`
-//! - `CancelApiJob`
+//! - `CancelApiJob` / `ReserveApiJobIds`
 //! - `CreateSession` / `GetSession` / `ListSessions` / `ListSessionsNeedingInput`
 //! - `AdvanceSession` / `CancelSession`
+//!
+//! A Sentinel started with `--read-only` answers every request that would
+//! change state with an `Error` whose code is [`READ_ONLY_MODE`].
 
 use casparian_protocol::http_types::{
-    Approval, ApprovalOperation, ApprovalStatus, HttpJobStatus, HttpJobType, Job as ApiJob,
-    JobProgress as ApiJobProgress, JobResult as ApiJobResult,
+    Approval, ApprovalAuditEntry, ApprovalOperation, ApprovalStatus, HttpJobStatus, HttpJobType,
+    Job as ApiJob, JobProgress as ApiJobProgress, JobResult as ApiJobResult,
+};
+use casparian_protocol::metrics::PluginMetricsReport;
+use casparian_protocol::types::ReceiptVerification;
+use casparian_protocol::{
+    ApiJobId, JobId, JobResultSummary, PipelineRun, ProcessingStatus, ScheduleCatchUp,
+    ScheduleTarget, WorkerHealth, WorkerStatus,
 };
-use casparian_protocol::{ApiJobId, JobId, ProcessingStatus};
 use casparian_scout::types::{SourceId, SourceType, TagSource, TaggingRuleId, WorkspaceId};
 use serde::{Deserialize, Serialize};
 
 use crate::db::{IntentState, Session, SessionId};
 
+/// Error code returned for mutating requests when the Sentinel is read-only.
+pub const READ_ONLY_MODE: &str = "READ_ONLY_MODE";
+
 /// Default Control API address (TCP loopback).
 pub const DEFAULT_CONTROL_ADDR: &str = casparian_protocol::defaults::DEFAULT_CONTROL_ADDR;
 
@@ -46,10 +64,69 @@ pub enum ControlRequest {
     },
     /// Get a single job by ID
     GetJob { job_id: JobId },
-    /// Request cancellation of a job
+    /// Request cancellation of a job. Running jobs are marked cancelled and
+    /// their worker is sent ABORT.
     CancelJob { job_id: JobId },
+    /// Re-check a completed job's artifacts against its sinks and record
+    /// the result in the job's diagnostics
+    VerifyJob { job_id: JobId },
+    /// Get the jobs of a pipeline run as a DAG with per-stage timing
+    GetPipelineRun { run_id: String },
     /// Get queue statistics
     GetQueueStats,
+    /// Per-plugin and per-tag job metrics since Sentinel start
+    GetPluginMetrics,
+    /// Health of connected workers and per-state counts
+    GetWorkerHealth,
+    /// List dead-letter entries, most recent first
+    ListDeadLetters {
+        plugin_name: Option<String>,
+        limit: Option<i64>,
+    },
+    /// Requeue a dead-letter entry as a fresh job
+    RequeueDeadLetter { dead_letter_id: i64 },
+    /// Purge dead-letter entries.
+    ///
+    /// With `dead_letter_id`, purges that entry only; otherwise purges entries
+    /// matching the optional age (`moved_before`, unix millis) and plugin filters.
+    PurgeDeadLetters {
+        dead_letter_id: Option<i64>,
+        moved_before: Option<i64>,
+        plugin_name: Option<String>,
+    },
+    /// Re-activate a previously active plugin version. Connected workers are
+    /// sent RELOAD and the change is recorded in `cf_plugin_events`.
+    RollbackPlugin {
+        plugin_name: String,
+        target_version: String,
+        actor: Option<String>,
+    },
+    /// List subscriptions of plugins to other plugins' output topics
+    ListSubscriptions,
+    /// Subscribe a plugin to an output topic (`producer_plugin:output_name`).
+    /// Each output the producer publishes then enqueues a job for the
+    /// subscriber. Rejected if it would create a cycle.
+    SubscribePlugin {
+        plugin_name: String,
+        topic_name: String,
+    },
+    /// Enable or disable one subscription edge
+    SetSubscriptionEnabled { subscription_id: i64, enabled: bool },
+    /// List cron schedules
+    ListSchedules,
+    /// Create a cron schedule that rescans a source or opens a processing
+    /// window for a tagging rule
+    CreateSchedule {
+        name: String,
+        cron: String,
+        target: ScheduleTarget,
+        #[serde(default)]
+        catch_up: ScheduleCatchUp,
+    },
+    /// Enable or disable a schedule
+    SetScheduleEnabled { schedule_id: i64, enabled: bool },
+    /// Delete a schedule; a rule window releases the jobs it holds
+    DeleteSchedule { schedule_id: i64 },
     /// Create an API job (cf_api_jobs)
     CreateApiJob {
         job_type: HttpJobType,
@@ -62,9 +139,10 @@ pub enum ControlRequest {
     },
     /// Get a single API job by ID
     GetApiJob { job_id: ApiJobId },
-    /// List API jobs with optional status filter
+    /// List API jobs with optional status and namespace filters
     ListApiJobs {
         status: Option<HttpJobStatus>,
+        namespace: Option<String>,
         limit: Option<i64>,
         offset: Option<i64>,
     },
@@ -87,6 +165,9 @@ pub enum ControlRequest {
     UpdateApiJobError { job_id: ApiJobId, error: String },
     /// Cancel an API job
     CancelApiJob { job_id: ApiJobId },
+    /// Reserve job IDs for the work of an API job, linked to it in
+    /// `cf_api_job_map`
+    ReserveApiJobIds { job_id: ApiJobId, count: u64 },
     /// List approvals with optional status filter
     ListApprovals {
         status: Option<ApprovalStatus>,
@@ -103,9 +184,19 @@ pub enum ControlRequest {
     /// Get a single approval by ID
     GetApproval { approval_id: String },
     /// Approve an approval request
-    Approve { approval_id: String },
+    Approve {
+        approval_id: String,
+        actor: Option<String>,
+        justification: Option<String>,
+    },
     /// Reject an approval request with reason
-    Reject { approval_id: String, reason: String },
+    Reject {
+        approval_id: String,
+        reason: String,
+        actor: Option<String>,
+    },
+    /// List audit log entries (who decided, when, why) for an approval
+    ListApprovalAudit { approval_id: String },
     /// Link a job ID to an approval
     SetApprovalJobId {
         approval_id: String,
@@ -260,8 +351,38 @@ pub enum ControlResponse {
     Job(Option<JobInfo>),
     /// Result of cancel operation
     CancelResult { success: bool, message: String },
+    /// Receipt verification of a job
+    ReceiptVerification(ReceiptVerification),
+    /// Single pipeline run (None if not found)
+    PipelineRun(Option<PipelineRun>),
     /// Queue statistics
     QueueStats(QueueStatsInfo),
+    /// Per-plugin and per-tag job metrics
+    PluginMetrics(PluginMetricsReport),
+    /// Worker health and system pulse
+    WorkerHealth(WorkerHealthReport),
+    /// List of dead-letter entries
+    DeadLetters(Vec<DeadLetterInfo>),
+    /// Result of a dead-letter requeue
+    DeadLetterRequeued {
+        success: bool,
+        message: String,
+        new_job_id: Option<JobId>,
+    },
+    /// Result of a dead-letter purge
+    DeadLettersPurged { purged: u64 },
+    /// Result of a plugin rollback
+    PluginRolledBack(PluginRollbackInfo),
+    /// List of plugin subscriptions
+    Subscriptions(Vec<SubscriptionInfo>),
+    /// Created or updated subscription
+    Subscription(SubscriptionInfo),
+    /// List of cron schedules
+    Schedules(Vec<ScheduleInfo>),
+    /// Created or updated schedule
+    Schedule(ScheduleInfo),
+    /// Result of a schedule deletion
+    ScheduleDeleted { deleted: bool },
     /// Single API job (None if not found)
     ApiJob(Option<ApiJob>),
     /// List of API jobs
@@ -270,12 +391,18 @@ pub enum ControlResponse {
     ApiJobCreated { job_id: ApiJobId },
     /// API job mutation result
     ApiJobResult { success: bool, message: String },
+    /// Job IDs reserved for an API job
+    ApiJobIdsReserved { job_ids: Vec<JobId> },
     /// List of approvals
     Approvals(Vec<Approval>),
     /// Single approval (None if not found)
     Approval(Option<Approval>),
     /// Result of approval decision
     ApprovalResult { success: bool, message: String },
+    /// Approval request created; names the rule that approved it on creation
+    ApprovalCreated { auto_approved_by: Option<String> },
+    /// Approval audit log entries, oldest first
+    ApprovalAudit(Vec<ApprovalAuditEntry>),
     /// Single session (None if not found)
     Session(Option<Session>),
     /// List of sessions
@@ -350,6 +477,69 @@ pub struct JobInfo {
     pub parser_version: Option<String>,
     pub pipeline_run_id: Option<String>,
     pub quarantine_rows: i64,
+    /// Artifacts, counters and warnings of a concluded job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<JobResultSummary>,
+}
+
+/// Plugin rollback outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginRollbackInfo {
+    pub plugin_name: String,
+    /// Version that was active before the rollback
+    pub from_version: Option<String>,
+    pub to_version: String,
+    /// Connected workers that were sent RELOAD
+    pub workers_notified: usize,
+}
+
+/// A plugin subscribed to another plugin's output topic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionInfo {
+    pub id: i64,
+    /// Subscribing (downstream) plugin
+    pub plugin_name: String,
+    /// `producer_plugin:output_name`
+    pub topic_name: String,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+/// A cron schedule evaluated by the sentinel in its local time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleInfo {
+    pub id: i64,
+    pub name: String,
+    pub cron: String,
+    pub target: ScheduleTarget,
+    pub catch_up: ScheduleCatchUp,
+    pub enabled: bool,
+    /// Next occurrence (unix millis), once the sentinel has evaluated it
+    pub next_fire_at: Option<i64>,
+    pub last_fired_at: Option<i64>,
+    /// Rule windows: open until this time (unix millis)
+    pub window_open_until: Option<i64>,
+    pub created_at: i64,
+}
+
+/// Dead-letter entry with the diagnostics captured when the job was moved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterInfo {
+    pub id: i64,
+    pub original_job_id: JobId,
+    pub file_id: Option<i64>,
+    pub input_file: Option<String>,
+    pub plugin_name: String,
+    pub error_message: Option<String>,
+    pub retry_count: i32,
+    pub moved_at: i64,
+    pub reason: Option<String>,
+    pub pipeline_run_id: Option<String>,
+    pub parser_version: Option<String>,
+    pub parser_fingerprint: Option<String>,
+    pub last_worker: Option<String>,
+    pub last_claim_time: Option<i64>,
+    pub result_summary: Option<String>,
 }
 
 /// Queue statistics for API responses
@@ -363,6 +553,49 @@ pub struct QueueStatsInfo {
     pub total: i64,
 }
 
+/// Worker counts per health state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemPulse {
+    pub workers_healthy: u64,
+    pub workers_degraded: u64,
+    pub workers_unreachable: u64,
+    /// Workers evicted since Sentinel start
+    pub workers_evicted: u64,
+    /// Connected workers held from dispatch for flapping
+    pub workers_flapping: u64,
+    /// Worker processes run by the Sentinel's local pool (0 without a pool)
+    #[serde(default)]
+    pub pool_size: u64,
+    /// Pool size the Sentinel is converging to
+    #[serde(default)]
+    pub pool_target: u64,
+    #[serde(default)]
+    pub pool_max: u64,
+    /// Pooled workers replaced after crashing or going silent
+    #[serde(default)]
+    pub pool_restarts: u64,
+}
+
+/// Health of one connected worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerHealthInfo {
+    pub worker_id: String,
+    pub health: WorkerHealth,
+    pub flapping: bool,
+    pub status: WorkerStatus,
+    pub seconds_since_seen: f64,
+    pub seconds_in_state: f64,
+    pub current_job_id: Option<JobId>,
+}
+
+/// Response to `GetWorkerHealth`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerHealthReport {
+    pub pulse: SystemPulse,
+    /// Connected workers, sorted by worker ID
+    pub workers: Vec<WorkerHealthInfo>,
+}
+
 // ============================================================================
 // Scout types
 // ============================================================================
@@ -504,7 +737,90 @@ pub struct ScoutPatternQueryResult {
     pub files: Vec<ScoutPatternMatch>,
 }
 
+impl ControlRequest {
+    /// Whether handling the request may write to the state store or change
+    /// what workers run. These are refused in read-only mode.
+    pub fn is_mutation(&self) -> bool {
+        match self {
+            Self::ListJobs { .. }
+            | Self::GetJob { .. }
+            | Self::GetPipelineRun { .. }
+            | Self::GetQueueStats
+            | Self::GetPluginMetrics
+            | Self::GetWorkerHealth
+            | Self::ListDeadLetters { .. }
+            | Self::ListSubscriptions
+            | Self::ListSchedules
+            | Self::GetApiJob { .. }
+            | Self::ListApiJobs { .. }
+            | Self::ListApprovals { .. }
+            | Self::GetApproval { .. }
+            | Self::ListApprovalAudit { .. }
+            | Self::GetSession { .. }
+            | Self::ListSessions { .. }
+            | Self::ListSessionsNeedingInput { .. }
+            | Self::ListSources { .. }
+            | Self::ListRules { .. }
+            | Self::ListTags { .. }
+            | Self::GetSourceByPath { .. }
+            | Self::ListFiles { .. }
+            | Self::ListFolders { .. }
+            | Self::PatternQuery { .. }
+            | Self::SamplePathsForEval { .. }
+            | Self::GetScan { .. }
+            | Self::ListScans { .. }
+            | Self::Ping => false,
+            // VerifyJob records its result in the job's diagnostics.
+            Self::CancelJob { .. }
+            | Self::VerifyJob { .. }
+            | Self::RequeueDeadLetter { .. }
+            | Self::PurgeDeadLetters { .. }
+            | Self::RollbackPlugin { .. }
+            | Self::SubscribePlugin { .. }
+            | Self::SetSubscriptionEnabled { .. }
+            | Self::CreateSchedule { .. }
+            | Self::SetScheduleEnabled { .. }
+            | Self::DeleteSchedule { .. }
+            | Self::CreateApiJob { .. }
+            | Self::UpdateApiJobStatus { .. }
+            | Self::UpdateApiJobProgress { .. }
+            | Self::UpdateApiJobResult { .. }
+            | Self::UpdateApiJobError { .. }
+            | Self::CancelApiJob { .. }
+            | Self::ReserveApiJobIds { .. }
+            | Self::CreateApproval { .. }
+            | Self::Approve { .. }
+            | Self::Reject { .. }
+            | Self::SetApprovalJobId { .. }
+            | Self::ExpireApprovals
+            | Self::CreateSession { .. }
+            | Self::AdvanceSession { .. }
+            | Self::CancelSession { .. }
+            | Self::UpsertSource { .. }
+            | Self::UpdateSource { .. }
+            | Self::DeleteSource { .. }
+            | Self::TouchSource { .. }
+            | Self::CreateRule { .. }
+            | Self::UpdateRuleEnabled { .. }
+            | Self::DeleteRule { .. }
+            | Self::ApplyTag { .. }
+            | Self::ApplyTagToPaths { .. }
+            | Self::ApplyRuleToSource { .. }
+            | Self::StartScan { .. }
+            | Self::CancelScan { .. } => true,
+        }
+    }
+}
+
 impl ControlResponse {
+    /// Error returned for a mutating request in read-only mode.
+    pub fn read_only_mode() -> Self {
+        Self::error(
+            READ_ONLY_MODE,
+            "Sentinel is in read-only mode; requests that change state are refused",
+        )
+    }
+
     /// Create an error response
     pub fn error(code: impl Into<String>, message: impl Into<String>) -> Self {
         Self::Error {
@@ -554,6 +870,7 @@ mod tests {
             parser_version: Some("1.0.0".to_string()),
             pipeline_run_id: None,
             quarantine_rows: 0,
+            result: None,
         }]);
         let json = serde_json::to_string(&resp).unwrap();
         assert!(json.contains("Jobs"));
@@ -584,6 +901,220 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_set_subscription_enabled_round_trip() {
+        let req = ControlRequest::SetSubscriptionEnabled {
+            subscription_id: 3,
+            enabled: false,
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        match serde_json::from_str::<ControlRequest>(&json).unwrap() {
+            ControlRequest::SetSubscriptionEnabled {
+                subscription_id,
+                enabled,
+            } => {
+                assert_eq!(subscription_id, 3);
+                assert!(!enabled);
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let resp = ControlResponse::Subscription(SubscriptionInfo {
+            id: 3,
+            plugin_name: "parser_b".to_string(),
+            topic_name: "parser_a:orders".to_string(),
+            enabled: false,
+            created_at: 0,
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        match serde_json::from_str::<ControlResponse>(&json).unwrap() {
+            ControlResponse::Subscription(info) => {
+                assert_eq!(info.topic_name, "parser_a:orders");
+                assert!(!info.enabled);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_create_schedule_round_trip() {
+        let json = r#"{"type":"CreateSchedule","payload":{"name":"eod","cron":"0 18 * * 1-5",
+            "target":{"kind":"rule_window","rule_id":"r1","window_minutes":240}}}"#;
+        match serde_json::from_str::<ControlRequest>(json).unwrap() {
+            ControlRequest::CreateSchedule {
+                name,
+                target,
+                catch_up,
+                ..
+            } => {
+                assert_eq!(name, "eod");
+                assert_eq!(target.window_ms(), 240 * 60_000);
+                assert_eq!(catch_up, ScheduleCatchUp::Skip);
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let resp = ControlResponse::Schedule(ScheduleInfo {
+            id: 1,
+            name: "hourly".to_string(),
+            cron: "@hourly".to_string(),
+            target: ScheduleTarget::RescanSource {
+                source_id: "42".to_string(),
+            },
+            catch_up: ScheduleCatchUp::RunOnce,
+            enabled: true,
+            next_fire_at: Some(3_600_000),
+            last_fired_at: None,
+            window_open_until: None,
+            created_at: 0,
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains(r#""kind":"rescan_source""#));
+        assert!(json.contains(r#""catch_up":"run_once""#));
+        match serde_json::from_str::<ControlResponse>(&json).unwrap() {
+            ControlResponse::Schedule(info) => {
+                assert_eq!(info.target.target_id(), "42");
+                assert_eq!(info.next_fire_at, Some(3_600_000));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_get_pipeline_run_round_trip() {
+        let req = ControlRequest::GetPipelineRun {
+            run_id: "run-1".to_string(),
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        match serde_json::from_str::<ControlRequest>(&json).unwrap() {
+            ControlRequest::GetPipelineRun { run_id } => assert_eq!(run_id, "run-1"),
+            _ => panic!("Wrong variant"),
+        }
+
+        let resp = ControlResponse::PipelineRun(Some(PipelineRun::from_jobs("run-1", Vec::new())));
+        let json = serde_json::to_string(&resp).unwrap();
+        match serde_json::from_str::<ControlResponse>(&json).unwrap() {
+            ControlResponse::PipelineRun(Some(run)) => {
+                assert_eq!(run.run_id, "run-1");
+                assert!(run.jobs.is_empty());
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_rollback_plugin_round_trip() {
+        let req = ControlRequest::RollbackPlugin {
+            plugin_name: "parser_a".to_string(),
+            target_version: "1.0.0".to_string(),
+            actor: Some("ops".to_string()),
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("RollbackPlugin"));
+        match serde_json::from_str::<ControlRequest>(&json).unwrap() {
+            ControlRequest::RollbackPlugin {
+                plugin_name,
+                target_version,
+                actor,
+            } => {
+                assert_eq!(plugin_name, "parser_a");
+                assert_eq!(target_version, "1.0.0");
+                assert_eq!(actor.as_deref(), Some("ops"));
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let resp = ControlResponse::PluginRolledBack(PluginRollbackInfo {
+            plugin_name: "parser_a".to_string(),
+            from_version: Some("1.1.0".to_string()),
+            to_version: "1.0.0".to_string(),
+            workers_notified: 2,
+        });
+        let json = serde_json::to_string(&resp).unwrap();
+        match serde_json::from_str::<ControlResponse>(&json).unwrap() {
+            ControlResponse::PluginRolledBack(info) => {
+                assert_eq!(info.from_version.as_deref(), Some("1.1.0"));
+                assert_eq!(info.workers_notified, 2);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_approval_decision_carries_actor() {
+        let req = ControlRequest::Reject {
+            approval_id: "appr-1".to_string(),
+            reason: "Wrong input dir".to_string(),
+            actor: Some("alice".to_string()),
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        match serde_json::from_str::<ControlRequest>(&json).unwrap() {
+            ControlRequest::Reject { reason, actor, .. } => {
+                assert_eq!(reason, "Wrong input dir");
+                assert_eq!(actor.as_deref(), Some("alice"));
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        // Older clients omit the actor
+        let req: ControlRequest =
+            serde_json::from_str(r#"{"type":"Approve","payload":{"approval_id":"appr-1"}}"#)
+                .unwrap();
+        match req {
+            ControlRequest::Approve {
+                actor,
+                justification,
+                ..
+            } => {
+                assert!(actor.is_none());
+                assert!(justification.is_none());
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_purge_dead_letters_request() {
+        let req = ControlRequest::PurgeDeadLetters {
+            dead_letter_id: None,
+            moved_before: Some(1_700_000_000_000),
+            plugin_name: Some("parser_a".to_string()),
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("PurgeDeadLetters"));
+        let parsed: ControlRequest = serde_json::from_str(&json).unwrap();
+        match parsed {
+            ControlRequest::PurgeDeadLetters {
+                dead_letter_id,
+                moved_before,
+                plugin_name,
+            } => {
+                assert_eq!(dead_letter_id, None);
+                assert_eq!(moved_before, Some(1_700_000_000_000));
+                assert_eq!(plugin_name.as_deref(), Some("parser_a"));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_read_only_classification() {
+        assert!(!ControlRequest::Ping.is_mutation());
+        assert!(!ControlRequest::ListSchedules.is_mutation());
+        assert!(ControlRequest::ExpireApprovals.is_mutation());
+        assert!(ControlRequest::Approve {
+            approval_id: "appr-1".to_string(),
+            actor: None,
+            justification: None,
+        }
+        .is_mutation());
+
+        match ControlResponse::read_only_mode() {
+            ControlResponse::Error { code, .. } => assert_eq!(code, READ_ONLY_MODE),
+            other => panic!("Wrong variant: {:?}", other),
+        }
+    }
+
     #[test]
     fn test_error_response() {
         let resp = ControlResponse::error("NOT_FOUND", "Job not found");
@@ -5,14 +5,17 @@
 use crate::control::{
     ControlRequest, ControlResponse, ScoutFilesPage, ScoutFolderEntry, ScoutPatternQueryResult,
     ScoutRuleInfo, ScoutScanStatus, ScoutSourceInfo, ScoutTagFilter, ScoutTagStats,
+    WorkerHealthReport, READ_ONLY_MODE,
 };
 use crate::db::{IntentState, Session, SessionId};
 use anyhow::{Context, Result};
-use casparian_protocol::http_types::{Approval, ApprovalStatus};
+use casparian_pipe::PipeLink;
+use casparian_protocol::http_types::{Approval, ApprovalAuditEntry, ApprovalStatus};
 use casparian_protocol::http_types::{
     HttpJobStatus, HttpJobType, Job as ApiJob, JobProgress as ApiJobProgress,
     JobResult as ApiJobResult,
 };
+use casparian_protocol::{ScheduleCatchUp, ScheduleTarget};
 use casparian_scout::types::{SourceId, TagSource, TaggingRuleId, WorkspaceId};
 use std::time::Duration;
 use zmq::{Context as ZmqContext, Socket};
@@ -20,10 +23,34 @@ use zmq::{Context as ZmqContext, Socket};
 /// Default timeout for control API requests (5 seconds)
 const DEFAULT_TIMEOUT_MS: i32 = 5000;
 
+/// A request refused because the Sentinel runs in read-only mode.
+///
+/// Returned inside the `anyhow::Error` of any client method; callers can
+/// `downcast_ref::<ReadOnlyMode>()` to tell it apart from other failures.
+#[derive(Debug, thiserror::Error)]
+#[error("Read-only mode: {message}")]
+pub struct ReadOnlyMode {
+    pub message: String,
+}
+
+/// Connect `socket` to `addr`, through a local relay for `pipe://`.
+fn connect_local(socket: &Socket, addr: &str) -> Result<Option<PipeLink>> {
+    let pipe_link = casparian_pipe::is_pipe_addr(addr)
+        .then(|| PipeLink::connect(addr))
+        .transpose()?;
+    let target = pipe_link.as_ref().map_or(addr, |link| link.endpoint());
+    socket
+        .connect(target)
+        .with_context(|| format!("Failed to connect to control API at {}", addr))?;
+    Ok(pipe_link)
+}
+
 /// Client for the Sentinel Control API
 pub struct ControlClient {
     socket: Socket,
     #[allow(dead_code)]
+    pipe_link: Option<PipeLink>, // Relays pipe:// addresses
+    #[allow(dead_code)]
     context: ZmqContext, // Keep context alive
 }
 
@@ -43,11 +70,13 @@ impl ControlClient {
             .context("Failed to set send timeout")?;
         socket.set_linger(0).context("Failed to set linger")?;
 
-        socket
-            .connect(addr)
-            .with_context(|| format!("Failed to connect to control API at {}", addr))?;
+        let pipe_link = connect_local(&socket, addr)?;
 
-        Ok(Self { socket, context })
+        Ok(Self {
+            socket,
+            pipe_link,
+            context,
+        })
     }
 
     /// Connect with custom timeout
@@ -66,11 +95,13 @@ impl ControlClient {
             .context("Failed to set send timeout")?;
         socket.set_linger(0).context("Failed to set linger")?;
 
-        socket
-            .connect(addr)
-            .with_context(|| format!("Failed to connect to control API at {}", addr))?;
+        let pipe_link = connect_local(&socket, addr)?;
 
-        Ok(Self { socket, context })
+        Ok(Self {
+            socket,
+            pipe_link,
+            context,
+        })
     }
 
     /// Send a request and receive a response
@@ -89,6 +120,14 @@ impl ControlClient {
         let resp: ControlResponse =
             serde_json::from_slice(&resp_bytes).context("Failed to parse response")?;
 
+        if let ControlResponse::Error { code, message } = &resp {
+            if code == READ_ONLY_MODE {
+                return Err(ReadOnlyMode {
+                    message: message.clone(),
+                }
+                .into());
+            }
+        }
         Ok(resp)
     }
 
@@ -137,6 +176,22 @@ impl ControlClient {
         }
     }
 
+    /// Get a pipeline run with its job DAG and per-stage timing
+    pub fn get_pipeline_run(
+        &self,
+        run_id: &str,
+    ) -> Result<Option<casparian_protocol::PipelineRun>> {
+        match self.request(ControlRequest::GetPipelineRun {
+            run_id: run_id.to_string(),
+        })? {
+            ControlResponse::PipelineRun(run) => Ok(run),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("GetPipelineRun failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to GetPipelineRun"),
+        }
+    }
+
     /// Cancel a job
     pub fn cancel_job(&self, job_id: casparian_protocol::JobId) -> Result<(bool, String)> {
         match self.request(ControlRequest::CancelJob { job_id })? {
@@ -148,6 +203,20 @@ impl ControlClient {
         }
     }
 
+    /// Re-check a completed job's artifacts against its sinks
+    pub fn verify_job(
+        &self,
+        job_id: casparian_protocol::JobId,
+    ) -> Result<casparian_protocol::types::ReceiptVerification> {
+        match self.request(ControlRequest::VerifyJob { job_id })? {
+            ControlResponse::ReceiptVerification(verification) => Ok(verification),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("VerifyJob failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to VerifyJob"),
+        }
+    }
+
     /// Get queue statistics
     pub fn get_queue_stats(&self) -> Result<crate::control::QueueStatsInfo> {
         match self.request(ControlRequest::GetQueueStats)? {
@@ -159,6 +228,224 @@ impl ControlClient {
         }
     }
 
+    /// Get per-plugin and per-tag job metrics
+    pub fn get_plugin_metrics(
+        &self,
+    ) -> Result<casparian_protocol::metrics::PluginMetricsReport> {
+        match self.request(ControlRequest::GetPluginMetrics)? {
+            ControlResponse::PluginMetrics(report) => Ok(report),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("GetPluginMetrics failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to GetPluginMetrics"),
+        }
+    }
+
+    /// Get worker health and per-state worker counts
+    pub fn get_worker_health(&self) -> Result<WorkerHealthReport> {
+        match self.request(ControlRequest::GetWorkerHealth)? {
+            ControlResponse::WorkerHealth(report) => Ok(report),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("GetWorkerHealth failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to GetWorkerHealth"),
+        }
+    }
+
+    // =====================================================================
+    // Dead-letter queue
+    // =====================================================================
+
+    /// List dead-letter entries, optionally for a single plugin
+    pub fn list_dead_letters(
+        &self,
+        plugin_name: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<Vec<crate::control::DeadLetterInfo>> {
+        match self.request(ControlRequest::ListDeadLetters {
+            plugin_name: plugin_name.map(|s| s.to_string()),
+            limit,
+        })? {
+            ControlResponse::DeadLetters(entries) => Ok(entries),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("ListDeadLetters failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to ListDeadLetters"),
+        }
+    }
+
+    /// Requeue a dead-letter entry; returns the new job ID on success
+    pub fn requeue_dead_letter(
+        &self,
+        dead_letter_id: i64,
+    ) -> Result<(bool, String, Option<casparian_protocol::JobId>)> {
+        match self.request(ControlRequest::RequeueDeadLetter { dead_letter_id })? {
+            ControlResponse::DeadLetterRequeued {
+                success,
+                message,
+                new_job_id,
+            } => Ok((success, message, new_job_id)),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("RequeueDeadLetter failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to RequeueDeadLetter"),
+        }
+    }
+
+    /// Purge one dead-letter entry, or all entries matching the filters.
+    /// Returns the number of entries removed.
+    pub fn purge_dead_letters(
+        &self,
+        dead_letter_id: Option<i64>,
+        moved_before: Option<i64>,
+        plugin_name: Option<&str>,
+    ) -> Result<u64> {
+        match self.request(ControlRequest::PurgeDeadLetters {
+            dead_letter_id,
+            moved_before,
+            plugin_name: plugin_name.map(|s| s.to_string()),
+        })? {
+            ControlResponse::DeadLettersPurged { purged } => Ok(purged),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("PurgeDeadLetters failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to PurgeDeadLetters"),
+        }
+    }
+
+    // =====================================================================
+    // Plugins
+    // =====================================================================
+
+    /// Re-activate a previously active plugin version
+    pub fn rollback_plugin(
+        &self,
+        plugin_name: &str,
+        target_version: &str,
+        actor: Option<&str>,
+    ) -> Result<crate::control::PluginRollbackInfo> {
+        match self.request(ControlRequest::RollbackPlugin {
+            plugin_name: plugin_name.to_string(),
+            target_version: target_version.to_string(),
+            actor: actor.map(|s| s.to_string()),
+        })? {
+            ControlResponse::PluginRolledBack(info) => Ok(info),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("RollbackPlugin failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to RollbackPlugin"),
+        }
+    }
+
+    /// List plugin subscriptions to output topics
+    pub fn list_subscriptions(&self) -> Result<Vec<crate::control::SubscriptionInfo>> {
+        match self.request(ControlRequest::ListSubscriptions)? {
+            ControlResponse::Subscriptions(subscriptions) => Ok(subscriptions),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("ListSubscriptions failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to ListSubscriptions"),
+        }
+    }
+
+    /// Subscribe a plugin to an output topic (`producer_plugin:output_name`)
+    pub fn subscribe_plugin(
+        &self,
+        plugin_name: &str,
+        topic_name: &str,
+    ) -> Result<crate::control::SubscriptionInfo> {
+        match self.request(ControlRequest::SubscribePlugin {
+            plugin_name: plugin_name.to_string(),
+            topic_name: topic_name.to_string(),
+        })? {
+            ControlResponse::Subscription(subscription) => Ok(subscription),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("SubscribePlugin failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to SubscribePlugin"),
+        }
+    }
+
+    /// Enable or disable one subscription edge
+    pub fn set_subscription_enabled(
+        &self,
+        subscription_id: i64,
+        enabled: bool,
+    ) -> Result<crate::control::SubscriptionInfo> {
+        match self.request(ControlRequest::SetSubscriptionEnabled {
+            subscription_id,
+            enabled,
+        })? {
+            ControlResponse::Subscription(subscription) => Ok(subscription),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("SetSubscriptionEnabled failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to SetSubscriptionEnabled"),
+        }
+    }
+
+    /// List cron schedules
+    pub fn list_schedules(&self) -> Result<Vec<crate::control::ScheduleInfo>> {
+        match self.request(ControlRequest::ListSchedules)? {
+            ControlResponse::Schedules(schedules) => Ok(schedules),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("ListSchedules failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to ListSchedules"),
+        }
+    }
+
+    /// Create a cron schedule for a source rescan or a rule window
+    pub fn create_schedule(
+        &self,
+        name: &str,
+        cron: &str,
+        target: ScheduleTarget,
+        catch_up: ScheduleCatchUp,
+    ) -> Result<crate::control::ScheduleInfo> {
+        match self.request(ControlRequest::CreateSchedule {
+            name: name.to_string(),
+            cron: cron.to_string(),
+            target,
+            catch_up,
+        })? {
+            ControlResponse::Schedule(schedule) => Ok(schedule),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("CreateSchedule failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to CreateSchedule"),
+        }
+    }
+
+    /// Enable or disable a schedule
+    pub fn set_schedule_enabled(
+        &self,
+        schedule_id: i64,
+        enabled: bool,
+    ) -> Result<crate::control::ScheduleInfo> {
+        match self.request(ControlRequest::SetScheduleEnabled {
+            schedule_id,
+            enabled,
+        })? {
+            ControlResponse::Schedule(schedule) => Ok(schedule),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("SetScheduleEnabled failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to SetScheduleEnabled"),
+        }
+    }
+
+    /// Delete a schedule. Returns false if it did not exist.
+    pub fn delete_schedule(&self, schedule_id: i64) -> Result<bool> {
+        match self.request(ControlRequest::DeleteSchedule { schedule_id })? {
+            ControlResponse::ScheduleDeleted { deleted } => Ok(deleted),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("DeleteSchedule failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to DeleteSchedule"),
+        }
+    }
+
     // =====================================================================
     // API job operations (cf_api_jobs)
     // =====================================================================
@@ -202,15 +489,17 @@ impl ControlClient {
         }
     }
 
-    /// List API jobs with optional status filter
+    /// List API jobs with optional status and namespace filters
     pub fn list_api_jobs(
         &self,
         status: Option<HttpJobStatus>,
+        namespace: Option<String>,
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> Result<Vec<ApiJob>> {
         match self.request(ControlRequest::ListApiJobs {
             status,
+            namespace,
             limit,
             offset,
         })? {
@@ -320,6 +609,21 @@ impl ControlClient {
         }
     }
 
+    /// Reserve `count` job IDs for the work of an API job
+    pub fn reserve_api_job_ids(
+        &self,
+        job_id: casparian_protocol::ApiJobId,
+        count: u64,
+    ) -> Result<Vec<casparian_protocol::JobId>> {
+        match self.request(ControlRequest::ReserveApiJobIds { job_id, count })? {
+            ControlResponse::ApiJobIdsReserved { job_ids } => Ok(job_ids),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("ReserveApiJobIds failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to ReserveApiJobIds"),
+        }
+    }
+
     /// List approvals with optional status filter
     pub fn list_approvals(
         &self,
@@ -340,27 +644,22 @@ impl ControlClient {
         }
     }
 
-    /// Create a new approval request
+    /// Create a new approval request. Returns the approval rule that
+    /// approved it on creation, if any.
     pub fn create_approval(
         &self,
         approval_id: &str,
         operation: casparian_protocol::http_types::ApprovalOperation,
         summary: &str,
         expires_in_seconds: i64,
-    ) -> Result<()> {
+    ) -> Result<Option<String>> {
         match self.request(ControlRequest::CreateApproval {
             approval_id: approval_id.to_string(),
             operation,
             summary: summary.to_string(),
             expires_in_seconds,
         })? {
-            ControlResponse::ApprovalResult { success, message } => {
-                if success {
-                    Ok(())
-                } else {
-                    anyhow::bail!("CreateApproval failed: {}", message)
-                }
-            }
+            ControlResponse::ApprovalCreated { auto_approved_by } => Ok(auto_approved_by),
             ControlResponse::Error { code, message } => {
                 anyhow::bail!("CreateApproval failed [{}]: {}", code, message)
             }
@@ -381,10 +680,18 @@ impl ControlClient {
         }
     }
 
-    /// Approve an approval request
-    pub fn approve(&self, approval_id: &str) -> Result<(bool, String)> {
+    /// Approve an approval request, recording `actor` and `justification`
+    /// in the approval audit log.
+    pub fn approve(
+        &self,
+        approval_id: &str,
+        actor: Option<&str>,
+        justification: Option<&str>,
+    ) -> Result<(bool, String)> {
         match self.request(ControlRequest::Approve {
             approval_id: approval_id.to_string(),
+            actor: actor.map(str::to_string),
+            justification: justification.map(str::to_string),
         })? {
             ControlResponse::ApprovalResult { success, message } => Ok((success, message)),
             ControlResponse::Error { code, message } => {
@@ -395,10 +702,16 @@ impl ControlClient {
     }
 
     /// Reject an approval request
-    pub fn reject(&self, approval_id: &str, reason: &str) -> Result<(bool, String)> {
+    pub fn reject(
+        &self,
+        approval_id: &str,
+        reason: &str,
+        actor: Option<&str>,
+    ) -> Result<(bool, String)> {
         match self.request(ControlRequest::Reject {
             approval_id: approval_id.to_string(),
             reason: reason.to_string(),
+            actor: actor.map(str::to_string),
         })? {
             ControlResponse::ApprovalResult { success, message } => Ok((success, message)),
             ControlResponse::Error { code, message } => {
@@ -408,6 +721,19 @@ impl ControlClient {
         }
     }
 
+    /// List audit log entries for an approval, oldest first
+    pub fn list_approval_audit(&self, approval_id: &str) -> Result<Vec<ApprovalAuditEntry>> {
+        match self.request(ControlRequest::ListApprovalAudit {
+            approval_id: approval_id.to_string(),
+        })? {
+            ControlResponse::ApprovalAudit(entries) => Ok(entries),
+            ControlResponse::Error { code, message } => {
+                anyhow::bail!("ListApprovalAudit failed [{}]: {}", code, message)
+            }
+            _ => anyhow::bail!("Unexpected response to ListApprovalAudit"),
+        }
+    }
+
     /// Link a job ID to an approval
     pub fn set_approval_job_id(
         &self,
@@ -1,20 +1,30 @@
 //! Database layer for Sentinel (re-exported from state store).
 
 pub use casparian_state_store::api_storage;
+pub use casparian_state_store::approval_policy;
+pub use casparian_state_store::audit;
+pub use casparian_state_store::compaction;
+pub use casparian_state_store::dataset_catalog;
 pub use casparian_state_store::expected_outputs;
+pub use casparian_state_store::job_ids;
 pub use casparian_state_store::legacy_models;
+pub use casparian_state_store::lineage;
 pub use casparian_state_store::models;
 pub use casparian_state_store::queue;
+pub use casparian_state_store::retention;
 pub use casparian_state_store::schema_version;
 pub use casparian_state_store::sessions;
 
 pub use casparian_state_store::ApiStorage;
 pub use casparian_state_store::ExpectedOutputs;
 pub use casparian_state_store::JobQueue;
+pub use casparian_state_store::LineageStorage;
 pub use casparian_state_store::OutputSpec;
 pub use casparian_state_store::QueueStats;
 pub use casparian_state_store::SessionStorage;
+pub use casparian_state_store::StateStore;
 pub use casparian_state_store::{ensure_schema_version, SCHEMA_VERSION};
+pub use casparian_state_store::{AuditEntry, AuditLog, AuditQuery};
 
 pub use casparian_intent::{
     IntentState, QuestionKind, QuestionOption, Session, SessionId, SessionQuestion,
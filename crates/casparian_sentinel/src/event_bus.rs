@@ -0,0 +1,270 @@
+//! In-process event bus between Sentinel subsystems.
+//!
+//! The event loop publishes what happened (a job changed state, a worker
+//! joined, an approval was created) once, as a [`SentinelEvent`]; it does not
+//! know who is listening. Subscribers registered at startup turn events into
+//! side effects:
+//!
+//! | Subscriber | Effect |
+//! |------------|--------|
+//! | [`record_metrics`] | Prometheus counters in [`METRICS`] |
+//! | [`record_audit`] | audit tape entries |
+//! | event log (`Sentinel::bind`) | `cf_api_events` rows, read by the UI and MCP clients |
+//! | HTTP API | wakes `/events/stream` clients |
+//!
+//! Handlers registered with [`EventBus::on`] run inline on the publishing
+//! thread, in registration order, so they must not block for long. Consumers
+//! on other threads use [`EventBus::subscribe`] and receive a copy of every
+//! later event on a channel.
+
+use casparian_protocol::http_types::EventType;
+use casparian_protocol::{JobId, WorkerHealth};
+use casparian_state_store::audit::{entity, AuditLog};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use crate::metrics::METRICS;
+
+/// How a job left its previous state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobTransition {
+    Dispatched,
+    Completed,
+    /// Failed and queued again
+    Retried,
+    Failed,
+    Rejected,
+    Aborted,
+    /// Requeued to make room for an URGENT job
+    Preempted,
+    /// No connected worker can run it
+    Unroutable,
+}
+
+/// Something that happened in the Sentinel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SentinelEvent {
+    JobStateChanged {
+        job_id: JobId,
+        transition: JobTransition,
+    },
+    WorkerJoined {
+        worker_id: String,
+    },
+    /// `evicted` is false when the worker pool stopped the worker
+    WorkerLeft {
+        worker_id: String,
+        evicted: bool,
+    },
+    WorkerHealthChanged {
+        worker_id: String,
+        from: WorkerHealth,
+        to: WorkerHealth,
+        flapping: bool,
+    },
+    ApprovalCreated {
+        approval_id: String,
+        /// Rule that approved the request on creation
+        auto_approved_by: Option<String>,
+    },
+    /// `action` is "approved" or "rejected"; `before`/`after` are snapshots
+    /// of the approval
+    ApprovalDecided {
+        approval_id: String,
+        action: &'static str,
+        actor: Option<String>,
+        before: Option<serde_json::Value>,
+        after: Option<serde_json::Value>,
+    },
+}
+
+type Handler = Arc<dyn Fn(&SentinelEvent) + Send + Sync>;
+
+#[derive(Default)]
+struct Subscribers {
+    handlers: Vec<Handler>,
+    channels: Vec<Sender<SentinelEvent>>,
+}
+
+/// Broadcasts [`SentinelEvent`]s to every subscriber. Clones share the same
+/// subscribers.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Subscribers>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `handler` for every event published from now on.
+    pub fn on(&self, handler: impl Fn(&SentinelEvent) + Send + Sync + 'static) {
+        self.lock().handlers.push(Arc::new(handler));
+    }
+
+    /// Receive every event published from now on. Dropping the receiver
+    /// unsubscribes.
+    pub fn subscribe(&self) -> Receiver<SentinelEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.lock().channels.push(tx);
+        rx
+    }
+
+    pub fn publish(&self, event: SentinelEvent) {
+        // Handlers run without the lock so they may publish or subscribe.
+        let handlers = {
+            let mut subscribers = self.lock();
+            subscribers
+                .channels
+                .retain(|tx| tx.send(event.clone()).is_ok());
+            subscribers.handlers.clone()
+        };
+        for handler in handlers {
+            handler(&event);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Subscribers> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Metrics subscriber: count job outcomes and worker churn.
+pub fn record_metrics(event: &SentinelEvent) {
+    match event {
+        SentinelEvent::JobStateChanged { transition, .. } => match transition {
+            JobTransition::Dispatched => METRICS.inc_jobs_dispatched(),
+            JobTransition::Completed => METRICS.inc_jobs_completed(),
+            JobTransition::Retried => METRICS.inc_jobs_retried(),
+            JobTransition::Failed => METRICS.inc_jobs_failed(),
+            JobTransition::Rejected => METRICS.inc_jobs_rejected(),
+            JobTransition::Aborted => METRICS.inc_jobs_aborted(),
+            JobTransition::Preempted => METRICS.inc_jobs_preempted(),
+            JobTransition::Unroutable => METRICS.inc_jobs_unroutable(),
+        },
+        SentinelEvent::WorkerJoined { .. } => METRICS.inc_workers_registered(),
+        SentinelEvent::WorkerLeft { evicted: true, .. } => METRICS.inc_workers_cleaned_up(),
+        _ => {}
+    }
+}
+
+/// Audit tape subscriber: record approval lifecycle changes.
+pub fn record_audit(audit: &AuditLog, event: &SentinelEvent) {
+    match event {
+        SentinelEvent::ApprovalCreated {
+            approval_id,
+            auto_approved_by,
+        } => audit.record(
+            entity::APPROVAL,
+            approval_id,
+            "created",
+            None,
+            None,
+            Some(serde_json::json!({ "auto_approved_by": auto_approved_by })),
+        ),
+        SentinelEvent::ApprovalDecided {
+            approval_id,
+            action,
+            actor,
+            before,
+            after,
+        } => audit.record(
+            entity::APPROVAL,
+            approval_id,
+            action,
+            actor.as_deref(),
+            before.clone(),
+            after.clone(),
+        ),
+        _ => {}
+    }
+}
+
+/// Event log entry for `event`, if it belongs in `cf_api_events`. Only
+/// job-independent events qualify; approval events are written by the
+/// approval store itself.
+pub fn api_event(event: &SentinelEvent) -> Option<EventType> {
+    match event {
+        SentinelEvent::WorkerJoined { worker_id } => Some(EventType::WorkerJoined {
+            worker_id: worker_id.clone(),
+        }),
+        SentinelEvent::WorkerLeft { worker_id, .. } => Some(EventType::WorkerLeft {
+            worker_id: worker_id.clone(),
+        }),
+        SentinelEvent::WorkerHealthChanged {
+            worker_id,
+            from,
+            to,
+            flapping,
+        } => Some(EventType::WorkerHealthChanged {
+            worker_id: worker_id.clone(),
+            from: *from,
+            to: *to,
+            flapping: *flapping,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn joined(worker_id: &str) -> SentinelEvent {
+        SentinelEvent::WorkerJoined {
+            worker_id: worker_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_publish_reaches_handlers_and_channels() {
+        let bus = EventBus::new();
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counter = seen.clone();
+        bus.on(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        bus.publish(joined("before-subscribe"));
+        let rx = bus.subscribe();
+        bus.clone().publish(joined("w1"));
+
+        assert_eq!(seen.load(Ordering::SeqCst), 2);
+        assert_eq!(rx.try_recv().unwrap(), joined("w1"));
+        assert!(rx.try_recv().is_err());
+
+        // A dropped receiver is pruned on the next publish
+        drop(rx);
+        bus.publish(joined("w2"));
+        assert!(bus.lock().channels.is_empty());
+    }
+
+    #[test]
+    fn test_handler_may_publish() {
+        let bus = EventBus::new();
+        let rx = bus.subscribe();
+        let inner = bus.clone();
+        bus.on(move |event| {
+            if let SentinelEvent::WorkerJoined { worker_id } = event {
+                inner.publish(SentinelEvent::WorkerLeft {
+                    worker_id: worker_id.clone(),
+                    evicted: false,
+                });
+            }
+        });
+
+        bus.publish(joined("w1"));
+        let received: Vec<_> = rx.try_iter().collect();
+        assert_eq!(received.len(), 2);
+        assert_eq!(
+            api_event(&received[1]),
+            Some(EventType::WorkerLeft {
+                worker_id: "w1".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,380 @@
+//! Artifact export: copy one job output out of its sink, with integrity checks.
+//!
+//! [`export_artifact`] resolves the output through `cf_job_artifacts` and
+//! checks the stored file against the worker's receipt before and after
+//! copying it:
+//!
+//! | Check | Source | Export |
+//! |-------|--------|--------|
+//! | blake3 hash | equals the receipt's `content_hash`, if reported | equals the source (as-stored copy) |
+//! | row count (Parquet) | equals the receipt's `rows` | equals the source (CSV conversion) |
+//!
+//! The export is written next to `dest` and renamed into place only once it
+//! passes, so a failed export never leaves a partial file behind. Every
+//! export is recorded in the audit log as entity `artifact`, id
+//! `<job_id>/<output_name>`, action `exported`.
+//!
+//! Only single-file local outputs can be exported; partitioned directories,
+//! DuckDB tables and remote sinks are refused.
+
+use anyhow::{bail, Context, Result};
+use casparian_db::DbConnection;
+use casparian_protocol::types::{ParsedSinkUri, SinkScheme};
+use casparian_state_store::audit::entity;
+use casparian_state_store::StateStore;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::receipt_verify::{escape_sql_literal, parquet_row_count};
+
+/// File format of an export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Byte-for-byte copy of the stored file
+    #[default]
+    Original,
+    /// Parquet output converted to CSV with a header row
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Original => "original",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "original" => Ok(Self::Original),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!(
+                "Unknown export format '{}': expected original or csv",
+                other
+            )),
+        }
+    }
+}
+
+/// How one export is written.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// Recorded in the audit log; defaults to the OS user
+    pub actor: Option<String>,
+}
+
+/// Outcome of a successful export.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub job_id: i64,
+    pub output_name: String,
+    pub source_uri: String,
+    pub dest: PathBuf,
+    pub format: ExportFormat,
+    /// Rows in the export; known for Parquet sources
+    pub rows: Option<u64>,
+    pub bytes: u64,
+    /// Hex blake3 hash of the stored file
+    pub source_hash: String,
+    /// Hex blake3 hash of the exported file
+    pub dest_hash: String,
+    /// False when the receipt carried no hash to check the source against
+    pub receipt_hash_checked: bool,
+}
+
+/// Export output `output_name` of job `job_id` to `dest`, which must not
+/// exist yet.
+pub fn export_artifact(
+    state_store: &StateStore,
+    job_id: i64,
+    output_name: &str,
+    dest: &Path,
+    options: &ExportOptions,
+) -> Result<ExportReport> {
+    let records: Vec<_> = state_store
+        .artifacts()
+        .list_job_artifacts(job_id)?
+        .into_iter()
+        .filter(|record| record.kind == "output" && record.name == output_name)
+        .collect();
+    let record = match records.as_slice() {
+        [] => bail!("Job {} has no output named '{}'", job_id, output_name),
+        [record] => record,
+        _ => bail!(
+            "Output '{}' of job {} spans {} files; only single-file outputs can be exported",
+            output_name,
+            job_id,
+            records.len()
+        ),
+    };
+
+    let source = local_file(&record.uri)?;
+    let is_parquet = source.extension().and_then(|e| e.to_str()) == Some("parquet");
+    if options.format == ExportFormat::Csv && !is_parquet {
+        bail!(
+            "CSV conversion needs a Parquet output; '{}' is not one",
+            record.uri
+        );
+    }
+    if dest.exists() {
+        bail!("Destination '{}' already exists", dest.display());
+    }
+
+    // The stored file must still be what the worker reported.
+    let source_hash = hash(&source)?;
+    if let Some(expected) = record.content_hash.as_deref() {
+        if expected != source_hash {
+            bail!(
+                "'{}' does not match its receipt: hash {} (receipt {})",
+                source.display(),
+                source_hash,
+                expected
+            );
+        }
+    }
+    let rows = if is_parquet {
+        let actual = parquet_row_count(&source)
+            .with_context(|| format!("Failed to read '{}'", source.display()))?;
+        if let Some(expected) = record.rows.and_then(|rows| u64::try_from(rows).ok()) {
+            if expected != actual {
+                bail!(
+                    "'{}' does not match its receipt: {} rows (receipt {})",
+                    source.display(),
+                    actual,
+                    expected
+                );
+            }
+        }
+        Some(actual)
+    } else {
+        None
+    };
+
+    let partial = partial_path(dest);
+    let written = write_export(&source, &partial, options.format, &source_hash, rows);
+    let dest_hash = match written {
+        Ok(dest_hash) => dest_hash,
+        Err(err) => {
+            let _ = fs::remove_file(&partial);
+            return Err(err);
+        }
+    };
+    if let Err(err) = fs::rename(&partial, dest) {
+        let _ = fs::remove_file(&partial);
+        return Err(err).with_context(|| format!("Failed to write '{}'", dest.display()));
+    }
+    let bytes = fs::metadata(dest)?.len();
+
+    let report = ExportReport {
+        job_id,
+        output_name: output_name.to_string(),
+        source_uri: record.uri.clone(),
+        dest: dest.to_path_buf(),
+        format: options.format,
+        rows,
+        bytes,
+        source_hash,
+        dest_hash,
+        receipt_hash_checked: record.content_hash.is_some(),
+    };
+    state_store.audit().record(
+        entity::ARTIFACT,
+        &format!("{}/{}", job_id, output_name),
+        "exported",
+        options.actor.as_deref(),
+        None,
+        Some(serde_json::to_value(&report)?),
+    );
+    Ok(report)
+}
+
+/// Path of the local file behind `sink_uri`, if it is a single file.
+fn local_file(sink_uri: &str) -> Result<PathBuf> {
+    let parsed = ParsedSinkUri::parse(sink_uri).map_err(anyhow::Error::msg)?;
+    match parsed.scheme {
+        SinkScheme::File
+        | SinkScheme::Parquet
+        | SinkScheme::Csv
+        | SinkScheme::Jsonl
+        | SinkScheme::Arrow => {}
+        SinkScheme::Duckdb | SinkScheme::S3 | SinkScheme::Postgres => {
+            bail!("'{}' is not a local file output", sink_uri)
+        }
+    }
+    if parsed.path.to_string_lossy().contains('*') || parsed.path.is_dir() {
+        bail!(
+            "'{}' is a partitioned output; only single-file outputs can be exported",
+            sink_uri
+        );
+    }
+    if !parsed.path.is_file() {
+        bail!("Output file '{}' is missing", parsed.path.display());
+    }
+    Ok(parsed.path)
+}
+
+/// Write the export to `partial` and check it. Returns its hash.
+fn write_export(
+    source: &Path,
+    partial: &Path,
+    format: ExportFormat,
+    source_hash: &str,
+    rows: Option<u64>,
+) -> Result<String> {
+    match format {
+        ExportFormat::Original => {
+            fs::copy(source, partial)
+                .with_context(|| format!("Failed to copy '{}'", source.display()))?;
+            let dest_hash = hash(partial)?;
+            if dest_hash != source_hash {
+                bail!(
+                    "Copy of '{}' is corrupt: hash {} (source {})",
+                    source.display(),
+                    dest_hash,
+                    source_hash
+                );
+            }
+            Ok(dest_hash)
+        }
+        ExportFormat::Csv => {
+            let conn = DbConnection::open_duckdb_memory()?;
+            let source_sql = escape_sql_literal(&source.to_string_lossy());
+            let partial_sql = escape_sql_literal(&partial.to_string_lossy());
+            conn.execute(
+                &format!(
+                    "COPY (SELECT * FROM parquet_scan('{}')) TO '{}' (FORMAT CSV, HEADER)",
+                    source_sql, partial_sql
+                ),
+                &[],
+            )
+            .with_context(|| format!("Failed to convert '{}' to CSV", source.display()))?;
+            let written = conn.query_scalar::<i64>(
+                &format!(
+                    "SELECT COUNT(*) FROM read_csv_auto('{}', header = true)",
+                    partial_sql
+                ),
+                &[],
+            )?;
+            let written = written.max(0) as u64;
+            if Some(written) != rows {
+                bail!(
+                    "CSV export of '{}' has {} rows (source {})",
+                    source.display(),
+                    written,
+                    rows.unwrap_or(0)
+                );
+            }
+            hash(partial)
+        }
+    }
+}
+
+fn hash(path: &Path) -> Result<String> {
+    Ok(casparian_hash::hash_file(path)?.hash)
+}
+
+/// Hidden sibling of `dest` the export is written to before the rename.
+fn partial_path(dest: &Path) -> PathBuf {
+    let name = dest
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    dest.with_file_name(format!(".{}.partial", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::types::ArtifactV1;
+    use casparian_state_store::AuditQuery;
+
+    const CONTENTS: &str = "id,amount\n1,10\n2,20\n";
+
+    /// State store with job 7's `orders` output; `receipt_hash` overrides the
+    /// hash its receipt reported.
+    fn store_with_output(dir: &Path, receipt_hash: Option<&str>) -> StateStore {
+        let db_path = dir.join("state.sqlite");
+        let state_store = StateStore::open(&format!("sqlite:{}", db_path.display())).unwrap();
+        state_store.artifacts().init_schema().unwrap();
+
+        let source = dir.join("orders.csv");
+        fs::write(&source, CONTENTS).unwrap();
+        let content_hash = match receipt_hash {
+            Some(receipt_hash) => receipt_hash.to_string(),
+            None => hash(&source).unwrap(),
+        };
+        state_store
+            .artifacts()
+            .insert_job_artifacts(
+                7,
+                &[ArtifactV1::Output {
+                    output_name: "orders".to_string(),
+                    sink_uri: format!("file://{}", source.display()),
+                    table: None,
+                    rows: Some(2),
+                    schema_hash: None,
+                    content_hash: Some(content_hash),
+                }],
+            )
+            .unwrap();
+        state_store
+    }
+
+    #[test]
+    fn test_export_copies_and_audits() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_store = store_with_output(dir.path(), None);
+
+        let dest = dir.path().join("export.csv");
+        let options = ExportOptions::default();
+        let report = export_artifact(&state_store, 7, "orders", &dest, &options).unwrap();
+        assert!(report.receipt_hash_checked);
+        assert_eq!(report.dest_hash, report.source_hash);
+        assert_eq!(report.bytes, CONTENTS.len() as u64);
+        assert_eq!(fs::read_to_string(&dest).unwrap(), CONTENTS);
+        assert!(!partial_path(&dest).exists());
+
+        let entries = state_store
+            .audit()
+            .query(&AuditQuery::new().entity_kind(entity::ARTIFACT))
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].record.entity_id, "7/orders");
+        assert_eq!(entries[0].record.action, "exported");
+
+        // An existing destination is never overwritten
+        let err = export_artifact(&state_store, 7, "orders", &dest, &options).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn test_export_refuses_output_that_differs_from_receipt() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_store = store_with_output(dir.path(), Some(&"0".repeat(64)));
+
+        let dest = dir.path().join("export.csv");
+        let options = ExportOptions::default();
+        let err = export_artifact(&state_store, 7, "orders", &dest, &options).unwrap_err();
+        assert!(err.to_string().contains("does not match its receipt"));
+        assert!(!dest.exists());
+
+        let err = export_artifact(&state_store, 7, "fills", &dest, &options).unwrap_err();
+        assert!(err.to_string().contains("no output named 'fills'"));
+
+        let options = ExportOptions {
+            format: ExportFormat::Csv,
+            actor: None,
+        };
+        let err = export_artifact(&state_store, 7, "orders", &dest, &options).unwrap_err();
+        assert!(err.to_string().contains("needs a Parquet output"));
+    }
+}
@@ -0,0 +1,1205 @@
+//! HTTP control-plane API (feature `http-api`).
+//!
+//! Serves the endpoints described by `casparian_protocol::http_types` as JSON:
+//!
+//! | Method | Path | Body → Response |
+//! |--------|------|-----------------|
+//! | `POST` | `/jobs` | `JobSpec` → `CreateJobResponse` |
+//! | `GET` | `/jobs?status=&namespace=&limit=&offset=` | `ListJobsResponse` |
+//! | `GET` | `/jobs/{id}` | `Job` |
+//! | `GET` | `/jobs/{id}/events?after=` | `ListEventsResponse` |
+//! | `GET` | `/events/stream?after=` | Server-sent `Event`s |
+//! | `GET` | `/approvals?status=` | `ListApprovalsResponse` |
+//! | `POST` | `/approvals/{id}/decide` | `ApprovalDecision` → `ApprovalDecideResponse` |
+//! | `GET` | `/approvals/{id}/audit` | `ListApprovalAuditResponse` |
+//! | `GET` | `/datasets?q=&plugin=&limit=` | `ListDatasetsResponse` |
+//! | `GET` | `/datasets/{name}` | `DatasetDetail` |
+//! | `POST` | `/query` | `QueryRequest` → `QueryResponse` |
+//! | `GET` | `/health`, `/version` | `HealthResponse`, `VersionResponse` |
+//!
+//! Job and approval requests run on the Sentinel's sqlite executor through
+//! the same handlers as the ZMQ control API, so both fronts share one writer.
+//! Queries run read-only against the query catalog. Failures are returned as
+//! `ErrorResponse`. There is no authentication, so only loopback addresses
+//! may be bound. A read-only Sentinel answers job creation and approval
+//! decisions with `403` and code `READ_ONLY_MODE`.
+//!
+//! `POST /jobs` honours an `Idempotency-Key` header: for 24 hours a retry
+//! with the same key and body gets the original `CreateJobResponse` (with
+//! `Idempotent-Replayed: true`) instead of a second job, and the same key
+//! with a different body is refused with `409` and code
+//! `IDEMPOTENCY_KEY_CONFLICT`.
+//!
+//! `/events/stream` sends every new API event (job status changes, approvals,
+//! worker membership) as an SSE message whose `id` is the global `EventId`.
+//! It resumes after the `Last-Event-ID` header or `?after=` cursor; without
+//! either it starts at the newest event.
+
+use crate::control::{ControlRequest, ControlResponse, READ_ONLY_MODE};
+use crate::event_bus::EventBus;
+use crate::sentinel::handle_control_request_db;
+use crate::sqlite_executor::{SqliteContext, SqliteExecutor};
+use anyhow::{bail, Context, Result};
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use casparian_db::{apply_row_limit, validate_read_only, DbConnection, DbValue, UnifiedDbRow};
+use casparian_protocol::http_types::{
+    ApiJobId, ApprovalDecideResponse, ApprovalDecision, ApprovalDecisionType, ApprovalStatus,
+    CreateJobResponse, DatasetDetail, ErrorResponse, Event, EventId, HealthResponse, HttpJobStatus,
+    JobSpec, ListApprovalAuditResponse, ListApprovalsResponse, ListDatasetsResponse,
+    ListEventsResponse, ListJobsResponse, QueryRequest, QueryResponse, RedactionMode,
+    RedactionPolicy, VersionResponse, IDEMPOTENCY_KEY_HEADER,
+};
+use casparian_protocol::types::DataType;
+use casparian_state_store::{StateStore, StateStoreQueueSession};
+use futures::stream::{self, Stream};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, Notify};
+use tracing::{info, warn};
+
+/// Version of the HTTP API surface reported by `/version`.
+pub const HTTP_API_VERSION: &str = "0.1";
+
+/// Upper bound on `QueryRequest::limit`.
+const MAX_QUERY_ROWS: usize = 10_000;
+/// Upper bound on `QueryRequest::timeout_ms`.
+const MAX_QUERY_TIMEOUT_MS: u64 = 60_000;
+/// Hex digits kept when hashing redacted values.
+const HASH_PREFIX_LEN: usize = 12;
+/// How often a caught-up `/events/stream` checks for new events when no
+/// Sentinel event wakes it (e.g. events written by a standalone MCP server).
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Events fetched per `/events/stream` poll.
+const STREAM_BATCH: usize = 256;
+/// How long an idempotency key replays its first response.
+const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+/// Upper bound on the length of an idempotency key.
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+/// Response header marking a replayed `POST /jobs` response.
+const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+/// Datasets returned by `/datasets` without a `limit`.
+const DEFAULT_DATASET_LIMIT: usize = 100;
+/// Upper bound on the `/datasets` `limit`.
+const MAX_DATASET_LIMIT: usize = 1_000;
+
+/// Running HTTP API listener; stopped on drop.
+pub struct HttpApiServer {
+    local_addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+#[derive(Clone)]
+struct ApiState {
+    executor: SqliteExecutor,
+    query_catalog_path: Arc<PathBuf>,
+    started_at: Instant,
+    read_only: bool,
+    /// Receives events from control requests made over HTTP
+    events: EventBus,
+    /// Notified on every Sentinel event so event streams poll right away
+    events_published: Arc<Notify>,
+}
+
+impl HttpApiServer {
+    /// Bind `addr` (e.g. "127.0.0.1:8420") and start serving on a background
+    /// runtime. Event streams follow `events`. With `read_only`, mutating
+    /// requests are refused.
+    pub(crate) fn start(
+        addr: &str,
+        executor: SqliteExecutor,
+        events: &EventBus,
+        query_catalog_path: PathBuf,
+        read_only: bool,
+    ) -> Result<Self> {
+        let bind_addr: SocketAddr = addr
+            .parse()
+            .with_context(|| format!("Invalid HTTP API address: {}", addr))?;
+        if !bind_addr.ip().is_loopback() {
+            bail!(
+                "HTTP API address {} is not loopback; the API is unauthenticated",
+                addr
+            );
+        }
+        let listener = std::net::TcpListener::bind(bind_addr)
+            .with_context(|| format!("Failed to bind HTTP API on {}", addr))?;
+        listener
+            .set_nonblocking(true)
+            .context("Failed to configure HTTP API listener")?;
+        let local_addr = listener
+            .local_addr()
+            .context("Failed to read HTTP API address")?;
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("sentinel-http-api")
+            .enable_all()
+            .build()
+            .context("Failed to start HTTP API runtime")?;
+        let events_published = Arc::new(Notify::new());
+        let notify = events_published.clone();
+        events.on(move |_| notify.notify_waiters());
+        let app = router(ApiState {
+            executor,
+            query_catalog_path: Arc::new(query_catalog_path),
+            started_at: Instant::now(),
+            read_only,
+            events: events.clone(),
+            events_published,
+        });
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("sentinel-http-api".to_string())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    let listener = match tokio::net::TcpListener::from_std(listener) {
+                        Ok(listener) => listener,
+                        Err(err) => {
+                            warn!("HTTP API listener failed: {}", err);
+                            return;
+                        }
+                    };
+                    // Not a graceful shutdown: event streams never finish on
+                    // their own. Dropping the runtime closes open connections.
+                    tokio::select! {
+                        result = axum::serve(listener, app) => {
+                            if let Err(err) = result {
+                                warn!("HTTP API server failed: {}", err);
+                            }
+                        }
+                        _ = shutdown_rx => {}
+                    }
+                });
+            })
+            .context("Failed to spawn HTTP API thread")?;
+        info!("HTTP API listening on http://{}", local_addr);
+        Ok(Self {
+            local_addr,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for HttpApiServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/jobs", post(create_job).get(list_jobs))
+        .route("/jobs/:job_id", get(get_job))
+        .route("/jobs/:job_id/events", get(list_events))
+        .route("/events/stream", get(stream_events))
+        .route("/approvals", get(list_approvals))
+        .route("/approvals/:approval_id/decide", post(decide_approval))
+        .route("/approvals/:approval_id/audit", get(approval_audit))
+        .route("/datasets", get(list_datasets))
+        .route("/datasets/:name", get(get_dataset))
+        .route("/query", post(query))
+        .route("/health", get(health))
+        .route("/version", get(version))
+        .with_state(state)
+}
+
+/// Error returned to HTTP clients as an `ErrorResponse` body.
+struct ApiError {
+    status: StatusCode,
+    body: ErrorResponse,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: ErrorResponse::new(message, code),
+        }
+    }
+
+    fn internal(err: impl std::fmt::Display) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "INTERNAL",
+            err.to_string(),
+        )
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "NOT_FOUND", message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// Run `f` on the sqlite executor without blocking the runtime.
+async fn run_db<R, F>(state: &ApiState, f: F) -> ApiResult<R>
+where
+    R: Send + 'static,
+    F: FnOnce(&StateStore, &StateStoreQueueSession, &mut SqliteContext) -> Result<R>
+        + Send
+        + 'static,
+{
+    let response_rx = state.executor.submit(f).map_err(ApiError::internal)?;
+    tokio::task::spawn_blocking(move || response_rx.recv())
+        .await
+        .map_err(ApiError::internal)?
+        .map_err(ApiError::internal)?
+        .map_err(|err| {
+            ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DB_ERROR",
+                err.to_string(),
+            )
+        })
+}
+
+/// Dispatch a control request exactly as the ZMQ control socket would.
+async fn control(state: &ApiState, request: ControlRequest) -> ApiResult<ControlResponse> {
+    refuse_if_read_only(state, &request)?;
+    let events = state.events.clone();
+    let response = run_db(state, move |state_store, queue, ctx| {
+        Ok(handle_control_request_db(
+            state_store,
+            queue,
+            ctx,
+            &events,
+            request,
+        ))
+    })
+    .await?;
+    control_result(response)
+}
+
+fn refuse_if_read_only(state: &ApiState, request: &ControlRequest) -> ApiResult<()> {
+    if state.read_only && request.is_mutation() {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            READ_ONLY_MODE,
+            "Sentinel is in read-only mode; requests that change state are refused",
+        ));
+    }
+    Ok(())
+}
+
+fn control_result(response: ControlResponse) -> ApiResult<ControlResponse> {
+    match response {
+        ControlResponse::Error { code, message } => Err(ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &code,
+            message,
+        )),
+        response => Ok(response),
+    }
+}
+
+fn unexpected(response: ControlResponse) -> ApiError {
+    ApiError::internal(format!("Unexpected control response: {:?}", response))
+}
+
+/// Result of `POST /jobs` with an idempotency key.
+enum IdempotentCreate {
+    Created(Box<ControlResponse>),
+    Replayed(CreateJobResponse),
+    Conflict,
+}
+
+async fn create_job(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(spec): Json<JobSpec>,
+) -> ApiResult<Response> {
+    let idempotency_key = idempotency_key(&headers)?;
+    let spec_json = serde_json::to_string(&spec).map_err(ApiError::internal)?;
+    let request = ControlRequest::CreateApiJob {
+        job_type: spec.job_type,
+        plugin_name: spec.plugin_name,
+        plugin_version: spec.plugin_version,
+        input_dir: spec.input_dir,
+        output: spec.output,
+        approval_id: None,
+        spec_json: Some(spec_json.clone()),
+    };
+    let Some(key) = idempotency_key else {
+        let response = control(&state, request).await?;
+        return job_created(response).map(IntoResponse::into_response);
+    };
+
+    refuse_if_read_only(&state, &request)?;
+    // The spec was re-serialized, so field order and whitespace do not matter.
+    let request_hash = format!("{:x}", Sha256::digest(spec_json.as_bytes()));
+    let events = state.events.clone();
+    let conflict_key = key.clone();
+    // Lookup, creation and save run as one executor task, so concurrent
+    // retries cannot both create a job.
+    let outcome = run_db(&state, move |state_store, queue, ctx| {
+        let ttl = chrono::Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+        if let Some(stored) = state_store.api().find_idempotent_response(&key, ttl)? {
+            if stored.request_hash != request_hash {
+                return Ok(IdempotentCreate::Conflict);
+            }
+            let original = serde_json::from_str(&stored.response_json)?;
+            return Ok(IdempotentCreate::Replayed(original));
+        }
+        let response = handle_control_request_db(state_store, queue, ctx, &events, request);
+        if let ControlResponse::ApiJobCreated { job_id } = &response {
+            let created = CreateJobResponse {
+                job_id: *job_id,
+                approval_id: None,
+            };
+            let response_json = serde_json::to_string(&created)?;
+            state_store
+                .api()
+                .save_idempotent_response(&key, &request_hash, &response_json)?;
+        }
+        Ok(IdempotentCreate::Created(Box::new(response)))
+    })
+    .await?;
+    match outcome {
+        IdempotentCreate::Created(response) => {
+            job_created(control_result(*response)?).map(IntoResponse::into_response)
+        }
+        IdempotentCreate::Replayed(original) => Ok((
+            StatusCode::CREATED,
+            [(IDEMPOTENT_REPLAYED_HEADER, "true")],
+            Json(original),
+        )
+            .into_response()),
+        IdempotentCreate::Conflict => Err(ApiError {
+            status: StatusCode::CONFLICT,
+            body: ErrorResponse::idempotency_conflict(&conflict_key),
+        }),
+    }
+}
+
+fn job_created(response: ControlResponse) -> ApiResult<(StatusCode, Json<CreateJobResponse>)> {
+    match response {
+        ControlResponse::ApiJobCreated { job_id } => Ok((
+            StatusCode::CREATED,
+            Json(CreateJobResponse {
+                job_id,
+                approval_id: None,
+            }),
+        )),
+        other => Err(unexpected(other)),
+    }
+}
+
+/// The `Idempotency-Key` header, if the request has one.
+fn idempotency_key(headers: &HeaderMap) -> ApiResult<Option<String>> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    let key = value.to_str().map(str::trim).unwrap_or_default();
+    if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "INVALID_IDEMPOTENCY_KEY",
+            format!(
+                "{} must be 1-{} visible ASCII characters",
+                IDEMPOTENCY_KEY_HEADER, MAX_IDEMPOTENCY_KEY_LEN
+            ),
+        ));
+    }
+    Ok(Some(key.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+struct ListJobsParams {
+    status: Option<HttpJobStatus>,
+    namespace: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+async fn list_jobs(
+    State(state): State<ApiState>,
+    Query(params): Query<ListJobsParams>,
+) -> ApiResult<Json<ListJobsResponse>> {
+    let request = ControlRequest::ListApiJobs {
+        status: params.status,
+        namespace: params.namespace,
+        limit: params.limit,
+        offset: params.offset,
+    };
+    match control(&state, request).await? {
+        ControlResponse::ApiJobs(jobs) => Ok(Json(ListJobsResponse {
+            total: jobs.len(),
+            jobs,
+        })),
+        other => Err(unexpected(other)),
+    }
+}
+
+async fn get_job(State(state): State<ApiState>, Path(job_id): Path<u64>) -> ApiResult<Response> {
+    let job_id = ApiJobId::new(job_id);
+    match control(&state, ControlRequest::GetApiJob { job_id }).await? {
+        ControlResponse::ApiJob(Some(job)) => Ok(Json(job).into_response()),
+        ControlResponse::ApiJob(None) => {
+            Err(ApiError::not_found(format!("Job {} not found", job_id)))
+        }
+        other => Err(unexpected(other)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListEventsParams {
+    after: Option<EventId>,
+}
+
+async fn list_events(
+    State(state): State<ApiState>,
+    Path(job_id): Path<u64>,
+    Query(params): Query<ListEventsParams>,
+) -> ApiResult<Json<ListEventsResponse>> {
+    let job_id = ApiJobId::new(job_id);
+    let events = run_db(&state, move |state_store, _, _| {
+        if state_store.api().get_job(job_id)?.is_none() {
+            return Ok(None);
+        }
+        state_store
+            .api()
+            .list_events(job_id, params.after)
+            .map(Some)
+    })
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Job {} not found", job_id)))?;
+    Ok(Json(ListEventsResponse {
+        last_event_id: events.last().map(|event| event.event_id),
+        events,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamParams {
+    after: Option<EventId>,
+}
+
+async fn stream_events(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Query(params): Query<StreamParams>,
+) -> ApiResult<Sse<impl Stream<Item = std::result::Result<SseEvent, axum::Error>>>> {
+    // A reconnecting EventSource sends the last ID it received.
+    let resume = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<EventId>().ok());
+    let cursor = match resume.or(params.after) {
+        Some(cursor) => cursor,
+        None => {
+            run_db(&state, |state_store, _, _| {
+                state_store.api().latest_event_id()
+            })
+            .await?
+        }
+    };
+
+    let pending: VecDeque<Event> = VecDeque::new();
+    let events = stream::unfold(
+        (state, cursor, pending),
+        |(state, mut cursor, mut pending)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    cursor = event.event_id;
+                    let message = SseEvent::default()
+                        .id(event.event_id.to_string())
+                        .json_data(&event);
+                    return Some((message, (state, cursor, pending)));
+                }
+                let after = cursor;
+                let polled = run_db(&state, move |state_store, _, _| {
+                    state_store.api().list_events_since(after, STREAM_BATCH)
+                })
+                .await;
+                match polled {
+                    Ok(events) if !events.is_empty() => pending.extend(events),
+                    Ok(_) => {
+                        tokio::select! {
+                            _ = state.events_published.notified() => {}
+                            _ = tokio::time::sleep(STREAM_POLL_INTERVAL) => {}
+                        }
+                    }
+                    Err(err) => {
+                        warn!("Event stream poll failed: {}", err.body.error);
+                        tokio::time::sleep(STREAM_POLL_INTERVAL).await;
+                    }
+                }
+            }
+        },
+    );
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+#[derive(Debug, Deserialize)]
+struct ListApprovalsParams {
+    status: Option<ApprovalStatus>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+async fn list_approvals(
+    State(state): State<ApiState>,
+    Query(params): Query<ListApprovalsParams>,
+) -> ApiResult<Json<ListApprovalsResponse>> {
+    let request = ControlRequest::ListApprovals {
+        status: params.status,
+        limit: params.limit,
+        offset: params.offset,
+    };
+    match control(&state, request).await? {
+        ControlResponse::Approvals(approvals) => Ok(Json(ListApprovalsResponse {
+            total: approvals.len(),
+            approvals,
+        })),
+        other => Err(unexpected(other)),
+    }
+}
+
+async fn decide_approval(
+    State(state): State<ApiState>,
+    Path(approval_id): Path<String>,
+    Json(decision): Json<ApprovalDecision>,
+) -> ApiResult<Json<ApprovalDecideResponse>> {
+    let request = match decision.decision {
+        ApprovalDecisionType::Approve => ControlRequest::Approve {
+            approval_id: approval_id.clone(),
+            actor: decision.actor,
+            justification: decision.reason,
+        },
+        ApprovalDecisionType::Reject => ControlRequest::Reject {
+            approval_id: approval_id.clone(),
+            reason: decision.reason.unwrap_or_default(),
+            actor: decision.actor,
+        },
+    };
+    match control(&state, request).await? {
+        ControlResponse::ApprovalResult { success: true, .. } => {}
+        ControlResponse::ApprovalResult { message, .. } => {
+            return Err(ApiError::new(StatusCode::CONFLICT, "NOT_PENDING", message));
+        }
+        other => return Err(unexpected(other)),
+    }
+
+    let request = ControlRequest::GetApproval {
+        approval_id: approval_id.clone(),
+    };
+    match control(&state, request).await? {
+        ControlResponse::Approval(Some(approval)) => Ok(Json(ApprovalDecideResponse {
+            approval_id: approval.approval_id,
+            status: approval.status,
+            decided_by: approval.decided_by,
+            job_id: approval.job_id,
+        })),
+        ControlResponse::Approval(None) => Err(ApiError::not_found(format!(
+            "Approval {} not found",
+            approval_id
+        ))),
+        other => Err(unexpected(other)),
+    }
+}
+
+async fn approval_audit(
+    State(state): State<ApiState>,
+    Path(approval_id): Path<String>,
+) -> ApiResult<Json<ListApprovalAuditResponse>> {
+    let request = ControlRequest::ListApprovalAudit {
+        approval_id: approval_id.clone(),
+    };
+    match control(&state, request).await? {
+        ControlResponse::ApprovalAudit(entries) => Ok(Json(ListApprovalAuditResponse {
+            approval_id,
+            entries,
+        })),
+        other => Err(unexpected(other)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListDatasetsParams {
+    q: Option<String>,
+    plugin: Option<String>,
+    limit: Option<usize>,
+}
+
+async fn list_datasets(
+    State(state): State<ApiState>,
+    Query(params): Query<ListDatasetsParams>,
+) -> ApiResult<Json<ListDatasetsResponse>> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_DATASET_LIMIT)
+        .min(MAX_DATASET_LIMIT);
+    let datasets = run_db(&state, move |_, queue, _| {
+        queue.list_datasets(params.q.as_deref(), params.plugin.as_deref(), limit)
+    })
+    .await?;
+    Ok(Json(ListDatasetsResponse {
+        datasets: datasets.iter().map(|dataset| dataset.summary()).collect(),
+    }))
+}
+
+async fn get_dataset(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<DatasetDetail>> {
+    let lookup = name.clone();
+    run_db(&state, move |_, queue, _| queue.describe_dataset(&lookup))
+        .await?
+        .map(|dataset| Json(dataset.detail()))
+        .ok_or_else(|| ApiError::not_found(format!("Dataset {} not found", name)))
+}
+
+async fn query(
+    State(state): State<ApiState>,
+    Json(request): Json<QueryRequest>,
+) -> ApiResult<Json<QueryResponse>> {
+    let catalog = state.query_catalog_path.clone();
+    tokio::task::spawn_blocking(move || run_query(&catalog, &request))
+        .await
+        .map_err(ApiError::internal)?
+        .map(Json)
+}
+
+async fn health(State(state): State<ApiState>) -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok".to_string(),
+        uptime_seconds: state.started_at.elapsed().as_secs(),
+    })
+}
+
+async fn version() -> Json<VersionResponse> {
+    Json(VersionResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol_version: HTTP_API_VERSION.to_string(),
+        build_info: None,
+    })
+}
+
+/// Validate and run a read-only query against the query catalog.
+fn run_query(catalog: &std::path::Path, request: &QueryRequest) -> ApiResult<QueryResponse> {
+    validate_read_only(&request.sql)
+        .map_err(|err| ApiError::new(StatusCode::BAD_REQUEST, "INVALID_QUERY", err.to_string()))?;
+    let limit = request.limit.clamp(1, MAX_QUERY_ROWS);
+    let timeout = Duration::from_millis(request.timeout_ms.min(MAX_QUERY_TIMEOUT_MS));
+
+    let start = Instant::now();
+    let conn = DbConnection::open_duckdb_readonly(catalog).map_err(|err| {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "CATALOG_UNAVAILABLE",
+            format!("Failed to open query catalog: {}", err),
+        )
+    })?;
+    let sql = apply_row_limit(&request.sql, limit);
+
+    let interrupt = conn.interrupt_handle();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let watchdog = std::thread::spawn(move || match done_rx.recv_timeout(timeout) {
+        Err(RecvTimeoutError::Timeout) => {
+            interrupt.interrupt();
+            true
+        }
+        _ => false,
+    });
+    let result = conn.query_all(&sql, &[]);
+    let _ = done_tx.send(());
+    let timed_out = watchdog.join().unwrap_or(false);
+    let rows = match result {
+        Ok(rows) => rows,
+        Err(_) if timed_out => {
+            return Err(ApiError::new(
+                StatusCode::REQUEST_TIMEOUT,
+                "QUERY_TIMEOUT",
+                format!("Query exceeded timeout of {}ms", timeout.as_millis()),
+            ))
+        }
+        Err(err) => {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "QUERY_FAILED",
+                format!("Query failed: {}", err),
+            ))
+        }
+    };
+
+    let (columns, types) = match rows.first() {
+        Some(first) => (
+            first.column_names().to_vec(),
+            (0..first.len())
+                .map(|index| first.get_raw(index).map_or(DataType::Null, value_type))
+                .collect(),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+    let rows: Vec<Vec<Value>> = rows
+        .iter()
+        .map(|row| row_to_json(row, &request.redaction))
+        .collect();
+    Ok(QueryResponse {
+        columns,
+        types,
+        row_count: rows.len(),
+        truncated: rows.len() >= limit,
+        rows,
+        execution_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+fn row_to_json(row: &UnifiedDbRow, policy: &RedactionPolicy) -> Vec<Value> {
+    (0..row.len())
+        .map(|index| {
+            let value = row.get_raw(index).map_or(Value::Null, value_to_json);
+            redact(value, policy)
+        })
+        .collect()
+}
+
+fn value_to_json(value: &DbValue) -> Value {
+    match value {
+        DbValue::Null => Value::Null,
+        DbValue::Integer(v) => Value::from(*v),
+        DbValue::Real(v) => Value::from(*v),
+        DbValue::Text(v) => Value::from(v.as_str()),
+        DbValue::Blob(v) => Value::from(format!("<{} bytes>", v.len())),
+        DbValue::Boolean(v) => Value::from(*v),
+        DbValue::Timestamp(v) => Value::from(v.to_rfc3339()),
+    }
+}
+
+fn value_type(value: &DbValue) -> DataType {
+    match value {
+        DbValue::Null => DataType::Null,
+        DbValue::Integer(_) => DataType::Int64,
+        DbValue::Real(_) => DataType::Float64,
+        DbValue::Text(_) => DataType::String,
+        DbValue::Blob(_) => DataType::Binary,
+        DbValue::Boolean(_) => DataType::Boolean,
+        DbValue::Timestamp(_) => DataType::Timestamp,
+    }
+}
+
+/// Redact a result value; booleans and nulls are never sensitive.
+fn redact(value: Value, policy: &RedactionPolicy) -> Value {
+    match (policy.mode, value) {
+        (RedactionMode::None, value) => value,
+        (_, value @ (Value::Null | Value::Bool(_))) => value,
+        (RedactionMode::Truncate, Value::String(text)) => {
+            if text.chars().count() <= policy.max_value_length {
+                Value::String(text)
+            } else {
+                let truncated: String = text.chars().take(policy.max_value_length).collect();
+                Value::String(format!("{}...", truncated))
+            }
+        }
+        (RedactionMode::Truncate, value) => value,
+        (RedactionMode::Hash, value) => {
+            let text = match value {
+                Value::String(text) => text,
+                other => other.to_string(),
+            };
+            let digest = format!("{:x}", Sha256::digest(text.as_bytes()));
+            Value::String(format!("[hash:{}]", &digest[..HASH_PREFIX_LEN]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::http_types::{EventType, HttpJobType};
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    fn request(addr: SocketAddr, method: &str, path: &str, body: Option<&str>) -> (u16, Value) {
+        let (status, _, payload) = request_with_headers(addr, method, path, "", body);
+        (status, payload)
+    }
+
+    /// Send a request with extra `headers` ("Name: value\r\n" lines) and
+    /// return the status, response head and JSON body.
+    fn request_with_headers(
+        addr: SocketAddr,
+        method: &str,
+        path: &str,
+        headers: &str,
+        body: Option<&str>,
+    ) -> (u16, String, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let body = body.unwrap_or("");
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+             {}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            headers,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let (head, payload) = response.split_once("\r\n\r\n").unwrap();
+        (
+            status,
+            head.to_ascii_lowercase(),
+            serde_json::from_str(payload).unwrap_or(Value::Null),
+        )
+    }
+
+    fn start(dir: &std::path::Path) -> (HttpApiServer, Arc<StateStore>) {
+        start_with(dir, false)
+    }
+
+    fn start_with(dir: &std::path::Path, read_only: bool) -> (HttpApiServer, Arc<StateStore>) {
+        let url = format!("sqlite:{}", dir.join("state.db").display());
+        let state_store = Arc::new(StateStore::open(&url).unwrap());
+        state_store.init().unwrap();
+        let executor = SqliteExecutor::start(state_store.clone()).unwrap();
+        let server = HttpApiServer::start(
+            "127.0.0.1:0",
+            executor,
+            &EventBus::new(),
+            dir.join("catalog.duckdb"),
+            read_only,
+        )
+        .unwrap();
+        (server, state_store)
+    }
+
+    #[test]
+    fn test_job_lifecycle_over_http() {
+        let dir = tempfile::tempdir().unwrap();
+        let (server, state_store) = start(dir.path());
+        let addr = server.local_addr();
+
+        let spec = r#"{"job_type":"run","plugin_name":"orders","input_dir":"/data/in"}"#;
+        let (status, created) = request(addr, "POST", "/jobs", Some(spec));
+        assert_eq!(status, 201, "{}", created);
+        let job_id = created["job_id"].as_u64().unwrap();
+
+        let (status, listed) = request(addr, "GET", "/jobs?status=queued", None);
+        assert_eq!(status, 200, "{}", listed);
+        assert_eq!(listed["total"], 1);
+        assert_eq!(listed["jobs"][0]["plugin_name"], "orders");
+
+        let (status, job) = request(addr, "GET", &format!("/jobs/{}", job_id), None);
+        assert_eq!(status, 200);
+        assert_eq!(job["input_dir"], "/data/in");
+
+        state_store
+            .api()
+            .insert_event(ApiJobId::new(job_id), &EventType::JobStarted)
+            .unwrap();
+        let (status, events) = request(addr, "GET", &format!("/jobs/{}/events", job_id), None);
+        assert_eq!(status, 200, "{}", events);
+        assert_eq!(events["events"][0]["type"], "job_started");
+        let after = events["last_event_id"].as_u64().unwrap();
+        let path = format!("/jobs/{}/events?after={}", job_id, after);
+        let (_, newer) = request(addr, "GET", &path, None);
+        assert_eq!(newer["events"], Value::Array(Vec::new()));
+
+        let (status, missing) = request(addr, "GET", "/jobs/999", None);
+        assert_eq!(status, 404);
+        assert_eq!(missing["code"], "NOT_FOUND");
+    }
+
+    #[test]
+    fn test_idempotency_key_replays_job_creation() {
+        let dir = tempfile::tempdir().unwrap();
+        let (server, _) = start(dir.path());
+        let addr = server.local_addr();
+        let post = |key: &str, body: &str| {
+            let header = format!("Idempotency-Key: {}\r\n", key);
+            request_with_headers(addr, "POST", "/jobs", &header, Some(body))
+        };
+
+        let spec = r#"{"job_type":"run","plugin_name":"orders","input_dir":"/data/in"}"#;
+        let (status, head, created) = post("retry-1", spec);
+        assert_eq!(status, 201, "{}", created);
+        assert!(!head.contains("idempotent-replayed"));
+
+        // Same body with other formatting and field order is a replay
+        let reordered =
+            r#"{ "input_dir": "/data/in", "plugin_name": "orders", "job_type": "run" }"#;
+        let (status, head, replayed) = post("retry-1", reordered);
+        assert_eq!(status, 201, "{}", replayed);
+        assert!(head.contains("idempotent-replayed: true"));
+        assert_eq!(replayed, created);
+
+        let other = r#"{"job_type":"run","plugin_name":"invoices","input_dir":"/data/in"}"#;
+        let (status, _, conflict) = post("retry-1", other);
+        assert_eq!(status, 409, "{}", conflict);
+        assert_eq!(conflict["code"], "IDEMPOTENCY_KEY_CONFLICT");
+        assert_eq!(conflict["details"]["idempotency_key"], "retry-1");
+
+        let (status, _, fresh) = post("retry-2", other);
+        assert_eq!(status, 201, "{}", fresh);
+        assert_ne!(fresh["job_id"], created["job_id"]);
+
+        let (_, listed) = request(addr, "GET", "/jobs", None);
+        assert_eq!(listed["total"], 2);
+
+        let (status, _, invalid) = post(" ", spec);
+        assert_eq!(status, 400, "{}", invalid);
+    }
+
+    fn open_stream(addr: SocketAddr, path: &str, last_event_id: Option<EventId>) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let resume = last_event_id
+            .map(|id| format!("Last-Event-ID: {}\r\n", id))
+            .unwrap_or_default();
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\n{}\r\n",
+            path, resume
+        )
+        .unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        stream
+    }
+
+    /// Read from an open stream until `needle` shows up (or 10s pass).
+    fn read_until(stream: &mut TcpStream, needle: &str) -> String {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut seen = String::new();
+        let mut buf = [0u8; 4096];
+        while !seen.contains(needle) && Instant::now() < deadline {
+            match stream.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => seen.push_str(&String::from_utf8_lossy(&buf[..read])),
+                Err(_) => {}
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn test_event_stream_resumes_after_last_event_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let (server, state_store) = start(dir.path());
+        let addr = server.local_addr();
+
+        let job_id = state_store
+            .api()
+            .create_job(
+                HttpJobType::Run,
+                "orders",
+                None,
+                "/data/in",
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        state_store
+            .api()
+            .update_job_status(job_id, HttpJobStatus::Running)
+            .unwrap();
+        let started = state_store.api().latest_event_id().unwrap();
+
+        let mut replay = open_stream(addr, "/events/stream?after=0", None);
+        let seen = read_until(&mut replay, "job_started");
+        assert!(seen.contains("text/event-stream"), "{}", seen);
+        assert!(seen.contains("job_started"), "{}", seen);
+        drop(replay);
+
+        let mut resumed = open_stream(addr, "/events/stream", Some(started));
+        state_store
+            .api()
+            .update_job_status(job_id, HttpJobStatus::Completed)
+            .unwrap();
+        let seen = read_until(&mut resumed, "job_finished");
+        assert!(seen.contains("job_finished"), "{}", seen);
+        assert!(!seen.contains("job_started"), "{}", seen);
+    }
+
+    #[test]
+    fn test_approval_decision_and_audit() {
+        let dir = tempfile::tempdir().unwrap();
+        let (server, state_store) = start(dir.path());
+        let addr = server.local_addr();
+
+        let operation = serde_json::from_value(serde_json::json!({
+            "type": "run",
+            "plugin_name": "orders",
+            "input_dir": "/data/in",
+            "file_count": 3
+        }))
+        .unwrap();
+        state_store
+            .api()
+            .create_approval(
+                "appr-1",
+                &operation,
+                "run orders",
+                chrono::Duration::hours(1),
+            )
+            .unwrap();
+
+        let (_, pending) = request(addr, "GET", "/approvals?status=pending", None);
+        assert_eq!(pending["total"], 1);
+
+        let decision = r#"{"decision":"approve","actor":"ops"}"#;
+        let (status, decided) = request(addr, "POST", "/approvals/appr-1/decide", Some(decision));
+        assert_eq!(status, 200, "{}", decided);
+        assert_eq!(decided["status"], "approved");
+        assert_eq!(decided["decided_by"], "ops");
+
+        let (status, again) = request(addr, "POST", "/approvals/appr-1/decide", Some(decision));
+        assert_eq!(status, 409, "{}", again);
+
+        let (_, audit) = request(addr, "GET", "/approvals/appr-1/audit", None);
+        assert_eq!(audit["approval_id"], "appr-1");
+        assert_eq!(audit["entries"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_read_only_refuses_mutations() {
+        let dir = tempfile::tempdir().unwrap();
+        let (server, _) = start_with(dir.path(), true);
+        let addr = server.local_addr();
+
+        let spec = r#"{"job_type":"run","plugin_name":"orders","input_dir":"/data/in"}"#;
+        let (status, refused) = request(addr, "POST", "/jobs", Some(spec));
+        assert_eq!(status, 403, "{}", refused);
+        assert_eq!(refused["code"], READ_ONLY_MODE);
+
+        let decision = r#"{"decision":"approve","actor":"ops"}"#;
+        let (status, _) = request(addr, "POST", "/approvals/appr-1/decide", Some(decision));
+        assert_eq!(status, 403);
+
+        let (status, listed) = request(addr, "GET", "/jobs", None);
+        assert_eq!(status, 200, "{}", listed);
+    }
+
+    #[test]
+    fn test_dataset_catalog_endpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let (server, _) = start(dir.path());
+        let addr = server.local_addr();
+
+        let conn = DbConnection::open_sqlite(&dir.path().join("state.db")).unwrap();
+        casparian_state_store::dataset_catalog::register(
+            &conn,
+            &casparian_state_store::DatasetCommit {
+                name: "orders".to_string(),
+                plugin_name: "orders_parser".to_string(),
+                job_id: 1,
+                sink_uri: "parquet:///out/orders_1.parquet".to_string(),
+                sink_mode: casparian_protocol::SinkMode::Append,
+                table_name: None,
+                schema: None,
+                schema_hash: None,
+                rows: 42,
+                partition_columns: Vec::new(),
+                lineage_root: Some("/data/orders".to_string()),
+            },
+        )
+        .unwrap();
+
+        let (status, listed) = request(addr, "GET", "/datasets?q=ORD", None);
+        assert_eq!(status, 200, "{}", listed);
+        assert_eq!(listed["datasets"][0]["name"], "orders");
+        assert_eq!(listed["datasets"][0]["row_count"], 42);
+        let (_, none) = request(addr, "GET", "/datasets?q=fills", None);
+        assert_eq!(none["datasets"], Value::Array(Vec::new()));
+
+        let (status, detail) = request(addr, "GET", "/datasets/orders", None);
+        assert_eq!(status, 200, "{}", detail);
+        assert_eq!(detail["lineage_root"], "/data/orders");
+        assert_eq!(detail["job_count"], 1);
+
+        let (status, missing) = request(addr, "GET", "/datasets/fills", None);
+        assert_eq!(status, 404);
+        assert_eq!(missing["code"], "NOT_FOUND");
+    }
+
+    #[test]
+    fn test_health_version_and_query_guard() {
+        let dir = tempfile::tempdir().unwrap();
+        let (server, _) = start(dir.path());
+        let addr = server.local_addr();
+
+        let (status, health) = request(addr, "GET", "/health", None);
+        assert_eq!(status, 200);
+        assert_eq!(health["status"], "ok");
+
+        let (_, version) = request(addr, "GET", "/version", None);
+        assert_eq!(version["protocol_version"], HTTP_API_VERSION);
+
+        let (status, rejected) = request(addr, "POST", "/query", Some(r#"{"sql":"DROP TABLE x"}"#));
+        assert_eq!(status, 400);
+        assert_eq!(rejected["code"], "INVALID_QUERY");
+    }
+
+    #[test]
+    fn test_rejects_non_loopback_bind() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite:{}", dir.path().join("state.db").display());
+        let state_store = Arc::new(StateStore::open(&url).unwrap());
+        state_store.init().unwrap();
+        let executor = SqliteExecutor::start(state_store).unwrap();
+        let err = HttpApiServer::start(
+            "0.0.0.0:0",
+            executor,
+            &EventBus::new(),
+            PathBuf::new(),
+            false,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("not loopback"), "{}", err);
+    }
+
+    #[test]
+    fn test_redaction_modes() {
+        let mut policy = RedactionPolicy::default();
+        let hashed = redact(Value::from("alice@example.com"), &policy);
+        assert!(hashed.as_str().unwrap().starts_with("[hash:"));
+        assert_eq!(redact(Value::Bool(true), &policy), Value::Bool(true));
+
+        policy.mode = RedactionMode::Truncate;
+        policy.max_value_length = 3;
+        assert_eq!(
+            redact(Value::from("abcdef"), &policy),
+            Value::from("abc...")
+        );
+
+        policy.mode = RedactionMode::None;
+        assert_eq!(redact(Value::from(42), &policy), Value::from(42));
+    }
+}
@@ -8,27 +8,60 @@
 #![allow(clippy::get_first)]
 #![allow(dead_code)]
 
+pub mod compaction;
+pub mod config_reload;
 pub mod control;
 pub mod control_client;
 mod catalog_executor;
 mod sqlite_executor;
 pub mod db;
+pub mod event_bus;
+pub mod export;
+#[cfg(feature = "http-api")]
+pub mod http_api;
 pub mod metrics;
+pub mod metrics_server;
+pub mod receipt_verify;
+pub mod retention;
+pub mod scheduler;
+pub mod schedules;
 pub mod sentinel;
+pub mod transport_security;
+pub mod worker_health;
+pub mod worker_pool;
 
 pub use control::{
-    ControlRequest, ControlResponse, JobInfo, QueueStatsInfo, ScoutRuleInfo, ScoutScanProgress,
-    ScoutScanStatus, ScoutSourceInfo, ScoutTagCount, ScoutTagStats, ScanState, DEFAULT_CONTROL_ADDR,
+    ControlRequest, ControlResponse, DeadLetterInfo, JobInfo, PluginRollbackInfo, QueueStatsInfo,
+    ScanState, ScheduleInfo, ScoutRuleInfo, ScoutScanProgress, ScoutScanStatus, ScoutSourceInfo,
+    ScoutTagCount, ScoutTagStats, SubscriptionInfo, SystemPulse, WorkerHealthInfo,
+    WorkerHealthReport, DEFAULT_CONTROL_ADDR, READ_ONLY_MODE,
 };
-pub use control_client::ControlClient;
+pub use control_client::{ControlClient, ReadOnlyMode};
 pub use db::api_storage::ApiStorage;
+pub use db::dataset_catalog::{self, DatasetRecord};
 pub use db::expected_outputs::{ExpectedOutputs, OutputSpec};
+pub use event_bus::{EventBus, JobTransition, SentinelEvent};
+pub use export::{export_artifact, ExportFormat, ExportOptions, ExportReport};
+pub use db::lineage::{LineageGraph, LineageNode, LineageRecord, LineageStorage};
 pub use db::{
-    queue::{Job, JobDetails, PluginDetails, QueueStats},
+    models::DeadLetterJob,
+    queue::{
+        Job, JobDetails, JobHistoryQuery, JobHistorySort, JobLogChunk, PluginDetails, QueueStats,
+    },
     JobQueue,
 };
 pub use metrics::METRICS;
-pub use sentinel::{Sentinel, SentinelConfig};
+pub use compaction::{compact_topic, run_due_compactions, CompactOptions, CompactionReport};
+pub use retention::{collect_garbage, GcOptions, GcReport};
+pub use scheduler::{
+    route_job, DispatchPolicy, DispatchScheduler, JobRouting, QueuedJob, SchedulingPolicy,
+};
+pub use sentinel::{
+    Sentinel, SentinelConfig, DEFAULT_PRIORITY_AGING_SECS, DEFAULT_SCHEMA_DRIFT_THRESHOLD,
+};
+pub use transport_security::SecurityConfig;
+pub use worker_health::WorkerHealthConfig;
+pub use worker_pool::WorkerPoolConfig;
 
 #[derive(clap::Parser, Debug)]
 #[command(
@@ -36,7 +69,8 @@ pub use sentinel::{Sentinel, SentinelConfig};
     about = "Rust Sentinel for Casparian Flow"
 )]
 pub struct SentinelArgs {
-    /// ZMQ bind address for workers
+    /// Bind address for workers (tcp://, ipc://, or grpc://host:port with the
+    /// grpc feature)
     #[arg(
         long,
         default_value_t = casparian_protocol::defaults::DEFAULT_SENTINEL_BIND_ADDR.to_string()
@@ -66,4 +100,145 @@ pub struct SentinelArgs {
     /// Disable the Control API entirely.
     #[arg(long)]
     pub no_control_api: bool,
+
+    /// Policy for choosing which idle worker receives the next job
+    #[arg(long, value_enum, default_value_t = SchedulingPolicy::RoundRobin)]
+    pub scheduling_policy: SchedulingPolicy,
+
+    /// Serve Prometheus `/metrics` and `/healthz` over HTTP on this address
+    /// (e.g., "127.0.0.1:9464"). Disabled if not specified.
+    #[arg(long)]
+    pub metrics_addr: Option<String>,
+
+    /// Serve the HTTP control-plane API on this loopback address
+    /// (e.g., "127.0.0.1:8420"). Requires the `http-api` feature.
+    #[arg(long)]
+    pub http_api_addr: Option<String>,
+
+    /// Encrypt worker traffic with CurveZMQ. Loads sentinel.key and the
+    /// authorized_workers allow-list from ~/.casparian_flow/keys.
+    #[arg(long)]
+    pub curve: bool,
+
+    /// Worker public key allow-list (default: ~/.casparian_flow/keys/authorized_workers)
+    #[arg(long, requires = "curve")]
+    pub authorized_keys: Option<std::path::PathBuf>,
+
+    /// Dev mode: accept plugin deploys without a publisher signature
+    #[arg(long, env = "CASPARIAN_ALLOW_UNSIGNED_DEPLOYS")]
+    pub allow_unsigned_deploys: bool,
+
+    /// Max files per DISPATCH: small files of the same plugin are sent to a
+    /// worker together (1 = one file per dispatch)
+    #[arg(long, env = "CASPARIAN_DISPATCH_BATCH_SIZE", default_value_t = 1)]
+    pub dispatch_batch_size: usize,
+
+    /// Seconds without a message before a worker is DEGRADED (no new dispatches)
+    #[arg(long, default_value_t = 35.0)]
+    pub worker_degraded_after: f64,
+
+    /// Seconds without a message before a worker is UNREACHABLE
+    #[arg(long, default_value_t = 45.0)]
+    pub worker_unreachable_after: f64,
+
+    /// Seconds without a message before a worker is EVICTED and its jobs failed
+    #[arg(long, default_value_t = 60.0)]
+    pub worker_evict_after: f64,
+
+    /// Recoveries within --worker-flap-window that mark a worker as flapping
+    #[arg(long, default_value_t = 3)]
+    pub worker_flap_threshold: usize,
+
+    /// Window (seconds) for counting worker recoveries
+    #[arg(long, default_value_t = 300.0)]
+    pub worker_flap_window: f64,
+
+    /// Seconds a job may wait in the queue before it is raised to HIGH (0 = never)
+    #[arg(long, default_value_t = DEFAULT_PRIORITY_AGING_SECS)]
+    pub priority_aging_secs: u64,
+
+    /// Let URGENT jobs preempt LOW jobs when every worker is busy
+    #[arg(long)]
+    pub preempt_low_priority: bool,
+
+    /// Audit mode: never dispatch, deploy or write to the state store;
+    /// mutating control requests fail with READ_ONLY_MODE
+    #[arg(long, env = "CASPARIAN_READ_ONLY")]
+    pub read_only: bool,
+
+    /// Ignore cached input hashes and have workers hash every file again
+    #[arg(long)]
+    pub rehash: bool,
+
+    /// Raise a SchemaDrift event once a dataset has more drifted columns
+    /// (unmapped source columns plus type drifts) than this
+    #[arg(long, default_value_t = DEFAULT_SCHEMA_DRIFT_THRESHOLD)]
+    pub schema_drift_threshold: usize,
+
+    /// Start up to N local worker processes as jobs queue up, restarting
+    /// crashed ones (capped by --max-workers)
+    #[arg(long, value_name = "N")]
+    pub worker_pool: Option<usize>,
+
+    /// Pooled workers kept running with an empty queue
+    #[arg(long, default_value_t = 0, requires = "worker_pool")]
+    pub worker_pool_min: usize,
+
+    /// Parquet output directory of pooled workers
+    #[arg(long, default_value = "output", requires = "worker_pool")]
+    pub worker_pool_output: std::path::PathBuf,
+
+    /// Settings file with scheduling overrides (max_workers, scheduling_policy,
+    /// ...); changes are applied without a restart
+    #[arg(long, env = "CASPARIAN_SENTINEL_CONFIG")]
+    pub config: Option<std::path::PathBuf>,
+}
+
+impl SentinelArgs {
+    /// Transport security selected by `--curve` / `--authorized-keys`.
+    pub fn security_config(&self) -> anyhow::Result<SecurityConfig> {
+        if !self.curve {
+            return Ok(SecurityConfig::disabled());
+        }
+        SecurityConfig::from_keys_dir(
+            &casparian_protocol::keys::keys_dir(),
+            self.authorized_keys.as_deref(),
+        )
+    }
+
+    /// Worker health thresholds selected by the `--worker-*` flags.
+    pub fn worker_health_config(&self) -> WorkerHealthConfig {
+        WorkerHealthConfig {
+            degraded_after_secs: self.worker_degraded_after,
+            unreachable_after_secs: self.worker_unreachable_after,
+            evict_after_secs: self.worker_evict_after,
+            flap_window_secs: self.worker_flap_window,
+            flap_threshold: self.worker_flap_threshold,
+        }
+    }
+
+    /// Local worker pool selected by `--worker-pool`. Workers run `program`
+    /// with `subcommand` followed by the connection flags.
+    pub fn worker_pool_config(
+        &self,
+        program: std::path::PathBuf,
+        subcommand: &[&str],
+    ) -> anyhow::Result<Option<WorkerPoolConfig>> {
+        let Some(max_workers) = self.worker_pool else {
+            return Ok(None);
+        };
+        if self.curve {
+            anyhow::bail!("--worker-pool does not support --curve");
+        }
+        let mut args: Vec<String> = subcommand.iter().map(|arg| arg.to_string()).collect();
+        args.extend([
+            "--connect".to_string(),
+            worker_pool::local_connect_addr(&self.bind),
+            "--output".to_string(),
+            self.worker_pool_output.display().to_string(),
+        ]);
+        let mut config = WorkerPoolConfig::new(program, args, max_workers);
+        config.min_workers = self.worker_pool_min;
+        Ok(Some(config))
+    }
 }
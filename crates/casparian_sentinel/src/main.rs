@@ -5,7 +5,9 @@
 //! Usage:
 //!     casparian-sentinel --bind tcp://127.0.0.1:5555 --state-store sqlite:/path/to/state.sqlite
 
-use casparian_sentinel::{Sentinel, SentinelConfig};
+use casparian_sentinel::{
+    SchedulingPolicy, SecurityConfig, Sentinel, SentinelConfig, WorkerHealthConfig,
+};
 use clap::Parser;
 use tracing_subscriber::Layer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -16,7 +18,8 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
     about = "Rust Sentinel for Casparian Flow"
 )]
 struct Args {
-    /// ZMQ bind address for workers
+    /// Bind address for workers (tcp://, ipc://, or grpc://host:port with the
+    /// grpc feature)
     #[arg(
         long,
         default_value_t = casparian_protocol::defaults::DEFAULT_SENTINEL_BIND_ADDR.to_string()
@@ -43,6 +46,81 @@ struct Args {
     /// Disable the Control API entirely.
     #[arg(long)]
     no_control_api: bool,
+
+    /// Policy for choosing which idle worker receives the next job
+    #[arg(long, value_enum, default_value_t = SchedulingPolicy::RoundRobin)]
+    scheduling_policy: SchedulingPolicy,
+
+    /// Serve Prometheus `/metrics` and `/healthz` over HTTP on this address
+    #[arg(long)]
+    metrics_addr: Option<String>,
+
+    /// Serve the HTTP control-plane API on this loopback address
+    #[arg(long)]
+    http_api_addr: Option<String>,
+
+    /// Encrypt worker traffic with CurveZMQ (keys from ~/.casparian_flow/keys)
+    #[arg(long)]
+    curve: bool,
+
+    /// Worker public key allow-list (default: ~/.casparian_flow/keys/authorized_workers)
+    #[arg(long, requires = "curve")]
+    authorized_keys: Option<std::path::PathBuf>,
+
+    /// Dev mode: accept plugin deploys without a publisher signature
+    #[arg(long, env = "CASPARIAN_ALLOW_UNSIGNED_DEPLOYS")]
+    allow_unsigned_deploys: bool,
+
+    /// Max files per DISPATCH: small files of the same plugin are sent to a
+    /// worker together (1 = one file per dispatch)
+    #[arg(long, env = "CASPARIAN_DISPATCH_BATCH_SIZE", default_value_t = 1)]
+    dispatch_batch_size: usize,
+
+    /// Seconds without a message before a worker is DEGRADED (no new dispatches)
+    #[arg(long, default_value_t = 35.0)]
+    worker_degraded_after: f64,
+
+    /// Seconds without a message before a worker is UNREACHABLE
+    #[arg(long, default_value_t = 45.0)]
+    worker_unreachable_after: f64,
+
+    /// Seconds without a message before a worker is EVICTED and its jobs failed
+    #[arg(long, default_value_t = 60.0)]
+    worker_evict_after: f64,
+
+    /// Recoveries within --worker-flap-window that mark a worker as flapping
+    #[arg(long, default_value_t = 3)]
+    worker_flap_threshold: usize,
+
+    /// Window (seconds) for counting worker recoveries
+    #[arg(long, default_value_t = 300.0)]
+    worker_flap_window: f64,
+
+    /// Seconds a job may wait in the queue before it is raised to HIGH (0 = never)
+    #[arg(long, default_value_t = casparian_sentinel::DEFAULT_PRIORITY_AGING_SECS)]
+    priority_aging_secs: u64,
+
+    /// Let URGENT jobs preempt LOW jobs when every worker is busy
+    #[arg(long)]
+    preempt_low_priority: bool,
+
+    /// Audit mode: never dispatch, deploy or write to the state store;
+    /// mutating control requests fail with READ_ONLY_MODE
+    #[arg(long, env = "CASPARIAN_READ_ONLY")]
+    read_only: bool,
+
+    /// Ignore cached input hashes and have workers hash every file again
+    #[arg(long)]
+    rehash: bool,
+
+    /// Raise a SchemaDrift event once a dataset has more drifted columns
+    /// (unmapped source columns plus type drifts) than this
+    #[arg(long, default_value_t = casparian_sentinel::DEFAULT_SCHEMA_DRIFT_THRESHOLD)]
+    schema_drift_threshold: usize,
+
+    /// Settings file with scheduling overrides; changes apply without a restart
+    #[arg(long, env = "CASPARIAN_SENTINEL_CONFIG")]
+    config: Option<std::path::PathBuf>,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -96,6 +174,16 @@ fn main() -> anyhow::Result<()> {
         tracing::info!("  Control API: {}", control);
     }
 
+    let security = if args.curve {
+        tracing::info!("  Transport: CURVE");
+        SecurityConfig::from_keys_dir(
+            &casparian_protocol::keys::keys_dir(),
+            args.authorized_keys.as_deref(),
+        )?
+    } else {
+        SecurityConfig::disabled()
+    };
+
     let config = SentinelConfig {
         bind_addr: args.bind,
         state_store_url,
@@ -104,6 +192,26 @@ fn main() -> anyhow::Result<()> {
         query_catalog_path: args
             .query_catalog
             .unwrap_or_else(casparian_protocol::paths::default_query_catalog_path),
+        scheduling_policy: args.scheduling_policy,
+        metrics_addr: args.metrics_addr,
+        http_api_addr: args.http_api_addr,
+        security,
+        allow_unsigned_deploys: args.allow_unsigned_deploys,
+        dispatch_batch_size: args.dispatch_batch_size,
+        worker_health: WorkerHealthConfig {
+            degraded_after_secs: args.worker_degraded_after,
+            unreachable_after_secs: args.worker_unreachable_after,
+            evict_after_secs: args.worker_evict_after,
+            flap_window_secs: args.worker_flap_window,
+            flap_threshold: args.worker_flap_threshold,
+        },
+        priority_aging_secs: args.priority_aging_secs,
+        preempt_low_priority: args.preempt_low_priority,
+        read_only: args.read_only,
+        rehash: args.rehash,
+        schema_drift_threshold: args.schema_drift_threshold,
+        worker_pool: None,
+        config_path: args.config,
     };
 
     // Bind and run
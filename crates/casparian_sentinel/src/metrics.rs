@@ -8,8 +8,14 @@
 //! - Lock-free atomics where possible
 //! - Single writer, multiple readers pattern
 
+use casparian_protocol::metrics::{
+    BreakdownEntry, JobBreakdown, JobOutcome, PluginMetricsReport, LATENCY_BUCKETS_MS,
+};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Instant;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 /// Global metrics instance - lock-free atomics for counters
 pub static METRICS: Metrics = Metrics::new();
@@ -23,6 +29,10 @@ pub struct Metrics {
     pub jobs_aborted: AtomicU64,
     pub jobs_rejected: AtomicU64,
     pub jobs_retried: AtomicU64,
+    /// Dispatch attempts where no connected worker met the plugin's requirements
+    pub jobs_unroutable: AtomicU64,
+    /// LOW jobs requeued to free a worker for an URGENT job
+    pub jobs_preempted: AtomicU64,
 
     // Worker counters
     pub workers_registered: AtomicU64,
@@ -39,6 +49,30 @@ pub struct Metrics {
     // Timing (cumulative microseconds for averaging)
     pub dispatch_time_us: AtomicU64,
     pub conclude_time_us: AtomicU64,
+
+    // Liveness (unix millis of the last event loop iteration, 0 = never)
+    pub last_loop_tick_ms: AtomicU64,
+
+    // Per-plugin / per-tag / per-namespace breakdown (updated once per concluded job)
+    breakdown: Mutex<Breakdown>,
+}
+
+/// Labelled job counters keyed by plugin name, file tag and plugin namespace.
+#[derive(Debug, Default)]
+struct Breakdown {
+    plugins: BTreeMap<String, JobBreakdown>,
+    tags: BTreeMap<String, JobBreakdown>,
+    namespaces: BTreeMap<String, JobBreakdown>,
+}
+
+impl Breakdown {
+    const fn new() -> Self {
+        Self {
+            plugins: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            namespaces: BTreeMap::new(),
+        }
+    }
 }
 
 impl Default for Metrics {
@@ -56,6 +90,8 @@ impl Metrics {
             jobs_aborted: AtomicU64::new(0),
             jobs_rejected: AtomicU64::new(0),
             jobs_retried: AtomicU64::new(0),
+            jobs_unroutable: AtomicU64::new(0),
+            jobs_preempted: AtomicU64::new(0),
             workers_registered: AtomicU64::new(0),
             workers_cleaned_up: AtomicU64::new(0),
             messages_received: AtomicU64::new(0),
@@ -64,9 +100,78 @@ impl Metrics {
             db_errors: AtomicU64::new(0),
             dispatch_time_us: AtomicU64::new(0),
             conclude_time_us: AtomicU64::new(0),
+            last_loop_tick_ms: AtomicU64::new(0),
+            breakdown: Mutex::new(Breakdown::new()),
+        }
+    }
+
+    /// Record a concluded job against its plugin, the plugin's namespace, and
+    /// each of its file's tags.
+    pub fn record_job_outcome(
+        &self,
+        plugin_name: &str,
+        namespace: &str,
+        tags: &[String],
+        outcome: JobOutcome,
+        rows: u64,
+        latency_ms: Option<u64>,
+    ) {
+        let mut breakdown = match self.breakdown.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        breakdown
+            .plugins
+            .entry(plugin_name.to_string())
+            .or_default()
+            .record(outcome, rows, latency_ms);
+        breakdown
+            .namespaces
+            .entry(namespace.to_string())
+            .or_default()
+            .record(outcome, rows, latency_ms);
+        for tag in tags {
+            breakdown
+                .tags
+                .entry(tag.clone())
+                .or_default()
+                .record(outcome, rows, latency_ms);
         }
     }
 
+    /// Per-plugin, per-tag and per-namespace breakdown, sorted by key.
+    pub fn plugin_metrics(&self) -> PluginMetricsReport {
+        let breakdown = match self.breakdown.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let entries = |map: &BTreeMap<String, JobBreakdown>| {
+            map.iter()
+                .map(|(key, jobs)| BreakdownEntry::new(key.clone(), jobs.clone()))
+                .collect()
+        };
+        PluginMetricsReport {
+            plugins: entries(&breakdown.plugins),
+            tags: entries(&breakdown.tags),
+            namespaces: entries(&breakdown.namespaces),
+        }
+    }
+
+    /// Record that the event loop is alive (read by `/healthz`).
+    #[inline]
+    pub fn record_loop_tick(&self) {
+        self.last_loop_tick_ms.store(unix_millis(), Ordering::Relaxed);
+    }
+
+    /// Milliseconds since the last event loop tick, or None if it never ran.
+    pub fn loop_tick_age_ms(&self) -> Option<u64> {
+        let last = self.last_loop_tick_ms.load(Ordering::Relaxed);
+        if last == 0 {
+            return None;
+        }
+        Some(unix_millis().saturating_sub(last))
+    }
+
     /// Increment a counter atomically
     #[inline]
     pub fn inc_jobs_dispatched(&self) {
@@ -98,6 +203,16 @@ impl Metrics {
         self.jobs_retried.fetch_add(1, Ordering::Relaxed);
     }
 
+    #[inline]
+    pub fn inc_jobs_unroutable(&self) {
+        self.jobs_unroutable.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn inc_jobs_preempted(&self) {
+        self.jobs_preempted.fetch_add(1, Ordering::Relaxed);
+    }
+
     #[inline]
     pub fn inc_workers_registered(&self) {
         self.workers_registered.fetch_add(1, Ordering::Relaxed);
@@ -153,6 +268,8 @@ impl Metrics {
             jobs_aborted: self.jobs_aborted.load(Ordering::Relaxed),
             jobs_rejected: self.jobs_rejected.load(Ordering::Relaxed),
             jobs_retried: self.jobs_retried.load(Ordering::Relaxed),
+            jobs_unroutable: self.jobs_unroutable.load(Ordering::Relaxed),
+            jobs_preempted: self.jobs_preempted.load(Ordering::Relaxed),
             workers_registered: self.workers_registered.load(Ordering::Relaxed),
             workers_cleaned_up: self.workers_cleaned_up.load(Ordering::Relaxed),
             messages_received: self.messages_received.load(Ordering::Relaxed),
@@ -192,6 +309,14 @@ casparian_jobs_rejected_total {}
 # TYPE casparian_jobs_retried_total counter
 casparian_jobs_retried_total {}
 
+# HELP casparian_jobs_unroutable_total Dispatch attempts with no worker matching the plugin's requirements
+# TYPE casparian_jobs_unroutable_total counter
+casparian_jobs_unroutable_total {}
+
+# HELP casparian_jobs_preempted_total Low-priority jobs requeued to make room for urgent jobs
+# TYPE casparian_jobs_preempted_total counter
+casparian_jobs_preempted_total {}
+
 # HELP casparian_workers_registered_total Total workers registered
 # TYPE casparian_workers_registered_total counter
 casparian_workers_registered_total {}
@@ -230,6 +355,8 @@ casparian_conclude_time_microseconds_total {}
             s.jobs_aborted,
             s.jobs_rejected,
             s.jobs_retried,
+            s.jobs_unroutable,
+            s.jobs_preempted,
             s.workers_registered,
             s.workers_cleaned_up,
             s.messages_received,
@@ -238,10 +365,125 @@ casparian_conclude_time_microseconds_total {}
             s.db_errors,
             s.dispatch_time_us,
             s.conclude_time_us,
-        )
+        ) + &breakdown_prometheus_format(&self.plugin_metrics())
     }
 }
 
+/// Labelled per-plugin / per-tag / per-namespace series appended to the
+/// Prometheus output.
+fn breakdown_prometheus_format(report: &PluginMetricsReport) -> String {
+    let mut out = String::new();
+    let series = [
+        ("plugin", &report.plugins),
+        ("tag", &report.tags),
+        ("namespace", &report.namespaces),
+    ];
+
+    let _ = writeln!(
+        out,
+        "\n# HELP casparian_jobs_concluded_by_label_total Concluded jobs by label and outcome"
+    );
+    let _ = writeln!(out, "# TYPE casparian_jobs_concluded_by_label_total counter");
+    for (label, entries) in series {
+        for entry in entries {
+            let key = escape_label_value(&entry.key);
+            let jobs = &entry.jobs;
+            for outcome in [
+                JobOutcome::Completed,
+                JobOutcome::Failed,
+                JobOutcome::Retried,
+                JobOutcome::Rejected,
+                JobOutcome::Aborted,
+            ] {
+                let count = match outcome {
+                    JobOutcome::Completed => jobs.completed,
+                    JobOutcome::Failed => jobs.failed,
+                    JobOutcome::Retried => jobs.retried,
+                    JobOutcome::Rejected => jobs.rejected,
+                    JobOutcome::Aborted => jobs.aborted,
+                };
+                let _ = writeln!(
+                    out,
+                    "casparian_jobs_concluded_by_label_total{{{}=\"{}\",outcome=\"{}\"}} {}",
+                    label, key, outcome, count
+                );
+            }
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "\n# HELP casparian_rows_written_by_label_total Rows written by plugin, tag or namespace"
+    );
+    let _ = writeln!(out, "# TYPE casparian_rows_written_by_label_total counter");
+    for (label, entries) in series {
+        for entry in entries {
+            let _ = writeln!(
+                out,
+                "casparian_rows_written_by_label_total{{{}=\"{}\"}} {}",
+                label,
+                escape_label_value(&entry.key),
+                entry.jobs.rows_written
+            );
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "\n# HELP casparian_job_latency_seconds Job latency from claim to conclude"
+    );
+    let _ = writeln!(out, "# TYPE casparian_job_latency_seconds histogram");
+    for (label, entries) in series {
+        for entry in entries {
+            let key = escape_label_value(&entry.key);
+            let latency = &entry.jobs.latency;
+            let cumulative = latency.cumulative();
+            for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(cumulative.iter()) {
+                let _ = writeln!(
+                    out,
+                    "casparian_job_latency_seconds_bucket{{{}=\"{}\",le=\"{}\"}} {}",
+                    label,
+                    key,
+                    *bound as f64 / 1000.0,
+                    count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "casparian_job_latency_seconds_bucket{{{}=\"{}\",le=\"+Inf\"}} {}",
+                label, key, latency.count
+            );
+            let _ = writeln!(
+                out,
+                "casparian_job_latency_seconds_sum{{{}=\"{}\"}} {}",
+                label,
+                key,
+                latency.sum_ms as f64 / 1000.0
+            );
+            let _ = writeln!(
+                out,
+                "casparian_job_latency_seconds_count{{{}=\"{}\"}} {}",
+                label, key, latency.count
+            );
+        }
+    }
+    out
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
 /// Immutable snapshot of metrics for reading
 #[derive(Debug, Clone)]
 pub struct MetricsSnapshot {
@@ -251,6 +493,8 @@ pub struct MetricsSnapshot {
     pub jobs_aborted: u64,
     pub jobs_rejected: u64,
     pub jobs_retried: u64,
+    pub jobs_unroutable: u64,
+    pub jobs_preempted: u64,
     pub workers_registered: u64,
     pub workers_cleaned_up: u64,
     pub messages_received: u64,
@@ -334,4 +578,49 @@ mod tests {
         let output = metrics.prometheus_format();
         assert!(output.contains("casparian_jobs_completed_total 1"));
     }
+
+    #[test]
+    fn test_plugin_and_tag_breakdown() {
+        let metrics = Metrics::new();
+        let tags = vec!["finance".to_string(), "daily".to_string()];
+        metrics.record_job_outcome(
+            "csv_parser",
+            "risk",
+            &tags,
+            JobOutcome::Completed,
+            100,
+            Some(200),
+        );
+        metrics.record_job_outcome("csv_parser", "risk", &tags, JobOutcome::Failed, 0, Some(50));
+        metrics.record_job_outcome("xml_parser", "default", &[], JobOutcome::Completed, 7, None);
+
+        let report = metrics.plugin_metrics();
+        assert_eq!(report.plugins.len(), 2);
+        let csv = &report.plugins[0];
+        assert_eq!(csv.key, "csv_parser");
+        assert_eq!(csv.jobs.rows_written, 100);
+        assert!((csv.failure_rate - 0.5).abs() < f64::EPSILON);
+        assert_eq!(
+            report.tags.iter().map(|t| t.key.as_str()).collect::<Vec<_>>(),
+            vec!["daily", "finance"]
+        );
+        assert_eq!(
+            report
+                .namespaces
+                .iter()
+                .map(|n| (n.key.as_str(), n.jobs.total()))
+                .collect::<Vec<_>>(),
+            vec![("default", 1), ("risk", 2)]
+        );
+
+        let output = metrics.prometheus_format();
+        assert!(output.contains(
+            "casparian_jobs_concluded_by_label_total{plugin=\"csv_parser\",outcome=\"failed\"} 1"
+        ));
+        assert!(output.contains("casparian_rows_written_by_label_total{tag=\"finance\"} 100"));
+        assert!(output.contains("casparian_rows_written_by_label_total{namespace=\"risk\"} 100"));
+        assert!(output.contains(
+            "casparian_job_latency_seconds_bucket{plugin=\"csv_parser\",le=\"0.25\"} 2"
+        ));
+    }
 }
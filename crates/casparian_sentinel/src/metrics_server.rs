@@ -0,0 +1,178 @@
+//! Embedded HTTP endpoint for headless monitoring.
+//!
+//! Serves `GET /metrics` (Prometheus text format from `METRICS`) and
+//! `GET /healthz` on a background thread. `/healthz` returns 503 until the
+//! event loop has ticked, and again if it stalls for `HEALTH_STALE_AFTER_MS`.
+//! Requests are handled one at a time; scrapes are small and infrequent.
+
+use crate::metrics::METRICS;
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Event loop silence after which `/healthz` reports unhealthy.
+pub const HEALTH_STALE_AFTER_MS: u64 = 30_000;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_LINE_BYTES: u64 = 8 * 1024;
+
+/// Running metrics listener; stopped on drop.
+pub struct MetricsServer {
+    local_addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Bind `addr` (e.g. "127.0.0.1:9464") and start serving.
+    pub fn start(addr: &str) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .with_context(|| format!("Failed to bind metrics endpoint on {}", addr))?;
+        let local_addr = listener
+            .local_addr()
+            .context("Failed to read metrics endpoint address")?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let handle = std::thread::Builder::new()
+            .name("sentinel-metrics".to_string())
+            .spawn(move || serve(listener, thread_stop))
+            .context("Failed to spawn metrics endpoint thread")?;
+        info!("Metrics endpoint listening on http://{}", local_addr);
+        Ok(Self {
+            local_addr,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Unblock accept() so the thread observes the stop flag.
+        let _ = TcpStream::connect(self.local_addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn serve(listener: TcpListener, stop: Arc<AtomicBool>) {
+    for stream in listener.incoming() {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        match stream {
+            Ok(stream) => {
+                if let Err(err) = handle_connection(stream) {
+                    debug!("Metrics request failed: {}", err);
+                }
+            }
+            Err(err) => warn!("Metrics endpoint accept failed: {}", err),
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.by_ref().take(MAX_LINE_BYTES).read_line(&mut request_line)?;
+    // Drain headers so the client sees a clean close.
+    let mut header = String::new();
+    loop {
+        header.clear();
+        let read = reader.by_ref().take(MAX_LINE_BYTES).read_line(&mut header)?;
+        if read == 0 || header == "\r\n" || header == "\n" {
+            break;
+        }
+    }
+
+    let (status, content_type, body) = route(&request_line);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn route(request_line: &str) -> (&'static str, &'static str, String) {
+    const TEXT: &str = "text/plain; charset=utf-8";
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts
+        .next()
+        .unwrap_or("")
+        .split('?')
+        .next()
+        .unwrap_or("");
+
+    if method != "GET" {
+        return ("405 Method Not Allowed", TEXT, "method not allowed\n".to_string());
+    }
+    match path {
+        "/metrics" => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            METRICS.prometheus_format(),
+        ),
+        "/healthz" => match METRICS.loop_tick_age_ms() {
+            Some(age) if age <= HEALTH_STALE_AFTER_MS => ("200 OK", TEXT, "ok\n".to_string()),
+            Some(age) => (
+                "503 Service Unavailable",
+                TEXT,
+                format!("event loop stalled for {}ms\n", age),
+            ),
+            None => (
+                "503 Service Unavailable",
+                TEXT,
+                "event loop not started\n".to_string(),
+            ),
+        },
+        _ => ("404 Not Found", TEXT, "not found\n".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_metrics_and_health_endpoints() {
+        let server = MetricsServer::start("127.0.0.1:0").unwrap();
+        let addr = server.local_addr();
+
+        let metrics = get(addr, "/metrics");
+        assert!(metrics.starts_with("HTTP/1.1 200 OK"), "{}", metrics);
+        assert!(metrics.contains("casparian_jobs_dispatched_total"));
+
+        METRICS.record_loop_tick();
+        let health = get(addr, "/healthz");
+        assert!(health.starts_with("HTTP/1.1 200 OK"), "{}", health);
+
+        let missing = get(addr, "/nope");
+        assert!(missing.starts_with("HTTP/1.1 404"), "{}", missing);
+    }
+}
@@ -0,0 +1,357 @@
+//! Receipt verification: cross-check what a worker reported in its CONCLUDE
+//! receipt against what the sinks actually hold.
+//!
+//! | Sink | Check |
+//! |------|-------|
+//! | `file://…parquet` (incl. `**` partition globs) | files exist, row count from Parquet metadata |
+//! | `file://…` (CSV, JSONL, Arrow IPC) | file exists |
+//! | `duckdb://…?table=` | table exists, rows tagged with this job's `_cf_job_id` |
+//! | S3, Postgres | skipped |
+//!
+//! Any discrepancy marks the job SUSPECT. A job none of whose artifacts could
+//! be checked is UNVERIFIABLE rather than VERIFIED.
+
+use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::types::{
+    ArtifactDiscrepancy, ArtifactV1, ParsedSinkUri, ReceiptVerification, SinkScheme,
+    VerificationStatus,
+};
+use casparian_state_store::JobArtifactRecord;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use tracing::debug;
+
+/// Verifies receipts on a background thread so DuckDB scans never stall the
+/// Sentinel event loop.
+pub struct ReceiptVerifier {
+    tx: Sender<(i64, Vec<ArtifactV1>)>,
+    results: Receiver<(i64, ReceiptVerification)>,
+}
+
+impl ReceiptVerifier {
+    pub fn start() -> Self {
+        let (tx, rx) = mpsc::channel::<(i64, Vec<ArtifactV1>)>();
+        let (result_tx, results) = mpsc::channel();
+        thread::spawn(move || {
+            for (job_id, artifacts) in rx {
+                let verification = verify_artifacts(job_id, &artifacts);
+                if result_tx.send((job_id, verification)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { tx, results }
+    }
+
+    pub fn submit(&self, job_id: i64, artifacts: Vec<ArtifactV1>) {
+        let _ = self.tx.send((job_id, artifacts));
+    }
+
+    /// Next finished verification, if any.
+    pub fn try_recv(&self) -> Option<(i64, ReceiptVerification)> {
+        self.results.try_recv().ok()
+    }
+}
+
+/// Rebuild receipt artifacts from stored `cf_job_artifacts` rows.
+pub fn artifacts_from_records(records: &[JobArtifactRecord]) -> Vec<ArtifactV1> {
+    records
+        .iter()
+        .filter_map(|record| {
+            let rows = record.rows.and_then(|rows| u64::try_from(rows).ok());
+            match record.kind.as_str() {
+                "output" => Some(ArtifactV1::Output {
+                    output_name: record.name.clone(),
+                    sink_uri: record.uri.clone(),
+                    table: record.table_name.clone(),
+                    rows,
+                    schema_hash: None,
+                    content_hash: record.content_hash.clone(),
+                }),
+                "quarantine" => Some(ArtifactV1::Quarantine {
+                    output_name: record.name.clone(),
+                    sink_uri: record.uri.clone(),
+                    table: record.table_name.clone(),
+                    rows,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Check every output and quarantine artifact of a concluded job.
+pub fn verify_artifacts(job_id: i64, artifacts: &[ArtifactV1]) -> ReceiptVerification {
+    let mut checked = 0;
+    let mut skipped = 0;
+    let mut discrepancies = Vec::new();
+
+    for artifact in artifacts {
+        let (output_name, sink_uri, table, rows, quarantine) = match artifact {
+            ArtifactV1::Output {
+                output_name,
+                sink_uri,
+                table,
+                rows,
+                ..
+            } => (output_name, sink_uri, table.as_deref(), *rows, false),
+            ArtifactV1::Quarantine {
+                output_name,
+                sink_uri,
+                table,
+                rows,
+            } => (output_name, sink_uri, table.as_deref(), *rows, true),
+            ArtifactV1::Log { .. } | ArtifactV1::Other { .. } => continue,
+        };
+
+        let check = ArtifactCheck {
+            job_id,
+            sink_uri,
+            table,
+            quarantine,
+        };
+        match check.run() {
+            Outcome::Rows(actual) => {
+                checked += 1;
+                if let Some(reported) = rows.filter(|reported| *reported != actual) {
+                    discrepancies.push(ArtifactDiscrepancy {
+                        output_name: output_name.clone(),
+                        sink_uri: sink_uri.clone(),
+                        reported_rows: Some(reported),
+                        actual_rows: Some(actual),
+                        reason: "row count mismatch".to_string(),
+                    });
+                }
+            }
+            Outcome::Exists => checked += 1,
+            Outcome::Bad(reason) => {
+                checked += 1;
+                discrepancies.push(ArtifactDiscrepancy {
+                    output_name: output_name.clone(),
+                    sink_uri: sink_uri.clone(),
+                    reported_rows: rows,
+                    actual_rows: None,
+                    reason,
+                });
+            }
+            Outcome::Skipped(why) => {
+                debug!("Job {}: not verifying {} ({})", job_id, sink_uri, why);
+                skipped += 1;
+            }
+        }
+    }
+
+    let status = if !discrepancies.is_empty() {
+        VerificationStatus::Suspect
+    } else if checked == 0 {
+        VerificationStatus::Unverifiable
+    } else {
+        VerificationStatus::Verified
+    };
+
+    ReceiptVerification {
+        status,
+        checked,
+        skipped,
+        discrepancies,
+        verified_at: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+enum Outcome {
+    /// Artifact found; actual row count.
+    Rows(u64),
+    /// Artifact found; format has no cheap row count.
+    Exists,
+    /// Artifact missing or unreadable.
+    Bad(String),
+    Skipped(&'static str),
+}
+
+struct ArtifactCheck<'a> {
+    job_id: i64,
+    sink_uri: &'a str,
+    table: Option<&'a str>,
+    quarantine: bool,
+}
+
+impl ArtifactCheck<'_> {
+    fn run(&self) -> Outcome {
+        let Ok(parsed) = ParsedSinkUri::parse(self.sink_uri) else {
+            return Outcome::Skipped("unparseable URI");
+        };
+        match parsed.scheme {
+            SinkScheme::File | SinkScheme::Parquet => self.check_file(&parsed.path),
+            SinkScheme::Csv | SinkScheme::Jsonl | SinkScheme::Arrow => {
+                exists_or_missing(&parsed.path)
+            }
+            SinkScheme::Duckdb => {
+                let table = self
+                    .table
+                    .map(str::to_string)
+                    .or_else(|| parsed.query.get("table").cloned());
+                match table {
+                    Some(table) => self.check_duckdb(&parsed.path, &table),
+                    None => Outcome::Skipped("no table in URI"),
+                }
+            }
+            SinkScheme::S3 | SinkScheme::Postgres => Outcome::Skipped("remote sink"),
+        }
+    }
+
+    fn check_file(&self, path: &Path) -> Outcome {
+        let is_parquet = path.extension().and_then(|e| e.to_str()) == Some("parquet");
+        let is_glob = path.to_string_lossy().contains('*');
+        if !is_glob {
+            if let Outcome::Bad(reason) = exists_or_missing(path) {
+                return Outcome::Bad(reason);
+            }
+        }
+        if !is_parquet {
+            return Outcome::Exists;
+        }
+
+        match parquet_row_count(path) {
+            Ok(count) => Outcome::Rows(count),
+            Err(err) => Outcome::Bad(format!("unreadable: {}", err)),
+        }
+    }
+
+    fn check_duckdb(&self, path: &Path, table: &str) -> Outcome {
+        if let Outcome::Bad(reason) = exists_or_missing(path) {
+            return Outcome::Bad(reason);
+        }
+        // The sink may still hold the write lock; try again on demand later.
+        let Ok(conn) = DbConnection::open_duckdb_readonly(path) else {
+            return Outcome::Skipped("database locked");
+        };
+
+        let table_ident = quote_table(table);
+        if self.quarantine {
+            // Quarantine rows carry no lineage columns; existence is all we can check.
+            let sql = format!("SELECT COUNT(*) FROM {}", table_ident);
+            return match conn.query_scalar::<i64>(&sql, &[]) {
+                Ok(_) => Outcome::Exists,
+                Err(err) => Outcome::Bad(format!("table {} unreadable: {}", table, err)),
+            };
+        }
+
+        let sql = format!(
+            "SELECT COUNT(*) FROM {} WHERE \"_cf_job_id\" = ?",
+            table_ident
+        );
+        match conn.query_scalar::<i64>(&sql, &[DbValue::from(self.job_id.to_string())]) {
+            Ok(count) => Outcome::Rows(count.max(0) as u64),
+            Err(err) => Outcome::Bad(format!("table {} unreadable: {}", table, err)),
+        }
+    }
+}
+
+fn exists_or_missing(path: &Path) -> Outcome {
+    if path.exists() {
+        Outcome::Exists
+    } else {
+        Outcome::Bad(format!("missing: {}", path.display()))
+    }
+}
+
+/// Row count of a Parquet file (or glob) from its metadata.
+pub(crate) fn parquet_row_count(path: &Path) -> anyhow::Result<u64> {
+    let sql = format!(
+        "SELECT COUNT(*) FROM parquet_scan('{}')",
+        escape_sql_literal(&path.to_string_lossy())
+    );
+    let conn = DbConnection::open_duckdb_memory()?;
+    let count = conn.query_scalar::<i64>(&sql, &[])?;
+    Ok(count.max(0) as u64)
+}
+
+fn quote_table(table: &str) -> String {
+    table
+        .split('.')
+        .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+pub(crate) fn escape_sql_literal(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_parquet(path: &Path, rows: u64) {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        let sql = format!(
+            "COPY (SELECT range AS id FROM range({})) TO '{}' (FORMAT PARQUET)",
+            rows,
+            escape_sql_literal(&path.to_string_lossy())
+        );
+        conn.execute(&sql, &[]).unwrap();
+    }
+
+    fn output(sink_uri: String, rows: Option<u64>) -> ArtifactV1 {
+        ArtifactV1::Output {
+            output_name: "orders".to_string(),
+            sink_uri,
+            table: None,
+            rows,
+            schema_hash: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_parquet_row_count_matches_receipt() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders_42.parquet");
+        write_parquet(&path, 7);
+
+        let uri = format!("file://{}", path.display());
+        let verification = verify_artifacts(42, &[output(uri, Some(7))]);
+        assert_eq!(verification.status, VerificationStatus::Verified);
+        assert_eq!(verification.checked, 1);
+        assert!(verification.discrepancies.is_empty());
+    }
+
+    #[test]
+    fn test_parquet_row_count_mismatch_is_suspect() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders_42.parquet");
+        write_parquet(&path, 7);
+
+        let uri = format!("file://{}", path.display());
+        let verification = verify_artifacts(42, &[output(uri, Some(10))]);
+        assert_eq!(verification.status, VerificationStatus::Suspect);
+        let discrepancy = &verification.discrepancies[0];
+        assert_eq!(discrepancy.reported_rows, Some(10));
+        assert_eq!(discrepancy.actual_rows, Some(7));
+    }
+
+    #[test]
+    fn test_missing_artifact_is_suspect() {
+        let dir = tempfile::tempdir().unwrap();
+        let uri = format!("file://{}", dir.path().join("gone_1.parquet").display());
+        let verification = verify_artifacts(1, &[output(uri, Some(3))]);
+        assert_eq!(verification.status, VerificationStatus::Suspect);
+        assert!(verification.discrepancies[0].reason.starts_with("missing"));
+    }
+
+    #[test]
+    fn test_remote_sinks_are_unverifiable() {
+        let artifacts = vec![
+            output("s3://bucket/prefix/orders_1.parquet".to_string(), Some(3)),
+            ArtifactV1::Log {
+                name: "stdout".to_string(),
+                uri: "file:///tmp/log".to_string(),
+            },
+        ];
+        let verification = verify_artifacts(1, &artifacts);
+        assert_eq!(verification.status, VerificationStatus::Unverifiable);
+        assert_eq!(verification.checked, 0);
+        assert_eq!(verification.skipped, 1);
+    }
+}
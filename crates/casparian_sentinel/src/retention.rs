@@ -0,0 +1,418 @@
+//! Garbage collection of expired outputs and job logs.
+//!
+//! Applies the retention policies of `casparian_state_store::retention` to
+//! the output artifacts jobs committed. Per output topic, artifacts are
+//! ranked newest first; one expires when it is beyond `keep_versions`, older
+//! than `keep_days`, or would push the topic past `max_bytes`. The newest
+//! artifact of a topic is always kept.
+//!
+//! An expired artifact stays when lineage shows a retained artifact was
+//! produced from it (a downstream parser read the file), so retained
+//! datasets keep their inputs. Only local files (`file://` URIs) are deleted;
+//! table sinks prune their own versions.
+//!
+//! With `dry_run` nothing is deleted and the report lists what would be.
+
+use anyhow::Result;
+use casparian_db::DbConnection;
+use casparian_state_store::retention::{
+    self, GcDeletion, LineageLink, LogUsage, OutputArtifact, RetentionPolicy, DEFAULT_POLICY_TOPIC,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+/// Logs of finished jobs untouched for this long are compacted.
+const LOG_COMPACT_AFTER_MS: i64 = 60 * 60 * 1000;
+
+/// What one GC run does.
+#[derive(Debug, Clone, Default)]
+pub struct GcOptions {
+    /// Report only; delete nothing
+    pub dry_run: bool,
+    /// Drop the logs of jobs whose last log line is older than this
+    pub log_keep_days: Option<u32>,
+    /// Current time (millis); defaults to now
+    pub now_millis: Option<i64>,
+}
+
+/// Why an artifact expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiryReason {
+    Age,
+    Versions,
+    Size,
+}
+
+impl fmt::Display for ExpiryReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ExpiryReason::Age => "age",
+            ExpiryReason::Versions => "versions",
+            ExpiryReason::Size => "size",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An artifact past its topic's retention.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExpiredArtifact {
+    pub job_id: i64,
+    pub output_name: String,
+    pub uri: String,
+    pub bytes: u64,
+    pub reason: ExpiryReason,
+}
+
+/// Outcome of a GC run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GcReport {
+    pub dry_run: bool,
+    /// Artifacts deleted (or, on a dry run, to delete)
+    pub expired: Vec<ExpiredArtifact>,
+    /// Expired artifacts kept because retained outputs were produced from them
+    pub protected: Vec<ExpiredArtifact>,
+    pub bytes_freed: u64,
+    /// Logs deleted (or to delete) for age
+    pub logs_expired: LogUsage,
+    /// Jobs whose log chunks were (or would be) merged
+    pub logs_compacted: usize,
+    /// Artifacts that could not be deleted
+    pub errors: Vec<String>,
+}
+
+/// Run the GC against the state store at `conn`.
+pub fn collect_garbage(conn: &DbConnection, options: &GcOptions) -> Result<GcReport> {
+    retention::init_schema(conn)?;
+    casparian_state_store::compaction::init_schema(conn)?;
+    let now = options
+        .now_millis
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+    let policies = retention::list_policies(conn)?;
+    let artifacts = retention::output_artifacts(conn)?;
+    let links = retention::lineage_links(conn)?;
+
+    let (expired, protected) = plan(&artifacts, &policies, &links, now, &artifact_bytes);
+    let mut report = GcReport {
+        dry_run: options.dry_run,
+        protected,
+        ..GcReport::default()
+    };
+
+    for artifact in expired {
+        if !options.dry_run {
+            if let Err(err) = remove_artifact_files(&artifact.uri) {
+                warn!("GC could not delete {}: {}", artifact.uri, err);
+                report.errors.push(format!("{}: {}", artifact.uri, err));
+                continue;
+            }
+            retention::record_deletion(
+                conn,
+                &GcDeletion {
+                    uri: artifact.uri.clone(),
+                    job_id: artifact.job_id,
+                    output_name: artifact.output_name.clone(),
+                    bytes: artifact.bytes,
+                    reason: artifact.reason.to_string(),
+                },
+            )?;
+        }
+        report.bytes_freed += artifact.bytes;
+        report.expired.push(artifact);
+    }
+
+    if let Some(days) = options.log_keep_days {
+        let before = now - i64::from(days) * MILLIS_PER_DAY;
+        report.logs_expired = if options.dry_run {
+            retention::expired_logs(conn, before)?
+        } else {
+            retention::delete_expired_logs(conn, before)?
+        };
+    }
+    let jobs = retention::compactable_log_jobs(conn, now - LOG_COMPACT_AFTER_MS)?;
+    if !options.dry_run {
+        for job_id in &jobs {
+            retention::compact_job_log(conn, *job_id)?;
+        }
+    }
+    report.logs_compacted = jobs.len();
+    Ok(report)
+}
+
+/// Split `artifacts` (newest first) into those to delete and expired ones
+/// kept for lineage. `bytes_of` sizes an artifact; None means it is not a
+/// local file and is never deleted.
+fn plan(
+    artifacts: &[OutputArtifact],
+    policies: &[RetentionPolicy],
+    links: &[LineageLink],
+    now: i64,
+    bytes_of: &dyn Fn(&str) -> Option<u64>,
+) -> (Vec<ExpiredArtifact>, Vec<ExpiredArtifact>) {
+    let policies: HashMap<&str, &RetentionPolicy> = policies
+        .iter()
+        .map(|policy| (policy.topic.as_str(), policy))
+        .collect();
+    let mut by_topic: HashMap<&str, Vec<&OutputArtifact>> = HashMap::new();
+    for artifact in artifacts {
+        by_topic
+            .entry(artifact.output_name.as_str())
+            .or_default()
+            .push(artifact);
+    }
+
+    let mut retained: HashSet<&str> = HashSet::new();
+    let mut expired = Vec::new();
+    for (topic, artifacts) in by_topic {
+        let Some(policy) = policies
+            .get(topic)
+            .or_else(|| policies.get(DEFAULT_POLICY_TOPIC))
+            .filter(|policy| !policy.is_empty())
+        else {
+            retained.extend(artifacts.iter().map(|artifact| artifact.uri.as_str()));
+            continue;
+        };
+        let cutoff = policy
+            .keep_days
+            .map(|days| now - i64::from(days) * MILLIS_PER_DAY);
+        let mut kept_bytes = 0u64;
+        for (rank, artifact) in artifacts.into_iter().enumerate() {
+            let bytes = bytes_of(&artifact.uri);
+            let size = bytes.unwrap_or(0);
+            let reason = if rank == 0 || bytes.is_none() {
+                None
+            } else if policy
+                .keep_versions
+                .is_some_and(|keep| rank >= keep as usize)
+            {
+                Some(ExpiryReason::Versions)
+            } else if cutoff.is_some_and(|cutoff| artifact.created_at < cutoff) {
+                Some(ExpiryReason::Age)
+            } else if policy
+                .max_bytes
+                .is_some_and(|max| kept_bytes.saturating_add(size) > max)
+            {
+                Some(ExpiryReason::Size)
+            } else {
+                None
+            };
+            match reason {
+                Some(reason) => expired.push(ExpiredArtifact {
+                    job_id: artifact.job_id,
+                    output_name: artifact.output_name.clone(),
+                    uri: artifact.uri.clone(),
+                    bytes: size,
+                    reason,
+                }),
+                None => {
+                    kept_bytes = kept_bytes.saturating_add(size);
+                    retained.insert(artifact.uri.as_str());
+                }
+            }
+        }
+    }
+
+    // Keep inputs of retained artifacts, and in turn the inputs of those.
+    let mut downstream: HashMap<&str, Vec<&str>> = HashMap::new();
+    for link in links {
+        downstream
+            .entry(link.source_path.as_str())
+            .or_default()
+            .push(link.artifact_uri.as_str());
+    }
+    let mut protected = Vec::new();
+    loop {
+        let (keep, rest): (Vec<ExpiredArtifact>, Vec<ExpiredArtifact>) =
+            expired.into_iter().partition(|artifact| {
+                artifact_paths(&artifact.uri).iter().any(|path| {
+                    downstream
+                        .get(path.to_string_lossy().as_ref())
+                        .is_some_and(|uris| uris.iter().any(|uri| retained.contains(uri)))
+                })
+            });
+        expired = rest;
+        if keep.is_empty() {
+            break;
+        }
+        for artifact in keep {
+            if let Some(original) = artifacts.iter().find(|a| a.uri == artifact.uri) {
+                retained.insert(original.uri.as_str());
+            }
+            protected.push(artifact);
+        }
+    }
+    expired.sort_by(|a, b| (&a.output_name, a.job_id).cmp(&(&b.output_name, b.job_id)));
+    protected.sort_by(|a, b| (&a.output_name, a.job_id).cmp(&(&b.output_name, b.job_id)));
+    (expired, protected)
+}
+
+/// Local path of a `file://` artifact URI. Partitioned Parquet outputs use
+/// `dir/**/name`, one file per partition directory.
+fn local_pattern(uri: &str) -> Option<PathBuf> {
+    uri.strip_prefix("file://").map(PathBuf::from)
+}
+
+/// Files an artifact URI stands for: the file itself, or every `name` below
+/// `dir` for `dir/**/name`. Empty for other URIs and missing files.
+fn artifact_paths(uri: &str) -> Vec<PathBuf> {
+    let Some(pattern) = local_pattern(uri) else {
+        return Vec::new();
+    };
+    let text = pattern.to_string_lossy();
+    let Some((dir, name)) = text.split_once("/**/") else {
+        return if pattern.is_file() {
+            vec![pattern]
+        } else {
+            Vec::new()
+        };
+    };
+    let mut files = Vec::new();
+    collect_named(Path::new(dir), name, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_named(dir: &Path, name: &str, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_named(&path, name, files);
+        } else if entry.file_name().to_str() == Some(name) {
+            files.push(path);
+        }
+    }
+}
+
+/// Total size of an artifact's files; None for non-local artifacts.
+fn artifact_bytes(uri: &str) -> Option<u64> {
+    local_pattern(uri)?;
+    Some(
+        artifact_paths(uri)
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum(),
+    )
+}
+
+fn remove_artifact_files(uri: &str) -> std::io::Result<()> {
+    for path in artifact_paths(uri) {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = MILLIS_PER_DAY;
+
+    fn artifact(job_id: i64, name: &str, uri: &str, created_at: i64) -> OutputArtifact {
+        OutputArtifact {
+            job_id,
+            output_name: name.to_string(),
+            uri: uri.to_string(),
+            created_at,
+        }
+    }
+
+    fn policy(topic: &str, keep_days: Option<u32>, keep_versions: Option<u32>) -> RetentionPolicy {
+        RetentionPolicy {
+            topic: topic.to_string(),
+            keep_days,
+            keep_versions,
+            max_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_applies_policies_and_keeps_newest() {
+        let artifacts = vec![
+            artifact(4, "orders", "file:///out/orders_4.parquet", 10 * DAY),
+            artifact(3, "orders", "file:///out/orders_3.parquet", 9 * DAY),
+            artifact(2, "orders", "file:///out/orders_2.parquet", 2 * DAY),
+            artifact(1, "fills", "file:///out/fills_1.parquet", DAY),
+            artifact(5, "totals", "duckdb:///out/db.duckdb?table=totals", DAY),
+        ];
+        let policies = vec![
+            policy("orders", Some(5), None),
+            policy(DEFAULT_POLICY_TOPIC, None, Some(1)),
+        ];
+        let (expired, protected) = plan(&artifacts, &policies, &[], 10 * DAY, &|uri| {
+            uri.starts_with("file://").then_some(100)
+        });
+
+        // fills_1 is its topic's newest, totals is not a local file
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].job_id, 2);
+        assert_eq!(expired[0].reason, ExpiryReason::Age);
+        assert!(protected.is_empty());
+
+        let capped = vec![RetentionPolicy {
+            max_bytes: Some(150),
+            ..policy("orders", None, None)
+        }];
+        let (expired, _) = plan(&artifacts, &capped, &[], 10 * DAY, &|_| Some(100));
+        let jobs: Vec<i64> = expired.iter().map(|a| a.job_id).collect();
+        assert_eq!(jobs, vec![2, 3]);
+        assert!(expired.iter().all(|a| a.reason == ExpiryReason::Size));
+    }
+
+    #[test]
+    fn test_plan_protects_inputs_of_retained_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let raw_old = dir.path().join("raw_1.parquet");
+        let raw_new = dir.path().join("raw_2.parquet");
+        std::fs::write(&raw_old, b"old").unwrap();
+        std::fs::write(&raw_new, b"new").unwrap();
+        let uri = |path: &Path| format!("file://{}", path.display());
+
+        let artifacts = vec![
+            artifact(3, "enriched", "file:///out/enriched_3.parquet", 3 * DAY),
+            artifact(2, "raw", &uri(&raw_new), 2 * DAY),
+            artifact(1, "raw", &uri(&raw_old), DAY),
+        ];
+        let policies = vec![policy("raw", None, Some(1))];
+        let links = vec![LineageLink {
+            source_path: raw_old.display().to_string(),
+            artifact_uri: "file:///out/enriched_3.parquet".to_string(),
+        }];
+        let (expired, protected) = plan(&artifacts, &policies, &links, 3 * DAY, &artifact_bytes);
+        assert!(expired.is_empty());
+        assert_eq!(protected.len(), 1);
+        assert_eq!(protected[0].job_id, 1);
+        assert_eq!(protected[0].bytes, 3);
+    }
+
+    #[test]
+    fn test_partitioned_artifact_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        for region in ["eu", "us"] {
+            let partition = dir.path().join(format!("region={}", region));
+            std::fs::create_dir_all(&partition).unwrap();
+            std::fs::write(partition.join("orders_7.parquet"), b"rows").unwrap();
+            std::fs::write(partition.join("orders_8.parquet"), b"rows").unwrap();
+        }
+        let uri = format!("file://{}/**/orders_7.parquet", dir.path().display());
+        assert_eq!(artifact_paths(&uri).len(), 2);
+        assert_eq!(artifact_bytes(&uri), Some(8));
+
+        remove_artifact_files(&uri).unwrap();
+        assert!(artifact_paths(&uri).is_empty());
+        assert!(dir.path().join("region=eu/orders_8.parquet").exists());
+        assert_eq!(artifact_bytes("s3://bucket/orders_7.parquet"), None);
+    }
+}
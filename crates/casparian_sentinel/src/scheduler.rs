@@ -0,0 +1,469 @@
+//! Load-aware worker selection for dispatch.
+//!
+//! Workers report queue depth, CPU and memory in HEARTBEAT. On each dispatch
+//! pass the scheduler drops idle workers that are over capacity or over the
+//! resource thresholds (backpressure), then orders the rest with the configured
+//! policy. Jobs are leased in that order, so when the queue holds fewer jobs
+//! than idle workers, the workers at the front win. Policies that need it see
+//! the plugin and file tags of the job at the head of the queue.
+//!
+//! Jobs themselves are leased by priority lane (URGENT, HIGH, NORMAL, LOW),
+//! FIFO within a lane. When every worker is busy, an URGENT job may preempt a
+//! LOW one ([`preemption_victim`]).
+
+use casparian_protocol::capabilities::{
+    normalize_capability_tag, satisfies_requirements, unmet_requirements,
+};
+use casparian_protocol::types::{JobPriority, WorkerLoad};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Built-in worker ordering policies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SchedulingPolicy {
+    /// Rotate through workers so each gets the first pick in turn.
+    #[default]
+    RoundRobin,
+    /// Prefer the worker with the lowest reported utilisation.
+    LeastLoaded,
+    /// Prefer workers advertising the queued job's plugin or one of its file
+    /// tags, then generic (`*`) workers; least loaded first within each.
+    TagAffinity,
+}
+
+impl SchedulingPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchedulingPolicy::RoundRobin => "round-robin",
+            SchedulingPolicy::LeastLoaded => "least-loaded",
+            SchedulingPolicy::TagAffinity => "tag-affinity",
+        }
+    }
+}
+
+impl fmt::Display for SchedulingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Resource limits above which a worker receives no new dispatches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SchedulerThresholds {
+    pub max_cpu_percent: f32,
+    pub max_memory_percent: f32,
+}
+
+impl Default for SchedulerThresholds {
+    fn default() -> Self {
+        Self {
+            max_cpu_percent: 95.0,
+            max_memory_percent: 90.0,
+        }
+    }
+}
+
+/// An idle worker eligible for dispatch.
+#[derive(Debug, Clone)]
+pub struct WorkerCandidate {
+    pub identity: Vec<u8>,
+    pub worker_id: String,
+    pub capabilities: Vec<String>,
+    /// Latest load from HEARTBEAT (None until the worker reports one).
+    pub load: Option<WorkerLoad>,
+}
+
+impl WorkerCandidate {
+    /// Highest of queue, CPU, and memory utilisation, as a 0.0-1.0+ fraction.
+    /// Workers that have not reported load count as empty.
+    pub fn utilization(&self) -> f32 {
+        let Some(load) = self.load else {
+            return 0.0;
+        };
+        let queue = if load.capacity == 0 {
+            1.0
+        } else {
+            load.queue_depth as f32 / load.capacity as f32
+        };
+        let cpu = load.cpu_percent.unwrap_or(0.0) / 100.0;
+        let memory = load.memory_percent().unwrap_or(0.0) / 100.0;
+        queue.max(cpu).max(memory)
+    }
+
+    fn has_specific_tags(&self) -> bool {
+        !self.capabilities.is_empty() && !self.capabilities.iter().any(|c| c == "*")
+    }
+
+    /// True when the worker advertises the job's plugin or one of its tags.
+    fn matches(&self, job: &QueuedJob) -> bool {
+        let plugin = normalize_capability_tag(&job.plugin_name);
+        self.capabilities.iter().any(|cap| {
+            let cap = normalize_capability_tag(cap);
+            cap == plugin
+                || job
+                    .tags
+                    .iter()
+                    .any(|tag| normalize_capability_tag(tag) == cap)
+        })
+    }
+}
+
+/// The job at the head of the queue, as seen by a policy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueuedJob {
+    pub plugin_name: String,
+    /// Tags of the job's input file.
+    pub tags: Vec<String>,
+}
+
+/// Orders eligible workers; the first worker gets the first leased job.
+///
+/// Implement this to plug a custom policy into `DispatchScheduler::with_policy`.
+pub trait DispatchPolicy: Send {
+    /// Whether `order` uses the queued job. The Sentinel only looks it up
+    /// for policies that do.
+    fn needs_job(&self) -> bool {
+        false
+    }
+
+    fn order(&mut self, job: Option<&QueuedJob>, candidates: &mut [WorkerCandidate]);
+}
+
+/// Rotates the starting worker on every pass.
+#[derive(Debug, Default)]
+pub struct RoundRobinPolicy {
+    next: usize,
+}
+
+impl DispatchPolicy for RoundRobinPolicy {
+    fn order(&mut self, _job: Option<&QueuedJob>, candidates: &mut [WorkerCandidate]) {
+        if candidates.is_empty() {
+            return;
+        }
+        candidates.sort_by(|a, b| a.worker_id.cmp(&b.worker_id));
+        let shift = self.next % candidates.len();
+        candidates.rotate_left(shift);
+        self.next = self.next.wrapping_add(1);
+    }
+}
+
+/// Lowest utilisation first; ties broken by worker ID.
+#[derive(Debug, Default)]
+pub struct LeastLoadedPolicy;
+
+impl DispatchPolicy for LeastLoadedPolicy {
+    fn order(&mut self, _job: Option<&QueuedJob>, candidates: &mut [WorkerCandidate]) {
+        candidates.sort_by(|a, b| {
+            a.utilization()
+                .total_cmp(&b.utilization())
+                .then_with(|| a.worker_id.cmp(&b.worker_id))
+        });
+    }
+}
+
+/// Workers advertising the queued job's plugin or one of its file tags first,
+/// then generic (`*`) workers, then specialised workers that match neither;
+/// least loaded within each group.
+///
+/// Only the front worker is sure to lease the queued job, so affinity applies
+/// to the head of the queue; specialised workers are kept free for their own
+/// jobs otherwise.
+#[derive(Debug, Default)]
+pub struct TagAffinityPolicy;
+
+impl TagAffinityPolicy {
+    fn rank(job: Option<&QueuedJob>, candidate: &WorkerCandidate) -> u8 {
+        if job.is_some_and(|job| candidate.matches(job)) {
+            0
+        } else if !candidate.has_specific_tags() {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+impl DispatchPolicy for TagAffinityPolicy {
+    fn needs_job(&self) -> bool {
+        true
+    }
+
+    fn order(&mut self, job: Option<&QueuedJob>, candidates: &mut [WorkerCandidate]) {
+        candidates.sort_by(|a, b| {
+            Self::rank(job, a)
+                .cmp(&Self::rank(job, b))
+                .then_with(|| a.utilization().total_cmp(&b.utilization()))
+                .then_with(|| a.worker_id.cmp(&b.worker_id))
+        });
+    }
+}
+
+/// Filters saturated workers and orders the rest by policy.
+pub struct DispatchScheduler {
+    policy: Box<dyn DispatchPolicy>,
+    thresholds: SchedulerThresholds,
+}
+
+impl DispatchScheduler {
+    pub fn new(policy: SchedulingPolicy) -> Self {
+        let policy: Box<dyn DispatchPolicy> = match policy {
+            SchedulingPolicy::RoundRobin => Box::new(RoundRobinPolicy::default()),
+            SchedulingPolicy::LeastLoaded => Box::new(LeastLoadedPolicy),
+            SchedulingPolicy::TagAffinity => Box::new(TagAffinityPolicy),
+        };
+        Self::with_policy(policy)
+    }
+
+    pub fn with_policy(policy: Box<dyn DispatchPolicy>) -> Self {
+        Self {
+            policy,
+            thresholds: SchedulerThresholds::default(),
+        }
+    }
+
+    pub fn with_thresholds(mut self, thresholds: SchedulerThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// True when `load` leaves no room for another job.
+    pub fn is_saturated(&self, load: &WorkerLoad) -> bool {
+        load.is_full()
+            || load
+                .cpu_percent
+                .is_some_and(|cpu| cpu > self.thresholds.max_cpu_percent)
+            || load
+                .memory_percent()
+                .is_some_and(|memory| memory > self.thresholds.max_memory_percent)
+    }
+
+    /// Whether the policy orders workers by the queued job.
+    pub fn needs_job(&self) -> bool {
+        self.policy.needs_job()
+    }
+
+    /// Split candidates into (dispatch order, throttled). `job` is the head
+    /// of the queue, when known.
+    pub fn schedule(
+        &mut self,
+        candidates: Vec<WorkerCandidate>,
+        job: Option<&QueuedJob>,
+    ) -> (Vec<WorkerCandidate>, Vec<WorkerCandidate>) {
+        let (mut eligible, throttled): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|c| !c.load.as_ref().is_some_and(|load| self.is_saturated(load)));
+        self.policy.order(job, &mut eligible);
+        (eligible, throttled)
+    }
+}
+
+impl Default for DispatchScheduler {
+    fn default() -> Self {
+        Self::new(SchedulingPolicy::default())
+    }
+}
+
+/// Where a leased job should run, given its plugin's requirements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobRouting {
+    /// The leasing worker advertises every requirement.
+    Compatible,
+    /// The leasing worker cannot run it, but another connected worker can.
+    OtherWorker,
+    /// No connected worker can run it; holds the requirements the leasing
+    /// worker is missing.
+    NoWorker(Vec<String>),
+}
+
+/// Match a plugin's requirements against the leasing worker and the pool.
+///
+/// Jobs are leased before their plugin is known, so the worker that leased
+/// the job may not be able to run it; `pool` holds the capabilities of every
+/// connected worker.
+pub fn route_job(
+    requirements: &[String],
+    worker_capabilities: &[String],
+    pool: &[Vec<String>],
+) -> JobRouting {
+    let unmet = unmet_requirements(worker_capabilities, requirements);
+    if unmet.is_empty() {
+        JobRouting::Compatible
+    } else if pool
+        .iter()
+        .any(|capabilities| satisfies_requirements(capabilities, requirements))
+    {
+        JobRouting::OtherWorker
+    } else {
+        JobRouting::NoWorker(unmet)
+    }
+}
+
+/// A busy worker and the lane of the job it is running.
+#[derive(Debug, Clone)]
+pub struct BusyWorker {
+    pub identity: Vec<u8>,
+    pub worker_id: String,
+    pub priority: JobPriority,
+    /// When the running job was dispatched (seconds since epoch)
+    pub dispatched_at: f64,
+}
+
+/// Whether a queued `incoming` job may take the worker of a running job in
+/// lane `running`. Only URGENT jobs preempt, and only LOW jobs are preempted.
+pub fn may_preempt(incoming: JobPriority, running: JobPriority) -> bool {
+    incoming == JobPriority::Urgent && running == JobPriority::Low
+}
+
+/// Busy worker to preempt for `incoming`, if any. Picks the most recently
+/// dispatched eligible job, which has the least work to lose.
+pub fn preemption_victim(busy: &[BusyWorker], incoming: JobPriority) -> Option<&BusyWorker> {
+    busy.iter()
+        .filter(|worker| may_preempt(incoming, worker.priority))
+        .max_by(|a, b| a.dispatched_at.total_cmp(&b.dispatched_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, caps: &[&str], load: Option<WorkerLoad>) -> WorkerCandidate {
+        WorkerCandidate {
+            identity: id.as_bytes().to_vec(),
+            worker_id: id.to_string(),
+            capabilities: caps.iter().map(|c| c.to_string()).collect(),
+            load,
+        }
+    }
+
+    fn load(queue_depth: usize, cpu_percent: f32) -> Option<WorkerLoad> {
+        Some(WorkerLoad {
+            queue_depth,
+            capacity: 4,
+            cpu_percent: Some(cpu_percent),
+            memory_used_bytes: None,
+            memory_total_bytes: None,
+        })
+    }
+
+    fn ids(candidates: &[WorkerCandidate]) -> Vec<&str> {
+        candidates.iter().map(|c| c.worker_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_round_robin_rotates_first_pick() {
+        let mut scheduler = DispatchScheduler::new(SchedulingPolicy::RoundRobin);
+        let workers = vec![
+            candidate("b", &["*"], None),
+            candidate("a", &["*"], None),
+            candidate("c", &["*"], None),
+        ];
+        let (first, _) = scheduler.schedule(workers.clone(), None);
+        let (second, _) = scheduler.schedule(workers, None);
+        assert_eq!(ids(&first), vec!["a", "b", "c"]);
+        assert_eq!(ids(&second), vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_least_loaded_orders_by_utilization() {
+        let mut scheduler = DispatchScheduler::new(SchedulingPolicy::LeastLoaded);
+        let (ordered, _) = scheduler.schedule(
+            vec![
+                candidate("busy", &["*"], load(3, 10.0)),
+                candidate("hot", &["*"], load(0, 70.0)),
+                candidate("calm", &["*"], load(1, 5.0)),
+            ],
+            None,
+        );
+        assert_eq!(ids(&ordered), vec!["calm", "hot", "busy"]);
+    }
+
+    #[test]
+    fn test_tag_affinity_prefers_workers_matching_the_job() {
+        let mut scheduler = DispatchScheduler::new(SchedulingPolicy::TagAffinity);
+        assert!(scheduler.needs_job());
+        let workers = vec![
+            candidate("generic", &["*"], load(0, 0.0)),
+            candidate("gpu", &["GPU_Parser"], load(2, 50.0)),
+            candidate("pdf", &["pdf"], load(0, 0.0)),
+        ];
+        let job = |plugin: &str, tags: &[&str]| QueuedJob {
+            plugin_name: plugin.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        };
+
+        let (by_plugin, _) = scheduler.schedule(workers.clone(), Some(&job("gpu_parser", &[])));
+        assert_eq!(ids(&by_plugin), vec!["gpu", "generic", "pdf"]);
+
+        let (by_tag, _) = scheduler.schedule(workers.clone(), Some(&job("reader", &["pdf"])));
+        assert_eq!(ids(&by_tag), vec!["pdf", "generic", "gpu"]);
+
+        // Without a match, generic workers go first and keep specialised ones free.
+        let (unmatched, _) = scheduler.schedule(workers.clone(), Some(&job("csv", &["csv"])));
+        assert_eq!(ids(&unmatched), vec!["generic", "pdf", "gpu"]);
+        let (no_job, _) = scheduler.schedule(workers, None);
+        assert_eq!(ids(&no_job), vec!["generic", "pdf", "gpu"]);
+    }
+
+    #[test]
+    fn test_saturated_workers_are_throttled() {
+        let mut scheduler = DispatchScheduler::new(SchedulingPolicy::LeastLoaded);
+        let (ordered, throttled) = scheduler.schedule(
+            vec![
+                candidate("full", &["*"], load(4, 10.0)),
+                candidate("pegged", &["*"], load(0, 99.0)),
+                candidate("unknown", &["*"], None),
+                candidate("ok", &["*"], load(1, 20.0)),
+            ],
+            None,
+        );
+        assert_eq!(ids(&ordered), vec!["unknown", "ok"]);
+        assert_eq!(ids(&throttled), vec!["full", "pegged"]);
+    }
+
+    #[test]
+    fn test_preemption_victim_is_newest_low_job() {
+        let busy = |id: &str, priority, dispatched_at| BusyWorker {
+            identity: id.as_bytes().to_vec(),
+            worker_id: id.to_string(),
+            priority,
+            dispatched_at,
+        };
+        let workers = vec![
+            busy("old-low", JobPriority::Low, 10.0),
+            busy("new-low", JobPriority::Low, 20.0),
+            busy("normal", JobPriority::Normal, 30.0),
+        ];
+
+        let victim = preemption_victim(&workers, JobPriority::Urgent).unwrap();
+        assert_eq!(victim.worker_id, "new-low");
+        assert!(preemption_victim(&workers, JobPriority::High).is_none());
+        assert!(preemption_victim(&workers[2..], JobPriority::Urgent).is_none());
+    }
+
+    #[test]
+    fn test_route_job_by_requirements() {
+        let tags = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let cpu = tags(&["python3.11"]);
+        let gpu = tags(&["python3.11", "gpu"]);
+        let requirements = tags(&["gpu"]);
+
+        assert_eq!(route_job(&[], &cpu, &[cpu.clone()]), JobRouting::Compatible);
+        assert_eq!(
+            route_job(&requirements, &gpu, &[cpu.clone(), gpu.clone()]),
+            JobRouting::Compatible
+        );
+        assert_eq!(
+            route_job(&requirements, &cpu, &[cpu.clone(), gpu.clone()]),
+            JobRouting::OtherWorker
+        );
+        assert_eq!(
+            route_job(&requirements, &cpu, &[cpu.clone()]),
+            JobRouting::NoWorker(tags(&["gpu"]))
+        );
+        assert_eq!(
+            route_job(&requirements, &tags(&["*"]), &[]),
+            JobRouting::Compatible
+        );
+    }
+}
@@ -0,0 +1,464 @@
+//! Cron schedules for source rescans and rule processing windows.
+//!
+//! Schedules live in `cf_schedules`. Each tick the Sentinel evaluates the
+//! enabled ones in its local time zone ([`evaluate`]) and persists the result:
+//!
+//! - a `rescan_source` schedule starts a scan of its source when it fires;
+//! - a `rule_window` schedule opens a window of `window_minutes` for files
+//!   tagged by its rule. Outside the window their jobs stay queued with
+//!   `waiting_for_schedule_window`; opening the window releases them.
+//!
+//! An occurrence the Sentinel missed (it was down, or a rule window passed
+//! entirely) is handled by the schedule's catch-up policy: `skip` waits for
+//! the next occurrence, `run_once` fires once now, opening a full window.
+
+use std::fmt;
+use std::str::FromStr;
+
+use casparian_protocol::ScheduleCatchUp;
+use casparian_state_store::{Schedule, ScheduleState};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+/// A rescan fired later than this after its occurrence counts as missed.
+pub const MISSED_GRACE_MS: i64 = 2 * 60 * 1000;
+
+/// Occurrences further out than this are treated as never (e.g. `0 0 30 2 *`).
+const MAX_LOOKAHEAD_YEARS: i32 = 5;
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A five-field cron expression: minute, hour, day of month, month, day of
+/// week.
+///
+/// Fields take `*`, values, ranges (`1-5`), steps (`*/15`, `8-18/2`) and
+/// comma lists; months and weekdays also take names (`JAN`, `MON`), and
+/// weekday 7 is Sunday. As in classic cron, when both day fields are
+/// restricted a day matches if either does. `@hourly`, `@daily`
+/// (`@midnight`), `@weekly`, `@monthly` and `@yearly` (`@annually`) are
+/// accepted as shorthands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronExpr {
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+
+    /// First occurrence strictly after `after`, in `after`'s time zone.
+    ///
+    /// Local times skipped by a DST change never occur; a repeated local
+    /// time occurs once, at its earlier instant.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let local = after.naive_local();
+        let last_year = local.year() + MAX_LOOKAHEAD_YEARS;
+        let mut candidate =
+            local.date().and_hms_opt(local.hour(), local.minute(), 0)? + Duration::minutes(1);
+        while candidate.year() <= last_year {
+            if self.months & (1 << candidate.month()) == 0 {
+                candidate = first_of_next_month(candidate)?;
+                continue;
+            }
+            if !self.matches_day(candidate.date()) {
+                candidate = candidate.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << candidate.hour()) == 0 {
+                candidate =
+                    candidate.date().and_hms_opt(candidate.hour(), 0, 0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << candidate.minute()) == 0 {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            match tz.from_local_datetime(&candidate).earliest() {
+                Some(at) if at > *after => return Some(at),
+                _ => candidate += Duration::minutes(1),
+            }
+        }
+        None
+    }
+
+    /// Latest occurrence in `(from, to]`, if any.
+    fn latest_in<Tz: TimeZone>(
+        &self,
+        from: &DateTime<Tz>,
+        to: &DateTime<Tz>,
+    ) -> Option<DateTime<Tz>> {
+        let mut latest = None;
+        let mut cursor = from.clone();
+        while let Some(at) = self.next_after(&cursor) {
+            if at > *to {
+                break;
+            }
+            cursor = at.clone();
+            latest = Some(at);
+        }
+        latest
+    }
+}
+
+fn first_of_next_month(at: NaiveDateTime) -> Option<NaiveDateTime> {
+    let (year, month) = if at.month() == 12 {
+        (at.year() + 1, 1)
+    } else {
+        (at.year(), at.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)
+}
+
+impl FromStr for CronExpr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expanded = match s.trim().to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            _ => s.trim().to_string(),
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields[..] else {
+            return Err(format!(
+                "Invalid cron expression '{}': expected 5 fields (minute hour day month weekday)",
+                s
+            ));
+        };
+        let invalid = |e: String| format!("Invalid cron expression '{}': {}", s, e);
+        let mut days_of_week = parse_field(dow, "weekday", 0, 7, WEEKDAY_NAMES).map_err(invalid)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, "minute", 0, 59, &[]).map_err(invalid)?,
+            hours: parse_field(hour, "hour", 0, 23, &[]).map_err(invalid)? as u32,
+            days_of_month: parse_field(dom, "day", 1, 31, &[]).map_err(invalid)? as u32,
+            months: parse_field(month, "month", 1, 12, MONTH_NAMES).map_err(invalid)? as u16,
+            days_of_week: days_of_week as u8,
+            day_of_month_restricted: !dom.starts_with('*'),
+            day_of_week_restricted: !dow.starts_with('*'),
+        })
+    }
+}
+
+/// Bitset of the values a field matches; bit `n` is value `n`.
+fn parse_field(field: &str, what: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |token: &str| -> Result<u32, String> {
+        let upper = token.to_ascii_uppercase();
+        let parsed = match names.iter().position(|name| *name == upper) {
+            Some(index) => index as u32 + min,
+            None => token
+                .parse::<u32>()
+                .map_err(|_| format!("invalid {} '{}'", what, token))?,
+        };
+        if parsed < min || parsed > max {
+            return Err(format!("{} {} out of range {}-{}", what, parsed, min, max));
+        }
+        Ok(parsed)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid {} step '{}'", what, step))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `5/15` runs from 5 to the end of the range.
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start > end {
+            return Err(format!("invalid {} range '{}'", what, range));
+        }
+        for v in (start..=end).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+impl fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |bits: u64, min: u32, max: u32| {
+            if (min..=max).all(|v| bits & (1 << v) != 0) {
+                return "*".to_string();
+            }
+            (min..=max)
+                .filter(|v| bits & (1 << v) != 0)
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        write!(
+            f,
+            "{} {} {} {} {}",
+            list(self.minutes, 0, 59),
+            list(u64::from(self.hours), 0, 23),
+            list(u64::from(self.days_of_month), 1, 31),
+            list(u64::from(self.months), 1, 12),
+            list(u64::from(self.days_of_week), 0, 6)
+        )
+    }
+}
+
+/// Outcome of evaluating one schedule at one tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleTick {
+    /// Rescan now / open (or keep open) the window now
+    pub fire: bool,
+    /// The due occurrence passed unserved; `fire` then follows the catch-up
+    /// policy
+    pub missed: bool,
+    /// State to persist; unchanged when nothing was due
+    pub state: ScheduleState,
+}
+
+/// Evaluate `schedule` at `now`.
+///
+/// A schedule never evaluated before (new, or just re-enabled) only
+/// computes its next occurrence, except that a rule window created inside
+/// one of its windows opens for the rest of it.
+pub fn evaluate<Tz: TimeZone>(
+    cron: &CronExpr,
+    schedule: &Schedule,
+    now: &DateTime<Tz>,
+) -> ScheduleTick {
+    let now_ms = now.timestamp_millis();
+    let window_ms = schedule.target.window_ms();
+    let mut tick = ScheduleTick {
+        fire: false,
+        missed: false,
+        state: ScheduleState {
+            next_fire_at: schedule.next_fire_at,
+            last_fired_at: schedule.last_fired_at,
+            window_open_until: schedule.window_open_until,
+        },
+    };
+    let lookback_ms = match schedule.next_fire_at {
+        Some(due) if due > now_ms => return tick,
+        Some(_) => window_ms.max(MISSED_GRACE_MS),
+        None => window_ms,
+    };
+
+    let from = now.clone() - Duration::milliseconds(lookback_ms);
+    if lookback_ms > 0 {
+        if let Some(latest) = cron.latest_in(&from, now) {
+            tick.fire = true;
+            tick.state.last_fired_at = Some(latest.timestamp_millis());
+            if window_ms > 0 {
+                tick.state.window_open_until = Some(latest.timestamp_millis() + window_ms);
+            }
+        }
+    }
+    if !tick.fire && schedule.next_fire_at.is_some() {
+        tick.missed = true;
+        if schedule.catch_up == ScheduleCatchUp::RunOnce {
+            tick.fire = true;
+            tick.state.last_fired_at = Some(now_ms);
+            if window_ms > 0 {
+                tick.state.window_open_until = Some(now_ms + window_ms);
+            }
+        }
+    }
+    tick.state.next_fire_at = cron.next_after(now).map(|at| at.timestamp_millis());
+    tick
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::ScheduleTarget;
+    use chrono::Utc;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(cron: &str, after: &str) -> Option<DateTime<Utc>> {
+        cron.parse::<CronExpr>().unwrap().next_after(&at(after))
+    }
+
+    fn schedule(target: ScheduleTarget, catch_up: ScheduleCatchUp) -> Schedule {
+        Schedule {
+            id: 1,
+            name: "test".to_string(),
+            cron: "0 18 * * *".to_string(),
+            target,
+            catch_up,
+            is_active: true,
+            next_fire_at: None,
+            last_fired_at: None,
+            window_open_until: None,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let cron: CronExpr = "*/15 8-18/2 * JAN,jul mon-FRI".parse().unwrap();
+        assert_eq!(
+            cron.to_string(),
+            "0,15,30,45 8,10,12,14,16,18 * 1,7 1,2,3,4,5"
+        );
+        let sunday: CronExpr = "0 0 * * 7".parse().unwrap();
+        assert_eq!(sunday, "0 0 * * 0".parse().unwrap());
+        assert_eq!(
+            "@daily".parse::<CronExpr>().unwrap().to_string(),
+            "0 0 * * *"
+        );
+
+        assert!("* * * *".parse::<CronExpr>().is_err());
+        assert!("60 * * * *".parse::<CronExpr>().is_err());
+        assert!("*/0 * * * *".parse::<CronExpr>().is_err());
+        assert!("5-1 * * * *".parse::<CronExpr>().is_err());
+        assert!("0 0 0 * *".parse::<CronExpr>().is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(
+            next("0 18 * * *", "2024-03-04T17:59:30Z"),
+            Some(at("2024-03-04T18:00:00Z"))
+        );
+        assert_eq!(
+            next("0 18 * * *", "2024-03-04T18:00:00Z"),
+            Some(at("2024-03-05T18:00:00Z"))
+        );
+        assert_eq!(
+            next("0 * * * *", "2024-12-31T23:10:00Z"),
+            Some(at("2025-01-01T00:00:00Z"))
+        );
+        // 2024-03-09 is a Saturday.
+        assert_eq!(
+            next("30 9 * * MON-FRI", "2024-03-08T10:00:00Z"),
+            Some(at("2024-03-11T09:30:00Z"))
+        );
+        // Both day fields restricted: the 1st of the month or any Sunday.
+        assert_eq!(
+            next("0 0 1 * SUN", "2024-03-04T00:00:00Z"),
+            Some(at("2024-03-10T00:00:00Z"))
+        );
+        assert_eq!(
+            next("0 0 29 2 *", "2024-03-01T00:00:00Z"),
+            Some(at("2028-02-29T00:00:00Z"))
+        );
+        assert_eq!(next("0 0 30 2 *", "2024-03-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn test_rule_window_opens_and_catches_up() {
+        let cron: CronExpr = "0 18 * * *".parse().unwrap();
+        let target = ScheduleTarget::RuleWindow {
+            rule_id: "eod".to_string(),
+            window_minutes: 120,
+        };
+
+        // Created at noon: waits for 18:00.
+        let mut eod = schedule(target.clone(), ScheduleCatchUp::Skip);
+        let tick = evaluate(&cron, &eod, &at("2024-03-04T12:00:00Z"));
+        assert!(!tick.fire);
+        assert_eq!(
+            tick.state.next_fire_at,
+            Some(at("2024-03-04T18:00:00Z").timestamp_millis())
+        );
+
+        // Created at 19:00: the 18:00 window is still open for an hour.
+        let tick = evaluate(&cron, &eod, &at("2024-03-04T19:00:00Z"));
+        assert!(tick.fire);
+        assert_eq!(
+            tick.state.window_open_until,
+            Some(at("2024-03-04T20:00:00Z").timestamp_millis())
+        );
+
+        // Due at 18:00 and evaluated then.
+        eod.next_fire_at = Some(at("2024-03-04T18:00:00Z").timestamp_millis());
+        let tick = evaluate(&cron, &eod, &at("2024-03-04T18:00:01Z"));
+        assert!(tick.fire && !tick.missed);
+        assert_eq!(
+            tick.state.next_fire_at,
+            Some(at("2024-03-05T18:00:00Z").timestamp_millis())
+        );
+
+        // Not due yet: nothing changes.
+        let tick = evaluate(&cron, &eod, &at("2024-03-04T17:00:00Z"));
+        assert!(!tick.fire);
+        assert_eq!(tick.state.next_fire_at, eod.next_fire_at);
+
+        // The Sentinel was down until 21:00: skip waits for tomorrow...
+        let tick = evaluate(&cron, &eod, &at("2024-03-04T21:00:00Z"));
+        assert!(!tick.fire && tick.missed);
+        assert_eq!(
+            tick.state.next_fire_at,
+            Some(at("2024-03-05T18:00:00Z").timestamp_millis())
+        );
+
+        // ...run_once opens a full window now.
+        eod.catch_up = ScheduleCatchUp::RunOnce;
+        let tick = evaluate(&cron, &eod, &at("2024-03-04T21:00:00Z"));
+        assert!(tick.fire && tick.missed);
+        assert_eq!(
+            tick.state.window_open_until,
+            Some(at("2024-03-04T23:00:00Z").timestamp_millis())
+        );
+    }
+
+    #[test]
+    fn test_rescan_grace() {
+        let cron: CronExpr = "0 * * * *".parse().unwrap();
+        let mut hourly = schedule(
+            ScheduleTarget::RescanSource {
+                source_id: "src".to_string(),
+            },
+            ScheduleCatchUp::Skip,
+        );
+        let tick = evaluate(&cron, &hourly, &at("2024-03-04T10:30:00Z"));
+        assert!(!tick.fire);
+
+        hourly.next_fire_at = Some(at("2024-03-04T11:00:00Z").timestamp_millis());
+        let tick = evaluate(&cron, &hourly, &at("2024-03-04T11:01:00Z"));
+        assert!(tick.fire);
+        assert_eq!(tick.state.window_open_until, None);
+
+        let tick = evaluate(&cron, &hourly, &at("2024-03-04T11:30:00Z"));
+        assert!(!tick.fire && tick.missed);
+        hourly.catch_up = ScheduleCatchUp::RunOnce;
+        let tick = evaluate(&cron, &hourly, &at("2024-03-04T11:30:00Z"));
+        assert!(tick.fire && tick.missed);
+        assert_eq!(
+            tick.state.next_fire_at,
+            Some(at("2024-03-04T12:00:00Z").timestamp_millis())
+        );
+    }
+}
@@ -5,15 +5,26 @@
 
 use anyhow::{Context, Result};
 use casparian_protocol::http_types::{
-    ApprovalOperation, ApprovalStatus, JobProgress as ApiJobProgress, JobResult as ApiJobResult,
+    Approval, ApprovalOperation, ApprovalStatus, EventType, JobProgress as ApiJobProgress,
+    JobResult as ApiJobResult, SYSTEM_EVENT_JOB_ID,
 };
 use casparian_protocol::types::{
-    self, ArtifactV1, DispatchCommand, IdentifyPayload, JobReceipt, JobStatus, ParsedSinkUri,
-    RuntimeKind, SchemaColumnSpec, SchemaDefinition, SinkConfig, SinkMode, SinkScheme,
+    self, ArtifactV1, BatchFile, CsvDialect, DispatchCommand, ErrorCategory, IdentifyPayload,
+    JobPriority, JobReceipt, JobResultSummary, JobStatus, LineageChain, ParsedSinkUri,
+    ResourceLimits, RuntimeKind, SchemaColumnSpec, SchemaDefinition, SchemaDrift,
+    SchemaEvolution, SinkConfig, SinkMode, SinkScheme, SourceHash, TextEncoding,
+    VerificationStatus, WorkerLoad,
 };
+use casparian_protocol::capabilities::normalize_requirements;
+use casparian_protocol::endpoint;
+use casparian_protocol::metrics::JobOutcome;
+use casparian_protocol::naming::validate_namespace;
+use casparian_protocol::stream::{split_payload, StreamAssembler, DEFAULT_STREAM_CHUNK_SIZE};
 use casparian_protocol::{
-    defaults, materialization_key, metrics, output_target_key, schema_hash, table_name_with_schema,
-    safe_output_id, ApiJobId, JobId, Message, OpCode, ProcessingStatus, WorkerStatus,
+    defaults, materialization_key, metrics, output_target_key, retry, schema_hash,
+    table_name_with_schema, safe_output_id, version, ApiJobId, JobId, Message, NegotiatedProtocol,
+    OpCode, ProcessingStatus, ProtocolFeatures, ProtocolVersionRange, RetryAttempt, RetryPolicy,
+    ScheduleCatchUp, ScheduleTarget, WorkerHealth, WorkerStatus,
 };
 use casparian_scout::{
     scan_path, ScanCancelToken, ScanConfig, ScanProgress, Source as ScoutSource, SourceId,
@@ -23,10 +34,12 @@ use casparian_schema::approval::derive_scope_id;
 use casparian_schema::{
     build_outputs_json, locked_schema_from_definition, SchemaContract, SchemaStorage,
 };
+use casparian_security::keyring::{publishers_dir, Keyring};
 use casparian_security::signing::compute_artifact_hash;
+use casparian_sinks::template::validate_sink_uri_template;
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use serde::Deserialize;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::mpsc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
@@ -35,22 +48,42 @@ use uuid::Uuid;
 use zmq::{Context as ZmqContext, Socket};
 
 use crate::control::{
-    ControlRequest, ControlResponse, JobInfo, QueueStatsInfo, ScanState, ScoutFileInfo,
-    ScoutFilesPage, ScoutFolderEntry, ScoutPatternMatch, ScoutPatternQueryResult, ScoutRuleInfo,
-    ScoutScanProgress, ScoutScanStatus, ScoutSourceInfo, ScoutTagCount, ScoutTagFilter,
-    ScoutTagStats,
+    ControlRequest, ControlResponse, DeadLetterInfo, JobInfo, PluginRollbackInfo, QueueStatsInfo,
+    ScanState, ScoutFileInfo, ScoutFilesPage, ScoutFolderEntry, ScoutPatternMatch,
+    ScoutPatternQueryResult, ScoutRuleInfo, ScoutScanProgress, ScoutScanStatus, ScoutSourceInfo,
+    ScheduleInfo, ScoutTagCount, ScoutTagFilter, ScoutTagStats, SubscriptionInfo, SystemPulse,
+    WorkerHealthInfo, WorkerHealthReport,
 };
 use crate::catalog_executor::{CatalogExecutor, CatalogIntent};
+use crate::event_bus::{self, EventBus, JobTransition, SentinelEvent};
+use crate::config_reload::{
+    changed_plugins, ConfigWatcher, ReloadableSettings, CONFIG_RELOADED_ACTION,
+    CONFIG_RELOAD_TICK_SECS,
+};
+use crate::receipt_verify::{artifacts_from_records, verify_artifacts, ReceiptVerifier};
 use crate::sqlite_executor::{SqliteContext, SqliteExecutor};
-use crate::db::queue::{OutputMaterialization, MAX_RETRY_COUNT};
+use crate::db::queue::{Job, OutputMaterialization};
 use crate::db::{
     models::*, IntentState, SessionId,
 };
 use crate::metrics::METRICS;
-use casparian_state_store::{DispatchData, StateStore, StateStoreQueueSession};
-
-/// Workers are considered stale after this many seconds without heartbeat
-const WORKER_TIMEOUT_SECS: f64 = 60.0;
+use crate::metrics_server::MetricsServer;
+use crate::schedules::{self, CronExpr};
+use crate::scheduler::{
+    may_preempt, preemption_victim, route_job, BusyWorker, DispatchScheduler, JobRouting,
+    QueuedJob, SchedulingPolicy, WorkerCandidate,
+};
+use crate::transport_security::{secure_router, SecurityConfig, ZapHandler};
+use crate::worker_health::{HealthTracker, WorkerHealthConfig};
+use crate::worker_pool::{PooledWorkerState, WorkerPoolConfig, WorkerSupervisor};
+use casparian_state_store::audit::{entity, snapshot};
+use casparian_state_store::write_buffer::{
+    BufferedWrite, MetricSample, WorkerHeartbeat, WriteBuffer, WriteBufferConfig,
+};
+use casparian_state_store::{
+    DatasetCommit, DispatchData, PluginRollback, Schedule, StateStore, StateStoreQueueSession, TopicSubscription,
+    SCHEDULE_HOLD_REASON,
+};
 
 /// How often to run cleanup (seconds)
 const CLEANUP_INTERVAL_SECS: f64 = 10.0;
@@ -67,17 +100,34 @@ const DISPATCH_LEASE_TTL_MS: i64 = 30_000;
 const DISPATCH_LEASE_SWEEP_SECS: f64 = 5.0;
 /// Delay before retrying transient dispatch preparation failures.
 const DISPATCH_PREP_RETRY_MS: i64 = 10_000;
+/// Delay before a job leased by an incompatible worker is offered again
+const DISPATCH_REROUTE_MS: i64 = 250;
+/// Only files up to this size ride along in a batched DISPATCH.
+const DISPATCH_BATCH_MAX_FILE_BYTES: u64 = 1024 * 1024;
 /// Grace period for worker reconnects after sentinel restart (seconds).
 const RECONNECT_GRACE_SECS: f64 = 60.0;
+/// In-process ROUTER endpoint behind the gRPC relay for `grpc://` binds
+const GRPC_ROUTER_ENDPOINT: &str = "inproc://sentinel-grpc";
+/// Default queue wait before a job is raised to the HIGH lane (seconds).
+pub const DEFAULT_PRIORITY_AGING_SECS: u64 = 600;
+/// Drifted columns a dataset may accumulate before a `SchemaDrift` event.
+pub const DEFAULT_SCHEMA_DRIFT_THRESHOLD: usize = 0;
+/// How often to look for a waiting URGENT job while every worker is busy (seconds).
+const PREEMPTION_CHECK_SECS: f64 = 1.0;
+/// How often to evaluate cron schedules (seconds).
+const SCHEDULE_TICK_SECS: f64 = 5.0;
+/// How often the local worker pool is reconciled with the queue (seconds).
+const WORKER_POOL_TICK_SECS: f64 = 2.0;
+/// How often output compaction policies are evaluated (seconds).
+const COMPACTION_TICK_SECS: f64 = 300.0;
+/// Delay before re-checking a job held by a window the Sentinel has not
+/// evaluated yet.
+const SCHEDULE_HOLD_RETRY_MS: i64 = 60_000;
 
 // ============================================================================
 // Circuit Breaker & Retry Constants
 // ============================================================================
 
-/// Base backoff in seconds for exponential retry (4^retry_count)
-/// Retry 1: 4s, Retry 2: 16s, Retry 3: 64s
-const BACKOFF_BASE_SECS: u64 = 4;
-
 /// Consecutive failure threshold before tripping circuit breaker
 const CIRCUIT_BREAKER_THRESHOLD: i32 = 5;
 
@@ -93,6 +143,14 @@ const HARD_MAX_WORKERS: usize = 8;
 // Scout scan tracking (control API)
 // ============================================================================
 
+/// A source rescan fired by a cron schedule.
+#[derive(Debug, Clone)]
+struct ScheduledRescan {
+    schedule_name: String,
+    workspace_id: WorkspaceId,
+    path: String,
+}
+
 #[derive(Debug, Clone)]
 struct ScanJobState {
     scan_id: String,
@@ -133,12 +191,43 @@ struct PendingConclude {
     rx: mpsc::Receiver<anyhow::Result<ConcludeOutcome>>,
 }
 
-struct PendingCancelJob {
+/// Routing tables read for hot reload.
+struct RoutingReload {
+    topic_map: HashMap<String, Vec<SinkConfig>>,
+    /// Plugins whose sinks changed since the last read, with their active
+    /// version (None when not deployed)
+    changed: Vec<(String, Option<String>)>,
+}
+
+struct PendingPreemption {
     identity: Vec<u8>,
+    worker_id: String,
     job_id: JobId,
+    /// Whether the running job was requeued
     rx: mpsc::Receiver<anyhow::Result<bool>>,
 }
 
+struct PendingCancelJob {
+    identity: Vec<u8>,
+    job_id: JobId,
+    rx: mpsc::Receiver<anyhow::Result<CancelOutcome>>,
+}
+
+struct PendingPluginRollback {
+    identity: Vec<u8>,
+    rx: mpsc::Receiver<anyhow::Result<PluginRollback>>,
+}
+
+/// Queue-side result of a CancelJob request.
+enum CancelOutcome {
+    /// Cancelled before any worker picked it up.
+    NotStarted,
+    /// Was RUNNING; marked cancelled and the owning worker must be sent ABORT.
+    Running,
+    /// Unknown job or already terminal.
+    Unchanged,
+}
+
 struct DispatchPlan {
     job_id_db: i64,
     job_id: JobId,
@@ -245,18 +334,63 @@ pub struct ConnectedWorker {
     pub capabilities: Vec<String>,
     pub current_job_id: Option<JobId>,
     pub current_lease_token: Option<String>,
+    /// Follow-on jobs (and their lease tokens) of the current batched DISPATCH.
+    pub batch_leases: Vec<(JobId, String)>,
     pub worker_id: String,
+    /// Protocol version and features agreed at IDENTIFY.
+    pub protocol: NegotiatedProtocol,
+    /// Latest load reported in HEARTBEAT.
+    pub load: Option<WorkerLoad>,
+    /// Heartbeat-driven health state
+    pub health: HealthTracker,
+    /// Queue lane of the current job
+    pub current_priority: JobPriority,
+    /// When the current job was dispatched
+    pub dispatched_at: f64,
 }
 
 impl ConnectedWorker {
-    fn new(worker_id: String, capabilities: Vec<String>) -> Self {
+    /// Whether `job_id` is the current job or one of its batched files.
+    fn owns_job(&self, job_id: JobId) -> bool {
+        self.current_job_id == Some(job_id)
+            || self.batch_leases.iter().any(|(batch_job_id, _)| *batch_job_id == job_id)
+    }
+
+    /// Lease of every job in the current DISPATCH, batched files included.
+    fn dispatched_leases(&self) -> Vec<(JobId, Option<String>)> {
+        self.current_job_id
+            .map(|job_id| (job_id, self.current_lease_token.clone()))
+            .into_iter()
+            .chain(
+                self.batch_leases
+                    .iter()
+                    .map(|(job_id, token)| (*job_id, Some(token.clone()))),
+            )
+            .collect()
+    }
+
+    fn clear_dispatch(&mut self) {
+        self.current_job_id = None;
+        self.current_lease_token = None;
+        self.batch_leases.clear();
+        self.current_priority = JobPriority::default();
+        self.dispatched_at = 0.0;
+    }
+
+    fn new(worker_id: String, capabilities: Vec<String>, protocol: NegotiatedProtocol) -> Self {
         Self {
             status: WorkerStatus::Idle,
             last_seen: current_time(),
             capabilities,
             current_job_id: None,
             current_lease_token: None,
+            batch_leases: Vec::new(),
             worker_id,
+            protocol,
+            load: None,
+            health: HealthTracker::new(current_time()),
+            current_priority: JobPriority::default(),
+            dispatched_at: 0.0,
         }
     }
 }
@@ -271,6 +405,40 @@ pub struct SentinelConfig {
     pub control_addr: Option<String>,
     /// DuckDB query catalog path (local SQL over Parquet)
     pub query_catalog_path: std::path::PathBuf,
+    /// Policy for ordering idle workers on each dispatch pass
+    pub scheduling_policy: SchedulingPolicy,
+    /// Optional HTTP address for Prometheus `/metrics` and `/healthz`.
+    /// If None, the endpoint is disabled.
+    pub metrics_addr: Option<String>,
+    /// Optional loopback address for the HTTP control-plane API.
+    /// Setting it without the `http-api` feature is an error.
+    pub http_api_addr: Option<String>,
+    /// CURVE encryption and worker allow-list for the worker socket
+    pub security: SecurityConfig,
+    /// Dev mode: accept deploys without a publisher signature
+    pub allow_unsigned_deploys: bool,
+    /// Max files per DISPATCH for workers that support batching (1 = no batching).
+    /// Extra files are queued small files of the same plugin.
+    pub dispatch_batch_size: usize,
+    /// Silence thresholds and flap detection for connected workers
+    pub worker_health: WorkerHealthConfig,
+    /// Queued jobs waiting longer than this are raised to HIGH (0 = never)
+    pub priority_aging_secs: u64,
+    /// Let a waiting URGENT job preempt a LOW job when every worker is busy
+    pub preempt_low_priority: bool,
+    /// Audit mode: no dispatch, deploys, background writes or mutating
+    /// control requests (answered with `READ_ONLY_MODE`)
+    pub read_only: bool,
+    /// Ignore cached input hashes: workers hash every dispatched file again
+    pub rehash: bool,
+    /// Raise a `SchemaDrift` event once a dataset has more drifted columns
+    /// (unmapped source columns plus type drifts) than this
+    pub schema_drift_threshold: usize,
+    /// Spawn and supervise local worker processes (desktop installs)
+    pub worker_pool: Option<WorkerPoolConfig>,
+    /// Settings file overriding the scheduling settings above; watched and
+    /// applied without a restart (see [`crate::config_reload`])
+    pub config_path: Option<std::path::PathBuf>,
 }
 
 /// Main Sentinel control plane
@@ -282,8 +450,14 @@ pub struct Sentinel {
     workers: HashMap<Vec<u8>, ConnectedWorker>,
     state_store: Arc<StateStore>,
     sqlite_executor: SqliteExecutor,
+    /// Broadcasts job, worker and approval events to metrics, the audit
+    /// tape, the API event log and the HTTP event feed
+    events: EventBus,
+    /// Heartbeats, log chunks and load samples waiting to be written
+    write_buffer: WriteBuffer,
     query_catalog_path: std::path::PathBuf,
     catalog_executor: CatalogExecutor,
+    receipt_verifier: ReceiptVerifier,
     state_store_path: Option<std::path::PathBuf>,
     scan_jobs: HashMap<String, ScanJobState>,
     scan_event_tx: mpsc::Sender<ScanEvent>,
@@ -292,10 +466,21 @@ pub struct Sentinel {
     pending_dispatches: Vec<PendingDispatch>,
     pending_concludes: Vec<PendingConclude>,
     pending_cancel_jobs: Vec<PendingCancelJob>,
+    pending_plugin_rollbacks: Vec<PendingPluginRollback>,
     pending_dispatch_sweep: Option<mpsc::Receiver<anyhow::Result<usize>>>,
+    pending_schedule_tick: Option<mpsc::Receiver<anyhow::Result<Vec<ScheduledRescan>>>>,
+    /// Queued plus running jobs, for sizing the worker pool
+    pending_pool_demand: Option<mpsc::Receiver<anyhow::Result<usize>>>,
+    /// Background thread compacting small Parquet outputs
+    pending_compaction: Option<std::thread::JoinHandle<()>>,
     running: bool,
     last_cleanup: f64, // Last time we ran stale worker cleanup
     last_dispatch_lease_sweep: f64,
+    last_schedule_tick: f64,
+    last_worker_pool_tick: f64,
+    last_compaction_tick: f64,
+    /// Local worker processes; None when workers are started externally
+    worker_supervisor: Option<WorkerSupervisor>,
     /// Jobs orphaned by stale workers - need to be failed asynchronously
     orphaned_jobs: Vec<(JobId, Option<String>)>,
     startup_grace_deadline: Option<f64>,
@@ -304,30 +489,110 @@ pub struct Sentinel {
     dispatch_backoff_ms: u64,
     dispatch_cooldown_until: Option<Instant>,
     max_workers: usize,
+    /// Reassembles chunked payloads per (worker identity, job id)
+    stream_assembler: StreamAssembler<(Vec<u8>, JobId)>,
+    /// Load-aware worker selection for dispatch
+    scheduler: DispatchScheduler,
+    /// HTTP `/metrics` + `/healthz` listener; stops when the Sentinel drops
+    metrics_server: Option<MetricsServer>,
+    /// HTTP control-plane API; stops when the Sentinel drops
+    #[cfg(feature = "http-api")]
+    http_api_server: Option<crate::http_api::HttpApiServer>,
+    /// gRPC listener relaying to the ROUTER for `grpc://` binds
+    #[cfg(feature = "grpc")]
+    grpc_relay: Option<casparian_grpc::GrpcRelay>,
+    /// Named-pipe servers in front of `pipe://` binds (worker and control)
+    pipe_relays: Vec<casparian_pipe::PipeRelay>,
+    /// Authenticates CURVE workers; None when the transport is plaintext
+    zap_handler: Option<ZapHandler>,
+    allow_unsigned_deploys: bool,
+    dispatch_batch_size: usize,
+    worker_health: WorkerHealthConfig,
+    /// Starvation protection: queue wait before a job is raised to HIGH (0 = off)
+    priority_aging_ms: i64,
+    preempt_low_priority: bool,
+    /// Audit mode: refuse workers, deploys and mutating control requests
+    read_only: bool,
+    /// Dispatch without cached input hashes
+    rehash: bool,
+    schema_drift_threshold: usize,
+    last_preemption_check: f64,
+    pending_preemption: Option<PendingPreemption>,
+    /// Scheduling settings in effect
+    settings: ReloadableSettings,
+    /// Settings file watched for changes; None without `config_path`
+    config_watcher: Option<ConfigWatcher>,
+    last_config_reload_tick: f64,
+    /// Topic configs at the last routing reload
+    routing_snapshot: Option<HashMap<String, Vec<SinkConfig>>>,
+    pending_routing_reload: Option<mpsc::Receiver<anyhow::Result<RoutingReload>>>,
 }
 
 impl Sentinel {
     /// Create and bind Sentinel
     pub fn bind(config: SentinelConfig) -> Result<Self> {
-        let max_workers = if config.max_workers == 0 {
-            DEFAULT_MAX_WORKERS
-        } else {
-            config.max_workers.min(HARD_MAX_WORKERS)
+        let flag_settings = ReloadableSettings {
+            max_workers: if config.max_workers == 0 {
+                DEFAULT_MAX_WORKERS
+            } else {
+                config.max_workers
+            },
+            scheduling_policy: config.scheduling_policy,
+            dispatch_batch_size: config.dispatch_batch_size.max(1),
+            priority_aging_secs: config.priority_aging_secs,
+            preempt_low_priority: config.preempt_low_priority,
+            schema_drift_threshold: config.schema_drift_threshold,
+            worker_health: config.worker_health,
+        };
+        let (config_watcher, settings) = match config.config_path.clone() {
+            Some(path) => {
+                let (watcher, settings) = ConfigWatcher::new(path, flag_settings)?;
+                info!("Watching Sentinel config {}", watcher.path().display());
+                (Some(watcher), settings)
+            }
+            None => {
+                flag_settings.validate()?;
+                (None, flag_settings)
+            }
         };
+        let max_workers = settings.max_workers.min(HARD_MAX_WORKERS);
 
         let state_store = StateStore::open(&config.state_store_url)
             .context("Failed to connect to state store")?;
-        state_store.init()?;
+        if config.read_only {
+            info!("Read-only mode: schema init, dispatch and mutating requests are disabled");
+        } else {
+            state_store.init()?;
+        }
 
         let state_store = Arc::new(state_store);
         let sqlite_executor =
             SqliteExecutor::start(state_store.clone()).context("Failed to start sqlite executor")?;
+        let events = EventBus::new();
+        events.on(event_bus::record_metrics);
+        let audit = state_store.audit().clone();
+        events.on(move |event| event_bus::record_audit(&audit, event));
+        let event_log = sqlite_executor.clone();
+        events.on(move |event| {
+            let Some(event) = event_bus::api_event(event) else {
+                return;
+            };
+            let result = event_log.execute(move |state_store, _, _| {
+                state_store.api().insert_event(SYSTEM_EVENT_JOB_ID, &event)?;
+                Ok(())
+            });
+            if let Err(err) = result {
+                warn!("Failed to record event: {}", err);
+            }
+        });
         let now = now_millis();
-        if let Ok(requeued) =
-            sqlite_executor.call(move |_, queue, _| queue.requeue_expired_dispatches(now))
-        {
-            if requeued > 0 {
-                info!("Requeued {} expired dispatch leases on startup", requeued);
+        if !config.read_only {
+            if let Ok(requeued) =
+                sqlite_executor.call(move |_, queue, _| queue.requeue_expired_dispatches(now))
+            {
+                if requeued > 0 {
+                    info!("Requeued {} expired dispatch leases on startup", requeued);
+                }
             }
         }
 
@@ -345,27 +610,35 @@ impl Sentinel {
             Ok(())
         });
 
-        // Destructive Initialization for IPC sockets (Unix only)
-        // Unlink stale socket files to prevent "Address in use" errors
-        #[cfg(unix)]
-        if let Some(socket_path) = config.bind_addr.strip_prefix("ipc://") {
-            let path = std::path::Path::new(socket_path);
-            if path.exists() {
-                info!("Removing stale IPC socket: {}", socket_path);
-                if let Err(e) = std::fs::remove_file(path) {
-                    warn!("Failed to remove stale socket {}: {}", socket_path, e);
-                }
-            }
+        // Unlink a stale IPC socket to prevent "Address in use" errors;
+        // a live one means another Sentinel owns the address
+        prepare_local_bind(&config.bind_addr)?;
+
+        // A grpc:// address binds the ROUTER in-process behind a gRPC relay
+        let grpc_addr = config.bind_addr.strip_prefix("grpc://");
+        if grpc_addr.is_some() && config.security.server_keypair.is_some() {
+            anyhow::bail!("CURVE security is only available on ZMQ bind addresses, not grpc://");
+        }
+        #[cfg(not(feature = "grpc"))]
+        if grpc_addr.is_some() {
+            anyhow::bail!(
+                "gRPC transport requested on {} but this build lacks the grpc feature",
+                config.bind_addr
+            );
         }
+        let router_addr = match grpc_addr {
+            Some(_) => GRPC_ROUTER_ENDPOINT,
+            None => config.bind_addr.as_str(),
+        };
 
         // Create and bind ROUTER socket
         let context = ZmqContext::new();
         let socket = context
             .socket(zmq::ROUTER)
             .context("Failed to create ROUTER socket")?;
-        socket
-            .bind(&config.bind_addr)
-            .context("Failed to bind ROUTER socket")?;
+        let zap_handler = secure_router(&context, &socket, &config.security)?;
+        let pipe_relay =
+            bind_local(&socket, router_addr).context("Failed to bind ROUTER socket")?;
         socket
             .set_router_mandatory(true)
             .context("Failed to set ROUTER_MANDATORY")?;
@@ -373,37 +646,83 @@ impl Sentinel {
             .set_sndtimeo(50)
             .context("Failed to set socket send timeout")?;
         info!("Sentinel bound to {}", config.bind_addr);
+        #[cfg(feature = "grpc")]
+        let grpc_relay = grpc_addr
+            .map(|addr| casparian_grpc::GrpcRelay::start(addr, &context, GRPC_ROUTER_ENDPOINT))
+            .transpose()?;
 
         // Optionally create control API socket
+        let mut pipe_relays: Vec<_> = pipe_relay.into_iter().collect();
         let control_socket = if let Some(ref control_addr) = config.control_addr {
-            // Destructive initialization for IPC sockets
-            #[cfg(unix)]
-            if let Some(socket_path) = control_addr.strip_prefix("ipc://") {
-                let path = std::path::Path::new(socket_path);
-                if path.exists() {
-                    info!("Removing stale control IPC socket: {}", socket_path);
-                    if let Err(e) = std::fs::remove_file(path) {
-                        warn!(
-                            "Failed to remove stale control socket {}: {}",
-                            socket_path, e
-                        );
-                    }
-                }
-            }
-
+            prepare_local_bind(control_addr)?;
             let ctrl_socket = context
                 .socket(zmq::ROUTER)
                 .context("Failed to create control ROUTER socket")?;
-            ctrl_socket
-                .bind(control_addr)
+            let control_relay = bind_local(&ctrl_socket, control_addr)
                 .with_context(|| format!("Failed to bind control socket to {}", control_addr))?;
+            pipe_relays.extend(control_relay);
             info!("Control API bound to {}", control_addr);
             Some(ctrl_socket)
         } else {
             None
         };
 
+        let metrics_server = config
+            .metrics_addr
+            .as_deref()
+            .map(MetricsServer::start)
+            .transpose()?;
+
+        #[cfg(feature = "http-api")]
+        let http_api_server = config
+            .http_api_addr
+            .as_deref()
+            .map(|addr| {
+                crate::http_api::HttpApiServer::start(
+                    addr,
+                    sqlite_executor.clone(),
+                    &events,
+                    config.query_catalog_path.clone(),
+                    config.read_only,
+                )
+            })
+            .transpose()?;
+        #[cfg(not(feature = "http-api"))]
+        if let Some(addr) = config.http_api_addr.as_deref() {
+            anyhow::bail!(
+                "HTTP API requested on {} but this build lacks the http-api feature",
+                addr
+            );
+        }
+
+        let worker_supervisor = match config.worker_pool {
+            Some(_) if config.read_only => {
+                warn!("Worker pool disabled: a read-only Sentinel refuses workers");
+                None
+            }
+            Some(mut pool) => {
+                pool.min_workers = pool.min_workers.min(pool.max_workers);
+                pool.validate()?;
+                info!(
+                    "Worker pool: {}..={} local workers",
+                    pool.min_workers.min(max_workers),
+                    pool.max_workers.min(max_workers)
+                );
+                let mut supervisor = WorkerSupervisor::new(pool);
+                supervisor.set_cap(max_workers);
+                Some(supervisor)
+            }
+            None => None,
+        };
+
         let state_store_path = sqlite_path_from_url(&config.state_store_url);
+        let write_buffer = WriteBuffer::open(WriteBufferConfig {
+            journal_dir: state_store_path
+                .as_deref()
+                .filter(|_| !config.read_only)
+                .map(write_journal_dir),
+            ..WriteBufferConfig::default()
+        })?;
         let catalog_executor = CatalogExecutor::start(config.query_catalog_path.clone());
 
         let (scan_event_tx, scan_event_rx) = mpsc::channel();
@@ -415,8 +734,11 @@ impl Sentinel {
             workers: HashMap::new(),
             state_store,
             sqlite_executor,
+            events,
+            write_buffer,
             query_catalog_path: config.query_catalog_path,
             catalog_executor,
+            receipt_verifier: ReceiptVerifier::start(),
             state_store_path,
             scan_jobs: HashMap::new(),
             scan_event_tx,
@@ -425,17 +747,51 @@ impl Sentinel {
             pending_dispatches: Vec::new(),
             pending_concludes: Vec::new(),
             pending_cancel_jobs: Vec::new(),
+            pending_plugin_rollbacks: Vec::new(),
             pending_dispatch_sweep: None,
+            pending_schedule_tick: None,
+            pending_pool_demand: None,
+            pending_compaction: None,
             running: false,
             last_cleanup: current_time(),
             last_dispatch_lease_sweep: current_time(),
+            last_schedule_tick: 0.0,
+            last_worker_pool_tick: 0.0,
+            last_compaction_tick: current_time(),
+            worker_supervisor,
             orphaned_jobs: Vec::new(),
-            startup_grace_deadline: Some(current_time() + RECONNECT_GRACE_SECS),
+            // Restart reconciliation fails orphaned jobs, a write.
+            startup_grace_deadline: (!config.read_only)
+                .then(|| current_time() + RECONNECT_GRACE_SECS),
             seen_worker_ids: HashSet::new(),
             reconciled_workers: HashSet::new(),
             dispatch_backoff_ms: 0,
             dispatch_cooldown_until: None,
             max_workers,
+            stream_assembler: StreamAssembler::default(),
+            scheduler: DispatchScheduler::new(settings.scheduling_policy),
+            metrics_server,
+            #[cfg(feature = "http-api")]
+            http_api_server,
+            #[cfg(feature = "grpc")]
+            grpc_relay,
+            pipe_relays,
+            zap_handler,
+            allow_unsigned_deploys: config.allow_unsigned_deploys,
+            dispatch_batch_size: settings.dispatch_batch_size,
+            worker_health: settings.worker_health,
+            priority_aging_ms: aging_ms(settings.priority_aging_secs),
+            preempt_low_priority: settings.preempt_low_priority,
+            read_only: config.read_only,
+            rehash: config.rehash,
+            schema_drift_threshold: settings.schema_drift_threshold,
+            last_preemption_check: 0.0,
+            pending_preemption: None,
+            settings,
+            config_watcher,
+            last_config_reload_tick: current_time(),
+            routing_snapshot: None,
+            pending_routing_reload: None,
         })
     }
 
@@ -457,10 +813,20 @@ impl Sentinel {
                     key.1
                 );
             }
+            let schema_evolution =
+                SchemaEvolution::from_sink_uri(&tc.uri).map_err(|e| anyhow::anyhow!(e))?;
+            let uri = tc.resolved_uri();
+            validate_sink_uri_template(&uri).with_context(|| {
+                format!(
+                    "Invalid sink for plugin '{}' and topic '{}'",
+                    tc.plugin_name, tc.topic_name
+                )
+            })?;
             let sink = SinkConfig {
+                uri,
                 topic: tc.topic_name,
-                uri: tc.uri,
                 mode: tc.mode, // Already a SinkMode enum, parsed at the boundary
+                schema_evolution,
                 quarantine_config: tc.quarantine_config.clone(),
                 schema: None,
             };
@@ -481,6 +847,7 @@ impl Sentinel {
                 topic: defaults::DEFAULT_SINK_TOPIC.to_string(),
                 uri: defaults::DEFAULT_SINK_URI.to_string(),
                 mode: SinkMode::Append,
+                schema_evolution: SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             });
@@ -507,6 +874,7 @@ impl Sentinel {
                 data_type: col.data_type.clone(),
                 nullable: col.nullable,
                 format: col.format.clone(),
+                constraints: col.constraints.clone(),
             })
             .collect();
 
@@ -598,23 +966,186 @@ impl Sentinel {
 
 
     fn is_default_sink(topic: &str) -> bool {
-        topic == "*" || topic == defaults::DEFAULT_SINK_TOPIC
+        types::is_default_sink_topic(topic)
     }
 
     fn select_sink_for_output<'a>(
         sinks: &'a [SinkConfig],
         output_name: &str,
     ) -> Option<&'a SinkConfig> {
-        if let Some(exact) = sinks.iter().find(|sink| sink.topic == output_name) {
-            return Some(exact);
+        types::select_sink_for_output(sinks, output_name)
+    }
+
+    /// Declared outputs of the plugin that no sink in `sinks` would receive.
+    fn untargeted_expected_outputs(
+        routing: &dyn casparian_state_store::RoutingStore,
+        plugin_name: &str,
+        parser_version: &str,
+        sinks: &[SinkConfig],
+    ) -> Result<Vec<String>> {
+        let expected_outputs = routing.expected_outputs_for_plugin(
+            plugin_name,
+            if parser_version.trim().is_empty() {
+                None
+            } else {
+                Some(parser_version)
+            },
+        )?;
+        let names: Vec<&str> = expected_outputs
+            .iter()
+            .map(|output| output.output_name.as_str())
+            .collect();
+        Ok(types::untargeted_outputs(sinks, &names)
+            .into_iter()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Prior materializations covering every output this job would write.
+    ///
+    /// Returns None (dispatch normally) when any output is missing a record,
+    /// or when the outputs or file generation cannot be determined.
+    fn prior_materializations(
+        routing: &dyn casparian_state_store::RoutingStore,
+        queue: &StateStoreQueueSession,
+        file_id: i64,
+        plugin_name: &str,
+        parser_version: &str,
+        parser_fingerprint: &str,
+        sinks: &[SinkConfig],
+    ) -> Result<Option<Vec<OutputMaterialization>>> {
+        let mut output_names: BTreeSet<String> = sinks
+            .iter()
+            .filter(|sink| !Self::is_default_sink(&sink.topic))
+            .map(|sink| sink.topic.clone())
+            .collect();
+        let expected_outputs = routing.expected_outputs_for_plugin(
+            plugin_name,
+            if parser_version.trim().is_empty() {
+                None
+            } else {
+                Some(parser_version)
+            },
+        )?;
+        output_names.extend(expected_outputs.into_iter().map(|output| output.output_name));
+        if output_names.is_empty() {
+            return Ok(None);
+        }
+
+        let Some((file_mtime, file_size)) = queue.load_file_generation(file_id)? else {
+            return Ok(None);
+        };
+
+        let mut keys = Vec::with_capacity(output_names.len());
+        for output_name in &output_names {
+            let Some(sink) = Self::select_sink_for_output(sinks, output_name) else {
+                return Ok(None);
+            };
+            let output_schema_hash = schema_hash(sink.schema.as_ref());
+            let table_name = table_name_with_schema(output_name, output_schema_hash.as_deref());
+            let target_key = output_target_key(
+                output_name,
+                &sink.uri,
+                sink.mode,
+                Some(&table_name),
+                output_schema_hash.as_deref(),
+            );
+            keys.push(materialization_key(
+                file_id,
+                file_mtime,
+                file_size,
+                parser_fingerprint,
+                &target_key,
+            ));
+        }
+
+        let records = queue.find_output_materializations(&keys)?;
+        let found: HashSet<&str> = records
+            .iter()
+            .map(|record| record.materialization_key.as_str())
+            .collect();
+        if keys.iter().all(|key| found.contains(key.as_str())) {
+            Ok(Some(records))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Conclude a leased job without dispatching it because its outputs are
+    /// already materialized. The prior artifacts are recorded on the job.
+    fn skip_materialized_job(
+        state_store: &StateStore,
+        queue: &StateStoreQueueSession,
+        job: &ProcessingJob,
+        lease_token: &str,
+        records: &[OutputMaterialization],
+    ) -> Result<()> {
+        let prior_jobs: BTreeSet<i64> = records.iter().map(|record| record.job_id).collect();
+        let rows: i64 = records.iter().map(|record| record.rows).sum();
+        let receipt = JobReceipt {
+            status: JobStatus::Success,
+            metrics: HashMap::from([("rows".to_string(), rows)]),
+            artifacts: records
+                .iter()
+                .map(|record| ArtifactV1::Output {
+                    output_name: record.output_name.clone(),
+                    sink_uri: record.sink_uri.clone(),
+                    table: record.table_name.clone(),
+                    rows: u64::try_from(record.rows).ok(),
+                    schema_hash: record.schema_hash.clone(),
+                    content_hash: None,
+                })
+                .collect(),
+            error_message: None,
+            diagnostics: None,
+            source_hash: None,
+            source_file: None,
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: None,
+            lease_token: Some(lease_token.to_string()),
+            batch_results: Vec::new(),
+        };
+
+        let summary = format!(
+            "Skipped: outputs already materialized by job(s) {}",
+            prior_jobs
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if !queue.skip_job_if_token_matches_dispatching(job.id, lease_token, &summary)? {
+            warn!("Stale dedup skip ignored for job {}", job.id);
+            return Ok(());
+        }
+        if let Err(err) =
+            queue.record_result_summary(job.id, &JobResultSummary::from_receipt(&receipt))
+        {
+            warn!(
+                "Failed to persist result summary for job {}: {}",
+                job.id, err
+            );
         }
-        if let Some(default) = sinks.iter().find(|sink| Self::is_default_sink(&sink.topic)) {
-            return Some(default);
+        if let Err(err) = state_store
+            .artifacts()
+            .insert_job_artifacts(job.id, &receipt.artifacts)
+        {
+            warn!("Failed to persist artifacts for job {}: {}", job.id, err);
         }
-        if sinks.len() == 1 {
-            return sinks.first();
+        if let Err(err) = queue.update_pipeline_run_status_for_job(job.id) {
+            warn!(
+                "Failed to update pipeline run status for job {}: {}",
+                job.id, err
+            );
         }
-        None
+        info!(
+            "Job {} ({}) skipped: {} output(s) already materialized",
+            job.id,
+            job.plugin_name,
+            receipt.artifacts.len()
+        );
+        Ok(())
     }
 
     fn record_materializations_for_job_with_context(
@@ -805,11 +1336,84 @@ impl Sentinel {
             };
             queue.insert_output_materialization(&record)?;
         }
+
+        Self::register_datasets(
+            queue,
+            &dispatch.plugin_name,
+            dispatch.file_id,
+            &sinks,
+            job_id,
+            receipt,
+            &output_rows,
+        )?;
+        Ok(())
+    }
+
+    /// Add every committed output of the job to the dataset catalog.
+    fn register_datasets(
+        queue: &casparian_state_store::StateStoreQueueSession,
+        plugin_name: &str,
+        file_id: i64,
+        sinks: &[SinkConfig],
+        job_id: i64,
+        receipt: &JobReceipt,
+        output_rows: &HashMap<String, i64>,
+    ) -> Result<()> {
+        let mut lineage_root = None;
+        for artifact in &receipt.artifacts {
+            let ArtifactV1::Output {
+                output_name,
+                sink_uri,
+                table,
+                rows,
+                schema_hash: artifact_schema_hash,
+                ..
+            } = artifact
+            else {
+                continue;
+            };
+            if lineage_root.is_none() {
+                lineage_root = Some(queue.load_source_root(file_id)?);
+            }
+            let sink = Self::select_sink_for_output(sinks, output_name);
+            let rows = rows.unwrap_or_else(|| {
+                output_rows.get(output_name).copied().unwrap_or(0).max(0) as u64
+            });
+            let partition_columns = sink
+                .and_then(|sink| ParsedSinkUri::parse(&sink.uri).ok())
+                .and_then(|parsed| parsed.query.get("partition_by").cloned())
+                .map(|columns| {
+                    columns
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|column| !column.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let schema = sink.and_then(|sink| sink.schema.clone());
+            let commit = DatasetCommit {
+                name: output_name.clone(),
+                plugin_name: plugin_name.to_string(),
+                job_id,
+                sink_uri: sink_uri.clone(),
+                sink_mode: sink.map(|sink| sink.mode).unwrap_or_default(),
+                table_name: table.clone(),
+                schema_hash: artifact_schema_hash
+                    .clone()
+                    .or_else(|| schema_hash(schema.as_ref())),
+                schema,
+                rows,
+                partition_columns,
+                lineage_root: lineage_root.clone().flatten(),
+            };
+            queue.register_dataset(&commit)?;
+        }
         Ok(())
     }
 
     fn update_query_catalog_for_artifacts(&self, artifacts: &[ArtifactV1]) -> Result<()> {
-        let mut views: HashMap<String, std::path::PathBuf> = HashMap::new();
+        let mut views: HashMap<String, (std::path::PathBuf, bool)> = HashMap::new();
         for artifact in artifacts {
             let (output_name, sink_uri, is_quarantine) = match artifact {
                 ArtifactV1::Output {
@@ -852,13 +1456,22 @@ impl Sentinel {
             };
 
             let safe_name = safe_output_id(output_name);
-            let pattern = base_dir.join(format!("{}_*.parquet", safe_name));
+            let hive_partitioning = matches!(parsed.scheme, SinkScheme::Parquet)
+                && parsed.query.contains_key("partition_by");
+            let file_glob = format!("{}_*.parquet", safe_name);
+            let pattern = if hive_partitioning {
+                base_dir.join("**").join(file_glob)
+            } else {
+                base_dir.join(file_glob)
+            };
             let view_name = if is_quarantine {
                 format!("quarantine.{}", quote_ident(output_name))
             } else {
                 format!("outputs.{}", quote_ident(output_name))
             };
-            views.entry(view_name).or_insert(pattern);
+            views
+                .entry(view_name)
+                .or_insert((pattern, hive_partitioning));
         }
 
         if views.is_empty() {
@@ -867,9 +1480,10 @@ impl Sentinel {
 
         let intents = views
             .into_iter()
-            .map(|(view_name, parquet_glob)| CatalogIntent {
+            .map(|(view_name, (parquet_glob, hive_partitioning))| CatalogIntent {
                 view_name,
                 parquet_glob,
+                hive_partitioning,
             })
             .collect::<Vec<_>>();
         self.catalog_executor.submit(intents);
@@ -892,6 +1506,7 @@ impl Sentinel {
         info!("Sentinel event loop started");
 
         while self.running {
+            METRICS.record_loop_tick();
             if let Some(rx) = stop_rx.as_ref() {
                 match rx.try_recv() {
                     Ok(()) => {
@@ -966,9 +1581,15 @@ impl Sentinel {
             if let Err(err) = self.drain_pending_cancel_jobs() {
                 warn!("Failed to send cancel responses: {}", err);
             }
+            if let Err(err) = self.drain_pending_plugin_rollbacks() {
+                warn!("Failed to send rollback responses: {}", err);
+            }
             self.drain_pending_dispatches();
             self.drain_pending_concludes();
+            self.drain_receipt_verifications();
             self.drain_pending_dispatch_sweep();
+            self.drain_pending_schedule_tick();
+            self.drain_pending_preemption();
 
             // Periodic cleanup of stale workers
             self.cleanup_stale_workers();
@@ -976,6 +1597,23 @@ impl Sentinel {
             // Periodic sweep of expired dispatch leases
             self.sweep_expired_dispatches();
 
+            // Fire due cron schedules
+            self.tick_schedules();
+
+            // Size the local worker pool to the queue
+            self.drain_pending_pool_demand();
+            self.tick_worker_pool();
+
+            // Compact small Parquet outputs whose policy is met
+            self.tick_compactions();
+
+            // Write buffered heartbeats and log chunks once due
+            self.tick_write_buffer();
+
+            // Apply changed settings and routing tables between passes
+            self.drain_pending_routing_reload();
+            self.tick_config_reload();
+
             // Reconcile running jobs after restart grace period
             if let Err(err) = self.reconcile_missing_workers_after_grace() {
                 warn!("Restart reconciliation failed: {}", err);
@@ -997,6 +1635,7 @@ impl Sentinel {
                         }
                     };
                     let lease_token = lease_token.clone();
+                    let events = self.events.clone();
                     self.sqlite_executor.execute(move |_, queue, _| {
                         let updated = if let Some(token) = lease_token.as_deref() {
                             queue.fail_job_if_token_matches(
@@ -1022,7 +1661,10 @@ impl Sentinel {
                             job_id_db,
                             ProcessingStatus::Failed.as_str()
                         );
-                        METRICS.inc_jobs_failed();
+                        events.publish(SentinelEvent::JobStateChanged {
+                            job_id,
+                            transition: JobTransition::Failed,
+                        });
                         Ok(())
                     })?;
                 }
@@ -1034,6 +1676,9 @@ impl Sentinel {
             }
         }
 
+        if let Err(err) = self.flush_write_buffer_blocking() {
+            warn!("Failed to flush buffered writes on shutdown: {}", err);
+        }
         info!("Sentinel stopped");
         Ok(())
     }
@@ -1117,49 +1762,46 @@ impl Sentinel {
                 Ok(result) => {
                     let pending = self.pending_cancel_jobs.swap_remove(index);
                     let response = match result {
-                        Ok(true) => {
+                        Ok(CancelOutcome::NotStarted) => {
                             info!("Job {} cancelled via control API", pending.job_id);
                             ControlResponse::CancelResult {
                                 success: true,
                                 message: "Job cancelled".to_string(),
                             }
                         }
-                        Ok(false) => {
-                            let mut aborted = false;
-                            let mut abort_error = None;
-                            for (identity, worker) in &self.workers {
-                                if worker.current_job_id == Some(pending.job_id) {
-                                    if let Err(e) =
-                                        self.send_abort_to_worker(identity.clone(), pending.job_id)
-                                    {
-                                        warn!(
-                                            "Failed to send abort for job {}: {}",
-                                            pending.job_id, e
-                                        );
-                                        abort_error = Some(format!("Failed to send abort: {}", e));
-                                    } else {
-                                        aborted = true;
+                        Ok(CancelOutcome::Running) => {
+                            info!("Running job {} cancelled via control API", pending.job_id);
+                            let owner = self
+                                .workers
+                                .iter()
+                                .find(|(_, worker)| worker.owns_job(pending.job_id))
+                                .map(|(identity, _)| identity.clone());
+                            let message = match owner {
+                                Some(identity) => {
+                                    match self.send_abort_to_worker(identity, pending.job_id) {
+                                        Ok(()) => "Job cancelled; abort sent to worker".to_string(),
+                                        Err(e) => {
+                                            // The row is already cancelled; a late CONCLUDE
+                                            // from the worker is discarded as stale.
+                                            warn!(
+                                                "Failed to send abort for job {}: {}",
+                                                pending.job_id, e
+                                            );
+                                            format!("Job cancelled; failed to send abort: {}", e)
+                                        }
                                     }
-                                    break;
-                                }
-                            }
-                            if let Some(error) = abort_error {
-                                ControlResponse::CancelResult {
-                                    success: false,
-                                    message: error,
-                                }
-                            } else if aborted {
-                                ControlResponse::CancelResult {
-                                    success: true,
-                                    message: "Abort signal sent to worker".to_string(),
-                                }
-                            } else {
-                                ControlResponse::CancelResult {
-                                    success: false,
-                                    message: "Job not found or already completed".to_string(),
                                 }
+                                None => "Job cancelled; owning worker not connected".to_string(),
+                            };
+                            ControlResponse::CancelResult {
+                                success: true,
+                                message,
                             }
                         }
+                        Ok(CancelOutcome::Unchanged) => ControlResponse::CancelResult {
+                            success: false,
+                            message: "Job not found or already completed".to_string(),
+                        },
                         Err(err) => ControlResponse::error(
                             "DB_ERROR",
                             format!("Failed to cancel job: {}", err),
@@ -1179,6 +1821,54 @@ impl Sentinel {
         Ok(())
     }
 
+    fn drain_pending_plugin_rollbacks(&mut self) -> Result<()> {
+        let mut index = 0;
+        while index < self.pending_plugin_rollbacks.len() {
+            match self.pending_plugin_rollbacks[index].rx.try_recv() {
+                Ok(result) => {
+                    let pending = self.pending_plugin_rollbacks.swap_remove(index);
+                    let response = match result {
+                        Ok(rollback) => {
+                            info!(
+                                "Plugin '{}' rolled back from {} to {}",
+                                rollback.plugin_name,
+                                rollback.from_version.as_deref().unwrap_or("<none>"),
+                                rollback.to_version
+                            );
+                            let payload = types::ReloadPayload {
+                                plugin_name: rollback.plugin_name.clone(),
+                                version: rollback.to_version.clone(),
+                                reason: Some("rollback".to_string()),
+                                env_hash: None,
+                                lockfile_content: None,
+                            };
+                            let workers_notified = self.broadcast_reload(&payload);
+                            ControlResponse::PluginRolledBack(PluginRollbackInfo {
+                                plugin_name: rollback.plugin_name,
+                                from_version: rollback.from_version,
+                                to_version: rollback.to_version,
+                                workers_notified,
+                            })
+                        }
+                        Err(err) => ControlResponse::error(
+                            "ROLLBACK_FAILED",
+                            format!("Failed to roll back plugin: {}", err),
+                        ),
+                    };
+                    self.send_control_response(pending.identity, response)?;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    index += 1;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    warn!("Plugin rollback response channel disconnected");
+                    self.pending_plugin_rollbacks.swap_remove(index);
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn drain_pending_dispatch_sweep(&mut self) {
         let Some(rx) = &self.pending_dispatch_sweep else {
             return;
@@ -1305,26 +1995,28 @@ impl Sentinel {
                                     job_id,
                                     artifacts.len()
                                 );
-                                METRICS.inc_jobs_completed();
+                                self.publish_conclude(job_id, JobTransition::Completed);
                                 if let Err(err) = self.update_query_catalog_for_artifacts(&artifacts)
                                 {
                                     warn!("Failed to update query catalog: {}", err);
                                 }
+                                self.receipt_verifier.submit(job_id, artifacts);
                             }
                             ConcludeOutcome::Failed { job_id, retried } => {
-                                if retried {
-                                    METRICS.inc_jobs_retried();
+                                let transition = if retried {
+                                    JobTransition::Retried
                                 } else {
-                                    METRICS.inc_jobs_failed();
-                                }
+                                    JobTransition::Failed
+                                };
+                                self.publish_conclude(job_id, transition);
                                 warn!("Job {} failed", job_id);
                             }
                             ConcludeOutcome::Rejected { job_id } => {
-                                METRICS.inc_jobs_rejected();
+                                self.publish_conclude(job_id, JobTransition::Rejected);
                                 warn!("Job {} rejected by worker", job_id);
                             }
                             ConcludeOutcome::Aborted { job_id } => {
-                                METRICS.inc_jobs_aborted();
+                                self.publish_conclude(job_id, JobTransition::Aborted);
                                 warn!("Job {} aborted", job_id);
                             }
                         },
@@ -1344,7 +2036,29 @@ impl Sentinel {
         }
     }
 
+    /// Persist finished receipt verifications into job diagnostics.
+    fn drain_receipt_verifications(&mut self) {
+        while let Some((job_id, verification)) = self.receipt_verifier.try_recv() {
+            if verification.status == VerificationStatus::Suspect {
+                warn!(
+                    "Job {} receipt SUSPECT: {} of {} artifacts disagree with the sink",
+                    job_id,
+                    verification.discrepancies.len(),
+                    verification.checked
+                );
+            }
+            if let Err(err) = self.sqlite_executor.execute(move |_, queue, _| {
+                queue.record_receipt_verification(job_id, &verification)
+            }) {
+                warn!("Failed to record receipt verification: {}", err);
+            }
+        }
+    }
+
     fn sweep_expired_dispatches(&mut self) {
+        if self.read_only {
+            return;
+        }
         let now = current_time();
         if now - self.last_dispatch_lease_sweep < DISPATCH_LEASE_SWEEP_SECS {
             return;
@@ -1354,33 +2068,472 @@ impl Sentinel {
         if self.pending_dispatch_sweep.is_some() {
             return;
         }
-        match self
-            .sqlite_executor
-            .submit(move |_, queue, _| queue.requeue_expired_dispatches(now_ms))
-        {
+        let aging_ms = self.priority_aging_ms;
+        match self.sqlite_executor.submit(move |_, queue, _| {
+            if aging_ms > 0 {
+                let queued_before = now_ms.saturating_sub(aging_ms);
+                let promoted = queue.promote_starved_jobs(queued_before, JobPriority::High)?;
+                if promoted > 0 {
+                    info!("Raised {} starved jobs to HIGH priority", promoted);
+                }
+            }
+            queue.requeue_expired_dispatches(now_ms)
+        }) {
             Ok(rx) => self.pending_dispatch_sweep = Some(rx),
             Err(err) => warn!("Failed to schedule dispatch lease sweep: {}", err),
         }
     }
 
-    fn reconcile_running_jobs_for_worker(
-        &mut self,
-        worker_id: &str,
-        active_job_ids: &[JobId],
-    ) -> Result<()> {
-        let mut active_set = HashSet::new();
-        for job_id in active_job_ids {
-            match job_id.to_i64() {
-                Ok(id) => {
-                    active_set.insert(id);
-                }
-                Err(err) => {
-                    warn!("Heartbeat job id {} not representable: {}", job_id, err);
-                }
-            }
+    fn tick_schedules(&mut self) {
+        if self.read_only {
+            return;
         }
-        let worker_id = worker_id.to_string();
-        self.sqlite_executor.execute(move |_, queue, _| {
+        let now = current_time();
+        if now - self.last_schedule_tick < SCHEDULE_TICK_SECS {
+            return;
+        }
+        self.last_schedule_tick = now;
+        if self.pending_schedule_tick.is_some() {
+            return;
+        }
+        match self
+            .sqlite_executor
+            .submit(move |state_store, queue, _| Self::fire_due_schedules(state_store, queue))
+        {
+            Ok(rx) => self.pending_schedule_tick = Some(rx),
+            Err(err) => warn!("Failed to schedule cron tick: {}", err),
+        }
+    }
+
+    /// Evaluate enabled schedules in local time and persist their state.
+    /// Windows that open release their held jobs here; rescans are returned
+    /// so the main thread can start them.
+    fn fire_due_schedules(
+        state_store: &StateStore,
+        queue: &StateStoreQueueSession,
+    ) -> Result<Vec<ScheduledRescan>> {
+        let now = chrono::Local::now();
+        let now_ms = now.timestamp_millis();
+        let mut rescans = Vec::new();
+        for schedule in queue.list_schedules()? {
+            if !schedule.is_active {
+                continue;
+            }
+            let cron = match schedule.cron.parse::<CronExpr>() {
+                Ok(cron) => cron,
+                Err(err) => {
+                    warn!("Schedule '{}' skipped: {}", schedule.name, err);
+                    continue;
+                }
+            };
+            let tick = schedules::evaluate(&cron, &schedule, &now);
+            if tick.state.next_fire_at != schedule.next_fire_at || tick.fire {
+                queue.record_schedule_state(schedule.id, tick.state)?;
+            }
+            if tick.missed {
+                let action = if tick.fire {
+                    "running it once now"
+                } else {
+                    "skipping to the next one"
+                };
+                info!("Schedule '{}' missed an occurrence; {}", schedule.name, action);
+            }
+            if !tick.fire {
+                continue;
+            }
+            match &schedule.target {
+                ScheduleTarget::RuleWindow { rule_id, .. } => {
+                    let released = queue.release_schedule_holds(rule_id, now_ms)?;
+                    info!(
+                        "Schedule '{}' opened the window for rule {} ({} held jobs released)",
+                        schedule.name, rule_id, released
+                    );
+                }
+                ScheduleTarget::RescanSource { source_id } => {
+                    let source = SourceId::parse(source_id)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|id| state_store.scout().get_source(&id));
+                    match source {
+                        Ok(Some(source)) => rescans.push(ScheduledRescan {
+                            schedule_name: schedule.name.clone(),
+                            workspace_id: source.workspace_id,
+                            path: source.path,
+                        }),
+                        Ok(None) => warn!(
+                            "Schedule '{}': source {} no longer exists",
+                            schedule.name, source_id
+                        ),
+                        Err(err) => warn!(
+                            "Schedule '{}': failed to load source {}: {}",
+                            schedule.name, source_id, err
+                        ),
+                    }
+                }
+            }
+        }
+        Ok(rescans)
+    }
+
+    fn tick_worker_pool(&mut self) {
+        if self.worker_supervisor.is_none() || self.pending_pool_demand.is_some() {
+            return;
+        }
+        let now = current_time();
+        if now - self.last_worker_pool_tick < WORKER_POOL_TICK_SECS {
+            return;
+        }
+        self.last_worker_pool_tick = now;
+        match self.sqlite_executor.submit(move |_, queue, _| {
+            let counts = queue.count_jobs_by_status()?;
+            let demand = [
+                ProcessingStatus::Queued,
+                ProcessingStatus::Dispatching,
+                ProcessingStatus::Running,
+            ]
+            .iter()
+            .map(|status| counts.get(status).copied().unwrap_or(0))
+            .sum::<i64>();
+            Ok(usize::try_from(demand).unwrap_or(0))
+        }) {
+            Ok(rx) => self.pending_pool_demand = Some(rx),
+            Err(err) => warn!("Failed to schedule worker pool tick: {}", err),
+        }
+    }
+
+    /// Run due output compactions on a background thread with its own
+    /// connection; rewriting files must not hold up the DB executor.
+    fn tick_compactions(&mut self) {
+        if self.read_only {
+            return;
+        }
+        let Some(db_path) = self.state_store_path.clone() else {
+            return;
+        };
+        let now = current_time();
+        if now - self.last_compaction_tick < COMPACTION_TICK_SECS {
+            return;
+        }
+        self.last_compaction_tick = now;
+        if self
+            .pending_compaction
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return;
+        }
+        self.pending_compaction = Some(std::thread::spawn(move || {
+            let report = casparian_db::DbConnection::open_sqlite(&db_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|conn| crate::compaction::run_due_compactions(&conn));
+            match report {
+                Ok(report) => {
+                    for error in &report.errors {
+                        warn!("Compaction failed: {}", error);
+                    }
+                }
+                Err(err) => warn!("Failed to run output compaction: {}", err),
+            }
+        }));
+    }
+
+    /// Re-read the settings file and, in the background, the routing tables.
+    fn tick_config_reload(&mut self) {
+        let now = current_time();
+        if now - self.last_config_reload_tick < CONFIG_RELOAD_TICK_SECS {
+            return;
+        }
+        self.last_config_reload_tick = now;
+        if let Some(result) = self.config_watcher.as_mut().and_then(ConfigWatcher::poll) {
+            match result {
+                Ok(settings) => self.apply_settings(settings),
+                Err(err) => warn!("Keeping current settings: {:#}", err),
+            }
+        }
+
+        if self.pending_routing_reload.is_some() {
+            return;
+        }
+        let previous = self.routing_snapshot.clone();
+        match self.sqlite_executor.submit(move |state_store, queue, ctx| {
+            let topic_map = Self::load_topic_configs(state_store.routing())?;
+            let changed = match &previous {
+                Some(previous) => changed_plugins(previous, &topic_map),
+                None => Vec::new(),
+            };
+            let changed = changed
+                .into_iter()
+                .map(|plugin_name| {
+                    let version = queue.active_plugin_version(&plugin_name)?;
+                    Ok((plugin_name, version))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            ctx.topic_map = topic_map.clone();
+            ctx.topic_map_last_refresh = current_time();
+            Ok(RoutingReload { topic_map, changed })
+        }) {
+            Ok(rx) => self.pending_routing_reload = Some(rx),
+            Err(err) => warn!("Failed to schedule routing reload: {}", err),
+        }
+    }
+
+    /// Switch to `settings` as a whole; runs between event loop passes.
+    fn apply_settings(&mut self, settings: ReloadableSettings) {
+        if settings == self.settings {
+            return;
+        }
+        if settings.scheduling_policy != self.settings.scheduling_policy {
+            self.scheduler = DispatchScheduler::new(settings.scheduling_policy);
+        }
+        self.max_workers = settings.max_workers.min(HARD_MAX_WORKERS);
+        if let Some(supervisor) = self.worker_supervisor.as_mut() {
+            supervisor.set_cap(self.max_workers);
+        }
+        self.dispatch_batch_size = settings.dispatch_batch_size;
+        self.priority_aging_ms = aging_ms(settings.priority_aging_secs);
+        self.preempt_low_priority = settings.preempt_low_priority;
+        self.schema_drift_threshold = settings.schema_drift_threshold;
+        self.worker_health = settings.worker_health;
+        info!(
+            "Reloaded Sentinel config: max_workers {}, {} scheduling, batch size {}",
+            self.max_workers, settings.scheduling_policy, settings.dispatch_batch_size
+        );
+        let before = snapshot(&self.settings);
+        self.settings = settings;
+        self.record_config_reload("sentinel", before, snapshot(&self.settings));
+    }
+
+    /// Adopt re-read routing tables; workers get a RELOAD for each deployed
+    /// plugin whose sinks changed.
+    fn drain_pending_routing_reload(&mut self) {
+        let Some(rx) = &self.pending_routing_reload else {
+            return;
+        };
+        let reload = match rx.try_recv() {
+            Ok(Ok(reload)) => Some(reload),
+            Ok(Err(err)) => {
+                warn!("Failed to reload routing tables: {}", err);
+                None
+            }
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => None,
+        };
+        self.pending_routing_reload = None;
+        let Some(reload) = reload else {
+            return;
+        };
+        let before = self.routing_snapshot.replace(reload.topic_map);
+        if reload.changed.is_empty() {
+            return;
+        }
+        let plugins: Vec<&str> = reload
+            .changed
+            .iter()
+            .map(|(plugin_name, _)| plugin_name.as_str())
+            .collect();
+        info!("Reloaded routing for {}", plugins.join(", "));
+        for (plugin_name, version) in &reload.changed {
+            let Some(version) = version else {
+                continue;
+            };
+            let payload = types::ReloadPayload {
+                plugin_name: plugin_name.clone(),
+                version: version.clone(),
+                reason: Some("config".to_string()),
+                env_hash: None,
+                lockfile_content: None,
+            };
+            let workers_notified = self.broadcast_reload(&payload);
+            debug!(
+                "Sent RELOAD for '{}' to {} workers",
+                plugin_name, workers_notified
+            );
+        }
+        let sinks = |map: Option<&HashMap<String, Vec<SinkConfig>>>| {
+            let sinks: BTreeMap<&str, Option<&Vec<SinkConfig>>> = plugins
+                .iter()
+                .map(|name| (*name, map.and_then(|map| map.get(*name))))
+                .collect();
+            snapshot(&sinks)
+        };
+        self.record_config_reload(
+            "routing",
+            sinks(before.as_ref()),
+            sinks(self.routing_snapshot.as_ref()),
+        );
+    }
+
+    fn record_config_reload(
+        &self,
+        entity_id: &str,
+        before: Option<serde_json::Value>,
+        after: Option<serde_json::Value>,
+    ) {
+        if self.read_only {
+            return;
+        }
+        self.state_store.audit().record(
+            entity::CONFIG,
+            entity_id,
+            CONFIG_RELOADED_ACTION,
+            None,
+            before,
+            after,
+        );
+    }
+
+    /// Reconcile the worker pool once its queue count arrives.
+    fn drain_pending_pool_demand(&mut self) {
+        let Some(rx) = &self.pending_pool_demand else {
+            return;
+        };
+        let demand = match rx.try_recv() {
+            Ok(Ok(demand)) => Some(demand),
+            Ok(Err(err)) => {
+                warn!("Failed to count jobs for the worker pool: {}", err);
+                None
+            }
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => None,
+        };
+        self.pending_pool_demand = None;
+        let (Some(demand), Some(supervisor)) = (demand, self.worker_supervisor.as_mut()) else {
+            return;
+        };
+        let workers = &self.workers;
+        let stopped = supervisor.reconcile(demand, current_time(), |worker_id| {
+            match workers.values().find(|w| w.worker_id == worker_id) {
+                None => PooledWorkerState::NotConnected,
+                Some(worker) => match worker.health.state() {
+                    WorkerHealth::Unreachable | WorkerHealth::Evicted => {
+                        PooledWorkerState::Unreachable
+                    }
+                    WorkerHealth::Healthy | WorkerHealth::Degraded => {
+                        if worker.current_job_id.is_none() && worker.status == WorkerStatus::Idle {
+                            PooledWorkerState::Idle
+                        } else {
+                            PooledWorkerState::Busy
+                        }
+                    }
+                },
+            }
+        });
+        for worker_id in stopped {
+            self.drop_pooled_worker(&worker_id);
+        }
+    }
+
+    /// Forget a pooled worker the supervisor stopped; its jobs are failed.
+    fn drop_pooled_worker(&mut self, worker_id: &str) {
+        let Some(id) = self
+            .workers
+            .iter()
+            .find(|(_, worker)| worker.worker_id == worker_id)
+            .map(|(id, _)| id.clone())
+        else {
+            return;
+        };
+        self.stream_assembler.retain(|(peer, _)| peer != &id);
+        if let Some(worker) = self.workers.remove(&id) {
+            self.events.publish(SentinelEvent::WorkerLeft {
+                worker_id: worker_id.to_string(),
+                evicted: false,
+            });
+            for (job_id, lease_token) in worker.dispatched_leases() {
+                warn!(
+                    "Job {} orphaned by stopped pooled worker [{}] - will be failed",
+                    job_id, worker_id
+                );
+                self.orphaned_jobs.push((job_id, lease_token));
+            }
+        }
+    }
+
+    fn drain_pending_schedule_tick(&mut self) {
+        let Some(rx) = &self.pending_schedule_tick else {
+            return;
+        };
+        let rescans = match rx.try_recv() {
+            Ok(Ok(rescans)) => rescans,
+            Ok(Err(err)) => {
+                warn!("Failed to evaluate schedules: {}", err);
+                Vec::new()
+            }
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                warn!("Schedule tick channel disconnected");
+                Vec::new()
+            }
+        };
+        self.pending_schedule_tick = None;
+        for rescan in rescans {
+            let in_progress = self.scan_jobs.values().any(|job| {
+                job.source_path == rescan.path
+                    && matches!(job.state, ScanState::Pending | ScanState::Running)
+            });
+            if in_progress {
+                info!(
+                    "Schedule '{}': scan of {} already running",
+                    rescan.schedule_name, rescan.path
+                );
+                continue;
+            }
+            match self.handle_start_scan(Some(rescan.workspace_id), &rescan.path) {
+                ControlResponse::Error { message, .. } => warn!(
+                    "Schedule '{}' failed to start scan of {}: {}",
+                    rescan.schedule_name, rescan.path, message
+                ),
+                _ => info!(
+                    "Schedule '{}' started scan of {}",
+                    rescan.schedule_name, rescan.path
+                ),
+            }
+        }
+    }
+
+    /// Put a leased job back in the queue if a closed rule window holds its
+    /// file. Returns true if the job was held.
+    fn defer_for_schedule_window(
+        queue: &StateStoreQueueSession,
+        job_id: i64,
+        file_id: i64,
+        lease_token: &str,
+        now_ms: i64,
+    ) -> Result<bool> {
+        let Some(hold) = queue.schedule_hold(file_id, now_ms)? else {
+            return Ok(false);
+        };
+        // Opening the window releases held jobs; the retry only matters if
+        // the Sentinel has not evaluated the schedule yet.
+        let until = hold
+            .next_open_at
+            .filter(|at| *at > now_ms)
+            .unwrap_or(now_ms + SCHEDULE_HOLD_RETRY_MS);
+        debug!(
+            "Job {} held until the '{}' window opens",
+            job_id, hold.schedule_name
+        );
+        queue.defer_job_if_token_matches(job_id, lease_token, until, Some(SCHEDULE_HOLD_REASON))?;
+        Ok(true)
+    }
+
+    fn reconcile_running_jobs_for_worker(
+        &mut self,
+        worker_id: &str,
+        active_job_ids: &[JobId],
+    ) -> Result<()> {
+        let mut active_set = HashSet::new();
+        for job_id in active_job_ids {
+            match job_id.to_i64() {
+                Ok(id) => {
+                    active_set.insert(id);
+                }
+                Err(err) => {
+                    warn!("Heartbeat job id {} not representable: {}", job_id, err);
+                }
+            }
+        }
+        let worker_id = worker_id.to_string();
+        self.sqlite_executor.execute(move |_, queue, _| {
             let db_jobs = queue.list_running_jobs_by_owner(&worker_id)?;
             let db_jobs_set: HashSet<i64> = db_jobs.iter().copied().collect();
             let now = now_millis();
@@ -1439,8 +2592,9 @@ impl Sentinel {
         Ok(())
     }
 
-    /// Remove workers that haven't sent a heartbeat within WORKER_TIMEOUT_SECS
-    /// Also collects orphaned jobs from stale workers to be failed asynchronously
+    /// Re-evaluate each worker's health from the time since it was last heard
+    /// from, emitting an event per transition. Evicted workers are removed and
+    /// their jobs collected to be failed asynchronously.
     fn cleanup_stale_workers(&mut self) {
         let now = current_time();
 
@@ -1450,38 +2604,51 @@ impl Sentinel {
         }
         self.last_cleanup = now;
 
-        let cutoff = now - WORKER_TIMEOUT_SECS;
         let before_count = self.workers.len();
+        let config = self.worker_health;
 
-        // Collect stale workers and their current jobs before removing
-        let stale_workers: Vec<(Vec<u8>, String, Option<JobId>, Option<String>)> = self
-            .workers
-            .iter()
-            .filter(|(_, w)| w.last_seen < cutoff)
-            .map(|(id, w)| {
-                (
-                    id.clone(),
-                    w.worker_id.clone(),
-                    w.current_job_id,
-                    w.current_lease_token.clone(),
-                )
-            })
-            .collect();
+        let mut transitions = Vec::new();
+        for (id, worker) in self.workers.iter_mut() {
+            if let Some(transition) = worker.health.evaluate(&config, now, worker.last_seen) {
+                transitions.push((id.clone(), worker.worker_id.clone(), transition));
+            }
+        }
 
-        // Remove stale workers and queue their jobs for failure
-        for (id, worker_id, job_id, lease_token) in stale_workers {
-            if self.workers.remove(&id).is_some() {
+        for (id, worker_id, transition) in transitions {
+            if transition.flapping {
                 warn!(
-                    "Removing stale worker [{}]: last seen {:.0}s ago",
+                    "Worker [{}] health {} -> {} (flapping, held from dispatch)",
+                    worker_id, transition.from, transition.to
+                );
+            } else {
+                info!("Worker [{}] health {} -> {}", worker_id, transition.from, transition.to);
+            }
+            self.events.publish(SentinelEvent::WorkerHealthChanged {
+                worker_id: worker_id.clone(),
+                from: transition.from,
+                to: transition.to,
+                flapping: transition.flapping,
+            });
+            if transition.to != WorkerHealth::Evicted {
+                continue;
+            }
+
+            // Evicted: remove the worker and queue its jobs for failure
+            self.stream_assembler.retain(|(peer, _)| peer != &id);
+            if let Some(worker) = self.workers.remove(&id) {
+                warn!(
+                    "Evicting worker [{}]: last seen {:.0}s ago",
                     worker_id,
-                    now - cutoff + WORKER_TIMEOUT_SECS
+                    now - worker.last_seen
                 );
-                METRICS.inc_workers_cleaned_up();
+                self.events.publish(SentinelEvent::WorkerLeft {
+                    worker_id: worker_id.clone(),
+                    evicted: true,
+                });
 
-                // Queue job for async failure if worker had an active job
-                if let Some(jid) = job_id {
+                for (jid, lease_token) in worker.dispatched_leases() {
                     warn!(
-                        "Job {} orphaned by stale worker [{}] - will be failed",
+                        "Job {} orphaned by evicted worker [{}] - will be failed",
                         jid, worker_id
                     );
                     self.orphaned_jobs.push((jid, lease_token));
@@ -1492,7 +2659,7 @@ impl Sentinel {
         let removed = before_count - self.workers.len();
         if removed > 0 {
             info!(
-                "Cleanup: removed {} stale workers, {} remaining, {} jobs to fail",
+                "Cleanup: evicted {} workers, {} remaining, {} jobs to fail",
                 removed,
                 self.workers.len(),
                 self.orphaned_jobs.len()
@@ -1502,6 +2669,49 @@ impl Sentinel {
         }
     }
 
+    /// Per-worker health and the per-state counts of the system pulse.
+    fn worker_health_report(&self) -> WorkerHealthReport {
+        let now = current_time();
+        let mut pulse = SystemPulse {
+            workers_evicted: METRICS.snapshot().workers_cleaned_up,
+            ..SystemPulse::default()
+        };
+        let mut workers: Vec<WorkerHealthInfo> = self
+            .workers
+            .values()
+            .map(|worker| {
+                let health = worker.health.state();
+                match health {
+                    WorkerHealth::Healthy => pulse.workers_healthy += 1,
+                    WorkerHealth::Degraded => pulse.workers_degraded += 1,
+                    WorkerHealth::Unreachable => pulse.workers_unreachable += 1,
+                    WorkerHealth::Evicted => {}
+                }
+                if worker.health.is_flapping() {
+                    pulse.workers_flapping += 1;
+                }
+                WorkerHealthInfo {
+                    worker_id: worker.worker_id.clone(),
+                    health,
+                    flapping: worker.health.is_flapping(),
+                    status: worker.status,
+                    seconds_since_seen: (now - worker.last_seen).max(0.0),
+                    seconds_in_state: (now - worker.health.since()).max(0.0),
+                    current_job_id: worker.current_job_id,
+                }
+            })
+            .collect();
+        workers.sort_by(|a, b| a.worker_id.cmp(&b.worker_id));
+        if let Some(supervisor) = &self.worker_supervisor {
+            let pool = supervisor.status();
+            pulse.pool_size = pool.size as u64;
+            pulse.pool_target = pool.target as u64;
+            pulse.pool_max = pool.max as u64;
+            pulse.pool_restarts = pool.restarts;
+        }
+        WorkerHealthReport { pulse, workers }
+    }
+
     // ========================================================================
     // Control API Handling
     // ========================================================================
@@ -1565,10 +2775,21 @@ impl Sentinel {
         identity: Vec<u8>,
         request: ControlRequest,
     ) -> Result<()> {
+        if self.read_only && request.is_mutation() {
+            return self.send_control_response(identity, ControlResponse::read_only_mode());
+        }
         match request {
             ControlRequest::Ping => {
                 self.send_control_response(identity, ControlResponse::Pong)?;
             }
+            ControlRequest::GetPluginMetrics => {
+                let report = METRICS.plugin_metrics();
+                self.send_control_response(identity, ControlResponse::PluginMetrics(report))?;
+            }
+            ControlRequest::GetWorkerHealth => {
+                let report = self.worker_health_report();
+                self.send_control_response(identity, ControlResponse::WorkerHealth(report))?;
+            }
             ControlRequest::StartScan { workspace_id, path } => {
                 let response = self.handle_start_scan(workspace_id, &path);
                 self.send_control_response(identity, response)?;
@@ -1586,18 +2807,56 @@ impl Sentinel {
                 self.send_control_response(identity, response)?;
             }
             ControlRequest::CancelJob { job_id } => {
-                let rx = self
-                    .sqlite_executor
-                    .submit(move |_, queue, _| queue.cancel_job(job_id))?;
+                let rx = self.sqlite_executor.submit(move |_, queue, _| {
+                    if queue.cancel_job(job_id)? {
+                        return Ok(CancelOutcome::NotStarted);
+                    }
+                    if !queue.cancel_running_job(job_id)? {
+                        return Ok(CancelOutcome::Unchanged);
+                    }
+                    if let Ok(job_id_db) = job_id.to_i64() {
+                        if let Err(err) = queue.update_pipeline_run_status_for_job(job_id_db) {
+                            warn!(
+                                "Failed to update pipeline run status for job {}: {}",
+                                job_id, err
+                            );
+                        }
+                    }
+                    Ok(CancelOutcome::Running)
+                })?;
                 self.pending_cancel_jobs.push(PendingCancelJob {
                     identity,
                     job_id,
                     rx,
                 });
             }
+            ControlRequest::RollbackPlugin {
+                plugin_name,
+                target_version,
+                actor,
+            } => {
+                let rx = self.sqlite_executor.submit(move |state_store, _, _| {
+                    let rollback = state_store.routing().rollback_plugin(
+                        &plugin_name,
+                        &target_version,
+                        actor.as_deref(),
+                    )?;
+                    state_store.audit().record(
+                        entity::PLUGIN,
+                        &rollback.plugin_name,
+                        "rolled_back",
+                        actor.as_deref(),
+                        Some(serde_json::json!({ "version": rollback.from_version })),
+                        Some(serde_json::json!({ "version": rollback.to_version })),
+                    );
+                    Ok(rollback)
+                })?;
+                self.pending_plugin_rollbacks.push(PendingPluginRollback { identity, rx });
+            }
             request => {
+                let events = self.events.clone();
                 let rx = self.sqlite_executor.submit(move |state_store, queue, ctx| {
-                    Ok(handle_control_request_db(state_store, queue, ctx, request))
+                    Ok(handle_control_request_db(state_store, queue, ctx, &events, request))
                 })?;
                 self.pending_control_replies.push(PendingControlReply { identity, rx });
             }
@@ -1659,6 +2918,7 @@ impl Sentinel {
     fn handle_list_api_jobs(
         &self,
         status: Option<casparian_protocol::HttpJobStatus>,
+        namespace: Option<&str>,
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> ControlResponse {
@@ -1667,7 +2927,7 @@ impl Sentinel {
         match self
             .state_store
             .api()
-            .list_jobs(status, limit.saturating_add(offset))
+            .list_jobs(status, namespace, limit.saturating_add(offset))
         {
             Ok(jobs) => {
                 let jobs = jobs
@@ -1811,10 +3071,7 @@ impl Sentinel {
             .api()
             .create_approval(approval_id, &operation, summary, expires_in)
         {
-            Ok(()) => ControlResponse::ApprovalResult {
-                success: true,
-                message: "Approval created".to_string(),
-            },
+            Ok(auto_approved_by) => ControlResponse::ApprovalCreated { auto_approved_by },
             Err(e) => ControlResponse::error(
                 "DB_ERROR",
                 format!("Failed to create approval {}: {}", approval_id, e),
@@ -1834,8 +3091,13 @@ impl Sentinel {
     }
 
     /// Handle Approve request
-    fn handle_approve(&self, approval_id: &str) -> ControlResponse {
-        match self.state_store.api().approve(approval_id, None) {
+    fn handle_approve(
+        &self,
+        approval_id: &str,
+        actor: Option<&str>,
+        justification: Option<&str>,
+    ) -> ControlResponse {
+        match self.state_store.api().approve(approval_id, actor, justification) {
             Ok(true) => ControlResponse::ApprovalResult {
                 success: true,
                 message: "Approval accepted".to_string(),
@@ -1852,8 +3114,13 @@ impl Sentinel {
     }
 
     /// Handle Reject request
-    fn handle_reject(&self, approval_id: &str, reason: &str) -> ControlResponse {
-        match self.state_store.api().reject(approval_id, None, Some(reason)) {
+    fn handle_reject(
+        &self,
+        approval_id: &str,
+        reason: &str,
+        actor: Option<&str>,
+    ) -> ControlResponse {
+        match self.state_store.api().reject(approval_id, actor, Some(reason)) {
             Ok(true) => ControlResponse::ApprovalResult {
                 success: true,
                 message: "Approval rejected".to_string(),
@@ -2503,13 +3770,43 @@ impl Sentinel {
             parser_version: job.parser_version,
             pipeline_run_id: job.pipeline_run_id,
             quarantine_rows: job.quarantine_rows,
-        }
+            result: job.result,
+        }
+    }
+
+    fn dead_letter_to_info(entry: DeadLetterJob) -> Result<DeadLetterInfo> {
+        Ok(DeadLetterInfo {
+            id: entry.id,
+            original_job_id: JobId::try_from(entry.original_job_id)?,
+            file_id: entry.file_id,
+            input_file: entry.input_file,
+            plugin_name: entry.plugin_name,
+            error_message: entry.error_message,
+            retry_count: entry.retry_count,
+            moved_at: entry.moved_at,
+            reason: entry.reason,
+            pipeline_run_id: entry.pipeline_run_id,
+            parser_version: entry.parser_version,
+            parser_fingerprint: entry.parser_fingerprint,
+            last_worker: entry.last_worker,
+            last_claim_time: entry.last_claim_time,
+            result_summary: entry.result_summary,
+        })
+    }
+
+    /// Protocol negotiated with a worker (v4 defaults if it has not identified).
+    fn worker_protocol(&self, identity: &[u8]) -> NegotiatedProtocol {
+        self.workers
+            .get(identity)
+            .map(|worker| worker.protocol)
+            .unwrap_or_default()
     }
 
     /// Send abort message to a specific worker
     fn send_abort_to_worker(&self, identity: Vec<u8>, job_id: JobId) -> Result<()> {
         // Create abort message with empty payload
         let msg = Message::new(OpCode::Abort, job_id, vec![])?;
+        let msg = version::downgrade(msg, &self.worker_protocol(&identity))?;
         let (header, body) = msg.pack()?;
 
         // Send ABORT message as multipart [identity, header, body]
@@ -2519,44 +3816,89 @@ impl Sentinel {
         Ok(())
     }
 
+    /// Send RELOAD to every connected worker; returns how many were reached.
+    fn broadcast_reload(&self, payload: &types::ReloadPayload) -> usize {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to encode RELOAD payload: {}", e);
+                return 0;
+            }
+        };
+        let mut notified = 0;
+        for identity in self.workers.keys() {
+            match self.send_reload_to_worker(identity, body.clone()) {
+                Ok(()) => notified += 1,
+                Err(e) => warn!(
+                    "Failed to send RELOAD to worker {}: {}",
+                    String::from_utf8_lossy(identity),
+                    e
+                ),
+            }
+        }
+        notified
+    }
+
+    fn send_reload_to_worker(&self, identity: &[u8], body: Vec<u8>) -> Result<()> {
+        let msg = Message::new(OpCode::Reload, JobId::new(0), body)?;
+        let msg = version::downgrade(msg, &self.worker_protocol(identity))?;
+        let (header, body) = msg.pack()?;
+        let frames = [identity, header.as_ref(), body.as_slice()];
+        self.socket.send_multipart(&frames, 0)?;
+        Ok(())
+    }
+
     /// Receive next message with timeout
     ///
     /// ROUTER receives multipart message: [identity, header, payload]
     fn recv_message(&mut self) -> Result<Option<(Vec<u8>, Message)>> {
-        let multipart = match self.socket.recv_multipart(zmq::DONTWAIT) {
-            Ok(parts) => parts,
-            Err(zmq::Error::EAGAIN) => return Ok(None),
-            Err(e) => return Err(anyhow::anyhow!("ZMQ error: {}", e)),
-        };
+        loop {
+            let multipart = match self.socket.recv_multipart(zmq::DONTWAIT) {
+                Ok(parts) => parts,
+                Err(zmq::Error::EAGAIN) => return Ok(None),
+                Err(e) => return Err(anyhow::anyhow!("ZMQ error: {}", e)),
+            };
 
-        let (identity, header, payload) = match multipart.len() {
-            3 => (
-                multipart[0].clone(),
-                multipart[1].clone(),
-                multipart[2].clone(),
-            ),
-            4 if multipart[1].is_empty() => (
-                multipart[0].clone(),
-                multipart[2].clone(),
-                multipart[3].clone(),
-            ),
-            count => {
-                warn!(
-                    "Expected 3 frames [identity, header, payload], got {}",
-                    count
-                );
-                return Ok(None);
-            }
-        };
+            let (identity, header, payload) = match multipart.len() {
+                3 => (
+                    multipart[0].clone(),
+                    multipart[1].clone(),
+                    multipart[2].clone(),
+                ),
+                4 if multipart[1].is_empty() => (
+                    multipart[0].clone(),
+                    multipart[2].clone(),
+                    multipart[3].clone(),
+                ),
+                count => {
+                    warn!(
+                        "Expected 3 frames [identity, header, payload], got {}",
+                        count
+                    );
+                    return Ok(None);
+                }
+            };
 
-        let msg = Message::unpack(&[header, payload])?;
-        Ok(Some((identity, msg)))
+            let msg = version::upgrade(Message::unpack(&[header, payload])?);
+            let key = (identity.clone(), msg.header.job_id);
+            // Chunked payloads are reassembled before dispatching to handlers;
+            // mid-stream chunks are consumed and we keep draining the socket.
+            if let Some(msg) = self.stream_assembler.push(key, msg)? {
+                return Ok(Some((identity, msg)));
+            }
+        }
     }
 
     /// Handle a received message
     fn handle_message(&mut self, identity: Vec<u8>, msg: Message) -> Result<()> {
         match msg.header.opcode {
             OpCode::Identify => {
+                if self.read_only {
+                    // Workers would conclude, heartbeat and fail jobs.
+                    warn!("Read-only mode: refusing worker registration");
+                    self.send_error(&identity, "Sentinel is in read-only mode")?;
+                    return Ok(());
+                }
                 let payload: IdentifyPayload = serde_json::from_slice(&msg.payload)?;
                 self.register_worker(identity, payload)?;
             }
@@ -2581,6 +3923,11 @@ impl Sentinel {
                 self.handle_heartbeat(identity, payload)?;
             }
 
+            OpCode::LogChunk => {
+                let payload: types::LogChunkPayload = serde_json::from_slice(&msg.payload)?;
+                self.handle_log_chunk(msg.header.job_id, payload)?;
+            }
+
             OpCode::Deploy => {
                 let cmd: types::DeployCommand = serde_json::from_slice(&msg.payload)?;
                 match self.handle_deploy(&identity, cmd) {
@@ -2620,6 +3967,21 @@ impl Sentinel {
         // Vec instead of HashSet - linear scan is faster for small N
         let capabilities: Vec<String> = payload.capabilities;
 
+        let protocol = match NegotiatedProtocol::negotiate(
+            ProtocolVersionRange::SUPPORTED,
+            ProtocolFeatures::SUPPORTED,
+            payload.protocol_versions,
+            payload.protocol_features,
+        ) {
+            Ok(protocol) => protocol,
+            Err(err) => {
+                let message = format!("Worker registration rejected [{}]: {}", worker_id, err);
+                warn!("{}", message);
+                self.send_error(&identity, &message)?;
+                return Ok(());
+            }
+        };
+
         if let Some(existing) = self.workers.get_mut(&identity) {
             existing.last_seen = current_time();
             if existing.worker_id != worker_id {
@@ -2630,9 +3992,10 @@ impl Sentinel {
                 existing.worker_id = worker_id.clone();
             }
             existing.capabilities = capabilities;
+            existing.protocol = protocol;
             self.seen_worker_ids.insert(worker_id.clone());
             info!("Worker re-identified: {}", worker_id);
-            return Ok(());
+            return self.send_identify_ack(&identity, protocol);
         }
 
         if self.workers.len() >= self.max_workers {
@@ -2645,16 +4008,46 @@ impl Sentinel {
             return Ok(());
         }
 
-        info!("Worker joined [{}]", worker_id);
+        info!(
+            "Worker joined [{}] (protocol v{}, features {:#x})",
+            worker_id,
+            protocol.version,
+            protocol.features.bits()
+        );
 
-        let worker = ConnectedWorker::new(worker_id.clone(), capabilities);
-        self.workers.insert(identity, worker);
+        let worker = ConnectedWorker::new(worker_id.clone(), capabilities, protocol);
+        self.workers.insert(identity.clone(), worker);
         self.seen_worker_ids.insert(worker_id.clone());
-        METRICS.inc_workers_registered();
         info!("Worker registered: {}", worker_id);
+        self.events.publish(SentinelEvent::WorkerJoined { worker_id });
+        self.send_identify_ack(&identity, protocol)
+    }
+
+    /// Tell a registered worker which protocol to speak from now on.
+    fn send_identify_ack(&self, identity: &[u8], protocol: NegotiatedProtocol) -> Result<()> {
+        let payload = types::IdentifyAckPayload {
+            protocol_version: protocol.version,
+            protocol_features: protocol.features,
+        };
+        let msg = Message::new(OpCode::Ack, JobId::new(0), serde_json::to_vec(&payload)?)?;
+        let msg = version::downgrade(msg, &protocol)?;
+        let (header, body) = msg.pack()?;
+        let frames = [identity, header.as_ref(), body.as_slice()];
+        self.socket.send_multipart(&frames, 0)?;
         Ok(())
     }
 
+    /// Publish how the concluded job `job_id_db` left the RUNNING state.
+    fn publish_conclude(&self, job_id_db: i64, transition: JobTransition) {
+        match JobId::try_from(job_id_db) {
+            Ok(job_id) => {
+                self.events
+                    .publish(SentinelEvent::JobStateChanged { job_id, transition });
+            }
+            Err(err) => warn!("Concluded job {} has no protocol id: {}", job_id_db, err),
+        }
+    }
+
     fn handle_dispatch_ack(
         &mut self,
         identity: Vec<u8>,
@@ -2676,7 +4069,7 @@ impl Sentinel {
             }
         }
 
-        if worker.current_job_id != Some(job_id) {
+        if !worker.owns_job(job_id) {
             warn!(
                 "Dispatch ACK for job {} does not match worker state {:?}",
                 job_id, worker.current_job_id
@@ -2684,7 +4077,16 @@ impl Sentinel {
             return Ok(());
         }
 
-        if worker.current_lease_token.as_deref() != Some(payload.lease_token.as_str()) {
+        let expected_token = if worker.current_job_id == Some(job_id) {
+            worker.current_lease_token.as_deref()
+        } else {
+            worker
+                .batch_leases
+                .iter()
+                .find(|(batch_job_id, _)| *batch_job_id == job_id)
+                .map(|(_, token)| token.as_str())
+        };
+        if expected_token != Some(payload.lease_token.as_str()) {
             warn!(
                 "Dispatch ACK lease token mismatch for job {}",
                 job_id
@@ -2723,6 +4125,14 @@ impl Sentinel {
                 types::HeartbeatStatus::Idle => WorkerStatus::Idle,
                 types::HeartbeatStatus::Busy | types::HeartbeatStatus::Alive => WorkerStatus::Busy,
             };
+            if payload.load.is_some() {
+                worker.load = payload.load;
+            }
+            if !self.read_only {
+                for write in heartbeat_writes(&worker.worker_id, worker.status, &payload) {
+                    self.write_buffer.push(write)?;
+                }
+            }
             self.seen_worker_ids.insert(worker.worker_id.clone());
             if self.startup_grace_deadline.is_some()
                 && !self.reconciled_workers.contains(&worker.worker_id)
@@ -2744,27 +4154,87 @@ impl Sentinel {
         Ok(())
     }
 
+    /// Handle LOG_CHUNK message: append live log output to `cf_job_logs`.
+    fn handle_log_chunk(&mut self, job_id: JobId, payload: types::LogChunkPayload) -> Result<()> {
+        let job_id: i64 = job_id.to_i64().map_err(|err| {
+            anyhow::anyhow!("Job ID {} is not representable in storage: {}", job_id, err)
+        })?;
+        let offset = i64::try_from(payload.offset)
+            .map_err(|_| anyhow::anyhow!("Log offset {} exceeds i64::MAX", payload.offset))?;
+        let due = self.write_buffer.push(BufferedWrite::LogChunk {
+            job_id,
+            offset,
+            chunk: payload.data,
+            created_at: Utc::now().timestamp_millis(),
+        })?;
+        if due {
+            self.flush_write_buffer()?;
+        }
+        Ok(())
+    }
+
+    fn tick_write_buffer(&mut self) {
+        if !self.write_buffer.flush_due() {
+            return;
+        }
+        if let Err(err) = self.flush_write_buffer() {
+            warn!("Failed to flush buffered writes: {}", err);
+        }
+    }
+
+    /// Queue buffered writes on the SQLite executor. Writes submitted
+    /// afterwards run after them, so a job's log is complete before its
+    /// conclude is recorded.
+    fn flush_write_buffer(&mut self) -> Result<()> {
+        let batch = self.write_buffer.take_batch()?;
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.sqlite_executor
+            .execute(move |_, queue, _| queue.apply_write_batch(batch))
+    }
+
+    fn flush_write_buffer_blocking(&mut self) -> Result<()> {
+        let batch = self.write_buffer.take_batch()?;
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.sqlite_executor
+            .call(move |_, queue, _| queue.apply_write_batch(batch))
+    }
+
     /// Handle CONCLUDE message (job completed/failed)
     ///
     /// For failed jobs:
-    /// - Extracts `is_transient` from receipt metrics to determine retry eligibility
-    /// - Applies exponential backoff for transient errors
+    /// - Classifies the error from the receipt (`error_class` / `error_category` / `is_transient`)
+    /// - Applies the plugin's retry policy (exponential backoff for retryable classes)
     /// - Updates parser health for circuit breaker tracking
-    /// - Moves to dead letter queue after MAX_RETRY_COUNT or for permanent errors
+    /// - Moves to dead letter queue once attempts run out or for non-retryable errors
+    ///
+    /// A batched DISPATCH concludes with one receipt; each file in
+    /// `batch_results` is processed as its own job.
     fn handle_conclude(
         &mut self,
         identity: Vec<u8>,
         job_id: JobId,
-        receipt: JobReceipt,
+        mut receipt: JobReceipt,
     ) -> Result<()> {
+        self.flush_write_buffer()?;
+
         // Mark worker as idle
         if let Some(worker) = self.workers.get_mut(&identity) {
             worker.status = WorkerStatus::Idle;
-            worker.current_job_id = None;
-            worker.current_lease_token = None;
+            worker.clear_dispatch();
             worker.last_seen = current_time();
         }
 
+        for result in std::mem::take(&mut receipt.batch_results) {
+            self.submit_conclude(result.job_id, result.receipt)?;
+        }
+        self.submit_conclude(job_id, receipt)
+    }
+
+    fn submit_conclude(&mut self, job_id: JobId, receipt: JobReceipt) -> Result<()> {
         // Validate job_id fits in i64 (database uses i64 for job IDs)
         let job_id: i64 = job_id.to_i64().map_err(|err| {
             anyhow::anyhow!(
@@ -2777,8 +4247,16 @@ impl Sentinel {
 
         let conclude_start = Instant::now();
         let receipt_for_db = receipt;
+        let drift_threshold = self.schema_drift_threshold;
         let rx = self.sqlite_executor.submit(move |state_store, queue, ctx| {
-            process_conclude_db(state_store, queue, ctx, job_id, receipt_for_db)
+            process_conclude_db(
+                state_store,
+                queue,
+                ctx,
+                job_id,
+                receipt_for_db,
+                drift_threshold,
+            )
         })?;
         self.pending_concludes.push(PendingConclude {
             job_id,
@@ -2800,25 +4278,39 @@ impl Sentinel {
             error!("Traceback:\n{}", trace);
         }
 
-        let lease_token = self
-            .workers
-            .get(&identity)
-            .and_then(|worker| worker.current_lease_token.clone());
+        // Fails every job of the DISPATCH, batched files included
+        let leases = match self.workers.get(&identity) {
+            Some(worker) if worker.current_job_id == Some(job_id) => worker.dispatched_leases(),
+            Some(worker) => vec![(job_id, worker.current_lease_token.clone())],
+            None => vec![(job_id, None)],
+        };
 
         // Mark worker as idle
         if let Some(worker) = self.workers.get_mut(&identity) {
             worker.status = WorkerStatus::Idle;
-            worker.current_job_id = None;
-            worker.current_lease_token = None;
+            worker.clear_dispatch();
             worker.last_seen = current_time();
         }
 
+        for (job_id, lease_token) in leases {
+            self.fail_job_on_error(job_id, lease_token, &err)?;
+        }
+        Ok(())
+    }
+
+    fn fail_job_on_error(
+        &mut self,
+        job_id: JobId,
+        lease_token: Option<String>,
+        err: &types::ErrorPayload,
+    ) -> Result<()> {
         // Validate job_id fits in i64
         let job_id: i64 = job_id.to_i64().map_err(|err| {
             anyhow::anyhow!("Job ID {} is not representable in storage: {}", job_id, err)
         })?;
 
         let error_message = err.message.clone();
+        let error_category = err.category;
         self.sqlite_executor.execute(move |_, queue, _| {
             if let Some(token) = lease_token.as_deref() {
                 let updated = queue.fail_job_if_token_matches(
@@ -2834,6 +4326,15 @@ impl Sentinel {
             } else {
                 queue.fail_job(job_id, JobStatus::Failed.as_str(), &error_message)?;
             }
+            if let Some(category) = error_category {
+                let diagnostics = types::JobDiagnostics {
+                    error_category: Some(category),
+                    ..Default::default()
+                };
+                if let Err(err) = queue.record_job_diagnostics(job_id, &diagnostics) {
+                    warn!("Failed to persist diagnostics for job {}: {}", job_id, err);
+                }
+            }
             if let Err(err) = queue.update_pipeline_run_status_for_job(job_id) {
                 warn!(
                     "Failed to update pipeline run status for job {}: {}",
@@ -2847,31 +4348,78 @@ impl Sentinel {
 
     /// Dispatch loop: assign jobs to ALL idle workers (not just one per iteration)
     fn dispatch_loop(&mut self) -> Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
         if let Some(cooldown_until) = self.dispatch_cooldown_until {
             if Instant::now() < cooldown_until {
                 return Ok(());
             }
         }
 
-        // Collect idle worker identities first (to avoid borrow issues)
-        let idle_identities: Vec<Vec<u8>> = self
+        // Collect idle workers first (to avoid borrow issues)
+        let candidates: Vec<WorkerCandidate> = self
             .workers
             .iter()
-            .filter(|(id, w)| w.status == WorkerStatus::Idle && !self.is_dispatch_pending(id))
-            .map(|(id, _)| id.clone())
+            .filter(|(id, w)| {
+                w.status == WorkerStatus::Idle
+                    && w.health.is_dispatchable()
+                    && !self.is_dispatch_pending(id)
+            })
+            .map(|(id, w)| WorkerCandidate {
+                identity: id.clone(),
+                worker_id: w.worker_id.clone(),
+                capabilities: w.capabilities.clone(),
+                load: w.load,
+            })
             .collect();
 
-        if idle_identities.is_empty() {
+        if candidates.is_empty() {
+            self.maybe_preempt();
             return Ok(());
         }
 
+        let queued = if self.scheduler.needs_job() {
+            self.peek_queued_job()
+        } else {
+            None
+        };
+        let (ordered, throttled) = self.scheduler.schedule(candidates, queued.as_ref());
+        for worker in &throttled {
+            debug!(
+                "Dispatch throttled for worker {} (load: {:?})",
+                worker.worker_id, worker.load
+            );
+        }
+        if ordered.is_empty() {
+            self.maybe_preempt();
+        }
+
+        // Capabilities of every connected worker, busy or not, so a job is only
+        // reported unroutable when nobody could ever take it.
+        let pool: Arc<Vec<Vec<String>>> = Arc::new(
+            self.workers
+                .values()
+                .map(|w| w.capabilities.clone())
+                .collect(),
+        );
+
         let now = now_millis();
-        for identity in idle_identities {
-            let Some(worker) = self.workers.get(&identity) else {
-                continue;
-            };
-            let worker_id = worker.worker_id.clone();
+        for candidate in ordered {
+            let identity = candidate.identity;
+            let worker_id = candidate.worker_id;
             let worker_id_for_task = worker_id.clone();
+            let capabilities = candidate.capabilities;
+            let pool = pool.clone();
+            let protocol = self.worker_protocol(&identity);
+            let batch_size = if protocol.supports(ProtocolFeatures::BATCH_DISPATCH) {
+                self.dispatch_batch_size
+            } else {
+                1
+            };
+            let byte_ranges = protocol.supports(ProtocolFeatures::BYTE_RANGE);
+            let rehash = self.rehash;
+            let events = self.events.clone();
             let rx = self.sqlite_executor.submit(move |state_store, queue, ctx| {
                 Sentinel::prepare_dispatch_plan(
                     state_store,
@@ -2880,6 +4428,12 @@ impl Sentinel {
                     now,
                     DISPATCH_LEASE_TTL_MS,
                     &worker_id_for_task,
+                    &capabilities,
+                    &pool,
+                    batch_size,
+                    byte_ranges,
+                    rehash,
+                    &events,
                 )
             })?;
             self.pending_dispatches.push(PendingDispatch {
@@ -2893,6 +4447,131 @@ impl Sentinel {
         Ok(())
     }
 
+    /// Plugin and file tags of the next job to lease, for job-aware policies.
+    fn peek_queued_job(&self) -> Option<QueuedJob> {
+        let result = self.sqlite_executor.call(|_, queue, _| {
+            let Some(job) = queue.peek_job()? else {
+                return Ok(None);
+            };
+            let tags = queue.load_file_tags(job.file_id)?;
+            Ok(Some(QueuedJob {
+                plugin_name: job.plugin_name,
+                tags,
+            }))
+        });
+        result.unwrap_or_else(|err| {
+            warn!("Failed to peek the queued job for scheduling: {}", err);
+            None
+        })
+    }
+
+    /// With every worker busy, check whether an URGENT job is waiting and, if
+    /// so, requeue the newest LOW job so its worker can be aborted and reused.
+    fn maybe_preempt(&mut self) {
+        if !self.preempt_low_priority || self.pending_preemption.is_some() {
+            return;
+        }
+        let now = current_time();
+        if now - self.last_preemption_check < PREEMPTION_CHECK_SECS {
+            return;
+        }
+        self.last_preemption_check = now;
+
+        let busy: Vec<BusyWorker> = self
+            .workers
+            .iter()
+            .filter(|(_, w)| w.status == WorkerStatus::Busy && w.current_job_id.is_some())
+            .map(|(id, w)| BusyWorker {
+                identity: id.clone(),
+                worker_id: w.worker_id.clone(),
+                priority: w.current_priority,
+                dispatched_at: w.dispatched_at,
+            })
+            .collect();
+        let Some(victim) = preemption_victim(&busy, JobPriority::Urgent) else {
+            return;
+        };
+        let Some((job_id, leases)) = self
+            .workers
+            .get(&victim.identity)
+            .and_then(|w| w.current_job_id.map(|job_id| (job_id, w.dispatched_leases())))
+        else {
+            return;
+        };
+
+        let now_ms = now_millis();
+        let running = victim.priority;
+        let submitted = self.sqlite_executor.submit(move |_, queue, _| {
+            let waiting = queue
+                .peek_job()?
+                .map(|job| JobPriority::from_value(job.priority));
+            if !waiting.is_some_and(|priority| may_preempt(priority, running)) {
+                return Ok(false);
+            }
+            let mut preempted = false;
+            for (job_id, lease_token) in leases {
+                let (Ok(job_id), Some(token)) = (job_id.to_i64(), lease_token) else {
+                    continue;
+                };
+                preempted |= queue.preempt_job_if_token_matches(job_id, &token, now_ms)?;
+            }
+            Ok(preempted)
+        });
+        match submitted {
+            Ok(rx) => {
+                self.pending_preemption = Some(PendingPreemption {
+                    identity: victim.identity.clone(),
+                    worker_id: victim.worker_id.clone(),
+                    job_id,
+                    rx,
+                });
+            }
+            Err(err) => warn!("Failed to schedule preemption check: {}", err),
+        }
+    }
+
+    /// Abort a job that `maybe_preempt` requeued. Its CONCLUDE carries the old
+    /// lease token and is discarded as stale; the worker goes idle on it.
+    fn drain_pending_preemption(&mut self) {
+        let result = match self.pending_preemption.as_ref().map(|p| p.rx.try_recv()) {
+            None | Some(Err(mpsc::TryRecvError::Empty)) => return,
+            Some(Err(mpsc::TryRecvError::Disconnected)) => {
+                warn!("Preemption channel disconnected");
+                self.pending_preemption = None;
+                return;
+            }
+            Some(Ok(result)) => result,
+        };
+        let Some(pending) = self.pending_preemption.take() else {
+            return;
+        };
+        match result {
+            Ok(true) => {
+                warn!(
+                    "Preempting job {} on worker [{}] for an URGENT job; requeued",
+                    pending.job_id, pending.worker_id
+                );
+                self.events.publish(SentinelEvent::JobStateChanged {
+                    job_id: pending.job_id,
+                    transition: JobTransition::Preempted,
+                });
+                if let Some(worker) = self.workers.get_mut(&pending.identity) {
+                    if worker.current_job_id == Some(pending.job_id) {
+                        worker.clear_dispatch();
+                    }
+                }
+                if let Err(err) = self.send_abort_to_worker(pending.identity, pending.job_id) {
+                    warn!(
+                        "Failed to send preemption abort for job {}: {}",
+                        pending.job_id, err
+                    );
+                }
+            }
+            Ok(false) => {}
+            Err(err) => warn!("Preemption check failed: {}", err),
+        }
+    }
+
     fn prepare_dispatch_plan(
         state_store: &StateStore,
         queue: &StateStoreQueueSession,
@@ -2900,6 +4579,12 @@ impl Sentinel {
         now_ms: i64,
         ttl_ms: i64,
         worker_id: &str,
+        worker_capabilities: &[String],
+        pool: &[Vec<String>],
+        batch_size: usize,
+        byte_ranges: bool,
+        rehash: bool,
+        events: &EventBus,
     ) -> Result<Option<DispatchPlan>> {
         let mut leased_jobs = queue.lease_jobs_for_dispatch(1, now_ms, ttl_ms)?;
         let Some(job) = leased_jobs.pop() else {
@@ -2921,6 +4606,9 @@ impl Sentinel {
             queue.defer_job(job.id, now_ms, Some("dispatch_lease_mismatch"))?;
             return Ok(None);
         }
+        if Self::defer_for_schedule_window(queue, job.id, job.file_id, &lease_token, now_ms)? {
+            return Ok(None);
+        }
 
         let fail_dispatch = |message: &str| -> Result<Option<DispatchPlan>> {
             warn!(
@@ -2982,7 +4670,7 @@ impl Sentinel {
             }
         }
 
-        let dispatch_data = match queue.load_dispatch_data(&job.plugin_name, job.file_id) {
+        let dispatch_data = match queue.load_dispatch_data(job.id, &job.plugin_name, job.file_id) {
             Ok(data) => data,
             Err(err) => {
                 let msg = format!(
@@ -3006,9 +4694,60 @@ impl Sentinel {
             platform_arch,
             signature_verified,
             signer_id,
+            system_requirements,
+            force_rerun,
+            byte_range,
+            input_path,
         } = dispatch_data;
 
-        let file_path = resolve_dispatch_path(&scan_root, exec_root.as_deref(), &rel_path);
+        if byte_range.is_some() && !byte_ranges {
+            debug!(
+                "Worker {} cannot read byte ranges; releasing incremental job {}",
+                worker_id, job.id
+            );
+            queue.defer_job_if_token_matches(
+                job.id,
+                &lease_token,
+                now_ms.saturating_add(DISPATCH_REROUTE_MS),
+                Some("waiting_for_byte_range_worker"),
+            )?;
+            return Ok(None);
+        }
+
+        match route_job(&system_requirements, worker_capabilities, pool) {
+            JobRouting::Compatible => {}
+            JobRouting::OtherWorker => {
+                debug!(
+                    "Worker {} lacks requirements {:?} of plugin '{}'; releasing job {}",
+                    worker_id, system_requirements, job.plugin_name, job.id
+                );
+                queue.defer_job_if_token_matches(
+                    job.id,
+                    &lease_token,
+                    now_ms.saturating_add(DISPATCH_REROUTE_MS),
+                    Some("waiting_for_capable_worker"),
+                )?;
+                return Ok(None);
+            }
+            JobRouting::NoWorker(unmet) => {
+                events.publish(SentinelEvent::JobStateChanged {
+                    job_id,
+                    transition: JobTransition::Unroutable,
+                });
+                let msg = format!(
+                    "No connected worker supports requirements [{}] of plugin '{}'",
+                    unmet.join(", "),
+                    job.plugin_name
+                );
+                error!("Job {} unroutable: {}", job.id, msg);
+                return defer_dispatch(&msg);
+            }
+        }
+
+        // Downstream jobs read the upstream artifact, not the scanned file.
+        let downstream = input_path.is_some();
+        let file_path = input_path
+            .unwrap_or_else(|| resolve_dispatch_path(&scan_root, exec_root.as_deref(), &rel_path));
 
         if entrypoint.trim().is_empty() {
             let msg = format!("Missing entrypoint for plugin '{}'", job.plugin_name);
@@ -3071,6 +4810,51 @@ impl Sentinel {
                 return defer_dispatch(&msg);
             }
         };
+        match Self::untargeted_expected_outputs(
+            state_store.routing(),
+            &job.plugin_name,
+            &parser_version,
+            &sinks,
+        ) {
+            Ok(missing) if !missing.is_empty() => {
+                let msg = format!(
+                    "Outputs [{}] of plugin '{}' have no sink; add a topic config for each \
+                     output or a '*' default sink",
+                    missing.join(", "),
+                    job.plugin_name
+                );
+                return fail_dispatch(&msg);
+            }
+            Ok(_) => {}
+            Err(err) => {
+                let msg = format!("Failed to load declared outputs: {}", err);
+                return defer_dispatch(&msg);
+            }
+        }
+
+        if !force_rerun {
+            match Self::prior_materializations(
+                state_store.routing(),
+                queue,
+                job.file_id,
+                &job.plugin_name,
+                &parser_version,
+                &artifact_hash,
+                &sinks,
+            ) {
+                Ok(Some(records)) => {
+                    Self::skip_materialized_job(state_store, queue, &job, &lease_token, &records)?;
+                    return Ok(None);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    warn!(
+                        "Materialization check failed for job {}; dispatching: {}",
+                        job.id, err
+                    );
+                }
+            }
+        }
 
         let sink_config_json = match serde_json::to_string(&sinks) {
             Ok(json) => json,
@@ -3088,6 +4872,75 @@ impl Sentinel {
             );
         }
 
+        // Workers build missing envs from the lockfile instead of failing the job
+        let lockfile_content = match (&runtime_kind, env_hash.as_deref()) {
+            (RuntimeKind::PythonShim, Some(hash)) => match queue.get_lockfile(hash) {
+                Ok(lockfile) => lockfile,
+                Err(err) => {
+                    warn!("Failed to load lockfile for env {}: {}", hash, err);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        let limits = match queue.get_resource_limits(&job.plugin_name) {
+            Ok(limits) => limits.unwrap_or_default(),
+            Err(err) => {
+                warn!(
+                    "Failed to load resource limits for plugin '{}'; dispatching unlimited: {}",
+                    job.plugin_name, err
+                );
+                ResourceLimits::default()
+            }
+        };
+
+        let batch = if batch_size > 1 && byte_range.is_none() {
+            Self::lease_dispatch_batch(
+                state_store,
+                queue,
+                &job,
+                &parser_version,
+                &artifact_hash,
+                &sinks,
+                &sink_config_json,
+                now_ms,
+                ttl_ms,
+                worker_id,
+                batch_size - 1,
+                rehash,
+            )?
+        } else {
+            Vec::new()
+        };
+
+        // Byte-range reads cover part of a file generation and downstream
+        // jobs read an artifact, so their output cannot be resumed under the
+        // generation's key.
+        let checkpoint_key = if byte_range.is_none() && !downstream {
+            match queue.load_file_generation(job.file_id) {
+                Ok(generation) => generation.map(|(file_mtime, file_size)| {
+                    materialization_key(job.file_id, file_mtime, file_size, &artifact_hash, "")
+                }),
+                Err(err) => {
+                    warn!(
+                        "Failed to load file generation for job {}; dispatching without \
+                         checkpoint: {}",
+                        job.id, err
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let cached_source_hash = file_source_hash(queue, &file_path, rehash);
+        let input_lineage = if downstream {
+            artifact_lineage(state_store, &file_path)
+        } else {
+            None
+        };
         let cmd = DispatchCommand {
             plugin_name: job.plugin_name.clone(),
             parser_version: Some(parser_version),
@@ -3104,6 +4957,17 @@ impl Sentinel {
             env_hash,
             source_code,
             artifact_hash,
+            lockfile_content,
+            limits,
+            batch,
+            priority: JobPriority::from_value(job.priority),
+            byte_range,
+            tag: file_tag(queue, job.file_id),
+            checkpoint_key,
+            csv_dialect: file_csv_dialect(queue, &job.plugin_name, job.file_id),
+            input_encoding: file_input_encoding(queue, job.file_id),
+            cached_source_hash,
+            input_lineage,
         };
 
         Ok(Some(DispatchPlan {
@@ -3116,6 +4980,138 @@ impl Sentinel {
         }))
     }
 
+    /// Lease up to `limit` more queued small files of `job`'s plugin to run in
+    /// the same DISPATCH. A file that cannot ride along as-is (missing dispatch
+    /// data, different plugin build) is released for a normal dispatch.
+    #[allow(clippy::too_many_arguments)]
+    fn lease_dispatch_batch(
+        state_store: &StateStore,
+        queue: &StateStoreQueueSession,
+        job: &ProcessingJob,
+        parser_version: &str,
+        artifact_hash: &str,
+        sinks: &[SinkConfig],
+        sink_config_json: &str,
+        now_ms: i64,
+        ttl_ms: i64,
+        worker_id: &str,
+        limit: usize,
+        rehash: bool,
+    ) -> Result<Vec<BatchFile>> {
+        let followers = queue.lease_batch_jobs_for_dispatch(
+            &job.plugin_name,
+            DISPATCH_BATCH_MAX_FILE_BYTES,
+            limit,
+            now_ms,
+            ttl_ms,
+        )?;
+        let mut batch = Vec::with_capacity(followers.len());
+        for follower in followers {
+            let Ok(follower_id) = JobId::try_from(follower.id) else {
+                warn!("Invalid job id from queue ({}); not batching", follower.id);
+                continue;
+            };
+            let lease_token = Uuid::new_v4().to_string();
+            if !queue.set_dispatch_lease(follower.id, &lease_token, worker_id)? {
+                warn!("Failed to set dispatch lease for job {}", follower.id);
+                queue.defer_job(follower.id, now_ms, Some("dispatch_lease_mismatch"))?;
+                continue;
+            }
+            if Self::defer_for_schedule_window(
+                queue,
+                follower.id,
+                follower.file_id,
+                &lease_token,
+                now_ms,
+            )? {
+                continue;
+            }
+            let release = |reason: &str| -> Result<()> {
+                debug!("Job {} not batched: {}", follower.id, reason);
+                queue.defer_job_if_token_matches(follower.id, &lease_token, now_ms, Some(reason))?;
+                Ok(())
+            };
+
+            let data = match queue.load_dispatch_data(
+                follower.id,
+                &job.plugin_name,
+                follower.file_id,
+            ) {
+                Ok(data) => data,
+                Err(_) => {
+                    release("batch_dispatch_data_missing")?;
+                    continue;
+                }
+            };
+            if data.parser_version != parser_version || data.artifact_hash != artifact_hash {
+                release("batch_plugin_mismatch")?;
+                continue;
+            }
+
+            if !data.force_rerun {
+                match Self::prior_materializations(
+                    state_store.routing(),
+                    queue,
+                    follower.file_id,
+                    &job.plugin_name,
+                    parser_version,
+                    artifact_hash,
+                    sinks,
+                ) {
+                    Ok(Some(records)) => {
+                        Self::skip_materialized_job(
+                            state_store,
+                            queue,
+                            &follower,
+                            &lease_token,
+                            &records,
+                        )?;
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        warn!(
+                            "Materialization check failed for job {}; dispatching: {}",
+                            follower.id, err
+                        );
+                    }
+                }
+            }
+
+            if let Err(err) = queue.record_dispatch_metadata(
+                follower.id,
+                parser_version,
+                artifact_hash,
+                sink_config_json,
+            ) {
+                warn!(
+                    "Failed to persist dispatch metadata for job {}: {}",
+                    follower.id, err
+                );
+            }
+
+            let input_lineage = data
+                .input_path
+                .as_deref()
+                .and_then(|path| artifact_lineage(state_store, path));
+            let file_path = data.input_path.unwrap_or_else(|| {
+                resolve_dispatch_path(&data.scan_root, data.exec_root.as_deref(), &data.rel_path)
+            });
+            batch.push(BatchFile {
+                job_id: follower_id,
+                file_id: follower.file_id,
+                cached_source_hash: file_source_hash(queue, &file_path, rehash),
+                file_path,
+                lease_token,
+                tag: file_tag(queue, follower.file_id),
+                csv_dialect: file_csv_dialect(queue, &job.plugin_name, follower.file_id),
+                input_encoding: file_input_encoding(queue, follower.file_id),
+                input_lineage,
+            });
+        }
+        Ok(batch)
+    }
+
     fn schedule_dispatch_backoff(&mut self) {
         let next = if self.dispatch_backoff_ms == 0 {
             DISPATCH_BACKOFF_BASE_MS
@@ -3153,24 +5149,51 @@ impl Sentinel {
         let dispatch_start = Instant::now();
 
         let payload = serde_json::to_vec(&plan.command)?;
-        let msg = Message::new(OpCode::Dispatch, plan.job_id, payload)?;
-        let (header, body) = msg.pack()?;
-
-        // Send DISPATCH message as multipart [identity, header, body]
-        let frames = [identity.as_slice(), header.as_ref(), body.as_slice()];
-        match self.socket.send_multipart(&frames, zmq::DONTWAIT) {
+        let protocol = self.worker_protocol(&identity);
+        let messages = if protocol.supports(ProtocolFeatures::STREAMING) {
+            split_payload(
+                OpCode::Dispatch,
+                plan.job_id,
+                payload,
+                DEFAULT_STREAM_CHUNK_SIZE,
+            )?
+        } else {
+            vec![Message::new(OpCode::Dispatch, plan.job_id, payload)?]
+        };
+        let messages = messages
+            .into_iter()
+            .map(|msg| version::downgrade(msg, &protocol))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // Send DISPATCH (or its chunk stream) as multipart [identity, header, body]
+        let send_result = messages.iter().try_for_each(|msg| {
+            let (header, body) = msg.pack()?;
+            let frames = [identity.as_slice(), header.as_ref(), body.as_slice()];
+            self.socket
+                .send_multipart(&frames, zmq::DONTWAIT)
+                .map_err(anyhow::Error::from)
+        });
+        match send_result {
             Ok(()) => {}
             Err(err) => {
                 warn!("Dispatch send failed for job {}: {}", plan.job_id_db, err);
-                let job_id_db = plan.job_id_db;
+                let job_ids: Vec<i64> = std::iter::once(plan.job_id_db)
+                    .chain(
+                        plan.command
+                            .batch
+                            .iter()
+                            .filter_map(|file| file.job_id.to_i64().ok()),
+                    )
+                    .collect();
                 let _ = self.sqlite_executor.execute(move |_, queue, _| {
-                    queue.defer_job(job_id_db, now_millis(), Some("dispatch_send_failed"))?;
+                    for job_id_db in job_ids {
+                        queue.defer_job(job_id_db, now_millis(), Some("dispatch_send_failed"))?;
+                    }
                     Ok(())
                 });
                 if let Some(worker) = self.workers.get_mut(&identity) {
                     worker.status = WorkerStatus::Idle;
-                    worker.current_job_id = None;
-                    worker.current_lease_token = None;
+                    worker.clear_dispatch();
                 }
                 return Ok(false);
             }
@@ -3191,19 +5214,45 @@ impl Sentinel {
             worker.status = WorkerStatus::Busy;
             worker.current_job_id = Some(plan.job_id);
             worker.current_lease_token = Some(plan.lease_token.clone());
+            worker.current_priority = plan.command.priority;
+            worker.dispatched_at = current_time();
+            worker.batch_leases = plan
+                .command
+                .batch
+                .iter()
+                .map(|file| (file.job_id, file.lease_token.clone()))
+                .collect();
+        }
+
+        let batch = plan.command.batch.iter().map(|file| file.job_id);
+        for job_id in std::iter::once(plan.job_id).chain(batch) {
+            self.events.publish(SentinelEvent::JobStateChanged {
+                job_id,
+                transition: JobTransition::Dispatched,
+            });
         }
-
-        METRICS.inc_jobs_dispatched();
         METRICS.inc_messages_sent();
         let duration_ms = dispatch_start.elapsed().as_millis() as u64;
         span.record("duration_ms", &duration_ms);
         METRICS.record_dispatch_time(dispatch_start);
-        info!("Dispatched job {} ({})", plan.job_id_db, plan.plugin_name);
+        if plan.command.batch.is_empty() {
+            info!("Dispatched job {} ({})", plan.job_id_db, plan.plugin_name);
+        } else {
+            info!(
+                "Dispatched job {} ({}) with {} batched files",
+                plan.job_id_db,
+                plan.plugin_name,
+                plan.command.batch.len()
+            );
+        }
         Ok(true)
     }
 
     /// Handle DEPLOY command - register a new plugin version
     fn handle_deploy(&mut self, identity: &[u8], cmd: types::DeployCommand) -> Result<()> {
+        if self.read_only {
+            anyhow::bail!("Sentinel is in read-only mode; deploys are refused");
+        }
         info!(
             "Deploying plugin {} v{} from {}",
             cmd.plugin_name, cmd.version, cmd.publisher_name
@@ -3306,6 +5355,11 @@ impl Sentinel {
         }
         let outputs_json =
             build_outputs_json(&schema_defs).context("Failed to build outputs_json")?;
+        let namespace = cmd
+            .namespace
+            .clone()
+            .unwrap_or_else(|| defaults::DEFAULT_NAMESPACE.to_string());
+        validate_namespace(&namespace).map_err(|err| anyhow::anyhow!(err))?;
 
         let mut contracts = Vec::new();
         for (output_name, schema_def) in &schema_defs {
@@ -3330,16 +5384,24 @@ impl Sentinel {
             );
         }
 
+        // Verify the publisher signature over the artifact_hash
+        let (signature_verified, signer_id) = self.verify_deploy_signature(&cmd)?;
+
         // 2. Compute source_hash (SHA256, not MD5)
         let source_hash = compute_sha256(&cmd.source_code);
 
         let now = now_millis();
-        let system_requirements_json = cmd
-            .system_requirements
-            .as_ref()
-            .map(|reqs| serde_json::to_string(reqs).unwrap_or_default());
+        let system_requirements_json = match cmd.system_requirements.as_deref() {
+            Some(reqs) if !reqs.is_empty() => {
+                let reqs = normalize_requirements(reqs)
+                    .map_err(|err| anyhow::anyhow!("Invalid system_requirements: {}", err))?;
+                Some(serde_json::to_string(&reqs)?)
+            }
+            _ => None,
+        };
         let request = casparian_state_store::PluginDeployRequest {
             plugin_name: cmd.plugin_name.clone(),
+            namespace: namespace.clone(),
             version: cmd.version.clone(),
             runtime_kind: manifest.runtime_kind,
             entrypoint: manifest.entrypoint.clone(),
@@ -3353,8 +5415,8 @@ impl Sentinel {
             protocol_version: cmd.protocol_version.clone(),
             schema_artifacts_json: cmd.schema_artifacts_json.clone(),
             outputs_json: outputs_json.clone(),
-            signature_verified: false,
-            signer_id: None,
+            signature_verified,
+            signer_id: signer_id.clone(),
             created_at: now,
             deployed_at: now,
             publisher_name: cmd.publisher_name.clone(),
@@ -3369,8 +5431,33 @@ impl Sentinel {
             contracts: contracts.clone(),
         };
 
+        let actor = signer_id.clone().unwrap_or_else(|| cmd.publisher_name.clone());
+        let published = serde_json::json!({
+            "version": cmd.version,
+            "namespace": namespace,
+            "artifact_hash": cmd.artifact_hash,
+            "env_hash": cmd.env_hash,
+            "signature_verified": signature_verified,
+            "signer_id": signer_id,
+        });
         self.sqlite_executor.call(move |state_store, _queue, _ctx| {
+            let plugin_name = request.plugin_name.clone();
+            // Reject sink URI typos now rather than when the first job writes.
+            for tc in state_store.routing().list_topic_configs()? {
+                if tc.plugin_name == plugin_name {
+                    validate_sink_uri_template(&tc.resolved_uri())
+                        .with_context(|| format!("Invalid sink for topic '{}'", tc.topic_name))?;
+                }
+            }
             state_store.routing().deploy_plugin(request)?;
+            state_store.audit().record(
+                entity::PLUGIN,
+                &plugin_name,
+                "published",
+                Some(&actor),
+                None,
+                Some(published),
+            );
             Ok(())
         })?;
 
@@ -3405,22 +5492,79 @@ impl Sentinel {
             Ok(())
         });
 
-        // 6. Send success response
+        // 6. Let connected workers build the environment before the first job
+        if manifest.runtime_kind == RuntimeKind::PythonShim {
+            let payload = types::ReloadPayload {
+                plugin_name: cmd.plugin_name.clone(),
+                version: cmd.version.clone(),
+                reason: Some("deploy".to_string()),
+                env_hash: Some(cmd.env_hash.clone()),
+                lockfile_content: Some(cmd.lockfile_content.clone()),
+            };
+            let workers_notified = self.broadcast_reload(&payload);
+            debug!(
+                "Sent env {} to {} workers",
+                &cmd.env_hash[..12.min(cmd.env_hash.len())],
+                workers_notified
+            );
+        }
+
+        // 7. Send success response
         let response = types::DeployResponse {
             success: true,
             message: format!("Deployed {} v{}", cmd.plugin_name, cmd.version),
             plugin_id: None,
+            signature_verified,
+            signer_id,
         };
         self.send_deploy_response(identity, &response)?;
 
         Ok(())
     }
 
+    /// Check a deploy's signature against the trusted publisher keyring
+    /// (~/.casparian_flow/keys/publishers). Returns (verified, signer_id).
+    ///
+    /// Unsigned deploys are accepted only with `allow_unsigned_deploys`; a
+    /// signature that does not verify is always rejected.
+    fn verify_deploy_signature(
+        &self,
+        cmd: &types::DeployCommand,
+    ) -> Result<(bool, Option<String>)> {
+        let (signer_id, signature) = match (cmd.signer_id.as_deref(), cmd.signature.as_deref()) {
+            (Some(signer_id), Some(signature)) => (signer_id, signature),
+            (None, None) => {
+                if !self.allow_unsigned_deploys {
+                    anyhow::bail!(
+                        "Unsigned artifact rejected: publish with a key from \
+                         'casparian keygen --publisher', or start the Sentinel \
+                         with --allow-unsigned-deploys (dev mode)"
+                    );
+                }
+                warn!(
+                    "Accepting unsigned deploy of {} v{} (unsigned deploys allowed)",
+                    cmd.plugin_name, cmd.version
+                );
+                return Ok((false, None));
+            }
+            _ => anyhow::bail!("Deploy must include both signer_id and signature"),
+        };
+
+        let keyring = Keyring::load(&publishers_dir(&casparian_protocol::keys::keys_dir()))
+            .context("Failed to load publisher keyring")?;
+        keyring
+            .verify(signer_id, &cmd.artifact_hash, signature)
+            .context("Artifact signature verification failed")?;
+        info!("Verified signature by '{}' for {} v{}", signer_id, cmd.plugin_name, cmd.version);
+        Ok((true, Some(signer_id.to_string())))
+    }
+
     /// Send error response to client
     fn send_error(&mut self, identity: &[u8], message: &str) -> Result<()> {
         let payload = types::ErrorPayload {
             message: message.to_string(),
             traceback: None,
+            category: None,
         };
 
         let msg_bytes = serde_json::to_vec(&payload)?;
@@ -3458,6 +5602,7 @@ impl Sentinel {
 struct ControlDbHandler<'a> {
     state_store: &'a StateStore,
     queue: &'a StateStoreQueueSession,
+    events: &'a EventBus,
 }
 
 impl<'a> ControlDbHandler<'a> {
@@ -3486,6 +5631,55 @@ impl<'a> ControlDbHandler<'a> {
         }
     }
 
+    fn handle_get_pipeline_run(&self, run_id: &str) -> ControlResponse {
+        match self.queue.get_pipeline_run(run_id) {
+            Ok(run) => ControlResponse::PipelineRun(run),
+            Err(e) => {
+                ControlResponse::error("DB_ERROR", format!("Failed to get pipeline run: {}", e))
+            }
+        }
+    }
+
+    fn handle_verify_job(&self, job_id: JobId) -> ControlResponse {
+        match self.queue.get_job(job_id) {
+            Ok(Some(job)) if job.status == ProcessingStatus::Completed => {}
+            Ok(Some(job)) => {
+                return ControlResponse::error(
+                    "INVALID_REQUEST",
+                    format!("Job {} is {}, not COMPLETED", job_id, job.status.as_str()),
+                )
+            }
+            Ok(None) => {
+                return ControlResponse::error("NOT_FOUND", format!("Job {} not found", job_id))
+            }
+            Err(e) => {
+                return ControlResponse::error("DB_ERROR", format!("Failed to get job: {}", e))
+            }
+        }
+        let job_id = match job_id.to_i64() {
+            Ok(id) => id,
+            Err(e) => return ControlResponse::error("INVALID_REQUEST", e.to_string()),
+        };
+        let records = match self.state_store.artifacts().list_job_artifacts(job_id) {
+            Ok(records) => records,
+            Err(e) => {
+                return ControlResponse::error(
+                    "DB_ERROR",
+                    format!("Failed to list artifacts: {}", e),
+                )
+            }
+        };
+
+        let verification = verify_artifacts(job_id, &artifacts_from_records(&records));
+        if let Err(e) = self.queue.record_receipt_verification(job_id, &verification) {
+            return ControlResponse::error(
+                "DB_ERROR",
+                format!("Failed to record verification: {}", e),
+            );
+        }
+        ControlResponse::ReceiptVerification(verification)
+    }
+
     fn handle_get_queue_stats(&self) -> ControlResponse {
         match self.queue.count_jobs_by_status() {
             Ok(counts) => {
@@ -3510,6 +5704,286 @@ impl<'a> ControlDbHandler<'a> {
         }
     }
 
+    fn handle_list_dead_letters(
+        &self,
+        plugin_name: Option<&str>,
+        limit: Option<i64>,
+    ) -> ControlResponse {
+        let limit = limit.unwrap_or(100).max(0);
+        match self.queue.get_dead_letter_jobs(plugin_name, limit) {
+            Ok(entries) => {
+                let mut infos = Vec::with_capacity(entries.len());
+                for entry in entries {
+                    match Sentinel::dead_letter_to_info(entry) {
+                        Ok(info) => infos.push(info),
+                        Err(e) => return ControlResponse::error("DB_ERROR", e.to_string()),
+                    }
+                }
+                ControlResponse::DeadLetters(infos)
+            }
+            Err(e) => ControlResponse::error(
+                "DB_ERROR",
+                format!("Failed to list dead letters: {}", e),
+            ),
+        }
+    }
+
+    fn handle_requeue_dead_letter(&self, dead_letter_id: i64) -> ControlResponse {
+        match self.queue.get_dead_letter_job(dead_letter_id) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return ControlResponse::DeadLetterRequeued {
+                    success: false,
+                    message: format!("Dead letter {} not found", dead_letter_id),
+                    new_job_id: None,
+                }
+            }
+            Err(e) => {
+                return ControlResponse::error(
+                    "DB_ERROR",
+                    format!("Failed to get dead letter {}: {}", dead_letter_id, e),
+                )
+            }
+        }
+        let new_job_id = match self
+            .queue
+            .replay_dead_letter(dead_letter_id)
+            .and_then(|id| JobId::try_from(id).map_err(anyhow::Error::from))
+        {
+            Ok(id) => id,
+            Err(e) => {
+                return ControlResponse::error(
+                    "DB_ERROR",
+                    format!("Failed to requeue dead letter {}: {}", dead_letter_id, e),
+                )
+            }
+        };
+        ControlResponse::DeadLetterRequeued {
+            success: true,
+            message: format!("Dead letter {} requeued as job {}", dead_letter_id, new_job_id),
+            new_job_id: Some(new_job_id),
+        }
+    }
+
+    fn handle_purge_dead_letters(
+        &self,
+        dead_letter_id: Option<i64>,
+        moved_before: Option<i64>,
+        plugin_name: Option<&str>,
+    ) -> ControlResponse {
+        let result = match dead_letter_id {
+            Some(id) => self.queue.purge_dead_letter(id).map(u64::from),
+            None => self.queue.purge_dead_letters(moved_before, plugin_name),
+        };
+        match result {
+            Ok(purged) => ControlResponse::DeadLettersPurged { purged },
+            Err(e) => ControlResponse::error(
+                "DB_ERROR",
+                format!("Failed to purge dead letters: {}", e),
+            ),
+        }
+    }
+
+    fn handle_list_subscriptions(&self) -> ControlResponse {
+        match self.state_store.routing().list_subscriptions() {
+            Ok(subscriptions) => ControlResponse::Subscriptions(
+                subscriptions.into_iter().map(subscription_info).collect(),
+            ),
+            Err(e) => ControlResponse::error(
+                "DB_ERROR",
+                format!("Failed to list subscriptions: {}", e),
+            ),
+        }
+    }
+
+    fn handle_subscribe_plugin(&self, plugin_name: &str, topic_name: &str) -> ControlResponse {
+        match self
+            .state_store
+            .routing()
+            .subscribe_plugin(plugin_name, topic_name)
+        {
+            Ok(subscription) => {
+                self.state_store.audit().record(
+                    entity::SUBSCRIPTION,
+                    &subscription.id.to_string(),
+                    "subscribed",
+                    None,
+                    None,
+                    snapshot(&subscription),
+                );
+                ControlResponse::Subscription(subscription_info(subscription))
+            }
+            Err(e) => ControlResponse::error("INVALID_REQUEST", format!("Subscribe failed: {}", e)),
+        }
+    }
+
+    fn handle_set_subscription_enabled(
+        &self,
+        subscription_id: i64,
+        enabled: bool,
+    ) -> ControlResponse {
+        match self
+            .state_store
+            .routing()
+            .set_subscription_active(subscription_id, enabled)
+        {
+            Ok(subscription) => {
+                self.state_store.audit().record(
+                    entity::SUBSCRIPTION,
+                    &subscription.id.to_string(),
+                    if enabled { "enabled" } else { "disabled" },
+                    None,
+                    None,
+                    snapshot(&subscription),
+                );
+                ControlResponse::Subscription(subscription_info(subscription))
+            }
+            Err(e) => ControlResponse::error(
+                "INVALID_REQUEST",
+                format!("Failed to update subscription {}: {}", subscription_id, e),
+            ),
+        }
+    }
+
+    fn handle_list_schedules(&self) -> ControlResponse {
+        match self.state_store.routing().list_schedules() {
+            Ok(schedules) => {
+                ControlResponse::Schedules(schedules.into_iter().map(schedule_info).collect())
+            }
+            Err(e) => {
+                ControlResponse::error("DB_ERROR", format!("Failed to list schedules: {}", e))
+            }
+        }
+    }
+
+    fn handle_create_schedule(
+        &self,
+        name: &str,
+        cron: &str,
+        target: ScheduleTarget,
+        catch_up: ScheduleCatchUp,
+    ) -> ControlResponse {
+        if name.trim().is_empty() {
+            return ControlResponse::error("INVALID_REQUEST", "Schedule name is required");
+        }
+        if let Err(e) = cron.parse::<CronExpr>() {
+            return ControlResponse::error("INVALID_REQUEST", e);
+        }
+        let target = match self.resolve_schedule_target(target) {
+            Ok(target) => target,
+            Err(e) => return ControlResponse::error("INVALID_REQUEST", e.to_string()),
+        };
+        match self
+            .state_store
+            .routing()
+            .create_schedule(name.trim(), cron.trim(), &target, catch_up)
+        {
+            Ok(schedule) => {
+                self.state_store.audit().record(
+                    entity::SCHEDULE,
+                    &schedule.id.to_string(),
+                    "created",
+                    None,
+                    None,
+                    snapshot(&schedule),
+                );
+                ControlResponse::Schedule(schedule_info(schedule))
+            }
+            Err(e) => ControlResponse::error(
+                "INVALID_REQUEST",
+                format!("Failed to create schedule: {:#}", e),
+            ),
+        }
+    }
+
+    /// Check that a schedule's source or rule exists, normalising its ID.
+    fn resolve_schedule_target(&self, target: ScheduleTarget) -> Result<ScheduleTarget> {
+        let scout = self.state_store.scout();
+        match target {
+            ScheduleTarget::RescanSource { source_id } => {
+                let id = SourceId::parse(&source_id)?;
+                if scout.get_source(&id)?.is_none() {
+                    anyhow::bail!("Source {} not found", source_id);
+                }
+                Ok(ScheduleTarget::RescanSource {
+                    source_id: id.to_string(),
+                })
+            }
+            ScheduleTarget::RuleWindow {
+                rule_id,
+                window_minutes,
+            } => {
+                if window_minutes == 0 {
+                    anyhow::bail!("window_minutes must be at least 1");
+                }
+                let id = TaggingRuleId::parse(&rule_id)?;
+                if scout.get_tagging_rule(&id)?.is_none() {
+                    anyhow::bail!("Tagging rule {} not found", rule_id);
+                }
+                Ok(ScheduleTarget::RuleWindow {
+                    rule_id: id.to_string(),
+                    window_minutes,
+                })
+            }
+        }
+    }
+
+    fn handle_set_schedule_enabled(&self, schedule_id: i64, enabled: bool) -> ControlResponse {
+        match self
+            .state_store
+            .routing()
+            .set_schedule_active(schedule_id, enabled)
+        {
+            Ok(schedule) => {
+                self.state_store.audit().record(
+                    entity::SCHEDULE,
+                    &schedule.id.to_string(),
+                    if enabled { "enabled" } else { "disabled" },
+                    None,
+                    None,
+                    snapshot(&schedule),
+                );
+                ControlResponse::Schedule(schedule_info(schedule))
+            }
+            Err(e) => ControlResponse::error(
+                "INVALID_REQUEST",
+                format!("Failed to update schedule {}: {}", schedule_id, e),
+            ),
+        }
+    }
+
+    fn handle_delete_schedule(&self, schedule_id: i64) -> ControlResponse {
+        let routing = self.state_store.routing();
+        let before = match routing.get_schedule(schedule_id) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                return ControlResponse::error(
+                    "DB_ERROR",
+                    format!("Failed to load schedule {}: {}", schedule_id, e),
+                )
+            }
+        };
+        match routing.delete_schedule(schedule_id) {
+            Ok(deleted) => {
+                if deleted {
+                    self.state_store.audit().record(
+                        entity::SCHEDULE,
+                        &schedule_id.to_string(),
+                        "deleted",
+                        None,
+                        before.as_ref().and_then(snapshot),
+                        None,
+                    );
+                }
+                ControlResponse::ScheduleDeleted { deleted }
+            }
+            Err(e) => ControlResponse::error(
+                "DB_ERROR",
+                format!("Failed to delete schedule {}: {}", schedule_id, e),
+            ),
+        }
+    }
+
     fn handle_create_api_job(
         &self,
         job_type: casparian_protocol::HttpJobType,
@@ -3549,6 +6023,7 @@ impl<'a> ControlDbHandler<'a> {
     fn handle_list_api_jobs(
         &self,
         status: Option<casparian_protocol::HttpJobStatus>,
+        namespace: Option<&str>,
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> ControlResponse {
@@ -3557,7 +6032,7 @@ impl<'a> ControlDbHandler<'a> {
         match self
             .state_store
             .api()
-            .list_jobs(status, limit.saturating_add(offset))
+            .list_jobs(status, namespace, limit.saturating_add(offset))
         {
             Ok(jobs) => {
                 let jobs = jobs
@@ -3658,6 +6133,16 @@ impl<'a> ControlDbHandler<'a> {
         }
     }
 
+    fn handle_reserve_api_job_ids(&self, job_id: ApiJobId, count: u64) -> ControlResponse {
+        match self.state_store.api().reserve_queue_jobs(job_id, count) {
+            Ok(job_ids) => ControlResponse::ApiJobIdsReserved { job_ids },
+            Err(e) => ControlResponse::error(
+                "DB_ERROR",
+                format!("Failed to reserve job IDs for API job {}: {}", job_id, e),
+            ),
+        }
+    }
+
     fn handle_list_approvals(
         &self,
         status: Option<ApprovalStatus>,
@@ -3694,10 +6179,13 @@ impl<'a> ControlDbHandler<'a> {
             .api()
             .create_approval(approval_id, &operation, summary, expires_in)
         {
-            Ok(()) => ControlResponse::ApprovalResult {
-                success: true,
-                message: "Approval created".to_string(),
-            },
+            Ok(auto_approved_by) => {
+                self.events.publish(SentinelEvent::ApprovalCreated {
+                    approval_id: approval_id.to_string(),
+                    auto_approved_by: auto_approved_by.clone(),
+                });
+                ControlResponse::ApprovalCreated { auto_approved_by }
+            }
             Err(e) => ControlResponse::error(
                 "DB_ERROR",
                 format!("Failed to create approval {}: {}", approval_id, e),
@@ -3715,12 +6203,21 @@ impl<'a> ControlDbHandler<'a> {
         }
     }
 
-    fn handle_approve(&self, approval_id: &str) -> ControlResponse {
-        match self.state_store.api().approve(approval_id, None) {
-            Ok(true) => ControlResponse::ApprovalResult {
-                success: true,
-                message: "Approval accepted".to_string(),
-            },
+    fn handle_approve(
+        &self,
+        approval_id: &str,
+        actor: Option<&str>,
+        justification: Option<&str>,
+    ) -> ControlResponse {
+        let before = self.state_store.api().get_approval(approval_id).ok().flatten();
+        match self.state_store.api().approve(approval_id, actor, justification) {
+            Ok(true) => {
+                self.publish_decision(approval_id, "approved", actor, before);
+                ControlResponse::ApprovalResult {
+                    success: true,
+                    message: "Approval accepted".to_string(),
+                }
+            }
             Ok(false) => ControlResponse::ApprovalResult {
                 success: false,
                 message: "Approval not found or not pending".to_string(),
@@ -3732,12 +6229,21 @@ impl<'a> ControlDbHandler<'a> {
         }
     }
 
-    fn handle_reject(&self, approval_id: &str, reason: &str) -> ControlResponse {
-        match self.state_store.api().reject(approval_id, None, Some(reason)) {
-            Ok(true) => ControlResponse::ApprovalResult {
-                success: true,
-                message: "Approval rejected".to_string(),
-            },
+    fn handle_reject(
+        &self,
+        approval_id: &str,
+        reason: &str,
+        actor: Option<&str>,
+    ) -> ControlResponse {
+        let before = self.state_store.api().get_approval(approval_id).ok().flatten();
+        match self.state_store.api().reject(approval_id, actor, Some(reason)) {
+            Ok(true) => {
+                self.publish_decision(approval_id, "rejected", actor, before);
+                ControlResponse::ApprovalResult {
+                    success: true,
+                    message: "Approval rejected".to_string(),
+                }
+            }
             Ok(false) => ControlResponse::ApprovalResult {
                 success: false,
                 message: "Approval not found or not pending".to_string(),
@@ -3749,6 +6255,33 @@ impl<'a> ControlDbHandler<'a> {
         }
     }
 
+    fn publish_decision(
+        &self,
+        approval_id: &str,
+        action: &'static str,
+        actor: Option<&str>,
+        before: Option<Approval>,
+    ) {
+        let after = self.state_store.api().get_approval(approval_id).ok().flatten();
+        self.events.publish(SentinelEvent::ApprovalDecided {
+            approval_id: approval_id.to_string(),
+            action,
+            actor: actor.map(str::to_string),
+            before: before.as_ref().and_then(snapshot),
+            after: after.as_ref().and_then(snapshot),
+        });
+    }
+
+    fn handle_list_approval_audit(&self, approval_id: &str) -> ControlResponse {
+        match self.state_store.api().list_approval_audit(approval_id) {
+            Ok(entries) => ControlResponse::ApprovalAudit(entries),
+            Err(e) => ControlResponse::error(
+                "DB_ERROR",
+                format!("Failed to list audit log for {}: {}", approval_id, e),
+            ),
+        }
+    }
+
     fn handle_set_approval_job_id(&self, approval_id: &str, job_id: ApiJobId) -> ControlResponse {
         match self.state_store.api().link_approval_to_job(approval_id, job_id) {
             Ok(()) => ControlResponse::ApprovalResult {
@@ -4007,10 +6540,20 @@ impl<'a> ControlDbHandler<'a> {
         };
 
         match self.state_store.scout().upsert_tagging_rule(&rule) {
-            Ok(()) => ControlResponse::RuleResult {
-                success: true,
-                message: "Rule created".to_string(),
-            },
+            Ok(()) => {
+                self.state_store.audit().record(
+                    entity::ROUTING_RULE,
+                    &rule.id.to_string(),
+                    "created",
+                    None,
+                    None,
+                    snapshot(&rule),
+                );
+                ControlResponse::RuleResult {
+                    success: true,
+                    message: "Rule created".to_string(),
+                }
+            }
             Err(e) => ControlResponse::error("DB_ERROR", format!("Rule create failed: {}", e)),
         }
     }
@@ -4034,13 +6577,24 @@ impl<'a> ControlDbHandler<'a> {
             }
         };
 
+        let before = snapshot(&rule);
         rule.enabled = enabled;
 
         match self.state_store.scout().upsert_tagging_rule(&rule) {
-            Ok(()) => ControlResponse::RuleResult {
-                success: true,
-                message: "Rule updated".to_string(),
-            },
+            Ok(()) => {
+                self.state_store.audit().record(
+                    entity::ROUTING_RULE,
+                    &rule_id.to_string(),
+                    if enabled { "enabled" } else { "disabled" },
+                    None,
+                    before,
+                    snapshot(&rule),
+                );
+                ControlResponse::RuleResult {
+                    success: true,
+                    message: "Rule updated".to_string(),
+                }
+            }
             Err(e) => ControlResponse::error("DB_ERROR", format!("Rule update failed: {}", e)),
         }
     }
@@ -4050,11 +6604,22 @@ impl<'a> ControlDbHandler<'a> {
         rule_id: TaggingRuleId,
         _workspace_id: WorkspaceId,
     ) -> ControlResponse {
+        let before = self.state_store.scout().get_tagging_rule(&rule_id).ok().flatten();
         match self.state_store.scout().delete_tagging_rule(&rule_id) {
-            Ok(true) => ControlResponse::RuleResult {
-                success: true,
-                message: "Rule deleted".to_string(),
-            },
+            Ok(true) => {
+                self.state_store.audit().record(
+                    entity::ROUTING_RULE,
+                    &rule_id.to_string(),
+                    "deleted",
+                    None,
+                    before.as_ref().and_then(snapshot),
+                    None,
+                );
+                ControlResponse::RuleResult {
+                    success: true,
+                    message: "Rule deleted".to_string(),
+                }
+            }
             Ok(false) => ControlResponse::RuleResult {
                 success: false,
                 message: "Rule not found".to_string(),
@@ -4313,13 +6878,18 @@ impl<'a> ControlDbHandler<'a> {
     }
 }
 
-fn handle_control_request_db(
+pub(crate) fn handle_control_request_db(
     state_store: &StateStore,
     queue: &StateStoreQueueSession,
     _context: &mut SqliteContext,
+    events: &EventBus,
     request: ControlRequest,
 ) -> ControlResponse {
-    let handler = ControlDbHandler { state_store, queue };
+    let handler = ControlDbHandler {
+        state_store,
+        queue,
+        events,
+    };
     match request {
         ControlRequest::ListJobs {
             status,
@@ -4327,7 +6897,43 @@ fn handle_control_request_db(
             offset,
         } => handler.handle_list_jobs(status, limit.unwrap_or(100), offset.unwrap_or(0)),
         ControlRequest::GetJob { job_id } => handler.handle_get_job(job_id),
+        ControlRequest::VerifyJob { job_id } => handler.handle_verify_job(job_id),
+        ControlRequest::GetPipelineRun { run_id } => handler.handle_get_pipeline_run(&run_id),
         ControlRequest::GetQueueStats => handler.handle_get_queue_stats(),
+        ControlRequest::ListDeadLetters { plugin_name, limit } => {
+            handler.handle_list_dead_letters(plugin_name.as_deref(), limit)
+        }
+        ControlRequest::RequeueDeadLetter { dead_letter_id } => {
+            handler.handle_requeue_dead_letter(dead_letter_id)
+        }
+        ControlRequest::PurgeDeadLetters {
+            dead_letter_id,
+            moved_before,
+            plugin_name,
+        } => handler.handle_purge_dead_letters(dead_letter_id, moved_before, plugin_name.as_deref()),
+        ControlRequest::ListSubscriptions => handler.handle_list_subscriptions(),
+        ControlRequest::SubscribePlugin {
+            plugin_name,
+            topic_name,
+        } => handler.handle_subscribe_plugin(&plugin_name, &topic_name),
+        ControlRequest::SetSubscriptionEnabled {
+            subscription_id,
+            enabled,
+        } => handler.handle_set_subscription_enabled(subscription_id, enabled),
+        ControlRequest::ListSchedules => handler.handle_list_schedules(),
+        ControlRequest::CreateSchedule {
+            name,
+            cron,
+            target,
+            catch_up,
+        } => handler.handle_create_schedule(&name, &cron, target, catch_up),
+        ControlRequest::SetScheduleEnabled {
+            schedule_id,
+            enabled,
+        } => handler.handle_set_schedule_enabled(schedule_id, enabled),
+        ControlRequest::DeleteSchedule { schedule_id } => {
+            handler.handle_delete_schedule(schedule_id)
+        }
         ControlRequest::CreateApiJob {
             job_type,
             plugin_name,
@@ -4348,9 +6954,10 @@ fn handle_control_request_db(
         ControlRequest::GetApiJob { job_id } => handler.handle_get_api_job(job_id),
         ControlRequest::ListApiJobs {
             status,
+            namespace,
             limit,
             offset,
-        } => handler.handle_list_api_jobs(status, limit, offset),
+        } => handler.handle_list_api_jobs(status, namespace.as_deref(), limit, offset),
         ControlRequest::UpdateApiJobStatus { job_id, status } => {
             handler.handle_update_api_job_status(job_id, status)
         }
@@ -4364,6 +6971,9 @@ fn handle_control_request_db(
             handler.handle_update_api_job_error(job_id, &error)
         }
         ControlRequest::CancelApiJob { job_id } => handler.handle_cancel_api_job(job_id),
+        ControlRequest::ReserveApiJobIds { job_id, count } => {
+            handler.handle_reserve_api_job_ids(job_id, count)
+        }
         ControlRequest::ListApprovals {
             status,
             limit,
@@ -4376,11 +6986,19 @@ fn handle_control_request_db(
             expires_in_seconds,
         } => handler.handle_create_approval(&approval_id, operation, &summary, expires_in_seconds),
         ControlRequest::GetApproval { approval_id } => handler.handle_get_approval(&approval_id),
-        ControlRequest::Approve { approval_id } => handler.handle_approve(&approval_id),
+        ControlRequest::Approve {
+            approval_id,
+            actor,
+            justification,
+        } => handler.handle_approve(&approval_id, actor.as_deref(), justification.as_deref()),
         ControlRequest::Reject {
             approval_id,
             reason,
-        } => handler.handle_reject(&approval_id, &reason),
+            actor,
+        } => handler.handle_reject(&approval_id, &reason, actor.as_deref()),
+        ControlRequest::ListApprovalAudit { approval_id } => {
+            handler.handle_list_approval_audit(&approval_id)
+        }
         ControlRequest::SetApprovalJobId {
             approval_id,
             job_id,
@@ -4505,11 +7123,14 @@ fn handle_control_request_db(
             &tag,
         ),
         ControlRequest::Ping
+        | ControlRequest::GetPluginMetrics
+        | ControlRequest::GetWorkerHealth
         | ControlRequest::StartScan { .. }
         | ControlRequest::GetScan { .. }
         | ControlRequest::ListScans { .. }
         | ControlRequest::CancelScan { .. }
-        | ControlRequest::CancelJob { .. } => ControlResponse::error(
+        | ControlRequest::CancelJob { .. }
+        | ControlRequest::RollbackPlugin { .. } => ControlResponse::error(
             "INVALID_REQUEST",
             "Request must be handled by reactor".to_string(),
         ),
@@ -4522,6 +7143,185 @@ fn process_conclude_db(
     context: &mut SqliteContext,
     job_id: i64,
     receipt: JobReceipt,
+    drift_threshold: usize,
+) -> Result<ConcludeOutcome> {
+    let job_info = JobId::try_from(job_id)
+        .ok()
+        .and_then(|id| queue.get_job(id).ok().flatten());
+    let rows = receipt
+        .metrics
+        .get(metrics::ROWS)
+        .and_then(|rows| u64::try_from(*rows).ok())
+        .unwrap_or(0);
+    let outcome = apply_conclude_db(
+        state_store,
+        queue,
+        context,
+        job_id,
+        job_info.as_ref(),
+        receipt,
+        drift_threshold,
+    )?;
+    if let Some(job) = job_info.as_ref() {
+        record_job_breakdown(queue, job, &outcome, rows);
+    }
+    Ok(outcome)
+}
+
+fn schedule_info(schedule: Schedule) -> ScheduleInfo {
+    ScheduleInfo {
+        id: schedule.id,
+        name: schedule.name,
+        cron: schedule.cron,
+        target: schedule.target,
+        catch_up: schedule.catch_up,
+        enabled: schedule.is_active,
+        next_fire_at: schedule.next_fire_at,
+        last_fired_at: schedule.last_fired_at,
+        window_open_until: schedule.window_open_until,
+        created_at: schedule.created_at,
+    }
+}
+
+fn subscription_info(subscription: TopicSubscription) -> SubscriptionInfo {
+    SubscriptionInfo {
+        id: subscription.id,
+        plugin_name: subscription.plugin_name,
+        topic_name: subscription.topic_name,
+        enabled: subscription.is_active,
+        created_at: subscription.created_at,
+    }
+}
+
+/// Tag sent with a dispatch for `{tag}` in sink URIs: the file's first tag.
+fn file_tag(queue: &StateStoreQueueSession, file_id: i64) -> Option<String> {
+    match queue.load_file_tags(file_id) {
+        Ok(tags) => tags.into_iter().next(),
+        Err(err) => {
+            warn!("Failed to load tags for file {}: {}", file_id, err);
+            None
+        }
+    }
+}
+
+/// Cached hash of the input at `path`, unless `--rehash` asks workers to
+/// hash every file again.
+fn file_source_hash(
+    queue: &StateStoreQueueSession,
+    path: &str,
+    rehash: bool,
+) -> Option<SourceHash> {
+    if rehash {
+        return None;
+    }
+    match queue.cached_source_hash(path) {
+        Ok(cached) => cached,
+        Err(err) => {
+            warn!(
+                "Failed to load cached hash of '{}'; the worker hashes it: {}",
+                path, err
+            );
+            None
+        }
+    }
+}
+
+/// Lineage of the upstream artifact a downstream job reads, for the worker to
+/// extend. None when no job recorded writing it.
+fn artifact_lineage(state_store: &StateStore, input_path: &str) -> Option<LineageChain> {
+    match state_store.lineage().lineage_for_input(input_path) {
+        Ok(lineage) => lineage,
+        Err(err) => {
+            warn!(
+                "Failed to load lineage of '{}'; dispatching without it: {}",
+                input_path, err
+            );
+            None
+        }
+    }
+}
+
+fn file_csv_dialect(
+    queue: &StateStoreQueueSession,
+    plugin_name: &str,
+    file_id: i64,
+) -> Option<CsvDialect> {
+    match queue.resolve_csv_dialect(plugin_name, file_id) {
+        Ok(dialect) => dialect,
+        Err(err) => {
+            warn!(
+                "Failed to resolve CSV dialect for file {}; dispatching parser defaults: {}",
+                file_id, err
+            );
+            None
+        }
+    }
+}
+
+fn file_input_encoding(queue: &StateStoreQueueSession, file_id: i64) -> Option<TextEncoding> {
+    match queue.resolve_input_encoding(file_id) {
+        Ok(encoding) => encoding,
+        Err(err) => {
+            warn!(
+                "Failed to resolve input encoding for file {}; the worker detects it: {}",
+                file_id, err
+            );
+            None
+        }
+    }
+}
+
+/// Feed the per-plugin / per-tag / per-namespace breakdown in `METRICS` for a
+/// concluded job.
+fn record_job_breakdown(
+    queue: &StateStoreQueueSession,
+    job: &Job,
+    outcome: &ConcludeOutcome,
+    rows: u64,
+) {
+    let outcome = match outcome {
+        ConcludeOutcome::Stale { .. } => return,
+        ConcludeOutcome::Completed { .. } => JobOutcome::Completed,
+        ConcludeOutcome::Failed { retried: true, .. } => JobOutcome::Retried,
+        ConcludeOutcome::Failed { retried: false, .. } => JobOutcome::Failed,
+        ConcludeOutcome::Rejected { .. } => JobOutcome::Rejected,
+        ConcludeOutcome::Aborted { .. } => JobOutcome::Aborted,
+    };
+    let tags = queue.load_file_tags(job.file_id).unwrap_or_else(|err| {
+        warn!("Failed to load tags for file {}: {}", job.file_id, err);
+        Vec::new()
+    });
+    let namespace = queue
+        .plugin_namespace(&job.plugin_name)
+        .unwrap_or_else(|err| {
+            warn!(
+                "Failed to load namespace of plugin {}: {}",
+                job.plugin_name, err
+            );
+            defaults::DEFAULT_NAMESPACE.to_string()
+        });
+    // Before conclusion `updated_at` is the claim time.
+    let latency_ms = job
+        .updated_at
+        .and_then(|claimed_at| u64::try_from(now_millis() - claimed_at).ok());
+    METRICS.record_job_outcome(
+        &job.plugin_name,
+        &namespace,
+        &tags,
+        outcome,
+        rows,
+        latency_ms,
+    );
+}
+
+fn apply_conclude_db(
+    state_store: &StateStore,
+    queue: &StateStoreQueueSession,
+    context: &mut SqliteContext,
+    job_id: i64,
+    job_info: Option<&Job>,
+    receipt: JobReceipt,
+    drift_threshold: usize,
 ) -> Result<ConcludeOutcome> {
     if let Some(diagnostics) = receipt.diagnostics.as_ref() {
         if let Some(mismatch) = diagnostics.schema_mismatch.as_ref() {
@@ -4532,6 +7332,27 @@ fn process_conclude_db(
                 );
             }
         }
+        if let Err(err) = queue.record_job_diagnostics(job_id, diagnostics) {
+            warn!("Failed to persist diagnostics for job {}: {}", job_id, err);
+        }
+        if !diagnostics.column_stats.is_empty() {
+            if let Err(err) = state_store
+                .artifacts()
+                .insert_column_stats(job_id, &diagnostics.column_stats)
+            {
+                warn!("Failed to persist column stats for job {}: {}", job_id, err);
+            }
+        }
+        if let (Some(drift), Some(job)) = (diagnostics.schema_drift.as_ref(), job_info) {
+            record_schema_drift_db(
+                state_store,
+                queue,
+                &job.plugin_name,
+                job_id,
+                drift,
+                drift_threshold,
+            );
+        }
     }
 
     if let Err(err) = state_store
@@ -4540,12 +7361,28 @@ fn process_conclude_db(
     {
         warn!("Failed to persist artifacts for job {}: {}", job_id, err);
     }
+    if let Err(err) = state_store.lineage().record_job_lineage(
+        job_id,
+        receipt.source_hash.as_deref(),
+        receipt.decompressed_hash.as_deref(),
+        receipt.source_encoding,
+        receipt.lineage.as_ref(),
+        &receipt.artifacts,
+    ) {
+        warn!("Failed to record lineage for job {}: {}", job_id, err);
+    }
+    if let (Some(hash), Some(file)) = (receipt.source_hash.as_ref(), receipt.source_file.as_ref()) {
+        let source_hash = SourceHash {
+            hash: hash.clone(),
+            fingerprint: file.fingerprint,
+        };
+        if let Err(err) = queue.record_source_hash(&file.path, &source_hash) {
+            warn!("Failed to cache source hash for job {}: {}", job_id, err);
+        }
+    }
 
-    let job_info = JobId::try_from(job_id)
-        .ok()
-        .and_then(|id| queue.get_job(id).ok().flatten());
-    let plugin_name = job_info.as_ref().map(|job| job.plugin_name.as_str());
-    let retry_count = job_info.as_ref().map(|job| job.retry_count).unwrap_or(0);
+    let plugin_name = job_info.map(|job| job.plugin_name.as_str());
+    let retry_count = job_info.map(|job| job.retry_count).unwrap_or(0);
     let lease_token = receipt.lease_token.clone();
 
     match receipt.status {
@@ -4579,6 +7416,14 @@ fn process_conclude_db(
             if !updated {
                 return Ok(ConcludeOutcome::Stale { job_id });
             }
+            if let Err(err) =
+                queue.record_result_summary(job_id, &JobResultSummary::from_receipt(&receipt))
+            {
+                warn!(
+                    "Failed to persist result summary for job {}: {}",
+                    job_id, err
+                );
+            }
 
             if let Err(err) = Sentinel::record_materializations_for_job_with_context(
                 state_store,
@@ -4593,6 +7438,19 @@ fn process_conclude_db(
                 );
             }
 
+            match queue.enqueue_downstream_jobs(job_id, &receipt.artifacts) {
+                Ok(downstream) if !downstream.is_empty() => {
+                    info!(
+                        "Job {} published to subscribers: enqueued job(s) {:?}",
+                        job_id, downstream
+                    );
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    warn!("Failed to enqueue downstream jobs for job {}: {}", job_id, err);
+                }
+            }
+
             if let Some(parser) = plugin_name {
                 if let Err(err) = record_success_db(queue, parser) {
                     warn!("Failed to record parser success for {}: {}", parser, err);
@@ -4616,11 +7474,11 @@ fn process_conclude_db(
                 .error_message
                 .clone()
                 .unwrap_or_else(|| "Unknown error".to_string());
-            let is_transient = receipt
-                .metrics
-                .get("is_transient")
-                .map(|v| *v == 1)
-                .unwrap_or(true);
+            let error_class = receipt_error_class(&receipt);
+            let error_category = receipt
+                .diagnostics
+                .as_ref()
+                .and_then(|diagnostics| diagnostics.error_category);
 
             if let Some(parser) = plugin_name {
                 if let Err(err) = record_failure_db(queue, parser, &error) {
@@ -4646,7 +7504,15 @@ fn process_conclude_db(
                 queue.fail_job(job_id, JobStatus::Failed.as_str(), &error)?;
             }
 
-            let retried = handle_job_failure_db(queue, job_id, &error, is_transient, retry_count)?;
+            let retried = handle_job_failure_db(
+                queue,
+                job_id,
+                plugin_name,
+                &error,
+                error_class,
+                error_category,
+                retry_count,
+            )?;
             if let Err(err) = queue.update_pipeline_run_status_for_job(job_id) {
                 warn!(
                     "Failed to update pipeline run status for job {}: {}",
@@ -4714,6 +7580,41 @@ fn process_conclude_db(
     }
 }
 
+/// Add a job's drift to its dataset and raise a `SchemaDrift` event when the
+/// job brought new drift and the dataset is now past `threshold`.
+fn record_schema_drift_db(
+    state_store: &StateStore,
+    queue: &StateStoreQueueSession,
+    plugin_name: &str,
+    job_id: i64,
+    drift: &SchemaDrift,
+    threshold: usize,
+) {
+    let dataset = match queue.record_schema_drift(plugin_name, job_id, drift) {
+        Ok(dataset) => dataset,
+        Err(err) => {
+            warn!("Failed to record schema drift for job {}: {}", job_id, err);
+            return;
+        }
+    };
+    if dataset.new_entries == 0 || dataset.drifted_columns() <= threshold {
+        return;
+    }
+    warn!(
+        "Schema drift for '{}' past threshold {}: unmapped {:?}, retyped {:?} (job {})",
+        plugin_name, threshold, dataset.unmapped_columns, dataset.type_drifts, job_id
+    );
+    let event = EventType::SchemaDrift {
+        plugin_name: plugin_name.to_string(),
+        job_id,
+        unmapped_columns: dataset.unmapped_columns,
+        type_drifts: dataset.type_drifts,
+    };
+    if let Err(err) = state_store.api().insert_event(SYSTEM_EVENT_JOB_ID, &event) {
+        warn!("Failed to record schema drift event: {}", err);
+    }
+}
+
 fn record_success_db(queue: &StateStoreQueueSession, parser_name: &str) -> Result<()> {
     queue.record_parser_success(parser_name)?;
     debug!(
@@ -4753,46 +7654,139 @@ fn check_circuit_breaker_db(queue: &StateStoreQueueSession, parser_name: &str) -
     Ok(true)
 }
 
+/// Failure class of a receipt: the worker's `error_class` diagnostic, falling
+/// back to the `is_transient` metric sent by older workers.
+fn receipt_error_class(receipt: &JobReceipt) -> &str {
+    if let Some(class) = receipt
+        .diagnostics
+        .as_ref()
+        .and_then(|diagnostics| diagnostics.error_class.as_deref())
+    {
+        return class;
+    }
+    let is_transient = receipt
+        .metrics
+        .get(metrics::IS_TRANSIENT)
+        .map(|v| *v == 1)
+        .unwrap_or(true);
+    if is_transient {
+        retry::ERROR_CLASS_TRANSIENT
+    } else {
+        retry::ERROR_CLASS_PERMANENT
+    }
+}
+
+/// Apply the plugin's retry policy to a failed job: schedule a retry with
+/// backoff, or move the job to the dead letter queue.
 fn handle_job_failure_db(
     queue: &StateStoreQueueSession,
     job_id: i64,
+    plugin_name: Option<&str>,
     error: &str,
-    is_transient: bool,
+    error_class: &str,
+    error_category: Option<ErrorCategory>,
     retry_count: i32,
 ) -> Result<bool> {
-    if is_transient && retry_count < MAX_RETRY_COUNT {
-        let backoff_secs = BACKOFF_BASE_SECS.pow(retry_count as u32 + 1);
+    let policy = match plugin_name.map(|name| queue.get_retry_policy(name)).transpose() {
+        Ok(policy) => policy.flatten().unwrap_or_default(),
+        Err(err) => {
+            warn!(
+                "Failed to load retry policy for job {}; using default: {}",
+                job_id, err
+            );
+            RetryPolicy::default()
+        }
+    };
+    let retries = u32::try_from(retry_count).unwrap_or(0);
+    let now = now_millis();
+    let mut attempt = RetryAttempt {
+        attempt: retries + 1,
+        error_class: error_class.to_string(),
+        error_message: error.to_string(),
+        failed_at: now,
+        retry_at: None,
+    };
+
+    if policy.should_retry(error_class, error_category, retries) {
+        let jitter_sample = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| f64::from(d.subsec_nanos()) / 1_000_000_000.0)
+            .unwrap_or(0.0);
+        let backoff_ms = policy.backoff_ms(retries + 1, jitter_sample);
         info!(
             job_id,
             retry_count = retry_count + 1,
-            backoff_secs,
+            error_class,
+            error_category = error_category.map(|category| category.as_str()),
+            backoff_ms,
             "Scheduling retry with exponential backoff"
         );
 
-        let now = now_millis();
-        let scheduled_at = now + (backoff_secs as i64 * 1_000);
+        let scheduled_at = now.saturating_add(i64::try_from(backoff_ms).unwrap_or(i64::MAX));
+        attempt.retry_at = Some(scheduled_at);
+        if let Err(err) = queue.record_retry_attempt(job_id, &attempt) {
+            warn!("Failed to record retry attempt for job {}: {}", job_id, err);
+        }
         queue.schedule_retry(job_id, retry_count + 1, error, scheduled_at)?;
         return Ok(true);
     }
 
-    let reason = if is_transient {
+    if let Err(err) = queue.record_retry_attempt(job_id, &attempt) {
+        warn!("Failed to record retry attempt for job {}: {}", job_id, err);
+    }
+    let reason = if policy.is_retryable(error_class, error_category) {
         DeadLetterReason::MaxRetriesExceeded
     } else {
         DeadLetterReason::PermanentError
     };
 
     warn!(
-        "Job {} moving to dead letter queue: {} (retries: {}/{})",
+        "Job {} moving to dead letter queue: {} ({} error, attempts: {}/{})",
         job_id,
         reason.as_str(),
-        retry_count,
-        MAX_RETRY_COUNT
+        error_class,
+        attempt.attempt,
+        policy.max_attempts
     );
 
     queue.move_to_dead_letter(job_id, error, reason)?;
     Ok(false)
 }
 
+/// Clear a local address for binding. A live socket or an invalid address
+/// fails; a stale socket that cannot be removed is only logged, and the
+/// bind reports it if it is really in the way.
+fn prepare_local_bind(addr: &str) -> Result<()> {
+    match endpoint::prepare_ipc_bind(addr) {
+        Err(err @ endpoint::IpcError::Io { .. }) => {
+            warn!("Failed to clean up {}: {}", addr, err);
+            Ok(())
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Bind `socket` to `addr`. A `pipe://` address binds an ephemeral loopback
+/// port instead and returns the relay serving the pipe in front of it.
+fn bind_local(socket: &Socket, addr: &str) -> Result<Option<casparian_pipe::PipeRelay>> {
+    if !casparian_pipe::is_pipe_addr(addr) {
+        socket.bind(addr)?;
+        return Ok(None);
+    }
+    socket.bind(casparian_pipe::LOOPBACK_ENDPOINT)?;
+    let target = socket
+        .get_last_endpoint()?
+        .map_err(|_| anyhow::anyhow!("Loopback endpoint is not UTF-8"))?;
+    casparian_pipe::PipeRelay::start(addr, &target).map(Some)
+}
+
+/// Queue wait before a job is raised to HIGH, in milliseconds.
+fn aging_ms(priority_aging_secs: u64) -> i64 {
+    i64::try_from(priority_aging_secs)
+        .unwrap_or(i64::MAX)
+        .saturating_mul(1000)
+}
+
 /// Get current Unix timestamp
 fn current_time() -> f64 {
     SystemTime::now()
@@ -4814,6 +7808,53 @@ fn sqlite_path_from_url(url: &str) -> Option<std::path::PathBuf> {
     url.strip_prefix("sqlite:").map(std::path::PathBuf::from)
 }
 
+/// Journal of the Sentinel's write buffer, next to the state store database.
+fn write_journal_dir(db_path: &std::path::Path) -> std::path::PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".journal");
+    db_path.with_file_name(name)
+}
+
+/// Buffered writes for one worker heartbeat: the heartbeat itself and a
+/// sample of each load figure the worker reported.
+fn heartbeat_writes(
+    worker_id: &str,
+    status: WorkerStatus,
+    payload: &types::HeartbeatPayload,
+) -> Vec<BufferedWrite> {
+    let now_ms = Utc::now().timestamp_millis();
+    let load = payload.load.as_ref();
+    let mut writes = vec![BufferedWrite::Heartbeat(WorkerHeartbeat {
+        worker_id: worker_id.to_string(),
+        status: status.as_str().to_string(),
+        active_jobs: payload.active_job_count as i64,
+        cpu_percent: load.and_then(|load| load.cpu_percent).map(f64::from),
+        memory_used_bytes: load
+            .and_then(|load| load.memory_used_bytes)
+            .map(|bytes| bytes as i64),
+        seen_at: now_ms,
+    })];
+    let Some(load) = load else {
+        return writes;
+    };
+    let mut samples = vec![("worker.queue_depth", load.queue_depth as f64)];
+    if let Some(cpu_percent) = load.cpu_percent {
+        samples.push(("worker.cpu_percent", f64::from(cpu_percent)));
+    }
+    if let Some(memory_used_bytes) = load.memory_used_bytes {
+        samples.push(("worker.memory_used_bytes", memory_used_bytes as f64));
+    }
+    writes.extend(samples.into_iter().map(|(name, value)| {
+        BufferedWrite::MetricSample(MetricSample {
+            name: name.to_string(),
+            series: worker_id.to_string(),
+            value,
+            recorded_at: now_ms,
+        })
+    }));
+    writes
+}
+
 fn millis_to_rfc3339(millis: i64) -> String {
     DateTime::<Utc>::from_timestamp_millis(millis)
         .unwrap_or_else(|| Utc::now())
@@ -4839,7 +7880,10 @@ fn escape_sql_literal(value: &str) -> String {
 mod tests {
     use super::*;
     use casparian_db::{DbConnection, DbValue};
-    use casparian_state_store::{ExpectedOutputs, OutputSpec, PluginDeployRequest, RoutingStore};
+    use casparian_state_store::{
+        ExpectedOutputs, OutputSpec, PluginDeployRequest, PluginRollback, RoutingStore,
+        TopicSubscription,
+    };
 
     struct TestRoutingStore {
         conn: DbConnection,
@@ -4861,6 +7905,61 @@ mod tests {
         fn deploy_plugin(&self, _request: PluginDeployRequest) -> Result<()> {
             Ok(())
         }
+
+        fn rollback_plugin(
+            &self,
+            _plugin_name: &str,
+            _target_version: &str,
+            _actor: Option<&str>,
+        ) -> Result<PluginRollback> {
+            anyhow::bail!("rollback not supported by test routing store")
+        }
+
+        fn list_subscriptions(&self) -> Result<Vec<TopicSubscription>> {
+            Ok(Vec::new())
+        }
+
+        fn subscribe_plugin(
+            &self,
+            _plugin_name: &str,
+            _topic_name: &str,
+        ) -> Result<TopicSubscription> {
+            anyhow::bail!("subscriptions not supported by test routing store")
+        }
+
+        fn set_subscription_active(
+            &self,
+            _subscription_id: i64,
+            _active: bool,
+        ) -> Result<TopicSubscription> {
+            anyhow::bail!("subscriptions not supported by test routing store")
+        }
+
+        fn list_schedules(&self) -> Result<Vec<Schedule>> {
+            Ok(Vec::new())
+        }
+
+        fn get_schedule(&self, _schedule_id: i64) -> Result<Option<Schedule>> {
+            Ok(None)
+        }
+
+        fn create_schedule(
+            &self,
+            _name: &str,
+            _cron: &str,
+            _target: &ScheduleTarget,
+            _catch_up: ScheduleCatchUp,
+        ) -> Result<Schedule> {
+            anyhow::bail!("schedules not supported by test routing store")
+        }
+
+        fn set_schedule_active(&self, _schedule_id: i64, _active: bool) -> Result<Schedule> {
+            anyhow::bail!("schedules not supported by test routing store")
+        }
+
+        fn delete_schedule(&self, _schedule_id: i64) -> Result<bool> {
+            Ok(false)
+        }
     }
 
     #[test]
@@ -4937,6 +8036,7 @@ mod tests {
                 data_type: casparian_protocol::DataType::Int64,
                 nullable: false,
                 format: None,
+                constraints: Vec::new(),
             }],
         };
         let locked = locked_schema_from_definition(output_name, &schema_def).unwrap();
@@ -4947,7 +8047,11 @@ mod tests {
 
     #[test]
     fn test_connected_worker() {
-        let worker = ConnectedWorker::new("test-worker".to_string(), vec!["*".to_string()]);
+        let worker = ConnectedWorker::new(
+            "test-worker".to_string(),
+            vec!["*".to_string()],
+            NegotiatedProtocol::default(),
+        );
 
         assert_eq!(worker.status, WorkerStatus::Idle);
         assert_eq!(worker.capabilities, vec!["*".to_string()]);
@@ -4956,7 +8060,8 @@ mod tests {
 
     #[test]
     fn test_worker_status() {
-        let mut worker = ConnectedWorker::new("test".to_string(), vec![]);
+        let mut worker =
+            ConnectedWorker::new("test".to_string(), vec![], NegotiatedProtocol::default());
 
         assert_eq!(worker.status, WorkerStatus::Idle);
 
@@ -4983,6 +8088,7 @@ mod tests {
                     topic: "alpha".to_string(),
                     uri: "parquet:///tmp/alpha".to_string(),
                     mode: SinkMode::Append,
+                    schema_evolution: SchemaEvolution::default(),
                     quarantine_config: None,
                     schema: None,
                 },
@@ -4990,6 +8096,7 @@ mod tests {
                     topic: "beta".to_string(),
                     uri: "parquet:///tmp/beta".to_string(),
                     mode: SinkMode::Append,
+                    schema_evolution: SchemaEvolution::default(),
                     quarantine_config: None,
                     schema: None,
                 },
@@ -5015,6 +8122,7 @@ mod tests {
             topic: defaults::DEFAULT_SINK_TOPIC.to_string(),
             uri: "parquet:///tmp/default".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -5048,6 +8156,7 @@ mod tests {
             topic: "*".to_string(),
             uri: "parquet:///tmp/default".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }];
@@ -5068,6 +8177,37 @@ mod tests {
         assert!(beta.schema.is_some());
     }
 
+    #[test]
+    fn test_untargeted_expected_outputs() {
+        let (conn, _schema_storage) = setup_contract_db();
+        let outputs_json = r#"{"events": {"columns": []}, "annotations": {"columns": []}}"#;
+        insert_test_plugin(&conn, "parser_c", "1.0.0", outputs_json);
+        let routing = TestRoutingStore { conn: conn.clone() };
+        let sink = |topic: &str, uri: &str| SinkConfig {
+            topic: topic.to_string(),
+            uri: uri.to_string(),
+            mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
+            quarantine_config: None,
+            schema: None,
+        };
+
+        let mut sinks = vec![
+            sink("events", "duckdb:///tmp/events.duckdb"),
+            sink("metrics", "parquet:///tmp/metrics"),
+        ];
+        let missing =
+            Sentinel::untargeted_expected_outputs(&routing, "parser_c", "1.0.0", &sinks).unwrap();
+        assert_eq!(missing, vec!["annotations".to_string()]);
+
+        sinks.push(sink("annotations", "parquet:///tmp/annotations"));
+        assert!(
+            Sentinel::untargeted_expected_outputs(&routing, "parser_c", "1.0.0", &sinks)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_load_topic_configs_without_schema() {
         let temp = tempfile::NamedTempFile::new().unwrap();
@@ -5078,6 +8218,7 @@ mod tests {
             CREATE TABLE cf_topic_config (
                 id INTEGER PRIMARY KEY,
                 plugin_name TEXT NOT NULL,
+                namespace TEXT NOT NULL DEFAULT 'default',
                 topic_name TEXT NOT NULL,
                 uri TEXT NOT NULL,
                 mode TEXT DEFAULT 'append',
@@ -5098,14 +8239,15 @@ mod tests {
 
         conn.execute(
             r#"
-            INSERT INTO cf_topic_config (id, plugin_name, topic_name, uri, mode)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO cf_topic_config (id, plugin_name, namespace, topic_name, uri, mode)
+            VALUES (?, ?, ?, ?, ?, ?)
             "#,
             &[
                 DbValue::from(1),
                 DbValue::from("test_plugin"),
+                DbValue::from("risk"),
                 DbValue::from("output"),
-                DbValue::from("parquet:///tmp/out"),
+                DbValue::from("parquet:///tmp/{namespace}/{date:yyyy-MM}/out"),
                 DbValue::from("append"),
             ],
         )
@@ -5115,7 +8257,20 @@ mod tests {
         let configs = Sentinel::load_topic_configs(store.routing()).unwrap();
         let sinks = configs.get("test_plugin").unwrap();
         assert_eq!(sinks.len(), 1);
+        // Write-time placeholders are left for the worker.
+        assert_eq!(sinks[0].uri, "parquet:///tmp/risk/{date:yyyy-MM}/out");
         assert!(sinks[0].schema.is_none());
+
+        conn.execute(
+            r#"
+            INSERT INTO cf_topic_config (id, plugin_name, topic_name, uri, mode)
+            VALUES (2, 'typo_plugin', 'output', 'parquet:///tmp/{plugn}/out', 'append')
+            "#,
+            &[],
+        )
+        .unwrap();
+        let err = Sentinel::load_topic_configs(store.routing()).unwrap_err();
+        assert!(format!("{:#}", err).contains("{plugn}"), "{:#}", err);
     }
 
     #[test]
@@ -5128,6 +8283,7 @@ mod tests {
             CREATE TABLE cf_topic_config (
                 id INTEGER PRIMARY KEY,
                 plugin_name TEXT NOT NULL,
+                namespace TEXT NOT NULL DEFAULT 'default',
                 topic_name TEXT NOT NULL,
                 uri TEXT NOT NULL,
                 mode TEXT DEFAULT 'append',
@@ -5157,6 +8313,34 @@ mod tests {
         assert!(err.to_string().contains("Duplicate sink config"));
     }
 
+    #[test]
+    fn test_receipt_error_class() {
+        let mut receipt = JobReceipt {
+            status: JobStatus::Failed,
+            metrics: HashMap::new(),
+            artifacts: Vec::new(),
+            error_message: Some("boom".to_string()),
+            diagnostics: None,
+            source_hash: None,
+            source_file: None,
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: None,
+            lease_token: None,
+            batch_results: Vec::new(),
+        };
+        assert_eq!(receipt_error_class(&receipt), retry::ERROR_CLASS_TRANSIENT);
+
+        receipt.metrics.insert(metrics::IS_TRANSIENT.to_string(), 0);
+        assert_eq!(receipt_error_class(&receipt), retry::ERROR_CLASS_PERMANENT);
+
+        receipt.diagnostics = Some(types::JobDiagnostics {
+            error_class: Some(retry::ERROR_CLASS_BRIDGE.to_string()),
+            ..Default::default()
+        });
+        assert_eq!(receipt_error_class(&receipt), retry::ERROR_CLASS_BRIDGE);
+    }
+
     #[test]
     fn test_compute_sha256() {
         // Test with known input
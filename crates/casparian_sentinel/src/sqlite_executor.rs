@@ -7,6 +7,7 @@ use std::sync::Arc;
 use std::thread;
 use tracing::error;
 
+#[derive(Clone)]
 pub struct SqliteExecutor {
     tx: SyncSender<SqliteCmd>,
 }
@@ -0,0 +1,287 @@
+//! CurveZMQ encryption and worker authentication for the worker socket.
+//!
+//! With a `SecurityConfig` enabled, the Sentinel's ROUTER socket acts as a
+//! CURVE server: all traffic is encrypted, and a ZAP handler thread
+//! (RFC 27) admits only workers whose public key is on the allow-list.
+//! Without one, the socket stays plaintext as before.
+
+use anyhow::{bail, Context as _, Result};
+use casparian_protocol::keys::{self, CurveKeyPair, KeyError};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tracing::{debug, info, warn};
+use zmq::{Context as ZmqContext, Socket};
+
+/// Well-known inproc endpoint libzmq sends authentication requests to.
+const ZAP_ENDPOINT: &str = "inproc://zeromq.zap.01";
+const ZAP_VERSION: &[u8] = b"1.0";
+const ZAP_DOMAIN: &str = "casparian";
+/// Poll interval for the handler's stop flag.
+const ZAP_POLL_MS: i32 = 100;
+
+/// Transport security for the worker socket.
+#[derive(Debug, Clone, Default)]
+pub struct SecurityConfig {
+    /// Sentinel key pair. None leaves the worker socket in plaintext.
+    pub server_keypair: Option<CurveKeyPair>,
+    /// Z85 public keys of workers allowed to connect
+    pub authorized_worker_keys: Vec<String>,
+}
+
+impl SecurityConfig {
+    /// Plaintext transport.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Load `sentinel.key` from `dir` and the worker allow-list from
+    /// `authorized_keys` (default: `dir/authorized_workers`).
+    pub fn from_keys_dir(dir: &Path, authorized_keys: Option<&Path>) -> Result<Self> {
+        let server_keypair =
+            keys::load_keypair(dir, keys::SENTINEL_KEY_NAME).map_err(|err| match err {
+                KeyError::NotFound { .. } => anyhow::anyhow!(
+                    "{} (run `casparian keygen --name {}`)",
+                    err,
+                    keys::SENTINEL_KEY_NAME
+                ),
+                other => other.into(),
+            })?;
+        let default_path = dir.join(keys::AUTHORIZED_WORKERS_FILE);
+        let authorized_path = authorized_keys.unwrap_or(&default_path);
+        let authorized_worker_keys = keys::load_authorized_keys(authorized_path)?;
+        Ok(Self {
+            server_keypair: Some(server_keypair),
+            authorized_worker_keys,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.server_keypair.is_some()
+    }
+}
+
+/// Background ZAP handler; stopped on drop.
+pub(crate) struct ZapHandler {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for ZapHandler {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Turn `socket` into a CURVE server. Must run before `socket` binds.
+///
+/// Returns the ZAP handler that enforces the allow-list, or None when
+/// security is disabled.
+pub(crate) fn secure_router(
+    context: &ZmqContext,
+    socket: &Socket,
+    config: &SecurityConfig,
+) -> Result<Option<ZapHandler>> {
+    let Some(keypair) = &config.server_keypair else {
+        return Ok(None);
+    };
+    if config.authorized_worker_keys.is_empty() {
+        bail!(
+            "CURVE security is enabled but no worker public keys are authorized; \
+             add worker keys to {}",
+            keys::keys_dir()
+                .join(keys::AUTHORIZED_WORKERS_FILE)
+                .display()
+        );
+    }
+
+    // The handler must be bound before any handshake reaches the socket.
+    let zap = context
+        .socket(zmq::REP)
+        .context("Failed to create ZAP handler socket")?;
+    zap.bind(ZAP_ENDPOINT)
+        .context("Failed to bind ZAP handler")?;
+    zap.set_rcvtimeo(ZAP_POLL_MS)
+        .context("Failed to set ZAP receive timeout")?;
+
+    let allowed: HashSet<String> = config.authorized_worker_keys.iter().cloned().collect();
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let handle = std::thread::Builder::new()
+        .name("sentinel-zap".to_string())
+        .spawn(move || serve_zap(zap, allowed, thread_stop))
+        .context("Failed to spawn ZAP handler thread")?;
+    let handler = ZapHandler {
+        stop,
+        handle: Some(handle),
+    };
+
+    let secret = zmq::z85_decode(&keypair.secret_key)
+        .map_err(|err| anyhow::anyhow!("Invalid sentinel secret key: {}", err))?;
+    socket
+        .set_zap_domain(ZAP_DOMAIN)
+        .context("Failed to set ZAP domain")?;
+    socket
+        .set_curve_server(true)
+        .context("Failed to enable CURVE server")?;
+    socket
+        .set_curve_secretkey(&secret)
+        .context("Failed to set CURVE secret key")?;
+    info!(
+        "CURVE encryption enabled ({} authorized workers)",
+        config.authorized_worker_keys.len()
+    );
+    Ok(Some(handler))
+}
+
+fn serve_zap(socket: Socket, allowed: HashSet<String>, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::Relaxed) {
+        let request = match socket.recv_multipart(0) {
+            Ok(frames) => frames,
+            Err(zmq::Error::EAGAIN) => continue,
+            Err(zmq::Error::ETERM) => break,
+            Err(err) => {
+                warn!("ZAP handler receive failed: {}", err);
+                continue;
+            }
+        };
+        let reply = zap_reply(&request, &allowed);
+        if let Err(err) = socket.send_multipart(reply, 0) {
+            warn!("ZAP handler reply failed: {}", err);
+        }
+    }
+}
+
+/// Build the ZAP reply frames for one request.
+fn zap_reply(request: &[Vec<u8>], allowed: &HashSet<String>) -> Vec<Vec<u8>> {
+    let request_id = request.get(1).cloned().unwrap_or_default();
+    let (status, text, user_id) = authenticate(request, allowed);
+    vec![
+        ZAP_VERSION.to_vec(),
+        request_id,
+        status.as_bytes().to_vec(),
+        text.as_bytes().to_vec(),
+        user_id.into_bytes(),
+        Vec::new(),
+    ]
+}
+
+/// Request frames: version, request id, domain, address, identity,
+/// mechanism, credentials...
+fn authenticate(
+    request: &[Vec<u8>],
+    allowed: &HashSet<String>,
+) -> (&'static str, &'static str, String) {
+    if request.len() < 7 || request[0] != ZAP_VERSION {
+        return ("500", "Malformed ZAP request", String::new());
+    }
+    if request[5] != b"CURVE" {
+        return ("400", "CURVE required", String::new());
+    }
+    let Ok(client_key) = zmq::z85_encode(&request[6]) else {
+        return ("400", "Invalid client key", String::new());
+    };
+    if allowed.contains(&client_key) {
+        debug!("ZAP: accepted worker key {}", client_key);
+        ("200", "OK", client_key)
+    } else {
+        warn!(
+            "ZAP: rejected unauthorized worker key {} from {}",
+            client_key,
+            String::from_utf8_lossy(&request[3])
+        );
+        ("400", "Unauthorized worker key", String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const WORKER_PUBLIC: &str = "Yne@$w-vo<fVvi]a<NY6T1ed:M$fCG*[IaLV{hID";
+    const SENTINEL_PUBLIC: &str = "rq:rM>}U?@Lns47E1%kR.o@n%FcmmsL/@{H8]yf7";
+    const SENTINEL_SECRET: &str = "JTKVSB%%)wK0E.X)V>+}o?pNmC{O&4W4b!Ni{Lh6";
+
+    fn request(mechanism: &str, key: &str) -> Vec<Vec<u8>> {
+        vec![
+            ZAP_VERSION.to_vec(),
+            b"7".to_vec(),
+            ZAP_DOMAIN.as_bytes().to_vec(),
+            b"127.0.0.1".to_vec(),
+            Vec::new(),
+            mechanism.as_bytes().to_vec(),
+            zmq::z85_decode(key).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_zap_accepts_only_authorized_curve_keys() {
+        let allowed: HashSet<String> = [WORKER_PUBLIC.to_string()].into_iter().collect();
+
+        let reply = zap_reply(&request("CURVE", WORKER_PUBLIC), &allowed);
+        assert_eq!(reply[1], b"7");
+        assert_eq!(reply[2], b"200");
+        assert_eq!(reply[4], WORKER_PUBLIC.as_bytes());
+
+        let reply = zap_reply(&request("CURVE", SENTINEL_PUBLIC), &allowed);
+        assert_eq!(reply[2], b"400");
+
+        let reply = zap_reply(&request("NULL", WORKER_PUBLIC), &allowed);
+        assert_eq!(reply[2], b"400");
+
+        let reply = zap_reply(&[b"1.0".to_vec()], &allowed);
+        assert_eq!(reply[2], b"500");
+    }
+
+    #[test]
+    fn test_from_keys_dir() {
+        let dir = tempdir().unwrap();
+        let err = SecurityConfig::from_keys_dir(dir.path(), None).unwrap_err();
+        assert!(err.to_string().contains("casparian keygen"), "{}", err);
+
+        keys::save_keypair(
+            dir.path(),
+            keys::SENTINEL_KEY_NAME,
+            &CurveKeyPair {
+                public_key: SENTINEL_PUBLIC.to_string(),
+                secret_key: SENTINEL_SECRET.to_string(),
+            },
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(keys::AUTHORIZED_WORKERS_FILE),
+            format!("{}\n", WORKER_PUBLIC),
+        )
+        .unwrap();
+
+        let config = SecurityConfig::from_keys_dir(dir.path(), None).unwrap();
+        assert!(config.is_enabled());
+        assert_eq!(config.authorized_worker_keys, vec![WORKER_PUBLIC]);
+        assert!(!SecurityConfig::disabled().is_enabled());
+    }
+
+    #[test]
+    fn test_secure_router_requires_authorized_keys() {
+        let config = SecurityConfig {
+            server_keypair: Some(CurveKeyPair {
+                public_key: SENTINEL_PUBLIC.to_string(),
+                secret_key: SENTINEL_SECRET.to_string(),
+            }),
+            authorized_worker_keys: Vec::new(),
+        };
+        let context = ZmqContext::new();
+        let socket = context.socket(zmq::ROUTER).unwrap();
+        assert!(secure_router(&context, &socket, &config).is_err());
+        assert!(
+            secure_router(&context, &socket, &SecurityConfig::disabled())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Heartbeat-driven worker health.
+//!
+//! Each connected worker carries a [`HealthTracker`]. On every cleanup pass
+//! the Sentinel re-evaluates it from the time since the worker was last heard
+//! from: HEALTHY -> DEGRADED -> UNREACHABLE -> EVICTED as the silence grows,
+//! and straight back to HEALTHY once the worker speaks again. EVICTED is
+//! terminal; the Sentinel drops the worker and fails its jobs.
+//!
+//! A worker that recovers `flap_threshold` times within `flap_window_secs` is
+//! flapping: it stays connected but receives no new dispatches until its
+//! recoveries age out of the window.
+
+use anyhow::Result;
+use casparian_protocol::WorkerHealth;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Silence thresholds (seconds since last message) and flap detection.
+///
+/// Workers heartbeat every 30s, so by default one missed heartbeat degrades a
+/// worker and it is evicted after 60s of silence.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorkerHealthConfig {
+    pub degraded_after_secs: f64,
+    pub unreachable_after_secs: f64,
+    pub evict_after_secs: f64,
+    pub flap_window_secs: f64,
+    /// Recoveries within the window that mark a worker as flapping
+    pub flap_threshold: usize,
+}
+
+impl Default for WorkerHealthConfig {
+    fn default() -> Self {
+        Self {
+            degraded_after_secs: 35.0,
+            unreachable_after_secs: 45.0,
+            evict_after_secs: 60.0,
+            flap_window_secs: 300.0,
+            flap_threshold: 3,
+        }
+    }
+}
+
+impl WorkerHealthConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(self.degraded_after_secs > 0.0
+            && self.degraded_after_secs < self.unreachable_after_secs
+            && self.unreachable_after_secs < self.evict_after_secs)
+        {
+            anyhow::bail!(
+                "Worker health thresholds must satisfy 0 < degraded ({}) < unreachable ({}) < evict ({})",
+                self.degraded_after_secs,
+                self.unreachable_after_secs,
+                self.evict_after_secs
+            );
+        }
+        if self.flap_threshold == 0 {
+            anyhow::bail!("Worker flap threshold must be at least 1");
+        }
+        Ok(())
+    }
+
+    /// Health implied by `silence_secs` without a message.
+    pub fn classify(&self, silence_secs: f64) -> WorkerHealth {
+        if silence_secs >= self.evict_after_secs {
+            WorkerHealth::Evicted
+        } else if silence_secs >= self.unreachable_after_secs {
+            WorkerHealth::Unreachable
+        } else if silence_secs >= self.degraded_after_secs {
+            WorkerHealth::Degraded
+        } else {
+            WorkerHealth::Healthy
+        }
+    }
+}
+
+/// A change of health state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthTransition {
+    pub from: WorkerHealth,
+    pub to: WorkerHealth,
+    /// Flapping status after the transition
+    pub flapping: bool,
+}
+
+/// Health state of one worker.
+#[derive(Debug, Clone)]
+pub struct HealthTracker {
+    state: WorkerHealth,
+    since: f64,
+    /// Times the worker recovered to HEALTHY, within the flap window
+    recoveries: VecDeque<f64>,
+    flapping: bool,
+}
+
+impl HealthTracker {
+    pub fn new(now: f64) -> Self {
+        Self {
+            state: WorkerHealth::Healthy,
+            since: now,
+            recoveries: VecDeque::new(),
+            flapping: false,
+        }
+    }
+
+    pub fn state(&self) -> WorkerHealth {
+        self.state
+    }
+
+    /// When the current state was entered.
+    pub fn since(&self) -> f64 {
+        self.since
+    }
+
+    pub fn is_flapping(&self) -> bool {
+        self.flapping
+    }
+
+    /// Whether the worker may receive new dispatches.
+    pub fn is_dispatchable(&self) -> bool {
+        self.state == WorkerHealth::Healthy && !self.flapping
+    }
+
+    /// Re-evaluate from the time the worker was last heard from. Returns the
+    /// transition if the state changed.
+    pub fn evaluate(
+        &mut self,
+        config: &WorkerHealthConfig,
+        now: f64,
+        last_seen: f64,
+    ) -> Option<HealthTransition> {
+        if self.state == WorkerHealth::Evicted {
+            return None;
+        }
+
+        while self
+            .recoveries
+            .front()
+            .is_some_and(|&at| now - at > config.flap_window_secs)
+        {
+            self.recoveries.pop_front();
+        }
+
+        let next = config.classify(now - last_seen);
+        if next == WorkerHealth::Healthy && self.state != WorkerHealth::Healthy {
+            self.recoveries.push_back(now);
+        }
+        self.flapping = self.recoveries.len() >= config.flap_threshold;
+
+        if next == self.state {
+            return None;
+        }
+        let from = self.state;
+        self.state = next;
+        self.since = now;
+        Some(HealthTransition {
+            from,
+            to: next,
+            flapping: self.flapping,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_silence_walks_through_states() {
+        let config = WorkerHealthConfig::default();
+        let mut tracker = HealthTracker::new(0.0);
+
+        assert_eq!(tracker.evaluate(&config, 30.0, 0.0), None);
+        let transition = tracker.evaluate(&config, 40.0, 0.0).unwrap();
+        assert_eq!(
+            (transition.from, transition.to),
+            (WorkerHealth::Healthy, WorkerHealth::Degraded)
+        );
+        assert!(!tracker.is_dispatchable());
+        let transition = tracker.evaluate(&config, 50.0, 0.0).unwrap();
+        assert_eq!(transition.to, WorkerHealth::Unreachable);
+        let transition = tracker.evaluate(&config, 60.0, 0.0).unwrap();
+        assert_eq!(transition.to, WorkerHealth::Evicted);
+
+        // Evicted is terminal, even if a late message arrives.
+        assert_eq!(tracker.evaluate(&config, 61.0, 61.0), None);
+        assert_eq!(tracker.state(), WorkerHealth::Evicted);
+    }
+
+    #[test]
+    fn test_recovery_returns_to_healthy() {
+        let config = WorkerHealthConfig::default();
+        let mut tracker = HealthTracker::new(0.0);
+        tracker.evaluate(&config, 50.0, 0.0).unwrap();
+        assert_eq!(tracker.state(), WorkerHealth::Unreachable);
+
+        let transition = tracker.evaluate(&config, 55.0, 52.0).unwrap();
+        assert_eq!(
+            transition,
+            HealthTransition {
+                from: WorkerHealth::Unreachable,
+                to: WorkerHealth::Healthy,
+                flapping: false,
+            }
+        );
+        assert_eq!(tracker.since(), 55.0);
+        assert!(tracker.is_dispatchable());
+    }
+
+    #[test]
+    fn test_repeated_recoveries_flag_flapping_until_window_passes() {
+        let config = WorkerHealthConfig::default();
+        let mut tracker = HealthTracker::new(0.0);
+
+        let mut now = 0.0;
+        let mut last = None;
+        for _ in 0..3 {
+            tracker.evaluate(&config, now + 40.0, now).unwrap();
+            last = tracker.evaluate(&config, now + 45.0, now + 44.0);
+            now += 50.0;
+        }
+        assert!(last.unwrap().flapping);
+        assert_eq!(tracker.state(), WorkerHealth::Healthy);
+        assert!(!tracker.is_dispatchable());
+
+        // Stable for a full window: the old recoveries age out.
+        assert_eq!(tracker.evaluate(&config, 500.0, 495.0), None);
+        assert!(!tracker.is_flapping());
+        assert!(tracker.is_dispatchable());
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(WorkerHealthConfig::default().validate().is_ok());
+        let config = WorkerHealthConfig {
+            unreachable_after_secs: 90.0,
+            ..WorkerHealthConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,432 @@
+//! Local worker pool for desktop installs.
+//!
+//! With `SentinelConfig::worker_pool` set, the Sentinel starts worker
+//! processes itself instead of waiting for them to connect: enough to cover
+//! queued and running jobs, between `min_workers` and `max_workers`. Workers
+//! above the minimum retire after `idle_retire_secs` without a job.
+//!
+//! A pooled worker that exits on its own, does not IDENTIFY within
+//! [`IDENTIFY_TIMEOUT_SECS`] or turns UNREACHABLE is stopped and replaced.
+//! Replacements back off exponentially while crashes repeat; a worker that
+//! stayed up for [`STABLE_AFTER_SECS`] resets the backoff. Pooled workers are
+//! killed when the Sentinel stops; their leases expire and the jobs requeue.
+
+use anyhow::Result;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use tracing::{info, warn};
+
+/// Seconds a new worker has to IDENTIFY before it is replaced.
+pub const IDENTIFY_TIMEOUT_SECS: f64 = 30.0;
+/// Uptime after which a crash no longer counts toward the backoff.
+pub const STABLE_AFTER_SECS: f64 = 60.0;
+/// First delay before replacing a crashed worker.
+const CRASH_BACKOFF_BASE_SECS: f64 = 1.0;
+/// Longest delay before replacing a crashed worker.
+const CRASH_BACKOFF_MAX_SECS: f64 = 60.0;
+
+/// How to start pooled workers and how many to keep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerPoolConfig {
+    /// Program started for each worker
+    pub program: PathBuf,
+    /// Arguments connecting it to this Sentinel; `--worker-id <id>` is
+    /// appended
+    pub args: Vec<String>,
+    /// Workers kept running with an empty queue
+    pub min_workers: usize,
+    /// Never more than this many pooled workers
+    pub max_workers: usize,
+    /// Idle time before a worker above the minimum is retired
+    pub idle_retire_secs: f64,
+}
+
+impl WorkerPoolConfig {
+    /// Pool of `program args...` growing to `max_workers`, empty when idle.
+    pub fn new(program: impl Into<PathBuf>, args: Vec<String>, max_workers: usize) -> Self {
+        Self {
+            program: program.into(),
+            args,
+            min_workers: 0,
+            max_workers,
+            idle_retire_secs: 60.0,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.max_workers == 0 {
+            anyhow::bail!("Worker pool max_workers must be at least 1");
+        }
+        if self.min_workers > self.max_workers {
+            anyhow::bail!(
+                "Worker pool min_workers ({}) exceeds max_workers ({})",
+                self.min_workers,
+                self.max_workers
+            );
+        }
+        if self.idle_retire_secs.is_nan() || self.idle_retire_secs < 0.0 {
+            anyhow::bail!("Worker pool idle_retire_secs must not be negative");
+        }
+        Ok(())
+    }
+}
+
+/// Address a local worker connects to for a Sentinel bound on `bind_addr`:
+/// wildcard hosts (`*`, `0.0.0.0`) become the loopback address.
+pub fn local_connect_addr(bind_addr: &str) -> String {
+    for wildcard in ["://*:", "://0.0.0.0:"] {
+        if let Some((scheme, port)) = bind_addr.split_once(wildcard) {
+            return format!("{}://127.0.0.1:{}", scheme, port);
+        }
+    }
+    bind_addr.to_string()
+}
+
+/// A pooled worker as seen in the Sentinel's worker registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PooledWorkerState {
+    /// Has not identified (yet)
+    NotConnected,
+    Idle,
+    Busy,
+    /// Connected but silent long enough to be UNREACHABLE
+    Unreachable,
+}
+
+/// Pool figures reported in `SystemPulse`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkerPoolStatus {
+    /// Running worker processes
+    pub size: usize,
+    /// Processes the pool is converging to
+    pub target: usize,
+    pub max: usize,
+    /// Workers replaced after a crash since the Sentinel started
+    pub restarts: u64,
+}
+
+struct PooledWorker {
+    worker_id: String,
+    child: Child,
+    started_at: f64,
+    idle_since: Option<f64>,
+}
+
+/// Spawns, replaces and retires local worker processes.
+pub struct WorkerSupervisor {
+    config: WorkerPoolConfig,
+    workers: Vec<PooledWorker>,
+    next_id: u64,
+    target: usize,
+    /// Crashes in a row without a stable worker in between
+    crash_streak: u32,
+    /// No spawns before this time while backing off
+    spawn_not_before: f64,
+    restarts: u64,
+    /// The Sentinel's max_workers; the pool never exceeds it
+    cap: usize,
+}
+
+impl WorkerSupervisor {
+    pub fn new(config: WorkerPoolConfig) -> Self {
+        Self {
+            target: config.min_workers,
+            cap: config.max_workers,
+            config,
+            workers: Vec::new(),
+            next_id: 0,
+            crash_streak: 0,
+            spawn_not_before: 0.0,
+            restarts: 0,
+        }
+    }
+
+    pub fn status(&self) -> WorkerPoolStatus {
+        WorkerPoolStatus {
+            size: self.workers.len(),
+            target: self.target,
+            max: self.max_workers(),
+            restarts: self.restarts,
+        }
+    }
+
+    /// Bound the pool by the Sentinel's worker cap, which may change at
+    /// runtime. Workers above a lowered cap retire like idle ones.
+    pub fn set_cap(&mut self, cap: usize) {
+        self.cap = cap;
+    }
+
+    fn max_workers(&self) -> usize {
+        self.config.max_workers.min(self.cap)
+    }
+
+    /// Whether `worker_id` belongs to a pooled process.
+    pub fn owns(&self, worker_id: &str) -> bool {
+        self.workers.iter().any(|w| w.worker_id == worker_id)
+    }
+
+    /// Replace dead or unhealthy workers and scale to `demand` (queued plus
+    /// running jobs). `state` looks a pooled worker up in the registry.
+    /// Returns the ids of workers that were stopped, so the caller can drop
+    /// them from the registry.
+    pub fn reconcile(
+        &mut self,
+        demand: usize,
+        now: f64,
+        state: impl Fn(&str) -> PooledWorkerState,
+    ) -> Vec<String> {
+        let mut stopped = Vec::new();
+
+        let mut index = 0;
+        while index < self.workers.len() {
+            let worker = &mut self.workers[index];
+            let failure = match worker.child.try_wait() {
+                Ok(Some(status)) => Some(format!("exited with {}", status)),
+                Ok(None) => match state(&worker.worker_id) {
+                    PooledWorkerState::NotConnected
+                        if now - worker.started_at >= IDENTIFY_TIMEOUT_SECS =>
+                    {
+                        Some("never identified".to_string())
+                    }
+                    PooledWorkerState::Unreachable => Some("unreachable".to_string()),
+                    PooledWorkerState::Idle => {
+                        worker.idle_since.get_or_insert(now);
+                        None
+                    }
+                    PooledWorkerState::NotConnected | PooledWorkerState::Busy => {
+                        worker.idle_since = None;
+                        None
+                    }
+                },
+                Err(err) => Some(format!("could not be polled: {}", err)),
+            };
+            match failure {
+                Some(reason) => {
+                    let mut worker = self.workers.remove(index);
+                    warn!(
+                        "Pooled worker {} {}; replacing it",
+                        worker.worker_id, reason
+                    );
+                    stop(&mut worker.child);
+                    self.record_crash(now - worker.started_at, now);
+                    stopped.push(worker.worker_id);
+                }
+                None => index += 1,
+            }
+        }
+
+        let max_workers = self.max_workers();
+        self.target = pool_target(
+            demand,
+            self.config.min_workers.min(max_workers),
+            max_workers,
+        );
+
+        while self.workers.len() < self.target && now >= self.spawn_not_before {
+            if let Err(err) = self.spawn(now) {
+                warn!("Failed to start pooled worker: {}", err);
+                self.record_crash(0.0, now);
+                break;
+            }
+        }
+
+        let mut surplus = self.workers.len().saturating_sub(self.target);
+        while surplus > 0 {
+            let Some(index) = self.longest_idle(now) else {
+                break;
+            };
+            let mut worker = self.workers.remove(index);
+            info!("Retiring idle pooled worker {}", worker.worker_id);
+            stop(&mut worker.child);
+            stopped.push(worker.worker_id);
+            surplus -= 1;
+        }
+
+        stopped
+    }
+
+    /// Kill every pooled worker.
+    pub fn shutdown(&mut self) {
+        for mut worker in self.workers.drain(..) {
+            stop(&mut worker.child);
+        }
+    }
+
+    fn spawn(&mut self, now: f64) -> Result<()> {
+        self.next_id += 1;
+        let worker_id = format!("pool-{}-{}", std::process::id(), self.next_id);
+        let child = Command::new(&self.config.program)
+            .args(&self.config.args)
+            .arg("--worker-id")
+            .arg(&worker_id)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|err| anyhow::anyhow!("{}: {}", self.config.program.display(), err))?;
+        info!("Started pooled worker {} (pid {})", worker_id, child.id());
+        self.workers.push(PooledWorker {
+            worker_id,
+            child,
+            started_at: now,
+            idle_since: None,
+        });
+        Ok(())
+    }
+
+    fn record_crash(&mut self, uptime_secs: f64, now: f64) {
+        if uptime_secs >= STABLE_AFTER_SECS {
+            self.crash_streak = 0;
+        }
+        self.crash_streak += 1;
+        self.restarts += 1;
+        self.spawn_not_before = now + crash_backoff_secs(self.crash_streak);
+    }
+
+    /// Worker idle past `idle_retire_secs` for the longest time.
+    fn longest_idle(&self, now: f64) -> Option<usize> {
+        self.workers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, worker)| worker.idle_since.map(|since| (index, since)))
+            .filter(|(_, since)| now - since >= self.config.idle_retire_secs)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+    }
+}
+
+impl Drop for WorkerSupervisor {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn stop(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Workers needed for `demand` jobs within the pool bounds.
+fn pool_target(demand: usize, min_workers: usize, max_workers: usize) -> usize {
+    demand.clamp(min_workers, max_workers)
+}
+
+/// Delay before replacing the `streak`-th crash in a row.
+fn crash_backoff_secs(streak: u32) -> f64 {
+    let exponent = streak.saturating_sub(1).min(16) as i32;
+    (CRASH_BACKOFF_BASE_SECS * 2f64.powi(exponent)).min(CRASH_BACKOFF_MAX_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_target_and_backoff() {
+        assert_eq!(pool_target(0, 0, 4), 0);
+        assert_eq!(pool_target(0, 1, 4), 1);
+        assert_eq!(pool_target(3, 0, 4), 3);
+        assert_eq!(pool_target(10, 0, 4), 4);
+
+        assert_eq!(crash_backoff_secs(1), 1.0);
+        assert_eq!(crash_backoff_secs(2), 2.0);
+        assert_eq!(crash_backoff_secs(4), 8.0);
+        assert_eq!(crash_backoff_secs(30), CRASH_BACKOFF_MAX_SECS);
+    }
+
+    #[test]
+    fn test_local_connect_addr() {
+        assert_eq!(local_connect_addr("tcp://*:5555"), "tcp://127.0.0.1:5555");
+        assert_eq!(
+            local_connect_addr("grpc://0.0.0.0:50051"),
+            "grpc://127.0.0.1:50051"
+        );
+        assert_eq!(
+            local_connect_addr("ipc:///tmp/casparian.sock"),
+            "ipc:///tmp/casparian.sock"
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut config = WorkerPoolConfig::new("casparian", Vec::new(), 2);
+        config.validate().unwrap();
+        config.min_workers = 3;
+        assert!(config.validate().is_err());
+        config.min_workers = 0;
+        config.max_workers = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[cfg(unix)]
+    fn shell_pool(script: &str, max_workers: usize) -> WorkerSupervisor {
+        let mut config = WorkerPoolConfig::new(
+            "/bin/sh",
+            vec!["-c".to_string(), script.to_string(), "worker".to_string()],
+            max_workers,
+        );
+        config.idle_retire_secs = 10.0;
+        WorkerSupervisor::new(config)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scales_with_demand_and_retires_idle_workers() {
+        let mut pool = shell_pool("sleep 30", 3);
+        assert!(pool
+            .reconcile(5, 0.0, |_| PooledWorkerState::NotConnected)
+            .is_empty());
+        assert_eq!(pool.status().size, 3);
+        assert_eq!(pool.status().target, 3);
+
+        // Idle workers stay until they have been idle long enough
+        assert!(pool
+            .reconcile(0, 1.0, |_| PooledWorkerState::Idle)
+            .is_empty());
+        assert_eq!(pool.status().size, 3);
+        let retired = pool.reconcile(0, 11.0, |_| PooledWorkerState::Idle);
+        assert_eq!(retired.len(), 3);
+        assert_eq!(pool.status().size, 0);
+        assert_eq!(pool.status().restarts, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_replaces_crashed_workers_with_backoff() {
+        let mut pool = shell_pool("exit 1", 1);
+        pool.reconcile(1, 0.0, |_| PooledWorkerState::NotConnected);
+        assert_eq!(pool.status().size, 1);
+        let worker_id = pool.workers[0].worker_id.clone();
+        assert!(pool.owns(&worker_id));
+        pool.workers[0].child.wait().unwrap();
+
+        // The crash is reaped and the replacement waits for the backoff
+        let stopped = pool.reconcile(1, 0.5, |_| PooledWorkerState::NotConnected);
+        assert_eq!(stopped, vec![worker_id]);
+        assert_eq!(pool.status().size, 0);
+        assert_eq!(pool.status().restarts, 1);
+        pool.reconcile(1, 1.0, |_| PooledWorkerState::NotConnected);
+        assert_eq!(pool.status().size, 0);
+        pool.reconcile(1, 1.6, |_| PooledWorkerState::NotConnected);
+        assert_eq!(pool.status().size, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_replaces_unhealthy_workers() {
+        let mut pool = shell_pool("sleep 30", 1);
+        pool.reconcile(1, 0.0, |_| PooledWorkerState::NotConnected);
+        let stopped = pool.reconcile(1, IDENTIFY_TIMEOUT_SECS, |_| {
+            PooledWorkerState::NotConnected
+        });
+        assert_eq!(stopped.len(), 1);
+
+        let mut pool = shell_pool("sleep 30", 1);
+        pool.reconcile(1, 0.0, |_| PooledWorkerState::Busy);
+        assert_eq!(
+            pool.reconcile(1, 1.0, |_| PooledWorkerState::Unreachable)
+                .len(),
+            1
+        );
+        assert_eq!(pool.status().restarts, 1);
+    }
+}
@@ -4,8 +4,13 @@
 
 use casparian_db::{DbConnection, DbValue};
 use casparian_protocol::types::{IdentifyPayload, JobReceipt, JobStatus};
-use casparian_protocol::{metrics, JobId, Message, OpCode, PipelineRunStatus, ProcessingStatus};
-use casparian_sentinel::{ControlClient, Sentinel, SentinelConfig};
+use casparian_protocol::{
+    metrics, JobId, Message, OpCode, PipelineRunStatus, ProcessingStatus, ProtocolFeatures,
+    ProtocolVersionRange,
+};
+use casparian_sentinel::{
+    ControlClient, SchedulingPolicy, SecurityConfig, Sentinel, SentinelConfig, WorkerHealthConfig,
+};
 use std::time::Duration;
 use std::{sync::mpsc, thread};
 use tempfile::TempDir;
@@ -70,6 +75,8 @@ fn test_identify_message() {
     let identify = IdentifyPayload {
         capabilities: vec!["*".to_string()],
         worker_id: Some("test-worker".to_string()),
+        protocol_versions: ProtocolVersionRange::SUPPORTED,
+        protocol_features: ProtocolFeatures::SUPPORTED,
     };
 
     let payload = serde_json::to_vec(&identify).unwrap();
@@ -99,7 +106,12 @@ fn test_conclude_message() {
         error_message: None,
         diagnostics: None,
         source_hash: Some("abc123def456".to_string()),
+        source_file: None,
+        decompressed_hash: None,
+        source_encoding: None,
+        lineage: None,
         lease_token: None,
+        batch_results: Vec::new(),
     };
 
     let payload = serde_json::to_vec(&receipt).unwrap();
@@ -144,6 +156,20 @@ fn test_control_api_smoke() {
             max_workers: 1,
             control_addr: Some(control_addr_clone),
             query_catalog_path: query_catalog,
+            scheduling_policy: SchedulingPolicy::default(),
+            metrics_addr: None,
+            http_api_addr: None,
+            security: SecurityConfig::disabled(),
+            allow_unsigned_deploys: false,
+            dispatch_batch_size: 1,
+            worker_health: WorkerHealthConfig::default(),
+            priority_aging_secs: 0,
+            preempt_low_priority: false,
+            read_only: false,
+            rehash: false,
+            schema_drift_threshold: casparian_sentinel::DEFAULT_SCHEMA_DRIFT_THRESHOLD,
+            worker_pool: None,
+            config_path: None,
         };
         let mut sentinel = Sentinel::bind(config).expect("bind sentinel");
         sentinel.run_with_shutdown(stop_rx).expect("run sentinel");
@@ -185,6 +211,62 @@ fn test_control_api_smoke() {
     let _ = handle.join();
 }
 
+/// Worker and control sockets served over named pipes (`pipe://`)
+#[test]
+fn test_control_api_over_pipe() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let db_url = format!("sqlite:{}", temp_dir.path().join("state.sqlite").display());
+    let query_catalog = temp_dir.path().join("query.duckdb");
+    let bind_addr = format!("pipe://casparian-it-sentinel-{}", std::process::id());
+    let control_addr = format!("pipe://casparian-it-control-{}", std::process::id());
+
+    let (stop_tx, stop_rx) = mpsc::channel();
+    let (bind_addr_clone, control_addr_clone) = (bind_addr.clone(), control_addr.clone());
+    let handle = thread::spawn(move || {
+        let config = SentinelConfig {
+            bind_addr: bind_addr_clone,
+            state_store_url: db_url,
+            max_workers: 1,
+            control_addr: Some(control_addr_clone),
+            query_catalog_path: query_catalog,
+            scheduling_policy: SchedulingPolicy::default(),
+            metrics_addr: None,
+            http_api_addr: None,
+            security: SecurityConfig::disabled(),
+            allow_unsigned_deploys: false,
+            dispatch_batch_size: 1,
+            worker_health: WorkerHealthConfig::default(),
+            priority_aging_secs: 0,
+            preempt_low_priority: false,
+            read_only: false,
+            rehash: false,
+            schema_drift_threshold: casparian_sentinel::DEFAULT_SCHEMA_DRIFT_THRESHOLD,
+            worker_pool: None,
+            config_path: None,
+        };
+        let mut sentinel = Sentinel::bind(config).expect("bind sentinel");
+        sentinel.run_with_shutdown(stop_rx).expect("run sentinel");
+    });
+
+    let ready = (0..40).any(|_| {
+        let pong = ControlClient::connect_with_timeout(&control_addr, Duration::from_millis(100))
+            .and_then(|client| client.ping())
+            .unwrap_or(false);
+        if !pong {
+            thread::sleep(Duration::from_millis(50));
+        }
+        pong
+    });
+    assert!(ready, "control API not reachable over the pipe");
+    assert_eq!(
+        casparian_protocol::endpoint::diagnose_endpoint(&bind_addr).reachable,
+        Some(true)
+    );
+
+    let _ = stop_tx.send(());
+    let _ = handle.join();
+}
+
 /// Test worker/sentinel ZMQ message exchange
 ///
 /// This tests the ACTUAL communication pattern:
@@ -208,6 +290,8 @@ fn test_worker_sentinel_exchange() {
     let identify = IdentifyPayload {
         capabilities: vec!["test_plugin".to_string()],
         worker_id: Some("worker-1".to_string()),
+        protocol_versions: ProtocolVersionRange::SUPPORTED,
+        protocol_features: ProtocolFeatures::SUPPORTED,
     };
     let payload = serde_json::to_vec(&identify).unwrap();
     let msg = Message::new(OpCode::Identify, JobId::new(0), payload).unwrap();
@@ -826,7 +910,10 @@ fn test_only_queued_jobs_are_dispatched() {
 /// Test bidirectional message flow: IDENTIFY -> ACK -> DISPATCH -> CONCLUDE
 #[test]
 fn test_full_worker_lifecycle_message_flow() {
-    use casparian_protocol::types::{DispatchCommand, RuntimeKind, SinkConfig, SinkMode};
+    use casparian_protocol::types::{
+        DispatchCommand, JobPriority, ResourceLimits, RuntimeKind, SchemaEvolution, SinkConfig,
+        SinkMode,
+    };
     let context = Context::new();
 
     let router = context.socket(zmq::ROUTER).unwrap();
@@ -842,6 +929,8 @@ fn test_full_worker_lifecycle_message_flow() {
     let identify = IdentifyPayload {
         capabilities: vec!["*".to_string()],
         worker_id: Some("lifecycle-test-worker".to_string()),
+        protocol_versions: ProtocolVersionRange::SUPPORTED,
+        protocol_features: ProtocolFeatures::SUPPORTED,
     };
     let payload = serde_json::to_vec(&identify).unwrap();
     let msg = Message::new(OpCode::Identify, JobId::new(0), payload).unwrap();
@@ -871,6 +960,7 @@ fn test_full_worker_lifecycle_message_flow() {
             topic: "output".to_string(),
             uri: "parquet://output.parquet".to_string(),
             mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }],
@@ -885,6 +975,17 @@ fn test_full_worker_lifecycle_message_flow() {
         env_hash: Some("abc123".to_string()),
         source_code: Some("# parser code".to_string()),
         artifact_hash: "artifact_hash_test".to_string(),
+        lockfile_content: None,
+        limits: ResourceLimits::default(),
+        batch: Vec::new(),
+        priority: JobPriority::default(),
+        byte_range: None,
+        tag: None,
+        checkpoint_key: None,
+        csv_dialect: None,
+        input_encoding: None,
+        cached_source_hash: None,
+        input_lineage: None,
     };
     let payload = serde_json::to_vec(&dispatch).unwrap();
     let dispatch_msg = Message::new(OpCode::Dispatch, JobId::new(12345), payload).unwrap();
@@ -922,6 +1023,7 @@ fn test_heartbeat_message_format() {
         status: HeartbeatStatus::Busy,
         active_job_count: 1,
         active_job_ids: vec![JobId::new(42)],
+        load: None,
     };
 
     let payload = serde_json::to_vec(&heartbeat).unwrap();
@@ -944,13 +1046,14 @@ fn test_heartbeat_message_format() {
 /// Test ERROR message carries full error info
 #[test]
 fn test_error_message_carries_full_info() {
-    use casparian_protocol::types::ErrorPayload;
+    use casparian_protocol::types::{ErrorCategory, ErrorPayload};
 
     let error = ErrorPayload {
         message: "Parser failed: invalid CSV format".to_string(),
         traceback: Some(
             "File parser.py, line 42\n  raise ValueError\nValueError: bad row".to_string(),
         ),
+        category: Some(ErrorCategory::PluginException),
     };
 
     let payload = serde_json::to_vec(&error).unwrap();
@@ -964,4 +1067,5 @@ fn test_error_message_carries_full_info() {
 
     assert!(parsed.message.contains("invalid CSV"));
     assert!(parsed.traceback.as_ref().unwrap().contains("line 42"));
+    assert_eq!(parsed.category, Some(ErrorCategory::PluginException));
 }
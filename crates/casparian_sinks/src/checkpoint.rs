@@ -0,0 +1,416 @@
+//! Checkpointed Parquet writes that survive a worker crash.
+//!
+//! With a checkpoint key (the job's materialization key), a `ParquetSink`
+//! writes batches to rolling part files under
+//! `{output_dir}/.checkpoints/{key}/{output}/` instead of straight to its temp
+//! file. A part is closed once it holds `rows_per_part` rows and is then
+//! listed in `manifest.json`; a part in the manifest is complete on disk, a
+//! part file that is not is an interrupted write and gets overwritten. Parts
+//! use a `.ckpt` extension so `*.parquet` globs over the output directory do
+//! not pick them up.
+//!
+//! A re-dispatched job with the same key skips the input batches its
+//! completed parts already hold. The key covers the file generation and the
+//! parser fingerprint, so the parser output is the same; the row count of
+//! every skipped batch is still checked against the manifest, and a mismatch
+//! discards the checkpoint and fails the write so the retry starts clean.
+//!
+//! On prepare the parts are merged into the staged output file; commit
+//! removes the checkpoint. A failed or cancelled write keeps the completed
+//! parts for the next attempt.
+
+use anyhow::{bail, Context, Result};
+use arrow::array::RecordBatch;
+use arrow::datatypes::Schema;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::arrow_writer::ArrowWriter;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use casparian_protocol::safe_output_id;
+
+/// Directory under a Parquet output directory holding checkpoints.
+pub const CHECKPOINT_DIR: &str = ".checkpoints";
+
+/// Rows per part file unless the sink URI sets `checkpoint_rows`.
+pub const DEFAULT_CHECKPOINT_ROWS: u64 = 1_000_000;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CheckpointManifest {
+    parts: Vec<CheckpointPart>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckpointPart {
+    file: String,
+    /// Row count of each input batch in the part, in write order
+    batch_rows: Vec<u64>,
+}
+
+/// Part file being written; not in the manifest until closed.
+struct OpenPart {
+    writer: ArrowWriter<File>,
+    path: PathBuf,
+    batch_rows: Vec<u64>,
+    rows: u64,
+}
+
+/// Rolling part files of one output.
+pub(crate) struct ParquetCheckpoint {
+    dir: PathBuf,
+    rows_per_part: u64,
+    schema: Option<Arc<Schema>>,
+    manifest: CheckpointManifest,
+    /// Row counts of checkpointed batches the input has not reached yet
+    pending_skip: VecDeque<u64>,
+    open: Option<OpenPart>,
+}
+
+impl ParquetCheckpoint {
+    pub(crate) fn new(output_dir: &Path, key: &str, output_name: &str, rows_per_part: u64) -> Self {
+        Self {
+            dir: checkpoint_dir(output_dir, key).join(safe_output_id(output_name)),
+            rows_per_part: rows_per_part.max(1),
+            schema: None,
+            manifest: CheckpointManifest::default(),
+            pending_skip: VecDeque::new(),
+            open: None,
+        }
+    }
+
+    /// Load the manifest of an earlier attempt, or start empty.
+    ///
+    /// A manifest that cannot be read, or whose parts are missing or have a
+    /// different schema, is discarded.
+    pub(crate) fn open(&mut self, schema: &Arc<Schema>) -> Result<()> {
+        self.schema = Some(schema.clone());
+        match self.load_manifest(schema) {
+            Ok(Some(manifest)) => {
+                self.pending_skip = manifest
+                    .parts
+                    .iter()
+                    .flat_map(|part| part.batch_rows.iter().copied())
+                    .collect();
+                info!(
+                    "Resuming Parquet checkpoint {}: {} part(s), {} rows",
+                    self.dir.display(),
+                    manifest.parts.len(),
+                    self.pending_skip.iter().sum::<u64>()
+                );
+                self.manifest = manifest;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                warn!(
+                    "Discarding unusable Parquet checkpoint {}: {:#}",
+                    self.dir.display(),
+                    err
+                );
+                self.discard();
+            }
+        }
+        std::fs::create_dir_all(&self.dir).with_context(|| {
+            format!(
+                "Failed to create checkpoint directory: {}",
+                self.dir.display()
+            )
+        })?;
+        Ok(())
+    }
+
+    fn load_manifest(&self, schema: &Arc<Schema>) -> Result<Option<CheckpointManifest>> {
+        let path = self.dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let manifest: CheckpointManifest = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        for part in &manifest.parts {
+            let file = File::open(self.dir.join(&part.file))
+                .with_context(|| format!("Missing checkpoint part {}", part.file))?;
+            let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+                .with_context(|| format!("Unreadable checkpoint part {}", part.file))?;
+            if builder.schema().fields() != schema.fields() {
+                bail!("checkpoint part {} has a different schema", part.file);
+            }
+        }
+        Ok(Some(manifest))
+    }
+
+    /// Write a batch to the current part, or skip it if a completed part
+    /// already holds it.
+    pub(crate) fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        let rows = batch.num_rows() as u64;
+        if let Some(expected) = self.pending_skip.pop_front() {
+            if expected != rows {
+                self.discard();
+                bail!(
+                    "Parquet checkpoint {} does not match the output (batch of {} rows, \
+                     checkpoint has {}); discarded it",
+                    self.dir.display(),
+                    rows,
+                    expected
+                );
+            }
+            return Ok(());
+        }
+
+        if self.open.is_none() {
+            self.open = Some(self.open_part()?);
+        }
+        let part = self.open.as_mut().expect("part opened above");
+        part.writer
+            .write(batch)
+            .context("Failed to write batch to Parquet checkpoint part")?;
+        part.batch_rows.push(rows);
+        part.rows += rows;
+        if part.rows >= self.rows_per_part {
+            self.close_part()?;
+        }
+        Ok(())
+    }
+
+    fn open_part(&self) -> Result<OpenPart> {
+        let schema = self
+            .schema
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Parquet checkpoint not opened"))?;
+        let path = self
+            .dir
+            .join(format!("part-{:05}.ckpt", self.manifest.parts.len()));
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create checkpoint part: {}", path.display()))?;
+        let props = parquet::file::properties::WriterProperties::builder()
+            .set_compression(parquet::basic::Compression::SNAPPY)
+            .build();
+        let writer = ArrowWriter::try_new(file, schema, Some(props))
+            .context("Failed to create Parquet writer")?;
+        Ok(OpenPart {
+            writer,
+            path,
+            batch_rows: Vec::new(),
+            rows: 0,
+        })
+    }
+
+    /// Close the current part and record it in the manifest.
+    fn close_part(&mut self) -> Result<()> {
+        let Some(part) = self.open.take() else {
+            return Ok(());
+        };
+        part.writer
+            .close()
+            .context("Failed to close Parquet checkpoint part")?;
+        let file = part
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.manifest.parts.push(CheckpointPart {
+            file,
+            batch_rows: part.batch_rows,
+        });
+        self.save_manifest()
+    }
+
+    fn save_manifest(&self) -> Result<()> {
+        let path = self.dir.join(MANIFEST_FILE);
+        let temp_path = self.dir.join(format!(".{}.tmp", MANIFEST_FILE));
+        let json = serde_json::to_string(&self.manifest)?;
+        std::fs::write(&temp_path, json)
+            .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Close the last part. Fails if the input ended before the checkpoint
+    /// did, which means the parser output changed.
+    pub(crate) fn finish(&mut self) -> Result<()> {
+        if !self.pending_skip.is_empty() {
+            let missing = self.pending_skip.len();
+            self.discard();
+            bail!(
+                "Parquet checkpoint {} has {} batch(es) more than the output; discarded it",
+                self.dir.display(),
+                missing
+            );
+        }
+        self.close_part()
+    }
+
+    /// Pass the batches of every part, in order, to `write`.
+    pub(crate) fn copy_into(
+        &self,
+        mut write: impl FnMut(&RecordBatch) -> Result<()>,
+    ) -> Result<()> {
+        for part in &self.manifest.parts {
+            let path = self.dir.join(&part.file);
+            let file = File::open(&path)
+                .with_context(|| format!("Failed to open checkpoint part: {}", path.display()))?;
+            let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+            for batch in reader {
+                write(&batch?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the part being written; completed parts are kept for a retry.
+    pub(crate) fn abandon_open_part(&mut self) {
+        if let Some(part) = self.open.take() {
+            drop(part.writer);
+            let _ = std::fs::remove_file(&part.path);
+        }
+    }
+
+    /// Delete the checkpoint (after commit, or when it cannot be used).
+    pub(crate) fn discard(&mut self) {
+        self.abandon_open_part();
+        self.manifest = CheckpointManifest::default();
+        self.pending_skip.clear();
+        if self.dir.exists() {
+            if let Err(err) = std::fs::remove_dir_all(&self.dir) {
+                warn!(
+                    "Failed to remove Parquet checkpoint {}: {}",
+                    self.dir.display(),
+                    err
+                );
+            }
+        }
+        // The key directory goes once its last output is gone.
+        if let Some(parent) = self.dir.parent() {
+            let _ = std::fs::remove_dir(parent);
+        }
+    }
+}
+
+/// Checkpoint directory for `key` under a Parquet output directory.
+pub fn checkpoint_dir(output_dir: &Path, key: &str) -> PathBuf {
+    output_dir.join(CHECKPOINT_DIR).join(safe_output_id(key))
+}
+
+/// `checkpoint_rows=N` on a Parquet URI: rows per checkpoint part file.
+pub(crate) fn checkpoint_rows(parsed: &casparian_protocol::types::ParsedSinkUri) -> Result<u64> {
+    match parsed.query.get("checkpoint_rows") {
+        Some(value) => {
+            let rows: u64 = value.trim().parse().with_context(|| {
+                format!("Invalid checkpoint_rows '{}' in Parquet sink URI", value)
+            })?;
+            if rows == 0 {
+                bail!("checkpoint_rows must be at least 1");
+            }
+            Ok(rows)
+        }
+        None => Ok(DEFAULT_CHECKPOINT_ROWS),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{output_filename, ParquetSink};
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field};
+    use tempfile::tempdir;
+
+    fn batch(start: i64, rows: i64) -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int64Array::from_iter_values(start..start + rows))],
+        )
+        .unwrap()
+    }
+
+    fn read_ids(path: &Path) -> Vec<i64> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        reader
+            .flat_map(|batch| {
+                let batch = batch.unwrap();
+                let ids = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .clone();
+                ids.values().to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resume_skips_completed_parts() {
+        let dir = tempdir().unwrap();
+        let batches: Vec<RecordBatch> = (0..4).map(|i| batch(i * 3, 3)).collect();
+
+        // First attempt dies after three batches: two parts complete, the
+        // third batch sits in an unfinished part.
+        {
+            let mut sink = ParquetSink::new(dir.path().to_path_buf(), "trades", "job-1")
+                .unwrap()
+                .with_checkpoint("mat-key", 5);
+            sink.init(batches[0].schema().as_ref()).unwrap();
+            for batch in &batches[..3] {
+                sink.write_batch(batch).unwrap();
+            }
+        }
+        let checkpoint = checkpoint_dir(dir.path(), "mat-key").join("trades");
+        assert!(checkpoint.join(MANIFEST_FILE).exists());
+        let manifest: CheckpointManifest =
+            serde_json::from_str(&std::fs::read_to_string(checkpoint.join(MANIFEST_FILE)).unwrap())
+                .unwrap();
+        assert_eq!(manifest.parts.len(), 1);
+        assert_eq!(manifest.parts[0].batch_rows, vec![3, 3]);
+
+        // The retry replays every batch; the checkpointed ones are skipped.
+        let mut sink = ParquetSink::new(dir.path().to_path_buf(), "trades", "job-1")
+            .unwrap()
+            .with_checkpoint("mat-key", 5);
+        sink.init(batches[0].schema().as_ref()).unwrap();
+        for batch in &batches {
+            assert_eq!(sink.write_batch(batch).unwrap(), 3);
+        }
+        sink.prepare().unwrap();
+        sink.commit().unwrap();
+
+        let output = dir
+            .path()
+            .join(output_filename("trades", "job-1", "parquet"));
+        assert_eq!(read_ids(&output), (0..12).collect::<Vec<_>>());
+        assert!(!checkpoint_dir(dir.path(), "mat-key").exists());
+    }
+
+    #[test]
+    fn test_mismatched_checkpoint_is_discarded() {
+        let dir = tempdir().unwrap();
+        {
+            let mut sink = ParquetSink::new(dir.path().to_path_buf(), "trades", "job-1")
+                .unwrap()
+                .with_checkpoint("mat-key", 2);
+            sink.init(batch(0, 3).schema().as_ref()).unwrap();
+            sink.write_batch(&batch(0, 3)).unwrap();
+        }
+
+        let mut sink = ParquetSink::new(dir.path().to_path_buf(), "trades", "job-1")
+            .unwrap()
+            .with_checkpoint("mat-key", 2);
+        sink.init(batch(0, 4).schema().as_ref()).unwrap();
+        let err = sink.write_batch(&batch(0, 4)).unwrap_err();
+        assert!(err.to_string().contains("does not match"), "{}", err);
+        assert!(!checkpoint_dir(dir.path(), "mat-key")
+            .join("trades")
+            .exists());
+    }
+}
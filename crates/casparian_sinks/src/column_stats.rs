@@ -0,0 +1,307 @@
+//! Per-column statistics gathered while output batches stream to a sink.
+//!
+//! For every data column the collector tracks the row and null counts, the
+//! minimum and maximum value, and a HyperLogLog estimate of distinct values.
+//! Values are compared and hashed in Arrow's row format, so every type the
+//! row format can sort gets min/max and a distinct estimate; other types
+//! (e.g. maps) only get counts. Lineage columns (`_cf_*`, `__cf_*`) are
+//! skipped.
+//!
+//! Stats are optional: they are collected for plans built with
+//! [`OutputPlan::with_column_stats`](crate::OutputPlan::with_column_stats) or
+//! whose quarantine config asks for them, and reported on the
+//! [`OutputArtifact`](crate::OutputArtifact).
+
+use anyhow::{Context, Result};
+use arrow::array::{Array, ArrayRef, RecordBatch};
+use arrow::datatypes::DataType;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+
+use casparian_protocol::ColumnStats;
+
+use crate::{OutputBatch, SinkResult};
+
+/// HyperLogLog precision: 2^12 registers, ~1.6% standard error.
+const HLL_PRECISION: u32 = 12;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+
+/// Rendered min/max values longer than this are truncated.
+const MAX_RENDERED_CHARS: usize = 128;
+
+/// Collect stats over all batches of one output.
+pub fn collect_column_stats<'a>(
+    batches: impl IntoIterator<Item = &'a OutputBatch>,
+) -> SinkResult<Vec<ColumnStats>> {
+    let mut collector = ColumnStatsCollector::default();
+    for batch in batches {
+        collector.update(batch)?;
+    }
+    Ok(collector.finish())
+}
+
+/// Incremental stats for one output; columns are taken from the first batch.
+#[derive(Default)]
+pub struct ColumnStatsCollector {
+    columns: Option<Vec<ColumnCollector>>,
+}
+
+impl ColumnStatsCollector {
+    pub fn update(&mut self, batch: &OutputBatch) -> SinkResult<()> {
+        Ok(self.update_batch(batch.record_batch())?)
+    }
+
+    pub(crate) fn update_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        let columns = self.columns.get_or_insert_with(|| {
+            batch
+                .schema()
+                .fields()
+                .iter()
+                .filter(|field| !is_lineage_column(field.name()))
+                .map(|field| ColumnCollector::new(field.name(), field.data_type()))
+                .collect()
+        });
+        for column in columns.iter_mut() {
+            if let Some(array) = batch.column_by_name(&column.name) {
+                column
+                    .update(array)
+                    .with_context(|| format!("column stats failed for '{}'", column.name))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Vec<ColumnStats> {
+        self.columns
+            .unwrap_or_default()
+            .into_iter()
+            .map(ColumnCollector::finish)
+            .collect()
+    }
+}
+
+fn is_lineage_column(name: &str) -> bool {
+    name.starts_with("_cf_") || name.starts_with("__cf_")
+}
+
+struct ColumnCollector {
+    name: String,
+    rows: u64,
+    nulls: u64,
+    /// None for types the row format cannot order.
+    ordered: Option<OrderedStats>,
+}
+
+struct OrderedStats {
+    converter: RowConverter,
+    min: Option<OwnedRow>,
+    max: Option<OwnedRow>,
+    distinct: HyperLogLog,
+}
+
+impl ColumnCollector {
+    fn new(name: &str, data_type: &DataType) -> Self {
+        let field = SortField::new(data_type.clone());
+        let ordered = RowConverter::supports_fields(std::slice::from_ref(&field))
+            .then(|| RowConverter::new(vec![field]).ok())
+            .flatten()
+            .map(|converter| OrderedStats {
+                converter,
+                min: None,
+                max: None,
+                distinct: HyperLogLog::new(),
+            });
+        Self {
+            name: name.to_string(),
+            rows: 0,
+            nulls: 0,
+            ordered,
+        }
+    }
+
+    fn update(&mut self, array: &ArrayRef) -> Result<()> {
+        self.rows += array.len() as u64;
+        self.nulls += array.null_count() as u64;
+        let Some(ordered) = self.ordered.as_mut() else {
+            return Ok(());
+        };
+
+        let rows = ordered
+            .converter
+            .convert_columns(std::slice::from_ref(array))?;
+        for index in 0..array.len() {
+            if array.is_null(index) {
+                continue;
+            }
+            let row = rows.row(index);
+            ordered.distinct.insert(row.as_ref());
+            if ordered.min.as_ref().map_or(true, |min| row < min.row()) {
+                ordered.min = Some(row.owned());
+            }
+            if ordered.max.as_ref().map_or(true, |max| row > max.row()) {
+                ordered.max = Some(row.owned());
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> ColumnStats {
+        let (min, max, distinct_estimate) = match self.ordered {
+            Some(ordered) => (
+                render_row(&ordered.converter, ordered.min.as_ref()),
+                render_row(&ordered.converter, ordered.max.as_ref()),
+                Some(ordered.distinct.estimate()),
+            ),
+            None => (None, None, None),
+        };
+        ColumnStats {
+            column_name: self.name,
+            row_count: self.rows,
+            null_count: self.nulls,
+            min,
+            max,
+            distinct_estimate,
+        }
+    }
+}
+
+fn render_row(converter: &RowConverter, row: Option<&OwnedRow>) -> Option<String> {
+    let arrays = converter.convert_rows([row?.row()]).ok()?;
+    let array = arrays.first()?;
+    let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default()).ok()?;
+    let rendered = formatter.value(0).to_string();
+    if rendered.chars().count() > MAX_RENDERED_CHARS {
+        let truncated: String = rendered.chars().take(MAX_RENDERED_CHARS).collect();
+        Some(format!("{}…", truncated))
+    } else {
+        Some(rendered)
+    }
+}
+
+/// Fixed-precision HyperLogLog over blake3 hashes.
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; HLL_REGISTERS],
+        }
+    }
+
+    fn insert(&mut self, value: &[u8]) {
+        let digest = blake3::hash(value);
+        let mut prefix = [0u8; 8];
+        prefix.copy_from_slice(&digest.as_bytes()[..8]);
+        let hash = u64::from_le_bytes(prefix);
+
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        let rest = hash << HLL_PRECISION;
+        let rank = (rest.leading_zeros().min(64 - HLL_PRECISION) + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-i32::from(rank)))
+            .sum();
+        let raw = alpha * m * m / sum;
+
+        // Linear counting is more accurate while many registers are empty.
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int64Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::Arc;
+
+    fn batch(ids: Vec<Option<i64>>, names: Vec<Option<&str>>) -> OutputBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("_cf_job_id", DataType::Utf8, false),
+        ]));
+        let job_ids = vec!["job-1"; ids.len()];
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(ids)) as ArrayRef,
+                Arc::new(StringArray::from(names)) as ArrayRef,
+                Arc::new(StringArray::from(job_ids)) as ArrayRef,
+            ],
+        )
+        .unwrap();
+        OutputBatch::from_record_batch(batch)
+    }
+
+    #[test]
+    fn test_stats_span_batches() {
+        let batches = [
+            batch(
+                vec![Some(5), None, Some(3)],
+                vec![Some("b"), Some("a"), None],
+            ),
+            batch(vec![Some(9), Some(3)], vec![Some("c"), Some("a")]),
+        ];
+        let stats = collect_column_stats(&batches).unwrap();
+
+        let names: Vec<&str> = stats.iter().map(|s| s.column_name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name"], "lineage columns are skipped");
+
+        let id = &stats[0];
+        assert_eq!(id.row_count, 5);
+        assert_eq!(id.null_count, 1);
+        assert_eq!(id.min.as_deref(), Some("3"));
+        assert_eq!(id.max.as_deref(), Some("9"));
+        assert_eq!(id.distinct_estimate, Some(3));
+
+        let name = &stats[1];
+        assert_eq!(name.null_count, 1);
+        assert_eq!(name.min.as_deref(), Some("a"));
+        assert_eq!(name.max.as_deref(), Some("c"));
+        assert_eq!(name.null_pct(), 20.0);
+    }
+
+    #[test]
+    fn test_all_null_column_has_no_bounds() {
+        let stats = collect_column_stats(&[batch(vec![None, None], vec![None, None])]).unwrap();
+        assert_eq!(stats[0].null_count, 2);
+        assert_eq!(stats[0].min, None);
+        assert_eq!(stats[0].distinct_estimate, Some(0));
+    }
+
+    #[test]
+    fn test_distinct_estimate_within_error() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "value",
+            DataType::Float64,
+            false,
+        )]));
+        let values: Vec<f64> = (0..50_000).map(|i| (i % 20_000) as f64).collect();
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(Float64Array::from(values))]).unwrap();
+        let stats = collect_column_stats(&[OutputBatch::from_record_batch(batch)]).unwrap();
+
+        let estimate = stats[0].distinct_estimate.unwrap() as f64;
+        let error = (estimate - 20_000.0).abs() / 20_000.0;
+        assert!(error < 0.05, "estimate {} too far from 20000", estimate);
+        assert_eq!(stats[0].max.as_deref(), Some("19999.0"));
+    }
+}
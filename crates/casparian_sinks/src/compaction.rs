@@ -0,0 +1,524 @@
+//! Compaction of small Parquet output files.
+//!
+//! Every job writes its own `{output}_{job}.parquet` file, so a busy output
+//! directory fills up with small files. Compaction rewrites runs of small
+//! files of one output (same directory, same schema) into one file of up to
+//! `target_file_bytes`, with row groups of `row_group_rows` rows. Rows are
+//! copied unchanged, lineage columns included. The compacted file follows the
+//! `{output}_{hex}.parquet` naming, so `{output}_*.parquet` globs over the
+//! directory keep matching.
+//!
+//! The swap is driven by a journal under `{output_dir}/.compaction/`: the new
+//! file is written there as `.tmp`, then a journal naming it and its sources is
+//! written (the commit point), the file is renamed into place and the sources
+//! are removed. A crash before the journal leaves the sources untouched and a
+//! stray `.tmp`; a crash after it is rolled forward by
+//! [`recover_compactions`], which [`compact_output`] runs first.
+
+use anyhow::{bail, Context, Result};
+use arrow::datatypes::Schema;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::arrow_writer::ArrowWriter;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+use casparian_protocol::safe_output_id;
+
+use crate::output_filename;
+
+/// Directory under a Parquet output directory holding compaction journals.
+pub const COMPACTION_DIR: &str = ".compaction";
+
+/// Size of a compacted file unless the caller sets one.
+pub const DEFAULT_TARGET_FILE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Rows per row group of a compacted file.
+pub const DEFAULT_ROW_GROUP_ROWS: usize = 1024 * 1024;
+
+/// How files are picked and written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionOptions {
+    /// Files below this size are compacted
+    pub small_file_bytes: u64,
+    /// A compacted file holds at most this many bytes of sources
+    pub target_file_bytes: u64,
+    pub row_group_rows: usize,
+}
+
+impl CompactionOptions {
+    /// Options for compacted files of `target_file_bytes`; files below a
+    /// quarter of that count as small.
+    pub fn for_target(target_file_bytes: u64) -> Self {
+        let target_file_bytes = target_file_bytes.max(1);
+        Self {
+            small_file_bytes: (target_file_bytes / 4).max(1),
+            target_file_bytes,
+            row_group_rows: DEFAULT_ROW_GROUP_ROWS,
+        }
+    }
+}
+
+impl Default for CompactionOptions {
+    fn default() -> Self {
+        Self::for_target(DEFAULT_TARGET_FILE_BYTES)
+    }
+}
+
+/// A small file to compact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompactionSource {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub rows: u64,
+}
+
+/// Sources that become one compacted file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompactionGroup {
+    pub target: PathBuf,
+    pub sources: Vec<CompactionSource>,
+}
+
+impl CompactionGroup {
+    pub fn bytes(&self) -> u64 {
+        self.sources.iter().map(|source| source.bytes).sum()
+    }
+
+    pub fn rows(&self) -> u64 {
+        self.sources.iter().map(|source| source.rows).sum()
+    }
+}
+
+/// A compacted file now in place of its sources.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompactedFile {
+    pub path: PathBuf,
+    pub sources: Vec<CompactionSource>,
+    pub rows: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CompactionJournal {
+    target: PathBuf,
+    temp: PathBuf,
+    sources: Vec<PathBuf>,
+}
+
+struct SmallFile {
+    source: CompactionSource,
+    dir: PathBuf,
+    schema: Arc<Schema>,
+    modified: Option<SystemTime>,
+}
+
+/// Group the small files of `output_name` under `output_dir` (partition
+/// directories included). Groups have at least two files; files are taken
+/// oldest first.
+pub fn plan_compaction(
+    output_dir: &Path,
+    output_name: &str,
+    options: &CompactionOptions,
+) -> Result<Vec<CompactionGroup>> {
+    let prefix = format!("{}_", safe_output_id(output_name));
+    let mut files = Vec::new();
+    collect_small_files(output_dir, &prefix, options.small_file_bytes, &mut files)?;
+    files.sort_by(|a, b| {
+        (&a.dir, a.modified, &a.source.path).cmp(&(&b.dir, b.modified, &b.source.path))
+    });
+
+    // Runs of one directory and schema, cut at the target size
+    let mut runs: Vec<(PathBuf, Arc<Schema>, Vec<Vec<CompactionSource>>)> = Vec::new();
+    for file in files {
+        let run = match runs
+            .iter_mut()
+            .find(|(dir, schema, _)| *dir == file.dir && schema.fields() == file.schema.fields())
+        {
+            Some(run) => run,
+            None => {
+                runs.push((file.dir.clone(), file.schema.clone(), vec![Vec::new()]));
+                runs.last_mut().expect("pushed above")
+            }
+        };
+        let current = run.2.last_mut().expect("runs start with a group");
+        let bytes: u64 = current.iter().map(|source| source.bytes).sum();
+        if !current.is_empty() && bytes + file.source.bytes > options.target_file_bytes {
+            run.2.push(vec![file.source]);
+        } else {
+            current.push(file.source);
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (dir, _, chunks) in runs {
+        for sources in chunks {
+            if sources.len() < 2 {
+                continue;
+            }
+            let names: Vec<String> = sources
+                .iter()
+                .map(|source| file_name(&source.path))
+                .collect();
+            let key = format!("compaction:{}", names.join(","));
+            groups.push(CompactionGroup {
+                target: dir.join(output_filename(output_name, &key, "parquet")),
+                sources,
+            });
+        }
+    }
+    Ok(groups)
+}
+
+fn collect_small_files(
+    dir: &Path,
+    prefix: &str,
+    small_file_bytes: u64,
+    files: &mut Vec<SmallFile>,
+) -> Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to list {}", dir.display()));
+        }
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Checkpoints and journals live in hidden directories
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            collect_small_files(&path, prefix, small_file_bytes, files)?;
+            continue;
+        }
+        if !is_output_file(&name, prefix) || metadata.len() >= small_file_bytes {
+            continue;
+        }
+        let builder = match File::open(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| Ok(ParquetRecordBatchReaderBuilder::try_new(file)?))
+        {
+            Ok(builder) => builder,
+            Err(err) => {
+                warn!(
+                    "Skipping unreadable Parquet file {}: {}",
+                    path.display(),
+                    err
+                );
+                continue;
+            }
+        };
+        let rows = builder.metadata().file_metadata().num_rows().max(0) as u64;
+        files.push(SmallFile {
+            source: CompactionSource {
+                path,
+                bytes: metadata.len(),
+                rows,
+            },
+            dir: dir.to_path_buf(),
+            schema: builder.schema().clone(),
+            modified: metadata.modified().ok(),
+        });
+    }
+    Ok(())
+}
+
+/// Write one group into its compacted file and swap it in for the sources.
+pub fn compact_group(
+    output_dir: &Path,
+    group: &CompactionGroup,
+    options: &CompactionOptions,
+) -> Result<CompactedFile> {
+    let Some(first) = group.sources.first() else {
+        bail!("compaction group has no sources");
+    };
+    let journal_dir = output_dir.join(COMPACTION_DIR);
+    std::fs::create_dir_all(&journal_dir)
+        .with_context(|| format!("Failed to create {}", journal_dir.display()))?;
+    let target_name = file_name(&group.target);
+    let temp = journal_dir.join(format!("{}.tmp", target_name));
+
+    let schema = ParquetRecordBatchReaderBuilder::try_new(File::open(&first.path)?)?
+        .schema()
+        .clone();
+    let file =
+        File::create(&temp).with_context(|| format!("Failed to create {}", temp.display()))?;
+    let props = parquet::file::properties::WriterProperties::builder()
+        .set_compression(parquet::basic::Compression::SNAPPY)
+        .set_max_row_group_size(options.row_group_rows.max(1))
+        .build();
+    let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(props))
+        .context("Failed to create Parquet writer")?;
+    let mut rows = 0u64;
+    for source in &group.sources {
+        let file = File::open(&source.path)
+            .with_context(|| format!("Failed to open {}", source.path.display()))?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        if builder.schema().fields() != schema.fields() {
+            let _ = std::fs::remove_file(&temp);
+            bail!("{} has a different schema", source.path.display());
+        }
+        for batch in builder.build()? {
+            let batch = batch?;
+            rows += batch.num_rows() as u64;
+            writer
+                .write(&batch)
+                .context("Failed to write batch to Parquet")?;
+        }
+    }
+    let file = writer
+        .into_inner()
+        .context("Failed to finish compacted Parquet file")?;
+    file.sync_all()?;
+    if rows != group.rows() {
+        let _ = std::fs::remove_file(&temp);
+        bail!(
+            "compacted {} rows but the sources hold {}",
+            rows,
+            group.rows()
+        );
+    }
+    let bytes = std::fs::metadata(&temp)?.len();
+
+    let journal = CompactionJournal {
+        target: group.target.clone(),
+        temp,
+        sources: group.sources.iter().map(|s| s.path.clone()).collect(),
+    };
+    let journal_path = journal_dir.join(format!("{}.json", target_name));
+    let journal_temp = journal_dir.join(format!(".{}.json.tmp", target_name));
+    std::fs::write(&journal_temp, serde_json::to_string(&journal)?)
+        .with_context(|| format!("Failed to write {}", journal_temp.display()))?;
+    std::fs::rename(&journal_temp, &journal_path)
+        .with_context(|| format!("Failed to write {}", journal_path.display()))?;
+    apply_journal(&journal_path, &journal)?;
+
+    info!(
+        "Compacted {} files ({} rows) into {}",
+        group.sources.len(),
+        rows,
+        group.target.display()
+    );
+    Ok(CompactedFile {
+        path: group.target.clone(),
+        sources: group.sources.clone(),
+        rows,
+        bytes,
+    })
+}
+
+/// Move the compacted file into place, remove its sources and the journal.
+fn apply_journal(journal_path: &Path, journal: &CompactionJournal) -> Result<()> {
+    if journal.temp.exists() {
+        std::fs::rename(&journal.temp, &journal.target)
+            .with_context(|| format!("Failed to move {} into place", journal.target.display()))?;
+    }
+    for source in &journal.sources {
+        match std::fs::remove_file(source) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to remove {}", source.display()));
+            }
+        }
+    }
+    std::fs::remove_file(journal_path)
+        .with_context(|| format!("Failed to remove {}", journal_path.display()))?;
+    Ok(())
+}
+
+/// Finish swaps interrupted after their journal was written and drop
+/// compacted files that never got one. Returns the swaps finished.
+pub fn recover_compactions(output_dir: &Path) -> Result<usize> {
+    let journal_dir = output_dir.join(COMPACTION_DIR);
+    let entries = match std::fs::read_dir(&journal_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to list {}", journal_dir.display()));
+        }
+    };
+    let mut journals = Vec::new();
+    let mut temps = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let name = file_name(&path);
+        if name.ends_with(".json") && !name.starts_with('.') {
+            journals.push(path);
+        } else {
+            temps.push(path);
+        }
+    }
+
+    let mut finished = 0;
+    let mut pending_temps = Vec::new();
+    for path in journals {
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let journal: CompactionJournal = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        pending_temps.push(journal.temp.clone());
+        apply_journal(&path, &journal)?;
+        warn!(
+            "Finished interrupted compaction into {}",
+            journal.target.display()
+        );
+        finished += 1;
+    }
+    for temp in temps {
+        if pending_temps.contains(&temp) {
+            continue;
+        }
+        if let Err(err) = std::fs::remove_file(&temp) {
+            warn!("Failed to remove {}: {}", temp.display(), err);
+        }
+    }
+    let _ = std::fs::remove_dir(&journal_dir);
+    Ok(finished)
+}
+
+/// Recover interrupted swaps, then compact every group of `output_name`.
+pub fn compact_output(
+    output_dir: &Path,
+    output_name: &str,
+    options: &CompactionOptions,
+) -> Result<Vec<CompactedFile>> {
+    recover_compactions(output_dir)?;
+    let mut compacted = Vec::new();
+    for group in plan_compaction(output_dir, output_name, options)? {
+        compacted.push(compact_group(output_dir, &group, options)?);
+    }
+    let _ = std::fs::remove_dir(output_dir.join(COMPACTION_DIR));
+    Ok(compacted)
+}
+
+/// `{prefix}{16 hex}.parquet`, as written by `output_filename`. A longer
+/// output name sharing the prefix does not match.
+fn is_output_file(name: &str, prefix: &str) -> bool {
+    name.strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix(".parquet"))
+        .is_some_and(|hex| hex.len() == 16 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field};
+    use tempfile::tempdir;
+
+    fn write_file(dir: &Path, output: &str, job: &str, ids: std::ops::Range<i64>) -> PathBuf {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from_iter_values(ids))],
+        )
+        .unwrap();
+        let path = dir.join(output_filename(output, job, "parquet"));
+        let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        path
+    }
+
+    fn read_ids(path: &Path) -> Vec<i64> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut ids: Vec<i64> = reader
+            .flat_map(|batch| {
+                let batch = batch.unwrap();
+                batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_compacts_small_files_of_one_output() {
+        let dir = tempdir().unwrap();
+        let sources: Vec<PathBuf> = (0..3)
+            .map(|i| {
+                write_file(
+                    dir.path(),
+                    "trades",
+                    &format!("job-{}", i),
+                    i * 10..i * 10 + 10,
+                )
+            })
+            .collect();
+        let other = write_file(dir.path(), "quotes", "job-9", 0..5);
+        let longer = write_file(dir.path(), "trades_daily", "job-8", 0..5);
+
+        let compacted =
+            compact_output(dir.path(), "trades", &CompactionOptions::default()).unwrap();
+        assert_eq!(compacted.len(), 1);
+        let file = &compacted[0];
+        assert_eq!(file.rows, 30);
+        assert_eq!(file.sources.len(), 3);
+        assert!(file_name(&file.path).starts_with("trades_"));
+        assert_eq!(read_ids(&file.path), (0..30).collect::<Vec<_>>());
+        assert!(sources.iter().all(|source| !source.exists()));
+        assert!(other.exists() && longer.exists());
+        assert!(!dir.path().join(COMPACTION_DIR).exists());
+
+        // A lone file is left as it is
+        let again = compact_output(dir.path(), "trades", &CompactionOptions::default()).unwrap();
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn test_recover_finishes_journaled_swap() {
+        let dir = tempdir().unwrap();
+        let a = write_file(dir.path(), "trades", "job-1", 0..4);
+        let b = write_file(dir.path(), "trades", "job-2", 4..8);
+        let group = plan_compaction(dir.path(), "trades", &CompactionOptions::default())
+            .unwrap()
+            .remove(0);
+
+        // Crash after the journal: the compacted file is still the temp file
+        let journal_dir = dir.path().join(COMPACTION_DIR);
+        std::fs::create_dir_all(&journal_dir).unwrap();
+        let temp = journal_dir.join("merged.tmp");
+        std::fs::copy(&a, &temp).unwrap();
+        let journal = CompactionJournal {
+            target: group.target.clone(),
+            temp,
+            sources: vec![a.clone(), b.clone()],
+        };
+        std::fs::write(
+            journal_dir.join("merged.json"),
+            serde_json::to_string(&journal).unwrap(),
+        )
+        .unwrap();
+        // A temp file without a journal is an abandoned write
+        std::fs::write(journal_dir.join("stray.tmp"), b"partial").unwrap();
+
+        assert_eq!(recover_compactions(dir.path()).unwrap(), 1);
+        assert!(group.target.exists());
+        assert!(!a.exists() && !b.exists());
+        assert!(!journal_dir.exists());
+    }
+}
@@ -0,0 +1,637 @@
+//! Parallel encoding of Parquet and CSV output.
+//!
+//! By default a sink encodes on the thread that writes its batches. When a
+//! job's outputs allow more than one encode thread
+//! ([`OutputPlan::with_encode_parallelism`](crate::OutputPlan::with_encode_parallelism)),
+//! its Parquet and CSV sinks share one [`EncodePool`] of that size:
+//!
+//! - Parquet buffers batches up to a row group (the writer's row group size,
+//!   or [`DEFAULT_BUFFER_BYTES`] of batches), then encodes the row group's
+//!   columns in parallel.
+//! - CSV buffers one batch per thread (or [`DEFAULT_BUFFER_BYTES`]) and
+//!   encodes the batches in parallel, keeping their order.
+//!
+//! Encoded data is written by a per-file writer thread through a channel
+//! that holds one chunk, so the next chunk is encoded while the previous one
+//! is written, and at most one encoded chunk waits besides the one being
+//! written. The files read back the same as serially written ones.
+//!
+//! Every file sink accounts the time it spends encoding and writing in
+//! [`WriteTimings`], reported on each [`OutputArtifact`](crate::OutputArtifact).
+
+use anyhow::{anyhow, Context, Result};
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use parquet::arrow::arrow_writer::{
+    compute_leaves, ArrowColumnChunk, ArrowLeafColumn, ArrowRowGroupWriterFactory, ArrowWriter,
+};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Bytes of batches a parallel writer buffers before encoding them.
+pub const DEFAULT_BUFFER_BYTES: usize = 128 * 1024 * 1024;
+
+/// Thread pool shared by the sinks of one job.
+#[derive(Clone)]
+pub struct EncodePool {
+    pool: Arc<rayon::ThreadPool>,
+}
+
+impl EncodePool {
+    pub fn new(threads: usize) -> Result<Self> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .thread_name(|index| format!("sink-encode-{}", index))
+            .build()
+            .context("Failed to start sink encode threads")?;
+        Ok(Self {
+            pool: Arc::new(pool),
+        })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+}
+
+impl std::fmt::Debug for EncodePool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncodePool")
+            .field("threads", &self.threads())
+            .finish()
+    }
+}
+
+/// Time one output spent encoding and writing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteTimings {
+    pub encode_ms: u64,
+    pub io_ms: u64,
+}
+
+/// Running encode and IO time of one sink, updated from its writer threads.
+#[derive(Debug, Default)]
+pub(crate) struct TimingCounters {
+    encode_nanos: AtomicU64,
+    io_nanos: AtomicU64,
+}
+
+impl TimingCounters {
+    pub(crate) fn snapshot(&self) -> WriteTimings {
+        let millis = |nanos: &AtomicU64| nanos.load(Ordering::Relaxed) / 1_000_000;
+        WriteTimings {
+            encode_ms: millis(&self.encode_nanos),
+            io_ms: millis(&self.io_nanos),
+        }
+    }
+
+    fn add_encode(&self, elapsed: Duration) {
+        self.encode_nanos
+            .fetch_add(nanos(elapsed), Ordering::Relaxed);
+    }
+
+    fn add_io(&self, elapsed: Duration) {
+        self.io_nanos.fetch_add(nanos(elapsed), Ordering::Relaxed);
+    }
+
+    /// Run `f` on this thread, counting its time minus the IO it did as
+    /// encode time.
+    fn measure<T>(&self, f: impl FnOnce() -> T) -> T {
+        let io_before = self.io_nanos.load(Ordering::Relaxed);
+        let started = Instant::now();
+        let result = f();
+        let io = self.io_nanos.load(Ordering::Relaxed) - io_before;
+        self.encode_nanos.fetch_add(
+            nanos(started.elapsed()).saturating_sub(io),
+            Ordering::Relaxed,
+        );
+        result
+    }
+}
+
+fn nanos(elapsed: Duration) -> u64 {
+    elapsed.as_nanos().min(u64::MAX as u128) as u64
+}
+
+/// Writer that counts the time spent in `write` and `flush` as IO time.
+pub(crate) struct TimedWrite<W> {
+    inner: W,
+    timings: Arc<TimingCounters>,
+}
+
+impl<W: Write> TimedWrite<W> {
+    pub(crate) fn new(inner: W, timings: Arc<TimingCounters>) -> Self {
+        Self { inner, timings }
+    }
+}
+
+impl<W: Write> Write for TimedWrite<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let started = Instant::now();
+        let written = self.inner.write(buf);
+        self.timings.add_io(started.elapsed());
+        written
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let started = Instant::now();
+        let flushed = self.inner.flush();
+        self.timings.add_io(started.elapsed());
+        flushed
+    }
+}
+
+/// Writes the chunks a sink encodes, in order, on its own thread.
+///
+/// The channel holds one chunk, which bounds how far encoding runs ahead of
+/// IO. Dropped without [`finish`](Self::finish), the thread stops without
+/// closing its output.
+struct WriterThread<T> {
+    sender: Option<SyncSender<Option<T>>>,
+    handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl<T: Send + 'static> WriterThread<T> {
+    fn spawn<S: Send + 'static>(
+        mut state: S,
+        mut write: impl FnMut(&mut S, T) -> Result<()> + Send + 'static,
+        close: impl FnOnce(S) -> Result<()> + Send + 'static,
+    ) -> Result<Self> {
+        let (sender, receiver) = sync_channel::<Option<T>>(1);
+        let handle = std::thread::Builder::new()
+            .name("sink-writer".to_string())
+            .spawn(move || {
+                for message in receiver {
+                    match message {
+                        Some(chunk) => write(&mut state, chunk)?,
+                        None => return close(state),
+                    }
+                }
+                Ok(())
+            })
+            .context("Failed to start sink writer thread")?;
+        Ok(Self {
+            sender: Some(sender),
+            handle: Some(handle),
+        })
+    }
+
+    fn send(&mut self, chunk: T) -> Result<()> {
+        let sender = self
+            .sender
+            .as_ref()
+            .ok_or_else(|| anyhow!("Sink writer already finished"))?;
+        if sender.send(Some(chunk)).is_err() {
+            // The thread stopped on a write error; report it.
+            self.join()?;
+            return Err(anyhow!("Sink writer thread stopped"));
+        }
+        Ok(())
+    }
+
+    /// Close the output once every chunk is written.
+    fn finish(mut self) -> Result<()> {
+        if let Some(sender) = self.sender.as_ref() {
+            // A failed send means the thread already stopped; join reports why.
+            let _ = sender.send(None);
+        }
+        self.join()
+    }
+}
+
+impl<T> WriterThread<T> {
+    fn join(&mut self) -> Result<()> {
+        drop(self.sender.take());
+        match self.handle.take() {
+            Some(handle) => handle
+                .join()
+                .map_err(|_| anyhow!("Sink writer thread panicked"))?,
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> Drop for WriterThread<T> {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+/// Parquet file writer, encoding serially or on an [`EncodePool`].
+pub(crate) enum ParquetFileWriter {
+    Serial {
+        writer: Box<ArrowWriter<TimedWrite<File>>>,
+        timings: Arc<TimingCounters>,
+    },
+    Parallel(Box<ParallelParquetWriter>),
+}
+
+impl ParquetFileWriter {
+    pub(crate) fn new(
+        file: File,
+        schema: SchemaRef,
+        props: WriterProperties,
+        pool: Option<&EncodePool>,
+        timings: &Arc<TimingCounters>,
+    ) -> Result<Self> {
+        let row_group_rows = props.max_row_group_size();
+        let writer = ArrowWriter::try_new(
+            TimedWrite::new(file, timings.clone()),
+            schema.clone(),
+            Some(props),
+        )
+        .context("Failed to create Parquet writer")?;
+        match pool {
+            Some(pool) if pool.threads() > 1 => {
+                let (file_writer, factory) = writer
+                    .into_serialized_writer()
+                    .context("Failed to create Parquet writer")?;
+                Ok(Self::Parallel(Box::new(ParallelParquetWriter::new(
+                    file_writer,
+                    factory,
+                    schema,
+                    pool.clone(),
+                    row_group_rows,
+                    timings.clone(),
+                )?)))
+            }
+            _ => Ok(Self::Serial {
+                writer: Box::new(writer),
+                timings: timings.clone(),
+            }),
+        }
+    }
+
+    pub(crate) fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self {
+            Self::Serial { writer, timings } => timings
+                .measure(|| writer.write(batch))
+                .context("Failed to write batch to Parquet"),
+            Self::Parallel(writer) => writer.write(batch),
+        }
+    }
+
+    pub(crate) fn close(self) -> Result<()> {
+        match self {
+            Self::Serial { writer, timings } => {
+                timings
+                    .measure(|| (*writer).close())
+                    .context("Failed to close Parquet writer")?;
+                Ok(())
+            }
+            Self::Parallel(writer) => writer.close(),
+        }
+    }
+}
+
+/// Parquet writer that encodes the columns of each row group in parallel.
+pub(crate) struct ParallelParquetWriter {
+    schema: SchemaRef,
+    factory: ArrowRowGroupWriterFactory,
+    pool: EncodePool,
+    row_group_rows: usize,
+    pending: Vec<RecordBatch>,
+    pending_rows: usize,
+    pending_bytes: usize,
+    row_groups: usize,
+    writer: WriterThread<Vec<ArrowColumnChunk>>,
+    timings: Arc<TimingCounters>,
+}
+
+impl ParallelParquetWriter {
+    fn new(
+        file_writer: SerializedFileWriter<TimedWrite<File>>,
+        factory: ArrowRowGroupWriterFactory,
+        schema: SchemaRef,
+        pool: EncodePool,
+        row_group_rows: usize,
+        timings: Arc<TimingCounters>,
+    ) -> Result<Self> {
+        let writer = WriterThread::spawn(
+            file_writer,
+            |file_writer, chunks: Vec<ArrowColumnChunk>| {
+                let mut row_group = file_writer.next_row_group()?;
+                for chunk in chunks {
+                    chunk.append_to_row_group(&mut row_group)?;
+                }
+                row_group
+                    .close()
+                    .context("Failed to write Parquet row group")?;
+                Ok(())
+            },
+            |file_writer| {
+                file_writer
+                    .close()
+                    .context("Failed to close Parquet writer")?;
+                Ok(())
+            },
+        )?;
+        Ok(Self {
+            schema,
+            factory,
+            pool,
+            row_group_rows: row_group_rows.max(1),
+            pending: Vec::new(),
+            pending_rows: 0,
+            pending_bytes: 0,
+            row_groups: 0,
+            writer,
+            timings,
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        let rows = batch.num_rows();
+        let bytes_per_row = batch.get_array_memory_size() / rows.max(1);
+        let mut offset = 0;
+        while offset < rows {
+            let take = (self.row_group_rows - self.pending_rows).min(rows - offset);
+            self.pending.push(batch.slice(offset, take));
+            self.pending_rows += take;
+            self.pending_bytes += take * bytes_per_row;
+            offset += take;
+            if self.pending_rows >= self.row_group_rows
+                || self.pending_bytes >= DEFAULT_BUFFER_BYTES
+            {
+                self.flush_row_group()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode the buffered rows as one row group and hand it to the writer.
+    fn flush_row_group(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let started = Instant::now();
+        let writers = self.factory.create_column_writers(self.row_groups)?;
+        let mut leaves: Vec<Vec<ArrowLeafColumn>> = writers.iter().map(|_| Vec::new()).collect();
+        for batch in self.pending.drain(..) {
+            let mut column_leaves = leaves.iter_mut();
+            for (field, column) in self.schema.fields().iter().zip(batch.columns()) {
+                for leaf in compute_leaves(field, column)? {
+                    column_leaves
+                        .next()
+                        .ok_or_else(|| anyhow!("Batch has more columns than the Parquet schema"))?
+                        .push(leaf);
+                }
+            }
+        }
+        let chunks = self.pool.pool.install(|| {
+            writers
+                .into_par_iter()
+                .zip(leaves)
+                .map(|(mut writer, leaves)| {
+                    for leaf in &leaves {
+                        writer.write(leaf)?;
+                    }
+                    writer.close()
+                })
+                .collect::<parquet::errors::Result<Vec<_>>>()
+        });
+        let chunks = chunks.context("Failed to encode Parquet row group")?;
+        self.timings.add_encode(started.elapsed());
+
+        self.pending_rows = 0;
+        self.pending_bytes = 0;
+        self.row_groups += 1;
+        self.writer.send(chunks)
+    }
+
+    fn close(mut self) -> Result<()> {
+        self.flush_row_group()?;
+        self.writer.finish()
+    }
+}
+
+/// CSV file writer, encoding serially or on an [`EncodePool`].
+pub(crate) enum CsvFileWriter {
+    Serial {
+        writer: Box<arrow::csv::Writer<TimedWrite<File>>>,
+        timings: Arc<TimingCounters>,
+    },
+    Parallel(ParallelCsvWriter),
+}
+
+impl CsvFileWriter {
+    pub(crate) fn new(
+        file: File,
+        pool: Option<&EncodePool>,
+        timings: &Arc<TimingCounters>,
+    ) -> Result<Self> {
+        match pool {
+            Some(pool) if pool.threads() > 1 => Ok(Self::Parallel(ParallelCsvWriter::new(
+                file,
+                pool.clone(),
+                timings.clone(),
+            )?)),
+            _ => Ok(Self::Serial {
+                writer: Box::new(
+                    arrow::csv::WriterBuilder::new()
+                        .with_header(true)
+                        .build(TimedWrite::new(file, timings.clone())),
+                ),
+                timings: timings.clone(),
+            }),
+        }
+    }
+
+    pub(crate) fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self {
+            Self::Serial { writer, timings } => timings
+                .measure(|| writer.write(batch))
+                .context("Failed to write batch to CSV"),
+            Self::Parallel(writer) => writer.write(batch),
+        }
+    }
+
+    pub(crate) fn close(self) -> Result<()> {
+        match self {
+            Self::Serial { writer, .. } => {
+                let mut file = (*writer).into_inner();
+                file.flush().context("Failed to flush CSV file")
+            }
+            Self::Parallel(writer) => writer.close(),
+        }
+    }
+}
+
+/// CSV writer that encodes buffered batches in parallel.
+pub(crate) struct ParallelCsvWriter {
+    pool: EncodePool,
+    pending: Vec<RecordBatch>,
+    pending_bytes: usize,
+    header_written: bool,
+    writer: WriterThread<Vec<Vec<u8>>>,
+    timings: Arc<TimingCounters>,
+}
+
+impl ParallelCsvWriter {
+    fn new(file: File, pool: EncodePool, timings: Arc<TimingCounters>) -> Result<Self> {
+        let writer = WriterThread::spawn(
+            TimedWrite::new(file, timings.clone()),
+            |file, chunks: Vec<Vec<u8>>| {
+                for chunk in chunks {
+                    file.write_all(&chunk)
+                        .context("Failed to write batch to CSV")?;
+                }
+                Ok(())
+            },
+            |mut file| file.flush().context("Failed to flush CSV file"),
+        )?;
+        Ok(Self {
+            pool,
+            pending: Vec::new(),
+            pending_bytes: 0,
+            header_written: false,
+            writer,
+            timings,
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.pending_bytes += batch.get_array_memory_size();
+        self.pending.push(batch.clone());
+        if self.pending.len() >= self.pool.threads() || self.pending_bytes >= DEFAULT_BUFFER_BYTES {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let started = Instant::now();
+        let with_header = !self.header_written;
+        let pending = std::mem::take(&mut self.pending);
+        let chunks = self.pool.pool.install(|| {
+            pending
+                .par_iter()
+                .enumerate()
+                .map(|(index, batch)| encode_csv(batch, with_header && index == 0))
+                .collect::<Result<Vec<_>>>()
+        })?;
+        self.timings.add_encode(started.elapsed());
+
+        self.header_written = true;
+        self.pending_bytes = 0;
+        self.writer.send(chunks)
+    }
+
+    fn close(mut self) -> Result<()> {
+        self.flush()?;
+        self.writer.finish()
+    }
+}
+
+fn encode_csv(batch: &RecordBatch, with_header: bool) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let mut writer = arrow::csv::WriterBuilder::new()
+        .with_header(with_header)
+        .build(&mut buf);
+    writer
+        .write(batch)
+        .context("Failed to write batch to CSV")?;
+    drop(writer);
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    fn batch(start: i64, rows: i64) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let ids: Vec<i64> = (start..start + rows).collect();
+        let names: Vec<Option<String>> = ids
+            .iter()
+            .map(|id| (id % 3 != 0).then(|| format!("row-{}", id)))
+            .collect();
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parallel_parquet_matches_serial() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = EncodePool::new(4).unwrap();
+        let batches = [batch(0, 700), batch(700, 0), batch(700, 900)];
+        let props = || {
+            WriterProperties::builder()
+                .set_max_row_group_size(500)
+                .build()
+        };
+
+        let mut read_back = Vec::new();
+        for (name, pool) in [("serial", None), ("parallel", Some(&pool))] {
+            let path = dir.path().join(format!("{}.parquet", name));
+            let timings = Arc::new(TimingCounters::default());
+            let mut writer = ParquetFileWriter::new(
+                File::create(&path).unwrap(),
+                batches[0].schema(),
+                props(),
+                pool,
+                &timings,
+            )
+            .unwrap();
+            for batch in &batches {
+                writer.write(batch).unwrap();
+            }
+            writer.close().unwrap();
+
+            let builder =
+                ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+            assert_eq!(builder.metadata().num_row_groups(), 4);
+            let rows: Vec<RecordBatch> = builder.build().unwrap().map(Result::unwrap).collect();
+            read_back.push(arrow::compute::concat_batches(&batches[0].schema(), &rows).unwrap());
+        }
+        assert_eq!(read_back[0], read_back[1]);
+        assert_eq!(read_back[1].num_rows(), 1600);
+    }
+
+    #[test]
+    fn test_parallel_csv_matches_serial() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = EncodePool::new(3).unwrap();
+        let batches: Vec<RecordBatch> = (0..7).map(|i| batch(i * 10, 10)).collect();
+
+        let mut contents = Vec::new();
+        for (name, pool) in [("serial", None), ("parallel", Some(&pool))] {
+            let path = dir.path().join(format!("{}.csv", name));
+            let timings = Arc::new(TimingCounters::default());
+            let mut writer =
+                CsvFileWriter::new(File::create(&path).unwrap(), pool, &timings).unwrap();
+            for batch in &batches {
+                writer.write(batch).unwrap();
+            }
+            writer.close().unwrap();
+            contents.push(std::fs::read_to_string(&path).unwrap());
+        }
+        assert_eq!(contents[0], contents[1]);
+        assert!(contents[1].starts_with("id,name\n0,\n1,row-1\n"));
+        assert_eq!(contents[1].lines().count(), 71);
+    }
+}
@@ -6,23 +6,51 @@
 //! - Schema setup
 //! - Batch writing
 //! - Lineage column injection
+//! - Optional per-column statistics (see [`column_stats`])
+//! - Placeholders in sink URIs such as `{plugin}` or `{date:yyyy-MM-dd}`
+//!   (see [`template`])
+//! - Resumable Parquet writes via part-file checkpoints (see [`checkpoint`])
+//! - Compaction of small Parquet output files (see [`compaction`])
+//! - Parallel Parquet and CSV encoding with encode/IO timings (see [`encode`])
 
 use anyhow::{bail, Context, Result};
-use arrow::array::{ArrayRef, RecordBatch, StringArray};
+use arrow::array::{Array, ArrayRef, RecordBatch, StringArray};
 use arrow::datatypes::{DataType, Field, Schema};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
-use casparian_protocol::{safe_output_id, SinkMode};
+use casparian_protocol::{
+    safe_output_id, ColumnStats, QuarantineConfig, SchemaEvolution, SinkMode,
+};
 #[cfg(feature = "sink-duckdb")]
 pub use casparian_sinks_duckdb::DuckDbSink;
 
+#[cfg(feature = "sink-postgres")]
+pub use casparian_sinks_postgres::PostgresSink;
+pub mod checkpoint;
+pub mod column_stats;
+pub mod compaction;
+pub mod encode;
+pub mod quarantine;
+pub mod template;
+#[cfg(feature = "sink-s3")]
+mod s3;
+#[cfg(feature = "sink-s3")]
+pub use s3::{S3Config, S3Location, S3Sink};
+
 #[cfg(not(feature = "sink-duckdb"))]
 const DUCKDB_DISABLED: &str = "DuckDB sink support is disabled (enable feature sink-duckdb)";
 
+#[cfg(not(feature = "sink-s3"))]
+const S3_DISABLED: &str = "S3 sink support is disabled (enable feature sink-s3)";
+
+#[cfg(not(feature = "sink-postgres"))]
+const POSTGRES_DISABLED: &str =
+    "PostgreSQL sink support is disabled (enable feature sink-postgres)";
+
 fn job_prefix(job_id: &str) -> String {
     // Use a stable 16-hex blake3 digest prefix to avoid collisions
     blake3::hash(job_id.as_bytes()).to_hex()[..16].to_string()
@@ -33,6 +61,80 @@ pub fn output_filename(output_name: &str, job_id: &str, extension: &str) -> Stri
     format!("{}_{}.{}", safe_name, job_prefix(job_id), extension)
 }
 
+/// Rename a staged temp file to `final_path`.
+///
+/// A file already at `final_path` (a re-run of the same job) is moved aside
+/// first and its backup path returned, so a rollback can put it back until the
+/// commit is released.
+fn promote_staged_file(temp_path: &Path, final_path: &Path) -> Result<Option<PathBuf>> {
+    let backup_path = if final_path.is_file() {
+        let file_name = final_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let backup_path = final_path.with_file_name(format!(".{}.bak", file_name));
+        std::fs::rename(final_path, &backup_path).with_context(|| {
+            format!(
+                "Failed to back up {} -> {}",
+                final_path.display(),
+                backup_path.display()
+            )
+        })?;
+        Some(backup_path)
+    } else {
+        None
+    };
+
+    if let Err(err) = std::fs::rename(temp_path, final_path) {
+        if let Some(backup_path) = &backup_path {
+            let _ = std::fs::rename(backup_path, final_path);
+        }
+        return Err(err).with_context(|| {
+            format!(
+                "Failed to rename {} -> {}",
+                temp_path.display(),
+                final_path.display()
+            )
+        });
+    }
+    Ok(backup_path)
+}
+
+/// Undo [`promote_staged_file`]: restore the replaced file, or remove the
+/// committed one if nothing was replaced.
+fn restore_replaced_file(final_path: &Path, backup_path: Option<&Path>) {
+    match backup_path {
+        Some(backup_path) => {
+            if let Err(err) = std::fs::rename(backup_path, final_path) {
+                warn!(
+                    "Failed to restore {} from {}: {}",
+                    final_path.display(),
+                    backup_path.display(),
+                    err
+                );
+            }
+        }
+        None => {
+            if final_path.exists() {
+                let _ = std::fs::remove_file(final_path);
+            }
+        }
+    }
+}
+
+/// Delete the backup kept by [`promote_staged_file`] once the commit is final.
+fn discard_backup(backup_path: Option<PathBuf>) {
+    if let Some(backup_path) = backup_path {
+        if let Err(err) = std::fs::remove_file(&backup_path) {
+            warn!(
+                "Failed to remove replaced file backup {}: {}",
+                backup_path.display(),
+                err
+            );
+        }
+    }
+}
+
 /// Errors returned by sink planning and writing.
 #[derive(Debug, Error)]
 pub enum SinkError {
@@ -118,6 +220,12 @@ pub struct OutputPlan {
     table: Option<String>,
     batches: Vec<OutputBatch>,
     sink_mode: SinkMode,
+    schema_evolution: SchemaEvolution,
+    quarantine: Option<QuarantineConfig>,
+    column_stats: bool,
+    max_null_pct: Option<f64>,
+    checkpoint_key: Option<String>,
+    encode_parallelism: usize,
 }
 
 impl OutputPlan {
@@ -132,9 +240,61 @@ impl OutputPlan {
             table,
             batches,
             sink_mode,
+            schema_evolution: SchemaEvolution::default(),
+            quarantine: None,
+            column_stats: false,
+            max_null_pct: None,
+            checkpoint_key: None,
+            encode_parallelism: 1,
         }
     }
 
+    /// Override how the output treats existing data at its sink.
+    pub fn with_sink_mode(mut self, sink_mode: SinkMode) -> Self {
+        self.sink_mode = sink_mode;
+        self
+    }
+
+    /// Column reconciliation when appending to an existing table.
+    pub fn with_schema_evolution(mut self, schema_evolution: SchemaEvolution) -> Self {
+        self.schema_evolution = schema_evolution;
+        self
+    }
+
+    /// Quarantine schema-violating rows instead of failing the write.
+    ///
+    /// See [`quarantine`] for how rows are split and written.
+    pub fn with_quarantine(mut self, config: QuarantineConfig) -> Self {
+        self.quarantine = Some(config);
+        self
+    }
+
+    /// Collect per-column statistics while the output is written.
+    ///
+    /// See [`column_stats`]; the stats are reported on the [`OutputArtifact`].
+    pub fn with_column_stats(mut self) -> Self {
+        self.column_stats = true;
+        self
+    }
+
+    /// Checkpoint Parquet writes under this key so a retry of the same
+    /// materialization resumes from the last complete part.
+    ///
+    /// See [`checkpoint`]; other sinks ignore the key.
+    pub fn with_checkpoint(mut self, key: impl Into<String>) -> Self {
+        self.checkpoint_key = Some(key.into());
+        self
+    }
+
+    /// Let Parquet and CSV sinks encode on up to `threads` threads.
+    ///
+    /// The job's sinks share one pool sized to the largest budget of its
+    /// outputs; see [`encode`].
+    pub fn with_encode_parallelism(mut self, threads: usize) -> Self {
+        self.encode_parallelism = threads.max(1);
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -150,12 +310,49 @@ impl OutputPlan {
     pub fn sink_mode(&self) -> SinkMode {
         self.sink_mode
     }
+
+    pub fn checkpoint_key(&self) -> Option<&str> {
+        self.checkpoint_key.as_deref()
+    }
+
+    pub fn encode_parallelism(&self) -> usize {
+        self.encode_parallelism
+    }
+
+    pub fn schema_evolution(&self) -> SchemaEvolution {
+        self.schema_evolution
+    }
+
+    pub fn quarantine_config(&self) -> Option<&QuarantineConfig> {
+        self.quarantine.as_ref()
+    }
+
+    pub fn collects_column_stats(&self) -> bool {
+        self.column_stats
+            || self
+                .quarantine
+                .as_ref()
+                .is_some_and(QuarantineConfig::collects_column_stats)
+    }
+
+    /// Null-percentage limit per column, from the quarantine config.
+    pub fn max_null_pct(&self) -> Option<f64> {
+        self.max_null_pct.or_else(|| {
+            self.quarantine
+                .as_ref()
+                .and_then(|config| config.max_null_pct)
+        })
+    }
 }
 
 pub struct OutputArtifact {
     pub name: String,
     pub uri: String,
     pub rows: u64,
+    /// Per-column statistics; empty unless the plan collects them.
+    pub column_stats: Vec<ColumnStats>,
+    /// Encode and IO time, for Parquet and CSV sinks.
+    pub write_timings: Option<encode::WriteTimings>,
 }
 
 pub fn plan_outputs(
@@ -198,21 +395,44 @@ pub fn plan_outputs(
         .collect())
 }
 
+/// DuckDB sink settings from the output plan and the sink URI.
+#[cfg_attr(not(feature = "sink-duckdb"), allow(dead_code))]
+struct DuckDbSinkOptions {
+    schema_evolution: SchemaEvolution,
+    /// `keep_versions=N`: previous table versions a Replace commit keeps.
+    keep_versions: usize,
+    /// `key=col1,col2`: key columns for Upsert commits.
+    upsert_keys: Vec<String>,
+}
+
+impl DuckDbSinkOptions {
+    fn from_uri(
+        parsed: &casparian_protocol::types::ParsedSinkUri,
+        schema_evolution: SchemaEvolution,
+    ) -> Result<Self> {
+        Ok(Self {
+            schema_evolution,
+            keep_versions: keep_versions(parsed)?,
+            upsert_keys: upsert_keys(parsed),
+        })
+    }
+}
+
 #[cfg(feature = "sink-duckdb")]
 fn create_duckdb_sink(
     db_path: PathBuf,
     table_name: &str,
     sink_mode: SinkMode,
+    options: DuckDbSinkOptions,
     job_id: &str,
     output_name: &str,
 ) -> Result<Sink> {
-    Ok(Sink::DuckDb(DuckDbSink::new(
-        db_path,
-        table_name,
-        sink_mode,
-        job_id,
-        output_name,
-    )?))
+    Ok(Sink::DuckDb(
+        DuckDbSink::new(db_path, table_name, sink_mode, job_id, output_name)?
+            .with_schema_evolution(options.schema_evolution)
+            .with_versions(options.keep_versions)
+            .with_upsert_keys(options.upsert_keys),
+    ))
 }
 
 #[cfg(not(feature = "sink-duckdb"))]
@@ -220,6 +440,7 @@ fn create_duckdb_sink(
     _db_path: PathBuf,
     _table_name: &str,
     _sink_mode: SinkMode,
+    _options: DuckDbSinkOptions,
     _job_id: &str,
     _output_name: &str,
 ) -> Result<Sink> {
@@ -236,6 +457,92 @@ fn duckdb_artifact_uri(_path: &Path, _table_name: &str) -> SinkResult<String> {
     Err(SinkError::message(DUCKDB_DISABLED))
 }
 
+#[cfg(feature = "sink-s3")]
+fn create_s3_sink(
+    parsed: &casparian_protocol::types::ParsedSinkUri,
+    output_name: &str,
+    job_id: &str,
+) -> Result<Sink> {
+    Ok(Sink::S3(Box::new(S3Sink::new(parsed, output_name, job_id)?)))
+}
+
+#[cfg(not(feature = "sink-s3"))]
+fn create_s3_sink(
+    _parsed: &casparian_protocol::types::ParsedSinkUri,
+    _output_name: &str,
+    _job_id: &str,
+) -> Result<Sink> {
+    bail!(S3_DISABLED)
+}
+
+#[cfg(feature = "sink-s3")]
+fn s3_artifact_uri(
+    parsed: &casparian_protocol::types::ParsedSinkUri,
+    output_name: &str,
+    job_id: &str,
+) -> SinkResult<String> {
+    let location = S3Location::from_parsed(parsed)?;
+    let key = location.key(&output_filename(output_name, job_id, "parquet"));
+    Ok(location.object_uri(&key))
+}
+
+#[cfg(not(feature = "sink-s3"))]
+fn s3_artifact_uri(
+    _parsed: &casparian_protocol::types::ParsedSinkUri,
+    _output_name: &str,
+    _job_id: &str,
+) -> SinkResult<String> {
+    Err(SinkError::message(S3_DISABLED))
+}
+
+#[cfg(feature = "sink-postgres")]
+fn create_postgres_sink(
+    parsed: &casparian_protocol::types::ParsedSinkUri,
+    table_name: &str,
+    sink_mode: SinkMode,
+    job_id: &str,
+    output_name: &str,
+) -> Result<Sink> {
+    let target = casparian_sinks_postgres::PostgresTarget::from_uri(
+        &parsed.path.to_string_lossy(),
+        &parsed.query,
+    )?;
+    Ok(Sink::Postgres(Box::new(PostgresSink::new(
+        &target.conn_str,
+        &target.db_schema,
+        table_name,
+        sink_mode,
+        job_id,
+        output_name,
+    )?)))
+}
+
+#[cfg(not(feature = "sink-postgres"))]
+fn create_postgres_sink(
+    _parsed: &casparian_protocol::types::ParsedSinkUri,
+    _table_name: &str,
+    _sink_mode: SinkMode,
+    _job_id: &str,
+    _output_name: &str,
+) -> Result<Sink> {
+    bail!(POSTGRES_DISABLED)
+}
+
+#[cfg(feature = "sink-postgres")]
+fn postgres_artifact_uri(path: &Path, table_name: &str) -> SinkResult<String> {
+    let path = path.to_string_lossy();
+    Ok(format!(
+        "postgres://{}?table={}",
+        casparian_sinks_postgres::redact_userinfo(&path),
+        table_name
+    ))
+}
+
+#[cfg(not(feature = "sink-postgres"))]
+fn postgres_artifact_uri(_path: &Path, _table_name: &str) -> SinkResult<String> {
+    Err(SinkError::message(POSTGRES_DISABLED))
+}
+
 pub fn artifact_uri_for_output(
     parsed_sink: &casparian_protocol::types::ParsedSinkUri,
     output_name: &str,
@@ -247,7 +554,12 @@ pub fn artifact_uri_for_output(
     let uri = match parsed_sink.scheme {
         SinkScheme::Parquet => {
             let filename = output_filename(output_name, job_id, "parquet");
-            let path = parsed_sink.path.join(filename);
+            let path = if partition_columns(parsed_sink).is_empty() {
+                parsed_sink.path.join(filename)
+            } else {
+                // One file per partition directory; point at all of them.
+                parsed_sink.path.join("**").join(filename)
+            };
             format!("file://{}", path.display())
         }
         SinkScheme::Csv => {
@@ -255,6 +567,17 @@ pub fn artifact_uri_for_output(
             let path = parsed_sink.path.join(filename);
             format!("file://{}", path.display())
         }
+        SinkScheme::Jsonl => {
+            let filename = output_filename(output_name, job_id, "jsonl");
+            let path = parsed_sink.path.join(filename);
+            format!("file://{}", path.display())
+        }
+        SinkScheme::Arrow => {
+            let extension = ArrowIpcFormat::from_uri(parsed_sink)?.extension();
+            let filename = output_filename(output_name, job_id, extension);
+            let path = parsed_sink.path.join(filename);
+            format!("file://{}", path.display())
+        }
         SinkScheme::Duckdb => {
             let table_name = output_table.unwrap_or(output_name);
             duckdb_artifact_uri(&parsed_sink.path, table_name)?
@@ -265,6 +588,11 @@ pub fn artifact_uri_for_output(
                 .extension()
                 .and_then(|e| e.to_str())
                 .unwrap_or("parquet");
+            // `.ndjson` outputs are written by `JsonlSink`, which always uses `.jsonl`.
+            let ext = match ext {
+                "ndjson" => "jsonl",
+                other => other,
+            };
             let filename = output_filename(output_name, job_id, ext);
             let parent = parsed_sink
                 .path
@@ -273,70 +601,927 @@ pub fn artifact_uri_for_output(
             let path = parent.join(filename);
             format!("file://{}", path.display())
         }
+        SinkScheme::S3 => s3_artifact_uri(parsed_sink, output_name, job_id)?,
+        SinkScheme::Postgres => {
+            let table_name = output_table.unwrap_or(output_name);
+            postgres_artifact_uri(&parsed_sink.path, table_name)?
+        }
     };
 
     Ok(uri)
 }
 
-pub fn write_output_plan(
-    sink_uri: &str,
-    outputs: &[OutputPlan],
-    job_id: &str,
-    should_commit: Option<&dyn Fn() -> bool>,
-) -> SinkResult<Vec<OutputArtifact>> {
-    let parsed = casparian_protocol::types::ParsedSinkUri::parse(sink_uri)
-        .map_err(|e| SinkError::message(format!("Failed to parse sink URI: {}", e)))?;
-    let mut registry = SinkRegistry::new();
+/// Sink target for one output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputRoute {
+    pub uri: String,
+    /// Overrides the output's planned sink mode when set.
+    pub mode: Option<SinkMode>,
+}
+
+/// Per-output sink targets for one job, with an optional default for
+/// outputs that are not routed explicitly.
+#[derive(Debug, Clone, Default)]
+pub struct OutputRoutes {
+    default_uri: Option<String>,
+    routes: BTreeMap<String, OutputRoute>,
+    template_vars: Option<template::TemplateVars>,
+}
+
+impl OutputRoutes {
+    pub fn new(default_uri: Option<String>) -> Self {
+        Self {
+            default_uri,
+            routes: BTreeMap::new(),
+            template_vars: None,
+        }
+    }
+
+    /// Resolve sink URI placeholders with `vars` when writing.
+    pub fn with_template_vars(mut self, vars: template::TemplateVars) -> Self {
+        self.template_vars = Some(vars);
+        self
+    }
+
+    /// Send `output` to `uri`, replacing any earlier route for it.
+    pub fn route(
+        mut self,
+        output: impl Into<String>,
+        uri: impl Into<String>,
+        mode: Option<SinkMode>,
+    ) -> Self {
+        self.routes.insert(
+            output.into(),
+            OutputRoute {
+                uri: uri.into(),
+                mode,
+            },
+        );
+        self
+    }
+
+    /// Set the sink mode of an already-routed output, or of `output` at the
+    /// default sink.
+    pub fn with_mode(mut self, output: &str, mode: SinkMode) -> SinkResult<Self> {
+        if let Some(route) = self.routes.get_mut(output) {
+            route.mode = Some(mode);
+            return Ok(self);
+        }
+        let Some(uri) = self.default_uri.clone() else {
+            return Err(SinkError::message(format!(
+                "Output '{}' has a sink mode but no sink",
+                output
+            )));
+        };
+        Ok(self.route(output, uri, Some(mode)))
+    }
+
+    /// Sink target for `output`: its own route, else the default sink.
+    pub fn resolve(&self, output: &str) -> Option<OutputRoute> {
+        if let Some(route) = self.routes.get(output) {
+            return Some(route.clone());
+        }
+        self.default_uri.as_ref().map(|uri| OutputRoute {
+            uri: uri.clone(),
+            mode: None,
+        })
+    }
+
+    /// Names of explicitly routed outputs, sorted.
+    pub fn routed_outputs(&self) -> impl Iterator<Item = &str> {
+        self.routes.keys().map(String::as_str)
+    }
+}
+
+/// Write each output to its routed sink.
+///
+/// Outputs are grouped by sink URI (after placeholders are resolved), but all
+/// sinks of the job share one two-phase commit: every sink is staged and
+/// prepared before any commits, and a failure before or during commit rolls
+/// back the staged state of all of them (see [`SinkRegistry::finish_with_guard`]).
+/// Fails before writing anything if an output has no target.
+pub fn write_routed_output_plan(
+    routes: &OutputRoutes,
+    outputs: &[OutputPlan],
+    job_id: &str,
+    should_commit: Option<&dyn Fn() -> bool>,
+) -> SinkResult<Vec<OutputArtifact>> {
+    let mut grouped: BTreeMap<String, Vec<OutputPlan>> = BTreeMap::new();
+    let mut missing = Vec::new();
+    for output in outputs {
+        let Some(route) = routes.resolve(output.name()) else {
+            missing.push(output.name().to_string());
+            continue;
+        };
+        let mut plan = output.clone();
+        if let Some(mode) = route.mode {
+            plan = plan.with_sink_mode(mode);
+        }
+        let uri = match routes.template_vars.as_ref() {
+            Some(vars) => template::render_sink_uri(&route.uri, vars, output.name(), job_id)?,
+            None => route.uri,
+        };
+        grouped.entry(uri).or_default().push(plan);
+    }
+    if !missing.is_empty() {
+        return Err(SinkError::message(format!(
+            "Outputs [{}] have no sink target",
+            missing.join(", ")
+        )));
+    }
+
+    let groups: Vec<(&str, &[OutputPlan])> = grouped
+        .iter()
+        .map(|(sink_uri, plans)| (sink_uri.as_str(), plans.as_slice()))
+        .collect();
+    write_output_groups(&groups, job_id, should_commit)
+}
+
+/// Write outputs to one sink URI, committing them as a unit.
+pub fn write_output_plan(
+    sink_uri: &str,
+    outputs: &[OutputPlan],
+    job_id: &str,
+    should_commit: Option<&dyn Fn() -> bool>,
+) -> SinkResult<Vec<OutputArtifact>> {
+    write_output_groups(&[(sink_uri, outputs)], job_id, should_commit)
+}
+
+/// Stage every group into one registry, then prepare and commit all sinks
+/// together.
+fn write_output_groups(
+    groups: &[(&str, &[OutputPlan])],
+    job_id: &str,
+    should_commit: Option<&dyn Fn() -> bool>,
+) -> SinkResult<Vec<OutputArtifact>> {
+    let parallelism = groups
+        .iter()
+        .flat_map(|(_, outputs)| outputs.iter())
+        .map(OutputPlan::encode_parallelism)
+        .max()
+        .unwrap_or(1);
+    let encode_pool = if parallelism > 1 {
+        Some(encode::EncodePool::new(parallelism)?)
+    } else {
+        None
+    };
+
+    let mut registry = SinkRegistry::new();
+    let mut staged = Vec::new();
+    for (sink_uri, outputs) in groups {
+        match register_sinks(
+            &mut registry,
+            sink_uri,
+            outputs,
+            job_id,
+            encode_pool.as_ref(),
+        ) {
+            Ok(group) => staged.push(group),
+            Err(err) => {
+                registry.rollback_all();
+                return Err(err);
+            }
+        }
+    }
+
+    // Staged data (temp files, DuckDB stage tables) must not outlive a failed
+    // or cancelled write.
+    let mut artifacts = Vec::new();
+    for (parsed, outputs) in &staged {
+        match stage_outputs(&mut registry, parsed, outputs, job_id, should_commit) {
+            Ok(group_artifacts) => artifacts.extend(group_artifacts),
+            Err(err) => {
+                registry.rollback_all();
+                return Err(err);
+            }
+        }
+    }
+
+    let timings: HashMap<String, Arc<encode::TimingCounters>> = registry
+        .sinks
+        .iter()
+        .filter_map(|(name, sink)| Some((name.clone(), sink.timing_counters()?)))
+        .collect();
+    registry.finish_with_guard(should_commit)?;
+
+    for artifact in &mut artifacts {
+        artifact.write_timings = timings
+            .get(&artifact.name)
+            .map(|counters| counters.snapshot());
+    }
+    Ok(artifacts)
+}
+
+/// Create a sink for each output of one sink URI (quarantine outputs
+/// included). Returns the parsed URI and the outputs to stage.
+fn register_sinks(
+    registry: &mut SinkRegistry,
+    sink_uri: &str,
+    outputs: &[OutputPlan],
+    job_id: &str,
+    encode_pool: Option<&encode::EncodePool>,
+) -> SinkResult<(casparian_protocol::types::ParsedSinkUri, Vec<OutputPlan>)> {
+    if template::has_placeholders(sink_uri) {
+        return Err(SinkError::message(format!(
+            "Sink URI '{}' has unresolved placeholders",
+            sink_uri
+        )));
+    }
+    let parsed = casparian_protocol::types::ParsedSinkUri::parse(sink_uri)
+        .map_err(|e| SinkError::message(format!("Failed to parse sink URI: {}", e)))?;
+    let outputs = quarantine::apply_quarantine(outputs)?;
+
+    for output in &outputs {
+        if registry.sinks.contains_key(output.name()) {
+            return Err(SinkError::message(format!(
+                "Output '{}' is written to more than one sink",
+                output.name()
+            )));
+        }
+        let sink = create_sink_from_uri(
+            sink_uri,
+            output.name(),
+            output.table(),
+            output.sink_mode(),
+            output.schema_evolution(),
+            job_id,
+        )?;
+        let sink = match (sink, output.checkpoint_key()) {
+            (Sink::Parquet(sink), Some(key)) => {
+                let rows_per_part = checkpoint::checkpoint_rows(&parsed)?;
+                Sink::Parquet(sink.with_checkpoint(key, rows_per_part))
+            }
+            (sink, _) => sink,
+        };
+        let sink = match encode_pool {
+            Some(pool) => sink.with_encode_pool(pool),
+            None => sink,
+        };
+        registry.add(output.name(), sink);
+    }
+
+    Ok((parsed, outputs))
+}
+
+fn stage_outputs(
+    registry: &mut SinkRegistry,
+    parsed: &casparian_protocol::types::ParsedSinkUri,
+    outputs: &[OutputPlan],
+    job_id: &str,
+    should_commit: Option<&dyn Fn() -> bool>,
+) -> SinkResult<Vec<OutputArtifact>> {
+    let mut artifacts = Vec::new();
+
+    for output in outputs {
+        if output.batches().is_empty() {
+            continue;
+        }
+
+        let first_schema = output.batches()[0].schema();
+        registry.init(output.name(), first_schema.as_ref())?;
+        let mut rows = 0;
+        let mut stats = output
+            .collects_column_stats()
+            .then(column_stats::ColumnStatsCollector::default);
+        for batch in output.batches() {
+            if should_commit.is_some_and(|guard| !guard()) {
+                return Err(SinkError::message("Output commit aborted"));
+            }
+            validate_batch_schema(batch.record_batch(), first_schema.as_ref(), output.name())?;
+            if let Some(stats) = stats.as_mut() {
+                stats.update(batch)?;
+            }
+            registry.write_batch(output.name(), batch.record_batch())?;
+            rows += batch.num_rows() as u64;
+        }
+
+        let column_stats = stats.map(|stats| stats.finish()).unwrap_or_default();
+        if let Some(max_null_pct) = output.max_null_pct() {
+            if let Some(reason) =
+                quarantine::check_null_policy(output.name(), &column_stats, max_null_pct)
+            {
+                return Err(SinkError::message(reason));
+            }
+        }
+
+        let uri = artifact_uri_for_output(parsed, output.name(), output.table(), job_id)?;
+
+        artifacts.push(OutputArtifact {
+            name: output.name().to_string(),
+            uri,
+            rows,
+            column_stats,
+            write_timings: None,
+        });
+    }
+
+    Ok(artifacts)
+}
+
+/// Parquet sink writer
+///
+/// Partitions output by job_id: {safe_output_id}_{job_id}.parquet
+///
+/// With `partition_by`, rows are additionally split Hive-style into
+/// `col=value/` subdirectories (one file per partition per job). Partition
+/// columns are encoded in the path and omitted from the files, so readers must
+/// enable hive partitioning (e.g. DuckDB `hive_partitioning = true`).
+///
+/// With a checkpoint, an unpartitioned output is first written to rolling
+/// part files that a retry can resume from (see [`checkpoint`]).
+pub struct ParquetSink {
+    output_dir: PathBuf,
+    output_name: String,
+    job_id: String,
+    /// Hive partition columns, in directory nesting order
+    partition_by: Vec<String>,
+    /// Column split computed from the schema at init
+    layout: Option<PartitionLayout>,
+    /// Staged files keyed by partition directory (relative to output_dir)
+    files: BTreeMap<PathBuf, StagedParquetFile>,
+    rows_written: u64,
+    checkpoint: Option<checkpoint::ParquetCheckpoint>,
+    encode_pool: Option<encode::EncodePool>,
+    timings: Arc<encode::TimingCounters>,
+}
+
+/// Which batch columns become path segments vs file columns.
+struct PartitionLayout {
+    partition_indices: Vec<usize>,
+    data_indices: Vec<usize>,
+    data_schema: Arc<Schema>,
+}
+
+/// One output file, written to a temp path and renamed on commit.
+struct StagedParquetFile {
+    writer: Option<encode::ParquetFileWriter>,
+    temp_path: PathBuf,
+    final_path: PathBuf,
+    /// True once the temp file has been promoted to final_path
+    committed: bool,
+    /// The file the commit replaced, kept until the commit is released
+    backup_path: Option<PathBuf>,
+}
+
+/// Directory name Hive/Spark use for NULL partition values.
+const HIVE_NULL_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+impl ParquetSink {
+    pub fn new(output_dir: PathBuf, output_name: &str, job_id: &str) -> Result<Self> {
+        // Ensure output directory exists
+        std::fs::create_dir_all(&output_dir).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                output_dir.display()
+            )
+        })?;
+
+        Ok(Self {
+            output_dir,
+            output_name: output_name.to_string(),
+            job_id: job_id.to_string(),
+            partition_by: Vec::new(),
+            layout: None,
+            files: BTreeMap::new(),
+            rows_written: 0,
+            checkpoint: None,
+            encode_pool: None,
+            timings: Arc::default(),
+        })
+    }
+
+    /// Partition output Hive-style by these columns (in nesting order).
+    pub fn with_partition_by(mut self, columns: Vec<String>) -> Self {
+        self.partition_by = columns;
+        self
+    }
+
+    /// Write through part files of `rows_per_part` rows checkpointed under
+    /// `key`. Partitioned outputs are written without a checkpoint.
+    pub fn with_checkpoint(mut self, key: &str, rows_per_part: u64) -> Self {
+        self.checkpoint = Some(checkpoint::ParquetCheckpoint::new(
+            &self.output_dir,
+            key,
+            &self.output_name,
+            rows_per_part,
+        ));
+        self
+    }
+
+    /// Encode row groups on `pool` (see [`encode`]).
+    pub fn with_encode_pool(mut self, pool: &encode::EncodePool) -> Self {
+        self.encode_pool = Some(pool.clone());
+        self
+    }
+}
+
+impl ParquetSink {
+    fn init(&mut self, schema: &Schema) -> Result<()> {
+        let mut partition_indices = Vec::with_capacity(self.partition_by.len());
+        for column in &self.partition_by {
+            let index = schema.index_of(column).map_err(|_| {
+                anyhow::anyhow!(
+                    "Partition column '{}' not found in output '{}'",
+                    column,
+                    self.output_name
+                )
+            })?;
+            partition_indices.push(index);
+        }
+        let data_indices: Vec<usize> = (0..schema.fields().len())
+            .filter(|index| !partition_indices.contains(index))
+            .collect();
+        if data_indices.is_empty() {
+            bail!(
+                "Output '{}' has no columns left after partitioning by {:?}",
+                self.output_name,
+                self.partition_by
+            );
+        }
+        let data_schema = Arc::new(schema.project(&data_indices)?);
+
+        if !partition_indices.is_empty() && self.checkpoint.take().is_some() {
+            warn!(
+                "Output '{}' is partitioned; writing it without a checkpoint",
+                self.output_name
+            );
+        }
+        if let Some(checkpoint) = self.checkpoint.as_mut() {
+            // The staged file is built from the parts on prepare.
+            checkpoint.open(&data_schema)?;
+        } else if partition_indices.is_empty() {
+            // Unpartitioned: stage the single file up front so empty outputs
+            // still produce a file.
+            self.stage_file(PathBuf::new(), &data_schema)?;
+        }
+        self.layout = Some(PartitionLayout {
+            partition_indices,
+            data_indices,
+            data_schema,
+        });
+        Ok(())
+    }
+
+    /// Open (or reuse) the staged file for a partition directory.
+    fn stage_file(
+        &mut self,
+        partition_dir: PathBuf,
+        schema: &Arc<Schema>,
+    ) -> Result<&mut StagedParquetFile> {
+        use std::collections::btree_map::Entry;
+
+        let entry = match self.files.entry(partition_dir) {
+            Entry::Occupied(entry) => return Ok(entry.into_mut()),
+            Entry::Vacant(entry) => entry,
+        };
+
+        // Partition by job_id: {output_name}_{job_id}.parquet
+        let dir = self.output_dir.join(entry.key());
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create partition directory: {}", dir.display()))?;
+        let filename = output_filename(&self.output_name, &self.job_id, "parquet");
+        let final_path = dir.join(&filename);
+
+        // Write to temp file first for atomic rename
+        let temp_path = dir.join(format!(".{}.tmp", filename));
+
+        info!(
+            "Initializing Parquet sink: {} (temp: {})",
+            final_path.display(),
+            temp_path.display()
+        );
+
+        let file = std::fs::File::create(&temp_path).with_context(|| {
+            format!(
+                "Failed to create temp parquet file: {}",
+                temp_path.display()
+            )
+        })?;
+
+        let props = parquet::file::properties::WriterProperties::builder()
+            .set_compression(parquet::basic::Compression::SNAPPY)
+            .build();
+
+        let writer = encode::ParquetFileWriter::new(
+            file,
+            schema.clone(),
+            props,
+            self.encode_pool.as_ref(),
+            &self.timings,
+        )?;
+
+        Ok(entry.insert(StagedParquetFile {
+            writer: Some(writer),
+            temp_path,
+            final_path,
+            committed: false,
+            backup_path: None,
+        }))
+    }
+
+    fn write_batch(&mut self, batch: &RecordBatch) -> Result<u64> {
+        let layout = self
+            .layout
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Parquet sink not initialized"))?;
+        let data_schema = layout.data_schema.clone();
+
+        if let Some(checkpoint) = self.checkpoint.as_mut() {
+            checkpoint.write(batch)?;
+            let rows = batch.num_rows() as u64;
+            self.rows_written += rows;
+            return Ok(rows);
+        }
+
+        let groups = if layout.partition_indices.is_empty() {
+            vec![(PathBuf::new(), batch.clone())]
+        } else {
+            split_by_partition(batch, layout)?
+        };
+
+        for (partition_dir, sub_batch) in groups {
+            let staged = self.stage_file(partition_dir, &data_schema)?;
+            let writer = staged
+                .writer
+                .as_mut()
+                .ok_or_else(|| anyhow::anyhow!("Parquet sink already finished"))?;
+            writer.write(&sub_batch)?;
+        }
+
+        let rows = batch.num_rows() as u64;
+        self.rows_written += rows;
+        debug!(
+            "Wrote {} rows to Parquet (total: {})",
+            rows, self.rows_written
+        );
+
+        Ok(rows)
+    }
+
+    fn prepare(&mut self) -> Result<()> {
+        if let Some(mut checkpoint) = self.checkpoint.take() {
+            let result = self.merge_checkpoint(&mut checkpoint);
+            self.checkpoint = Some(checkpoint);
+            result?;
+        }
+        for staged in self.files.values_mut() {
+            if let Some(writer) = staged.writer.take() {
+                writer.close()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Close the last checkpoint part and copy all parts into the staged file.
+    fn merge_checkpoint(&mut self, checkpoint: &mut checkpoint::ParquetCheckpoint) -> Result<()> {
+        checkpoint.finish()?;
+        let data_schema = self
+            .layout
+            .as_ref()
+            .map(|layout| layout.data_schema.clone())
+            .ok_or_else(|| anyhow::anyhow!("Parquet sink not initialized"))?;
+        let staged = self.stage_file(PathBuf::new(), &data_schema)?;
+        let writer = staged
+            .writer
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Parquet sink already finished"))?;
+        checkpoint.copy_into(|batch| writer.write(batch))
+    }
+
+    fn commit(&mut self) -> Result<()> {
+        for staged in self.files.values_mut() {
+            if staged.committed {
+                continue;
+            }
+            staged.backup_path = promote_staged_file(&staged.temp_path, &staged.final_path)?;
+            staged.committed = true;
+        }
+        if let Some(mut checkpoint) = self.checkpoint.take() {
+            checkpoint.discard();
+        }
+        info!(
+            "Committed Parquet sink: {} ({} rows, {} file(s))",
+            self.output_dir.display(),
+            self.rows_written,
+            self.files.len()
+        );
+        Ok(())
+    }
+
+    fn rollback(&mut self) -> Result<()> {
+        if let Some(checkpoint) = self.checkpoint.as_mut() {
+            checkpoint.abandon_open_part();
+        }
+        for staged in std::mem::take(&mut self.files).into_values() {
+            if staged.committed {
+                restore_replaced_file(&staged.final_path, staged.backup_path.as_deref());
+                warn!(
+                    "Rolled back Parquet committed file: {}",
+                    staged.final_path.display()
+                );
+            } else if staged.temp_path.exists() {
+                let _ = std::fs::remove_file(&staged.temp_path);
+                warn!(
+                    "Rolled back Parquet temp file: {}",
+                    staged.temp_path.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn release(&mut self) {
+        for staged in self.files.values_mut() {
+            discard_backup(staged.backup_path.take());
+        }
+    }
+}
+
+impl Drop for ParquetSink {
+    fn drop(&mut self) {
+        if let Some(checkpoint) = self.checkpoint.as_mut() {
+            checkpoint.abandon_open_part();
+        }
+        // Cleanup temp files if we didn't finish properly
+        for staged in self.files.values() {
+            if !staged.committed && staged.temp_path.exists() {
+                let _ = std::fs::remove_file(&staged.temp_path);
+                warn!(
+                    "Cleaned up orphaned temp file: {}",
+                    staged.temp_path.display()
+                );
+            }
+        }
+    }
+}
+
+/// Group a batch's rows by partition values, returning the relative partition
+/// directory and the rows (without partition columns) for each group.
+fn split_by_partition(
+    batch: &RecordBatch,
+    layout: &PartitionLayout,
+) -> Result<Vec<(PathBuf, RecordBatch)>> {
+    use arrow::util::display::{ArrayFormatter, FormatOptions};
+
+    let schema = batch.schema();
+    let options = FormatOptions::default();
+    let formatters = layout
+        .partition_indices
+        .iter()
+        .map(|&index| ArrayFormatter::try_new(batch.column(index).as_ref(), &options))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut groups: BTreeMap<PathBuf, Vec<u32>> = BTreeMap::new();
+    for row in 0..batch.num_rows() {
+        let mut dir = PathBuf::new();
+        for (formatter, &index) in formatters.iter().zip(&layout.partition_indices) {
+            let value = if Array::is_null(batch.column(index).as_ref(), row) {
+                HIVE_NULL_PARTITION.to_string()
+            } else {
+                escape_partition_value(&formatter.value(row).to_string())
+            };
+            dir.push(format!("{}={}", schema.field(index).name(), value));
+        }
+        let row = u32::try_from(row).context("Batch too large to partition")?;
+        groups.entry(dir).or_default().push(row);
+    }
+
+    groups
+        .into_iter()
+        .map(|(dir, rows)| {
+            let indices = arrow::array::UInt32Array::from(rows);
+            let columns = layout
+                .data_indices
+                .iter()
+                .map(|&index| arrow::compute::take(batch.column(index).as_ref(), &indices, None))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let sub_batch = RecordBatch::try_new(layout.data_schema.clone(), columns)?;
+            Ok((dir, sub_batch))
+        })
+        .collect()
+}
+
+/// Percent-encode characters that are unsafe in a Hive partition directory.
+fn escape_partition_value(value: &str) -> String {
+    use std::fmt::Write;
+
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        let unsafe_char = ch.is_control()
+            || matches!(
+                ch,
+                '/' | '\\' | '=' | '%' | ':' | '"' | '#' | '\'' | '*' | '?' | '[' | ']' | '{' | '^'
+            );
+        if unsafe_char {
+            let mut buf = [0u8; 4];
+            for byte in ch.encode_utf8(&mut buf).bytes() {
+                let _ = write!(escaped, "%{:02X}", byte);
+            }
+        } else {
+            escaped.push(ch);
+        }
+    }
+    escaped
+}
+
+/// Partition columns from a sink URI's `partition_by` query (comma-separated).
+fn partition_columns(parsed: &casparian_protocol::types::ParsedSinkUri) -> Vec<String> {
+    column_list(parsed, "partition_by")
+}
+
+/// `key=col1,col2` on a DuckDB URI: key columns for Upsert commits.
+fn upsert_keys(parsed: &casparian_protocol::types::ParsedSinkUri) -> Vec<String> {
+    column_list(parsed, "key")
+}
+
+fn column_list(parsed: &casparian_protocol::types::ParsedSinkUri, param: &str) -> Vec<String> {
+    parsed
+        .query
+        .get(param)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|column| !column.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `keep_versions=N` on a DuckDB URI: previous table versions a Replace
+/// commit keeps (default 0).
+fn keep_versions(parsed: &casparian_protocol::types::ParsedSinkUri) -> Result<usize> {
+    match parsed.query.get("keep_versions") {
+        Some(value) => value
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid keep_versions '{}' in DuckDB sink URI", value)),
+        None => Ok(0),
+    }
+}
+
+/// CSV sink writer
+///
+/// Partitions output by job_id: {safe_output_id}_{job_id}.csv
+pub struct CsvSink {
+    output_dir: PathBuf,
+    output_name: String,
+    job_id: String,
+    writer: Option<encode::CsvFileWriter>,
+    rows_written: u64,
+    /// Temp file path for staging
+    temp_path: Option<PathBuf>,
+    /// Final file path
+    final_path: Option<PathBuf>,
+    /// True once final file has been promoted
+    committed: bool,
+    /// The file the commit replaced, kept until the commit is released
+    backup_path: Option<PathBuf>,
+    encode_pool: Option<encode::EncodePool>,
+    timings: Arc<encode::TimingCounters>,
+}
+
+impl CsvSink {
+    pub fn new(output_dir: PathBuf, output_name: &str, job_id: &str) -> Result<Self> {
+        std::fs::create_dir_all(&output_dir).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                output_dir.display()
+            )
+        })?;
+
+        Ok(Self {
+            output_dir,
+            output_name: output_name.to_string(),
+            job_id: job_id.to_string(),
+            writer: None,
+            rows_written: 0,
+            temp_path: None,
+            final_path: None,
+            committed: false,
+            backup_path: None,
+            encode_pool: None,
+            timings: Arc::default(),
+        })
+    }
+
+    /// Encode batches on `pool` (see [`encode`]).
+    pub fn with_encode_pool(mut self, pool: &encode::EncodePool) -> Self {
+        self.encode_pool = Some(pool.clone());
+        self
+    }
+}
+
+impl CsvSink {
+    fn init(&mut self, _schema: &Schema) -> Result<()> {
+        // Partition by job_id: {output_name}_{job_id}.csv
+        let filename = output_filename(&self.output_name, &self.job_id, "csv");
+        let final_path = self.output_dir.join(&filename);
+
+        // Write to temp file first for atomic rename
+        let temp_filename = format!(".{}", filename);
+        let temp_filename = format!("{}.tmp", temp_filename);
+        let temp_path = self.output_dir.join(&temp_filename);
+
+        info!(
+            "Initializing CSV sink: {} (temp: {})",
+            final_path.display(),
+            temp_path.display()
+        );
+
+        let file = std::fs::File::create(&temp_path)
+            .with_context(|| format!("Failed to create temp CSV file: {}", temp_path.display()))?;
+
+        let writer = encode::CsvFileWriter::new(file, self.encode_pool.as_ref(), &self.timings)?;
+
+        self.writer = Some(writer);
+        self.temp_path = Some(temp_path);
+        self.final_path = Some(final_path);
+        Ok(())
+    }
+
+    fn write_batch(&mut self, batch: &RecordBatch) -> Result<u64> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("CSV sink not initialized"))?;
+
+        writer.write(batch)?;
 
-    for output in outputs {
-        let sink = create_sink_from_uri(
-            sink_uri,
-            output.name(),
-            output.table(),
-            output.sink_mode(),
-            job_id,
-        )?;
-        registry.add(output.name(), sink);
-    }
+        let rows = batch.num_rows() as u64;
+        self.rows_written += rows;
+        debug!("Wrote {} rows to CSV (total: {})", rows, self.rows_written);
 
-    let mut artifacts = Vec::new();
+        Ok(rows)
+    }
 
-    for output in outputs {
-        if output.batches().is_empty() {
-            continue;
+    fn prepare(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.close()?;
         }
+        Ok(())
+    }
 
-        let first_schema = output.batches()[0].schema();
-        registry.init(output.name(), first_schema.as_ref())?;
-        let mut rows = 0;
-        for batch in output.batches() {
-            validate_batch_schema(batch.record_batch(), first_schema.as_ref(), output.name())?;
-            registry.write_batch(output.name(), batch.record_batch())?;
-            rows += batch.num_rows() as u64;
+    fn commit(&mut self) -> Result<()> {
+        if let (Some(temp_path), Some(final_path)) = (&self.temp_path, &self.final_path) {
+            self.backup_path = promote_staged_file(temp_path, final_path)?;
+            info!(
+                "Committed CSV sink: {} ({} rows)",
+                final_path.display(),
+                self.rows_written
+            );
+            self.committed = true;
         }
+        self.temp_path = None;
+        Ok(())
+    }
 
-        let uri = artifact_uri_for_output(&parsed, output.name(), output.table(), job_id)?;
-
-        artifacts.push(OutputArtifact {
-            name: output.name().to_string(),
-            uri,
-            rows,
-        });
+    fn rollback(&mut self) -> Result<()> {
+        if self.committed {
+            if let Some(final_path) = &self.final_path {
+                restore_replaced_file(final_path, self.backup_path.take().as_deref());
+                warn!("Rolled back CSV committed file: {}", final_path.display());
+            }
+        }
+        if let Some(temp_path) = &self.temp_path {
+            if temp_path.exists() {
+                let _ = std::fs::remove_file(temp_path);
+                warn!("Rolled back CSV temp file: {}", temp_path.display());
+            }
+        }
+        self.temp_path = None;
+        self.final_path = None;
+        self.committed = false;
+        Ok(())
     }
 
-    registry.finish_with_guard(should_commit)?;
+    fn release(&mut self) {
+        discard_backup(self.backup_path.take());
+    }
+}
 
-    Ok(artifacts)
+impl Drop for CsvSink {
+    fn drop(&mut self) {
+        // Cleanup temp file if we didn't finish properly
+        if let Some(temp_path) = &self.temp_path {
+            if temp_path.exists() {
+                let _ = std::fs::remove_file(temp_path);
+                warn!("Cleaned up orphaned temp file: {}", temp_path.display());
+            }
+        }
+    }
 }
 
-/// Parquet sink writer
+/// JSON Lines sink writer
 ///
-/// Partitions output by job_id: {safe_output_id}_{job_id}.parquet
-pub struct ParquetSink {
+/// Writes one JSON object per row. Partitions output by job_id:
+/// {safe_output_id}_{job_id}.jsonl
+pub struct JsonlSink {
     output_dir: PathBuf,
     output_name: String,
     job_id: String,
-    writer: Option<parquet::arrow::arrow_writer::ArrowWriter<std::fs::File>>,
+    writer: Option<arrow::json::LineDelimitedWriter<std::io::BufWriter<std::fs::File>>>,
     rows_written: u64,
     /// Temp file path for staging
     temp_path: Option<PathBuf>,
@@ -344,11 +1529,12 @@ pub struct ParquetSink {
     final_path: Option<PathBuf>,
     /// True once final file has been promoted
     committed: bool,
+    /// The file the commit replaced, kept until the commit is released
+    backup_path: Option<PathBuf>,
 }
 
-impl ParquetSink {
+impl JsonlSink {
     pub fn new(output_dir: PathBuf, output_name: &str, job_id: &str) -> Result<Self> {
-        // Ensure output directory exists
         std::fs::create_dir_all(&output_dir).with_context(|| {
             format!(
                 "Failed to create output directory: {}",
@@ -365,44 +1551,33 @@ impl ParquetSink {
             temp_path: None,
             final_path: None,
             committed: false,
+            backup_path: None,
         })
     }
 }
 
-impl ParquetSink {
-    fn init(&mut self, schema: &Schema) -> Result<()> {
-        // Partition by job_id: {output_name}_{job_id}.parquet
-        let filename = output_filename(&self.output_name, &self.job_id, "parquet");
+impl JsonlSink {
+    fn init(&mut self, _schema: &Schema) -> Result<()> {
+        // Partition by job_id: {output_name}_{job_id}.jsonl
+        let filename = output_filename(&self.output_name, &self.job_id, "jsonl");
         let final_path = self.output_dir.join(&filename);
 
         // Write to temp file first for atomic rename
-        let temp_filename = format!(".{}", filename);
-        let temp_filename = format!("{}.tmp", temp_filename);
-        let temp_path = self.output_dir.join(&temp_filename);
+        let temp_path = self.output_dir.join(format!(".{}.tmp", filename));
 
         info!(
-            "Initializing Parquet sink: {} (temp: {})",
+            "Initializing JSONL sink: {} (temp: {})",
             final_path.display(),
             temp_path.display()
         );
 
         let file = std::fs::File::create(&temp_path).with_context(|| {
-            format!(
-                "Failed to create temp parquet file: {}",
-                temp_path.display()
-            )
+            format!("Failed to create temp JSONL file: {}", temp_path.display())
         })?;
 
-        let props = parquet::file::properties::WriterProperties::builder()
-            .set_compression(parquet::basic::Compression::SNAPPY)
-            .build();
-
-        let arrow_schema = Arc::new(schema.clone());
-        let writer =
-            parquet::arrow::arrow_writer::ArrowWriter::try_new(file, arrow_schema, Some(props))
-                .context("Failed to create Parquet writer")?;
-
-        self.writer = Some(writer);
+        self.writer = Some(arrow::json::LineDelimitedWriter::new(
+            std::io::BufWriter::new(file),
+        ));
         self.temp_path = Some(temp_path);
         self.final_path = Some(final_path);
         Ok(())
@@ -412,40 +1587,33 @@ impl ParquetSink {
         let writer = self
             .writer
             .as_mut()
-            .ok_or_else(|| anyhow::anyhow!("Parquet sink not initialized"))?;
+            .ok_or_else(|| anyhow::anyhow!("JSONL sink not initialized"))?;
 
         writer
             .write(batch)
-            .context("Failed to write batch to Parquet")?;
+            .context("Failed to write batch to JSONL")?;
 
         let rows = batch.num_rows() as u64;
         self.rows_written += rows;
-        debug!(
-            "Wrote {} rows to Parquet (total: {})",
-            rows, self.rows_written
-        );
+        debug!("Wrote {} rows to JSONL (total: {})", rows, self.rows_written);
 
         Ok(rows)
     }
 
     fn prepare(&mut self) -> Result<()> {
-        if let Some(writer) = self.writer.take() {
-            writer.close().context("Failed to close Parquet writer")?;
+        if let Some(mut writer) = self.writer.take() {
+            writer.finish().context("Failed to finish JSONL writer")?;
+            let mut file = writer.into_inner();
+            std::io::Write::flush(&mut file).context("Failed to flush JSONL file")?;
         }
         Ok(())
     }
 
     fn commit(&mut self) -> Result<()> {
         if let (Some(temp_path), Some(final_path)) = (&self.temp_path, &self.final_path) {
-            std::fs::rename(temp_path, final_path).with_context(|| {
-                format!(
-                    "Failed to rename {} -> {}",
-                    temp_path.display(),
-                    final_path.display()
-                )
-            })?;
+            self.backup_path = promote_staged_file(temp_path, final_path)?;
             info!(
-                "Committed Parquet sink: {} ({} rows)",
+                "Committed JSONL sink: {} ({} rows)",
                 final_path.display(),
                 self.rows_written
             );
@@ -458,19 +1626,14 @@ impl ParquetSink {
     fn rollback(&mut self) -> Result<()> {
         if self.committed {
             if let Some(final_path) = &self.final_path {
-                if final_path.exists() {
-                    let _ = std::fs::remove_file(final_path);
-                    warn!(
-                        "Rolled back Parquet committed file: {}",
-                        final_path.display()
-                    );
-                }
+                restore_replaced_file(final_path, self.backup_path.take().as_deref());
+                warn!("Rolled back JSONL committed file: {}", final_path.display());
             }
         }
         if let Some(temp_path) = &self.temp_path {
             if temp_path.exists() {
                 let _ = std::fs::remove_file(temp_path);
-                warn!("Rolled back Parquet temp file: {}", temp_path.display());
+                warn!("Rolled back JSONL temp file: {}", temp_path.display());
             }
         }
         self.temp_path = None;
@@ -478,9 +1641,13 @@ impl ParquetSink {
         self.committed = false;
         Ok(())
     }
+
+    fn release(&mut self) {
+        discard_backup(self.backup_path.take());
+    }
 }
 
-impl Drop for ParquetSink {
+impl Drop for JsonlSink {
     fn drop(&mut self) {
         // Cleanup temp file if we didn't finish properly
         if let Some(temp_path) = &self.temp_path {
@@ -492,14 +1659,52 @@ impl Drop for ParquetSink {
     }
 }
 
-/// CSV sink writer
+/// Arrow IPC encoding written by [`ArrowIpcSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowIpcFormat {
+    /// IPC stream (`.arrows`), read front to back
+    Stream,
+    /// IPC file (`.arrow`), whose footer lets readers mmap and seek batches
+    File,
+}
+
+impl ArrowIpcFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArrowIpcFormat::Stream => "arrows",
+            ArrowIpcFormat::File => "arrow",
+        }
+    }
+
+    /// `?format=stream|file` on an `arrow://` URI (default: stream).
+    fn from_uri(parsed: &casparian_protocol::types::ParsedSinkUri) -> Result<Self> {
+        match parsed.query.get("format").map(|value| value.trim()) {
+            None | Some("stream") => Ok(ArrowIpcFormat::Stream),
+            Some("file") => Ok(ArrowIpcFormat::File),
+            Some(other) => bail!(
+                "Invalid format '{}' in Arrow sink URI (expected stream or file)",
+                other
+            ),
+        }
+    }
+}
+
+enum IpcWriter {
+    Stream(arrow::ipc::writer::StreamWriter<std::io::BufWriter<std::fs::File>>),
+    File(arrow::ipc::writer::FileWriter<std::io::BufWriter<std::fs::File>>),
+}
+
+/// Arrow IPC sink writer
 ///
-/// Partitions output by job_id: {safe_output_id}_{job_id}.csv
-pub struct CsvSink {
+/// Writes batches unchanged in the Arrow IPC format, so downstream processes
+/// can read (or mmap, with [`ArrowIpcFormat::File`]) them without a Parquet
+/// encode/decode. Partitions output by job_id: {safe_output_id}_{job_id}.arrows
+pub struct ArrowIpcSink {
     output_dir: PathBuf,
     output_name: String,
     job_id: String,
-    writer: Option<arrow::csv::Writer<std::fs::File>>,
+    format: ArrowIpcFormat,
+    writer: Option<IpcWriter>,
     rows_written: u64,
     /// Temp file path for staging
     temp_path: Option<PathBuf>,
@@ -507,10 +1712,17 @@ pub struct CsvSink {
     final_path: Option<PathBuf>,
     /// True once final file has been promoted
     committed: bool,
+    /// The file the commit replaced, kept until the commit is released
+    backup_path: Option<PathBuf>,
 }
 
-impl CsvSink {
-    pub fn new(output_dir: PathBuf, output_name: &str, job_id: &str) -> Result<Self> {
+impl ArrowIpcSink {
+    pub fn new(
+        output_dir: PathBuf,
+        output_name: &str,
+        job_id: &str,
+        format: ArrowIpcFormat,
+    ) -> Result<Self> {
         std::fs::create_dir_all(&output_dir).with_context(|| {
             format!(
                 "Failed to create output directory: {}",
@@ -522,38 +1734,46 @@ impl CsvSink {
             output_dir,
             output_name: output_name.to_string(),
             job_id: job_id.to_string(),
+            format,
             writer: None,
             rows_written: 0,
             temp_path: None,
             final_path: None,
             committed: false,
+            backup_path: None,
         })
     }
 }
 
-impl CsvSink {
-    fn init(&mut self, _schema: &Schema) -> Result<()> {
-        // Partition by job_id: {output_name}_{job_id}.csv
-        let filename = output_filename(&self.output_name, &self.job_id, "csv");
+impl ArrowIpcSink {
+    fn init(&mut self, schema: &Schema) -> Result<()> {
+        let filename = output_filename(&self.output_name, &self.job_id, self.format.extension());
         let final_path = self.output_dir.join(&filename);
 
         // Write to temp file first for atomic rename
-        let temp_filename = format!(".{}", filename);
-        let temp_filename = format!("{}.tmp", temp_filename);
-        let temp_path = self.output_dir.join(&temp_filename);
+        let temp_path = self.output_dir.join(format!(".{}.tmp", filename));
 
         info!(
-            "Initializing CSV sink: {} (temp: {})",
+            "Initializing Arrow IPC sink: {} (temp: {})",
             final_path.display(),
             temp_path.display()
         );
 
-        let file = std::fs::File::create(&temp_path)
-            .with_context(|| format!("Failed to create temp CSV file: {}", temp_path.display()))?;
-
-        let writer = arrow::csv::WriterBuilder::new()
-            .with_header(true)
-            .build(file);
+        let file = std::fs::File::create(&temp_path).with_context(|| {
+            format!(
+                "Failed to create temp Arrow IPC file: {}",
+                temp_path.display()
+            )
+        })?;
+        let file = std::io::BufWriter::new(file);
+        let writer = match self.format {
+            ArrowIpcFormat::Stream => {
+                IpcWriter::Stream(arrow::ipc::writer::StreamWriter::try_new(file, schema)?)
+            }
+            ArrowIpcFormat::File => {
+                IpcWriter::File(arrow::ipc::writer::FileWriter::try_new(file, schema)?)
+            }
+        };
 
         self.writer = Some(writer);
         self.temp_path = Some(temp_path);
@@ -565,36 +1785,42 @@ impl CsvSink {
         let writer = self
             .writer
             .as_mut()
-            .ok_or_else(|| anyhow::anyhow!("CSV sink not initialized"))?;
+            .ok_or_else(|| anyhow::anyhow!("Arrow IPC sink not initialized"))?;
 
-        writer
-            .write(batch)
-            .context("Failed to write batch to CSV")?;
+        match writer {
+            IpcWriter::Stream(writer) => writer.write(batch),
+            IpcWriter::File(writer) => writer.write(batch),
+        }
+        .context("Failed to write batch to Arrow IPC")?;
 
         let rows = batch.num_rows() as u64;
         self.rows_written += rows;
-        debug!("Wrote {} rows to CSV (total: {})", rows, self.rows_written);
+        debug!(
+            "Wrote {} rows to Arrow IPC (total: {})",
+            rows, self.rows_written
+        );
 
         Ok(rows)
     }
 
     fn prepare(&mut self) -> Result<()> {
-        // Drop writer to flush
-        drop(self.writer.take());
+        if let Some(writer) = self.writer.take() {
+            // into_inner writes the end-of-stream marker / file footer.
+            let mut file = match writer {
+                IpcWriter::Stream(writer) => writer.into_inner(),
+                IpcWriter::File(writer) => writer.into_inner(),
+            }
+            .context("Failed to finish Arrow IPC writer")?;
+            std::io::Write::flush(&mut file).context("Failed to flush Arrow IPC file")?;
+        }
         Ok(())
     }
 
     fn commit(&mut self) -> Result<()> {
         if let (Some(temp_path), Some(final_path)) = (&self.temp_path, &self.final_path) {
-            std::fs::rename(temp_path, final_path).with_context(|| {
-                format!(
-                    "Failed to rename {} -> {}",
-                    temp_path.display(),
-                    final_path.display()
-                )
-            })?;
+            self.backup_path = promote_staged_file(temp_path, final_path)?;
             info!(
-                "Committed CSV sink: {} ({} rows)",
+                "Committed Arrow IPC sink: {} ({} rows)",
                 final_path.display(),
                 self.rows_written
             );
@@ -607,16 +1833,17 @@ impl CsvSink {
     fn rollback(&mut self) -> Result<()> {
         if self.committed {
             if let Some(final_path) = &self.final_path {
-                if final_path.exists() {
-                    let _ = std::fs::remove_file(final_path);
-                    warn!("Rolled back CSV committed file: {}", final_path.display());
-                }
+                restore_replaced_file(final_path, self.backup_path.take().as_deref());
+                warn!(
+                    "Rolled back Arrow IPC committed file: {}",
+                    final_path.display()
+                );
             }
         }
         if let Some(temp_path) = &self.temp_path {
             if temp_path.exists() {
                 let _ = std::fs::remove_file(temp_path);
-                warn!("Rolled back CSV temp file: {}", temp_path.display());
+                warn!("Rolled back Arrow IPC temp file: {}", temp_path.display());
             }
         }
         self.temp_path = None;
@@ -624,9 +1851,13 @@ impl CsvSink {
         self.committed = false;
         Ok(())
     }
+
+    fn release(&mut self) {
+        discard_backup(self.backup_path.take());
+    }
 }
 
-impl Drop for CsvSink {
+impl Drop for ArrowIpcSink {
     fn drop(&mut self) {
         // Cleanup temp file if we didn't finish properly
         if let Some(temp_path) = &self.temp_path {
@@ -641,8 +1872,14 @@ impl Drop for CsvSink {
 enum Sink {
     Parquet(ParquetSink),
     Csv(Box<CsvSink>),
+    Jsonl(Box<JsonlSink>),
+    ArrowIpc(Box<ArrowIpcSink>),
     #[cfg(feature = "sink-duckdb")]
     DuckDb(DuckDbSink),
+    #[cfg(feature = "sink-s3")]
+    S3(Box<S3Sink>),
+    #[cfg(feature = "sink-postgres")]
+    Postgres(Box<PostgresSink>),
 }
 
 impl Sink {
@@ -650,8 +1887,14 @@ impl Sink {
         match self {
             Sink::Parquet(sink) => sink.init(schema),
             Sink::Csv(sink) => sink.init(schema),
+            Sink::Jsonl(sink) => sink.init(schema),
+            Sink::ArrowIpc(sink) => sink.init(schema),
             #[cfg(feature = "sink-duckdb")]
             Sink::DuckDb(sink) => sink.init(schema),
+            #[cfg(feature = "sink-s3")]
+            Sink::S3(sink) => sink.init(schema),
+            #[cfg(feature = "sink-postgres")]
+            Sink::Postgres(sink) => sink.init(schema),
         }
     }
 
@@ -659,8 +1902,14 @@ impl Sink {
         match self {
             Sink::Parquet(sink) => sink.write_batch(batch),
             Sink::Csv(sink) => sink.write_batch(batch),
+            Sink::Jsonl(sink) => sink.write_batch(batch),
+            Sink::ArrowIpc(sink) => sink.write_batch(batch),
             #[cfg(feature = "sink-duckdb")]
             Sink::DuckDb(sink) => sink.write_batch(batch),
+            #[cfg(feature = "sink-s3")]
+            Sink::S3(sink) => sink.write_batch(batch),
+            #[cfg(feature = "sink-postgres")]
+            Sink::Postgres(sink) => sink.write_batch(batch),
         }
     }
 
@@ -668,8 +1917,14 @@ impl Sink {
         match self {
             Sink::Parquet(sink) => sink.prepare(),
             Sink::Csv(sink) => sink.prepare(),
+            Sink::Jsonl(sink) => sink.prepare(),
+            Sink::ArrowIpc(sink) => sink.prepare(),
             #[cfg(feature = "sink-duckdb")]
             Sink::DuckDb(sink) => sink.prepare(),
+            #[cfg(feature = "sink-s3")]
+            Sink::S3(sink) => sink.prepare(),
+            #[cfg(feature = "sink-postgres")]
+            Sink::Postgres(sink) => sink.prepare(),
         }
     }
 
@@ -677,8 +1932,14 @@ impl Sink {
         match self {
             Sink::Parquet(sink) => sink.commit(),
             Sink::Csv(sink) => sink.commit(),
+            Sink::Jsonl(sink) => sink.commit(),
+            Sink::ArrowIpc(sink) => sink.commit(),
             #[cfg(feature = "sink-duckdb")]
             Sink::DuckDb(sink) => sink.commit(),
+            #[cfg(feature = "sink-s3")]
+            Sink::S3(sink) => sink.commit(),
+            #[cfg(feature = "sink-postgres")]
+            Sink::Postgres(sink) => sink.commit(),
         }
     }
 
@@ -686,8 +1947,62 @@ impl Sink {
         match self {
             Sink::Parquet(sink) => sink.rollback(),
             Sink::Csv(sink) => sink.rollback(),
+            Sink::Jsonl(sink) => sink.rollback(),
+            Sink::ArrowIpc(sink) => sink.rollback(),
             #[cfg(feature = "sink-duckdb")]
             Sink::DuckDb(sink) => sink.rollback(),
+            #[cfg(feature = "sink-s3")]
+            Sink::S3(sink) => sink.rollback(),
+            #[cfg(feature = "sink-postgres")]
+            Sink::Postgres(sink) => sink.rollback(),
+        }
+    }
+
+    /// Drop what a rollback would need once the commit is final.
+    fn release(&mut self) {
+        match self {
+            Sink::Parquet(sink) => sink.release(),
+            Sink::Csv(sink) => sink.release(),
+            Sink::Jsonl(sink) => sink.release(),
+            Sink::ArrowIpc(sink) => sink.release(),
+            #[cfg(feature = "sink-duckdb")]
+            Sink::DuckDb(_) => {}
+            #[cfg(feature = "sink-s3")]
+            Sink::S3(_) => {}
+            #[cfg(feature = "sink-postgres")]
+            Sink::Postgres(_) => {}
+        }
+    }
+
+    /// Share the job's encode pool with sinks that can use it.
+    fn with_encode_pool(self, pool: &encode::EncodePool) -> Self {
+        match self {
+            Sink::Parquet(sink) => Sink::Parquet(sink.with_encode_pool(pool)),
+            Sink::Csv(sink) => Sink::Csv(Box::new(sink.with_encode_pool(pool))),
+            sink => sink,
+        }
+    }
+
+    fn timing_counters(&self) -> Option<Arc<encode::TimingCounters>> {
+        match self {
+            Sink::Parquet(sink) => Some(sink.timings.clone()),
+            Sink::Csv(sink) => Some(sink.timings.clone()),
+            _ => None,
+        }
+    }
+
+    /// Whether `rollback` undoes a commit. File and object sinks delete what
+    /// they committed (file sinks restore any file the commit replaced); a
+    /// committed database transaction stays.
+    fn commit_is_reversible(&self) -> bool {
+        match self {
+            Sink::Parquet(_) | Sink::Csv(_) | Sink::Jsonl(_) | Sink::ArrowIpc(_) => true,
+            #[cfg(feature = "sink-duckdb")]
+            Sink::DuckDb(_) => false,
+            #[cfg(feature = "sink-s3")]
+            Sink::S3(_) => true,
+            #[cfg(feature = "sink-postgres")]
+            Sink::Postgres(_) => false,
         }
     }
 }
@@ -741,7 +2056,12 @@ impl SinkRegistry {
 
     /// Finish all sinks with an optional commit guard.
     ///
-    /// If the guard returns false, all sinks are rolled back.
+    /// Two-phase: every sink is prepared before any commits. If a prepare
+    /// fails or the guard returns false, all sinks are rolled back. Sinks
+    /// whose commit can be undone commit first and database sinks last, so a
+    /// failed commit rolls back everything committed before it unless that
+    /// includes another database transaction. Files replaced by a commit are
+    /// kept aside until every sink has committed.
     pub fn finish_with_guard(mut self, should_commit: Option<&dyn Fn() -> bool>) -> Result<()> {
         let mut names: Vec<String> = self.sinks.keys().cloned().collect();
         names.sort();
@@ -749,45 +2069,65 @@ impl SinkRegistry {
         for name in &names {
             if let Some(sink) = self.sinks.get_mut(name) {
                 debug!("Preparing sink: {}", name);
-                sink.prepare()?;
+                if let Err(err) = sink.prepare() {
+                    warn!("Sink '{}' failed to prepare, rolling back: {}", name, err);
+                    self.rollback_all();
+                    return Err(err);
+                }
             }
         }
 
         if let Some(guard) = should_commit {
             if !guard() {
                 warn!("Sink commit aborted by guard; rolling back");
-                for name in &names {
-                    if let Some(sink) = self.sinks.get_mut(name) {
-                        let _ = sink.rollback();
-                    }
-                }
+                self.rollback_all();
                 bail!("Output commit aborted");
             }
         }
 
-        let commit_result: Result<()> = (|| {
-            for name in &names {
-                if let Some(sink) = self.sinks.get_mut(name) {
-                    debug!("Committing sink: {}", name);
-                    sink.commit()?;
+        // Stable sort: by name within each class
+        names.sort_by_key(|name| !self.sinks[name].commit_is_reversible());
+        let mut committed_transactions = Vec::new();
+        for name in &names {
+            let Some(sink) = self.sinks.get_mut(name) else {
+                continue;
+            };
+            debug!("Committing sink: {}", name);
+            if let Err(err) = sink.commit() {
+                warn!("Sink '{}' failed to commit, rolling back: {}", name, err);
+                if !committed_transactions.is_empty() {
+                    warn!(
+                        "Sinks [{}] already committed their transactions and keep their output",
+                        committed_transactions.join(", ")
+                    );
                 }
+                self.rollback_all();
+                return Err(err);
             }
-            Ok(())
-        })();
-
-        if let Err(err) = commit_result {
-            warn!("Sink commit failed, rolling back: {}", err);
-            for name in &names {
-                if let Some(sink) = self.sinks.get_mut(name) {
-                    let _ = sink.rollback();
-                }
+            if !sink.commit_is_reversible() {
+                committed_transactions.push(name.clone());
             }
-            return Err(err);
         }
 
+        for sink in self.sinks.values_mut() {
+            sink.release();
+        }
         Ok(())
     }
 
+    /// Roll back every sink, discarding staged output. Errors are logged.
+    pub fn rollback_all(&mut self) {
+        let mut names: Vec<String> = self.sinks.keys().cloned().collect();
+        names.sort();
+        for name in &names {
+            if let Some(sink) = self.sinks.get_mut(name) {
+                if let Err(err) = sink.rollback() {
+                    warn!("Failed to roll back sink '{}': {}", name, err);
+                }
+            }
+        }
+    }
+
     /// Get registered sink names
     pub fn sink_names(&self) -> Vec<&str> {
         self.sinks.keys().map(|s| s.as_str()).collect()
@@ -944,6 +2284,7 @@ pub(crate) fn create_sink_from_uri(
     output_name: &str,
     output_table: Option<&str>,
     sink_mode: SinkMode,
+    schema_evolution: SchemaEvolution,
     job_id: &str,
 ) -> Result<Sink> {
     let parsed =
@@ -957,28 +2298,76 @@ pub(crate) fn create_sink_from_uri(
                     sink_mode
                 );
             }
-            Ok(Sink::Parquet(ParquetSink::new(
+            let partition_by = partition_columns(&parsed);
+            let sink = ParquetSink::new(parsed.path, output_name, job_id)?
+                .with_partition_by(partition_by);
+            Ok(Sink::Parquet(sink))
+        }
+        casparian_protocol::types::SinkScheme::Csv => {
+            if sink_mode != SinkMode::Append {
+                bail!(
+                    "CSV sink does not support {:?} mode (only Append)",
+                    sink_mode
+                );
+            }
+            Ok(Sink::Csv(Box::new(CsvSink::new(
+                parsed.path,
+                output_name,
+                job_id,
+            )?)))
+        }
+        casparian_protocol::types::SinkScheme::Jsonl => {
+            if sink_mode != SinkMode::Append {
+                bail!(
+                    "JSONL sink does not support {:?} mode (only Append)",
+                    sink_mode
+                );
+            }
+            Ok(Sink::Jsonl(Box::new(JsonlSink::new(
+                parsed.path,
+                output_name,
+                job_id,
+            )?)))
+        }
+        casparian_protocol::types::SinkScheme::Arrow => {
+            if sink_mode != SinkMode::Append {
+                bail!(
+                    "Arrow sink does not support {:?} mode (only Append)",
+                    sink_mode
+                );
+            }
+            let format = ArrowIpcFormat::from_uri(&parsed)?;
+            Ok(Sink::ArrowIpc(Box::new(ArrowIpcSink::new(
+                parsed.path,
+                output_name,
+                job_id,
+                format,
+            )?)))
+        }
+        casparian_protocol::types::SinkScheme::Duckdb => {
+            let table_name = output_table.unwrap_or(output_name);
+            let options = DuckDbSinkOptions::from_uri(&parsed, schema_evolution)?;
+            create_duckdb_sink(
                 parsed.path,
-                output_name,
+                table_name,
+                sink_mode,
+                options,
                 job_id,
-            )?))
+                output_name,
+            )
         }
-        casparian_protocol::types::SinkScheme::Csv => {
+        casparian_protocol::types::SinkScheme::S3 => {
             if sink_mode != SinkMode::Append {
                 bail!(
-                    "CSV sink does not support {:?} mode (only Append)",
+                    "S3 sink does not support {:?} mode (only Append)",
                     sink_mode
                 );
             }
-            Ok(Sink::Csv(Box::new(CsvSink::new(
-                parsed.path,
-                output_name,
-                job_id,
-            )?)))
+            create_s3_sink(&parsed, output_name, job_id)
         }
-        casparian_protocol::types::SinkScheme::Duckdb => {
+        casparian_protocol::types::SinkScheme::Postgres => {
             let table_name = output_table.unwrap_or(output_name);
-            create_duckdb_sink(parsed.path, table_name, sink_mode, job_id, output_name)
+            create_postgres_sink(&parsed, table_name, sink_mode, job_id, output_name)
         }
         casparian_protocol::types::SinkScheme::File => {
             // File sink: infer by extension
@@ -1022,9 +2411,57 @@ pub(crate) fn create_sink_from_uri(
                         job_id,
                     )?)))
                 }
+                "jsonl" | "ndjson" => {
+                    if sink_mode != SinkMode::Append {
+                        bail!(
+                            "JSONL sink does not support {:?} mode (only Append)",
+                            sink_mode
+                        );
+                    }
+                    Ok(Sink::Jsonl(Box::new(JsonlSink::new(
+                        parsed
+                            .path
+                            .parent()
+                            .unwrap_or_else(|| std::path::Path::new("."))
+                            .to_path_buf(),
+                        output_name,
+                        job_id,
+                    )?)))
+                }
+                "arrow" | "arrows" => {
+                    if sink_mode != SinkMode::Append {
+                        bail!(
+                            "Arrow sink does not support {:?} mode (only Append)",
+                            sink_mode
+                        );
+                    }
+                    let format = if ext == "arrow" {
+                        ArrowIpcFormat::File
+                    } else {
+                        ArrowIpcFormat::Stream
+                    };
+                    Ok(Sink::ArrowIpc(Box::new(ArrowIpcSink::new(
+                        parsed
+                            .path
+                            .parent()
+                            .unwrap_or_else(|| std::path::Path::new("."))
+                            .to_path_buf(),
+                        output_name,
+                        job_id,
+                        format,
+                    )?)))
+                }
                 "duckdb" | "db" => {
                     let table_name = output_table.unwrap_or(output_name);
-                    create_duckdb_sink(parsed.path, table_name, sink_mode, job_id, output_name)
+                    let options = DuckDbSinkOptions::from_uri(&parsed, schema_evolution)?;
+                    create_duckdb_sink(
+                        parsed.path,
+                        table_name,
+                        sink_mode,
+                        options,
+                        job_id,
+                        output_name,
+                    )
                 }
                 _ => bail!("Unsupported file sink extension: '{}'", ext),
             }
@@ -1086,6 +2523,72 @@ mod tests {
         assert!(!temp_path.exists());
     }
 
+    #[test]
+    fn test_parquet_sink_hive_partitioning() {
+        let dir = tempdir().unwrap();
+        let job_id = "job-partitioned";
+        let mut sink = ParquetSink::new(dir.path().to_path_buf(), "trades", job_id)
+            .unwrap()
+            .with_partition_by(vec!["date".to_string()]);
+
+        let schema = Schema::new(vec![
+            Field::new("date", DataType::Utf8, true),
+            Field::new("qty", DataType::Int64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("2024-01-01"),
+                    Some("2024-01-02"),
+                    Some("2024-01-01"),
+                    None,
+                ])),
+                Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+            ],
+        )
+        .unwrap();
+
+        sink.init(batch.schema().as_ref()).unwrap();
+        assert_eq!(sink.write_batch(&batch).unwrap(), 4);
+        sink.prepare().unwrap();
+        sink.commit().unwrap();
+
+        let filename = output_filename("trades", job_id, "parquet");
+        let read_rows = |partition: &str| {
+            let path = dir.path().join(partition).join(&filename);
+            let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+                .unwrap()
+                .build()
+                .unwrap();
+            let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+            // Partition column lives in the path, not the file.
+            assert_eq!(batches[0].schema().fields().len(), 1);
+            batches.iter().map(|b| b.num_rows()).sum::<usize>()
+        };
+        assert_eq!(read_rows("date=2024-01-01"), 2);
+        assert_eq!(read_rows("date=2024-01-02"), 1);
+        assert_eq!(read_rows("date=__HIVE_DEFAULT_PARTITION__"), 1);
+        assert!(!dir.path().join(&filename).exists());
+    }
+
+    #[test]
+    fn test_parquet_partition_column_must_exist() {
+        let dir = tempdir().unwrap();
+        let mut sink = ParquetSink::new(dir.path().to_path_buf(), "test", "job-1")
+            .unwrap()
+            .with_partition_by(vec!["missing".to_string()]);
+        let batch = create_test_batch();
+        assert!(sink.init(batch.schema().as_ref()).is_err());
+    }
+
+    #[test]
+    fn test_escape_partition_value() {
+        assert_eq!(escape_partition_value("2024-01-01"), "2024-01-01");
+        assert_eq!(escape_partition_value("a/b=c"), "a%2Fb%3Dc");
+        assert_eq!(escape_partition_value("10:30"), "10%3A30");
+    }
+
     #[test]
     fn conf_t1_parquet_decimal_timestamp_tz_roundtrip() {
         let dir = tempdir().unwrap();
@@ -1199,6 +2702,118 @@ mod tests {
         assert!(!temp_path.exists());
     }
 
+    #[test]
+    fn test_jsonl_sink() {
+        let dir = tempdir().unwrap();
+        let job_id = "12345678-abcd-1234-abcd-123456789abc";
+        let mut sink = JsonlSink::new(dir.path().to_path_buf(), "test", job_id).unwrap();
+
+        let batch = create_test_batch();
+        sink.init(batch.schema().as_ref()).unwrap();
+        let rows = sink.write_batch(&batch).unwrap();
+        assert_eq!(rows, 3);
+
+        sink.prepare().unwrap();
+        sink.commit().unwrap();
+
+        let output_path = dir.path().join(output_filename("test", job_id, "jsonl"));
+        let content = std::fs::read_to_string(&output_path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with('{'));
+        assert!(lines[0].contains("\"Alice\""));
+
+        let temp_path = dir
+            .path()
+            .join(format!(".{}.tmp", output_filename("test", job_id, "jsonl")));
+        assert!(!temp_path.exists());
+    }
+
+    #[test]
+    fn test_jsonl_sink_from_uri_and_extension() {
+        let dir = tempdir().unwrap();
+        let scheme_uri = format!("jsonl://{}", dir.path().display());
+        let evolution = SchemaEvolution::default();
+        let sink =
+            create_sink_from_uri(&scheme_uri, "out", None, SinkMode::Append, evolution, "job-1");
+        assert!(matches!(sink, Ok(Sink::Jsonl(_))));
+
+        let file_uri = format!("file://{}/out.jsonl", dir.path().display());
+        let sink =
+            create_sink_from_uri(&file_uri, "out", None, SinkMode::Append, evolution, "job-1");
+        assert!(matches!(sink, Ok(Sink::Jsonl(_))));
+
+        let sink =
+            create_sink_from_uri(&scheme_uri, "out", None, SinkMode::Replace, evolution, "job-1");
+        assert!(sink.is_err());
+    }
+
+    #[test]
+    fn test_ndjson_artifact_uri_matches_written_file() {
+        let dir = tempdir().unwrap();
+        let job_id = "job-ndjson";
+        let outputs = [OutputPlan::new(
+            "test",
+            None,
+            vec![OutputBatch::from_record_batch(create_test_batch())],
+            SinkMode::Append,
+        )];
+
+        let file_uri = format!("file://{}/x.ndjson", dir.path().display());
+        let artifacts = write_output_plan(&file_uri, &outputs, job_id, None).unwrap();
+        let written = dir.path().join(output_filename("test", job_id, "jsonl"));
+        assert_eq!(artifacts[0].uri, format!("file://{}", written.display()));
+        assert!(written.exists());
+    }
+
+    #[test]
+    fn test_arrow_ipc_sink_round_trip() {
+        let dir = tempdir().unwrap();
+        let job_id = "job-arrow";
+        let outputs = [OutputPlan::new(
+            "test",
+            None,
+            vec![OutputBatch::from_record_batch(create_test_batch())],
+            SinkMode::Append,
+        )];
+
+        let stream_uri = format!("arrow://{}", dir.path().display());
+        let artifacts = write_output_plan(&stream_uri, &outputs, job_id, None).unwrap();
+        let stream_path = dir.path().join(output_filename("test", job_id, "arrows"));
+        assert_eq!(
+            artifacts[0].uri,
+            format!("file://{}", stream_path.display())
+        );
+        let reader =
+            arrow::ipc::reader::StreamReader::try_new(File::open(&stream_path).unwrap(), None)
+                .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+        assert_eq!(batches, vec![create_test_batch()]);
+
+        let file_uri = format!("arrow://{}?format=file", dir.path().display());
+        write_output_plan(&file_uri, &outputs, job_id, None).unwrap();
+        let file_path = dir.path().join(output_filename("test", job_id, "arrow"));
+        let reader =
+            arrow::ipc::reader::FileReader::try_new(File::open(&file_path).unwrap(), None).unwrap();
+        assert_eq!(reader.num_batches(), 1);
+
+        let evolution = SchemaEvolution::default();
+        let sink = create_sink_from_uri(
+            &format!("file://{}/out.arrow", dir.path().display()),
+            "out",
+            None,
+            SinkMode::Append,
+            evolution,
+            job_id,
+        );
+        assert!(matches!(sink, Ok(Sink::ArrowIpc(_))));
+        let bad_uri = format!("arrow://{}?format=feather", dir.path().display());
+        assert!(
+            create_sink_from_uri(&bad_uri, "out", None, SinkMode::Append, evolution, job_id)
+                .is_err()
+        );
+    }
+
     #[cfg(not(feature = "sink-duckdb"))]
     #[test]
     fn test_duckdb_disabled_error() {
@@ -1207,6 +2822,7 @@ mod tests {
             "output",
             None,
             SinkMode::Append,
+            SchemaEvolution::default(),
             "job-1",
         ) {
             Ok(_) => panic!("expected DuckDB disabled error"),
@@ -1219,6 +2835,48 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "sink-s3"))]
+    #[test]
+    fn test_s3_disabled_error() {
+        let err = match create_sink_from_uri(
+            "s3://bucket/prefix",
+            "output",
+            None,
+            SinkMode::Append,
+            SchemaEvolution::default(),
+            "job-1",
+        ) {
+            Ok(_) => panic!("expected S3 disabled error"),
+            Err(err) => err,
+        };
+        assert!(
+            err.to_string().contains(S3_DISABLED),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[cfg(not(feature = "sink-postgres"))]
+    #[test]
+    fn test_postgres_disabled_error() {
+        let err = match create_sink_from_uri(
+            "postgres://cf@localhost:5432/warehouse",
+            "output",
+            None,
+            SinkMode::Append,
+            SchemaEvolution::default(),
+            "job-1",
+        ) {
+            Ok(_) => panic!("expected PostgreSQL disabled error"),
+            Err(err) => err,
+        };
+        assert!(
+            err.to_string().contains(POSTGRES_DISABLED),
+            "unexpected error: {}",
+            err
+        );
+    }
+
     #[test]
     fn conf_t1_lineage_injection_appends_columns() {
         let batch = OutputBatch::from_record_batch(create_test_batch());
@@ -1312,4 +2970,317 @@ mod tests {
             .join(output_filename(output_name, job_id, "parquet"));
         assert!(!final_path.exists(), "final output should not exist");
     }
+
+    #[test]
+    fn test_write_routed_output_plan_splits_outputs_by_sink() {
+        let parquet_dir = tempdir().unwrap();
+        let csv_dir = tempdir().unwrap();
+        let plan = |name: &str| {
+            OutputPlan::new(
+                name,
+                None,
+                vec![OutputBatch::from_record_batch(create_test_batch())],
+                SinkMode::Append,
+            )
+        };
+        let routes = OutputRoutes::new(Some(format!("parquet://{}", parquet_dir.path().display())))
+            .route(
+                "events",
+                format!("csv://{}", csv_dir.path().display()),
+                None,
+            );
+        assert_eq!(routes.resolve("notes").unwrap().mode, None);
+
+        let artifacts = write_routed_output_plan(
+            &routes,
+            &[plan("events"), plan("annotations")],
+            "job-routed",
+            None,
+        )
+        .unwrap();
+        assert_eq!(artifacts.len(), 2);
+        assert!(csv_dir
+            .path()
+            .join(output_filename("events", "job-routed", "csv"))
+            .exists());
+        assert!(parquet_dir
+            .path()
+            .join(output_filename("annotations", "job-routed", "parquet"))
+            .exists());
+
+        let routes = OutputRoutes::new(None).route("events", "csv:///tmp/unused", None);
+        let err =
+            match write_routed_output_plan(&routes, &[plan("events"), plan("notes")], "j", None) {
+                Ok(_) => panic!("untargeted output should fail"),
+                Err(err) => err,
+            };
+        assert!(err.to_string().contains("[notes]"), "{}", err);
+
+        // Per-output modes reach the sink: file sinks reject Replace.
+        let routes = routes.with_mode("events", SinkMode::Replace).unwrap();
+        assert_eq!(
+            routes.resolve("events").unwrap().mode,
+            Some(SinkMode::Replace)
+        );
+        let err = match write_routed_output_plan(&routes, &[plan("events")], "j", None) {
+            Ok(_) => panic!("csv sink should reject Replace"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("Replace"), "{}", err);
+        assert!(OutputRoutes::new(None)
+            .with_mode("events", SinkMode::Replace)
+            .is_err());
+    }
+
+    #[test]
+    fn test_parallel_encoding_reports_write_timings() {
+        let parquet_dir = tempdir().unwrap();
+        let csv_dir = tempdir().unwrap();
+        let plan = |name: &str| {
+            OutputPlan::new(
+                name,
+                None,
+                vec![
+                    OutputBatch::from_record_batch(create_test_batch()),
+                    OutputBatch::from_record_batch(create_test_batch()),
+                ],
+                SinkMode::Append,
+            )
+            .with_encode_parallelism(2)
+        };
+        let routes = OutputRoutes::new(Some(format!("parquet://{}", parquet_dir.path().display())))
+            .route("zeta", format!("csv://{}", csv_dir.path().display()), None);
+
+        let artifacts =
+            write_routed_output_plan(&routes, &[plan("alpha"), plan("zeta")], "job-par", None)
+                .unwrap();
+        assert!(artifacts
+            .iter()
+            .all(|artifact| artifact.write_timings.is_some()));
+
+        let parquet_path = parquet_dir
+            .path()
+            .join(output_filename("alpha", "job-par", "parquet"));
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(parquet_path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(rows, 2 * create_test_batch().num_rows());
+
+        let csv = std::fs::read_to_string(
+            csv_dir
+                .path()
+                .join(output_filename("zeta", "job-par", "csv")),
+        )
+        .unwrap();
+        assert_eq!(csv.lines().count(), 1 + 2 * create_test_batch().num_rows());
+    }
+
+    #[test]
+    fn test_routed_sinks_commit_or_roll_back_together() {
+        let parquet_dir = tempdir().unwrap();
+        let csv_dir = tempdir().unwrap();
+        let plan = |name: &str| {
+            OutputPlan::new(
+                name,
+                None,
+                vec![OutputBatch::from_record_batch(create_test_batch())],
+                SinkMode::Append,
+            )
+        };
+        let routes = OutputRoutes::new(Some(format!("parquet://{}", parquet_dir.path().display())))
+            .route("zeta", format!("csv://{}", csv_dir.path().display()), None);
+
+        // A non-empty directory where the CSV file goes makes its commit
+        // fail after the Parquet output ("alpha") has committed.
+        let blocked = csv_dir
+            .path()
+            .join(output_filename("zeta", "job-2pc", "csv"));
+        std::fs::create_dir_all(blocked.join("occupied")).unwrap();
+
+        let err = match write_routed_output_plan(
+            &routes,
+            &[plan("alpha"), plan("zeta")],
+            "job-2pc",
+            None,
+        ) {
+            Ok(_) => panic!("blocked commit should fail"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("Failed to rename"), "{}", err);
+        let parquet_files: Vec<_> = std::fs::read_dir(parquet_dir.path())
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name())
+            .collect();
+        assert!(parquet_files.is_empty(), "left behind: {:?}", parquet_files);
+        assert!(!csv_dir
+            .path()
+            .join(format!(
+                ".{}.tmp",
+                output_filename("zeta", "job-2pc", "csv")
+            ))
+            .exists());
+    }
+
+    #[test]
+    fn test_rollback_restores_file_replaced_by_commit() {
+        let parquet_dir = tempdir().unwrap();
+        let csv_dir = tempdir().unwrap();
+        let plan = |name: &str| {
+            OutputPlan::new(
+                name,
+                None,
+                vec![OutputBatch::from_record_batch(create_test_batch())],
+                SinkMode::Append,
+            )
+        };
+        let routes = OutputRoutes::new(Some(format!("parquet://{}", parquet_dir.path().display())))
+            .route("zeta", format!("csv://{}", csv_dir.path().display()), None);
+
+        // An earlier attempt of the same job already committed "alpha".
+        let previous = parquet_dir
+            .path()
+            .join(output_filename("alpha", "job-retry", "parquet"));
+        std::fs::write(&previous, b"previous attempt").unwrap();
+        let blocked = csv_dir
+            .path()
+            .join(output_filename("zeta", "job-retry", "csv"));
+        std::fs::create_dir_all(blocked.join("occupied")).unwrap();
+
+        let result =
+            write_routed_output_plan(&routes, &[plan("alpha"), plan("zeta")], "job-retry", None);
+        assert!(result.is_err(), "blocked commit should fail");
+        assert_eq!(std::fs::read(&previous).unwrap(), b"previous attempt");
+        let parquet_files: Vec<_> = std::fs::read_dir(parquet_dir.path())
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name())
+            .collect();
+        assert_eq!(parquet_files.len(), 1, "left behind: {:?}", parquet_files);
+
+        // Once every sink commits, the replaced file's backup is dropped.
+        std::fs::remove_dir_all(&blocked).unwrap();
+        write_routed_output_plan(&routes, &[plan("alpha"), plan("zeta")], "job-retry", None)
+            .unwrap();
+        assert_ne!(std::fs::read(&previous).unwrap(), b"previous attempt");
+        let parquet_files: Vec<_> = std::fs::read_dir(parquet_dir.path())
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name())
+            .collect();
+        assert_eq!(parquet_files.len(), 1, "left behind: {:?}", parquet_files);
+    }
+
+    #[test]
+    fn test_write_routed_output_plan_resolves_placeholders() {
+        let dir = tempdir().unwrap();
+        let plan = |name: &str| {
+            OutputPlan::new(
+                name,
+                None,
+                vec![OutputBatch::from_record_batch(create_test_batch())],
+                SinkMode::Append,
+            )
+        };
+        let template_uri = format!("csv://{}/{{plugin}}/{{output}}", dir.path().display());
+        let err = match write_output_plan(&template_uri, &[plan("events")], "j", None) {
+            Ok(_) => panic!("unresolved placeholders should fail"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("placeholders"), "{}", err);
+
+        let routes = OutputRoutes::new(Some(template_uri))
+            .with_template_vars(template::TemplateVars::new("trades"));
+        let artifacts =
+            write_routed_output_plan(&routes, &[plan("events"), plan("notes")], "j", None).unwrap();
+        assert_eq!(artifacts.len(), 2);
+        for name in ["events", "notes"] {
+            assert!(dir
+                .path()
+                .join("trades")
+                .join(name)
+                .join(output_filename(name, "j", "csv"))
+                .exists());
+        }
+    }
+
+    #[test]
+    fn test_cancel_mid_write_discards_staged_output() {
+        let dir = tempdir().unwrap();
+        let sink_uri = format!("csv://{}", dir.path().display());
+        let output_plan = OutputPlan::new(
+            "cancelled",
+            None,
+            vec![
+                OutputBatch::from_record_batch(create_test_batch()),
+                OutputBatch::from_record_batch(create_test_batch()),
+            ],
+            SinkMode::Append,
+        );
+
+        // Cancel after the first batch has been staged.
+        let checks = std::cell::Cell::new(0);
+        let guard = || {
+            checks.set(checks.get() + 1);
+            checks.get() < 2
+        };
+        let err = match write_output_plan(&sink_uri, &[output_plan], "job-cancel", Some(&guard)) {
+            Ok(_) => panic!("cancelled write should fail"),
+            Err(err) => err,
+        };
+        assert!(
+            err.to_string().contains("Output commit aborted"),
+            "unexpected error: {}",
+            err
+        );
+        let leftovers: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert!(leftovers.is_empty(), "staged files left behind: {:?}", leftovers);
+    }
+
+    #[test]
+    fn test_column_stats_reported_and_null_limit_enforced() {
+        let dir = tempdir().unwrap();
+        let sink_uri = format!("parquet://{}", dir.path().display());
+        let plan = || {
+            OutputPlan::new(
+                "people",
+                None,
+                vec![OutputBatch::from_record_batch(create_test_batch())],
+                SinkMode::Append,
+            )
+        };
+
+        let artifacts = write_output_plan(&sink_uri, &[plan()], "job-plain", None).unwrap();
+        assert!(artifacts[0].column_stats.is_empty());
+
+        let artifacts =
+            write_output_plan(&sink_uri, &[plan().with_column_stats()], "job-stats", None).unwrap();
+        let stats = &artifacts[0].column_stats;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[1].column_name, "name");
+        assert_eq!(stats[1].null_count, 1);
+        assert_eq!(stats[1].min.as_deref(), Some("Alice"));
+
+        // One of three names is null: 33% breaks a 25% limit.
+        let strict = QuarantineConfig {
+            max_null_pct: Some(25.0),
+            ..Default::default()
+        };
+        let err = match write_output_plan(
+            &sink_uri,
+            &[plan().with_quarantine(strict)],
+            "job-nulls",
+            None,
+        ) {
+            Ok(_) => panic!("null limit should fail the write"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("'people.name'"), "{}", err);
+        assert!(!dir
+            .path()
+            .join(output_filename("people", "job-nulls", "parquet"))
+            .exists());
+    }
 }
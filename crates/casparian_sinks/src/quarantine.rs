@@ -0,0 +1,651 @@
+//! Row-level quarantine for schema-violating output.
+//!
+//! When an `OutputPlan` carries a `QuarantineConfig`, batches that do not
+//! match the output schema (the schema of its first batch) are checked row by
+//! row instead of failing the whole write. Conforming rows are cast to the
+//! output schema and written as usual; violating rows go to a sibling
+//! `{output}_quarantine` output with every data column rendered as text plus
+//! violation metadata columns. The config's thresholds decide whether the
+//! write proceeds at all.
+//!
+//! Quarantine outputs are written to the same sink URI as the output itself;
+//! `quarantine_dir` routing is the caller's concern.
+
+use anyhow::{Context, Result};
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, StringBuilder,
+};
+use arrow::compute::{cast_with_options, filter, filter_record_batch, CastOptions};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use casparian_protocol::{ColumnStats, QuarantineConfig, SinkMode};
+
+use crate::{validate_batch_schema, OutputBatch, OutputPlan, SinkError, SinkResult};
+
+/// Suffix appended to output and table names for quarantined rows.
+pub const QUARANTINE_SUFFIX: &str = "_quarantine";
+
+/// Row position within the output (across all batches), 0-based.
+pub const OUTPUT_ROW_INDEX_COLUMN: &str = "_output_row_index";
+/// Violation class: `schema`, `type_conversion`, or `null_not_allowed`.
+pub const VIOLATION_TYPE_COLUMN: &str = "_violation_type";
+/// Offending column, when the violation is tied to one.
+pub const VIOLATION_COLUMN_COLUMN: &str = "_violation_column";
+/// Human-readable description of the violation.
+pub const ERROR_MSG_COLUMN: &str = "_error_msg";
+
+/// Return a policy failure reason if `quarantine_rows` breaks `config`.
+pub fn check_quarantine_policy(
+    output_name: &str,
+    quarantine_rows: u64,
+    total_rows: u64,
+    config: &QuarantineConfig,
+) -> Option<String> {
+    if quarantine_rows == 0 {
+        return None;
+    }
+    if !config.allow_quarantine {
+        return Some(format!(
+            "quarantine disabled for '{}': {} rows",
+            output_name, quarantine_rows
+        ));
+    }
+
+    if let Some(max_count) = config.max_quarantine_count {
+        if quarantine_rows > max_count {
+            return Some(format!(
+                "quarantine count exceeded for '{}': {} > {}",
+                output_name, quarantine_rows, max_count
+            ));
+        }
+    }
+
+    let pct = quarantine_pct(quarantine_rows, total_rows);
+    if pct > config.max_quarantine_pct {
+        return Some(format!(
+            "quarantine pct exceeded for '{}': {:.2}% > {:.2}%",
+            output_name, pct, config.max_quarantine_pct
+        ));
+    }
+
+    None
+}
+
+/// Return a policy failure reason if any column's null share exceeds
+/// `max_null_pct`.
+pub fn check_null_policy(
+    output_name: &str,
+    stats: &[ColumnStats],
+    max_null_pct: f64,
+) -> Option<String> {
+    stats
+        .iter()
+        .find(|column| column.null_pct() > max_null_pct)
+        .map(|column| {
+            format!(
+                "null pct exceeded for '{}.{}': {:.2}% > {:.2}%",
+                output_name,
+                column.column_name,
+                column.null_pct(),
+                max_null_pct
+            )
+        })
+}
+
+pub fn quarantine_pct(quarantine_rows: u64, total_rows: u64) -> f64 {
+    if total_rows == 0 {
+        0.0
+    } else {
+        (quarantine_rows as f64 / total_rows as f64) * 100.0
+    }
+}
+
+/// Split every plan that has a quarantine config into its conforming plan and,
+/// if any rows violated the schema, a `{name}_quarantine` plan.
+///
+/// Plans without a config are returned unchanged. Fails if a plan's
+/// quarantine policy is exceeded.
+pub(crate) fn apply_quarantine(outputs: &[OutputPlan]) -> SinkResult<Vec<OutputPlan>> {
+    let mut planned = Vec::with_capacity(outputs.len());
+    for output in outputs {
+        let Some(config) = output.quarantine_config() else {
+            planned.push(output.clone());
+            continue;
+        };
+        let split = split_output(output)?;
+        let total_rows = (split.conforming_rows + split.quarantined_rows) as u64;
+        if let Some(reason) = check_quarantine_policy(
+            output.name(),
+            split.quarantined_rows as u64,
+            total_rows,
+            config,
+        ) {
+            return Err(SinkError::message(reason));
+        }
+        planned.push(split.conforming);
+        if let Some(quarantine) = split.quarantine {
+            planned.push(quarantine);
+        }
+    }
+    Ok(planned)
+}
+
+struct OutputSplit {
+    conforming: OutputPlan,
+    quarantine: Option<OutputPlan>,
+    conforming_rows: usize,
+    quarantined_rows: usize,
+}
+
+fn split_output(output: &OutputPlan) -> SinkResult<OutputSplit> {
+    let Some(first) = output.batches().first() else {
+        return Ok(OutputSplit {
+            conforming: output.clone(),
+            quarantine: None,
+            conforming_rows: 0,
+            quarantined_rows: 0,
+        });
+    };
+    let declared = first.schema();
+
+    let mut conforming_batches = Vec::new();
+    let mut quarantine_batches = Vec::new();
+    let mut conforming_rows = 0;
+    let mut quarantined_rows = 0;
+    let mut row_offset = 0;
+
+    for batch in output.batches() {
+        let record_batch = batch.record_batch();
+        if validate_batch_schema(record_batch, declared.as_ref(), output.name()).is_ok() {
+            conforming_rows += record_batch.num_rows();
+            row_offset += record_batch.num_rows();
+            conforming_batches.push(batch.clone());
+            continue;
+        }
+
+        let checked = check_batch(record_batch, &declared)
+            .with_context(|| format!("quarantine check failed for output '{}'", output.name()))?;
+        if let Some(valid) = checked.conforming {
+            conforming_rows += valid.num_rows();
+            conforming_batches.push(OutputBatch::from_record_batch(valid));
+        }
+        if !checked.violations.is_empty() {
+            quarantined_rows += checked.violations.len();
+            let quarantined =
+                build_quarantine_batch(record_batch, &declared, &checked.violations, row_offset)
+                    .with_context(|| {
+                        format!("failed to build quarantine rows for '{}'", output.name())
+                    })?;
+            quarantine_batches.push(OutputBatch::from_record_batch(quarantined));
+        }
+        row_offset += record_batch.num_rows();
+    }
+
+    let mut conforming = OutputPlan::new(
+        output.name(),
+        output.table().map(str::to_string),
+        conforming_batches,
+        output.sink_mode(),
+    )
+    .with_schema_evolution(output.schema_evolution());
+    // Stats and the null limit apply to the rows that are actually written.
+    conforming.column_stats = output.collects_column_stats();
+    conforming.max_null_pct = output.max_null_pct();
+    let quarantine = (!quarantine_batches.is_empty()).then(|| {
+        OutputPlan::new(
+            format!("{}{}", output.name(), QUARANTINE_SUFFIX),
+            output
+                .table()
+                .map(|table| format!("{}{}", table, QUARANTINE_SUFFIX)),
+            quarantine_batches,
+            SinkMode::Append,
+        )
+    });
+
+    Ok(OutputSplit {
+        conforming,
+        quarantine,
+        conforming_rows,
+        quarantined_rows,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RowViolation {
+    /// Row index within the batch
+    row: usize,
+    violation_type: &'static str,
+    column: Option<String>,
+    message: String,
+}
+
+struct CheckedBatch {
+    /// Conforming rows cast to the declared schema
+    conforming: Option<RecordBatch>,
+    /// At most one violation per row, in row order
+    violations: Vec<RowViolation>,
+}
+
+fn check_batch(batch: &RecordBatch, declared: &Arc<Schema>) -> Result<CheckedBatch> {
+    let rows = batch.num_rows();
+    let schema = batch.schema();
+
+    // Structural mismatches can't be fixed per row: quarantine the whole batch.
+    let declared_names: HashSet<&str> = declared
+        .fields()
+        .iter()
+        .map(|field| field.name().as_str())
+        .collect();
+    let missing = declared
+        .fields()
+        .iter()
+        .find(|field| schema.index_of(field.name()).is_err());
+    let extra = schema
+        .fields()
+        .iter()
+        .find(|field| !declared_names.contains(field.name().as_str()));
+    let structural = match (missing, extra) {
+        (Some(field), _) => Some((
+            field.name().clone(),
+            format!("schema: column '{}' is missing", field.name()),
+        )),
+        (None, Some(field)) => Some((
+            field.name().clone(),
+            format!("schema: unexpected column '{}'", field.name()),
+        )),
+        (None, None) => None,
+    };
+    if let Some((column, message)) = structural {
+        let violations = (0..rows)
+            .map(|row| RowViolation {
+                row,
+                violation_type: "schema",
+                column: Some(column.clone()),
+                message: message.clone(),
+            })
+            .collect();
+        return Ok(CheckedBatch {
+            conforming: None,
+            violations,
+        });
+    }
+
+    let mut violations: Vec<Option<RowViolation>> = vec![None; rows];
+    let mut cast_columns = Vec::with_capacity(declared.fields().len());
+    let cast_options = CastOptions {
+        safe: true,
+        ..Default::default()
+    };
+
+    for field in declared.fields() {
+        let source = batch
+            .column_by_name(field.name())
+            .context("declared column disappeared from batch")?;
+        let cast = if source.data_type() == field.data_type() {
+            Some(source.clone())
+        } else {
+            cast_with_options(source, field.data_type(), &cast_options).ok()
+        };
+
+        for (row, slot) in violations.iter_mut().enumerate() {
+            if slot.is_some() {
+                continue;
+            }
+            let violation = match &cast {
+                None => Some((
+                    "type_conversion",
+                    format!(
+                        "schema: column '{}' cannot be converted from {} to {}",
+                        field.name(),
+                        source.data_type(),
+                        field.data_type()
+                    ),
+                )),
+                Some(array) if array.is_null(row) && source.is_valid(row) => Some((
+                    "type_conversion",
+                    format!(
+                        "schema: column '{}' value cannot be converted to {}",
+                        field.name(),
+                        field.data_type()
+                    ),
+                )),
+                Some(array) if array.is_null(row) && !field.is_nullable() => Some((
+                    "null_not_allowed",
+                    format!("schema: column '{}' null not allowed", field.name()),
+                )),
+                Some(_) => None,
+            };
+            if let Some((violation_type, message)) = violation {
+                *slot = Some(RowViolation {
+                    row,
+                    violation_type,
+                    column: Some(field.name().clone()),
+                    message,
+                });
+            }
+        }
+
+        cast_columns.push(cast);
+    }
+
+    let keep = BooleanArray::from(
+        violations
+            .iter()
+            .map(|violation| violation.is_none())
+            .collect::<Vec<_>>(),
+    );
+    let violations: Vec<RowViolation> = violations.into_iter().flatten().collect();
+
+    let conforming = if violations.len() == rows {
+        None
+    } else {
+        let mut columns = Vec::with_capacity(cast_columns.len());
+        for column in cast_columns {
+            // A failed cast marks every row, so reaching here means all casts succeeded.
+            let column = column.context("cast column missing for conforming rows")?;
+            columns.push(filter(column.as_ref(), &keep)?);
+        }
+        Some(RecordBatch::try_new(declared.clone(), columns)?)
+    };
+
+    Ok(CheckedBatch {
+        conforming,
+        violations,
+    })
+}
+
+/// Render the violating rows as text columns in declared order plus metadata.
+fn build_quarantine_batch(
+    batch: &RecordBatch,
+    declared: &Schema,
+    violations: &[RowViolation],
+    row_offset: usize,
+) -> Result<RecordBatch> {
+    let rows = BooleanArray::from({
+        let mut mask = vec![false; batch.num_rows()];
+        for violation in violations {
+            mask[violation.row] = true;
+        }
+        mask
+    });
+    let selected = filter_record_batch(batch, &rows)?;
+
+    let mut fields = Vec::with_capacity(declared.fields().len() + 4);
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(declared.fields().len() + 4);
+    for field in declared.fields() {
+        fields.push(Field::new(field.name(), DataType::Utf8, true));
+        columns.push(match selected.column_by_name(field.name()) {
+            Some(array) => render_as_text(array.as_ref())?,
+            None => Arc::new(StringArray::new_null(selected.num_rows())),
+        });
+    }
+
+    let mut row_index = Vec::with_capacity(violations.len());
+    let mut violation_type = StringBuilder::new();
+    let mut violation_column = StringBuilder::new();
+    let mut error_msg = StringBuilder::new();
+    for violation in violations {
+        let index =
+            i64::try_from(row_offset + violation.row).context("output row index overflow")?;
+        row_index.push(index);
+        violation_type.append_value(violation.violation_type);
+        violation_column.append_option(violation.column.as_deref());
+        error_msg.append_value(&violation.message);
+    }
+
+    fields.push(Field::new(OUTPUT_ROW_INDEX_COLUMN, DataType::Int64, false));
+    columns.push(Arc::new(Int64Array::from(row_index)));
+    fields.push(Field::new(VIOLATION_TYPE_COLUMN, DataType::Utf8, false));
+    columns.push(Arc::new(violation_type.finish()));
+    fields.push(Field::new(VIOLATION_COLUMN_COLUMN, DataType::Utf8, true));
+    columns.push(Arc::new(violation_column.finish()));
+    fields.push(Field::new(ERROR_MSG_COLUMN, DataType::Utf8, false));
+    columns.push(Arc::new(error_msg.finish()));
+
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+fn render_as_text(array: &dyn Array) -> Result<ArrayRef> {
+    let formatter = ArrayFormatter::try_new(array, &FormatOptions::default())?;
+    let mut builder = StringBuilder::with_capacity(array.len(), array.len() * 8);
+    for row in 0..array.len() {
+        if array.is_null(row) {
+            builder.append_null();
+        } else {
+            builder.append_value(formatter.value(row).to_string());
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{output_filename, write_output_plan};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    fn typed_batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("a"), Some("b")])),
+            ],
+        )
+        .unwrap()
+    }
+
+    /// Same columns, but `id` arrives as text (as from a loosely typed parser).
+    fn text_batch(ids: Vec<Option<&str>>) -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Utf8, true),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        let names: Vec<Option<&str>> = ids.iter().map(|_| Some("x")).collect();
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(ids)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn allow_all() -> QuarantineConfig {
+        QuarantineConfig {
+            allow_quarantine: true,
+            max_quarantine_pct: 100.0,
+            ..Default::default()
+        }
+    }
+
+    fn plan(batches: Vec<RecordBatch>, config: Option<QuarantineConfig>) -> OutputPlan {
+        let plan = OutputPlan::new(
+            "orders",
+            Some("orders".to_string()),
+            batches
+                .into_iter()
+                .map(OutputBatch::from_record_batch)
+                .collect(),
+            SinkMode::Append,
+        );
+        match config {
+            Some(config) => plan.with_quarantine(config),
+            None => plan,
+        }
+    }
+
+    #[test]
+    fn test_policy_thresholds() {
+        let config = QuarantineConfig {
+            allow_quarantine: true,
+            max_quarantine_pct: 5.0,
+            max_quarantine_count: Some(2),
+            ..Default::default()
+        };
+        assert!(check_quarantine_policy("out", 0, 10, &config).is_none());
+        assert!(check_quarantine_policy("out", 3, 1000, &config)
+            .unwrap()
+            .contains("count exceeded"));
+        assert!(check_quarantine_policy("out", 2, 10, &config)
+            .unwrap()
+            .contains("pct exceeded"));
+        assert!(check_quarantine_policy("out", 1, 100, &config).is_none());
+        assert!(
+            check_quarantine_policy("out", 1, 100, &QuarantineConfig::default())
+                .unwrap()
+                .contains("quarantine disabled")
+        );
+    }
+
+    #[test]
+    fn test_split_casts_conforming_and_quarantines_bad_values() {
+        let output = plan(
+            vec![
+                typed_batch(),
+                text_batch(vec![Some("3"), Some("oops"), None]),
+            ],
+            Some(allow_all()),
+        );
+        let planned = apply_quarantine(&[output]).unwrap();
+        assert_eq!(planned.len(), 2);
+
+        let conforming = &planned[0];
+        assert_eq!(conforming.name(), "orders");
+        let rows: usize = conforming.batches().iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 3);
+        let cast = conforming.batches()[1].record_batch();
+        assert_eq!(cast.schema().field(0).data_type(), &DataType::Int64);
+        let ids = cast
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(ids.value(0), 3);
+
+        let quarantine = &planned[1];
+        assert_eq!(quarantine.name(), "orders_quarantine");
+        assert_eq!(quarantine.table(), Some("orders_quarantine"));
+        let batch = quarantine.batches()[0].record_batch();
+        assert_eq!(batch.num_rows(), 2);
+
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .unwrap()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap()
+                .clone()
+        };
+        let ids = column("id");
+        assert_eq!(ids.value(0), "oops");
+        assert!(ids.is_null(1));
+        let types = column(VIOLATION_TYPE_COLUMN);
+        assert_eq!(types.value(0), "type_conversion");
+        assert_eq!(types.value(1), "null_not_allowed");
+        assert_eq!(column(VIOLATION_COLUMN_COLUMN).value(0), "id");
+        let index = batch
+            .column_by_name(OUTPUT_ROW_INDEX_COLUMN)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(index.values().to_vec(), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_split_quarantines_batch_with_missing_column() {
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+        let narrow =
+            RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Int64Array::from(vec![9]))])
+                .unwrap();
+        let planned =
+            apply_quarantine(&[plan(vec![typed_batch(), narrow], Some(allow_all()))]).unwrap();
+
+        let quarantine = planned[1].batches()[0].record_batch();
+        assert_eq!(quarantine.num_rows(), 1);
+        let message = quarantine
+            .column_by_name(ERROR_MSG_COLUMN)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .value(0)
+            .to_string();
+        assert!(message.contains("'name' is missing"), "{}", message);
+    }
+
+    #[test]
+    fn test_policy_exceeded_fails_split() {
+        let config = QuarantineConfig {
+            allow_quarantine: true,
+            max_quarantine_pct: 10.0,
+            ..Default::default()
+        };
+        let err = match apply_quarantine(&[plan(
+            vec![typed_batch(), text_batch(vec![Some("bad")])],
+            Some(config),
+        )]) {
+            Ok(_) => panic!("policy should reject 1 of 3 rows"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("pct exceeded"), "{}", err);
+    }
+
+    #[test]
+    fn test_without_config_plans_pass_through() {
+        let output = plan(vec![typed_batch(), text_batch(vec![Some("bad")])], None);
+        let planned = apply_quarantine(&[output]).unwrap();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].batches().len(), 2);
+    }
+
+    #[test]
+    fn test_write_output_plan_writes_quarantine_parquet() {
+        let dir = tempdir().unwrap();
+        let sink_uri = format!("parquet://{}", dir.path().display());
+        let job_id = "job-quarantine";
+        let output = plan(
+            vec![typed_batch(), text_batch(vec![Some("7"), Some("x")])],
+            Some(allow_all()),
+        );
+
+        let artifacts = write_output_plan(&sink_uri, &[output], job_id, None).unwrap();
+        let rows: Vec<(String, u64)> = artifacts
+            .iter()
+            .map(|artifact| (artifact.name.clone(), artifact.rows))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("orders".to_string(), 3),
+                ("orders_quarantine".to_string(), 1)
+            ]
+        );
+
+        let path = dir
+            .path()
+            .join(output_filename("orders_quarantine", job_id, "parquet"));
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+        assert_eq!(batches[0].num_rows(), 1);
+        assert!(batches[0].schema().index_of(ERROR_MSG_COLUMN).is_ok());
+    }
+}
@@ -0,0 +1,602 @@
+//! S3-compatible object-store sink (AWS S3, MinIO, Ceph RGW).
+//!
+//! Output is encoded as Parquet and streamed to the bucket with a multipart
+//! upload. Parts are staged while batches are written; the object only becomes
+//! visible when the upload is completed during `commit()`, so readers never
+//! observe a partial file.
+//!
+//! Sink URI: `s3://bucket/prefix?endpoint=http://minio:9000&region=us-east-1&path_style=true`
+//!
+//! Credentials come from the environment (`AWS_ACCESS_KEY_ID`,
+//! `AWS_SECRET_ACCESS_KEY`, `AWS_SESSION_TOKEN`). Endpoint and region fall back
+//! to `AWS_ENDPOINT_URL` and `AWS_REGION` when not present in the URI query.
+
+use anyhow::{bail, Context, Result};
+use arrow::array::RecordBatch;
+use arrow::datatypes::Schema;
+use casparian_protocol::types::{ParsedSinkUri, SinkScheme};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::output_filename;
+
+/// Minimum S3 part size is 5 MiB (except the final part); stage 8 MiB parts.
+const PART_SIZE_BYTES: usize = 8 * 1024 * 1024;
+const DEFAULT_REGION: &str = "us-east-1";
+const CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+/// Bucket + key prefix parsed from an `s3://bucket/prefix` sink URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Location {
+    pub bucket: String,
+    pub prefix: String,
+}
+
+impl S3Location {
+    pub fn from_parsed(parsed: &ParsedSinkUri) -> Result<Self> {
+        if parsed.scheme != SinkScheme::S3 {
+            bail!("Sink URI '{}' is not an s3:// URI", parsed.original);
+        }
+        let raw = parsed.path.to_string_lossy();
+        let raw = raw.trim_matches('/');
+        let (bucket, prefix) = raw.split_once('/').unwrap_or((raw, ""));
+        if bucket.is_empty() {
+            bail!("Sink URI '{}' is missing a bucket name", parsed.original);
+        }
+        Ok(Self {
+            bucket: bucket.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+        })
+    }
+
+    /// Object key for a file name under this prefix.
+    pub fn key(&self, filename: &str) -> String {
+        if self.prefix.is_empty() {
+            filename.to_string()
+        } else {
+            format!("{}/{}", self.prefix, filename)
+        }
+    }
+
+    /// Canonical `s3://` URI for an object key in this bucket.
+    pub fn object_uri(&self, key: &str) -> String {
+        format!("s3://{}/{}", self.bucket, key)
+    }
+}
+
+/// Connection settings for an S3-compatible endpoint.
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub region: String,
+    pub endpoint: Option<String>,
+    pub path_style: bool,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub session_token: Option<String>,
+}
+
+impl S3Config {
+    /// Build config from sink URI query parameters, falling back to env vars.
+    pub fn from_query(query: &HashMap<String, String>) -> Result<Self> {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        let endpoint = query
+            .get("endpoint")
+            .cloned()
+            .or_else(|| env("AWS_ENDPOINT_URL"));
+        let region = query
+            .get("region")
+            .cloned()
+            .or_else(|| env("AWS_REGION"))
+            .or_else(|| env("AWS_DEFAULT_REGION"))
+            .unwrap_or_else(|| DEFAULT_REGION.to_string());
+        // Custom endpoints (MinIO etc.) almost always need path-style addressing.
+        let path_style = match query.get("path_style").map(|v| v.as_str()) {
+            Some("true") | Some("1") => true,
+            Some("false") | Some("0") => false,
+            Some(other) => bail!("Invalid path_style value '{}' (expected true/false)", other),
+            None => endpoint.is_some(),
+        };
+
+        Ok(Self {
+            region,
+            endpoint,
+            path_style,
+            access_key: env("AWS_ACCESS_KEY_ID"),
+            secret_key: env("AWS_SECRET_ACCESS_KEY"),
+            session_token: env("AWS_SESSION_TOKEN"),
+        })
+    }
+}
+
+/// Minimal multipart-upload surface needed by the sink.
+///
+/// Kept narrow so the sink logic can be exercised without a live endpoint.
+pub(crate) trait MultipartStore: Send {
+    fn begin(&mut self, key: &str) -> Result<String>;
+    fn upload_part(
+        &mut self,
+        key: &str,
+        upload_id: &str,
+        part_number: u32,
+        data: Vec<u8>,
+    ) -> Result<UploadedPart>;
+    fn complete(&mut self, key: &str, upload_id: &str, parts: Vec<UploadedPart>) -> Result<()>;
+    fn abort(&mut self, key: &str, upload_id: &str) -> Result<()>;
+    fn delete(&mut self, key: &str) -> Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UploadedPart {
+    pub part_number: u32,
+    pub etag: String,
+}
+
+struct RustS3Store {
+    bucket: Box<s3::Bucket>,
+}
+
+impl RustS3Store {
+    fn connect(location: &S3Location, config: &S3Config) -> Result<Self> {
+        let region = match &config.endpoint {
+            Some(endpoint) => s3::Region::Custom {
+                region: config.region.clone(),
+                endpoint: endpoint.clone(),
+            },
+            None => config
+                .region
+                .parse()
+                .with_context(|| format!("Invalid S3 region '{}'", config.region))?,
+        };
+        let credentials = s3::creds::Credentials::new(
+            config.access_key.as_deref(),
+            config.secret_key.as_deref(),
+            None,
+            config.session_token.as_deref(),
+            None,
+        )
+        .context("Failed to resolve S3 credentials")?;
+
+        let mut bucket = s3::Bucket::new(&location.bucket, region, credentials)
+            .with_context(|| format!("Failed to configure S3 bucket '{}'", location.bucket))?;
+        if config.path_style {
+            bucket = bucket.with_path_style();
+        }
+        Ok(Self { bucket })
+    }
+}
+
+impl MultipartStore for RustS3Store {
+    fn begin(&mut self, key: &str) -> Result<String> {
+        let response = self
+            .bucket
+            .initiate_multipart_upload(key, CONTENT_TYPE)
+            .with_context(|| format!("Failed to start multipart upload for '{}'", key))?;
+        Ok(response.upload_id)
+    }
+
+    fn upload_part(
+        &mut self,
+        key: &str,
+        upload_id: &str,
+        part_number: u32,
+        data: Vec<u8>,
+    ) -> Result<UploadedPart> {
+        let part = self
+            .bucket
+            .put_multipart_chunk(data, key, part_number, upload_id, CONTENT_TYPE)
+            .with_context(|| format!("Failed to upload part {} of '{}'", part_number, key))?;
+        Ok(UploadedPart {
+            part_number: part.part_number,
+            etag: part.etag,
+        })
+    }
+
+    fn complete(&mut self, key: &str, upload_id: &str, parts: Vec<UploadedPart>) -> Result<()> {
+        let parts = parts
+            .into_iter()
+            .map(|part| s3::serde_types::Part {
+                part_number: part.part_number,
+                etag: part.etag,
+            })
+            .collect();
+        self.bucket
+            .complete_multipart_upload(key, upload_id, parts)
+            .with_context(|| format!("Failed to complete multipart upload for '{}'", key))?;
+        Ok(())
+    }
+
+    fn abort(&mut self, key: &str, upload_id: &str) -> Result<()> {
+        self.bucket
+            .abort_upload(key, upload_id)
+            .with_context(|| format!("Failed to abort multipart upload for '{}'", key))?;
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &str) -> Result<()> {
+        self.bucket
+            .delete_object(key)
+            .with_context(|| format!("Failed to delete object '{}'", key))?;
+        Ok(())
+    }
+}
+
+/// Upload lifecycle for a single output object.
+enum UploadState {
+    /// `init()` has not been called yet.
+    Idle,
+    /// Multipart upload is open; parts are staged but not visible.
+    Staging {
+        key: String,
+        upload_id: String,
+        parts: Vec<UploadedPart>,
+    },
+    /// Upload completed; object is visible at `key`.
+    Committed { key: String },
+}
+
+/// S3 sink writer
+///
+/// Partitions output by job_id: s3://bucket/prefix/{safe_output_id}_{job_id}.parquet
+pub struct S3Sink {
+    location: S3Location,
+    output_name: String,
+    job_id: String,
+    store: Box<dyn MultipartStore>,
+    writer: Option<parquet::arrow::arrow_writer::ArrowWriter<Vec<u8>>>,
+    state: UploadState,
+    rows_written: u64,
+}
+
+impl S3Sink {
+    pub fn new(parsed: &ParsedSinkUri, output_name: &str, job_id: &str) -> Result<Self> {
+        let location = S3Location::from_parsed(parsed)?;
+        let config = S3Config::from_query(&parsed.query)?;
+        let store = RustS3Store::connect(&location, &config)?;
+        Ok(Self::with_store(
+            location,
+            output_name,
+            job_id,
+            Box::new(store),
+        ))
+    }
+
+    pub(crate) fn with_store(
+        location: S3Location,
+        output_name: &str,
+        job_id: &str,
+        store: Box<dyn MultipartStore>,
+    ) -> Self {
+        Self {
+            location,
+            output_name: output_name.to_string(),
+            job_id: job_id.to_string(),
+            store,
+            writer: None,
+            state: UploadState::Idle,
+            rows_written: 0,
+        }
+    }
+
+    /// Object key the output will be committed to.
+    pub fn object_key(&self) -> String {
+        self.location
+            .key(&output_filename(&self.output_name, &self.job_id, "parquet"))
+    }
+
+    pub(crate) fn init(&mut self, schema: &Schema) -> Result<()> {
+        let key = self.object_key();
+        info!(
+            "Initializing S3 sink: {}",
+            self.location.object_uri(&key)
+        );
+
+        let props = parquet::file::properties::WriterProperties::builder()
+            .set_compression(parquet::basic::Compression::SNAPPY)
+            .build();
+        let writer = parquet::arrow::arrow_writer::ArrowWriter::try_new(
+            Vec::new(),
+            Arc::new(schema.clone()),
+            Some(props),
+        )
+        .context("Failed to create Parquet writer")?;
+
+        let upload_id = self.store.begin(&key)?;
+        self.writer = Some(writer);
+        self.state = UploadState::Staging {
+            key,
+            upload_id,
+            parts: Vec::new(),
+        };
+        Ok(())
+    }
+
+    pub(crate) fn write_batch(&mut self, batch: &RecordBatch) -> Result<u64> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("S3 sink not initialized"))?;
+        writer
+            .write(batch)
+            .context("Failed to write batch to Parquet")?;
+
+        let rows = batch.num_rows() as u64;
+        self.rows_written += rows;
+        debug!("Wrote {} rows to S3 sink (total: {})", rows, self.rows_written);
+
+        if writer.inner().len() >= PART_SIZE_BYTES {
+            let chunk = std::mem::take(writer.inner_mut());
+            self.upload_chunk(chunk)?;
+        }
+        Ok(rows)
+    }
+
+    pub(crate) fn prepare(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            let tail = writer
+                .into_inner()
+                .context("Failed to close Parquet writer")?;
+            if !tail.is_empty() {
+                self.upload_chunk(tail)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn commit(&mut self) -> Result<()> {
+        let state = std::mem::replace(&mut self.state, UploadState::Idle);
+        match state {
+            UploadState::Staging {
+                key,
+                upload_id,
+                parts,
+            } => {
+                // S3 rejects completing an upload without parts, so an
+                // output that staged nothing is sent as one empty part
+                let parts = if parts.is_empty() {
+                    self.store
+                        .upload_part(&key, &upload_id, 1, Vec::new())
+                        .map(|part| vec![part])
+                } else {
+                    Ok(parts)
+                };
+                let completed =
+                    parts.and_then(|parts| self.store.complete(&key, &upload_id, parts));
+                if let Err(err) = completed {
+                    let _ = self.store.abort(&key, &upload_id);
+                    return Err(err);
+                }
+                info!(
+                    "Committed S3 sink: {} ({} rows)",
+                    self.location.object_uri(&key),
+                    self.rows_written
+                );
+                self.state = UploadState::Committed { key };
+            }
+            other => self.state = other,
+        }
+        Ok(())
+    }
+
+    pub(crate) fn rollback(&mut self) -> Result<()> {
+        self.writer = None;
+        let state = std::mem::replace(&mut self.state, UploadState::Idle);
+        match state {
+            UploadState::Idle => {}
+            UploadState::Staging { key, upload_id, .. } => {
+                self.store.abort(&key, &upload_id)?;
+                warn!("Aborted S3 multipart upload: {}", key);
+            }
+            UploadState::Committed { key } => {
+                self.store.delete(&key)?;
+                warn!("Rolled back S3 committed object: {}", key);
+            }
+        }
+        Ok(())
+    }
+
+    fn upload_chunk(&mut self, chunk: Vec<u8>) -> Result<()> {
+        let UploadState::Staging {
+            key,
+            upload_id,
+            parts,
+        } = &mut self.state
+        else {
+            bail!("S3 sink has no open multipart upload");
+        };
+        let part_number = u32::try_from(parts.len() + 1).context("Too many S3 upload parts")?;
+        let part = self.store.upload_part(key, upload_id, part_number, chunk)?;
+        parts.push(part);
+        Ok(())
+    }
+}
+
+impl Drop for S3Sink {
+    fn drop(&mut self) {
+        // Abort staged parts if we didn't finish properly
+        if let UploadState::Staging { key, upload_id, .. } = &self.state {
+            if let Err(err) = self.store.abort(key, upload_id) {
+                warn!("Failed to abort orphaned S3 upload {}: {}", key, err);
+            } else {
+                warn!("Aborted orphaned S3 upload: {}", key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryState {
+        staged: HashMap<String, Vec<(u32, Vec<u8>)>>,
+        objects: HashMap<String, Vec<u8>>,
+        aborted: Vec<String>,
+    }
+
+    #[derive(Clone, Default)]
+    struct MemoryStore {
+        state: Arc<Mutex<MemoryState>>,
+    }
+
+    impl MultipartStore for MemoryStore {
+        fn begin(&mut self, key: &str) -> Result<String> {
+            let upload_id = format!("upload-{}", key);
+            self.state
+                .lock()
+                .unwrap()
+                .staged
+                .insert(upload_id.clone(), Vec::new());
+            Ok(upload_id)
+        }
+
+        fn upload_part(
+            &mut self,
+            _key: &str,
+            upload_id: &str,
+            part_number: u32,
+            data: Vec<u8>,
+        ) -> Result<UploadedPart> {
+            let mut state = self.state.lock().unwrap();
+            let parts = state
+                .staged
+                .get_mut(upload_id)
+                .ok_or_else(|| anyhow::anyhow!("unknown upload"))?;
+            parts.push((part_number, data));
+            Ok(UploadedPart {
+                part_number,
+                etag: format!("etag-{}", part_number),
+            })
+        }
+
+        fn complete(&mut self, key: &str, upload_id: &str, parts: Vec<UploadedPart>) -> Result<()> {
+            let mut state = self.state.lock().unwrap();
+            let staged = state
+                .staged
+                .remove(upload_id)
+                .ok_or_else(|| anyhow::anyhow!("unknown upload"))?;
+            if parts.is_empty() {
+                bail!("MalformedXML: multipart upload needs at least one part");
+            }
+            assert_eq!(staged.len(), parts.len());
+            let body = staged.into_iter().flat_map(|(_, data)| data).collect();
+            state.objects.insert(key.to_string(), body);
+            Ok(())
+        }
+
+        fn abort(&mut self, key: &str, upload_id: &str) -> Result<()> {
+            let mut state = self.state.lock().unwrap();
+            state.staged.remove(upload_id);
+            state.aborted.push(key.to_string());
+            Ok(())
+        }
+
+        fn delete(&mut self, key: &str) -> Result<()> {
+            self.state.lock().unwrap().objects.remove(key);
+            Ok(())
+        }
+    }
+
+    fn test_batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("a"), Some("b"), None])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn location() -> S3Location {
+        let parsed = ParsedSinkUri::parse("s3://bucket/exports/daily").unwrap();
+        S3Location::from_parsed(&parsed).unwrap()
+    }
+
+    #[test]
+    fn test_s3_location_parses_bucket_and_prefix() {
+        let loc = location();
+        assert_eq!(loc.bucket, "bucket");
+        assert_eq!(loc.prefix, "exports/daily");
+        assert_eq!(loc.key("a.parquet"), "exports/daily/a.parquet");
+
+        let parsed = ParsedSinkUri::parse("s3://bucket").unwrap();
+        let loc = S3Location::from_parsed(&parsed).unwrap();
+        assert_eq!(loc.key("a.parquet"), "a.parquet");
+    }
+
+    #[test]
+    fn test_s3_sink_object_visible_only_after_commit() {
+        let store = MemoryStore::default();
+        let mut sink = S3Sink::with_store(location(), "events", "job-1", Box::new(store.clone()));
+        let key = sink.object_key();
+
+        let batch = test_batch();
+        sink.init(batch.schema().as_ref()).unwrap();
+        sink.write_batch(&batch).unwrap();
+        sink.prepare().unwrap();
+        assert!(!store.state.lock().unwrap().objects.contains_key(&key));
+
+        sink.commit().unwrap();
+        let state = store.state.lock().unwrap();
+        let body = state.objects.get(&key).expect("object committed");
+        assert_eq!(&body[..4], b"PAR1");
+    }
+
+    #[test]
+    fn test_s3_sink_commits_empty_upload_as_one_empty_part() {
+        let store = MemoryStore::default();
+        let mut sink = S3Sink::with_store(location(), "events", "job-1", Box::new(store.clone()));
+        let key = sink.object_key();
+
+        // Nothing staged: the writer output never became a part
+        sink.init(test_batch().schema().as_ref()).unwrap();
+        sink.writer = None;
+        sink.prepare().unwrap();
+        sink.commit().unwrap();
+
+        let state = store.state.lock().unwrap();
+        assert_eq!(state.objects.get(&key), Some(&Vec::new()));
+        assert!(state.aborted.is_empty());
+    }
+
+    #[test]
+    fn test_s3_sink_rollback_aborts_upload() {
+        let store = MemoryStore::default();
+        let mut sink = S3Sink::with_store(location(), "events", "job-1", Box::new(store.clone()));
+        let key = sink.object_key();
+
+        let batch = test_batch();
+        sink.init(batch.schema().as_ref()).unwrap();
+        sink.write_batch(&batch).unwrap();
+        sink.prepare().unwrap();
+        sink.rollback().unwrap();
+
+        let state = store.state.lock().unwrap();
+        assert!(state.objects.is_empty());
+        assert!(state.staged.is_empty());
+        assert_eq!(state.aborted, vec![key]);
+    }
+
+    #[test]
+    fn test_s3_config_path_style_defaults_on_for_custom_endpoint() {
+        let mut query = HashMap::new();
+        query.insert("endpoint".to_string(), "http://minio:9000".to_string());
+        query.insert("region".to_string(), "local".to_string());
+        let config = S3Config::from_query(&query).unwrap();
+        assert!(config.path_style);
+        assert_eq!(config.region, "local");
+
+        query.insert("path_style".to_string(), "maybe".to_string());
+        assert!(S3Config::from_query(&query).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! Placeholders in sink URIs, resolved at write time.
+//!
+//! A sink URI may contain `{name}` placeholders that are filled in per output
+//! when the job writes, e.g. `parquet:///data/{plugin}/{date:yyyy-MM-dd}/`:
+//!
+//! - `{plugin}`: plugin name
+//! - `{tag}`: the input file's tag ([`UNTAGGED`] when it has none)
+//! - `{output}`: output name
+//! - `{job_id}`: job id
+//! - `{namespace}`: the sink's namespace (usually expanded earlier, when the
+//!   topic config is loaded)
+//! - `{date:FORMAT}`: the UTC write time; `FORMAT` is built from `yyyy`, `MM`,
+//!   `dd`, `HH`, `mm`, `ss` and punctuation, and defaults to `yyyy-MM-dd`
+//!
+//! Substituted names go through `safe_output_id`, so a value can never add
+//! path segments. [`validate_sink_uri_template`] checks a URI without a job
+//! so typos are rejected when the sink is configured, not when a job writes.
+
+use chrono::{DateTime, Utc};
+
+use casparian_protocol::defaults::DEFAULT_NAMESPACE;
+use casparian_protocol::safe_output_id;
+use casparian_protocol::types::ParsedSinkUri;
+
+use crate::{SinkError, SinkResult};
+
+/// `{tag}` value for files without a tag.
+pub const UNTAGGED: &str = "untagged";
+
+const DEFAULT_DATE_FORMAT: &str = "yyyy-MM-dd";
+
+/// Job-level values for placeholders; `{output}` and `{job_id}` are passed
+/// per render.
+#[derive(Debug, Clone)]
+pub struct TemplateVars {
+    pub plugin: String,
+    pub tag: Option<String>,
+    pub namespace: Option<String>,
+    pub now: DateTime<Utc>,
+}
+
+impl TemplateVars {
+    pub fn new(plugin: impl Into<String>) -> Self {
+        Self {
+            plugin: plugin.into(),
+            tag: None,
+            namespace: None,
+            now: Utc::now(),
+        }
+    }
+
+    pub fn with_tag(mut self, tag: Option<String>) -> Self {
+        self.tag = tag;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment<'a> {
+    Literal(&'a str),
+    Plugin,
+    Tag,
+    Output,
+    JobId,
+    Namespace,
+    /// chrono format string
+    Date(String),
+}
+
+/// True if `uri` contains placeholders.
+pub fn has_placeholders(uri: &str) -> bool {
+    uri.contains('{') || uri.contains('}')
+}
+
+/// Resolve the placeholders of `uri` for one output of a job.
+pub fn render_sink_uri(
+    uri: &str,
+    vars: &TemplateVars,
+    output: &str,
+    job_id: &str,
+) -> SinkResult<String> {
+    if !has_placeholders(uri) {
+        return Ok(uri.to_string());
+    }
+    let mut rendered = String::with_capacity(uri.len());
+    for segment in parse_template(uri)? {
+        match segment {
+            Segment::Literal(text) => rendered.push_str(text),
+            Segment::Plugin => rendered.push_str(&safe_output_id(&vars.plugin)),
+            Segment::Tag => {
+                rendered.push_str(&safe_output_id(vars.tag.as_deref().unwrap_or(UNTAGGED)))
+            }
+            Segment::Output => rendered.push_str(&safe_output_id(output)),
+            Segment::JobId => rendered.push_str(&safe_output_id(job_id)),
+            Segment::Namespace => {
+                rendered.push_str(vars.namespace.as_deref().unwrap_or(DEFAULT_NAMESPACE))
+            }
+            Segment::Date(format) => rendered.push_str(&vars.now.format(&format).to_string()),
+        }
+    }
+    Ok(rendered)
+}
+
+/// Check that `uri` only uses known placeholders and renders to a valid
+/// sink URI.
+pub fn validate_sink_uri_template(uri: &str) -> SinkResult<()> {
+    let vars = TemplateVars::new("plugin").with_tag(Some("tag".to_string()));
+    let rendered = render_sink_uri(uri, &vars, "output", "0")?;
+    ParsedSinkUri::parse(&rendered)
+        .map_err(|err| SinkError::message(format!("Invalid sink URI '{}': {}", uri, err)))?;
+    Ok(())
+}
+
+fn parse_template(uri: &str) -> SinkResult<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = uri;
+    while !rest.is_empty() {
+        let Some(start) = rest.find(['{', '}']) else {
+            segments.push(Segment::Literal(rest));
+            break;
+        };
+        if rest[start..].starts_with('}') {
+            return Err(template_error(uri, "unmatched '}'"));
+        }
+        if start > 0 {
+            segments.push(Segment::Literal(&rest[..start]));
+        }
+        let Some(len) = rest[start + 1..].find(['{', '}']) else {
+            return Err(template_error(uri, "unclosed '{'"));
+        };
+        let end = start + 1 + len;
+        if rest[end..].starts_with('{') {
+            return Err(template_error(uri, "nested '{'"));
+        }
+        segments.push(parse_placeholder(uri, &rest[start + 1..end])?);
+        rest = &rest[end + 1..];
+    }
+    Ok(segments)
+}
+
+fn parse_placeholder<'a>(uri: &str, placeholder: &str) -> SinkResult<Segment<'a>> {
+    let (name, argument) = match placeholder.split_once(':') {
+        Some((name, argument)) => (name, Some(argument)),
+        None => (placeholder, None),
+    };
+    let segment = match (name, argument) {
+        ("plugin", None) => Segment::Plugin,
+        ("tag", None) => Segment::Tag,
+        ("output", None) => Segment::Output,
+        ("job_id", None) => Segment::JobId,
+        ("namespace", None) => Segment::Namespace,
+        ("date", format) => Segment::Date(
+            date_format(format.unwrap_or(DEFAULT_DATE_FORMAT))
+                .map_err(|reason| template_error(uri, &reason))?,
+        ),
+        (_, Some(_)) => {
+            return Err(template_error(
+                uri,
+                &format!("placeholder '{{{}}}' takes no argument", name),
+            ))
+        }
+        _ => {
+            return Err(template_error(
+                uri,
+                &format!(
+                    "unknown placeholder '{{{}}}' (expected plugin, tag, output, job_id, \
+                     namespace or date)",
+                    name
+                ),
+            ))
+        }
+    };
+    Ok(segment)
+}
+
+/// Translate a `yyyy-MM-dd` style format into a chrono format string.
+fn date_format(format: &str) -> Result<String, String> {
+    if format.is_empty() {
+        return Err("empty date format".to_string());
+    }
+    let mut translated = String::with_capacity(format.len() * 2);
+    let chars: Vec<char> = format.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        if !ch.is_ascii_alphabetic() {
+            if ch == '%' {
+                translated.push('%');
+            }
+            translated.push(ch);
+            i += 1;
+            continue;
+        }
+        let run = chars[i..].iter().take_while(|&&c| c == ch).count();
+        let token: String = chars[i..i + run].iter().collect();
+        let spec = match token.as_str() {
+            "yyyy" => "%Y",
+            "MM" => "%m",
+            "dd" => "%d",
+            "HH" => "%H",
+            "mm" => "%M",
+            "ss" => "%S",
+            _ => {
+                return Err(format!(
+                    "unknown date token '{}' (expected yyyy, MM, dd, HH, mm or ss)",
+                    token
+                ))
+            }
+        };
+        translated.push_str(spec);
+        i += run;
+    }
+    Ok(translated)
+}
+
+fn template_error(uri: &str, reason: &str) -> SinkError {
+    SinkError::message(format!("Invalid sink URI template '{}': {}", uri, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn vars() -> TemplateVars {
+        TemplateVars {
+            plugin: "trades".to_string(),
+            tag: Some("fix_logs".to_string()),
+            namespace: Some("risk".to_string()),
+            now: Utc.with_ymd_and_hms(2024, 3, 9, 7, 5, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let uri = "parquet:///data/{namespace}/{plugin}/{tag}/{date:yyyy-MM-dd}/{output}_{job_id}/";
+        assert_eq!(
+            render_sink_uri(uri, &vars(), "orders", "42").unwrap(),
+            "parquet:///data/risk/trades/fix_logs/2024-03-09/orders_42/"
+        );
+        assert_eq!(
+            render_sink_uri("parquet:///d/{date:yyyy/MM/dd-HHmmss}/", &vars(), "o", "1").unwrap(),
+            "parquet:///d/2024/03/09-070500/"
+        );
+        assert_eq!(
+            render_sink_uri("parquet:///d/{date}/", &vars(), "o", "1").unwrap(),
+            "parquet:///d/2024-03-09/"
+        );
+        assert_eq!(
+            render_sink_uri("duckdb:///data.db", &vars(), "o", "1").unwrap(),
+            "duckdb:///data.db"
+        );
+    }
+
+    #[test]
+    fn test_substituted_values_stay_in_one_segment() {
+        let vars = TemplateVars::new("../etc");
+        let rendered = render_sink_uri("parquet:///d/{plugin}/{tag}/", &vars, "o", "1").unwrap();
+        assert!(!rendered.contains(".."));
+        assert!(rendered.ends_with(&format!("/{}/", UNTAGGED)));
+    }
+
+    #[test]
+    fn test_validate_rejects_typos() {
+        assert!(validate_sink_uri_template("parquet:///d/{plugin}/{date:yyyy-MM-dd}/").is_ok());
+        for uri in [
+            "parquet:///d/{plugn}/",
+            "parquet:///d/{date:YYYY-MM-dd}/",
+            "parquet:///d/{date:}/",
+            "parquet:///d/{output:x}/",
+            "parquet:///d/{plugin/",
+            "parquet:///d/plugin}/",
+            "nope:///d/{plugin}/",
+        ] {
+            assert!(validate_sink_uri_template(uri).is_err(), "{}", uri);
+        }
+    }
+}
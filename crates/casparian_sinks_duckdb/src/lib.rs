@@ -2,10 +2,13 @@ use anyhow::{bail, Context, Result};
 use arrow::array::RecordBatch;
 use arrow::datatypes::{DataType, Schema};
 use casparian_db::{try_lock_exclusive, DbLockGuard, LockError};
-use casparian_protocol::SinkMode;
+use casparian_protocol::{SchemaEvolution, SinkMode};
 use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
+pub mod versions;
+pub use versions::{query_as_of, table_versions, TableVersion};
+
 fn quote_ident(name: &str) -> String {
     format!("\"{}\"", name.replace('"', "\"\""))
 }
@@ -52,7 +55,12 @@ pub struct DuckDbSink {
     db_path: PathBuf,
     table_name: String,
     stage_table: String,
+    job_id: String,
     sink_mode: SinkMode,
+    schema_evolution: SchemaEvolution,
+    keep_versions: usize,
+    /// Columns identifying a row for Upsert commits
+    upsert_keys: Vec<String>,
     conn: duckdb::Connection,
     rows_written: u64,
     schema: Option<Schema>,
@@ -101,7 +109,11 @@ impl DuckDbSink {
             db_path,
             table_name: table_name.to_string(),
             stage_table,
+            job_id: job_id.to_string(),
             sink_mode,
+            schema_evolution: SchemaEvolution::default(),
+            keep_versions: 0,
+            upsert_keys: Vec::new(),
             conn,
             rows_written: 0,
             schema: None,
@@ -109,6 +121,26 @@ impl DuckDbSink {
         })
     }
 
+    /// How Append commits reconcile columns with an existing destination table.
+    pub fn with_schema_evolution(mut self, schema_evolution: SchemaEvolution) -> Self {
+        self.schema_evolution = schema_evolution;
+        self
+    }
+
+    /// Keep up to `keep` previous versions when a Replace commit overwrites
+    /// the table (0 drops the old table). See [`versions`].
+    pub fn with_versions(mut self, keep: usize) -> Self {
+        self.keep_versions = keep;
+        self
+    }
+
+    /// Key columns for Upsert commits: destination rows whose keys match an
+    /// output row are replaced by it.
+    pub fn with_upsert_keys(mut self, keys: Vec<String>) -> Self {
+        self.upsert_keys = keys;
+        self
+    }
+
     fn with_conn_mut<F, T>(&mut self, f: F) -> Result<T>
     where
         F: FnOnce(&mut duckdb::Connection) -> Result<T>,
@@ -149,7 +181,97 @@ impl DuckDbSink {
         }
     }
 
+    /// Column names of `table_name`, in table order; empty if it does not exist.
+    fn table_columns(tx: &duckdb::Transaction<'_>, table_name: &str) -> Result<Vec<String>> {
+        let mut stmt = tx
+            .prepare(
+                "SELECT column_name FROM information_schema.columns \
+                 WHERE table_schema = current_schema() AND table_name = ? \
+                 ORDER BY ordinal_position",
+            )
+            .context("Failed to prepare DuckDB column lookup")?;
+        let rows = stmt
+            .query_map([table_name], |row| row.get::<_, String>(0))
+            .context("Failed to query DuckDB destination columns")?;
+        rows.collect::<duckdb::Result<Vec<_>>>()
+            .context("Failed to read DuckDB destination columns")
+    }
+
+    /// Statements that bring an existing destination table in line with
+    /// `schema` under `schema_evolution`, or an error naming the mismatch.
+    fn plan_schema_evolution(
+        table_name: &str,
+        schema: &Schema,
+        existing: &[String],
+        schema_evolution: SchemaEvolution,
+    ) -> Result<Vec<String>> {
+        let extra: Vec<_> = schema
+            .fields()
+            .iter()
+            .filter(|f| !existing.iter().any(|c| c == f.name()))
+            .collect();
+        let missing: Vec<&str> = existing
+            .iter()
+            .filter(|c| schema.field_with_name(c).is_err())
+            .map(String::as_str)
+            .collect();
+
+        if !missing.is_empty() && schema_evolution == SchemaEvolution::Strict {
+            bail!(
+                "DuckDB table '{}' has columns missing from the output: {} (schema_evolution={})",
+                table_name,
+                missing.join(", "),
+                schema_evolution
+            );
+        }
+        if extra.is_empty() {
+            return Ok(Vec::new());
+        }
+        if schema_evolution != SchemaEvolution::AddMissingColumns {
+            let names: Vec<&str> = extra.iter().map(|f| f.name().as_str()).collect();
+            bail!(
+                "Output has columns not in DuckDB table '{}': {} (schema_evolution={})",
+                table_name,
+                names.join(", "),
+                schema_evolution
+            );
+        }
+
+        // Added columns are always nullable: existing rows have no value for them.
+        let target = quote_ident(table_name);
+        Ok(extra
+            .iter()
+            .map(|f| {
+                format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    target,
+                    quote_ident(f.name()),
+                    Self::arrow_to_duckdb_type(f.data_type())
+                )
+            })
+            .collect())
+    }
+
     pub fn init(&mut self, schema: &Schema) -> Result<()> {
+        if self.sink_mode == SinkMode::Upsert {
+            if self.upsert_keys.is_empty() {
+                bail!(
+                    "DuckDB sink in Upsert mode needs key columns for table '{}' \
+                     (add ?key=col1,col2 to the sink URI)",
+                    self.table_name
+                );
+            }
+            for key in &self.upsert_keys {
+                if schema.field_with_name(key).is_err() {
+                    bail!(
+                        "Upsert key column '{}' not found in output for table '{}'",
+                        key,
+                        self.table_name
+                    );
+                }
+            }
+        }
+
         info!(
             "Initializing DuckDB sink: {} (table: {}, stage: {})",
             self.db_path.display(),
@@ -228,20 +350,43 @@ impl DuckDbSink {
         let target = quote_ident(&self.table_name);
         let stage = quote_ident(&self.stage_table);
         let sink_mode = self.sink_mode;
+        let schema_evolution = self.schema_evolution;
+        let keep_versions = self.keep_versions;
+        let upsert_keys = self.upsert_keys.clone();
         let table_name = self.table_name.clone();
+        let stage_table = self.stage_table.clone();
+        let job_id = self.job_id.clone();
+        let schema = schema.clone();
 
         self.with_conn_mut(|conn| {
             let tx = conn
                 .transaction()
                 .context("Failed to begin DuckDB transaction")?;
             match sink_mode {
-                SinkMode::Append => {
-                    let create_dest = format!(
-                        "CREATE TABLE IF NOT EXISTS {} AS SELECT {} FROM {} WHERE 1=0",
-                        target, column_list, stage
-                    );
-                    tx.execute(&create_dest, [])
-                        .context("Failed to ensure DuckDB destination table")?;
+                SinkMode::Append | SinkMode::Upsert => {
+                    let existing = Self::table_columns(&tx, &table_name)?;
+                    if existing.is_empty() {
+                        let create_dest = format!(
+                            "CREATE TABLE {} AS SELECT {} FROM {} WHERE 1=0",
+                            target, column_list, stage
+                        );
+                        tx.execute(&create_dest, [])
+                            .context("Failed to create DuckDB destination table")?;
+                    } else {
+                        for alter_sql in Self::plan_schema_evolution(
+                            &table_name,
+                            &schema,
+                            &existing,
+                            schema_evolution,
+                        )? {
+                            debug!("ALTER TABLE: {}", alter_sql);
+                            tx.execute(&alter_sql, [])
+                                .context("Failed to evolve DuckDB destination table")?;
+                        }
+                    }
+                    if sink_mode == SinkMode::Upsert {
+                        Self::delete_upserted_rows(&tx, &table_name, &stage_table, &upsert_keys)?;
+                    }
                     let insert_sql = format!(
                         "INSERT INTO {} ({}) SELECT {} FROM {}",
                         target, column_list, column_list, stage
@@ -252,6 +397,15 @@ impl DuckDbSink {
                     tx.execute(&drop_stage, [])
                         .context("Failed to drop DuckDB stage table")?;
                 }
+                SinkMode::Replace if keep_versions > 0 => {
+                    versions::replace_versioned(
+                        &tx,
+                        &table_name,
+                        &stage_table,
+                        &job_id,
+                        keep_versions,
+                    )?;
+                }
                 SinkMode::Replace => {
                     let drop_target = format!("DROP TABLE IF EXISTS {}", target);
                     tx.execute(&drop_target, [])
@@ -283,6 +437,70 @@ impl DuckDbSink {
         Ok(())
     }
 
+    /// Delete destination rows that the staged output replaces. Fails if the
+    /// output itself has more than one row for a key.
+    fn delete_upserted_rows(
+        tx: &duckdb::Transaction<'_>,
+        table_name: &str,
+        stage_table: &str,
+        keys: &[String],
+    ) -> Result<()> {
+        let existing = Self::table_columns(tx, table_name)?;
+        if let Some(missing) = keys.iter().find(|key| !existing.contains(key)) {
+            bail!(
+                "Upsert key column '{}' not found in DuckDB table '{}'",
+                missing,
+                table_name
+            );
+        }
+
+        let target = quote_ident(table_name);
+        let stage = quote_ident(stage_table);
+        let key_list = keys
+            .iter()
+            .map(|key| quote_ident(key))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let duplicate_sql = format!(
+            "SELECT COUNT(*) FROM (SELECT {} FROM {} GROUP BY {} HAVING COUNT(*) > 1)",
+            key_list, stage, key_list
+        );
+        let duplicates: i64 = tx
+            .query_row(&duplicate_sql, [], |row| row.get(0))
+            .context("Failed to check DuckDB upsert keys")?;
+        if duplicates > 0 {
+            bail!(
+                "Output has {} duplicate key(s) on ({}) for upsert into '{}'",
+                duplicates,
+                keys.join(", "),
+                table_name
+            );
+        }
+
+        // NULL keys match NULL keys, the same grouping the duplicate check uses.
+        let matches = keys
+            .iter()
+            .map(|key| {
+                let column = quote_ident(key);
+                format!(
+                    "{}.{} IS NOT DISTINCT FROM {}.{}",
+                    stage, column, target, column
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let delete_sql = format!(
+            "DELETE FROM {} WHERE EXISTS (SELECT 1 FROM {} WHERE {})",
+            target, stage, matches
+        );
+        debug!("UPSERT DELETE: {}", delete_sql);
+        let deleted = tx
+            .execute(&delete_sql, [])
+            .context("Failed to delete DuckDB rows replaced by upsert")?;
+        debug!("Upsert replaced {} row(s) in {}", deleted, table_name);
+        Ok(())
+    }
+
     pub fn rollback(&mut self) -> Result<()> {
         let stage = quote_ident(&self.stage_table);
         self.with_conn_mut(|conn| {
@@ -584,4 +802,161 @@ mod tests {
             .unwrap();
         assert_eq!(name, "Alice");
     }
+
+    fn commit_to_existing(
+        db_path: &Path,
+        create_sql: &str,
+        schema_evolution: SchemaEvolution,
+    ) -> Result<()> {
+        {
+            let conn = duckdb::Connection::open(db_path).unwrap();
+            conn.execute_batch(create_sql).unwrap();
+        }
+        let mut sink = DuckDbSink::new(
+            db_path.to_path_buf(),
+            "records",
+            SinkMode::Append,
+            "job-3",
+            "records",
+        )?
+        .with_schema_evolution(schema_evolution);
+        let batch = create_test_batch();
+        sink.init(batch.schema().as_ref())?;
+        sink.write_batch(&batch)?;
+        sink.commit()
+    }
+
+    #[test]
+    fn test_schema_evolution_adds_missing_columns() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("evolve.duckdb");
+        commit_to_existing(
+            &db_path,
+            "CREATE TABLE records (\"id\" BIGINT, \"region\" TEXT)",
+            SchemaEvolution::AddMissingColumns,
+        )
+        .unwrap();
+
+        let conn = duckdb::Connection::open(db_path).unwrap();
+        let (name, region): (String, Option<String>) = conn
+            .query_row(
+                "SELECT \"name\", \"region\" FROM records WHERE \"id\" = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(name, "Alice");
+        assert_eq!(region, None);
+    }
+
+    #[test]
+    fn test_schema_evolution_fail_on_extra_rejects_new_columns() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("extra.duckdb");
+        let err = commit_to_existing(
+            &db_path,
+            "CREATE TABLE records (\"id\" BIGINT)",
+            SchemaEvolution::FailOnExtra,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("name"), "unexpected error: {}", err);
+
+        let conn = duckdb::Connection::open(db_path).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM records", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    fn upsert(db_path: &Path, job_id: &str, ids: Vec<i64>, names: Vec<&str>) -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )
+        .unwrap();
+        let mut sink = DuckDbSink::new(
+            db_path.to_path_buf(),
+            "records",
+            SinkMode::Upsert,
+            job_id,
+            "records",
+        )?
+        .with_upsert_keys(vec!["id".to_string()]);
+        sink.init(batch.schema().as_ref())?;
+        sink.write_batch(&batch)?;
+        sink.commit()
+    }
+
+    #[test]
+    fn test_upsert_replaces_rows_by_key() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("upsert.duckdb");
+        upsert(&db_path, "job-1", vec![1, 2], vec!["Alice", "Bob"]).unwrap();
+        // Corrected source: row 2 changes, row 3 is new.
+        upsert(&db_path, "job-2", vec![2, 3], vec!["Bobby", "Carol"]).unwrap();
+
+        let conn = duckdb::Connection::open(&db_path).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT \"id\", \"name\" FROM records ORDER BY \"id\"")
+            .unwrap();
+        let rows: Vec<(i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (1, "Alice".to_string()),
+                (2, "Bobby".to_string()),
+                (3, "Carol".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upsert_rejects_duplicate_and_missing_keys() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("upsert_dup.duckdb");
+        upsert(&db_path, "job-1", vec![1], vec!["Alice"]).unwrap();
+        let err = upsert(&db_path, "job-2", vec![1, 1], vec!["A", "B"]).unwrap_err();
+        assert!(
+            err.to_string().contains("duplicate"),
+            "unexpected error: {}",
+            err
+        );
+
+        let mut sink = DuckDbSink::new(
+            dir.path().join("nokey.duckdb"),
+            "records",
+            SinkMode::Upsert,
+            "job-3",
+            "records",
+        )
+        .unwrap();
+        let err = sink
+            .init(create_test_batch().schema().as_ref())
+            .unwrap_err();
+        assert!(err.to_string().contains("key"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_schema_evolution_strict_rejects_missing_columns() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("strict.duckdb");
+        let create_sql = "CREATE TABLE records (\"id\" BIGINT, \"name\" TEXT, \"region\" TEXT)";
+        let err = commit_to_existing(&db_path, create_sql, SchemaEvolution::Strict).unwrap_err();
+        assert!(err.to_string().contains("region"), "unexpected error: {}", err);
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("lenient.duckdb");
+        commit_to_existing(&db_path, create_sql, SchemaEvolution::FailOnExtra).unwrap();
+    }
 }
@@ -0,0 +1,355 @@
+//! Versioned tables: keep previous generations of a Replace-mode table.
+//!
+//! With [`DuckDbSink::with_versions`](crate::DuckDbSink::with_versions), a
+//! Replace commit renames the live table to `{table}__v{n}` instead of
+//! dropping it. `__cf_table_versions` records the job that wrote each version;
+//! the highest version of a table is the live table itself. Archived versions
+//! beyond the keep count are dropped by the commit that archives a new one.
+//!
+//! A live table that predates versioning is archived as version 1 with no job.
+
+use anyhow::{bail, Context, Result};
+use arrow::array::RecordBatch;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+use crate::{quote_ident, DuckDbSink};
+
+/// Metadata table listing the versions of every versioned table.
+pub const VERSIONS_TABLE: &str = "__cf_table_versions";
+
+/// One retained version of a versioned table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableVersion {
+    pub version: i64,
+    /// Job that wrote this version; `None` if it predates versioning.
+    pub job_id: Option<String>,
+    pub committed_at_ms: i64,
+    /// Physical table holding this version's rows.
+    pub table: String,
+}
+
+/// Physical name of an archived version.
+pub fn version_table_name(table_name: &str, version: i64) -> String {
+    format!("{}__v{}", table_name, version)
+}
+
+/// Retained versions of `table_name`, newest (live) first.
+pub fn table_versions(db_path: &Path, table_name: &str) -> Result<Vec<TableVersion>> {
+    let conn = open_read_only(db_path)?;
+    list_versions(&conn, table_name)
+}
+
+/// Rows of `table_name` as written by `job_id`, for before/after comparisons
+/// of a rerun. Fails if no retained version was written by that job.
+pub fn query_as_of(db_path: &Path, table_name: &str, job_id: &str) -> Result<Vec<RecordBatch>> {
+    let conn = open_read_only(db_path)?;
+    let versions = list_versions(&conn, table_name)?;
+    let Some(version) = versions
+        .iter()
+        .find(|version| version.job_id.as_deref() == Some(job_id))
+    else {
+        bail!(
+            "No retained version of DuckDB table '{}' was written by job {}",
+            table_name,
+            job_id
+        );
+    };
+
+    let sql = format!("SELECT * FROM {}", quote_ident(&version.table));
+    let mut stmt = conn
+        .prepare(&sql)
+        .with_context(|| format!("Failed to query DuckDB table '{}'", version.table))?;
+    let batches = stmt
+        .query_arrow([])
+        .with_context(|| format!("Failed to read DuckDB table '{}'", version.table))?
+        .collect();
+    Ok(batches)
+}
+
+/// Replace `table_name` with `stage`, archiving the live table as a version
+/// and pruning archives beyond `keep`.
+pub(crate) fn replace_versioned(
+    tx: &duckdb::Transaction<'_>,
+    table_name: &str,
+    stage: &str,
+    job_id: &str,
+    keep: usize,
+) -> Result<()> {
+    let create_sql = format!(
+        "CREATE TABLE IF NOT EXISTS {} (\
+         table_name VARCHAR NOT NULL, \
+         version BIGINT NOT NULL, \
+         job_id VARCHAR, \
+         committed_at BIGINT NOT NULL, \
+         PRIMARY KEY (table_name, version))",
+        quote_ident(VERSIONS_TABLE)
+    );
+    tx.execute(&create_sql, [])
+        .context("Failed to create DuckDB versions table")?;
+
+    let target = quote_ident(table_name);
+    let latest_sql = format!(
+        "SELECT MAX(version) FROM {} WHERE table_name = ?",
+        quote_ident(VERSIONS_TABLE)
+    );
+    let mut latest: Option<i64> = tx
+        .query_row(&latest_sql, [table_name], |row| row.get(0))
+        .context("Failed to read DuckDB table versions")?;
+
+    if !DuckDbSink::table_columns(tx, table_name)?.is_empty() {
+        let live = match latest {
+            Some(version) => version,
+            None => {
+                record_version(tx, table_name, 1, None)?;
+                1
+            }
+        };
+        latest = Some(live);
+        let archived = quote_ident(&version_table_name(table_name, live));
+        debug!("Archiving DuckDB table {} as {}", target, archived);
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", archived), [])
+            .context("Failed to drop stale DuckDB table version")?;
+        tx.execute(
+            &format!("ALTER TABLE {} RENAME TO {}", target, archived),
+            [],
+        )
+        .context("Failed to archive DuckDB table version")?;
+    }
+
+    let version = latest.unwrap_or(0) + 1;
+    tx.execute(
+        &format!("ALTER TABLE {} RENAME TO {}", quote_ident(stage), target),
+        [],
+    )
+    .context("Failed to rename DuckDB stage table")?;
+    record_version(tx, table_name, version, Some(job_id))?;
+
+    prune_versions(tx, table_name, version, keep)
+}
+
+fn record_version(
+    tx: &duckdb::Transaction<'_>,
+    table_name: &str,
+    version: i64,
+    job_id: Option<&str>,
+) -> Result<()> {
+    let sql = format!(
+        "INSERT INTO {} (table_name, version, job_id, committed_at) VALUES (?, ?, ?, ?)",
+        quote_ident(VERSIONS_TABLE)
+    );
+    tx.execute(
+        &sql,
+        duckdb::params![table_name, version, job_id, now_millis()],
+    )
+    .context("Failed to record DuckDB table version")?;
+    Ok(())
+}
+
+/// Drop archived versions older than the newest `keep`.
+fn prune_versions(
+    tx: &duckdb::Transaction<'_>,
+    table_name: &str,
+    live: i64,
+    keep: usize,
+) -> Result<()> {
+    let versions_ident = quote_ident(VERSIONS_TABLE);
+    let mut stmt = tx
+        .prepare(&format!(
+            "SELECT version FROM {} WHERE table_name = ? AND version < ? ORDER BY version DESC",
+            versions_ident
+        ))
+        .context("Failed to prepare DuckDB version lookup")?;
+    let archived = stmt
+        .query_map(duckdb::params![table_name, live], |row| {
+            row.get::<_, i64>(0)
+        })
+        .context("Failed to list DuckDB table versions")?
+        .collect::<duckdb::Result<Vec<_>>>()
+        .context("Failed to read DuckDB table versions")?;
+
+    let delete_sql = format!(
+        "DELETE FROM {} WHERE table_name = ? AND version = ?",
+        versions_ident
+    );
+    for version in archived.into_iter().skip(keep) {
+        let archived = quote_ident(&version_table_name(table_name, version));
+        debug!("Pruning DuckDB table version {}", archived);
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", archived), [])
+            .context("Failed to drop DuckDB table version")?;
+        tx.execute(&delete_sql, duckdb::params![table_name, version])
+            .context("Failed to forget DuckDB table version")?;
+    }
+    Ok(())
+}
+
+fn list_versions(conn: &duckdb::Connection, table_name: &str) -> Result<Vec<TableVersion>> {
+    let exists: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM information_schema.tables \
+             WHERE table_schema = current_schema() AND table_name = ?",
+            [VERSIONS_TABLE],
+            |row| row.get(0),
+        )
+        .context("Failed to look up DuckDB versions table")?;
+    if exists == 0 {
+        return Ok(Vec::new());
+    }
+
+    let sql = format!(
+        "SELECT version, job_id, committed_at FROM {} \
+         WHERE table_name = ? ORDER BY version DESC",
+        quote_ident(VERSIONS_TABLE)
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .context("Failed to prepare DuckDB version lookup")?;
+    let rows = stmt
+        .query_map([table_name], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .context("Failed to list DuckDB table versions")?
+        .collect::<duckdb::Result<Vec<_>>>()
+        .context("Failed to read DuckDB table versions")?;
+
+    Ok(rows
+        .into_iter()
+        .enumerate()
+        .map(|(index, (version, job_id, committed_at_ms))| TableVersion {
+            version,
+            job_id,
+            committed_at_ms,
+            table: if index == 0 {
+                table_name.to_string()
+            } else {
+                version_table_name(table_name, version)
+            },
+        })
+        .collect())
+}
+
+fn open_read_only(db_path: &Path) -> Result<duckdb::Connection> {
+    let config = duckdb::Config::default()
+        .access_mode(duckdb::AccessMode::ReadOnly)
+        .context("Failed to configure DuckDB read-only access")?;
+    duckdb::Connection::open_with_flags(db_path, config)
+        .with_context(|| format!("Failed to open DuckDB database: {}", db_path.display()))
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Int64Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use casparian_protocol::SinkMode;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn replace(db_path: &Path, job_id: &str, ids: Vec<i64>, keep: usize) {
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+        let batch =
+            RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Int64Array::from(ids))]).unwrap();
+        let mut sink = DuckDbSink::new(
+            db_path.to_path_buf(),
+            "records",
+            SinkMode::Replace,
+            job_id,
+            "records",
+        )
+        .unwrap()
+        .with_versions(keep);
+        sink.init(batch.schema().as_ref()).unwrap();
+        sink.write_batch(&batch).unwrap();
+        sink.commit().unwrap();
+    }
+
+    fn ids(batches: &[RecordBatch]) -> Vec<i64> {
+        batches
+            .iter()
+            .flat_map(|batch| {
+                let ids = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap();
+                (0..ids.len()).map(|i| ids.value(i)).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replace_keeps_and_prunes_versions() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("versions.duckdb");
+        for run in 1..=4 {
+            replace(&db_path, &format!("job-{}", run), vec![run, run * 10], 2);
+        }
+
+        let versions = table_versions(&db_path, "records").unwrap();
+        let summary: Vec<(i64, Option<&str>, &str)> = versions
+            .iter()
+            .map(|v| (v.version, v.job_id.as_deref(), v.table.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (4, Some("job-4"), "records"),
+                (3, Some("job-3"), "records__v3"),
+                (2, Some("job-2"), "records__v2"),
+            ]
+        );
+
+        assert_eq!(
+            ids(&query_as_of(&db_path, "records", "job-4").unwrap()),
+            vec![4, 40]
+        );
+        assert_eq!(
+            ids(&query_as_of(&db_path, "records", "job-2").unwrap()),
+            vec![2, 20]
+        );
+        let err = query_as_of(&db_path, "records", "job-1").unwrap_err();
+        assert!(err.to_string().contains("job-1"), "{}", err);
+
+        let conn = duckdb::Connection::open(&db_path).unwrap();
+        let pruned: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = 'records__v1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(pruned, 0);
+    }
+
+    #[test]
+    fn test_unversioned_table_is_archived_without_job() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("adopt.duckdb");
+        {
+            let conn = duckdb::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE records (\"id\" BIGINT); INSERT INTO records VALUES (7)",
+            )
+            .unwrap();
+        }
+
+        replace(&db_path, "job-1", vec![1], 1);
+        let versions = table_versions(&db_path, "records").unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1].job_id, None);
+        assert_eq!(versions[1].table, "records__v1");
+        assert!(table_versions(&db_path, "other").unwrap().is_empty());
+    }
+}
@@ -1,14 +1,20 @@
 //! Storage layer for the Control Plane API.
 //!
-//! Manages jobs, events, and approvals in DuckDB tables.
+//! Manages jobs, events, approvals, and the approval audit log in DuckDB tables.
 //! Used directly by casparian_mcp to drive job execution.
 
+use super::approval_policy::{self, POLICY_ACTOR};
+use super::idempotency::{self, IdempotentResponse};
+use super::job_ids;
+use super::queue::plugin_namespace_sql;
 use super::schema_version::{ensure_schema_version, SCHEMA_VERSION};
 use anyhow::{Context, Result};
 use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
+use casparian_protocol::defaults::DEFAULT_NAMESPACE;
 use casparian_protocol::{
-    ApiJobId, Approval, ApprovalOperation, ApprovalStatus, Event, EventId, EventType,
-    HttpJobStatus, HttpJobType, Job, JobProgress, JobResult, OutputInfo,
+    ApiJobId, Approval, ApprovalAuditEntry, ApprovalDecisionType, ApprovalOperation,
+    ApprovalStatus, Event, EventId, EventType, HttpJobStatus, HttpJobType, Job, JobId,
+    JobProgress, JobResult, OutputInfo, SchemaSpec, SYSTEM_EVENT_JOB_ID,
 };
 use chrono::{DateTime, Duration, Utc};
 use std::collections::HashMap;
@@ -46,7 +52,8 @@ impl ApiStorage {
         let job_status_values = "'queued','running','completed','failed','cancelled'";
         let job_type_values = "'run','backtest','preview'";
         let approval_status_values = "'pending','approved','rejected','expired'";
-        let event_type_values = "'job_started','phase','progress','violation','output','job_finished','approval_required'";
+        let decision_values = "'approve','reject'";
+        let event_type_values = "'job_started','phase','progress','violation','output','job_finished','approval_required','approval_decided','worker_joined','worker_left','worker_health_changed','schema_drift'";
 
         let create_sql = if self.conn.backend_name() == "SQLite" {
             format!(
@@ -105,11 +112,24 @@ impl ApiStorage {
             );
             CREATE INDEX IF NOT EXISTS ix_api_approvals_status ON cf_api_approvals(status);
             CREATE INDEX IF NOT EXISTS ix_api_approvals_expires ON cf_api_approvals(expires_at);
+
+            -- API Approval audit log
+            CREATE TABLE IF NOT EXISTS cf_api_approval_audit (
+                audit_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                approval_id TEXT NOT NULL,
+                decision TEXT NOT NULL CHECK (decision IN ({decision_values})),
+                actor TEXT,
+                justification TEXT,
+                rule TEXT,
+                decided_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS ix_api_approval_audit_approval ON cf_api_approval_audit(approval_id, audit_id);
             "#,
                 job_type_values = job_type_values,
                 job_status_values = job_status_values,
                 approval_status_values = approval_status_values,
                 event_type_values = event_type_values,
+                decision_values = decision_values,
             )
         } else {
             format!(
@@ -170,17 +190,34 @@ impl ApiStorage {
             );
             CREATE INDEX IF NOT EXISTS ix_api_approvals_status ON cf_api_approvals(status);
             CREATE INDEX IF NOT EXISTS ix_api_approvals_expires ON cf_api_approvals(expires_at);
+
+            -- API Approval audit log
+            CREATE SEQUENCE IF NOT EXISTS seq_cf_api_approval_audit;
+            CREATE TABLE IF NOT EXISTS cf_api_approval_audit (
+                audit_id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_api_approval_audit'),
+                approval_id TEXT NOT NULL,
+                decision TEXT NOT NULL CHECK (decision IN ({decision_values})),
+                actor TEXT,
+                justification TEXT,
+                rule TEXT,
+                decided_at BIGINT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS ix_api_approval_audit_approval ON cf_api_approval_audit(approval_id, audit_id);
             "#,
                 job_type_values = job_type_values,
                 job_status_values = job_status_values,
                 approval_status_values = approval_status_values,
                 event_type_values = event_type_values,
+                decision_values = decision_values,
             )
         };
 
         self.conn
             .execute_batch(&create_sql)
             .context("Failed to initialize API schema")?;
+        job_ids::init_schema(&self.conn)?;
+        approval_policy::init_schema(&self.conn)?;
+        idempotency::init_schema(&self.conn)?;
 
         Ok(())
     }
@@ -189,7 +226,8 @@ impl ApiStorage {
     // Job Operations
     // ========================================================================
 
-    /// Create a new job.
+    /// Create a new job. Its ID comes from the shared job ID sequence, so it
+    /// never equals a queue job ID.
     pub fn create_job(
         &self,
         job_type: HttpJobType,
@@ -207,15 +245,17 @@ impl ApiStorage {
         };
 
         let sql = r#"
-            INSERT INTO cf_api_jobs (job_type, plugin_name, plugin_version, input_dir, output_sink, approval_id, job_spec_json, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO cf_api_jobs (job_id, job_type, plugin_name, plugin_version, input_dir, output_sink, approval_id, job_spec_json, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             RETURNING job_id
         "#;
 
         let now = now_millis();
+        let allocated = job_ids::next_job_id(&self.conn)?;
         let job_id_raw: i64 = self.conn.query_scalar(
             sql,
             &[
+                DbValue::from(allocated.to_i64()?),
                 DbValue::from(job_type_str),
                 DbValue::from(plugin_name),
                 DbValue::from(plugin_version),
@@ -255,75 +295,124 @@ impl ApiStorage {
 
     /// List jobs with optional status filter.
     pub fn list_jobs(&self, status: Option<HttpJobStatus>, limit: usize) -> Result<Vec<Job>> {
-        let (sql, params) = match status {
-            Some(s) => {
-                let status_str = job_status_to_str(s);
-                (
-                    r#"
-                    SELECT job_id, job_type, status, plugin_name, plugin_version, input_dir, output_sink,
-                           approval_id, job_spec_json, created_at, started_at, finished_at, error_message,
-                           progress_phase, progress_items_done, progress_items_total, progress_message,
-                           result_rows_processed, result_bytes_written, result_outputs_json, result_metrics_json
-                    FROM cf_api_jobs
-                    WHERE status = ?
-                    ORDER BY created_at DESC
-                    LIMIT ?
-                    "#
-                    .to_string(),
-                    vec![DbValue::from(status_str), DbValue::from(limit as i64)],
-                )
+        self.list_jobs_in_namespace(status, None, limit)
+    }
+
+    /// List jobs with optional status and namespace filters.
+    ///
+    /// A job's namespace is that of its plugin in `cf_plugin_manifest`;
+    /// plugins missing from the registry belong to `DEFAULT_NAMESPACE`.
+    pub fn list_jobs_in_namespace(
+        &self,
+        status: Option<HttpJobStatus>,
+        namespace: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Job>> {
+        let mut clauses = Vec::new();
+        let mut params = Vec::new();
+        if let Some(status) = status {
+            clauses.push("status = ?".to_string());
+            params.push(DbValue::from(job_status_to_str(status)));
+        }
+        if let Some(namespace) = namespace {
+            if self.conn.table_exists("cf_plugin_manifest")? {
+                let namespace_sql = plugin_namespace_sql("cf_api_jobs.plugin_name");
+                clauses.push(format!("{} = ?", namespace_sql));
+                params.push(DbValue::from(namespace));
+            } else if namespace != DEFAULT_NAMESPACE {
+                return Ok(Vec::new());
             }
-            None => (
-                r#"
-                SELECT job_id, job_type, status, plugin_name, plugin_version, input_dir, output_sink,
-                       approval_id, job_spec_json, created_at, started_at, finished_at, error_message,
-                       progress_phase, progress_items_done, progress_items_total, progress_message,
-                       result_rows_processed, result_bytes_written, result_outputs_json, result_metrics_json
-                FROM cf_api_jobs
-                ORDER BY created_at DESC
-                LIMIT ?
-                "#
-                .to_string(),
-                vec![DbValue::from(limit as i64)],
-            ),
+        }
+        let where_sql = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
         };
+        let sql = format!(
+            r#"
+            SELECT job_id, job_type, status, plugin_name, plugin_version, input_dir, output_sink,
+                   approval_id, job_spec_json, created_at, started_at, finished_at, error_message,
+                   progress_phase, progress_items_done, progress_items_total, progress_message,
+                   result_rows_processed, result_bytes_written, result_outputs_json, result_metrics_json
+            FROM cf_api_jobs
+            {where_sql}
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            where_sql = where_sql
+        );
+        params.push(DbValue::from(limit as i64));
 
         let rows = self.conn.query_all(&sql, &params)?;
         rows.iter().map(|r| self.row_to_job(r)).collect()
     }
 
     /// Update job status.
+    ///
+    /// A change to running emits `JobStarted`; a change to completed or failed
+    /// emits `JobFinished`. Repeating the current status emits nothing.
     pub fn update_job_status(&self, job_id: ApiJobId, status: HttpJobStatus) -> Result<()> {
+        if !self.set_job_status(job_id, status)? {
+            return Ok(());
+        }
+        match status {
+            HttpJobStatus::Running => {
+                self.insert_event(job_id, &EventType::JobStarted)?;
+            }
+            HttpJobStatus::Completed | HttpJobStatus::Failed => {
+                let error_message = self.get_job(job_id)?.and_then(|job| job.error_message);
+                self.insert_event(
+                    job_id,
+                    &EventType::JobFinished {
+                        status,
+                        error_message,
+                    },
+                )?;
+            }
+            // Cancellation emits its own event in `cancel_job`.
+            HttpJobStatus::Queued | HttpJobStatus::Cancelled => {}
+        }
+        Ok(())
+    }
+
+    /// Write a job status; false if the job is missing or already has it.
+    fn set_job_status(&self, job_id: ApiJobId, status: HttpJobStatus) -> Result<bool> {
         let status_str = job_status_to_str(status);
         let now = now_millis();
         let job_id_i64 = job_id.to_i64().context("job_id exceeds i64::MAX")?;
 
         let (sql, params) = match status {
             HttpJobStatus::Running => (
-                r#"UPDATE cf_api_jobs SET status = ?, started_at = ? WHERE job_id = ?"#,
+                r#"UPDATE cf_api_jobs SET status = ?, started_at = ? WHERE job_id = ? AND status <> ?"#,
                 vec![
                     DbValue::from(status_str),
                     DbValue::from(now),
                     DbValue::from(job_id_i64),
+                    DbValue::from(status_str),
                 ],
             ),
             HttpJobStatus::Completed | HttpJobStatus::Failed | HttpJobStatus::Cancelled => (
-                r#"UPDATE cf_api_jobs SET status = ?, finished_at = ? WHERE job_id = ?"#,
+                r#"UPDATE cf_api_jobs SET status = ?, finished_at = ? WHERE job_id = ? AND status <> ?"#,
                 vec![
                     DbValue::from(status_str),
                     DbValue::from(now),
                     DbValue::from(job_id_i64),
+                    DbValue::from(status_str),
                 ],
             ),
             HttpJobStatus::Queued => (
-                r#"UPDATE cf_api_jobs SET status = ? WHERE job_id = ?"#,
-                vec![DbValue::from(status_str), DbValue::from(job_id_i64)],
+                r#"UPDATE cf_api_jobs SET status = ? WHERE job_id = ? AND status <> ?"#,
+                vec![
+                    DbValue::from(status_str),
+                    DbValue::from(job_id_i64),
+                    DbValue::from(status_str),
+                ],
             ),
         };
 
-        self.conn.execute(sql, &params)?;
+        let changed = self.conn.execute(sql, &params)?;
 
-        Ok(())
+        Ok(changed > 0)
     }
 
     /// Update job progress.
@@ -412,7 +501,7 @@ impl ApiStorage {
         let job = self.get_job(job_id)?;
         match job {
             Some(j) if !is_terminal_status(j.status) => {
-                self.update_job_status(job_id, HttpJobStatus::Cancelled)?;
+                self.set_job_status(job_id, HttpJobStatus::Cancelled)?;
                 self.insert_event(
                     job_id,
                     &EventType::JobFinished {
@@ -429,6 +518,34 @@ impl ApiStorage {
         }
     }
 
+    /// Record that queue job `job_id` was enqueued for API job `api_job_id`.
+    pub fn link_queue_job(&self, api_job_id: ApiJobId, job_id: JobId) -> Result<()> {
+        job_ids::link_api_job(&self.conn, api_job_id, job_id)
+    }
+
+    /// Reserve `count` job IDs for the work of API job `api_job_id` and link
+    /// each one to it.
+    pub fn reserve_queue_jobs(&self, api_job_id: ApiJobId, count: u64) -> Result<Vec<JobId>> {
+        if self.get_job(api_job_id)?.is_none() {
+            anyhow::bail!("API job {} not found", api_job_id);
+        }
+        let job_ids: Vec<JobId> = job_ids::reserve_job_ids(&self.conn, count)?.collect();
+        for &job_id in &job_ids {
+            self.link_queue_job(api_job_id, job_id)?;
+        }
+        Ok(job_ids)
+    }
+
+    /// Queue jobs enqueued for an API job, in ID order.
+    pub fn queue_jobs_for(&self, api_job_id: ApiJobId) -> Result<Vec<JobId>> {
+        job_ids::jobs_for_api_job(&self.conn, api_job_id)
+    }
+
+    /// API job a queue job was enqueued for, if any.
+    pub fn api_job_for(&self, job_id: JobId) -> Result<Option<ApiJobId>> {
+        job_ids::api_job_for_job(&self.conn, job_id)
+    }
+
     fn row_to_job(&self, row: &UnifiedDbRow) -> Result<Job> {
         let job_id_raw: i64 = row.get(0)?;
         let job_type_str: String = row.get(1)?;
@@ -601,6 +718,58 @@ impl ApiStorage {
         rows.iter().map(|r| self.row_to_event(r)).collect()
     }
 
+    /// List events across all jobs after `after_event_id`, oldest first.
+    ///
+    /// Event IDs are global, so the last ID returned is a resumable cursor.
+    pub fn list_events_since(&self, after_event_id: EventId, limit: usize) -> Result<Vec<Event>> {
+        let after_id = i64::try_from(after_event_id).context("event_id exceeds i64::MAX")?;
+        let limit = i64::try_from(limit).context("limit exceeds i64::MAX")?;
+        let sql = r#"
+            SELECT event_id, job_id, timestamp, payload_json
+            FROM cf_api_events
+            WHERE event_id > ?
+            ORDER BY event_id ASC
+            LIMIT ?
+        "#;
+        let rows = self
+            .conn
+            .query_all(sql, &[DbValue::from(after_id), DbValue::from(limit)])?;
+        rows.iter().map(|r| self.row_to_event(r)).collect()
+    }
+
+    /// Highest event ID recorded so far (0 if there are no events).
+    pub fn latest_event_id(&self) -> Result<EventId> {
+        let latest: i64 = self
+            .conn
+            .query_scalar("SELECT COALESCE(MAX(event_id), 0) FROM cf_api_events", &[])?;
+        latest.try_into().context("event_id must be non-negative")
+    }
+
+    // ========================================================================
+    // Idempotency Keys
+    // ========================================================================
+
+    /// Response recorded for `key` within the last `ttl`, if any.
+    pub fn find_idempotent_response(
+        &self,
+        key: &str,
+        ttl: Duration,
+    ) -> Result<Option<IdempotentResponse>> {
+        let not_before = (Utc::now() - ttl).timestamp_millis();
+        idempotency::find(&self.conn, key, not_before)
+    }
+
+    /// Record the response returned for the request `request_hash` under `key`.
+    pub fn save_idempotent_response(
+        &self,
+        key: &str,
+        request_hash: &str,
+        response_json: &str,
+    ) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        idempotency::save(&self.conn, key, request_hash, response_json, now)
+    }
+
     fn row_to_event(&self, row: &UnifiedDbRow) -> Result<Event> {
         let event_id_raw: i64 = row.get(0)?;
         let job_id_raw: i64 = row.get(1)?;
@@ -631,14 +800,17 @@ impl ApiStorage {
         Uuid::new_v4().to_string()
     }
 
-    /// Create a new approval request.
+    /// Create a new approval request and check it against the approval rules.
+    ///
+    /// Returns the name of the rule that approved the request right away, if
+    /// any; otherwise the request stays pending for a human.
     pub fn create_approval(
         &self,
         approval_id: &str,
         operation: &ApprovalOperation,
         summary: &str,
         expires_in: Duration,
-    ) -> Result<()> {
+    ) -> Result<Option<String>> {
         let operation_type = match operation {
             ApprovalOperation::Run { .. } => "run",
             ApprovalOperation::SchemaPromote { .. } => "schema_promote",
@@ -664,8 +836,74 @@ impl ApiStorage {
                 DbValue::from(expires_ts),
             ],
         )?;
+        self.insert_event(
+            SYSTEM_EVENT_JOB_ID,
+            &EventType::ApprovalRequired {
+                approval_id: approval_id.to_string(),
+            },
+        )?;
 
-        Ok(())
+        self.apply_approval_rules(approval_id, operation)
+    }
+
+    /// Approve a new request if an approval rule matches it.
+    fn apply_approval_rules(
+        &self,
+        approval_id: &str,
+        operation: &ApprovalOperation,
+    ) -> Result<Option<String>> {
+        let rules = approval_policy::list_rules(&self.conn)?;
+        if rules.is_empty() {
+            return Ok(None);
+        }
+        let approved_schema = match operation {
+            ApprovalOperation::SchemaPromote {
+                plugin_name,
+                output_name,
+                ..
+            } => self.approved_schema(plugin_name, output_name)?,
+            ApprovalOperation::Run { .. } => None,
+        };
+        let Some(rule) = approval_policy::evaluate(&rules, operation, approved_schema.as_ref())
+        else {
+            return Ok(None);
+        };
+        let justification = format!("Matched approval rule '{}'", rule.name);
+        let decided = self.decide(
+            approval_id,
+            ApprovalDecisionType::Approve,
+            Some(POLICY_ACTOR),
+            Some(&justification),
+            Some(&rule.name),
+        )?;
+        Ok(decided.then(|| rule.name.clone()))
+    }
+
+    /// Schema of the most recently approved promotion of `output_name`.
+    fn approved_schema(&self, plugin_name: &str, output_name: &str) -> Result<Option<SchemaSpec>> {
+        let rows = self.conn.query_all(
+            r#"
+            SELECT operation_json
+            FROM cf_api_approvals
+            WHERE status = 'approved' AND operation_type = 'schema_promote'
+            ORDER BY decided_at DESC
+            "#,
+            &[],
+        )?;
+        for row in &rows {
+            let operation_json: String = row.get(0)?;
+            if let ApprovalOperation::SchemaPromote {
+                plugin_name: approved_plugin,
+                output_name: approved_output,
+                schema,
+            } = serde_json::from_str(&operation_json)?
+            {
+                if approved_plugin == plugin_name && approved_output == output_name {
+                    return Ok(Some(schema));
+                }
+            }
+        }
+        Ok(None)
     }
 
     /// Get an approval by ID.
@@ -721,51 +959,144 @@ impl ApiStorage {
     }
 
     /// Approve an approval request.
-    pub fn approve(&self, approval_id: &str, decided_by: Option<&str>) -> Result<bool> {
-        let now = now_millis();
-        let sql = r#"
-            UPDATE cf_api_approvals
-            SET status = 'approved', decided_at = ?, decided_by = ?
-            WHERE approval_id = ? AND status = 'pending'
-        "#;
-
-        let rows = self.conn.execute(
-            sql,
-            &[
-                DbValue::from(now),
-                DbValue::from(decided_by),
-                DbValue::from(approval_id),
-            ],
-        )?;
-
-        Ok(rows > 0)
+    ///
+    /// The decision is recorded in the approval audit log with `decided_by`
+    /// as the actor.
+    pub fn approve(
+        &self,
+        approval_id: &str,
+        decided_by: Option<&str>,
+        justification: Option<&str>,
+    ) -> Result<bool> {
+        self.decide(
+            approval_id,
+            ApprovalDecisionType::Approve,
+            decided_by,
+            justification,
+            None,
+        )
     }
 
     /// Reject an approval request.
+    ///
+    /// `reason` is stored as the rejection reason and as the audit justification.
     pub fn reject(
         &self,
         approval_id: &str,
         decided_by: Option<&str>,
         reason: Option<&str>,
+    ) -> Result<bool> {
+        self.decide(
+            approval_id,
+            ApprovalDecisionType::Reject,
+            decided_by,
+            reason,
+            None,
+        )
+    }
+
+    /// Apply a decision to a pending approval and append it to the audit log.
+    /// `rule` names the approval rule behind an automatic decision.
+    fn decide(
+        &self,
+        approval_id: &str,
+        decision: ApprovalDecisionType,
+        decided_by: Option<&str>,
+        justification: Option<&str>,
+        rule: Option<&str>,
     ) -> Result<bool> {
         let now = now_millis();
+        let (status, rejection_reason) = match decision {
+            ApprovalDecisionType::Approve => ("approved", None),
+            ApprovalDecisionType::Reject => ("rejected", justification),
+        };
+
+        let decided = self.conn.transaction(|tx| {
+            let rows = tx.execute(
+                r#"
+                UPDATE cf_api_approvals
+                SET status = ?, decided_at = ?, decided_by = ?, rejection_reason = ?
+                WHERE approval_id = ? AND status = 'pending'
+                "#,
+                &[
+                    DbValue::from(status),
+                    DbValue::from(now),
+                    DbValue::from(decided_by),
+                    DbValue::from(rejection_reason),
+                    DbValue::from(approval_id),
+                ],
+            )?;
+            if rows == 0 {
+                return Ok(false);
+            }
+            tx.execute(
+                r#"
+                INSERT INTO cf_api_approval_audit
+                    (approval_id, decision, actor, justification, rule, decided_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+                &[
+                    DbValue::from(approval_id),
+                    DbValue::from(decision_type_to_str(decision)),
+                    DbValue::from(decided_by),
+                    DbValue::from(justification),
+                    DbValue::from(rule),
+                    DbValue::from(now),
+                ],
+            )?;
+            Ok(true)
+        })?;
+
+        if decided {
+            let job_id = self
+                .get_approval(approval_id)?
+                .and_then(|approval| approval.job_id)
+                .unwrap_or(SYSTEM_EVENT_JOB_ID);
+            let status = match decision {
+                ApprovalDecisionType::Approve => ApprovalStatus::Approved,
+                ApprovalDecisionType::Reject => ApprovalStatus::Rejected,
+            };
+            self.insert_event(
+                job_id,
+                &EventType::ApprovalDecided {
+                    approval_id: approval_id.to_string(),
+                    status,
+                },
+            )?;
+        }
+
+        Ok(decided)
+    }
+
+    /// List audit log entries for an approval, oldest first.
+    pub fn list_approval_audit(&self, approval_id: &str) -> Result<Vec<ApprovalAuditEntry>> {
         let sql = r#"
-            UPDATE cf_api_approvals
-            SET status = 'rejected', decided_at = ?, decided_by = ?, rejection_reason = ?
-            WHERE approval_id = ? AND status = 'pending'
+            SELECT approval_id, decision, actor, justification, decided_at, rule
+            FROM cf_api_approval_audit
+            WHERE approval_id = ?
+            ORDER BY audit_id ASC
         "#;
 
-        let rows = self.conn.execute(
-            sql,
-            &[
-                DbValue::from(now),
-                DbValue::from(decided_by),
-                DbValue::from(reason),
-                DbValue::from(approval_id),
-            ],
-        )?;
+        let rows = self.conn.query_all(sql, &[DbValue::from(approval_id)])?;
+        rows.iter().map(|r| self.row_to_audit_entry(r)).collect()
+    }
 
-        Ok(rows > 0)
+    fn row_to_audit_entry(&self, row: &UnifiedDbRow) -> Result<ApprovalAuditEntry> {
+        let approval_id: String = row.get(0)?;
+        let decision_str: String = row.get(1)?;
+        let actor: Option<String> = row.get(2)?;
+        let justification: Option<String> = row.get(3)?;
+        let decided_at: i64 = row.get(4)?;
+        let rule: Option<String> = row.get(5)?;
+
+        Ok(ApprovalAuditEntry {
+            approval_id,
+            decision: str_to_decision_type(&decision_str)?,
+            actor,
+            justification,
+            decided_at: millis_to_rfc3339(decided_at),
+            rule,
+        })
     }
 
     /// Mark expired approvals.
@@ -922,6 +1253,21 @@ fn str_to_approval_status(s: &str) -> Result<ApprovalStatus> {
     }
 }
 
+fn decision_type_to_str(decision: ApprovalDecisionType) -> &'static str {
+    match decision {
+        ApprovalDecisionType::Approve => "approve",
+        ApprovalDecisionType::Reject => "reject",
+    }
+}
+
+fn str_to_decision_type(s: &str) -> Result<ApprovalDecisionType> {
+    match s {
+        "approve" => Ok(ApprovalDecisionType::Approve),
+        "reject" => Ok(ApprovalDecisionType::Reject),
+        other => anyhow::bail!("Unknown approval decision: {}", other),
+    }
+}
+
 fn event_type_to_str(event_type: &EventType) -> &'static str {
     match event_type {
         EventType::JobStarted => "job_started",
@@ -931,6 +1277,11 @@ fn event_type_to_str(event_type: &EventType) -> &'static str {
         EventType::Output { .. } => "output",
         EventType::JobFinished { .. } => "job_finished",
         EventType::ApprovalRequired { .. } => "approval_required",
+        EventType::ApprovalDecided { .. } => "approval_decided",
+        EventType::WorkerJoined { .. } => "worker_joined",
+        EventType::WorkerLeft { .. } => "worker_left",
+        EventType::WorkerHealthChanged { .. } => "worker_health_changed",
+        EventType::SchemaDrift { .. } => "schema_drift",
     }
 }
 
@@ -1082,7 +1433,7 @@ mod tests {
 
         // Approve
         let approved = storage
-            .approve(&approval_id, Some("user@example.com"))
+            .approve(&approval_id, Some("user@example.com"), None)
             .unwrap();
         assert!(approved);
 
@@ -1091,10 +1442,106 @@ mod tests {
         assert!(approval.decided_at.is_some());
 
         // Can't approve again
-        let approved_again = storage.approve(&approval_id, None).unwrap();
+        let approved_again = storage.approve(&approval_id, None, None).unwrap();
         assert!(!approved_again);
     }
 
+    #[test]
+    fn test_approval_audit_log() {
+        let storage = setup_storage();
+
+        let approval_id = ApiStorage::generate_approval_id();
+        let operation = ApprovalOperation::Run {
+            plugin_name: "test_parser".to_string(),
+            plugin_version: None,
+            input_dir: "/data/input".to_string(),
+            file_count: 10,
+            output: None,
+        };
+        storage
+            .create_approval(
+                &approval_id,
+                &operation,
+                "Run test_parser",
+                Duration::hours(1),
+            )
+            .unwrap();
+        assert!(storage
+            .list_approval_audit(&approval_id)
+            .unwrap()
+            .is_empty());
+
+        assert!(storage
+            .approve(&approval_id, Some("alice"), Some("Reviewed output schema"))
+            .unwrap());
+        // A no-op decision on an already decided approval is not audited
+        assert!(!storage
+            .reject(&approval_id, Some("bob"), Some("Too late"))
+            .unwrap());
+
+        let entries = storage.list_approval_audit(&approval_id).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].approval_id, approval_id);
+        assert_eq!(entries[0].decision, ApprovalDecisionType::Approve);
+        assert_eq!(entries[0].actor.as_deref(), Some("alice"));
+        assert_eq!(
+            entries[0].justification.as_deref(),
+            Some("Reviewed output schema")
+        );
+
+        let approval = storage.get_approval(&approval_id).unwrap().unwrap();
+        assert_eq!(approval.decided_by.as_deref(), Some("alice"));
+        assert!(approval.rejection_reason.is_none());
+    }
+
+    #[test]
+    fn test_approval_rules_auto_approve() {
+        use crate::approval_policy::{set_rule, ApprovalRule, RuleCondition};
+
+        let storage = setup_storage();
+        set_rule(
+            storage.connection(),
+            &ApprovalRule {
+                name: "small-runs".to_string(),
+                plugins: Vec::new(),
+                condition: RuleCondition::SmallRun { max_files: 10 },
+            },
+        )
+        .unwrap();
+
+        let run = |file_count| ApprovalOperation::Run {
+            plugin_name: "test_parser".to_string(),
+            plugin_version: None,
+            input_dir: "/data/input".to_string(),
+            file_count,
+            output: None,
+        };
+        let rule = storage
+            .create_approval("appr-small", &run(3), "Run test_parser", Duration::hours(1))
+            .unwrap();
+        assert_eq!(rule.as_deref(), Some("small-runs"));
+        let approval = storage.get_approval("appr-small").unwrap().unwrap();
+        assert_eq!(approval.status, ApprovalStatus::Approved);
+        assert_eq!(approval.decided_by.as_deref(), Some(POLICY_ACTOR));
+
+        let entries = storage.list_approval_audit("appr-small").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor.as_deref(), Some(POLICY_ACTOR));
+        assert_eq!(entries[0].rule.as_deref(), Some("small-runs"));
+
+        let rule = storage
+            .create_approval(
+                "appr-large",
+                &run(500),
+                "Run test_parser",
+                Duration::hours(1),
+            )
+            .unwrap();
+        assert!(rule.is_none());
+        let approval = storage.get_approval("appr-large").unwrap().unwrap();
+        assert_eq!(approval.status, ApprovalStatus::Pending);
+    }
+
     #[test]
     fn test_approval_rejection() {
         let storage = setup_storage();
@@ -1170,6 +1617,16 @@ mod tests {
 
         let all = storage.list_jobs(None, 10).unwrap();
         assert_eq!(all.len(), 2);
+
+        // Without a plugin registry every job is in the default namespace
+        let default = storage
+            .list_jobs_in_namespace(None, Some("default"), 10)
+            .unwrap();
+        assert_eq!(default.len(), 2);
+        let risk = storage
+            .list_jobs_in_namespace(None, Some("risk"), 10)
+            .unwrap();
+        assert!(risk.is_empty());
     }
 
     #[test]
@@ -1246,4 +1703,64 @@ mod tests {
         assert_eq!(r.rows_processed, 1000);
         assert_eq!(r.outputs.len(), 1);
     }
+
+    #[test]
+    fn test_status_changes_and_approvals_feed_event_stream() {
+        let storage = setup_storage();
+        let job_id = storage
+            .create_job(HttpJobType::Run, "parser", None, "/input", None, None, None)
+            .unwrap();
+        assert_eq!(storage.latest_event_id().unwrap(), 0);
+
+        storage
+            .update_job_status(job_id, HttpJobStatus::Running)
+            .unwrap();
+        storage
+            .update_job_status(job_id, HttpJobStatus::Running)
+            .unwrap();
+        let cursor = storage.latest_event_id().unwrap();
+
+        let operation = ApprovalOperation::Run {
+            plugin_name: "parser".to_string(),
+            plugin_version: None,
+            input_dir: "/input".to_string(),
+            file_count: 1,
+            output: None,
+        };
+        storage
+            .create_approval("appr-1", &operation, "run parser", Duration::hours(1))
+            .unwrap();
+        storage.approve("appr-1", Some("ops"), None).unwrap();
+        storage
+            .update_job_status(job_id, HttpJobStatus::Completed)
+            .unwrap();
+
+        let all = storage.list_events_since(0, 100).unwrap();
+        let kinds: Vec<&str> = all
+            .iter()
+            .map(|e| event_type_to_str(&e.event_type))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "job_started",
+                "approval_required",
+                "approval_decided",
+                "job_finished"
+            ],
+            "repeating a status emits nothing"
+        );
+        assert_eq!(all[1].job_id, SYSTEM_EVENT_JOB_ID);
+        assert_eq!(
+            all[2].event_type,
+            EventType::ApprovalDecided {
+                approval_id: "appr-1".to_string(),
+                status: ApprovalStatus::Approved,
+            }
+        );
+
+        let resumed = storage.list_events_since(cursor, 2).unwrap();
+        assert_eq!(resumed.len(), 2);
+        assert_eq!(resumed[0].event_id, all[1].event_id);
+    }
 }
@@ -0,0 +1,350 @@
+//! Approval policy: rules that approve low-risk operations without a human.
+//!
+//! Rules are kept in `cf_approval_rules`, so the Sentinel and a standalone
+//! MCP server share one policy. `ApiStorage::create_approval` checks every
+//! new request against them in the order they were added; the first rule that matches
+//! approves the request on the spot as [`POLICY_ACTOR`], and the audit entry
+//! names the rule.
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
+use casparian_protocol::{ApprovalOperation, SchemaSpec};
+use serde::{Deserialize, Serialize};
+
+/// Actor recorded for decisions made by an approval rule.
+pub const POLICY_ACTOR: &str = "policy";
+
+/// What an operation must look like for a rule to approve it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleCondition {
+    /// Any run of the rule's plugins
+    AnyRun,
+    /// Runs over at most `max_files` input files
+    SmallRun { max_files: u64 },
+    /// Schema promotions that keep the output's last approved schema as is
+    /// and only append nullable columns
+    AdditiveSchema,
+}
+
+impl RuleCondition {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::AnyRun => "any_run",
+            Self::SmallRun { .. } => "small_run",
+            Self::AdditiveSchema => "additive_schema",
+        }
+    }
+}
+
+/// A named auto-approval rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRule {
+    pub name: String,
+    /// Plugins the rule covers; empty covers every plugin
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<String>,
+    pub condition: RuleCondition,
+}
+
+impl ApprovalRule {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            anyhow::bail!("Approval rule name must not be empty");
+        }
+        match self.condition {
+            RuleCondition::AnyRun if self.plugins.is_empty() => {
+                anyhow::bail!(
+                    "Approval rule '{}' would approve every run; name the plugins it covers",
+                    self.name
+                );
+            }
+            RuleCondition::SmallRun { max_files: 0 } => {
+                anyhow::bail!("Approval rule '{}' needs max_files > 0", self.name);
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the rule approves `operation`. `approved_schema` is the last
+    /// approved schema of the output a schema promotion targets.
+    pub fn matches(
+        &self,
+        operation: &ApprovalOperation,
+        approved_schema: Option<&SchemaSpec>,
+    ) -> bool {
+        let plugin_name = match operation {
+            ApprovalOperation::Run { plugin_name, .. }
+            | ApprovalOperation::SchemaPromote { plugin_name, .. } => plugin_name,
+        };
+        if !self.plugins.is_empty() && !self.plugins.contains(plugin_name) {
+            return false;
+        }
+        match (self.condition, operation) {
+            (RuleCondition::AnyRun, ApprovalOperation::Run { .. }) => true,
+            (RuleCondition::SmallRun { max_files }, ApprovalOperation::Run { file_count, .. }) => {
+                *file_count <= max_files
+            }
+            (RuleCondition::AdditiveSchema, ApprovalOperation::SchemaPromote { schema, .. }) => {
+                approved_schema.is_some_and(|approved| is_additive(approved, schema))
+            }
+            _ => false,
+        }
+    }
+
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        let name: String = row.get_by_name("name")?;
+        let plugins: String = row.get_by_name("plugins_json")?;
+        let condition: String = row.get_by_name("condition")?;
+        let max_files: Option<i64> = row.get_by_name("max_files")?;
+        let condition = match (condition.as_str(), max_files) {
+            ("any_run", _) => RuleCondition::AnyRun,
+            ("small_run", Some(max_files)) => RuleCondition::SmallRun {
+                max_files: max_files.max(0) as u64,
+            },
+            ("additive_schema", _) => RuleCondition::AdditiveSchema,
+            (other, _) => anyhow::bail!("Invalid condition '{}' for approval rule {}", other, name),
+        };
+        Ok(Self {
+            plugins: serde_json::from_str(&plugins)
+                .with_context(|| format!("Invalid plugins for approval rule {}", name))?,
+            name,
+            condition,
+        })
+    }
+}
+
+/// Whether `next` only appends nullable columns to `approved`: every
+/// approved column keeps its position and definition, and the mode is
+/// unchanged.
+pub fn is_additive(approved: &SchemaSpec, next: &SchemaSpec) -> bool {
+    approved.mode == next.mode
+        && next.columns.len() > approved.columns.len()
+        && next.columns.starts_with(&approved.columns)
+        && next.columns[approved.columns.len()..]
+            .iter()
+            .all(|column| column.nullable)
+}
+
+/// The first of `rules` that approves `operation`.
+pub fn evaluate<'a>(
+    rules: &'a [ApprovalRule],
+    operation: &ApprovalOperation,
+    approved_schema: Option<&SchemaSpec>,
+) -> Option<&'a ApprovalRule> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(operation, approved_schema))
+}
+
+/// Create the rule table (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let int_type = if conn.backend_name() == "SQLite" {
+        "INTEGER"
+    } else {
+        "BIGINT"
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_approval_rules (
+            name TEXT PRIMARY KEY,
+            plugins_json TEXT NOT NULL,
+            condition TEXT NOT NULL CHECK (condition IN ('any_run', 'small_run', 'additive_schema')),
+            max_files {int_type},
+            position {int_type} NOT NULL,
+            updated_at {int_type} NOT NULL
+        );
+        "#,
+        int_type = int_type,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize approval rule schema")?;
+    Ok(())
+}
+
+/// Create or replace the rule named `rule.name`. New rules are evaluated
+/// after the existing ones; a replaced rule keeps its place.
+pub fn set_rule(conn: &DbConnection, rule: &ApprovalRule) -> Result<()> {
+    rule.validate()?;
+    let existing = conn.query_optional(
+        "SELECT position FROM cf_approval_rules WHERE name = ?",
+        &[DbValue::from(rule.name.as_str())],
+    )?;
+    let position: i64 = match existing {
+        Some(row) => row.get(0)?,
+        None => conn
+            .query_optional("SELECT MAX(position) FROM cf_approval_rules", &[])?
+            .map(|row| row.get::<Option<i64>>(0))
+            .transpose()?
+            .flatten()
+            .map_or(1, |last| last + 1),
+    };
+    let max_files = match rule.condition {
+        RuleCondition::SmallRun { max_files } => Some(max_files.min(i64::MAX as u64) as i64),
+        _ => None,
+    };
+    let plugins_json = serde_json::to_string(&rule.plugins)?;
+    conn.execute(
+        "DELETE FROM cf_approval_rules WHERE name = ?",
+        &[DbValue::from(rule.name.as_str())],
+    )?;
+    conn.execute(
+        r#"
+        INSERT INTO cf_approval_rules (name, plugins_json, condition, max_files, position, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        &[
+            DbValue::from(rule.name.as_str()),
+            DbValue::from(plugins_json.as_str()),
+            DbValue::from(rule.condition.as_str()),
+            DbValue::from(max_files),
+            DbValue::from(position),
+            DbValue::from(chrono::Utc::now().timestamp_millis()),
+        ],
+    )
+    .context("Failed to save approval rule")?;
+    Ok(())
+}
+
+/// Remove the rule `name`. Returns false if there was none.
+pub fn remove_rule(conn: &DbConnection, name: &str) -> Result<bool> {
+    let removed = conn.execute(
+        "DELETE FROM cf_approval_rules WHERE name = ?",
+        &[DbValue::from(name)],
+    )?;
+    Ok(removed > 0)
+}
+
+/// All rules, in evaluation order.
+pub fn list_rules(conn: &DbConnection) -> Result<Vec<ApprovalRule>> {
+    let rows = conn.query_all(
+        r#"
+        SELECT name, plugins_json, condition, max_files
+        FROM cf_approval_rules
+        ORDER BY position
+        "#,
+        &[],
+    )?;
+    rows.iter().map(ApprovalRule::from_row).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::types::{DataType, SchemaColumnSpec};
+    use casparian_protocol::SchemaMode;
+
+    fn column(name: &str, nullable: bool) -> SchemaColumnSpec {
+        SchemaColumnSpec {
+            name: name.to_string(),
+            data_type: DataType::String,
+            nullable,
+            format: None,
+            constraints: Vec::new(),
+        }
+    }
+
+    fn schema(columns: Vec<SchemaColumnSpec>) -> SchemaSpec {
+        SchemaSpec {
+            columns,
+            mode: SchemaMode::Strict,
+        }
+    }
+
+    fn run(plugin_name: &str, file_count: u64) -> ApprovalOperation {
+        ApprovalOperation::Run {
+            plugin_name: plugin_name.to_string(),
+            plugin_version: None,
+            input_dir: "/data/in".to_string(),
+            file_count,
+            output: None,
+        }
+    }
+
+    #[test]
+    fn test_rules_match_runs_and_additive_schemas() {
+        let rules = vec![
+            ApprovalRule {
+                name: "small-runs".to_string(),
+                plugins: Vec::new(),
+                condition: RuleCondition::SmallRun { max_files: 10 },
+            },
+            ApprovalRule {
+                name: "trusted".to_string(),
+                plugins: vec!["orders".to_string()],
+                condition: RuleCondition::AnyRun,
+            },
+            ApprovalRule {
+                name: "additive".to_string(),
+                plugins: Vec::new(),
+                condition: RuleCondition::AdditiveSchema,
+            },
+        ];
+
+        let matched = |op: &ApprovalOperation, approved: Option<&SchemaSpec>| {
+            evaluate(&rules, op, approved).map(|rule| rule.name.clone())
+        };
+        assert_eq!(
+            matched(&run("invoices", 3), None).as_deref(),
+            Some("small-runs")
+        );
+        assert_eq!(
+            matched(&run("orders", 500), None).as_deref(),
+            Some("trusted")
+        );
+        assert_eq!(matched(&run("invoices", 500), None), None);
+
+        let approved = schema(vec![column("id", false)]);
+        let promote = |columns| ApprovalOperation::SchemaPromote {
+            plugin_name: "orders".to_string(),
+            output_name: "orders".to_string(),
+            schema: schema(columns),
+        };
+        let added = promote(vec![column("id", false), column("note", true)]);
+        assert_eq!(
+            matched(&added, Some(&approved)).as_deref(),
+            Some("additive")
+        );
+        assert_eq!(matched(&added, None), None);
+        let required = promote(vec![column("id", false), column("note", false)]);
+        assert_eq!(matched(&required, Some(&approved)), None);
+        let retyped = promote(vec![column("id", true), column("note", true)]);
+        assert_eq!(matched(&retyped, Some(&approved)), None);
+    }
+
+    #[test]
+    fn test_rule_storage_round_trip() {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let small = ApprovalRule {
+            name: "small-runs".to_string(),
+            plugins: vec!["orders".to_string()],
+            condition: RuleCondition::SmallRun { max_files: 10 },
+        };
+        let additive = ApprovalRule {
+            name: "additive".to_string(),
+            plugins: Vec::new(),
+            condition: RuleCondition::AdditiveSchema,
+        };
+        set_rule(&conn, &small).unwrap();
+        set_rule(&conn, &additive).unwrap();
+        let widened = ApprovalRule {
+            condition: RuleCondition::SmallRun { max_files: 50 },
+            ..small
+        };
+        set_rule(&conn, &widened).unwrap();
+        assert_eq!(list_rules(&conn).unwrap(), vec![widened, additive]);
+
+        let unbounded = ApprovalRule {
+            name: "everything".to_string(),
+            plugins: Vec::new(),
+            condition: RuleCondition::AnyRun,
+        };
+        assert!(set_rule(&conn, &unbounded).is_err());
+
+        assert!(remove_rule(&conn, "additive").unwrap());
+        assert!(!remove_rule(&conn, "additive").unwrap());
+        assert_eq!(list_rules(&conn).unwrap().len(), 1);
+    }
+}
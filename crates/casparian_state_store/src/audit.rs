@@ -0,0 +1,139 @@
+//! Audit log for control-plane mutations.
+//!
+//! Every process that opens a state store (Sentinel, CLI, desktop app) shares
+//! one audit tape stored next to the database (`state.sqlite` ->
+//! `state.audit.tape`). Mutations of routing rules, plugins, approvals,
+//! schema amendments and config are recorded with the actor and before/after
+//! snapshots.
+//!
+//! Recording is best-effort: a failed append is logged and never fails the
+//! mutation that was already committed.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use casparian_tape::{AuditEntry, AuditQuery, AuditRecordV1, AuditTape};
+use serde::Serialize;
+use tracing::warn;
+
+/// Entity kinds recorded on the audit tape.
+pub mod entity {
+    pub const ROUTING_RULE: &str = "routing_rule";
+    pub const PLUGIN: &str = "plugin";
+    pub const APPROVAL: &str = "approval";
+    pub const CONFIG: &str = "config";
+    pub const SCHEMA_AMENDMENT: &str = "schema_amendment";
+    pub const SUBSCRIPTION: &str = "subscription";
+    pub const SCHEDULE: &str = "schedule";
+    pub const ARTIFACT: &str = "artifact";
+}
+
+/// Audit tape path for a SQLite state store: `<db stem>.audit.tape` beside it.
+pub fn audit_tape_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("audit.tape")
+}
+
+/// Handle for recording and querying audit entries.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    tape: Option<AuditTape>,
+}
+
+impl AuditLog {
+    /// Audit log of the SQLite state store at `db_path`.
+    pub fn for_sqlite(db_path: &Path) -> Self {
+        Self {
+            tape: Some(AuditTape::new(audit_tape_path(db_path))),
+        }
+    }
+
+    /// A log that records nothing.
+    pub fn disabled() -> Self {
+        Self { tape: None }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.tape.as_ref().map(AuditTape::path)
+    }
+
+    /// Record a mutation. `actor` defaults to the OS user of this process.
+    pub fn record(
+        &self,
+        entity_kind: &str,
+        entity_id: &str,
+        action: &str,
+        actor: Option<&str>,
+        before: Option<serde_json::Value>,
+        after: Option<serde_json::Value>,
+    ) {
+        let Some(tape) = &self.tape else {
+            return;
+        };
+        let actor = actor.map(str::to_string).unwrap_or_else(default_actor);
+        let mut record = AuditRecordV1::new(entity_kind, entity_id, action, actor);
+        record.before = before;
+        record.after = after;
+        if let Err(err) = tape.append(&record) {
+            warn!(
+                "Failed to record audit entry {} {} {}: {}",
+                entity_kind, entity_id, action, err
+            );
+        }
+    }
+
+    /// Matching entries in tape order.
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        match &self.tape {
+            Some(tape) => Ok(tape.query(query)?),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// JSON snapshot of an entity for `before`/`after`.
+pub fn snapshot(value: &impl Serialize) -> Option<serde_json::Value> {
+    serde_json::to_value(value).ok()
+}
+
+/// OS user of this process, for mutations without an explicit actor.
+pub fn default_actor() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_query_beside_database() {
+        let temp = tempfile::tempdir().unwrap();
+        let db_path = temp.path().join("state.sqlite");
+        let log = AuditLog::for_sqlite(&db_path);
+        assert_eq!(
+            log.path(),
+            Some(temp.path().join("state.audit.tape").as_path())
+        );
+
+        log.record(
+            entity::CONFIG,
+            "parser_a/resource_limits",
+            "updated",
+            Some("alice"),
+            None,
+            Some(serde_json::json!({ "timeout_secs": 60 })),
+        );
+        let entries = log
+            .query(&AuditQuery::new().entity_kind(entity::CONFIG))
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].record.actor, "alice");
+
+        AuditLog::disabled().record(entity::PLUGIN, "p", "published", None, None, None);
+        assert!(AuditLog::disabled()
+            .query(&AuditQuery::new())
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+//! Compaction policies and the compaction ledger.
+//!
+//! A policy says when the small Parquet files of one output topic are
+//! compacted: once there are `min_files` of them or they add up to
+//! `min_bytes`, into files of `target_file_bytes`. The `*` topic applies to
+//! outputs without their own policy; outputs with no policy at all are only
+//! compacted on request.
+//!
+//! Every file a compaction replaced is recorded in `cf_output_compactions`
+//! with the job artifact it belonged to. The artifact's lineage hops are
+//! copied onto the compacted file, so lineage queries on it still reach every
+//! source and job.
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
+use serde::{Deserialize, Serialize};
+
+/// Topic of the policy that applies to outputs without their own.
+pub const DEFAULT_POLICY_TOPIC: &str = "*";
+
+/// When and how the small files of one output topic are compacted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionPolicy {
+    pub topic: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_files: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_file_bytes: Option<u64>,
+}
+
+impl CompactionPolicy {
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        let min_files: Option<i64> = row.get_by_name("min_files")?;
+        let min_bytes: Option<i64> = row.get_by_name("min_bytes")?;
+        let target_file_bytes: Option<i64> = row.get_by_name("target_file_bytes")?;
+        Ok(Self {
+            topic: row.get_by_name("topic")?,
+            min_files: min_files.map(|n| n.clamp(0, u32::MAX as i64) as u32),
+            min_bytes: min_bytes.map(|bytes| bytes.max(0) as u64),
+            target_file_bytes: target_file_bytes.map(|bytes| bytes.max(0) as u64),
+        })
+    }
+}
+
+/// A file replaced by a compacted file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionRecord {
+    pub source_path: String,
+    /// Artifact URI of the job that wrote the file, if known
+    pub artifact_uri: Option<String>,
+    pub compacted_uri: String,
+    pub output_name: String,
+    pub rows: u64,
+    pub bytes: u64,
+}
+
+/// Create the policy and ledger tables (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let int_type = if conn.backend_name() == "SQLite" {
+        "INTEGER"
+    } else {
+        "BIGINT"
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_compaction_policies (
+            topic TEXT PRIMARY KEY,
+            min_files {int_type},
+            min_bytes {int_type},
+            target_file_bytes {int_type},
+            updated_at {int_type} NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS cf_output_compactions (
+            source_path TEXT PRIMARY KEY,
+            artifact_uri TEXT,
+            compacted_uri TEXT NOT NULL,
+            output_name TEXT NOT NULL,
+            rows {int_type} NOT NULL,
+            bytes {int_type} NOT NULL,
+            compacted_at {int_type} NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS ix_output_compactions_artifact
+            ON cf_output_compactions(artifact_uri);
+        "#,
+        int_type = int_type,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize compaction schema")?;
+    Ok(())
+}
+
+/// Create or replace the policy of `policy.topic`.
+pub fn set_policy(conn: &DbConnection, policy: &CompactionPolicy) -> Result<()> {
+    conn.execute(
+        "DELETE FROM cf_compaction_policies WHERE topic = ?",
+        &[DbValue::from(policy.topic.as_str())],
+    )?;
+    conn.execute(
+        r#"
+        INSERT INTO cf_compaction_policies (topic, min_files, min_bytes, target_file_bytes, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        &[
+            DbValue::from(policy.topic.as_str()),
+            DbValue::from(policy.min_files.map(i64::from)),
+            DbValue::from(policy.min_bytes.map(clamp_bytes)),
+            DbValue::from(policy.target_file_bytes.map(clamp_bytes)),
+            DbValue::from(chrono::Utc::now().timestamp_millis()),
+        ],
+    )
+    .context("Failed to save compaction policy")?;
+    Ok(())
+}
+
+/// Remove the policy of `topic`. Returns false if it had none.
+pub fn remove_policy(conn: &DbConnection, topic: &str) -> Result<bool> {
+    let removed = conn.execute(
+        "DELETE FROM cf_compaction_policies WHERE topic = ?",
+        &[DbValue::from(topic)],
+    )?;
+    Ok(removed > 0)
+}
+
+/// All policies, by topic.
+pub fn list_policies(conn: &DbConnection) -> Result<Vec<CompactionPolicy>> {
+    let rows = conn.query_all(
+        r#"
+        SELECT topic, min_files, min_bytes, target_file_bytes
+        FROM cf_compaction_policies
+        ORDER BY topic
+        "#,
+        &[],
+    )?;
+    rows.iter().map(CompactionPolicy::from_row).collect()
+}
+
+/// The policy for `topic`: its own, else the `*` default.
+pub fn policy_for<'a>(
+    policies: &'a [CompactionPolicy],
+    topic: &str,
+) -> Option<&'a CompactionPolicy> {
+    policies
+        .iter()
+        .find(|policy| policy.topic == topic)
+        .or_else(|| {
+            policies
+                .iter()
+                .find(|policy| policy.topic == DEFAULT_POLICY_TOPIC)
+        })
+}
+
+/// Record a replaced file and carry its artifact's lineage over to the
+/// compacted file.
+pub fn record_compaction(conn: &DbConnection, record: &CompactionRecord) -> Result<()> {
+    let now = chrono::Utc::now().timestamp_millis();
+    conn.execute(
+        r#"
+        INSERT INTO cf_output_compactions
+            (source_path, artifact_uri, compacted_uri, output_name, rows, bytes, compacted_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT (source_path) DO NOTHING
+        "#,
+        &[
+            DbValue::from(record.source_path.as_str()),
+            DbValue::from(record.artifact_uri.as_deref()),
+            DbValue::from(record.compacted_uri.as_str()),
+            DbValue::from(record.output_name.as_str()),
+            DbValue::from(clamp_bytes(record.rows)),
+            DbValue::from(clamp_bytes(record.bytes)),
+            DbValue::from(now),
+        ],
+    )
+    .context("Failed to record compaction")?;
+
+    let Some(artifact_uri) = record.artifact_uri.as_deref() else {
+        return Ok(());
+    };
+    if !conn.table_exists("cf_lineage_hops")? {
+        return Ok(());
+    }
+    conn.execute(
+        r#"
+        INSERT INTO cf_lineage_hops (
+            job_id, source_file_id, source_path, source_hash, decompressed_hash,
+            source_encoding, plugin_name, parser_version, artifact_kind, artifact_name,
+            artifact_uri, table_name, rows, created_at, lineage_chain
+        )
+        SELECT job_id, source_file_id, source_path, source_hash, decompressed_hash,
+               source_encoding, plugin_name, parser_version, artifact_kind, artifact_name,
+               ?, table_name, rows, ?, lineage_chain
+        FROM cf_lineage_hops
+        WHERE artifact_uri = ?
+        ON CONFLICT DO NOTHING
+        "#,
+        &[
+            DbValue::from(record.compacted_uri.as_str()),
+            DbValue::from(now),
+            DbValue::from(artifact_uri),
+        ],
+    )
+    .context("Failed to copy lineage to compacted file")?;
+    Ok(())
+}
+
+/// Files replaced by compaction, newest first; all outputs when
+/// `output_name` is None.
+pub fn list_compactions(
+    conn: &DbConnection,
+    output_name: Option<&str>,
+) -> Result<Vec<CompactionRecord>> {
+    let (filter, params) = match output_name {
+        Some(name) => ("WHERE output_name = ?", vec![DbValue::from(name)]),
+        None => ("", Vec::new()),
+    };
+    let rows = conn.query_all(
+        &format!(
+            r#"
+            SELECT source_path, artifact_uri, compacted_uri, output_name, rows, bytes
+            FROM cf_output_compactions
+            {filter}
+            ORDER BY compacted_at DESC, source_path
+            "#
+        ),
+        &params,
+    )?;
+    rows.iter()
+        .map(|row| -> Result<CompactionRecord> {
+            let rows: i64 = row.get_by_name("rows")?;
+            let bytes: i64 = row.get_by_name("bytes")?;
+            Ok(CompactionRecord {
+                source_path: row.get_by_name("source_path")?,
+                artifact_uri: row.get_by_name("artifact_uri")?,
+                compacted_uri: row.get_by_name("compacted_uri")?,
+                output_name: row.get_by_name("output_name")?,
+                rows: rows.max(0) as u64,
+                bytes: bytes.max(0) as u64,
+            })
+        })
+        .collect()
+}
+
+fn clamp_bytes(value: u64) -> i64 {
+    value.min(i64::MAX as u64) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lineage::LineageStorage;
+
+    #[test]
+    fn test_compaction_carries_lineage() {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        init_schema(&conn).unwrap();
+        LineageStorage::new(conn.clone()).init_schema().unwrap();
+        conn.execute(
+            r#"
+            INSERT INTO cf_lineage_hops (job_id, source_path, plugin_name, artifact_kind,
+                artifact_name, artifact_uri, rows, created_at)
+            VALUES (7, '/in/a.csv', 'trades_parser', 'output', 'trades',
+                'file:///out/trades_aaaa.parquet', 10, 1)
+            "#,
+            &[],
+        )
+        .unwrap();
+
+        let record = CompactionRecord {
+            source_path: "/out/trades_aaaa.parquet".to_string(),
+            artifact_uri: Some("file:///out/trades_aaaa.parquet".to_string()),
+            compacted_uri: "file:///out/trades_bbbb.parquet".to_string(),
+            output_name: "trades".to_string(),
+            rows: 10,
+            bytes: 512,
+        };
+        record_compaction(&conn, &record).unwrap();
+        record_compaction(&conn, &record).unwrap();
+
+        assert_eq!(
+            list_compactions(&conn, Some("trades")).unwrap(),
+            vec![record]
+        );
+        let hops = LineageStorage::new(conn.clone())
+            .lineage_for_artifact("file:///out/trades_bbbb.parquet")
+            .unwrap();
+        assert_eq!(hops.len(), 1);
+        assert_eq!(hops[0].job_id, 7);
+        assert_eq!(hops[0].source_path.as_deref(), Some("/in/a.csv"));
+
+        let policies = vec![CompactionPolicy {
+            topic: DEFAULT_POLICY_TOPIC.to_string(),
+            min_files: Some(8),
+            ..CompactionPolicy::default()
+        }];
+        assert_eq!(
+            policy_for(&policies, "trades").and_then(|p| p.min_files),
+            Some(8)
+        );
+    }
+}
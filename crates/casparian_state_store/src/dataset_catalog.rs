@@ -0,0 +1,342 @@
+//! Dataset catalog: every output a job has committed, by name.
+//!
+//! The Sentinel registers each `Output` artifact of a successful CONCLUDE in
+//! `cf_datasets`, keyed by output name like the query catalog's
+//! `outputs.<name>` views. A dataset keeps the sink it was last written to,
+//! its contract schema, partition columns, row count and the source root its
+//! inputs came from, so users can list and search what tables exist without
+//! walking the output directories.
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
+use casparian_protocol::http_types::{DatasetDetail, DatasetSummary};
+use casparian_protocol::types::{SchemaDefinition, SinkMode};
+use chrono::{DateTime, Utc};
+
+const COLUMNS: &str = "name, plugin_name, sink_uri, sink_mode, table_name, schema_json, \
+    schema_hash, row_count, partition_columns, lineage_root, job_count, last_job_id, \
+    created_at, updated_at";
+
+/// One committed output to register.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetCommit {
+    pub name: String,
+    pub plugin_name: String,
+    pub job_id: i64,
+    pub sink_uri: String,
+    pub sink_mode: SinkMode,
+    pub table_name: Option<String>,
+    pub schema: Option<SchemaDefinition>,
+    pub schema_hash: Option<String>,
+    pub rows: u64,
+    pub partition_columns: Vec<String>,
+    pub lineage_root: Option<String>,
+}
+
+/// A catalogued dataset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetRecord {
+    pub name: String,
+    pub plugin_name: String,
+    pub sink_uri: String,
+    pub sink_mode: SinkMode,
+    pub table_name: Option<String>,
+    pub schema: Option<SchemaDefinition>,
+    pub schema_hash: Option<String>,
+    /// Rows committed since the last Replace (all rows for Append)
+    pub row_count: u64,
+    pub partition_columns: Vec<String>,
+    pub lineage_root: Option<String>,
+    pub job_count: u64,
+    pub last_job_id: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl DatasetRecord {
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        let sink_mode: String = row.get_by_name("sink_mode")?;
+        let schema_json: Option<String> = row.get_by_name("schema_json")?;
+        let partition_columns: String = row.get_by_name("partition_columns")?;
+        let row_count: i64 = row.get_by_name("row_count")?;
+        let job_count: i64 = row.get_by_name("job_count")?;
+        Ok(Self {
+            name: row.get_by_name("name")?,
+            plugin_name: row.get_by_name("plugin_name")?,
+            sink_uri: row.get_by_name("sink_uri")?,
+            sink_mode: sink_mode.parse().map_err(anyhow::Error::msg)?,
+            table_name: row.get_by_name("table_name")?,
+            schema: schema_json
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()
+                .context("Invalid schema_json in cf_datasets")?,
+            schema_hash: row.get_by_name("schema_hash")?,
+            row_count: row_count.max(0) as u64,
+            partition_columns: serde_json::from_str(&partition_columns)
+                .context("Invalid partition_columns in cf_datasets")?,
+            lineage_root: row.get_by_name("lineage_root")?,
+            job_count: job_count.max(0) as u64,
+            last_job_id: row.get_by_name("last_job_id")?,
+            created_at: row.get_by_name("created_at")?,
+            updated_at: row.get_by_name("updated_at")?,
+        })
+    }
+
+    /// Row of `GET /datasets`.
+    pub fn summary(&self) -> DatasetSummary {
+        DatasetSummary {
+            name: self.name.clone(),
+            plugin_name: self.plugin_name.clone(),
+            sink_uri: self.sink_uri.clone(),
+            row_count: self.row_count,
+            byte_size: None,
+            last_updated: millis_to_rfc3339(self.updated_at),
+            table_name: self.table_name.clone(),
+            partition_columns: self.partition_columns.clone(),
+        }
+    }
+
+    /// Body of `GET /datasets/{name}`.
+    pub fn detail(&self) -> DatasetDetail {
+        DatasetDetail {
+            summary: self.summary(),
+            sink_mode: self.sink_mode,
+            columns: self
+                .schema
+                .as_ref()
+                .map(|schema| schema.columns.clone())
+                .unwrap_or_default(),
+            schema_hash: self.schema_hash.clone(),
+            lineage_root: self.lineage_root.clone(),
+            job_count: self.job_count,
+            last_job_id: self.last_job_id,
+            created_at: millis_to_rfc3339(self.created_at),
+        }
+    }
+}
+
+/// Create the catalog table (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let int_type = if conn.backend_name() == "SQLite" {
+        "INTEGER"
+    } else {
+        "BIGINT"
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_datasets (
+            name TEXT PRIMARY KEY,
+            plugin_name TEXT NOT NULL,
+            sink_uri TEXT NOT NULL,
+            sink_mode TEXT NOT NULL,
+            table_name TEXT,
+            schema_json TEXT,
+            schema_hash TEXT,
+            row_count {int_type} NOT NULL,
+            partition_columns TEXT NOT NULL,
+            lineage_root TEXT,
+            job_count {int_type} NOT NULL,
+            last_job_id {int_type} NOT NULL,
+            created_at {int_type} NOT NULL,
+            updated_at {int_type} NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS ix_datasets_plugin ON cf_datasets(plugin_name);
+        "#,
+        int_type = int_type,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize cf_datasets schema")?;
+    Ok(())
+}
+
+/// Register a committed output. The first commit creates the dataset; later
+/// ones move it to the latest sink and schema and add their rows, except a
+/// Replace commit, whose rows replace the count.
+pub fn register(conn: &DbConnection, commit: &DatasetCommit) -> Result<()> {
+    let now = Utc::now().timestamp_millis();
+    let schema_json = commit
+        .schema
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()?;
+    let partition_columns = serde_json::to_string(&commit.partition_columns)?;
+    let rows = commit.rows as i64;
+    let row_count = if commit.sink_mode == SinkMode::Replace {
+        "row_count = ?"
+    } else {
+        "row_count = row_count + ?"
+    };
+    let updated = conn
+        .execute(
+            &format!(
+                r#"
+                UPDATE cf_datasets
+                SET plugin_name = ?, sink_uri = ?, sink_mode = ?, table_name = ?,
+                    schema_json = ?, schema_hash = ?, {row_count}, partition_columns = ?,
+                    lineage_root = COALESCE(?, lineage_root), job_count = job_count + 1,
+                    last_job_id = ?, updated_at = ?
+                WHERE name = ?
+                "#,
+                row_count = row_count,
+            ),
+            &[
+                DbValue::from(commit.plugin_name.as_str()),
+                DbValue::from(commit.sink_uri.as_str()),
+                DbValue::from(commit.sink_mode.as_str()),
+                DbValue::from(commit.table_name.clone()),
+                DbValue::from(schema_json.clone()),
+                DbValue::from(commit.schema_hash.clone()),
+                DbValue::from(rows),
+                DbValue::from(partition_columns.as_str()),
+                DbValue::from(commit.lineage_root.clone()),
+                DbValue::from(commit.job_id),
+                DbValue::from(now),
+                DbValue::from(commit.name.as_str()),
+            ],
+        )
+        .context("Failed to update dataset")?;
+    if updated > 0 {
+        return Ok(());
+    }
+    conn.execute(
+        &format!(
+            "INSERT INTO cf_datasets ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?, ?)",
+            COLUMNS
+        ),
+        &[
+            DbValue::from(commit.name.as_str()),
+            DbValue::from(commit.plugin_name.as_str()),
+            DbValue::from(commit.sink_uri.as_str()),
+            DbValue::from(commit.sink_mode.as_str()),
+            DbValue::from(commit.table_name.clone()),
+            DbValue::from(schema_json),
+            DbValue::from(commit.schema_hash.clone()),
+            DbValue::from(rows),
+            DbValue::from(partition_columns),
+            DbValue::from(commit.lineage_root.clone()),
+            DbValue::from(commit.job_id),
+            DbValue::from(now),
+            DbValue::from(now),
+        ],
+    )
+    .context("Failed to register dataset")?;
+    Ok(())
+}
+
+/// Datasets whose name, plugin, table, sink or column names contain `search`
+/// (case-insensitive), all datasets without one. Most recently updated first.
+pub fn list(
+    conn: &DbConnection,
+    search: Option<&str>,
+    plugin_name: Option<&str>,
+    limit: usize,
+) -> Result<Vec<DatasetRecord>> {
+    let mut filters = Vec::new();
+    let mut params = Vec::new();
+    if let Some(search) = search.map(str::trim).filter(|search| !search.is_empty()) {
+        filters.push(
+            "(LOWER(name) LIKE ? OR LOWER(plugin_name) LIKE ? OR LOWER(COALESCE(table_name, '')) LIKE ? \
+             OR LOWER(sink_uri) LIKE ? OR LOWER(COALESCE(schema_json, '')) LIKE ?)",
+        );
+        let pattern = format!("%{}%", search.to_lowercase());
+        params.extend((0..5).map(|_| DbValue::from(pattern.as_str())));
+    }
+    if let Some(plugin_name) = plugin_name {
+        filters.push("plugin_name = ?");
+        params.push(DbValue::from(plugin_name));
+    }
+    let where_clause = if filters.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", filters.join(" AND "))
+    };
+    params.push(DbValue::from(limit as i64));
+    let rows = conn.query_all(
+        &format!(
+            "SELECT {} FROM cf_datasets {} ORDER BY updated_at DESC, name LIMIT ?",
+            COLUMNS, where_clause
+        ),
+        &params,
+    )?;
+    rows.iter().map(DatasetRecord::from_row).collect()
+}
+
+/// The dataset called `name`, if any job has committed it.
+pub fn describe(conn: &DbConnection, name: &str) -> Result<Option<DatasetRecord>> {
+    let row = conn.query_optional(
+        &format!("SELECT {} FROM cf_datasets WHERE name = ?", COLUMNS),
+        &[DbValue::from(name)],
+    )?;
+    row.as_ref().map(DatasetRecord::from_row).transpose()
+}
+
+fn millis_to_rfc3339(millis: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(millis)
+        .unwrap_or_else(Utc::now)
+        .to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::types::SchemaColumnSpec;
+    use casparian_protocol::DataType;
+
+    fn commit(name: &str, job_id: i64, mode: SinkMode, rows: u64) -> DatasetCommit {
+        DatasetCommit {
+            name: name.to_string(),
+            plugin_name: "orders_parser".to_string(),
+            job_id,
+            sink_uri: "parquet:///out?partition_by=region".to_string(),
+            sink_mode: mode,
+            table_name: None,
+            schema: Some(SchemaDefinition {
+                columns: vec![SchemaColumnSpec {
+                    name: "coupon_code".to_string(),
+                    data_type: DataType::String,
+                    nullable: true,
+                    format: None,
+                    constraints: Vec::new(),
+                }],
+            }),
+            schema_hash: Some("abc".to_string()),
+            rows,
+            partition_columns: vec!["region".to_string()],
+            lineage_root: Some("/data/orders".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_register_list_and_describe() {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        register(&conn, &commit("orders", 1, SinkMode::Append, 10)).unwrap();
+        register(&conn, &commit("orders", 2, SinkMode::Append, 5)).unwrap();
+        register(&conn, &commit("daily_totals", 3, SinkMode::Replace, 4)).unwrap();
+        register(&conn, &commit("daily_totals", 4, SinkMode::Replace, 3)).unwrap();
+
+        let orders = describe(&conn, "orders").unwrap().unwrap();
+        assert_eq!(orders.row_count, 15);
+        assert_eq!(orders.job_count, 2);
+        assert_eq!(orders.last_job_id, 2);
+        assert_eq!(orders.partition_columns, vec!["region".to_string()]);
+        let detail = orders.detail();
+        assert_eq!(detail.columns[0].name, "coupon_code");
+        assert_eq!(detail.lineage_root.as_deref(), Some("/data/orders"));
+
+        let totals = describe(&conn, "daily_totals").unwrap().unwrap();
+        assert_eq!(totals.row_count, 3);
+        assert!(describe(&conn, "missing").unwrap().is_none());
+
+        assert_eq!(list(&conn, None, None, 10).unwrap().len(), 2);
+        let found = list(&conn, Some("DAILY"), None, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "daily_totals");
+        // Column names are searchable too
+        assert_eq!(list(&conn, Some("coupon"), None, 10).unwrap().len(), 2);
+        assert!(list(&conn, None, Some("other"), 10).unwrap().is_empty());
+        assert_eq!(list(&conn, None, None, 1).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,117 @@
+//! Idempotency keys for API requests that create things.
+//!
+//! A client that retries a request with the same `Idempotency-Key` must get
+//! the original response instead of a second job. Each key stores a hash of
+//! the request it was first used with and the response that was returned;
+//! entries older than the caller's TTL are ignored and purged.
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue};
+
+/// Response recorded for an idempotency key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotentResponse {
+    /// Hash of the request body the key was first used with
+    pub request_hash: String,
+    pub response_json: String,
+    pub created_at: i64,
+}
+
+/// Create the idempotency key table (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let int_type = if conn.backend_name() == "SQLite" {
+        "INTEGER"
+    } else {
+        "BIGINT"
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_idempotency_keys (
+            idempotency_key TEXT PRIMARY KEY,
+            request_hash TEXT NOT NULL,
+            response_json TEXT NOT NULL,
+            created_at {int_type} NOT NULL
+        );
+        "#,
+        int_type = int_type,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize idempotency key schema")?;
+    Ok(())
+}
+
+/// The response stored for `key`, unless it was recorded before
+/// `not_before` (Unix millis). Expired entries are purged first.
+pub fn find(conn: &DbConnection, key: &str, not_before: i64) -> Result<Option<IdempotentResponse>> {
+    conn.execute(
+        "DELETE FROM cf_idempotency_keys WHERE created_at < ?",
+        &[DbValue::from(not_before)],
+    )?;
+    let row = conn.query_optional(
+        r#"
+        SELECT request_hash, response_json, created_at
+        FROM cf_idempotency_keys
+        WHERE idempotency_key = ?
+        "#,
+        &[DbValue::from(key)],
+    )?;
+    row.map(|row| {
+        Ok(IdempotentResponse {
+            request_hash: row.get_by_name("request_hash")?,
+            response_json: row.get_by_name("response_json")?,
+            created_at: row.get_by_name("created_at")?,
+        })
+    })
+    .transpose()
+}
+
+/// Record the response returned for `key`, replacing any earlier entry.
+pub fn save(
+    conn: &DbConnection,
+    key: &str,
+    request_hash: &str,
+    response_json: &str,
+    now: i64,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM cf_idempotency_keys WHERE idempotency_key = ?",
+        &[DbValue::from(key)],
+    )?;
+    conn.execute(
+        r#"
+        INSERT INTO cf_idempotency_keys (idempotency_key, request_hash, response_json, created_at)
+        VALUES (?, ?, ?, ?)
+        "#,
+        &[
+            DbValue::from(key),
+            DbValue::from(request_hash),
+            DbValue::from(response_json),
+            DbValue::from(now),
+        ],
+    )
+    .context("Failed to save idempotency key")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_ignores_expired_keys() {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        save(&conn, "retry-1", "hash-a", r#"{"job_id":7}"#, 1_000).unwrap();
+        let stored = find(&conn, "retry-1", 500).unwrap().unwrap();
+        assert_eq!(stored.request_hash, "hash-a");
+        assert_eq!(stored.response_json, r#"{"job_id":7}"#);
+        assert_eq!(find(&conn, "retry-2", 500).unwrap(), None);
+
+        save(&conn, "retry-1", "hash-b", r#"{"job_id":8}"#, 2_000).unwrap();
+        assert_eq!(find(&conn, "retry-1", 500).unwrap().unwrap().request_hash, "hash-b");
+
+        assert_eq!(find(&conn, "retry-1", 2_001).unwrap(), None);
+        assert_eq!(find(&conn, "retry-1", 0).unwrap(), None);
+    }
+}
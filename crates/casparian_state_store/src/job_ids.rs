@@ -0,0 +1,287 @@
+//! Persistent job ID allocation.
+//!
+//! Queue jobs (`cf_processing_queue.id`, [`JobId`]) and Control Plane API
+//! jobs (`cf_api_jobs.job_id`, [`ApiJobId`]) draw from one monotonic
+//! sequence stored in `cf_id_sequences`, so an ID is never handed out twice:
+//! not after a restart, and not across the two tables. Bulk enqueues reserve
+//! a block of IDs with a single write ([`reserve_job_ids`]); IDs of a block
+//! that go unused are skipped, never reissued.
+//!
+//! Every reservation also starts past the highest ID already stored, so rows
+//! inserted without the allocator (tests, older tools) cannot collide with it.
+//!
+//! `cf_api_job_map` records which queue jobs were enqueued for an API job.
+//! `ApiStorage::reserve_queue_jobs` fills it: the MCP executor reserves one
+//! ID per file an API job runs, directly or through the Control API
+//! (`ReserveApiJobIds`).
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::{ApiJobId, JobId};
+
+/// Sequence shared by queue and API jobs.
+pub const JOB_SEQUENCE: &str = "job";
+
+/// Tables whose IDs come from [`JOB_SEQUENCE`], as (table, id column).
+const JOB_ID_TABLES: &[(&str, &str)] = &[("cf_processing_queue", "id"), ("cf_api_jobs", "job_id")];
+
+/// Contiguous IDs reserved by one [`reserve_job_ids`] call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobIdBlock {
+    next: u64,
+    end: u64,
+}
+
+impl JobIdBlock {
+    /// IDs not yet taken from the block.
+    pub fn remaining(&self) -> u64 {
+        self.end - self.next
+    }
+}
+
+impl Iterator for JobIdBlock {
+    type Item = JobId;
+
+    fn next(&mut self) -> Option<JobId> {
+        if self.next >= self.end {
+            return None;
+        }
+        let id = JobId::new(self.next);
+        self.next += 1;
+        Some(id)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.remaining()).unwrap_or(usize::MAX);
+        (remaining, Some(remaining))
+    }
+}
+
+/// Create the sequence and API mapping tables (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let (id_type, time_type) = if conn.backend_name() == "SQLite" {
+        ("INTEGER", "INTEGER")
+    } else {
+        ("BIGINT", "BIGINT")
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_id_sequences (
+            name TEXT PRIMARY KEY,
+            next_id {id_type} NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS cf_api_job_map (
+            job_id {id_type} PRIMARY KEY,
+            api_job_id {id_type} NOT NULL,
+            created_at {time_type} NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS ix_api_job_map_api ON cf_api_job_map(api_job_id);
+        "#,
+        id_type = id_type,
+        time_type = time_type,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize job ID schema")?;
+    conn.execute(
+        "INSERT INTO cf_id_sequences (name, next_id) VALUES (?, 1) ON CONFLICT (name) DO NOTHING",
+        &[DbValue::from(JOB_SEQUENCE)],
+    )
+    .context("Failed to seed job ID sequence")?;
+    Ok(())
+}
+
+/// Reserve `count` consecutive job IDs with one write.
+pub fn reserve_job_ids(conn: &DbConnection, count: u64) -> Result<JobIdBlock> {
+    if count == 0 {
+        anyhow::bail!("Job ID reservation must be at least 1");
+    }
+    let count_i64 = i64::try_from(count).context("Job ID reservation too large")?;
+
+    let mut floors = vec!["next_id".to_string()];
+    for (table, column) in JOB_ID_TABLES {
+        if conn.table_exists(table)? {
+            floors.push(format!(
+                "(SELECT COALESCE(MAX({column}), 0) + 1 FROM {table})"
+            ));
+        }
+    }
+    let start = if floors.len() == 1 {
+        "next_id".to_string()
+    } else if conn.backend_name() == "SQLite" {
+        format!("MAX({})", floors.join(", "))
+    } else {
+        format!("GREATEST({})", floors.join(", "))
+    };
+    let sql = format!(
+        "UPDATE cf_id_sequences SET next_id = {start} + ? WHERE name = ? RETURNING next_id"
+    );
+    let end: Option<i64> = conn
+        .query_optional(
+            &sql,
+            &[DbValue::from(count_i64), DbValue::from(JOB_SEQUENCE)],
+        )?
+        .map(|row| row.get_by_name("next_id"))
+        .transpose()?;
+    let end = end.context("Job ID sequence missing; initialize the state store schema")?;
+    let end = u64::try_from(end).context("Job ID sequence is negative")?;
+    Ok(JobIdBlock {
+        next: end - count,
+        end,
+    })
+}
+
+/// Reserve a single job ID.
+pub fn next_job_id(conn: &DbConnection) -> Result<JobId> {
+    reserve_job_ids(conn, 1)?
+        .next()
+        .context("Job ID reservation returned no IDs")
+}
+
+/// Record that queue job `job_id` was enqueued for API job `api_job_id`.
+///
+/// Linking the same pair twice is a no-op; a queue job belongs to at most
+/// one API job.
+pub fn link_api_job(conn: &DbConnection, api_job_id: ApiJobId, job_id: JobId) -> Result<()> {
+    if let Some(existing) = api_job_for_job(conn, job_id)? {
+        if existing == api_job_id {
+            return Ok(());
+        }
+        anyhow::bail!("Job {} is already linked to API job {}", job_id, existing);
+    }
+    conn.execute(
+        "INSERT INTO cf_api_job_map (job_id, api_job_id, created_at) VALUES (?, ?, ?)",
+        &[
+            DbValue::from(job_id.to_i64()?),
+            DbValue::from(api_job_id.to_i64()?),
+            DbValue::from(chrono::Utc::now().timestamp_millis()),
+        ],
+    )
+    .context("Failed to link job to API job")?;
+    Ok(())
+}
+
+/// Queue jobs enqueued for `api_job_id`, in ID order.
+pub fn jobs_for_api_job(conn: &DbConnection, api_job_id: ApiJobId) -> Result<Vec<JobId>> {
+    let rows = conn.query_all(
+        "SELECT job_id FROM cf_api_job_map WHERE api_job_id = ? ORDER BY job_id",
+        &[DbValue::from(api_job_id.to_i64()?)],
+    )?;
+    rows.iter()
+        .map(|row| {
+            let raw: i64 = row.get_by_name("job_id")?;
+            Ok(JobId::try_from(raw)?)
+        })
+        .collect()
+}
+
+/// API job a queue job was enqueued for, if any.
+pub fn api_job_for_job(conn: &DbConnection, job_id: JobId) -> Result<Option<ApiJobId>> {
+    let raw: Option<i64> = conn
+        .query_optional(
+            "SELECT api_job_id FROM cf_api_job_map WHERE job_id = ?",
+            &[DbValue::from(job_id.to_i64()?)],
+        )?
+        .map(|row| row.get_by_name("api_job_id"))
+        .transpose()?;
+    raw.map(|raw| ApiJobId::try_from(raw).context("api_job_id must be non-negative"))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::JobQueue;
+    use crate::ApiStorage;
+    use casparian_protocol::{HttpJobType, ProcessingStatus};
+
+    fn setup() -> DbConnection {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        JobQueue::new(conn.clone()).init_queue_schema().unwrap();
+        ApiStorage::new(conn.clone()).init_schema().unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_reservations_are_monotonic_and_disjoint() {
+        let conn = setup();
+        let first: Vec<u64> = reserve_job_ids(&conn, 3)
+            .unwrap()
+            .map(JobId::as_u64)
+            .collect();
+        assert_eq!(first, [1, 2, 3]);
+        let single = next_job_id(&conn).unwrap();
+        assert_eq!(single, JobId::new(4));
+        let mut block = reserve_job_ids(&conn, 10).unwrap();
+        assert_eq!(block.remaining(), 10);
+        assert_eq!(block.next(), Some(JobId::new(5)));
+        assert_eq!(block.remaining(), 9);
+        assert!(reserve_job_ids(&conn, 0).is_err());
+    }
+
+    #[test]
+    fn test_reservation_skips_ids_inserted_without_allocator() {
+        let conn = setup();
+        conn.execute(
+            r#"
+            INSERT INTO cf_processing_queue (id, file_id, plugin_name, status, scheduled_at)
+            VALUES (41, 1, 'p', ?, 0)
+            "#,
+            &[DbValue::from(ProcessingStatus::Queued.as_str())],
+        )
+        .unwrap();
+        assert_eq!(next_job_id(&conn).unwrap(), JobId::new(42));
+    }
+
+    #[test]
+    fn test_api_and_queue_jobs_share_the_sequence() {
+        let conn = setup();
+        let storage = ApiStorage::new(conn.clone());
+        let api_job = storage
+            .create_job(HttpJobType::Run, "parser", None, "/in", None, None, None)
+            .unwrap();
+        let queue_job = next_job_id(&conn).unwrap();
+        assert_ne!(api_job.as_u64(), queue_job.as_u64());
+
+        // Re-initializing (a restart) never rewinds the sequence
+        init_schema(&conn).unwrap();
+        let after_restart = next_job_id(&conn).unwrap();
+        assert!(after_restart > queue_job);
+
+        link_api_job(&conn, api_job, queue_job).unwrap();
+        link_api_job(&conn, api_job, after_restart).unwrap();
+        link_api_job(&conn, api_job, queue_job).unwrap();
+        assert_eq!(
+            jobs_for_api_job(&conn, api_job).unwrap(),
+            [queue_job, after_restart]
+        );
+        assert_eq!(api_job_for_job(&conn, queue_job).unwrap(), Some(api_job));
+        assert!(link_api_job(&conn, ApiJobId::new(999), queue_job).is_err());
+        assert_eq!(api_job_for_job(&conn, JobId::new(12345)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_reserved_queue_jobs_are_linked_to_their_api_job() {
+        let conn = setup();
+        let storage = ApiStorage::new(conn.clone());
+        let api_job = storage
+            .create_job(
+                HttpJobType::Backtest,
+                "parser",
+                None,
+                "/in",
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let job_ids = storage.reserve_queue_jobs(api_job, 3).unwrap();
+        assert_eq!(job_ids.len(), 3);
+        assert!(job_ids
+            .iter()
+            .all(|job_id| job_id.as_u64() != api_job.as_u64()));
+        assert_eq!(storage.queue_jobs_for(api_job).unwrap(), job_ids);
+        assert_eq!(storage.api_job_for(job_ids[2]).unwrap(), Some(api_job));
+        assert!(storage.reserve_queue_jobs(ApiJobId::new(999), 1).is_err());
+    }
+}
@@ -9,26 +9,54 @@
 #![allow(dead_code)]
 
 pub mod api_storage;
+pub mod approval_policy;
+pub mod audit;
+pub mod compaction;
+pub mod dataset_catalog;
 pub mod expected_outputs;
+pub mod idempotency;
+pub mod job_ids;
 pub mod legacy_models;
+pub mod lineage;
 pub mod models;
 pub mod queue;
+pub mod retention;
+pub mod schema_drift;
 pub mod schema_version;
 pub mod sessions;
+pub mod source_hashes;
 pub mod state_store;
+pub mod write_buffer;
 
 pub use api_storage::ApiStorage;
+pub use approval_policy::{ApprovalRule, RuleCondition};
+pub use audit::AuditLog;
 pub use casparian_intent::{
     IntentState, QuestionKind, QuestionOption, Session, SessionId, SessionQuestion,
 };
+pub use casparian_tape::{AuditEntry, AuditQuery};
+pub use compaction::{CompactionPolicy, CompactionRecord};
+pub use dataset_catalog::{DatasetCommit, DatasetRecord};
 pub use expected_outputs::{ExpectedOutputs, OutputSpec};
-pub use queue::{Job, JobQueue, QueueStats};
+pub use idempotency::IdempotentResponse;
+pub use job_ids::{next_job_id, reserve_job_ids, JobIdBlock};
+pub use lineage::{LineageEdge, LineageGraph, LineageNode, LineageRecord, LineageStorage};
+pub use queue::{
+    output_topic, Job, JobHistoryPage, JobHistoryQuery, JobHistorySort, JobLogChunk, JobQueue,
+    PluginEvent, PluginRollback, QueueStats, Schedule, ScheduleHold, ScheduleState,
+    TopicSubscription, SCHEDULE_HOLD_REASON,
+};
+pub use retention::RetentionPolicy;
+pub use schema_drift::DatasetDrift;
 pub use schema_version::{ensure_schema_version, SCHEMA_VERSION};
 pub use sessions::SessionStorage;
 pub use state_store::{
-    ApiStore, ArtifactStore, DispatchData, JobArtifactRecord, PluginDeployRequest, QueueStore,
-    RoutingStore, ScoutFileRecord, ScoutFileTagFilter, ScoutFilesPage, ScoutFolderEntry,
-    ScoutPatternMatch, ScoutPatternQueryResult, ScoutStore, ScoutTagCount, ScoutTagStats,
-    SessionStore, StateStore, StateStoreBackend, StateStoreQueueSession, StateStoreScoutSession,
-    StateStoreUrl,
+    ApiStore, ArtifactStore, ColumnStatsRecord, DispatchData, JobArtifactRecord, LineageStore,
+    PluginDeployRequest, QueueStore, RoutingStore, ScoutFileRecord, ScoutFileTagFilter,
+    ScoutFilesPage, ScoutFolderEntry, ScoutPatternMatch, ScoutPatternQueryResult, ScoutStore,
+    ScoutTagCount, ScoutTagStats, SessionStore, StateStore, StateStoreBackend,
+    StateStoreQueueSession, StateStoreScoutSession, StateStoreUrl,
+};
+pub use write_buffer::{
+    BufferedWrite, FlushBatch, MetricSample, WorkerHeartbeat, WriteBuffer, WriteBufferConfig,
 };
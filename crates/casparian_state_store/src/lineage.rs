@@ -0,0 +1,648 @@
+//! Output lineage: which source file and job produced each artifact.
+//!
+//! The sentinel records one hop (source file → job → artifact) per artifact on
+//! every CONCLUDE. Queries walk those hops upstream from an artifact URI or
+//! downstream from a source content hash.
+//!
+//! A job whose input is an earlier job's artifact (pipeline chaining) also
+//! stores the worker's [`LineageChain`]: every file between its input and the
+//! raw source, with the job and parser version that read it. Upstream queries
+//! follow that chain through every pipeline stage.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
+use casparian_protocol::{
+    ArtifactV1, JobId, LineageChain, LineageFileType, LineageHop, TextEncoding,
+};
+use serde::{Deserialize, Serialize};
+
+/// One source → job → artifact hop.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineageRecord {
+    pub job_id: i64,
+    pub source_file_id: Option<i64>,
+    pub source_path: Option<String>,
+    /// Blake3 hash of the source content reported by the worker.
+    pub source_hash: Option<String>,
+    /// Blake3 hash of the decompressed content, for compressed sources.
+    pub decompressed_hash: Option<String>,
+    /// Encoding of a non-UTF-8 text source the worker transcoded.
+    #[serde(default)]
+    pub source_encoding: Option<String>,
+    pub plugin_name: String,
+    pub parser_version: Option<String>,
+    pub artifact_kind: String,
+    pub artifact_name: String,
+    pub artifact_uri: String,
+    pub table_name: Option<String>,
+    pub rows: Option<i64>,
+    pub created_at: i64,
+    /// Files the job's input was derived from, input first, when the worker
+    /// reported them.
+    #[serde(default)]
+    pub lineage: Option<LineageChain>,
+}
+
+impl LineageRecord {
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        let lineage: Option<String> = row.get_by_name("lineage_chain")?;
+        let lineage = lineage
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .context("Invalid lineage_chain JSON")?;
+        Ok(Self {
+            job_id: row.get_by_name("job_id")?,
+            source_file_id: row.get_by_name("source_file_id")?,
+            source_path: row.get_by_name("source_path")?,
+            source_hash: row.get_by_name("source_hash")?,
+            decompressed_hash: row.get_by_name("decompressed_hash")?,
+            source_encoding: row.get_by_name("source_encoding")?,
+            plugin_name: row.get_by_name("plugin_name")?,
+            parser_version: row.get_by_name("parser_version")?,
+            artifact_kind: row.get_by_name("artifact_kind")?,
+            artifact_name: row.get_by_name("artifact_name")?,
+            artifact_uri: row.get_by_name("artifact_uri")?,
+            table_name: row.get_by_name("table_name")?,
+            rows: row.get_by_name("rows")?,
+            created_at: row.get_by_name("created_at")?,
+            lineage,
+        })
+    }
+
+    /// Chain of this hop's job, from its stored lineage or, for a job that
+    /// reported none, a single hop for its source file.
+    fn chain(&self) -> Option<LineageChain> {
+        if let Some(lineage) = &self.lineage {
+            return Some(lineage.clone());
+        }
+        let path = self.source_path.as_ref()?;
+        Some(LineageChain {
+            hops: vec![LineageHop {
+                file_path: path.into(),
+                file_type: LineageFileType::Original,
+                offset: 0,
+                row_number: 0,
+                job_id: u64::try_from(self.job_id).ok().map(JobId::new),
+                plugin_name: Some(self.plugin_name.clone()),
+                parser_version: self.parser_version.clone(),
+                content_hash: self.source_hash.clone(),
+            }],
+        })
+    }
+}
+
+/// Node in a lineage graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LineageNode {
+    Source {
+        id: String,
+        path: Option<String>,
+        hash: Option<String>,
+    },
+    Job {
+        id: String,
+        job_id: i64,
+        plugin_name: String,
+        parser_version: Option<String>,
+    },
+    Artifact {
+        id: String,
+        uri: String,
+        name: String,
+        artifact_kind: String,
+        rows: Option<i64>,
+    },
+}
+
+impl LineageNode {
+    pub fn id(&self) -> &str {
+        match self {
+            LineageNode::Source { id, .. }
+            | LineageNode::Job { id, .. }
+            | LineageNode::Artifact { id, .. } => id,
+        }
+    }
+}
+
+/// Directed edge between two node IDs (upstream → downstream).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LineageEdge {
+    pub from: String,
+    pub to: String,
+}
+
+/// Deduplicated graph built from lineage hops, for rendering.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineageGraph {
+    pub nodes: Vec<LineageNode>,
+    pub edges: Vec<LineageEdge>,
+}
+
+impl LineageGraph {
+    pub fn from_records(records: &[LineageRecord]) -> Self {
+        let mut nodes: BTreeMap<String, LineageNode> = BTreeMap::new();
+        let mut edges = Vec::new();
+
+        for record in records {
+            let job_id = format!("job:{}", record.job_id);
+            nodes.entry(job_id.clone()).or_insert_with(|| LineageNode::Job {
+                id: job_id.clone(),
+                job_id: record.job_id,
+                plugin_name: record.plugin_name.clone(),
+                parser_version: record.parser_version.clone(),
+            });
+
+            let source_key = record
+                .source_hash
+                .as_deref()
+                .map(|hash| format!("source:{}", hash))
+                .or_else(|| record.source_path.as_deref().map(|p| format!("source:{}", p)));
+            if let Some(source_id) = source_key {
+                nodes
+                    .entry(source_id.clone())
+                    .or_insert_with(|| LineageNode::Source {
+                        id: source_id.clone(),
+                        path: record.source_path.clone(),
+                        hash: record.source_hash.clone(),
+                    });
+                edges.push(LineageEdge {
+                    from: source_id,
+                    to: job_id.clone(),
+                });
+            }
+
+            let artifact_id = format!("artifact:{}", record.artifact_uri);
+            nodes
+                .entry(artifact_id.clone())
+                .or_insert_with(|| LineageNode::Artifact {
+                    id: artifact_id.clone(),
+                    uri: record.artifact_uri.clone(),
+                    name: record.artifact_name.clone(),
+                    artifact_kind: record.artifact_kind.clone(),
+                    rows: record.rows,
+                });
+            edges.push(LineageEdge {
+                from: job_id,
+                to: artifact_id,
+            });
+        }
+
+        edges.sort();
+        edges.dedup();
+        Self {
+            nodes: nodes.into_values().collect(),
+            edges,
+        }
+    }
+}
+
+/// Lineage hop storage.
+pub struct LineageStorage {
+    conn: DbConnection,
+}
+
+impl LineageStorage {
+    pub fn new(conn: DbConnection) -> Self {
+        Self { conn }
+    }
+
+    pub fn init_schema(&self) -> Result<()> {
+        self.conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS cf_lineage_hops (
+                job_id BIGINT NOT NULL,
+                source_file_id BIGINT,
+                source_path TEXT,
+                source_hash TEXT,
+                decompressed_hash TEXT,
+                source_encoding TEXT,
+                plugin_name TEXT NOT NULL,
+                parser_version TEXT,
+                artifact_kind TEXT NOT NULL,
+                artifact_name TEXT NOT NULL,
+                artifact_uri TEXT NOT NULL,
+                table_name TEXT,
+                rows BIGINT,
+                created_at BIGINT NOT NULL,
+                lineage_chain TEXT,
+                UNIQUE(job_id, artifact_kind, artifact_name, artifact_uri)
+            );
+            CREATE INDEX IF NOT EXISTS ix_lineage_hops_artifact ON cf_lineage_hops(artifact_uri);
+            CREATE INDEX IF NOT EXISTS ix_lineage_hops_source ON cf_lineage_hops(source_hash);
+            "#,
+        )?;
+        Ok(())
+    }
+
+    /// Record a hop for each artifact of a concluded job.
+    ///
+    /// Source file, plugin, and parser version come from the job's queue row;
+    /// nothing is recorded if the job is unknown. `decompressed_hash` is set
+    /// when the worker decompressed the source for the parser,
+    /// `source_encoding` when it transcoded the source to UTF-8, and
+    /// `lineage` when it reported the chain back to the raw source.
+    pub fn record_job_lineage(
+        &self,
+        job_id: i64,
+        source_hash: Option<&str>,
+        decompressed_hash: Option<&str>,
+        source_encoding: Option<TextEncoding>,
+        lineage: Option<&LineageChain>,
+        artifacts: &[ArtifactV1],
+    ) -> Result<()> {
+        let Some(job) = self.conn.query_optional(
+            r#"
+            SELECT file_id, input_file, plugin_name, parser_version
+            FROM cf_processing_queue
+            WHERE id = ?
+            "#,
+            &[DbValue::from(job_id)],
+        )?
+        else {
+            return Ok(());
+        };
+        let source_file_id: Option<i64> = job.get_by_name("file_id")?;
+        let source_path: Option<String> = job.get_by_name("input_file")?;
+        let plugin_name: String = job.get_by_name("plugin_name")?;
+        let parser_version: Option<String> = job.get_by_name("parser_version")?;
+        let lineage = lineage
+            .map(serde_json::to_string)
+            .transpose()
+            .context("Failed to serialize lineage chain")?;
+        let now = chrono::Utc::now().timestamp_millis();
+
+        for artifact in artifacts {
+            let Some(columns) = ArtifactColumns::from_artifact(artifact)? else {
+                continue;
+            };
+            self.conn.execute(
+                r#"
+                INSERT OR IGNORE INTO cf_lineage_hops
+                    (job_id, source_file_id, source_path, source_hash, decompressed_hash,
+                     source_encoding, plugin_name, parser_version, artifact_kind,
+                     artifact_name, artifact_uri, table_name, rows, created_at, lineage_chain)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                &[
+                    DbValue::from(job_id),
+                    DbValue::from(source_file_id),
+                    DbValue::from(source_path.as_deref()),
+                    DbValue::from(source_hash),
+                    DbValue::from(decompressed_hash),
+                    DbValue::from(source_encoding.map(|encoding| encoding.as_str())),
+                    DbValue::from(plugin_name.as_str()),
+                    DbValue::from(parser_version.as_deref()),
+                    DbValue::from(columns.kind),
+                    DbValue::from(columns.name),
+                    DbValue::from(columns.uri),
+                    DbValue::from(columns.table_name),
+                    DbValue::from(columns.rows),
+                    DbValue::from(now),
+                    DbValue::from(lineage.as_deref()),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Where did this output come from? The hops that wrote it, most recent
+    /// first, followed by the hops of every earlier pipeline stage on their
+    /// lineage chains, nearest stage first, back to the raw source.
+    pub fn lineage_for_artifact(&self, uri: &str) -> Result<Vec<LineageRecord>> {
+        let mut records = self.query_hops("artifact_uri = ?", &[DbValue::from(uri)])?;
+        let mut seen: HashSet<(i64, String)> = records
+            .iter()
+            .map(|record| (record.job_id, record.artifact_uri.clone()))
+            .collect();
+        let mut stage = 0;
+        while stage < records.len() {
+            let Some(lineage) = records[stage].lineage.clone() else {
+                stage += 1;
+                continue;
+            };
+            // hops[i + 1] is the input of the job that wrote hops[i]
+            for pair in lineage.hops.windows(2) {
+                let Some(job_id) = pair[1].job_id.and_then(|id| id.to_i64().ok()) else {
+                    break;
+                };
+                let uri = artifact_uri_for_input(&pair[0].file_path.to_string_lossy());
+                if !seen.insert((job_id, uri.clone())) {
+                    continue;
+                }
+                records.extend(self.query_hops(
+                    "job_id = ? AND artifact_uri = ?",
+                    &[DbValue::from(job_id), DbValue::from(uri.as_str())],
+                )?);
+            }
+            stage += 1;
+        }
+        Ok(records)
+    }
+
+    /// Lineage of a downstream job's input: the chain of the most recent job
+    /// that wrote the artifact at `input_path`. None if no job recorded it.
+    pub fn lineage_for_input(&self, input_path: &str) -> Result<Option<LineageChain>> {
+        let uri = artifact_uri_for_input(input_path);
+        let records = self.query_hops("artifact_uri = ?", &[DbValue::from(uri.as_str())])?;
+        Ok(records.first().and_then(LineageRecord::chain))
+    }
+
+    /// Hops for every artifact written to table `table`. Artifacts without a
+    /// recorded table match on their output name instead.
+    pub fn lineage_for_table(&self, table: &str) -> Result<Vec<LineageRecord>> {
+        self.query_hops(
+            "table_name = ? OR (table_name IS NULL AND artifact_name = ?)",
+            &[DbValue::from(table), DbValue::from(table)],
+        )
+    }
+
+    /// Everything produced from a source file with this content hash. A
+    /// compressed source also matches on the hash of its decompressed content.
+    pub fn downstream_of(&self, source_hash: &str) -> Result<Vec<LineageRecord>> {
+        self.query_hops(
+            "source_hash = ? OR decompressed_hash = ?",
+            &[DbValue::from(source_hash), DbValue::from(source_hash)],
+        )
+    }
+
+    fn query_hops(&self, filter: &'static str, params: &[DbValue]) -> Result<Vec<LineageRecord>> {
+        let sql = format!(
+            r#"
+            SELECT job_id, source_file_id, source_path, source_hash, decompressed_hash,
+                   source_encoding, plugin_name, parser_version, artifact_kind,
+                   artifact_name, artifact_uri, table_name, rows, created_at, lineage_chain
+            FROM cf_lineage_hops
+            WHERE {}
+            ORDER BY created_at DESC, job_id DESC
+            "#,
+            filter
+        );
+        let rows = self.conn.query_all(&sql, params)?;
+        rows.iter().map(LineageRecord::from_row).collect()
+    }
+}
+
+/// Sink URI of the artifact a downstream job reads from `input_path`.
+/// Subscribers are only enqueued for `file://` outputs.
+fn artifact_uri_for_input(input_path: &str) -> String {
+    format!("file://{}", input_path)
+}
+
+/// Artifact fields shared by `cf_job_artifacts` and `cf_lineage_hops`.
+pub(crate) struct ArtifactColumns<'a> {
+    pub kind: &'static str,
+    pub name: &'a str,
+    pub uri: &'a str,
+    pub table_name: Option<&'a str>,
+    pub rows: Option<i64>,
+    pub content_hash: Option<&'a str>,
+}
+
+impl<'a> ArtifactColumns<'a> {
+    /// Returns None for artifacts without a URI.
+    pub fn from_artifact(artifact: &'a ArtifactV1) -> Result<Option<Self>> {
+        let (kind, name, uri, table_name, rows) = match artifact {
+            ArtifactV1::Output {
+                output_name,
+                sink_uri,
+                table,
+                rows,
+                ..
+            } => ("output", output_name, sink_uri, table.as_deref(), *rows),
+            ArtifactV1::Quarantine {
+                output_name,
+                sink_uri,
+                table,
+                rows,
+            } => ("quarantine", output_name, sink_uri, table.as_deref(), *rows),
+            ArtifactV1::Log { name, uri } => ("log", name, uri, None, None),
+            ArtifactV1::Other { name, uri } => {
+                let Some(uri) = uri.as_ref() else {
+                    return Ok(None);
+                };
+                ("other", name, uri, None, None)
+            }
+        };
+        let content_hash = match artifact {
+            ArtifactV1::Output { content_hash, .. } => content_hash.as_deref(),
+            _ => None,
+        };
+        let rows = rows
+            .map(i64::try_from)
+            .transpose()
+            .context("artifact row count overflow")?;
+        Ok(Some(Self {
+            kind,
+            name: name.as_str(),
+            uri: uri.as_str(),
+            table_name,
+            rows,
+            content_hash,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::JobQueue;
+    use casparian_protocol::ProcessingStatus;
+
+    fn setup() -> (DbConnection, LineageStorage) {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        JobQueue::new(conn.clone()).init_queue_schema().unwrap();
+        let lineage = LineageStorage::new(conn.clone());
+        lineage.init_schema().unwrap();
+        (conn, lineage)
+    }
+
+    fn enqueue(conn: &DbConnection, file_id: i64, input_file: &str) -> i64 {
+        conn.query_scalar::<i64>(
+            r#"
+            INSERT INTO cf_processing_queue (file_id, input_file, plugin_name, status, scheduled_at)
+            VALUES (?, ?, 'csv_parser', ?, 0)
+            RETURNING id
+            "#,
+            &[
+                DbValue::from(file_id),
+                DbValue::from(input_file),
+                DbValue::from(ProcessingStatus::Running.as_str()),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn output(uri: &str) -> ArtifactV1 {
+        ArtifactV1::Output {
+            output_name: "trades".to_string(),
+            sink_uri: uri.to_string(),
+            table: None,
+            rows: Some(10),
+            schema_hash: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_lineage_upstream_and_downstream() {
+        let (conn, lineage) = setup();
+        let job_a = enqueue(&conn, 1, "/data/a.csv");
+        let job_b = enqueue(&conn, 2, "/data/b.csv");
+        lineage
+            .record_job_lineage(
+                job_a,
+                Some("hash_a"),
+                None,
+                Some(TextEncoding::Windows1252),
+                None,
+                &[output("parquet:///out/a.parquet")],
+            )
+            .unwrap();
+        lineage
+            .record_job_lineage(
+                job_b,
+                Some("hash_b"),
+                Some("hash_b_decompressed"),
+                None,
+                None,
+                &[
+                    output("parquet:///out/b.parquet"),
+                    ArtifactV1::Other {
+                        name: "no_uri".to_string(),
+                        uri: None,
+                    },
+                ],
+            )
+            .unwrap();
+
+        let upstream = lineage
+            .lineage_for_artifact("parquet:///out/b.parquet")
+            .unwrap();
+        assert_eq!(upstream.len(), 1);
+        assert_eq!(upstream[0].job_id, job_b);
+        assert_eq!(upstream[0].source_path.as_deref(), Some("/data/b.csv"));
+        assert_eq!(upstream[0].plugin_name, "csv_parser");
+
+        let downstream = lineage.downstream_of("hash_a").unwrap();
+        assert_eq!(downstream.len(), 1);
+        assert_eq!(downstream[0].artifact_uri, "parquet:///out/a.parquet");
+        assert_eq!(
+            downstream[0].source_encoding.as_deref(),
+            Some("windows-1252")
+        );
+        let downstream = lineage.downstream_of("hash_b_decompressed").unwrap();
+        assert_eq!(downstream.len(), 1);
+        assert_eq!(downstream[0].source_hash.as_deref(), Some("hash_b"));
+
+        let by_table = lineage.lineage_for_table("trades").unwrap();
+        assert_eq!(by_table.len(), 2);
+        assert!(lineage.lineage_for_table("no_uri").unwrap().is_empty());
+
+        let graph = LineageGraph::from_records(&upstream);
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(
+            graph.edges,
+            vec![
+                LineageEdge {
+                    from: format!("job:{}", job_b),
+                    to: "artifact:parquet:///out/b.parquet".to_string(),
+                },
+                LineageEdge {
+                    from: "source:hash_b".to_string(),
+                    to: format!("job:{}", job_b),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lineage_follows_pipeline_chain() {
+        let (conn, lineage) = setup();
+        let hop = |path: &str, file_type, job_id: i64, hash: &str| LineageHop {
+            file_path: path.into(),
+            file_type,
+            offset: 0,
+            row_number: 0,
+            job_id: Some(JobId::new(job_id as u64)),
+            plugin_name: Some("csv_parser".to_string()),
+            parser_version: Some("1.0.0".to_string()),
+            content_hash: Some(hash.to_string()),
+        };
+
+        let job_a = enqueue(&conn, 1, "/data/raw.csv");
+        let chain_a = LineageChain::with_upstream(
+            hop(
+                "/data/raw.csv",
+                LineageFileType::Original,
+                job_a,
+                "hash_raw",
+            ),
+            None,
+        );
+        lineage
+            .record_job_lineage(
+                job_a,
+                Some("hash_raw"),
+                None,
+                None,
+                Some(&chain_a),
+                &[output("file:///out/stage1.parquet")],
+            )
+            .unwrap();
+
+        // The downstream job's input lineage is the chain of its producer
+        let input = lineage
+            .lineage_for_input("/out/stage1.parquet")
+            .unwrap()
+            .unwrap();
+        assert_eq!(input, chain_a);
+        assert!(lineage
+            .lineage_for_input("/out/other.parquet")
+            .unwrap()
+            .is_none());
+
+        let job_b = enqueue(&conn, 1, "/out/stage1.parquet");
+        let chain_b = LineageChain::with_upstream(
+            hop(
+                "/out/stage1.parquet",
+                LineageFileType::Artifact,
+                job_b,
+                "hash_stage1",
+            ),
+            Some(&input),
+        );
+        lineage
+            .record_job_lineage(
+                job_b,
+                Some("hash_stage1"),
+                None,
+                None,
+                Some(&chain_b),
+                &[output("file:///out/stage2.parquet")],
+            )
+            .unwrap();
+
+        let upstream = lineage
+            .lineage_for_artifact("file:///out/stage2.parquet")
+            .unwrap();
+        let jobs: Vec<i64> = upstream.iter().map(|record| record.job_id).collect();
+        assert_eq!(jobs, vec![job_b, job_a]);
+        assert_eq!(upstream[1].source_path.as_deref(), Some("/data/raw.csv"));
+        let chain = upstream[0].lineage.as_ref().unwrap();
+        assert_eq!(
+            chain
+                .hops
+                .iter()
+                .map(|hop| hop.file_type)
+                .collect::<Vec<_>>(),
+            vec![LineageFileType::Artifact, LineageFileType::Original]
+        );
+        assert_eq!(
+            chain.source().map(|hop| hop.file_path.as_path()),
+            Some(std::path::Path::new("/data/raw.csv"))
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! These models are backend-agnostic and map from casparian_db rows.
 
 use casparian_db::{BackendError, UnifiedDbRow};
+use casparian_protocol::naming::expand_namespace;
 use casparian_protocol::{
     JobStatus as ProtocolJobStatus, PluginStatus, ProcessingStatus, QuarantineConfig, RuntimeKind,
     SinkMode,
@@ -13,6 +14,7 @@ pub use casparian_protocol::{PluginStatus as PluginStatusEnum, ProcessingStatus
 pub const TOPIC_CONFIG_COLUMNS: &[&str] = &[
     "id",
     "plugin_name",
+    "namespace",
     "topic_name",
     "uri",
     "mode",
@@ -54,6 +56,14 @@ pub const DEAD_LETTER_COLUMNS: &[&str] = &[
     "retry_count",
     "moved_at",
     "reason",
+    "pipeline_run_id",
+    "parser_version",
+    "parser_fingerprint",
+    "config_overrides",
+    "sink_config_json",
+    "last_worker",
+    "last_claim_time",
+    "result_summary",
 ];
 
 pub const PARSER_HEALTH_COLUMNS: &[&str] = &[
@@ -87,6 +97,8 @@ pub const QUARANTINE_LIST_COLUMNS: &[&str] =
 pub struct TopicConfig {
     pub id: i64,
     pub plugin_name: String,
+    /// Tenant namespace of the sink; substituted for `{namespace}` in `uri`.
+    pub namespace: String,
     pub topic_name: String,
     pub uri: String,
     /// Sink mode - stored as SinkMode enum, parsed at the boundary.
@@ -133,6 +145,7 @@ impl TopicConfig {
         Ok(Self {
             id: row.get_by_name("id")?,
             plugin_name: row.get_by_name("plugin_name")?,
+            namespace: row.get_by_name("namespace")?,
             topic_name: row.get_by_name("topic_name")?,
             uri: row.get_by_name("uri")?,
             mode,
@@ -143,6 +156,11 @@ impl TopicConfig {
             },
         })
     }
+
+    /// Sink URI with `{namespace}` expanded.
+    pub fn resolved_uri(&self) -> String {
+        expand_namespace(&self.uri, &self.namespace)
+    }
 }
 
 // ============================================================================
@@ -308,6 +326,15 @@ pub struct DeadLetterJob {
     pub retry_count: i32,
     pub moved_at: i64,
     pub reason: Option<String>,
+    pub pipeline_run_id: Option<String>,
+    pub parser_version: Option<String>,
+    pub parser_fingerprint: Option<String>,
+    pub config_overrides: Option<String>,
+    pub sink_config_json: Option<String>,
+    /// Lease owner (worker ID) of the final attempt.
+    pub last_worker: Option<String>,
+    pub last_claim_time: Option<i64>,
+    pub result_summary: Option<String>,
 }
 
 impl DeadLetterJob {
@@ -322,6 +349,14 @@ impl DeadLetterJob {
             retry_count: row.get_by_name("retry_count")?,
             moved_at: row.get_by_name("moved_at")?,
             reason: row.get_by_name("reason")?,
+            pipeline_run_id: row.get_by_name("pipeline_run_id")?,
+            parser_version: row.get_by_name("parser_version")?,
+            parser_fingerprint: row.get_by_name("parser_fingerprint")?,
+            config_overrides: row.get_by_name("config_overrides")?,
+            sink_config_json: row.get_by_name("sink_config_json")?,
+            last_worker: row.get_by_name("last_worker")?,
+            last_claim_time: row.get_by_name("last_claim_time")?,
+            result_summary: row.get_by_name("result_summary")?,
         })
     }
 }
@@ -5,14 +5,30 @@
 use anyhow::{Context, Result};
 use casparian_db::{BackendError, DbConnection, DbValue, UnifiedDbRow};
 use chrono::Utc;
-use casparian_protocol::types::{ObservedDataType, SchemaMismatch};
+use casparian_protocol::defaults::DEFAULT_NAMESPACE;
+use casparian_protocol::retry::{RetryAttempt, RetryPolicy};
+use casparian_protocol::types::{
+    CsvDialect, DetectionConfidence, ErrorCategory, JobDiagnostics, ObservedDataType,
+    ReceiptVerification, ResourceLimits, SchemaDrift, SchemaMismatch, TextEncoding,
+};
 use casparian_protocol::{
-    JobId, JobStatus, PipelineRunStatus, PluginStatus, ProcessingStatus, RuntimeKind, SinkMode,
+    ArtifactKind, ArtifactV1, JobId, JobPriority, JobResultSummary, JobStatus, PipelineRun,
+    PipelineRunJob, PipelineRunStatus, PluginStatus, ProcessingStatus, ResultArtifact, RuntimeKind,
+    ScheduleCatchUp, ScheduleTarget, SinkMode, SourceHash,
 };
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+use uuid::Uuid;
 
 use crate::DispatchData;
+use super::compaction;
+use super::dataset_catalog::{self, DatasetCommit, DatasetRecord};
+use super::job_ids;
+use super::retention;
+use super::schema_drift::{self, DatasetDrift};
+use super::source_hashes;
+use super::write_buffer::{self, FlushBatch};
 use super::models::{
     DeadLetterJob, DeadLetterReason, ParserHealth, ProcessingJob, QuarantinedRow,
     QuarantinedRowSummary, DEAD_LETTER_COLUMNS, PARSER_HEALTH_COLUMNS, PROCESSING_JOB_COLUMNS,
@@ -41,6 +57,212 @@ pub struct PluginDetails {
     pub env_hash: Option<String>,
 }
 
+/// `cf_plugin_events.event_type` for a rollback.
+pub const PLUGIN_EVENT_ROLLBACK: &str = "rollback";
+
+/// Outcome of rolling a plugin back to an earlier version.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginRollback {
+    pub plugin_name: String,
+    /// Version that was active before the rollback (None if none was).
+    pub from_version: Option<String>,
+    pub to_version: String,
+}
+
+/// Audit record of a change to a plugin's active version.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginEvent {
+    pub id: i64,
+    pub plugin_name: String,
+    pub event_type: String,
+    pub from_version: Option<String>,
+    pub to_version: String,
+    pub actor: Option<String>,
+    pub created_at: i64,
+}
+
+/// A plugin subscribed to an output topic of another plugin.
+///
+/// Each successful job publishes its outputs under [`output_topic`]; every
+/// active subscriber of a topic gets a downstream job reading the artifact.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicSubscription {
+    pub id: i64,
+    /// Subscribing (downstream) plugin
+    pub plugin_name: String,
+    /// `producer_plugin:output_name`
+    pub topic_name: String,
+    pub is_active: bool,
+    pub created_at: i64,
+}
+
+/// Topic a plugin publishes one of its outputs under.
+pub fn output_topic(plugin_name: &str, output_name: &str) -> String {
+    format!("{}:{}", plugin_name, output_name)
+}
+
+/// Producing plugin of an output topic (`None` if malformed).
+fn topic_producer(topic_name: &str) -> Option<&str> {
+    let (producer, output) = topic_name.split_once(':')?;
+    if producer.trim().is_empty() || output.trim().is_empty() {
+        return None;
+    }
+    Some(producer)
+}
+
+/// Plugins on a path of subscription edges (`producer -> subscriber`) from
+/// `from` to `to`, both included.
+fn subscription_path(edges: &[(String, String)], from: &str, to: &str) -> Option<Vec<String>> {
+    let mut parents: HashMap<&str, &str> = HashMap::new();
+    let mut frontier = vec![from];
+    while let Some(node) = frontier.pop() {
+        if node == to {
+            let mut path = vec![to.to_string()];
+            let mut current = to;
+            while current != from {
+                current = parents[current];
+                path.push(current.to_string());
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for (producer, subscriber) in edges {
+            if producer == node && subscriber != from && !parents.contains_key(subscriber.as_str())
+            {
+                parents.insert(subscriber.as_str(), node);
+                frontier.push(subscriber.as_str());
+            }
+        }
+    }
+    None
+}
+
+/// Local path of a file artifact a downstream job can read.
+///
+/// Table sinks and partitioned outputs (several files) have none.
+fn artifact_input_path(sink_uri: &str) -> Option<&str> {
+    let path = sink_uri.strip_prefix("file://")?;
+    if path.is_empty() || path.contains("**") {
+        return None;
+    }
+    Some(path)
+}
+
+const SUBSCRIPTION_COLUMNS: &[&str] =
+    &["id", "plugin_name", "topic_name", "is_active", "created_at"];
+
+fn subscription_from_row(row: &UnifiedDbRow) -> Result<TopicSubscription> {
+    Ok(TopicSubscription {
+        id: row.get_by_name("id")?,
+        plugin_name: row.get_by_name("plugin_name")?,
+        topic_name: row.get_by_name("topic_name")?,
+        is_active: row.get_by_name("is_active")?,
+        created_at: row.get_by_name("created_at")?,
+    })
+}
+
+/// `error_message` of a job deferred because its rule's window is closed.
+pub const SCHEDULE_HOLD_REASON: &str = "waiting_for_schedule_window";
+
+/// A cron-triggered schedule evaluated by the Sentinel.
+#[derive(Debug, Clone, Serialize)]
+pub struct Schedule {
+    pub id: i64,
+    pub name: String,
+    /// Five-field cron expression, evaluated in the Sentinel's local time
+    pub cron: String,
+    pub target: ScheduleTarget,
+    pub catch_up: ScheduleCatchUp,
+    pub is_active: bool,
+    /// Next cron occurrence (unix millis); None until the Sentinel evaluates
+    /// the schedule, or after it is re-enabled
+    pub next_fire_at: Option<i64>,
+    pub last_fired_at: Option<i64>,
+    /// Rule windows: open until this time (unix millis)
+    pub window_open_until: Option<i64>,
+    pub created_at: i64,
+}
+
+impl Schedule {
+    /// Whether a rule window is open at `now`.
+    pub fn window_open(&self, now: i64) -> bool {
+        self.window_open_until.is_some_and(|until| until > now)
+    }
+}
+
+/// Persisted evaluation state of a schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleState {
+    pub next_fire_at: Option<i64>,
+    pub last_fired_at: Option<i64>,
+    pub window_open_until: Option<i64>,
+}
+
+/// A closed rule window holding a file's jobs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleHold {
+    pub schedule_name: String,
+    /// When the window next opens, if the Sentinel has evaluated it
+    pub next_open_at: Option<i64>,
+}
+
+const SCHEDULE_COLUMNS: &[&str] = &[
+    "id",
+    "name",
+    "cron",
+    "target_kind",
+    "target_id",
+    "window_minutes",
+    "catch_up",
+    "is_active",
+    "next_fire_at",
+    "last_fired_at",
+    "window_open_until",
+    "created_at",
+];
+
+fn schedule_from_row(row: &UnifiedDbRow) -> Result<Schedule> {
+    let kind: String = row.get_by_name("target_kind")?;
+    let target_id: String = row.get_by_name("target_id")?;
+    let target = match kind.as_str() {
+        "rescan_source" => ScheduleTarget::RescanSource {
+            source_id: target_id,
+        },
+        "rule_window" => {
+            let minutes: Option<i64> = row.get_by_name("window_minutes")?;
+            ScheduleTarget::RuleWindow {
+                rule_id: target_id,
+                window_minutes: minutes
+                    .and_then(|minutes| u32::try_from(minutes).ok())
+                    .unwrap_or(0),
+            }
+        }
+        other => anyhow::bail!("Unknown schedule target kind '{}'", other),
+    };
+    let catch_up: String = row.get_by_name("catch_up")?;
+    Ok(Schedule {
+        id: row.get_by_name("id")?,
+        name: row.get_by_name("name")?,
+        cron: row.get_by_name("cron")?,
+        target,
+        catch_up: catch_up.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+        is_active: row.get_by_name("is_active")?,
+        next_fire_at: row.get_by_name("next_fire_at")?,
+        last_fired_at: row.get_by_name("last_fired_at")?,
+        window_open_until: row.get_by_name("window_open_until")?,
+        created_at: row.get_by_name("created_at")?,
+    })
+}
+
+/// A chunk of a job's log, streamed from the worker while it runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobLogChunk {
+    /// Byte offset of the chunk in the worker's log file
+    pub offset: i64,
+    pub chunk: String,
+    pub created_at: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct DispatchMetadata {
     pub file_id: i64,
@@ -124,7 +346,7 @@ impl JobQueue {
             .map(|status| format!("'{}'", status.as_str()))
             .collect::<Vec<_>>()
             .join(",");
-        let sink_mode_values = [SinkMode::Append, SinkMode::Replace, SinkMode::Error]
+        let sink_mode_values = SinkMode::ALL
             .iter()
             .map(|mode| format!("'{}'", mode.as_str()))
             .collect::<Vec<_>>()
@@ -141,6 +363,8 @@ impl JobQueue {
                 config_overrides TEXT,
                 parser_version TEXT,
                 parser_fingerprint TEXT,
+                pinned_version TEXT,
+                force_rerun BOOLEAN DEFAULT false,
                 sink_config_json TEXT,
                 status TEXT NOT NULL DEFAULT '{default_status}'
                     CHECK (status IN ({status_values})),
@@ -157,11 +381,16 @@ impl JobQueue {
                 scheduled_at INTEGER NOT NULL,
                 end_time INTEGER,
                 result_summary TEXT,
+                result_summary_json TEXT,
                 error_message TEXT,
                 retry_count INTEGER DEFAULT 0,
-                quarantine_rows BIGINT DEFAULT 0
+                quarantine_rows BIGINT DEFAULT 0,
+                range_start BIGINT,
+                range_end BIGINT,
+                upstream_job_id BIGINT
             );
             CREATE INDEX IF NOT EXISTS ix_queue_pop ON cf_processing_queue(status, priority, id);
+            CREATE INDEX IF NOT EXISTS ix_queue_history ON cf_processing_queue(scheduled_at, id);
 
             CREATE TABLE IF NOT EXISTS cf_output_materializations (
                 materialization_key TEXT PRIMARY KEY,
@@ -206,6 +435,8 @@ impl JobQueue {
                 config_overrides TEXT,
                 parser_version TEXT,
                 parser_fingerprint TEXT,
+                pinned_version TEXT,
+                force_rerun BOOLEAN DEFAULT false,
                 sink_config_json TEXT,
                 status TEXT NOT NULL DEFAULT '{default_status}'
                     CHECK (status IN ({status_values})),
@@ -222,11 +453,16 @@ impl JobQueue {
                 scheduled_at BIGINT NOT NULL,
                 end_time BIGINT,
                 result_summary TEXT,
+                result_summary_json TEXT,
                 error_message TEXT,
                 retry_count INTEGER DEFAULT 0,
-                quarantine_rows BIGINT DEFAULT 0
+                quarantine_rows BIGINT DEFAULT 0,
+                range_start BIGINT,
+                range_end BIGINT,
+                upstream_job_id BIGINT
             );
             CREATE INDEX IF NOT EXISTS ix_queue_pop ON cf_processing_queue(status, priority, id);
+            CREATE INDEX IF NOT EXISTS ix_queue_history ON cf_processing_queue(scheduled_at, id);
 
             CREATE TABLE IF NOT EXISTS cf_output_materializations (
                 materialization_key TEXT PRIMARY KEY,
@@ -263,6 +499,14 @@ impl JobQueue {
         self.conn
             .execute_batch(&create_sql)
             .context("Failed to initialize cf_processing_queue schema")?;
+        job_ids::init_schema(&self.conn)?;
+        source_hashes::init_schema(&self.conn)?;
+        schema_drift::init_schema(&self.conn)?;
+        dataset_catalog::init_schema(&self.conn)?;
+        retention::init_schema(&self.conn)?;
+        compaction::init_schema(&self.conn)?;
+        write_buffer::init_schema(&self.conn)?;
+        self.migrate_result_summary_column()?;
         self.require_columns(
             "cf_processing_queue",
             &[
@@ -271,16 +515,91 @@ impl JobQueue {
                 "quarantine_rows",
                 "parser_version",
                 "parser_fingerprint",
+                "pinned_version",
+                "force_rerun",
                 "sink_config_json",
                 "lease_token",
                 "lease_owner",
                 "lease_expires_at",
                 "dispatch_ack_at",
+                "range_start",
+                "range_end",
+                "result_summary_json",
+                "upstream_job_id",
             ],
         )?;
         Ok(())
     }
 
+    /// Add `result_summary_json` to a queue created before it existed.
+    ///
+    /// Concluded jobs get a summary built from their recorded artifacts, or
+    /// from the free-text `result_summary` when none were recorded.
+    fn migrate_result_summary_column(&self) -> Result<()> {
+        if self.column_exists("cf_processing_queue", "result_summary_json")? {
+            return Ok(());
+        }
+        self.conn
+            .execute(
+                "ALTER TABLE cf_processing_queue ADD COLUMN result_summary_json TEXT",
+                &[],
+            )
+            .context("Failed to add cf_processing_queue.result_summary_json")?;
+
+        let has_artifacts = self.table_exists("cf_job_artifacts")?;
+        let rows = self.conn.query_all(
+            "SELECT id, result_summary FROM cf_processing_queue WHERE result_summary IS NOT NULL",
+            &[],
+        )?;
+        for row in rows {
+            let job_id: i64 = row.get_by_name("id")?;
+            let text: String = row.get_by_name("result_summary")?;
+            let mut summary = JobResultSummary::from_legacy(&text);
+            if has_artifacts {
+                let artifacts = self.recorded_result_artifacts(job_id)?;
+                if !artifacts.is_empty() {
+                    summary.artifacts = artifacts;
+                }
+            }
+            self.record_result_summary(job_id, &summary)?;
+        }
+        Ok(())
+    }
+
+    fn recorded_result_artifacts(&self, job_id: i64) -> Result<Vec<ResultArtifact>> {
+        let rows = self.conn.query_all(
+            "SELECT kind, name, uri, rows FROM cf_job_artifacts WHERE job_id = ? ORDER BY created_at",
+            &[DbValue::from(job_id)],
+        )?;
+        rows.iter()
+            .map(|row| {
+                let kind = match row.get_by_name::<String>("kind")?.as_str() {
+                    "output" => ArtifactKind::Output,
+                    "quarantine" => ArtifactKind::Quarantine,
+                    "log" => ArtifactKind::Log,
+                    _ => ArtifactKind::Other,
+                };
+                let rows: Option<i64> = row.get_by_name("rows")?;
+                Ok(ResultArtifact {
+                    kind,
+                    name: row.get_by_name("name")?,
+                    uri: Some(row.get_by_name("uri")?),
+                    rows: rows.and_then(|rows| u64::try_from(rows).ok()),
+                })
+            })
+            .collect()
+    }
+
+    /// Store the structured result of a concluded job.
+    pub fn record_result_summary(&self, job_id: i64, summary: &JobResultSummary) -> Result<()> {
+        let json = serde_json::to_string(summary)?;
+        self.conn.execute(
+            "UPDATE cf_processing_queue SET result_summary_json = ? WHERE id = ?",
+            &[DbValue::from(json.as_str()), DbValue::from(job_id)],
+        )?;
+        Ok(())
+    }
+
     /// Initialize plugin registry and topic configuration tables.
     pub fn init_registry_schema(&self) -> Result<()> {
         let plugin_status_values = PluginStatus::ALL
@@ -293,7 +612,12 @@ impl JobQueue {
             .map(|kind| format!("'{}'", kind.as_str()))
             .collect::<Vec<_>>()
             .join(",");
-        let sink_mode_values = [SinkMode::Append, SinkMode::Replace, SinkMode::Error]
+        let sink_mode_values = SinkMode::ALL
+            .iter()
+            .map(|mode| format!("'{}'", mode.as_str()))
+            .collect::<Vec<_>>()
+            .join(",");
+        let catch_up_values = ScheduleCatchUp::ALL
             .iter()
             .map(|mode| format!("'{}'", mode.as_str()))
             .collect::<Vec<_>>()
@@ -304,6 +628,7 @@ impl JobQueue {
             CREATE TABLE IF NOT EXISTS cf_plugin_manifest (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 plugin_name TEXT NOT NULL,
+                namespace TEXT NOT NULL DEFAULT '{default_namespace}',
                 version TEXT NOT NULL,
                 runtime_kind TEXT NOT NULL CHECK (runtime_kind IN ({runtime_kind_values})),
                 entrypoint TEXT NOT NULL,
@@ -343,6 +668,7 @@ impl JobQueue {
             CREATE TABLE IF NOT EXISTS cf_topic_config (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 plugin_name TEXT NOT NULL,
+                namespace TEXT NOT NULL DEFAULT '{default_namespace}',
                 topic_name TEXT NOT NULL,
                 uri TEXT NOT NULL,
                 mode TEXT NOT NULL DEFAULT 'append' CHECK (mode IN ({sink_mode_values})),
@@ -353,11 +679,50 @@ impl JobQueue {
             );
             CREATE INDEX IF NOT EXISTS ix_topic_lookup ON cf_topic_config(plugin_name, topic_name);
             CREATE UNIQUE INDEX IF NOT EXISTS ux_topic_unique ON cf_topic_config(plugin_name, topic_name);
+
+            CREATE TABLE IF NOT EXISTS cf_plugin_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                plugin_name TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                from_version TEXT,
+                to_version TEXT NOT NULL,
+                actor TEXT,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS ix_plugin_events_plugin ON cf_plugin_events(plugin_name, created_at);
+
+            CREATE TABLE IF NOT EXISTS cf_plugin_subscriptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                plugin_name TEXT NOT NULL,
+                topic_name TEXT NOT NULL,
+                is_active BOOLEAN NOT NULL DEFAULT true,
+                created_at INTEGER NOT NULL,
+                UNIQUE(plugin_name, topic_name)
+            );
+            CREATE INDEX IF NOT EXISTS ix_plugin_subscriptions_topic ON cf_plugin_subscriptions(topic_name);
+
+            CREATE TABLE IF NOT EXISTS cf_schedules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                cron TEXT NOT NULL,
+                target_kind TEXT NOT NULL CHECK (target_kind IN ('rescan_source', 'rule_window')),
+                target_id TEXT NOT NULL,
+                window_minutes INTEGER,
+                catch_up TEXT NOT NULL DEFAULT 'skip' CHECK (catch_up IN ({catch_up_values})),
+                is_active BOOLEAN NOT NULL DEFAULT true,
+                next_fire_at INTEGER,
+                last_fired_at INTEGER,
+                window_open_until INTEGER,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS ix_schedules_target ON cf_schedules(target_kind, target_id);
         "#,
                 default_status = PluginStatus::Pending.as_str(),
+                default_namespace = DEFAULT_NAMESPACE,
                 plugin_status_values = plugin_status_values,
                 runtime_kind_values = runtime_kind_values,
-                sink_mode_values = sink_mode_values
+                sink_mode_values = sink_mode_values,
+                catch_up_values = catch_up_values
             )
         } else {
             format!(
@@ -366,6 +731,7 @@ impl JobQueue {
             CREATE TABLE IF NOT EXISTS cf_plugin_manifest (
                 id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_plugin_manifest'),
                 plugin_name TEXT NOT NULL,
+                namespace TEXT NOT NULL DEFAULT '{default_namespace}',
                 version TEXT NOT NULL,
                 runtime_kind TEXT NOT NULL CHECK (runtime_kind IN ({runtime_kind_values})),
                 entrypoint TEXT NOT NULL,
@@ -406,6 +772,7 @@ impl JobQueue {
             CREATE TABLE IF NOT EXISTS cf_topic_config (
                 id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_topic_config'),
                 plugin_name TEXT NOT NULL,
+                namespace TEXT NOT NULL DEFAULT '{default_namespace}',
                 topic_name TEXT NOT NULL,
                 uri TEXT NOT NULL,
                 mode TEXT NOT NULL DEFAULT 'append' CHECK (mode IN ({sink_mode_values})),
@@ -416,11 +783,53 @@ impl JobQueue {
             );
             CREATE INDEX IF NOT EXISTS ix_topic_lookup ON cf_topic_config(plugin_name, topic_name);
             CREATE UNIQUE INDEX IF NOT EXISTS ux_topic_unique ON cf_topic_config(plugin_name, topic_name);
+
+            CREATE SEQUENCE IF NOT EXISTS seq_cf_plugin_events;
+            CREATE TABLE IF NOT EXISTS cf_plugin_events (
+                id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_plugin_events'),
+                plugin_name TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                from_version TEXT,
+                to_version TEXT NOT NULL,
+                actor TEXT,
+                created_at BIGINT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS ix_plugin_events_plugin ON cf_plugin_events(plugin_name, created_at);
+
+            CREATE SEQUENCE IF NOT EXISTS seq_cf_plugin_subscriptions;
+            CREATE TABLE IF NOT EXISTS cf_plugin_subscriptions (
+                id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_plugin_subscriptions'),
+                plugin_name TEXT NOT NULL,
+                topic_name TEXT NOT NULL,
+                is_active BOOLEAN NOT NULL DEFAULT true,
+                created_at BIGINT NOT NULL,
+                UNIQUE(plugin_name, topic_name)
+            );
+            CREATE INDEX IF NOT EXISTS ix_plugin_subscriptions_topic ON cf_plugin_subscriptions(topic_name);
+
+            CREATE SEQUENCE IF NOT EXISTS seq_cf_schedules;
+            CREATE TABLE IF NOT EXISTS cf_schedules (
+                id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_schedules'),
+                name TEXT NOT NULL UNIQUE,
+                cron TEXT NOT NULL,
+                target_kind TEXT NOT NULL CHECK (target_kind IN ('rescan_source', 'rule_window')),
+                target_id TEXT NOT NULL,
+                window_minutes BIGINT,
+                catch_up TEXT NOT NULL DEFAULT 'skip' CHECK (catch_up IN ({catch_up_values})),
+                is_active BOOLEAN NOT NULL DEFAULT true,
+                next_fire_at BIGINT,
+                last_fired_at BIGINT,
+                window_open_until BIGINT,
+                created_at BIGINT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS ix_schedules_target ON cf_schedules(target_kind, target_id);
         "#,
                 default_status = PluginStatus::Pending.as_str(),
+                default_namespace = DEFAULT_NAMESPACE,
                 plugin_status_values = plugin_status_values,
                 runtime_kind_values = runtime_kind_values,
-                sink_mode_values = sink_mode_values
+                sink_mode_values = sink_mode_values,
+                catch_up_values = catch_up_values
             )
         };
 
@@ -440,11 +849,13 @@ impl JobQueue {
                 "signature_verified",
                 "signer_id",
                 "outputs_json",
+                "namespace",
             ],
         )?;
         self.require_columns(
             "cf_topic_config",
             &[
+                "namespace",
                 "quarantine_allow",
                 "quarantine_max_pct",
                 "quarantine_max_count",
@@ -545,887 +956,1021 @@ impl JobQueue {
         .transpose()
     }
 
-    /// Get lockfile content from plugin environment.
-    pub fn get_lockfile(&self, env_hash: &str) -> Result<Option<String>> {
+    /// Namespace that owns `plugin_name`.
+    ///
+    /// Plugins missing from the registry belong to `DEFAULT_NAMESPACE`.
+    pub fn plugin_namespace(&self, plugin_name: &str) -> Result<String> {
+        if !table_exists(&self.conn, "cf_plugin_manifest")? {
+            return Ok(DEFAULT_NAMESPACE.to_string());
+        }
+        let namespace = self
+            .conn
+            .query_optional(
+                "SELECT namespace FROM cf_plugin_manifest WHERE plugin_name = ? LIMIT 1",
+                &[DbValue::from(plugin_name)],
+            )?
+            .map(|row| row.get_by_name::<String>("namespace"))
+            .transpose()?;
+        Ok(namespace.unwrap_or_else(|| DEFAULT_NAMESPACE.to_string()))
+    }
+
+    /// Version of `plugin_name` that jobs currently run, if it is deployed.
+    pub fn active_plugin_version(&self, plugin_name: &str) -> Result<Option<String>> {
+        if !table_exists(&self.conn, "cf_plugin_manifest")? {
+            return Ok(None);
+        }
         let row = self.conn.query_optional(
-            "SELECT lockfile_content FROM cf_plugin_environment WHERE hash = ?",
-            &[DbValue::from(env_hash)],
+            r#"
+                SELECT version
+                FROM cf_plugin_manifest
+                WHERE plugin_name = ? AND status IN (?, ?)
+                ORDER BY deployed_at DESC
+                LIMIT 1
+                "#,
+            &[
+                DbValue::from(plugin_name),
+                DbValue::from(PluginStatus::Active.as_str()),
+                DbValue::from(PluginStatus::Deployed.as_str()),
+            ],
         )?;
-        row.map(|row| {
-            row.get_by_name("lockfile_content")
-                .context("Failed to read 'lockfile_content' from cf_plugin_environment")
-        })
-        .transpose()
+        row.map(|row| row.get_by_name::<String>("version"))
+            .transpose()
+            .map_err(Into::into)
     }
 
-    /// Peek at the next job without claiming it.
-    pub fn peek_job(&self) -> Result<Option<ProcessingJob>> {
-        let has_health = self.table_exists("cf_parser_health")?;
-        let now = now_millis();
-        let (query, params) = if has_health {
-            (
-                format!(
-                    r#"
-                SELECT {columns}
-                FROM cf_processing_queue q
-                LEFT JOIN cf_parser_health ph ON ph.parser_name = q.plugin_name
-                WHERE q.status = ?
-                  AND (q.scheduled_at IS NULL OR q.scheduled_at <= ?)
-                  AND (ph.paused_at IS NULL)
-                ORDER BY q.priority DESC, q.id ASC
+    /// Make `target_version` the active version of `plugin_name` again.
+    ///
+    /// The currently active version is marked SUPERSEDED and the change is
+    /// recorded in `cf_plugin_events`, all in one transaction. Only versions
+    /// that were once active (SUPERSEDED) can be rolled back to.
+    pub fn rollback_plugin(
+        &self,
+        plugin_name: &str,
+        target_version: &str,
+        actor: Option<&str>,
+    ) -> Result<PluginRollback> {
+        let row = self.conn.query_optional(
+            r#"
+                SELECT status
+                FROM cf_plugin_manifest
+                WHERE plugin_name = ? AND version = ?
+                ORDER BY created_at DESC
                 LIMIT 1
                 "#,
-                    columns = prefixed_columns("q", PROCESSING_JOB_COLUMNS)
-                ),
-                vec![
-                    DbValue::from(ProcessingStatus::Queued.as_str()),
+            &[DbValue::from(plugin_name), DbValue::from(target_version)],
+        )?;
+        let Some(row) = row else {
+            anyhow::bail!(
+                "Plugin '{}' version '{}' not found in registry",
+                plugin_name,
+                target_version
+            );
+        };
+        let status_raw: String = row.get_by_name("status")?;
+        let status = status_raw
+            .parse::<PluginStatus>()
+            .map_err(|e| anyhow::anyhow!("Invalid plugin status '{}': {}", status_raw, e))?;
+        match status {
+            PluginStatus::Superseded => {}
+            PluginStatus::Active | PluginStatus::Deployed => anyhow::bail!(
+                "Plugin '{}' version '{}' is already active",
+                plugin_name,
+                target_version
+            ),
+            other => anyhow::bail!(
+                "Plugin '{}' version '{}' is {} and was never active; cannot roll back to it",
+                plugin_name,
+                target_version,
+                other
+            ),
+        }
+
+        let from_version = self
+            .conn
+            .query_optional(
+                r#"
+                SELECT version
+                FROM cf_plugin_manifest
+                WHERE plugin_name = ? AND status IN (?, ?)
+                ORDER BY created_at DESC
+                LIMIT 1
+                "#,
+                &[
+                    DbValue::from(plugin_name),
+                    DbValue::from(PluginStatus::Active.as_str()),
+                    DbValue::from(PluginStatus::Deployed.as_str()),
+                ],
+            )?
+            .map(|row| row.get_by_name::<String>("version"))
+            .transpose()?;
+
+        let now = now_millis();
+        self.conn.transaction(|tx| {
+            tx.execute(
+                r#"
+                UPDATE cf_plugin_manifest
+                SET status = ?
+                WHERE plugin_name = ? AND status IN (?, ?)
+                "#,
+                &[
+                    DbValue::from(PluginStatus::Superseded.as_str()),
+                    DbValue::from(plugin_name),
+                    DbValue::from(PluginStatus::Active.as_str()),
+                    DbValue::from(PluginStatus::Deployed.as_str()),
+                ],
+            )?;
+            tx.execute(
+                r#"
+                UPDATE cf_plugin_manifest
+                SET status = ?, deployed_at = ?
+                WHERE plugin_name = ? AND version = ? AND status = ?
+                "#,
+                &[
+                    DbValue::from(PluginStatus::Active.as_str()),
                     DbValue::from(now),
+                    DbValue::from(plugin_name),
+                    DbValue::from(target_version),
+                    DbValue::from(PluginStatus::Superseded.as_str()),
                 ],
-            )
-        } else {
-            (
-                format!(
-                    r#"
-                SELECT {columns}
-                FROM cf_processing_queue
-                WHERE status = ?
-                  AND (scheduled_at IS NULL OR scheduled_at <= ?)
-                ORDER BY priority DESC, id ASC
-                LIMIT 1
+            )?;
+            tx.execute(
+                r#"
+                INSERT INTO cf_plugin_events
+                    (plugin_name, event_type, from_version, to_version, actor, created_at)
+                VALUES (?, ?, ?, ?, ?, ?)
                 "#,
-                    columns = column_list(PROCESSING_JOB_COLUMNS)
-                ),
-                vec![
-                    DbValue::from(ProcessingStatus::Queued.as_str()),
+                &[
+                    DbValue::from(plugin_name),
+                    DbValue::from(PLUGIN_EVENT_ROLLBACK),
+                    DbValue::from(from_version.as_deref()),
+                    DbValue::from(target_version),
+                    DbValue::from(actor),
                     DbValue::from(now),
                 ],
-            )
-        };
+            )?;
+            Ok(())
+        })?;
 
-        let row = self.conn.query_optional(&query, &params)?;
-        Ok(row.map(|row| ProcessingJob::from_row(&row)).transpose()?)
+        Ok(PluginRollback {
+            plugin_name: plugin_name.to_string(),
+            from_version,
+            to_version: target_version.to_string(),
+        })
     }
 
-    /// Atomically pop a job from the queue.
-    pub fn pop_job(&self) -> Result<Option<ProcessingJob>> {
-        let has_health = self.table_exists("cf_parser_health")?;
-        let now = now_millis();
-        let lease_expires_at = now.saturating_add(DEFAULT_DISPATCH_LEASE_TTL_MS);
-        let (query, params) = if has_health {
-            (
-                format!(
-                    r#"
-                UPDATE cf_processing_queue
-                SET status = ?,
-                    claim_time = ?,
-                    lease_expires_at = ?,
-                    lease_token = NULL,
-                    lease_owner = NULL,
-                    dispatch_ack_at = NULL
-                WHERE id = (
-                    SELECT q.id
-                    FROM cf_processing_queue q
-                    LEFT JOIN cf_parser_health ph ON ph.parser_name = q.plugin_name
-                    WHERE q.status = ?
-                      AND (q.scheduled_at IS NULL OR q.scheduled_at <= ?)
-                      AND (ph.paused_at IS NULL)
-                    ORDER BY q.priority DESC, q.id ASC
-                    LIMIT 1
-                )
-                RETURNING {columns}
+    /// List audit events for a plugin, most recent first.
+    pub fn list_plugin_events(&self, plugin_name: &str) -> Result<Vec<PluginEvent>> {
+        let rows = self.conn.query_all(
+            r#"
+                SELECT id, plugin_name, event_type, from_version, to_version, actor, created_at
+                FROM cf_plugin_events
+                WHERE plugin_name = ?
+                ORDER BY created_at DESC, id DESC
                 "#,
-                    columns = column_list(PROCESSING_JOB_COLUMNS)
-                ),
-                vec![
-                    DbValue::from(ProcessingStatus::Dispatching.as_str()),
-                    DbValue::from(now.clone()),
-                    DbValue::from(lease_expires_at),
-                    DbValue::from(ProcessingStatus::Queued.as_str()),
-                    DbValue::from(now),
-                ],
-            )
-        } else {
-            (
-                format!(
-                    r#"
-                UPDATE cf_processing_queue
-                SET status = ?,
-                    claim_time = ?,
-                    lease_expires_at = ?,
-                    lease_token = NULL,
-                    lease_owner = NULL,
-                    dispatch_ack_at = NULL
-                WHERE id = (
-                    SELECT id
-                    FROM cf_processing_queue
-                    WHERE status = ?
-                      AND (scheduled_at IS NULL OR scheduled_at <= ?)
-                    ORDER BY priority DESC, id ASC
-                    LIMIT 1
-                )
-                RETURNING {columns}
-                "#,
-                    columns = column_list(PROCESSING_JOB_COLUMNS)
-                ),
-                vec![
-                    DbValue::from(ProcessingStatus::Dispatching.as_str()),
-                    DbValue::from(now.clone()),
-                    DbValue::from(lease_expires_at),
-                    DbValue::from(ProcessingStatus::Queued.as_str()),
-                    DbValue::from(now),
-                ],
-            )
-        };
-
-        let row = self.conn.query_optional(&query, &params)?;
+            &[DbValue::from(plugin_name)],
+        )?;
+        rows.iter()
+            .map(|row| {
+                Ok(PluginEvent {
+                    id: row.get_by_name("id")?,
+                    plugin_name: row.get_by_name("plugin_name")?,
+                    event_type: row.get_by_name("event_type")?,
+                    from_version: row.get_by_name("from_version")?,
+                    to_version: row.get_by_name("to_version")?,
+                    actor: row.get_by_name("actor")?,
+                    created_at: row.get_by_name("created_at")?,
+                })
+            })
+            .collect()
+    }
 
-        Ok(row.map(|row| ProcessingJob::from_row(&row)).transpose()?)
+    /// Subscribe `plugin_name` to an output topic (`producer_plugin:output`).
+    ///
+    /// Subscribing again re-enables an existing subscription. Fails if the
+    /// subscription would close a cycle between plugins.
+    pub fn subscribe_plugin(&self, plugin_name: &str, topic_name: &str) -> Result<TopicSubscription> {
+        let Some(producer) = topic_producer(topic_name) else {
+            anyhow::bail!(
+                "Invalid topic '{}': expected 'producer_plugin:output_name'",
+                topic_name
+            );
+        };
+        self.check_subscription_cycle(plugin_name, topic_name, producer)?;
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_plugin_subscriptions (plugin_name, topic_name, is_active, created_at)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT(plugin_name, topic_name) DO UPDATE SET is_active = excluded.is_active
+                "#,
+            &[
+                DbValue::from(plugin_name),
+                DbValue::from(topic_name),
+                DbValue::from(true),
+                DbValue::from(now_millis()),
+            ],
+        )?;
+        let row = self.conn.query_one(
+            &format!(
+                "SELECT {} FROM cf_plugin_subscriptions WHERE plugin_name = ? AND topic_name = ?",
+                column_list(SUBSCRIPTION_COLUMNS)
+            ),
+            &[DbValue::from(plugin_name), DbValue::from(topic_name)],
+        )?;
+        subscription_from_row(&row)
     }
 
-    /// Atomically lease up to `limit` jobs from the queue for dispatch.
-    pub fn lease_jobs_for_dispatch(
+    /// Enable or disable a subscription without deleting it.
+    ///
+    /// Enabling re-checks for cycles, since other subscriptions may have been
+    /// added while this one was disabled.
+    pub fn set_subscription_active(
         &self,
-        limit: usize,
-        now: i64,
-        ttl_ms: i64,
-    ) -> Result<Vec<ProcessingJob>> {
-        if limit == 0 {
-            return Ok(Vec::new());
+        subscription_id: i64,
+        active: bool,
+    ) -> Result<TopicSubscription> {
+        let row = self.conn.query_optional(
+            &format!(
+                "SELECT {} FROM cf_plugin_subscriptions WHERE id = ?",
+                column_list(SUBSCRIPTION_COLUMNS)
+            ),
+            &[DbValue::from(subscription_id)],
+        )?;
+        let Some(row) = row else {
+            anyhow::bail!("Subscription {} not found", subscription_id);
+        };
+        let mut subscription = subscription_from_row(&row)?;
+        if active && !subscription.is_active {
+            let producer = topic_producer(&subscription.topic_name)
+                .ok_or_else(|| anyhow::anyhow!("Invalid topic '{}'", subscription.topic_name))?;
+            self.check_subscription_cycle(
+                &subscription.plugin_name,
+                &subscription.topic_name,
+                producer,
+            )?;
         }
-        let limit: i64 = limit
-            .try_into()
-            .context("lease_jobs_for_dispatch limit overflow")?;
-        let has_health = self.table_exists("cf_parser_health")?;
-        let lease_expires_at = now.saturating_add(ttl_ms);
+        self.conn.execute(
+            "UPDATE cf_plugin_subscriptions SET is_active = ? WHERE id = ?",
+            &[DbValue::from(active), DbValue::from(subscription_id)],
+        )?;
+        subscription.is_active = active;
+        Ok(subscription)
+    }
 
-        let (query, params) = if has_health {
-            (
-                format!(
-                    r#"
-                WITH to_claim AS (
-                    SELECT q.id
-                    FROM cf_processing_queue q
-                    LEFT JOIN cf_parser_health ph ON ph.parser_name = q.plugin_name
-                    WHERE q.status = ?
-                      AND (q.scheduled_at IS NULL OR q.scheduled_at <= ?)
-                      AND (ph.paused_at IS NULL)
-                    ORDER BY q.priority DESC, q.id ASC
-                    LIMIT ?
-                )
-                UPDATE cf_processing_queue
-                SET status = ?,
-                    claim_time = ?,
-                    lease_expires_at = ?,
-                    lease_token = NULL,
-                    lease_owner = NULL,
-                    dispatch_ack_at = NULL
-                WHERE id IN (SELECT id FROM to_claim)
-                RETURNING {columns}
-                "#,
-                    columns = column_list(PROCESSING_JOB_COLUMNS)
-                ),
-                vec![
-                    DbValue::from(ProcessingStatus::Queued.as_str()),
-                    DbValue::from(now),
-                    DbValue::from(limit),
-                    DbValue::from(ProcessingStatus::Dispatching.as_str()),
-                    DbValue::from(now),
-                    DbValue::from(lease_expires_at),
-                ],
-            )
-        } else {
-            (
-                format!(
-                    r#"
-                WITH to_claim AS (
-                    SELECT id
-                    FROM cf_processing_queue
-                    WHERE status = ?
-                      AND (scheduled_at IS NULL OR scheduled_at <= ?)
-                    ORDER BY priority DESC, id ASC
-                    LIMIT ?
-                )
-                UPDATE cf_processing_queue
-                SET status = ?,
-                    claim_time = ?,
-                    lease_expires_at = ?,
-                    lease_token = NULL,
-                    lease_owner = NULL,
-                    dispatch_ack_at = NULL
-                WHERE id IN (SELECT id FROM to_claim)
-                RETURNING {columns}
+    /// List all subscriptions, enabled or not, ordered by topic.
+    pub fn list_subscriptions(&self) -> Result<Vec<TopicSubscription>> {
+        let rows = self.conn.query_all(
+            &format!(
+                "SELECT {} FROM cf_plugin_subscriptions ORDER BY topic_name, plugin_name",
+                column_list(SUBSCRIPTION_COLUMNS)
+            ),
+            &[],
+        )?;
+        rows.iter().map(subscription_from_row).collect()
+    }
+
+    /// Create a schedule. The Sentinel computes its first occurrence on its
+    /// next tick.
+    pub fn create_schedule(
+        &self,
+        name: &str,
+        cron: &str,
+        target: &ScheduleTarget,
+        catch_up: ScheduleCatchUp,
+    ) -> Result<Schedule> {
+        let window_minutes = match target {
+            ScheduleTarget::RuleWindow { window_minutes, .. } => Some(i64::from(*window_minutes)),
+            ScheduleTarget::RescanSource { .. } => None,
+        };
+        self.conn
+            .execute(
+                r#"
+                INSERT INTO cf_schedules
+                    (name, cron, target_kind, target_id, window_minutes, catch_up, is_active, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
-                    columns = column_list(PROCESSING_JOB_COLUMNS)
-                ),
-                vec![
-                    DbValue::from(ProcessingStatus::Queued.as_str()),
-                    DbValue::from(now),
-                    DbValue::from(limit),
-                    DbValue::from(ProcessingStatus::Dispatching.as_str()),
-                    DbValue::from(now),
-                    DbValue::from(lease_expires_at),
+                &[
+                    DbValue::from(name),
+                    DbValue::from(cron),
+                    DbValue::from(target.kind()),
+                    DbValue::from(target.target_id()),
+                    DbValue::from(window_minutes),
+                    DbValue::from(catch_up.as_str()),
+                    DbValue::from(true),
+                    DbValue::from(now_millis()),
                 ],
             )
+            .with_context(|| format!("Failed to create schedule '{}'", name))?;
+        let row = self.conn.query_one(
+            &format!(
+                "SELECT {} FROM cf_schedules WHERE name = ?",
+                column_list(SCHEDULE_COLUMNS)
+            ),
+            &[DbValue::from(name)],
+        )?;
+        schedule_from_row(&row)
+    }
+
+    /// List all schedules, enabled or not, ordered by name.
+    pub fn list_schedules(&self) -> Result<Vec<Schedule>> {
+        if !self.table_exists("cf_schedules")? {
+            return Ok(Vec::new());
+        }
+        let rows = self.conn.query_all(
+            &format!(
+                "SELECT {} FROM cf_schedules ORDER BY name",
+                column_list(SCHEDULE_COLUMNS)
+            ),
+            &[],
+        )?;
+        rows.iter().map(schedule_from_row).collect()
+    }
+
+    pub fn get_schedule(&self, schedule_id: i64) -> Result<Option<Schedule>> {
+        let row = self.conn.query_optional(
+            &format!(
+                "SELECT {} FROM cf_schedules WHERE id = ?",
+                column_list(SCHEDULE_COLUMNS)
+            ),
+            &[DbValue::from(schedule_id)],
+        )?;
+        row.as_ref().map(schedule_from_row).transpose()
+    }
+
+    /// Enable or disable a schedule.
+    ///
+    /// Enabling clears the stored occurrence so occurrences missed while the
+    /// schedule was disabled are not caught up. Disabling a rule window
+    /// releases the jobs it was holding.
+    pub fn set_schedule_active(&self, schedule_id: i64, active: bool) -> Result<Schedule> {
+        let Some(mut schedule) = self.get_schedule(schedule_id)? else {
+            anyhow::bail!("Schedule {} not found", schedule_id);
         };
+        if active && !schedule.is_active {
+            self.conn.execute(
+                "UPDATE cf_schedules SET is_active = ?, next_fire_at = NULL, window_open_until = NULL WHERE id = ?",
+                &[DbValue::from(true), DbValue::from(schedule_id)],
+            )?;
+            schedule.next_fire_at = None;
+            schedule.window_open_until = None;
+        } else if !active {
+            self.conn.execute(
+                "UPDATE cf_schedules SET is_active = ? WHERE id = ?",
+                &[DbValue::from(false), DbValue::from(schedule_id)],
+            )?;
+            if let ScheduleTarget::RuleWindow { rule_id, .. } = &schedule.target {
+                self.release_schedule_holds(rule_id, now_millis())?;
+            }
+        }
+        schedule.is_active = active;
+        Ok(schedule)
+    }
 
-        let rows = self.conn.query_all(&query, &params)?;
-        let mut jobs = rows
-            .into_iter()
-            .map(|row| ProcessingJob::from_row(&row))
-            .collect::<Result<Vec<_>, _>>()?;
-        jobs.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.id.cmp(&b.id)));
-        Ok(jobs)
+    /// Delete a schedule, releasing any jobs its window was holding.
+    /// Returns false if it did not exist.
+    pub fn delete_schedule(&self, schedule_id: i64) -> Result<bool> {
+        let Some(schedule) = self.get_schedule(schedule_id)? else {
+            return Ok(false);
+        };
+        self.conn.execute(
+            "DELETE FROM cf_schedules WHERE id = ?",
+            &[DbValue::from(schedule_id)],
+        )?;
+        if let ScheduleTarget::RuleWindow { rule_id, .. } = &schedule.target {
+            self.release_schedule_holds(rule_id, now_millis())?;
+        }
+        Ok(true)
     }
 
-    /// Persist the lease token/owner for a dispatching job.
-    pub fn set_dispatch_lease(
-        &self,
-        job_id: i64,
-        lease_token: &str,
-        lease_owner: &str,
-    ) -> Result<bool> {
-        let affected = self.conn.execute(
+    /// Persist a schedule's evaluation state after a Sentinel tick.
+    pub fn record_schedule_state(&self, schedule_id: i64, state: ScheduleState) -> Result<()> {
+        self.conn.execute(
             r#"
-                UPDATE cf_processing_queue
-                SET lease_token = ?, lease_owner = ?
-                WHERE id = ? AND status = ?
+                UPDATE cf_schedules
+                SET next_fire_at = ?, last_fired_at = ?, window_open_until = ?
+                WHERE id = ?
                 "#,
             &[
-                DbValue::from(lease_token),
-                DbValue::from(lease_owner),
-                DbValue::from(job_id),
-                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                DbValue::from(state.next_fire_at),
+                DbValue::from(state.last_fired_at),
+                DbValue::from(state.window_open_until),
+                DbValue::from(schedule_id),
             ],
         )?;
-        Ok(affected > 0)
+        Ok(())
     }
 
-    /// Acknowledge a dispatch lease and transition to RUNNING.
-    pub fn ack_dispatch(
-        &self,
-        job_id: i64,
-        lease_token: &str,
-        lease_owner: &str,
-        now: i64,
-    ) -> Result<bool> {
-        let affected = self.conn.execute(
+    /// The closed rule window holding a file's jobs at `now`, if any.
+    ///
+    /// A file is held when a rule that tagged it has an enabled window
+    /// schedule that is not open.
+    pub fn schedule_hold(&self, file_id: i64, now: i64) -> Result<Option<ScheduleHold>> {
+        if !self.table_exists("cf_schedules")? || !self.table_exists("scout_file_tags")? {
+            return Ok(None);
+        }
+        let row = self.conn.query_optional(
             r#"
-                UPDATE cf_processing_queue
-                SET status = ?,
-                    dispatch_ack_at = ?,
-                    lease_owner = ?
-                WHERE id = ? AND status = ? AND lease_token = ?
+                SELECT s.name, s.next_fire_at
+                FROM cf_schedules s
+                JOIN scout_file_tags t ON t.rule_id = s.target_id
+                WHERE t.file_id = ?
+                  AND s.target_kind = 'rule_window'
+                  AND s.is_active = ?
+                  AND (s.window_open_until IS NULL OR s.window_open_until <= ?)
+                ORDER BY s.next_fire_at DESC
+                LIMIT 1
                 "#,
             &[
-                DbValue::from(ProcessingStatus::Running.as_str()),
+                DbValue::from(file_id),
+                DbValue::from(true),
                 DbValue::from(now),
-                DbValue::from(lease_owner),
-                DbValue::from(job_id),
-                DbValue::from(ProcessingStatus::Dispatching.as_str()),
-                DbValue::from(lease_token),
             ],
         )?;
-        Ok(affected > 0)
+        row.map(|row| {
+            Ok(ScheduleHold {
+                schedule_name: row.get_by_name("name")?,
+                next_open_at: row.get_by_name("next_fire_at")?,
+            })
+        })
+        .transpose()
     }
 
-    /// Requeue dispatches whose lease has expired.
-    pub fn requeue_expired_dispatches(&self, now: i64) -> Result<usize> {
-        let affected = self.conn.execute(
+    /// Make queued jobs held for `rule_id`'s window runnable now. Returns
+    /// the number of jobs released.
+    pub fn release_schedule_holds(&self, rule_id: &str, now: i64) -> Result<usize> {
+        if !self.table_exists("scout_file_tags")? {
+            return Ok(0);
+        }
+        let released = self.conn.execute(
             r#"
                 UPDATE cf_processing_queue
-                SET status = ?,
-                    claim_time = NULL,
-                    lease_token = NULL,
-                    lease_owner = NULL,
-                    lease_expires_at = NULL,
-                    dispatch_ack_at = NULL
-                WHERE status = ? AND lease_expires_at IS NOT NULL AND lease_expires_at <= ?
+                SET scheduled_at = ?, error_message = NULL
+                WHERE status = ?
+                  AND error_message = ?
+                  AND file_id IN (SELECT file_id FROM scout_file_tags WHERE rule_id = ?)
                 "#,
             &[
-                DbValue::from(ProcessingStatus::Queued.as_str()),
-                DbValue::from(ProcessingStatus::Dispatching.as_str()),
                 DbValue::from(now),
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(SCHEDULE_HOLD_REASON),
+                DbValue::from(rule_id),
             ],
         )?;
-        Ok(affected as usize)
-    }
-
-    fn column_exists(&self, table: &str, column: &str) -> Result<bool> {
-        Ok(self.conn.column_exists(table, column)?)
-    }
-
-    fn table_exists(&self, table: &str) -> Result<bool> {
-        Ok(self.conn.table_exists(table)?)
+        Ok(released as usize)
     }
 
-    fn require_columns(&self, table: &str, columns: &[&str]) -> Result<()> {
-        let mut missing = Vec::new();
-        for column in columns {
-            if !self.column_exists(table, column)? {
-                missing.push(*column);
+    fn check_subscription_cycle(
+        &self,
+        plugin_name: &str,
+        topic_name: &str,
+        producer: &str,
+    ) -> Result<()> {
+        let rows = self.conn.query_all(
+            "SELECT plugin_name, topic_name FROM cf_plugin_subscriptions WHERE is_active = ?",
+            &[DbValue::from(true)],
+        )?;
+        let mut edges = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let subscriber: String = row.get_by_name("plugin_name")?;
+            let topic: String = row.get_by_name("topic_name")?;
+            if let Some(edge_producer) = topic_producer(&topic) {
+                edges.push((edge_producer.to_string(), subscriber));
             }
         }
-
-        if missing.is_empty() {
-            return Ok(());
+        if let Some(path) = subscription_path(&edges, plugin_name, producer) {
+            anyhow::bail!(
+                "Subscribing '{}' to '{}' would create a cycle: {} -> {}",
+                plugin_name,
+                topic_name,
+                path.join(" -> "),
+                plugin_name
+            );
         }
-
-        anyhow::bail!(
-            "Database schema for '{}' is missing columns: {}. \
-Manual reset required. Delete the state store (default: ~/.casparian_flow/state.sqlite) \
-or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
-            table,
-            missing.join(", ")
-        );
+        Ok(())
     }
 
-    /// Mark job as complete with outcome details.
+    /// Enqueue a downstream job for each active subscriber of the outputs a
+    /// completed job published. Returns the new job ids.
     ///
-    /// `completion_status` should be one of: SUCCESS, PARTIAL_SUCCESS, COMPLETED_WITH_WARNINGS
-    pub fn complete_job(
+    /// Downstream jobs keep the upstream job's file id, pipeline run and
+    /// priority, read the artifact as their input, and always run (the
+    /// source file may be unchanged while the artifact is new). A subscriber
+    /// already on the job's upstream chain is skipped, so a cycle in the
+    /// subscription table cannot loop forever.
+    pub fn enqueue_downstream_jobs(
         &self,
         job_id: i64,
-        completion_status: &str,
-        summary: &str,
-        quarantine_rows: Option<i64>,
-    ) -> Result<()> {
-        let now = now_millis();
-        if let Some(rows) = quarantine_rows {
-            self.conn.execute(
-                r#"
-                    UPDATE cf_processing_queue
-                    SET status = ?,
-                        completion_status = ?,
-                        end_time = ?,
-                        result_summary = ?,
-                        quarantine_rows = ?
-                    WHERE id = ?
-                    "#,
-                &[
-                    DbValue::from(ProcessingStatus::Completed.as_str()),
-                    DbValue::from(completion_status),
-                    DbValue::from(now),
-                    DbValue::from(summary),
-                    DbValue::from(rows),
-                    DbValue::from(job_id),
-                ],
-            )?;
-        } else {
-            self.conn.execute(
-                r#"
-                    UPDATE cf_processing_queue
-                    SET status = ?,
-                        completion_status = ?,
-                        end_time = ?,
-                        result_summary = ?
-                    WHERE id = ?
+        artifacts: &[ArtifactV1],
+    ) -> Result<Vec<i64>> {
+        let row = self.conn.query_optional(
+            r#"
+                SELECT file_id, plugin_name, pipeline_run_id, priority
+                FROM cf_processing_queue
+                WHERE id = ?
+                "#,
+            &[DbValue::from(job_id)],
+        )?;
+        let Some(row) = row else {
+            return Ok(Vec::new());
+        };
+        let file_id: i64 = row.get_by_name("file_id")?;
+        let plugin_name: String = row.get_by_name("plugin_name")?;
+        let mut pipeline_run_id: Option<String> = row.get_by_name("pipeline_run_id")?;
+        let priority: Option<i32> = row.get_by_name("priority")?;
+
+        let mut chain: Option<Vec<String>> = None;
+        let mut enqueued = Vec::new();
+        for artifact in artifacts {
+            let ArtifactV1::Output {
+                output_name,
+                sink_uri,
+                ..
+            } = artifact
+            else {
+                continue;
+            };
+            let topic = output_topic(&plugin_name, output_name);
+            let subscribers = self.active_subscribers(&topic)?;
+            if subscribers.is_empty() {
+                continue;
+            }
+            let Some(input_file) = artifact_input_path(sink_uri) else {
+                warn!(
+                    "Output '{}' of job {} is not a single file ({}); subscribers of '{}' not enqueued",
+                    output_name, job_id, sink_uri, topic
+                );
+                continue;
+            };
+            if chain.is_none() {
+                chain = Some(self.upstream_plugins(job_id)?);
+            }
+            let chain = chain.as_deref().unwrap_or_default();
+            if pipeline_run_id.is_none() {
+                // A chain started by one file is one run, even when the
+                // root job was not enqueued by a pipeline.
+                let run_id = Uuid::new_v4().to_string();
+                self.conn.execute(
+                    "UPDATE cf_processing_queue SET pipeline_run_id = ? WHERE id = ?",
+                    &[DbValue::from(run_id.as_str()), DbValue::from(job_id)],
+                )?;
+                pipeline_run_id = Some(run_id);
+            }
+            for subscriber in subscribers {
+                if chain.contains(&subscriber) {
+                    warn!(
+                        "Not enqueueing '{}' for job {}: it already ran upstream ({})",
+                        subscriber,
+                        job_id,
+                        chain.join(" <- ")
+                    );
+                    continue;
+                }
+                let new_id = job_ids::next_job_id(&self.conn)?.to_i64()?;
+                self.conn.execute(
+                    r#"
+                    INSERT INTO cf_processing_queue
+                        (id, file_id, input_file, pipeline_run_id, plugin_name, status,
+                         priority, scheduled_at, force_rerun, upstream_job_id)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                     "#,
-                &[
-                    DbValue::from(ProcessingStatus::Completed.as_str()),
-                    DbValue::from(completion_status),
-                    DbValue::from(now),
-                    DbValue::from(summary),
-                    DbValue::from(job_id),
-                ],
-            )?;
+                    &[
+                        DbValue::from(new_id),
+                        DbValue::from(file_id),
+                        DbValue::from(input_file),
+                        DbValue::from(pipeline_run_id.as_deref()),
+                        DbValue::from(subscriber.as_str()),
+                        DbValue::from(ProcessingStatus::Queued.as_str()),
+                        DbValue::from(priority.unwrap_or(0)),
+                        DbValue::from(now_millis()),
+                        DbValue::from(true),
+                        DbValue::from(job_id),
+                    ],
+                )?;
+                enqueued.push(new_id);
+            }
         }
-        Ok(())
+        Ok(enqueued)
     }
 
-    /// Persist dispatch metadata for a job (parser version/hash + sink config snapshot).
-    pub fn record_dispatch_metadata(
-        &self,
-        job_id: i64,
-        parser_version: &str,
-        parser_fingerprint: &str,
-        sink_config_json: &str,
-    ) -> Result<()> {
-        self.conn.execute(
+    fn active_subscribers(&self, topic_name: &str) -> Result<Vec<String>> {
+        let rows = self.conn.query_all(
             r#"
-                UPDATE cf_processing_queue
-                SET parser_version = ?, parser_fingerprint = ?, sink_config_json = ?
-                WHERE id = ?
+                SELECT plugin_name
+                FROM cf_plugin_subscriptions
+                WHERE topic_name = ? AND is_active = ?
+                ORDER BY plugin_name
                 "#,
-            &[
-                DbValue::from(parser_version),
-                DbValue::from(parser_fingerprint),
-                DbValue::from(sink_config_json),
-                DbValue::from(job_id),
-            ],
+            &[DbValue::from(topic_name), DbValue::from(true)],
         )?;
-        Ok(())
+        rows.iter()
+            .map(|row| Ok(row.get_by_name("plugin_name")?))
+            .collect()
     }
 
-    /// Load dispatch metadata for a job (used for idempotent materialization tracking).
-    pub fn get_dispatch_metadata(&self, job_id: i64) -> Result<Option<DispatchMetadata>> {
+    /// Plugins of a job and of the jobs upstream of it, nearest first.
+    fn upstream_plugins(&self, job_id: i64) -> Result<Vec<String>> {
+        let mut plugins = Vec::new();
+        let mut seen = HashSet::new();
+        let mut next = Some(job_id);
+        while let Some(id) = next.filter(|id| seen.insert(*id)) {
+            let row = self.conn.query_optional(
+                "SELECT plugin_name, upstream_job_id FROM cf_processing_queue WHERE id = ?",
+                &[DbValue::from(id)],
+            )?;
+            let Some(row) = row else {
+                break;
+            };
+            plugins.push(row.get_by_name("plugin_name")?);
+            next = row.get_by_name("upstream_job_id")?;
+        }
+        Ok(plugins)
+    }
+
+    /// Get lockfile content from plugin environment.
+    pub fn get_lockfile(&self, env_hash: &str) -> Result<Option<String>> {
         let row = self.conn.query_optional(
-            r#"
-            SELECT file_id, plugin_name, parser_version, parser_fingerprint, sink_config_json
-            FROM cf_processing_queue
-            WHERE id = ?
-            "#,
-            &[DbValue::from(job_id)],
+            "SELECT lockfile_content FROM cf_plugin_environment WHERE hash = ?",
+            &[DbValue::from(env_hash)],
         )?;
-
         row.map(|row| {
-            Ok(DispatchMetadata {
-                file_id: row
-                    .get_by_name("file_id")
-                    .context("Failed to read 'file_id' from cf_processing_queue")?,
-                plugin_name: row
-                    .get_by_name("plugin_name")
-                    .context("Failed to read 'plugin_name' from cf_processing_queue")?,
-                parser_version: row.get_by_name("parser_version").ok().flatten(),
-                parser_fingerprint: row.get_by_name("parser_fingerprint").ok().flatten(),
-                sink_config_json: row.get_by_name("sink_config_json").ok().flatten(),
-            })
+            row.get_by_name("lockfile_content")
+                .context("Failed to read 'lockfile_content' from cf_plugin_environment")
         })
         .transpose()
     }
 
-    /// Load full dispatch data for a job (file path + plugin manifest).
-    pub fn load_dispatch_data(&self, plugin_name: &str, file_id: i64) -> Result<DispatchData> {
-        let row = self.conn.query_optional(
-            r#"
-                SELECT
-                    sf.rel_path as rel_path,
-                    ss.path as scan_root,
-                    ss.exec_path as exec_root,
-                    pm.source_code,
-                    pm.version as parser_version,
-                    pm.env_hash,
-                    pm.artifact_hash,
-                    pm.runtime_kind,
-                    pm.entrypoint,
-                    pm.platform_os,
-                    pm.platform_arch,
-                    pm.signature_verified,
-                    pm.signer_id
-                FROM scout_files sf
-                JOIN scout_sources ss ON ss.id = sf.source_id
-                JOIN cf_plugin_manifest pm ON pm.plugin_name = ? AND pm.status IN (?, ?)
-                WHERE sf.id = ?
-                ORDER BY pm.created_at DESC
-                LIMIT 1
-                "#,
-            &[
-                DbValue::from(plugin_name),
-                DbValue::from(PluginStatus::Active.as_str()),
-                DbValue::from(PluginStatus::Deployed.as_str()),
-                DbValue::from(file_id),
-            ],
-        )?;
-        let row = row.ok_or_else(|| anyhow::anyhow!("Dispatch data missing"))?;
-        DispatchData::from_row(&row)
+    /// Cached hash of the input at `path` (see [`source_hashes`]).
+    pub fn cached_source_hash(&self, path: &str) -> Result<Option<SourceHash>> {
+        source_hashes::lookup(&self.conn, path)
     }
 
-    /// Load scout file mtime/size for generation checks.
-    pub fn load_file_generation(&self, file_id: i64) -> Result<Option<(i64, i64)>> {
-        let row = self.conn.query_optional(
-            "SELECT mtime, size FROM scout_files WHERE id = ?",
-            &[DbValue::from(file_id)],
-        )?;
-        let Some(row) = row else {
-            return Ok(None);
-        };
-        let mtime: i64 = row.get_by_name("mtime")?;
-        let size: i64 = row.get_by_name("size")?;
-        Ok(Some((mtime, size)))
+    pub fn record_source_hash(&self, path: &str, source_hash: &SourceHash) -> Result<()> {
+        source_hashes::record(&self.conn, path, source_hash)
     }
 
-    /// Update pipeline run status for a specific job.
-    pub fn update_pipeline_run_status_for_job(&self, job_id: i64) -> Result<()> {
-        let run_id = self
-            .conn
-            .query_optional(
-                "SELECT pipeline_run_id FROM cf_processing_queue WHERE id = ?",
-                &[DbValue::from(job_id)],
-            )?
-            .and_then(|row| row.get_by_name::<String>("pipeline_run_id").ok());
-        let Some(run_id) = run_id else {
-            return Ok(());
-        };
-        update_pipeline_run_status(&self.conn, &run_id)
+    /// Add a job's drift to its dataset's (see [`schema_drift`]).
+    pub fn record_schema_drift(
+        &self,
+        plugin_name: &str,
+        job_id: i64,
+        drift: &SchemaDrift,
+    ) -> Result<DatasetDrift> {
+        schema_drift::record(&self.conn, plugin_name, job_id, drift)
     }
 
-    /// Mark a pipeline run as running.
-    pub fn set_pipeline_run_running(&self, run_id: &str) -> Result<()> {
-        set_pipeline_run_running(&self.conn, run_id)
+    pub fn schema_drift(&self, plugin_name: &str) -> Result<DatasetDrift> {
+        schema_drift::for_plugin(&self.conn, plugin_name)
     }
 
-    /// Update a pipeline run status based on queued job states.
-    pub fn update_pipeline_run_status(&self, run_id: &str) -> Result<()> {
-        update_pipeline_run_status(&self.conn, run_id)
+    /// Register a committed output in the dataset catalog (see [`dataset_catalog`]).
+    pub fn register_dataset(&self, commit: &DatasetCommit) -> Result<()> {
+        dataset_catalog::register(&self.conn, commit)
     }
 
-    /// Record a completed output materialization (idempotent insert).
-    pub fn insert_output_materialization(&self, record: &OutputMaterialization) -> Result<()> {
-        self.conn.execute(
-            r#"
-                INSERT INTO cf_output_materializations (
-                    materialization_key,
-                    output_target_key,
-                    file_id,
-                    file_mtime,
-                    file_size,
-                    plugin_name,
-                    parser_version,
-                    parser_fingerprint,
-                    output_name,
-                    sink_uri,
-                    sink_mode,
-                    table_name,
-                    schema_hash,
-                    status,
-                    rows,
-                    job_id,
-                    created_at
-                )
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                ON CONFLICT(materialization_key) DO NOTHING
-                "#,
-            &[
-                DbValue::from(record.materialization_key.as_str()),
-                DbValue::from(record.output_target_key.as_str()),
-                DbValue::from(record.file_id),
-                DbValue::from(record.file_mtime),
-                DbValue::from(record.file_size),
-                DbValue::from(record.plugin_name.as_str()),
-                record
-                    .parser_version
-                    .as_deref()
-                    .map(DbValue::from)
-                    .unwrap_or(DbValue::Null),
-                DbValue::from(record.parser_fingerprint.as_str()),
-                DbValue::from(record.output_name.as_str()),
-                DbValue::from(record.sink_uri.as_str()),
-                DbValue::from(record.sink_mode.as_str()),
-                record
-                    .table_name
-                    .as_deref()
-                    .map(DbValue::from)
-                    .unwrap_or(DbValue::Null),
-                record
-                    .schema_hash
-                    .as_deref()
-                    .map(DbValue::from)
-                    .unwrap_or(DbValue::Null),
-                DbValue::from(record.status.as_str()),
-                DbValue::from(record.rows),
-                DbValue::from(record.job_id),
-                DbValue::from(now_millis()),
-            ],
-        )?;
-        Ok(())
-    }
-
-    /// Mark job as failed with outcome details.
-    ///
-    /// `completion_status` should be one of: FAILED, REJECTED
-    pub fn fail_job(&self, job_id: i64, completion_status: &str, error: &str) -> Result<()> {
-        let now = now_millis();
-        self.conn.execute(
-            r#"
-                UPDATE cf_processing_queue
-                SET status = ?,
-                    completion_status = ?,
-                    end_time = ?,
-                    error_message = ?
-                WHERE id = ?
-                "#,
-            &[
-                DbValue::from(ProcessingStatus::Failed.as_str()),
-                DbValue::from(completion_status),
-                DbValue::from(now),
-                DbValue::from(error),
-                DbValue::from(job_id),
-            ],
-        )?;
-        Ok(())
+    pub fn list_datasets(
+        &self,
+        search: Option<&str>,
+        plugin_name: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<DatasetRecord>> {
+        dataset_catalog::list(&self.conn, search, plugin_name, limit)
     }
 
-    /// Mark job as aborted with outcome details.
-    pub fn abort_job(&self, job_id: i64, error: &str) -> Result<()> {
-        let now = now_millis();
-        self.conn.execute(
-            r#"
-                UPDATE cf_processing_queue
-                SET status = ?,
-                    completion_status = ?,
-                    end_time = ?,
-                    error_message = ?
-                WHERE id = ?
-                "#,
-            &[
-                DbValue::from(ProcessingStatus::Aborted.as_str()),
-                DbValue::from(JobStatus::Aborted.as_str()),
-                DbValue::from(now),
-                DbValue::from(error),
-                DbValue::from(job_id),
-            ],
-        )?;
-        Ok(())
+    pub fn describe_dataset(&self, name: &str) -> Result<Option<DatasetRecord>> {
+        dataset_catalog::describe(&self.conn, name)
     }
 
-    /// Mark job as complete if the lease token matches.
-    pub fn complete_job_if_token_matches(
-        &self,
-        job_id: i64,
-        lease_token: &str,
-        completion_status: &str,
-        summary: &str,
-        quarantine_rows: Option<i64>,
-    ) -> Result<bool> {
+    /// Peek at the next job without claiming it.
+    pub fn peek_job(&self) -> Result<Option<ProcessingJob>> {
+        let has_health = self.table_exists("cf_parser_health")?;
         let now = now_millis();
-        let (query, params) = if let Some(rows) = quarantine_rows {
+        let (query, params) = if has_health {
             (
-                r#"
-                    UPDATE cf_processing_queue
-                    SET status = ?,
-                        completion_status = ?,
-                        end_time = ?,
-                        result_summary = ?,
-                        quarantine_rows = ?
-                    WHERE id = ? AND status = ? AND lease_token = ?
-                    "#,
+                format!(
+                    r#"
+                SELECT {columns}
+                FROM cf_processing_queue q
+                LEFT JOIN cf_parser_health ph ON ph.parser_name = q.plugin_name
+                WHERE q.status = ?
+                  AND (q.scheduled_at IS NULL OR q.scheduled_at <= ?)
+                  AND (ph.paused_at IS NULL)
+                ORDER BY q.priority DESC, q.id ASC
+                LIMIT 1
+                "#,
+                    columns = prefixed_columns("q", PROCESSING_JOB_COLUMNS)
+                ),
                 vec![
-                    DbValue::from(ProcessingStatus::Completed.as_str()),
-                    DbValue::from(completion_status),
+                    DbValue::from(ProcessingStatus::Queued.as_str()),
                     DbValue::from(now),
-                    DbValue::from(summary),
-                    DbValue::from(rows),
-                    DbValue::from(job_id),
-                    DbValue::from(ProcessingStatus::Running.as_str()),
-                    DbValue::from(lease_token),
                 ],
             )
         } else {
             (
-                r#"
-                    UPDATE cf_processing_queue
-                    SET status = ?,
-                        completion_status = ?,
-                        end_time = ?,
-                        result_summary = ?
-                    WHERE id = ? AND status = ? AND lease_token = ?
-                    "#,
+                format!(
+                    r#"
+                SELECT {columns}
+                FROM cf_processing_queue
+                WHERE status = ?
+                  AND (scheduled_at IS NULL OR scheduled_at <= ?)
+                ORDER BY priority DESC, id ASC
+                LIMIT 1
+                "#,
+                    columns = column_list(PROCESSING_JOB_COLUMNS)
+                ),
                 vec![
-                    DbValue::from(ProcessingStatus::Completed.as_str()),
-                    DbValue::from(completion_status),
+                    DbValue::from(ProcessingStatus::Queued.as_str()),
                     DbValue::from(now),
-                    DbValue::from(summary),
-                    DbValue::from(job_id),
-                    DbValue::from(ProcessingStatus::Running.as_str()),
-                    DbValue::from(lease_token),
                 ],
             )
         };
-        let affected = self.conn.execute(query, &params)?;
-        Ok(affected > 0)
+
+        let row = self.conn.query_optional(&query, &params)?;
+        Ok(row.map(|row| ProcessingJob::from_row(&row)).transpose()?)
     }
 
-    /// Mark job as failed if the lease token matches.
-    pub fn fail_job_if_token_matches(
-        &self,
-        job_id: i64,
-        lease_token: &str,
-        completion_status: &str,
-        error: &str,
-    ) -> Result<bool> {
+    /// Atomically pop a job from the queue.
+    pub fn pop_job(&self) -> Result<Option<ProcessingJob>> {
+        let has_health = self.table_exists("cf_parser_health")?;
         let now = now_millis();
-        let affected = self.conn.execute(
-            r#"
+        let lease_expires_at = now.saturating_add(DEFAULT_DISPATCH_LEASE_TTL_MS);
+        let (query, params) = if has_health {
+            (
+                format!(
+                    r#"
                 UPDATE cf_processing_queue
                 SET status = ?,
-                    completion_status = ?,
-                    end_time = ?,
-                    error_message = ?
-                WHERE id = ? AND status = ? AND lease_token = ?
+                    claim_time = ?,
+                    lease_expires_at = ?,
+                    lease_token = NULL,
+                    lease_owner = NULL,
+                    dispatch_ack_at = NULL
+                WHERE id = (
+                    SELECT q.id
+                    FROM cf_processing_queue q
+                    LEFT JOIN cf_parser_health ph ON ph.parser_name = q.plugin_name
+                    WHERE q.status = ?
+                      AND (q.scheduled_at IS NULL OR q.scheduled_at <= ?)
+                      AND (ph.paused_at IS NULL)
+                    ORDER BY q.priority DESC, q.id ASC
+                    LIMIT 1
+                )
+                RETURNING {columns}
                 "#,
-            &[
-                DbValue::from(ProcessingStatus::Failed.as_str()),
-                DbValue::from(completion_status),
-                DbValue::from(now),
-                DbValue::from(error),
-                DbValue::from(job_id),
-                DbValue::from(ProcessingStatus::Running.as_str()),
-                DbValue::from(lease_token),
-            ],
-        )?;
-        Ok(affected > 0)
-    }
-
-    /// Mark job as aborted if the lease token matches.
-    pub fn abort_job_if_token_matches(
-        &self,
-        job_id: i64,
-        lease_token: &str,
-        error: &str,
-    ) -> Result<bool> {
-        let now = now_millis();
-        let affected = self.conn.execute(
-            r#"
+                    columns = column_list(PROCESSING_JOB_COLUMNS)
+                ),
+                vec![
+                    DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                    DbValue::from(now.clone()),
+                    DbValue::from(lease_expires_at),
+                    DbValue::from(ProcessingStatus::Queued.as_str()),
+                    DbValue::from(now),
+                ],
+            )
+        } else {
+            (
+                format!(
+                    r#"
                 UPDATE cf_processing_queue
                 SET status = ?,
-                    completion_status = ?,
-                    end_time = ?,
-                    error_message = ?
-                WHERE id = ? AND status = ? AND lease_token = ?
+                    claim_time = ?,
+                    lease_expires_at = ?,
+                    lease_token = NULL,
+                    lease_owner = NULL,
+                    dispatch_ack_at = NULL
+                WHERE id = (
+                    SELECT id
+                    FROM cf_processing_queue
+                    WHERE status = ?
+                      AND (scheduled_at IS NULL OR scheduled_at <= ?)
+                    ORDER BY priority DESC, id ASC
+                    LIMIT 1
+                )
+                RETURNING {columns}
                 "#,
-            &[
-                DbValue::from(ProcessingStatus::Aborted.as_str()),
-                DbValue::from(JobStatus::Aborted.as_str()),
-                DbValue::from(now),
-                DbValue::from(error),
-                DbValue::from(job_id),
-                DbValue::from(ProcessingStatus::Running.as_str()),
-                DbValue::from(lease_token),
-            ],
-        )?;
-        Ok(affected > 0)
-    }
+                    columns = column_list(PROCESSING_JOB_COLUMNS)
+                ),
+                vec![
+                    DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                    DbValue::from(now.clone()),
+                    DbValue::from(lease_expires_at),
+                    DbValue::from(ProcessingStatus::Queued.as_str()),
+                    DbValue::from(now),
+                ],
+            )
+        };
 
-    /// Requeue a job.
-    /// Clears terminal fields (completion_status, end_time, result_summary, error_message)
-    /// when transitioning back to QUEUED state.
-    pub fn requeue_job(&self, job_id: i64) -> Result<()> {
-        let row = self.conn.query_optional(
-            "SELECT retry_count FROM cf_processing_queue WHERE id = ?",
-            &[DbValue::from(job_id)],
-        )?;
+        let row = self.conn.query_optional(&query, &params)?;
 
-        if let Some(row) = row {
-            let retry_count: i32 = row.get_by_name("retry_count")?;
-            if retry_count >= MAX_RETRY_COUNT {
-                self.move_to_dead_letter(
-                    job_id,
-                    DeadLetterReason::MaxRetriesExceeded.as_str(),
-                    DeadLetterReason::MaxRetriesExceeded,
-                )?;
-                return Ok(());
-            }
+        Ok(row.map(|row| ProcessingJob::from_row(&row)).transpose()?)
+    }
+
+    /// Atomically lease up to `limit` jobs from the queue for dispatch.
+    pub fn lease_jobs_for_dispatch(
+        &self,
+        limit: usize,
+        now: i64,
+        ttl_ms: i64,
+    ) -> Result<Vec<ProcessingJob>> {
+        if limit == 0 {
+            return Ok(Vec::new());
         }
+        let limit: i64 = limit
+            .try_into()
+            .context("lease_jobs_for_dispatch limit overflow")?;
+        let has_health = self.table_exists("cf_parser_health")?;
+        let lease_expires_at = now.saturating_add(ttl_ms);
 
-        self.conn.execute(
-            r#"
+        let (query, params) = if has_health {
+            (
+                format!(
+                    r#"
+                WITH to_claim AS (
+                    SELECT q.id
+                    FROM cf_processing_queue q
+                    LEFT JOIN cf_parser_health ph ON ph.parser_name = q.plugin_name
+                    WHERE q.status = ?
+                      AND (q.scheduled_at IS NULL OR q.scheduled_at <= ?)
+                      AND (ph.paused_at IS NULL)
+                    ORDER BY q.priority DESC, q.id ASC
+                    LIMIT ?
+                )
                 UPDATE cf_processing_queue
                 SET status = ?,
-                    completion_status = NULL,
-                    claim_time = NULL,
+                    claim_time = ?,
+                    lease_expires_at = ?,
                     lease_token = NULL,
                     lease_owner = NULL,
-                    lease_expires_at = NULL,
-                    dispatch_ack_at = NULL,
-                    end_time = NULL,
-                    result_summary = NULL,
-                    error_message = NULL,
-                    scheduled_at = ?,
-                    retry_count = retry_count + 1
-                WHERE id = ?
+                    dispatch_ack_at = NULL
+                WHERE id IN (SELECT id FROM to_claim)
+                RETURNING {columns}
                 "#,
-            &[
-                DbValue::from(ProcessingStatus::Queued.as_str()),
-                DbValue::from(now_millis()),
-                DbValue::from(job_id),
-            ],
-        )?;
-        Ok(())
-    }
-
-    /// Defer a job without incrementing retry count.
-    /// Clears terminal fields when transitioning back to QUEUED state.
-    pub fn defer_job(
-        &self,
-        job_id: i64,
-        scheduled_at: i64,
-        reason: Option<&str>,
-    ) -> Result<()> {
-        self.conn.execute(
-            r#"
+                    columns = column_list(PROCESSING_JOB_COLUMNS)
+                ),
+                vec![
+                    DbValue::from(ProcessingStatus::Queued.as_str()),
+                    DbValue::from(now),
+                    DbValue::from(limit),
+                    DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                    DbValue::from(now),
+                    DbValue::from(lease_expires_at),
+                ],
+            )
+        } else {
+            (
+                format!(
+                    r#"
+                WITH to_claim AS (
+                    SELECT id
+                    FROM cf_processing_queue
+                    WHERE status = ?
+                      AND (scheduled_at IS NULL OR scheduled_at <= ?)
+                    ORDER BY priority DESC, id ASC
+                    LIMIT ?
+                )
                 UPDATE cf_processing_queue
                 SET status = ?,
-                    completion_status = NULL,
-                    claim_time = NULL,
+                    claim_time = ?,
+                    lease_expires_at = ?,
                     lease_token = NULL,
                     lease_owner = NULL,
-                    lease_expires_at = NULL,
-                    dispatch_ack_at = NULL,
-                    end_time = NULL,
-                    result_summary = NULL,
-                    scheduled_at = ?,
-                    error_message = ?
-                WHERE id = ?
+                    dispatch_ack_at = NULL
+                WHERE id IN (SELECT id FROM to_claim)
+                RETURNING {columns}
                 "#,
+                    columns = column_list(PROCESSING_JOB_COLUMNS)
+                ),
+                vec![
+                    DbValue::from(ProcessingStatus::Queued.as_str()),
+                    DbValue::from(now),
+                    DbValue::from(limit),
+                    DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                    DbValue::from(now),
+                    DbValue::from(lease_expires_at),
+                ],
+            )
+        };
+
+        let rows = self.conn.query_all(&query, &params)?;
+        let mut jobs = rows
+            .into_iter()
+            .map(|row| ProcessingJob::from_row(&row))
+            .collect::<Result<Vec<_>, _>>()?;
+        jobs.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.id.cmp(&b.id)));
+        Ok(jobs)
+    }
+
+    /// Lease queued jobs of `plugin_name` whose input file is at most
+    /// `max_file_bytes`, to ride along in a batched DISPATCH.
+    ///
+    /// Same claim semantics as [`Self::lease_jobs_for_dispatch`]. Jobs whose
+    /// file size is unknown (no `scout_files` row) and incremental jobs (with
+    /// a byte range) are never batched.
+    pub fn lease_batch_jobs_for_dispatch(
+        &self,
+        plugin_name: &str,
+        max_file_bytes: u64,
+        limit: usize,
+        now: i64,
+        ttl_ms: i64,
+    ) -> Result<Vec<ProcessingJob>> {
+        if limit == 0 || !self.table_exists("scout_files")? {
+            return Ok(Vec::new());
+        }
+        let limit: i64 = limit
+            .try_into()
+            .context("lease_batch_jobs_for_dispatch limit overflow")?;
+        let max_file_bytes = i64::try_from(max_file_bytes).unwrap_or(i64::MAX);
+        let lease_expires_at = now.saturating_add(ttl_ms);
+
+        let query = format!(
+            r#"
+            WITH to_claim AS (
+                SELECT q.id
+                FROM cf_processing_queue q
+                JOIN scout_files sf ON sf.id = q.file_id
+                WHERE q.status = ?
+                  AND (q.scheduled_at IS NULL OR q.scheduled_at <= ?)
+                  AND q.plugin_name = ?
+                  AND q.range_start IS NULL
+                  AND sf.size <= ?
+                ORDER BY q.priority DESC, q.id ASC
+                LIMIT ?
+            )
+            UPDATE cf_processing_queue
+            SET status = ?,
+                claim_time = ?,
+                lease_expires_at = ?,
+                lease_token = NULL,
+                lease_owner = NULL,
+                dispatch_ack_at = NULL
+            WHERE id IN (SELECT id FROM to_claim)
+            RETURNING {columns}
+            "#,
+            columns = column_list(PROCESSING_JOB_COLUMNS)
+        );
+        let rows = self.conn.query_all(
+            &query,
             &[
                 DbValue::from(ProcessingStatus::Queued.as_str()),
-                DbValue::from(scheduled_at),
-                DbValue::from(reason),
-                DbValue::from(job_id),
+                DbValue::from(now),
+                DbValue::from(plugin_name),
+                DbValue::from(max_file_bytes),
+                DbValue::from(limit),
+                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                DbValue::from(now),
+                DbValue::from(lease_expires_at),
             ],
         )?;
-        Ok(())
+        let mut jobs = rows
+            .into_iter()
+            .map(|row| ProcessingJob::from_row(&row))
+            .collect::<Result<Vec<_>, _>>()?;
+        jobs.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.id.cmp(&b.id)));
+        Ok(jobs)
     }
 
-    /// Defer a job if the lease token matches and status is DISPATCHING.
-    /// Clears terminal fields when transitioning back to QUEUED state.
-    pub fn defer_job_if_token_matches(
+    /// Persist the lease token/owner for a dispatching job.
+    pub fn set_dispatch_lease(
         &self,
         job_id: i64,
         lease_token: &str,
-        scheduled_at: i64,
-        reason: Option<&str>,
+        lease_owner: &str,
     ) -> Result<bool> {
         let affected = self.conn.execute(
             r#"
                 UPDATE cf_processing_queue
-                SET status = ?,
-                    completion_status = NULL,
-                    claim_time = NULL,
-                    lease_token = NULL,
-                    lease_owner = NULL,
-                    lease_expires_at = NULL,
-                    dispatch_ack_at = NULL,
-                    end_time = NULL,
-                    result_summary = NULL,
-                    scheduled_at = ?,
-                    error_message = ?
-                WHERE id = ? AND status = ? AND lease_token = ?
+                SET lease_token = ?, lease_owner = ?
+                WHERE id = ? AND status = ?
                 "#,
             &[
-                DbValue::from(ProcessingStatus::Queued.as_str()),
-                DbValue::from(scheduled_at),
-                DbValue::from(reason),
+                DbValue::from(lease_token),
+                DbValue::from(lease_owner),
                 DbValue::from(job_id),
                 DbValue::from(ProcessingStatus::Dispatching.as_str()),
-                DbValue::from(lease_token),
             ],
         )?;
         Ok(affected > 0)
     }
 
-    /// Mark job as failed if the lease token matches and status is DISPATCHING.
-    pub fn fail_job_if_token_matches_dispatching(
+    /// Acknowledge a dispatch lease and transition to RUNNING.
+    pub fn ack_dispatch(
         &self,
         job_id: i64,
         lease_token: &str,
-        completion_status: &str,
-        error: &str,
+        lease_owner: &str,
+        now: i64,
     ) -> Result<bool> {
-        let now = now_millis();
         let affected = self.conn.execute(
             r#"
                 UPDATE cf_processing_queue
                 SET status = ?,
-                    completion_status = ?,
-                    end_time = ?,
-                    error_message = ?
+                    dispatch_ack_at = ?,
+                    lease_owner = ?
                 WHERE id = ? AND status = ? AND lease_token = ?
                 "#,
             &[
-                DbValue::from(ProcessingStatus::Failed.as_str()),
-                DbValue::from(completion_status),
+                DbValue::from(ProcessingStatus::Running.as_str()),
                 DbValue::from(now),
-                DbValue::from(error),
+                DbValue::from(lease_owner),
                 DbValue::from(job_id),
                 DbValue::from(ProcessingStatus::Dispatching.as_str()),
                 DbValue::from(lease_token),
@@ -1434,1150 +1979,3596 @@ or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
         Ok(affected > 0)
     }
 
-    /// Schedule a retry for a failed job with backoff.
-    /// Clears terminal fields (except error_message which stores the retry reason)
-    /// when transitioning back to QUEUED state.
-    pub fn schedule_retry(
-        &self,
-        job_id: i64,
-        next_retry_count: i32,
-        error: &str,
-        scheduled_at: i64,
-    ) -> Result<()> {
-        self.conn.execute(
+    /// Requeue dispatches whose lease has expired.
+    pub fn requeue_expired_dispatches(&self, now: i64) -> Result<usize> {
+        let affected = self.conn.execute(
             r#"
                 UPDATE cf_processing_queue
                 SET status = ?,
-                    completion_status = NULL,
-                    retry_count = ?,
                     claim_time = NULL,
                     lease_token = NULL,
                     lease_owner = NULL,
                     lease_expires_at = NULL,
-                    dispatch_ack_at = NULL,
-                    end_time = NULL,
-                    result_summary = NULL,
-                    scheduled_at = ?,
-                    error_message = ?
-                WHERE id = ?
+                    dispatch_ack_at = NULL
+                WHERE status = ? AND lease_expires_at IS NOT NULL AND lease_expires_at <= ?
                 "#,
             &[
                 DbValue::from(ProcessingStatus::Queued.as_str()),
-                DbValue::from(next_retry_count),
-                DbValue::from(scheduled_at),
-                DbValue::from(error),
-                DbValue::from(job_id),
+                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                DbValue::from(now),
             ],
         )?;
-        Ok(())
+        Ok(affected as usize)
     }
 
-    /// Queue stats for monitoring.
-    pub fn stats(&self) -> Result<QueueStats> {
-        let row = self.conn.query_one(
-            &format!(
-                r#"
-                SELECT
-                    SUM(CASE WHEN status = '{queued}' THEN 1 ELSE 0 END) AS queued,
-                    SUM(CASE WHEN status = '{running}' THEN 1 ELSE 0 END)
-                        + SUM(CASE WHEN status = '{dispatching}' THEN 1 ELSE 0 END) AS running,
-                    SUM(CASE WHEN status = '{completed}' THEN 1 ELSE 0 END) AS completed,
-                    SUM(CASE WHEN status = '{failed}' THEN 1 ELSE 0 END) AS failed
-                FROM cf_processing_queue
-                "#,
-                queued = ProcessingStatus::Queued.as_str(),
-                running = ProcessingStatus::Running.as_str(),
-                dispatching = ProcessingStatus::Dispatching.as_str(),
-                completed = ProcessingStatus::Completed.as_str(),
-                failed = ProcessingStatus::Failed.as_str(),
-            ),
-            &[],
-        )?;
-
-        Ok(QueueStats {
-            queued: row.get_by_name("queued")?,
-            running: row.get_by_name("running")?,
-            completed: row.get_by_name("completed")?,
-            failed: row.get_by_name("failed")?,
-        })
+    fn column_exists(&self, table: &str, column: &str) -> Result<bool> {
+        Ok(self.conn.column_exists(table, column)?)
     }
 
-    /// Initialize dead-letter, health, quarantine tables.
-    pub fn init_error_handling_schema(&self) -> Result<()> {
-        let sql = match self.conn.backend_name() {
-            "DuckDB" => {
-                r#"
-                CREATE SEQUENCE IF NOT EXISTS seq_cf_dead_letter;
-                CREATE SEQUENCE IF NOT EXISTS seq_cf_quarantine;
-                CREATE SEQUENCE IF NOT EXISTS seq_cf_job_schema_mismatch;
-
-                CREATE TABLE IF NOT EXISTS cf_dead_letter (
-                    id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_dead_letter'),
-                    original_job_id BIGINT NOT NULL,
-                    file_id BIGINT,
-                    input_file TEXT,
-                    plugin_name TEXT NOT NULL,
-                    error_message TEXT,
-                    retry_count INTEGER NOT NULL,
-                    moved_at BIGINT NOT NULL,
-                    reason TEXT
-                );
-
-                CREATE TABLE IF NOT EXISTS cf_parser_health (
-                    parser_name TEXT PRIMARY KEY,
-                    total_executions INTEGER NOT NULL DEFAULT 0,
-                    successful_executions INTEGER NOT NULL DEFAULT 0,
-                    consecutive_failures INTEGER NOT NULL DEFAULT 0,
-                    last_failure_reason TEXT,
-                    paused_at BIGINT,
-                    created_at BIGINT NOT NULL,
-                    updated_at BIGINT NOT NULL
-                );
-
-                CREATE TABLE IF NOT EXISTS cf_quarantine (
-                    id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_quarantine'),
-                    job_id BIGINT NOT NULL,
-                    row_index BIGINT NOT NULL,
-                    error_reason TEXT NOT NULL,
-                    raw_data BLOB,
-                    created_at BIGINT NOT NULL
-                );
-
-                CREATE TABLE IF NOT EXISTS cf_job_schema_mismatch (
-                    id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_job_schema_mismatch'),
-                    job_id BIGINT NOT NULL,
-                    output_name TEXT NOT NULL,
-                    mismatch_kind TEXT NOT NULL,
-                    expected_name TEXT,
-                    actual_name TEXT,
-                    expected_type TEXT,
-                    actual_type TEXT,
-                    expected_index INTEGER,
-                    actual_index INTEGER,
-                    created_at BIGINT NOT NULL
-                );
+    fn table_exists(&self, table: &str) -> Result<bool> {
+        Ok(self.conn.table_exists(table)?)
+    }
 
-                CREATE INDEX IF NOT EXISTS ix_schema_mismatch_job
-                    ON cf_job_schema_mismatch(job_id);
-                "#
+    fn require_columns(&self, table: &str, columns: &[&str]) -> Result<()> {
+        let mut missing = Vec::new();
+        for column in columns {
+            if !self.column_exists(table, column)? {
+                missing.push(*column);
             }
-            _ => {
-                r#"
-                CREATE TABLE IF NOT EXISTS cf_dead_letter (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    original_job_id INTEGER NOT NULL,
-                    file_id INTEGER,
-                    input_file TEXT,
-                    plugin_name TEXT NOT NULL,
-                    error_message TEXT,
-                    retry_count INTEGER NOT NULL,
-                    moved_at INTEGER NOT NULL,
-                    reason TEXT
-                );
-
-                CREATE TABLE IF NOT EXISTS cf_parser_health (
-                    parser_name TEXT PRIMARY KEY,
-                    total_executions INTEGER NOT NULL DEFAULT 0,
-                    successful_executions INTEGER NOT NULL DEFAULT 0,
-                    consecutive_failures INTEGER NOT NULL DEFAULT 0,
-                    last_failure_reason TEXT,
-                    paused_at INTEGER,
-                    created_at INTEGER NOT NULL,
-                    updated_at INTEGER NOT NULL
-                );
-
-                CREATE TABLE IF NOT EXISTS cf_quarantine (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    job_id INTEGER NOT NULL,
-                    row_index INTEGER NOT NULL,
-                    error_reason TEXT NOT NULL,
-                    raw_data BLOB,
-                    created_at INTEGER NOT NULL
-                );
-
-                CREATE TABLE IF NOT EXISTS cf_job_schema_mismatch (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    job_id INTEGER NOT NULL,
-                    output_name TEXT NOT NULL,
-                    mismatch_kind TEXT NOT NULL,
-                    expected_name TEXT,
-                    actual_name TEXT,
-                    expected_type TEXT,
-                    actual_type TEXT,
-                    expected_index INTEGER,
-                    actual_index INTEGER,
-                    created_at INTEGER NOT NULL
-                );
+        }
 
-                CREATE INDEX IF NOT EXISTS ix_schema_mismatch_job
-                    ON cf_job_schema_mismatch(job_id);
-                "#
-            }
-        };
+        if missing.is_empty() {
+            return Ok(());
+        }
 
-        self.conn
-            .execute(sql, &[])
-            .context("Failed to initialize error handling schema")?;
-        Ok(())
+        anyhow::bail!(
+            "Database schema for '{}' is missing columns: {}. \
+Manual reset required. Delete the state store (default: ~/.casparian_flow/state.sqlite) \
+or set CASPARIAN_DEV_ALLOW_RESET=1 to allow destructive reset (pre-v1 only).",
+            table,
+            missing.join(", ")
+        );
     }
 
-    pub fn record_schema_mismatch(&self, job_id: i64, mismatch: &SchemaMismatch) -> Result<()> {
+    /// Mark job as complete with outcome details.
+    ///
+    /// `completion_status` should be one of: SUCCESS, PARTIAL_SUCCESS, COMPLETED_WITH_WARNINGS
+    pub fn complete_job(
+        &self,
+        job_id: i64,
+        completion_status: &str,
+        summary: &str,
+        quarantine_rows: Option<i64>,
+    ) -> Result<()> {
         let now = now_millis();
-
-        for name in &mismatch.missing_columns {
-            self.insert_schema_mismatch_row(
-                job_id,
-                &mismatch.output_name,
-                "missing_column",
-                Some(name.as_str()),
-                None,
-                None,
-                None,
-                None,
-                None,
-                now.clone(),
+        if let Some(rows) = quarantine_rows {
+            self.conn.execute(
+                r#"
+                    UPDATE cf_processing_queue
+                    SET status = ?,
+                        completion_status = ?,
+                        end_time = ?,
+                        result_summary = ?,
+                        quarantine_rows = ?
+                    WHERE id = ?
+                    "#,
+                &[
+                    DbValue::from(ProcessingStatus::Completed.as_str()),
+                    DbValue::from(completion_status),
+                    DbValue::from(now),
+                    DbValue::from(summary),
+                    DbValue::from(rows),
+                    DbValue::from(job_id),
+                ],
+            )?;
+        } else {
+            self.conn.execute(
+                r#"
+                    UPDATE cf_processing_queue
+                    SET status = ?,
+                        completion_status = ?,
+                        end_time = ?,
+                        result_summary = ?
+                    WHERE id = ?
+                    "#,
+                &[
+                    DbValue::from(ProcessingStatus::Completed.as_str()),
+                    DbValue::from(completion_status),
+                    DbValue::from(now),
+                    DbValue::from(summary),
+                    DbValue::from(job_id),
+                ],
             )?;
         }
+        Ok(())
+    }
 
-        for name in &mismatch.extra_columns {
-            self.insert_schema_mismatch_row(
-                job_id,
-                &mismatch.output_name,
-                "extra_column",
-                None,
-                Some(name.as_str()),
-                None,
-                None,
-                None,
-                None,
-                now.clone(),
-            )?;
-        }
-
-        for order in &mismatch.order_mismatches {
-            let expected_index = i64::try_from(order.index).map_err(|_| {
-                anyhow::anyhow!(
-                    "schema mismatch index overflow for job {} output '{}'",
-                    job_id,
-                    mismatch.output_name
-                )
-            })?;
-            self.insert_schema_mismatch_row(
-                job_id,
-                &mismatch.output_name,
-                "order_mismatch",
-                Some(order.expected.as_str()),
-                Some(order.actual.as_str()),
-                None,
-                None,
-                Some(expected_index),
-                None,
-                now.clone(),
-            )?;
-        }
-
-        for type_mismatch in &mismatch.type_mismatches {
-            let expected_type = type_mismatch.expected.to_string();
-            let actual_type = observed_type_label(&type_mismatch.actual);
-            self.insert_schema_mismatch_row(
-                job_id,
-                &mismatch.output_name,
-                "type_mismatch",
-                Some(type_mismatch.name.as_str()),
-                None,
-                Some(expected_type.as_str()),
-                Some(actual_type.as_str()),
-                None,
-                None,
-                now.clone(),
-            )?;
-        }
-
-        Ok(())
-    }
-
-    fn insert_schema_mismatch_row(
+    /// Persist dispatch metadata for a job (parser version/hash + sink config snapshot).
+    pub fn record_dispatch_metadata(
         &self,
         job_id: i64,
-        output_name: &str,
-        mismatch_kind: &str,
-        expected_name: Option<&str>,
-        actual_name: Option<&str>,
-        expected_type: Option<&str>,
-        actual_type: Option<&str>,
-        expected_index: Option<i64>,
-        actual_index: Option<i64>,
-        created_at: i64,
+        parser_version: &str,
+        parser_fingerprint: &str,
+        sink_config_json: &str,
     ) -> Result<()> {
-        self.conn
-            .execute(
-                r#"
-                INSERT INTO cf_job_schema_mismatch
-                    (job_id, output_name, mismatch_kind, expected_name, actual_name, expected_type, actual_type, expected_index, actual_index, created_at)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET parser_version = ?, parser_fingerprint = ?, sink_config_json = ?
+                WHERE id = ?
                 "#,
-                &[
-                    DbValue::from(job_id),
-                    DbValue::from(output_name),
-                    DbValue::from(mismatch_kind),
-                    DbValue::from(expected_name),
-                    DbValue::from(actual_name),
-                    DbValue::from(expected_type),
-                    DbValue::from(actual_type),
-                    DbValue::from(expected_index),
-                    DbValue::from(actual_index),
-                    DbValue::from(created_at),
-                ],
-            )
-            ?;
+            &[
+                DbValue::from(parser_version),
+                DbValue::from(parser_fingerprint),
+                DbValue::from(sink_config_json),
+                DbValue::from(job_id),
+            ],
+        )?;
         Ok(())
     }
 
-    /// Move a job to dead letter.
-    pub fn move_to_dead_letter(
-        &self,
-        job_id: i64,
-        error: &str,
-        reason: DeadLetterReason,
-    ) -> Result<()> {
+    /// Load dispatch metadata for a job (used for idempotent materialization tracking).
+    pub fn get_dispatch_metadata(&self, job_id: i64) -> Result<Option<DispatchMetadata>> {
         let row = self.conn.query_optional(
             r#"
-                SELECT file_id, input_file, plugin_name, retry_count
-                FROM cf_processing_queue
-                WHERE id = ?
-                "#,
+            SELECT file_id, plugin_name, parser_version, parser_fingerprint, sink_config_json
+            FROM cf_processing_queue
+            WHERE id = ?
+            "#,
             &[DbValue::from(job_id)],
         )?;
 
-        let Some(row) = row else {
-            return Ok(());
-        };
-
-        let file_id: i64 = row.get_by_name("file_id")?;
-        let input_file: Option<String> = row.get_by_name("input_file")?;
-        let plugin_name: String = row.get_by_name("plugin_name")?;
-        let retry_count: i32 = row.get_by_name("retry_count")?;
-
-        let now = now_millis();
-        let full_error = format!("{}: {}", reason.as_str(), error);
-        self.conn
-            .execute(
-                r#"
-                INSERT INTO cf_dead_letter (original_job_id, file_id, input_file, plugin_name, error_message, retry_count, moved_at, reason)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-                "#,
-                &[
-                    DbValue::from(job_id),
-                    DbValue::from(file_id),
-                    DbValue::from(input_file),
-                    DbValue::from(plugin_name),
-                    DbValue::from(full_error.as_str()),
-                    DbValue::from(retry_count),
-                    DbValue::from(now.clone()),
-                    DbValue::from(reason.as_str()),
-                ],
-            )
-            ?;
+        row.map(|row| {
+            Ok(DispatchMetadata {
+                file_id: row
+                    .get_by_name("file_id")
+                    .context("Failed to read 'file_id' from cf_processing_queue")?,
+                plugin_name: row
+                    .get_by_name("plugin_name")
+                    .context("Failed to read 'plugin_name' from cf_processing_queue")?,
+                parser_version: row.get_by_name("parser_version").ok().flatten(),
+                parser_fingerprint: row.get_by_name("parser_fingerprint").ok().flatten(),
+                sink_config_json: row.get_by_name("sink_config_json").ok().flatten(),
+            })
+        })
+        .transpose()
+    }
 
-        // Mark the job as FAILED with FAILED completion_status (dead-lettered)
-        self.conn.execute(
+    /// Load full dispatch data for a job (file path + plugin manifest).
+    ///
+    /// Uses the job's `pinned_version` when set (which may be a superseded
+    /// version), otherwise the latest active version of the plugin.
+    pub fn load_dispatch_data(
+        &self,
+        job_id: i64,
+        plugin_name: &str,
+        file_id: i64,
+    ) -> Result<DispatchData> {
+        let row = self.conn.query_optional(
             r#"
-                UPDATE cf_processing_queue
-                SET status = ?,
-                    completion_status = ?,
-                    end_time = ?,
-                    error_message = ?
-                WHERE id = ?
+                SELECT
+                    sf.rel_path as rel_path,
+                    ss.path as scan_root,
+                    ss.exec_path as exec_root,
+                    pm.source_code,
+                    pm.version as parser_version,
+                    pm.env_hash,
+                    pm.artifact_hash,
+                    pm.runtime_kind,
+                    pm.entrypoint,
+                    pm.platform_os,
+                    pm.platform_arch,
+                    pm.signature_verified,
+                    pm.signer_id,
+                    pm.system_requirements,
+                    q.force_rerun,
+                    q.range_start,
+                    q.range_end,
+                    q.input_file,
+                    q.upstream_job_id
+                FROM scout_files sf
+                JOIN scout_sources ss ON ss.id = sf.source_id
+                JOIN cf_processing_queue q ON q.id = ?
+                JOIN cf_plugin_manifest pm ON pm.plugin_name = ? AND (
+                    (q.pinned_version IS NULL AND pm.status IN (?, ?))
+                    OR (pm.version = q.pinned_version AND pm.status IN (?, ?, ?))
+                )
+                WHERE sf.id = ?
+                ORDER BY pm.created_at DESC
+                LIMIT 1
                 "#,
             &[
-                DbValue::from(ProcessingStatus::Failed.as_str()),
-                DbValue::from(JobStatus::Failed.as_str()),
-                DbValue::from(now_millis()),
-                DbValue::from(full_error.as_str()),
                 DbValue::from(job_id),
+                DbValue::from(plugin_name),
+                DbValue::from(PluginStatus::Active.as_str()),
+                DbValue::from(PluginStatus::Deployed.as_str()),
+                DbValue::from(PluginStatus::Active.as_str()),
+                DbValue::from(PluginStatus::Deployed.as_str()),
+                DbValue::from(PluginStatus::Superseded.as_str()),
+                DbValue::from(file_id),
             ],
         )?;
+        let row = row.ok_or_else(|| anyhow::anyhow!("Dispatch data missing"))?;
+        DispatchData::from_row(&row)
+    }
 
-        Ok(())
+    /// Load scout file mtime/size for generation checks.
+    pub fn load_file_generation(&self, file_id: i64) -> Result<Option<(i64, i64)>> {
+        let row = self.conn.query_optional(
+            "SELECT mtime, size FROM scout_files WHERE id = ?",
+            &[DbValue::from(file_id)],
+        )?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let mtime: i64 = row.get_by_name("mtime")?;
+        let size: i64 = row.get_by_name("size")?;
+        Ok(Some((mtime, size)))
     }
 
-    pub fn get_dead_letter_jobs(&self, limit: i64) -> Result<Vec<DeadLetterJob>> {
-        let rows = self.conn.query_all(
-            &format!(
-                "SELECT {} FROM cf_dead_letter ORDER BY moved_at DESC LIMIT ?",
-                column_list(DEAD_LETTER_COLUMNS)
-            ),
-            &[DbValue::from(limit)],
+    /// Load the root path of the scout source a file was scanned from.
+    pub fn load_source_root(&self, file_id: i64) -> Result<Option<String>> {
+        let row = self.conn.query_optional(
+            r#"
+            SELECT s.path
+            FROM scout_files f
+            JOIN scout_sources s ON s.id = f.source_id
+            WHERE f.id = ?
+            "#,
+            &[DbValue::from(file_id)],
         )?;
-        rows.iter()
-            .map(DeadLetterJob::from_row)
-            .collect::<Result<_, _>>()
-            .map_err(Into::into)
+        row.map(|row| row.get::<String>(0).map_err(Into::into))
+            .transpose()
     }
 
-    pub fn get_dead_letter_jobs_by_plugin(
-        &self,
-        plugin: &str,
-        limit: i64,
-    ) -> Result<Vec<DeadLetterJob>> {
+    /// Load the tags attached to a scout file, sorted.
+    pub fn load_file_tags(&self, file_id: i64) -> Result<Vec<String>> {
         let rows = self.conn.query_all(
-            &format!(
-                "SELECT {} FROM cf_dead_letter WHERE plugin_name = ? ORDER BY moved_at DESC LIMIT ?",
-                column_list(DEAD_LETTER_COLUMNS)
-            ),
-            &[DbValue::from(plugin), DbValue::from(limit)],
+            "SELECT DISTINCT tag FROM scout_file_tags WHERE file_id = ? ORDER BY tag",
+            &[DbValue::from(file_id)],
         )?;
         rows.iter()
-            .map(DeadLetterJob::from_row)
+            .map(|row| row.get::<String>(0))
             .collect::<Result<_, _>>()
             .map_err(Into::into)
     }
 
-    pub fn replay_dead_letter(&self, dead_letter_id: i64) -> Result<i64> {
-        let row = self.conn.query_optional(
-            "SELECT original_job_id, file_id, input_file, plugin_name FROM cf_dead_letter WHERE id = ?",
-            &[DbValue::from(dead_letter_id)],
-        )?;
-        let Some(row) = row else {
-            return Ok(0);
-        };
-
-        let file_id: Option<i64> = row.get_by_name("file_id")?;
-        let input_file: Option<String> = row.get_by_name("input_file")?;
-        let plugin_name: String = row.get_by_name("plugin_name")?;
-        let input_file = input_file.filter(|value| !value.trim().is_empty()).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Dead letter {} missing input_file; cannot replay",
-                dead_letter_id
-            )
-        })?;
-
-        let new_id = self
+    /// Update pipeline run status for a specific job.
+    pub fn update_pipeline_run_status_for_job(&self, job_id: i64) -> Result<()> {
+        let run_id = self
             .conn
-            .query_one(
-                r#"
-                INSERT INTO cf_processing_queue (file_id, input_file, plugin_name, status, scheduled_at)
-                VALUES (?, ?, ?, ?, ?)
-                RETURNING id
-                "#,
-                &[
-                    DbValue::from(file_id.unwrap_or_default()),
-                    DbValue::from(input_file),
-                    DbValue::from(plugin_name),
-                    DbValue::from(ProcessingStatus::Queued.as_str()),
-                    DbValue::from(now_millis()),
-                ],
+            .query_optional(
+                "SELECT pipeline_run_id FROM cf_processing_queue WHERE id = ?",
+                &[DbValue::from(job_id)],
             )?
-            .get_by_name::<i64>("id")?;
-
-        self.conn.execute(
-            "DELETE FROM cf_dead_letter WHERE id = ?",
-            &[DbValue::from(dead_letter_id)],
-        )?;
-
-        Ok(new_id)
+            .and_then(|row| row.get_by_name::<String>("pipeline_run_id").ok());
+        let Some(run_id) = run_id else {
+            return Ok(());
+        };
+        update_pipeline_run_status(&self.conn, &run_id)
     }
 
-    pub fn count_dead_letter_jobs(&self) -> Result<i64> {
-        let row = self
-            .conn
-            .query_one("SELECT COUNT(*) AS cnt FROM cf_dead_letter", &[])?;
-        Ok(row.get_by_name("cnt")?)
+    /// Mark a pipeline run as running.
+    pub fn set_pipeline_run_running(&self, run_id: &str) -> Result<()> {
+        set_pipeline_run_running(&self.conn, run_id)
     }
 
-    pub fn record_parser_success(&self, parser_name: &str) -> Result<()> {
-        let now = now_millis();
-        self.conn
-            .execute(
-                r#"
-                INSERT INTO cf_parser_health (parser_name, total_executions, successful_executions, consecutive_failures, created_at, updated_at)
-                VALUES (?, 1, 1, 0, ?, ?)
-                ON CONFLICT(parser_name) DO UPDATE SET
-                    total_executions = total_executions + 1,
-                    successful_executions = successful_executions + 1,
-                    consecutive_failures = 0,
-                    updated_at = ?
-                "#,
-                &[
-                    DbValue::from(parser_name),
-                    DbValue::from(now.clone()),
-                    DbValue::from(now.clone()),
-                    DbValue::from(now),
-                ],
-            )
-            ?;
-        Ok(())
+    /// Update a pipeline run status based on queued job states.
+    pub fn update_pipeline_run_status(&self, run_id: &str) -> Result<()> {
+        update_pipeline_run_status(&self.conn, run_id)
     }
 
-    pub fn record_parser_failure(&self, parser_name: &str, reason: &str) -> Result<i32> {
-        let now = now_millis();
-        self.conn
-            .execute(
-                r#"
-                INSERT INTO cf_parser_health (parser_name, total_executions, successful_executions, consecutive_failures, last_failure_reason, created_at, updated_at)
-                VALUES (?, 1, 0, 1, ?, ?, ?)
-                ON CONFLICT(parser_name) DO UPDATE SET
-                    total_executions = total_executions + 1,
-                    consecutive_failures = consecutive_failures + 1,
-                    last_failure_reason = ?,
-                    updated_at = ?
+    /// All jobs of a pipeline run as a DAG with per-stage timing.
+    ///
+    /// A run without jobs is reported from its `cf_pipeline_runs` row (e.g. a
+    /// no-op run); `None` if neither exists.
+    pub fn get_pipeline_run(&self, run_id: &str) -> Result<Option<PipelineRun>> {
+        let rows = self.conn.query_all(
+            r#"
+                SELECT id, plugin_name, file_id, upstream_job_id, status, scheduled_at,
+                       claim_time, end_time, error_message
+                FROM cf_processing_queue
+                WHERE pipeline_run_id = ?
+                ORDER BY id
                 "#,
-                &[
-                    DbValue::from(parser_name),
-                    DbValue::from(reason),
-                    DbValue::from(now.clone()),
-                    DbValue::from(now.clone()),
-                    DbValue::from(reason),
-                    DbValue::from(now),
-                ],
-            )
-            ?;
-
-        let health = self.get_parser_health(parser_name)?;
-        Ok(health.map(|h| h.consecutive_failures).unwrap_or(0))
-    }
-
-    pub fn pause_parser(&self, parser_name: &str) -> Result<()> {
-        let now = now_millis();
-        self.conn.execute(
-            "UPDATE cf_parser_health SET paused_at = ?, updated_at = ? WHERE parser_name = ?",
-            &[
-                DbValue::from(now.clone()),
-                DbValue::from(now),
-                DbValue::from(parser_name),
-            ],
+            &[DbValue::from(run_id)],
         )?;
-        Ok(())
-    }
+        if rows.is_empty() {
+            return self.pipeline_run_without_jobs(run_id);
+        }
 
-    pub fn resume_parser(&self, parser_name: &str) -> Result<()> {
-        let now = now_millis();
-        self.conn.execute(
-            "UPDATE cf_parser_health SET paused_at = NULL, updated_at = ? WHERE parser_name = ?",
-            &[DbValue::from(now), DbValue::from(parser_name)],
-        )?;
-        Ok(())
+        let mut jobs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let status_raw: String = row.get_by_name("status")?;
+            let status = status_raw
+                .parse::<ProcessingStatus>()
+                .map_err(|e| anyhow::anyhow!("Invalid job status '{}': {}", status_raw, e))?;
+            jobs.push(PipelineRunJob {
+                job_id: row.get_by_name("id")?,
+                plugin_name: row.get_by_name("plugin_name")?,
+                file_id: row.get_by_name("file_id")?,
+                upstream_job_id: row.get_by_name("upstream_job_id")?,
+                depth: 0,
+                status,
+                queued_at: row.get_by_name("scheduled_at")?,
+                started_at: row.get_by_name("claim_time")?,
+                finished_at: row.get_by_name("end_time")?,
+                error_message: row.get_by_name("error_message")?,
+            });
+        }
+        Ok(Some(PipelineRun::from_jobs(run_id, jobs)))
     }
 
-    pub fn is_parser_paused(&self, parser_name: &str) -> Result<bool> {
+    fn pipeline_run_without_jobs(&self, run_id: &str) -> Result<Option<PipelineRun>> {
+        if !self.table_exists("cf_pipeline_runs")? {
+            return Ok(None);
+        }
         let row = self.conn.query_optional(
-            "SELECT paused_at FROM cf_parser_health WHERE parser_name = ?",
-            &[DbValue::from(parser_name)],
+            "SELECT status, started_at, completed_at FROM cf_pipeline_runs WHERE id = ?",
+            &[DbValue::from(run_id)],
         )?;
-        Ok(row
-            .and_then(|r| r.get_by_name::<Option<i64>>("paused_at").ok())
-            .flatten()
-            .is_some())
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let status_raw: String = row.get_by_name("status")?;
+        let status = status_raw
+            .parse::<PipelineRunStatus>()
+            .map_err(|e| anyhow::anyhow!("Invalid pipeline run status '{}': {}", status_raw, e))?;
+        Ok(Some(PipelineRun {
+            run_id: run_id.to_string(),
+            status,
+            started_at: row.get_by_name("started_at")?,
+            completed_at: row.get_by_name("completed_at")?,
+            jobs: Vec::new(),
+            stages: Vec::new(),
+        }))
     }
 
-    pub fn get_parser_health(&self, parser_name: &str) -> Result<Option<ParserHealth>> {
-        let row = self.conn.query_optional(
-            &format!(
-                "SELECT {} FROM cf_parser_health WHERE parser_name = ?",
-                column_list(PARSER_HEALTH_COLUMNS)
-            ),
-            &[DbValue::from(parser_name)],
+    /// Record a completed output materialization (idempotent insert).
+    pub fn insert_output_materialization(&self, record: &OutputMaterialization) -> Result<()> {
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_output_materializations (
+                    materialization_key,
+                    output_target_key,
+                    file_id,
+                    file_mtime,
+                    file_size,
+                    plugin_name,
+                    parser_version,
+                    parser_fingerprint,
+                    output_name,
+                    sink_uri,
+                    sink_mode,
+                    table_name,
+                    schema_hash,
+                    status,
+                    rows,
+                    job_id,
+                    created_at
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(materialization_key) DO NOTHING
+                "#,
+            &[
+                DbValue::from(record.materialization_key.as_str()),
+                DbValue::from(record.output_target_key.as_str()),
+                DbValue::from(record.file_id),
+                DbValue::from(record.file_mtime),
+                DbValue::from(record.file_size),
+                DbValue::from(record.plugin_name.as_str()),
+                record
+                    .parser_version
+                    .as_deref()
+                    .map(DbValue::from)
+                    .unwrap_or(DbValue::Null),
+                DbValue::from(record.parser_fingerprint.as_str()),
+                DbValue::from(record.output_name.as_str()),
+                DbValue::from(record.sink_uri.as_str()),
+                DbValue::from(record.sink_mode.as_str()),
+                record
+                    .table_name
+                    .as_deref()
+                    .map(DbValue::from)
+                    .unwrap_or(DbValue::Null),
+                record
+                    .schema_hash
+                    .as_deref()
+                    .map(DbValue::from)
+                    .unwrap_or(DbValue::Null),
+                DbValue::from(record.status.as_str()),
+                DbValue::from(record.rows),
+                DbValue::from(record.job_id),
+                DbValue::from(now_millis()),
+            ],
         )?;
-        Ok(row.map(|row| ParserHealth::from_row(&row)).transpose()?)
+        Ok(())
     }
 
-    pub fn get_all_parser_health(&self) -> Result<Vec<ParserHealth>> {
-        let rows = self.conn.query_all(
-            &format!(
-                "SELECT {} FROM cf_parser_health",
-                column_list(PARSER_HEALTH_COLUMNS)
-            ),
-            &[],
-        )?;
-        rows.iter()
-            .map(ParserHealth::from_row)
-            .collect::<Result<_, _>>()
-            .map_err(Into::into)
+    /// Load recorded materializations for the given keys.
+    ///
+    /// Keys without a record are omitted from the result.
+    pub fn find_output_materializations(
+        &self,
+        keys: &[String],
+    ) -> Result<Vec<OutputMaterialization>> {
+        let mut records = Vec::new();
+        const CHUNK_SIZE: usize = 500;
+        for chunk in keys.chunks(CHUNK_SIZE) {
+            let placeholders = (0..chunk.len()).map(|_| "?").collect::<Vec<_>>().join(", ");
+            let sql = format!(
+                r#"
+                SELECT materialization_key, output_target_key, file_id, file_mtime, file_size,
+                       plugin_name, parser_version, parser_fingerprint, output_name, sink_uri,
+                       sink_mode, table_name, schema_hash, status, rows, job_id
+                FROM cf_output_materializations
+                WHERE materialization_key IN ({})
+                "#,
+                placeholders
+            );
+            let params: Vec<DbValue> = chunk.iter().map(|k| DbValue::from(k.as_str())).collect();
+            for row in self.conn.query_all(&sql, &params)? {
+                let sink_mode: String = row.get_by_name("sink_mode")?;
+                records.push(OutputMaterialization {
+                    materialization_key: row.get_by_name("materialization_key")?,
+                    output_target_key: row.get_by_name("output_target_key")?,
+                    file_id: row.get_by_name("file_id")?,
+                    file_mtime: row.get_by_name("file_mtime")?,
+                    file_size: row.get_by_name("file_size")?,
+                    plugin_name: row.get_by_name("plugin_name")?,
+                    parser_version: row.get_by_name("parser_version")?,
+                    parser_fingerprint: row
+                        .get_by_name::<Option<String>>("parser_fingerprint")?
+                        .unwrap_or_default(),
+                    output_name: row.get_by_name("output_name")?,
+                    sink_uri: row.get_by_name("sink_uri")?,
+                    sink_mode: sink_mode.parse().map_err(anyhow::Error::msg)?,
+                    table_name: row.get_by_name("table_name")?,
+                    schema_hash: row.get_by_name("schema_hash")?,
+                    status: row.get_by_name("status")?,
+                    rows: row.get_by_name("rows")?,
+                    job_id: row
+                        .get_by_name::<Option<i64>>("job_id")?
+                        .unwrap_or_default(),
+                });
+            }
+        }
+        Ok(records)
     }
 
-    pub fn quarantine_row(
+    /// Mark a DISPATCHING job as skipped because its outputs already exist.
+    ///
+    /// Only applies while `lease_token` still owns the job.
+    pub fn skip_job_if_token_matches_dispatching(
         &self,
         job_id: i64,
-        row_index: i64,
-        error: &str,
-        raw: Option<&[u8]>,
-    ) -> Result<()> {
+        lease_token: &str,
+        summary: &str,
+    ) -> Result<bool> {
         let now = now_millis();
-        self.conn.execute(
+        let affected = self.conn.execute(
             r#"
-                INSERT INTO cf_quarantine (job_id, row_index, error_reason, raw_data, created_at)
-                VALUES (?, ?, ?, ?, ?)
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = ?,
+                    end_time = ?,
+                    result_summary = ?
+                WHERE id = ? AND status = ? AND lease_token = ?
                 "#,
             &[
-                DbValue::from(job_id),
-                DbValue::from(row_index),
-                DbValue::from(error),
-                DbValue::from(raw.map(|v| v.to_vec())),
+                DbValue::from(ProcessingStatus::Skipped.as_str()),
+                DbValue::from(JobStatus::Success.as_str()),
                 DbValue::from(now),
+                DbValue::from(summary),
+                DbValue::from(job_id),
+                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                DbValue::from(lease_token),
             ],
         )?;
-        Ok(())
+        Ok(affected > 0)
     }
 
-    pub fn get_quarantined_rows(&self, job_id: i64) -> Result<Vec<QuarantinedRowSummary>> {
-        let rows = self.conn.query_all(
-            &format!(
-                "SELECT {} FROM cf_quarantine WHERE job_id = ? ORDER BY row_index",
-                column_list(QUARANTINE_LIST_COLUMNS)
-            ),
-            &[DbValue::from(job_id)],
-        )?;
-        rows.iter()
-            .map(QuarantinedRowSummary::from_row)
-            .collect::<Result<_, _>>()
-            .map_err(Into::into)
-    }
-
-    pub fn get_quarantined_rows_with_raw(&self, job_id: i64) -> Result<Vec<QuarantinedRow>> {
-        let rows = self.conn.query_all(
-            &format!(
-                "SELECT {} FROM cf_quarantine WHERE job_id = ? ORDER BY row_index",
-                column_list(QUARANTINE_COLUMNS)
-            ),
-            &[DbValue::from(job_id)],
-        )?;
-        rows.iter()
-            .map(QuarantinedRow::from_row)
-            .collect::<Result<_, _>>()
-            .map_err(Into::into)
-    }
-
-    pub fn count_quarantined_rows(&self, job_id: i64) -> Result<i64> {
-        let row = self.conn.query_one(
-            "SELECT COUNT(*) AS cnt FROM cf_quarantine WHERE job_id = ?",
-            &[DbValue::from(job_id)],
+    /// Mark job as failed with outcome details.
+    ///
+    /// `completion_status` should be one of: FAILED, REJECTED
+    pub fn fail_job(&self, job_id: i64, completion_status: &str, error: &str) -> Result<()> {
+        let now = now_millis();
+        self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = ?,
+                    end_time = ?,
+                    error_message = ?
+                WHERE id = ?
+                "#,
+            &[
+                DbValue::from(ProcessingStatus::Failed.as_str()),
+                DbValue::from(completion_status),
+                DbValue::from(now),
+                DbValue::from(error),
+                DbValue::from(job_id),
+            ],
         )?;
-        Ok(row.get_by_name("cnt")?)
+        Ok(())
     }
 
-    pub fn delete_quarantined_rows(&self, job_id: i64) -> Result<u64> {
-        let affected = self.conn.execute(
-            "DELETE FROM cf_quarantine WHERE job_id = ?",
-            &[DbValue::from(job_id)],
+    /// Mark job as aborted with outcome details.
+    pub fn abort_job(&self, job_id: i64, error: &str) -> Result<()> {
+        let now = now_millis();
+        self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = ?,
+                    end_time = ?,
+                    error_message = ?
+                WHERE id = ?
+                "#,
+            &[
+                DbValue::from(ProcessingStatus::Aborted.as_str()),
+                DbValue::from(JobStatus::Aborted.as_str()),
+                DbValue::from(now),
+                DbValue::from(error),
+                DbValue::from(job_id),
+            ],
         )?;
-        Ok(affected)
+        Ok(())
     }
 
-    // ========================================================================
-    // Canonical Job Query API
-    // ========================================================================
-
-    /// List jobs with optional status filter.
-    ///
-    /// Returns jobs from `cf_processing_queue` ordered by creation time (newest first).
-    pub fn list_jobs(
+    /// Mark job as complete if the lease token matches.
+    pub fn complete_job_if_token_matches(
         &self,
-        status: Option<ProcessingStatus>,
-        limit: usize,
-        offset: usize,
-    ) -> Result<Vec<Job>> {
-        let limit_i64 = i64::try_from(limit).context("list_jobs limit exceeds i64::MAX")?;
-        let offset_i64 = i64::try_from(offset).context("list_jobs offset exceeds i64::MAX")?;
-
-        let (sql, params): (&str, Vec<DbValue>) = match status {
-            Some(s) => (
+        job_id: i64,
+        lease_token: &str,
+        completion_status: &str,
+        summary: &str,
+        quarantine_rows: Option<i64>,
+    ) -> Result<bool> {
+        let now = now_millis();
+        let (query, params) = if let Some(rows) = quarantine_rows {
+            (
                 r#"
-                SELECT id, file_id, plugin_name, status, priority, retry_count,
-                       scheduled_at, claim_time, end_time, error_message,
-                       completion_status, parser_version, pipeline_run_id,
-                       result_summary, quarantine_rows
-                FROM cf_processing_queue
-                WHERE status = ?
-                ORDER BY scheduled_at DESC
-                LIMIT ? OFFSET ?
-                "#,
+                    UPDATE cf_processing_queue
+                    SET status = ?,
+                        completion_status = ?,
+                        end_time = ?,
+                        result_summary = ?,
+                        quarantine_rows = ?
+                    WHERE id = ? AND status = ? AND lease_token = ?
+                    "#,
                 vec![
-                    DbValue::from(s.as_str()),
-                    DbValue::from(limit_i64),
-                    DbValue::from(offset_i64),
+                    DbValue::from(ProcessingStatus::Completed.as_str()),
+                    DbValue::from(completion_status),
+                    DbValue::from(now),
+                    DbValue::from(summary),
+                    DbValue::from(rows),
+                    DbValue::from(job_id),
+                    DbValue::from(ProcessingStatus::Running.as_str()),
+                    DbValue::from(lease_token),
                 ],
-            ),
-            None => (
+            )
+        } else {
+            (
                 r#"
-                SELECT id, file_id, plugin_name, status, priority, retry_count,
-                       scheduled_at, claim_time, end_time, error_message,
-                       completion_status, parser_version, pipeline_run_id,
-                       result_summary, quarantine_rows
-                FROM cf_processing_queue
-                ORDER BY scheduled_at DESC
-                LIMIT ? OFFSET ?
-                "#,
-                vec![DbValue::from(limit_i64), DbValue::from(offset_i64)],
-            ),
+                    UPDATE cf_processing_queue
+                    SET status = ?,
+                        completion_status = ?,
+                        end_time = ?,
+                        result_summary = ?
+                    WHERE id = ? AND status = ? AND lease_token = ?
+                    "#,
+                vec![
+                    DbValue::from(ProcessingStatus::Completed.as_str()),
+                    DbValue::from(completion_status),
+                    DbValue::from(now),
+                    DbValue::from(summary),
+                    DbValue::from(job_id),
+                    DbValue::from(ProcessingStatus::Running.as_str()),
+                    DbValue::from(lease_token),
+                ],
+            )
         };
-
-        let rows = self.conn.query_all(sql, &params)?;
-        rows.iter()
-            .map(Job::from_row)
-            .collect::<Result<_, _>>()
-            .map_err(Into::into)
+        let affected = self.conn.execute(query, &params)?;
+        Ok(affected > 0)
     }
 
-    /// Get a single job by ID.
-    ///
-    /// Returns `None` if the job does not exist.
-    pub fn get_job(&self, job_id: JobId) -> Result<Option<Job>> {
-        let job_id_i64 = job_id.to_i64().context("job_id exceeds i64::MAX")?;
-
-        let sql = r#"
-            SELECT id, file_id, plugin_name, status, priority, retry_count,
-                   scheduled_at, claim_time, end_time, error_message,
-                   completion_status, parser_version, pipeline_run_id,
-                   result_summary, quarantine_rows
-            FROM cf_processing_queue
-            WHERE id = ?
-        "#;
-
-        let row = self
-            .conn
-            .query_optional(sql, &[DbValue::from(job_id_i64)])?;
-
-        match row {
-            Some(r) => Ok(Some(Job::from_row(&r)?)),
-            None => Ok(None),
-        }
+    /// Mark job as failed if the lease token matches.
+    pub fn fail_job_if_token_matches(
+        &self,
+        job_id: i64,
+        lease_token: &str,
+        completion_status: &str,
+        error: &str,
+    ) -> Result<bool> {
+        let now = now_millis();
+        let affected = self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = ?,
+                    end_time = ?,
+                    error_message = ?
+                WHERE id = ? AND status = ? AND lease_token = ?
+                "#,
+            &[
+                DbValue::from(ProcessingStatus::Failed.as_str()),
+                DbValue::from(completion_status),
+                DbValue::from(now),
+                DbValue::from(error),
+                DbValue::from(job_id),
+                DbValue::from(ProcessingStatus::Running.as_str()),
+                DbValue::from(lease_token),
+            ],
+        )?;
+        Ok(affected > 0)
     }
 
-    /// Cancel a queued, pending, or dispatching job.
-    ///
-    /// Only jobs in QUEUED, PENDING, or DISPATCHING status can be cancelled. Jobs that are already
-    /// RUNNING, COMPLETED, or FAILED are not affected.
-    ///
-    /// Returns `true` if the job was cancelled, `false` if it was not found or
-    /// was already in a terminal state.
-    pub fn cancel_job(&self, job_id: JobId) -> Result<bool> {
-        let job_id_i64 = job_id.to_i64().context("job_id exceeds i64::MAX")?;
+    /// Mark job as aborted if the lease token matches.
+    pub fn abort_job_if_token_matches(
+        &self,
+        job_id: i64,
+        lease_token: &str,
+        error: &str,
+    ) -> Result<bool> {
         let now = now_millis();
-
-        // Only cancel jobs that are in cancellable states
         let affected = self.conn.execute(
             r#"
-            UPDATE cf_processing_queue
-            SET status = ?,
-                completion_status = ?,
-                end_time = ?,
-                error_message = ?
-            WHERE id = ? AND status IN (?, ?, ?)
-            "#,
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = ?,
+                    end_time = ?,
+                    error_message = ?
+                WHERE id = ? AND status = ? AND lease_token = ?
+                "#,
             &[
                 DbValue::from(ProcessingStatus::Aborted.as_str()),
                 DbValue::from(JobStatus::Aborted.as_str()),
                 DbValue::from(now),
-                DbValue::from(casparian_protocol::defaults::CANCELLED_BY_USER_MESSAGE),
-                DbValue::from(job_id_i64),
-                DbValue::from(ProcessingStatus::Queued.as_str()),
-                DbValue::from(ProcessingStatus::Pending.as_str()),
-                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                DbValue::from(error),
+                DbValue::from(job_id),
+                DbValue::from(ProcessingStatus::Running.as_str()),
+                DbValue::from(lease_token),
             ],
         )?;
-
         Ok(affected > 0)
     }
 
-    /// Get job count grouped by status.
-    ///
-    /// Returns a map from ProcessingStatus to count. Only statuses with non-zero
-    /// counts are included in the map.
-    pub fn count_jobs_by_status(&self) -> Result<HashMap<ProcessingStatus, i64>> {
-        let sql = r#"
-            SELECT status, COUNT(*) AS cnt
-            FROM cf_processing_queue
-            GROUP BY status
-        "#;
-
-        let rows = self.conn.query_all(sql, &[])?;
-        let mut counts = HashMap::new();
-
-        for row in &rows {
-            let status_str: String = row.get_by_name("status")?;
-            let count: i64 = row.get_by_name("cnt")?;
+    /// Requeue a job.
+    /// Clears terminal fields (completion_status, end_time, result_summary, error_message)
+    /// when transitioning back to QUEUED state.
+    pub fn requeue_job(&self, job_id: i64) -> Result<()> {
+        let row = self.conn.query_optional(
+            "SELECT retry_count FROM cf_processing_queue WHERE id = ?",
+            &[DbValue::from(job_id)],
+        )?;
 
-            if let Ok(status) = status_str.parse::<ProcessingStatus>() {
-                counts.insert(status, count);
+        if let Some(row) = row {
+            let retry_count: i32 = row.get_by_name("retry_count")?;
+            if retry_count >= MAX_RETRY_COUNT {
+                self.move_to_dead_letter(
+                    job_id,
+                    DeadLetterReason::MaxRetriesExceeded.as_str(),
+                    DeadLetterReason::MaxRetriesExceeded,
+                )?;
+                return Ok(());
             }
         }
 
-        Ok(counts)
-    }
-
-    /// List running job ids for a specific lease owner.
-    pub fn list_running_jobs_by_owner(&self, lease_owner: &str) -> Result<Vec<i64>> {
-        let rows = self.conn.query_all(
+        self.conn.execute(
             r#"
-                SELECT id
-                FROM cf_processing_queue
-                WHERE status = ? AND lease_owner = ?
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = NULL,
+                    claim_time = NULL,
+                    lease_token = NULL,
+                    lease_owner = NULL,
+                    lease_expires_at = NULL,
+                    dispatch_ack_at = NULL,
+                    end_time = NULL,
+                    result_summary = NULL,
+                    result_summary_json = NULL,
+                    error_message = NULL,
+                    scheduled_at = ?,
+                    retry_count = retry_count + 1
+                WHERE id = ?
                 "#,
             &[
-                DbValue::from(ProcessingStatus::Running.as_str()),
-                DbValue::from(lease_owner),
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(now_millis()),
+                DbValue::from(job_id),
             ],
         )?;
-        let mut jobs = Vec::with_capacity(rows.len());
-        for row in rows {
-            let id: i64 = row.get_by_name("id")?;
-            jobs.push(id);
-        }
-        Ok(jobs)
+        Ok(())
     }
 
-    /// List running job ids with their lease owner (if any).
-    pub fn list_running_jobs_with_owner(&self) -> Result<Vec<(i64, Option<String>)>> {
-        let rows = self.conn.query_all(
+    /// Defer a job without incrementing retry count.
+    /// Clears terminal fields when transitioning back to QUEUED state.
+    pub fn defer_job(
+        &self,
+        job_id: i64,
+        scheduled_at: i64,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
             r#"
-                SELECT id, lease_owner
-                FROM cf_processing_queue
-                WHERE status = ?
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = NULL,
+                    claim_time = NULL,
+                    lease_token = NULL,
+                    lease_owner = NULL,
+                    lease_expires_at = NULL,
+                    dispatch_ack_at = NULL,
+                    end_time = NULL,
+                    result_summary = NULL,
+                    result_summary_json = NULL,
+                    scheduled_at = ?,
+                    error_message = ?
+                WHERE id = ?
                 "#,
-            &[DbValue::from(ProcessingStatus::Running.as_str())],
+            &[
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(scheduled_at),
+                DbValue::from(reason),
+                DbValue::from(job_id),
+            ],
         )?;
-        let mut jobs = Vec::with_capacity(rows.len());
-        for row in rows {
-            let id: i64 = row.get_by_name("id")?;
-            let lease_owner: Option<String> = row.get_by_name("lease_owner")?;
-            jobs.push((id, lease_owner));
-        }
-        Ok(jobs)
-    }
-}
-
-fn table_exists(conn: &DbConnection, table: &str) -> Result<bool> {
-    Ok(conn.table_exists(table)?)
-}
-
-fn set_pipeline_run_running(conn: &DbConnection, run_id: &str) -> Result<()> {
-    if !table_exists(conn, "cf_pipeline_runs")? {
-        return Ok(());
-    }
-    conn.execute(
-        r#"
-            UPDATE cf_pipeline_runs
-            SET status = ?,
-                started_at = COALESCE(started_at, ?)
-            WHERE id = ?
-            "#,
-        &[
-            DbValue::from(PipelineRunStatus::Running.as_str()),
-            DbValue::from(now_millis()),
-            DbValue::from(run_id),
-        ],
-    )?;
-    Ok(())
-}
-
-fn update_pipeline_run_status(conn: &DbConnection, run_id: &str) -> Result<()> {
-    if !table_exists(conn, "cf_pipeline_runs")? {
-        return Ok(());
+        Ok(())
     }
 
-    let row = conn.query_optional(
-        &format!(
+    /// Defer a job if the lease token matches and status is DISPATCHING.
+    /// Clears terminal fields when transitioning back to QUEUED state.
+    pub fn defer_job_if_token_matches(
+        &self,
+        job_id: i64,
+        lease_token: &str,
+        scheduled_at: i64,
+        reason: Option<&str>,
+    ) -> Result<bool> {
+        let affected = self.conn.execute(
             r#"
-            SELECT
-                SUM(CASE WHEN status IN ('{failed}', '{aborted}') THEN 1 ELSE 0 END) AS failed,
-                SUM(CASE WHEN status IN ('{queued}', '{running}') THEN 1 ELSE 0 END) AS active,
-                SUM(CASE WHEN status = '{completed}' THEN 1 ELSE 0 END) AS completed
-            FROM cf_processing_queue
-            WHERE pipeline_run_id = ?
-            "#,
-            failed = ProcessingStatus::Failed.as_str(),
-            aborted = ProcessingStatus::Aborted.as_str(),
-            queued = ProcessingStatus::Queued.as_str(),
-            running = ProcessingStatus::Running.as_str(),
-            completed = ProcessingStatus::Completed.as_str(),
-        ),
-        &[DbValue::from(run_id)],
-    )?;
-
-    let Some(row) = row else {
-        return Ok(());
-    };
-
-    let failed: i64 = row.get_by_name("failed").unwrap_or(0);
-    let active: i64 = row.get_by_name("active").unwrap_or(0);
-    let completed: i64 = row.get_by_name("completed").unwrap_or(0);
-
-    if failed > 0 {
-        conn.execute(
-            "UPDATE cf_pipeline_runs SET status = ?, completed_at = ? WHERE id = ?",
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = NULL,
+                    claim_time = NULL,
+                    lease_token = NULL,
+                    lease_owner = NULL,
+                    lease_expires_at = NULL,
+                    dispatch_ack_at = NULL,
+                    end_time = NULL,
+                    result_summary = NULL,
+                    result_summary_json = NULL,
+                    scheduled_at = ?,
+                    error_message = ?
+                WHERE id = ? AND status = ? AND lease_token = ?
+                "#,
             &[
-                DbValue::from(PipelineRunStatus::Failed.as_str()),
-                DbValue::from(now_millis()),
-                DbValue::from(run_id),
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(scheduled_at),
+                DbValue::from(reason),
+                DbValue::from(job_id),
+                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                DbValue::from(lease_token),
             ],
         )?;
-        return Ok(());
+        Ok(affected > 0)
     }
 
-    if active > 0 {
-        set_pipeline_run_running(conn, run_id)?;
-        return Ok(());
+    /// Return a dispatched or running job to the queue so a higher-priority
+    /// job can take its worker. The retry count is untouched and the job keeps
+    /// its lane; a late CONCLUDE for the old lease is discarded as stale.
+    pub fn preempt_job_if_token_matches(
+        &self,
+        job_id: i64,
+        lease_token: &str,
+        now: i64,
+    ) -> Result<bool> {
+        let affected = self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = NULL,
+                    claim_time = NULL,
+                    lease_token = NULL,
+                    lease_owner = NULL,
+                    lease_expires_at = NULL,
+                    dispatch_ack_at = NULL,
+                    end_time = NULL,
+                    result_summary = NULL,
+                    result_summary_json = NULL,
+                    scheduled_at = ?,
+                    error_message = ?
+                WHERE id = ? AND status IN (?, ?) AND lease_token = ?
+                "#,
+            &[
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(now),
+                DbValue::from(casparian_protocol::defaults::PREEMPTED_MESSAGE),
+                DbValue::from(job_id),
+                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                DbValue::from(ProcessingStatus::Running.as_str()),
+                DbValue::from(lease_token),
+            ],
+        )?;
+        Ok(affected > 0)
     }
 
-    if completed > 0 {
-        conn.execute(
-            "UPDATE cf_pipeline_runs SET status = ?, completed_at = ? WHERE id = ?",
+    /// Starvation protection: raise queued jobs below `lane` that have been
+    /// waiting since `queued_before` (ms) to `lane`. Returns how many moved.
+    pub fn promote_starved_jobs(&self, queued_before: i64, lane: JobPriority) -> Result<usize> {
+        let affected = self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET priority = ?
+                WHERE status = ? AND priority < ? AND scheduled_at <= ?
+                "#,
             &[
-                DbValue::from(PipelineRunStatus::Completed.as_str()),
-                DbValue::from(now_millis()),
-                DbValue::from(run_id),
+                DbValue::from(lane.value()),
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(lane.value()),
+                DbValue::from(queued_before),
             ],
         )?;
+        Ok(affected as usize)
     }
 
-    Ok(())
-}
-
-#[derive(Debug, Clone, Default)]
-pub struct QueueStats {
+    /// Mark job as failed if the lease token matches and status is DISPATCHING.
+    pub fn fail_job_if_token_matches_dispatching(
+        &self,
+        job_id: i64,
+        lease_token: &str,
+        completion_status: &str,
+        error: &str,
+    ) -> Result<bool> {
+        let now = now_millis();
+        let affected = self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = ?,
+                    end_time = ?,
+                    error_message = ?
+                WHERE id = ? AND status = ? AND lease_token = ?
+                "#,
+            &[
+                DbValue::from(ProcessingStatus::Failed.as_str()),
+                DbValue::from(completion_status),
+                DbValue::from(now),
+                DbValue::from(error),
+                DbValue::from(job_id),
+                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+                DbValue::from(lease_token),
+            ],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// Schedule a retry for a failed job with backoff.
+    /// Clears terminal fields (except error_message which stores the retry reason)
+    /// when transitioning back to QUEUED state.
+    pub fn schedule_retry(
+        &self,
+        job_id: i64,
+        next_retry_count: i32,
+        error: &str,
+        scheduled_at: i64,
+    ) -> Result<()> {
+        self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = NULL,
+                    retry_count = ?,
+                    claim_time = NULL,
+                    lease_token = NULL,
+                    lease_owner = NULL,
+                    lease_expires_at = NULL,
+                    dispatch_ack_at = NULL,
+                    end_time = NULL,
+                    result_summary = NULL,
+                    result_summary_json = NULL,
+                    scheduled_at = ?,
+                    error_message = ?
+                WHERE id = ?
+                "#,
+            &[
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(next_retry_count),
+                DbValue::from(scheduled_at),
+                DbValue::from(error),
+                DbValue::from(job_id),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Queue stats for monitoring.
+    pub fn stats(&self) -> Result<QueueStats> {
+        let row = self.conn.query_one(
+            &format!(
+                r#"
+                SELECT
+                    SUM(CASE WHEN status = '{queued}' THEN 1 ELSE 0 END) AS queued,
+                    SUM(CASE WHEN status = '{running}' THEN 1 ELSE 0 END)
+                        + SUM(CASE WHEN status = '{dispatching}' THEN 1 ELSE 0 END) AS running,
+                    SUM(CASE WHEN status = '{completed}' THEN 1 ELSE 0 END) AS completed,
+                    SUM(CASE WHEN status = '{failed}' THEN 1 ELSE 0 END) AS failed
+                FROM cf_processing_queue
+                "#,
+                queued = ProcessingStatus::Queued.as_str(),
+                running = ProcessingStatus::Running.as_str(),
+                dispatching = ProcessingStatus::Dispatching.as_str(),
+                completed = ProcessingStatus::Completed.as_str(),
+                failed = ProcessingStatus::Failed.as_str(),
+            ),
+            &[],
+        )?;
+
+        Ok(QueueStats {
+            queued: row.get_by_name("queued")?,
+            running: row.get_by_name("running")?,
+            completed: row.get_by_name("completed")?,
+            failed: row.get_by_name("failed")?,
+        })
+    }
+
+    /// Initialize dead-letter, health, quarantine tables.
+    pub fn init_error_handling_schema(&self) -> Result<()> {
+        let sql = match self.conn.backend_name() {
+            "DuckDB" => {
+                r#"
+                CREATE SEQUENCE IF NOT EXISTS seq_cf_dead_letter;
+                CREATE SEQUENCE IF NOT EXISTS seq_cf_quarantine;
+                CREATE SEQUENCE IF NOT EXISTS seq_cf_job_schema_mismatch;
+                CREATE SEQUENCE IF NOT EXISTS seq_cf_job_retries;
+
+                CREATE TABLE IF NOT EXISTS cf_dead_letter (
+                    id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_dead_letter'),
+                    original_job_id BIGINT NOT NULL,
+                    file_id BIGINT,
+                    input_file TEXT,
+                    plugin_name TEXT NOT NULL,
+                    error_message TEXT,
+                    retry_count INTEGER NOT NULL,
+                    moved_at BIGINT NOT NULL,
+                    reason TEXT,
+                    pipeline_run_id TEXT,
+                    parser_version TEXT,
+                    parser_fingerprint TEXT,
+                    config_overrides TEXT,
+                    sink_config_json TEXT,
+                    last_worker TEXT,
+                    last_claim_time BIGINT,
+                    result_summary TEXT,
+                    upstream_job_id BIGINT
+                );
+                CREATE INDEX IF NOT EXISTS ix_dead_letter_plugin ON cf_dead_letter(plugin_name);
+
+                CREATE TABLE IF NOT EXISTS cf_parser_health (
+                    parser_name TEXT PRIMARY KEY,
+                    total_executions INTEGER NOT NULL DEFAULT 0,
+                    successful_executions INTEGER NOT NULL DEFAULT 0,
+                    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+                    last_failure_reason TEXT,
+                    paused_at BIGINT,
+                    created_at BIGINT NOT NULL,
+                    updated_at BIGINT NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS cf_quarantine (
+                    id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_quarantine'),
+                    job_id BIGINT NOT NULL,
+                    row_index BIGINT NOT NULL,
+                    error_reason TEXT NOT NULL,
+                    raw_data BLOB,
+                    created_at BIGINT NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS cf_job_schema_mismatch (
+                    id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_job_schema_mismatch'),
+                    job_id BIGINT NOT NULL,
+                    output_name TEXT NOT NULL,
+                    mismatch_kind TEXT NOT NULL,
+                    expected_name TEXT,
+                    actual_name TEXT,
+                    expected_type TEXT,
+                    actual_type TEXT,
+                    expected_index INTEGER,
+                    actual_index INTEGER,
+                    created_at BIGINT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS ix_schema_mismatch_job
+                    ON cf_job_schema_mismatch(job_id);
+
+                CREATE TABLE IF NOT EXISTS cf_plugin_config (
+                    plugin_name TEXT PRIMARY KEY,
+                    retry_policy_json TEXT,
+                    resource_limits_json TEXT,
+                    csv_dialect_json TEXT,
+                    updated_at BIGINT NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS cf_job_retries (
+                    id BIGINT PRIMARY KEY DEFAULT nextval('seq_cf_job_retries'),
+                    job_id BIGINT NOT NULL,
+                    attempt INTEGER NOT NULL,
+                    error_class TEXT NOT NULL,
+                    error_message TEXT,
+                    failed_at BIGINT NOT NULL,
+                    retry_at BIGINT
+                );
+
+                CREATE INDEX IF NOT EXISTS ix_job_retries_job ON cf_job_retries(job_id);
+
+                CREATE TABLE IF NOT EXISTS cf_job_diagnostics (
+                    job_id BIGINT PRIMARY KEY,
+                    error_category TEXT,
+                    error_class TEXT,
+                    failed_stage TEXT,
+                    diagnostics_json TEXT NOT NULL,
+                    recorded_at BIGINT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS ix_job_diagnostics_category
+                    ON cf_job_diagnostics(error_category);
+
+                CREATE TABLE IF NOT EXISTS cf_job_logs (
+                    job_id BIGINT NOT NULL,
+                    byte_offset BIGINT NOT NULL,
+                    chunk TEXT NOT NULL,
+                    created_at BIGINT NOT NULL,
+                    PRIMARY KEY (job_id, byte_offset)
+                );
+                "#
+            }
+            _ => {
+                r#"
+                CREATE TABLE IF NOT EXISTS cf_dead_letter (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    original_job_id INTEGER NOT NULL,
+                    file_id INTEGER,
+                    input_file TEXT,
+                    plugin_name TEXT NOT NULL,
+                    error_message TEXT,
+                    retry_count INTEGER NOT NULL,
+                    moved_at INTEGER NOT NULL,
+                    reason TEXT,
+                    pipeline_run_id TEXT,
+                    parser_version TEXT,
+                    parser_fingerprint TEXT,
+                    config_overrides TEXT,
+                    sink_config_json TEXT,
+                    last_worker TEXT,
+                    last_claim_time INTEGER,
+                    result_summary TEXT,
+                    upstream_job_id INTEGER
+                );
+                CREATE INDEX IF NOT EXISTS ix_dead_letter_plugin ON cf_dead_letter(plugin_name);
+
+                CREATE TABLE IF NOT EXISTS cf_parser_health (
+                    parser_name TEXT PRIMARY KEY,
+                    total_executions INTEGER NOT NULL DEFAULT 0,
+                    successful_executions INTEGER NOT NULL DEFAULT 0,
+                    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+                    last_failure_reason TEXT,
+                    paused_at INTEGER,
+                    created_at INTEGER NOT NULL,
+                    updated_at INTEGER NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS cf_quarantine (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    job_id INTEGER NOT NULL,
+                    row_index INTEGER NOT NULL,
+                    error_reason TEXT NOT NULL,
+                    raw_data BLOB,
+                    created_at INTEGER NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS cf_job_schema_mismatch (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    job_id INTEGER NOT NULL,
+                    output_name TEXT NOT NULL,
+                    mismatch_kind TEXT NOT NULL,
+                    expected_name TEXT,
+                    actual_name TEXT,
+                    expected_type TEXT,
+                    actual_type TEXT,
+                    expected_index INTEGER,
+                    actual_index INTEGER,
+                    created_at INTEGER NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS ix_schema_mismatch_job
+                    ON cf_job_schema_mismatch(job_id);
+
+                CREATE TABLE IF NOT EXISTS cf_plugin_config (
+                    plugin_name TEXT PRIMARY KEY,
+                    retry_policy_json TEXT,
+                    resource_limits_json TEXT,
+                    csv_dialect_json TEXT,
+                    updated_at INTEGER NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS cf_job_retries (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    job_id INTEGER NOT NULL,
+                    attempt INTEGER NOT NULL,
+                    error_class TEXT NOT NULL,
+                    error_message TEXT,
+                    failed_at INTEGER NOT NULL,
+                    retry_at INTEGER
+                );
+
+                CREATE INDEX IF NOT EXISTS ix_job_retries_job ON cf_job_retries(job_id);
+
+                CREATE TABLE IF NOT EXISTS cf_job_diagnostics (
+                    job_id INTEGER PRIMARY KEY,
+                    error_category TEXT,
+                    error_class TEXT,
+                    failed_stage TEXT,
+                    diagnostics_json TEXT NOT NULL,
+                    recorded_at INTEGER NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS ix_job_diagnostics_category
+                    ON cf_job_diagnostics(error_category);
+
+                CREATE TABLE IF NOT EXISTS cf_job_logs (
+                    job_id INTEGER NOT NULL,
+                    byte_offset INTEGER NOT NULL,
+                    chunk TEXT NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    PRIMARY KEY (job_id, byte_offset)
+                );
+                "#
+            }
+        };
+
+        self.conn
+            .execute(sql, &[])
+            .context("Failed to initialize error handling schema")?;
+        Ok(())
+    }
+
+    pub fn record_schema_mismatch(&self, job_id: i64, mismatch: &SchemaMismatch) -> Result<()> {
+        let now = now_millis();
+
+        for name in &mismatch.missing_columns {
+            self.insert_schema_mismatch_row(
+                job_id,
+                &mismatch.output_name,
+                "missing_column",
+                Some(name.as_str()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                now.clone(),
+            )?;
+        }
+
+        for name in &mismatch.extra_columns {
+            self.insert_schema_mismatch_row(
+                job_id,
+                &mismatch.output_name,
+                "extra_column",
+                None,
+                Some(name.as_str()),
+                None,
+                None,
+                None,
+                None,
+                now.clone(),
+            )?;
+        }
+
+        for order in &mismatch.order_mismatches {
+            let expected_index = i64::try_from(order.index).map_err(|_| {
+                anyhow::anyhow!(
+                    "schema mismatch index overflow for job {} output '{}'",
+                    job_id,
+                    mismatch.output_name
+                )
+            })?;
+            self.insert_schema_mismatch_row(
+                job_id,
+                &mismatch.output_name,
+                "order_mismatch",
+                Some(order.expected.as_str()),
+                Some(order.actual.as_str()),
+                None,
+                None,
+                Some(expected_index),
+                None,
+                now.clone(),
+            )?;
+        }
+
+        for type_mismatch in &mismatch.type_mismatches {
+            let expected_type = type_mismatch.expected.to_string();
+            let actual_type = observed_type_label(&type_mismatch.actual);
+            self.insert_schema_mismatch_row(
+                job_id,
+                &mismatch.output_name,
+                "type_mismatch",
+                Some(type_mismatch.name.as_str()),
+                None,
+                Some(expected_type.as_str()),
+                Some(actual_type.as_str()),
+                None,
+                None,
+                now.clone(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn insert_schema_mismatch_row(
+        &self,
+        job_id: i64,
+        output_name: &str,
+        mismatch_kind: &str,
+        expected_name: Option<&str>,
+        actual_name: Option<&str>,
+        expected_type: Option<&str>,
+        actual_type: Option<&str>,
+        expected_index: Option<i64>,
+        actual_index: Option<i64>,
+        created_at: i64,
+    ) -> Result<()> {
+        self.conn
+            .execute(
+                r#"
+                INSERT INTO cf_job_schema_mismatch
+                    (job_id, output_name, mismatch_kind, expected_name, actual_name, expected_type, actual_type, expected_index, actual_index, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                &[
+                    DbValue::from(job_id),
+                    DbValue::from(output_name),
+                    DbValue::from(mismatch_kind),
+                    DbValue::from(expected_name),
+                    DbValue::from(actual_name),
+                    DbValue::from(expected_type),
+                    DbValue::from(actual_type),
+                    DbValue::from(expected_index),
+                    DbValue::from(actual_index),
+                    DbValue::from(created_at),
+                ],
+            )
+            ?;
+        Ok(())
+    }
+
+    /// Move a job to dead letter.
+    ///
+    /// Captures the job's dispatch diagnostics (parser version/fingerprint,
+    /// sink config, last worker, result summary) so the entry can be
+    /// inspected and requeued after the queue row is reused.
+    pub fn move_to_dead_letter(
+        &self,
+        job_id: i64,
+        error: &str,
+        reason: DeadLetterReason,
+    ) -> Result<()> {
+        let row = self.conn.query_optional(
+            r#"
+                SELECT file_id, input_file, plugin_name, retry_count, pipeline_run_id,
+                       parser_version, parser_fingerprint, config_overrides, sink_config_json,
+                       lease_owner, claim_time, result_summary, upstream_job_id
+                FROM cf_processing_queue
+                WHERE id = ?
+                "#,
+            &[DbValue::from(job_id)],
+        )?;
+
+        let Some(row) = row else {
+            return Ok(());
+        };
+
+        let file_id: i64 = row.get_by_name("file_id")?;
+        let input_file: Option<String> = row.get_by_name("input_file")?;
+        let plugin_name: String = row.get_by_name("plugin_name")?;
+        let retry_count: i32 = row.get_by_name("retry_count")?;
+        let pipeline_run_id: Option<String> = row.get_by_name("pipeline_run_id")?;
+        let parser_version: Option<String> = row.get_by_name("parser_version")?;
+        let parser_fingerprint: Option<String> = row.get_by_name("parser_fingerprint")?;
+        let config_overrides: Option<String> = row.get_by_name("config_overrides")?;
+        let sink_config_json: Option<String> = row.get_by_name("sink_config_json")?;
+        let last_worker: Option<String> = row.get_by_name("lease_owner")?;
+        let last_claim_time: Option<i64> = row.get_by_name("claim_time")?;
+        let result_summary: Option<String> = row.get_by_name("result_summary")?;
+        let upstream_job_id: Option<i64> = row.get_by_name("upstream_job_id")?;
+
+        let now = now_millis();
+        let full_error = format!("{}: {}", reason.as_str(), error);
+        self.conn
+            .execute(
+                r#"
+                INSERT INTO cf_dead_letter (
+                    original_job_id, file_id, input_file, plugin_name, error_message, retry_count,
+                    moved_at, reason, pipeline_run_id, parser_version, parser_fingerprint,
+                    config_overrides, sink_config_json, last_worker, last_claim_time, result_summary,
+                    upstream_job_id
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                &[
+                    DbValue::from(job_id),
+                    DbValue::from(file_id),
+                    DbValue::from(input_file),
+                    DbValue::from(plugin_name),
+                    DbValue::from(full_error.as_str()),
+                    DbValue::from(retry_count),
+                    DbValue::from(now.clone()),
+                    DbValue::from(reason.as_str()),
+                    DbValue::from(pipeline_run_id),
+                    DbValue::from(parser_version),
+                    DbValue::from(parser_fingerprint),
+                    DbValue::from(config_overrides),
+                    DbValue::from(sink_config_json),
+                    DbValue::from(last_worker),
+                    DbValue::from(last_claim_time),
+                    DbValue::from(result_summary),
+                    DbValue::from(upstream_job_id),
+                ],
+            )
+            ?;
+
+        // Mark the job as FAILED with FAILED completion_status (dead-lettered)
+        self.conn.execute(
+            r#"
+                UPDATE cf_processing_queue
+                SET status = ?,
+                    completion_status = ?,
+                    end_time = ?,
+                    error_message = ?
+                WHERE id = ?
+                "#,
+            &[
+                DbValue::from(ProcessingStatus::Failed.as_str()),
+                DbValue::from(JobStatus::Failed.as_str()),
+                DbValue::from(now_millis()),
+                DbValue::from(full_error.as_str()),
+                DbValue::from(job_id),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn get_dead_letter_jobs(&self, limit: i64) -> Result<Vec<DeadLetterJob>> {
+        let rows = self.conn.query_all(
+            &format!(
+                "SELECT {} FROM cf_dead_letter ORDER BY moved_at DESC LIMIT ?",
+                column_list(DEAD_LETTER_COLUMNS)
+            ),
+            &[DbValue::from(limit)],
+        )?;
+        rows.iter()
+            .map(DeadLetterJob::from_row)
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
+    pub fn get_dead_letter_jobs_by_plugin(
+        &self,
+        plugin: &str,
+        limit: i64,
+    ) -> Result<Vec<DeadLetterJob>> {
+        let rows = self.conn.query_all(
+            &format!(
+                "SELECT {} FROM cf_dead_letter WHERE plugin_name = ? ORDER BY moved_at DESC LIMIT ?",
+                column_list(DEAD_LETTER_COLUMNS)
+            ),
+            &[DbValue::from(plugin), DbValue::from(limit)],
+        )?;
+        rows.iter()
+            .map(DeadLetterJob::from_row)
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
+    pub fn get_dead_letter_job(&self, dead_letter_id: i64) -> Result<Option<DeadLetterJob>> {
+        let row = self.conn.query_optional(
+            &format!(
+                "SELECT {} FROM cf_dead_letter WHERE id = ?",
+                column_list(DEAD_LETTER_COLUMNS)
+            ),
+            &[DbValue::from(dead_letter_id)],
+        )?;
+        row.as_ref()
+            .map(DeadLetterJob::from_row)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Requeue a dead-lettered job as a fresh queue entry and remove it from the DLQ.
+    ///
+    /// Returns the new job ID, or 0 if the entry does not exist.
+    pub fn replay_dead_letter(&self, dead_letter_id: i64) -> Result<i64> {
+        let row = self.conn.query_optional(
+            r#"
+            SELECT original_job_id, file_id, input_file, plugin_name, pipeline_run_id,
+                   config_overrides, upstream_job_id
+            FROM cf_dead_letter
+            WHERE id = ?
+            "#,
+            &[DbValue::from(dead_letter_id)],
+        )?;
+        let Some(row) = row else {
+            return Ok(0);
+        };
+
+        let file_id: Option<i64> = row.get_by_name("file_id")?;
+        let input_file: Option<String> = row.get_by_name("input_file")?;
+        let plugin_name: String = row.get_by_name("plugin_name")?;
+        let pipeline_run_id: Option<String> = row.get_by_name("pipeline_run_id")?;
+        let config_overrides: Option<String> = row.get_by_name("config_overrides")?;
+        let upstream_job_id: Option<i64> = row.get_by_name("upstream_job_id")?;
+        let input_file = input_file.filter(|value| !value.trim().is_empty()).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Dead letter {} missing input_file; cannot replay",
+                dead_letter_id
+            )
+        })?;
+
+        let new_id = job_ids::next_job_id(&self.conn)?.to_i64()?;
+        self.conn.execute(
+            r#"
+            INSERT INTO cf_processing_queue
+                (id, file_id, input_file, plugin_name, pipeline_run_id, config_overrides, status,
+                 scheduled_at, upstream_job_id, force_rerun)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            &[
+                DbValue::from(new_id),
+                DbValue::from(file_id.unwrap_or_default()),
+                DbValue::from(input_file),
+                DbValue::from(plugin_name),
+                DbValue::from(pipeline_run_id),
+                DbValue::from(config_overrides),
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(now_millis()),
+                DbValue::from(upstream_job_id),
+                DbValue::from(upstream_job_id.is_some()),
+            ],
+        )?;
+
+        self.conn.execute(
+            "DELETE FROM cf_dead_letter WHERE id = ?",
+            &[DbValue::from(dead_letter_id)],
+        )?;
+
+        Ok(new_id)
+    }
+
+    /// Delete a single dead-letter entry. Returns true if it existed.
+    pub fn purge_dead_letter(&self, dead_letter_id: i64) -> Result<bool> {
+        let affected = self.conn.execute(
+            "DELETE FROM cf_dead_letter WHERE id = ?",
+            &[DbValue::from(dead_letter_id)],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// Delete dead-letter entries moved before `moved_before` (all entries if None),
+    /// optionally restricted to one plugin. Returns the number deleted.
+    pub fn purge_dead_letters(
+        &self,
+        moved_before: Option<i64>,
+        plugin: Option<&str>,
+    ) -> Result<u64> {
+        let mut clauses = Vec::new();
+        let mut params = Vec::new();
+        if let Some(moved_before) = moved_before {
+            clauses.push("moved_at < ?");
+            params.push(DbValue::from(moved_before));
+        }
+        if let Some(plugin) = plugin {
+            clauses.push("plugin_name = ?");
+            params.push(DbValue::from(plugin));
+        }
+        let sql = if clauses.is_empty() {
+            "DELETE FROM cf_dead_letter".to_string()
+        } else {
+            format!("DELETE FROM cf_dead_letter WHERE {}", clauses.join(" AND "))
+        };
+        let affected = self.conn.execute(&sql, &params)?;
+        Ok(affected)
+    }
+
+    pub fn count_dead_letter_jobs(&self) -> Result<i64> {
+        let row = self
+            .conn
+            .query_one("SELECT COUNT(*) AS cnt FROM cf_dead_letter", &[])?;
+        Ok(row.get_by_name("cnt")?)
+    }
+
+    pub fn record_parser_success(&self, parser_name: &str) -> Result<()> {
+        let now = now_millis();
+        self.conn
+            .execute(
+                r#"
+                INSERT INTO cf_parser_health (parser_name, total_executions, successful_executions, consecutive_failures, created_at, updated_at)
+                VALUES (?, 1, 1, 0, ?, ?)
+                ON CONFLICT(parser_name) DO UPDATE SET
+                    total_executions = total_executions + 1,
+                    successful_executions = successful_executions + 1,
+                    consecutive_failures = 0,
+                    updated_at = ?
+                "#,
+                &[
+                    DbValue::from(parser_name),
+                    DbValue::from(now.clone()),
+                    DbValue::from(now.clone()),
+                    DbValue::from(now),
+                ],
+            )
+            ?;
+        Ok(())
+    }
+
+    pub fn record_parser_failure(&self, parser_name: &str, reason: &str) -> Result<i32> {
+        let now = now_millis();
+        self.conn
+            .execute(
+                r#"
+                INSERT INTO cf_parser_health (parser_name, total_executions, successful_executions, consecutive_failures, last_failure_reason, created_at, updated_at)
+                VALUES (?, 1, 0, 1, ?, ?, ?)
+                ON CONFLICT(parser_name) DO UPDATE SET
+                    total_executions = total_executions + 1,
+                    consecutive_failures = consecutive_failures + 1,
+                    last_failure_reason = ?,
+                    updated_at = ?
+                "#,
+                &[
+                    DbValue::from(parser_name),
+                    DbValue::from(reason),
+                    DbValue::from(now.clone()),
+                    DbValue::from(now.clone()),
+                    DbValue::from(reason),
+                    DbValue::from(now),
+                ],
+            )
+            ?;
+
+        let health = self.get_parser_health(parser_name)?;
+        Ok(health.map(|h| h.consecutive_failures).unwrap_or(0))
+    }
+
+    pub fn pause_parser(&self, parser_name: &str) -> Result<()> {
+        let now = now_millis();
+        self.conn.execute(
+            "UPDATE cf_parser_health SET paused_at = ?, updated_at = ? WHERE parser_name = ?",
+            &[
+                DbValue::from(now.clone()),
+                DbValue::from(now),
+                DbValue::from(parser_name),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn resume_parser(&self, parser_name: &str) -> Result<()> {
+        let now = now_millis();
+        self.conn.execute(
+            "UPDATE cf_parser_health SET paused_at = NULL, updated_at = ? WHERE parser_name = ?",
+            &[DbValue::from(now), DbValue::from(parser_name)],
+        )?;
+        Ok(())
+    }
+
+    pub fn is_parser_paused(&self, parser_name: &str) -> Result<bool> {
+        let row = self.conn.query_optional(
+            "SELECT paused_at FROM cf_parser_health WHERE parser_name = ?",
+            &[DbValue::from(parser_name)],
+        )?;
+        Ok(row
+            .and_then(|r| r.get_by_name::<Option<i64>>("paused_at").ok())
+            .flatten()
+            .is_some())
+    }
+
+    pub fn get_parser_health(&self, parser_name: &str) -> Result<Option<ParserHealth>> {
+        let row = self.conn.query_optional(
+            &format!(
+                "SELECT {} FROM cf_parser_health WHERE parser_name = ?",
+                column_list(PARSER_HEALTH_COLUMNS)
+            ),
+            &[DbValue::from(parser_name)],
+        )?;
+        Ok(row.map(|row| ParserHealth::from_row(&row)).transpose()?)
+    }
+
+    pub fn get_all_parser_health(&self) -> Result<Vec<ParserHealth>> {
+        let rows = self.conn.query_all(
+            &format!(
+                "SELECT {} FROM cf_parser_health",
+                column_list(PARSER_HEALTH_COLUMNS)
+            ),
+            &[],
+        )?;
+        rows.iter()
+            .map(ParserHealth::from_row)
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Retry policy configured for a plugin, if any.
+    pub fn get_retry_policy(&self, plugin_name: &str) -> Result<Option<RetryPolicy>> {
+        let json: Option<String> = self
+            .conn
+            .query_optional(
+                "SELECT retry_policy_json FROM cf_plugin_config WHERE plugin_name = ?",
+                &[DbValue::from(plugin_name)],
+            )?
+            .map(|row| row.get_by_name::<Option<String>>("retry_policy_json"))
+            .transpose()?
+            .flatten();
+        json.map(|json| {
+            serde_json::from_str(&json)
+                .with_context(|| format!("Invalid retry policy for plugin '{}'", plugin_name))
+        })
+        .transpose()
+    }
+
+    /// Set (or clear, with None) a plugin's retry policy.
+    pub fn set_retry_policy(&self, plugin_name: &str, policy: Option<&RetryPolicy>) -> Result<()> {
+        if let Some(policy) = policy {
+            policy.validate().map_err(anyhow::Error::msg)?;
+        }
+        let json = policy.map(serde_json::to_string).transpose()?;
+        let now = now_millis();
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_plugin_config (plugin_name, retry_policy_json, updated_at)
+                VALUES (?, ?, ?)
+                ON CONFLICT(plugin_name) DO UPDATE SET
+                    retry_policy_json = ?,
+                    updated_at = ?
+                "#,
+            &[
+                DbValue::from(plugin_name),
+                DbValue::from(json.as_deref()),
+                DbValue::from(now),
+                DbValue::from(json.as_deref()),
+                DbValue::from(now),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Resource limits configured for a plugin, if any.
+    pub fn get_resource_limits(&self, plugin_name: &str) -> Result<Option<ResourceLimits>> {
+        let json: Option<String> = self
+            .conn
+            .query_optional(
+                "SELECT resource_limits_json FROM cf_plugin_config WHERE plugin_name = ?",
+                &[DbValue::from(plugin_name)],
+            )?
+            .map(|row| row.get_by_name::<Option<String>>("resource_limits_json"))
+            .transpose()?
+            .flatten();
+        json.map(|json| {
+            serde_json::from_str(&json)
+                .with_context(|| format!("Invalid resource limits for plugin '{}'", plugin_name))
+        })
+        .transpose()
+    }
+
+    /// Set (or clear, with None) a plugin's resource limits.
+    pub fn set_resource_limits(
+        &self,
+        plugin_name: &str,
+        limits: Option<&ResourceLimits>,
+    ) -> Result<()> {
+        if let Some(limits) = limits {
+            limits.validate().map_err(anyhow::Error::msg)?;
+        }
+        let limits = limits.filter(|limits| !limits.is_unlimited());
+        let json = limits.map(serde_json::to_string).transpose()?;
+        let now = now_millis();
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_plugin_config (plugin_name, resource_limits_json, updated_at)
+                VALUES (?, ?, ?)
+                ON CONFLICT(plugin_name) DO UPDATE SET
+                    resource_limits_json = ?,
+                    updated_at = ?
+                "#,
+            &[
+                DbValue::from(plugin_name),
+                DbValue::from(json.as_deref()),
+                DbValue::from(now),
+                DbValue::from(json.as_deref()),
+                DbValue::from(now),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// CSV dialect configured for a plugin, if any.
+    pub fn get_csv_dialect(&self, plugin_name: &str) -> Result<Option<CsvDialect>> {
+        let json: Option<String> = self
+            .conn
+            .query_optional(
+                "SELECT csv_dialect_json FROM cf_plugin_config WHERE plugin_name = ?",
+                &[DbValue::from(plugin_name)],
+            )?
+            .map(|row| row.get_by_name::<Option<String>>("csv_dialect_json"))
+            .transpose()?
+            .flatten();
+        json.map(|json| {
+            serde_json::from_str(&json)
+                .with_context(|| format!("Invalid CSV dialect for plugin '{}'", plugin_name))
+        })
+        .transpose()
+    }
+
+    /// Set (or clear, with None) a plugin's CSV dialect.
+    pub fn set_csv_dialect(&self, plugin_name: &str, dialect: Option<&CsvDialect>) -> Result<()> {
+        if let Some(dialect) = dialect {
+            dialect.validate().map_err(anyhow::Error::msg)?;
+        }
+        let json = dialect.map(serde_json::to_string).transpose()?;
+        let now = now_millis();
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_plugin_config (plugin_name, csv_dialect_json, updated_at)
+                VALUES (?, ?, ?)
+                ON CONFLICT(plugin_name) DO UPDATE SET
+                    csv_dialect_json = ?,
+                    updated_at = ?
+                "#,
+            &[
+                DbValue::from(plugin_name),
+                DbValue::from(json.as_deref()),
+                DbValue::from(now),
+                DbValue::from(json.as_deref()),
+                DbValue::from(now),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// CSV dialect to dispatch with a job: the highest-priority tagging rule
+    /// of the file that sets one, else the plugin's, else the dialect scout
+    /// sniffed from the file when the detection is trusted.
+    pub fn resolve_csv_dialect(
+        &self,
+        plugin_name: &str,
+        file_id: i64,
+    ) -> Result<Option<CsvDialect>> {
+        let rule_json: Option<String> = self
+            .conn
+            .query_optional(
+                r#"
+                SELECT r.csv_dialect
+                FROM scout_file_tags t
+                JOIN scout_rules r ON r.id = t.rule_id
+                WHERE t.file_id = ? AND r.csv_dialect IS NOT NULL
+                ORDER BY r.priority DESC, r.id
+                LIMIT 1
+                "#,
+                &[DbValue::from(file_id)],
+            )?
+            .map(|row| row.get::<Option<String>>(0))
+            .transpose()?
+            .flatten();
+        if let Some(json) = rule_json {
+            let dialect = serde_json::from_str(&json)
+                .with_context(|| format!("Invalid CSV dialect on rule for file {}", file_id))?;
+            return Ok(Some(dialect));
+        }
+
+        if let Some(dialect) = self.get_csv_dialect(plugin_name)? {
+            return Ok(Some(dialect));
+        }
+
+        let detected_json: Option<String> = self
+            .conn
+            .query_optional(
+                r#"
+                SELECT csv_dialect FROM scout_files
+                WHERE id = ? AND detection_confidence IN (?, ?)
+                "#,
+                &[
+                    DbValue::from(file_id),
+                    DbValue::from(DetectionConfidence::High.as_str()),
+                    DbValue::from(DetectionConfidence::Medium.as_str()),
+                ],
+            )?
+            .map(|row| row.get::<Option<String>>(0))
+            .transpose()?
+            .flatten();
+        detected_json
+            .map(|json| {
+                serde_json::from_str(&json)
+                    .with_context(|| format!("Invalid detected CSV dialect for file {}", file_id))
+            })
+            .transpose()
+    }
+
+    /// Text encoding override for a file: the highest-priority tagging rule
+    /// of the file that sets one. `None` leaves detection to the worker.
+    pub fn resolve_input_encoding(&self, file_id: i64) -> Result<Option<TextEncoding>> {
+        let raw: Option<String> = self
+            .conn
+            .query_optional(
+                r#"
+                SELECT r.input_encoding
+                FROM scout_file_tags t
+                JOIN scout_rules r ON r.id = t.rule_id
+                WHERE t.file_id = ? AND r.input_encoding IS NOT NULL
+                ORDER BY r.priority DESC, r.id
+                LIMIT 1
+                "#,
+                &[DbValue::from(file_id)],
+            )?
+            .map(|row| row.get::<Option<String>>(0))
+            .transpose()?
+            .flatten();
+        raw.map(|raw| {
+            raw.parse().map_err(|e: String| {
+                anyhow::anyhow!("Invalid encoding on rule for file {}: {}", file_id, e)
+            })
+        })
+        .transpose()
+    }
+
+    /// Record a failed attempt of a job.
+    pub fn record_retry_attempt(&self, job_id: i64, attempt: &RetryAttempt) -> Result<()> {
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_job_retries
+                    (job_id, attempt, error_class, error_message, failed_at, retry_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            &[
+                DbValue::from(job_id),
+                DbValue::from(attempt.attempt as i64),
+                DbValue::from(attempt.error_class.as_str()),
+                DbValue::from(attempt.error_message.as_str()),
+                DbValue::from(attempt.failed_at),
+                DbValue::from(attempt.retry_at),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Failed attempts of a job, oldest first.
+    pub fn list_job_retries(&self, job_id: i64) -> Result<Vec<RetryAttempt>> {
+        let rows = self.conn.query_all(
+            r#"
+                SELECT attempt, error_class, error_message, failed_at, retry_at
+                FROM cf_job_retries
+                WHERE job_id = ?
+                ORDER BY attempt ASC, id ASC
+                "#,
+            &[DbValue::from(job_id)],
+        )?;
+        rows.iter()
+            .map(|row| -> Result<RetryAttempt> {
+                let attempt: i64 = row.get_by_name("attempt")?;
+                Ok(RetryAttempt {
+                    attempt: u32::try_from(attempt).unwrap_or(0),
+                    error_class: row.get_by_name("error_class")?,
+                    error_message: row
+                        .get_by_name::<Option<String>>("error_message")?
+                        .unwrap_or_default(),
+                    failed_at: row.get_by_name("failed_at")?,
+                    retry_at: row.get_by_name("retry_at")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Store a job's diagnostics, replacing those of an earlier attempt.
+    pub fn record_job_diagnostics(&self, job_id: i64, diagnostics: &JobDiagnostics) -> Result<()> {
+        let json = serde_json::to_string(diagnostics)?;
+        let category = diagnostics.error_category.map(|category| category.as_str());
+        let stage = diagnostics.failed_stage.map(|stage| stage.as_str());
+        let now = now_millis();
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_job_diagnostics
+                    (job_id, error_category, error_class, failed_stage, diagnostics_json, recorded_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT(job_id) DO UPDATE SET
+                    error_category = ?,
+                    error_class = ?,
+                    failed_stage = ?,
+                    diagnostics_json = ?,
+                    recorded_at = ?
+                "#,
+            &[
+                DbValue::from(job_id),
+                DbValue::from(category),
+                DbValue::from(diagnostics.error_class.as_deref()),
+                DbValue::from(stage),
+                DbValue::from(json.as_str()),
+                DbValue::from(now),
+                DbValue::from(category),
+                DbValue::from(diagnostics.error_class.as_deref()),
+                DbValue::from(stage),
+                DbValue::from(json.as_str()),
+                DbValue::from(now),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Diagnostics of a job's latest attempt, if the worker reported any.
+    pub fn get_job_diagnostics(&self, job_id: i64) -> Result<Option<JobDiagnostics>> {
+        let json: Option<String> = self
+            .conn
+            .query_optional(
+                "SELECT diagnostics_json FROM cf_job_diagnostics WHERE job_id = ?",
+                &[DbValue::from(job_id)],
+            )?
+            .map(|row| row.get_by_name("diagnostics_json"))
+            .transpose()?;
+        json.map(|json| {
+            serde_json::from_str(&json)
+                .with_context(|| format!("Invalid diagnostics for job {}", job_id))
+        })
+        .transpose()
+    }
+
+    /// Attach a receipt verification result to the job's diagnostics, keeping
+    /// whatever the worker reported.
+    pub fn record_receipt_verification(
+        &self,
+        job_id: i64,
+        verification: &ReceiptVerification,
+    ) -> Result<()> {
+        let mut diagnostics = self.get_job_diagnostics(job_id)?.unwrap_or_default();
+        diagnostics.verification = Some(verification.clone());
+        self.record_job_diagnostics(job_id, &diagnostics)
+    }
+
+    /// Number of jobs whose latest attempt failed, per error category, most
+    /// frequent first.
+    pub fn count_failures_by_category(&self) -> Result<Vec<(ErrorCategory, i64)>> {
+        let rows = self.conn.query_all(
+            r#"
+                SELECT error_category, COUNT(*) AS jobs
+                FROM cf_job_diagnostics
+                WHERE error_category IS NOT NULL
+                GROUP BY error_category
+                ORDER BY jobs DESC, error_category ASC
+                "#,
+            &[],
+        )?;
+        let mut counts = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let category: String = row.get_by_name("error_category")?;
+            // Categories from newer workers are skipped rather than failing the summary
+            if let Ok(category) = category.parse::<ErrorCategory>() {
+                counts.push((category, row.get_by_name("jobs")?));
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Apply a batch of buffered heartbeats, log chunks and metric samples
+    /// in one transaction.
+    pub fn apply_write_batch(&self, batch: FlushBatch) -> Result<()> {
+        batch.apply(&self.conn)
+    }
+
+    /// Append a chunk of a running job's log. `offset` is the chunk's byte
+    /// position in the worker's log file, so re-sent chunks are ignored.
+    pub fn append_job_log(&self, job_id: i64, offset: i64, chunk: &str) -> Result<()> {
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_job_logs (job_id, byte_offset, chunk, created_at)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT (job_id, byte_offset) DO NOTHING
+                "#,
+            &[
+                DbValue::from(job_id),
+                DbValue::from(offset),
+                DbValue::from(chunk),
+                DbValue::from(now_millis()),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Log chunks of a job starting at or after `from_offset`, in order.
+    pub fn read_job_log(
+        &self,
+        job_id: i64,
+        from_offset: i64,
+        limit: i64,
+    ) -> Result<Vec<JobLogChunk>> {
+        let rows = self.conn.query_all(
+            r#"
+                SELECT byte_offset, chunk, created_at
+                FROM cf_job_logs
+                WHERE job_id = ? AND byte_offset >= ?
+                ORDER BY byte_offset ASC
+                LIMIT ?
+                "#,
+            &[
+                DbValue::from(job_id),
+                DbValue::from(from_offset),
+                DbValue::from(limit),
+            ],
+        )?;
+        rows.iter()
+            .map(|row| -> Result<JobLogChunk> {
+                Ok(JobLogChunk {
+                    offset: row.get_by_name("byte_offset")?,
+                    chunk: row.get_by_name("chunk")?,
+                    created_at: row.get_by_name("created_at")?,
+                })
+            })
+            .collect()
+    }
+
+    pub fn quarantine_row(
+        &self,
+        job_id: i64,
+        row_index: i64,
+        error: &str,
+        raw: Option<&[u8]>,
+    ) -> Result<()> {
+        let now = now_millis();
+        self.conn.execute(
+            r#"
+                INSERT INTO cf_quarantine (job_id, row_index, error_reason, raw_data, created_at)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            &[
+                DbValue::from(job_id),
+                DbValue::from(row_index),
+                DbValue::from(error),
+                DbValue::from(raw.map(|v| v.to_vec())),
+                DbValue::from(now),
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_quarantined_rows(&self, job_id: i64) -> Result<Vec<QuarantinedRowSummary>> {
+        let rows = self.conn.query_all(
+            &format!(
+                "SELECT {} FROM cf_quarantine WHERE job_id = ? ORDER BY row_index",
+                column_list(QUARANTINE_LIST_COLUMNS)
+            ),
+            &[DbValue::from(job_id)],
+        )?;
+        rows.iter()
+            .map(QuarantinedRowSummary::from_row)
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
+    pub fn get_quarantined_rows_with_raw(&self, job_id: i64) -> Result<Vec<QuarantinedRow>> {
+        let rows = self.conn.query_all(
+            &format!(
+                "SELECT {} FROM cf_quarantine WHERE job_id = ? ORDER BY row_index",
+                column_list(QUARANTINE_COLUMNS)
+            ),
+            &[DbValue::from(job_id)],
+        )?;
+        rows.iter()
+            .map(QuarantinedRow::from_row)
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
+    pub fn count_quarantined_rows(&self, job_id: i64) -> Result<i64> {
+        let row = self.conn.query_one(
+            "SELECT COUNT(*) AS cnt FROM cf_quarantine WHERE job_id = ?",
+            &[DbValue::from(job_id)],
+        )?;
+        Ok(row.get_by_name("cnt")?)
+    }
+
+    pub fn delete_quarantined_rows(&self, job_id: i64) -> Result<u64> {
+        let affected = self.conn.execute(
+            "DELETE FROM cf_quarantine WHERE job_id = ?",
+            &[DbValue::from(job_id)],
+        )?;
+        Ok(affected)
+    }
+
+    // ========================================================================
+    // Canonical Job Query API
+    // ========================================================================
+
+    /// List jobs with optional status filter.
+    ///
+    /// Returns jobs from `cf_processing_queue` ordered by creation time (newest first).
+    pub fn list_jobs(
+        &self,
+        status: Option<ProcessingStatus>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Job>> {
+        let limit_i64 = i64::try_from(limit).context("list_jobs limit exceeds i64::MAX")?;
+        let offset_i64 = i64::try_from(offset).context("list_jobs offset exceeds i64::MAX")?;
+
+        let (sql, params): (&str, Vec<DbValue>) = match status {
+            Some(s) => (
+                r#"
+                SELECT id, file_id, plugin_name, status, priority, retry_count,
+                       scheduled_at, claim_time, end_time, error_message,
+                       completion_status, parser_version, pipeline_run_id,
+                       result_summary, result_summary_json, quarantine_rows
+                FROM cf_processing_queue
+                WHERE status = ?
+                ORDER BY scheduled_at DESC
+                LIMIT ? OFFSET ?
+                "#,
+                vec![
+                    DbValue::from(s.as_str()),
+                    DbValue::from(limit_i64),
+                    DbValue::from(offset_i64),
+                ],
+            ),
+            None => (
+                r#"
+                SELECT id, file_id, plugin_name, status, priority, retry_count,
+                       scheduled_at, claim_time, end_time, error_message,
+                       completion_status, parser_version, pipeline_run_id,
+                       result_summary, result_summary_json, quarantine_rows
+                FROM cf_processing_queue
+                ORDER BY scheduled_at DESC
+                LIMIT ? OFFSET ?
+                "#,
+                vec![DbValue::from(limit_i64), DbValue::from(offset_i64)],
+            ),
+        };
+
+        let rows = self.conn.query_all(sql, &params)?;
+        rows.iter()
+            .map(Job::from_row)
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Query job history with filters, server-side sort, and cursor pagination.
+    ///
+    /// Pages are keyset-paginated on `(sort key, id)`, so deep pages cost the
+    /// same as the first one. Pass the returned `next_cursor` back in
+    /// `query.cursor` to fetch the following page; cursors are only valid for
+    /// the sort they were produced with.
+    pub fn query_job_history(&self, query: &JobHistoryQuery) -> Result<JobHistoryPage> {
+        let limit = query.limit.clamp(1, MAX_JOB_HISTORY_PAGE);
+        let sort_key = query.sort.sort_key();
+        let mut clauses: Vec<String> = Vec::new();
+        let mut params: Vec<DbValue> = Vec::new();
+
+        if !query.statuses.is_empty() {
+            let placeholders = vec!["?"; query.statuses.len()].join(", ");
+            clauses.push(format!("q.status IN ({})", placeholders));
+            params.extend(query.statuses.iter().map(|s| DbValue::from(s.as_str())));
+        }
+        if let Some(plugin_name) = &query.plugin_name {
+            clauses.push("q.plugin_name = ?".to_string());
+            params.push(DbValue::from(plugin_name.as_str()));
+        }
+        if let Some(namespace) = &query.namespace {
+            if table_exists(&self.conn, "cf_plugin_manifest")? {
+                clauses.push(format!("{} = ?", plugin_namespace_sql("q.plugin_name")));
+                params.push(DbValue::from(namespace.as_str()));
+            } else if namespace != DEFAULT_NAMESPACE {
+                return Ok(JobHistoryPage::default());
+            }
+        }
+        if let Some(tag) = &query.tag {
+            if !table_exists(&self.conn, "scout_file_tags")? {
+                return Ok(JobHistoryPage::default());
+            }
+            clauses.push(
+                "EXISTS (SELECT 1 FROM scout_file_tags t WHERE t.file_id = q.file_id AND t.tag = ?)"
+                    .to_string(),
+            );
+            params.push(DbValue::from(tag.as_str()));
+        }
+        if let Some(after) = query.created_after {
+            clauses.push("q.scheduled_at >= ?".to_string());
+            params.push(DbValue::from(after));
+        }
+        if let Some(before) = query.created_before {
+            clauses.push("q.scheduled_at < ?".to_string());
+            params.push(DbValue::from(before));
+        }
+        let cmp = if query.ascending { ">" } else { "<" };
+        if let Some(cursor) = &query.cursor {
+            let (id, key) = query.sort.decode_cursor(cursor)?;
+            clauses.push(format!(
+                "({key_col} {cmp} ? OR ({key_col} = ? AND q.id {cmp} ?))",
+                key_col = sort_key,
+                cmp = cmp
+            ));
+            params.push(key.clone());
+            params.push(key);
+            params.push(DbValue::from(id));
+        }
+
+        let where_sql = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+        let direction = if query.ascending { "ASC" } else { "DESC" };
+        let sql = format!(
+            r#"
+            SELECT q.id, q.file_id, q.plugin_name, q.status, q.priority, q.retry_count,
+                   q.scheduled_at, q.claim_time, q.end_time, q.error_message,
+                   q.completion_status, q.parser_version, q.pipeline_run_id,
+                   q.result_summary, q.result_summary_json, q.quarantine_rows,
+                   {sort_key} AS sort_key
+            FROM cf_processing_queue q
+            {where_sql}
+            ORDER BY sort_key {direction}, q.id {direction}
+            LIMIT ?
+            "#,
+            sort_key = sort_key,
+            where_sql = where_sql,
+            direction = direction
+        );
+        let fetch = i64::try_from(limit + 1).context("job history limit exceeds i64::MAX")?;
+        params.push(DbValue::from(fetch));
+
+        let rows = self.conn.query_all(&sql, &params)?;
+        let has_more = rows.len() > limit;
+        let mut jobs = Vec::with_capacity(rows.len().min(limit));
+        let mut next_cursor = None;
+        for row in rows.iter().take(limit) {
+            let job = Job::from_row(row)?;
+            if has_more {
+                let id = job.id.to_i64().context("job_id exceeds i64::MAX")?;
+                next_cursor = Some(query.sort.encode_cursor(id, row)?);
+            }
+            jobs.push(job);
+        }
+
+        Ok(JobHistoryPage { jobs, next_cursor })
+    }
+
+    /// Get a single job by ID.
+    ///
+    /// Returns `None` if the job does not exist.
+    pub fn get_job(&self, job_id: JobId) -> Result<Option<Job>> {
+        let job_id_i64 = job_id.to_i64().context("job_id exceeds i64::MAX")?;
+
+        let sql = r#"
+            SELECT id, file_id, plugin_name, status, priority, retry_count,
+                   scheduled_at, claim_time, end_time, error_message,
+                   completion_status, parser_version, pipeline_run_id,
+                   result_summary, result_summary_json, quarantine_rows
+            FROM cf_processing_queue
+            WHERE id = ?
+        "#;
+
+        let row = self
+            .conn
+            .query_optional(sql, &[DbValue::from(job_id_i64)])?;
+
+        match row {
+            Some(r) => Ok(Some(Job::from_row(&r)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Cancel a queued, pending, or dispatching job.
+    ///
+    /// Only jobs in QUEUED, PENDING, or DISPATCHING status can be cancelled. Jobs that are already
+    /// RUNNING, COMPLETED, or FAILED are not affected.
+    ///
+    /// Returns `true` if the job was cancelled, `false` if it was not found or
+    /// was already in a terminal state.
+    pub fn cancel_job(&self, job_id: JobId) -> Result<bool> {
+        let job_id_i64 = job_id.to_i64().context("job_id exceeds i64::MAX")?;
+        let now = now_millis();
+
+        // Only cancel jobs that are in cancellable states
+        let affected = self.conn.execute(
+            r#"
+            UPDATE cf_processing_queue
+            SET status = ?,
+                completion_status = ?,
+                end_time = ?,
+                error_message = ?
+            WHERE id = ? AND status IN (?, ?, ?)
+            "#,
+            &[
+                DbValue::from(ProcessingStatus::Aborted.as_str()),
+                DbValue::from(JobStatus::Aborted.as_str()),
+                DbValue::from(now),
+                DbValue::from(casparian_protocol::defaults::CANCELLED_BY_USER_MESSAGE),
+                DbValue::from(job_id_i64),
+                DbValue::from(ProcessingStatus::Queued.as_str()),
+                DbValue::from(ProcessingStatus::Pending.as_str()),
+                DbValue::from(ProcessingStatus::Dispatching.as_str()),
+            ],
+        )?;
+
+        Ok(affected > 0)
+    }
+
+    /// Cancel a RUNNING job on behalf of the user.
+    ///
+    /// The row is marked ABORTED immediately; the owning worker still has to be
+    /// sent ABORT. Its eventual CONCLUDE no longer matches a RUNNING row and is
+    /// treated as stale, so cancellation wins over a late success.
+    ///
+    /// Returns `true` if the job was running and is now cancelled.
+    pub fn cancel_running_job(&self, job_id: JobId) -> Result<bool> {
+        let job_id_i64 = job_id.to_i64().context("job_id exceeds i64::MAX")?;
+        let affected = self.conn.execute(
+            r#"
+            UPDATE cf_processing_queue
+            SET status = ?,
+                completion_status = ?,
+                end_time = ?,
+                error_message = ?
+            WHERE id = ? AND status = ?
+            "#,
+            &[
+                DbValue::from(ProcessingStatus::Aborted.as_str()),
+                DbValue::from(JobStatus::Aborted.as_str()),
+                DbValue::from(now_millis()),
+                DbValue::from(casparian_protocol::defaults::CANCELLED_BY_USER_MESSAGE),
+                DbValue::from(job_id_i64),
+                DbValue::from(ProcessingStatus::Running.as_str()),
+            ],
+        )?;
+
+        Ok(affected > 0)
+    }
+
+    /// Get job count grouped by status.
+    ///
+    /// Returns a map from ProcessingStatus to count. Only statuses with non-zero
+    /// counts are included in the map.
+    pub fn count_jobs_by_status(&self) -> Result<HashMap<ProcessingStatus, i64>> {
+        let sql = r#"
+            SELECT status, COUNT(*) AS cnt
+            FROM cf_processing_queue
+            GROUP BY status
+        "#;
+
+        let rows = self.conn.query_all(sql, &[])?;
+        let mut counts = HashMap::new();
+
+        for row in &rows {
+            let status_str: String = row.get_by_name("status")?;
+            let count: i64 = row.get_by_name("cnt")?;
+
+            if let Ok(status) = status_str.parse::<ProcessingStatus>() {
+                counts.insert(status, count);
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// List running job ids for a specific lease owner.
+    pub fn list_running_jobs_by_owner(&self, lease_owner: &str) -> Result<Vec<i64>> {
+        let rows = self.conn.query_all(
+            r#"
+                SELECT id
+                FROM cf_processing_queue
+                WHERE status = ? AND lease_owner = ?
+                "#,
+            &[
+                DbValue::from(ProcessingStatus::Running.as_str()),
+                DbValue::from(lease_owner),
+            ],
+        )?;
+        let mut jobs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: i64 = row.get_by_name("id")?;
+            jobs.push(id);
+        }
+        Ok(jobs)
+    }
+
+    /// List running job ids with their lease owner (if any).
+    pub fn list_running_jobs_with_owner(&self) -> Result<Vec<(i64, Option<String>)>> {
+        let rows = self.conn.query_all(
+            r#"
+                SELECT id, lease_owner
+                FROM cf_processing_queue
+                WHERE status = ?
+                "#,
+            &[DbValue::from(ProcessingStatus::Running.as_str())],
+        )?;
+        let mut jobs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: i64 = row.get_by_name("id")?;
+            let lease_owner: Option<String> = row.get_by_name("lease_owner")?;
+            jobs.push((id, lease_owner));
+        }
+        Ok(jobs)
+    }
+}
+
+fn table_exists(conn: &DbConnection, table: &str) -> Result<bool> {
+    Ok(conn.table_exists(table)?)
+}
+
+/// SQL expression for the namespace of the plugin named by `plugin_column`.
+///
+/// Plugins missing from `cf_plugin_manifest` belong to `DEFAULT_NAMESPACE`.
+pub(crate) fn plugin_namespace_sql(plugin_column: &str) -> String {
+    format!(
+        "COALESCE((SELECT pm.namespace FROM cf_plugin_manifest pm \
+         WHERE pm.plugin_name = {} LIMIT 1), '{}')",
+        plugin_column, DEFAULT_NAMESPACE
+    )
+}
+
+fn set_pipeline_run_running(conn: &DbConnection, run_id: &str) -> Result<()> {
+    if !table_exists(conn, "cf_pipeline_runs")? {
+        return Ok(());
+    }
+    conn.execute(
+        r#"
+            UPDATE cf_pipeline_runs
+            SET status = ?,
+                started_at = COALESCE(started_at, ?)
+            WHERE id = ?
+            "#,
+        &[
+            DbValue::from(PipelineRunStatus::Running.as_str()),
+            DbValue::from(now_millis()),
+            DbValue::from(run_id),
+        ],
+    )?;
+    Ok(())
+}
+
+fn update_pipeline_run_status(conn: &DbConnection, run_id: &str) -> Result<()> {
+    if !table_exists(conn, "cf_pipeline_runs")? {
+        return Ok(());
+    }
+
+    let row = conn.query_optional(
+        &format!(
+            r#"
+            SELECT
+                SUM(CASE WHEN status IN ('{failed}', '{aborted}') THEN 1 ELSE 0 END) AS failed,
+                SUM(CASE WHEN status IN ('{queued}', '{running}') THEN 1 ELSE 0 END) AS active,
+                SUM(CASE WHEN status IN ('{completed}', '{skipped}') THEN 1 ELSE 0 END) AS completed
+            FROM cf_processing_queue
+            WHERE pipeline_run_id = ?
+            "#,
+            failed = ProcessingStatus::Failed.as_str(),
+            aborted = ProcessingStatus::Aborted.as_str(),
+            queued = ProcessingStatus::Queued.as_str(),
+            running = ProcessingStatus::Running.as_str(),
+            completed = ProcessingStatus::Completed.as_str(),
+            skipped = ProcessingStatus::Skipped.as_str(),
+        ),
+        &[DbValue::from(run_id)],
+    )?;
+
+    let Some(row) = row else {
+        return Ok(());
+    };
+
+    let failed: i64 = row.get_by_name("failed").unwrap_or(0);
+    let active: i64 = row.get_by_name("active").unwrap_or(0);
+    let completed: i64 = row.get_by_name("completed").unwrap_or(0);
+
+    if failed > 0 {
+        conn.execute(
+            "UPDATE cf_pipeline_runs SET status = ?, completed_at = ? WHERE id = ?",
+            &[
+                DbValue::from(PipelineRunStatus::Failed.as_str()),
+                DbValue::from(now_millis()),
+                DbValue::from(run_id),
+            ],
+        )?;
+        return Ok(());
+    }
+
+    if active > 0 {
+        set_pipeline_run_running(conn, run_id)?;
+        return Ok(());
+    }
+
+    if completed > 0 {
+        conn.execute(
+            "UPDATE cf_pipeline_runs SET status = ?, completed_at = ? WHERE id = ?",
+            &[
+                DbValue::from(PipelineRunStatus::Completed.as_str()),
+                DbValue::from(now_millis()),
+                DbValue::from(run_id),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QueueStats {
     pub queued: i64,
     pub running: i64,
     pub completed: i64,
     pub failed: i64,
 }
 
-// ============================================================================
-// Canonical Job Model
-// ============================================================================
+/// Largest page `query_job_history` returns, regardless of the requested limit.
+pub const MAX_JOB_HISTORY_PAGE: usize = 1000;
+
+/// Column a job history page is ordered by. Ties are broken by job id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JobHistorySort {
+    #[default]
+    CreatedAt,
+    FinishedAt,
+    PluginName,
+    Status,
+}
+
+impl JobHistorySort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CreatedAt => "created_at",
+            Self::FinishedAt => "finished_at",
+            Self::PluginName => "plugin_name",
+            Self::Status => "status",
+        }
+    }
+
+    /// SQL expression the page is ordered by. Unfinished jobs sort as 0.
+    fn sort_key(self) -> &'static str {
+        match self {
+            Self::CreatedAt => "q.scheduled_at",
+            Self::FinishedAt => "COALESCE(q.end_time, 0)",
+            Self::PluginName => "q.plugin_name",
+            Self::Status => "q.status",
+        }
+    }
+
+    fn is_text(self) -> bool {
+        matches!(self, Self::PluginName | Self::Status)
+    }
+
+    /// Cursor format: `<id>:<sort key>`. The id goes first so text keys may
+    /// contain `:`.
+    fn encode_cursor(self, id: i64, row: &UnifiedDbRow) -> Result<String> {
+        let key = if self.is_text() {
+            row.get_by_name::<String>("sort_key")?
+        } else {
+            row.get_by_name::<i64>("sort_key")?.to_string()
+        };
+        Ok(format!("{}:{}", id, key))
+    }
+
+    fn decode_cursor(self, cursor: &str) -> Result<(i64, DbValue)> {
+        let invalid = || anyhow::anyhow!("Invalid job history cursor '{}'", cursor);
+        let (id, key) = cursor.split_once(':').ok_or_else(invalid)?;
+        let id: i64 = id.parse().map_err(|_| invalid())?;
+        let key = if self.is_text() {
+            DbValue::from(key)
+        } else {
+            DbValue::from(key.parse::<i64>().map_err(|_| invalid())?)
+        };
+        Ok((id, key))
+    }
+}
+
+impl std::str::FromStr for JobHistorySort {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "created_at" | "created" => Ok(Self::CreatedAt),
+            "finished_at" | "finished" => Ok(Self::FinishedAt),
+            "plugin_name" | "plugin" => Ok(Self::PluginName),
+            "status" => Ok(Self::Status),
+            other => Err(format!(
+                "Unknown sort '{}' (expected created_at, finished_at, plugin_name, or status)",
+                other
+            )),
+        }
+    }
+}
+
+/// Filters, sort, and page position for `JobQueue::query_job_history`.
+///
+/// Dates are epoch millis against the job's scheduled time; `created_after`
+/// is inclusive and `created_before` exclusive.
+#[derive(Debug, Clone, Default)]
+pub struct JobHistoryQuery {
+    /// Match any of these statuses (empty = all)
+    pub statuses: Vec<ProcessingStatus>,
+    pub plugin_name: Option<String>,
+    /// Only jobs of plugins deployed into this namespace
+    pub namespace: Option<String>,
+    /// Only jobs whose input file carries this tag
+    pub tag: Option<String>,
+    pub created_after: Option<i64>,
+    pub created_before: Option<i64>,
+    pub sort: JobHistorySort,
+    /// Default is newest/last first
+    pub ascending: bool,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    /// Clamped to `1..=MAX_JOB_HISTORY_PAGE`
+    pub limit: usize,
+}
+
+/// One page of job history.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JobHistoryPage {
+    pub jobs: Vec<Job>,
+    /// Cursor for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+// ============================================================================
+// Canonical Job Model
+// ============================================================================
+
+/// Canonical job representation for UI/API.
+///
+/// This is the single source of truth for job status, backed by `cf_processing_queue`.
+/// Use this instead of `cf_api_jobs` to avoid split-brain issues.
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    /// Unique job identifier
+    pub id: JobId,
+    /// File being processed (foreign key to scout_files)
+    pub file_id: i64,
+    /// Plugin/parser name
+    pub plugin_name: String,
+    /// Current processing status (QUEUED, RUNNING, COMPLETED, FAILED, etc.)
+    pub status: ProcessingStatus,
+    /// Job priority (higher = processed first)
+    pub priority: i32,
+    /// Number of retry attempts
+    pub retry_count: i32,
+    /// When the job was created/scheduled
+    pub created_at: Option<i64>,
+    /// When the job was last updated (claimed, completed, etc.)
+    pub updated_at: Option<i64>,
+    /// Error message if job failed
+    pub error_message: Option<String>,
+    /// Completion outcome (SUCCESS, FAILED, PARTIAL_SUCCESS, etc.)
+    pub completion_status: Option<JobStatus>,
+    /// Parser version used for this job
+    pub parser_version: Option<String>,
+    /// Pipeline run ID for correlation
+    pub pipeline_run_id: Option<String>,
+    /// Result summary text
+    pub result_summary: Option<String>,
+    /// Structured result (artifacts, counters, warnings) of a concluded job
+    pub result: Option<JobResultSummary>,
+    /// Number of quarantined rows
+    pub quarantine_rows: i64,
+}
+
+impl Job {
+    /// Parse Job from a database row.
+    pub fn from_row(row: &UnifiedDbRow) -> Result<Self, BackendError> {
+        let status_str: String = row.get_by_name("status")?;
+        let status = status_str.parse::<ProcessingStatus>().map_err(|e| {
+            BackendError::TypeConversion(format!(
+                "Invalid processing status '{}': {}",
+                status_str, e
+            ))
+        })?;
+
+        // Parse completion_status if present
+        let completion_status_raw: Option<String> = row.get_by_name("completion_status")?;
+        let completion_status = match completion_status_raw {
+            Some(s) if !s.is_empty() => Some(s.parse::<JobStatus>().map_err(|e| {
+                BackendError::TypeConversion(format!("Invalid completion status '{}': {}", s, e))
+            })?),
+            _ => None,
+        };
+
+        let id_raw: i64 = row.get_by_name("id")?;
+        let id = JobId::try_from(id_raw).map_err(|e| {
+            BackendError::TypeConversion(format!("Invalid job id '{}': {}", id_raw, e))
+        })?;
+
+        let result_raw: Option<String> = row.get_by_name("result_summary_json")?;
+        let result = result_raw
+            .map(|json| {
+                serde_json::from_str(&json).map_err(|e| {
+                    BackendError::TypeConversion(format!("Invalid result summary JSON: {}", e))
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            id,
+            file_id: row.get_by_name("file_id")?,
+            plugin_name: row.get_by_name("plugin_name")?,
+            status,
+            priority: row.get_by_name("priority")?,
+            retry_count: row.get_by_name("retry_count")?,
+            created_at: row.get_by_name("scheduled_at")?,
+            updated_at: row.get_by_name("end_time").ok().flatten().or_else(|| {
+                row.get_by_name::<Option<i64>>("claim_time")
+                    .ok()
+                    .flatten()
+            }),
+            error_message: row.get_by_name("error_message")?,
+            completion_status,
+            parser_version: row.get_by_name("parser_version")?,
+            pipeline_run_id: row.get_by_name("pipeline_run_id")?,
+            result_summary: row.get_by_name("result_summary")?,
+            result,
+            quarantine_rows: row.get_by_name("quarantine_rows").unwrap_or(0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_db::DbConnection;
+
+    fn setup_queue() -> JobQueue {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        let queue = JobQueue::new(conn);
+        queue.init_queue_schema().unwrap();
+        queue
+    }
+
+    fn enqueue_test_job(queue: &JobQueue, plugin_name: &str, file_id: i64) -> i64 {
+        enqueue_test_job_with_priority(queue, plugin_name, file_id, 0)
+    }
 
-/// Canonical job representation for UI/API.
-///
-/// This is the single source of truth for job status, backed by `cf_processing_queue`.
-/// Use this instead of `cf_api_jobs` to avoid split-brain issues.
-#[derive(Debug, Clone, Serialize)]
-pub struct Job {
-    /// Unique job identifier
-    pub id: JobId,
-    /// File being processed (foreign key to scout_files)
-    pub file_id: i64,
-    /// Plugin/parser name
-    pub plugin_name: String,
-    /// Current processing status (QUEUED, RUNNING, COMPLETED, FAILED, etc.)
-    pub status: ProcessingStatus,
-    /// Job priority (higher = processed first)
-    pub priority: i32,
-    /// Number of retry attempts
-    pub retry_count: i32,
-    /// When the job was created/scheduled
-    pub created_at: Option<i64>,
-    /// When the job was last updated (claimed, completed, etc.)
-    pub updated_at: Option<i64>,
-    /// Error message if job failed
-    pub error_message: Option<String>,
-    /// Completion outcome (SUCCESS, FAILED, PARTIAL_SUCCESS, etc.)
-    pub completion_status: Option<JobStatus>,
-    /// Parser version used for this job
-    pub parser_version: Option<String>,
-    /// Pipeline run ID for correlation
-    pub pipeline_run_id: Option<String>,
-    /// Result summary text
-    pub result_summary: Option<String>,
-    /// Number of quarantined rows
-    pub quarantine_rows: i64,
-}
+    fn enqueue_test_job_with_priority(
+        queue: &JobQueue,
+        plugin_name: &str,
+        file_id: i64,
+        priority: i32,
+    ) -> i64 {
+        let input_file = format!("/tmp/test_{}.csv", file_id);
+        queue
+            .conn
+            .query_scalar::<i64>(
+                r#"
+                INSERT INTO cf_processing_queue (file_id, input_file, plugin_name, status, priority, scheduled_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                RETURNING id
+                "#,
+                &[
+                    DbValue::from(file_id),
+                    DbValue::from(input_file.as_str()),
+                    DbValue::from(plugin_name),
+                    DbValue::from(ProcessingStatus::Queued.as_str()),
+                    DbValue::from(priority),
+                    DbValue::from(now_millis()),
+                ],
+            )
+            .unwrap()
+    }
 
-impl Job {
-    /// Parse Job from a database row.
-    pub fn from_row(row: &UnifiedDbRow) -> Result<Self, BackendError> {
-        let status_str: String = row.get_by_name("status")?;
-        let status = status_str.parse::<ProcessingStatus>().map_err(|e| {
-            BackendError::TypeConversion(format!(
-                "Invalid processing status '{}': {}",
-                status_str, e
-            ))
-        })?;
+    #[test]
+    fn test_list_jobs_empty() {
+        let queue = setup_queue();
+        let jobs = queue.list_jobs(None, 100, 0).unwrap();
+        assert!(jobs.is_empty());
+    }
+
+    #[test]
+    fn test_list_jobs_returns_all() {
+        let queue = setup_queue();
+
+        enqueue_test_job(&queue, "parser_a", 1);
+        enqueue_test_job(&queue, "parser_b", 2);
+        enqueue_test_job(&queue, "parser_c", 3);
+
+        let jobs = queue.list_jobs(None, 100, 0).unwrap();
+        assert_eq!(jobs.len(), 3);
+
+        // Check all jobs are present
+        let names: Vec<&str> = jobs.iter().map(|j| j.plugin_name.as_str()).collect();
+        assert!(names.contains(&"parser_a"));
+        assert!(names.contains(&"parser_b"));
+        assert!(names.contains(&"parser_c"));
+    }
+
+    #[test]
+    fn test_list_jobs_with_status_filter() {
+        let queue = setup_queue();
+
+        let job1_id = enqueue_test_job(&queue, "parser_a", 1);
+        enqueue_test_job(&queue, "parser_b", 2);
+
+        // Mark job1 as completed
+        queue
+            .complete_job(job1_id, JobStatus::Success.as_str(), "done", None)
+            .unwrap();
+
+        // Filter by QUEUED status
+        let queued_jobs = queue
+            .list_jobs(Some(ProcessingStatus::Queued), 100, 0)
+            .unwrap();
+        assert_eq!(queued_jobs.len(), 1);
+        assert_eq!(queued_jobs[0].plugin_name, "parser_b");
+
+        // Filter by COMPLETED status
+        let completed_jobs = queue
+            .list_jobs(Some(ProcessingStatus::Completed), 100, 0)
+            .unwrap();
+        assert_eq!(completed_jobs.len(), 1);
+        assert_eq!(completed_jobs[0].plugin_name, "parser_a");
+    }
+
+    #[test]
+    fn test_lease_batch_jobs_filters_plugin_and_size() {
+        let queue = setup_queue();
+        queue
+            .conn
+            .execute_batch(
+                r#"
+                CREATE TABLE scout_files (id BIGINT PRIMARY KEY, size BIGINT NOT NULL);
+                INSERT INTO scout_files VALUES (1, 100), (2, 100), (3, 100), (4, 5000);
+                "#,
+            )
+            .unwrap();
+        let small = enqueue_test_job(&queue, "parser_a", 1);
+        enqueue_test_job(&queue, "parser_b", 2);
+        let small_2 = enqueue_test_job(&queue, "parser_a", 3);
+        enqueue_test_job(&queue, "parser_a", 4);
+
+        let leased = queue
+            .lease_batch_jobs_for_dispatch("parser_a", 1024, 10, now_millis(), 5_000)
+            .unwrap();
+        let ids: Vec<i64> = leased.iter().map(|job| job.id).collect();
+        assert_eq!(ids, vec![small, small_2]);
+
+        let again = queue
+            .lease_batch_jobs_for_dispatch("parser_a", 1024, 10, now_millis(), 5_000)
+            .unwrap();
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn test_lease_jobs_batching_updates_status() {
+        let queue = setup_queue();
+
+        let id_high = enqueue_test_job_with_priority(&queue, "parser_high", 1, 10);
+        let id_mid = enqueue_test_job_with_priority(&queue, "parser_mid", 2, 5);
+        let id_low = enqueue_test_job_with_priority(&queue, "parser_low", 3, 0);
+
+        let claimed = queue.lease_jobs_for_dispatch(2, now_millis(), 5_000).unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert_eq!(claimed[0].id, id_high);
+        assert_eq!(claimed[1].id, id_mid);
+
+        let queued = queue
+            .list_jobs(Some(ProcessingStatus::Queued), 10, 0)
+            .unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].id, id_low);
+
+        let dispatching = queue
+            .list_jobs(Some(ProcessingStatus::Dispatching), 10, 0)
+            .unwrap();
+        let dispatching_ids: Vec<i64> = dispatching.iter().map(|job| job.id).collect();
+        assert!(dispatching_ids.contains(&id_high));
+        assert!(dispatching_ids.contains(&id_mid));
+    }
+
+    #[test]
+    fn test_promote_starved_jobs_and_preempt() {
+        let queue = setup_queue();
+
+        let id_low =
+            enqueue_test_job_with_priority(&queue, "parser_low", 1, JobPriority::Low.value());
+        let id_urgent =
+            enqueue_test_job_with_priority(&queue, "parser_urgent", 2, JobPriority::Urgent.value());
+
+        // Nothing has waited since before the epoch; then both have, but the
+        // urgent job is already above the aging lane.
+        assert_eq!(queue.promote_starved_jobs(0, JobPriority::High).unwrap(), 0);
+        let promoted = queue
+            .promote_starved_jobs(now_millis() + 1, JobPriority::High)
+            .unwrap();
+        assert_eq!(promoted, 1);
+
+        let claimed = queue
+            .lease_jobs_for_dispatch(1, now_millis(), 5_000)
+            .unwrap();
+        assert_eq!(claimed[0].id, id_urgent);
+        assert!(queue
+            .set_dispatch_lease(id_urgent, "lease-1", "worker-1")
+            .unwrap());
+
+        assert!(!queue
+            .preempt_job_if_token_matches(id_urgent, "stale", now_millis())
+            .unwrap());
+        assert!(queue
+            .preempt_job_if_token_matches(id_urgent, "lease-1", now_millis())
+            .unwrap());
+
+        let queued = queue
+            .list_jobs(Some(ProcessingStatus::Queued), 10, 0)
+            .unwrap();
+        let low = queued.iter().find(|job| job.id.as_u64() == id_low as u64).unwrap();
+        assert_eq!(low.priority, JobPriority::High.value());
+        let urgent = queued.iter().find(|job| job.id.as_u64() == id_urgent as u64).unwrap();
+        assert_eq!(urgent.priority, JobPriority::Urgent.value());
+        assert_eq!(urgent.retry_count, 0);
+    }
+
+    #[test]
+    fn test_list_jobs_pagination() {
+        let queue = setup_queue();
+
+        for i in 1..=5 {
+            enqueue_test_job(&queue, &format!("parser_{}", i), i);
+        }
+
+        // Get first 2
+        let page1 = queue.list_jobs(None, 2, 0).unwrap();
+        assert_eq!(page1.len(), 2);
+
+        // Get next 2
+        let page2 = queue.list_jobs(None, 2, 2).unwrap();
+        assert_eq!(page2.len(), 2);
+
+        // Get last 1
+        let page3 = queue.list_jobs(None, 2, 4).unwrap();
+        assert_eq!(page3.len(), 1);
+
+        // Offset beyond range
+        let page4 = queue.list_jobs(None, 2, 10).unwrap();
+        assert!(page4.is_empty());
+    }
+
+    #[test]
+    fn test_query_job_history_cursor_and_filters() {
+        let queue = setup_queue();
+        for i in 1..=5 {
+            let plugin = if i % 2 == 0 {
+                "parser_even"
+            } else {
+                "parser_odd"
+            };
+            enqueue_test_job(&queue, plugin, i);
+        }
+
+        // Walk every page newest first; ties on scheduled_at break by id.
+        let mut query = JobHistoryQuery {
+            limit: 2,
+            ..Default::default()
+        };
+        let mut seen = Vec::new();
+        loop {
+            let page = queue.query_job_history(&query).unwrap();
+            seen.extend(page.jobs.iter().map(|job| job.file_id));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(seen, vec![5, 4, 3, 2, 1]);
+
+        let page = queue
+            .query_job_history(&JobHistoryQuery {
+                plugin_name: Some("parser_even".to_string()),
+                sort: JobHistorySort::PluginName,
+                ascending: true,
+                limit: 10,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.jobs.len(), 2);
+        assert!(page.next_cursor.is_none());
+
+        // Tag filter without a scout schema matches nothing
+        let tagged = JobHistoryQuery {
+            tag: Some("finance".to_string()),
+            limit: 10,
+            ..Default::default()
+        };
+        assert!(queue.query_job_history(&tagged).unwrap().jobs.is_empty());
+        queue
+            .conn
+            .execute_batch(
+                "CREATE TABLE scout_file_tags (workspace_id TEXT, file_id BIGINT, tag TEXT);
+                 INSERT INTO scout_file_tags VALUES ('ws', 3, 'finance');",
+            )
+            .unwrap();
+        let page = queue.query_job_history(&tagged).unwrap();
+        assert_eq!(page.jobs.len(), 1);
+        assert_eq!(page.jobs[0].file_id, 3);
+
+        // Unregistered plugins belong to the default namespace
+        let in_namespace = |namespace: &str| JobHistoryQuery {
+            namespace: Some(namespace.to_string()),
+            limit: 10,
+            ..Default::default()
+        };
+        let page = queue.query_job_history(&in_namespace("default")).unwrap();
+        assert_eq!(page.jobs.len(), 5);
+        assert!(queue
+            .query_job_history(&in_namespace("risk"))
+            .unwrap()
+            .jobs
+            .is_empty());
+        queue.init_registry_schema().unwrap();
+        insert_plugin_version(&queue, "parser_even", "1.0.0", PluginStatus::Active, 1);
+        queue
+            .conn
+            .execute(
+                "UPDATE cf_plugin_manifest SET namespace = 'risk' WHERE plugin_name = ?",
+                &[DbValue::from("parser_even")],
+            )
+            .unwrap();
+        assert_eq!(queue.plugin_namespace("parser_even").unwrap(), "risk");
+        assert_eq!(queue.plugin_namespace("parser_odd").unwrap(), "default");
+        let page = queue.query_job_history(&in_namespace("risk")).unwrap();
+        assert_eq!(
+            page.jobs.iter().map(|job| job.file_id).collect::<Vec<_>>(),
+            vec![4, 2]
+        );
+
+        let bad_cursor = JobHistoryQuery {
+            cursor: Some("not-a-cursor".to_string()),
+            limit: 10,
+            ..Default::default()
+        };
+        assert!(queue.query_job_history(&bad_cursor).is_err());
+    }
+
+    #[test]
+    fn test_get_job_existing() {
+        let queue = setup_queue();
+        let job_id = enqueue_test_job(&queue, "test_parser", 42);
+
+        let job = queue
+            .get_job(JobId::try_from(job_id).unwrap())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(job.id.as_u64(), job_id as u64);
+        assert_eq!(job.plugin_name, "test_parser");
+        assert_eq!(job.file_id, 42);
+        assert_eq!(job.status, ProcessingStatus::Queued);
+    }
 
-        // Parse completion_status if present
-        let completion_status_raw: Option<String> = row.get_by_name("completion_status")?;
-        let completion_status = match completion_status_raw {
-            Some(s) if !s.is_empty() => Some(s.parse::<JobStatus>().map_err(|e| {
-                BackendError::TypeConversion(format!("Invalid completion status '{}': {}", s, e))
-            })?),
-            _ => None,
-        };
+    #[test]
+    fn test_get_job_non_existing() {
+        let queue = setup_queue();
+        let job = queue.get_job(JobId::new(99999)).unwrap();
+        assert!(job.is_none());
+    }
 
-        let id_raw: i64 = row.get_by_name("id")?;
-        let id = JobId::try_from(id_raw).map_err(|e| {
-            BackendError::TypeConversion(format!("Invalid job id '{}': {}", id_raw, e))
-        })?;
+    #[test]
+    fn test_cancel_job_queued() {
+        let queue = setup_queue();
+        let job_id = enqueue_test_job(&queue, "test_parser", 1);
 
-        Ok(Self {
-            id,
-            file_id: row.get_by_name("file_id")?,
-            plugin_name: row.get_by_name("plugin_name")?,
-            status,
-            priority: row.get_by_name("priority")?,
-            retry_count: row.get_by_name("retry_count")?,
-            created_at: row.get_by_name("scheduled_at")?,
-            updated_at: row.get_by_name("end_time").ok().flatten().or_else(|| {
-                row.get_by_name::<Option<i64>>("claim_time")
-                    .ok()
-                    .flatten()
-            }),
-            error_message: row.get_by_name("error_message")?,
-            completion_status,
-            parser_version: row.get_by_name("parser_version")?,
-            pipeline_run_id: row.get_by_name("pipeline_run_id")?,
-            result_summary: row.get_by_name("result_summary")?,
-            quarantine_rows: row.get_by_name("quarantine_rows").unwrap_or(0),
-        })
+        let cancelled = queue.cancel_job(JobId::try_from(job_id).unwrap()).unwrap();
+        assert!(cancelled);
+
+        // Verify job is now ABORTED with ABORTED completion_status
+        let job = queue
+            .get_job(JobId::try_from(job_id).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.status, ProcessingStatus::Aborted);
+        assert_eq!(job.completion_status, Some(JobStatus::Aborted));
+        assert_eq!(
+            job.error_message,
+            Some(casparian_protocol::defaults::CANCELLED_BY_USER_MESSAGE.to_string())
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use casparian_db::DbConnection;
+    #[test]
+    fn test_cancel_job_already_completed() {
+        let queue = setup_queue();
+        let job_id = enqueue_test_job(&queue, "test_parser", 1);
 
-    fn setup_queue() -> JobQueue {
-        let conn = DbConnection::open_duckdb_memory().unwrap();
-        let queue = JobQueue::new(conn);
-        queue.init_queue_schema().unwrap();
+        // Complete the job
         queue
-    }
+            .complete_job(job_id, JobStatus::Success.as_str(), "done", None)
+            .unwrap();
 
-    fn enqueue_test_job(queue: &JobQueue, plugin_name: &str, file_id: i64) -> i64 {
-        enqueue_test_job_with_priority(queue, plugin_name, file_id, 0)
+        // Try to cancel - should return false
+        let cancelled = queue.cancel_job(JobId::try_from(job_id).unwrap()).unwrap();
+        assert!(!cancelled);
+
+        // Verify job is still COMPLETED
+        let job = queue
+            .get_job(JobId::try_from(job_id).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.status, ProcessingStatus::Completed);
     }
 
-    fn enqueue_test_job_with_priority(
-        queue: &JobQueue,
-        plugin_name: &str,
-        file_id: i64,
-        priority: i32,
-    ) -> i64 {
-        let input_file = format!("/tmp/test_{}.csv", file_id);
+    #[test]
+    fn test_skip_already_materialized_job() {
+        let queue = setup_queue();
+        let prior_job = enqueue_test_job(&queue, "test_parser", 1);
         queue
-            .conn
-            .query_scalar::<i64>(
-                r#"
-                INSERT INTO cf_processing_queue (file_id, input_file, plugin_name, status, priority, scheduled_at)
-                VALUES (?, ?, ?, ?, ?, ?)
-                RETURNING id
-                "#,
-                &[
-                    DbValue::from(file_id),
-                    DbValue::from(input_file.as_str()),
-                    DbValue::from(plugin_name),
-                    DbValue::from(ProcessingStatus::Queued.as_str()),
-                    DbValue::from(priority),
-                    DbValue::from(now_millis()),
-                ],
-            )
+            .insert_output_materialization(&OutputMaterialization {
+                materialization_key: "mat-1".to_string(),
+                output_target_key: "target-1".to_string(),
+                file_id: 1,
+                file_mtime: 100,
+                file_size: 200,
+                plugin_name: "test_parser".to_string(),
+                parser_version: Some("1.0.0".to_string()),
+                parser_fingerprint: "fp".to_string(),
+                output_name: "orders".to_string(),
+                sink_uri: "parquet:///tmp/out".to_string(),
+                sink_mode: SinkMode::Append,
+                table_name: Some("orders".to_string()),
+                schema_hash: None,
+                status: "success".to_string(),
+                rows: 42,
+                job_id: prior_job,
+            })
+            .unwrap();
+
+        let found = queue
+            .find_output_materializations(&["mat-1".to_string(), "mat-2".to_string()])
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].rows, 42);
+        assert_eq!(found[0].job_id, prior_job);
+        assert_eq!(found[0].sink_mode, SinkMode::Append);
+
+        let job_id = enqueue_test_job(&queue, "test_parser", 2);
+        queue.fail_job(prior_job, "FAILED", "done").unwrap();
+        let leased = queue
+            .lease_jobs_for_dispatch(1, now_millis(), 5_000)
+            .unwrap();
+        assert_eq!(leased[0].id, job_id);
+        assert!(queue
+            .set_dispatch_lease(job_id, "token", "worker-1")
+            .unwrap());
+
+        assert!(!queue
+            .skip_job_if_token_matches_dispatching(job_id, "stale", "Already materialized")
+            .unwrap());
+        assert!(queue
+            .skip_job_if_token_matches_dispatching(job_id, "token", "Already materialized")
+            .unwrap());
+        let job = queue
+            .get_job(JobId::try_from(job_id).unwrap())
             .unwrap()
+            .unwrap();
+        assert_eq!(job.status, ProcessingStatus::Skipped);
+        assert_eq!(job.completion_status, Some(JobStatus::Success));
     }
 
     #[test]
-    fn test_list_jobs_empty() {
+    fn test_cancel_running_job_makes_conclude_stale() {
         let queue = setup_queue();
-        let jobs = queue.list_jobs(None, 100, 0).unwrap();
-        assert!(jobs.is_empty());
+        let job_id = enqueue_test_job(&queue, "test_parser", 1);
+        let leased = queue.lease_jobs_for_dispatch(1, now_millis(), 5_000).unwrap();
+        let token = leased[0].lease_token.clone().unwrap();
+        assert!(queue
+            .ack_dispatch(job_id, &token, "worker-1", now_millis())
+            .unwrap());
+
+        let id = JobId::try_from(job_id).unwrap();
+        assert!(!queue.cancel_job(id).unwrap());
+        assert!(queue.cancel_running_job(id).unwrap());
+
+        let job = queue.get_job(id).unwrap().unwrap();
+        assert_eq!(job.status, ProcessingStatus::Aborted);
+        assert!(!queue
+            .complete_job_if_token_matches(job_id, &token, JobStatus::Success.as_str(), "{}", None)
+            .unwrap());
+        assert!(!queue.cancel_running_job(id).unwrap());
     }
 
     #[test]
-    fn test_list_jobs_returns_all() {
+    fn test_cancel_job_non_existing() {
+        let queue = setup_queue();
+        let cancelled = queue.cancel_job(JobId::new(99999)).unwrap();
+        assert!(!cancelled);
+    }
+
+    #[test]
+    fn test_count_jobs_by_status() {
         let queue = setup_queue();
 
-        enqueue_test_job(&queue, "parser_a", 1);
+        // Create jobs in different states
+        let job1 = enqueue_test_job(&queue, "parser_a", 1);
         enqueue_test_job(&queue, "parser_b", 2);
-        enqueue_test_job(&queue, "parser_c", 3);
+        let job3 = enqueue_test_job(&queue, "parser_c", 3);
 
-        let jobs = queue.list_jobs(None, 100, 0).unwrap();
-        assert_eq!(jobs.len(), 3);
+        // Complete job1
+        queue
+            .complete_job(job1, JobStatus::Success.as_str(), "done", None)
+            .unwrap();
 
-        // Check all jobs are present
-        let names: Vec<&str> = jobs.iter().map(|j| j.plugin_name.as_str()).collect();
-        assert!(names.contains(&"parser_a"));
-        assert!(names.contains(&"parser_b"));
-        assert!(names.contains(&"parser_c"));
+        // Fail job3
+        queue
+            .fail_job(job3, JobStatus::Failed.as_str(), "error")
+            .unwrap();
+
+        let counts = queue.count_jobs_by_status().unwrap();
+
+        assert_eq!(counts.get(&ProcessingStatus::Queued), Some(&1));
+        assert_eq!(counts.get(&ProcessingStatus::Completed), Some(&1));
+        assert_eq!(counts.get(&ProcessingStatus::Failed), Some(&1));
+        // RUNNING should not exist (no jobs in that state)
+        assert!(!counts.contains_key(&ProcessingStatus::Running));
     }
 
     #[test]
-    fn test_list_jobs_with_status_filter() {
+    fn test_count_jobs_by_status_empty() {
         let queue = setup_queue();
+        let counts = queue.count_jobs_by_status().unwrap();
+        assert!(counts.is_empty());
+    }
 
-        let job1_id = enqueue_test_job(&queue, "parser_a", 1);
-        enqueue_test_job(&queue, "parser_b", 2);
+    #[test]
+    fn test_dead_letter_captures_diagnostics_and_replays() {
+        let queue = setup_queue();
+        queue.init_error_handling_schema().unwrap();
 
-        // Mark job1 as completed
+        let job_id = enqueue_test_job(&queue, "parser_dlq", 7);
         queue
-            .complete_job(job1_id, JobStatus::Success.as_str(), "done", None)
+            .record_dispatch_metadata(job_id, "1.2.0", "fp-abc", r#"{"sinks":[]}"#)
+            .unwrap();
+        queue
+            .move_to_dead_letter(job_id, "boom", DeadLetterReason::PermanentError)
             .unwrap();
 
-        // Filter by QUEUED status
-        let queued_jobs = queue
-            .list_jobs(Some(ProcessingStatus::Queued), 100, 0)
+        let entries = queue.get_dead_letter_jobs(10).unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.original_job_id, job_id);
+        assert_eq!(entry.parser_version.as_deref(), Some("1.2.0"));
+        assert_eq!(entry.parser_fingerprint.as_deref(), Some("fp-abc"));
+        assert_eq!(entry.reason.as_deref(), Some("permanent_error"));
+
+        let new_job_id = queue.replay_dead_letter(entry.id).unwrap();
+        assert_ne!(new_job_id, 0);
+        assert_eq!(queue.count_dead_letter_jobs().unwrap(), 0);
+        let job = queue
+            .get_job(JobId::new(u64::try_from(new_job_id).unwrap()))
+            .unwrap()
             .unwrap();
-        assert_eq!(queued_jobs.len(), 1);
-        assert_eq!(queued_jobs[0].plugin_name, "parser_b");
+        assert_eq!(job.status, ProcessingStatus::Queued);
+    }
 
-        // Filter by COMPLETED status
-        let completed_jobs = queue
-            .list_jobs(Some(ProcessingStatus::Completed), 100, 0)
+    #[test]
+    fn test_purge_dead_letters() {
+        let queue = setup_queue();
+        queue.init_error_handling_schema().unwrap();
+
+        for (file_id, plugin) in [(1, "parser_a"), (2, "parser_a"), (3, "parser_b")] {
+            let job_id = enqueue_test_job(&queue, plugin, file_id);
+            queue
+                .move_to_dead_letter(job_id, "boom", DeadLetterReason::MaxRetriesExceeded)
+                .unwrap();
+        }
+
+        let first = queue.get_dead_letter_jobs_by_plugin("parser_b", 10).unwrap();
+        assert!(queue.purge_dead_letter(first[0].id).unwrap());
+        assert!(!queue.purge_dead_letter(first[0].id).unwrap());
+
+        assert_eq!(queue.purge_dead_letters(Some(0), None).unwrap(), 0);
+        assert_eq!(queue.purge_dead_letters(None, Some("parser_a")).unwrap(), 2);
+        assert_eq!(queue.count_dead_letter_jobs().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_retry_policy_and_history() {
+        let queue = setup_queue();
+        queue.init_error_handling_schema().unwrap();
+
+        assert!(queue.get_retry_policy("parser_a").unwrap().is_none());
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            backoff_base_secs: 2,
+            jitter: 0.25,
+            retryable_errors: vec!["transient".to_string(), "bridge".to_string()],
+        };
+        queue.set_retry_policy("parser_a", Some(&policy)).unwrap();
+        assert_eq!(queue.get_retry_policy("parser_a").unwrap(), Some(policy));
+        queue.set_retry_policy("parser_a", None).unwrap();
+        assert!(queue.get_retry_policy("parser_a").unwrap().is_none());
+
+        let invalid = RetryPolicy {
+            max_attempts: 0,
+            ..RetryPolicy::default()
+        };
+        assert!(queue.set_retry_policy("parser_a", Some(&invalid)).is_err());
+
+        // Resource limits share the plugin config row without clobbering it
+        let policy = RetryPolicy::default();
+        queue.set_retry_policy("parser_a", Some(&policy)).unwrap();
+        assert!(queue.get_resource_limits("parser_a").unwrap().is_none());
+        let limits = ResourceLimits {
+            timeout_secs: Some(600),
+            max_cpu_secs: None,
+            max_memory_bytes: Some(2 * 1024 * 1024 * 1024),
+            max_decompressed_bytes: Some(20 * 1024 * 1024 * 1024),
+            max_scratch_bytes: Some(50 * 1024 * 1024 * 1024),
+        };
+        queue
+            .set_resource_limits("parser_a", Some(&limits))
             .unwrap();
-        assert_eq!(completed_jobs.len(), 1);
-        assert_eq!(completed_jobs[0].plugin_name, "parser_a");
+        assert_eq!(queue.get_resource_limits("parser_a").unwrap(), Some(limits));
+        assert_eq!(queue.get_retry_policy("parser_a").unwrap(), Some(policy));
+        let too_small = ResourceLimits {
+            max_memory_bytes: Some(1024),
+            ..ResourceLimits::default()
+        };
+        assert!(queue
+            .set_resource_limits("parser_a", Some(&too_small))
+            .is_err());
+        queue
+            .set_resource_limits("parser_a", Some(&ResourceLimits::default()))
+            .unwrap();
+        assert!(queue.get_resource_limits("parser_a").unwrap().is_none());
+        queue.set_retry_policy("parser_a", None).unwrap();
+
+        let job_id = enqueue_test_job(&queue, "parser_a", 1);
+        for (attempt, retry_at) in [(2, None), (1, Some(5_000))] {
+            queue
+                .record_retry_attempt(
+                    job_id,
+                    &RetryAttempt {
+                        attempt,
+                        error_class: "transient".to_string(),
+                        error_message: format!("timeout {}", attempt),
+                        failed_at: 1_000 * i64::from(attempt),
+                        retry_at,
+                    },
+                )
+                .unwrap();
+        }
+        let history = queue.list_job_retries(job_id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].attempt, 1);
+        assert_eq!(history[0].retry_at, Some(5_000));
+        assert_eq!(history[1].error_message, "timeout 2");
+        assert!(queue.list_job_retries(job_id + 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_csv_dialect_precedence() {
+        let queue = setup_queue();
+        queue
+            .conn
+            .execute_batch(
+                r#"
+                CREATE TABLE scout_files (
+                    id BIGINT, detection_confidence TEXT, csv_dialect TEXT
+                );
+                CREATE TABLE scout_rules (
+                    id TEXT, priority INTEGER, csv_dialect TEXT
+                );
+                CREATE TABLE scout_file_tags (file_id BIGINT, tag TEXT, rule_id TEXT);
+                INSERT INTO scout_files VALUES
+                    (1, 'HIGH', '{"delimiter":";"}'),
+                    (2, 'LOW', '{"delimiter":";"}');
+                "#,
+            )
+            .unwrap();
+        let semicolon = CsvDialect {
+            delimiter: ';',
+            ..CsvDialect::default()
+        };
+        assert_eq!(
+            queue.resolve_csv_dialect("parser_a", 1).unwrap(),
+            Some(semicolon.clone())
+        );
+        // Low-confidence detections are not trusted.
+        assert_eq!(queue.resolve_csv_dialect("parser_a", 2).unwrap(), None);
+
+        let pipe = CsvDialect {
+            delimiter: '|',
+            skip_rows: 2,
+            ..CsvDialect::default()
+        };
+        queue.set_csv_dialect("parser_a", Some(&pipe)).unwrap();
+        assert_eq!(
+            queue.get_csv_dialect("parser_a").unwrap(),
+            Some(pipe.clone())
+        );
+        assert_eq!(
+            queue.resolve_csv_dialect("parser_a", 1).unwrap(),
+            Some(pipe.clone())
+        );
+        let invalid = CsvDialect {
+            quote: ',',
+            ..CsvDialect::default()
+        };
+        assert!(queue.set_csv_dialect("parser_a", Some(&invalid)).is_err());
+
+        // The highest-priority rule with a dialect wins over the plugin.
+        queue
+            .conn
+            .execute_batch(
+                r#"
+                INSERT INTO scout_rules VALUES
+                    ('low', 1, '{"delimiter":"\t"}'),
+                    ('high', 5, '{"delimiter":";","skip_rows":1}'),
+                    ('none', 9, NULL);
+                INSERT INTO scout_file_tags VALUES
+                    (1, 'a', 'low'), (1, 'b', 'high'), (1, 'c', 'none');
+                "#,
+            )
+            .unwrap();
+        assert_eq!(
+            queue.resolve_csv_dialect("parser_a", 1).unwrap(),
+            Some(CsvDialect {
+                skip_rows: 1,
+                ..semicolon
+            })
+        );
+        assert_eq!(
+            queue.resolve_csv_dialect("parser_a", 2).unwrap(),
+            Some(pipe)
+        );
+        queue.set_csv_dialect("parser_a", None).unwrap();
+        assert!(queue.get_csv_dialect("parser_a").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resolve_input_encoding_from_rules() {
+        let queue = setup_queue();
+        queue
+            .conn
+            .execute_batch(
+                r#"
+                CREATE TABLE scout_rules (id TEXT, priority INTEGER, input_encoding TEXT);
+                CREATE TABLE scout_file_tags (file_id BIGINT, tag TEXT, rule_id TEXT);
+                INSERT INTO scout_rules VALUES
+                    ('low', 1, 'latin-1'),
+                    ('high', 5, 'utf-16le'),
+                    ('none', 9, NULL);
+                INSERT INTO scout_file_tags VALUES
+                    (1, 'a', 'low'), (1, 'b', 'high'), (1, 'c', 'none'),
+                    (2, 'a', 'low'),
+                    (3, 'c', 'none');
+                "#,
+            )
+            .unwrap();
+        assert_eq!(
+            queue.resolve_input_encoding(1).unwrap(),
+            Some(TextEncoding::Utf16Le)
+        );
+        assert_eq!(
+            queue.resolve_input_encoding(2).unwrap(),
+            Some(TextEncoding::Latin1)
+        );
+        assert_eq!(queue.resolve_input_encoding(3).unwrap(), None);
     }
 
     #[test]
-    fn test_lease_jobs_batching_updates_status() {
+    fn test_job_log_chunks_append_in_order() {
         let queue = setup_queue();
+        queue.init_error_handling_schema().unwrap();
 
-        let id_high = enqueue_test_job_with_priority(&queue, "parser_high", 1, 10);
-        let id_mid = enqueue_test_job_with_priority(&queue, "parser_mid", 2, 5);
-        let id_low = enqueue_test_job_with_priority(&queue, "parser_low", 3, 0);
+        queue.append_job_log(7, 6, "line 2\n").unwrap();
+        queue.append_job_log(7, 0, "line1\n").unwrap();
+        // Re-sent chunk is ignored
+        queue.append_job_log(7, 6, "line 2\n").unwrap();
+        queue.append_job_log(8, 0, "other job\n").unwrap();
 
-        let claimed = queue.lease_jobs_for_dispatch(2, now_millis(), 5_000).unwrap();
-        assert_eq!(claimed.len(), 2);
-        assert_eq!(claimed[0].id, id_high);
-        assert_eq!(claimed[1].id, id_mid);
+        let chunks = queue.read_job_log(7, 0, 100).unwrap();
+        let offsets: Vec<i64> = chunks.iter().map(|c| c.offset).collect();
+        assert_eq!(offsets, vec![0, 6]);
+        assert_eq!(chunks[1].chunk, "line 2\n");
 
-        let queued = queue
-            .list_jobs(Some(ProcessingStatus::Queued), 10, 0)
-            .unwrap();
-        assert_eq!(queued.len(), 1);
-        assert_eq!(queued[0].id, id_low);
+        let tail = queue.read_job_log(7, 6, 100).unwrap();
+        assert_eq!(tail.len(), 1);
+    }
 
-        let dispatching = queue
-            .list_jobs(Some(ProcessingStatus::Dispatching), 10, 0)
+    #[test]
+    fn test_job_diagnostics_grouped_by_category() {
+        use casparian_protocol::types::{JobStage, StageTiming};
+
+        let queue = setup_queue();
+        queue.init_error_handling_schema().unwrap();
+        let diagnostics = |category| JobDiagnostics {
+            error_class: Some("transient".to_string()),
+            error_category: Some(category),
+            failed_stage: Some(JobStage::Write),
+            stage_timings: vec![StageTiming {
+                stage: JobStage::Write,
+                duration_ms: 12,
+            }],
+            ..Default::default()
+        };
+
+        queue
+            .record_job_diagnostics(1, &diagnostics(ErrorCategory::Timeout))
             .unwrap();
-        let dispatching_ids: Vec<i64> = dispatching.iter().map(|job| job.id).collect();
-        assert!(dispatching_ids.contains(&id_high));
-        assert!(dispatching_ids.contains(&id_mid));
+        queue
+            .record_job_diagnostics(2, &diagnostics(ErrorCategory::SinkConflict))
+            .unwrap();
+        queue
+            .record_job_diagnostics(3, &diagnostics(ErrorCategory::SinkConflict))
+            .unwrap();
+        // A later attempt replaces the earlier diagnostics
+        queue
+            .record_job_diagnostics(1, &diagnostics(ErrorCategory::SinkConflict))
+            .unwrap();
+        queue
+            .record_job_diagnostics(4, &JobDiagnostics::default())
+            .unwrap();
+
+        let stored = queue.get_job_diagnostics(1).unwrap().unwrap();
+        assert_eq!(stored.error_category, Some(ErrorCategory::SinkConflict));
+        assert_eq!(stored.stage_timings[0].duration_ms, 12);
+        assert!(queue.get_job_diagnostics(99).unwrap().is_none());
+
+        assert_eq!(
+            queue.count_failures_by_category().unwrap(),
+            vec![(ErrorCategory::SinkConflict, 3)]
+        );
     }
 
     #[test]
-    fn test_list_jobs_pagination() {
-        let queue = setup_queue();
+    fn test_receipt_verification_merges_into_diagnostics() {
+        use casparian_protocol::types::VerificationStatus;
 
-        for i in 1..=5 {
-            enqueue_test_job(&queue, &format!("parser_{}", i), i);
-        }
+        let queue = setup_queue();
+        queue.init_error_handling_schema().unwrap();
+        let reported = JobDiagnostics {
+            error_class: Some("transient".to_string()),
+            ..Default::default()
+        };
+        queue.record_job_diagnostics(1, &reported).unwrap();
+
+        let verification = ReceiptVerification {
+            status: VerificationStatus::Suspect,
+            checked: 1,
+            skipped: 0,
+            discrepancies: Vec::new(),
+            verified_at: 1,
+        };
+        queue.record_receipt_verification(1, &verification).unwrap();
+        queue.record_receipt_verification(2, &verification).unwrap();
 
-        // Get first 2
-        let page1 = queue.list_jobs(None, 2, 0).unwrap();
-        assert_eq!(page1.len(), 2);
+        let stored = queue.get_job_diagnostics(1).unwrap().unwrap();
+        assert_eq!(stored.error_class.as_deref(), Some("transient"));
+        assert_eq!(
+            stored.verification.map(|v| v.status),
+            Some(VerificationStatus::Suspect)
+        );
+        let fresh = queue.get_job_diagnostics(2).unwrap().unwrap();
+        assert!(fresh.verification.is_some());
+    }
 
-        // Get next 2
-        let page2 = queue.list_jobs(None, 2, 2).unwrap();
-        assert_eq!(page2.len(), 2);
+    #[test]
+    fn test_job_includes_parser_version() {
+        let queue = setup_queue();
+        let job_id = enqueue_test_job(&queue, "test_parser", 1);
 
-        // Get last 1
-        let page3 = queue.list_jobs(None, 2, 4).unwrap();
-        assert_eq!(page3.len(), 1);
+        // Record dispatch metadata including parser version
+        queue
+            .record_dispatch_metadata(job_id, "1.2.3", "abc123", "{}")
+            .unwrap();
 
-        // Offset beyond range
-        let page4 = queue.list_jobs(None, 2, 10).unwrap();
-        assert!(page4.is_empty());
+        let job = queue
+            .get_job(JobId::try_from(job_id).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.parser_version, Some("1.2.3".to_string()));
     }
 
     #[test]
-    fn test_get_job_existing() {
+    fn test_job_serializes_to_json() {
         let queue = setup_queue();
         let job_id = enqueue_test_job(&queue, "test_parser", 42);
 
@@ -2586,135 +5577,426 @@ mod tests {
             .unwrap()
             .unwrap();
 
-        assert_eq!(job.id.as_u64(), job_id as u64);
-        assert_eq!(job.plugin_name, "test_parser");
-        assert_eq!(job.file_id, 42);
-        assert_eq!(job.status, ProcessingStatus::Queued);
+        // Job should serialize without error
+        let json = serde_json::to_string(&job).unwrap();
+        assert!(json.contains("\"plugin_name\":\"test_parser\""));
+        assert!(json.contains("\"file_id\":42"));
+        assert!(json.contains("\"status\":\"QUEUED\""));
     }
 
     #[test]
-    fn test_get_job_non_existing() {
+    fn test_result_summary_round_trip_and_cleared_on_requeue() {
         let queue = setup_queue();
-        let job = queue.get_job(JobId::new(99999)).unwrap();
-        assert!(job.is_none());
+        let job_id = enqueue_test_job(&queue, "test_parser", 1);
+        let summary = JobResultSummary {
+            artifacts: vec![ResultArtifact {
+                kind: ArtifactKind::Output,
+                name: "orders".to_string(),
+                uri: Some("parquet:///out/orders.parquet".to_string()),
+                rows: Some(3),
+            }],
+            counters: [("rows".to_string(), 3)].into_iter().collect(),
+            warnings: Vec::new(),
+        };
+        queue
+            .complete_job(job_id, JobStatus::Success.as_str(), "Success", None)
+            .unwrap();
+        queue.record_result_summary(job_id, &summary).unwrap();
+
+        let id = JobId::try_from(job_id).unwrap();
+        let job = queue.get_job(id).unwrap().unwrap();
+        assert_eq!(job.result.as_ref(), Some(&summary));
+        assert_eq!(
+            job.result.unwrap().output_uris().collect::<Vec<_>>(),
+            vec!["parquet:///out/orders.parquet"]
+        );
+
+        queue.requeue_job(job_id).unwrap();
+        assert!(queue.get_job(id).unwrap().unwrap().result.is_none());
     }
 
     #[test]
-    fn test_cancel_job_queued() {
+    fn test_migrate_result_summary_column_from_legacy_text() {
         let queue = setup_queue();
         let job_id = enqueue_test_job(&queue, "test_parser", 1);
+        queue
+            .complete_job(
+                job_id,
+                JobStatus::Success.as_str(),
+                "/data/out/orders.parquet",
+                None,
+            )
+            .unwrap();
+        queue
+            .conn
+            .execute_batch("ALTER TABLE cf_processing_queue DROP COLUMN result_summary_json")
+            .unwrap();
 
-        let cancelled = queue.cancel_job(JobId::try_from(job_id).unwrap()).unwrap();
-        assert!(cancelled);
+        queue.init_queue_schema().unwrap();
 
-        // Verify job is now ABORTED with ABORTED completion_status
         let job = queue
             .get_job(JobId::try_from(job_id).unwrap())
             .unwrap()
             .unwrap();
-        assert_eq!(job.status, ProcessingStatus::Aborted);
-        assert_eq!(job.completion_status, Some(JobStatus::Aborted));
+        let result = job.result.expect("migrated summary");
         assert_eq!(
-            job.error_message,
-            Some(casparian_protocol::defaults::CANCELLED_BY_USER_MESSAGE.to_string())
+            result.output_uris().collect::<Vec<_>>(),
+            vec!["/data/out/orders.parquet"]
         );
     }
 
-    #[test]
-    fn test_cancel_job_already_completed() {
-        let queue = setup_queue();
-        let job_id = enqueue_test_job(&queue, "test_parser", 1);
-
-        // Complete the job
+    fn insert_plugin_version(
+        queue: &JobQueue,
+        plugin_name: &str,
+        version: &str,
+        status: PluginStatus,
+        created_at: i64,
+    ) {
         queue
-            .complete_job(job_id, JobStatus::Success.as_str(), "done", None)
+            .conn
+            .execute(
+                r#"
+                INSERT INTO cf_plugin_manifest (
+                    plugin_name, version, runtime_kind, entrypoint,
+                    source_code, source_hash, status, env_hash, artifact_hash,
+                    manifest_json, protocol_version, schema_artifacts_json, outputs_json,
+                    created_at, deployed_at
+                ) VALUES (?, ?, 'python_shim', 'parser.py:parse', ?, ?, ?, '', '',
+                          '{}', '1.0', '{}', '{}', ?, ?)
+                "#,
+                &[
+                    DbValue::from(plugin_name),
+                    DbValue::from(version),
+                    DbValue::from(format!("# {} {}", plugin_name, version)),
+                    DbValue::from(format!("hash_{}_{}", plugin_name, version)),
+                    DbValue::from(status.as_str()),
+                    DbValue::from(created_at),
+                    DbValue::from(created_at),
+                ],
+            )
             .unwrap();
+    }
 
-        // Try to cancel - should return false
-        let cancelled = queue.cancel_job(JobId::try_from(job_id).unwrap()).unwrap();
-        assert!(!cancelled);
-
-        // Verify job is still COMPLETED
-        let job = queue
-            .get_job(JobId::try_from(job_id).unwrap())
+    fn plugin_status(queue: &JobQueue, plugin_name: &str, version: &str) -> String {
+        queue
+            .conn
+            .query_scalar::<String>(
+                "SELECT status FROM cf_plugin_manifest WHERE plugin_name = ? AND version = ?",
+                &[DbValue::from(plugin_name), DbValue::from(version)],
+            )
             .unwrap()
-            .unwrap();
-        assert_eq!(job.status, ProcessingStatus::Completed);
     }
 
     #[test]
-    fn test_cancel_job_non_existing() {
+    fn test_rollback_plugin_reactivates_superseded_version() {
         let queue = setup_queue();
-        let cancelled = queue.cancel_job(JobId::new(99999)).unwrap();
-        assert!(!cancelled);
+        queue.init_registry_schema().unwrap();
+        insert_plugin_version(&queue, "parser_a", "1.0.0", PluginStatus::Superseded, 1);
+        insert_plugin_version(&queue, "parser_a", "1.1.0", PluginStatus::Active, 2);
+        insert_plugin_version(&queue, "parser_a", "1.2.0", PluginStatus::Rejected, 3);
+
+        let rollback = queue
+            .rollback_plugin("parser_a", "1.0.0", Some("ops@example.com"))
+            .unwrap();
+        assert_eq!(rollback.from_version.as_deref(), Some("1.1.0"));
+        assert_eq!(rollback.to_version, "1.0.0");
+        assert_eq!(plugin_status(&queue, "parser_a", "1.0.0"), "ACTIVE");
+        assert_eq!(plugin_status(&queue, "parser_a", "1.1.0"), "SUPERSEDED");
+
+        let events = queue.list_plugin_events("parser_a").unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, PLUGIN_EVENT_ROLLBACK);
+        assert_eq!(events[0].from_version.as_deref(), Some("1.1.0"));
+        assert_eq!(events[0].to_version, "1.0.0");
+        assert_eq!(events[0].actor.as_deref(), Some("ops@example.com"));
+
+        // Active, never-active, and unknown versions are refused.
+        assert!(queue.rollback_plugin("parser_a", "1.0.0", None).is_err());
+        assert!(queue.rollback_plugin("parser_a", "1.2.0", None).is_err());
+        assert!(queue.rollback_plugin("parser_a", "9.9.9", None).is_err());
+        assert_eq!(queue.list_plugin_events("parser_a").unwrap().len(), 1);
     }
 
     #[test]
-    fn test_count_jobs_by_status() {
+    fn test_load_dispatch_data_honors_pinned_version() {
         let queue = setup_queue();
+        queue.init_registry_schema().unwrap();
+        queue
+            .conn
+            .execute_batch(
+                r#"
+                CREATE TABLE scout_sources (id BIGINT, path TEXT, exec_path TEXT);
+                CREATE TABLE scout_files (id BIGINT, source_id BIGINT, rel_path TEXT);
+                INSERT INTO scout_sources VALUES (1, '/data', NULL);
+                INSERT INTO scout_files VALUES (7, 1, 'a.csv');
+                "#,
+            )
+            .unwrap();
+        insert_plugin_version(&queue, "parser_a", "1.0.0", PluginStatus::Superseded, 1);
+        insert_plugin_version(&queue, "parser_a", "2.0.0", PluginStatus::Active, 2);
 
-        // Create jobs in different states
-        let job1 = enqueue_test_job(&queue, "parser_a", 1);
-        enqueue_test_job(&queue, "parser_b", 2);
-        let job3 = enqueue_test_job(&queue, "parser_c", 3);
+        let latest_job = enqueue_test_job(&queue, "parser_a", 7);
+        let data = queue.load_dispatch_data(latest_job, "parser_a", 7).unwrap();
+        assert_eq!(data.parser_version, "2.0.0");
 
-        // Complete job1
+        let pinned_job = enqueue_test_job(&queue, "parser_a", 7);
         queue
-            .complete_job(job1, JobStatus::Success.as_str(), "done", None)
+            .conn
+            .execute(
+                "UPDATE cf_processing_queue SET pinned_version = '1.0.0' WHERE id = ?",
+                &[DbValue::from(pinned_job)],
+            )
             .unwrap();
+        let data = queue.load_dispatch_data(pinned_job, "parser_a", 7).unwrap();
+        assert_eq!(data.parser_version, "1.0.0");
+        assert_eq!(data.rel_path, "a.csv");
+    }
 
-        // Fail job3
+    #[test]
+    fn test_subscriptions_reject_cycles() {
+        let queue = setup_queue();
+        queue.init_registry_schema().unwrap();
         queue
-            .fail_job(job3, JobStatus::Failed.as_str(), "error")
+            .subscribe_plugin("parser_b", "parser_a:orders")
+            .unwrap();
+        let c = queue
+            .subscribe_plugin("parser_c", "parser_b:enriched")
             .unwrap();
 
-        let counts = queue.count_jobs_by_status().unwrap();
+        let err = queue
+            .subscribe_plugin("parser_a", "parser_c:report")
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("parser_a -> parser_b -> parser_c -> parser_a"));
+        assert!(queue
+            .subscribe_plugin("parser_a", "parser_a:orders")
+            .is_err());
+        assert!(queue.subscribe_plugin("parser_a", "no_output").is_err());
+
+        // Disabling an edge breaks the cycle; re-enabling it is then refused.
+        queue.set_subscription_active(c.id, false).unwrap();
+        queue
+            .subscribe_plugin("parser_a", "parser_c:report")
+            .unwrap();
+        assert!(queue.set_subscription_active(c.id, true).is_err());
 
-        assert_eq!(counts.get(&ProcessingStatus::Queued), Some(&1));
-        assert_eq!(counts.get(&ProcessingStatus::Completed), Some(&1));
-        assert_eq!(counts.get(&ProcessingStatus::Failed), Some(&1));
-        // RUNNING should not exist (no jobs in that state)
-        assert!(!counts.contains_key(&ProcessingStatus::Running));
+        let subscriptions = queue.list_subscriptions().unwrap();
+        assert_eq!(subscriptions.len(), 3);
+        assert!(subscriptions
+            .iter()
+            .any(|sub| sub.id == c.id && !sub.is_active));
     }
 
     #[test]
-    fn test_count_jobs_by_status_empty() {
+    fn test_enqueue_downstream_jobs_reads_artifact() {
         let queue = setup_queue();
-        let counts = queue.count_jobs_by_status().unwrap();
-        assert!(counts.is_empty());
+        queue.init_registry_schema().unwrap();
+        queue
+            .conn
+            .execute_batch(
+                r#"
+                CREATE TABLE scout_sources (id BIGINT, path TEXT, exec_path TEXT);
+                CREATE TABLE scout_files (id BIGINT, source_id BIGINT, rel_path TEXT);
+                INSERT INTO scout_sources VALUES (1, '/data', NULL);
+                INSERT INTO scout_files VALUES (7, 1, 'a.csv');
+                "#,
+            )
+            .unwrap();
+        insert_plugin_version(&queue, "parser_b", "1.0.0", PluginStatus::Active, 1);
+        queue
+            .subscribe_plugin("parser_b", "parser_a:orders")
+            .unwrap();
+        queue
+            .subscribe_plugin("parser_b", "parser_a:fills")
+            .unwrap();
+
+        let upstream = enqueue_test_job(&queue, "parser_a", 7);
+        let artifacts = vec![
+            ArtifactV1::Output {
+                output_name: "orders".to_string(),
+                sink_uri: "file:///out/orders_1.parquet".to_string(),
+                table: None,
+                rows: Some(3),
+                schema_hash: None,
+                content_hash: None,
+            },
+            ArtifactV1::Output {
+                output_name: "fills".to_string(),
+                sink_uri: "duckdb:///out/db.duckdb?table=fills".to_string(),
+                table: Some("fills".to_string()),
+                rows: Some(1),
+                schema_hash: None,
+                content_hash: None,
+            },
+        ];
+        let downstream = queue.enqueue_downstream_jobs(upstream, &artifacts).unwrap();
+        assert_eq!(downstream.len(), 1);
+
+        let data = queue
+            .load_dispatch_data(downstream[0], "parser_b", 7)
+            .unwrap();
+        assert_eq!(data.input_path.as_deref(), Some("/out/orders_1.parquet"));
+        assert!(data.force_rerun);
+        let data = queue.load_dispatch_data(upstream, "parser_b", 7).unwrap();
+        assert_eq!(data.input_path, None);
+
+        // A subscriber already upstream of the job is not enqueued again.
+        queue
+            .conn
+            .execute(
+                "INSERT INTO cf_plugin_subscriptions (plugin_name, topic_name, is_active, created_at)
+                 VALUES ('parser_a', 'parser_b:orders', true, 0)",
+                &[],
+            )
+            .unwrap();
+        let again = queue
+            .enqueue_downstream_jobs(downstream[0], &artifacts)
+            .unwrap();
+        assert!(again.is_empty());
     }
 
     #[test]
-    fn test_job_includes_parser_version() {
+    fn test_schedule_window_holds_tagged_files() {
         let queue = setup_queue();
-        let job_id = enqueue_test_job(&queue, "test_parser", 1);
+        queue.init_registry_schema().unwrap();
+        queue
+            .conn
+            .execute_batch(
+                r#"
+                CREATE TABLE scout_file_tags (file_id BIGINT, tag TEXT, rule_id TEXT);
+                INSERT INTO scout_file_tags VALUES (7, 'eod', 'rule-eod');
+                INSERT INTO scout_file_tags VALUES (8, 'intraday', 'rule-intraday');
+                "#,
+            )
+            .unwrap();
+        let target = ScheduleTarget::RuleWindow {
+            rule_id: "rule-eod".to_string(),
+            window_minutes: 60,
+        };
+        let schedule = queue
+            .create_schedule("eod", "0 18 * * *", &target, ScheduleCatchUp::RunOnce)
+            .unwrap();
+        assert_eq!(schedule.target, target);
+        assert!(schedule.is_active);
+        assert_eq!(schedule.next_fire_at, None);
+        assert!(queue
+            .create_schedule("eod", "0 * * * *", &target, ScheduleCatchUp::Skip)
+            .is_err());
+
+        // Closed until the Sentinel opens the window.
+        let hold = queue.schedule_hold(7, 1_000).unwrap().unwrap();
+        assert_eq!(hold.schedule_name, "eod");
+        assert_eq!(queue.schedule_hold(8, 1_000).unwrap(), None);
 
-        // Record dispatch metadata including parser version
         queue
-            .record_dispatch_metadata(job_id, "1.2.3", "abc123", "{}")
+            .record_schedule_state(
+                schedule.id,
+                ScheduleState {
+                    next_fire_at: Some(90_000),
+                    last_fired_at: Some(1_000),
+                    window_open_until: Some(5_000),
+                },
+            )
             .unwrap();
+        assert_eq!(queue.schedule_hold(7, 2_000).unwrap(), None);
+        let hold = queue.schedule_hold(7, 5_000).unwrap().unwrap();
+        assert_eq!(hold.next_open_at, Some(90_000));
 
-        let job = queue
-            .get_job(JobId::try_from(job_id).unwrap())
-            .unwrap()
+        // Jobs held for the window become runnable when it is released.
+        let held = enqueue_test_job(&queue, "parser_a", 7);
+        let other = enqueue_test_job(&queue, "parser_a", 8);
+        queue
+            .conn
+            .execute(
+                "UPDATE cf_processing_queue SET scheduled_at = ?, error_message = ?",
+                &[
+                    DbValue::from(90_000_i64),
+                    DbValue::from(SCHEDULE_HOLD_REASON),
+                ],
+            )
             .unwrap();
-        assert_eq!(job.parser_version, Some("1.2.3".to_string()));
+        assert_eq!(queue.release_schedule_holds("rule-eod", 6_000).unwrap(), 1);
+        let scheduled_at = |job_id: i64| {
+            queue
+                .conn
+                .query_scalar::<i64>(
+                    "SELECT scheduled_at FROM cf_processing_queue WHERE id = ?",
+                    &[DbValue::from(job_id)],
+                )
+                .unwrap()
+        };
+        assert_eq!(scheduled_at(held), 6_000);
+        assert_eq!(scheduled_at(other), 90_000);
+
+        // Disabling stops the hold; re-enabling forgets the stored state.
+        let disabled = queue.set_schedule_active(schedule.id, false).unwrap();
+        assert!(!disabled.is_active);
+        assert_eq!(queue.schedule_hold(7, 10_000).unwrap(), None);
+        let enabled = queue.set_schedule_active(schedule.id, true).unwrap();
+        assert_eq!(enabled.next_fire_at, None);
+        assert_eq!(enabled.window_open_until, None);
+        assert_eq!(queue.list_schedules().unwrap().len(), 1);
+
+        assert!(queue.delete_schedule(schedule.id).unwrap());
+        assert!(!queue.delete_schedule(schedule.id).unwrap());
+        assert!(queue.list_schedules().unwrap().is_empty());
     }
 
     #[test]
-    fn test_job_serializes_to_json() {
+    fn test_get_pipeline_run_groups_chained_jobs() {
         let queue = setup_queue();
-        let job_id = enqueue_test_job(&queue, "test_parser", 42);
+        queue.init_registry_schema().unwrap();
+        queue
+            .subscribe_plugin("parser_b", "parser_a:orders")
+            .unwrap();
 
-        let job = queue
-            .get_job(JobId::try_from(job_id).unwrap())
-            .unwrap()
+        let upstream = enqueue_test_job(&queue, "parser_a", 7);
+        queue
+            .conn
+            .execute(
+                "UPDATE cf_processing_queue SET status = ?, claim_time = 100, end_time = 250 WHERE id = ?",
+                &[
+                    DbValue::from(ProcessingStatus::Completed.as_str()),
+                    DbValue::from(upstream),
+                ],
+            )
+            .unwrap();
+        let artifacts = vec![ArtifactV1::Output {
+            output_name: "orders".to_string(),
+            sink_uri: "file:///out/orders_1.parquet".to_string(),
+            table: None,
+            rows: Some(3),
+            schema_hash: None,
+            content_hash: None,
+        }];
+        let downstream = queue.enqueue_downstream_jobs(upstream, &artifacts).unwrap();
+        assert_eq!(downstream.len(), 1);
+
+        // The root job had no run; enqueueing its subscribers started one.
+        let run_id = queue
+            .conn
+            .query_scalar::<String>(
+                "SELECT pipeline_run_id FROM cf_processing_queue WHERE id = ?",
+                &[DbValue::from(downstream[0])],
+            )
             .unwrap();
+        let run = queue.get_pipeline_run(&run_id).unwrap().unwrap();
+        assert_eq!(run.status, PipelineRunStatus::Running);
+        assert_eq!(run.started_at, Some(100));
+        let nodes: Vec<(i64, Option<i64>, u32)> = run
+            .jobs
+            .iter()
+            .map(|job| (job.job_id, job.upstream_job_id, job.depth))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![(upstream, None, 0), (downstream[0], Some(upstream), 1)]
+        );
+        assert_eq!(run.stages.len(), 2);
+        assert_eq!(run.stages[0].duration_ms, Some(150));
+        assert_eq!(run.stages[1].active, 1);
 
-        // Job should serialize without error
-        let json = serde_json::to_string(&job).unwrap();
-        assert!(json.contains("\"plugin_name\":\"test_parser\""));
-        assert!(json.contains("\"file_id\":42"));
-        assert!(json.contains("\"status\":\"QUEUED\""));
+        assert!(queue.get_pipeline_run("missing").unwrap().is_none());
     }
 }
@@ -0,0 +1,382 @@
+//! Retention policies and the garbage-collection ledger.
+//!
+//! A policy bounds how much of one output topic is kept: artifacts older than
+//! `keep_days`, beyond the newest `keep_versions`, or past `max_bytes` in
+//! total expire. The `*` topic applies to outputs without their own policy.
+//! Every artifact the GC deletes is recorded in `cf_gc_deletions`, so it is
+//! not considered again and the space it freed can be reported.
+//!
+//! Job logs have no per-topic policy; the GC drops the logs of jobs whose last
+//! chunk is older than a cutoff and merges the chunks of finished jobs.
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
+use casparian_protocol::ProcessingStatus;
+use serde::{Deserialize, Serialize};
+
+/// Topic of the policy that applies to outputs without their own.
+pub const DEFAULT_POLICY_TOPIC: &str = "*";
+
+/// Retention limits of one output topic. Unset limits do not expire anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub topic: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_days: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_versions: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.keep_days.is_none() && self.keep_versions.is_none() && self.max_bytes.is_none()
+    }
+
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        let keep_days: Option<i64> = row.get_by_name("keep_days")?;
+        let keep_versions: Option<i64> = row.get_by_name("keep_versions")?;
+        let max_bytes: Option<i64> = row.get_by_name("max_bytes")?;
+        Ok(Self {
+            topic: row.get_by_name("topic")?,
+            keep_days: keep_days.map(|days| days.clamp(0, u32::MAX as i64) as u32),
+            keep_versions: keep_versions.map(|n| n.clamp(0, u32::MAX as i64) as u32),
+            max_bytes: max_bytes.map(|bytes| bytes.max(0) as u64),
+        })
+    }
+}
+
+/// An output artifact a job committed and the GC has not deleted yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputArtifact {
+    pub job_id: i64,
+    pub output_name: String,
+    pub uri: String,
+    pub created_at: i64,
+}
+
+/// A source file → artifact hop from the lineage store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineageLink {
+    pub source_path: String,
+    pub artifact_uri: String,
+}
+
+/// An artifact the GC deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcDeletion {
+    pub uri: String,
+    pub job_id: i64,
+    pub output_name: String,
+    pub bytes: u64,
+    pub reason: String,
+}
+
+/// Job log volume.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogUsage {
+    pub jobs: u64,
+    pub chunks: u64,
+    pub bytes: u64,
+}
+
+/// Create the policy and ledger tables (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let int_type = if conn.backend_name() == "SQLite" {
+        "INTEGER"
+    } else {
+        "BIGINT"
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_retention_policies (
+            topic TEXT PRIMARY KEY,
+            keep_days {int_type},
+            keep_versions {int_type},
+            max_bytes {int_type},
+            updated_at {int_type} NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS cf_gc_deletions (
+            uri TEXT PRIMARY KEY,
+            job_id {int_type} NOT NULL,
+            output_name TEXT NOT NULL,
+            bytes {int_type} NOT NULL,
+            reason TEXT NOT NULL,
+            deleted_at {int_type} NOT NULL
+        );
+        "#,
+        int_type = int_type,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize retention schema")?;
+    Ok(())
+}
+
+/// Create or replace the policy of `policy.topic`.
+pub fn set_policy(conn: &DbConnection, policy: &RetentionPolicy) -> Result<()> {
+    conn.execute(
+        "DELETE FROM cf_retention_policies WHERE topic = ?",
+        &[DbValue::from(policy.topic.as_str())],
+    )?;
+    conn.execute(
+        r#"
+        INSERT INTO cf_retention_policies (topic, keep_days, keep_versions, max_bytes, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        &[
+            DbValue::from(policy.topic.as_str()),
+            DbValue::from(policy.keep_days.map(i64::from)),
+            DbValue::from(policy.keep_versions.map(i64::from)),
+            DbValue::from(
+                policy
+                    .max_bytes
+                    .map(|bytes| bytes.min(i64::MAX as u64) as i64),
+            ),
+            DbValue::from(chrono::Utc::now().timestamp_millis()),
+        ],
+    )
+    .context("Failed to save retention policy")?;
+    Ok(())
+}
+
+/// Remove the policy of `topic`. Returns false if it had none.
+pub fn remove_policy(conn: &DbConnection, topic: &str) -> Result<bool> {
+    let removed = conn.execute(
+        "DELETE FROM cf_retention_policies WHERE topic = ?",
+        &[DbValue::from(topic)],
+    )?;
+    Ok(removed > 0)
+}
+
+/// All policies, by topic.
+pub fn list_policies(conn: &DbConnection) -> Result<Vec<RetentionPolicy>> {
+    let rows = conn.query_all(
+        "SELECT topic, keep_days, keep_versions, max_bytes FROM cf_retention_policies ORDER BY topic",
+        &[],
+    )?;
+    rows.iter().map(RetentionPolicy::from_row).collect()
+}
+
+/// Output artifacts not deleted by the GC, newest first. Artifacts whose
+/// files were compacted are left out: their rows now share a file with
+/// other jobs'.
+pub fn output_artifacts(conn: &DbConnection) -> Result<Vec<OutputArtifact>> {
+    if !conn.table_exists("cf_job_artifacts")? {
+        return Ok(Vec::new());
+    }
+    let rows = conn.query_all(
+        r#"
+        SELECT a.job_id, a.name, a.uri, a.created_at
+        FROM cf_job_artifacts a
+        WHERE a.kind = 'output'
+          AND NOT EXISTS (SELECT 1 FROM cf_gc_deletions d WHERE d.uri = a.uri)
+          AND NOT EXISTS (SELECT 1 FROM cf_output_compactions c WHERE c.artifact_uri = a.uri)
+        ORDER BY a.created_at DESC, a.job_id DESC
+        "#,
+        &[],
+    )?;
+    rows.iter()
+        .map(|row| -> Result<OutputArtifact> {
+            Ok(OutputArtifact {
+                job_id: row.get_by_name("job_id")?,
+                output_name: row.get_by_name("name")?,
+                uri: row.get_by_name("uri")?,
+                created_at: row.get_by_name("created_at")?,
+            })
+        })
+        .collect()
+}
+
+/// Every recorded source → artifact hop with a known source path.
+pub fn lineage_links(conn: &DbConnection) -> Result<Vec<LineageLink>> {
+    if !conn.table_exists("cf_lineage_hops")? {
+        return Ok(Vec::new());
+    }
+    let rows = conn.query_all(
+        r#"
+        SELECT DISTINCT source_path, artifact_uri
+        FROM cf_lineage_hops
+        WHERE source_path IS NOT NULL
+        "#,
+        &[],
+    )?;
+    rows.iter()
+        .map(|row| -> Result<LineageLink> {
+            Ok(LineageLink {
+                source_path: row.get_by_name("source_path")?,
+                artifact_uri: row.get_by_name("artifact_uri")?,
+            })
+        })
+        .collect()
+}
+
+/// Record an artifact the GC deleted.
+pub fn record_deletion(conn: &DbConnection, deletion: &GcDeletion) -> Result<()> {
+    conn.execute(
+        r#"
+        INSERT INTO cf_gc_deletions (uri, job_id, output_name, bytes, reason, deleted_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT (uri) DO NOTHING
+        "#,
+        &[
+            DbValue::from(deletion.uri.as_str()),
+            DbValue::from(deletion.job_id),
+            DbValue::from(deletion.output_name.as_str()),
+            DbValue::from(deletion.bytes.min(i64::MAX as u64) as i64),
+            DbValue::from(deletion.reason.as_str()),
+            DbValue::from(chrono::Utc::now().timestamp_millis()),
+        ],
+    )
+    .context("Failed to record GC deletion")?;
+    Ok(())
+}
+
+const EXPIRED_LOG_JOBS: &str = r#"
+    SELECT job_id FROM cf_job_logs GROUP BY job_id HAVING MAX(created_at) < ?
+"#;
+
+/// Logs of jobs whose last chunk was written before `before` (millis).
+pub fn expired_logs(conn: &DbConnection, before: i64) -> Result<LogUsage> {
+    let row = conn.query_optional(
+        &format!(
+            r#"
+            SELECT COUNT(DISTINCT job_id) AS jobs, COUNT(*) AS chunks,
+                   COALESCE(SUM(LENGTH(chunk)), 0) AS bytes
+            FROM cf_job_logs
+            WHERE job_id IN ({})
+            "#,
+            EXPIRED_LOG_JOBS
+        ),
+        &[DbValue::from(before)],
+    )?;
+    let Some(row) = row else {
+        return Ok(LogUsage::default());
+    };
+    let jobs: i64 = row.get_by_name("jobs")?;
+    let chunks: i64 = row.get_by_name("chunks")?;
+    let bytes: i64 = row.get_by_name("bytes")?;
+    Ok(LogUsage {
+        jobs: jobs.max(0) as u64,
+        chunks: chunks.max(0) as u64,
+        bytes: bytes.max(0) as u64,
+    })
+}
+
+/// Delete the logs [`expired_logs`] reports and return their volume.
+pub fn delete_expired_logs(conn: &DbConnection, before: i64) -> Result<LogUsage> {
+    let usage = expired_logs(conn, before)?;
+    conn.execute(
+        &format!(
+            "DELETE FROM cf_job_logs WHERE job_id IN ({})",
+            EXPIRED_LOG_JOBS
+        ),
+        &[DbValue::from(before)],
+    )
+    .context("Failed to delete expired job logs")?;
+    Ok(usage)
+}
+
+/// Finished jobs whose log is split over several chunks, the last written
+/// before `before` (millis).
+pub fn compactable_log_jobs(conn: &DbConnection, before: i64) -> Result<Vec<i64>> {
+    let terminal = [
+        ProcessingStatus::Completed,
+        ProcessingStatus::Aborted,
+        ProcessingStatus::Failed,
+        ProcessingStatus::Skipped,
+    ];
+    let placeholders = vec!["?"; terminal.len()].join(", ");
+    let mut params = vec![DbValue::from(before)];
+    params.extend(terminal.iter().map(|status| DbValue::from(status.as_str())));
+    let rows = conn.query_all(
+        &format!(
+            r#"
+            SELECT l.job_id
+            FROM cf_job_logs l
+            JOIN cf_processing_queue q ON q.id = l.job_id
+            GROUP BY l.job_id, q.status
+            HAVING COUNT(*) > 1 AND MAX(l.created_at) < ? AND q.status IN ({})
+            ORDER BY l.job_id
+            "#,
+            placeholders
+        ),
+        &params,
+    )?;
+    rows.iter()
+        .map(|row| row.get::<i64>(0).map_err(Into::into))
+        .collect()
+}
+
+/// Merge the log chunks of a finished job into one at its first offset, so
+/// reads from the start return the whole log. Returns the chunks merged.
+pub fn compact_job_log(conn: &DbConnection, job_id: i64) -> Result<usize> {
+    let rows = conn.query_all(
+        "SELECT byte_offset, chunk, created_at FROM cf_job_logs WHERE job_id = ? ORDER BY byte_offset",
+        &[DbValue::from(job_id)],
+    )?;
+    if rows.len() < 2 {
+        return Ok(0);
+    }
+    let first_offset: i64 = rows[0].get_by_name("byte_offset")?;
+    let mut last_written = 0i64;
+    let mut log = String::new();
+    for row in &rows {
+        let chunk: String = row.get_by_name("chunk")?;
+        let created_at: i64 = row.get_by_name("created_at")?;
+        log.push_str(&chunk);
+        last_written = last_written.max(created_at);
+    }
+    conn.execute(
+        "DELETE FROM cf_job_logs WHERE job_id = ?",
+        &[DbValue::from(job_id)],
+    )?;
+    conn.execute(
+        "INSERT INTO cf_job_logs (job_id, byte_offset, chunk, created_at) VALUES (?, ?, ?, ?)",
+        &[
+            DbValue::from(job_id),
+            DbValue::from(first_offset),
+            DbValue::from(log.as_str()),
+            DbValue::from(last_written),
+        ],
+    )
+    .context("Failed to write compacted job log")?;
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies_roundtrip() {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let policy = RetentionPolicy {
+            topic: "orders".to_string(),
+            keep_days: Some(30),
+            keep_versions: None,
+            max_bytes: Some(10 << 30),
+        };
+        set_policy(&conn, &policy).unwrap();
+        set_policy(
+            &conn,
+            &RetentionPolicy {
+                topic: DEFAULT_POLICY_TOPIC.to_string(),
+                keep_versions: Some(3),
+                ..RetentionPolicy::default()
+            },
+        )
+        .unwrap();
+
+        let policies = list_policies(&conn).unwrap();
+        assert_eq!(policies.len(), 2);
+        assert_eq!(policies[0].topic, DEFAULT_POLICY_TOPIC);
+        assert_eq!(policies[1], policy);
+
+        assert!(remove_policy(&conn, "orders").unwrap());
+        assert!(!remove_policy(&conn, "orders").unwrap());
+        assert_eq!(list_policies(&conn).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,245 @@
+//! Schema drift per dataset.
+//!
+//! Workers report, per job, the input columns no output carries and the
+//! contract columns that arrived as another accepted type
+//! (`JobDiagnostics::schema_drift`). Each distinct drift is kept in
+//! `cf_schema_drift` keyed by plugin, kind, output and column, with the jobs
+//! it was seen in, so the Sentinel can tell a dataset that keeps drifting
+//! from a single odd file and alert once the drift passes its threshold.
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::types::{ObservedDataType, SchemaDrift};
+
+const KIND_UNMAPPED: &str = "unmapped_column";
+const KIND_TYPE: &str = "type_drift";
+
+/// Drift recorded for one dataset (plugin) across its runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatasetDrift {
+    /// Source columns no output carries
+    pub unmapped_columns: Vec<String>,
+    /// `output.column` names that arrived as another accepted type
+    pub type_drifts: Vec<String>,
+    /// Entries first seen by the last [`record`] call
+    pub new_entries: usize,
+}
+
+impl DatasetDrift {
+    /// Distinct drifted columns, unmapped or retyped.
+    pub fn drifted_columns(&self) -> usize {
+        self.unmapped_columns.len() + self.type_drifts.len()
+    }
+}
+
+/// Create the drift table (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let int_type = if conn.backend_name() == "SQLite" {
+        "INTEGER"
+    } else {
+        "BIGINT"
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_schema_drift (
+            plugin_name TEXT NOT NULL,
+            drift_kind TEXT NOT NULL CHECK (drift_kind IN ('{unmapped}', '{retyped}')),
+            output_name TEXT NOT NULL,
+            column_name TEXT NOT NULL,
+            actual_type TEXT,
+            job_count {int_type} NOT NULL,
+            first_job_id {int_type} NOT NULL,
+            last_job_id {int_type} NOT NULL,
+            first_seen_at {int_type} NOT NULL,
+            last_seen_at {int_type} NOT NULL,
+            PRIMARY KEY (plugin_name, drift_kind, output_name, column_name)
+        );
+        "#,
+        int_type = int_type,
+        unmapped = KIND_UNMAPPED,
+        retyped = KIND_TYPE,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize cf_schema_drift schema")?;
+    Ok(())
+}
+
+/// Record the drift one job of `plugin_name` reported and return the
+/// dataset's drift with it. Unmapped columns have an empty output name.
+pub fn record(
+    conn: &DbConnection,
+    plugin_name: &str,
+    job_id: i64,
+    drift: &SchemaDrift,
+) -> Result<DatasetDrift> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut entries: Vec<(&str, &str, &str, Option<String>)> = Vec::new();
+    for column in &drift.unmapped_columns {
+        entries.push((KIND_UNMAPPED, "", column, None));
+    }
+    for type_drift in &drift.type_drifts {
+        entries.push((
+            KIND_TYPE,
+            &type_drift.output_name,
+            &type_drift.column,
+            Some(observed_type_label(&type_drift.actual)),
+        ));
+    }
+    entries.sort();
+    entries.dedup_by(|a, b| (a.0, a.1, a.2) == (b.0, b.1, b.2));
+
+    let mut new_entries = 0;
+    for (kind, output_name, column, actual_type) in entries {
+        let key = [
+            DbValue::from(plugin_name),
+            DbValue::from(kind),
+            DbValue::from(output_name),
+            DbValue::from(column),
+        ];
+        let updated = conn
+            .execute(
+                r#"
+                UPDATE cf_schema_drift
+                SET job_count = job_count + 1, last_job_id = ?, last_seen_at = ?,
+                    actual_type = ?
+                WHERE plugin_name = ? AND drift_kind = ? AND output_name = ? AND column_name = ?
+                "#,
+                &[
+                    DbValue::from(job_id),
+                    DbValue::from(now),
+                    DbValue::from(actual_type.clone()),
+                    key[0].clone(),
+                    key[1].clone(),
+                    key[2].clone(),
+                    key[3].clone(),
+                ],
+            )
+            .context("Failed to update schema drift")?;
+        if updated > 0 {
+            continue;
+        }
+        conn.execute(
+            r#"
+            INSERT INTO cf_schema_drift (
+                plugin_name, drift_kind, output_name, column_name, actual_type,
+                job_count, first_job_id, last_job_id, first_seen_at, last_seen_at
+            )
+            VALUES (?, ?, ?, ?, ?, 1, ?, ?, ?, ?)
+            "#,
+            &[
+                key[0].clone(),
+                key[1].clone(),
+                key[2].clone(),
+                key[3].clone(),
+                DbValue::from(actual_type),
+                DbValue::from(job_id),
+                DbValue::from(job_id),
+                DbValue::from(now),
+                DbValue::from(now),
+            ],
+        )
+        .context("Failed to record schema drift")?;
+        new_entries += 1;
+    }
+
+    let mut dataset = for_plugin(conn, plugin_name)?;
+    dataset.new_entries = new_entries;
+    Ok(dataset)
+}
+
+/// Drift recorded for `plugin_name`, sorted by output and column.
+pub fn for_plugin(conn: &DbConnection, plugin_name: &str) -> Result<DatasetDrift> {
+    let rows = conn.query_all(
+        r#"
+        SELECT drift_kind, output_name, column_name
+        FROM cf_schema_drift
+        WHERE plugin_name = ?
+        ORDER BY output_name, column_name
+        "#,
+        &[DbValue::from(plugin_name)],
+    )?;
+    let mut dataset = DatasetDrift::default();
+    for row in rows {
+        let kind: String = row.get_by_name("drift_kind")?;
+        let output_name: String = row.get_by_name("output_name")?;
+        let column: String = row.get_by_name("column_name")?;
+        if kind == KIND_UNMAPPED {
+            dataset.unmapped_columns.push(column);
+        } else {
+            dataset
+                .type_drifts
+                .push(format!("{}.{}", output_name, column));
+        }
+    }
+    Ok(dataset)
+}
+
+/// Forget the drift of `plugin_name`, e.g. after its contract was updated.
+pub fn clear(conn: &DbConnection, plugin_name: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM cf_schema_drift WHERE plugin_name = ?",
+        &[DbValue::from(plugin_name)],
+    )
+    .context("Failed to clear schema drift")?;
+    Ok(())
+}
+
+fn observed_type_label(actual: &ObservedDataType) -> String {
+    match actual {
+        ObservedDataType::Canonical { data_type } => data_type.to_string(),
+        ObservedDataType::Arrow { name } => name.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_protocol::types::TypeDrift;
+    use casparian_protocol::DataType;
+
+    fn drift(unmapped: &[&str], retyped: &[&str]) -> SchemaDrift {
+        SchemaDrift {
+            unmapped_columns: unmapped.iter().map(|c| c.to_string()).collect(),
+            type_drifts: retyped
+                .iter()
+                .map(|column| TypeDrift {
+                    output_name: "orders".to_string(),
+                    column: column.to_string(),
+                    expected: DataType::Float64,
+                    actual: ObservedDataType::Canonical {
+                        data_type: DataType::Int64,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_record_aggregates_per_dataset() {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let first = record(&conn, "orders_parser", 1, &drift(&["coupon"], &["amount"])).unwrap();
+        assert_eq!(first.new_entries, 2);
+        assert_eq!(first.drifted_columns(), 2);
+
+        // The same drift again adds nothing new
+        let again = record(&conn, "orders_parser", 2, &drift(&["coupon"], &[])).unwrap();
+        assert_eq!(again.new_entries, 0);
+        assert_eq!(again.unmapped_columns, vec!["coupon".to_string()]);
+        assert_eq!(again.type_drifts, vec!["orders.amount".to_string()]);
+
+        let grown = record(&conn, "orders_parser", 3, &drift(&["region"], &[])).unwrap();
+        assert_eq!(grown.new_entries, 1);
+        assert_eq!(grown.drifted_columns(), 3);
+
+        assert_eq!(for_plugin(&conn, "other").unwrap(), DatasetDrift::default());
+        clear(&conn, "orders_parser").unwrap();
+        assert_eq!(
+            for_plugin(&conn, "orders_parser")
+                .unwrap()
+                .drifted_columns(),
+            0
+        );
+    }
+}
@@ -9,7 +9,7 @@ use casparian_db::{dev_allow_destructive_reset, DbConnection, DbValue};
 use tracing::warn;
 
 /// Current schema version. Increment when schema changes.
-pub const SCHEMA_VERSION: i32 = 5;
+pub const SCHEMA_VERSION: i32 = 26;
 
 /// Known tables that will be dropped on schema mismatch.
 ///
@@ -20,6 +20,29 @@ const KNOWN_TABLES: &[&str] = &[
     "cf_api_events",
     "cf_api_jobs",
     "cf_api_approvals",
+    "cf_api_approval_audit",
+    // Auto-approval rules (approval_policy.rs)
+    "cf_approval_rules",
+    // Idempotency keys of API requests (idempotency.rs)
+    "cf_idempotency_keys",
+    // Job ID tables (job_ids.rs)
+    "cf_api_job_map",
+    "cf_id_sequences",
+    // Source hash cache (source_hashes.rs)
+    "cf_source_hashes",
+    // Schema drift per dataset (schema_drift.rs)
+    "cf_schema_drift",
+    // Dataset catalog (dataset_catalog.rs)
+    "cf_datasets",
+    // Retention policies and GC ledger (retention.rs)
+    "cf_retention_policies",
+    "cf_gc_deletions",
+    // Compaction policies and ledger (compaction.rs)
+    "cf_compaction_policies",
+    "cf_output_compactions",
+    // Heartbeats and metric samples (write_buffer.rs)
+    "cf_worker_heartbeats",
+    "cf_metric_samples",
     // Pipeline tables (storage/duckdb.rs)
     "cf_selection_specs",
     "cf_selection_snapshots",
@@ -29,6 +52,7 @@ const KNOWN_TABLES: &[&str] = &[
     // Schema tables (schema_storage)
     "schema_contracts",
     "schema_discovery_results",
+    "schema_amendments",
     // UI session tables (tauri session_storage)
     "cf_sessions",
     // Queue tables (queue.rs)
@@ -37,12 +61,22 @@ const KNOWN_TABLES: &[&str] = &[
     "cf_plugin_manifest",
     "cf_plugin_environment",
     "cf_topic_config",
+    "cf_plugin_events",
+    "cf_plugin_subscriptions",
+    "cf_schedules",
     // Error handling tables (queue.rs)
     "cf_dead_letter",
     "cf_parser_health",
     "cf_quarantine",
     "cf_job_schema_mismatch",
     "cf_job_artifacts",
+    "cf_column_stats",
+    "cf_plugin_config",
+    "cf_job_retries",
+    "cf_job_diagnostics",
+    "cf_job_logs",
+    // Lineage tables (lineage.rs)
+    "cf_lineage_hops",
     // Meta table (last, so version check fails if others exist without it)
     "cf_meta",
 ];
@@ -52,11 +86,16 @@ const KNOWN_SEQUENCES: &[&str] = &[
     "seq_cf_processing_queue",
     "seq_cf_plugin_manifest",
     "seq_cf_topic_config",
+    "seq_cf_plugin_events",
+    "seq_cf_plugin_subscriptions",
+    "seq_cf_schedules",
     "seq_cf_dead_letter",
     "seq_cf_quarantine",
     "seq_cf_job_schema_mismatch",
+    "seq_cf_job_retries",
     "seq_cf_api_jobs",
     "seq_cf_api_events",
+    "seq_cf_api_approval_audit",
 ];
 
 /// Ensure the database schema version matches the expected version.
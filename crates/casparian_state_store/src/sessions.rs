@@ -5,7 +5,10 @@
 use anyhow::{Context, Result};
 use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
 use chrono::{DateTime, Utc};
-use casparian_intent::{IntentState, Session, SessionId, StateParseError};
+use casparian_intent::{
+    IntentState, PersistenceError, Session, SessionId, StateMachineCheckpoint,
+    StateMachinePersistence, StateParseError, StateTransition,
+};
 
 /// Storage for Intent Pipeline sessions.
 ///
@@ -53,6 +56,23 @@ impl SessionStorage {
             );
             CREATE INDEX IF NOT EXISTS ix_sessions_state ON cf_sessions(state);
             CREATE INDEX IF NOT EXISTS ix_sessions_created ON cf_sessions(created_at DESC);
+
+            -- State machine checkpoints (current state + full transition history)
+            CREATE TABLE IF NOT EXISTS cf_session_checkpoints (
+                session_id TEXT PRIMARY KEY,
+                current_state TEXT NOT NULL CHECK (current_state IN ({state_values})),
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS cf_session_transitions (
+                session_id TEXT NOT NULL,
+                seq BIGINT NOT NULL,
+                from_state TEXT NOT NULL CHECK (from_state IN ({state_values})),
+                to_state TEXT NOT NULL CHECK (to_state IN ({state_values})),
+                transitioned_at INTEGER NOT NULL,
+                reason TEXT,
+                actor TEXT,
+                PRIMARY KEY (session_id, seq)
+            );
             "#,
             state_values = state_values,
         );
@@ -373,3 +393,175 @@ fn millis_to_rfc3339(millis: i64) -> String {
         .unwrap_or_else(|| Utc::now())
         .to_rfc3339()
 }
+
+// ============================================================================
+// State Machine Checkpoints
+// ============================================================================
+
+impl SessionStorage {
+    fn write_checkpoint(
+        &self,
+        session_id: SessionId,
+        checkpoint: &StateMachineCheckpoint,
+    ) -> Result<()> {
+        let session_key = session_id.to_string();
+        let now = now_millis();
+
+        self.conn.transaction(|tx| {
+            tx.execute(
+                r#"
+                INSERT INTO cf_session_checkpoints (session_id, current_state, updated_at)
+                VALUES (?, ?, ?)
+                ON CONFLICT(session_id) DO UPDATE SET
+                    current_state = excluded.current_state,
+                    updated_at = excluded.updated_at
+                "#,
+                &[
+                    DbValue::from(session_key.as_str()),
+                    DbValue::from(checkpoint.current.as_str()),
+                    DbValue::from(now),
+                ],
+            )?;
+            tx.execute(
+                "DELETE FROM cf_session_transitions WHERE session_id = ?",
+                &[DbValue::from(session_key.as_str())],
+            )?;
+            for (seq, transition) in checkpoint.history.iter().enumerate() {
+                let seq = i64::try_from(seq).map_err(|_| {
+                    casparian_db::BackendError::Transaction(
+                        "transition history too long".to_string(),
+                    )
+                })?;
+                tx.execute(
+                    r#"
+                    INSERT INTO cf_session_transitions
+                        (session_id, seq, from_state, to_state, transitioned_at, reason, actor)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                    &[
+                        DbValue::from(session_key.as_str()),
+                        DbValue::from(seq),
+                        DbValue::from(transition.from.as_str()),
+                        DbValue::from(transition.to.as_str()),
+                        DbValue::from(transition.timestamp.timestamp_millis()),
+                        DbValue::from(transition.reason.as_deref()),
+                        DbValue::from(transition.actor.as_deref()),
+                    ],
+                )?;
+            }
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    fn read_checkpoint(&self, session_id: SessionId) -> Result<Option<StateMachineCheckpoint>> {
+        let session_key = session_id.to_string();
+        let row = self.conn.query_optional(
+            "SELECT current_state FROM cf_session_checkpoints WHERE session_id = ?",
+            &[DbValue::from(session_key.as_str())],
+        )?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let current = parse_state(&row.get::<String>(0)?)?;
+
+        let rows = self.conn.query_all(
+            r#"
+            SELECT from_state, to_state, transitioned_at, reason, actor
+            FROM cf_session_transitions
+            WHERE session_id = ?
+            ORDER BY seq
+            "#,
+            &[DbValue::from(session_key.as_str())],
+        )?;
+        let history = rows
+            .iter()
+            .map(|row| {
+                let millis: i64 = row.get(2)?;
+                let timestamp = DateTime::<Utc>::from_timestamp_millis(millis)
+                    .with_context(|| format!("Invalid transition timestamp: {}", millis))?;
+                Ok(StateTransition {
+                    from: parse_state(&row.get::<String>(0)?)?,
+                    to: parse_state(&row.get::<String>(1)?)?,
+                    timestamp,
+                    reason: row.get(3)?,
+                    actor: row.get(4)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(StateMachineCheckpoint { current, history }))
+    }
+}
+
+impl StateMachinePersistence for SessionStorage {
+    fn save_checkpoint(
+        &self,
+        session_id: SessionId,
+        checkpoint: &StateMachineCheckpoint,
+    ) -> std::result::Result<(), PersistenceError> {
+        self.write_checkpoint(session_id, checkpoint)
+            .map_err(Into::into)
+    }
+
+    fn load_checkpoint(
+        &self,
+        session_id: SessionId,
+    ) -> std::result::Result<Option<StateMachineCheckpoint>, PersistenceError> {
+        self.read_checkpoint(session_id).map_err(Into::into)
+    }
+}
+
+fn parse_state(value: &str) -> Result<IntentState> {
+    value.parse().map_err(|e: StateParseError| {
+        anyhow::anyhow!("Invalid state in database: {} - {}", value, e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casparian_intent::StateMachine;
+
+    fn storage() -> SessionStorage {
+        let storage = SessionStorage::new(DbConnection::open_duckdb_memory().unwrap());
+        storage.init_schema().unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_state_machine_checkpoint_roundtrip() {
+        let storage = storage();
+        let session_id = SessionId::new();
+
+        let mut sm = StateMachine::new();
+        sm.transition(IntentState::ScanCorpus).unwrap();
+        sm.transition_with_reason(
+            IntentState::ProposeSelection,
+            Some("scan complete".to_string()),
+            None,
+        )
+        .unwrap();
+        sm.save(session_id, &storage).unwrap();
+
+        // A later checkpoint replaces the earlier one.
+        sm.transition(IntentState::AwaitingSelectionApproval).unwrap();
+        sm.save(session_id, &storage).unwrap();
+
+        let resumed = StateMachine::resume(session_id, &storage).unwrap();
+        assert_eq!(resumed.current(), IntentState::AwaitingSelectionApproval);
+        assert_eq!(resumed.history().len(), 3);
+        assert_eq!(
+            resumed.history()[1].reason.as_deref(),
+            Some("scan complete")
+        );
+        assert_eq!(resumed.history()[2].from, IntentState::ProposeSelection);
+    }
+
+    #[test]
+    fn test_resume_unknown_session() {
+        let storage = storage();
+        assert!(StateMachine::resume(SessionId::new(), &storage).is_err());
+    }
+}
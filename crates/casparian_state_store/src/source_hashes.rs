@@ -0,0 +1,112 @@
+//! Cache of input file hashes.
+//!
+//! Hashing a multi-GB input costs a full read, so the blake3 hash a worker
+//! reports for a file is kept in `cf_source_hashes` keyed by path, together
+//! with the size and mtime it was computed from. The Sentinel sends the cached
+//! hash with the DISPATCH; the worker reuses it while the file still matches
+//! the fingerprint and rehashes otherwise. One row per path: a new generation
+//! of the file replaces the previous hash.
+
+use anyhow::{Context, Result};
+use casparian_db::{DbConnection, DbValue};
+use casparian_protocol::{SourceFingerprint, SourceHash};
+
+/// Create the hash cache table (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let int_type = if conn.backend_name() == "SQLite" {
+        "INTEGER"
+    } else {
+        "BIGINT"
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_source_hashes (
+            path TEXT PRIMARY KEY,
+            size {int_type} NOT NULL,
+            mtime_ms {int_type} NOT NULL,
+            hash TEXT NOT NULL,
+            hashed_at {int_type} NOT NULL
+        );
+        "#,
+        int_type = int_type,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize cf_source_hashes schema")?;
+    Ok(())
+}
+
+/// Cached hash of `path`, whatever generation it was computed from.
+///
+/// Callers compare [`SourceHash::fingerprint`] with the file on disk before
+/// trusting it.
+pub fn lookup(conn: &DbConnection, path: &str) -> Result<Option<SourceHash>> {
+    let row = conn.query_optional(
+        "SELECT size, mtime_ms, hash FROM cf_source_hashes WHERE path = ?",
+        &[DbValue::from(path)],
+    )?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let size: i64 = row.get_by_name("size")?;
+    Ok(Some(SourceHash {
+        hash: row.get_by_name("hash")?,
+        fingerprint: SourceFingerprint {
+            size: u64::try_from(size).context("Cached source size is negative")?,
+            mtime_ms: row.get_by_name("mtime_ms")?,
+        },
+    }))
+}
+
+/// Cache `source_hash` for `path`, replacing any earlier generation.
+pub fn record(conn: &DbConnection, path: &str, source_hash: &SourceHash) -> Result<()> {
+    let size =
+        i64::try_from(source_hash.fingerprint.size).context("Source file size out of range")?;
+    conn.execute(
+        r#"
+        INSERT INTO cf_source_hashes (path, size, mtime_ms, hash, hashed_at)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT (path) DO UPDATE SET
+            size = excluded.size,
+            mtime_ms = excluded.mtime_ms,
+            hash = excluded.hash,
+            hashed_at = excluded.hashed_at
+        "#,
+        &[
+            DbValue::from(path),
+            DbValue::from(size),
+            DbValue::from(source_hash.fingerprint.mtime_ms),
+            DbValue::from(source_hash.hash.as_str()),
+            DbValue::from(chrono::Utc::now().timestamp_millis()),
+        ],
+    )
+    .context("Failed to record source hash")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source_hash(hash: &str, size: u64, mtime_ms: i64) -> SourceHash {
+        SourceHash {
+            hash: hash.to_string(),
+            fingerprint: SourceFingerprint { size, mtime_ms },
+        }
+    }
+
+    #[test]
+    fn test_record_replaces_previous_generation() {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        init_schema(&conn).unwrap();
+        assert_eq!(lookup(&conn, "/data/a.csv").unwrap(), None);
+
+        let first = source_hash("aaa", 10, 1_000);
+        record(&conn, "/data/a.csv", &first).unwrap();
+        assert_eq!(lookup(&conn, "/data/a.csv").unwrap(), Some(first));
+
+        let second = source_hash("bbb", 20, 2_000);
+        record(&conn, "/data/a.csv", &second).unwrap();
+        assert_eq!(lookup(&conn, "/data/a.csv").unwrap(), Some(second));
+        assert_eq!(lookup(&conn, "/data/b.csv").unwrap(), None);
+    }
+}
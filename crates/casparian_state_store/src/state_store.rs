@@ -4,27 +4,42 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use casparian_db::{DbConnection, DbValue, UnifiedDbRow};
 use casparian_protocol::http_types::{
-    ApiJobId, Approval, ApprovalStatus, HttpJobStatus, HttpJobType, Job as ApiJob, JobResult,
+    ApiJobId, Approval, ApprovalAuditEntry, ApprovalStatus, Event, EventId, EventType,
+    HttpJobStatus, HttpJobType, Job as ApiJob, JobResult,
 };
 use casparian_protocol::{
-    ArtifactV1, JobId, PipelineRunStatus, PluginStatus, ProcessingStatus, RuntimeKind,
+    ArtifactV1, ByteRange, CsvDialect, ErrorCategory, JobDiagnostics, JobId, JobPriority, JobResultSummary, LineageChain,
+    OutputColumnStats,
+    PipelineRun, PipelineRunStatus, PluginStatus, ProcessingStatus, ReceiptVerification, ResourceLimits, RetryAttempt, RetryPolicy, RuntimeKind, ScheduleCatchUp, ScheduleTarget, SchemaDrift, SourceHash, TextEncoding,
 };
 use casparian_schema::{SchemaContract, SchemaStorage};
 use casparian_scout::{
     patterns, rule_apply::match_rules_to_files, rule_apply::RuleApplyFile,
-    rule_apply::RuleApplyRule, Database as ScoutDatabase, ScanConfig, Scanner as ScoutScanner,
+    rule_apply::RuleApplyRule, ContentDetection, Database as ScoutDatabase, ScanConfig,
+    Scanner as ScoutScanner,
 };
 use casparian_scout::types::{
-    Source, SourceId, SourceType, TagSource, TaggingRule, TaggingRuleId, Workspace, WorkspaceId,
+    RuleMatchMode, Source, SourceId, SourceType, TagSource, TaggingRule, TaggingRuleId, Workspace,
+    WorkspaceId,
 };
 
 use crate::api_storage::ApiStorage;
+use crate::audit::AuditLog;
 use crate::expected_outputs::{ExpectedOutputs, OutputSpec};
+use crate::idempotency::IdempotentResponse;
+use crate::lineage::{ArtifactColumns, LineageRecord, LineageStorage};
+use crate::dataset_catalog::{DatasetCommit, DatasetRecord};
+use crate::schema_drift::DatasetDrift;
 use crate::models::{
-    DeadLetterReason, ParserHealth, ProcessingJob, TopicConfig, TOPIC_CONFIG_COLUMNS,
+    DeadLetterJob, DeadLetterReason, ParserHealth, ProcessingJob, TopicConfig, TOPIC_CONFIG_COLUMNS,
+};
+use crate::queue::{
+    DispatchMetadata, Job, JobDetails, JobHistoryPage, JobHistoryQuery, JobLogChunk, JobQueue,
+    OutputMaterialization, PluginRollback, Schedule, ScheduleHold, ScheduleState,
+    TopicSubscription,
 };
-use crate::queue::{DispatchMetadata, Job, JobDetails, JobQueue, OutputMaterialization};
 use crate::sessions::SessionStorage;
+use crate::write_buffer::FlushBatch;
 
 /// Parsed state store URL.
 #[derive(Debug, Clone)]
@@ -56,6 +71,7 @@ impl StateStoreUrl {
 /// Semantic state store wrapper.
 pub struct StateStore {
     inner: Box<dyn StateStoreBackend>,
+    audit: AuditLog,
 }
 
 impl StateStore {
@@ -67,6 +83,7 @@ impl StateStore {
     pub fn from_url(url: StateStoreUrl) -> Result<Self> {
         match url {
             StateStoreUrl::Sqlite(path) => Ok(Self {
+                audit: AuditLog::for_sqlite(&path),
                 inner: Box::new(SqliteStateStore::new(path)),
             }),
             StateStoreUrl::Postgres(_) => anyhow::bail!("Postgres state store not yet supported"),
@@ -102,6 +119,15 @@ impl StateStore {
         self.inner.artifacts()
     }
 
+    pub fn lineage(&self) -> &dyn LineageStore {
+        self.inner.lineage()
+    }
+
+    /// Audit log of control-plane mutations made through this store.
+    pub fn audit(&self) -> &AuditLog {
+        &self.audit
+    }
+
     pub fn session_fast(&self) -> Result<StateStoreQueueSession> {
         self.inner.session_fast()
     }
@@ -130,6 +156,18 @@ impl StateStoreQueueSession {
         self.queue.lease_jobs_for_dispatch(limit, now, ttl_ms)
     }
 
+    pub fn lease_batch_jobs_for_dispatch(
+        &self,
+        plugin_name: &str,
+        max_file_bytes: u64,
+        limit: usize,
+        now: i64,
+        ttl_ms: i64,
+    ) -> Result<Vec<ProcessingJob>> {
+        self.queue
+            .lease_batch_jobs_for_dispatch(plugin_name, max_file_bytes, limit, now, ttl_ms)
+    }
+
     pub fn set_dispatch_lease(
         &self,
         job_id: i64,
@@ -155,6 +193,24 @@ impl StateStoreQueueSession {
         self.queue.requeue_expired_dispatches(now)
     }
 
+    pub fn promote_starved_jobs(&self, queued_before: i64, lane: JobPriority) -> Result<usize> {
+        self.queue.promote_starved_jobs(queued_before, lane)
+    }
+
+    pub fn preempt_job_if_token_matches(
+        &self,
+        job_id: i64,
+        lease_token: &str,
+        now: i64,
+    ) -> Result<bool> {
+        self.queue
+            .preempt_job_if_token_matches(job_id, lease_token, now)
+    }
+
+    pub fn peek_job(&self) -> Result<Option<ProcessingJob>> {
+        self.queue.peek_job()
+    }
+
     pub fn complete_job_if_token_matches(
         &self,
         job_id: i64,
@@ -209,6 +265,16 @@ impl StateStoreQueueSession {
         )
     }
 
+    pub fn skip_job_if_token_matches_dispatching(
+        &self,
+        job_id: i64,
+        lease_token: &str,
+        summary: &str,
+    ) -> Result<bool> {
+        self.queue
+            .skip_job_if_token_matches_dispatching(job_id, lease_token, summary)
+    }
+
     pub fn fail_job(&self, job_id: i64, completion_status: &str, error: &str) -> Result<()> {
         self.queue.fail_job(job_id, completion_status, error)
     }
@@ -239,6 +305,22 @@ impl StateStoreQueueSession {
         self.queue.load_file_generation(file_id)
     }
 
+    pub fn load_source_root(&self, file_id: i64) -> Result<Option<String>> {
+        self.queue.load_source_root(file_id)
+    }
+
+    pub fn load_file_tags(&self, file_id: i64) -> Result<Vec<String>> {
+        self.queue.load_file_tags(file_id)
+    }
+
+    pub fn plugin_namespace(&self, plugin_name: &str) -> Result<String> {
+        self.queue.plugin_namespace(plugin_name)
+    }
+
+    pub fn active_plugin_version(&self, plugin_name: &str) -> Result<Option<String>> {
+        self.queue.active_plugin_version(plugin_name)
+    }
+
     pub fn get_dispatch_metadata(&self, job_id: i64) -> Result<Option<DispatchMetadata>> {
         self.queue.get_dispatch_metadata(job_id)
     }
@@ -261,6 +343,13 @@ impl StateStoreQueueSession {
         self.queue.insert_output_materialization(record)
     }
 
+    pub fn find_output_materializations(
+        &self,
+        keys: &[String],
+    ) -> Result<Vec<OutputMaterialization>> {
+        self.queue.find_output_materializations(keys)
+    }
+
     pub fn record_schema_mismatch(
         &self,
         job_id: i64,
@@ -304,6 +393,37 @@ impl StateStoreQueueSession {
         self.queue.move_to_dead_letter(job_id, error, reason)
     }
 
+    pub fn get_dead_letter_jobs(
+        &self,
+        plugin: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DeadLetterJob>> {
+        match plugin {
+            Some(plugin) => self.queue.get_dead_letter_jobs_by_plugin(plugin, limit),
+            None => self.queue.get_dead_letter_jobs(limit),
+        }
+    }
+
+    pub fn get_dead_letter_job(&self, dead_letter_id: i64) -> Result<Option<DeadLetterJob>> {
+        self.queue.get_dead_letter_job(dead_letter_id)
+    }
+
+    pub fn replay_dead_letter(&self, dead_letter_id: i64) -> Result<i64> {
+        self.queue.replay_dead_letter(dead_letter_id)
+    }
+
+    pub fn purge_dead_letter(&self, dead_letter_id: i64) -> Result<bool> {
+        self.queue.purge_dead_letter(dead_letter_id)
+    }
+
+    pub fn purge_dead_letters(
+        &self,
+        moved_before: Option<i64>,
+        plugin: Option<&str>,
+    ) -> Result<u64> {
+        self.queue.purge_dead_letters(moved_before, plugin)
+    }
+
     pub fn record_parser_success(&self, parser_name: &str) -> Result<()> {
         self.queue.record_parser_success(parser_name)
     }
@@ -320,6 +440,92 @@ impl StateStoreQueueSession {
         self.queue.get_parser_health(parser_name)
     }
 
+    pub fn get_retry_policy(&self, plugin_name: &str) -> Result<Option<RetryPolicy>> {
+        self.queue.get_retry_policy(plugin_name)
+    }
+
+    pub fn get_resource_limits(&self, plugin_name: &str) -> Result<Option<ResourceLimits>> {
+        self.queue.get_resource_limits(plugin_name)
+    }
+
+    pub fn resolve_csv_dialect(
+        &self,
+        plugin_name: &str,
+        file_id: i64,
+    ) -> Result<Option<CsvDialect>> {
+        self.queue.resolve_csv_dialect(plugin_name, file_id)
+    }
+
+    pub fn resolve_input_encoding(&self, file_id: i64) -> Result<Option<TextEncoding>> {
+        self.queue.resolve_input_encoding(file_id)
+    }
+
+    pub fn get_lockfile(&self, env_hash: &str) -> Result<Option<String>> {
+        self.queue.get_lockfile(env_hash)
+    }
+
+    pub fn cached_source_hash(&self, path: &str) -> Result<Option<SourceHash>> {
+        self.queue.cached_source_hash(path)
+    }
+
+    pub fn record_source_hash(&self, path: &str, source_hash: &SourceHash) -> Result<()> {
+        self.queue.record_source_hash(path, source_hash)
+    }
+
+    pub fn record_schema_drift(
+        &self,
+        plugin_name: &str,
+        job_id: i64,
+        drift: &SchemaDrift,
+    ) -> Result<DatasetDrift> {
+        self.queue.record_schema_drift(plugin_name, job_id, drift)
+    }
+
+    pub fn register_dataset(&self, commit: &DatasetCommit) -> Result<()> {
+        self.queue.register_dataset(commit)
+    }
+
+    pub fn list_datasets(
+        &self,
+        search: Option<&str>,
+        plugin_name: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<DatasetRecord>> {
+        self.queue.list_datasets(search, plugin_name, limit)
+    }
+
+    pub fn describe_dataset(&self, name: &str) -> Result<Option<DatasetRecord>> {
+        self.queue.describe_dataset(name)
+    }
+
+    pub fn record_retry_attempt(&self, job_id: i64, attempt: &RetryAttempt) -> Result<()> {
+        self.queue.record_retry_attempt(job_id, attempt)
+    }
+
+    pub fn record_job_diagnostics(&self, job_id: i64, diagnostics: &JobDiagnostics) -> Result<()> {
+        self.queue.record_job_diagnostics(job_id, diagnostics)
+    }
+
+    pub fn record_result_summary(&self, job_id: i64, summary: &JobResultSummary) -> Result<()> {
+        self.queue.record_result_summary(job_id, summary)
+    }
+
+    pub fn record_receipt_verification(
+        &self,
+        job_id: i64,
+        verification: &ReceiptVerification,
+    ) -> Result<()> {
+        self.queue.record_receipt_verification(job_id, verification)
+    }
+
+    pub fn append_job_log(&self, job_id: i64, offset: i64, chunk: &str) -> Result<()> {
+        self.queue.append_job_log(job_id, offset, chunk)
+    }
+
+    pub fn apply_write_batch(&self, batch: FlushBatch) -> Result<()> {
+        self.queue.apply_write_batch(batch)
+    }
+
     pub fn update_pipeline_run_status_for_job(&self, job_id: i64) -> Result<()> {
         self.queue.update_pipeline_run_status_for_job(job_id)
     }
@@ -332,6 +538,10 @@ impl StateStoreQueueSession {
         self.queue.update_pipeline_run_status(run_id)
     }
 
+    pub fn get_pipeline_run(&self, run_id: &str) -> Result<Option<PipelineRun>> {
+        self.queue.get_pipeline_run(run_id)
+    }
+
     pub fn list_jobs(
         &self,
         status: Option<ProcessingStatus>,
@@ -349,16 +559,53 @@ impl StateStoreQueueSession {
         self.queue.get_job(job_id)
     }
 
+    pub fn query_job_history(&self, query: &JobHistoryQuery) -> Result<JobHistoryPage> {
+        self.queue.query_job_history(query)
+    }
+
     pub fn cancel_job(&self, job_id: JobId) -> Result<bool> {
         self.queue.cancel_job(job_id)
     }
 
+    pub fn cancel_running_job(&self, job_id: JobId) -> Result<bool> {
+        self.queue.cancel_running_job(job_id)
+    }
+
     pub fn count_jobs_by_status(&self) -> Result<HashMap<ProcessingStatus, i64>> {
         self.queue.count_jobs_by_status()
     }
 
-    pub fn load_dispatch_data(&self, plugin_name: &str, file_id: i64) -> Result<DispatchData> {
-        self.queue.load_dispatch_data(plugin_name, file_id)
+    pub fn load_dispatch_data(
+        &self,
+        job_id: i64,
+        plugin_name: &str,
+        file_id: i64,
+    ) -> Result<DispatchData> {
+        self.queue.load_dispatch_data(job_id, plugin_name, file_id)
+    }
+
+    pub fn enqueue_downstream_jobs(
+        &self,
+        job_id: i64,
+        artifacts: &[ArtifactV1],
+    ) -> Result<Vec<i64>> {
+        self.queue.enqueue_downstream_jobs(job_id, artifacts)
+    }
+
+    pub fn list_schedules(&self) -> Result<Vec<Schedule>> {
+        self.queue.list_schedules()
+    }
+
+    pub fn record_schedule_state(&self, schedule_id: i64, state: ScheduleState) -> Result<()> {
+        self.queue.record_schedule_state(schedule_id, state)
+    }
+
+    pub fn schedule_hold(&self, file_id: i64, now: i64) -> Result<Option<ScheduleHold>> {
+        self.queue.schedule_hold(file_id, now)
+    }
+
+    pub fn release_schedule_holds(&self, rule_id: &str, now: i64) -> Result<usize> {
+        self.queue.release_schedule_holds(rule_id, now)
     }
 }
 
@@ -386,6 +633,7 @@ pub trait StateStoreBackend: Send + Sync {
     fn routing(&self) -> &dyn RoutingStore;
     fn scout(&self) -> &dyn ScoutStore;
     fn artifacts(&self) -> &dyn ArtifactStore;
+    fn lineage(&self) -> &dyn LineageStore;
     fn session_fast(&self) -> Result<StateStoreQueueSession>;
     fn session_bulk(&self) -> Result<StateStoreScoutSession>;
     fn schema_storage(&self) -> Result<SchemaStorage>;
@@ -410,6 +658,14 @@ pub struct DispatchData {
     pub platform_arch: Option<String>,
     pub signature_verified: bool,
     pub signer_id: Option<String>,
+    /// Capability tags a worker must advertise to run this plugin
+    pub system_requirements: Vec<String>,
+    /// Dispatch even when every output is already materialized
+    pub force_rerun: bool,
+    /// Part of the file to read (incremental job); `None` reads all of it
+    pub byte_range: Option<ByteRange>,
+    /// Upstream artifact to read instead of the scanned file (downstream job)
+    pub input_path: Option<String>,
 }
 
 impl DispatchData {
@@ -427,6 +683,29 @@ impl DispatchData {
             }
         });
 
+        let system_requirements: Option<String> = row.get_by_name("system_requirements")?;
+        let system_requirements = match system_requirements.as_deref().map(str::trim) {
+            Some(json) if !json.is_empty() => serde_json::from_str(json)
+                .with_context(|| format!("Invalid system_requirements JSON: {}", json))?,
+            _ => Vec::new(),
+        };
+
+        let range_start: Option<i64> = row.get_by_name("range_start")?;
+        let range_end: Option<i64> = row.get_by_name("range_end")?;
+        let byte_range = match (range_start, range_end) {
+            (Some(start), Some(end)) => Some(ByteRange {
+                start: u64::try_from(start).context("Negative range_start")?,
+                end: u64::try_from(end).context("Negative range_end")?,
+            }),
+            _ => None,
+        };
+
+        let upstream_job_id: Option<i64> = row.get_by_name("upstream_job_id")?;
+        let input_path = match upstream_job_id {
+            Some(_) => row.get_by_name("input_file")?,
+            None => None,
+        };
+
         Ok(Self {
             rel_path: row.get_by_name("rel_path")?,
             scan_root: row.get_by_name("scan_root")?,
@@ -441,6 +720,12 @@ impl DispatchData {
             platform_arch: row.get_by_name("platform_arch")?,
             signature_verified: row.get_by_name("signature_verified")?,
             signer_id: row.get_by_name("signer_id")?,
+            system_requirements,
+            force_rerun: row
+                .get_by_name::<Option<bool>>("force_rerun")?
+                .unwrap_or(false),
+            byte_range,
+            input_path,
         })
     }
 }
@@ -478,6 +763,7 @@ pub trait QueueStore: Send + Sync {
         offset: i64,
     ) -> Result<Vec<Job>>;
     fn get_job(&self, job_id: JobId) -> Result<Option<Job>>;
+    fn query_job_history(&self, query: &JobHistoryQuery) -> Result<JobHistoryPage>;
     fn count_jobs_by_status(&self) -> Result<HashMap<ProcessingStatus, i64>>;
 
     fn get_job_details(&self, job_id: i64) -> Result<Option<JobDetails>>;
@@ -500,6 +786,17 @@ pub trait QueueStore: Send + Sync {
     fn record_parser_failure(&self, parser_name: &str, reason: &str) -> Result<i32>;
     fn pause_parser(&self, parser_name: &str) -> Result<()>;
     fn get_parser_health(&self, parser_name: &str) -> Result<Option<ParserHealth>>;
+    fn get_retry_policy(&self, plugin_name: &str) -> Result<Option<RetryPolicy>>;
+    fn set_retry_policy(&self, plugin_name: &str, policy: Option<&RetryPolicy>) -> Result<()>;
+    fn get_resource_limits(&self, plugin_name: &str) -> Result<Option<ResourceLimits>>;
+    fn set_resource_limits(&self, plugin_name: &str, limits: Option<&ResourceLimits>)
+        -> Result<()>;
+    fn get_csv_dialect(&self, plugin_name: &str) -> Result<Option<CsvDialect>>;
+    fn set_csv_dialect(&self, plugin_name: &str, dialect: Option<&CsvDialect>) -> Result<()>;
+    fn list_job_retries(&self, job_id: i64) -> Result<Vec<RetryAttempt>>;
+    fn get_job_diagnostics(&self, job_id: i64) -> Result<Option<JobDiagnostics>>;
+    fn count_failures_by_category(&self) -> Result<Vec<(ErrorCategory, i64)>>;
+    fn read_job_log(&self, job_id: i64, from_offset: i64, limit: i64) -> Result<Vec<JobLogChunk>>;
     fn move_to_dead_letter(
         &self,
         job_id: i64,
@@ -507,7 +804,12 @@ pub trait QueueStore: Send + Sync {
         reason: DeadLetterReason,
     ) -> Result<()>;
 
-    fn load_dispatch_data(&self, plugin_name: &str, file_id: i64) -> Result<DispatchData>;
+    fn load_dispatch_data(
+        &self,
+        job_id: i64,
+        plugin_name: &str,
+        file_id: i64,
+    ) -> Result<DispatchData>;
     fn load_file_generation(&self, file_id: i64) -> Result<Option<(i64, i64)>>;
 
     fn update_pipeline_run_status_for_job(&self, job_id: i64) -> Result<()>;
@@ -618,6 +920,10 @@ impl QueueStore for SqliteQueueStore {
         self.with_queue(|queue| queue.get_job(job_id))
     }
 
+    fn query_job_history(&self, query: &JobHistoryQuery) -> Result<JobHistoryPage> {
+        self.with_queue(|queue| queue.query_job_history(query))
+    }
+
     fn count_jobs_by_status(&self) -> Result<HashMap<ProcessingStatus, i64>> {
         self.with_queue(|queue| queue.count_jobs_by_status())
     }
@@ -670,6 +976,50 @@ impl QueueStore for SqliteQueueStore {
         self.with_queue(|queue| queue.get_parser_health(parser_name))
     }
 
+    fn get_retry_policy(&self, plugin_name: &str) -> Result<Option<RetryPolicy>> {
+        self.with_queue(|queue| queue.get_retry_policy(plugin_name))
+    }
+
+    fn set_retry_policy(&self, plugin_name: &str, policy: Option<&RetryPolicy>) -> Result<()> {
+        self.with_queue(|queue| queue.set_retry_policy(plugin_name, policy))
+    }
+
+    fn get_resource_limits(&self, plugin_name: &str) -> Result<Option<ResourceLimits>> {
+        self.with_queue(|queue| queue.get_resource_limits(plugin_name))
+    }
+
+    fn set_resource_limits(
+        &self,
+        plugin_name: &str,
+        limits: Option<&ResourceLimits>,
+    ) -> Result<()> {
+        self.with_queue(|queue| queue.set_resource_limits(plugin_name, limits))
+    }
+
+    fn get_csv_dialect(&self, plugin_name: &str) -> Result<Option<CsvDialect>> {
+        self.with_queue(|queue| queue.get_csv_dialect(plugin_name))
+    }
+
+    fn set_csv_dialect(&self, plugin_name: &str, dialect: Option<&CsvDialect>) -> Result<()> {
+        self.with_queue(|queue| queue.set_csv_dialect(plugin_name, dialect))
+    }
+
+    fn list_job_retries(&self, job_id: i64) -> Result<Vec<RetryAttempt>> {
+        self.with_queue(|queue| queue.list_job_retries(job_id))
+    }
+
+    fn get_job_diagnostics(&self, job_id: i64) -> Result<Option<JobDiagnostics>> {
+        self.with_queue(|queue| queue.get_job_diagnostics(job_id))
+    }
+
+    fn read_job_log(&self, job_id: i64, from_offset: i64, limit: i64) -> Result<Vec<JobLogChunk>> {
+        self.with_queue(|queue| queue.read_job_log(job_id, from_offset, limit))
+    }
+
+    fn count_failures_by_category(&self) -> Result<Vec<(ErrorCategory, i64)>> {
+        self.with_queue(|queue| queue.count_failures_by_category())
+    }
+
     fn move_to_dead_letter(
         &self,
         job_id: i64,
@@ -679,41 +1029,13 @@ impl QueueStore for SqliteQueueStore {
         self.with_queue(|queue| queue.move_to_dead_letter(job_id, error, reason))
     }
 
-    fn load_dispatch_data(&self, plugin_name: &str, file_id: i64) -> Result<DispatchData> {
-        self.with_conn(|conn| {
-            let row = conn.query_optional(
-                r#"
-                SELECT
-                    sf.rel_path as rel_path,
-                    ss.path as scan_root,
-                    ss.exec_path as exec_root,
-                    pm.source_code,
-                    pm.version as parser_version,
-                    pm.env_hash,
-                    pm.artifact_hash,
-                    pm.runtime_kind,
-                    pm.entrypoint,
-                    pm.platform_os,
-                    pm.platform_arch,
-                    pm.signature_verified,
-                    pm.signer_id
-                FROM scout_files sf
-                JOIN scout_sources ss ON ss.id = sf.source_id
-                JOIN cf_plugin_manifest pm ON pm.plugin_name = ? AND pm.status IN (?, ?)
-                WHERE sf.id = ?
-                ORDER BY pm.created_at DESC
-                LIMIT 1
-                "#,
-                &[
-                    DbValue::from(plugin_name),
-                    DbValue::from(PluginStatus::Active.as_str()),
-                    DbValue::from(PluginStatus::Deployed.as_str()),
-                    DbValue::from(file_id),
-                ],
-            )?;
-            let row = row.ok_or_else(|| anyhow::anyhow!("Dispatch data missing"))?;
-            DispatchData::from_row(&row)
-        })
+    fn load_dispatch_data(
+        &self,
+        job_id: i64,
+        plugin_name: &str,
+        file_id: i64,
+    ) -> Result<DispatchData> {
+        self.with_queue(|queue| queue.load_dispatch_data(job_id, plugin_name, file_id))
     }
 
     fn load_file_generation(&self, file_id: i64) -> Result<Option<(i64, i64)>> {
@@ -854,7 +1176,7 @@ pub trait ApiStore: Send + Sync {
         operation: &casparian_protocol::ApprovalOperation,
         summary: &str,
         expires_in: chrono::Duration,
-    ) -> Result<()>;
+    ) -> Result<Option<String>>;
 
     fn create_job(
         &self,
@@ -870,6 +1192,7 @@ pub trait ApiStore: Send + Sync {
     fn list_jobs(
         &self,
         status: Option<HttpJobStatus>,
+        namespace: Option<&str>,
         limit: usize,
     ) -> Result<Vec<ApiJob>>;
     fn update_job_status(
@@ -888,21 +1211,55 @@ pub trait ApiStore: Send + Sync {
     fn update_job_result(&self, job_id: ApiJobId, result: &JobResult) -> Result<()>;
     fn update_job_error(&self, job_id: ApiJobId, error: &str) -> Result<()>;
     fn cancel_job(&self, job_id: ApiJobId) -> Result<bool>;
+    /// Record that queue job `job_id` was enqueued for API job `api_job_id`.
+    fn link_queue_job(&self, api_job_id: ApiJobId, job_id: JobId) -> Result<()>;
+    /// Reserve `count` job IDs for the work of API job `api_job_id`, linked to it.
+    fn reserve_queue_jobs(&self, api_job_id: ApiJobId, count: u64) -> Result<Vec<JobId>>;
+    fn queue_jobs_for(&self, api_job_id: ApiJobId) -> Result<Vec<JobId>>;
+    fn api_job_for(&self, job_id: JobId) -> Result<Option<ApiJobId>>;
 
     fn list_approvals(
         &self,
         status: Option<ApprovalStatus>,
     ) -> Result<Vec<Approval>>;
     fn get_approval(&self, approval_id: &str) -> Result<Option<Approval>>;
-    fn approve(&self, approval_id: &str, decided_by: Option<&str>) -> Result<bool>;
+    fn approve(
+        &self,
+        approval_id: &str,
+        decided_by: Option<&str>,
+        justification: Option<&str>,
+    ) -> Result<bool>;
     fn reject(
         &self,
         approval_id: &str,
         decided_by: Option<&str>,
         reason: Option<&str>,
     ) -> Result<bool>;
+    fn list_approval_audit(&self, approval_id: &str) -> Result<Vec<ApprovalAuditEntry>>;
     fn link_approval_to_job(&self, approval_id: &str, job_id: ApiJobId) -> Result<()>;
     fn expire_approvals(&self) -> Result<usize>;
+    /// Append an event to the API event log.
+    fn insert_event(&self, job_id: ApiJobId, event_type: &EventType) -> Result<EventId>;
+    fn list_events(
+        &self,
+        job_id: ApiJobId,
+        after_event_id: Option<EventId>,
+    ) -> Result<Vec<Event>>;
+    /// Events across all jobs after `after_event_id`, oldest first.
+    fn list_events_since(&self, after_event_id: EventId, limit: usize) -> Result<Vec<Event>>;
+    fn latest_event_id(&self) -> Result<EventId>;
+    /// Response recorded for idempotency key `key` within the last `ttl`.
+    fn find_idempotent_response(
+        &self,
+        key: &str,
+        ttl: chrono::Duration,
+    ) -> Result<Option<IdempotentResponse>>;
+    fn save_idempotent_response(
+        &self,
+        key: &str,
+        request_hash: &str,
+        response_json: &str,
+    ) -> Result<()>;
 }
 
 #[derive(Debug, Clone)]
@@ -937,7 +1294,7 @@ impl ApiStore for SqliteApiStore {
         operation: &casparian_protocol::ApprovalOperation,
         summary: &str,
         expires_in: chrono::Duration,
-    ) -> Result<()> {
+    ) -> Result<Option<String>> {
         self.with_storage(|storage| storage.create_approval(approval_id, operation, summary, expires_in))
     }
 
@@ -971,9 +1328,10 @@ impl ApiStore for SqliteApiStore {
     fn list_jobs(
         &self,
         status: Option<HttpJobStatus>,
+        namespace: Option<&str>,
         limit: usize,
     ) -> Result<Vec<ApiJob>> {
-        self.with_storage(|storage| storage.list_jobs(status, limit))
+        self.with_storage(|storage| storage.list_jobs_in_namespace(status, namespace, limit))
     }
 
     fn update_job_status(
@@ -1009,6 +1367,22 @@ impl ApiStore for SqliteApiStore {
         self.with_storage(|storage| storage.cancel_job(job_id))
     }
 
+    fn link_queue_job(&self, api_job_id: ApiJobId, job_id: JobId) -> Result<()> {
+        self.with_storage(|storage| storage.link_queue_job(api_job_id, job_id))
+    }
+
+    fn reserve_queue_jobs(&self, api_job_id: ApiJobId, count: u64) -> Result<Vec<JobId>> {
+        self.with_storage(|storage| storage.reserve_queue_jobs(api_job_id, count))
+    }
+
+    fn queue_jobs_for(&self, api_job_id: ApiJobId) -> Result<Vec<JobId>> {
+        self.with_storage(|storage| storage.queue_jobs_for(api_job_id))
+    }
+
+    fn api_job_for(&self, job_id: JobId) -> Result<Option<ApiJobId>> {
+        self.with_storage(|storage| storage.api_job_for(job_id))
+    }
+
     fn list_approvals(
         &self,
         status: Option<ApprovalStatus>,
@@ -1020,8 +1394,13 @@ impl ApiStore for SqliteApiStore {
         self.with_storage(|storage| storage.get_approval(approval_id))
     }
 
-    fn approve(&self, approval_id: &str, decided_by: Option<&str>) -> Result<bool> {
-        self.with_storage(|storage| storage.approve(approval_id, decided_by))
+    fn approve(
+        &self,
+        approval_id: &str,
+        decided_by: Option<&str>,
+        justification: Option<&str>,
+    ) -> Result<bool> {
+        self.with_storage(|storage| storage.approve(approval_id, decided_by, justification))
     }
 
     fn reject(
@@ -1033,6 +1412,10 @@ impl ApiStore for SqliteApiStore {
         self.with_storage(|storage| storage.reject(approval_id, decided_by, reason))
     }
 
+    fn list_approval_audit(&self, approval_id: &str) -> Result<Vec<ApprovalAuditEntry>> {
+        self.with_storage(|storage| storage.list_approval_audit(approval_id))
+    }
+
     fn link_approval_to_job(&self, approval_id: &str, job_id: ApiJobId) -> Result<()> {
         self.with_storage(|storage| storage.link_approval_to_job(approval_id, job_id))
     }
@@ -1040,6 +1423,41 @@ impl ApiStore for SqliteApiStore {
     fn expire_approvals(&self) -> Result<usize> {
         self.with_storage(|storage| storage.expire_approvals())
     }
+
+    fn insert_event(&self, job_id: ApiJobId, event_type: &EventType) -> Result<EventId> {
+        self.with_storage(|storage| storage.insert_event(job_id, event_type))
+    }
+
+    fn list_events(&self, job_id: ApiJobId, after_event_id: Option<EventId>) -> Result<Vec<Event>> {
+        self.with_storage(|storage| storage.list_events(job_id, after_event_id))
+    }
+
+    fn list_events_since(&self, after_event_id: EventId, limit: usize) -> Result<Vec<Event>> {
+        self.with_storage(|storage| storage.list_events_since(after_event_id, limit))
+    }
+
+    fn latest_event_id(&self) -> Result<EventId> {
+        self.with_storage(|storage| storage.latest_event_id())
+    }
+
+    fn find_idempotent_response(
+        &self,
+        key: &str,
+        ttl: chrono::Duration,
+    ) -> Result<Option<IdempotentResponse>> {
+        self.with_storage(|storage| storage.find_idempotent_response(key, ttl))
+    }
+
+    fn save_idempotent_response(
+        &self,
+        key: &str,
+        request_hash: &str,
+        response_json: &str,
+    ) -> Result<()> {
+        self.with_storage(|storage| {
+            storage.save_idempotent_response(key, request_hash, response_json)
+        })
+    }
 }
 
 // ============================================================================
@@ -1145,11 +1563,42 @@ pub trait RoutingStore: Send + Sync {
         parser_version: Option<&str>,
     ) -> Result<Vec<OutputSpec>>;
     fn deploy_plugin(&self, request: PluginDeployRequest) -> Result<()>;
+    /// Re-activate a previously active plugin version and record the change.
+    fn rollback_plugin(
+        &self,
+        plugin_name: &str,
+        target_version: &str,
+        actor: Option<&str>,
+    ) -> Result<PluginRollback>;
+    /// Subscriptions of plugins to other plugins' output topics.
+    fn list_subscriptions(&self) -> Result<Vec<TopicSubscription>>;
+    /// Subscribe a plugin to an output topic; rejects cycles.
+    fn subscribe_plugin(&self, plugin_name: &str, topic_name: &str) -> Result<TopicSubscription>;
+    /// Enable or disable one subscription edge.
+    fn set_subscription_active(
+        &self,
+        subscription_id: i64,
+        active: bool,
+    ) -> Result<TopicSubscription>;
+    /// Cron schedules for source rescans and rule processing windows.
+    fn list_schedules(&self) -> Result<Vec<Schedule>>;
+    fn get_schedule(&self, schedule_id: i64) -> Result<Option<Schedule>>;
+    fn create_schedule(
+        &self,
+        name: &str,
+        cron: &str,
+        target: &ScheduleTarget,
+        catch_up: ScheduleCatchUp,
+    ) -> Result<Schedule>;
+    /// Enable or disable a schedule; disabling releases held jobs.
+    fn set_schedule_active(&self, schedule_id: i64, active: bool) -> Result<Schedule>;
+    fn delete_schedule(&self, schedule_id: i64) -> Result<bool>;
 }
 
 #[derive(Debug, Clone)]
 pub struct PluginDeployRequest {
     pub plugin_name: String,
+    pub namespace: String,
     pub version: String,
     pub runtime_kind: RuntimeKind,
     pub entrypoint: String,
@@ -1247,6 +1696,29 @@ impl RoutingStore for SqliteRoutingStore {
                 }
             }
 
+            // A plugin name stays in the namespace of its first deploy.
+            let existing_namespace = match conn.query_optional(
+                "SELECT namespace FROM cf_plugin_manifest WHERE plugin_name = ? LIMIT 1",
+                &[DbValue::from(request.plugin_name.as_str())],
+            ) {
+                Ok(row) => row.map(|row| row.get_by_name::<String>("namespace")),
+                Err(e) => {
+                    let _ = conn.execute("ROLLBACK", &[]);
+                    return Err(e.into());
+                }
+            };
+            if let Some(existing) = existing_namespace.transpose()? {
+                if existing != request.namespace {
+                    let _ = conn.execute("ROLLBACK", &[]);
+                    anyhow::bail!(
+                        "Plugin '{}' belongs to namespace '{}', cannot deploy it into '{}'",
+                        request.plugin_name,
+                        existing,
+                        request.namespace
+                    );
+                }
+            }
+
             let publisher_email = request
                 .publisher_email
                 .as_deref()
@@ -1266,16 +1738,17 @@ impl RoutingStore for SqliteRoutingStore {
             if let Err(e) = conn.execute(
                 r#"
                     INSERT INTO cf_plugin_manifest
-                    (plugin_name, version, runtime_kind, entrypoint, platform_os, platform_arch,
-                     source_code, source_hash, status,
+                    (plugin_name, namespace, version, runtime_kind, entrypoint, platform_os,
+                     platform_arch, source_code, source_hash, status,
                      env_hash, artifact_hash, manifest_json, protocol_version, schema_artifacts_json,
                      outputs_json, signature_verified, signer_id,
                      created_at, deployed_at,
                      publisher_name, publisher_email, azure_oid, system_requirements)
-                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                     "#,
                 &[
                     DbValue::from(request.plugin_name.as_str()),
+                    DbValue::from(request.namespace.as_str()),
                     DbValue::from(request.version.as_str()),
                     DbValue::from(request.runtime_kind.as_str()),
                     DbValue::from(request.entrypoint.as_str()),
@@ -1381,6 +1854,63 @@ Delete the database to republish.",
             Ok(())
         })
     }
+
+    fn rollback_plugin(
+        &self,
+        plugin_name: &str,
+        target_version: &str,
+        actor: Option<&str>,
+    ) -> Result<PluginRollback> {
+        self.with_conn(|conn| {
+            JobQueue::new(conn.clone()).rollback_plugin(plugin_name, target_version, actor)
+        })
+    }
+
+    fn list_subscriptions(&self) -> Result<Vec<TopicSubscription>> {
+        self.with_conn(|conn| JobQueue::new(conn.clone()).list_subscriptions())
+    }
+
+    fn subscribe_plugin(&self, plugin_name: &str, topic_name: &str) -> Result<TopicSubscription> {
+        self.with_conn(|conn| JobQueue::new(conn.clone()).subscribe_plugin(plugin_name, topic_name))
+    }
+
+    fn set_subscription_active(
+        &self,
+        subscription_id: i64,
+        active: bool,
+    ) -> Result<TopicSubscription> {
+        self.with_conn(|conn| {
+            JobQueue::new(conn.clone()).set_subscription_active(subscription_id, active)
+        })
+    }
+
+    fn list_schedules(&self) -> Result<Vec<Schedule>> {
+        self.with_conn(|conn| JobQueue::new(conn.clone()).list_schedules())
+    }
+
+    fn get_schedule(&self, schedule_id: i64) -> Result<Option<Schedule>> {
+        self.with_conn(|conn| JobQueue::new(conn.clone()).get_schedule(schedule_id))
+    }
+
+    fn create_schedule(
+        &self,
+        name: &str,
+        cron: &str,
+        target: &ScheduleTarget,
+        catch_up: ScheduleCatchUp,
+    ) -> Result<Schedule> {
+        self.with_conn(|conn| {
+            JobQueue::new(conn.clone()).create_schedule(name, cron, target, catch_up)
+        })
+    }
+
+    fn set_schedule_active(&self, schedule_id: i64, active: bool) -> Result<Schedule> {
+        self.with_conn(|conn| JobQueue::new(conn.clone()).set_schedule_active(schedule_id, active))
+    }
+
+    fn delete_schedule(&self, schedule_id: i64) -> Result<bool> {
+        self.with_conn(|conn| JobQueue::new(conn.clone()).delete_schedule(schedule_id))
+    }
 }
 
 // ============================================================================
@@ -1466,6 +1996,8 @@ pub trait ScoutStore: Send + Sync {
     fn get_tagging_rule(&self, id: &TaggingRuleId) -> Result<Option<TaggingRule>>;
     fn upsert_tagging_rule(&self, rule: &TaggingRule) -> Result<()>;
     fn delete_tagging_rule(&self, id: &TaggingRuleId) -> Result<bool>;
+    fn get_rule_match_mode(&self, workspace_id: &WorkspaceId) -> Result<RuleMatchMode>;
+    fn set_rule_match_mode(&self, workspace_id: &WorkspaceId, mode: RuleMatchMode) -> Result<()>;
 
     fn tag_file(&self, file_id: i64, tag: &str) -> Result<()>;
     fn tag_file_by_rule(&self, file_id: i64, tag: &str, rule_id: &TaggingRuleId)
@@ -2118,6 +2650,17 @@ impl ScoutStore for SqliteScoutStore {
         Ok(db.delete_tagging_rule(id)?)
     }
 
+    fn get_rule_match_mode(&self, workspace_id: &WorkspaceId) -> Result<RuleMatchMode> {
+        let db = self.open_db()?;
+        Ok(db.get_rule_match_mode(workspace_id)?)
+    }
+
+    fn set_rule_match_mode(&self, workspace_id: &WorkspaceId, mode: RuleMatchMode) -> Result<()> {
+        let db = self.open_db()?;
+        db.set_rule_match_mode(workspace_id, mode)?;
+        Ok(())
+    }
+
     fn tag_file(&self, file_id: i64, tag: &str) -> Result<()> {
         let db = self.open_db()?;
         db.tag_file(file_id, tag)?;
@@ -2451,9 +2994,10 @@ impl ScoutStore for SqliteScoutStore {
             enabled: true,
         };
         db.upsert_tagging_rule(&rule)?;
+        db.detect_content_types(&workspace_id)?;
 
         let rows = conn.query_all(
-            "SELECT id, path, rel_path, size FROM scout_files WHERE workspace_id = ? AND source_id = ? ORDER BY rel_path",
+            "SELECT id, path, rel_path, size, detected_type, detection_confidence FROM scout_files WHERE workspace_id = ? AND source_id = ? ORDER BY rel_path",
             &[
                 DbValue::Text(workspace_id.to_string()),
                 DbValue::Integer(source_id.as_i64()),
@@ -2466,11 +3010,17 @@ impl ScoutStore for SqliteScoutStore {
             let path: String = row.get(1)?;
             let rel_path: String = row.get(2)?;
             let size: i64 = row.get(3)?;
+            let detected_type: Option<String> = row.get(4)?;
+            let confidence: Option<String> = row.get(5)?;
             files.push(RuleApplyFile {
                 id,
                 path,
                 rel_path,
                 size,
+                detection: ContentDetection::from_columns(
+                    detected_type.as_deref(),
+                    confidence.as_deref(),
+                ),
             });
         }
 
@@ -2481,7 +3031,8 @@ impl ScoutStore for SqliteScoutStore {
             priority: 100,
         }];
 
-        let (matches, _summary) = match_rules_to_files(&files, &rules)?;
+        let (matches, _summary) =
+            match_rules_to_files(&files, &rules, RuleMatchMode::FirstMatch)?;
 
         let tagged_rows = conn.query_all(
             "SELECT file_id FROM scout_file_tags WHERE workspace_id = ? AND tag = ?",
@@ -2542,13 +3093,40 @@ pub struct JobArtifactRecord {
     pub uri: String,
     pub table_name: Option<String>,
     pub rows: Option<i64>,
+    /// Hex blake3 hash reported for single-file outputs
+    pub content_hash: Option<String>,
     pub created_at: i64,
 }
 
+/// One `cf_column_stats` row: stats for one column of one job output.
+#[derive(Debug, Clone)]
+pub struct ColumnStatsRecord {
+    pub job_id: i64,
+    pub output_name: String,
+    pub column_name: String,
+    pub row_count: i64,
+    pub null_count: i64,
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+    pub distinct_estimate: Option<i64>,
+    pub recorded_at: i64,
+}
+
 pub trait ArtifactStore: Send + Sync {
     fn init_schema(&self) -> Result<()>;
     fn insert_job_artifacts(&self, job_id: i64, artifacts: &[ArtifactV1]) -> Result<()>;
     fn list_job_artifacts(&self, job_id: i64) -> Result<Vec<JobArtifactRecord>>;
+    /// Replace the stored column stats of each reported output of a job.
+    fn insert_column_stats(&self, job_id: i64, stats: &[OutputColumnStats]) -> Result<()>;
+    /// Column stats of a job, grouped by output in column order.
+    fn list_column_stats(&self, job_id: i64) -> Result<Vec<ColumnStatsRecord>>;
+    /// Output artifacts (kind "output"), most recent first, optionally for one
+    /// output name.
+    fn list_output_artifacts(
+        &self,
+        output_name: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<JobArtifactRecord>>;
 }
 
 #[derive(Debug, Clone)]
@@ -2583,10 +3161,24 @@ impl ArtifactStore for SqliteArtifactStore {
                     uri TEXT NOT NULL,
                     table_name TEXT,
                     rows BIGINT,
+                    content_hash TEXT,
                     created_at BIGINT NOT NULL,
                     UNIQUE(job_id, kind, name, uri)
                 );
                 CREATE INDEX IF NOT EXISTS ix_job_artifacts_job ON cf_job_artifacts(job_id);
+                CREATE TABLE IF NOT EXISTS cf_column_stats (
+                    job_id BIGINT NOT NULL,
+                    output_name TEXT NOT NULL,
+                    column_name TEXT NOT NULL,
+                    column_index BIGINT NOT NULL,
+                    row_count BIGINT NOT NULL,
+                    null_count BIGINT NOT NULL,
+                    min_value TEXT,
+                    max_value TEXT,
+                    distinct_estimate BIGINT,
+                    recorded_at BIGINT NOT NULL,
+                    PRIMARY KEY (job_id, output_name, column_name)
+                );
                 "#,
             )?;
             Ok(())
@@ -2598,56 +3190,24 @@ impl ArtifactStore for SqliteArtifactStore {
             let now = now_millis();
             let sql = r#"
                 INSERT OR IGNORE INTO cf_job_artifacts
-                    (job_id, kind, name, uri, table_name, rows, created_at)
-                VALUES (?, ?, ?, ?, ?, ?, ?)
+                    (job_id, kind, name, uri, table_name, rows, content_hash, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
             "#;
 
             for artifact in artifacts {
-                let (kind, name, uri, table_name, rows) = match artifact {
-                    ArtifactV1::Output {
-                        output_name,
-                        sink_uri,
-                        table,
-                        rows,
-                        ..
-                    } => (
-                        "output",
-                        output_name.as_str(),
-                        sink_uri.as_str(),
-                        table.as_deref(),
-                        rows.map(|r| i64::try_from(r)),
-                    ),
-                    ArtifactV1::Quarantine {
-                        output_name,
-                        sink_uri,
-                        table,
-                        rows,
-                    } => (
-                        "quarantine",
-                        output_name.as_str(),
-                        sink_uri.as_str(),
-                        table.as_deref(),
-                        rows.map(|r| i64::try_from(r)),
-                    ),
-                    ArtifactV1::Log { name, uri } => ("log", name.as_str(), uri.as_str(), None, None),
-                    ArtifactV1::Other { name, uri } => {
-                        let Some(uri) = uri.as_ref() else {
-                            continue;
-                        };
-                        ("other", name.as_str(), uri.as_str(), None, None)
-                    }
+                let Some(columns) = ArtifactColumns::from_artifact(artifact)? else {
+                    continue;
                 };
-
-                let rows = rows.transpose().context("artifact row count overflow")?;
                 conn.execute(
                     sql,
                     &[
                         DbValue::from(job_id),
-                        DbValue::from(kind),
-                        DbValue::from(name),
-                        DbValue::from(uri),
-                        DbValue::from(table_name),
-                        DbValue::from(rows),
+                        DbValue::from(columns.kind),
+                        DbValue::from(columns.name),
+                        DbValue::from(columns.uri),
+                        DbValue::from(columns.table_name),
+                        DbValue::from(columns.rows),
+                        DbValue::from(columns.content_hash),
                         DbValue::from(now),
                     ],
                 )?;
@@ -2661,7 +3221,7 @@ impl ArtifactStore for SqliteArtifactStore {
         self.with_conn(|conn| {
             let rows = conn.query_all(
                 r#"
-                SELECT job_id, kind, name, uri, table_name, rows, created_at
+                SELECT job_id, kind, name, uri, table_name, rows, content_hash, created_at
                 FROM cf_job_artifacts
                 WHERE job_id = ?
                 ORDER BY created_at ASC
@@ -2669,23 +3229,210 @@ impl ArtifactStore for SqliteArtifactStore {
                 &[DbValue::from(job_id)],
             )?;
 
-            let mut records = Vec::with_capacity(rows.len());
-            for row in rows {
-                records.push(JobArtifactRecord {
-                    job_id: row.get_by_name("job_id")?,
-                    kind: row.get_by_name("kind")?,
-                    name: row.get_by_name("name")?,
-                    uri: row.get_by_name("uri")?,
-                    table_name: row.get_by_name("table_name")?,
-                    rows: row.get_by_name("rows")?,
-                    created_at: row.get_by_name("created_at")?,
-                });
+            rows.iter().map(JobArtifactRecord::from_row).collect()
+        })
+    }
+
+    fn insert_column_stats(&self, job_id: i64, stats: &[OutputColumnStats]) -> Result<()> {
+        self.with_conn(|conn| {
+            let now = now_millis();
+            for output in stats {
+                conn.execute(
+                    "DELETE FROM cf_column_stats WHERE job_id = ? AND output_name = ?",
+                    &[
+                        DbValue::from(job_id),
+                        DbValue::from(output.output_name.as_str()),
+                    ],
+                )?;
+                for (index, column) in output.columns.iter().enumerate() {
+                    conn.execute(
+                        r#"
+                        INSERT INTO cf_column_stats
+                            (job_id, output_name, column_name, column_index, row_count,
+                             null_count, min_value, max_value, distinct_estimate, recorded_at)
+                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                        "#,
+                        &[
+                            DbValue::from(job_id),
+                            DbValue::from(output.output_name.as_str()),
+                            DbValue::from(column.column_name.as_str()),
+                            DbValue::from(index as i64),
+                            DbValue::from(saturating_i64(column.row_count)),
+                            DbValue::from(saturating_i64(column.null_count)),
+                            DbValue::from(column.min.as_deref()),
+                            DbValue::from(column.max.as_deref()),
+                            DbValue::from(column.distinct_estimate.map(saturating_i64)),
+                            DbValue::from(now),
+                        ],
+                    )?;
+                }
             }
-            Ok(records)
+            Ok(())
+        })
+    }
+
+    fn list_column_stats(&self, job_id: i64) -> Result<Vec<ColumnStatsRecord>> {
+        self.with_conn(|conn| {
+            let rows = conn.query_all(
+                r#"
+                SELECT job_id, output_name, column_name, row_count, null_count,
+                       min_value, max_value, distinct_estimate, recorded_at
+                FROM cf_column_stats
+                WHERE job_id = ?
+                ORDER BY output_name ASC, column_index ASC
+                "#,
+                &[DbValue::from(job_id)],
+            )?;
+            rows.iter().map(ColumnStatsRecord::from_row).collect()
+        })
+    }
+
+    fn list_output_artifacts(
+        &self,
+        output_name: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<JobArtifactRecord>> {
+        self.with_conn(|conn| {
+            let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+            let rows = conn.query_all(
+                r#"
+                SELECT job_id, kind, name, uri, table_name, rows, content_hash, created_at
+                FROM cf_job_artifacts
+                WHERE kind = 'output' AND (? IS NULL OR name = ?)
+                ORDER BY created_at DESC, job_id DESC
+                LIMIT ?
+                "#,
+                &[
+                    DbValue::from(output_name),
+                    DbValue::from(output_name),
+                    DbValue::from(limit),
+                ],
+            )?;
+            rows.iter().map(JobArtifactRecord::from_row).collect()
         })
     }
 }
 
+impl JobArtifactRecord {
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        Ok(Self {
+            job_id: row.get_by_name("job_id")?,
+            kind: row.get_by_name("kind")?,
+            name: row.get_by_name("name")?,
+            uri: row.get_by_name("uri")?,
+            table_name: row.get_by_name("table_name")?,
+            rows: row.get_by_name("rows")?,
+            content_hash: row.get_by_name("content_hash")?,
+            created_at: row.get_by_name("created_at")?,
+        })
+    }
+}
+
+impl ColumnStatsRecord {
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        Ok(Self {
+            job_id: row.get_by_name("job_id")?,
+            output_name: row.get_by_name("output_name")?,
+            column_name: row.get_by_name("column_name")?,
+            row_count: row.get_by_name("row_count")?,
+            null_count: row.get_by_name("null_count")?,
+            min_value: row.get_by_name("min_value")?,
+            max_value: row.get_by_name("max_value")?,
+            distinct_estimate: row.get_by_name("distinct_estimate")?,
+            recorded_at: row.get_by_name("recorded_at")?,
+        })
+    }
+}
+
+fn saturating_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+// ============================================================================
+// Lineage Store
+// ============================================================================
+
+pub trait LineageStore: Send + Sync {
+    fn init_schema(&self) -> Result<()>;
+    fn record_job_lineage(
+        &self,
+        job_id: i64,
+        source_hash: Option<&str>,
+        decompressed_hash: Option<&str>,
+        source_encoding: Option<TextEncoding>,
+        lineage: Option<&LineageChain>,
+        artifacts: &[ArtifactV1],
+    ) -> Result<()>;
+    fn lineage_for_artifact(&self, uri: &str) -> Result<Vec<LineageRecord>>;
+    fn lineage_for_input(&self, input_path: &str) -> Result<Option<LineageChain>>;
+    fn lineage_for_table(&self, table: &str) -> Result<Vec<LineageRecord>>;
+    fn downstream_of(&self, source_hash: &str) -> Result<Vec<LineageRecord>>;
+}
+
+#[derive(Debug, Clone)]
+struct SqliteLineageStore {
+    path: PathBuf,
+    busy_timeout_ms: u64,
+}
+
+impl SqliteLineageStore {
+    fn new(path: PathBuf, busy_timeout_ms: u64) -> Self {
+        Self {
+            path,
+            busy_timeout_ms,
+        }
+    }
+
+    fn with_storage<T>(&self, op: impl FnOnce(&LineageStorage) -> Result<T>) -> Result<T> {
+        let conn = DbConnection::open_sqlite_with_busy_timeout(&self.path, self.busy_timeout_ms)?;
+        let storage = LineageStorage::new(conn);
+        op(&storage)
+    }
+}
+
+impl LineageStore for SqliteLineageStore {
+    fn init_schema(&self) -> Result<()> {
+        self.with_storage(|storage| storage.init_schema())
+    }
+
+    fn record_job_lineage(
+        &self,
+        job_id: i64,
+        source_hash: Option<&str>,
+        decompressed_hash: Option<&str>,
+        source_encoding: Option<TextEncoding>,
+        lineage: Option<&LineageChain>,
+        artifacts: &[ArtifactV1],
+    ) -> Result<()> {
+        self.with_storage(|storage| {
+            storage.record_job_lineage(
+                job_id,
+                source_hash,
+                decompressed_hash,
+                source_encoding,
+                lineage,
+                artifacts,
+            )
+        })
+    }
+
+    fn lineage_for_artifact(&self, uri: &str) -> Result<Vec<LineageRecord>> {
+        self.with_storage(|storage| storage.lineage_for_artifact(uri))
+    }
+
+    fn lineage_for_input(&self, input_path: &str) -> Result<Option<LineageChain>> {
+        self.with_storage(|storage| storage.lineage_for_input(input_path))
+    }
+
+    fn lineage_for_table(&self, table: &str) -> Result<Vec<LineageRecord>> {
+        self.with_storage(|storage| storage.lineage_for_table(table))
+    }
+
+    fn downstream_of(&self, source_hash: &str) -> Result<Vec<LineageRecord>> {
+        self.with_storage(|storage| storage.downstream_of(source_hash))
+    }
+}
+
 // ============================================================================
 // SQLite State Store
 // ============================================================================
@@ -2699,6 +3446,7 @@ struct SqliteStateStore {
     routing: SqliteRoutingStore,
     scout: SqliteScoutStore,
     artifacts: SqliteArtifactStore,
+    lineage: SqliteLineageStore,
 }
 
 impl SqliteStateStore {
@@ -2712,7 +3460,8 @@ impl SqliteStateStore {
             sessions: SqliteSessionStore::new(path.clone(), fast_timeout_ms),
             routing: SqliteRoutingStore::new(path.clone(), fast_timeout_ms),
             scout: SqliteScoutStore::new(path.clone(), bulk_timeout_ms),
-            artifacts: SqliteArtifactStore::new(path, fast_timeout_ms),
+            artifacts: SqliteArtifactStore::new(path.clone(), fast_timeout_ms),
+            lineage: SqliteLineageStore::new(path, fast_timeout_ms),
         }
     }
 }
@@ -2726,6 +3475,7 @@ impl StateStoreBackend for SqliteStateStore {
         self.sessions.init_schema()?;
         self.scout.init_schema()?;
         self.artifacts.init_schema()?;
+        self.lineage.init_schema()?;
         Ok(())
     }
 
@@ -2753,6 +3503,10 @@ impl StateStoreBackend for SqliteStateStore {
         &self.artifacts
     }
 
+    fn lineage(&self) -> &dyn LineageStore {
+        &self.lineage
+    }
+
     fn session_fast(&self) -> Result<StateStoreQueueSession> {
         let conn = self.queue.open_conn()?;
         Ok(StateStoreQueueSession {
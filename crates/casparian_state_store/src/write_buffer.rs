@@ -0,0 +1,590 @@
+//! Write-behind buffer for high-frequency control-plane writes.
+//!
+//! Worker heartbeats, live log chunks and metric samples arrive many times a
+//! second across a fleet. Writing each one on arrival takes the state store's
+//! single writer lock once per message. [`WriteBuffer`] collects them instead
+//! and hands them out as a [`FlushBatch`] once [`WriteBufferConfig::flush_interval`]
+//! has passed or [`WriteBufferConfig::max_pending`] writes are waiting; the
+//! batch is applied in one transaction. Heartbeats of the same worker are
+//! coalesced: only the newest one is written.
+//!
+//! With a journal directory every write is appended to a JSON-lines segment
+//! before it is buffered. Taking a batch seals the current segment, and the
+//! segment is deleted once the batch is committed, so writes that were
+//! buffered when the process died are replayed by the next
+//! [`WriteBuffer::open`]. Every write is idempotent (log chunks are keyed by
+//! offset, heartbeats are upserts, samples are keyed by name and time), so a
+//! segment replayed after its batch committed changes nothing.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use casparian_db::{BackendError, DbConnection, DbValue, UnifiedDbRow};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Default time between flushes.
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+/// Default number of buffered writes that forces a flush.
+pub const DEFAULT_MAX_PENDING: usize = 1024;
+/// Metric samples older than this are pruned on every flush (24 hours).
+pub const METRIC_SAMPLE_RETENTION_MS: i64 = 24 * 60 * 60 * 1000;
+
+const SEGMENT_PREFIX: &str = "segment-";
+const SEGMENT_SUFFIX: &str = ".jsonl";
+
+/// When a [`WriteBuffer`] flushes and where it journals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteBufferConfig {
+    pub flush_interval: Duration,
+    pub max_pending: usize,
+    /// Directory of journal segments; None buffers in memory only.
+    pub journal_dir: Option<PathBuf>,
+}
+
+impl Default for WriteBufferConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+            max_pending: DEFAULT_MAX_PENDING,
+            journal_dir: None,
+        }
+    }
+}
+
+/// Latest heartbeat of a worker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkerHeartbeat {
+    pub worker_id: String,
+    pub status: String,
+    pub active_jobs: i64,
+    #[serde(default)]
+    pub cpu_percent: Option<f64>,
+    #[serde(default)]
+    pub memory_used_bytes: Option<i64>,
+    pub seen_at: i64,
+}
+
+impl WorkerHeartbeat {
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        Ok(Self {
+            worker_id: row.get_by_name("worker_id")?,
+            status: row.get_by_name("status")?,
+            active_jobs: row.get_by_name("active_jobs")?,
+            cpu_percent: row.get_by_name("cpu_percent")?,
+            memory_used_bytes: row.get_by_name("memory_used_bytes")?,
+            seen_at: row.get_by_name("seen_at")?,
+        })
+    }
+}
+
+/// One point of a metric time series, e.g. a worker's queue depth.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub name: String,
+    /// Series within the metric, e.g. the worker id ("" = none)
+    #[serde(default)]
+    pub series: String,
+    pub value: f64,
+    pub recorded_at: i64,
+}
+
+impl MetricSample {
+    fn from_row(row: &UnifiedDbRow) -> Result<Self> {
+        Ok(Self {
+            name: row.get_by_name("name")?,
+            series: row.get_by_name("series")?,
+            value: row.get_by_name("value")?,
+            recorded_at: row.get_by_name("recorded_at")?,
+        })
+    }
+}
+
+/// A write the buffer defers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BufferedWrite {
+    Heartbeat(WorkerHeartbeat),
+    /// Chunk of a running job's log (see `JobQueue::append_job_log`)
+    LogChunk {
+        job_id: i64,
+        offset: i64,
+        chunk: String,
+        created_at: i64,
+    },
+    MetricSample(MetricSample),
+}
+
+/// Writes taken from a [`WriteBuffer`], to apply on the writer connection.
+#[derive(Debug, Default)]
+pub struct FlushBatch {
+    pub writes: Vec<BufferedWrite>,
+    /// Sealed journal segments holding `writes`
+    segments: Vec<PathBuf>,
+}
+
+impl FlushBatch {
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty() && self.segments.is_empty()
+    }
+
+    /// Write the batch in one transaction, then drop its journal segments.
+    pub fn apply(self, conn: &DbConnection) -> Result<()> {
+        apply_writes(conn, &self.writes)?;
+        for segment in &self.segments {
+            if let Err(err) = fs::remove_file(segment) {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    warn!(
+                        "Failed to remove flushed journal segment {}: {}",
+                        segment.display(),
+                        err
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Buffers writes between flushes. Owned by one thread (the Sentinel event
+/// loop); batches are applied wherever the writer connection lives.
+pub struct WriteBuffer {
+    config: WriteBufferConfig,
+    pending: Vec<BufferedWrite>,
+    /// Index in `pending` of each worker's heartbeat
+    heartbeats: HashMap<String, usize>,
+    journal: Option<Journal>,
+    last_flush: Instant,
+}
+
+struct Journal {
+    dir: PathBuf,
+    next_segment: u64,
+    /// Open segment and its path; created on the first write after a seal
+    current: Option<(PathBuf, BufWriter<File>)>,
+    /// Segments recovered at open, handed to the first batch
+    sealed: Vec<PathBuf>,
+}
+
+impl WriteBuffer {
+    /// Open the buffer, replaying writes left in the journal by a previous
+    /// process. Recovered writes are part of the first batch.
+    pub fn open(config: WriteBufferConfig) -> Result<Self> {
+        let mut buffer = Self {
+            config,
+            pending: Vec::new(),
+            heartbeats: HashMap::new(),
+            journal: None,
+            last_flush: Instant::now(),
+        };
+        if let Some(dir) = buffer.config.journal_dir.clone() {
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create write journal {}", dir.display()))?;
+            let segments = journal_segments(&dir)?;
+            let next_segment = segments.last().map(|(seq, _)| seq + 1).unwrap_or(0);
+            let mut sealed = Vec::with_capacity(segments.len());
+            for (_, path) in segments {
+                for write in read_segment(&path)? {
+                    buffer.buffer(write);
+                }
+                sealed.push(path);
+            }
+            buffer.journal = Some(Journal {
+                dir,
+                next_segment,
+                current: None,
+                sealed,
+            });
+        }
+        Ok(buffer)
+    }
+
+    /// Buffer `write`, journaling it first. Returns true when a flush is due.
+    pub fn push(&mut self, write: BufferedWrite) -> Result<bool> {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.append(&write)?;
+        }
+        self.buffer(write);
+        Ok(self.flush_due())
+    }
+
+    /// Writes waiting for the next flush.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// True once the flush interval has passed with writes waiting, or the
+    /// buffer holds `max_pending` writes.
+    pub fn flush_due(&self) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+        self.pending.len() >= self.config.max_pending
+            || self.last_flush.elapsed() >= self.config.flush_interval
+    }
+
+    /// Take every buffered write, sealing the journal segment that holds
+    /// them. The segment is deleted when the batch is applied.
+    pub fn take_batch(&mut self) -> Result<FlushBatch> {
+        self.last_flush = Instant::now();
+        self.heartbeats.clear();
+        let writes = std::mem::take(&mut self.pending);
+        let segments = match self.journal.as_mut() {
+            Some(journal) => journal.seal()?,
+            None => Vec::new(),
+        };
+        Ok(FlushBatch { writes, segments })
+    }
+
+    fn buffer(&mut self, write: BufferedWrite) {
+        if let BufferedWrite::Heartbeat(heartbeat) = &write {
+            if let Some(&index) = self.heartbeats.get(&heartbeat.worker_id) {
+                self.pending[index] = write;
+                return;
+            }
+            self.heartbeats
+                .insert(heartbeat.worker_id.clone(), self.pending.len());
+        }
+        self.pending.push(write);
+    }
+}
+
+impl Journal {
+    fn append(&mut self, write: &BufferedWrite) -> Result<()> {
+        if self.current.is_none() {
+            let path = self.dir.join(format!(
+                "{}{:020}{}",
+                SEGMENT_PREFIX, self.next_segment, SEGMENT_SUFFIX
+            ));
+            self.next_segment += 1;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("Failed to open journal segment {}", path.display()))?;
+            self.current = Some((path, BufWriter::new(file)));
+        }
+        let Some((path, writer)) = self.current.as_mut() else {
+            unreachable!("journal segment opened above");
+        };
+        let mut line = serde_json::to_vec(write).context("Failed to serialize buffered write")?;
+        line.push(b'\n');
+        writer
+            .write_all(&line)
+            .and_then(|()| writer.flush())
+            .with_context(|| format!("Failed to append to journal segment {}", path.display()))?;
+        Ok(())
+    }
+
+    fn seal(&mut self) -> Result<Vec<PathBuf>> {
+        let mut sealed = std::mem::take(&mut self.sealed);
+        if let Some((path, mut writer)) = self.current.take() {
+            writer
+                .flush()
+                .with_context(|| format!("Failed to flush journal segment {}", path.display()))?;
+            sealed.push(path);
+        }
+        Ok(sealed)
+    }
+}
+
+/// Journal segments in `dir`, oldest first.
+fn journal_segments(dir: &Path) -> Result<Vec<(u64, PathBuf)>> {
+    let mut segments = Vec::new();
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("Failed to read journal {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        let Some(seq) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(SEGMENT_PREFIX))
+            .and_then(|name| name.strip_suffix(SEGMENT_SUFFIX))
+            .and_then(|seq| seq.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        segments.push((seq, path));
+    }
+    segments.sort();
+    Ok(segments)
+}
+
+/// Writes of one segment. A line cut short by a crash ends the segment.
+fn read_segment(path: &Path) -> Result<Vec<BufferedWrite>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open journal segment {}", path.display()))?;
+    let mut writes = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        match serde_json::from_str(&line) {
+            Ok(write) => writes.push(write),
+            Err(err) => {
+                warn!(
+                    "Ignoring torn write at the end of journal segment {}: {}",
+                    path.display(),
+                    err
+                );
+                break;
+            }
+        }
+    }
+    Ok(writes)
+}
+
+/// Create the heartbeat and metric sample tables (idempotent).
+pub fn init_schema(conn: &DbConnection) -> Result<()> {
+    let int_type = if conn.backend_name() == "SQLite" {
+        "INTEGER"
+    } else {
+        "BIGINT"
+    };
+    let create_sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS cf_worker_heartbeats (
+            worker_id TEXT PRIMARY KEY,
+            status TEXT NOT NULL,
+            active_jobs {int_type} NOT NULL,
+            cpu_percent DOUBLE,
+            memory_used_bytes {int_type},
+            seen_at {int_type} NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS cf_metric_samples (
+            name TEXT NOT NULL,
+            series TEXT NOT NULL,
+            value DOUBLE NOT NULL,
+            recorded_at {int_type} NOT NULL,
+            PRIMARY KEY (name, series, recorded_at)
+        );
+        "#,
+        int_type = int_type,
+    );
+    conn.execute_batch(&create_sql)
+        .context("Failed to initialize write buffer schema")?;
+    Ok(())
+}
+
+/// Apply `writes` in one transaction and prune expired metric samples.
+pub fn apply_writes(conn: &DbConnection, writes: &[BufferedWrite]) -> Result<()> {
+    if writes.is_empty() {
+        return Ok(());
+    }
+    let prune_before = chrono::Utc::now().timestamp_millis() - METRIC_SAMPLE_RETENTION_MS;
+    let has_samples = writes
+        .iter()
+        .any(|write| matches!(write, BufferedWrite::MetricSample(_)));
+    conn.transaction(|tx| {
+        for write in writes {
+            match write {
+                BufferedWrite::Heartbeat(heartbeat) => {
+                    tx.execute(
+                        r#"
+                        INSERT INTO cf_worker_heartbeats
+                            (worker_id, status, active_jobs, cpu_percent, memory_used_bytes, seen_at)
+                        VALUES (?, ?, ?, ?, ?, ?)
+                        ON CONFLICT (worker_id) DO UPDATE SET
+                            status = excluded.status,
+                            active_jobs = excluded.active_jobs,
+                            cpu_percent = excluded.cpu_percent,
+                            memory_used_bytes = excluded.memory_used_bytes,
+                            seen_at = excluded.seen_at
+                        "#,
+                        &[
+                            DbValue::from(heartbeat.worker_id.as_str()),
+                            DbValue::from(heartbeat.status.as_str()),
+                            DbValue::from(heartbeat.active_jobs),
+                            DbValue::from(heartbeat.cpu_percent),
+                            DbValue::from(heartbeat.memory_used_bytes),
+                            DbValue::from(heartbeat.seen_at),
+                        ],
+                    )?;
+                }
+                BufferedWrite::LogChunk {
+                    job_id,
+                    offset,
+                    chunk,
+                    created_at,
+                } => {
+                    tx.execute(
+                        r#"
+                        INSERT INTO cf_job_logs (job_id, byte_offset, chunk, created_at)
+                        VALUES (?, ?, ?, ?)
+                        ON CONFLICT (job_id, byte_offset) DO NOTHING
+                        "#,
+                        &[
+                            DbValue::from(*job_id),
+                            DbValue::from(*offset),
+                            DbValue::from(chunk.as_str()),
+                            DbValue::from(*created_at),
+                        ],
+                    )?;
+                }
+                BufferedWrite::MetricSample(sample) => {
+                    tx.execute(
+                        r#"
+                        INSERT INTO cf_metric_samples (name, series, value, recorded_at)
+                        VALUES (?, ?, ?, ?)
+                        ON CONFLICT (name, series, recorded_at) DO NOTHING
+                        "#,
+                        &[
+                            DbValue::from(sample.name.as_str()),
+                            DbValue::from(sample.series.as_str()),
+                            DbValue::from(sample.value),
+                            DbValue::from(sample.recorded_at),
+                        ],
+                    )?;
+                }
+            }
+        }
+        if has_samples {
+            tx.execute(
+                "DELETE FROM cf_metric_samples WHERE recorded_at < ?",
+                &[DbValue::from(prune_before)],
+            )?;
+        }
+        Ok::<(), BackendError>(())
+    })
+    .context("Failed to apply buffered writes")?;
+    Ok(())
+}
+
+/// Latest heartbeat of every worker, most recently seen first.
+pub fn worker_heartbeats(conn: &DbConnection) -> Result<Vec<WorkerHeartbeat>> {
+    let rows = conn.query_all(
+        r#"
+        SELECT worker_id, status, active_jobs, cpu_percent, memory_used_bytes, seen_at
+        FROM cf_worker_heartbeats
+        ORDER BY seen_at DESC, worker_id ASC
+        "#,
+        &[],
+    )?;
+    rows.iter().map(WorkerHeartbeat::from_row).collect()
+}
+
+/// Samples of metric `name` recorded at or after `since`, oldest first.
+pub fn metric_samples(conn: &DbConnection, name: &str, since: i64) -> Result<Vec<MetricSample>> {
+    let rows = conn.query_all(
+        r#"
+        SELECT name, series, value, recorded_at
+        FROM cf_metric_samples
+        WHERE name = ? AND recorded_at >= ?
+        ORDER BY recorded_at ASC, series ASC
+        "#,
+        &[DbValue::from(name), DbValue::from(since)],
+    )?;
+    rows.iter().map(MetricSample::from_row).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::JobQueue;
+
+    fn heartbeat(worker_id: &str, active_jobs: i64, seen_at: i64) -> BufferedWrite {
+        BufferedWrite::Heartbeat(WorkerHeartbeat {
+            worker_id: worker_id.to_string(),
+            status: "busy".to_string(),
+            active_jobs,
+            cpu_percent: Some(12.5),
+            memory_used_bytes: None,
+            seen_at,
+        })
+    }
+
+    fn queue_conn() -> DbConnection {
+        let conn = DbConnection::open_duckdb_memory().unwrap();
+        let queue = JobQueue::new(conn.clone());
+        queue.init_queue_schema().unwrap();
+        queue.init_error_handling_schema().unwrap();
+        conn
+    }
+
+    fn log_chunk(offset: i64, chunk: &str) -> BufferedWrite {
+        BufferedWrite::LogChunk {
+            job_id: 7,
+            offset,
+            chunk: chunk.to_string(),
+            created_at: 1,
+        }
+    }
+
+    #[test]
+    fn test_buffer_coalesces_heartbeats_and_flushes_at_threshold() {
+        let conn = queue_conn();
+        let mut buffer = WriteBuffer::open(WriteBufferConfig {
+            flush_interval: Duration::from_secs(3600),
+            max_pending: 4,
+            journal_dir: None,
+        })
+        .unwrap();
+
+        assert!(!buffer.push(heartbeat("w1", 1, 10)).unwrap());
+        assert!(!buffer.push(heartbeat("w1", 2, 20)).unwrap());
+        assert!(!buffer.push(log_chunk(0, "line 1\n")).unwrap());
+        assert!(!buffer
+            .push(BufferedWrite::MetricSample(MetricSample {
+                name: "worker.queue_depth".to_string(),
+                series: "w1".to_string(),
+                value: 2.0,
+                recorded_at: chrono::Utc::now().timestamp_millis(),
+            }))
+            .unwrap());
+        assert_eq!(buffer.pending(), 3);
+        assert!(buffer.push(log_chunk(7, "line 2\n")).unwrap());
+
+        buffer.take_batch().unwrap().apply(&conn).unwrap();
+        assert_eq!(buffer.pending(), 0);
+        assert!(!buffer.flush_due());
+
+        let heartbeats = worker_heartbeats(&conn).unwrap();
+        assert_eq!(heartbeats.len(), 1);
+        assert_eq!(heartbeats[0].active_jobs, 2);
+        assert_eq!(heartbeats[0].seen_at, 20);
+        let logs = JobQueue::new(conn.clone()).read_job_log(7, 0, 10).unwrap();
+        assert_eq!(logs.len(), 2);
+        let samples = metric_samples(&conn, "worker.queue_depth", 0).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].series, "w1");
+    }
+
+    #[test]
+    fn test_journal_replays_unflushed_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = WriteBufferConfig {
+            flush_interval: Duration::from_secs(3600),
+            max_pending: 100,
+            journal_dir: Some(dir.path().join("journal")),
+        };
+        let conn = queue_conn();
+
+        let mut buffer = WriteBuffer::open(config.clone()).unwrap();
+        buffer.push(log_chunk(0, "flushed\n")).unwrap();
+        buffer.take_batch().unwrap().apply(&conn).unwrap();
+        buffer.push(log_chunk(8, "lost\n")).unwrap();
+        buffer.push(heartbeat("w1", 1, 10)).unwrap();
+        drop(buffer);
+
+        // A torn final line is dropped, everything before it is replayed
+        let segments = journal_segments(config.journal_dir.as_ref().unwrap()).unwrap();
+        assert_eq!(segments.len(), 1);
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&segments[0].1)
+            .unwrap();
+        file.write_all(b"{\"kind\":\"log_chu").unwrap();
+
+        let mut recovered = WriteBuffer::open(config.clone()).unwrap();
+        assert_eq!(recovered.pending(), 2);
+        recovered.take_batch().unwrap().apply(&conn).unwrap();
+        let logs = JobQueue::new(conn.clone()).read_job_log(7, 0, 10).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(worker_heartbeats(&conn).unwrap().len(), 1);
+        assert!(journal_segments(config.journal_dir.as_ref().unwrap())
+            .unwrap()
+            .is_empty());
+    }
+}
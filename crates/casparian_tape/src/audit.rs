@@ -0,0 +1,367 @@
+//! Append-only audit tape for control-plane mutations.
+//!
+//! Unlike session tapes, the audit tape is shared: the Sentinel, the CLI and
+//! the desktop app all append to the same file. Each append takes an
+//! exclusive file lock, continues `seq` from the last envelope on disk, and
+//! writes one `EventName::Audit` envelope, so the file stays a valid tape
+//! that `TapeReader` can validate.
+//!
+//! Records carry the actor and JSON snapshots of the entity before and after
+//! the change (`None` for creates and deletes respectively).
+
+use crate::{EnvelopeV1, EventName, TapeError, TapeFilter, TapeReader, SCHEMA_VERSION};
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Bytes read per step when scanning backwards for the last envelope.
+const TAIL_CHUNK_BYTES: u64 = 8 * 1024;
+
+/// One audited mutation (the payload of an `EventName::Audit` envelope).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecordV1 {
+    /// What was changed, e.g. "routing_rule", "plugin", "approval", "config"
+    pub entity_kind: String,
+    pub entity_id: String,
+    /// What happened, e.g. "created", "published", "approved"
+    pub action: String,
+    /// Who did it (user name, publisher, or the process acting on their behalf)
+    pub actor: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl AuditRecordV1 {
+    pub fn new(
+        entity_kind: impl Into<String>,
+        entity_id: impl Into<String>,
+        action: impl Into<String>,
+        actor: impl Into<String>,
+    ) -> Self {
+        Self {
+            entity_kind: entity_kind.into(),
+            entity_id: entity_id.into(),
+            action: action.into(),
+            actor: actor.into(),
+            before: None,
+            after: None,
+        }
+    }
+
+    pub fn with_before(mut self, before: serde_json::Value) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    pub fn with_after(mut self, after: serde_json::Value) -> Self {
+        self.after = Some(after);
+        self
+    }
+}
+
+/// An audit record as read back from the tape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub event_id: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub record: AuditRecordV1,
+}
+
+/// Selects audit entries. Criteria are combined with AND; an empty query
+/// matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    entity_kind: Option<String>,
+    entity_id: Option<String>,
+    actor: Option<String>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    limit: Option<usize>,
+}
+
+impl AuditQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entity_kind(mut self, entity_kind: impl Into<String>) -> Self {
+        self.entity_kind = Some(entity_kind.into());
+        self
+    }
+
+    pub fn entity_id(mut self, entity_id: impl Into<String>) -> Self {
+        self.entity_id = Some(entity_id.into());
+        self
+    }
+
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Match entries at or after `since`.
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Match entries strictly before `until`.
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Keep only the most recent `limit` matches.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn matches(&self, record: &AuditRecordV1) -> bool {
+        let field =
+            |want: &Option<String>, have: &str| want.is_none() || want.as_deref() == Some(have);
+        field(&self.entity_kind, &record.entity_kind)
+            && field(&self.entity_id, &record.entity_id)
+            && field(&self.actor, &record.actor)
+    }
+
+    fn tape_filter(&self) -> TapeFilter {
+        let mut filter = TapeFilter::new();
+        if let Some(since) = self.since {
+            filter = filter.since(since);
+        }
+        if let Some(until) = self.until {
+            filter = filter.until(until);
+        }
+        filter
+    }
+}
+
+/// Handle to the shared audit tape file.
+///
+/// Holds no open file: every append opens, locks, writes and closes, so any
+/// number of processes can hold an `AuditTape` for the same path.
+#[derive(Debug, Clone)]
+pub struct AuditTape {
+    path: PathBuf,
+}
+
+impl AuditTape {
+    /// The file is created on the first append.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record. Returns the envelope's sequence number.
+    pub fn append(&self, record: &AuditRecordV1) -> Result<u64, TapeError> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&self.path)?;
+        // Fully qualified: std's inherent File locks need Rust 1.89.
+        FileExt::lock_exclusive(&file)?;
+        let result = append_locked(&mut file, record);
+        let _ = FileExt::unlock(&file);
+        result
+    }
+
+    /// Read matching records in tape order. A missing file has no records.
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, TapeError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = File::open(&self.path)?;
+        FileExt::lock_shared(&file)?;
+        let reader =
+            TapeReader::new(std::io::BufReader::new(&file)).with_filter(query.tape_filter());
+
+        let mut entries = Vec::new();
+        for envelope in reader {
+            let envelope = envelope?;
+            if !matches!(envelope.event_name, EventName::Audit(_)) {
+                continue;
+            }
+            let record: AuditRecordV1 = serde_json::from_value(envelope.payload)?;
+            if query.matches(&record) {
+                entries.push(AuditEntry {
+                    seq: envelope.seq,
+                    event_id: envelope.event_id,
+                    timestamp: envelope.timestamp,
+                    record,
+                });
+            }
+        }
+        let _ = FileExt::unlock(&file);
+
+        if let Some(limit) = query.limit {
+            let skip = entries.len().saturating_sub(limit);
+            entries.drain(..skip);
+        }
+        Ok(entries)
+    }
+}
+
+fn append_locked(file: &mut File, record: &AuditRecordV1) -> Result<u64, TapeError> {
+    let seq = last_seq(file)?.map_or(0, |seq| seq + 1);
+    let envelope = EnvelopeV1 {
+        schema_version: SCHEMA_VERSION,
+        event_id: Uuid::new_v4().to_string(),
+        seq,
+        timestamp: Utc::now(),
+        correlation_id: None,
+        parent_id: None,
+        event_name: EventName::Audit(record.action.clone()),
+        payload: serde_json::to_value(record)?,
+    };
+    let mut line = serde_json::to_string(&envelope)?;
+    line.push('\n');
+    file.write_all(line.as_bytes())?;
+    file.sync_data()?;
+    Ok(seq)
+}
+
+/// Sequence number of the last envelope in the file, reading backwards from
+/// the end until a complete line is found.
+fn last_seq(file: &mut File) -> Result<Option<u64>, TapeError> {
+    let len = file.metadata()?.len();
+    let mut start = len;
+    let mut tail = Vec::new();
+    loop {
+        let read_from = start.saturating_sub(TAIL_CHUNK_BYTES);
+        let mut chunk = vec![0u8; (start - read_from) as usize];
+        file.seek(SeekFrom::Start(read_from))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&tail);
+        tail = chunk;
+        start = read_from;
+
+        let text = String::from_utf8_lossy(&tail);
+        let trimmed = text.trim_end();
+        match trimmed.rfind('\n') {
+            Some(newline) => return parse_seq(&trimmed[newline + 1..]),
+            None if start == 0 => {
+                return if trimmed.is_empty() {
+                    Ok(None)
+                } else {
+                    parse_seq(trimmed)
+                };
+            }
+            None => continue,
+        }
+    }
+}
+
+fn parse_seq(line: &str) -> Result<Option<u64>, TapeError> {
+    let envelope: EnvelopeV1 = serde_json::from_str(line)?;
+    Ok(Some(envelope.seq))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn rule_record(id: &str, action: &str, actor: &str) -> AuditRecordV1 {
+        AuditRecordV1::new("routing_rule", id, action, actor)
+            .with_after(serde_json::json!({ "pattern": "*.csv", "tag": "sales" }))
+    }
+
+    #[test]
+    fn test_append_continues_seq_across_handles() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("audit").join("audit.tape");
+
+        assert_eq!(
+            AuditTape::new(&path)
+                .append(&rule_record("r1", "created", "alice"))
+                .unwrap(),
+            0
+        );
+        // A second handle (e.g. another process) picks up where the first left off
+        let other = AuditTape::new(&path);
+        assert_eq!(
+            other.append(&rule_record("r1", "updated", "bob")).unwrap(),
+            1
+        );
+        assert_eq!(
+            other
+                .append(&rule_record("r2", "created", "alice"))
+                .unwrap(),
+            2
+        );
+
+        // The file is a valid tape
+        let stats = TapeReader::open(&path)
+            .unwrap()
+            .replay(|_| crate::ReplayControl::Continue)
+            .unwrap();
+        assert_eq!(stats.events_read, 3);
+        assert_eq!(stats.last_seq, Some(2));
+    }
+
+    #[test]
+    fn test_last_seq_with_large_records() {
+        let dir = tempdir().unwrap();
+        let tape = AuditTape::new(dir.path().join("audit.tape"));
+        let big = "x".repeat(3 * TAIL_CHUNK_BYTES as usize);
+        for expected in 0..3 {
+            let record = AuditRecordV1::new("plugin", "p", "published", "ci")
+                .with_after(serde_json::json!({ "notes": big }));
+            assert_eq!(tape.append(&record).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_query_filters_by_entity_actor_and_limit() {
+        let dir = tempdir().unwrap();
+        let tape = AuditTape::new(dir.path().join("audit.tape"));
+        assert!(tape.query(&AuditQuery::new()).unwrap().is_empty());
+
+        tape.append(&rule_record("r1", "created", "alice")).unwrap();
+        tape.append(&rule_record("r2", "created", "bob")).unwrap();
+        tape.append(&rule_record("r1", "deleted", "alice")).unwrap();
+        tape.append(&AuditRecordV1::new("approval", "a1", "approved", "alice"))
+            .unwrap();
+
+        let r1 = tape
+            .query(
+                &AuditQuery::new()
+                    .entity_kind("routing_rule")
+                    .entity_id("r1"),
+            )
+            .unwrap();
+        let actions: Vec<_> = r1.iter().map(|e| e.record.action.as_str()).collect();
+        assert_eq!(actions, ["created", "deleted"]);
+
+        let alice = tape
+            .query(&AuditQuery::new().actor("alice").limit(2))
+            .unwrap();
+        assert_eq!(alice.len(), 2);
+        assert_eq!(alice[0].record.action, "deleted");
+        assert_eq!(alice[1].record.entity_kind, "approval");
+
+        let future = Utc::now() + chrono::Duration::hours(1);
+        assert!(tape
+            .query(&AuditQuery::new().since(future))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            tape.query(&AuditQuery::new().until(future)).unwrap().len(),
+            4
+        );
+    }
+}
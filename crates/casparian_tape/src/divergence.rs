@@ -0,0 +1,295 @@
+//! Divergence detection between a recorded tape and a replay of it.
+//!
+//! Only `DomainEvent`s are compared: they are the observable behaviour of the
+//! scheduler and pipelines, while UI commands and responses are just framing.
+//! Events are paired by position; the first pair whose names or payloads
+//! differ is reported with a field-level diff. Fields that change on every
+//! run (run IDs, timestamps, durations) are ignored by name at any depth.
+
+use crate::{EnvelopeV1, EventName};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Payload fields that legitimately differ between a recording and its replay.
+pub const VOLATILE_FIELDS: &[&str] = &[
+    "run_id",
+    "started_at",
+    "duration_ms",
+    "elapsed_ms",
+    "files_per_sec",
+];
+
+/// Outcome of comparing the domain events of two tapes.
+#[derive(Debug, Clone, Serialize)]
+pub struct DivergenceReport {
+    pub recorded_events: usize,
+    pub replayed_events: usize,
+    /// First diverging pair, or `None` if the streams match.
+    pub divergence: Option<Divergence>,
+}
+
+impl DivergenceReport {
+    pub fn is_match(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+/// The first pair of domain events that differ.
+///
+/// One side is `None` when the other tape ran out of domain events first.
+#[derive(Debug, Clone, Serialize)]
+pub struct Divergence {
+    /// Position among the domain events (0-based).
+    pub index: usize,
+    pub recorded_seq: Option<u64>,
+    pub replayed_seq: Option<u64>,
+    pub recorded_event: Option<String>,
+    pub replayed_event: Option<String>,
+    /// Differing payload fields; empty if only the event name differs or one
+    /// side is missing.
+    pub fields: Vec<FieldDiff>,
+}
+
+/// A payload field whose value differs. A missing side means the field is
+/// absent from that payload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldDiff {
+    /// Path into the payload, e.g. `$.config.threads` or `$.outputs[1]`.
+    pub path: String,
+    pub recorded: Option<Value>,
+    pub replayed: Option<Value>,
+}
+
+/// Compare the domain events of `recorded` and `replayed`, ignoring payload
+/// fields named in `ignore_fields`. Non-domain events are skipped.
+pub fn compare_domain_events(
+    recorded: &[EnvelopeV1],
+    replayed: &[EnvelopeV1],
+    ignore_fields: &[&str],
+) -> DivergenceReport {
+    let recorded: Vec<(&EnvelopeV1, &str)> = domain_events(recorded).collect();
+    let replayed: Vec<(&EnvelopeV1, &str)> = domain_events(replayed).collect();
+
+    let mut divergence = None;
+    for index in 0..recorded.len().max(replayed.len()) {
+        let left = recorded.get(index);
+        let right = replayed.get(index);
+        let fields = match (left, right) {
+            (Some((left, left_name)), Some((right, right_name))) => {
+                let fields = diff_payloads(&left.payload, &right.payload, ignore_fields);
+                if left_name == right_name && fields.is_empty() {
+                    continue;
+                }
+                if left_name == right_name {
+                    fields
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
+        };
+        divergence = Some(Divergence {
+            index,
+            recorded_seq: left.map(|(envelope, _)| envelope.seq),
+            replayed_seq: right.map(|(envelope, _)| envelope.seq),
+            recorded_event: left.map(|(_, name)| name.to_string()),
+            replayed_event: right.map(|(_, name)| name.to_string()),
+            fields,
+        });
+        break;
+    }
+
+    DivergenceReport {
+        recorded_events: recorded.len(),
+        replayed_events: replayed.len(),
+        divergence,
+    }
+}
+
+/// Field-level diff of two payloads, skipping object keys in `ignore_fields`.
+pub fn diff_payloads(recorded: &Value, replayed: &Value, ignore_fields: &[&str]) -> Vec<FieldDiff> {
+    let mut diffs = Vec::new();
+    diff_values(
+        "$",
+        Some(recorded),
+        Some(replayed),
+        ignore_fields,
+        &mut diffs,
+    );
+    diffs
+}
+
+fn domain_events(envelopes: &[EnvelopeV1]) -> impl Iterator<Item = (&EnvelopeV1, &str)> {
+    envelopes
+        .iter()
+        .filter_map(|envelope| match &envelope.event_name {
+            EventName::DomainEvent(name) => Some((envelope, name.as_str())),
+            _ => None,
+        })
+}
+
+fn diff_values(
+    path: &str,
+    recorded: Option<&Value>,
+    replayed: Option<&Value>,
+    ignore_fields: &[&str],
+    diffs: &mut Vec<FieldDiff>,
+) {
+    match (recorded, replayed) {
+        (Some(Value::Object(left)), Some(Value::Object(right))) => {
+            let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                if ignore_fields.contains(&key.as_str()) {
+                    continue;
+                }
+                let child = format!("{}.{}", path, key);
+                diff_values(&child, left.get(key), right.get(key), ignore_fields, diffs);
+            }
+        }
+        (Some(Value::Array(left)), Some(Value::Array(right))) => {
+            for index in 0..left.len().max(right.len()) {
+                let child = format!("{}[{}]", path, index);
+                diff_values(
+                    &child,
+                    left.get(index),
+                    right.get(index),
+                    ignore_fields,
+                    diffs,
+                );
+            }
+        }
+        (left, right) if left != right => diffs.push(FieldDiff {
+            path: path.to_string(),
+            recorded: left.cloned(),
+            replayed: right.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn envelope(seq: u64, event_name: EventName, payload: Value) -> EnvelopeV1 {
+        EnvelopeV1 {
+            schema_version: crate::SCHEMA_VERSION,
+            event_id: format!("e{}", seq),
+            seq,
+            timestamp: Utc::now(),
+            correlation_id: None,
+            parent_id: None,
+            event_name,
+            payload,
+        }
+    }
+
+    fn domain(seq: u64, name: &str, payload: Value) -> EnvelopeV1 {
+        envelope(seq, EventName::DomainEvent(name.to_string()), payload)
+    }
+
+    #[test]
+    fn test_matching_streams_ignore_volatile_fields_and_framing() {
+        let recorded = vec![
+            envelope(0, EventName::TapeStarted, json!({})),
+            envelope(1, EventName::UICommand("Run".to_string()), json!({})),
+            domain(2, "run.start", json!({"run_id": "a", "kind": "dev_run"})),
+            domain(
+                3,
+                "run.complete",
+                json!({"run_id": "a", "duration_ms": 12, "total_rows": 5}),
+            ),
+        ];
+        let replayed = vec![
+            domain(7, "run.start", json!({"run_id": "b", "kind": "dev_run"})),
+            domain(
+                9,
+                "run.complete",
+                json!({"run_id": "b", "duration_ms": 40, "total_rows": 5}),
+            ),
+        ];
+
+        let report = compare_domain_events(&recorded, &replayed, VOLATILE_FIELDS);
+        assert!(report.is_match(), "{:?}", report.divergence);
+        assert_eq!(report.recorded_events, 2);
+        assert_eq!(report.replayed_events, 2);
+    }
+
+    #[test]
+    fn test_reports_first_divergence_with_field_paths() {
+        let recorded = vec![
+            domain(
+                1,
+                "scan.start",
+                json!({"config": {"threads": 4, "follow_symlinks": false}}),
+            ),
+            domain(
+                2,
+                "scan.complete",
+                json!({"files_new": 3, "outputs": ["a", "b"]}),
+            ),
+            domain(3, "scan.complete", json!({"files_new": 9})),
+        ];
+        let replayed = vec![
+            domain(
+                1,
+                "scan.start",
+                json!({"config": {"threads": 4, "follow_symlinks": false}}),
+            ),
+            domain(
+                2,
+                "scan.complete",
+                json!({"files_new": 2, "outputs": ["a"], "errors": 1}),
+            ),
+            domain(3, "scan.complete", json!({"files_new": 0})),
+        ];
+
+        let report = compare_domain_events(&recorded, &replayed, VOLATILE_FIELDS);
+        let divergence = report.divergence.unwrap();
+        assert_eq!(divergence.index, 1);
+        assert_eq!(divergence.recorded_seq, Some(2));
+        assert_eq!(
+            divergence.fields,
+            vec![
+                FieldDiff {
+                    path: "$.errors".to_string(),
+                    recorded: None,
+                    replayed: Some(json!(1)),
+                },
+                FieldDiff {
+                    path: "$.files_new".to_string(),
+                    recorded: Some(json!(3)),
+                    replayed: Some(json!(2)),
+                },
+                FieldDiff {
+                    path: "$.outputs[1]".to_string(),
+                    recorded: Some(json!("b")),
+                    replayed: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_renamed_and_missing_events() {
+        let recorded = vec![domain(1, "run.complete", json!({}))];
+        let renamed = vec![domain(1, "run.fail", json!({"error_class": "io"}))];
+        let divergence = compare_domain_events(&recorded, &renamed, VOLATILE_FIELDS)
+            .divergence
+            .unwrap();
+        assert_eq!(divergence.recorded_event.as_deref(), Some("run.complete"));
+        assert_eq!(divergence.replayed_event.as_deref(), Some("run.fail"));
+        assert!(divergence.fields.is_empty());
+
+        let divergence = compare_domain_events(&recorded, &[], VOLATILE_FIELDS)
+            .divergence
+            .unwrap();
+        assert_eq!(divergence.index, 0);
+        assert_eq!(divergence.replayed_seq, None);
+        assert_eq!(divergence.replayed_event, None);
+    }
+}
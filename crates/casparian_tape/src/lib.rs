@@ -8,7 +8,13 @@
 //! - **Schema-versioned events**: All events use `EnvelopeV1` with explicit versioning
 //! - **Monotonic sequencing**: Each event has a strictly increasing sequence number
 //! - **Redaction by default**: Sensitive values are hashed with a session-specific salt
+//! - **Redaction policy**: `RedactionPolicy` hashes or omits payload fields by path on `emit()`
 //! - **NDJSON format**: One JSON object per line for easy streaming and processing
+//! - **Rotation**: `TapeWriterConfig` rotates by size/age and optionally compresses old segments
+//! - **Filtered replay**: `TapeReader` streams envelopes back with `TapeFilter` and `replay()`
+//! - **Divergence detection**: `compare_domain_events` diffs the domain events of a replay
+//!   against the recording
+//! - **Audit tape**: `AuditTape` appends control-plane mutations to a tape shared across processes
 //!
 //! # Example
 //!
@@ -29,14 +35,27 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::fs::{File, OpenOptions};
-use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 use thiserror::Error;
 use uuid::Uuid;
 
+mod audit;
+mod divergence;
+mod reader;
+mod redaction;
+mod rotation;
+
+pub use audit::{AuditEntry, AuditQuery, AuditRecordV1, AuditTape};
+pub use divergence::{
+    compare_domain_events, diff_payloads, Divergence, DivergenceReport, FieldDiff, VOLATILE_FIELDS,
+};
+pub use reader::{ReplayControl, ReplayStats, TapeFilter, TapeReader};
+pub use redaction::{RedactionPolicy, RedactionRule};
+pub use rotation::{TapeCompression, TapeWriterConfig};
+use rotation::TapeFile;
+
 /// Current schema version for event envelopes.
 pub const SCHEMA_VERSION: u32 = 1;
 
@@ -51,6 +70,22 @@ pub enum TapeError {
 
     #[error("Failed to acquire lock")]
     LockError,
+
+    #[error("Line {line}: invalid envelope: {source}")]
+    Parse {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Line {line}: unsupported schema version {version}")]
+    UnsupportedSchemaVersion { line: usize, version: u32 },
+
+    #[error("Line {line}: seq {seq} does not follow previous seq {previous}")]
+    NonMonotonicSeq { line: usize, previous: u64, seq: u64 },
+
+    #[error("Compression codec '{0}' is not enabled in this build")]
+    CompressionUnavailable(&'static str),
 }
 
 /// Event envelope containing metadata and payload.
@@ -106,12 +141,18 @@ pub enum EventName {
     /// Examples: "QueryFailed", "ValidationError"
     #[serde(rename = "error_event")]
     ErrorEvent(String),
+    /// Control-plane mutation recorded on the audit tape (payload: `AuditRecordV1`)
+    /// Examples: "created", "published", "approved"
+    #[serde(rename = "audit")]
+    Audit(String),
 }
 
 /// Redaction modes for sensitive data.
 ///
 /// Controls how sensitive values are transformed before recording.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Selected per field by a `RedactionPolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RedactionMode {
     /// Replace sensitive values with their salted hash (default)
     #[default]
@@ -146,18 +187,23 @@ struct TapeStartedPayload {
     hostname: String,
     /// Working directory at tape start
     cwd: String,
+    /// Redaction rules applied to every payload of this tape
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    redaction: Vec<RedactionRule>,
 }
 
 /// Writer for recording events to a tape file.
 ///
 /// The writer maintains a monotonically increasing sequence number
-/// and a redaction salt for hashing sensitive values.
+/// and a redaction salt for hashing sensitive values. Payloads pass through
+/// the configured `RedactionPolicy` before they are written.
 ///
 /// Events are written in NDJSON format (one JSON object per line).
 pub struct TapeWriter {
-    file: Mutex<BufWriter<File>>,
+    file: Mutex<TapeFile>,
     seq: AtomicU64,
     redaction_salt: [u8; 32],
+    redaction: RedactionPolicy,
 }
 
 impl TapeWriter {
@@ -165,14 +211,18 @@ impl TapeWriter {
     ///
     /// This will create the file if it doesn't exist, or truncate it if it does.
     /// A `TapeStarted` event is automatically written as the first event.
+    /// The file is never rotated; use `with_config` for rotation.
     pub fn new(path: &Path) -> Result<Self, TapeError> {
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(path)?;
+        Self::with_config(path, TapeWriterConfig::default())
+    }
 
-        let writer = BufWriter::new(file);
+    /// Create a new tape writer that rotates and compresses per `config`.
+    ///
+    /// Fails with `TapeError::CompressionUnavailable` if the configured codec's
+    /// feature is not enabled.
+    pub fn with_config(path: &Path, mut config: TapeWriterConfig) -> Result<Self, TapeError> {
+        let redaction = std::mem::take(&mut config.redaction);
+        let file = TapeFile::create(path, config)?;
 
         // Generate a random salt for this session
         let mut redaction_salt = [0u8; 32];
@@ -183,9 +233,10 @@ impl TapeWriter {
         redaction_salt[16..].copy_from_slice(uuid2.as_bytes());
 
         let tape = Self {
-            file: Mutex::new(writer),
+            file: Mutex::new(file),
             seq: AtomicU64::new(0),
             redaction_salt,
+            redaction,
         };
 
         // Write the TapeStarted event
@@ -197,18 +248,13 @@ impl TapeWriter {
     /// Create a new tape writer with a specific salt (for testing).
     #[cfg(test)]
     fn new_with_salt(path: &Path, salt: [u8; 32]) -> Result<Self, TapeError> {
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(path)?;
-
-        let writer = BufWriter::new(file);
+        let file = TapeFile::create(path, TapeWriterConfig::default())?;
 
         let tape = Self {
-            file: Mutex::new(writer),
+            file: Mutex::new(file),
             seq: AtomicU64::new(0),
             redaction_salt: salt,
+            redaction: RedactionPolicy::default(),
         };
 
         tape.write_tape_started()?;
@@ -229,6 +275,7 @@ impl TapeWriter {
             salt_hash,
             hostname,
             cwd,
+            redaction: self.redaction.rules().to_vec(),
         };
 
         self.emit(
@@ -248,15 +295,20 @@ impl TapeWriter {
     /// * `event_name` - The type/classification of the event
     /// * `correlation_id` - Optional ID to group related events
     /// * `parent_id` - Optional parent event ID for causal chains
-    /// * `payload` - Event-specific data
+    /// * `payload` - Event-specific data, redacted per the writer's policy
     pub fn emit(
         &self,
         event_name: EventName,
         correlation_id: Option<&str>,
         parent_id: Option<&str>,
-        payload: serde_json::Value,
+        mut payload: serde_json::Value,
     ) -> Result<String, TapeError> {
         let event_id = Uuid::new_v4().to_string();
+        self.redaction
+            .apply(&mut payload, &|data| self.redact_bytes(data));
+
+        // Allocate seq under the file lock so on-disk order matches seq order.
+        let mut file = self.file.lock().map_err(|_| TapeError::LockError)?;
         let seq = self.seq.fetch_add(1, Ordering::SeqCst);
 
         let envelope = EnvelopeV1 {
@@ -271,10 +323,7 @@ impl TapeWriter {
         };
 
         let json = serde_json::to_string(&envelope)?;
-
-        let mut file = self.file.lock().map_err(|_| TapeError::LockError)?;
-        writeln!(file, "{}", json)?;
-        file.flush()?;
+        file.write_line(&json)?;
 
         Ok(event_id)
     }
@@ -295,6 +344,11 @@ impl TapeWriter {
         hash.to_hex()[..16].to_string()
     }
 
+    /// The policy applied to every emitted payload.
+    pub fn redaction_policy(&self) -> &RedactionPolicy {
+        &self.redaction
+    }
+
     /// Create an artifact reference with a hashed URI.
     pub fn create_artifact_ref(
         &self,
@@ -370,6 +424,10 @@ mod tests {
                 EventName::ErrorEvent("ValidationError".to_string()),
                 r#"{"type":"error_event","name":"ValidationError"}"#,
             ),
+            (
+                EventName::Audit("approved".to_string()),
+                r#"{"type":"audit","name":"approved"}"#,
+            ),
         ];
 
         for (event_name, expected_json) in cases {
@@ -522,6 +580,40 @@ mod tests {
         assert_eq!(artifact.uri_hash, expected_hash);
     }
 
+    #[test]
+    fn test_emit_applies_redaction_policy() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.tape");
+
+        let policy = RedactionPolicy::new()
+            .with_rule("sql", RedactionMode::Hash)
+            .with_rule("token", RedactionMode::Omit);
+        let config = TapeWriterConfig::default().with_redaction(policy.clone());
+        let writer = TapeWriter::with_config(&path, config).unwrap();
+        assert_eq!(writer.redaction_policy(), &policy);
+
+        writer
+            .emit(
+                EventName::UICommand("Query".to_string()),
+                None,
+                None,
+                serde_json::json!({"sql": "SELECT 1", "token": "secret", "limit": 10}),
+            )
+            .unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        let started: EnvelopeV1 = serde_json::from_str(lines[0]).unwrap();
+        let started: TapeStartedPayload = serde_json::from_value(started.payload).unwrap();
+        assert_eq!(started.redaction, policy.rules());
+
+        let envelope: EnvelopeV1 = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(
+            envelope.payload,
+            serde_json::json!({"sql": writer.redact_string("SELECT 1"), "limit": 10})
+        );
+    }
+
     #[test]
     fn test_ndjson_format() {
         let dir = tempdir().unwrap();
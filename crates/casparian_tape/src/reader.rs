@@ -0,0 +1,350 @@
+//! Reading, filtering, and replaying tape files.
+//!
+//! `TapeReader` streams NDJSON envelopes back as `EnvelopeV1`, one line at a
+//! time. Every envelope is validated (schema version, strictly increasing
+//! `seq`) before filtering, so a filtered read still fails loud on a corrupt
+//! tape.
+
+use crate::{EnvelopeV1, EventName, TapeError, SCHEMA_VERSION};
+use chrono::{DateTime, Utc};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Selects which envelopes a `TapeReader` yields.
+///
+/// An empty filter matches everything. Criteria are combined with AND;
+/// multiple event names are combined with OR.
+#[derive(Debug, Clone, Default)]
+pub struct TapeFilter {
+    event_names: Vec<EventName>,
+    correlation_id: Option<String>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+impl TapeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match events with this name (may be called repeatedly to match several).
+    pub fn event_name(mut self, name: EventName) -> Self {
+        self.event_names.push(name);
+        self
+    }
+
+    /// Match events with this correlation ID.
+    pub fn correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// Match events at or after `since`.
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Match events strictly before `until`.
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    pub fn matches(&self, envelope: &EnvelopeV1) -> bool {
+        if !self.event_names.is_empty() && !self.event_names.contains(&envelope.event_name) {
+            return false;
+        }
+        if let Some(correlation_id) = &self.correlation_id {
+            if envelope.correlation_id.as_deref() != Some(correlation_id.as_str()) {
+                return false;
+            }
+        }
+        if self.since.is_some_and(|since| envelope.timestamp < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| envelope.timestamp >= until) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Whether `replay` should keep feeding events to the callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayControl {
+    Continue,
+    Stop,
+}
+
+/// Counters returned by `TapeReader::replay`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    /// Envelopes read and validated (including ones skipped by the filter).
+    pub events_read: u64,
+    /// Envelopes passed to the callback.
+    pub events_replayed: u64,
+    /// Sequence number of the last envelope read.
+    pub last_seq: Option<u64>,
+}
+
+/// Streaming reader for tape files.
+pub struct TapeReader<R: BufRead> {
+    reader: R,
+    filter: TapeFilter,
+    line_num: usize,
+    last_seq: Option<u64>,
+    events_read: u64,
+    failed: bool,
+}
+
+impl TapeReader<BufReader<File>> {
+    /// Open a tape file for reading.
+    pub fn open(path: &Path) -> Result<Self, TapeError> {
+        let file = File::open(path)?;
+        Ok(Self::new(BufReader::new(file)))
+    }
+}
+
+impl<R: BufRead> TapeReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            filter: TapeFilter::default(),
+            line_num: 0,
+            last_seq: None,
+            events_read: 0,
+            failed: false,
+        }
+    }
+
+    /// Only yield envelopes matching `filter`.
+    pub fn with_filter(mut self, filter: TapeFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Feed every matching envelope to `callback` in tape order.
+    ///
+    /// Stops early when the callback returns `ReplayControl::Stop`. The first
+    /// read or validation error aborts the replay.
+    pub fn replay<F>(mut self, mut callback: F) -> Result<ReplayStats, TapeError>
+    where
+        F: FnMut(&EnvelopeV1) -> ReplayControl,
+    {
+        let mut events_replayed = 0;
+        for envelope in self.by_ref() {
+            let envelope = envelope?;
+            events_replayed += 1;
+            if callback(&envelope) == ReplayControl::Stop {
+                break;
+            }
+        }
+        Ok(ReplayStats {
+            events_read: self.events_read,
+            events_replayed,
+            last_seq: self.last_seq,
+        })
+    }
+
+    fn read_envelope(&mut self) -> Result<Option<EnvelopeV1>, TapeError> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            self.line_num += 1;
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+
+        let envelope: EnvelopeV1 =
+            serde_json::from_str(&line).map_err(|source| TapeError::Parse {
+                line: self.line_num,
+                source,
+            })?;
+
+        if envelope.schema_version != SCHEMA_VERSION {
+            return Err(TapeError::UnsupportedSchemaVersion {
+                line: self.line_num,
+                version: envelope.schema_version,
+            });
+        }
+        if let Some(previous) = self.last_seq {
+            if envelope.seq <= previous {
+                return Err(TapeError::NonMonotonicSeq {
+                    line: self.line_num,
+                    previous,
+                    seq: envelope.seq,
+                });
+            }
+        }
+        self.last_seq = Some(envelope.seq);
+        self.events_read += 1;
+        Ok(Some(envelope))
+    }
+}
+
+impl<R: BufRead> Iterator for TapeReader<R> {
+    type Item = Result<EnvelopeV1, TapeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            match self.read_envelope() {
+                Ok(Some(envelope)) => {
+                    if self.filter.matches(&envelope) {
+                        return Some(Ok(envelope));
+                    }
+                }
+                Ok(None) => return None,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TapeWriter;
+    use chrono::TimeZone;
+    use std::io::Cursor;
+    use tempfile::tempdir;
+
+    fn line(seq: u64, second: u32, name: &str, correlation: Option<&str>) -> String {
+        let envelope = EnvelopeV1 {
+            schema_version: SCHEMA_VERSION,
+            event_id: format!("e{}", seq),
+            seq,
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, second).unwrap(),
+            correlation_id: correlation.map(String::from),
+            parent_id: None,
+            event_name: EventName::DomainEvent(name.to_string()),
+            payload: serde_json::json!({}),
+        };
+        serde_json::to_string(&envelope).unwrap()
+    }
+
+    fn tape(lines: &[String]) -> Cursor<Vec<u8>> {
+        Cursor::new(format!("{}\n", lines.join("\n")).into_bytes())
+    }
+
+    #[test]
+    fn test_reader_roundtrips_writer_output() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.tape");
+        let writer = TapeWriter::new(&path).unwrap();
+        writer
+            .emit(
+                EventName::UICommand("Scan".to_string()),
+                Some("session-1"),
+                None,
+                serde_json::json!({"dir": "/data"}),
+            )
+            .unwrap();
+        drop(writer);
+
+        let events: Vec<EnvelopeV1> = TapeReader::open(&path)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_name, EventName::TapeStarted);
+        assert_eq!(events[1].correlation_id.as_deref(), Some("session-1"));
+    }
+
+    #[test]
+    fn test_filter_by_name_correlation_and_time() {
+        let lines = vec![
+            line(0, 0, "JobDispatched", Some("run-1")),
+            line(1, 10, "JobCompleted", Some("run-1")),
+            line(2, 20, "JobDispatched", Some("run-2")),
+            line(3, 30, "JobCompleted", Some("run-2")),
+        ];
+
+        let filter = TapeFilter::new()
+            .event_name(EventName::DomainEvent("JobCompleted".to_string()))
+            .correlation_id("run-2");
+        let seqs: Vec<u64> = TapeReader::new(tape(&lines))
+            .with_filter(filter)
+            .map(|e| e.unwrap().seq)
+            .collect();
+        assert_eq!(seqs, vec![3]);
+
+        let filter = TapeFilter::new()
+            .since(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 10).unwrap())
+            .until(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 30).unwrap());
+        let seqs: Vec<u64> = TapeReader::new(tape(&lines))
+            .with_filter(filter)
+            .map(|e| e.unwrap().seq)
+            .collect();
+        assert_eq!(seqs, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_non_monotonic_seq_is_rejected() {
+        let lines = vec![
+            line(0, 0, "A", None),
+            line(2, 1, "B", None),
+            line(1, 2, "C", None),
+        ];
+        let results: Vec<_> = TapeReader::new(tape(&lines)).collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            results[2],
+            Err(TapeError::NonMonotonicSeq {
+                line: 3,
+                previous: 2,
+                seq: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_validation_applies_to_filtered_events() {
+        let lines = vec![line(5, 0, "A", None), line(5, 1, "B", None)];
+        let filter = TapeFilter::new().event_name(EventName::DomainEvent("Z".to_string()));
+        let results: Vec<_> = TapeReader::new(tape(&lines)).with_filter(filter).collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+
+    #[test]
+    fn test_replay_stops_on_request() {
+        let lines = vec![
+            line(0, 0, "A", None),
+            line(1, 1, "B", None),
+            line(2, 2, "C", None),
+        ];
+        let mut seen = Vec::new();
+        let stats = TapeReader::new(tape(&lines))
+            .replay(|envelope| {
+                seen.push(envelope.seq);
+                if envelope.seq == 1 {
+                    ReplayControl::Stop
+                } else {
+                    ReplayControl::Continue
+                }
+            })
+            .unwrap();
+        assert_eq!(seen, vec![0, 1]);
+        assert_eq!(stats.events_replayed, 2);
+        assert_eq!(stats.last_seq, Some(1));
+    }
+
+    #[test]
+    fn test_parse_error_reports_line() {
+        let lines = vec![line(0, 0, "A", None), "{not json".to_string()];
+        let results: Vec<_> = TapeReader::new(tape(&lines)).collect();
+        assert!(matches!(results[1], Err(TapeError::Parse { line: 2, .. })));
+    }
+}
@@ -0,0 +1,207 @@
+//! Per-field redaction of event payloads.
+//!
+//! A `RedactionPolicy` maps field paths to a `RedactionMode` and is applied by
+//! `TapeWriter::emit` to every payload, so callers put raw values in the
+//! payload and the policy decides what reaches the tape.
+//!
+//! Patterns are dot-separated object keys, matched against the path of each
+//! field from the payload root:
+//!
+//! - `sql` matches the top-level `sql` field only
+//! - `*` matches one key, and may be part of a key (`*_hash`, `input_*`)
+//! - `**` matches any number of keys, including none
+//!
+//! Arrays are transparent: each element has the path of the array, so
+//! `files.path` matches `{"files": [{"path": ...}]}`. The first matching rule
+//! wins; fields no rule matches are written as-is.
+
+use crate::RedactionMode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One pattern of a `RedactionPolicy`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub pattern: String,
+    pub mode: RedactionMode,
+}
+
+/// Ordered redaction rules for tape payloads.
+///
+/// The default policy has no rules and records payloads unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionPolicy {
+    rules: Vec<RedactionRule>,
+}
+
+impl RedactionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a rule; earlier rules take precedence.
+    pub fn with_rule(mut self, pattern: impl Into<String>, mode: RedactionMode) -> Self {
+        self.rules.push(RedactionRule {
+            pattern: pattern.into(),
+            mode,
+        });
+        self
+    }
+
+    pub fn rules(&self) -> &[RedactionRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Mode of the first rule matching `path`, if any.
+    pub fn mode_for(&self, path: &[&str]) -> Option<RedactionMode> {
+        self.rules
+            .iter()
+            .find(|rule| path_matches(&rule.pattern, path))
+            .map(|rule| rule.mode)
+    }
+
+    /// Apply the policy to `payload` in place; `hash` turns a value's bytes
+    /// into its redacted form.
+    pub(crate) fn apply(&self, payload: &mut Value, hash: &dyn Fn(&[u8]) -> String) {
+        if self.rules.is_empty() {
+            return;
+        }
+        let mut path = Vec::new();
+        self.apply_at(payload, &mut path, hash);
+    }
+
+    fn apply_at(&self, value: &mut Value, path: &mut Vec<String>, hash: &dyn Fn(&[u8]) -> String) {
+        match value {
+            Value::Object(map) => {
+                let mut omitted = Vec::new();
+                for (key, field) in map.iter_mut() {
+                    path.push(key.clone());
+                    let keys: Vec<&str> = path.iter().map(String::as_str).collect();
+                    match self.mode_for(&keys) {
+                        Some(RedactionMode::Omit) => omitted.push(key.clone()),
+                        Some(RedactionMode::Hash) => *field = hash_value(field, hash),
+                        Some(RedactionMode::Plaintext) => {}
+                        None => self.apply_at(field, path, hash),
+                    }
+                    path.pop();
+                }
+                for key in omitted {
+                    map.remove(&key);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.apply_at(item, path, hash);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn hash_value(value: &Value, hash: &dyn Fn(&[u8]) -> String) -> Value {
+    match value {
+        Value::Null => Value::Null,
+        Value::String(text) => Value::String(hash(text.as_bytes())),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|item| hash_value(item, hash)).collect())
+        }
+        other => Value::String(hash(other.to_string().as_bytes())),
+    }
+}
+
+fn path_matches(pattern: &str, path: &[&str]) -> bool {
+    let segments: Vec<&str> = pattern.split('.').collect();
+    segments_match(&segments, path)
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((key, path_rest)) => key_matches(segment, key) && segments_match(rest, path_rest),
+            None => false,
+        },
+    }
+}
+
+/// Glob match of one key, `*` matching any run of characters.
+fn key_matches(pattern: &str, key: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == key;
+    };
+    let Some(key) = key.strip_prefix(prefix) else {
+        return false;
+    };
+    if rest.is_empty() {
+        return true;
+    }
+    (0..=key.len())
+        .filter(|&start| key.is_char_boundary(start))
+        .any(|start| key_matches(rest, &key[start..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fake_hash(bytes: &[u8]) -> String {
+        format!("h({})", String::from_utf8_lossy(bytes))
+    }
+
+    #[test]
+    fn test_patterns() {
+        assert!(path_matches("sql", &["sql"]));
+        assert!(!path_matches("sql", &["query", "sql"]));
+        assert!(path_matches("*.sql", &["query", "sql"]));
+        assert!(path_matches("**.sql", &["sql"]));
+        assert!(path_matches("**.sql", &["a", "b", "sql"]));
+        assert!(path_matches("**.*_hash", &["input_dir_hash"]));
+        assert!(path_matches("input_*", &["input_dir"]));
+        assert!(!path_matches("input_*", &["output_dir"]));
+        assert!(path_matches("a.**", &["a", "b", "c"]));
+    }
+
+    #[test]
+    fn test_apply_modes() {
+        let policy = RedactionPolicy::new()
+            .with_rule("token", RedactionMode::Omit)
+            .with_rule("files.path", RedactionMode::Hash)
+            .with_rule("**.row_count", RedactionMode::Plaintext)
+            .with_rule("**.*_hash", RedactionMode::Hash);
+        let mut payload = json!({
+            "token": "secret",
+            "files": [{"path": "/a.csv", "size": 3}, {"path": "/b.csv", "size": 4}],
+            "sql_hash": "SELECT 1",
+            "result": {"row_count": 2, "limit_hash": 10},
+        });
+        policy.apply(&mut payload, &fake_hash);
+        assert_eq!(
+            payload,
+            json!({
+                "files": [{"path": "h(/a.csv)", "size": 3}, {"path": "h(/b.csv)", "size": 4}],
+                "sql_hash": "h(SELECT 1)",
+                "result": {"row_count": 2, "limit_hash": "h(10)"},
+            })
+        );
+    }
+
+    #[test]
+    fn test_first_rule_wins() {
+        let policy = RedactionPolicy::new()
+            .with_rule("debug_hash", RedactionMode::Plaintext)
+            .with_rule("**.*_hash", RedactionMode::Hash);
+        assert_eq!(
+            policy.mode_for(&["debug_hash"]),
+            Some(RedactionMode::Plaintext)
+        );
+        assert_eq!(policy.mode_for(&["path_hash"]), Some(RedactionMode::Hash));
+        assert_eq!(policy.mode_for(&["path"]), None);
+    }
+}
@@ -0,0 +1,352 @@
+//! Size/time-based rotation and compression of tape files.
+//!
+//! The active tape is always written at the configured path. When it exceeds
+//! `max_bytes` or `max_age`, it is renamed to `<path>.1` (compressed to
+//! `<path>.1.gz` / `<path>.1.zst` when a codec is configured), older segments
+//! shift up by one, and anything beyond `max_files` is deleted. Sequence
+//! numbers continue across segments.
+
+use crate::{RedactionPolicy, TapeError};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Compression applied to rotated tape segments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TapeCompression {
+    #[default]
+    None,
+    /// Requires the `gzip` feature.
+    Gzip,
+    /// Requires the `zstd` feature.
+    Zstd,
+}
+
+impl TapeCompression {
+    /// File extension appended to rotated segments (without the dot).
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            TapeCompression::None => None,
+            TapeCompression::Gzip => Some("gz"),
+            TapeCompression::Zstd => Some("zst"),
+        }
+    }
+
+    fn ensure_available(&self) -> Result<(), TapeError> {
+        match self {
+            TapeCompression::None => Ok(()),
+            TapeCompression::Gzip if cfg!(feature = "gzip") => Ok(()),
+            TapeCompression::Zstd if cfg!(feature = "zstd") => Ok(()),
+            TapeCompression::Gzip => Err(TapeError::CompressionUnavailable("gzip")),
+            TapeCompression::Zstd => Err(TapeError::CompressionUnavailable("zstd")),
+        }
+    }
+}
+
+/// Rotation, compression and redaction settings for `TapeWriter`.
+///
+/// The default never rotates (a single file grows for the lifetime of the
+/// writer) and has no redaction rules, matching `TapeWriter::new`.
+#[derive(Debug, Clone, Default)]
+pub struct TapeWriterConfig {
+    /// Rotate once the active segment reaches this many bytes.
+    pub max_bytes: Option<u64>,
+    /// Rotate once the active segment has been open this long.
+    pub max_age: Option<Duration>,
+    /// Rotated segments to keep; older ones are deleted. Zero keeps none.
+    pub max_files: usize,
+    /// Codec applied to rotated segments.
+    pub compression: TapeCompression,
+    /// Redaction applied to every payload on `emit()`.
+    pub redaction: RedactionPolicy,
+}
+
+impl TapeWriterConfig {
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    pub fn with_compression(mut self, compression: TapeCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn with_redaction(mut self, redaction: RedactionPolicy) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Path of the `index`-th rotated segment (1 = most recent).
+    pub fn segment_path(&self, path: &Path, index: usize) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        if let Some(ext) = self.compression.extension() {
+            name.push(format!(".{}", ext));
+        }
+        PathBuf::from(name)
+    }
+
+    fn rotates(&self) -> bool {
+        self.max_bytes.is_some() || self.max_age.is_some()
+    }
+}
+
+/// The active tape segment.
+pub(crate) struct TapeFile {
+    path: PathBuf,
+    config: TapeWriterConfig,
+    writer: BufWriter<File>,
+    bytes_written: u64,
+    opened_at: Instant,
+}
+
+impl TapeFile {
+    pub(crate) fn create(path: &Path, config: TapeWriterConfig) -> Result<Self, TapeError> {
+        config.compression.ensure_available()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: open_truncated(path)?,
+            config,
+            bytes_written: 0,
+            opened_at: Instant::now(),
+        })
+    }
+
+    /// Write one NDJSON line, rotating afterwards if a limit was reached.
+    pub(crate) fn write_line(&mut self, json: &str) -> Result<(), TapeError> {
+        writeln!(self.writer, "{}", json)?;
+        self.writer.flush()?;
+        self.bytes_written = self
+            .bytes_written
+            .saturating_add(u64::try_from(json.len()).unwrap_or(u64::MAX))
+            .saturating_add(1);
+
+        if self.should_rotate() {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    fn should_rotate(&self) -> bool {
+        if !self.config.rotates() {
+            return false;
+        }
+        let size_exceeded = self
+            .config
+            .max_bytes
+            .is_some_and(|max| self.bytes_written >= max);
+        let age_exceeded = self
+            .config
+            .max_age
+            .is_some_and(|max| self.opened_at.elapsed() >= max);
+        size_exceeded || age_exceeded
+    }
+
+    fn rotate(&mut self) -> Result<(), TapeError> {
+        self.writer.flush()?;
+
+        if self.config.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let oldest = self.config.segment_path(&self.path, self.config.max_files);
+            remove_if_exists(&oldest)?;
+            for index in (1..self.config.max_files).rev() {
+                let from = self.config.segment_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(&from, self.config.segment_path(&self.path, index + 1))?;
+                }
+            }
+            let target = self.config.segment_path(&self.path, 1);
+            match self.config.compression {
+                TapeCompression::None => fs::rename(&self.path, &target)?,
+                codec => {
+                    compress_file(&self.path, &target, codec)?;
+                    fs::remove_file(&self.path)?;
+                }
+            }
+        }
+
+        self.writer = open_truncated(&self.path)?;
+        self.bytes_written = 0;
+        self.opened_at = Instant::now();
+        Ok(())
+    }
+}
+
+fn open_truncated(path: &Path) -> Result<BufWriter<File>, TapeError> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    Ok(BufWriter::new(file))
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), TapeError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn compress_file(src: &Path, dst: &Path, codec: TapeCompression) -> Result<(), TapeError> {
+    match codec {
+        TapeCompression::None => fs::copy(src, dst).map(|_| ()).map_err(Into::into),
+        #[cfg(feature = "gzip")]
+        TapeCompression::Gzip => {
+            let output = BufWriter::new(File::create(dst)?);
+            let mut encoder =
+                flate2::write::GzEncoder::new(output, flate2::Compression::default());
+            std::io::copy(&mut File::open(src)?, &mut encoder)?;
+            encoder.finish()?.flush()?;
+            Ok(())
+        }
+        #[cfg(feature = "zstd")]
+        TapeCompression::Zstd => {
+            let output = BufWriter::new(File::create(dst)?);
+            let mut encoder = zstd::stream::write::Encoder::new(output, 0)?;
+            std::io::copy(&mut File::open(src)?, &mut encoder)?;
+            encoder.finish()?.flush()?;
+            Ok(())
+        }
+        #[cfg(not(feature = "gzip"))]
+        TapeCompression::Gzip => Err(TapeError::CompressionUnavailable("gzip")),
+        #[cfg(not(feature = "zstd"))]
+        TapeCompression::Zstd => Err(TapeError::CompressionUnavailable("zstd")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventName, TapeReader, TapeWriter};
+    use tempfile::tempdir;
+
+    fn emit_n(writer: &TapeWriter, n: usize) {
+        for i in 0..n {
+            writer
+                .emit(
+                    EventName::DomainEvent("Tick".to_string()),
+                    None,
+                    None,
+                    serde_json::json!({ "i": i }),
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_default_config_never_rotates() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.tape");
+        let writer = TapeWriter::new(&path).unwrap();
+        emit_n(&writer, 50);
+
+        let config = TapeWriterConfig::default();
+        assert!(!config.segment_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_size_rotation_keeps_max_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.tape");
+        let config = TapeWriterConfig::default()
+            .with_max_bytes(512)
+            .with_max_files(2);
+        let writer = TapeWriter::with_config(&path, config.clone()).unwrap();
+        emit_n(&writer, 40);
+
+        assert!(path.exists());
+        assert!(config.segment_path(&path, 1).exists());
+        assert!(config.segment_path(&path, 2).exists());
+        assert!(!config.segment_path(&path, 3).exists());
+
+        // Seq continues across segments: the older segment ends before the newer begins.
+        let last_of = |p: &Path| {
+            TapeReader::open(p)
+                .unwrap()
+                .map(|e| e.unwrap().seq)
+                .last()
+                .unwrap()
+        };
+        let first_of = |p: &Path| TapeReader::open(p).unwrap().next().map(|e| e.unwrap().seq);
+        let older = config.segment_path(&path, 2);
+        let newer = config.segment_path(&path, 1);
+        assert!(last_of(&older) < first_of(&newer).unwrap());
+        // The live segment is empty when the last emit rotated it.
+        if let Some(first_live) = first_of(&path) {
+            assert!(last_of(&newer) < first_live);
+        }
+    }
+
+    #[test]
+    fn test_zero_max_files_discards_rotated_segments() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.tape");
+        let config = TapeWriterConfig::default().with_max_bytes(256);
+        let writer = TapeWriter::with_config(&path, config.clone()).unwrap();
+        emit_n(&writer, 20);
+
+        assert!(path.exists());
+        assert!(!config.segment_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_segment_path_includes_codec_extension() {
+        let path = Path::new("/tmp/session.tape");
+        let config = TapeWriterConfig::default().with_compression(TapeCompression::Zstd);
+        assert_eq!(
+            config.segment_path(path, 3),
+            PathBuf::from("/tmp/session.tape.3.zst")
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_rotated_segment_decompresses() {
+        use std::io::Read;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.tape");
+        let config = TapeWriterConfig::default()
+            .with_max_bytes(256)
+            .with_max_files(1)
+            .with_compression(TapeCompression::Gzip);
+        let writer = TapeWriter::with_config(&path, config.clone()).unwrap();
+        emit_n(&writer, 5);
+
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(File::open(config.segment_path(&path, 1)).unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        let events: Vec<_> = TapeReader::new(decoded.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(!events.is_empty());
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn test_unavailable_codec_is_rejected() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.tape");
+        let config = TapeWriterConfig::default().with_compression(TapeCompression::Zstd);
+        assert!(matches!(
+            TapeWriter::with_config(&path, config),
+            Err(TapeError::CompressionUnavailable("zstd"))
+        ));
+    }
+}
@@ -24,10 +24,25 @@ Usage in parsers:
             Output("events", events_df),
             Output("metrics", metrics_df, table="mcdata_metrics"),
         ]
+
+    # CSV input in whatever dialect the host resolved (rule, plugin, or sniffed)
+    def parse(file_path: str) -> pd.DataFrame:
+        return pd.read_csv(file_path, **csv_dialect().pandas_kwargs())
+
+    # Scratch files go in the job's scratch directory, removed after the job
+    def parse(file_path: str) -> pd.DataFrame:
+        sorted_path = os.path.join(scratch_dir(), "sorted.csv")
+        ...
 """
 
+import json
+import os
+import tempfile
 from typing import NamedTuple, Union, Any
 
+CSV_DIALECT_ENV = "CASPARIAN_CSV_DIALECT"
+SCRATCH_DIR_ENV = "CASPARIAN_SCRATCH_DIR"
+
 # Type alias for supported data types
 # Bridge converts all to PyArrow Table before IPC serialization
 DataType = Any  # pl.DataFrame, pd.DataFrame, or pa.Table
@@ -74,3 +89,58 @@ def validate_output(output: Output) -> None:
     # Validate data type (basic check - full validation happens in bridge)
     if output.data is None:
         raise ValueError(f"Output '{output.name}' has None data")
+
+
+class CsvDialect(NamedTuple):
+    """
+    How to read a CSV input, as resolved by the host for this job.
+
+    Attributes:
+        delimiter: Field delimiter.
+        quote: Quote character.
+        escape: Escape character inside quotes (None = quotes are doubled).
+        skip_rows: Lines before the header row.
+        encoding: Text encoding (a Python codec name).
+        null_tokens: Field values read as null.
+    """
+
+    delimiter: str = ","
+    quote: str = '"'
+    escape: str | None = None
+    skip_rows: int = 0
+    encoding: str = "utf-8"
+    null_tokens: tuple[str, ...] = ("",)
+
+    def pandas_kwargs(self) -> dict:
+        """Keyword arguments for pandas.read_csv."""
+        return {
+            "sep": self.delimiter,
+            "quotechar": self.quote,
+            "escapechar": self.escape,
+            "skiprows": self.skip_rows,
+            "encoding": self.encoding,
+            "na_values": list(self.null_tokens),
+            "keep_default_na": False,
+        }
+
+
+def csv_dialect() -> CsvDialect:
+    """
+    CSV dialect of the current job (defaults when the host sent none).
+    """
+    raw = os.environ.get(CSV_DIALECT_ENV)
+    if not raw:
+        return CsvDialect()
+    fields = json.loads(raw)
+    if "null_tokens" in fields:
+        fields["null_tokens"] = tuple(fields["null_tokens"])
+    return CsvDialect(**{k: v for k, v in fields.items() if k in CsvDialect._fields})
+
+
+def scratch_dir() -> str:
+    """
+    Scratch directory of the current job. Counts against the job's scratch
+    quota and is deleted when the job ends. The system temp directory when
+    run outside a worker.
+    """
+    return os.environ.get(SCRATCH_DIR_ENV) or tempfile.gettempdir()
@@ -21,10 +21,11 @@ use anyhow::{Context, Result};
 use arrow::array::RecordBatch;
 use arrow::datatypes::SchemaRef;
 use arrow::ipc::reader::StreamReader;
+use casparian_protocol::types::{CsvDialect, ResourceLimits, CSV_DIALECT_ENV};
 use casparian_protocol::JobId;
 use casparian_sinks::OutputBatch;
 use serde::Deserialize;
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::net::{TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
@@ -33,6 +34,8 @@ use thiserror::Error;
 use tracing::{debug, error, info, warn};
 
 use crate::cancel::CancellationToken;
+use crate::sandbox::{LimitExceeded, ProcessSandbox};
+use crate::scratch;
 /// Embedded Python bridge shim source code.
 /// This is baked into the binary at compile time for single-file distribution.
 const BRIDGE_SHIM_SOURCE: &str = include_str!("../shim/bridge_shim.py");
@@ -79,6 +82,8 @@ const MAX_METRICS_MESSAGE_SIZE: u32 = 1024 * 1024;
 const MAX_LOG_FILE_SIZE: usize = 10 * 1024 * 1024;
 /// Maximum log snippet size for in-memory error display (64 KB)
 const LOG_SNIPPET_BYTES: usize = 64 * 1024;
+/// How often buffered log lines are flushed so live tailing sees them
+const LOG_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
 
 /// Timeout for Python guest to connect to Unix socket
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
@@ -141,6 +146,45 @@ fn read_log_snippet(path: &Path) -> Result<String> {
     Ok(content)
 }
 
+/// Read the next chunk of a running job's log, starting at byte `offset`.
+///
+/// Returns the text and the offset just past it, or `None` if no complete line
+/// has been written since `offset`.
+pub(crate) fn read_job_log_chunk(
+    job_id: JobId,
+    offset: u64,
+    max_bytes: usize,
+) -> Result<Option<(String, u64)>> {
+    read_log_chunk(&job_log_path(job_id)?, offset, max_bytes)
+}
+
+/// Only whole lines are returned (unless one line exceeds `max_bytes`), so a
+/// chunk never ends inside a UTF-8 sequence or a partially flushed line.
+fn read_log_chunk(path: &Path, offset: u64, max_bytes: usize) -> Result<Option<(String, u64)>> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to open log file: {}", path.display()))
+        }
+    };
+    file.seek(SeekFrom::Start(offset))
+        .with_context(|| format!("Failed to seek log file: {}", path.display()))?;
+    let mut buffer = Vec::with_capacity(max_bytes.min(8192));
+    file.take(max_bytes as u64)
+        .read_to_end(&mut buffer)
+        .with_context(|| format!("Failed to read log file: {}", path.display()))?;
+
+    let end = match buffer.iter().rposition(|&b| b == b'\n') {
+        Some(pos) => pos + 1,
+        None if buffer.len() >= max_bytes => buffer.len(),
+        None => return Ok(None),
+    };
+    buffer.truncate(end);
+    let text = String::from_utf8_lossy(&buffer).into_owned();
+    Ok(Some((text, offset + end as u64)))
+}
+
 /// Streaming log writer that writes to a durable file with size cap.
 /// Memory usage is O(1) regardless of log volume - key for preventing OOM.
 struct JobLogWriter {
@@ -148,6 +192,7 @@ struct JobLogWriter {
     path: PathBuf,
     bytes_written: usize,
     truncated: bool,
+    last_flush: Instant,
 }
 
 impl JobLogWriter {
@@ -165,6 +210,7 @@ impl JobLogWriter {
             path,
             bytes_written: 0,
             truncated: false,
+            last_flush: Instant::now(),
         })
     }
 
@@ -203,6 +249,13 @@ impl JobLogWriter {
         }
 
         self.bytes_written += line_bytes.len();
+
+        if self.last_flush.elapsed() >= LOG_FLUSH_INTERVAL {
+            if let Err(e) = self.writer.flush() {
+                warn!("Failed to flush log file: {}", e);
+            }
+            self.last_flush = Instant::now();
+        }
     }
 
     /// Flush and close the log file, returning the path.
@@ -236,6 +289,10 @@ pub struct BridgeConfig {
     pub shim_path: PathBuf,
     pub inherit_stdio: bool,
     pub cancel_token: CancellationToken,
+    pub limits: ResourceLimits,
+    pub csv_dialect: Option<CsvDialect>,
+    /// Job scratch directory; the plugin inherits the worker's temp dir when None
+    pub scratch_dir: Option<PathBuf>,
 }
 
 /// Metadata about a single output from a parser
@@ -292,7 +349,12 @@ fn execute_bridge_sync(config: BridgeConfig) -> Result<BridgeResult> {
         job_id, port
     );
 
-    let mut process = spawn_guest(&config, port)?;
+    let mut sandbox = ProcessSandbox::new(&config.limits);
+    let mut process = spawn_guest(&config, port, &sandbox)?;
+    if let Err(e) = sandbox.attach(&process) {
+        cleanup_process(&mut process);
+        return Err(e.context(format!("[Job {}] Failed to apply resource limits", job_id)));
+    }
     if config.cancel_token.is_cancelled() {
         cleanup_process(&mut process);
         anyhow::bail!("[Job {}] Cancelled before guest connected", job_id);
@@ -347,6 +409,7 @@ fn execute_bridge_sync(config: BridgeConfig) -> Result<BridgeResult> {
             Ok(result) => result,
             Err(e) => {
                 let stderr_output = collect_stderr(&mut process);
+                let exceeded = sandbox.check_exit(&mut process, &stderr_output);
                 cleanup_process(&mut process);
 
                 if !stderr_output.is_empty() {
@@ -357,6 +420,10 @@ fn execute_bridge_sync(config: BridgeConfig) -> Result<BridgeResult> {
                     log_writer.write_log(log_level::STDERR, &stderr_output);
                 }
                 let logs = log_writer.read_snippet().unwrap_or_default();
+                if let Some(kind) = exceeded {
+                    let err = LimitExceeded::new(kind, sandbox.limits());
+                    return Err(anyhow::Error::new(err).context(format!("Logs:\n{}", logs)));
+                }
                 return Err(e.context(format!("Logs:\n{}", logs)));
             }
         };
@@ -376,6 +443,11 @@ fn execute_bridge_sync(config: BridgeConfig) -> Result<BridgeResult> {
             log_writer.write_log(log_level::STDERR, &stderr_output);
         }
         let logs = log_writer.read_snippet().unwrap_or_default();
+        if let Some(kind) = sandbox.exceeded(&status, &stderr_output) {
+            let err = LimitExceeded::new(kind, sandbox.limits());
+            error!("[Job {}] {}", job_id, err);
+            return Err(anyhow::Error::new(err).context(format!("Logs:\n{}", logs)));
+        }
         anyhow::bail!(
             "[Job {}] Guest process (pid={}) exited with {}: {}\n\nLogs:\n{}",
             job_id,
@@ -632,9 +704,9 @@ fn wait_for_exit(
 ///
 /// Prefers `uv run` for correct Python environment setup on all platforms.
 /// Falls back to spawning the interpreter directly if uv is unavailable.
-fn spawn_guest(config: &BridgeConfig, port: u16) -> Result<Child> {
+fn spawn_guest(config: &BridgeConfig, port: u16, sandbox: &ProcessSandbox) -> Result<Child> {
     if let Some(uv_path) = find_uv_path() {
-        return spawn_guest_with_uv(config, port, &uv_path);
+        return spawn_guest_with_uv(config, port, &uv_path, sandbox);
     }
 
     if cfg!(target_os = "macos") {
@@ -650,10 +722,15 @@ fn spawn_guest(config: &BridgeConfig, port: u16) -> Result<Child> {
         );
     }
 
-    spawn_guest_direct(config, port)
+    spawn_guest_direct(config, port, sandbox)
 }
 
-fn spawn_guest_with_uv(config: &BridgeConfig, port: u16, uv_path: &Path) -> Result<Child> {
+fn spawn_guest_with_uv(
+    config: &BridgeConfig,
+    port: u16,
+    uv_path: &Path,
+    sandbox: &ProcessSandbox,
+) -> Result<Child> {
     use base64::{engine::general_purpose, Engine as _};
     let source_b64 = general_purpose::STANDARD.encode(&config.source_code);
 
@@ -685,6 +762,12 @@ fn spawn_guest_with_uv(config: &BridgeConfig, port: u16, uv_path: &Path) -> Resu
                 "piped"
             },
         );
+    if let Some(dialect) = &config.csv_dialect {
+        cmd.env(CSV_DIALECT_ENV, serde_json::to_string(dialect)?);
+    }
+    if let Some(scratch_dir) = &config.scratch_dir {
+        scratch::configure(&mut cmd, scratch_dir);
+    }
 
     if config.inherit_stdio {
         cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
@@ -694,6 +777,8 @@ fn spawn_guest_with_uv(config: &BridgeConfig, port: u16, uv_path: &Path) -> Resu
 
     // NOTE: uv sets VIRTUAL_ENV automatically, no need to do it ourselves
 
+    sandbox.configure(&mut cmd);
+
     let child = cmd.spawn().with_context(|| {
         format!(
             "[Job {}] Failed to spawn guest process via 'uv run'. \
@@ -715,7 +800,7 @@ fn spawn_guest_with_uv(config: &BridgeConfig, port: u16, uv_path: &Path) -> Resu
     Ok(child)
 }
 
-fn spawn_guest_direct(config: &BridgeConfig, port: u16) -> Result<Child> {
+fn spawn_guest_direct(config: &BridgeConfig, port: u16, sandbox: &ProcessSandbox) -> Result<Child> {
     use base64::{engine::general_purpose, Engine as _};
     let source_b64 = general_purpose::STANDARD.encode(&config.source_code);
 
@@ -736,6 +821,12 @@ fn spawn_guest_direct(config: &BridgeConfig, port: u16) -> Result<Child> {
                 "piped"
             },
         );
+    if let Some(dialect) = &config.csv_dialect {
+        cmd.env(CSV_DIALECT_ENV, serde_json::to_string(dialect)?);
+    }
+    if let Some(scratch_dir) = &config.scratch_dir {
+        scratch::configure(&mut cmd, scratch_dir);
+    }
 
     if let Some(venv_root) = venv_root_for_interpreter(&config.interpreter_path) {
         cmd.env("VIRTUAL_ENV", venv_root);
@@ -747,6 +838,8 @@ fn spawn_guest_direct(config: &BridgeConfig, port: u16) -> Result<Child> {
         cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
     }
 
+    sandbox.configure(&mut cmd);
+
     let child = cmd.spawn().with_context(|| {
         format!(
             "[Job {}] Failed to spawn guest process directly. Interpreter: {}, Shim: {}",
@@ -1425,6 +1518,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_read_log_chunk_returns_whole_lines() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("job.log");
+        assert!(read_log_chunk(&path, 0, 1024).unwrap().is_none());
+
+        std::fs::write(&path, "[INFO] one\n[INFO] two\n[INFO] thr").unwrap();
+        let (text, next) = read_log_chunk(&path, 0, 1024).unwrap().unwrap();
+        assert_eq!(text, "[INFO] one\n[INFO] two\n");
+        assert_eq!(next, text.len() as u64);
+
+        // The partial last line is held back until it is complete
+        assert!(read_log_chunk(&path, next, 1024).unwrap().is_none());
+
+        // A chunk is capped at max_bytes, ending on a line boundary
+        let (text, next) = read_log_chunk(&path, 0, 15).unwrap().unwrap();
+        assert_eq!(text, "[INFO] one\n");
+        assert_eq!(next, 11);
+    }
+
     fn write_ipc_batch(stream: &mut TcpStream, batch: &RecordBatch) {
         let mut sink = Vec::new();
         let mut writer = StreamWriter::try_new(&mut sink, &batch.schema()).unwrap();
@@ -1,12 +1,18 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 /// Token for cooperative cancellation of job execution.
 ///
 /// Uses an AtomicBool internally. Clone is cheap and shares state.
+/// A token may also carry a deadline, after which it reads as cancelled;
+/// this is how wall-clock timeouts reach the runtimes. A stop flag does the
+/// same for limits watched by another thread (the scratch space quota).
 #[derive(Debug, Clone, Default)]
 pub struct CancellationToken {
     cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+    stop: Option<Arc<AtomicBool>>,
 }
 
 impl CancellationToken {
@@ -14,12 +20,49 @@ impl CancellationToken {
     pub fn new() -> Self {
         Self {
             cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: None,
+            stop: None,
         }
     }
 
-    /// Check if cancellation has been requested.
+    /// A token sharing this token's cancellation that also fires at `deadline`.
+    ///
+    /// Expiry does not cancel the original token.
+    pub fn with_deadline(&self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self.clone()
+        }
+    }
+
+    /// A token sharing this token's cancellation that also fires once `stop`
+    /// is set.
+    ///
+    /// Setting `stop` does not cancel the original token.
+    pub fn with_stop_flag(&self, stop: Arc<AtomicBool>) -> Self {
+        Self {
+            stop: Some(stop),
+            ..self.clone()
+        }
+    }
+
+    /// Check if cancellation has been requested, the deadline has passed or
+    /// the stop flag is set.
     pub fn is_cancelled(&self) -> bool {
-        self.cancelled.load(Ordering::SeqCst)
+        self.cancelled.load(Ordering::SeqCst) || self.is_expired() || self.is_stopped()
+    }
+
+    /// Check if the stop flag is set.
+    pub fn is_stopped(&self) -> bool {
+        self.stop
+            .as_ref()
+            .is_some_and(|stop| stop.load(Ordering::SeqCst))
+    }
+
+    /// Check if the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
     }
 
     /// Request cancellation.
@@ -27,3 +70,26 @@ impl CancellationToken {
         self.cancelled.store(true, Ordering::SeqCst);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_deadline_token() {
+        let token = CancellationToken::new();
+        let timed = token.with_deadline(Instant::now() + Duration::from_secs(3600));
+        assert!(!timed.is_cancelled());
+
+        token.cancel();
+        assert!(timed.is_cancelled());
+        assert!(!timed.is_expired());
+
+        let token = CancellationToken::new();
+        let expired = token.with_deadline(Instant::now());
+        assert!(expired.is_cancelled());
+        assert!(expired.is_expired());
+        assert!(!token.is_cancelled());
+    }
+}
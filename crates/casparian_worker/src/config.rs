@@ -0,0 +1,386 @@
+//! Layered worker configuration.
+//!
+//! The effective [`WorkerSettings`] are built from, lowest precedence first:
+//! built-in defaults, a `worker.toml` file, `CASPARIAN_WORKER_*` environment
+//! variables and command-line flags. The file is `--config` (or
+//! `CASPARIAN_WORKER_CONFIG`) when given, else `worker.toml` in the Casparian
+//! home directory if it exists. Every environment variable has a matching
+//! flag (see [`WorkerArgs`](crate::WorkerArgs)); `--print-config` prints the
+//! merged settings as TOML.
+//!
+//! ```toml
+//! connect = "tcp://10.0.0.5:5555"
+//! capabilities = ["python3.11", "duckdb"]
+//!
+//! [runtime]
+//! venvs_dir = "/var/cache/casparian/venvs"
+//! scratch_dir = "/mnt/scratch/casparian"
+//!
+//! [limits]
+//! timeout_secs = 3600
+//! max_memory_bytes = 8589934592
+//! max_scratch_bytes = 21474836480
+//!
+//! [sinks]
+//! output = "/data/output"
+//! encode_threads = 4
+//!
+//! [heartbeat]
+//! interval_secs = 15
+//!
+//! [reconnect]
+//! interval_ms = 500
+//! max_interval_ms = 60000
+//!
+//! [health]
+//! listen = "127.0.0.1:9465"
+//! min_free_bytes = 5368709120
+//! ```
+
+use anyhow::{bail, Context, Result};
+use casparian_protocol::types::ResourceLimits;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::health::DEFAULT_MIN_FREE_BYTES;
+use crate::native_runtime::{BatchSizing, DEFAULT_BATCH_BYTES, DEFAULT_BATCH_ROWS};
+use crate::worker::{WorkerConfig, WorkerCurveConfig};
+
+/// Name of the worker config file in the Casparian home directory.
+pub const CONFIG_FILE_NAME: &str = "worker.toml";
+/// Default Parquet output directory.
+pub const DEFAULT_OUTPUT_DIR: &str = "output";
+/// Default interval between heartbeats to the Sentinel.
+pub const DEFAULT_HEARTBEAT_INTERVAL_SECS: u64 = 30;
+/// Default delay before reconnecting to the Sentinel.
+pub const DEFAULT_RECONNECT_INTERVAL_MS: u64 = 100;
+/// Default cap of the reconnect delay, which doubles per failed attempt.
+pub const DEFAULT_RECONNECT_MAX_INTERVAL_MS: u64 = 30_000;
+
+/// Effective worker settings, as read from `worker.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkerSettings {
+    /// Sentinel address (tcp://, ipc://, or grpc://host:port)
+    pub connect: String,
+    /// Auto-generated when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker_id: Option<String>,
+    /// Runtime/feature tags; empty accepts every plugin
+    pub capabilities: Vec<String>,
+    pub runtime: RuntimeConfig,
+    /// Limits for jobs whose plugin sets none
+    pub limits: ResourceLimits,
+    pub sinks: SinkDefaults,
+    pub heartbeat: HeartbeatConfig,
+    pub reconnect: ReconnectPolicy,
+    pub security: SecurityConfig,
+    pub health: HealthConfig,
+}
+
+impl Default for WorkerSettings {
+    fn default() -> Self {
+        Self {
+            connect: casparian_protocol::defaults::DEFAULT_SENTINEL_BIND_ADDR.to_string(),
+            worker_id: None,
+            capabilities: Vec::new(),
+            runtime: RuntimeConfig::default(),
+            limits: ResourceLimits::default(),
+            sinks: SinkDefaults::default(),
+            heartbeat: HeartbeatConfig::default(),
+            reconnect: ReconnectPolicy::default(),
+            security: SecurityConfig::default(),
+            health: HealthConfig::default(),
+        }
+    }
+}
+
+/// Plugin runtime paths and output batch sizing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    /// Python bridge shim; the embedded one is written out when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shim_path: Option<PathBuf>,
+    /// Plugin env cache; ~/.casparian_flow/venvs when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venvs_dir: Option<PathBuf>,
+    /// Root of per-job scratch directories; casparian-scratch in the system
+    /// temp dir when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scratch_dir: Option<PathBuf>,
+    pub batch_rows: usize,
+    pub batch_bytes: usize,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            shim_path: None,
+            venvs_dir: None,
+            scratch_dir: None,
+            batch_rows: DEFAULT_BATCH_ROWS,
+            batch_bytes: DEFAULT_BATCH_BYTES,
+        }
+    }
+}
+
+/// Defaults of the sinks jobs write to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SinkDefaults {
+    /// Parquet output directory of jobs without a sink URI
+    pub output: PathBuf,
+    /// Threads each job's Parquet/CSV sinks may encode on (1 = serial)
+    pub encode_threads: usize,
+}
+
+impl Default for SinkDefaults {
+    fn default() -> Self {
+        Self {
+            output: PathBuf::from(DEFAULT_OUTPUT_DIR),
+            encode_threads: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeartbeatConfig {
+    pub interval_secs: u64,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: DEFAULT_HEARTBEAT_INTERVAL_SECS,
+        }
+    }
+}
+
+/// How the worker reconnects after losing the Sentinel: after `interval_ms`,
+/// doubling per failed attempt up to `max_interval_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReconnectPolicy {
+    pub interval_ms: u64,
+    pub max_interval_ms: u64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            interval_ms: DEFAULT_RECONNECT_INTERVAL_MS,
+            max_interval_ms: DEFAULT_RECONNECT_MAX_INTERVAL_MS,
+        }
+    }
+}
+
+/// CurveZMQ transport security.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityConfig {
+    /// Sentinel CURVE public key (Z85); the connection is plaintext when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_key: Option<String>,
+    /// Name of this worker's key pair under ~/.casparian_flow/keys
+    pub key_name: String,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            server_key: None,
+            key_name: casparian_protocol::keys::WORKER_KEY_NAME.to_string(),
+        }
+    }
+}
+
+/// Health and readiness probes; see [`crate::health`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    /// `host:port` or `unix:/path` to serve probes on; no probes when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen: Option<String>,
+    /// Free space below which the output directory degrades the worker
+    pub min_free_bytes: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            listen: None,
+            min_free_bytes: DEFAULT_MIN_FREE_BYTES,
+        }
+    }
+}
+
+/// `worker.toml` in the Casparian home directory.
+pub fn default_config_path() -> PathBuf {
+    casparian_protocol::casparian_home().join(CONFIG_FILE_NAME)
+}
+
+impl WorkerSettings {
+    /// Read settings from a TOML file; unset keys keep their defaults.
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read worker config {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("Invalid worker config {}", path.display()))
+    }
+
+    /// The settings of `path`, else of the default config file if it exists,
+    /// else the defaults.
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::load(path),
+            None => {
+                let path = default_config_path();
+                if path.is_file() {
+                    Self::load(&path)
+                } else {
+                    Ok(Self::default())
+                }
+            }
+        }
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("Failed to serialize worker config")
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.connect.trim().is_empty() {
+            bail!("connect must not be empty");
+        }
+        if self.runtime.batch_rows == 0 || self.runtime.batch_bytes == 0 {
+            bail!("runtime.batch_rows and runtime.batch_bytes must be positive");
+        }
+        if self.sinks.encode_threads == 0 {
+            bail!("sinks.encode_threads must be at least 1");
+        }
+        if self.heartbeat.interval_secs == 0 {
+            bail!("heartbeat.interval_secs must be positive");
+        }
+        let reconnect = &self.reconnect;
+        if reconnect.interval_ms == 0 || reconnect.max_interval_ms < reconnect.interval_ms {
+            bail!("reconnect.interval_ms must be positive and at most reconnect.max_interval_ms");
+        }
+        if reconnect.max_interval_ms > i32::MAX as u64 {
+            bail!("reconnect.max_interval_ms must be at most {}", i32::MAX);
+        }
+        if let Some(listen) = &self.health.listen {
+            if listen.trim().is_empty() {
+                bail!("health.listen must not be empty");
+            }
+        }
+        if let Some(server_key) = &self.security.server_key {
+            if !casparian_protocol::keys::is_valid_z85_key(server_key) {
+                bail!("security.server_key must be a 40-character Z85 CURVE public key");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn batch_sizing(&self) -> BatchSizing {
+        BatchSizing {
+            target_rows: self.runtime.batch_rows,
+            target_bytes: self.runtime.batch_bytes,
+        }
+    }
+
+    /// CURVE client settings; loads the key pair named by `key_name`.
+    pub fn curve_config(&self) -> Result<Option<WorkerCurveConfig>> {
+        let Some(server_key) = &self.security.server_key else {
+            return Ok(None);
+        };
+        let key_name = &self.security.key_name;
+        let keypair =
+            casparian_protocol::keys::load_keypair(&casparian_protocol::keys::keys_dir(), key_name)
+                .map_err(|err| {
+                    anyhow::anyhow!("{} (run `casparian keygen --name {}`)", err, key_name)
+                })?;
+        Ok(Some(WorkerCurveConfig {
+            server_public_key: server_key.clone(),
+            keypair,
+        }))
+    }
+
+    /// Validate and build the worker config. Writes out the embedded bridge
+    /// shim unless `runtime.shim_path` is set.
+    pub fn into_config(self, worker_id: String) -> Result<WorkerConfig> {
+        self.validate()?;
+        let curve = self.curve_config()?;
+        let batch_sizing = self.batch_sizing();
+        let shim_path = match &self.runtime.shim_path {
+            Some(path) => path.clone(),
+            None => crate::bridge::materialize_bridge_shim().map_err(|e| anyhow::anyhow!(e))?,
+        };
+        let mut config = WorkerConfig::new(self.connect, self.sinks.output, worker_id, shim_path)
+            .with_capabilities(self.capabilities)
+            .with_batch_sizing(batch_sizing)
+            .with_encode_threads(self.sinks.encode_threads)
+            .with_default_limits(self.limits)
+            .with_heartbeat_interval(Duration::from_secs(self.heartbeat.interval_secs))
+            .with_reconnect(self.reconnect)
+            .with_health(self.health);
+        if let Some(venvs_dir) = self.runtime.venvs_dir {
+            config = config.with_venvs_dir(venvs_dir);
+        }
+        if let Some(scratch_dir) = self.runtime.scratch_dir {
+            config = config.with_scratch_dir(scratch_dir);
+        }
+        if let Some(curve) = curve {
+            config = config.with_curve(curve);
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_file_keeps_defaults() {
+        let settings: WorkerSettings = toml::from_str(
+            r#"
+            connect = "tcp://10.0.0.5:5555"
+
+            [limits]
+            timeout_secs = 60
+
+            [reconnect]
+            max_interval_ms = 5000
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.connect, "tcp://10.0.0.5:5555");
+        assert_eq!(settings.limits.timeout_secs, Some(60));
+        assert_eq!(
+            settings.reconnect.interval_ms,
+            DEFAULT_RECONNECT_INTERVAL_MS
+        );
+        assert_eq!(settings.reconnect.max_interval_ms, 5000);
+        assert_eq!(settings.sinks, SinkDefaults::default());
+        settings.validate().unwrap();
+
+        let printed = settings.to_toml().unwrap();
+        let reparsed: WorkerSettings = toml::from_str(&printed).unwrap();
+        assert_eq!(reparsed, settings);
+    }
+
+    #[test]
+    fn test_unknown_keys_and_bad_values_rejected() {
+        assert!(toml::from_str::<WorkerSettings>("[sinks]\nencode_thread = 4\n").is_err());
+
+        let mut settings = WorkerSettings::default();
+        settings.reconnect.max_interval_ms = 10;
+        assert!(settings.validate().is_err());
+
+        let mut settings = WorkerSettings::default();
+        settings.sinks.encode_threads = 0;
+        assert!(settings.validate().is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! Column constraint enforcement.
+//!
+//! Runs after schema validation on an output's batches. Each row that breaks a
+//! `ColumnConstraint` gets a `constraint: ...` message merged into its
+//! `_cf_row_error`, so the normal quarantine split and policy apply. Violations
+//! are also aggregated per column for `JobDiagnostics`.
+
+use anyhow::{Context, Result};
+use arrow::array::{Array, Float64Array};
+use arrow::compute::cast;
+use arrow::datatypes::DataType as ArrowDataType;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use casparian_protocol::types::{ColumnConstraint, SchemaDefinition};
+use casparian_protocol::{ViolationSummary, ViolationType};
+use regex::Regex;
+use std::collections::HashSet;
+
+use crate::schema_validation::{append_error, merge_error_column};
+
+/// Sample values kept per column summary.
+const MAX_SAMPLES: usize = 3;
+
+pub(crate) struct ConstraintOutcome {
+    /// Input batches with constraint errors merged into `_cf_row_error`
+    pub batches: Vec<RecordBatch>,
+    /// One summary per column with at least one violation, in schema order
+    pub violations: Vec<ViolationSummary>,
+}
+
+/// A constraint compiled once per output.
+enum Check<'a> {
+    NotNull,
+    Unique(HashSet<String>),
+    Range {
+        min: Option<f64>,
+        max: Option<f64>,
+        label: String,
+    },
+    Regex(Regex),
+    Enum(&'a [String]),
+}
+
+struct ColumnChecks<'a> {
+    name: &'a str,
+    checks: Vec<Check<'a>>,
+    summary: ViolationSummary,
+}
+
+pub(crate) fn enforce_constraints(
+    batches: &[RecordBatch],
+    schema_def: &SchemaDefinition,
+) -> Result<ConstraintOutcome> {
+    let mut columns = Vec::new();
+    for column in &schema_def.columns {
+        if column.constraints.is_empty() {
+            continue;
+        }
+        let mut checks = Vec::with_capacity(column.constraints.len());
+        for constraint in &column.constraints {
+            checks.push(compile(&column.name, constraint)?);
+        }
+        columns.push(ColumnChecks {
+            name: &column.name,
+            checks,
+            summary: ViolationSummary {
+                violation_type: ViolationType::ConstraintViolated,
+                column_name: Some(column.name.clone()),
+                count: 0,
+                samples: Vec::new(),
+            },
+        });
+    }
+
+    if columns.is_empty() {
+        return Ok(ConstraintOutcome {
+            batches: batches.to_vec(),
+            violations: Vec::new(),
+        });
+    }
+
+    let mut checked = Vec::with_capacity(batches.len());
+    for batch in batches {
+        checked.push(check_batch(batch, &mut columns)?);
+    }
+
+    let violations = columns
+        .into_iter()
+        .map(|column| column.summary)
+        .filter(|summary| summary.count > 0)
+        .collect();
+    Ok(ConstraintOutcome {
+        batches: checked,
+        violations,
+    })
+}
+
+fn compile<'a>(column: &str, constraint: &'a ColumnConstraint) -> Result<Check<'a>> {
+    Ok(match constraint {
+        ColumnConstraint::NotNull => Check::NotNull,
+        ColumnConstraint::Unique => Check::Unique(HashSet::new()),
+        ColumnConstraint::Range { min, max } => Check::Range {
+            min: *min,
+            max: *max,
+            label: constraint.to_string(),
+        },
+        ColumnConstraint::Regex { pattern } => Check::Regex(
+            Regex::new(pattern)
+                .with_context(|| format!("invalid regex constraint on '{}'", column))?,
+        ),
+        ColumnConstraint::Enum { values } => Check::Enum(values),
+    })
+}
+
+fn check_batch(batch: &RecordBatch, columns: &mut [ColumnChecks<'_>]) -> Result<RecordBatch> {
+    let mut row_errors = vec![String::new(); batch.num_rows()];
+    let mut has_errors = false;
+
+    for column in columns.iter_mut() {
+        let array = batch
+            .column_by_name(column.name)
+            .with_context(|| format!("constraint column '{}' missing from output", column.name))?;
+        let text = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default())?;
+        let numeric = if column
+            .checks
+            .iter()
+            .any(|check| matches!(check, Check::Range { .. }))
+        {
+            Some(as_f64(array.as_ref(), column.name)?)
+        } else {
+            None
+        };
+
+        for (row, row_error) in row_errors.iter_mut().enumerate() {
+            let value = (!array.is_null(row)).then(|| text.value(row).to_string());
+            for check in column.checks.iter_mut() {
+                let Some(reason) = violation(check, value.as_deref(), numeric.as_ref(), row) else {
+                    continue;
+                };
+                append_error(
+                    row_error,
+                    &format!("constraint: '{}' {}", column.name, reason),
+                );
+                has_errors = true;
+                column.summary.count += 1;
+                if column.summary.samples.len() < MAX_SAMPLES {
+                    let shown = value.as_deref().unwrap_or("null");
+                    column
+                        .summary
+                        .samples
+                        .push(format!("{} ({})", shown, reason));
+                }
+                // One violation per column per row is enough to quarantine it.
+                break;
+            }
+        }
+    }
+
+    if has_errors {
+        merge_error_column(batch, &row_errors)
+    } else {
+        Ok(batch.clone())
+    }
+}
+
+fn violation(
+    check: &mut Check<'_>,
+    value: Option<&str>,
+    numeric: Option<&Float64Array>,
+    row: usize,
+) -> Option<String> {
+    match check {
+        Check::NotNull => value.is_none().then(|| "is null".to_string()),
+        Check::Unique(seen) => {
+            let value = value?;
+            (!seen.insert(value.to_string())).then(|| "is not unique".to_string())
+        }
+        Check::Range { min, max, label } => {
+            let numeric = numeric?;
+            if numeric.is_null(row) {
+                return None;
+            }
+            let number = numeric.value(row);
+            let below = min.is_some_and(|min| number < min);
+            let above = max.is_some_and(|max| number > max);
+            (below || above).then(|| format!("outside {}", label))
+        }
+        Check::Regex(regex) => {
+            let value = value?;
+            (!regex.is_match(value)).then(|| format!("does not match /{}/", regex.as_str()))
+        }
+        Check::Enum(values) => {
+            let value = value?;
+            (!values.iter().any(|allowed| allowed == value))
+                .then(|| "is not an allowed value".to_string())
+        }
+    }
+}
+
+fn as_f64(array: &dyn Array, column: &str) -> Result<Float64Array> {
+    let cast = cast(array, &ArrowDataType::Float64)
+        .with_context(|| format!("range constraint on non-numeric column '{}'", column))?;
+    cast.as_any()
+        .downcast_ref::<Float64Array>()
+        .cloned()
+        .with_context(|| format!("range cast produced unexpected type for '{}'", column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use casparian_protocol::types::SchemaColumnSpec;
+    use casparian_protocol::DataType;
+    use std::sync::Arc;
+
+    fn column(
+        name: &str,
+        data_type: DataType,
+        constraints: Vec<ColumnConstraint>,
+    ) -> SchemaColumnSpec {
+        SchemaColumnSpec {
+            name: name.to_string(),
+            data_type,
+            nullable: true,
+            format: None,
+            constraints,
+        }
+    }
+
+    fn batch(ids: Vec<Option<i64>>, codes: Vec<Option<&str>>) -> RecordBatch {
+        RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("id", ArrowDataType::Int64, true),
+                Field::new("code", ArrowDataType::Utf8, true),
+            ])),
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(StringArray::from(codes)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn row_errors(batch: &RecordBatch) -> Vec<Option<String>> {
+        let errors = batch
+            .column_by_name("_cf_row_error")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        (0..errors.len())
+            .map(|row| (!errors.is_null(row)).then(|| errors.value(row).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_no_constraints_passes_batches_through() {
+        let schema = SchemaDefinition {
+            columns: vec![column("id", DataType::Int64, vec![])],
+        };
+        let input = batch(vec![Some(1)], vec![Some("A")]);
+        let outcome = enforce_constraints(&[input], &schema).unwrap();
+        assert!(outcome.violations.is_empty());
+        assert!(outcome.batches[0].column_by_name("_cf_row_error").is_none());
+    }
+
+    #[test]
+    fn test_constraints_mark_rows_and_summarize() {
+        let schema = SchemaDefinition {
+            columns: vec![
+                column(
+                    "id",
+                    DataType::Int64,
+                    vec![
+                        ColumnConstraint::NotNull,
+                        ColumnConstraint::Unique,
+                        ColumnConstraint::Range {
+                            min: Some(0.0),
+                            max: Some(100.0),
+                        },
+                    ],
+                ),
+                column(
+                    "code",
+                    DataType::String,
+                    vec![
+                        ColumnConstraint::Regex {
+                            pattern: "^[A-Z]+$".to_string(),
+                        },
+                        ColumnConstraint::Enum {
+                            values: vec!["AB".to_string(), "CD".to_string()],
+                        },
+                    ],
+                ),
+            ],
+        };
+        let first = batch(
+            vec![Some(1), Some(2), None],
+            vec![Some("AB"), Some("cd"), Some("CD")],
+        );
+        // Unique spans batches: id 1 repeats here.
+        let second = batch(vec![Some(1), Some(500)], vec![Some("XY"), None]);
+
+        let outcome = enforce_constraints(&[first, second], &schema).unwrap();
+
+        let first_errors = row_errors(&outcome.batches[0]);
+        assert!(first_errors[0].is_none());
+        assert!(first_errors[1]
+            .as_deref()
+            .unwrap()
+            .contains("'code' does not match"));
+        assert!(first_errors[2].as_deref().unwrap().contains("'id' is null"));
+
+        let second_errors = row_errors(&outcome.batches[1]);
+        let duplicate = second_errors[0].as_deref().unwrap();
+        assert!(duplicate.contains("'id' is not unique"), "{}", duplicate);
+        assert!(duplicate.contains("'code' is not an allowed value"));
+        assert!(second_errors[1]
+            .as_deref()
+            .unwrap()
+            .contains("outside range[0..100]"));
+
+        assert_eq!(outcome.violations.len(), 2);
+        let id = &outcome.violations[0];
+        assert_eq!(id.column_name.as_deref(), Some("id"));
+        assert_eq!(id.violation_type, ViolationType::ConstraintViolated);
+        assert_eq!(id.count, 3);
+        assert_eq!(id.samples.len(), MAX_SAMPLES);
+        assert_eq!(outcome.violations[1].count, 2);
+    }
+}
@@ -0,0 +1,250 @@
+//! Transparent decompression of compressed job inputs.
+//!
+//! gzip, zstd, and bzip2 inputs are recognized by their magic bytes and
+//! streamed into a temp file that the parser reads in place of the original.
+//! The original path stays the job's input (lineage, `source_hash`); the hash
+//! of the decompressed bytes is reported separately as `decompressed_hash`.
+//!
+//! Decompression stops with `LimitExceeded` once the output passes the job's
+//! `max_decompressed_bytes`, so a decompression bomb cannot fill the disk.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use casparian_protocol::{LimitKind, ResourceLimits};
+
+use crate::cancel::CancellationToken;
+use crate::sandbox::LimitExceeded;
+
+/// Extensions stripped from the temp file name, so `trades.csv.gz` is handed
+/// to the parser as a `.csv` file.
+const COMPRESSED_EXTENSIONS: &[&str] = &["gz", "gzip", "zst", "zstd", "bz2", "bzip2"];
+
+const COPY_CHUNK_BYTES: usize = 64 * 1024;
+
+/// A compression format the worker decompresses transparently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Recognize a compressed stream from its first bytes.
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        if head.starts_with(&[0x1f, 0x8b]) {
+            Some(Compression::Gzip)
+        } else if head.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Compression::Zstd)
+        } else if head.starts_with(b"BZh") && head.get(3).is_some_and(u8::is_ascii_digit) {
+            Some(Compression::Bzip2)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Bzip2 => "bzip2",
+        }
+    }
+
+    fn decoder(&self, input: BufReader<File>) -> Result<Box<dyn Read>> {
+        Ok(match self {
+            // Multi-member so concatenated gzip files (e.g. rotated logs) read in full.
+            Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(input)),
+            Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(input)?),
+            Compression::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(input)),
+        })
+    }
+}
+
+/// Decompressed copy of a job input; the temp file is removed on drop.
+pub struct DecompressedInput {
+    file: tempfile::NamedTempFile,
+    pub compression: Compression,
+    /// Blake3 hash of the decompressed bytes
+    pub hash: String,
+    pub bytes: u64,
+}
+
+impl DecompressedInput {
+    /// Path the parser should read.
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+
+/// Compression format of the file at `path`, if any.
+pub fn sniff_compression(path: &Path) -> Result<Option<Compression>> {
+    let mut head = Vec::with_capacity(4);
+    File::open(path)
+        .with_context(|| format!("Failed to open input {}", path.display()))?
+        .take(4)
+        .read_to_end(&mut head)?;
+    Ok(Compression::detect(&head))
+}
+
+/// Decompress `input_path` into a temp file if it is compressed.
+///
+/// Returns `None` for uncompressed inputs. Fails with `LimitExceeded`
+/// (`LimitKind::DecompressedSize`) when the output would exceed
+/// `limits.decompressed_limit()`.
+pub fn decompress_input(
+    input_path: &Path,
+    limits: &ResourceLimits,
+    cancel_token: &CancellationToken,
+) -> Result<Option<DecompressedInput>> {
+    let Some(compression) = sniff_compression(input_path)? else {
+        return Ok(None);
+    };
+    let input = File::open(input_path)
+        .with_context(|| format!("Failed to open input {}", input_path.display()))?;
+    let mut decoder = compression.decoder(BufReader::new(input))?;
+
+    let mut output = tempfile::Builder::new()
+        .prefix("casparian-decompressed-")
+        .suffix(&inner_suffix(input_path))
+        .tempfile()
+        .context("Failed to create decompressed input file")?;
+    let limit = limits.decompressed_limit();
+    let mut hasher = blake3::Hasher::new();
+    let mut bytes = 0u64;
+    let mut buffer = vec![0u8; COPY_CHUNK_BYTES];
+    loop {
+        if cancel_token.is_cancelled() {
+            anyhow::bail!("Decompression of {} cancelled", input_path.display());
+        }
+        let read = decoder.read(&mut buffer).with_context(|| {
+            format!(
+                "Failed to decompress {} input {}",
+                compression.as_str(),
+                input_path.display()
+            )
+        })?;
+        if read == 0 {
+            break;
+        }
+        bytes += read as u64;
+        if bytes > limit {
+            return Err(LimitExceeded::new(LimitKind::DecompressedSize, limits).into());
+        }
+        hasher.update(&buffer[..read]);
+        output.as_file_mut().write_all(&buffer[..read])?;
+    }
+    output.as_file_mut().flush()?;
+
+    Ok(Some(DecompressedInput {
+        file: output,
+        compression,
+        hash: hasher.finalize().to_hex().to_string(),
+        bytes,
+    }))
+}
+
+/// Extension the input has under its compression suffix (".csv" for
+/// `trades.csv.gz`), or "" when there is none.
+fn inner_suffix(input_path: &Path) -> String {
+    let inner = match input_path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if COMPRESSED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()) => {
+            input_path.file_stem().map(Path::new)
+        }
+        _ => Some(input_path),
+    };
+    inner
+        .and_then(|path| path.extension())
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_compressed(
+        dir: &Path,
+        name: &str,
+        compression: Compression,
+        data: &[u8],
+    ) -> std::path::PathBuf {
+        let path = dir.join(name);
+        let file = File::create(&path).unwrap();
+        match compression {
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(file, flate2::Compression::default());
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap();
+            }
+            Compression::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(file, 0).unwrap();
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap();
+            }
+            Compression::Bzip2 => {
+                let mut encoder = bzip2::write::BzEncoder::new(file, bzip2::Compression::default());
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap();
+            }
+        }
+        path
+    }
+
+    #[test]
+    fn test_decompresses_each_format_with_inner_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = b"id,name\n1,a\n2,b\n".repeat(100);
+        let expected_hash = blake3::hash(&data).to_hex().to_string();
+        for (name, compression) in [
+            ("trades.csv.gz", Compression::Gzip),
+            ("trades.csv.zst", Compression::Zstd),
+            ("trades.csv.bz2", Compression::Bzip2),
+        ] {
+            let path = write_compressed(dir.path(), name, compression, &data);
+            let input =
+                decompress_input(&path, &ResourceLimits::default(), &CancellationToken::new())
+                    .unwrap()
+                    .unwrap();
+            assert_eq!(input.compression, compression);
+            assert_eq!(input.hash, expected_hash);
+            assert_eq!(input.bytes, data.len() as u64);
+            assert_eq!(std::fs::read(input.path()).unwrap(), data);
+            assert_eq!(input.path().extension().unwrap(), "csv");
+        }
+
+        let plain = dir.path().join("plain.csv");
+        std::fs::write(&plain, &data).unwrap();
+        assert!(decompress_input(
+            &plain,
+            &ResourceLimits::default(),
+            &CancellationToken::new()
+        )
+        .unwrap()
+        .is_none());
+    }
+
+    #[test]
+    fn test_decompressed_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_compressed(
+            dir.path(),
+            "zeros.gz",
+            Compression::Gzip,
+            &vec![0u8; 1024 * 1024],
+        );
+        let limits = ResourceLimits {
+            max_decompressed_bytes: Some(64 * 1024),
+            ..Default::default()
+        };
+        let err = decompress_input(&path, &limits, &CancellationToken::new())
+            .err()
+            .unwrap();
+        let exceeded = err.downcast_ref::<LimitExceeded>().unwrap();
+        assert_eq!(exceeded.kind, LimitKind::DecompressedSize);
+    }
+}
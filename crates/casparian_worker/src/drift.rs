@@ -0,0 +1,159 @@
+//! Source columns dropped on the way to the sinks.
+//!
+//! A locked contract checks what a parser emits, not what the input holds, so
+//! a new column in a source CSV passes unnoticed when the parser ignores it.
+//! The header of a delimited input is compared with the columns of every
+//! output; source columns no output carries are reported as unmapped in
+//! `JobDiagnostics::schema_drift`.
+//!
+//! Parsers commonly rename columns (`Order ID` -> `order_id`), so names are
+//! compared case-insensitively on their letters and digits only.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use casparian_protocol::CsvDialect;
+
+/// Dialect of a delimited input: the routed dialect, else the default one
+/// for `.csv` / `.tsv` files. Other inputs have no header to compare.
+pub(crate) fn input_dialect(path: &Path, routed: Option<&CsvDialect>) -> Option<CsvDialect> {
+    if let Some(dialect) = routed {
+        return Some(dialect.clone());
+    }
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let delimiter = match extension.as_str() {
+        "csv" => ',',
+        "tsv" => '\t',
+        _ => return None,
+    };
+    Some(CsvDialect {
+        delimiter,
+        ..CsvDialect::default()
+    })
+}
+
+/// Column names from the header row of a UTF-8 delimited file, after the
+/// dialect's `skip_rows`. None for an empty file.
+pub(crate) fn read_csv_header(path: &Path, dialect: &CsvDialect) -> Result<Option<Vec<String>>> {
+    let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut lines = BufReader::new(file).lines().skip(dialect.skip_rows);
+    for line in lines.by_ref() {
+        let line = line.with_context(|| format!("read header of {}", path.display()))?;
+        let line = line.trim_start_matches('\u{feff}').trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        return Ok(Some(split_header(line, dialect)));
+    }
+    Ok(None)
+}
+
+/// Source columns with no counterpart among `emitted`, in header order.
+pub(crate) fn unmapped_columns<'a>(
+    source: &[String],
+    emitted: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let emitted: HashSet<String> = emitted.into_iter().map(normalize).collect();
+    let mut seen = HashSet::new();
+    source
+        .iter()
+        .filter(|name| {
+            let key = normalize(name);
+            !key.is_empty() && !emitted.contains(&key) && seen.insert(key)
+        })
+        .cloned()
+        .collect()
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Unquoted, trimmed fields of a header line.
+fn split_header(line: &str, dialect: &CsvDialect) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted && Some(c) == dialect.escape {
+            if let Some(next) = chars.next() {
+                field.push(next);
+            }
+        } else if c == dialect.quote {
+            if quoted && chars.peek() == Some(&dialect.quote) {
+                field.push(c);
+                chars.next();
+            } else {
+                quoted = !quoted;
+            }
+        } else if c == dialect.delimiter && !quoted {
+            fields.push(field.trim().to_string());
+            field.clear();
+        } else {
+            field.push(c);
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_read_header_after_skipped_rows() {
+        let mut file = tempfile::Builder::new().suffix(".csv").tempfile().unwrap();
+        write!(
+            file,
+            "Export of 2026-10-01\r\n\r\n\u{feff}\"Order ID\";\"Note; free text\";Amount\r\n1;x;2\r\n"
+        )
+        .unwrap();
+        let dialect = CsvDialect {
+            delimiter: ';',
+            skip_rows: 1,
+            ..CsvDialect::default()
+        };
+        let header = read_csv_header(file.path(), &dialect).unwrap();
+        assert_eq!(
+            header,
+            Some(names(&["Order ID", "Note; free text", "Amount"]))
+        );
+    }
+
+    #[test]
+    fn test_unmapped_columns_ignore_renames() {
+        let source = names(&["Order ID", "Amount", "Discount Code", "", "amount"]);
+        let unmapped = unmapped_columns(&source, ["order_id", "AMOUNT"]);
+        assert_eq!(unmapped, names(&["Discount Code"]));
+    }
+
+    #[test]
+    fn test_input_dialect_by_extension() {
+        assert_eq!(
+            input_dialect(Path::new("/in/a.TSV"), None).map(|d| d.delimiter),
+            Some('\t')
+        );
+        assert!(input_dialect(Path::new("/in/a.parquet"), None).is_none());
+        let routed = CsvDialect {
+            delimiter: '|',
+            ..CsvDialect::default()
+        };
+        assert_eq!(
+            input_dialect(Path::new("/in/a.txt"), Some(&routed)).map(|d| d.delimiter),
+            Some('|')
+        );
+    }
+}
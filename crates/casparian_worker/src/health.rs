@@ -0,0 +1,497 @@
+//! Health and readiness probes for systemd, Kubernetes and load balancers.
+//!
+//! With `health.listen` set (`host:port`, or `unix:/path` for a Unix
+//! socket), the worker answers plain HTTP on that address:
+//!
+//! - `GET /healthz` - liveness: 200 while the event loop is turning, 503 once
+//!   it has been stuck for [`EVENT_LOOP_STALL_SECS`].
+//! - `GET /readyz` - readiness: 200 when healthy, 503 when degraded. The body
+//!   of both is the JSON [`HealthReport`].
+//!
+//! The worker is degraded while it is not connected to the Sentinel, its
+//! event loop is stuck, the plugin env cache is not writable, or the output
+//! directory has less than `health.min_free_bytes` free. Connection status
+//! comes from ZMQ socket events; for a `grpc://` Sentinel it is the
+//! connection to the local relay.
+//!
+//! `casparian worker healthcheck` queries `/readyz` and exits nonzero unless
+//! the worker is healthy.
+
+use anyhow::{bail, Context, Result};
+use casparian_protocol::JobId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::venv_manager::VenvManager;
+
+/// Seconds without an event loop pass after which the worker is stuck.
+pub const EVENT_LOOP_STALL_SECS: u64 = 30;
+/// Default free space the output directory needs (1 GiB).
+pub const DEFAULT_MIN_FREE_BYTES: u64 = 1 << 30;
+/// Prefix of Unix socket addresses.
+pub const UNIX_ADDR_PREFIX: &str = "unix:";
+
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Live state the worker's event loop shares with the probe server.
+#[derive(Debug)]
+pub struct HealthState {
+    started: Instant,
+    sentinel_connected: AtomicBool,
+    /// Milliseconds after `started` of the last event loop pass
+    last_loop_ms: AtomicU64,
+    jobs: Mutex<HashMap<JobId, RunningJob>>,
+}
+
+#[derive(Debug, Clone)]
+struct RunningJob {
+    plugin_name: String,
+    started: Instant,
+}
+
+impl Default for HealthState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthState {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            sentinel_connected: AtomicBool::new(false),
+            last_loop_ms: AtomicU64::new(0),
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_sentinel_connected(&self, connected: bool) {
+        self.sentinel_connected.store(connected, Ordering::Relaxed);
+    }
+
+    pub fn sentinel_connected(&self) -> bool {
+        self.sentinel_connected.load(Ordering::Relaxed)
+    }
+
+    /// Record a pass of the event loop.
+    pub fn tick(&self) {
+        let elapsed = self.started.elapsed().as_millis() as u64;
+        self.last_loop_ms.store(elapsed, Ordering::Relaxed);
+    }
+
+    /// Seconds since the last event loop pass.
+    pub fn event_loop_age(&self) -> Duration {
+        let last = Duration::from_millis(self.last_loop_ms.load(Ordering::Relaxed));
+        self.started.elapsed().saturating_sub(last)
+    }
+
+    pub fn job_started(&self, job_id: JobId, plugin_name: &str) {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            job_id,
+            RunningJob {
+                plugin_name: plugin_name.to_string(),
+                started: Instant::now(),
+            },
+        );
+    }
+
+    pub fn job_finished(&self, job_id: JobId) {
+        self.jobs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&job_id);
+    }
+
+    fn current_jobs(&self) -> Vec<CurrentJob> {
+        let jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        let mut current: Vec<CurrentJob> = jobs
+            .iter()
+            .map(|(job_id, job)| CurrentJob {
+                job_id: *job_id,
+                plugin_name: job.plugin_name.clone(),
+                running_secs: job.started.elapsed().as_secs_f64(),
+            })
+            .collect();
+        current.sort_by_key(|job| job.job_id);
+        current
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+}
+
+/// Body of `/healthz` and `/readyz`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub worker_id: String,
+    pub sentinel_addr: String,
+    pub sentinel_connected: bool,
+    pub event_loop_age_secs: f64,
+    /// Jobs running now
+    pub jobs: Vec<CurrentJob>,
+    pub venv_cache: VenvCacheHealth,
+    pub output: DiskHealth,
+    /// Why the worker is degraded; empty when healthy
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentJob {
+    pub job_id: JobId,
+    pub plugin_name: String,
+    pub running_secs: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VenvCacheHealth {
+    pub dir: PathBuf,
+    pub writable: bool,
+    pub envs: usize,
+    pub bytes: u64,
+    /// Without uv, only preinstalled envs can run
+    pub uv_available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskHealth {
+    pub dir: PathBuf,
+    /// None when the filesystem could not be queried
+    pub available_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub min_free_bytes: u64,
+}
+
+/// Builds health reports of one worker.
+#[derive(Clone)]
+pub struct HealthProbe {
+    pub worker_id: String,
+    pub sentinel_addr: String,
+    pub output_dir: PathBuf,
+    pub min_free_bytes: u64,
+    pub venv_manager: Arc<VenvManager>,
+    pub state: Arc<HealthState>,
+}
+
+impl HealthProbe {
+    pub fn report(&self) -> HealthReport {
+        let mut problems = Vec::new();
+        let sentinel_connected = self.state.sentinel_connected();
+        if !sentinel_connected {
+            problems.push(format!(
+                "not connected to Sentinel at {}",
+                self.sentinel_addr
+            ));
+        }
+        let event_loop_age = self.state.event_loop_age();
+        if event_loop_age >= Duration::from_secs(EVENT_LOOP_STALL_SECS) {
+            problems.push(format!(
+                "event loop stuck for {}s",
+                event_loop_age.as_secs()
+            ));
+        }
+
+        let venv_dir = self.venv_manager.venvs_dir.clone();
+        let (envs, bytes) = self.venv_manager.stats();
+        let writable = tempfile::tempfile_in(&venv_dir).is_ok();
+        if !writable {
+            problems.push(format!(
+                "plugin env cache {} is not writable",
+                venv_dir.display()
+            ));
+        }
+
+        let (available_bytes, total_bytes) = disk_space(&self.output_dir);
+        if let Some(available) = available_bytes.filter(|free| *free < self.min_free_bytes) {
+            problems.push(format!(
+                "output directory {} has {} bytes free, below {}",
+                self.output_dir.display(),
+                available,
+                self.min_free_bytes
+            ));
+        }
+
+        HealthReport {
+            status: if problems.is_empty() {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Degraded
+            },
+            worker_id: self.worker_id.clone(),
+            sentinel_addr: self.sentinel_addr.clone(),
+            sentinel_connected,
+            event_loop_age_secs: event_loop_age.as_secs_f64(),
+            jobs: self.state.current_jobs(),
+            venv_cache: VenvCacheHealth {
+                dir: venv_dir,
+                writable,
+                envs,
+                bytes,
+                uv_available: self.venv_manager.uv_available(),
+            },
+            output: DiskHealth {
+                dir: self.output_dir.clone(),
+                available_bytes,
+                total_bytes,
+                min_free_bytes: self.min_free_bytes,
+            },
+            problems,
+        }
+    }
+
+    /// Whether the event loop is turning (liveness).
+    fn alive(&self) -> bool {
+        self.state.event_loop_age() < Duration::from_secs(EVENT_LOOP_STALL_SECS)
+    }
+}
+
+/// Free and total bytes of the filesystem holding `dir`, or of its nearest
+/// existing ancestor while `dir` has not been created yet.
+fn disk_space(dir: &Path) -> (Option<u64>, Option<u64>) {
+    let Some(existing) = dir.ancestors().find(|path| path.exists()) else {
+        return (None, None);
+    };
+    // A relative dir whose ancestors are all missing resolves to "."
+    let existing = if existing.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        existing
+    };
+    (
+        fs2::available_space(existing).ok(),
+        fs2::total_space(existing).ok(),
+    )
+}
+
+/// Serve the probes on `addr` from a background thread.
+pub fn spawn_server(addr: &str, probe: HealthProbe) -> Result<()> {
+    let listener = Listener::bind(addr)?;
+    info!("Health probes listening on {}", addr);
+    std::thread::Builder::new()
+        .name("worker-health".to_string())
+        .spawn(move || listener.serve(&probe))
+        .context("Failed to start health probe thread")?;
+    Ok(())
+}
+
+enum Listener {
+    Tcp(std::net::TcpListener),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixListener),
+}
+
+impl Listener {
+    fn bind(addr: &str) -> Result<Self> {
+        if let Some(path) = addr.strip_prefix(UNIX_ADDR_PREFIX) {
+            return Self::bind_unix(path);
+        }
+        let listener = std::net::TcpListener::bind(addr)
+            .with_context(|| format!("Failed to bind health address {}", addr))?;
+        Ok(Self::Tcp(listener))
+    }
+
+    #[cfg(unix)]
+    fn bind_unix(path: &str) -> Result<Self> {
+        // A socket left by a previous run would make bind fail
+        let _ = std::fs::remove_file(path);
+        let listener = std::os::unix::net::UnixListener::bind(path)
+            .with_context(|| format!("Failed to bind health socket {}", path))?;
+        Ok(Self::Unix(listener))
+    }
+
+    #[cfg(not(unix))]
+    fn bind_unix(path: &str) -> Result<Self> {
+        bail!("Unix socket health address {} needs a Unix platform", path)
+    }
+
+    fn serve(self, probe: &HealthProbe) {
+        match self {
+            Self::Tcp(listener) => {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => {
+                            let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+                            let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+                            handle_connection(stream, probe);
+                        }
+                        Err(err) => warn!("Health probe accept failed: {}", err),
+                    }
+                }
+            }
+            #[cfg(unix)]
+            Self::Unix(listener) => {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => {
+                            let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+                            let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+                            handle_connection(stream, probe);
+                        }
+                        Err(err) => warn!("Health probe accept failed: {}", err),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn handle_connection<S: Read + Write>(mut stream: S, probe: &HealthProbe) {
+    let mut request_line = String::new();
+    if let Err(err) = BufReader::new(&mut stream).read_line(&mut request_line) {
+        debug!("Health probe read failed: {}", err);
+        return;
+    }
+    let (status, body) = respond(&request_line, probe);
+    let response = format!(
+        "HTTP/1.0 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    if let Err(err) = stream.write_all(response.as_bytes()) {
+        debug!("Health probe write failed: {}", err);
+    }
+}
+
+fn respond(request_line: &str, probe: &HealthProbe) -> (&'static str, String) {
+    let mut parts = request_line.split_whitespace();
+    let (Some("GET"), Some(path)) = (parts.next(), parts.next()) else {
+        return ("405 Method Not Allowed", String::new());
+    };
+    let report = probe.report();
+    let ok = match path {
+        "/healthz" => probe.alive(),
+        "/readyz" => report.status == HealthStatus::Healthy,
+        _ => return ("404 Not Found", String::new()),
+    };
+    let status = if ok {
+        "200 OK"
+    } else {
+        "503 Service Unavailable"
+    };
+    (status, serde_json::to_string(&report).unwrap_or_default())
+}
+
+/// Query `/readyz` of the worker serving probes on `addr`. Returns the
+/// report if the worker is healthy and an error otherwise.
+pub fn healthcheck(addr: &str) -> Result<HealthReport> {
+    let request = b"GET /readyz HTTP/1.0\r\n\r\n";
+    let response = if let Some(path) = addr.strip_prefix(UNIX_ADDR_PREFIX) {
+        request_unix(path, request)?
+    } else {
+        let socket_addr = std::net::ToSocketAddrs::to_socket_addrs(addr)
+            .with_context(|| format!("Invalid health address {}", addr))?
+            .next()
+            .with_context(|| format!("Invalid health address {}", addr))?;
+        let stream = std::net::TcpStream::connect_timeout(&socket_addr, IO_TIMEOUT)
+            .with_context(|| format!("Worker health endpoint {} unreachable", addr))?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        exchange(stream, request)?
+    };
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .context("Malformed health response")?;
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    let report: HealthReport = serde_json::from_str(body).context("Malformed health report")?;
+    if status != "200" || report.status != HealthStatus::Healthy {
+        bail!(
+            "Worker {} is degraded: {}",
+            report.worker_id,
+            report.problems.join("; ")
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(unix)]
+fn request_unix(path: &str, request: &[u8]) -> Result<String> {
+    let stream = std::os::unix::net::UnixStream::connect(path)
+        .with_context(|| format!("Worker health socket {} unreachable", path))?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    exchange(stream, request)
+}
+
+#[cfg(not(unix))]
+fn request_unix(path: &str, _request: &[u8]) -> Result<String> {
+    bail!("Unix socket health address {} needs a Unix platform", path)
+}
+
+fn exchange<S: Read + Write>(mut stream: S, request: &[u8]) -> Result<String> {
+    stream.write_all(request)?;
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .context("Failed to read health response")?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(dir: &Path) -> HealthProbe {
+        let venv_manager = VenvManager::with_path(dir.join("venvs")).unwrap();
+        HealthProbe {
+            worker_id: "w1".to_string(),
+            sentinel_addr: "tcp://127.0.0.1:5555".to_string(),
+            output_dir: dir.join("output"),
+            min_free_bytes: 0,
+            venv_manager: Arc::new(venv_manager),
+            state: Arc::new(HealthState::new()),
+        }
+    }
+
+    #[test]
+    fn test_report_degraded_until_connected() {
+        let dir = tempfile::tempdir().unwrap();
+        let probe = probe(dir.path());
+        let report = probe.report();
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert!(report.problems[0].contains("not connected"));
+        assert!(report.venv_cache.writable);
+        assert!(report.output.available_bytes.is_some());
+
+        probe.state.set_sentinel_connected(true);
+        probe.state.job_started(JobId::new(7), "orders");
+        let report = probe.report();
+        assert_eq!(
+            report.status,
+            HealthStatus::Healthy,
+            "{:?}",
+            report.problems
+        );
+        assert_eq!(report.jobs.len(), 1);
+        assert_eq!(report.jobs[0].plugin_name, "orders");
+
+        let mut starved = probe.clone();
+        starved.min_free_bytes = u64::MAX;
+        assert_eq!(starved.report().status, HealthStatus::Degraded);
+    }
+
+    #[test]
+    fn test_healthcheck_over_tcp() {
+        let dir = tempfile::tempdir().unwrap();
+        let probe = probe(dir.path());
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let state = probe.state.clone();
+        std::thread::spawn(move || Listener::Tcp(listener).serve(&probe));
+
+        let err = healthcheck(&addr).unwrap_err();
+        assert!(err.to_string().contains("degraded"));
+        state.set_sentinel_connected(true);
+        let report = healthcheck(&addr).unwrap();
+        assert_eq!(report.worker_id, "w1");
+    }
+}
@@ -16,32 +16,211 @@
 
 pub mod bridge;
 pub mod cancel;
+pub mod config;
+mod constraints;
+mod drift;
+pub mod decompress;
+pub mod health;
+mod load;
 pub mod metrics;
 pub mod native_runtime;
 pub mod runtime;
+pub mod sandbox;
+pub mod scratch;
 mod schema_validation;
+pub mod transcode;
 pub mod type_inference;
 pub mod venv_manager;
 pub mod worker;
+pub mod xlsx;
 
+pub use config::WorkerSettings;
 pub use metrics::METRICS;
-pub use worker::{Worker, WorkerConfig, WorkerError, WorkerHandle};
+pub use native_runtime::BatchSizing;
+pub use worker::{Worker, WorkerConfig, WorkerCurveConfig, WorkerError, WorkerHandle};
 
+/// Worker command line. Every setting can also come from a
+/// `CASPARIAN_WORKER_*` environment variable or `worker.toml`; see [`config`].
 #[derive(clap::Parser, Debug)]
 #[command(name = "casparian-worker", about = "Rust Worker for Casparian Flow")]
 pub struct WorkerArgs {
-    /// Sentinel address
-    #[arg(
-        long,
-        default_value_t = casparian_protocol::defaults::DEFAULT_SENTINEL_BIND_ADDR.to_string()
-    )]
-    pub connect: String,
+    /// Worker config file (default: worker.toml in the Casparian home, if present)
+    #[arg(long, env = "CASPARIAN_WORKER_CONFIG")]
+    pub config: Option<std::path::PathBuf>,
+
+    /// Print the effective configuration as TOML and exit
+    #[arg(long)]
+    pub print_config: bool,
 
-    /// Parquet output directory
-    #[arg(long, default_value = "output")]
-    pub output: std::path::PathBuf,
+    /// Sentinel address (tcp://, ipc://, or grpc://host:port with the grpc
+    /// feature) [default: tcp://127.0.0.1:5555]
+    #[arg(long, env = "CASPARIAN_WORKER_CONNECT")]
+    pub connect: Option<String>,
+
+    /// Parquet output directory [default: output]
+    #[arg(long, env = "CASPARIAN_WORKER_OUTPUT")]
+    pub output: Option<std::path::PathBuf>,
 
     /// Worker ID (auto-generated if not provided)
-    #[arg(long)]
+    #[arg(long, env = "CASPARIAN_WORKER_ID")]
     pub worker_id: Option<String>,
+
+    /// Runtime/feature tag this worker supports (e.g. python3.11, duckdb, gpu).
+    /// Repeatable; replaces the configured tags. Without any, the worker
+    /// accepts every plugin ("*").
+    #[arg(
+        long = "capability",
+        value_name = "TAG",
+        env = "CASPARIAN_WORKER_CAPABILITIES",
+        value_delimiter = ','
+    )]
+    pub capabilities: Vec<String>,
+
+    /// Sentinel CURVE public key (Z85). Enables encrypted transport.
+    #[arg(long, env = "CASPARIAN_WORKER_SERVER_KEY")]
+    pub server_key: Option<String>,
+
+    /// Name of this worker's key pair under ~/.casparian_flow/keys [default: worker]
+    #[arg(long, env = "CASPARIAN_WORKER_KEY_NAME")]
+    pub key_name: Option<String>,
+
+    /// Python bridge shim (default: the embedded one)
+    #[arg(long, env = "CASPARIAN_WORKER_SHIM_PATH")]
+    pub shim_path: Option<std::path::PathBuf>,
+
+    /// Plugin env cache directory [default: ~/.casparian_flow/venvs]
+    #[arg(long, env = "CASPARIAN_WORKER_VENVS_DIR")]
+    pub venvs_dir: Option<std::path::PathBuf>,
+
+    /// Target rows per output batch of native plugins
+    #[arg(long, env = "CASPARIAN_WORKER_BATCH_ROWS")]
+    pub batch_rows: Option<usize>,
+
+    /// Target bytes per output batch of native plugins
+    #[arg(long, env = "CASPARIAN_WORKER_BATCH_BYTES")]
+    pub batch_bytes: Option<usize>,
+
+    /// Threads each job's Parquet/CSV sinks may encode on (1 = serial)
+    #[arg(long, env = "CASPARIAN_WORKER_ENCODE_THREADS")]
+    pub encode_threads: Option<usize>,
+
+    /// Wall-clock limit of jobs whose plugin sets none, in seconds
+    #[arg(long, env = "CASPARIAN_WORKER_TIMEOUT_SECS")]
+    pub timeout_secs: Option<u64>,
+
+    /// CPU time limit of jobs whose plugin sets none, in seconds
+    #[arg(long, env = "CASPARIAN_WORKER_MAX_CPU_SECS")]
+    pub max_cpu_secs: Option<u64>,
+
+    /// Memory limit of jobs whose plugin sets none, in bytes
+    #[arg(long, env = "CASPARIAN_WORKER_MAX_MEMORY_BYTES")]
+    pub max_memory_bytes: Option<u64>,
+
+    /// Largest decompressed input of jobs whose plugin sets none, in bytes
+    #[arg(long, env = "CASPARIAN_WORKER_MAX_DECOMPRESSED_BYTES")]
+    pub max_decompressed_bytes: Option<u64>,
+
+    /// Scratch directory quota of jobs whose plugin sets none, in bytes
+    #[arg(long, env = "CASPARIAN_WORKER_MAX_SCRATCH_BYTES")]
+    pub max_scratch_bytes: Option<u64>,
+
+    /// Root of per-job scratch directories [default: system temp dir]
+    #[arg(long, env = "CASPARIAN_WORKER_SCRATCH_DIR")]
+    pub scratch_dir: Option<std::path::PathBuf>,
+
+    /// Seconds between heartbeats to the Sentinel [default: 30]
+    #[arg(long, env = "CASPARIAN_WORKER_HEARTBEAT_SECS")]
+    pub heartbeat_secs: Option<u64>,
+
+    /// Milliseconds before reconnecting to the Sentinel [default: 100]
+    #[arg(long, env = "CASPARIAN_WORKER_RECONNECT_MS")]
+    pub reconnect_ms: Option<u64>,
+
+    /// Cap of the reconnect delay, which doubles per attempt, in milliseconds
+    /// [default: 30000]
+    #[arg(long, env = "CASPARIAN_WORKER_RECONNECT_MAX_MS")]
+    pub reconnect_max_ms: Option<u64>,
+
+    /// Serve health probes on host:port or unix:/path
+    #[arg(long, env = "CASPARIAN_WORKER_HEALTH_LISTEN")]
+    pub health_listen: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<WorkerCommand>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum WorkerCommand {
+    /// Query a running worker's readiness probe; exits nonzero unless healthy
+    Healthcheck {
+        /// Probe address [default: health.listen of the worker config]
+        #[arg(long)]
+        addr: Option<String>,
+    },
+}
+
+impl WorkerArgs {
+    /// Effective settings: the config file, overridden by environment
+    /// variables and flags.
+    pub fn settings(&self) -> anyhow::Result<WorkerSettings> {
+        let mut settings = WorkerSettings::load_or_default(self.config.as_deref())?;
+        if let Some(connect) = &self.connect {
+            settings.connect = connect.clone();
+        }
+        if let Some(output) = &self.output {
+            settings.sinks.output = output.clone();
+        }
+        if let Some(worker_id) = &self.worker_id {
+            settings.worker_id = Some(worker_id.clone());
+        }
+        if !self.capabilities.is_empty() {
+            settings.capabilities = self.capabilities.clone();
+        }
+        if let Some(server_key) = &self.server_key {
+            settings.security.server_key = Some(server_key.clone());
+        }
+        if let Some(key_name) = &self.key_name {
+            settings.security.key_name = key_name.clone();
+        }
+        if let Some(shim_path) = &self.shim_path {
+            settings.runtime.shim_path = Some(shim_path.clone());
+        }
+        if let Some(venvs_dir) = &self.venvs_dir {
+            settings.runtime.venvs_dir = Some(venvs_dir.clone());
+        }
+        if let Some(scratch_dir) = &self.scratch_dir {
+            settings.runtime.scratch_dir = Some(scratch_dir.clone());
+        }
+        if let Some(batch_rows) = self.batch_rows {
+            settings.runtime.batch_rows = batch_rows;
+        }
+        if let Some(batch_bytes) = self.batch_bytes {
+            settings.runtime.batch_bytes = batch_bytes;
+        }
+        if let Some(encode_threads) = self.encode_threads {
+            settings.sinks.encode_threads = encode_threads;
+        }
+        let limits = &mut settings.limits;
+        limits.timeout_secs = self.timeout_secs.or(limits.timeout_secs);
+        limits.max_cpu_secs = self.max_cpu_secs.or(limits.max_cpu_secs);
+        limits.max_memory_bytes = self.max_memory_bytes.or(limits.max_memory_bytes);
+        limits.max_decompressed_bytes = self
+            .max_decompressed_bytes
+            .or(limits.max_decompressed_bytes);
+        limits.max_scratch_bytes = self.max_scratch_bytes.or(limits.max_scratch_bytes);
+        if let Some(interval_secs) = self.heartbeat_secs {
+            settings.heartbeat.interval_secs = interval_secs;
+        }
+        if let Some(interval_ms) = self.reconnect_ms {
+            settings.reconnect.interval_ms = interval_ms;
+        }
+        if let Some(max_interval_ms) = self.reconnect_max_ms {
+            settings.reconnect.max_interval_ms = max_interval_ms;
+        }
+        if let Some(listen) = &self.health_listen {
+            settings.health.listen = Some(listen.clone());
+        }
+        settings.validate()?;
+        Ok(settings)
+    }
 }
@@ -0,0 +1,81 @@
+//! Host load sampling for HEARTBEAT.
+//!
+//! CPU is approximated from the 1-minute load average divided by the number of
+//! available cores; memory comes from `/proc/meminfo`. Both are Linux-only and
+//! reported as `None` elsewhere, in which case the Sentinel schedules on queue
+//! depth alone.
+
+use casparian_protocol::types::WorkerLoad;
+
+/// Snapshot the worker's load for a heartbeat.
+pub(crate) fn sample_load(queue_depth: usize, capacity: usize) -> WorkerLoad {
+    let (memory_used_bytes, memory_total_bytes) = match memory_usage() {
+        Some((used, total)) => (Some(used), Some(total)),
+        None => (None, None),
+    };
+    WorkerLoad {
+        queue_depth,
+        capacity,
+        cpu_percent: cpu_percent(),
+        memory_used_bytes,
+        memory_total_bytes,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_percent() -> Option<f32> {
+    let loadavg = std::fs::read_to_string("/proc/loadavg").ok()?;
+    let load_1m: f32 = loadavg.split_whitespace().next()?.parse().ok()?;
+    let cores = std::thread::available_parallelism().ok()?.get();
+    let cores = u16::try_from(cores).map(f32::from).unwrap_or(f32::from(u16::MAX));
+    Some(load_1m / cores * 100.0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cpu_percent() -> Option<f32> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn memory_usage() -> Option<(u64, u64)> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_meminfo(&meminfo)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn memory_usage() -> Option<(u64, u64)> {
+    None
+}
+
+/// Parse (used, total) bytes from `/proc/meminfo` contents.
+fn parse_meminfo(meminfo: &str) -> Option<(u64, u64)> {
+    let field_kib = |name: &str| -> Option<u64> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|value| value.parse().ok())
+    };
+    let total = field_kib("MemTotal")?.checked_mul(1024)?;
+    let available = field_kib("MemAvailable")?.checked_mul(1024)?;
+    Some((total.saturating_sub(available), total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo() {
+        let meminfo = "MemTotal: 2048 kB\nMemFree: 100 kB\nMemAvailable: 512 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some((1536 * 1024, 2048 * 1024)));
+        assert_eq!(parse_meminfo("MemTotal: 1 kB\n"), None);
+    }
+
+    #[test]
+    fn test_sample_load_reports_queue() {
+        let load = sample_load(1, 1);
+        assert_eq!(load.queue_depth, 1);
+        assert!(load.is_full());
+    }
+}
@@ -1,7 +1,11 @@
 use anyhow::{Context, Result};
+use arrow::compute::concat_batches;
 use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use casparian_protocol::types::{ByteRange, CSV_DIALECT_ENV};
 use casparian_sinks::OutputBatch;
-use std::io::{BufRead, BufReader};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::sync::mpsc::{self, Receiver, Sender};
@@ -11,10 +15,160 @@ use std::time::Duration;
 use crate::bridge::OutputInfo;
 use crate::cancel::CancellationToken;
 use crate::runtime::{PluginRuntime, RunContext, RunOutputs};
+use crate::sandbox::{LimitExceeded, ProcessSandbox};
+use crate::scratch;
+use crate::xlsx::{read_xlsx, XlsxOptions};
 
 const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
 const PROTOCOL_VERSION: &str = "0.1";
 
+/// Default target rows per output batch.
+pub const DEFAULT_BATCH_ROWS: usize = 8192;
+/// Default target bytes per output batch.
+pub const DEFAULT_BATCH_BYTES: usize = 64 * 1024 * 1024;
+
+/// Target size of the batches a job hands to its sinks; a batch is complete
+/// once it reaches either limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchSizing {
+    pub target_rows: usize,
+    pub target_bytes: usize,
+}
+
+impl Default for BatchSizing {
+    fn default() -> Self {
+        Self {
+            target_rows: DEFAULT_BATCH_ROWS,
+            target_bytes: DEFAULT_BATCH_BYTES,
+        }
+    }
+}
+
+/// Reshapes plugin batches to `BatchSizing` and tracks the memory a job
+/// holds in batches.
+///
+/// Plugins emit batches of any size: thousands of tiny batches mean
+/// thousands of small allocations in every later stage, and one huge batch
+/// is written in one piece. Small batches are concatenated into one batch of
+/// the target size; large ones are split into zero-copy slices.
+///
+/// Each output stream takes a `BatchBuilder` from the pool and returns it
+/// when the stream ends, so the staging buffers are reused across batches
+/// and streams instead of being reallocated.
+#[derive(Debug)]
+pub struct BatchPool {
+    sizing: BatchSizing,
+    free: Vec<BatchBuilder>,
+    /// Bytes of completed batches plus the batches being staged
+    held_bytes: u64,
+    peak_bytes: u64,
+}
+
+/// Staging area for the batches of one output stream.
+#[derive(Debug, Default)]
+pub struct BatchBuilder {
+    pending: Vec<RecordBatch>,
+    pending_rows: usize,
+    pending_bytes: u64,
+    completed: Vec<OutputBatch>,
+}
+
+impl BatchPool {
+    pub fn new(sizing: BatchSizing) -> Self {
+        Self {
+            sizing: BatchSizing {
+                target_rows: sizing.target_rows.max(1),
+                target_bytes: sizing.target_bytes.max(1),
+            },
+            free: Vec::new(),
+            held_bytes: 0,
+            peak_bytes: 0,
+        }
+    }
+
+    /// Take a builder for a new output stream.
+    pub fn builder(&mut self) -> BatchBuilder {
+        self.free.pop().unwrap_or_default()
+    }
+
+    /// Add a plugin batch to the stream of `builder`.
+    pub fn push(&mut self, builder: &mut BatchBuilder, batch: RecordBatch) -> Result<()> {
+        let rows = batch.num_rows();
+        if rows == 0 {
+            return Ok(());
+        }
+        // Slices share the parent's buffers; account for them per row.
+        let row_bytes = (batch.get_array_memory_size() / rows).max(1) as u64;
+        let mut offset = 0;
+        while offset < rows {
+            let room_rows = self.sizing.target_rows - builder.pending_rows;
+            let room_bytes = self.sizing.target_bytes as u64 - builder.pending_bytes;
+            let len = (rows - offset)
+                .min(room_rows)
+                .min(((room_bytes / row_bytes) as usize).max(1));
+            let chunk = if offset == 0 && len == rows {
+                batch.clone()
+            } else {
+                batch.slice(offset, len)
+            };
+            builder.pending.push(chunk);
+            builder.pending_rows += len;
+            builder.pending_bytes += len as u64 * row_bytes;
+            self.hold(len as u64 * row_bytes);
+            offset += len;
+
+            if builder.pending_rows >= self.sizing.target_rows
+                || builder.pending_bytes >= self.sizing.target_bytes as u64
+            {
+                self.flush(builder)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// End the stream of `builder` and return its batches; the builder goes
+    /// back to the pool.
+    pub fn finish(&mut self, mut builder: BatchBuilder) -> Result<Vec<OutputBatch>> {
+        self.flush(&mut builder)?;
+        let completed = std::mem::take(&mut builder.completed);
+        self.free.push(builder);
+        Ok(completed)
+    }
+
+    /// Highest number of bytes held in batches so far.
+    pub fn peak_bytes(&self) -> u64 {
+        self.peak_bytes
+    }
+
+    fn flush(&mut self, builder: &mut BatchBuilder) -> Result<()> {
+        let staged = builder.pending_bytes;
+        builder.pending_rows = 0;
+        builder.pending_bytes = 0;
+        let batch = match builder.pending.len() {
+            0 => return Ok(()),
+            1 => builder.pending.pop().expect("one pending batch"),
+            _ => {
+                let schema = builder.pending[0].schema();
+                let batch = concat_batches(&schema, &builder.pending)
+                    .context("Failed to concatenate Arrow batches")?;
+                // The inputs and their concatenated copy are alive together
+                // until the inputs are dropped.
+                self.hold(batch.get_array_memory_size() as u64);
+                builder.pending.clear();
+                self.held_bytes -= staged;
+                batch
+            }
+        };
+        builder.completed.push(OutputBatch::from_record_batch(batch));
+        Ok(())
+    }
+
+    fn hold(&mut self, bytes: u64) {
+        self.held_bytes += bytes;
+        self.peak_bytes = self.peak_bytes.max(self.held_bytes);
+    }
+}
+
 #[derive(Debug)]
 enum ControlFrame {
     Hello {
@@ -36,11 +190,19 @@ enum ControlFrame {
     Error(String),
 }
 
-pub struct NativeSubprocessRuntime;
+pub struct NativeSubprocessRuntime {
+    sizing: BatchSizing,
+}
 
 impl NativeSubprocessRuntime {
     pub fn new() -> Self {
-        Self
+        Self {
+            sizing: BatchSizing::default(),
+        }
+    }
+
+    pub fn with_batch_sizing(sizing: BatchSizing) -> Self {
+        Self { sizing }
     }
 }
 
@@ -57,13 +219,48 @@ impl PluginRuntime for NativeSubprocessRuntime {
         if ctx.schema_hashes.is_empty() {
             anyhow::bail!("Schema hashes are required for native runtime");
         }
+        if let Some(options) = XlsxOptions::from_entrypoint(&ctx.entrypoint)? {
+            if ctx.byte_range.is_some() {
+                anyhow::bail!("The built-in xlsx reader cannot read a byte range");
+            }
+            return run_builtin_xlsx(ctx, input_path, &options, cancel_token, self.sizing);
+        }
+
+        // Incremental job: the plugin reads a copy of just the new lines, so a
+        // line-oriented parser emits only the new rows without knowing it.
+        let slice = match ctx.byte_range {
+            Some(range) => Some(extract_range(input_path, range)?),
+            None => None,
+        };
 
-        let mut child = Command::new(&ctx.entrypoint)
-            .arg(input_path)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
+        let mut sandbox = ProcessSandbox::new(&ctx.limits);
+        let mut command = Command::new(&ctx.entrypoint);
+        match (&slice, ctx.byte_range) {
+            (Some(slice), Some(range)) => {
+                command
+                    .arg(slice.path())
+                    .env("CASPARIAN_INPUT_PATH", input_path)
+                    .env("CASPARIAN_INPUT_OFFSET", range.start.to_string());
+            }
+            _ => {
+                command.arg(input_path);
+            }
+        }
+        if let Some(dialect) = &ctx.csv_dialect {
+            command.env(CSV_DIALECT_ENV, serde_json::to_string(dialect)?);
+        }
+        if let Some(scratch_dir) = &ctx.scratch_dir {
+            scratch::configure(&mut command, scratch_dir);
+        }
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        sandbox.configure(&mut command);
+        let mut child = command
             .spawn()
             .context("Failed to spawn native plugin process")?;
+        if let Err(err) = sandbox.attach(&child) {
+            let _ = child.kill();
+            return Err(err.context("Failed to apply resource limits"));
+        }
 
         let stdout = child
             .stdout
@@ -78,6 +275,7 @@ impl PluginRuntime for NativeSubprocessRuntime {
         let stderr_handle = spawn_stderr_reader(stderr, tx);
 
         let mut logs = String::new();
+        let mut pool = BatchPool::new(self.sizing);
         let mut output_batches = Vec::new();
         let mut output_info = Vec::new();
         let mut stream_index_expected = 0;
@@ -150,7 +348,7 @@ impl PluginRuntime for NativeSubprocessRuntime {
                         );
                     }
 
-                    let batches = read_arrow_stream(&mut stdout_reader)
+                    let batches = read_arrow_stream(&mut stdout_reader, &mut pool)
                         .with_context(|| format!("Failed to read Arrow stream for '{}'", output))?;
 
                     let end_frame = loop {
@@ -221,6 +419,9 @@ impl PluginRuntime for NativeSubprocessRuntime {
                 .try_wait()
                 .context("Failed to poll native plugin status")?
             {
+                if let Some(kind) = sandbox.exceeded(&status, &logs) {
+                    return Err(LimitExceeded::new(kind, &ctx.limits).into());
+                }
                 if !status.success() {
                     anyhow::bail!("Native plugin exited with status {}", status);
                 }
@@ -234,10 +435,100 @@ impl PluginRuntime for NativeSubprocessRuntime {
             output_batches,
             output_info,
             logs,
+            peak_memory_bytes: pool.peak_bytes(),
         })
     }
 }
 
+/// Copy `range` of `input_path` to a temp file (same extension) for the plugin.
+fn extract_range(input_path: &Path, range: ByteRange) -> Result<tempfile::NamedTempFile> {
+    let mut input = File::open(input_path)
+        .with_context(|| format!("Failed to open input {}", input_path.display()))?;
+    let size = input.metadata()?.len();
+    if range.start > range.end || range.end > size {
+        anyhow::bail!(
+            "Byte range {}..{} is outside {} ({} bytes); the file was truncated or replaced",
+            range.start,
+            range.end,
+            input_path.display(),
+            size
+        );
+    }
+
+    let suffix = input_path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut slice = tempfile::Builder::new()
+        .prefix("casparian-range-")
+        .suffix(&suffix)
+        .tempfile()
+        .context("Failed to create byte-range input file")?;
+    input.seek(SeekFrom::Start(range.start))?;
+    let copied = std::io::copy(&mut input.take(range.len()), slice.as_file_mut())
+        .context("Failed to copy byte range of input")?;
+    if copied != range.len() {
+        anyhow::bail!(
+            "Short read of byte range {}..{} ({} of {} bytes)",
+            range.start,
+            range.end,
+            copied,
+            range.len()
+        );
+    }
+    Ok(slice)
+}
+
+/// Read an xlsx sheet in-process instead of spawning a plugin binary.
+fn run_builtin_xlsx(
+    ctx: &RunContext,
+    input_path: &Path,
+    options: &XlsxOptions,
+    cancel_token: &CancellationToken,
+    sizing: BatchSizing,
+) -> Result<RunOutputs> {
+    let table = read_xlsx(input_path, options)?;
+    if cancel_token.is_cancelled() {
+        anyhow::bail!("Native plugin cancelled");
+    }
+
+    let mut declared = ctx.schema_hashes.keys().filter(|name| name.as_str() != "*");
+    let single_declared = match (declared.next(), declared.next()) {
+        (Some(name), None) => Some(name.clone()),
+        _ => None,
+    };
+    let output = options
+        .output
+        .clone()
+        .or(single_declared)
+        .unwrap_or_else(|| casparian_protocol::safe_output_id(&table.sheet_name));
+
+    let logs = format!(
+        "xlsx: sheet '{}', header row {}, {} columns, {} rows\n",
+        table.sheet_name,
+        table
+            .header_row
+            .map(|row| (row + 1).to_string())
+            .unwrap_or_else(|| "none".to_string()),
+        table.observed_columns.len(),
+        table.batches.iter().map(|b| b.num_rows()).sum::<usize>()
+    );
+    let mut pool = BatchPool::new(sizing);
+    let mut builder = pool.builder();
+    for batch in table.batches {
+        pool.push(&mut builder, batch)?;
+    }
+    Ok(RunOutputs {
+        output_batches: vec![pool.finish(builder)?],
+        output_info: vec![OutputInfo {
+            name: output,
+            table: None,
+        }],
+        logs,
+        peak_memory_bytes: pool.peak_bytes(),
+    })
+}
+
 fn spawn_stderr_reader(
     stderr: std::process::ChildStderr,
     tx: Sender<ControlFrame>,
@@ -353,13 +644,90 @@ fn parse_control_frame(line: &str) -> Result<ControlFrame> {
 
 fn read_arrow_stream(
     reader: &mut BufReader<std::process::ChildStdout>,
+    pool: &mut BatchPool,
 ) -> Result<Vec<OutputBatch>> {
     let mut stream_reader =
         StreamReader::try_new(reader, None).context("stdout is not valid Arrow IPC stream")?;
-    let mut batches = Vec::new();
+    let mut builder = pool.builder();
     for batch in stream_reader.by_ref() {
         let batch = batch.context("Failed to read Arrow batch")?;
-        batches.push(OutputBatch::from_record_batch(batch));
+        pool.push(&mut builder, batch)?;
+    }
+    pool.finish(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn int_batch(start: i64, rows: usize) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, false)]));
+        let values = Int64Array::from_iter_values(start..start + rows as i64);
+        RecordBatch::try_new(schema, vec![Arc::new(values)]).unwrap()
+    }
+
+    fn row_counts(batches: &[OutputBatch]) -> Vec<usize> {
+        batches.iter().map(OutputBatch::num_rows).collect()
+    }
+
+    #[test]
+    fn test_batch_pool_coalesces_and_splits() {
+        let mut pool = BatchPool::new(BatchSizing {
+            target_rows: 100,
+            target_bytes: usize::MAX,
+        });
+
+        // Tiny batches are merged up to the target
+        let mut builder = pool.builder();
+        for i in 0..25 {
+            pool.push(&mut builder, int_batch(i * 10, 10)).unwrap();
+        }
+        let batches = pool.finish(builder).unwrap();
+        assert_eq!(row_counts(&batches), vec![100, 100, 50]);
+        let first = batches[0].as_record_batch().column(0);
+        let first = first.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(first.value(99), 99);
+
+        // A huge batch is split, and the builder is reused
+        let mut builder = pool.builder();
+        assert!(builder.pending.capacity() > 0);
+        pool.push(&mut builder, int_batch(0, 250)).unwrap();
+        let batches = pool.finish(builder).unwrap();
+        assert_eq!(row_counts(&batches), vec![100, 100, 50]);
+        assert!(pool.peak_bytes() > 0);
+    }
+
+    #[test]
+    fn test_batch_pool_byte_target() {
+        let batch = int_batch(0, 1000);
+        let row_bytes = batch.get_array_memory_size() / 1000;
+        let mut pool = BatchPool::new(BatchSizing {
+            target_rows: usize::MAX,
+            target_bytes: row_bytes * 400,
+        });
+        let mut builder = pool.builder();
+        pool.push(&mut builder, batch).unwrap();
+        let batches = pool.finish(builder).unwrap();
+        assert_eq!(row_counts(&batches), vec![400, 400, 200]);
+    }
+
+    #[test]
+    fn test_extract_range_copies_only_the_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "old 1\nold 2\nnew 1\nnew 2\n").unwrap();
+
+        let slice = extract_range(&path, ByteRange { start: 12, end: 24 }).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(slice.path()).unwrap(),
+            "new 1\nnew 2\n"
+        );
+        assert_eq!(slice.path().extension().unwrap(), "log");
+
+        let err = extract_range(&path, ByteRange { start: 12, end: 99 }).unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{}", err);
     }
-    Ok(batches)
 }
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use casparian_protocol::types::{ByteRange, CsvDialect, ResourceLimits};
 use casparian_protocol::JobId;
 use casparian_sinks::OutputBatch;
 use std::collections::HashMap;
@@ -14,14 +15,26 @@ pub struct RunContext {
     pub file_id: i64,
     pub entrypoint: String,
     pub env_hash: Option<String>,
+    /// uv.lock for `env_hash`, used to build the env on a cache miss
+    pub lockfile_content: Option<String>,
     pub source_code: Option<String>,
     pub schema_hashes: HashMap<String, String>,
+    /// CPU and memory limits for the plugin process
+    pub limits: ResourceLimits,
+    /// Read only this part of the input (incremental job)
+    pub byte_range: Option<ByteRange>,
+    /// How to read a CSV input, passed to the plugin as `CSV_DIALECT_ENV`
+    pub csv_dialect: Option<CsvDialect>,
+    /// Scratch directory handed to the plugin (see `crate::scratch`)
+    pub scratch_dir: Option<PathBuf>,
 }
 
 pub struct RunOutputs {
     pub output_batches: Vec<Vec<OutputBatch>>,
     pub output_info: Vec<OutputInfo>,
     pub logs: String,
+    /// Peak bytes of Arrow batches held while the plugin ran
+    pub peak_memory_bytes: u64,
 }
 
 pub trait PluginRuntime {
@@ -64,15 +77,14 @@ impl PluginRuntime for PythonShimRuntime {
         if env_hash == "system" {
             anyhow::bail!("System env_hash is not supported; deploy with a lockfile");
         }
-
-        let interpreter = self.venv_manager.interpreter_path(env_hash);
-        if !interpreter.exists() {
-            anyhow::bail!(
-                "Environment {} not installed on worker. Preinstall the env for this plugin.",
-                env_hash
-            );
+        if ctx.byte_range.is_some() {
+            anyhow::bail!("Incremental byte-range reads are only supported by native parsers");
         }
 
+        let interpreter = self
+            .venv_manager
+            .resolve(env_hash, ctx.lockfile_content.as_deref())?;
+
         let source_code = ctx
             .source_code
             .clone()
@@ -90,14 +102,25 @@ impl PluginRuntime for PythonShimRuntime {
             shim_path: self.shim_path.clone(),
             inherit_stdio: false,
             cancel_token: cancel_token.clone(),
+            limits: ctx.limits.clone(),
+            csv_dialect: ctx.csv_dialect.clone(),
+            scratch_dir: ctx.scratch_dir.clone(),
         };
 
         let result = bridge::execute_bridge(config).context("Bridge execution failed")?;
 
+        // The bridge holds every batch until the plugin exits.
+        let peak_memory_bytes = result
+            .output_batches
+            .iter()
+            .flatten()
+            .map(|batch| batch.as_record_batch().get_array_memory_size() as u64)
+            .sum();
         Ok(RunOutputs {
             output_batches: result.output_batches,
             output_info: result.output_info,
             logs: result.logs,
+            peak_memory_bytes,
         })
     }
 }
@@ -0,0 +1,400 @@
+//! Process Sandbox: per-job resource limits for plugin processes
+//!
+//! CPU time and memory limits from `DispatchCommand::limits` are enforced by
+//! the OS on the spawned plugin process:
+//! - Unix: `setrlimit` in the child before exec (`RLIMIT_CPU`, `RLIMIT_AS`).
+//!   The soft CPU limit delivers SIGXCPU; the hard limit one second later
+//!   kills processes that ignore it. Memory limits cap the address space, so
+//!   allocations past the limit fail inside the plugin.
+//! - Windows: the process is assigned to a job object with per-process user
+//!   time and committed memory limits. Closing the job kills the process.
+//!
+//! Limits are inherited by child processes (e.g. `uv run` -> python).
+//!
+//! Wall-clock timeouts are enforced by the worker, not the OS: the job's
+//! `CancellationToken` carries a deadline and every runtime already kills the
+//! process when the token fires (see `cancel.rs`).
+
+use anyhow::Result;
+use casparian_protocol::types::{LimitKind, ResourceLimits};
+use std::process::{Child, Command, ExitStatus};
+use thiserror::Error;
+
+/// Plugin process terminated for exceeding a resource limit.
+///
+/// Returned in the error chain of `PluginRuntime::run_file`; the worker
+/// downcasts it to report `JobDiagnostics::limit_exceeded`.
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct LimitExceeded {
+    pub kind: LimitKind,
+    pub message: String,
+}
+
+impl LimitExceeded {
+    pub fn new(kind: LimitKind, limits: &ResourceLimits) -> Self {
+        let message = match kind {
+            LimitKind::WallClock => format!(
+                "Plugin exceeded wall-clock timeout of {}s; process terminated",
+                limits.timeout_secs.unwrap_or_default()
+            ),
+            LimitKind::CpuTime => format!(
+                "Plugin exceeded CPU time limit of {}s; process terminated",
+                limits.max_cpu_secs.unwrap_or_default()
+            ),
+            LimitKind::Memory => format!(
+                "Plugin exceeded memory limit of {} MiB; process terminated",
+                limits.max_memory_bytes.unwrap_or_default() / (1024 * 1024)
+            ),
+            LimitKind::DecompressedSize => format!(
+                "Input exceeded decompressed size limit of {} MiB; decompression aborted",
+                limits.decompressed_limit() / (1024 * 1024)
+            ),
+            LimitKind::ScratchSpace => format!(
+                "Plugin exceeded scratch space quota of {} MiB; process terminated",
+                limits.max_scratch_bytes.unwrap_or_default() / (1024 * 1024)
+            ),
+        };
+        Self { kind, message }
+    }
+}
+
+/// Resource limits for one plugin process.
+///
+/// Call `configure` on the command before spawning and `attach` right after;
+/// keep the sandbox alive until the process has exited.
+pub struct ProcessSandbox {
+    limits: ResourceLimits,
+    #[cfg(windows)]
+    job: Option<windows::JobObject>,
+}
+
+impl ProcessSandbox {
+    pub fn new(limits: &ResourceLimits) -> Self {
+        Self {
+            limits: limits.clone(),
+            #[cfg(windows)]
+            job: None,
+        }
+    }
+
+    pub fn limits(&self) -> &ResourceLimits {
+        &self.limits
+    }
+
+    /// Apply OS limits to the process before it is spawned (Unix).
+    #[cfg(unix)]
+    pub fn configure(&self, cmd: &mut Command) {
+        use std::os::unix::process::CommandExt;
+
+        let cpu_secs = self.limits.max_cpu_secs;
+        let memory_bytes = self.limits.max_memory_bytes;
+        if cpu_secs.is_none() && memory_bytes.is_none() {
+            return;
+        }
+
+        // SAFETY: the hook runs between fork and exec and only calls
+        // getrlimit/setrlimit, which are async-signal-safe.
+        unsafe {
+            cmd.pre_exec(move || {
+                let set_limit = |resource, soft: u64, hard: u64| {
+                    let mut current = libc::rlimit {
+                        rlim_cur: 0,
+                        rlim_max: 0,
+                    };
+                    if libc::getrlimit(resource, &mut current) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    // Unprivileged processes cannot raise their hard limit
+                    let hard = (hard as libc::rlim_t).min(current.rlim_max);
+                    let limit = libc::rlimit {
+                        rlim_cur: (soft as libc::rlim_t).min(hard),
+                        rlim_max: hard,
+                    };
+                    if libc::setrlimit(resource, &limit) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    Ok(())
+                };
+                if let Some(secs) = cpu_secs {
+                    set_limit(libc::RLIMIT_CPU, secs, secs.saturating_add(1))?;
+                }
+                if let Some(bytes) = memory_bytes {
+                    set_limit(libc::RLIMIT_AS, bytes, bytes)?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    /// Limits are applied after spawn on this platform (see `attach`).
+    #[cfg(not(unix))]
+    pub fn configure(&self, _cmd: &mut Command) {}
+
+    /// Place a spawned process under OS limits (Windows job object).
+    #[cfg(windows)]
+    pub fn attach(&mut self, child: &Child) -> Result<()> {
+        if self.limits.max_cpu_secs.is_none() && self.limits.max_memory_bytes.is_none() {
+            return Ok(());
+        }
+        self.job = Some(windows::JobObject::attach(child, &self.limits)?);
+        Ok(())
+    }
+
+    /// Limits were applied before exec on this platform (see `configure`).
+    #[cfg(not(windows))]
+    pub fn attach(&mut self, _child: &Child) -> Result<()> {
+        Ok(())
+    }
+
+    /// The limit a process exceeded, judged from how it exited.
+    pub fn exceeded(&self, status: &ExitStatus, stderr: &str) -> Option<LimitKind> {
+        if status.success() {
+            return None;
+        }
+        if self.limits.max_cpu_secs.is_some() && self.cpu_limit_hit(status) {
+            return Some(LimitKind::CpuTime);
+        }
+        if self.limits.max_memory_bytes.is_some() && is_out_of_memory(stderr) {
+            return Some(LimitKind::Memory);
+        }
+        None
+    }
+
+    /// The limit a process exceeded, if it has already exited.
+    ///
+    /// Used when the transport fails mid-run: a process killed for exceeding
+    /// its CPU or memory limit usually surfaces first as a closed socket.
+    pub fn check_exit(&self, child: &mut Child, stderr: &str) -> Option<LimitKind> {
+        let status = match child.try_wait() {
+            Ok(Some(status)) => status,
+            _ => return None,
+        };
+        self.exceeded(&status, stderr)
+    }
+
+    #[cfg(unix)]
+    fn cpu_limit_hit(&self, status: &ExitStatus) -> bool {
+        use std::os::unix::process::ExitStatusExt;
+
+        match status.signal() {
+            Some(signal) => signal == libc::SIGXCPU || signal == libc::SIGKILL,
+            // `uv run` reports a signalled child as exit code 128 + signal
+            None => status.code() == Some(128 + libc::SIGXCPU),
+        }
+    }
+
+    #[cfg(windows)]
+    fn cpu_limit_hit(&self, _status: &ExitStatus) -> bool {
+        match (&self.job, self.limits.max_cpu_secs) {
+            (Some(job), Some(secs)) => job.user_time_secs() >= secs,
+            _ => false,
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn cpu_limit_hit(&self, _status: &ExitStatus) -> bool {
+        false
+    }
+}
+
+/// Out-of-memory markers from Python and the Rust/C allocators.
+fn is_out_of_memory(stderr: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "MemoryError",
+        "Cannot allocate memory",
+        "memory allocation of",
+        "out of memory",
+        "bad_alloc",
+    ];
+    MARKERS.iter().any(|marker| stderr.contains(marker))
+}
+
+#[cfg(windows)]
+mod windows {
+    use anyhow::{bail, Result};
+    use casparian_protocol::types::ResourceLimits;
+    use std::mem::{size_of, zeroed};
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use std::ptr::null;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectBasicAccountingInformation,
+        JobObjectExtendedLimitInformation, QueryInformationJobObject, SetInformationJobObject,
+        JOBOBJECT_BASIC_ACCOUNTING_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+        JOB_OBJECT_LIMIT_PROCESS_TIME,
+    };
+
+    /// 100ns ticks per second (FILETIME resolution)
+    const TICKS_PER_SEC: i64 = 10_000_000;
+
+    pub(super) struct JobObject {
+        handle: HANDLE,
+    }
+
+    impl JobObject {
+        pub(super) fn attach(child: &Child, limits: &ResourceLimits) -> Result<Self> {
+            // SAFETY: plain Win32 calls on handles owned by this struct and
+            // the live child; the info struct is fully initialized.
+            unsafe {
+                let handle = CreateJobObjectW(null(), null());
+                if handle == 0 {
+                    bail!(
+                        "CreateJobObjectW failed: {}",
+                        std::io::Error::last_os_error()
+                    );
+                }
+                let job = Self { handle };
+
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = zeroed();
+                let mut flags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                if let Some(secs) = limits.max_cpu_secs {
+                    flags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+                    info.BasicLimitInformation.PerProcessUserTimeLimit =
+                        (secs as i64).saturating_mul(TICKS_PER_SEC);
+                }
+                if let Some(bytes) = limits.max_memory_bytes {
+                    flags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+                    info.ProcessMemoryLimit = bytes as usize;
+                }
+                info.BasicLimitInformation.LimitFlags = flags;
+
+                if SetInformationJobObject(
+                    job.handle,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const _ as *const _,
+                    size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                ) == 0
+                {
+                    bail!(
+                        "SetInformationJobObject failed: {}",
+                        std::io::Error::last_os_error()
+                    );
+                }
+                if AssignProcessToJobObject(job.handle, child.as_raw_handle() as HANDLE) == 0 {
+                    bail!(
+                        "AssignProcessToJobObject failed: {}",
+                        std::io::Error::last_os_error()
+                    );
+                }
+                Ok(job)
+            }
+        }
+
+        /// Total user-mode CPU time of processes in the job, in seconds.
+        pub(super) fn user_time_secs(&self) -> u64 {
+            // SAFETY: the handle is a live job object owned by self.
+            unsafe {
+                let mut info: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = zeroed();
+                let ok = QueryInformationJobObject(
+                    self.handle,
+                    JobObjectBasicAccountingInformation,
+                    &mut info as *mut _ as *mut _,
+                    size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                    std::ptr::null_mut(),
+                );
+                if ok == 0 {
+                    return 0;
+                }
+                (info.TotalUserTime / TICKS_PER_SEC).max(0) as u64
+            }
+        }
+    }
+
+    impl Drop for JobObject {
+        fn drop(&mut self) {
+            // SAFETY: the handle was created by CreateJobObjectW and is closed once.
+            unsafe {
+                CloseHandle(self.handle);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(cpu: Option<u64>, memory: Option<u64>) -> ResourceLimits {
+        ResourceLimits {
+            timeout_secs: None,
+            max_cpu_secs: cpu,
+            max_memory_bytes: memory,
+            max_decompressed_bytes: None,
+            max_scratch_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_limit_exceeded_message() {
+        let limits = ResourceLimits {
+            timeout_secs: Some(30),
+            max_cpu_secs: Some(10),
+            max_memory_bytes: Some(512 * 1024 * 1024),
+            max_decompressed_bytes: None,
+            max_scratch_bytes: None,
+        };
+        let err = LimitExceeded::new(LimitKind::Memory, &limits);
+        assert_eq!(
+            err.to_string(),
+            "Plugin exceeded memory limit of 512 MiB; process terminated"
+        );
+        let err = LimitExceeded::new(LimitKind::WallClock, &limits);
+        assert!(err.to_string().contains("wall-clock timeout of 30s"));
+
+        // Found through anyhow context layers
+        let wrapped = anyhow::Error::new(err).context("Bridge execution failed");
+        let found = wrapped
+            .chain()
+            .find_map(|e| e.downcast_ref::<LimitExceeded>())
+            .map(|e| e.kind);
+        assert_eq!(found, Some(LimitKind::WallClock));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cpu_limit_kills_process() {
+        let sandbox = ProcessSandbox::new(&limits(Some(1), None));
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("while :; do :; done");
+        sandbox.configure(&mut cmd);
+        let mut child = cmd.spawn().unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(20);
+        let status = loop {
+            if let Some(status) = child.try_wait().unwrap() {
+                break status;
+            }
+            if std::time::Instant::now() > deadline {
+                let _ = child.kill();
+                panic!("CPU limit was not enforced");
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        };
+        assert_eq!(sandbox.exceeded(&status, ""), Some(LimitKind::CpuTime));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exceeded_requires_configured_limit() {
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg("echo MemoryError >&2; exit 1")
+            .status()
+            .unwrap();
+        let unlimited = ProcessSandbox::new(&ResourceLimits::default());
+        assert_eq!(unlimited.exceeded(&status, "MemoryError"), None);
+
+        let memory = ProcessSandbox::new(&limits(None, Some(256 * 1024 * 1024)));
+        assert_eq!(
+            memory.exceeded(&status, "Traceback ...\nMemoryError"),
+            Some(LimitKind::Memory)
+        );
+        assert_eq!(memory.exceeded(&status, "ValueError: bad row"), None);
+
+        let ok = Command::new("true").status().unwrap();
+        assert_eq!(memory.exceeded(&ok, "MemoryError"), None);
+    }
+}
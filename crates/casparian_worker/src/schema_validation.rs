@@ -7,7 +7,7 @@ use arrow::datatypes::DataType as ArrowDataType;
 use arrow::record_batch::RecordBatch;
 use casparian_protocol::types::{
     ColumnOrderMismatch, ObservedColumn, ObservedDataType, SchemaColumnSpec, SchemaDefinition,
-    SchemaMismatch, TypeMismatch,
+    SchemaMismatch, TypeDrift, TypeMismatch,
 };
 use casparian_protocol::DataType as SchemaDataType;
 use chrono::Timelike;
@@ -119,6 +119,58 @@ pub fn enforce_schema_on_batches(
     Ok(validated)
 }
 
+/// Contract columns whose values arrived as a different type that the
+/// contract accepts (text cast to a decimal or date, integers widened to
+/// floats). Run on the batches before enforcement; columns that do not
+/// match the contract are schema mismatches, not drift.
+pub fn detect_type_drift(
+    batches: &[RecordBatch],
+    schema_def: &SchemaDefinition,
+    output_name: &str,
+) -> Vec<TypeDrift> {
+    let Ok(schema_def) = schema_def_from_definition(schema_def) else {
+        return Vec::new();
+    };
+    let mut drifts: Vec<TypeDrift> = Vec::new();
+    for batch in batches {
+        let schema = batch.schema();
+        for expected in &schema_def.columns {
+            let Ok(field) = schema.field_with_name(&expected.name) else {
+                continue;
+            };
+            let actual = field.data_type();
+            if !is_type_drift(&expected.data_type, actual, expected) {
+                continue;
+            }
+            let actual = observed_data_type(actual);
+            let seen = drifts
+                .iter()
+                .any(|drift| drift.column == expected.name && drift.actual == actual);
+            if !seen {
+                drifts.push(TypeDrift {
+                    output_name: output_name.to_string(),
+                    column: expected.name.clone(),
+                    expected: expected.data_type.clone(),
+                    actual,
+                });
+            }
+        }
+    }
+    drifts
+}
+
+fn is_type_drift(expected: &SchemaDataType, actual: &ArrowDataType, column: &ColumnDef) -> bool {
+    if matches!(actual, ArrowDataType::Null) || !is_type_compatible(expected, actual, column) {
+        return false;
+    }
+    match (expected, canonical_type_for_arrow(actual)) {
+        // Compatible timestamps already matched the zone up to aliases
+        (SchemaDataType::TimestampTz { .. }, Some(SchemaDataType::TimestampTz { .. })) => false,
+        (expected, Some(canonical)) => *expected != canonical,
+        (_, None) => false,
+    }
+}
+
 fn schema_def_from_definition(schema_def: &SchemaDefinition) -> SchemaResult<SchemaDef> {
     if schema_def.columns.is_empty() {
         return Err(SchemaValidationError::InvalidSchemaDef {
@@ -314,6 +366,7 @@ fn build_schema_mismatch(
             data_type: col.data_type.clone(),
             nullable: col.nullable,
             format: col.format.clone(),
+            constraints: Vec::new(),
         })
         .collect();
 
@@ -325,6 +378,7 @@ fn build_schema_mismatch(
             ObservedColumn {
                 name: field.name().to_string(),
                 data_type: observed_data_type(field.data_type()),
+                inference: None,
             }
         })
         .collect();
@@ -443,7 +497,7 @@ fn is_type_compatible(
     }
 }
 
-fn observed_data_type(actual: &ArrowDataType) -> ObservedDataType {
+pub(crate) fn observed_data_type(actual: &ArrowDataType) -> ObservedDataType {
     if let Some(data_type) = canonical_type_for_arrow(actual) {
         return ObservedDataType::Canonical { data_type };
     }
@@ -853,7 +907,10 @@ fn pow10_i128(exp: usize) -> Option<i128> {
     Some(value)
 }
 
-fn merge_error_column(batch: &RecordBatch, new_errors: &[String]) -> AnyhowResult<RecordBatch> {
+pub(crate) fn merge_error_column(
+    batch: &RecordBatch,
+    new_errors: &[String],
+) -> AnyhowResult<RecordBatch> {
     let error_idx = batch.schema().index_of("_cf_row_error").ok();
     let use_large = match error_idx {
         Some(idx) => matches!(batch.column(idx).data_type(), ArrowDataType::LargeUtf8),
@@ -964,7 +1021,7 @@ fn error_value(array: &ArrayRef, row: usize) -> AnyhowResult<Option<String>> {
     }
 }
 
-fn append_error(target: &mut String, message: &str) {
+pub(crate) fn append_error(target: &mut String, message: &str) {
     if !target.is_empty() {
         target.push_str("; ");
     }
@@ -1010,6 +1067,7 @@ mod tests {
             data_type: SchemaDataType::Int64,
             nullable: false,
             format: None,
+            constraints: Vec::new(),
         }]);
         let ids = StringArray::from(vec![Some("1"), Some("bad")]);
         let batch = RecordBatch::try_new(
@@ -1033,6 +1091,7 @@ mod tests {
             data_type: SchemaDataType::Int64,
             nullable: false,
             format: None,
+            constraints: Vec::new(),
         }]);
         let ids = Int64Array::from(vec![Some(1), None]);
         let batch = RecordBatch::try_new(
@@ -1063,6 +1122,7 @@ mod tests {
             data_type: SchemaDataType::Int64,
             nullable: true,
             format: None,
+            constraints: Vec::new(),
         }]);
         let ids = arrow::array::BooleanArray::from(vec![Some(true), Some(false)]);
         let batch = RecordBatch::try_new(
@@ -1086,6 +1146,7 @@ mod tests {
             data_type: SchemaDataType::Date,
             nullable: true,
             format: Some("%Y-%m-%d".to_string()),
+            constraints: Vec::new(),
         }]);
         let dates = StringArray::from(vec![Some("2024-01-15"), Some("01/15/2024")]);
         let batch = RecordBatch::try_new(
@@ -1116,6 +1177,7 @@ mod tests {
             data_type: SchemaDataType::Timestamp,
             nullable: true,
             format: Some("%Y-%m-%d %H:%M:%S".to_string()),
+            constraints: Vec::new(),
         }]);
         let values = StringArray::from(vec![
             Some("2024-01-15 10:30:00"),
@@ -1152,6 +1214,7 @@ mod tests {
             },
             nullable: true,
             format: None,
+            constraints: Vec::new(),
         }]);
         assert!(matches!(
             schema.columns[0].data_type,
@@ -1183,6 +1246,7 @@ mod tests {
             },
             nullable: true,
             format: None,
+            constraints: Vec::new(),
         }]);
 
         // Timestamp WITH matching TZ should pass
@@ -1214,6 +1278,7 @@ mod tests {
             },
             nullable: true,
             format: None,
+            constraints: Vec::new(),
         }]);
 
         // Timestamp with different TZ should fail
@@ -1245,6 +1310,7 @@ mod tests {
             },
             nullable: true,
             format: None,
+            constraints: Vec::new(),
         }]);
 
         // Decimal with matching precision/scale should pass
@@ -1274,6 +1340,7 @@ mod tests {
             },
             nullable: true,
             format: None,
+            constraints: Vec::new(),
         }]);
 
         // Decimal with different precision should fail
@@ -1303,6 +1370,7 @@ mod tests {
             },
             nullable: true,
             format: Some("%Y-%m-%dT%H:%M:%S%z".to_string()),
+            constraints: Vec::new(),
         }]);
 
         // Valid RFC3339 format should pass
@@ -1333,6 +1401,7 @@ mod tests {
             },
             nullable: true,
             format: Some("%Y-%m-%dT%H:%M:%S%z".to_string()),
+            constraints: Vec::new(),
         }]);
 
         // Invalid format should quarantine
@@ -1367,6 +1436,7 @@ mod tests {
             data_type: SchemaDataType::Date,
             nullable: true,
             format: Some("%Y-%m-%d".to_string()),
+            constraints: Vec::new(),
         }]);
         let dates = StringArray::from(vec![Some("2024-01-15"), Some("bad")]);
         let batch = RecordBatch::try_new(
@@ -1416,6 +1486,7 @@ mod tests {
             },
             nullable: true,
             format: None,
+            constraints: Vec::new(),
         }]);
         let values =
             StringArray::from(vec![Some("12.30"), Some("12.345"), Some("bad"), Some("12")]);
@@ -1463,6 +1534,7 @@ mod tests {
             },
             nullable: true,
             format: Some("%Y-%m-%dT%H:%M:%S%z".to_string()),
+            constraints: Vec::new(),
         }]);
         let values = StringArray::from(vec![
             Some("2024-01-15T10:30:00+00:00"),
@@ -1498,4 +1570,60 @@ mod tests {
             .unwrap();
         assert!(error_col.value(1).contains("format"));
     }
+
+    #[test]
+    fn test_detect_type_drift_on_accepted_casts() {
+        let definition = SchemaDefinition {
+            columns: vec![
+                SchemaColumnSpec {
+                    name: "id".to_string(),
+                    data_type: SchemaDataType::Int64,
+                    nullable: false,
+                    format: None,
+                    constraints: Vec::new(),
+                },
+                SchemaColumnSpec {
+                    name: "amount".to_string(),
+                    data_type: SchemaDataType::Float64,
+                    nullable: true,
+                    format: None,
+                    constraints: Vec::new(),
+                },
+                SchemaColumnSpec {
+                    name: "price".to_string(),
+                    data_type: SchemaDataType::Decimal {
+                        precision: 10,
+                        scale: 2,
+                    },
+                    nullable: true,
+                    format: None,
+                    constraints: Vec::new(),
+                },
+            ],
+        };
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("id", ArrowDataType::Int32, false),
+                Field::new("amount", ArrowDataType::Int64, true),
+                Field::new("price", ArrowDataType::Utf8, true),
+            ])),
+            vec![
+                Arc::new(arrow::array::Int32Array::from(vec![1])) as ArrayRef,
+                Arc::new(Int64Array::from(vec![Some(5)])) as ArrayRef,
+                Arc::new(StringArray::from(vec![Some("1.50")])) as ArrayRef,
+            ],
+        )
+        .unwrap();
+
+        let drifts = detect_type_drift(&[batch.clone(), batch], &definition, "orders");
+        let columns: Vec<_> = drifts.iter().map(|d| d.column.as_str()).collect();
+        assert_eq!(columns, vec!["amount", "price"]);
+        assert_eq!(drifts[0].output_name, "orders");
+        assert_eq!(
+            drifts[0].actual,
+            ObservedDataType::Canonical {
+                data_type: SchemaDataType::Int64
+            }
+        );
+    }
 }
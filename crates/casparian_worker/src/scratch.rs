@@ -0,0 +1,213 @@
+//! Per-job scratch directories.
+//!
+//! Every plugin run gets an empty directory under the worker's scratch root,
+//! passed to the plugin as `SCRATCH_DIR_ENV` and as `TMPDIR`/`TMP`/`TEMP`, so
+//! temp files written through the usual libraries land there too. The
+//! directory is removed when the [`ScratchDir`] is dropped, which happens on
+//! success, failure and ABORT alike.
+//!
+//! A monitor thread samples the directory size while the plugin runs and
+//! keeps the high-water mark for `JobDiagnostics::scratch_peak_bytes`. When
+//! the job has a `max_scratch_bytes` quota and the directory grows past it,
+//! the monitor trips the run token; the runtimes then kill the plugin and
+//! the job fails with `LimitKind::ScratchSpace`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use casparian_protocol::types::SCRATCH_DIR_ENV;
+use casparian_protocol::{JobId, LimitKind, ResourceLimits};
+use tracing::warn;
+
+use crate::cancel::CancellationToken;
+use crate::sandbox::LimitExceeded;
+
+/// How often the monitor measures the scratch directory.
+const SCRATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Scratch root used when the worker config sets none.
+pub fn default_scratch_root() -> PathBuf {
+    std::env::temp_dir().join("casparian-scratch")
+}
+
+/// Point a plugin process at its scratch directory.
+pub fn configure(command: &mut Command, dir: &Path) {
+    command
+        .env(SCRATCH_DIR_ENV, dir)
+        .env("TMPDIR", dir)
+        .env("TMP", dir)
+        .env("TEMP", dir);
+}
+
+/// Scratch directory of one job, removed on drop.
+pub struct ScratchDir {
+    dir: Option<tempfile::TempDir>,
+    quota: Option<u64>,
+    usage: Arc<ScratchUsage>,
+    monitor: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct ScratchUsage {
+    peak_bytes: AtomicU64,
+    /// Set once the quota is exceeded; trips the run token
+    exceeded: Arc<AtomicBool>,
+    stop: AtomicBool,
+}
+
+impl ScratchUsage {
+    fn measure(&self, dir: &Path, quota: Option<u64>) {
+        let bytes = dir_size(dir);
+        self.peak_bytes.fetch_max(bytes, Ordering::Relaxed);
+        if quota.is_some_and(|quota| bytes > quota) {
+            self.exceeded.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+impl ScratchDir {
+    /// Create the scratch directory of `job_id` under `root` and start
+    /// watching its size against `limits.max_scratch_bytes`.
+    pub fn create(root: &Path, job_id: JobId, limits: &ResourceLimits) -> Result<Self> {
+        std::fs::create_dir_all(root)
+            .with_context(|| format!("Failed to create scratch root {}", root.display()))?;
+        let dir = tempfile::Builder::new()
+            .prefix(&format!("job-{}-", job_id))
+            .tempdir_in(root)
+            .with_context(|| format!("Failed to create scratch directory in {}", root.display()))?;
+
+        let quota = limits.max_scratch_bytes;
+        let usage = Arc::new(ScratchUsage::default());
+        let monitor = {
+            let usage = usage.clone();
+            let path = dir.path().to_path_buf();
+            std::thread::Builder::new()
+                .name(format!("scratch-{}", job_id))
+                .spawn(move || {
+                    while !usage.stop.load(Ordering::SeqCst) {
+                        usage.measure(&path, quota);
+                        std::thread::park_timeout(SCRATCH_POLL_INTERVAL);
+                    }
+                })
+                .context("Failed to start scratch monitor")?
+        };
+
+        Ok(Self {
+            dir: Some(dir),
+            quota,
+            usage,
+            monitor: Some(monitor),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir
+            .as_ref()
+            .expect("scratch directory present until drop")
+            .path()
+    }
+
+    /// `token`, tripped as well once the directory exceeds its quota.
+    pub fn watch(&self, token: &CancellationToken) -> CancellationToken {
+        token.with_stop_flag(self.usage.exceeded.clone())
+    }
+
+    /// Stop the monitor and take a final measurement. Returns the peak size
+    /// of the directory, in bytes.
+    pub fn finish(&mut self) -> u64 {
+        self.stop_monitor();
+        if let Some(dir) = &self.dir {
+            self.usage.measure(dir.path(), self.quota);
+        }
+        self.peak_bytes()
+    }
+
+    pub fn peak_bytes(&self) -> u64 {
+        self.usage.peak_bytes.load(Ordering::Relaxed)
+    }
+
+    /// The quota violation, if the directory ever grew past it.
+    pub fn exceeded(&self, limits: &ResourceLimits) -> Option<LimitExceeded> {
+        self.usage
+            .exceeded
+            .load(Ordering::SeqCst)
+            .then(|| LimitExceeded::new(LimitKind::ScratchSpace, limits))
+    }
+
+    fn stop_monitor(&mut self) {
+        if let Some(monitor) = self.monitor.take() {
+            self.usage.stop.store(true, Ordering::SeqCst);
+            monitor.thread().unpark();
+            let _ = monitor.join();
+        }
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        self.stop_monitor();
+        if let Some(dir) = self.dir.take() {
+            let path = dir.path().to_path_buf();
+            if let Err(err) = dir.close() {
+                warn!(
+                    "Failed to remove scratch directory {}: {}",
+                    path.display(),
+                    err
+                );
+            }
+        }
+    }
+}
+
+/// Bytes of the regular files under `dir`. Entries removed mid-walk are
+/// skipped.
+fn dir_size(dir: &Path) -> u64 {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scratch_dir_quota_and_cleanup() {
+        let root = tempfile::tempdir().unwrap();
+        let limits = ResourceLimits {
+            max_scratch_bytes: Some(1024),
+            ..Default::default()
+        };
+        let mut scratch = ScratchDir::create(root.path(), JobId::new(7), &limits).unwrap();
+        let path = scratch.path().to_path_buf();
+        let token = CancellationToken::new();
+        let run_token = scratch.watch(&token);
+
+        std::fs::write(path.join("small.bin"), vec![0u8; 512]).unwrap();
+        assert_eq!(scratch.finish(), 512);
+        assert!(scratch.exceeded(&limits).is_none());
+        assert!(!run_token.is_cancelled());
+
+        std::fs::create_dir(path.join("nested")).unwrap();
+        std::fs::write(path.join("nested/big.bin"), vec![0u8; 2048]).unwrap();
+        assert_eq!(scratch.finish(), 2560);
+        assert_eq!(
+            scratch.exceeded(&limits).unwrap().kind,
+            LimitKind::ScratchSpace
+        );
+        assert!(run_token.is_cancelled());
+        assert!(!token.is_cancelled());
+
+        drop(scratch);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,281 @@
+//! Transcoding of non-UTF-8 text inputs.
+//!
+//! Parsers read UTF-8. A text input in UTF-16 or a legacy single-byte
+//! encoding is detected from its head (BOM, NUL byte layout, UTF-8 validity)
+//! and streamed through encoding_rs into a UTF-8 temp file that the parser
+//! reads in place of the original. The original path stays the job's input;
+//! the detected encoding is reported as `source_encoding` and recorded on the
+//! lineage hop.
+//!
+//! Binary inputs (parquet, xlsx, ...) are left alone, even when a routing
+//! rule overrides the encoding.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use casparian_protocol::TextEncoding;
+use encoding_rs::{CoderResult, Encoding};
+
+use crate::cancel::CancellationToken;
+
+/// Bytes of the input inspected to detect its encoding.
+const HEAD_BYTES: usize = 64 * 1024;
+
+const COPY_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Transcoded copy of a job input; the temp file is removed on drop.
+pub struct TranscodedInput {
+    file: tempfile::NamedTempFile,
+    /// Encoding the input was read as
+    pub encoding: TextEncoding,
+    /// Size of the UTF-8 output
+    pub bytes: u64,
+    /// Whether malformed sequences were replaced with U+FFFD
+    pub had_errors: bool,
+}
+
+impl TranscodedInput {
+    /// Path the parser should read.
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+
+/// Encoding of a text input from its first bytes, or `None` for binary data.
+///
+/// `complete` is true when `head` is the whole file, so a multi-byte UTF-8
+/// sequence cut off at the end is an error rather than a truncated read.
+pub fn detect_encoding(head: &[u8], complete: bool) -> Option<TextEncoding> {
+    if head.starts_with(&[0xef, 0xbb, 0xbf]) {
+        return Some(TextEncoding::Utf8);
+    }
+    if head.starts_with(&[0xff, 0xfe]) {
+        return Some(TextEncoding::Utf16Le);
+    }
+    if head.starts_with(&[0xfe, 0xff]) {
+        return Some(TextEncoding::Utf16Be);
+    }
+    if head.contains(&0) {
+        return detect_utf16_without_bom(head);
+    }
+    if head
+        .iter()
+        .any(|&b| (b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1a)) || b == 0x7f)
+    {
+        return None;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => Some(TextEncoding::Utf8),
+        Err(err) if err.error_len().is_none() && !complete => Some(TextEncoding::Utf8),
+        Err(_) => Some(TextEncoding::Windows1252),
+    }
+}
+
+/// UTF-16 text without a BOM has a NUL in every other byte for ASCII content:
+/// the high byte of each code unit, odd offsets for LE and even for BE.
+fn detect_utf16_without_bom(head: &[u8]) -> Option<TextEncoding> {
+    let units = head.len() / 2;
+    if units == 0 {
+        return None;
+    }
+    let even_nuls = head.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd_nuls = head.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    if odd_nuls * 10 >= units * 7 && even_nuls == 0 {
+        Some(TextEncoding::Utf16Le)
+    } else if even_nuls * 10 >= units * 7 && odd_nuls == 0 {
+        Some(TextEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn encoding_rs_for(encoding: TextEncoding) -> &'static Encoding {
+    match encoding {
+        TextEncoding::Utf8 => encoding_rs::UTF_8,
+        TextEncoding::Utf16Le => encoding_rs::UTF_16LE,
+        TextEncoding::Utf16Be => encoding_rs::UTF_16BE,
+        // The WHATWG decoder for latin-1 is windows-1252, which only differs
+        // in 0x80-0x9F (C1 controls in latin-1, never used as text).
+        TextEncoding::Latin1 | TextEncoding::Windows1252 => encoding_rs::WINDOWS_1252,
+    }
+}
+
+/// Transcode `input_path` into a UTF-8 temp file if it is non-UTF-8 text.
+///
+/// `encoding_override` replaces the detected encoding of a text input.
+/// Returns `None` for UTF-8 and binary inputs.
+pub fn transcode_input(
+    input_path: &Path,
+    encoding_override: Option<TextEncoding>,
+    cancel_token: &CancellationToken,
+) -> Result<Option<TranscodedInput>> {
+    let mut input = File::open(input_path)
+        .with_context(|| format!("Failed to open input {}", input_path.display()))?;
+    let mut head = Vec::with_capacity(HEAD_BYTES);
+    Read::by_ref(&mut input)
+        .take(HEAD_BYTES as u64 + 1)
+        .read_to_end(&mut head)?;
+    let complete = head.len() <= HEAD_BYTES;
+    let Some(detected) = detect_encoding(&head[..head.len().min(HEAD_BYTES)], complete) else {
+        return Ok(None);
+    };
+    let encoding = encoding_override.unwrap_or(detected);
+    if encoding == TextEncoding::Utf8 {
+        return Ok(None);
+    }
+
+    let mut output = tempfile::Builder::new()
+        .prefix("casparian-transcoded-")
+        .suffix(
+            &input_path
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy()))
+                .unwrap_or_default(),
+        )
+        .tempfile()
+        .context("Failed to create transcoded input file")?;
+    let mut decoder = encoding_rs_for(encoding).new_decoder_with_bom_removal();
+    let mut source = head.as_slice().chain(input);
+    let mut buffer = vec![0u8; COPY_CHUNK_BYTES];
+    let mut utf8 = vec![0u8; COPY_CHUNK_BYTES * 3 + 16];
+    let mut bytes = 0u64;
+    let mut had_errors = false;
+    loop {
+        if cancel_token.is_cancelled() {
+            anyhow::bail!("Transcoding of {} cancelled", input_path.display());
+        }
+        let read = source
+            .read(&mut buffer)
+            .with_context(|| format!("Failed to read input {}", input_path.display()))?;
+        let last = read == 0;
+        let mut pending = &buffer[..read];
+        loop {
+            let (result, consumed, written, replaced) =
+                decoder.decode_to_utf8(pending, &mut utf8, last);
+            had_errors |= replaced;
+            output.as_file_mut().write_all(&utf8[..written])?;
+            bytes += written as u64;
+            pending = &pending[consumed..];
+            if result == CoderResult::InputEmpty {
+                break;
+            }
+        }
+        if last {
+            break;
+        }
+    }
+    output.as_file_mut().flush()?;
+
+    Ok(Some(TranscodedInput {
+        file: output,
+        encoding,
+        bytes,
+        had_errors,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, big_endian: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let units = bom.then_some(0xfeff).into_iter().chain(text.encode_utf16());
+        for unit in units {
+            if big_endian {
+                bytes.extend_from_slice(&unit.to_be_bytes());
+            } else {
+                bytes.extend_from_slice(&unit.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(
+            detect_encoding("id,name\n1,é\n".as_bytes(), true),
+            Some(TextEncoding::Utf8)
+        );
+        assert_eq!(
+            detect_encoding(b"id,name\n1,caf\xe9\n", true),
+            Some(TextEncoding::Windows1252)
+        );
+        assert_eq!(
+            detect_encoding(&utf16("id,name\n", false, true), true),
+            Some(TextEncoding::Utf16Le)
+        );
+        assert_eq!(
+            detect_encoding(&utf16("id,name\n", true, false), true),
+            Some(TextEncoding::Utf16Be)
+        );
+        assert_eq!(
+            detect_encoding(&utf16("id,name\n", false, false), true),
+            Some(TextEncoding::Utf16Le)
+        );
+        // A UTF-8 sequence cut off by the head read is not an error
+        assert_eq!(
+            detect_encoding(&"id,é".as_bytes()[..4], false),
+            Some(TextEncoding::Utf8)
+        );
+        assert_eq!(detect_encoding(b"PAR1\x15\x04\x15\x00", true), None);
+        assert_eq!(detect_encoding(b"PK\x03\x04\x14\x00", true), None);
+    }
+
+    #[test]
+    fn test_transcodes_to_utf8() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = "id,name\n1,café\n2,naïve €\n".repeat(5000);
+
+        let path = dir.path().join("utf16.csv");
+        std::fs::write(&path, utf16(&text, false, true)).unwrap();
+        let input = transcode_input(&path, None, &CancellationToken::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(input.encoding, TextEncoding::Utf16Le);
+        assert!(!input.had_errors);
+        assert_eq!(std::fs::read_to_string(input.path()).unwrap(), text);
+        assert_eq!(input.path().extension().unwrap(), "csv");
+
+        let path = dir.path().join("legacy.csv");
+        let (legacy, _, _) = encoding_rs::WINDOWS_1252.encode(&text);
+        std::fs::write(&path, &legacy).unwrap();
+        let input = transcode_input(&path, None, &CancellationToken::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(input.encoding, TextEncoding::Windows1252);
+        assert_eq!(std::fs::read_to_string(input.path()).unwrap(), text);
+        assert_eq!(input.bytes, text.len() as u64);
+
+        let path = dir.path().join("plain.csv");
+        std::fs::write(&path, &text).unwrap();
+        assert!(transcode_input(&path, None, &CancellationToken::new())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_override_applies_to_text_only() {
+        let dir = tempfile::tempdir().unwrap();
+        // Valid UTF-8 that is really latin-1 double-encoded text
+        let path = dir.path().join("names.txt");
+        std::fs::write(&path, b"caf\xc3\xa9\n").unwrap();
+        let input = transcode_input(&path, Some(TextEncoding::Latin1), &CancellationToken::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(input.encoding, TextEncoding::Latin1);
+        assert_eq!(std::fs::read_to_string(input.path()).unwrap(), "cafÃ©\n");
+
+        let path = dir.path().join("data.parquet");
+        std::fs::write(&path, b"PAR1\x15\x04\x15\x00\x15\x00PAR1").unwrap();
+        assert!(transcode_input(
+            &path,
+            Some(TextEncoding::Windows1252),
+            &CancellationToken::new()
+        )
+        .unwrap()
+        .is_none());
+    }
+}
@@ -3,7 +3,7 @@
 //! This module handles date format inference using constraint-based elimination.
 //! When a value like "31/05/24" is seen, formats where 31 could be a month are eliminated.
 
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 
 /// Common date formats to try (ordered by popularity)
 pub const DATE_FORMATS: &[DateFormatSpec] = &[
@@ -169,6 +169,31 @@ pub const DATE_FORMATS: &[DateFormatSpec] = &[
     },
 ];
 
+/// ISO 8601 datetime formats, most specific first. `%.f` also matches a
+/// value without fractional seconds. Offsets are normalized to UTC by
+/// [`parse_datetime`].
+pub const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.fZ",
+    "%Y-%m-%dT%H:%M:%S%.f%:z",
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Parse a datetime with one of [`DATETIME_FORMATS`], as UTC.
+pub fn parse_datetime(value: &str, format: &str) -> Option<NaiveDateTime> {
+    let trimmed = value.trim();
+    if format.ends_with("%:z") {
+        DateTime::parse_from_str(trimmed, format)
+            .ok()
+            .map(|datetime| datetime.naive_utc())
+    } else {
+        NaiveDateTime::parse_from_str(trimmed, format).ok()
+    }
+}
+
 /// Date format specification with component positions
 #[derive(Debug, Clone)]
 pub struct DateFormatSpec {
@@ -492,6 +517,30 @@ use chrono::Datelike;
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_datetime_formats() {
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(10, 30, 0)
+            .unwrap();
+        assert_eq!(
+            parse_datetime("2024-01-15T10:30:00", "%Y-%m-%dT%H:%M:%S%.f"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_datetime("2024-01-15T12:30:00+02:00", "%Y-%m-%dT%H:%M:%S%.f%:z"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_datetime("2024-01-15 10:30", "%Y-%m-%d %H:%M"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_datetime("2024-01-15T10:30:00+02:00", "%Y-%m-%dT%H:%M:%S%.f"),
+            None
+        );
+    }
+
     #[test]
     fn test_days_in_month() {
         // Normal months
@@ -0,0 +1,104 @@
+//! Per-column confidence and sample evidence
+//!
+//! Elimination proves which types a column *can* be. Evidence records how
+//! well each type fits the values actually seen, so a reviewer can tell a
+//! clean integer column from a text column that is 98% integers, and see
+//! the values behind each verdict.
+
+use casparian_protocol::types::{ColumnInference, InferredTypeCandidate};
+
+use super::{DataType, DecimalShape};
+
+/// How well one type fits the non-null values of a column
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeEvidence {
+    pub data_type: DataType,
+    /// Non-null values that parse as this type
+    pub matched: usize,
+    /// `matched` as a share of the non-null values (0.0 - 1.0)
+    pub confidence: f64,
+    /// First distinct values that parse as this type
+    pub samples: Vec<String>,
+    /// First distinct values that do not
+    pub counterexamples: Vec<String>,
+}
+
+/// Inferred type of a column with its confidence and per-type evidence
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnEvidence {
+    pub data_type: DataType,
+    /// Precision and scale when `data_type` is [`DataType::Decimal`]
+    pub decimal: Option<DecimalShape>,
+    /// Confidence that `data_type` is the column's type (0.0 - 1.0).
+    /// Below 1.0 when values did not all parse, or when another
+    /// interpretation (a boolean 0/1 column, an ambiguous date format)
+    /// fits the values equally well.
+    pub confidence: f64,
+    /// Non-null values the evidence was gathered from
+    pub non_null_values: usize,
+    /// Every type at least one value parsed as, best fit first
+    pub candidates: Vec<TypeEvidence>,
+}
+
+impl ColumnEvidence {
+    /// Evidence for one candidate type, if any value parsed as it
+    pub fn candidate(&self, data_type: DataType) -> Option<&TypeEvidence> {
+        self.candidates.iter().find(|c| c.data_type == data_type)
+    }
+
+    /// The inferred type in protocol terms, with the decimal shape applied
+    pub fn protocol_data_type(&self) -> casparian_protocol::DataType {
+        protocol_type(self.data_type, self.decimal)
+    }
+
+    /// Protocol form attached to observed columns for schema review
+    pub fn to_protocol(&self) -> ColumnInference {
+        ColumnInference {
+            data_type: self.protocol_data_type(),
+            confidence: self.confidence,
+            candidates: self
+                .candidates
+                .iter()
+                .map(|candidate| InferredTypeCandidate {
+                    data_type: protocol_type(candidate.data_type, self.decimal),
+                    matched: candidate.matched as u64,
+                    confidence: candidate.confidence,
+                    samples: candidate.samples.clone(),
+                    counterexamples: candidate.counterexamples.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn protocol_type(
+    data_type: DataType,
+    decimal: Option<DecimalShape>,
+) -> casparian_protocol::DataType {
+    match (data_type, decimal) {
+        (DataType::Decimal, Some(shape)) => shape.into(),
+        (data_type, _) => data_type.into(),
+    }
+}
+
+/// Running match counts and samples for one type
+#[derive(Debug, Clone, Default)]
+pub(super) struct TypeTally {
+    pub(super) matched: usize,
+    pub(super) samples: Vec<String>,
+    pub(super) counterexamples: Vec<String>,
+}
+
+impl TypeTally {
+    pub(super) fn record(&mut self, value: &str, matched: bool, sample_limit: usize) {
+        let bucket = if matched {
+            self.matched += 1;
+            &mut self.samples
+        } else {
+            &mut self.counterexamples
+        };
+        if bucket.len() < sample_limit && !bucket.iter().any(|seen| seen == value) {
+            bucket.push(value.to_string());
+        }
+    }
+}
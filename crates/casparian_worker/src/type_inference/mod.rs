@@ -27,14 +27,16 @@
 
 pub mod constraints;
 pub mod date_formats;
+pub mod evidence;
 pub mod solver;
 pub mod streaming;
 
 pub use constraints::{
     Constraint, Contradiction, EliminationEvidence, EliminationReason, TypeInferenceResult,
 };
-pub use date_formats::{ParsedDate, DATE_FORMATS};
-pub use solver::ConstraintSolver;
+pub use date_formats::{ParsedDate, DATETIME_FORMATS, DATE_FORMATS};
+pub use evidence::{ColumnEvidence, TypeEvidence};
+pub use solver::{ConstraintSolver, InferenceConfig};
 pub use streaming::infer_types_streaming;
 
 /// Type inference engine data types.
@@ -51,6 +53,7 @@ pub use streaming::infer_types_streaming;
 /// | Inference | Protocol | Arrow |
 /// |-----------|----------|-------|
 /// | Integer | Int64 | Int64 |
+/// | Decimal | Decimal | Decimal128 |
 /// | Float | Float64 | Float64 |
 /// | DateTime | Timestamp | Timestamp |
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -61,6 +64,9 @@ pub enum DataType {
     Boolean,
     /// 64-bit signed integer
     Integer,
+    /// Fixed-point number (e.g. "12.50"); precision and scale are
+    /// captured separately as a [`DecimalShape`]
+    Decimal,
     /// 64-bit floating point
     Float,
     /// Date (no time component)
@@ -82,6 +88,7 @@ impl DataType {
             DataType::Null,
             DataType::Boolean,
             DataType::Integer,
+            DataType::Decimal,
             DataType::Float,
             DataType::Date,
             DataType::DateTime,
@@ -93,7 +100,7 @@ impl DataType {
 
     /// Returns numeric types
     pub fn numeric() -> Vec<DataType> {
-        vec![DataType::Integer, DataType::Float]
+        vec![DataType::Integer, DataType::Decimal, DataType::Float]
     }
 
     /// Returns temporal types
@@ -103,7 +110,10 @@ impl DataType {
 
     /// Returns true if this type is numeric
     pub fn is_numeric(&self) -> bool {
-        matches!(self, DataType::Integer | DataType::Float)
+        matches!(
+            self,
+            DataType::Integer | DataType::Decimal | DataType::Float
+        )
     }
 
     /// Returns true if this type is temporal
@@ -118,6 +128,7 @@ impl std::fmt::Display for DataType {
             DataType::Null => write!(f, "null"),
             DataType::Boolean => write!(f, "boolean"),
             DataType::Integer => write!(f, "integer"),
+            DataType::Decimal => write!(f, "decimal"),
             DataType::Float => write!(f, "float"),
             DataType::Date => write!(f, "date"),
             DataType::DateTime => write!(f, "datetime"),
@@ -128,6 +139,63 @@ impl std::fmt::Display for DataType {
     }
 }
 
+/// Precision and scale covering every value of a decimal column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DecimalShape {
+    /// Total significant digits
+    pub precision: u8,
+    /// Digits after the decimal point
+    pub scale: u8,
+}
+
+impl DecimalShape {
+    /// Shape used when a decimal type is converted without an observed one:
+    /// Decimal128's full precision.
+    pub const DEFAULT: DecimalShape = DecimalShape {
+        precision: 38,
+        scale: 10,
+    };
+
+    /// Shape of a single fixed-point literal such as `-012.50`
+    /// (precision 4, scale 2). Exponents, thousands separators and other
+    /// characters are not decimals.
+    pub fn of(value: &str) -> Option<DecimalShape> {
+        let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+        let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction)
+        {
+            return None;
+        }
+        let integer_digits = whole.trim_start_matches('0').len();
+        let scale = u8::try_from(fraction.len()).ok()?;
+        let precision = u8::try_from(integer_digits + fraction.len()).ok()?;
+        Some(DecimalShape {
+            precision: precision.max(1),
+            scale,
+        })
+    }
+
+    /// Smallest shape that holds values of both shapes.
+    pub fn widen(self, other: DecimalShape) -> DecimalShape {
+        let integer_digits = (self.precision - self.scale).max(other.precision - other.scale);
+        let scale = self.scale.max(other.scale);
+        DecimalShape {
+            precision: integer_digits.saturating_add(scale).max(1),
+            scale,
+        }
+    }
+}
+
+impl From<DecimalShape> for casparian_protocol::DataType {
+    fn from(shape: DecimalShape) -> Self {
+        casparian_protocol::DataType::Decimal {
+            precision: shape.precision,
+            scale: shape.scale,
+        }
+    }
+}
+
 // ============================================================================
 // Conversions to/from canonical casparian_protocol::DataType
 // ============================================================================
@@ -138,6 +206,7 @@ impl From<DataType> for casparian_protocol::DataType {
             DataType::Null => casparian_protocol::DataType::Null,
             DataType::Boolean => casparian_protocol::DataType::Boolean,
             DataType::Integer => casparian_protocol::DataType::Int64,
+            DataType::Decimal => DecimalShape::DEFAULT.into(),
             DataType::Float => casparian_protocol::DataType::Float64,
             DataType::Date => casparian_protocol::DataType::Date,
             DataType::DateTime => casparian_protocol::DataType::Timestamp,
@@ -162,7 +231,7 @@ impl From<casparian_protocol::DataType> for DataType {
             casparian_protocol::DataType::Duration => DataType::Duration,
             casparian_protocol::DataType::String => DataType::String,
             casparian_protocol::DataType::Binary => DataType::String, // Fallback
-            casparian_protocol::DataType::Decimal { .. } => DataType::Float,
+            casparian_protocol::DataType::Decimal { .. } => DataType::Decimal,
             casparian_protocol::DataType::List { .. } => DataType::String,
             casparian_protocol::DataType::Struct { .. } => DataType::String,
         }
@@ -189,6 +258,45 @@ mod tests {
         assert!(!DataType::Date.is_numeric());
     }
 
+    #[test]
+    fn test_decimal_shape() {
+        assert_eq!(
+            DecimalShape::of("-012.50"),
+            Some(DecimalShape {
+                precision: 4,
+                scale: 2
+            })
+        );
+        assert_eq!(
+            DecimalShape::of("0.005"),
+            Some(DecimalShape {
+                precision: 3,
+                scale: 3
+            })
+        );
+        assert_eq!(
+            DecimalShape::of("42"),
+            Some(DecimalShape {
+                precision: 2,
+                scale: 0
+            })
+        );
+        assert_eq!(DecimalShape::of("1e5"), None);
+        assert_eq!(DecimalShape::of("1,234.5"), None);
+        assert_eq!(DecimalShape::of("."), None);
+
+        let wide = DecimalShape::of("12345.6")
+            .unwrap()
+            .widen(DecimalShape::of("0.125").unwrap());
+        assert_eq!(
+            wide,
+            DecimalShape {
+                precision: 8,
+                scale: 3
+            }
+        );
+    }
+
     #[test]
     fn test_datatype_is_temporal() {
         assert!(DataType::Date.is_temporal());
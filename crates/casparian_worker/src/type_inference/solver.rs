@@ -9,10 +9,42 @@ use super::constraints::{
     Constraint, EliminatedItem, EliminationEvidence, EliminationReason, TypeInferenceResult,
 };
 use super::date_formats::{
-    can_be_day, can_be_month, days_in_month, extract_components, try_parse_date, DateFormatSpec,
-    DATE_FORMATS,
+    can_be_day, can_be_month, days_in_month, extract_components, parse_datetime, try_parse_date,
+    DateFormatSpec, DATETIME_FORMATS, DATE_FORMATS,
 };
-use super::DataType;
+use super::evidence::{ColumnEvidence, TypeEvidence, TypeTally};
+use super::{DataType, DecimalShape};
+
+/// Types whose fit is tallied per value for [`ConstraintSolver::evidence`]
+const EVIDENCE_TYPES: &[DataType] = &[
+    DataType::Boolean,
+    DataType::Integer,
+    DataType::Decimal,
+    DataType::Float,
+    DataType::Date,
+    DataType::DateTime,
+    DataType::Time,
+    DataType::Duration,
+];
+
+/// Tunables for a [`ConstraintSolver`]
+#[derive(Debug, Clone)]
+pub struct InferenceConfig {
+    /// Widest decimal (total digits) still inferred as Decimal; wider
+    /// fixed-point columns fall back to Float
+    pub max_decimal_precision: u8,
+    /// Sample values and counterexamples kept per type as evidence
+    pub sample_limit: usize,
+}
+
+impl Default for InferenceConfig {
+    fn default() -> Self {
+        Self {
+            max_decimal_precision: 38,
+            sample_limit: 5,
+        }
+    }
+}
 
 /// Constraint-based type inference solver
 ///
@@ -22,6 +54,8 @@ pub struct ConstraintSolver {
     /// Column name (for debugging)
     column_name: String,
 
+    config: InferenceConfig,
+
     /// Types that are still possible
     possible_types: HashSet<DataType>,
 
@@ -29,6 +63,15 @@ pub struct ConstraintSolver {
     /// Key is the format pattern string
     date_format_candidates: HashSet<String>,
 
+    /// Datetime formats still possible, in [`DATETIME_FORMATS`] order
+    datetime_format_candidates: Vec<&'static str>,
+
+    /// Shape covering every decimal value seen so far
+    decimal_shape: Option<DecimalShape>,
+
+    /// Per-type match counts and samples (for confidence and review)
+    tallies: HashMap<DataType, TypeTally>,
+
     /// Evidence of eliminations (for explainability)
     elimination_evidence: Vec<EliminationEvidence>,
 
@@ -55,6 +98,11 @@ pub struct ConstraintSolver {
 impl ConstraintSolver {
     /// Create a new solver for a column
     pub fn new(column_name: impl Into<String>) -> Self {
+        Self::with_config(column_name, InferenceConfig::default())
+    }
+
+    /// Create a solver with non-default tunables
+    pub fn with_config(column_name: impl Into<String>, config: InferenceConfig) -> Self {
         let mut possible_types = HashSet::new();
         for dtype in DataType::all() {
             possible_types.insert(dtype);
@@ -65,8 +113,12 @@ impl ConstraintSolver {
 
         Self {
             column_name: column_name.into(),
+            config,
             possible_types,
             date_format_candidates,
+            datetime_format_candidates: DATETIME_FORMATS.to_vec(),
+            decimal_shape: None,
+            tallies: HashMap::new(),
             elimination_evidence: Vec::new(),
             values_processed: 0,
             null_count: 0,
@@ -101,6 +153,43 @@ impl ConstraintSolver {
         self.apply_datetime_constraints(trimmed);
         self.apply_time_constraints(trimmed);
         self.apply_duration_constraints(trimmed);
+        self.record_evidence(trimmed);
+    }
+
+    /// Tally which types this value parses as, independent of elimination
+    fn record_evidence(&mut self, value: &str) {
+        for data_type in EVIDENCE_TYPES {
+            let matched = self.value_is(*data_type, value);
+            self.tallies.entry(*data_type).or_default().record(
+                value,
+                matched,
+                self.config.sample_limit,
+            );
+        }
+    }
+
+    /// Whether `value` on its own parses as `data_type`
+    fn value_is(&self, data_type: DataType, value: &str) -> bool {
+        match data_type {
+            DataType::Boolean => is_boolean_token(value),
+            DataType::Integer => value.parse::<i64>().is_ok(),
+            DataType::Decimal => DecimalShape::of(value)
+                .is_some_and(|shape| shape.precision <= self.config.max_decimal_precision),
+            DataType::Float => self.is_potentially_numeric(value) && value.parse::<f64>().is_ok(),
+            DataType::Date => {
+                (self.detect_separator(value).is_some()
+                    || (matches!(value.len(), 6 | 8) && value.bytes().all(|b| b.is_ascii_digit())))
+                    && DATE_FORMATS.iter().any(|format| {
+                        try_parse_date(value, format).is_some_and(|parsed| parsed.is_valid())
+                    })
+            }
+            DataType::DateTime => DATETIME_FORMATS
+                .iter()
+                .any(|format| parse_datetime(value, format).is_some()),
+            DataType::Time => time_violation(value).is_none(),
+            DataType::Duration => looks_like_duration(value),
+            DataType::Null | DataType::String => false,
+        }
     }
 
     /// Apply constraints for boolean type
@@ -109,17 +198,7 @@ impl ConstraintSolver {
             return;
         }
 
-        // F-002: Use eq_ignore_ascii_case() instead of to_lowercase() to avoid allocation
-        let is_boolean = value.eq_ignore_ascii_case("true")
-            || value.eq_ignore_ascii_case("false")
-            || value.eq_ignore_ascii_case("yes")
-            || value.eq_ignore_ascii_case("no")
-            || matches!(
-                value,
-                "y" | "Y" | "n" | "N" | "1" | "0" | "t" | "T" | "f" | "F"
-            );
-
-        if !is_boolean {
+        if !is_boolean_token(value) {
             self.eliminate_type(
                 DataType::Boolean,
                 EliminationReason::NotBooleanValue {
@@ -129,34 +208,29 @@ impl ConstraintSolver {
         }
     }
 
-    /// Apply constraints for numeric types (Integer and Float)
+    /// Apply constraints for numeric types (Integer, Decimal and Float)
     fn apply_numeric_constraints(&mut self, value: &str) {
         // Check if it looks numeric at all
         let is_potentially_numeric = self.is_potentially_numeric(value);
 
         if !is_potentially_numeric {
-            // Eliminate both Integer and Float
-            if self.possible_types.contains(&DataType::Integer) {
-                self.eliminate_type(
-                    DataType::Integer,
-                    EliminationReason::InvalidCharacters {
-                        value: value.to_string(),
-                        chars: "non-numeric characters".to_string(),
-                    },
-                );
-            }
-            if self.possible_types.contains(&DataType::Float) {
-                self.eliminate_type(
-                    DataType::Float,
-                    EliminationReason::InvalidCharacters {
-                        value: value.to_string(),
-                        chars: "non-numeric characters".to_string(),
-                    },
-                );
+            // Eliminate all numeric types
+            for data_type in DataType::numeric() {
+                if self.possible_types.contains(&data_type) {
+                    self.eliminate_type(
+                        data_type,
+                        EliminationReason::InvalidCharacters {
+                            value: value.to_string(),
+                            chars: "non-numeric characters".to_string(),
+                        },
+                    );
+                }
             }
             return;
         }
 
+        self.apply_decimal_constraints(value);
+
         // Track if we've seen decimal points
         if value.contains('.') {
             self.has_decimal_point = true;
@@ -190,6 +264,43 @@ impl ConstraintSolver {
         }
     }
 
+    /// Apply constraints for fixed-point decimals, widening the column's
+    /// precision and scale to cover the value
+    fn apply_decimal_constraints(&mut self, value: &str) {
+        if !self.possible_types.contains(&DataType::Decimal) {
+            return;
+        }
+
+        let Some(shape) = DecimalShape::of(value) else {
+            self.eliminate_type(
+                DataType::Decimal,
+                EliminationReason::PatternMismatch {
+                    value: value.to_string(),
+                    expected: "fixed-point decimal (e.g., 12.50)".to_string(),
+                },
+            );
+            return;
+        };
+
+        let widened = match self.decimal_shape {
+            Some(current) => current.widen(shape),
+            None => shape,
+        };
+        if widened.precision > self.config.max_decimal_precision {
+            self.eliminate_type(
+                DataType::Decimal,
+                EliminationReason::OutOfRange {
+                    value: value.to_string(),
+                    component: "decimal precision".to_string(),
+                    actual: i32::from(widened.precision),
+                    max: i32::from(self.config.max_decimal_precision),
+                },
+            );
+            return;
+        }
+        self.decimal_shape = Some(widened);
+    }
+
     /// Check if a value looks potentially numeric
     fn is_potentially_numeric(&self, value: &str) -> bool {
         // Allow optional leading sign
@@ -439,57 +550,49 @@ impl ConstraintSolver {
                     expected: "date and time separated by space or T".to_string(),
                 },
             );
-        }
-    }
-
-    /// Apply constraints for time type
-    fn apply_time_constraints(&mut self, value: &str) {
-        if !self.possible_types.contains(&DataType::Time) {
             return;
         }
 
-        // Time should have colons and be relatively short
-        let has_colon = value.contains(':');
-        let parts: Vec<&str> = value.split(':').collect();
+        // Keep only the formats that parse this value
+        let mut eliminated = Vec::new();
+        self.datetime_format_candidates.retain(|format| {
+            let parses = parse_datetime(value, format).is_some();
+            if !parses {
+                eliminated.push(*format);
+            }
+            parses
+        });
+        for format in eliminated {
+            self.elimination_evidence.push(EliminationEvidence {
+                eliminated: EliminatedItem::DateTimeFormat(format.to_string()),
+                reason: EliminationReason::ParseFailed {
+                    value: value.to_string(),
+                    error: "chrono parse failed".to_string(),
+                },
+                row_index: self.values_processed - 1,
+                value: value.to_string(),
+            });
+        }
 
-        if !has_colon || parts.len() < 2 || parts.len() > 3 {
+        if self.datetime_format_candidates.is_empty() {
             self.eliminate_type(
-                DataType::Time,
-                EliminationReason::PatternMismatch {
+                DataType::DateTime,
+                EliminationReason::Custom {
                     value: value.to_string(),
-                    expected: "HH:MM or HH:MM:SS format".to_string(),
+                    reason: "all datetime formats eliminated".to_string(),
                 },
             );
-            return;
         }
+    }
 
-        // Check hour and minute ranges
-        if let Ok(hour) = parts[0].trim().parse::<i32>() {
-            if hour < 0 || hour > 23 {
-                self.eliminate_type(
-                    DataType::Time,
-                    EliminationReason::OutOfRange {
-                        value: value.to_string(),
-                        component: "hour".to_string(),
-                        actual: hour,
-                        max: 23,
-                    },
-                );
-            }
+    /// Apply constraints for time type
+    fn apply_time_constraints(&mut self, value: &str) {
+        if !self.possible_types.contains(&DataType::Time) {
+            return;
         }
 
-        if let Ok(minute) = parts[1].trim().parse::<i32>() {
-            if minute < 0 || minute > 59 {
-                self.eliminate_type(
-                    DataType::Time,
-                    EliminationReason::OutOfRange {
-                        value: value.to_string(),
-                        component: "minute".to_string(),
-                        actual: minute,
-                        max: 59,
-                    },
-                );
-            }
+        if let Some(reason) = time_violation(value) {
+            self.eliminate_type(DataType::Time, reason);
         }
     }
 
@@ -499,74 +602,15 @@ impl ConstraintSolver {
             return;
         }
 
-        // Duration formats typically look like:
-        // - ISO 8601: PT1H30M, P1DT2H, etc.
-        // - Human readable: 1h30m, 2 hours, 30 min, etc.
-        // - Numeric durations: values like "10.50" are NOT durations
-
-        // Check for ISO 8601 duration (starts with P) - case insensitive without allocation
-        let first_char = value.chars().next();
-        if matches!(first_char, Some('p') | Some('P')) {
-            // Check for duration markers in rest of string
-            let has_duration_marker = value[1..]
-                .chars()
-                .any(|c| matches!(c, 't' | 'T' | 'y' | 'Y' | 'm' | 'M' | 'd' | 'D'));
-            if has_duration_marker {
-                return; // Looks like ISO 8601 duration
-            }
-        }
-
-        // Check for human-readable duration markers (case insensitive without allocation)
-        let value_bytes = value.as_bytes();
-
-        // Helper to check if slice contains substring case-insensitively
-        fn contains_ignore_case(haystack: &[u8], needle: &[u8]) -> bool {
-            haystack.windows(needle.len()).any(|window| {
-                window
-                    .iter()
-                    .zip(needle.iter())
-                    .all(|(h, n)| h.to_ascii_lowercase() == *n)
-            })
-        }
-
-        let has_hour = contains_ignore_case(value_bytes, b"hour");
-        let has_min = contains_ignore_case(value_bytes, b"min");
-        let has_sec = contains_ignore_case(value_bytes, b"sec");
-        let has_day = contains_ignore_case(value_bytes, b"day");
-        let ends_with_unit = matches!(
-            value_bytes.last(),
-            Some(b'h')
-                | Some(b'H')
-                | Some(b'm')
-                | Some(b'M')
-                | Some(b's')
-                | Some(b'S')
-                | Some(b'd')
-                | Some(b'D')
-        );
-
-        if has_hour || has_min || has_sec || has_day || ends_with_unit {
-            return; // Looks like human-readable duration
-        }
-
-        // Check for short form like "1h30m" or "2h" - check for digits AND duration chars
-        let has_digit = value.chars().any(|c| c.is_ascii_digit());
-        let has_duration_char = value
-            .chars()
-            .any(|c| matches!(c, 'h' | 'H' | 'm' | 'M' | 's' | 'S'));
-
-        if has_digit && has_duration_char {
-            return; // Looks like short duration format
+        if !looks_like_duration(value) {
+            self.eliminate_type(
+                DataType::Duration,
+                EliminationReason::PatternMismatch {
+                    value: value.to_string(),
+                    expected: "duration format (e.g., PT1H30M, 1h30m, 2 hours)".to_string(),
+                },
+            );
         }
-
-        // Plain numbers (like "10.50") are NOT durations
-        self.eliminate_type(
-            DataType::Duration,
-            EliminationReason::PatternMismatch {
-                value: value.to_string(),
-                expected: "duration format (e.g., PT1H30M, 1h30m, 2 hours)".to_string(),
-            },
-        );
     }
 
     /// Detect separator in a potential date value
@@ -591,30 +635,47 @@ impl ConstraintSolver {
         }
     }
 
-    /// Check if the type is resolved (only one possibility remains)
-    pub fn is_resolved(&self) -> bool {
-        // String is always possible (fallback), so resolved means 1 non-String type
-        let non_string_types: Vec<_> = self
-            .possible_types
-            .iter()
-            .filter(|t| **t != DataType::String && **t != DataType::Null)
-            .collect();
-
-        if non_string_types.len() == 1 {
-            return true;
-        }
+    /// Types still possible besides the Null and String fallbacks, in
+    /// [`DataType::all`] order
+    fn meaningful_types(&self) -> Vec<DataType> {
+        DataType::all()
+            .into_iter()
+            .filter(|t| {
+                *t != DataType::Null && *t != DataType::String && self.possible_types.contains(t)
+            })
+            .collect()
+    }
 
-        // Special case: if only Integer and Float remain and no decimal seen,
-        // consider it resolved as Integer (integers are a subset of floats)
-        if non_string_types.len() == 2
-            && non_string_types.contains(&&DataType::Integer)
-            && non_string_types.contains(&&DataType::Float)
-            && !self.has_decimal_point
-        {
-            return true;
+    /// The type to report among the remaining possibilities, if one wins.
+    ///
+    /// Integers are decimals are floats, so the narrowest remaining numeric
+    /// type wins. A column of only 0/1 also fits Boolean; it reads as a
+    /// number, since a boolean column would show a boolean word somewhere.
+    fn preferred_type(meaningful_types: &[DataType]) -> Option<DataType> {
+        match meaningful_types {
+            [] => None,
+            [only] => Some(*only),
+            _ if meaningful_types
+                .iter()
+                .all(|t| t.is_numeric() || *t == DataType::Boolean) =>
+            {
+                DataType::numeric()
+                    .into_iter()
+                    .find(|t| meaningful_types.contains(t))
+            }
+            _ => None,
         }
+    }
 
-        false
+    /// Check if the type is resolved (only one possibility remains)
+    pub fn is_resolved(&self) -> bool {
+        // String is always possible (fallback), so resolved means 1 non-String
+        // type, or a numeric type that subsumes the rest. While Boolean is
+        // still possible a later "yes" could settle a 0/1 column, so it is
+        // not resolved yet.
+        let meaningful_types = self.meaningful_types();
+        Self::preferred_type(&meaningful_types).is_some()
+            && (meaningful_types.len() == 1 || !meaningful_types.contains(&DataType::Boolean))
     }
 
     /// Get the current result
@@ -630,33 +691,17 @@ impl ConstraintSolver {
         }
 
         // Filter to meaningful types (not Null, and String is fallback)
-        let meaningful_types: Vec<DataType> = self
-            .possible_types
-            .iter()
-            .filter(|t| **t != DataType::Null && **t != DataType::String)
-            .copied()
-            .collect();
-
-        // Special case: if only Integer and Float remain and no decimal seen,
-        // resolve as Integer (integers are a subset of floats)
-        let resolved_type = if meaningful_types.len() == 2
-            && meaningful_types.contains(&DataType::Integer)
-            && meaningful_types.contains(&DataType::Float)
-            && !self.has_decimal_point
-        {
-            Some(DataType::Integer)
-        } else if meaningful_types.len() == 1 {
-            Some(meaningful_types[0])
-        } else {
-            None
-        };
+        let meaningful_types = self.meaningful_types();
 
-        match resolved_type {
+        match Self::preferred_type(&meaningful_types) {
             Some(data_type) => {
-                let format = if data_type == DataType::Date {
-                    self.date_format_candidates.iter().next().cloned()
-                } else {
-                    None
+                let format = match data_type {
+                    DataType::Date => self.date_format(),
+                    DataType::DateTime => self
+                        .datetime_format_candidates
+                        .first()
+                        .map(|format| format.to_string()),
+                    _ => None,
                 };
 
                 TypeInferenceResult::Resolved {
@@ -692,6 +737,91 @@ impl ConstraintSolver {
         }
     }
 
+    /// First remaining date format, in [`DATE_FORMATS`] order
+    fn date_format(&self) -> Option<String> {
+        DATE_FORMATS
+            .iter()
+            .find(|format| self.date_format_candidates.contains(format.pattern))
+            .map(|format| format.pattern.to_string())
+    }
+
+    /// The inferred type with its confidence and per-type sample evidence
+    pub fn evidence(&self) -> ColumnEvidence {
+        let non_null_values = self.values_processed - self.null_count;
+        let share = |matched: usize| {
+            if non_null_values == 0 {
+                0.0
+            } else {
+                matched as f64 / non_null_values as f64
+            }
+        };
+
+        let mut candidates: Vec<TypeEvidence> = EVIDENCE_TYPES
+            .iter()
+            .filter_map(|data_type| {
+                let tally = self.tallies.get(data_type)?;
+                (tally.matched > 0).then(|| TypeEvidence {
+                    data_type: *data_type,
+                    matched: tally.matched,
+                    confidence: share(tally.matched),
+                    samples: tally.samples.clone(),
+                    counterexamples: tally.counterexamples.clone(),
+                })
+            })
+            .collect();
+        // Stable sort keeps EVIDENCE_TYPES order (narrowest first) on ties
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.matched));
+        let matched = |data_type: DataType| {
+            candidates
+                .iter()
+                .find(|c| c.data_type == data_type)
+                .map_or(0, |c| c.matched)
+        };
+
+        let meaningful_types = self.meaningful_types();
+        let (data_type, confidence) = match self.get_result() {
+            TypeInferenceResult::Resolved {
+                data_type: DataType::Null,
+                ..
+            } => (DataType::Null, 0.0),
+            TypeInferenceResult::Resolved { data_type, .. } => {
+                // Other readings that fit the same values as well: a 0/1
+                // column that could be Boolean, or several date formats
+                let mut readings = 1;
+                if data_type != DataType::Boolean && meaningful_types.contains(&DataType::Boolean) {
+                    readings += 1;
+                }
+                if data_type == DataType::Date {
+                    readings += self.date_format_candidates.len().saturating_sub(1);
+                }
+                (data_type, share(matched(data_type)) / readings as f64)
+            }
+            TypeInferenceResult::Ambiguous { possible_types, .. } => {
+                let data_type = possible_types.first().copied().unwrap_or(DataType::String);
+                (
+                    data_type,
+                    share(matched(data_type)) / possible_types.len().max(1) as f64,
+                )
+            }
+            // Confidence in the String fallback is what no other type explains
+            TypeInferenceResult::NoValidType { fallback, .. } => (
+                fallback,
+                1.0 - candidates.first().map_or(0.0, |best| best.confidence),
+            ),
+            TypeInferenceResult::Contradiction(_) => (DataType::String, 0.0),
+        };
+
+        ColumnEvidence {
+            data_type,
+            decimal: self
+                .decimal_shape
+                .filter(|_| data_type == DataType::Decimal),
+            confidence,
+            non_null_values,
+            candidates,
+        }
+    }
+
     /// Apply a constraint from external source (e.g., schema hint)
     pub fn apply_constraint(&mut self, constraint: Constraint) {
         match constraint {
@@ -763,6 +893,133 @@ impl ConstraintSolver {
     pub fn null_count(&self) -> usize {
         self.null_count
     }
+
+    /// Get the precision and scale covering every decimal value seen
+    pub fn decimal_shape(&self) -> Option<DecimalShape> {
+        self.decimal_shape
+    }
+}
+
+/// Boolean spellings: true/false, yes/no, y/n, t/f and 1/0 (any case)
+fn is_boolean_token(value: &str) -> bool {
+    // F-002: Use eq_ignore_ascii_case() instead of to_lowercase() to avoid allocation
+    value.eq_ignore_ascii_case("true")
+        || value.eq_ignore_ascii_case("false")
+        || value.eq_ignore_ascii_case("yes")
+        || value.eq_ignore_ascii_case("no")
+        || matches!(
+            value,
+            "y" | "Y" | "n" | "N" | "1" | "0" | "t" | "T" | "f" | "F"
+        )
+}
+
+/// Why `value` is not an `HH:MM` or `HH:MM:SS[.fff]` time, if it is not
+fn time_violation(value: &str) -> Option<EliminationReason> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let pattern_mismatch = || EliminationReason::PatternMismatch {
+        value: value.to_string(),
+        expected: "HH:MM or HH:MM:SS format".to_string(),
+    };
+
+    if parts.len() < 2 || parts.len() > 3 {
+        return Some(pattern_mismatch());
+    }
+
+    // Every component must be numeric: "2024-01-15T10:30" is not a time
+    let is_component =
+        |part: &str| (1..=2).contains(&part.len()) && part.bytes().all(|b| b.is_ascii_digit());
+    let seconds_ok = match parts.get(2) {
+        Some(seconds) => {
+            let whole = seconds.split_once('.').map_or(*seconds, |(whole, _)| whole);
+            is_component(whole) && seconds.parse::<f64>().is_ok()
+        }
+        None => true,
+    };
+    if !is_component(parts[0]) || !is_component(parts[1]) || !seconds_ok {
+        return Some(pattern_mismatch());
+    }
+
+    // Check hour and minute ranges
+    let hour: i32 = parts[0].parse().ok()?;
+    if hour > 23 {
+        return Some(EliminationReason::OutOfRange {
+            value: value.to_string(),
+            component: "hour".to_string(),
+            actual: hour,
+            max: 23,
+        });
+    }
+    let minute: i32 = parts[1].parse().ok()?;
+    if minute > 59 {
+        return Some(EliminationReason::OutOfRange {
+            value: value.to_string(),
+            component: "minute".to_string(),
+            actual: minute,
+            max: 59,
+        });
+    }
+    None
+}
+
+/// Whether `value` reads as a duration: ISO 8601 (`PT1H30M`) or human
+/// readable (`1h30m`, `2 hours`). Durations always carry a number, so
+/// words that merely end in a unit letter ("yes") are not durations.
+fn looks_like_duration(value: &str) -> bool {
+    // Plain words (like "yes") are NOT durations
+    if !value.chars().any(|c| c.is_ascii_digit()) {
+        return false;
+    }
+
+    // Check for ISO 8601 duration (starts with P) - case insensitive without allocation
+    let first_char = value.chars().next();
+    if matches!(first_char, Some('p') | Some('P')) {
+        // Check for duration markers in rest of string
+        let has_duration_marker = value[1..]
+            .chars()
+            .any(|c| matches!(c, 't' | 'T' | 'y' | 'Y' | 'm' | 'M' | 'd' | 'D'));
+        if has_duration_marker {
+            return true; // Looks like ISO 8601 duration
+        }
+    }
+
+    // Check for human-readable duration markers (case insensitive without allocation)
+    let value_bytes = value.as_bytes();
+
+    // Helper to check if slice contains substring case-insensitively
+    fn contains_ignore_case(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| {
+            window
+                .iter()
+                .zip(needle.iter())
+                .all(|(h, n)| h.to_ascii_lowercase() == *n)
+        })
+    }
+
+    let has_hour = contains_ignore_case(value_bytes, b"hour");
+    let has_min = contains_ignore_case(value_bytes, b"min");
+    let has_sec = contains_ignore_case(value_bytes, b"sec");
+    let has_day = contains_ignore_case(value_bytes, b"day");
+    let ends_with_unit = matches!(
+        value_bytes.last(),
+        Some(b'h')
+            | Some(b'H')
+            | Some(b'm')
+            | Some(b'M')
+            | Some(b's')
+            | Some(b'S')
+            | Some(b'd')
+            | Some(b'D')
+    );
+
+    if has_hour || has_min || has_sec || has_day || ends_with_unit {
+        return true; // Looks like human-readable duration
+    }
+
+    // Check for short form like "1h30m" or "2h" - digits AND duration chars.
+    // Plain numbers (like "10.50") are NOT durations
+    value
+        .chars()
+        .any(|c| matches!(c, 'h' | 'H' | 'm' | 'M' | 's' | 'S'))
 }
 
 #[cfg(test)]
@@ -947,6 +1204,90 @@ mod tests {
         assert!(integer_elimination.is_some());
     }
 
+    #[test]
+    fn test_zero_one_column_is_integer_unless_boolean_words_appear() {
+        let mut solver = ConstraintSolver::new("flag");
+        solver.add_value("1");
+        solver.add_value("0");
+        assert!(!solver.is_resolved(), "a later 'yes' could still settle it");
+        assert_eq!(solver.get_result().data_type(), Some(DataType::Integer));
+        assert_eq!(solver.evidence().confidence, 0.5);
+
+        solver.add_value("yes");
+        assert_eq!(solver.get_result().data_type(), Some(DataType::Boolean));
+        assert_eq!(solver.evidence().confidence, 1.0);
+
+        // Words ending in a unit letter are not durations
+        let mut solver = ConstraintSolver::new("answer");
+        solver.add_value("yes");
+        solver.add_value("no");
+        assert!(solver.is_resolved());
+        assert_eq!(solver.get_result().data_type(), Some(DataType::Boolean));
+    }
+
+    #[test]
+    fn test_iso_datetime_resolves_with_format() {
+        let mut solver = ConstraintSolver::new("ts");
+        solver.add_value("2024-01-15T10:30:00");
+        solver.add_value("2024-01-16T08:00:00.250");
+
+        assert!(!solver.possible_types.contains(&DataType::Time));
+        match solver.get_result() {
+            TypeInferenceResult::Resolved {
+                data_type, format, ..
+            } => {
+                assert_eq!(data_type, DataType::DateTime);
+                assert_eq!(format.as_deref(), Some("%Y-%m-%dT%H:%M:%S%.f"));
+            }
+            other => panic!("Expected DateTime, got {}", other),
+        }
+
+        solver.add_value("2024-01-15 not a time");
+        assert!(!solver.possible_types.contains(&DataType::DateTime));
+    }
+
+    #[test]
+    fn test_decimal_precision_is_captured_and_capped() {
+        let mut solver = ConstraintSolver::new("amount");
+        solver.add_value("19.99");
+        solver.add_value("-1250.5");
+        assert_eq!(solver.get_result().data_type(), Some(DataType::Decimal));
+        assert_eq!(
+            solver.decimal_shape(),
+            Some(DecimalShape {
+                precision: 6,
+                scale: 2
+            })
+        );
+
+        let config = InferenceConfig {
+            max_decimal_precision: 4,
+            ..InferenceConfig::default()
+        };
+        let mut solver = ConstraintSolver::with_config("amount", config);
+        solver.add_value("19.99");
+        solver.add_value("-1250.5");
+        assert_eq!(solver.get_result().data_type(), Some(DataType::Float));
+        assert_eq!(solver.evidence().decimal, None);
+    }
+
+    #[test]
+    fn test_evidence_for_mostly_integer_text_column() {
+        let mut solver = ConstraintSolver::new("qty");
+        for value in ["1", "2", "N/A", "4"] {
+            solver.add_value(value);
+        }
+
+        let evidence = solver.evidence();
+        assert_eq!(evidence.data_type, DataType::String);
+        assert_eq!(evidence.confidence, 0.25);
+        let integer = evidence.candidate(DataType::Integer).unwrap();
+        assert_eq!(integer.matched, 3);
+        assert_eq!(integer.confidence, 0.75);
+        assert_eq!(integer.samples, vec!["1", "2", "4"]);
+        assert_eq!(integer.counterexamples, vec!["N/A"]);
+    }
+
     #[test]
     fn test_apply_must_be_constraint() {
         let mut solver = ConstraintSolver::new("forced_date");
@@ -4,7 +4,8 @@
 
 use std::collections::HashMap;
 
-use super::solver::ConstraintSolver;
+use super::evidence::ColumnEvidence;
+use super::solver::{ConstraintSolver, InferenceConfig};
 use super::{DataType, TypeInferenceResult};
 
 /// Result of streaming type inference for all columns
@@ -13,6 +14,9 @@ pub struct StreamingInferenceResult {
     /// Results per column
     pub columns: HashMap<String, TypeInferenceResult>,
 
+    /// Confidence and sample evidence per column
+    pub evidence: HashMap<String, ColumnEvidence>,
+
     /// Total rows processed
     pub rows_processed: usize,
 
@@ -53,6 +57,9 @@ pub struct StreamingConfig {
 
     /// Minimum rows to process before allowing early termination
     pub min_rows_before_termination: usize,
+
+    /// Per-column solver tunables
+    pub inference: InferenceConfig,
 }
 
 impl Default for StreamingConfig {
@@ -61,6 +68,7 @@ impl Default for StreamingConfig {
             max_rows: 100_000,
             early_termination: true,
             min_rows_before_termination: 100,
+            inference: InferenceConfig::default(),
         }
     }
 }
@@ -104,7 +112,7 @@ where
     // Create a solver for each column
     let mut solvers: Vec<ConstraintSolver> = column_names
         .iter()
-        .map(|name| ConstraintSolver::new(*name))
+        .map(|name| ConstraintSolver::with_config(*name, config.inference.clone()))
         .collect();
 
     let mut rows_processed = 0;
@@ -146,12 +154,17 @@ where
 
     // Collect results
     let columns: HashMap<String, TypeInferenceResult> = solvers
-        .into_iter()
+        .iter()
         .map(|s| (s.column_name().to_string(), s.get_result()))
         .collect();
+    let evidence: HashMap<String, ColumnEvidence> = solvers
+        .iter()
+        .map(|s| (s.column_name().to_string(), s.evidence()))
+        .collect();
 
     StreamingInferenceResult {
         columns,
+        evidence,
         rows_processed,
         early_termination,
         resolution_order,
@@ -163,7 +176,20 @@ pub fn infer_column_type<'a, I>(column_name: &str, values: I) -> TypeInferenceRe
 where
     I: Iterator<Item = &'a str>,
 {
-    let mut solver = ConstraintSolver::new(column_name);
+    solve_column(column_name, values, InferenceConfig::default()).get_result()
+}
+
+/// Run the solver over a single column, for callers that want both the
+/// result and its [`ConstraintSolver::evidence`]
+pub fn solve_column<'a, I>(
+    column_name: &str,
+    values: I,
+    config: InferenceConfig,
+) -> ConstraintSolver
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut solver = ConstraintSolver::with_config(column_name, config);
 
     for value in values {
         solver.add_value(value);
@@ -174,7 +200,7 @@ where
         }
     }
 
-    solver.get_result()
+    solver
 }
 
 /// Convenience function for inferring types from a Vec of rows
@@ -259,6 +285,7 @@ mod tests {
             max_rows: 100_000,
             early_termination: true,
             min_rows_before_termination: 100,
+            inference: InferenceConfig::default(),
         };
 
         let result = infer_types_streaming(&columns, rows.iter().map(|r| r.as_slice()), config);
@@ -278,6 +305,7 @@ mod tests {
             max_rows: 50,
             early_termination: false, // Disable early termination
             min_rows_before_termination: 100,
+            inference: InferenceConfig::default(),
         };
 
         let result = infer_types_streaming(&columns, rows.iter().map(|r| r.as_slice()), config);
@@ -292,10 +320,14 @@ mod tests {
 
         match result {
             TypeInferenceResult::Resolved { data_type, .. } => {
-                assert_eq!(data_type, DataType::Float);
+                assert_eq!(data_type, DataType::Decimal);
             }
-            _ => panic!("Expected Float to be resolved"),
+            _ => panic!("Expected Decimal to be resolved"),
         }
+
+        let values = vec!["1.5", "2.5e3"];
+        let result = infer_column_type("reading", values.into_iter());
+        assert_eq!(result.data_type(), Some(DataType::Float));
     }
 
     #[test]
@@ -315,7 +347,7 @@ mod tests {
 
         // Check types
         assert_eq!(schema.get("id").unwrap().0, DataType::Integer);
-        assert_eq!(schema.get("price").unwrap().0, DataType::Float);
+        assert_eq!(schema.get("price").unwrap().0, DataType::Decimal);
         assert_eq!(schema.get("date").unwrap().0, DataType::Date);
     }
 
@@ -334,6 +366,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_evidence_per_column() {
+        let columns = vec!["amount", "active"];
+        let rows = vec![vec!["10.50", "yes"], vec!["3.125", "no"], vec!["", "1"]];
+
+        let result = infer_types_from_rows(&columns, &rows);
+
+        let amount = &result.evidence["amount"];
+        assert_eq!(amount.data_type, DataType::Decimal);
+        assert_eq!(amount.non_null_values, 2);
+        assert_eq!(amount.confidence, 1.0);
+        assert_eq!(
+            amount.protocol_data_type(),
+            casparian_protocol::DataType::Decimal {
+                precision: 5,
+                scale: 3
+            }
+        );
+
+        let active = &result.evidence["active"];
+        assert_eq!(active.data_type, DataType::Boolean);
+        assert_eq!(active.confidence, 1.0);
+        let boolean = active.candidate(DataType::Boolean).unwrap();
+        assert_eq!(boolean.samples, vec!["yes", "no", "1"]);
+        assert!(boolean.counterexamples.is_empty());
+    }
+
     #[test]
     fn test_all_null_column() {
         let columns = vec!["empty"];
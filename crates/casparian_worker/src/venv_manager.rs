@@ -5,6 +5,11 @@
 //! - All I/O is synchronous (no async lies)
 //! - Thread-safe via std::sync::Mutex (not async mutex)
 //! - Plain functions where possible, minimal state
+//!
+//! Envs live under `<venvs_dir>/<env_hash>`, where env_hash is the SHA-256
+//! of the plugin's uv.lock. A cache miss builds the env from the lockfile
+//! (`uv venv` + `uv sync --frozen`); later jobs with the same env_hash reuse
+//! it. Envs unused for `DEFAULT_MAX_AGE_DAYS` are garbage-collected.
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -22,6 +27,10 @@ const DEFAULT_MAX_AGE_DAYS: u32 = 30;
 /// Threshold above which we trigger automatic cleanup
 const CLEANUP_THRESHOLD: usize = 60;
 
+/// Present while an env is being built. A crashed build leaves it behind,
+/// so the half-built env is rebuilt instead of used.
+const BUILDING_MARKER: &str = ".casparian_building";
+
 /// Venv entry - plain data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VenvEntry {
@@ -69,6 +78,8 @@ pub struct VenvManager {
     uv_path: Option<PathBuf>,
     metadata_path: PathBuf,
     metadata: Mutex<VenvMetadata>,
+    /// Serializes env builds so concurrent jobs never build the same env twice
+    build_lock: Mutex<()>,
 }
 
 // VenvManager is automatically Send + Sync because:
@@ -111,6 +122,7 @@ impl VenvManager {
             uv_path,
             metadata_path,
             metadata: Mutex::new(metadata),
+            build_lock: Mutex::new(()),
         })
     }
 
@@ -124,6 +136,32 @@ impl VenvManager {
         }
     }
 
+    /// True if the env exists and is not a leftover of an interrupted build.
+    pub fn is_ready(&self, env_hash: &str) -> bool {
+        self.interpreter_path(env_hash).exists()
+            && !self.venvs_dir.join(env_hash).join(BUILDING_MARKER).exists()
+    }
+
+    /// Interpreter for an env, building it from the lockfile on a cache miss.
+    ///
+    /// The lockfile must hash to `env_hash` (as computed on deploy), so a
+    /// cached env always matches the lockfile it was built from.
+    pub fn resolve(&self, env_hash: &str, lockfile_content: Option<&str>) -> Result<PathBuf> {
+        if self.is_ready(env_hash) {
+            self.touch(env_hash);
+            return Ok(self.interpreter_path(env_hash));
+        }
+        let lockfile_content = lockfile_content.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Environment {} not installed on worker and no lockfile was provided. \
+                 Redeploy the plugin or preinstall the env.",
+                env_hash
+            )
+        })?;
+        verify_env_hash(env_hash, lockfile_content)?;
+        self.get_or_create(env_hash, lockfile_content, None)
+    }
+
     /// Get or create venv. Synchronous - call from spawn_blocking.
     /// Thread-safe via internal mutex.
     pub fn get_or_create(
@@ -135,12 +173,20 @@ impl VenvManager {
         let interpreter = self.interpreter_path(env_hash);
 
         // Cache hit - quick check without heavy operations
-        if interpreter.exists() {
+        if self.is_ready(env_hash) {
             info!("VenvManager: cache hit for {}", truncate_hash(env_hash));
             self.touch(env_hash);
             return Ok(interpreter);
         }
 
+        // One build at a time; a job waiting on a build of the same env
+        // takes the cache hit once it gets the lock.
+        let _build = self.build_lock.lock().unwrap();
+        if self.is_ready(env_hash) {
+            self.touch(env_hash);
+            return Ok(interpreter);
+        }
+
         // Cache miss - create venv (this is the slow path)
         info!(
             "VenvManager: cache miss for {}, creating...",
@@ -154,7 +200,21 @@ impl VenvManager {
                 env_hash
             )
         })?;
-        create_venv(uv_path, &venv_path, lockfile_content, python_version)?;
+        if venv_path.exists() {
+            warn!(
+                "VenvManager: removing incomplete env {}",
+                truncate_hash(env_hash)
+            );
+            std::fs::remove_dir_all(&venv_path)?;
+        }
+        std::fs::create_dir_all(&venv_path)?;
+        let marker = venv_path.join(BUILDING_MARKER);
+        std::fs::write(&marker, b"")?;
+        if let Err(err) = create_venv(uv_path, &venv_path, lockfile_content, python_version) {
+            let _ = std::fs::remove_dir_all(&venv_path);
+            return Err(err.context(format!("Failed to build env {}", truncate_hash(env_hash))));
+        }
+        std::fs::remove_file(&marker)?;
 
         // Record metadata (under lock)
         let size = dir_size(&venv_path);
@@ -213,6 +273,11 @@ impl VenvManager {
         // Lock is held until function returns - prevents TOCTOU race
     }
 
+    /// Whether uv was found to build new envs
+    pub fn uv_available(&self) -> bool {
+        self.uv_path.is_some()
+    }
+
     /// Get cache stats
     pub fn stats(&self) -> (usize, u64) {
         let metadata = self.metadata.lock().unwrap();
@@ -221,6 +286,12 @@ impl VenvManager {
         (count, total_bytes)
     }
 
+    /// Remove envs unused for longer than the TTL, and the least recently
+    /// used beyond the cache cap. Returns the number removed.
+    pub fn collect_garbage(&self) -> usize {
+        self.cleanup(DEFAULT_MAX_VENVS, DEFAULT_MAX_AGE_DAYS)
+    }
+
     /// Clean up old venvs using LRU eviction
     ///
     /// Removes venvs that:
@@ -317,6 +388,20 @@ fn truncate_hash(hash: &str) -> &str {
     }
 }
 
+/// Check that a lockfile is the one `env_hash` was computed from (SHA-256).
+fn verify_env_hash(env_hash: &str, lockfile_content: &str) -> Result<()> {
+    use sha2::{Digest, Sha256};
+    let computed = format!("{:x}", Sha256::digest(lockfile_content.as_bytes()));
+    if computed != env_hash {
+        anyhow::bail!(
+            "Lockfile does not match env {} (hashes to {})",
+            truncate_hash(env_hash),
+            truncate_hash(&computed)
+        );
+    }
+    Ok(())
+}
+
 fn load_metadata(path: &Path) -> VenvMetadata {
     if !path.exists() {
         return VenvMetadata::default();
@@ -360,8 +445,6 @@ fn create_venv(
     lockfile_content: &str,
     python_version: Option<&str>,
 ) -> Result<()> {
-    std::fs::create_dir_all(venv_path)?;
-
     // Write lockfile
     std::fs::write(venv_path.join("uv.lock"), lockfile_content)?;
 
@@ -478,6 +561,64 @@ mod tests {
         assert_eq!(meta.find("abc").unwrap().size_bytes, 200);
     }
 
+    fn fake_env(manager: &VenvManager, env_hash: &str) {
+        let interpreter = manager.interpreter_path(env_hash);
+        std::fs::create_dir_all(interpreter.parent().unwrap()).unwrap();
+        std::fs::write(&interpreter, b"").unwrap();
+    }
+
+    #[test]
+    fn test_verify_env_hash() {
+        use sha2::{Digest, Sha256};
+        let hash = format!("{:x}", Sha256::digest(b"lock"));
+        assert!(verify_env_hash(&hash, "lock").is_ok());
+        assert!(verify_env_hash(&hash, "other lock").is_err());
+    }
+
+    #[test]
+    fn test_interrupted_build_is_not_ready() {
+        let temp = tempfile::tempdir().unwrap();
+        let manager = VenvManager::with_path(temp.path().to_path_buf()).unwrap();
+        assert!(!manager.is_ready("abc"));
+
+        fake_env(&manager, "abc");
+        let marker = temp.path().join("abc").join(BUILDING_MARKER);
+        std::fs::write(&marker, b"").unwrap();
+        assert!(!manager.is_ready("abc"));
+
+        std::fs::remove_file(&marker).unwrap();
+        assert!(manager.is_ready("abc"));
+        assert_eq!(
+            manager.resolve("abc", None).unwrap(),
+            manager.interpreter_path("abc")
+        );
+        assert!(manager.resolve("missing", None).is_err());
+    }
+
+    #[test]
+    fn test_collect_garbage_removes_expired_envs() {
+        let temp = tempfile::tempdir().unwrap();
+        let manager = VenvManager::with_path(temp.path().to_path_buf()).unwrap();
+        let expired = (chrono::Utc::now()
+            - chrono::Duration::days(DEFAULT_MAX_AGE_DAYS as i64 + 1))
+        .to_rfc3339();
+        let now = chrono::Utc::now().to_rfc3339();
+        for (hash, last_used) in [("old", &expired), ("fresh", &now)] {
+            fake_env(&manager, hash);
+            manager.metadata.lock().unwrap().upsert(VenvEntry {
+                env_hash: hash.to_string(),
+                created_at: last_used.clone(),
+                last_used: last_used.clone(),
+                size_bytes: 0,
+            });
+        }
+
+        assert_eq!(manager.collect_garbage(), 1);
+        assert!(!temp.path().join("old").exists());
+        assert!(manager.is_ready("fresh"));
+        assert_eq!(manager.stats().0, 1);
+    }
+
     #[test]
     fn test_truncate_hash() {
         assert_eq!(truncate_hash("abc"), "abc");
@@ -8,17 +8,20 @@
 //! - Graceful shutdown via shutdown channel
 
 use anyhow::Result;
+use casparian_protocol::endpoint;
 use casparian_protocol::types::{
-    self, ArtifactV1, DispatchCommand, HeartbeatStatus, JobStatus, ParsedSinkUri, RuntimeKind,
-    SinkScheme,
+    self, ArtifactV1, BatchFileReceipt, DispatchCommand, ErrorCategory, HeartbeatStatus, JobStage,
+    JobStatus, LimitKind, LineageChain, LineageFileType, LineageHop, ParsedSinkUri, ResourceLimits,
+    RuntimeKind, SinkScheme, SourceFile, SourceFingerprint, TextEncoding,
 };
+use casparian_protocol::keys::CurveKeyPair;
+use casparian_protocol::stream::StreamAssembler;
 use casparian_protocol::{
-    metrics, schema_hash, table_name_with_schema, JobId, Message, OpCode, SinkMode,
+    metrics, retry, schema_hash, table_name_with_schema, version, JobId, Message,
+    NegotiatedProtocol, OpCode, ProtocolFeatures, ProtocolVersionRange, SchemaEvolution, SinkMode,
 };
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::sync::Arc;
@@ -31,10 +34,20 @@ use zmq::{Context, Socket};
 use crate::bridge;
 use crate::bridge::BridgeError;
 use crate::cancel::CancellationToken;
-use crate::native_runtime::NativeSubprocessRuntime;
+use crate::config::{HealthConfig, ReconnectPolicy, DEFAULT_HEARTBEAT_INTERVAL_SECS};
+use crate::constraints;
+use crate::decompress;
+use crate::drift;
+use crate::health::{self, HealthProbe, HealthState};
+use crate::load;
+use crate::native_runtime::{BatchSizing, NativeSubprocessRuntime};
 use crate::runtime::{PluginRuntime, PythonShimRuntime, RunContext};
+use crate::sandbox::LimitExceeded;
+use crate::scratch::{self, ScratchDir};
 use crate::schema_validation;
+use crate::transcode;
 use crate::venv_manager::VenvManager;
+use crate::xlsx::BUILTIN_XLSX_ENTRYPOINT;
 use arrow::array::{
     Array, ArrayRef, BooleanArray, Int64Array, LargeStringArray, StringArray, StringBuilder,
     UInt64Array,
@@ -42,6 +55,8 @@ use arrow::array::{
 use arrow::compute::filter_record_batch;
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
+use casparian_sinks::column_stats::collect_column_stats;
+use casparian_sinks::quarantine::{check_null_policy, check_quarantine_policy};
 
 // ============================================================================
 // Error Types
@@ -64,7 +79,7 @@ pub enum WorkerError {
     #[error("Permanent error (no retry): {message}")]
     PermanentWithDiagnostics {
         message: String,
-        diagnostics: types::JobDiagnostics,
+        diagnostics: Box<types::JobDiagnostics>,
     },
 
     /// Transient error - may succeed on retry (e.g., network timeout, resource busy)
@@ -109,6 +124,18 @@ impl WorkerError {
         )
     }
 
+    /// Error class reported to the Sentinel for retry decisions.
+    pub fn error_class(&self) -> &'static str {
+        match self {
+            WorkerError::Transient { .. } => retry::ERROR_CLASS_TRANSIENT,
+            WorkerError::Permanent { .. } | WorkerError::PermanentWithDiagnostics { .. } => {
+                retry::ERROR_CLASS_PERMANENT
+            }
+            WorkerError::Bridge(_) => retry::ERROR_CLASS_BRIDGE,
+            WorkerError::Internal { .. } => retry::ERROR_CLASS_INTERNAL,
+        }
+    }
+
     pub fn diagnostics(&self) -> Option<&types::JobDiagnostics> {
         match self {
             WorkerError::PermanentWithDiagnostics { diagnostics, .. } => Some(diagnostics),
@@ -116,6 +143,55 @@ impl WorkerError {
         }
     }
 
+    /// Failure category reported in `JobDiagnostics`, from the error and the
+    /// stage the job failed in.
+    pub fn category(&self, stage: JobStage) -> ErrorCategory {
+        if let Some(kind) = self.diagnostics().and_then(|d| d.limit_exceeded) {
+            return kind.error_category();
+        }
+        let message = match self {
+            WorkerError::PermanentWithDiagnostics { diagnostics, .. }
+                if diagnostics.schema_mismatch.is_some() =>
+            {
+                return ErrorCategory::SchemaViolation;
+            }
+            WorkerError::Bridge(_) => return ErrorCategory::PluginException,
+            WorkerError::Internal { .. } => return ErrorCategory::Internal,
+            WorkerError::Permanent { message }
+            | WorkerError::PermanentWithDiagnostics { message, .. }
+            | WorkerError::Transient { message } => message.to_lowercase(),
+        };
+
+        if message.contains("timeout") || message.contains("timed out") {
+            return ErrorCategory::Timeout;
+        }
+        match stage {
+            JobStage::Setup if message.contains("trust policy") => ErrorCategory::TrustPolicy,
+            JobStage::Setup => ErrorCategory::InvalidConfig,
+            JobStage::Execute if is_io_failure(&message) => ErrorCategory::IoError,
+            JobStage::Execute if message.contains("schema") => ErrorCategory::SchemaViolation,
+            JobStage::Execute => ErrorCategory::PluginException,
+            JobStage::Validate if message.contains("schema") || message.contains("constraint") => {
+                ErrorCategory::SchemaViolation
+            }
+            JobStage::Validate if message.contains("source hash") => ErrorCategory::IoError,
+            JobStage::Validate
+                if message.contains("invalid") || message.contains("sink config") =>
+            {
+                ErrorCategory::InvalidConfig
+            }
+            JobStage::Validate => ErrorCategory::Internal,
+            JobStage::Write
+                if message.contains("lock")
+                    || message.contains("already exists")
+                    || message.contains("schema_evolution=") =>
+            {
+                ErrorCategory::SinkConflict
+            }
+            JobStage::Write => ErrorCategory::SinkError,
+        }
+    }
+
     fn internal(err: impl std::fmt::Display) -> Self {
         WorkerError::Internal {
             message: err.to_string(),
@@ -164,6 +240,14 @@ impl WorkerError {
     }
 }
 
+fn is_io_failure(message: &str) -> bool {
+    message.contains("no such file")
+        || message.contains("permission denied")
+        || message.contains("filenotfounderror")
+        || message.contains("ioerror")
+        || message.contains("oserror")
+}
+
 #[derive(Debug, Deserialize)]
 struct BridgeErrorPayload {
     retryable: Option<bool>,
@@ -195,11 +279,17 @@ fn parse_bridge_retryable(message: &str) -> Option<bool> {
 /// Maximum concurrent jobs per worker
 const MAX_CONCURRENT_JOBS: usize = 1;
 
-/// Heartbeat interval (seconds) - worker sends heartbeat to Sentinel
-const HEARTBEAT_INTERVAL_SECS: u64 = 30;
 /// Identify interval (seconds) - re-sends IDENTIFY so sentinel restarts can re-register
 const IDENTIFY_INTERVAL_SECS: u64 = 30;
+/// Log tail interval (milliseconds) - new log lines of running jobs are sent as LOG_CHUNK
+const LOG_TAIL_INTERVAL_MS: u64 = 1000;
+/// Env GC interval (seconds) - removes plugin envs past their TTL
+const ENV_GC_INTERVAL_SECS: u64 = 60 * 60;
+/// Maximum bytes of log output per LOG_CHUNK message
+const MAX_LOG_CHUNK_BYTES: usize = 64 * 1024;
 const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+/// Where the Sentinel socket reports connection events
+const MONITOR_ENDPOINT: &str = "inproc://worker-monitor";
 
 /// Grace period before SIGKILL after SIGTERM (seconds)
 const KILL_GRACE_PERIOD_SECS: u64 = 3;
@@ -209,20 +299,150 @@ struct ActiveJob {
     handle: JoinHandle<()>,
     cancel_token: CancellationToken,
     lease_token: Option<String>,
+    /// Follow-on files of a batched DISPATCH, run on the same thread.
+    batch: Vec<BatchJob>,
 }
 
+/// Worker settings the plugin runtimes need for every job.
+#[derive(Debug, Clone)]
+struct RuntimeSettings {
+    shim_path: PathBuf,
+    batch_sizing: BatchSizing,
+    encode_threads: usize,
+    /// Limits of jobs whose plugin sets none
+    default_limits: ResourceLimits,
+    /// Directory the per-job scratch directories are created in
+    scratch_root: PathBuf,
+}
+
+/// One follow-on file of a batched DISPATCH. Each has its own token so an
+/// ABORT for that job does not cancel the rest of the batch.
+struct BatchJob {
+    job_id: JobId,
+    cancel_token: CancellationToken,
+    lease_token: String,
+}
+
+/// Job id and lease token of each follow-on file of a batch.
+type BatchLeases = Vec<(JobId, String)>;
+
 /// Worker configuration (plain data)
 pub struct WorkerConfig {
     pub sentinel_addr: String,
     pub parquet_root: PathBuf,
     pub worker_id: String,
     pub shim_path: PathBuf,
-    /// Plugin capabilities this worker can handle. "*" means all plugins.
+    /// Runtime/feature tags this worker supports, matched against plugin
+    /// requirements by the Sentinel. "*" means all plugins.
     /// Defaults to ["*"] if empty.
     pub capabilities: Vec<String>,
     /// Custom venvs directory. If None, uses ~/.casparian_flow/venvs.
     /// Useful for testing with isolated temp directories.
     pub venvs_dir: Option<PathBuf>,
+    /// Root of per-job scratch directories. If None, uses
+    /// [`scratch::default_scratch_root`].
+    pub scratch_dir: Option<PathBuf>,
+    /// CurveZMQ client keys. If None, the connection is plaintext.
+    pub curve: Option<WorkerCurveConfig>,
+    /// Target size of the output batches of native plugins
+    pub batch_sizing: BatchSizing,
+    /// Threads each job's Parquet/CSV sinks may encode on (1 = serial)
+    pub encode_threads: usize,
+    /// Limits of jobs whose plugin sets none
+    pub default_limits: ResourceLimits,
+    /// Interval between heartbeats to the Sentinel
+    pub heartbeat_interval: Duration,
+    /// Reconnect backoff after losing the Sentinel
+    pub reconnect: ReconnectPolicy,
+    /// Health probe endpoint and thresholds
+    pub health: HealthConfig,
+}
+
+impl WorkerConfig {
+    /// A config with default runtime settings; see [`crate::config`] for
+    /// building one from `worker.toml`, the environment and flags.
+    pub fn new(
+        sentinel_addr: impl Into<String>,
+        parquet_root: impl Into<PathBuf>,
+        worker_id: impl Into<String>,
+        shim_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            sentinel_addr: sentinel_addr.into(),
+            parquet_root: parquet_root.into(),
+            worker_id: worker_id.into(),
+            shim_path: shim_path.into(),
+            capabilities: Vec::new(),
+            venvs_dir: None,
+            scratch_dir: None,
+            curve: None,
+            batch_sizing: BatchSizing::default(),
+            encode_threads: 1,
+            default_limits: ResourceLimits::default(),
+            heartbeat_interval: Duration::from_secs(DEFAULT_HEARTBEAT_INTERVAL_SECS),
+            reconnect: ReconnectPolicy::default(),
+            health: HealthConfig::default(),
+        }
+    }
+
+    pub fn with_capabilities(mut self, capabilities: Vec<String>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    pub fn with_venvs_dir(mut self, venvs_dir: impl Into<PathBuf>) -> Self {
+        self.venvs_dir = Some(venvs_dir.into());
+        self
+    }
+
+    pub fn with_scratch_dir(mut self, scratch_dir: impl Into<PathBuf>) -> Self {
+        self.scratch_dir = Some(scratch_dir.into());
+        self
+    }
+
+    pub fn with_curve(mut self, curve: WorkerCurveConfig) -> Self {
+        self.curve = Some(curve);
+        self
+    }
+
+    pub fn with_batch_sizing(mut self, batch_sizing: BatchSizing) -> Self {
+        self.batch_sizing = batch_sizing;
+        self
+    }
+
+    pub fn with_encode_threads(mut self, threads: usize) -> Self {
+        self.encode_threads = threads.max(1);
+        self
+    }
+
+    pub fn with_default_limits(mut self, limits: ResourceLimits) -> Self {
+        self.default_limits = limits;
+        self
+    }
+
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    pub fn with_reconnect(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    pub fn with_health(mut self, health: HealthConfig) -> Self {
+        self.health = health;
+        self
+    }
+}
+
+/// CurveZMQ settings for the worker's connection to the Sentinel.
+#[derive(Debug, Clone)]
+pub struct WorkerCurveConfig {
+    /// Sentinel public key (Z85)
+    pub server_public_key: String,
+    /// This worker's key pair; its public key must be authorized by the Sentinel
+    pub keypair: CurveKeyPair,
 }
 
 /// Handle for controlling a running worker
@@ -266,6 +486,11 @@ pub struct Worker {
     config: WorkerConfig,
     context: Context,
     socket: Socket,
+    /// Relay to a `grpc://` Sentinel; the socket is connected to it
+    #[cfg(feature = "grpc")]
+    grpc_link: Option<casparian_grpc::GrpcLink>,
+    /// Relay to a `pipe://` Sentinel; the socket is connected to it
+    pipe_link: Option<casparian_pipe::PipeLink>,
     venv_manager: Arc<VenvManager>, // VenvManager is now Sync (uses std::sync::Mutex internally)
     result_tx: mpsc::Sender<JobResult>,
     result_rx: mpsc::Receiver<JobResult>,
@@ -273,6 +498,16 @@ pub struct Worker {
     shutdown_complete_tx: Option<mpsc::Sender<()>>,
     /// Active jobs with their thread handles and cancellation tokens
     active_jobs: HashMap<JobId, ActiveJob>,
+    /// Reassembles chunked payloads (large DISPATCH commands) per job id
+    stream_assembler: StreamAssembler<JobId>,
+    /// Bytes of each job's log already sent to the Sentinel as LOG_CHUNK
+    log_offsets: HashMap<JobId, u64>,
+    /// State reported by the health probes
+    health: Arc<HealthState>,
+    /// Connection events of `socket`
+    monitor: Socket,
+    /// Protocol agreed with the Sentinel; v4 defaults until its IDENTIFY ACK
+    protocol: NegotiatedProtocol,
 }
 
 /// Result from a completed job
@@ -293,17 +528,23 @@ impl Worker {
         }
     }
 
+    /// Send a message at the protocol version negotiated with the Sentinel.
+    fn send<T: serde::Serialize>(&self, opcode: OpCode, job_id: JobId, payload: &T) -> Result<()> {
+        send_message(&self.socket, &self.protocol, opcode, job_id, payload)
+    }
+
+    /// IDENTIFY always goes out at the pre-negotiation version so that any
+    /// supported Sentinel can read it; the ACK tells us what to speak next.
     fn send_identify(&self) -> Result<()> {
-        let capabilities = if self.config.capabilities.is_empty() {
-            vec!["*".to_string()]
-        } else {
-            self.config.capabilities.clone()
-        };
-        let identify = types::IdentifyPayload {
-            capabilities,
-            worker_id: Some(self.config.worker_id.clone()),
-        };
-        send_message(&self.socket, OpCode::Identify, JobId::new(0), &identify)?;
+        let identify = identify_payload(&self.config);
+        let protocol = NegotiatedProtocol::default();
+        send_message(
+            &self.socket,
+            &protocol,
+            OpCode::Identify,
+            JobId::new(0),
+            &identify,
+        )?;
         Ok(())
     }
 
@@ -325,34 +566,118 @@ impl Worker {
             count,
             bytes / 1_000_000
         );
+        let venv_manager = Arc::new(venv_manager);
 
         // Create and connect socket
         let context = Context::new();
         let socket = context
             .socket(zmq::DEALER)
             .map_err(|err| anyhow::anyhow!("Failed to create DEALER socket: {}", err))?;
+        let grpc = config.sentinel_addr.starts_with("grpc://");
+        if grpc && config.curve.is_some() {
+            anyhow::bail!(
+                "CURVE security is only available on ZMQ sentinel addresses, not grpc://"
+            );
+        }
+        #[cfg(not(feature = "grpc"))]
+        if grpc {
+            anyhow::bail!(
+                "gRPC transport requested for {} but this build lacks the grpc feature",
+                config.sentinel_addr
+            );
+        }
+        // A grpc:// Sentinel is reached through a local relay
+        #[cfg(feature = "grpc")]
+        let grpc_link = grpc
+            .then(|| casparian_grpc::GrpcLink::connect(&config.sentinel_addr, &context))
+            .transpose()?;
+        #[cfg(feature = "grpc")]
+        let connect_addr = grpc_link
+            .as_ref()
+            .map_or(config.sentinel_addr.as_str(), |link| link.endpoint());
+        #[cfg(not(feature = "grpc"))]
+        let connect_addr = config.sentinel_addr.as_str();
+        // So is a pipe:// one, which libzmq cannot open itself
+        let pipe_link = casparian_pipe::is_pipe_addr(&config.sentinel_addr)
+            .then(|| casparian_pipe::PipeLink::connect(&config.sentinel_addr))
+            .transpose()?;
+        let connect_addr = pipe_link
+            .as_ref()
+            .map_or(connect_addr, |link| link.endpoint());
+        endpoint::validate_ipc_addr(connect_addr)?;
+        if let Some(curve) = &config.curve {
+            configure_curve_client(&socket, curve)?;
+        }
+        // Monitor before connecting so the first CONNECTED is not missed
+        let events = [
+            zmq::SocketEvent::CONNECTED,
+            zmq::SocketEvent::HANDSHAKE_SUCCEEDED,
+            zmq::SocketEvent::DISCONNECTED,
+            zmq::SocketEvent::HANDSHAKE_FAILED_NO_DETAIL,
+            zmq::SocketEvent::HANDSHAKE_FAILED_PROTOCOL,
+            zmq::SocketEvent::HANDSHAKE_FAILED_AUTH,
+        ]
+        .iter()
+        .fold(0, |mask, event| mask | event.to_raw() as i32);
+        socket
+            .monitor(MONITOR_ENDPOINT, events)
+            .map_err(|err| anyhow::anyhow!("Failed to monitor sentinel socket: {}", err))?;
+        let monitor = context
+            .socket(zmq::PAIR)
+            .map_err(|err| anyhow::anyhow!("Failed to create monitor socket: {}", err))?;
+        monitor
+            .connect(MONITOR_ENDPOINT)
+            .map_err(|err| anyhow::anyhow!("Failed to connect monitor socket: {}", err))?;
+        socket
+            .set_reconnect_ivl(reconnect_ms(config.reconnect.interval_ms))
+            .map_err(|err| anyhow::anyhow!("Failed to set reconnect interval: {}", err))?;
+        socket
+            .set_reconnect_ivl_max(reconnect_ms(config.reconnect.max_interval_ms))
+            .map_err(|err| anyhow::anyhow!("Failed to set reconnect interval: {}", err))?;
         socket
-            .connect(&config.sentinel_addr)
+            .connect(connect_addr)
             .map_err(|err| anyhow::anyhow!("Failed to connect to sentinel: {}", err))?;
         socket
             .set_rcvtimeo(100)
             .map_err(|err| anyhow::anyhow!("Failed to set socket receive timeout: {}", err))?;
 
         info!("Connected to sentinel: {}", config.sentinel_addr);
+        // ZMQ connects lazily, so say now why the Sentinel cannot be reached
+        let diagnosis = endpoint::diagnose_endpoint(&config.sentinel_addr);
+        if diagnosis.reachable == Some(false) {
+            warn!(
+                "Sentinel at {} is not reachable yet: {}",
+                config.sentinel_addr, diagnosis.detail
+            );
+        }
 
-        // Send IDENTIFY with configured capabilities
-        let capabilities = if config.capabilities.is_empty() {
-            vec!["*".to_string()] // Default to wildcard
-        } else {
-            config.capabilities.clone()
-        };
-        let identify = types::IdentifyPayload {
-            capabilities,
-            worker_id: Some(config.worker_id.clone()),
-        };
-        send_message(&socket, OpCode::Identify, JobId::new(0), &identify)?;
+        // Send IDENTIFY with configured capabilities and supported protocol versions
+        let identify = identify_payload(&config);
+        let protocol = NegotiatedProtocol::default();
+        send_message(
+            &socket,
+            &protocol,
+            OpCode::Identify,
+            JobId::new(0),
+            &identify,
+        )?;
         info!("Sent IDENTIFY as {}", config.worker_id);
 
+        let health = Arc::new(HealthState::new());
+        if let Some(listen) = &config.health.listen {
+            health::spawn_server(
+                listen,
+                HealthProbe {
+                    worker_id: config.worker_id.clone(),
+                    sentinel_addr: config.sentinel_addr.clone(),
+                    output_dir: config.parquet_root.clone(),
+                    min_free_bytes: config.health.min_free_bytes,
+                    venv_manager: venv_manager.clone(),
+                    state: health.clone(),
+                },
+            )?;
+        }
+
         // Initialize channels
         let (result_tx, result_rx) = mpsc::channel();
         let (shutdown_tx, shutdown_rx) = mpsc::channel();
@@ -368,12 +693,20 @@ impl Worker {
                 config,
                 context,
                 socket,
-                venv_manager: Arc::new(venv_manager),
+                #[cfg(feature = "grpc")]
+                grpc_link,
+                pipe_link,
+                venv_manager,
                 result_tx,
                 result_rx,
                 shutdown_rx,
                 shutdown_complete_tx: Some(completion_tx),
                 active_jobs: HashMap::new(),
+                stream_assembler: StreamAssembler::default(),
+                log_offsets: HashMap::new(),
+                health,
+                monitor,
+                protocol,
             },
             handle,
         ))
@@ -389,13 +722,29 @@ impl Worker {
         result
     }
 
+    /// Remove unused plugin envs in the background.
+    fn spawn_env_gc(&self) {
+        let venv_mgr = self.venv_manager.clone();
+        std::thread::spawn(move || {
+            let removed = venv_mgr.collect_garbage();
+            if removed > 0 {
+                info!("Removed {} unused plugin envs", removed);
+            }
+        });
+    }
+
     fn run_inner(mut self) -> Result<()> {
         info!("Entering event loop...");
 
         let mut last_heartbeat = Instant::now();
         let mut last_identify = Instant::now();
+        let mut last_log_tail = Instant::now();
+        let mut last_env_gc = Instant::now();
+        self.spawn_env_gc();
 
         loop {
+            self.health.tick();
+            self.poll_connection_events();
             // Clean up completed jobs
             self.reap_completed_jobs();
 
@@ -418,12 +767,13 @@ impl Worker {
 
             while let Ok(result) = self.result_rx.try_recv() {
                 info!("Job {} finished, sending CONCLUDE", result.job_id);
-                if let Err(e) = send_message(
-                    &self.socket,
-                    OpCode::Conclude,
-                    result.job_id,
-                    &result.receipt,
-                ) {
+                // Flush the rest of the log so tails are complete before CONCLUDE
+                let batch_job_ids = result.receipt.batch_results.iter().map(|r| r.job_id);
+                for job_id in std::iter::once(result.job_id).chain(batch_job_ids) {
+                    while self.send_log_chunk(job_id) {}
+                    self.log_offsets.remove(&job_id);
+                }
+                if let Err(e) = self.send(OpCode::Conclude, result.job_id, &result.receipt) {
                     error!("Failed to send CONCLUDE for job {}: {}", result.job_id, e);
                 }
             }
@@ -435,21 +785,33 @@ impl Worker {
                 last_identify = Instant::now();
             }
 
-            if last_heartbeat.elapsed() >= Duration::from_secs(HEARTBEAT_INTERVAL_SECS) {
-                let active_job_ids: Vec<JobId> = self.active_jobs.keys().copied().collect();
+            if last_log_tail.elapsed() >= Duration::from_millis(LOG_TAIL_INTERVAL_MS) {
+                let running = self.active_job_ids();
+                for job_id in running {
+                    self.send_log_chunk(job_id);
+                }
+                last_log_tail = Instant::now();
+            }
+
+            if last_env_gc.elapsed() >= Duration::from_secs(ENV_GC_INTERVAL_SECS) {
+                self.spawn_env_gc();
+                last_env_gc = Instant::now();
+            }
+
+            if last_heartbeat.elapsed() >= self.config.heartbeat_interval {
+                let active_job_ids = self.active_job_ids();
                 let status = self.compute_heartbeat_status();
                 let payload = types::HeartbeatPayload {
                     status,
                     active_job_count: active_job_ids.len(),
+                    load: Some(load::sample_load(active_job_ids.len(), MAX_CONCURRENT_JOBS)),
                     active_job_ids,
                 };
                 debug!(
                     "Sending heartbeat: {:?} ({} active jobs)",
                     status, payload.active_job_count
                 );
-                if let Err(e) =
-                    send_message(&self.socket, OpCode::Heartbeat, JobId::new(0), &payload)
-                {
+                if let Err(e) = self.send(OpCode::Heartbeat, JobId::new(0), &payload) {
                     warn!("Failed to send heartbeat: {}", e);
                 }
                 last_heartbeat = Instant::now();
@@ -466,12 +828,18 @@ impl Worker {
                         }
                     };
 
-                    match Message::unpack(&[header, payload]) {
-                        Ok(msg) => {
+                    let msg = Message::unpack(&[header, payload]).and_then(|msg| {
+                        let msg = version::upgrade(msg);
+                        self.stream_assembler.push(msg.header.job_id, msg)
+                    });
+                    match msg {
+                        Ok(Some(msg)) => {
                             if let Err(e) = self.handle_message(msg) {
                                 error!("Error handling message: {}", e);
                             }
                         }
+                        // Mid-stream chunk; message completes on STREAM_END
+                        Ok(None) => {}
                         Err(e) => warn!("Failed to unpack message: {}", e),
                     }
                 }
@@ -487,6 +855,62 @@ impl Worker {
         Ok(())
     }
 
+    /// Send the job's log output written since the last LOG_CHUNK.
+    ///
+    /// Returns true if a chunk was sent.
+    fn send_log_chunk(&mut self, job_id: JobId) -> bool {
+        let offset = self.log_offsets.get(&job_id).copied().unwrap_or(0);
+        let (data, next_offset) =
+            match bridge::read_job_log_chunk(job_id, offset, MAX_LOG_CHUNK_BYTES) {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => return false,
+                Err(e) => {
+                    debug!("Failed to read log for job {}: {}", job_id, e);
+                    return false;
+                }
+            };
+        let payload = types::LogChunkPayload { offset, data };
+        if let Err(e) = self.send(OpCode::LogChunk, job_id, &payload) {
+            warn!("Failed to send LOG_CHUNK for job {}: {}", job_id, e);
+            return false;
+        }
+        self.log_offsets.insert(job_id, next_offset);
+        true
+    }
+
+    /// Track the Sentinel connection from the socket's monitor events.
+    fn poll_connection_events(&mut self) {
+        while let Ok(frames) = self.monitor.recv_multipart(zmq::DONTWAIT) {
+            // First frame: event id (u16) and value (u32), native endian
+            let Some(event) = frames.first().filter(|frame| frame.len() >= 2) else {
+                continue;
+            };
+            let event = u16::from_ne_bytes([event[0], event[1]]);
+            let connected = [
+                zmq::SocketEvent::CONNECTED,
+                zmq::SocketEvent::HANDSHAKE_SUCCEEDED,
+            ]
+            .iter()
+            .any(|known| known.to_raw() == event);
+            if !connected {
+                debug!("Sentinel connection lost (socket event {})", event);
+                // The next Sentinel may be older; renegotiate on IDENTIFY
+                self.protocol = NegotiatedProtocol::default();
+            }
+            self.health.set_sentinel_connected(connected);
+        }
+    }
+
+    /// IDs of every running job, including follow-on files of batches.
+    fn active_job_ids(&self) -> Vec<JobId> {
+        self.active_jobs
+            .iter()
+            .flat_map(|(job_id, active_job)| {
+                std::iter::once(*job_id).chain(active_job.batch.iter().map(|file| file.job_id))
+            })
+            .collect()
+    }
+
     /// Remove completed job handles from active_jobs map
     fn reap_completed_jobs(&mut self) {
         let finished: Vec<JobId> = self
@@ -499,8 +923,10 @@ impl Worker {
         for job_id in finished {
             if let Some(active_job) = self.active_jobs.remove(&job_id) {
                 debug!("Reaped completed job {}", job_id);
+                self.health.job_finished(job_id);
                 if let Err(err) = active_job.handle.join() {
                     warn!("Job {} thread panicked: {:?}", job_id, err);
+                    self.log_offsets.remove(&job_id);
                 }
             }
         }
@@ -527,14 +953,18 @@ impl Worker {
         for (job_id, active_job) in &self.active_jobs {
             debug!("Signaling cancellation to job {}", job_id);
             active_job.cancel_token.cancel();
+            for file in &active_job.batch {
+                file.cancel_token.cancel();
+            }
         }
 
         let shutdown_timeout = Duration::from_secs(DEFAULT_SHUTDOWN_TIMEOUT_SECS);
-        let mut timed_out_jobs: Vec<(JobId, Option<String>)> = Vec::new();
+        let mut timed_out_jobs: Vec<(JobId, Option<String>, BatchLeases)> = Vec::new();
 
         // Wait for all job handles to complete (with per-job timeout)
         for (job_id, active_job) in self.active_jobs.drain() {
             debug!("Waiting for job {} to complete...", job_id);
+            self.health.job_finished(job_id);
             let start = Instant::now();
             loop {
                 if active_job.handle.is_finished() {
@@ -549,7 +979,12 @@ impl Worker {
                         "Job {} timed out during shutdown ({}s), aborting",
                         job_id, DEFAULT_SHUTDOWN_TIMEOUT_SECS
                     );
-                    timed_out_jobs.push((job_id, active_job.lease_token.clone()));
+                    let batch = active_job
+                        .batch
+                        .iter()
+                        .map(|file| (file.job_id, file.lease_token.clone()))
+                        .collect();
+                    timed_out_jobs.push((job_id, active_job.lease_token.clone(), batch));
                     break;
                 }
                 std::thread::sleep(Duration::from_millis(50));
@@ -557,24 +992,30 @@ impl Worker {
         }
 
         // Send explicit Aborted receipts for timed-out jobs so sentinel receives terminal receipts
-        for (job_id, lease_token) in &timed_out_jobs {
+        for (job_id, lease_token, batch) in &timed_out_jobs {
             warn!(
                 "Shutdown: sending ABORTED receipt for timed-out job {}",
                 job_id
             );
+            let message = format!(
+                "Job aborted: shutdown timeout exceeded ({}s)",
+                DEFAULT_SHUTDOWN_TIMEOUT_SECS
+            );
             let receipt = types::JobReceipt {
                 status: JobStatus::Aborted,
                 metrics: HashMap::new(),
                 artifacts: vec![],
-                error_message: Some(format!(
-                    "Job aborted: shutdown timeout exceeded ({}s)",
-                    DEFAULT_SHUTDOWN_TIMEOUT_SECS
-                )),
+                error_message: Some(message.clone()),
                 diagnostics: None,
                 source_hash: None, // Not available for timed-out jobs
+                source_file: None,
+                decompressed_hash: None,
+                source_encoding: None,
+                lineage: None,
                 lease_token: lease_token.clone(),
+                batch_results: unrun_batch_receipts(batch, JobStatus::Aborted, &message),
             };
-            if let Err(e) = send_message(&self.socket, OpCode::Conclude, *job_id, &receipt) {
+            if let Err(e) = self.send(OpCode::Conclude, *job_id, &receipt) {
                 error!(
                     "Failed to send ABORTED CONCLUDE for job {} during shutdown: {}",
                     job_id, e
@@ -583,7 +1024,10 @@ impl Worker {
         }
 
         if !timed_out_jobs.is_empty() {
-            let job_ids: Vec<JobId> = timed_out_jobs.iter().map(|(job_id, _)| *job_id).collect();
+            let job_ids: Vec<JobId> = timed_out_jobs
+                .iter()
+                .map(|(job_id, _, _)| *job_id)
+                .collect();
             info!(
                 "Shutdown: sent ABORTED receipts for {} timed-out jobs: {:?}",
                 timed_out_jobs.len(),
@@ -597,7 +1041,7 @@ impl Worker {
         while let Ok(result) = self.result_rx.try_recv() {
             if timed_out_jobs
                 .iter()
-                .any(|(job_id, _)| *job_id == result.job_id)
+                .any(|(job_id, _, _)| *job_id == result.job_id)
             {
                 debug!(
                     "Shutdown: skipping CONCLUDE for timed-out job {} (already aborted)",
@@ -609,12 +1053,7 @@ impl Worker {
                 "Shutdown: sending CONCLUDE for job {} (status: {:?})",
                 result.job_id, result.receipt.status
             );
-            if let Err(e) = send_message(
-                &self.socket,
-                OpCode::Conclude,
-                result.job_id,
-                &result.receipt,
-            ) {
+            if let Err(e) = self.send(OpCode::Conclude, result.job_id, &result.receipt) {
                 error!(
                     "Failed to send CONCLUDE for job {} during shutdown: {}",
                     result.job_id, e
@@ -635,6 +1074,11 @@ impl Worker {
             OpCode::Dispatch => {
                 let cmd: DispatchCommand = serde_json::from_slice(&msg.payload)?;
                 let job_id = msg.header.job_id;
+                let batch_leases: BatchLeases = cmd
+                    .batch
+                    .iter()
+                    .map(|file| (file.job_id, file.lease_token.clone()))
+                    .collect();
 
                 // Check if we're at capacity
                 if self.active_jobs.len() >= MAX_CONCURRENT_JOBS {
@@ -649,46 +1093,92 @@ impl Worker {
                         error_message: Some("Worker at capacity".to_string()),
                         diagnostics: None,
                         source_hash: None, // Not computed before rejection
+                        source_file: None,
+                        decompressed_hash: None,
+                        source_encoding: None,
+                        lineage: None,
                         lease_token: cmd.lease_token.clone(),
+                        batch_results: unrun_batch_receipts(
+                            &batch_leases,
+                            JobStatus::Rejected,
+                            "Worker at capacity",
+                        ),
                     };
-                    send_message(&self.socket, OpCode::Conclude, job_id, &receipt)?;
+                    self.send(OpCode::Conclude, job_id, &receipt)?;
                     return Ok(());
                 }
 
-                info!(
-                    "DISPATCH job {} -> {} ({} active)",
-                    job_id,
-                    cmd.plugin_name,
-                    self.active_jobs.len() + 1
-                );
+                if batch_leases.is_empty() {
+                    info!(
+                        "DISPATCH job {} -> {} ({} active)",
+                        job_id,
+                        cmd.plugin_name,
+                        self.active_jobs.len() + 1
+                    );
+                } else {
+                    info!(
+                        "DISPATCH job {} -> {} (batch of {} files, {} active)",
+                        job_id,
+                        cmd.plugin_name,
+                        batch_leases.len() + 1,
+                        self.active_jobs.len() + 1
+                    );
+                }
 
                 let lease_token = cmd.lease_token.clone();
-                if let Some(token) = lease_token.clone() {
+                let acks = lease_token
+                    .clone()
+                    .map(|token| (job_id, token))
+                    .into_iter()
+                    .chain(batch_leases.iter().cloned());
+                for (ack_job_id, token) in acks {
                     let ack = types::DispatchAckPayload {
                         lease_token: token,
                         worker_id: Some(self.config.worker_id.clone()),
                     };
-                    send_message(&self.socket, OpCode::DispatchAck, job_id, &ack)?;
+                    self.send(OpCode::DispatchAck, ack_job_id, &ack)?;
                 }
 
-                // Create cancellation token for this job
+                // Create cancellation tokens for this job and each batched file
                 let cancel_token = CancellationToken::new();
                 let cancel_token_clone = cancel_token.clone();
+                let batch: Vec<BatchJob> = batch_leases
+                    .into_iter()
+                    .map(|(job_id, lease_token)| BatchJob {
+                        job_id,
+                        cancel_token: CancellationToken::new(),
+                        lease_token,
+                    })
+                    .collect();
+                let batch_tokens: Vec<CancellationToken> =
+                    batch.iter().map(|file| file.cancel_token.clone()).collect();
 
                 // Clone what we need for the spawned task
                 let tx = self.result_tx.clone();
                 let venv_mgr = self.venv_manager.clone();
                 let parquet_root = self.config.parquet_root.clone();
-                let shim_path = self.config.shim_path.clone();
+                let runtime_settings = RuntimeSettings {
+                    shim_path: self.config.shim_path.clone(),
+                    batch_sizing: self.config.batch_sizing,
+                    encode_threads: self.config.encode_threads,
+                    default_limits: self.config.default_limits.clone(),
+                    scratch_root: self
+                        .config
+                        .scratch_dir
+                        .clone()
+                        .unwrap_or_else(scratch::default_scratch_root),
+                };
 
+                self.health.job_started(job_id, &cmd.plugin_name);
                 let handle = std::thread::spawn(move || {
-                    let receipt = execute_job(
+                    let receipt = execute_dispatch(
                         job_id,
                         cmd,
                         venv_mgr,
                         parquet_root,
-                        shim_path,
+                        runtime_settings,
                         cancel_token_clone,
+                        batch_tokens,
                     );
                     // If channel is closed, worker is shutting down - that's fine
                     let _ = tx.send(JobResult { job_id, receipt });
@@ -700,13 +1190,14 @@ impl Worker {
                         handle,
                         cancel_token,
                         lease_token,
+                        batch,
                     },
                 );
             }
 
             OpCode::Heartbeat => {
                 debug!("Received HEARTBEAT, replying...");
-                let active_job_ids: Vec<JobId> = self.active_jobs.keys().copied().collect();
+                let active_job_ids = self.active_job_ids();
                 let active_job_count = self.active_jobs.len();
                 let status = self.compute_heartbeat_status();
 
@@ -714,26 +1205,74 @@ impl Worker {
                     status,
                     active_job_count,
                     active_job_ids,
+                    load: Some(load::sample_load(active_job_count, MAX_CONCURRENT_JOBS)),
                 };
-                send_message(&self.socket, OpCode::Heartbeat, JobId::new(0), &payload)?;
+                self.send(OpCode::Heartbeat, JobId::new(0), &payload)?;
             }
 
             OpCode::Abort => {
                 let job_id = msg.header.job_id;
+                let batch_token = self
+                    .active_jobs
+                    .values()
+                    .flat_map(|active_job| active_job.batch.iter())
+                    .find(|file| file.job_id == job_id)
+                    .map(|file| &file.cancel_token);
                 if let Some(active_job) = self.active_jobs.get(&job_id) {
                     warn!("ABORT job {} - signaling cancellation", job_id);
                     // Signal cancellation to the job - this will trigger subprocess termination
                     active_job.cancel_token.cancel();
+                } else if let Some(token) = batch_token {
+                    warn!("ABORT batched job {} - signaling cancellation", job_id);
+                    token.cancel();
                 } else {
                     warn!("ABORT job {} - not found in active jobs", job_id);
                 }
             }
 
+            OpCode::Ack => {
+                let ack: types::IdentifyAckPayload = serde_json::from_slice(&msg.payload)?;
+                self.protocol = NegotiatedProtocol {
+                    version: ack.protocol_version,
+                    features: ack.protocol_features,
+                };
+                debug!(
+                    "Sentinel acknowledged IDENTIFY (protocol v{}, features {:#x})",
+                    ack.protocol_version,
+                    ack.protocol_features.bits()
+                );
+            }
+
             OpCode::Err => {
                 let err: types::ErrorPayload = serde_json::from_slice(&msg.payload)?;
                 error!("Received ERR from sentinel: {}", err.message);
             }
 
+            OpCode::Reload => {
+                let reload: types::ReloadPayload = serde_json::from_slice(&msg.payload)?;
+                // Plugin source travels with every DISPATCH and venvs are keyed
+                // by env_hash, so nothing is cached per version; the next
+                // dispatch already carries the new version.
+                info!(
+                    "RELOAD plugin '{}' -> version {}",
+                    reload.plugin_name, reload.version
+                );
+                // Build the new env now so the first job does not pay for it
+                if let (Some(env_hash), Some(lockfile)) = (reload.env_hash, reload.lockfile_content)
+                {
+                    let venv_mgr = self.venv_manager.clone();
+                    std::thread::spawn(move || {
+                        match venv_mgr.resolve(&env_hash, Some(&lockfile)) {
+                            Ok(_) => info!("Env {} ready for '{}'", env_hash, reload.plugin_name),
+                            Err(e) => warn!(
+                                "Failed to prepare env {} for '{}': {:#}",
+                                env_hash, reload.plugin_name, e
+                            ),
+                        }
+                    });
+                }
+            }
+
             _ => {
                 warn!("Unhandled opcode: {:?}", msg.header.opcode);
             }
@@ -753,21 +1292,75 @@ fn truncate_hash(hash: &str) -> &str {
     }
 }
 
-fn compute_source_hash(path: &str) -> Result<String> {
-    let mut file = File::open(path)
-        .map_err(|e| anyhow::anyhow!("failed to open source file '{}': {}", path, e))?;
-    let mut hasher = blake3::Hasher::new();
-    let mut buffer = [0u8; 16 * 1024];
-    loop {
-        let bytes = file
-            .read(&mut buffer)
-            .map_err(|e| anyhow::anyhow!("failed to read source file '{}': {}", path, e))?;
-        if bytes == 0 {
-            break;
+/// Blake3 hash of a job's input and the file generation it covers.
+struct InputHash {
+    hash: String,
+    file: SourceFile,
+}
+
+/// Hash the input of `cmd`, reusing the Sentinel's cached hash while the file
+/// still has the size and mtime it was computed from.
+fn resolve_source_hash(cmd: &DispatchCommand) -> Result<InputHash> {
+    let path = Path::new(&cmd.file_path);
+    if let Some(cached) = cmd.cached_source_hash.as_ref() {
+        let current = casparian_hash::FileFingerprint::of(path)?;
+        if current.size == cached.fingerprint.size
+            && current.mtime_ms == cached.fingerprint.mtime_ms
+        {
+            return Ok(InputHash {
+                hash: cached.hash.clone(),
+                file: SourceFile {
+                    path: cmd.file_path.clone(),
+                    fingerprint: cached.fingerprint,
+                },
+            });
+        }
+        debug!("Cached hash of '{}' is stale; rehashing", cmd.file_path);
+    }
+    let hashed = casparian_hash::hash_file(path)?;
+    Ok(InputHash {
+        hash: hashed.hash,
+        file: SourceFile {
+            path: cmd.file_path.clone(),
+            fingerprint: SourceFingerprint {
+                size: hashed.fingerprint.size,
+                mtime_ms: hashed.fingerprint.mtime_ms,
+            },
+        },
+    })
+}
+
+/// Blake3 hash of a written output, when it is a single local file. Partitioned
+/// outputs, databases and remote sinks have no single file to hash.
+fn output_content_hash(sink_uri: &str) -> Option<String> {
+    let parsed = ParsedSinkUri::parse(sink_uri).ok()?;
+    if !matches!(
+        parsed.scheme,
+        SinkScheme::File
+            | SinkScheme::Parquet
+            | SinkScheme::Csv
+            | SinkScheme::Jsonl
+            | SinkScheme::Arrow
+    ) || !parsed.path.is_file()
+    {
+        return None;
+    }
+    let is_database = parsed
+        .path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|ext| matches!(ext.to_ascii_lowercase().as_str(), "duckdb" | "db"))
+        .unwrap_or(false);
+    if is_database {
+        return None;
+    }
+    match casparian_hash::hash_file(&parsed.path) {
+        Ok(hashed) => Some(hashed.hash),
+        Err(err) => {
+            warn!("Failed to hash output '{}': {}", sink_uri, err);
+            None
         }
-        hasher.update(&buffer[..bytes]);
     }
-    Ok(hasher.finalize().to_hex().to_string())
 }
 
 fn build_schema_hashes(cmd: &DispatchCommand) -> HashMap<String, String> {
@@ -842,6 +1435,10 @@ fn validate_entrypoint(entrypoint: &Path, base_dir: &Path) -> WorkerResult<PathB
 fn resolve_entrypoint(cmd: &DispatchCommand) -> WorkerResult<String> {
     match cmd.runtime_kind {
         RuntimeKind::PythonShim => Ok(cmd.entrypoint.clone()),
+        RuntimeKind::NativeExec if cmd.entrypoint.starts_with(BUILTIN_XLSX_ENTRYPOINT) => {
+            // Built-in readers run in-process; there is no binary to resolve.
+            Ok(cmd.entrypoint.clone())
+        }
         RuntimeKind::NativeExec => {
             let version = cmd
                 .parser_version
@@ -1045,21 +1642,88 @@ struct ExecutionMetrics {
     quarantine_rows: usize,
     lineage_unavailable_rows: usize,
     outputs: Vec<OutputMetrics>,
+    constraint_violations: Vec<types::ConstraintViolations>,
+    column_stats: Vec<types::OutputColumnStats>,
+    /// Input changes the contracts accepted
+    schema_drift: Option<types::SchemaDrift>,
+    /// Hash of the decompressed input, when the input was compressed
+    decompressed_hash: Option<String>,
+    /// Encoding of the input, when it was transcoded to UTF-8
+    source_encoding: Option<TextEncoding>,
+    peak_memory_bytes: u64,
+    /// High-water mark of the plugin's scratch directory
+    scratch_peak_bytes: u64,
+    /// Encode and IO time of the Parquet/CSV sinks, once outputs are written
+    write_timings: Option<casparian_sinks::encode::WriteTimings>,
+}
+
+impl ExecutionMetrics {
+    /// Diagnostics for a completed run: stage timings, constraint violations,
+    /// column stats, schema drift, peak batch memory and peak scratch usage.
+    fn diagnostics(&self, stage_timings: Vec<types::StageTiming>) -> types::JobDiagnostics {
+        types::JobDiagnostics {
+            constraint_violations: self.constraint_violations.clone(),
+            stage_timings,
+            column_stats: self.column_stats.clone(),
+            peak_memory_bytes: Some(self.peak_memory_bytes),
+            scratch_peak_bytes: Some(self.scratch_peak_bytes),
+            schema_drift: self.schema_drift.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Tracks wall time per execution stage for `JobDiagnostics::stage_timings`.
+struct StageTimer {
+    current: JobStage,
+    started: Instant,
+    timings: Vec<types::StageTiming>,
+}
+
+impl StageTimer {
+    fn new() -> Self {
+        Self {
+            current: JobStage::Setup,
+            started: Instant::now(),
+            timings: Vec::new(),
+        }
+    }
+
+    /// End the current stage and start `next`.
+    fn enter(&mut self, next: JobStage) {
+        self.record_current();
+        self.current = next;
+        self.started = Instant::now();
+    }
+
+    /// End the current stage. Returns the last stage entered (the failed
+    /// stage, if the job failed) and the timings of every stage.
+    fn finish(mut self) -> (JobStage, Vec<types::StageTiming>) {
+        self.record_current();
+        (self.current, self.timings)
+    }
+
+    fn record_current(&mut self) {
+        self.timings.push(types::StageTiming {
+            stage: self.current,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+        });
+    }
 }
 
 enum ExecutionOutcome {
     Success {
         metrics: ExecutionMetrics,
         artifacts: Vec<ArtifactV1>,
-        source_hash: String,
+        source: InputHash,
     },
     QuarantineRejected {
         metrics: ExecutionMetrics,
         reason: String,
-        source_hash: String,
+        source: InputHash,
     },
     /// Job was cancelled during execution
-    Cancelled { source_hash: Option<String> },
+    Cancelled { source: Option<InputHash> },
 }
 
 fn insert_execution_metrics(metrics: &mut HashMap<String, i64>, exec: &ExecutionMetrics) {
@@ -1073,6 +1737,13 @@ fn insert_execution_metrics(metrics: &mut HashMap<String, i64>, exec: &Execution
         exec.lineage_unavailable_rows as i64,
     );
     metrics.insert(metrics::OUTPUT_COUNT.to_string(), exec.outputs.len() as i64);
+    if let Some(timings) = exec.write_timings {
+        metrics.insert(
+            metrics::SINK_ENCODE_MS.to_string(),
+            timings.encode_ms as i64,
+        );
+        metrics.insert(metrics::SINK_IO_MS.to_string(), timings.io_ms as i64);
+    }
 
     // Per-output metrics including status
     for output in &exec.outputs {
@@ -1098,38 +1769,100 @@ fn insert_execution_metrics(metrics: &mut HashMap<String, i64>, exec: &Execution
     }
 }
 
-fn is_default_sink(topic: &str) -> bool {
-    topic == "*" || topic == "output"
+/// Header columns of a delimited input that no output emits. Only checked
+/// when an output has a locked contract; byte-range shards have no header.
+fn unmapped_source_columns(
+    cmd: &DispatchCommand,
+    ctx: &RunContext,
+    source_path: &Path,
+    input_path: &Path,
+    outputs: &[casparian_sinks::OutputPlan],
+) -> Vec<String> {
+    let contracted = outputs.iter().any(|output| {
+        matches!(
+            select_sink_config(cmd, output.name()),
+            Ok(Some(sink)) if sink.schema.is_some()
+        )
+    });
+    if !contracted || cmd.byte_range.is_some() {
+        return Vec::new();
+    }
+    let Some(dialect) = drift::input_dialect(source_path, ctx.csv_dialect.as_ref()) else {
+        return Vec::new();
+    };
+    let header = match drift::read_csv_header(input_path, &dialect) {
+        Ok(Some(header)) => header,
+        Ok(None) => return Vec::new(),
+        Err(err) => {
+            warn!(
+                "Skipping unmapped column check for '{}': {:#}",
+                cmd.file_path, err
+            );
+            return Vec::new();
+        }
+    };
+    let emitted: Vec<String> = outputs
+        .iter()
+        .filter_map(|output| output.batches().first())
+        .flat_map(|batch| {
+            let schema = batch.as_record_batch().schema();
+            schema
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    drift::unmapped_columns(&header, emitted.iter().map(String::as_str))
 }
 
 fn select_sink_config<'a>(
     cmd: &'a DispatchCommand,
     output_name: &str,
 ) -> WorkerResult<Option<&'a types::SinkConfig>> {
-    if let Some(exact) = cmd.sinks.iter().find(|sink| sink.topic == output_name) {
-        return Ok(Some(exact));
-    }
-    if let Some(default) = cmd.sinks.iter().find(|sink| is_default_sink(&sink.topic)) {
-        return Ok(Some(default));
-    }
-    if cmd.sinks.len() <= 1 {
-        return Ok(cmd.sinks.first());
+    if cmd.sinks.is_empty() {
+        return Ok(None);
     }
+    types::select_sink_for_output(&cmd.sinks, output_name)
+        .map(Some)
+        .ok_or_else(|| WorkerError::Permanent {
+            message: format!(
+                "Output '{}' has no sink config; configured topics: {}",
+                output_name,
+                sink_topics(cmd)
+            ),
+        })
+}
 
-    let topics = cmd
-        .sinks
-        .iter()
-        .map(|sink| sink.topic.as_str())
-        .collect::<Vec<_>>()
-        .join(", ");
+/// Fail before anything is written if any output lacks a sink, naming all of
+/// them rather than the first.
+fn ensure_outputs_targeted(
+    cmd: &DispatchCommand,
+    outputs: &[casparian_sinks::OutputPlan],
+) -> WorkerResult<()> {
+    let names: Vec<&str> = outputs.iter().map(|output| output.name()).collect();
+    let missing = types::untargeted_outputs(&cmd.sinks, &names);
+    if missing.is_empty() {
+        return Ok(());
+    }
     Err(WorkerError::Permanent {
         message: format!(
-            "Output '{}' has no sink config; configured topics: {}",
-            output_name, topics
+            "Outputs [{}] have no sink config; configured topics: {} \
+             (add a sink for each output or a '*' default sink)",
+            missing.join(", "),
+            sink_topics(cmd)
         ),
     })
 }
 
+fn sink_topics(cmd: &DispatchCommand) -> String {
+    cmd.sinks
+        .iter()
+        .map(|sink| sink.topic.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn resolve_quarantine_config(
     config: Option<&types::QuarantineConfig>,
 ) -> Result<types::QuarantineConfig> {
@@ -1171,9 +1904,17 @@ fn sink_uri_for_quarantine(sink_uri: &str, quarantine_dir: Option<&str>) -> Resu
     if matches!(parsed.scheme, SinkScheme::Duckdb) || is_duckdb_file {
         anyhow::bail!("quarantine_dir is not supported for duckdb sinks");
     }
+    if matches!(parsed.scheme, SinkScheme::S3 | SinkScheme::Postgres) {
+        anyhow::bail!(
+            "quarantine_dir is not supported for {} sinks",
+            parsed.scheme.as_str()
+        );
+    }
 
     let target_path = match parsed.scheme {
-        SinkScheme::Parquet | SinkScheme::Csv => PathBuf::from(trimmed),
+        SinkScheme::Parquet | SinkScheme::Csv | SinkScheme::Jsonl | SinkScheme::Arrow => {
+            PathBuf::from(trimmed)
+        }
         SinkScheme::File => {
             let ext = parsed
                 .path
@@ -1184,8 +1925,8 @@ fn sink_uri_for_quarantine(sink_uri: &str, quarantine_dir: Option<&str>) -> Resu
             path.push(format!("placeholder.{}", ext));
             path
         }
-        SinkScheme::Duckdb => {
-            unreachable!("duckdb handled above");
+        SinkScheme::Duckdb | SinkScheme::S3 | SinkScheme::Postgres => {
+            unreachable!("non-file sinks handled above");
         }
     };
 
@@ -1204,51 +1945,115 @@ fn validate_quarantine_config(config: &types::QuarantineConfig) -> Result<()> {
     if config.max_quarantine_pct < 0.0 || config.max_quarantine_pct > 100.0 {
         anyhow::bail!("max_quarantine_pct must be between 0 and 100");
     }
+    if let Some(max_null_pct) = config.max_null_pct {
+        if !(0.0..=100.0).contains(&max_null_pct) {
+            anyhow::bail!("max_null_pct must be between 0 and 100");
+        }
+    }
     Ok(())
 }
 
-fn check_quarantine_policy(
-    output_name: &str,
-    quarantine_rows: u64,
-    total_rows: u64,
-    config: &types::QuarantineConfig,
-) -> Option<String> {
-    if quarantine_rows == 0 {
-        return None;
-    }
-    if !config.allow_quarantine {
-        return Some(format!(
-            "quarantine disabled for '{}': {} rows",
-            output_name, quarantine_rows
-        ));
-    }
+/// Execute a DISPATCH: the header job, then each batched file in order.
+///
+/// Batched files reuse the header's plugin, sinks and env; each gets its own
+/// receipt in `batch_results` of the returned (header) receipt.
+/// Fill the limits a plugin left unset from the worker's defaults.
+fn apply_default_limits(limits: &mut ResourceLimits, defaults: &ResourceLimits) {
+    limits.timeout_secs = limits.timeout_secs.or(defaults.timeout_secs);
+    limits.max_cpu_secs = limits.max_cpu_secs.or(defaults.max_cpu_secs);
+    limits.max_memory_bytes = limits.max_memory_bytes.or(defaults.max_memory_bytes);
+    limits.max_decompressed_bytes = limits
+        .max_decompressed_bytes
+        .or(defaults.max_decompressed_bytes);
+    limits.max_scratch_bytes = limits.max_scratch_bytes.or(defaults.max_scratch_bytes);
+}
 
-    if let Some(max_count) = config.max_quarantine_count {
-        if quarantine_rows > max_count {
-            return Some(format!(
-                "quarantine count exceeded for '{}': {} > {}",
-                output_name, quarantine_rows, max_count
-            ));
-        }
+fn execute_dispatch(
+    job_id: JobId,
+    mut cmd: DispatchCommand,
+    venv_manager: Arc<VenvManager>,
+    parquet_root: PathBuf,
+    runtime_settings: RuntimeSettings,
+    cancel_token: CancellationToken,
+    batch_tokens: Vec<CancellationToken>,
+) -> types::JobReceipt {
+    let batch = std::mem::take(&mut cmd.batch);
+    apply_default_limits(&mut cmd.limits, &runtime_settings.default_limits);
+    if batch.is_empty() {
+        return execute_job(
+            job_id,
+            cmd,
+            venv_manager,
+            parquet_root,
+            runtime_settings,
+            cancel_token,
+        );
     }
 
-    let pct = quarantine_pct(quarantine_rows, total_rows);
-    if pct > config.max_quarantine_pct {
-        return Some(format!(
-            "quarantine pct exceeded for '{}': {:.2}% > {:.2}%",
-            output_name, pct, config.max_quarantine_pct
-        ));
+    let mut receipt = execute_job(
+        job_id,
+        cmd.clone(),
+        venv_manager.clone(),
+        parquet_root.clone(),
+        runtime_settings.clone(),
+        cancel_token,
+    );
+    for (file, file_token) in batch.into_iter().zip(batch_tokens) {
+        let file_cmd = DispatchCommand {
+            file_path: file.file_path,
+            file_id: file.file_id,
+            lease_token: Some(file.lease_token),
+            byte_range: None,
+            tag: file.tag,
+            checkpoint_key: None,
+            csv_dialect: file.csv_dialect,
+            input_encoding: file.input_encoding,
+            cached_source_hash: file.cached_source_hash,
+            input_lineage: file.input_lineage,
+            ..cmd.clone()
+        };
+        let file_receipt = execute_job(
+            file.job_id,
+            file_cmd,
+            venv_manager.clone(),
+            parquet_root.clone(),
+            runtime_settings.clone(),
+            file_token,
+        );
+        receipt.batch_results.push(BatchFileReceipt {
+            job_id: file.job_id,
+            receipt: file_receipt,
+        });
     }
-
-    None
+    receipt
 }
 
-fn quarantine_pct(quarantine_rows: u64, total_rows: u64) -> f64 {
-    if total_rows == 0 {
-        0.0
-    } else {
-        (quarantine_rows as f64 / total_rows as f64) * 100.0
-    }
+/// Terminal receipts for batched files that were never run.
+fn unrun_batch_receipts(
+    batch: &[(JobId, String)],
+    status: JobStatus,
+    message: &str,
+) -> Vec<BatchFileReceipt> {
+    batch
+        .iter()
+        .map(|(job_id, lease_token)| BatchFileReceipt {
+            job_id: *job_id,
+            receipt: types::JobReceipt {
+                status: status.clone(),
+                metrics: HashMap::new(),
+                artifacts: vec![],
+                error_message: Some(message.to_string()),
+                diagnostics: None,
+                source_hash: None,
+                source_file: None,
+                decompressed_hash: None,
+                source_encoding: None,
+                lineage: None,
+                lease_token: Some(lease_token.clone()),
+                batch_results: Vec::new(),
+            },
+        })
+        .collect()
 }
 
 /// Execute a job and return receipt
@@ -1261,7 +2066,7 @@ fn execute_job(
     cmd: DispatchCommand,
     venv_manager: Arc<VenvManager>,
     parquet_root: PathBuf,
-    shim_path: PathBuf,
+    runtime_settings: RuntimeSettings,
     cancel_token: CancellationToken,
 ) -> types::JobReceipt {
     let lease_token = cmd.lease_token.clone();
@@ -1286,25 +2091,35 @@ fn execute_job(
             error_message: Some("Job cancelled before execution".to_string()),
             diagnostics: None,
             source_hash: None,
+            source_file: None,
+            decompressed_hash: None,
+            source_encoding: None,
+            lineage: None,
             lease_token: lease_token.clone(),
+            batch_results: Vec::new(),
         };
         let duration_ms = start.elapsed().as_millis() as u64;
         span.record("duration_ms", &duration_ms);
         return receipt;
     }
 
-    match execute_job_inner(
+    let mut stages = StageTimer::new();
+    let result = execute_job_inner(
         job_id,
         &cmd,
         &venv_manager,
         &parquet_root,
-        &shim_path,
+        &runtime_settings,
         &cancel_token,
-    ) {
+        &mut stages,
+    );
+    let (last_stage, stage_timings) = stages.finish();
+
+    match result {
         Ok(ExecutionOutcome::Success {
             metrics: exec_metrics,
             mut artifacts,
-            source_hash,
+            source,
         }) => {
             let mut metrics = HashMap::new();
             insert_execution_metrics(&mut metrics, &exec_metrics);
@@ -1316,14 +2131,20 @@ fn execute_job(
                 artifacts.push(log_artifact);
             }
 
+            let lineage = job_lineage(job_id, &cmd, &source.hash);
             let receipt = types::JobReceipt {
                 status: aggregated_status,
                 metrics,
                 artifacts,
                 error_message: None,
-                diagnostics: None,
-                source_hash: Some(source_hash),
+                diagnostics: Some(exec_metrics.diagnostics(stage_timings)),
+                source_hash: Some(source.hash),
+                source_file: Some(source.file),
+                decompressed_hash: exec_metrics.decompressed_hash,
+                source_encoding: exec_metrics.source_encoding,
+                lineage: Some(lineage),
                 lease_token: lease_token.clone(),
+                batch_results: Vec::new(),
             };
             let duration_ms = start.elapsed().as_millis() as u64;
             span.record("duration_ms", &duration_ms);
@@ -1332,31 +2153,45 @@ fn execute_job(
         Ok(ExecutionOutcome::QuarantineRejected {
             metrics: exec_metrics,
             reason,
-            source_hash,
+            source,
         }) => {
             let mut metrics = HashMap::new();
             insert_execution_metrics(&mut metrics, &exec_metrics);
             metrics.insert(metrics::IS_TRANSIENT.to_string(), 0);
             metrics.insert(metrics::QUARANTINE_REJECTED.to_string(), 1);
+            let mut diagnostics = exec_metrics.diagnostics(stage_timings);
+            diagnostics.error_class = Some(retry::ERROR_CLASS_PERMANENT.to_string());
+            diagnostics.error_category = Some(ErrorCategory::QuarantineRejected);
+            diagnostics.failed_stage = Some(JobStage::Validate);
             let mut artifacts = Vec::new();
             if let Some(log_artifact) = log_artifact_for_job(job_id) {
                 artifacts.push(log_artifact);
             }
 
+            let lineage = job_lineage(job_id, &cmd, &source.hash);
             let receipt = types::JobReceipt {
                 status: JobStatus::Failed,
                 metrics,
                 artifacts,
                 error_message: Some(reason),
-                diagnostics: None,
-                source_hash: Some(source_hash),
+                diagnostics: Some(diagnostics),
+                source_hash: Some(source.hash),
+                source_file: Some(source.file),
+                decompressed_hash: exec_metrics.decompressed_hash,
+                source_encoding: exec_metrics.source_encoding,
+                lineage: Some(lineage),
                 lease_token: lease_token.clone(),
+                batch_results: Vec::new(),
             };
             let duration_ms = start.elapsed().as_millis() as u64;
             span.record("duration_ms", &duration_ms);
             receipt
         }
-        Ok(ExecutionOutcome::Cancelled { source_hash }) => {
+        Ok(ExecutionOutcome::Cancelled { source }) => {
+            let (source_hash, source_file) = match source {
+                Some(source) => (Some(source.hash), Some(source.file)),
+                None => (None, None),
+            };
             let artifacts = log_artifact_for_job(job_id).into_iter().collect();
             let receipt = types::JobReceipt {
                 status: JobStatus::Aborted,
@@ -1365,7 +2200,12 @@ fn execute_job(
                 error_message: Some("Job cancelled during execution".to_string()),
                 diagnostics: None,
                 source_hash,
+                source_file,
+                decompressed_hash: None,
+                source_encoding: None,
+                lineage: None,
                 lease_token: lease_token.clone(),
+                batch_results: Vec::new(),
             };
             let duration_ms = start.elapsed().as_millis() as u64;
             span.record("duration_ms", &duration_ms);
@@ -1374,7 +2214,11 @@ fn execute_job(
         Err(worker_err) => {
             let is_transient = worker_err.is_transient();
             let error_message = worker_err.to_string();
-            let diagnostics = worker_err.diagnostics().cloned();
+            let mut diagnostics = worker_err.diagnostics().cloned().unwrap_or_default();
+            diagnostics.error_class = Some(worker_err.error_class().to_string());
+            diagnostics.error_category = Some(worker_err.category(last_stage));
+            diagnostics.failed_stage = Some(last_stage);
+            diagnostics.stage_timings = stage_timings;
             let artifacts = log_artifact_for_job(job_id).into_iter().collect();
 
             if is_transient {
@@ -1401,10 +2245,15 @@ fn execute_job(
                 metrics,
                 artifacts,
                 error_message: Some(error_message),
-                diagnostics,
+                diagnostics: Some(diagnostics),
                 // Hash unavailable on early failure (e.g., file not found, venv setup failure)
                 source_hash: None,
+                source_file: None,
+                decompressed_hash: None,
+                source_encoding: None,
+                lineage: None,
                 lease_token: lease_token.clone(),
+                batch_results: Vec::new(),
             };
             let duration_ms = start.elapsed().as_millis() as u64;
             span.record("duration_ms", &duration_ms);
@@ -1413,22 +2262,62 @@ fn execute_job(
     }
 }
 
+/// Lineage of a job's input: the job's own hop, then the hops of the
+/// pipeline stages that wrote the input, back to the raw source.
+fn job_lineage(job_id: JobId, cmd: &DispatchCommand, source_hash: &str) -> LineageChain {
+    let file_type = if cmd.input_lineage.is_some() {
+        LineageFileType::Artifact
+    } else {
+        LineageFileType::Original
+    };
+    let hop = LineageHop {
+        file_path: PathBuf::from(&cmd.file_path),
+        file_type,
+        offset: cmd.byte_range.map(|range| range.start).unwrap_or(0),
+        row_number: 0,
+        job_id: Some(job_id),
+        plugin_name: Some(cmd.plugin_name.clone()),
+        parser_version: cmd.parser_version.clone(),
+        content_hash: Some(source_hash.to_string()),
+    };
+    LineageChain::with_upstream(hop, cmd.input_lineage.as_ref())
+}
+
+/// The resource limit a failed plugin run was terminated for exceeding, if any.
+fn limit_failure(
+    err: &anyhow::Error,
+    run_token: &CancellationToken,
+    limits: &ResourceLimits,
+) -> Option<(LimitKind, String)> {
+    if run_token.is_expired() {
+        let exceeded = LimitExceeded::new(LimitKind::WallClock, limits);
+        return Some((exceeded.kind, exceeded.message));
+    }
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<LimitExceeded>())
+        .map(|exceeded| (exceeded.kind, exceeded.message.clone()))
+}
+
 /// Execute a job, returning WorkerError with retry classification on failure
 fn execute_job_inner(
     job_id: JobId,
     cmd: &DispatchCommand,
     venv_manager: &Arc<VenvManager>,
     parquet_root: &std::path::Path,
-    shim_path: &std::path::Path,
+    runtime_settings: &RuntimeSettings,
     cancel_token: &CancellationToken,
+    stages: &mut StageTimer,
 ) -> std::result::Result<ExecutionOutcome, WorkerError> {
     // Check cancellation early
     if cancel_token.is_cancelled() {
-        return Ok(ExecutionOutcome::Cancelled { source_hash: None });
+        return Ok(ExecutionOutcome::Cancelled { source: None });
     }
 
-    // Trust policy enforcement for native plugins
-    if cmd.runtime_kind == RuntimeKind::NativeExec && !cmd.signature_verified {
+    // Trust policy enforcement for native plugins (built-in readers ship with the worker)
+    if cmd.runtime_kind == RuntimeKind::NativeExec
+        && !cmd.signature_verified
+        && !cmd.entrypoint.starts_with(BUILTIN_XLSX_ENTRYPOINT)
+    {
         if !allow_unsigned_native().unwrap_or(false) {
             return Err(WorkerError::Permanent {
                 message: "Unsigned native plugin blocked by trust policy".to_string(),
@@ -1455,33 +2344,137 @@ fn execute_job_inner(
 
     let entrypoint = resolve_entrypoint(cmd)?;
     let schema_hashes = build_schema_hashes(cmd);
-    let ctx = RunContext {
+    let mut ctx = RunContext {
         job_id,
         file_id: cmd.file_id,
         entrypoint,
         env_hash: cmd.env_hash.clone(),
+        lockfile_content: cmd.lockfile_content.clone(),
         source_code: cmd.source_code.clone(),
         schema_hashes,
+        limits: cmd.limits.clone(),
+        byte_range: cmd.byte_range,
+        csv_dialect: cmd.csv_dialect.clone(),
+        scratch_dir: None,
     };
 
     let runtime: Box<dyn PluginRuntime> = match cmd.runtime_kind {
         RuntimeKind::PythonShim => Box::new(PythonShimRuntime::new(
             venv_manager.clone(),
-            shim_path.to_path_buf(),
+            runtime_settings.shim_path.clone(),
+        )),
+        RuntimeKind::NativeExec => Box::new(NativeSubprocessRuntime::with_batch_sizing(
+            runtime_settings.batch_sizing,
         )),
-        RuntimeKind::NativeExec => Box::new(NativeSubprocessRuntime::new()),
     };
 
     // Check cancellation before running the plugin
     if cancel_token.is_cancelled() {
-        return Ok(ExecutionOutcome::Cancelled { source_hash: None });
+        return Ok(ExecutionOutcome::Cancelled { source: None });
     }
 
-    let run_outputs = match runtime.run_file(&ctx, Path::new(&cmd.file_path), cancel_token) {
+    // Compressed inputs are decompressed to a temp file the parser reads instead
+    let source_path = Path::new(&cmd.file_path);
+    let decompressed = match decompress::decompress_input(source_path, &cmd.limits, cancel_token) {
+        Ok(decompressed) => decompressed,
+        Err(_) if cancel_token.is_cancelled() => {
+            return Ok(ExecutionOutcome::Cancelled { source: None });
+        }
+        Err(e) => {
+            if let Some((kind, message)) = limit_failure(&e, cancel_token, &cmd.limits) {
+                warn!("Job {}: {}", job_id, message);
+                return Err(WorkerError::PermanentWithDiagnostics {
+                    message,
+                    diagnostics: Box::new(types::JobDiagnostics {
+                        limit_exceeded: Some(kind),
+                        ..Default::default()
+                    }),
+                });
+            }
+            return Err(WorkerError::Permanent {
+                message: format!("{:#}", e),
+            });
+        }
+    };
+    if decompressed.is_some() && cmd.byte_range.is_some() {
+        return Err(WorkerError::Permanent {
+            message: "Byte-range reads are not supported for compressed inputs".to_string(),
+        });
+    }
+    let input_path = decompressed
+        .as_ref()
+        .map_or(source_path, |input| input.path());
+
+    // Non-UTF-8 text is transcoded so the parser always reads UTF-8. Byte-range
+    // reads slice the original bytes and are passed through unchanged.
+    let transcoded = if cmd.byte_range.is_some() {
+        None
+    } else {
+        match transcode::transcode_input(input_path, cmd.input_encoding, cancel_token) {
+            Ok(transcoded) => transcoded,
+            Err(_) if cancel_token.is_cancelled() => {
+                return Ok(ExecutionOutcome::Cancelled { source: None });
+            }
+            Err(e) => {
+                return Err(WorkerError::Permanent {
+                    message: format!("{:#}", e),
+                });
+            }
+        }
+    };
+    if let Some(input) = &transcoded {
+        if input.had_errors {
+            warn!(
+                "Job {}: input is not valid {}; malformed bytes were replaced",
+                job_id, input.encoding
+            );
+        }
+        if let Some(dialect) = ctx.csv_dialect.as_mut() {
+            dialect.encoding = TextEncoding::Utf8;
+        }
+    }
+    let input_path = transcoded.as_ref().map_or(input_path, |input| input.path());
+
+    // Scratch space for the plugin; removed when dropped, however the job ends
+    let mut scratch =
+        ScratchDir::create(&runtime_settings.scratch_root, job_id, &cmd.limits).map_err(|e| {
+            WorkerError::Transient {
+                message: format!("{:#}", e),
+            }
+        })?;
+    ctx.scratch_dir = Some(scratch.path().to_path_buf());
+
+    // Wall-clock limit: the runtimes kill the plugin once this token fires
+    let run_token = match cmd.limits.timeout_secs {
+        Some(secs) => cancel_token.with_deadline(Instant::now() + Duration::from_secs(secs)),
+        None => cancel_token.clone(),
+    };
+    let run_token = scratch.watch(&run_token);
+
+    stages.enter(JobStage::Execute);
+    let run_result = runtime.run_file(&ctx, input_path, &run_token);
+    let scratch_peak_bytes = scratch.finish();
+    let run_outputs = match run_result {
         Ok(outputs) => outputs,
         Err(e) => {
             if cancel_token.is_cancelled() {
-                return Ok(ExecutionOutcome::Cancelled { source_hash: None });
+                return Ok(ExecutionOutcome::Cancelled { source: None });
+            }
+
+            let failure = scratch
+                .exceeded(&cmd.limits)
+                .map(|exceeded| (exceeded.kind, exceeded.message))
+                .or_else(|| limit_failure(&e, &run_token, &cmd.limits));
+            if let Some((kind, message)) = failure {
+                warn!("Job {}: {}", job_id, message);
+                return Err(WorkerError::PermanentWithDiagnostics {
+                    message,
+                    diagnostics: Box::new(types::JobDiagnostics {
+                        limit_exceeded: Some(kind),
+                        scratch_peak_bytes: Some(scratch_peak_bytes),
+                        ..Default::default()
+                    }),
+                });
             }
 
             let error_message = e.to_string();
@@ -1537,8 +2530,22 @@ fn execute_job_inner(
             return Err(worker_err);
         }
     };
+    // A plugin can pass the quota and exit between two samples of the monitor
+    if let Some(exceeded) = scratch.exceeded(&cmd.limits) {
+        warn!("Job {}: {}", job_id, exceeded.message);
+        return Err(WorkerError::PermanentWithDiagnostics {
+            message: exceeded.message,
+            diagnostics: Box::new(types::JobDiagnostics {
+                limit_exceeded: Some(exceeded.kind),
+                scratch_peak_bytes: Some(scratch_peak_bytes),
+                ..Default::default()
+            }),
+        });
+    }
+    drop(scratch);
 
     let output_batches = run_outputs.output_batches;
+    let peak_memory_bytes = run_outputs.peak_memory_bytes;
 
     // Log the captured snippet for debugging (full log is persisted to disk)
     if !run_outputs.logs.is_empty() {
@@ -1553,14 +2560,15 @@ fn execute_job_inner(
     // Check cancellation after plugin execution, before writing to sinks
     // This prevents committing outputs for cancelled jobs
     if cancel_token.is_cancelled() {
-        let source_hash = compute_source_hash(&cmd.file_path).ok();
+        let source = resolve_source_hash(cmd).ok();
         info!(
             "Job {} cancelled after plugin execution, not writing outputs",
             job_id
         );
-        return Ok(ExecutionOutcome::Cancelled { source_hash });
+        return Ok(ExecutionOutcome::Cancelled { source });
     }
 
+    stages.enter(JobStage::Validate);
     let default_sink = format!("parquet://{}", parquet_root.display());
     let sink_uri = cmd
         .sinks
@@ -1583,16 +2591,18 @@ fn execute_job_inner(
                 message: e.to_string(),
             }
         })?;
+    ensure_outputs_targeted(cmd, &outputs)?;
 
     let job_id_str = job_id.to_string();
-    let source_hash =
-        compute_source_hash(&cmd.file_path).map_err(|err| WorkerError::Permanent {
-            message: format!(
-                "Failed to compute source hash for '{}': {}",
-                cmd.file_path, err
-            ),
-        })?;
+    let source = resolve_source_hash(cmd).map_err(|err| WorkerError::Permanent {
+        message: format!(
+            "Failed to compute source hash for '{}': {}",
+            cmd.file_path, err
+        ),
+    })?;
     let parser_version = cmd.parser_version.as_deref().unwrap_or("unknown");
+    let template_vars = casparian_sinks::template::TemplateVars::new(cmd.plugin_name.clone())
+        .with_tag(cmd.tag.clone());
 
     let mut total_rows = 0;
     let mut quarantine_rows = 0;
@@ -1600,6 +2610,10 @@ fn execute_job_inner(
     let mut artifacts: Vec<ArtifactV1> = Vec::new();
     let mut output_metrics = Vec::new();
     let mut policy_failures = Vec::new();
+    let mut constraint_violations = Vec::new();
+    let mut column_stats = Vec::new();
+    let mut type_drifts = Vec::new();
+    let unmapped_columns = unmapped_source_columns(cmd, &ctx, source_path, input_path, &outputs);
 
     let mut owned_outputs = Vec::new();
 
@@ -1610,6 +2624,9 @@ fn execute_job_inner(
         let sink_mode = sink_config
             .map(|sink| sink.mode)
             .unwrap_or(SinkMode::Append);
+        let schema_evolution = sink_config
+            .map(|sink| sink.schema_evolution)
+            .unwrap_or_default();
         let schema_def = sink_config.and_then(|sink| sink.schema.as_ref());
         let schema_hash_value = schema_hash(schema_def);
         if schema_hash_value.is_some() {
@@ -1619,7 +2636,15 @@ fn execute_job_inner(
         let sink_uri_for_config = sink_config
             .map(|sink| sink.uri.as_str())
             .unwrap_or(sink_uri);
-        let sink_uri_for_output = sink_uri_for_config.to_string();
+        let sink_uri_for_output = casparian_sinks::template::render_sink_uri(
+            sink_uri_for_config,
+            &template_vars,
+            &output_name,
+            &job_id_str,
+        )
+        .map_err(|e| WorkerError::Permanent {
+            message: e.to_string(),
+        })?;
 
         let mut output_batches: Vec<RecordBatch> = output
             .batches()
@@ -1627,6 +2652,11 @@ fn execute_job_inner(
             .map(|batch| batch.as_record_batch().clone())
             .collect();
         if let Some(schema_def) = schema_def {
+            type_drifts.extend(schema_validation::detect_type_drift(
+                &output_batches,
+                schema_def,
+                &output_name,
+            ));
             output_batches = match schema_validation::enforce_schema_on_batches(
                 &output_batches,
                 schema_def,
@@ -1642,9 +2672,10 @@ fn execute_job_inner(
                             let summary = schema_validation::summarize_schema_mismatch(&mismatch);
                             WorkerError::PermanentWithDiagnostics {
                                 message: summary,
-                                diagnostics: types::JobDiagnostics {
+                                diagnostics: Box::new(types::JobDiagnostics {
                                     schema_mismatch: Some(mismatch),
-                                },
+                                    ..Default::default()
+                                }),
                             }
                         }
                         schema_validation::SchemaValidationError::InvalidSchemaDef { message } => {
@@ -1658,6 +2689,18 @@ fn execute_job_inner(
                     });
                 }
             };
+
+            let checked = constraints::enforce_constraints(&output_batches, schema_def)
+                .map_err(|e| WorkerError::Permanent {
+                    message: format!("constraint check failed for '{}': {}", output_name, e),
+                })?;
+            output_batches = checked.batches;
+            if !checked.violations.is_empty() {
+                constraint_violations.push(types::ConstraintViolations {
+                    output_name: output_name.clone(),
+                    violations: checked.violations,
+                });
+            }
         }
 
         let output_batch_refs: Vec<&RecordBatch> = output_batches.iter().collect();
@@ -1692,13 +2735,36 @@ fn execute_job_inner(
             message: format!("output row count overflow for '{}'", output_name),
         })?;
 
+        // Stats cover the rows that will be written, before lineage injection.
+        let mut null_failure = None;
+        if quarantine_config.collects_column_stats() {
+            let stat_batches: Vec<casparian_sinks::OutputBatch> = valid_batches
+                .iter()
+                .cloned()
+                .map(casparian_sinks::OutputBatch::from_record_batch)
+                .collect();
+            let columns =
+                collect_column_stats(&stat_batches).map_err(|e| WorkerError::Permanent {
+                    message: format!("column stats failed for '{}': {}", output_name, e),
+                })?;
+            null_failure = quarantine_config
+                .max_null_pct
+                .and_then(|max_null_pct| check_null_policy(&output_name, &columns, max_null_pct));
+            column_stats.push(types::OutputColumnStats {
+                output_name: output_name.clone(),
+                columns,
+            });
+        }
+
         // Determine per-output status based on quarantine policy
         let output_status = if let Some(reason) = check_quarantine_policy(
             &output_name,
             quarantined_u64,
             output_rows_u64,
             &quarantine_config,
-        ) {
+        )
+        .or(null_failure)
+        {
             policy_failures.push(reason);
             OutputStatus::Failed
         } else if quarantined > 0 {
@@ -1719,7 +2785,7 @@ fn execute_job_inner(
             let lineage_batches = inject_lineage_batches(
                 &output_name,
                 valid_batches,
-                &source_hash,
+                &source.hash,
                 &job_id_str,
                 parser_version,
             )
@@ -1732,6 +2798,7 @@ fn execute_job_inner(
                 batches: lineage_batches,
                 sink_uri: sink_uri_for_output.clone(),
                 sink_mode,
+                schema_evolution,
                 is_quarantine: false,
                 schema_hash: schema_hash_value.clone(),
             });
@@ -1751,17 +2818,39 @@ fn execute_job_inner(
                 batches: quarantine_batches,
                 sink_uri: quarantine_sink_uri,
                 sink_mode: SinkMode::Append,
+                schema_evolution: SchemaEvolution::default(),
                 is_quarantine: true,
                 schema_hash: None,
             });
         }
     }
 
-    let exec_metrics = ExecutionMetrics {
+    let schema_drift = types::SchemaDrift {
+        unmapped_columns,
+        type_drifts,
+    };
+    if !schema_drift.is_empty() {
+        warn!(
+            "Job {}: input drifted from the contract ({} unmapped source column(s), {} type drift(s))",
+            job_id,
+            schema_drift.unmapped_columns.len(),
+            schema_drift.type_drifts.len()
+        );
+    }
+
+    let mut exec_metrics = ExecutionMetrics {
         rows: total_rows,
         quarantine_rows,
         lineage_unavailable_rows,
         outputs: output_metrics,
+        constraint_violations,
+        column_stats,
+        schema_drift: (!schema_drift.is_empty()).then_some(schema_drift),
+        decompressed_hash: decompressed.map(|input| input.hash),
+        source_encoding: transcoded.map(|input| input.encoding),
+        peak_memory_bytes,
+        scratch_peak_bytes,
+        write_timings: None,
     };
 
     if !policy_failures.is_empty() {
@@ -1769,7 +2858,7 @@ fn execute_job_inner(
         return Ok(ExecutionOutcome::QuarantineRejected {
             metrics: exec_metrics,
             reason,
-            source_hash,
+            source,
         });
     }
 
@@ -1787,17 +2876,30 @@ fn execute_job_inner(
                 )
             })
             .collect();
-        let written = match write_outputs_grouped(owned_outputs, &job_id_str, cancel_token) {
+        stages.enter(JobStage::Write);
+        let written = match write_outputs_grouped(
+            owned_outputs,
+            &job_id_str,
+            cmd.checkpoint_key.as_deref(),
+            runtime_settings.encode_threads,
+            cancel_token,
+        ) {
             Ok(written) => written,
             Err(err) => {
                 if cancel_token.is_cancelled() {
                     return Ok(ExecutionOutcome::Cancelled {
-                        source_hash: Some(source_hash),
+                        source: Some(source),
                     });
                 }
                 return Err(err);
             }
         };
+        let mut write_timings = casparian_sinks::encode::WriteTimings::default();
+        for timings in written.iter().filter_map(|output| output.write_timings) {
+            write_timings.encode_ms += timings.encode_ms;
+            write_timings.io_ms += timings.io_ms;
+        }
+        exec_metrics.write_timings = Some(write_timings);
         for output in written {
             let (table, is_quarantine, schema_hash) = output_meta
                 .get(&output.name)
@@ -1813,12 +2915,14 @@ fn execute_job_inner(
                     rows,
                 });
             } else {
+                let content_hash = output_content_hash(&output.uri);
                 artifacts.push(ArtifactV1::Output {
                     output_name: output.name.clone(),
                     sink_uri: output.uri,
                     table,
                     rows,
                     schema_hash,
+                    content_hash,
                 });
             }
         }
@@ -1831,7 +2935,7 @@ fn execute_job_inner(
     Ok(ExecutionOutcome::Success {
         metrics: exec_metrics,
         artifacts,
-        source_hash,
+        source,
     })
 }
 
@@ -1841,27 +2945,44 @@ struct OwnedOutput {
     batches: Vec<casparian_sinks::OutputBatch>,
     sink_uri: String,
     sink_mode: SinkMode,
+    schema_evolution: SchemaEvolution,
     is_quarantine: bool,
     schema_hash: Option<String>,
 }
 
-fn to_output_plans(outputs: &[OwnedOutput]) -> Vec<casparian_sinks::OutputPlan> {
+fn to_output_plans(
+    outputs: &[OwnedOutput],
+    checkpoint_key: Option<&str>,
+    encode_threads: usize,
+) -> Vec<casparian_sinks::OutputPlan> {
     outputs
         .iter()
         .map(|output| {
-            casparian_sinks::OutputPlan::new(
+            let plan = casparian_sinks::OutputPlan::new(
                 output.name.clone(),
                 output.table.clone(),
                 output.batches.clone(),
                 output.sink_mode,
             )
+            .with_schema_evolution(output.schema_evolution)
+            .with_encode_parallelism(encode_threads);
+            match checkpoint_key {
+                Some(key) => plan.with_checkpoint(key),
+                None => plan,
+            }
         })
         .collect()
 }
 
+/// Write outputs grouped by sink URI. With a checkpoint key, Parquet outputs
+/// resume from the part files of an earlier attempt at the same
+/// materialization. Parquet/CSV sinks encode on up to `encode_threads`
+/// threads.
 fn write_outputs_grouped(
     outputs: Vec<OwnedOutput>,
     job_id: &str,
+    checkpoint_key: Option<&str>,
+    encode_threads: usize,
     cancel_token: &CancellationToken,
 ) -> WorkerResult<Vec<casparian_sinks::OutputArtifact>> {
     let mut grouped: HashMap<String, Vec<OwnedOutput>> = HashMap::new();
@@ -1879,7 +3000,7 @@ fn write_outputs_grouped(
     for sink_uri in sink_uris {
         let mut group = grouped.remove(&sink_uri).unwrap_or_default();
         group.sort_by(|a, b| a.name.cmp(&b.name));
-        let plans = to_output_plans(&group);
+        let plans = to_output_plans(&group, checkpoint_key, encode_threads);
         let should_commit = || !cancel_token.is_cancelled();
         let written =
             casparian_sinks::write_output_plan(&sink_uri, &plans, job_id, Some(&should_commit))
@@ -2211,6 +3332,7 @@ fn source_row_from_row_id(
 fn violation_type_for_message(message: Option<&str>) -> &'static str {
     match message.map(str::trim) {
         Some(msg) if msg.is_empty() => "unknown",
+        Some(msg) if msg.starts_with("constraint:") => "constraint",
         Some(msg) if msg.starts_with("schema:") => {
             if msg.contains("null not allowed") {
                 "null_not_allowed"
@@ -2265,15 +3387,56 @@ fn build_quarantine_masks(error_col: &ArrayRef) -> Result<(BooleanArray, Boolean
     ))
 }
 
-/// Send a protocol message as multipart (header + body in one ZMQ message)
+/// ZMQ takes reconnect intervals as i32 milliseconds.
+fn reconnect_ms(ms: u64) -> i32 {
+    ms.min(i32::MAX as u64) as i32
+}
+
+/// Make `socket` a CURVE client of the Sentinel. Must run before connect.
+fn configure_curve_client(socket: &zmq::Socket, curve: &WorkerCurveConfig) -> Result<()> {
+    let decode = |what: &str, key: &str| {
+        zmq::z85_decode(key).map_err(|err| anyhow::anyhow!("Invalid {}: {}", what, err))
+    };
+    socket
+        .set_curve_serverkey(&decode("sentinel public key", &curve.server_public_key)?)
+        .map_err(|err| anyhow::anyhow!("Failed to set CURVE server key: {}", err))?;
+    socket
+        .set_curve_publickey(&decode("worker public key", &curve.keypair.public_key)?)
+        .map_err(|err| anyhow::anyhow!("Failed to set CURVE public key: {}", err))?;
+    socket
+        .set_curve_secretkey(&decode("worker secret key", &curve.keypair.secret_key)?)
+        .map_err(|err| anyhow::anyhow!("Failed to set CURVE secret key: {}", err))?;
+    info!("CURVE encryption enabled for sentinel connection");
+    Ok(())
+}
+
+/// Build the IDENTIFY payload, advertising the protocol range this build speaks.
+fn identify_payload(config: &WorkerConfig) -> types::IdentifyPayload {
+    let capabilities = if config.capabilities.is_empty() {
+        vec!["*".to_string()] // Default to wildcard
+    } else {
+        config.capabilities.clone()
+    };
+    types::IdentifyPayload {
+        capabilities,
+        worker_id: Some(config.worker_id.clone()),
+        protocol_versions: ProtocolVersionRange::SUPPORTED,
+        protocol_features: ProtocolFeatures::SUPPORTED,
+    }
+}
+
+/// Send a protocol message as multipart (header + body in one ZMQ message),
+/// translated to the version negotiated with the Sentinel
 fn send_message<T: serde::Serialize>(
     socket: &Socket,
+    protocol: &NegotiatedProtocol,
     opcode: OpCode,
     job_id: JobId,
     payload: &T,
 ) -> Result<()> {
     let payload_bytes = serde_json::to_vec(payload)?;
     let msg = Message::new(opcode, job_id, payload_bytes)
+        .and_then(|msg| version::downgrade(msg, protocol))
         .map_err(|e| anyhow::anyhow!("Failed to create message: {}", e))?;
     let (header, body) = msg
         .pack()
@@ -2312,19 +3475,80 @@ mod tests {
             env_hash: Some("env_hash_test".to_string()),
             source_code: Some("print('ok')".to_string()),
             artifact_hash: "artifact_hash_test".to_string(),
+            lockfile_content: None,
+            limits: ResourceLimits::default(),
+            batch: Vec::new(),
+            priority: types::JobPriority::default(),
+            byte_range: None,
+            tag: None,
+            checkpoint_key: None,
+            csv_dialect: None,
+            input_encoding: None,
+            cached_source_hash: None,
+            input_lineage: None,
         }
     }
 
+    #[test]
+    fn test_job_lineage_extends_upstream_chain() {
+        let mut cmd = make_dispatch_command(Vec::new());
+        let raw = job_lineage(JobId::new(1), &cmd, "hash_raw");
+        assert_eq!(raw.hops.len(), 1);
+        assert_eq!(raw.hops[0].file_type, LineageFileType::Original);
+
+        cmd.file_path = "/out/stage1.parquet".to_string();
+        cmd.parser_version = Some("v2".to_string());
+        cmd.input_lineage = Some(raw.clone());
+        let chained = job_lineage(JobId::new(2), &cmd, "hash_stage1");
+        assert_eq!(chained.hops.len(), 2);
+        assert_eq!(chained.hops[0].file_type, LineageFileType::Artifact);
+        assert_eq!(chained.hops[0].job_id, Some(JobId::new(2)));
+        assert_eq!(chained.hops[0].parser_version.as_deref(), Some("v2"));
+        assert_eq!(chained.source(), raw.source());
+    }
+
+    #[test]
+    fn test_resolve_source_hash_reuses_matching_cache_entry() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("input.csv");
+        std::fs::write(&path, "a,b\n1,2\n").unwrap();
+        let mut cmd = make_dispatch_command(Vec::new());
+        cmd.file_path = path.to_string_lossy().to_string();
+
+        let hashed = resolve_source_hash(&cmd).unwrap();
+        assert_eq!(
+            hashed.hash,
+            blake3::hash(b"a,b\n1,2\n").to_hex().to_string()
+        );
+        assert_eq!(hashed.file.fingerprint.size, 8);
+
+        // A cache entry for the current generation is trusted as-is
+        cmd.cached_source_hash = Some(types::SourceHash {
+            hash: "cached".to_string(),
+            fingerprint: hashed.file.fingerprint,
+        });
+        assert_eq!(resolve_source_hash(&cmd).unwrap().hash, "cached");
+
+        // A stale entry is ignored
+        cmd.cached_source_hash = Some(types::SourceHash {
+            hash: "stale".to_string(),
+            fingerprint: SourceFingerprint {
+                size: 3,
+                mtime_ms: hashed.file.fingerprint.mtime_ms,
+            },
+        });
+        assert_eq!(resolve_source_hash(&cmd).unwrap().hash, hashed.hash);
+    }
+
     #[test]
     fn test_worker_config() {
-        let config = WorkerConfig {
-            sentinel_addr: "tcp://localhost:5555".to_string(),
-            parquet_root: PathBuf::from("/tmp/output"),
-            worker_id: "test-worker".to_string(),
-            shim_path: PathBuf::from("bridge_shim.py"),
-            capabilities: vec!["plugin_a".to_string(), "plugin_b".to_string()],
-            venvs_dir: None, // Use default
-        };
+        let config = WorkerConfig::new(
+            "tcp://localhost:5555",
+            "/tmp/output",
+            "test-worker",
+            "bridge_shim.py",
+        )
+        .with_capabilities(vec!["plugin_a".to_string(), "plugin_b".to_string()]);
 
         assert_eq!(config.sentinel_addr, "tcp://localhost:5555");
         assert_eq!(config.worker_id, "test-worker");
@@ -2333,28 +3557,27 @@ mod tests {
 
     #[test]
     fn test_worker_config_default_capabilities() {
-        let config = WorkerConfig {
-            sentinel_addr: "tcp://localhost:5555".to_string(),
-            parquet_root: PathBuf::from("/tmp/output"),
-            worker_id: "test-worker".to_string(),
-            shim_path: PathBuf::from("bridge_shim.py"),
-            capabilities: vec![], // Empty means wildcard "*"
-            venvs_dir: None,
-        };
+        // Empty capabilities mean wildcard "*"
+        let config = WorkerConfig::new(
+            "tcp://localhost:5555",
+            "/tmp/output",
+            "test-worker",
+            "bridge_shim.py",
+        );
 
         assert!(config.capabilities.is_empty());
     }
 
     #[test]
     fn test_worker_config_custom_venvs_dir() {
-        let config = WorkerConfig {
-            sentinel_addr: "tcp://localhost:5555".to_string(),
-            parquet_root: PathBuf::from("/tmp/output"),
-            worker_id: "test-worker".to_string(),
-            shim_path: PathBuf::from("bridge_shim.py"),
-            capabilities: vec!["*".to_string()],
-            venvs_dir: Some(PathBuf::from("/tmp/custom_venvs")),
-        };
+        let config = WorkerConfig::new(
+            "tcp://localhost:5555",
+            "/tmp/output",
+            "test-worker",
+            "bridge_shim.py",
+        )
+        .with_capabilities(vec!["*".to_string()])
+        .with_venvs_dir("/tmp/custom_venvs");
 
         assert_eq!(config.venvs_dir, Some(PathBuf::from("/tmp/custom_venvs")));
     }
@@ -2426,6 +3649,129 @@ mod tests {
         assert!(!permanent.is_transient());
         assert!(transient.is_transient());
         assert!(!transient.is_permanent());
+        assert_eq!(permanent.error_class(), retry::ERROR_CLASS_PERMANENT);
+        assert_eq!(transient.error_class(), retry::ERROR_CLASS_TRANSIENT);
+    }
+
+    #[test]
+    fn test_error_category_by_stage() {
+        let permanent = |message: &str| WorkerError::Permanent {
+            message: message.to_string(),
+        };
+        let transient = |message: &str| WorkerError::Transient {
+            message: message.to_string(),
+        };
+
+        assert_eq!(
+            permanent("Unsigned Python plugin blocked by trust policy").category(JobStage::Setup),
+            ErrorCategory::TrustPolicy
+        );
+        assert_eq!(
+            permanent("parser_version is required for native plugins").category(JobStage::Setup),
+            ErrorCategory::InvalidConfig
+        );
+        assert_eq!(
+            permanent("FileNotFoundError: [Errno 2] No such file or directory")
+                .category(JobStage::Execute),
+            ErrorCategory::IoError
+        );
+        assert_eq!(
+            permanent("ValueError: bad row").category(JobStage::Execute),
+            ErrorCategory::PluginException
+        );
+        assert_eq!(
+            transient("Parser timed out after 300s").category(JobStage::Execute),
+            ErrorCategory::Timeout
+        );
+        assert_eq!(
+            permanent("constraint check failed for 'events': bad regex")
+                .category(JobStage::Validate),
+            ErrorCategory::SchemaViolation
+        );
+        assert_eq!(
+            transient("DuckDB sink is locked by another writer: /tmp/out.duckdb")
+                .category(JobStage::Write),
+            ErrorCategory::SinkConflict
+        );
+        assert_eq!(
+            transient("Failed to upload part 3").category(JobStage::Write),
+            ErrorCategory::SinkError
+        );
+
+        let mismatch = WorkerError::PermanentWithDiagnostics {
+            message: "missing column".to_string(),
+            diagnostics: Box::new(types::JobDiagnostics {
+                schema_mismatch: Some(types::SchemaMismatch {
+                    output_name: "events".to_string(),
+                    expected_columns: Vec::new(),
+                    actual_columns: Vec::new(),
+                    missing_columns: vec!["id".to_string()],
+                    extra_columns: Vec::new(),
+                    order_mismatches: Vec::new(),
+                    type_mismatches: Vec::new(),
+                }),
+                ..Default::default()
+            }),
+        };
+        assert_eq!(
+            mismatch.category(JobStage::Validate),
+            ErrorCategory::SchemaViolation
+        );
+
+        let limit = |kind| WorkerError::PermanentWithDiagnostics {
+            message: "Plugin exceeded a resource limit; process terminated".to_string(),
+            diagnostics: Box::new(types::JobDiagnostics {
+                limit_exceeded: Some(kind),
+                ..Default::default()
+            }),
+        };
+        assert_eq!(
+            limit(LimitKind::WallClock).category(JobStage::Execute),
+            ErrorCategory::Timeout
+        );
+        assert_eq!(
+            limit(LimitKind::Memory).category(JobStage::Execute),
+            ErrorCategory::ResourceLimit
+        );
+    }
+
+    #[test]
+    fn test_limit_failure_classification() {
+        let limits = ResourceLimits {
+            timeout_secs: Some(5),
+            max_cpu_secs: Some(10),
+            max_memory_bytes: None,
+            max_decompressed_bytes: None,
+            max_scratch_bytes: None,
+        };
+        let token = CancellationToken::new();
+        let err = anyhow::anyhow!("Guest process exited with signal 9");
+        assert!(limit_failure(&err, &token, &limits).is_none());
+
+        let expired = token.with_deadline(Instant::now());
+        let (kind, message) = limit_failure(&err, &expired, &limits).unwrap();
+        assert_eq!(kind, LimitKind::WallClock);
+        assert!(message.contains("wall-clock timeout of 5s"));
+
+        let err = anyhow::Error::new(LimitExceeded::new(LimitKind::CpuTime, &limits))
+            .context("Bridge execution failed");
+        let (kind, _) = limit_failure(&err, &token, &limits).unwrap();
+        assert_eq!(kind, LimitKind::CpuTime);
+    }
+
+    #[test]
+    fn test_stage_timer_records_stages_in_order() {
+        let mut stages = StageTimer::new();
+        stages.enter(JobStage::Execute);
+        stages.enter(JobStage::Validate);
+        let (last, timings) = stages.finish();
+
+        assert_eq!(last, JobStage::Validate);
+        let order: Vec<JobStage> = timings.iter().map(|t| t.stage).collect();
+        assert_eq!(
+            order,
+            vec![JobStage::Setup, JobStage::Execute, JobStage::Validate]
+        );
     }
 
     #[test]
@@ -2541,6 +3887,7 @@ mod tests {
                 data_type: casparian_protocol::DataType::Int64,
                 nullable: false,
                 format: None,
+                constraints: Vec::new(),
             }],
         };
         let ids = Int64Array::from(vec![Some(1), None]);
@@ -2571,6 +3918,7 @@ mod tests {
             batches: valid_batches,
             sink_uri: "parquet://./output".to_string(),
             sink_mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             is_quarantine: false,
             schema_hash: None,
         });
@@ -2584,11 +3932,12 @@ mod tests {
             batches: quarantine_batches,
             sink_uri: "parquet://./output".to_string(),
             sink_mode: SinkMode::Append,
+            schema_evolution: SchemaEvolution::default(),
             is_quarantine: true,
             schema_hash: None,
         });
 
-        let plans = to_output_plans(&outputs);
+        let plans = to_output_plans(&outputs, None, 1);
         let dir = tempdir().unwrap();
         let sink_uri = format!("parquet://{}", dir.path().display());
         let artifacts =
@@ -2631,6 +3980,7 @@ mod tests {
                 batches: vec![casparian_sinks::OutputBatch::from_record_batch(batch_one)],
                 sink_uri: sink_one,
                 sink_mode: SinkMode::Append,
+                schema_evolution: SchemaEvolution::default(),
                 is_quarantine: false,
                 schema_hash: None,
             },
@@ -2640,13 +3990,14 @@ mod tests {
                 batches: vec![casparian_sinks::OutputBatch::from_record_batch(batch_two)],
                 sink_uri: sink_two,
                 sink_mode: SinkMode::Append,
+                schema_evolution: SchemaEvolution::default(),
                 is_quarantine: false,
                 schema_hash: None,
             },
         ];
 
         let token = CancellationToken::new();
-        let artifacts = write_outputs_grouped(outputs, "job-xyz", &token).unwrap();
+        let artifacts = write_outputs_grouped(outputs, "job-xyz", None, 2, &token).unwrap();
         assert_eq!(artifacts.len(), 2);
 
         let mut paths = HashMap::new();
@@ -2668,6 +4019,7 @@ mod tests {
                 topic: "alpha".to_string(),
                 uri: "parquet:///tmp/alpha".to_string(),
                 mode: types::SinkMode::Append,
+                schema_evolution: types::SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             },
@@ -2675,6 +4027,7 @@ mod tests {
                 topic: "beta".to_string(),
                 uri: "parquet:///tmp/beta".to_string(),
                 mode: types::SinkMode::Append,
+                schema_evolution: types::SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             },
@@ -2691,6 +4044,7 @@ mod tests {
                 topic: "alpha".to_string(),
                 uri: "parquet:///tmp/alpha".to_string(),
                 mode: types::SinkMode::Append,
+                schema_evolution: types::SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             },
@@ -2698,6 +4052,7 @@ mod tests {
                 topic: "*".to_string(),
                 uri: "parquet:///tmp/default".to_string(),
                 mode: types::SinkMode::Append,
+                schema_evolution: types::SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             },
@@ -2713,6 +4068,7 @@ mod tests {
             topic: "alpha".to_string(),
             uri: "parquet:///tmp/alpha".to_string(),
             mode: types::SinkMode::Append,
+            schema_evolution: types::SchemaEvolution::default(),
             quarantine_config: None,
             schema: None,
         }]);
@@ -2728,6 +4084,7 @@ mod tests {
                 topic: "alpha".to_string(),
                 uri: "parquet:///tmp/alpha".to_string(),
                 mode: types::SinkMode::Append,
+                schema_evolution: types::SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             },
@@ -2735,6 +4092,7 @@ mod tests {
                 topic: "beta".to_string(),
                 uri: "parquet:///tmp/beta".to_string(),
                 mode: types::SinkMode::Append,
+                schema_evolution: types::SchemaEvolution::default(),
                 quarantine_config: None,
                 schema: None,
             },
@@ -2742,6 +4100,14 @@ mod tests {
 
         let err = select_sink_config(&cmd, "gamma").unwrap_err();
         assert!(err.to_string().contains("no sink config"));
+
+        let plan = |name: &str| {
+            casparian_sinks::OutputPlan::new(name, None, Vec::new(), types::SinkMode::Append)
+        };
+        assert!(ensure_outputs_targeted(&cmd, &[plan("alpha"), plan("beta")]).is_ok());
+        let err = ensure_outputs_targeted(&cmd, &[plan("alpha"), plan("gamma"), plan("delta")])
+            .unwrap_err();
+        assert!(err.to_string().contains("[gamma, delta]"), "{}", err);
     }
 
     #[test]
@@ -2750,7 +4116,7 @@ mod tests {
             allow_quarantine: false,
             max_quarantine_pct: 10.0,
             max_quarantine_count: None,
-            quarantine_dir: None,
+            ..Default::default()
         };
         let reason = check_quarantine_policy("output", 1, 10, &config).unwrap();
         assert!(reason.contains("quarantine disabled"));
@@ -2803,7 +4169,7 @@ mod tests {
             allow_quarantine: true,
             max_quarantine_pct: 5.0,
             max_quarantine_count: None,
-            quarantine_dir: None,
+            ..Default::default()
         };
         let reason = check_quarantine_policy("output", 6, 100, &config).unwrap();
         assert!(reason.contains("pct exceeded"));
@@ -2815,7 +4181,7 @@ mod tests {
             allow_quarantine: true,
             max_quarantine_pct: 100.0,
             max_quarantine_count: Some(2),
-            quarantine_dir: None,
+            ..Default::default()
         };
         let reason = check_quarantine_policy("output", 3, 100, &config).unwrap();
         assert!(reason.contains("count exceeded"));
@@ -2827,6 +4193,13 @@ mod tests {
         assert!(!config.allow_quarantine);
         assert_eq!(config.max_quarantine_pct, 10.0);
         assert_eq!(config.max_quarantine_count, None);
+        assert!(!config.collects_column_stats());
+
+        let config = types::QuarantineConfig {
+            max_null_pct: Some(150.0),
+            ..Default::default()
+        };
+        assert!(resolve_quarantine_config(Some(&config)).is_err());
     }
 
     #[test]
@@ -2972,4 +4345,22 @@ mod tests {
         let err = result.unwrap_err();
         assert!(err.to_string().contains("Failed to resolve entrypoint"));
     }
+
+    #[test]
+    fn test_unrun_batch_receipts_keep_each_lease() {
+        let batch = vec![
+            (JobId::new(11), "lease-11".to_string()),
+            (JobId::new(12), "lease-12".to_string()),
+        ];
+        let receipts = unrun_batch_receipts(&batch, JobStatus::Rejected, "Worker at capacity");
+        assert_eq!(receipts.len(), 2);
+        for (result, (job_id, lease_token)) in receipts.iter().zip(&batch) {
+            assert_eq!(result.job_id, *job_id);
+            assert_eq!(result.receipt.status, JobStatus::Rejected);
+            assert_eq!(
+                result.receipt.lease_token.as_deref(),
+                Some(lease_token.as_str())
+            );
+        }
+    }
 }
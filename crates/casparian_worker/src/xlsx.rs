@@ -0,0 +1,676 @@
+//! Built-in Excel (.xlsx) reader for the native runtime.
+//!
+//! A parser whose entrypoint is `builtin:xlsx` is executed in-process instead
+//! of spawning a plugin binary. Options ride on the entrypoint query string:
+//!
+//! ```text
+//! builtin:xlsx?sheet=Trades&header_row=auto&output=trades
+//! ```
+//!
+//! | Key | Values | Default |
+//! |-----|--------|---------|
+//! | `sheet` | sheet name | first sheet |
+//! | `sheet_index` | 0-based sheet position | - |
+//! | `header_row` | `auto`, `none`, or a 1-based row number | `auto` |
+//! | `output` | output name | the parser's only output, else the sheet name |
+//!
+//! Column types come from the cells: uniformly numeric, boolean, or date
+//! columns map directly; text columns go through the constraint solver in
+//! `type_inference` so dates, decimals and numbers stored as text are still
+//! typed, and their observed column carries the solver's confidence and
+//! sample evidence. Anything ambiguous stays Utf8.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow::array::{
+    ArrayRef, BooleanArray, Date32Array, Decimal128Array, Float64Array, Int64Array, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow::datatypes::{Field, Schema};
+use arrow::record_batch::RecordBatch;
+use calamine::{open_workbook, Data, Reader, Xlsx};
+use casparian_protocol::types::{ColumnInference, ObservedColumn};
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::schema_validation::observed_data_type;
+use crate::type_inference::date_formats::parse_datetime;
+use crate::type_inference::streaming::solve_column;
+use crate::type_inference::{
+    DataType, DecimalShape, InferenceConfig, TypeInferenceResult, DATETIME_FORMATS,
+};
+
+/// Entrypoint that selects the built-in xlsx reader.
+pub const BUILTIN_XLSX_ENTRYPOINT: &str = "builtin:xlsx";
+
+/// Rows per emitted RecordBatch.
+pub const DEFAULT_XLSX_BATCH_ROWS: usize = 8192;
+
+/// Rows scanned when auto-detecting the header.
+const HEADER_SCAN_ROWS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SheetSelector {
+    #[default]
+    First,
+    Name(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeaderRow {
+    /// First row (within the first few) that looks like column labels.
+    #[default]
+    Auto,
+    /// Fixed 0-based row index.
+    Row(usize),
+    /// No header; columns are named `column_1`, `column_2`, ...
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XlsxOptions {
+    pub sheet: SheetSelector,
+    pub header_row: HeaderRow,
+    pub output: Option<String>,
+    pub batch_rows: usize,
+}
+
+impl Default for XlsxOptions {
+    fn default() -> Self {
+        Self {
+            sheet: SheetSelector::default(),
+            header_row: HeaderRow::default(),
+            output: None,
+            batch_rows: DEFAULT_XLSX_BATCH_ROWS,
+        }
+    }
+}
+
+impl XlsxOptions {
+    /// Parse options from a `builtin:xlsx[?...]` entrypoint.
+    /// Returns None for any other entrypoint.
+    pub fn from_entrypoint(entrypoint: &str) -> Result<Option<Self>> {
+        let entrypoint = entrypoint.trim();
+        let Some(rest) = entrypoint.strip_prefix(BUILTIN_XLSX_ENTRYPOINT) else {
+            return Ok(None);
+        };
+        let query = match rest {
+            "" => "",
+            _ => match rest.strip_prefix('?') {
+                Some(query) => query,
+                None => return Ok(None),
+            },
+        };
+
+        let mut options = Self::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .with_context(|| format!("Invalid xlsx option '{}'", pair))?;
+            match key {
+                "sheet" => options.sheet = SheetSelector::Name(value.to_string()),
+                "sheet_index" => {
+                    let index = value
+                        .parse()
+                        .with_context(|| format!("Invalid sheet_index '{}'", value))?;
+                    options.sheet = SheetSelector::Index(index);
+                }
+                "header_row" => {
+                    options.header_row = match value {
+                        "auto" => HeaderRow::Auto,
+                        "none" => HeaderRow::None,
+                        row => {
+                            let row: usize = row
+                                .parse()
+                                .with_context(|| format!("Invalid header_row '{}'", row))?;
+                            let index = row
+                                .checked_sub(1)
+                                .context("header_row is 1-based; use 'none' for no header")?;
+                            HeaderRow::Row(index)
+                        }
+                    }
+                }
+                "output" => options.output = Some(value.to_string()),
+                other => anyhow::bail!("Unknown xlsx option '{}'", other),
+            }
+        }
+        Ok(Some(options))
+    }
+}
+
+/// One sheet decoded to Arrow.
+#[derive(Debug)]
+pub struct XlsxTable {
+    pub sheet_name: String,
+    /// 0-based header row, if one was used.
+    pub header_row: Option<usize>,
+    pub batches: Vec<RecordBatch>,
+    pub observed_columns: Vec<ObservedColumn>,
+}
+
+/// Read the selected sheet of an xlsx workbook.
+pub fn read_xlsx(path: &Path, options: &XlsxOptions) -> Result<XlsxTable> {
+    let mut workbook: Xlsx<_> = open_workbook(path)
+        .with_context(|| format!("Failed to open xlsx workbook {}", path.display()))?;
+    let sheet_name = select_sheet(&workbook.sheet_names(), &options.sheet)?;
+    let range = workbook
+        .worksheet_range(&sheet_name)
+        .with_context(|| format!("Failed to read sheet '{}'", sheet_name))?;
+    let rows: Vec<Vec<Data>> = range.rows().map(|row| row.to_vec()).collect();
+    build_table(sheet_name, rows, options)
+}
+
+fn select_sheet(names: &[String], selector: &SheetSelector) -> Result<String> {
+    match selector {
+        SheetSelector::First => names.first().cloned().context("Workbook has no sheets"),
+        SheetSelector::Name(name) => names
+            .iter()
+            .find(|candidate| *candidate == name)
+            .cloned()
+            .with_context(|| format!("Sheet '{}' not found (have: {})", name, names.join(", "))),
+        SheetSelector::Index(index) => names.get(*index).cloned().with_context(|| {
+            format!(
+                "Sheet index {} out of range ({} sheets)",
+                index,
+                names.len()
+            )
+        }),
+    }
+}
+
+fn build_table(
+    sheet_name: String,
+    rows: Vec<Vec<Data>>,
+    options: &XlsxOptions,
+) -> Result<XlsxTable> {
+    let header_row = match options.header_row {
+        HeaderRow::Auto => detect_header_row(&rows),
+        HeaderRow::Row(index) => {
+            if index >= rows.len() {
+                anyhow::bail!(
+                    "header_row {} is past the end of sheet '{}' ({} rows)",
+                    index + 1,
+                    sheet_name,
+                    rows.len()
+                );
+            }
+            Some(index)
+        }
+        HeaderRow::None => None,
+    };
+
+    let data_start = header_row.map(|index| index + 1).unwrap_or(0);
+    let data: Vec<&[Data]> = rows[data_start.min(rows.len())..]
+        .iter()
+        .map(Vec::as_slice)
+        .filter(|row| !row.iter().all(is_empty_cell))
+        .collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let names = column_names(header_row.map(|index| rows[index].as_slice()), width);
+
+    let mut fields = Vec::with_capacity(width);
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(width);
+    let mut observed_columns = Vec::with_capacity(width);
+    for (column, name) in names.iter().enumerate() {
+        let cells: Vec<&Data> = data
+            .iter()
+            .map(|row| row.get(column).unwrap_or(&Data::Empty))
+            .collect();
+        let (array, inference) = build_column(name, &cells);
+        observed_columns.push(ObservedColumn {
+            name: name.clone(),
+            data_type: observed_data_type(array.data_type()),
+            inference,
+        });
+        fields.push(Field::new(name, array.data_type().clone(), true));
+        arrays.push(array);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema, arrays)
+        .with_context(|| format!("Failed to build RecordBatch for sheet '{}'", sheet_name))?;
+
+    let batch_rows = options.batch_rows.max(1);
+    let mut batches = Vec::new();
+    let mut offset = 0;
+    while offset < batch.num_rows() {
+        let len = batch_rows.min(batch.num_rows() - offset);
+        batches.push(batch.slice(offset, len));
+        offset += len;
+    }
+
+    Ok(XlsxTable {
+        sheet_name,
+        header_row,
+        batches,
+        observed_columns,
+    })
+}
+
+/// First row whose non-empty cells are all text and that is at least as wide
+/// as the next non-empty row. Falls back to the first non-empty row.
+fn detect_header_row(rows: &[Vec<Data>]) -> Option<usize> {
+    let width = |row: &[Data]| row.iter().filter(|cell| !is_empty_cell(cell)).count();
+    let first_non_empty = rows.iter().position(|row| width(row) > 0)?;
+    let candidate = rows
+        .iter()
+        .enumerate()
+        .skip(first_non_empty)
+        .take(HEADER_SCAN_ROWS)
+        .find(|(index, row)| {
+            let labels = width(row);
+            labels > 0
+                && row
+                    .iter()
+                    .filter(|cell| !is_empty_cell(cell))
+                    .all(|cell| matches!(cell, Data::String(_)))
+                && rows[index + 1..]
+                    .iter()
+                    .map(|next| width(next))
+                    .find(|next| *next > 0)
+                    .map(|next| labels >= next)
+                    .unwrap_or(true)
+        })
+        .map(|(index, _)| index);
+    Some(candidate.unwrap_or(first_non_empty))
+}
+
+fn column_names(header: Option<&[Data]>, width: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(width);
+    for column in 0..width {
+        let label = header
+            .and_then(|row| row.get(column))
+            .map(cell_text)
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .unwrap_or_else(|| format!("column_{}", column + 1));
+        let mut name = label.clone();
+        let mut suffix = 2;
+        while names.contains(&name) {
+            name = format!("{}_{}", label, suffix);
+            suffix += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+fn is_empty_cell(cell: &Data) -> bool {
+    match cell {
+        Data::Empty => true,
+        Data::String(text) => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(value) => match value.as_datetime() {
+            Some(datetime) => format_datetime(datetime),
+            None => value.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+fn format_datetime(datetime: NaiveDateTime) -> String {
+    if datetime.time() == chrono::NaiveTime::MIN {
+        datetime.date().format("%Y-%m-%d").to_string()
+    } else {
+        datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+/// Column type chosen for a sheet column.
+#[derive(Debug, Clone, PartialEq)]
+enum ColumnKind {
+    Int64,
+    Decimal(DecimalShape),
+    Float64,
+    Boolean,
+    Date(Option<String>),
+    Timestamp,
+    Utf8,
+}
+
+/// Column kind, plus the solver's evidence when text cells had to be
+/// inferred.
+fn classify_column(name: &str, cells: &[&Data]) -> (ColumnKind, Option<ColumnInference>) {
+    let values: Vec<&Data> = cells
+        .iter()
+        .copied()
+        .filter(|c| !is_empty_cell(c))
+        .collect();
+    if values.is_empty() {
+        return (ColumnKind::Utf8, None);
+    }
+    if values
+        .iter()
+        .all(|c| matches!(c, Data::Int(_) | Data::Float(_)))
+    {
+        let whole = values.iter().all(|c| match c {
+            Data::Float(value) => value.fract() == 0.0 && value.abs() < 9.0e15,
+            _ => true,
+        });
+        let kind = if whole {
+            ColumnKind::Int64
+        } else {
+            ColumnKind::Float64
+        };
+        return (kind, None);
+    }
+    if values.iter().all(|c| matches!(c, Data::Bool(_))) {
+        return (ColumnKind::Boolean, None);
+    }
+    if values.iter().all(|c| matches!(c, Data::DateTime(_))) {
+        let dates_only = values.iter().all(|c| match c {
+            Data::DateTime(value) => value
+                .as_datetime()
+                .is_some_and(|dt| dt.time() == chrono::NaiveTime::MIN),
+            _ => false,
+        });
+        let kind = if dates_only {
+            ColumnKind::Date(None)
+        } else {
+            ColumnKind::Timestamp
+        };
+        return (kind, None);
+    }
+
+    // Text or mixed cells: fall back to the constraint solver.
+    let texts: Vec<String> = values.iter().map(|c| cell_text(c)).collect();
+    let solver = solve_column(
+        name,
+        texts.iter().map(String::as_str),
+        InferenceConfig::default(),
+    );
+    let evidence = solver.evidence();
+    let kind = match solver.get_result() {
+        TypeInferenceResult::Resolved {
+            data_type, format, ..
+        } => match data_type {
+            DataType::Integer => ColumnKind::Int64,
+            DataType::Decimal => solver
+                .decimal_shape()
+                .map_or(ColumnKind::Float64, ColumnKind::Decimal),
+            DataType::Float => ColumnKind::Float64,
+            DataType::Boolean => ColumnKind::Boolean,
+            DataType::Date => ColumnKind::Date(format),
+            DataType::DateTime => ColumnKind::Timestamp,
+            _ => ColumnKind::Utf8,
+        },
+        _ => ColumnKind::Utf8,
+    };
+    (kind, Some(evidence.to_protocol()))
+}
+
+fn build_column(name: &str, cells: &[&Data]) -> (ArrayRef, Option<ColumnInference>) {
+    let (kind, inference) = classify_column(name, cells);
+    // A value the chosen type cannot hold demotes the column to Utf8.
+    let array = try_build_typed(&kind, cells).unwrap_or_else(|| build_utf8(cells));
+    (array, inference)
+}
+
+fn try_build_typed(kind: &ColumnKind, cells: &[&Data]) -> Option<ArrayRef> {
+    let array: ArrayRef = match kind {
+        ColumnKind::Utf8 => return None,
+        ColumnKind::Int64 => Arc::new(Int64Array::from(convert_cells(cells, cell_to_i64)?)),
+        ColumnKind::Decimal(shape) => Arc::new(
+            Decimal128Array::from(convert_cells(cells, |cell| {
+                cell_to_decimal(cell, shape.scale)
+            })?)
+            .with_precision_and_scale(shape.precision, i8::try_from(shape.scale).ok()?)
+            .ok()?,
+        ),
+        ColumnKind::Float64 => Arc::new(Float64Array::from(convert_cells(cells, cell_to_f64)?)),
+        ColumnKind::Boolean => Arc::new(BooleanArray::from(convert_cells(cells, cell_to_bool)?)),
+        ColumnKind::Date(format) => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+            Arc::new(Date32Array::from(convert_cells(cells, |cell| {
+                let date = cell_to_date(cell, format.as_deref())?;
+                i32::try_from((date - epoch).num_days()).ok()
+            })?))
+        }
+        ColumnKind::Timestamp => Arc::new(TimestampMillisecondArray::from(convert_cells(
+            cells,
+            |cell| cell_to_datetime(cell).map(|dt| dt.and_utc().timestamp_millis()),
+        )?)),
+    };
+    Some(array)
+}
+
+/// Convert every cell, keeping empty cells as nulls. `None` if a non-empty
+/// cell does not convert.
+fn convert_cells<T>(
+    cells: &[&Data],
+    convert: impl Fn(&Data) -> Option<T>,
+) -> Option<Vec<Option<T>>> {
+    cells
+        .iter()
+        .map(|cell| {
+            if is_empty_cell(cell) {
+                Some(None)
+            } else {
+                convert(cell).map(Some)
+            }
+        })
+        .collect()
+}
+
+fn build_utf8(cells: &[&Data]) -> ArrayRef {
+    Arc::new(StringArray::from(
+        cells
+            .iter()
+            .map(|cell| (!is_empty_cell(cell)).then(|| cell_text(cell)))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+fn cell_to_i64(cell: &Data) -> Option<i64> {
+    match cell {
+        Data::Int(value) => Some(*value),
+        Data::Float(value) if value.fract() == 0.0 && value.abs() < 9.0e15 => {
+            format!("{:.0}", value).parse().ok()
+        }
+        Data::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn cell_to_f64(cell: &Data) -> Option<f64> {
+    match cell {
+        Data::Float(value) => Some(*value),
+        Data::Int(value) => value.to_string().parse().ok(),
+        Data::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Unscaled Decimal128 value of a cell, e.g. "12.5" at scale 2 is 1250.
+fn cell_to_decimal(cell: &Data, scale: u8) -> Option<i128> {
+    let text = match cell {
+        Data::String(text) => text.trim().to_string(),
+        Data::Int(value) => value.to_string(),
+        Data::Float(value) => value.to_string(),
+        _ => return None,
+    };
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(&text)),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let padding = usize::from(scale).checked_sub(fraction.len())?;
+    let digits = format!("{}{}{}", whole, fraction, "0".repeat(padding));
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let value: i128 = digits.parse().ok()?;
+    Some(if negative { -value } else { value })
+}
+
+fn cell_to_bool(cell: &Data) -> Option<bool> {
+    match cell {
+        Data::Bool(value) => Some(*value),
+        Data::Int(1) => Some(true),
+        Data::Int(0) => Some(false),
+        Data::String(text) => match text.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" | "t" | "1" => Some(true),
+            "false" | "no" | "n" | "f" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn cell_to_date(cell: &Data, format: Option<&str>) -> Option<NaiveDate> {
+    match cell {
+        Data::DateTime(value) => value.as_datetime().map(|dt| dt.date()),
+        Data::DateTimeIso(text) => NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok(),
+        Data::String(text) => {
+            NaiveDate::parse_from_str(text.trim(), format.unwrap_or("%Y-%m-%d")).ok()
+        }
+        _ => None,
+    }
+}
+
+fn cell_to_datetime(cell: &Data) -> Option<NaiveDateTime> {
+    match cell {
+        Data::DateTime(value) => value.as_datetime(),
+        Data::DateTimeIso(text) | Data::String(text) => DATETIME_FORMATS
+            .iter()
+            .find_map(|format| parse_datetime(text, format)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Array;
+    use arrow::datatypes::DataType as ArrowDataType;
+
+    fn text(value: &str) -> Data {
+        Data::String(value.to_string())
+    }
+
+    #[test]
+    fn test_entrypoint_options() {
+        assert_eq!(XlsxOptions::from_entrypoint("./my_parser").unwrap(), None);
+        assert_eq!(
+            XlsxOptions::from_entrypoint("builtin:xlsx").unwrap(),
+            Some(XlsxOptions::default())
+        );
+        let options =
+            XlsxOptions::from_entrypoint("builtin:xlsx?sheet=Trades&header_row=3&output=trades")
+                .unwrap()
+                .unwrap();
+        assert_eq!(options.sheet, SheetSelector::Name("Trades".to_string()));
+        assert_eq!(options.header_row, HeaderRow::Row(2));
+        assert_eq!(options.output.as_deref(), Some("trades"));
+        assert!(XlsxOptions::from_entrypoint("builtin:xlsx?header_row=0").is_err());
+        assert!(XlsxOptions::from_entrypoint("builtin:xlsx?bogus=1").is_err());
+    }
+
+    #[test]
+    fn test_header_detection_skips_title_rows() {
+        let rows = vec![
+            vec![text("Quarterly export"), Data::Empty, Data::Empty],
+            vec![Data::Empty, Data::Empty, Data::Empty],
+            vec![text("id"), text("amount"), text("traded_on")],
+            vec![Data::Float(1.0), Data::Float(9.5), text("31/05/2024")],
+            vec![Data::Float(2.0), Data::Float(3.0), text("01/06/2024")],
+        ];
+        let table = build_table("Sheet1".to_string(), rows, &XlsxOptions::default()).unwrap();
+        assert_eq!(table.header_row, Some(2));
+
+        let batch = &table.batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name(), "id");
+        assert_eq!(schema.field(0).data_type(), &ArrowDataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &ArrowDataType::Float64);
+        assert_eq!(schema.field(2).data_type(), &ArrowDataType::Date32);
+        assert_eq!(table.observed_columns.len(), 3);
+    }
+
+    #[test]
+    fn test_mixed_column_falls_back_to_utf8() {
+        let rows = vec![
+            vec![text("code"), text("note")],
+            vec![Data::Float(1.0), text("a")],
+            vec![text("X-2"), Data::Empty],
+        ];
+        let options = XlsxOptions {
+            batch_rows: 1,
+            ..XlsxOptions::default()
+        };
+        let table = build_table("Sheet1".to_string(), rows, &options).unwrap();
+        assert_eq!(table.batches.len(), 2);
+        let schema = table.batches[0].schema();
+        assert_eq!(schema.field(0).data_type(), &ArrowDataType::Utf8);
+        assert!(table.batches[1].column(1).is_null(0));
+    }
+
+    #[test]
+    fn test_text_decimals_carry_inference_evidence() {
+        let rows = vec![
+            vec![text("price"), text("active")],
+            vec![text("19.99"), text("1")],
+            vec![text("-1250.5"), text("0")],
+        ];
+        let table = build_table("Sheet1".to_string(), rows, &XlsxOptions::default()).unwrap();
+        let batch = &table.batches[0];
+        let schema = batch.schema();
+        assert_eq!(
+            schema.field(0).data_type(),
+            &ArrowDataType::Decimal128(6, 2)
+        );
+        let prices = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        assert_eq!(prices.value(0), 1999);
+        assert_eq!(prices.value(1), -125050);
+        assert_eq!(schema.field(1).data_type(), &ArrowDataType::Int64);
+
+        let price = table.observed_columns[0].inference.as_ref().unwrap();
+        assert_eq!(price.confidence, 1.0);
+        assert_eq!(price.candidates[0].samples, vec!["19.99", "-1250.5"]);
+        // A 0/1 column could also be boolean
+        let active = table.observed_columns[1].inference.as_ref().unwrap();
+        assert_eq!(active.confidence, 0.5);
+    }
+
+    #[test]
+    fn test_no_header_and_duplicate_names() {
+        let rows = vec![vec![Data::Float(1.0), Data::Float(2.0)]];
+        let options = XlsxOptions {
+            header_row: HeaderRow::None,
+            ..XlsxOptions::default()
+        };
+        let table = build_table("Sheet1".to_string(), rows, &options).unwrap();
+        let schema = table.batches[0].schema();
+        assert_eq!(schema.field(0).name(), "column_1");
+        assert_eq!(
+            column_names(Some(&[text("a"), text("a"), Data::Empty]), 3),
+            vec!["a", "a_2", "column_3"]
+        );
+    }
+
+    #[test]
+    fn test_select_sheet() {
+        let names = vec!["Summary".to_string(), "Data".to_string()];
+        assert_eq!(
+            select_sheet(&names, &SheetSelector::First).unwrap(),
+            "Summary"
+        );
+        assert_eq!(
+            select_sheet(&names, &SheetSelector::Index(1)).unwrap(),
+            "Data"
+        );
+        assert!(select_sheet(&names, &SheetSelector::Name("Missing".to_string())).is_err());
+    }
+}
@@ -41,6 +41,7 @@ CREATE TABLE IF NOT EXISTS cf_processing_queue (
     claim_time TEXT,
     end_time TEXT,
     result_summary TEXT,
+    result_summary_json TEXT,
     error_message TEXT,
     retry_count INTEGER DEFAULT 0,
     quarantine_rows INTEGER DEFAULT 0,
@@ -83,8 +84,8 @@ CREATE TABLE IF NOT EXISTS cf_plugin_subscriptions (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     plugin_name TEXT NOT NULL,
     topic_name TEXT NOT NULL,
-    is_active INTEGER DEFAULT 1,
-    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at INTEGER NOT NULL,
     UNIQUE(plugin_name, topic_name)
 );
 
@@ -162,9 +163,9 @@ VALUES
     ('data_validator', 'demo', '{"strict": true}');
 
 -- Plugin subscriptions (creates topic -> plugin edges in topology)
-INSERT INTO cf_plugin_subscriptions (plugin_name, topic_name, is_active)
+INSERT INTO cf_plugin_subscriptions (plugin_name, topic_name, is_active, created_at)
 VALUES
-    ('data_validator', 'slow_processor:processed_output', 1);
+    ('data_validator', 'slow_processor:processed_output', true, 0);
 
 -- Pre-completed jobs with output files (for immediate Data tab testing)
 -- These get IDs 1-4 automatically
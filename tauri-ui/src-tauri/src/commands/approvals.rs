@@ -4,9 +4,12 @@
 //!
 //! Tape instrumentation (WS7-05):
 //! - Records approval decisions (approve/reject) with approval_id
+//!
+//! Decisions are also appended to the shared control-plane audit log.
 
 use crate::state::{AppState, CommandError, CommandResult};
 use casparian_protocol::ApprovalStatus as ProtocolApprovalStatus;
+use casparian_sentinel::db::audit;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -146,6 +149,7 @@ pub async fn approval_decide(
     decision: ApprovalDecision,
     state: State<'_, AppState>,
 ) -> CommandResult<ApprovalDecisionResponse> {
+    state.ensure_writable("Deciding approvals")?;
     // Record tape event
     let tape_ids = {
         let tape = state.tape().read().ok();
@@ -175,22 +179,34 @@ pub async fn approval_decide(
         CommandError::Database(e.to_string())
     })?;
 
+    let actor = std::env::var("USER").ok();
+    let before = storage.get_approval(&decision.approval_id).ok().flatten();
     let success = match decision.decision.as_str() {
-        "approve" => storage.approve(&decision.approval_id, None).map_err(|e| {
-            if let Some((event_id, correlation_id)) = &tape_ids {
-                if let Ok(tape) = state.tape().read() {
-                    tape.emit_error(
-                        correlation_id,
-                        event_id,
-                        &e.to_string(),
-                        serde_json::json!({"status": "failed"}),
-                    );
+        "approve" => storage
+            .approve(
+                &decision.approval_id,
+                actor.as_deref(),
+                decision.reason.as_deref(),
+            )
+            .map_err(|e| {
+                if let Some((event_id, correlation_id)) = &tape_ids {
+                    if let Ok(tape) = state.tape().read() {
+                        tape.emit_error(
+                            correlation_id,
+                            event_id,
+                            &e.to_string(),
+                            serde_json::json!({"status": "failed"}),
+                        );
+                    }
                 }
-            }
-            CommandError::Database(e.to_string())
-        })?,
+                CommandError::Database(e.to_string())
+            })?,
         "reject" => storage
-            .reject(&decision.approval_id, None, decision.reason.as_deref())
+            .reject(
+                &decision.approval_id,
+                actor.as_deref(),
+                decision.reason.as_deref(),
+            )
             .map_err(|e| {
                 if let Some((event_id, correlation_id)) = &tape_ids {
                     if let Ok(tape) = state.tape().read() {
@@ -227,6 +243,23 @@ pub async fn approval_decide(
         "unchanged".to_string()
     };
 
+    if success {
+        let after = storage.get_approval(&decision.approval_id).ok().flatten();
+        let action = if decision.decision == "approve" {
+            "approved"
+        } else {
+            "rejected"
+        };
+        state.audit_log().record(
+            audit::entity::APPROVAL,
+            &decision.approval_id,
+            action,
+            actor.as_deref(),
+            before.as_ref().and_then(audit::snapshot),
+            after.as_ref().and_then(audit::snapshot),
+        );
+    }
+
     // Record success
     if let Some((event_id, correlation_id)) = tape_ids {
         if let Ok(tape) = state.tape().read() {
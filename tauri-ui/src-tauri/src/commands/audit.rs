@@ -0,0 +1,93 @@
+//! Control-plane audit log commands.
+//!
+//! Reads the audit tape shared with the Sentinel and CLI: routing rule
+//! changes, plugin publishes and rollbacks, approval decisions and parser
+//! config updates, each with the actor and before/after snapshots.
+
+use crate::state::{AppState, CommandError, CommandResult};
+use casparian_sentinel::db::{AuditEntry, AuditQuery};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Default number of entries returned when no limit is given.
+const DEFAULT_AUDIT_LIMIT: usize = 200;
+
+/// Audit log filters. All fields are optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditListRequest {
+    pub entity_kind: Option<String>,
+    pub entity_id: Option<String>,
+    pub actor: Option<String>,
+    /// RFC 3339 lower bound (inclusive)
+    pub since: Option<String>,
+    /// RFC 3339 upper bound (exclusive)
+    pub until: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// One audit log entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditItem {
+    pub seq: u64,
+    pub timestamp: String,
+    pub entity_kind: String,
+    pub entity_id: String,
+    pub action: String,
+    pub actor: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl From<AuditEntry> for AuditItem {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            seq: entry.seq,
+            timestamp: entry.timestamp.to_rfc3339(),
+            entity_kind: entry.record.entity_kind,
+            entity_id: entry.record.entity_id,
+            action: entry.record.action,
+            actor: entry.record.actor,
+            before: entry.record.before,
+            after: entry.record.after,
+        }
+    }
+}
+
+/// List audit entries, most recent last.
+#[tauri::command]
+pub async fn audit_list(
+    request: AuditListRequest,
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<AuditItem>> {
+    let mut query = AuditQuery::new().limit(request.limit.unwrap_or(DEFAULT_AUDIT_LIMIT));
+    if let Some(kind) = request.entity_kind {
+        query = query.entity_kind(kind);
+    }
+    if let Some(id) = request.entity_id {
+        query = query.entity_id(id);
+    }
+    if let Some(actor) = request.actor {
+        query = query.actor(actor);
+    }
+    if let Some(since) = request.since.as_deref() {
+        query = query.since(parse_rfc3339(since)?);
+    }
+    if let Some(until) = request.until.as_deref() {
+        query = query.until(parse_rfc3339(until)?);
+    }
+
+    let entries = state
+        .audit_log()
+        .query(&query)
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    Ok(entries.into_iter().map(AuditItem::from).collect())
+}
+
+fn parse_rfc3339(raw: &str) -> CommandResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|e| CommandError::InvalidArgument(format!("Invalid date '{}': {}", raw, e)))
+}
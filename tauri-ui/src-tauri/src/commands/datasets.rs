@@ -0,0 +1,141 @@
+//! Dataset catalog commands.
+//!
+//! The sentinel registers every committed output in the dataset catalog on
+//! CONCLUDE. These commands list, search and describe those datasets
+//! (read-only) so the Deck can show which tables exist.
+
+use crate::state::{AppState, CommandError, CommandResult};
+use casparian_sentinel::{dataset_catalog, DatasetRecord};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Datasets returned when no limit is given.
+const DEFAULT_DATASET_LIMIT: usize = 200;
+
+/// Dataset row for the catalog list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetItem {
+    pub name: String,
+    pub plugin_name: String,
+    pub sink_uri: String,
+    pub table_name: Option<String>,
+    pub row_count: u64,
+    pub partition_columns: Vec<String>,
+    pub last_updated: String,
+}
+
+impl From<&DatasetRecord> for DatasetItem {
+    fn from(record: &DatasetRecord) -> Self {
+        let summary = record.summary();
+        Self {
+            name: summary.name,
+            plugin_name: summary.plugin_name,
+            sink_uri: summary.sink_uri,
+            table_name: summary.table_name,
+            row_count: summary.row_count,
+            partition_columns: summary.partition_columns,
+            last_updated: summary.last_updated,
+        }
+    }
+}
+
+/// Column of a dataset's contract schema.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetColumnItem {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+/// Full dataset description.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetDetailItem {
+    #[serde(flatten)]
+    pub dataset: DatasetItem,
+    pub sink_mode: String,
+    pub columns: Vec<DatasetColumnItem>,
+    pub schema_hash: Option<String>,
+    pub lineage_root: Option<String>,
+    pub job_count: u64,
+    pub last_job_id: String,
+    pub created_at: String,
+}
+
+impl From<&DatasetRecord> for DatasetDetailItem {
+    fn from(record: &DatasetRecord) -> Self {
+        let detail = record.detail();
+        Self {
+            dataset: DatasetItem::from(record),
+            sink_mode: detail.sink_mode.to_string(),
+            columns: detail
+                .columns
+                .into_iter()
+                .map(|column| DatasetColumnItem {
+                    name: column.name,
+                    data_type: column.data_type.to_string(),
+                    nullable: column.nullable,
+                })
+                .collect(),
+            schema_hash: detail.schema_hash,
+            lineage_root: detail.lineage_root,
+            job_count: detail.job_count,
+            last_job_id: detail.last_job_id.to_string(),
+            created_at: detail.created_at,
+        }
+    }
+}
+
+/// List datasets, most recently updated first. `query` matches dataset,
+/// plugin, table, sink and column names (case-insensitive).
+#[tauri::command]
+pub async fn dataset_list(
+    query: Option<String>,
+    plugin_name: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<DatasetItem>> {
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    if !conn
+        .table_exists("cf_datasets")
+        .map_err(|e| CommandError::Database(e.to_string()))?
+    {
+        return Ok(Vec::new());
+    }
+    let records = dataset_catalog::list(
+        &conn,
+        query.as_deref(),
+        plugin_name.as_deref(),
+        limit.unwrap_or(DEFAULT_DATASET_LIMIT),
+    )
+    .map_err(|e| CommandError::Database(e.to_string()))?;
+    Ok(records.iter().map(DatasetItem::from).collect())
+}
+
+/// Describe one dataset by name.
+#[tauri::command]
+pub async fn dataset_describe(
+    name: String,
+    state: State<'_, AppState>,
+) -> CommandResult<DatasetDetailItem> {
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let record = if conn
+        .table_exists("cf_datasets")
+        .map_err(|e| CommandError::Database(e.to_string()))?
+    {
+        dataset_catalog::describe(&conn, &name)
+            .map_err(|e| CommandError::Database(e.to_string()))?
+    } else {
+        None
+    };
+    record
+        .as_ref()
+        .map(DatasetDetailItem::from)
+        .ok_or_else(|| CommandError::NotFound(format!("Dataset {} not found", name)))
+}
@@ -0,0 +1,260 @@
+//! Dead-letter queue commands.
+//!
+//! Jobs that exhaust their retries (or fail permanently) are moved to
+//! `cf_dead_letter` by the sentinel. These commands let the Deck browse those
+//! entries, requeue them as fresh jobs, and purge them.
+//!
+//! Listing falls back to a read-only connection when the sentinel is down;
+//! requeue/purge mutate the queue and require the Control API.
+
+use crate::state::{AppState, CommandError, CommandResult};
+use casparian_sentinel::{DeadLetterInfo, DeadLetterJob, JobQueue};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Dead-letter entry for the list view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterItem {
+    pub id: i64,
+    pub original_job_id: String,
+    pub plugin_name: String,
+    pub input_file: Option<String>,
+    pub error_message: Option<String>,
+    pub reason: Option<String>,
+    pub retry_count: i32,
+    pub moved_at: String,
+    pub parser_version: Option<String>,
+    pub pipeline_run_id: Option<String>,
+    pub last_worker: Option<String>,
+    pub last_claim_time: Option<String>,
+}
+
+impl From<DeadLetterInfo> for DeadLetterItem {
+    fn from(entry: DeadLetterInfo) -> Self {
+        Self {
+            id: entry.id,
+            original_job_id: entry.original_job_id.as_u64().to_string(),
+            plugin_name: entry.plugin_name,
+            input_file: entry.input_file,
+            error_message: entry.error_message,
+            reason: entry.reason,
+            retry_count: entry.retry_count,
+            moved_at: millis_to_rfc3339(entry.moved_at),
+            parser_version: entry.parser_version,
+            pipeline_run_id: entry.pipeline_run_id,
+            last_worker: entry.last_worker,
+            last_claim_time: entry.last_claim_time.map(millis_to_rfc3339),
+        }
+    }
+}
+
+impl From<DeadLetterJob> for DeadLetterItem {
+    fn from(entry: DeadLetterJob) -> Self {
+        Self {
+            id: entry.id,
+            original_job_id: entry.original_job_id.to_string(),
+            plugin_name: entry.plugin_name,
+            input_file: entry.input_file,
+            error_message: entry.error_message,
+            reason: entry.reason,
+            retry_count: entry.retry_count,
+            moved_at: millis_to_rfc3339(entry.moved_at),
+            parser_version: entry.parser_version,
+            pipeline_run_id: entry.pipeline_run_id,
+            last_worker: entry.last_worker,
+            last_claim_time: entry.last_claim_time.map(millis_to_rfc3339),
+        }
+    }
+}
+
+/// Dead-letter requeue response.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterRequeueResponse {
+    pub success: bool,
+    pub message: String,
+    pub new_job_id: Option<String>,
+}
+
+/// Dead-letter purge response.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterPurgeResponse {
+    pub purged: u64,
+}
+
+/// List dead-letter entries, most recent first.
+#[tauri::command]
+pub async fn dlq_list(
+    plugin_name: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<DeadLetterItem>> {
+    let limit = i64::try_from(limit.unwrap_or(100)).unwrap_or(i64::MAX);
+
+    if let Some(client) = state.try_control_client() {
+        let entries = client
+            .list_dead_letters(plugin_name.as_deref(), Some(limit))
+            .map_err(CommandError::control)?;
+        return Ok(entries.into_iter().map(DeadLetterItem::from).collect());
+    }
+
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let queue = JobQueue::new(conn);
+    let entries = match plugin_name.as_deref() {
+        Some(plugin) => queue.get_dead_letter_jobs_by_plugin(plugin, limit),
+        None => queue.get_dead_letter_jobs(limit),
+    }
+    .map_err(|e| CommandError::Database(e.to_string()))?;
+    Ok(entries.into_iter().map(DeadLetterItem::from).collect())
+}
+
+/// Requeue a dead-letter entry as a fresh job.
+#[tauri::command]
+pub async fn dlq_requeue(
+    dead_letter_id: i64,
+    state: State<'_, AppState>,
+) -> CommandResult<DeadLetterRequeueResponse> {
+    state.ensure_writable("Requeueing dead letters")?;
+    let tape_ids = {
+        let tape = state.tape().read().ok();
+        tape.as_ref().and_then(|t| {
+            t.emit_command(
+                "DeadLetterRequeue",
+                serde_json::json!({ "dead_letter_id": dead_letter_id }),
+            )
+        })
+    };
+
+    let result = match state.try_control_client() {
+        Some(client) => client
+            .requeue_dead_letter(dead_letter_id)
+            .map_err(CommandError::control),
+        None => Err(CommandError::Internal(
+            "Sentinel must be running to requeue dead letters".to_string(),
+        )),
+    };
+
+    let (success, message, new_job_id) = match result {
+        Ok(result) => result,
+        Err(err) => {
+            if let Some((event_id, correlation_id)) = &tape_ids {
+                if let Ok(tape) = state.tape().read() {
+                    tape.emit_error(
+                        correlation_id,
+                        event_id,
+                        &err.to_string(),
+                        serde_json::json!({"status": "failed", "dead_letter_id": dead_letter_id}),
+                    );
+                }
+            }
+            return Err(err);
+        }
+    };
+    let new_job_id = new_job_id.map(|id| id.as_u64().to_string());
+
+    if let Some((event_id, correlation_id)) = tape_ids {
+        if let Ok(tape) = state.tape().read() {
+            tape.emit_success(
+                &correlation_id,
+                &event_id,
+                serde_json::json!({
+                    "status": "success",
+                    "dead_letter_id": dead_letter_id,
+                    "requeued": success,
+                    "new_job_id": new_job_id,
+                }),
+            );
+        }
+    }
+
+    Ok(DeadLetterRequeueResponse {
+        success,
+        message,
+        new_job_id,
+    })
+}
+
+/// Purge a single dead-letter entry, or every entry matching the filters.
+///
+/// `moved_before` is an RFC 3339 timestamp; entries moved earlier are purged.
+#[tauri::command]
+pub async fn dlq_purge(
+    dead_letter_id: Option<i64>,
+    moved_before: Option<String>,
+    plugin_name: Option<String>,
+    state: State<'_, AppState>,
+) -> CommandResult<DeadLetterPurgeResponse> {
+    state.ensure_writable("Purging dead letters")?;
+    let moved_before = moved_before
+        .map(|raw| {
+            DateTime::parse_from_rfc3339(&raw)
+                .map(|ts| ts.timestamp_millis())
+                .map_err(|e| {
+                    CommandError::InvalidArgument(format!("Invalid movedBefore '{}': {}", raw, e))
+                })
+        })
+        .transpose()?;
+
+    let tape_ids = {
+        let tape = state.tape().read().ok();
+        tape.as_ref().and_then(|t| {
+            t.emit_command(
+                "DeadLetterPurge",
+                serde_json::json!({
+                    "dead_letter_id": dead_letter_id,
+                    "moved_before": moved_before,
+                    "plugin_name": plugin_name,
+                }),
+            )
+        })
+    };
+
+    let result = match state.try_control_client() {
+        Some(client) => client
+            .purge_dead_letters(dead_letter_id, moved_before, plugin_name.as_deref())
+            .map_err(CommandError::control),
+        None => Err(CommandError::Internal(
+            "Sentinel must be running to purge dead letters".to_string(),
+        )),
+    };
+
+    let purged = match result {
+        Ok(purged) => purged,
+        Err(err) => {
+            if let Some((event_id, correlation_id)) = &tape_ids {
+                if let Ok(tape) = state.tape().read() {
+                    tape.emit_error(
+                        correlation_id,
+                        event_id,
+                        &err.to_string(),
+                        serde_json::json!({"status": "failed"}),
+                    );
+                }
+            }
+            return Err(err);
+        }
+    };
+
+    if let Some((event_id, correlation_id)) = tape_ids {
+        if let Ok(tape) = state.tape().read() {
+            tape.emit_success(
+                &correlation_id,
+                &event_id,
+                serde_json::json!({"status": "success", "purged": purged}),
+            );
+        }
+    }
+
+    Ok(DeadLetterPurgeResponse { purged })
+}
+
+fn millis_to_rfc3339(millis: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(millis)
+        .map(|ts| ts.to_rfc3339())
+        .unwrap_or_else(|| "-".to_string())
+}
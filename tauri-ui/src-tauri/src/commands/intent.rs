@@ -300,8 +300,9 @@ pub struct SelectApproveResponse {
 #[tauri::command]
 pub async fn casp_select_approve(
     request: SelectApproveRequest,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> CommandResult<SelectApproveResponse> {
+    state.ensure_writable("Approving selections")?;
     let session_id: SessionId = request.session_id.parse().map_err(|_| {
         CommandError::InvalidArgument(format!("Invalid session ID: {}", request.session_id))
     })?;
@@ -477,8 +478,9 @@ pub struct TagsApplyRulesResponse {
 #[tauri::command]
 pub async fn casp_tags_apply_rules(
     request: TagsApplyRulesRequest,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> CommandResult<TagsApplyRulesResponse> {
+    state.ensure_writable("Applying tagging rules")?;
     let session_id: SessionId = request.session_id.parse().map_err(|_| {
         CommandError::InvalidArgument(format!("Invalid session ID: {}", request.session_id))
     })?;
@@ -527,8 +529,9 @@ pub struct PathFieldsApplyResponse {
 #[tauri::command]
 pub async fn casp_path_fields_apply(
     request: PathFieldsApplyRequest,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> CommandResult<PathFieldsApplyResponse> {
+    state.ensure_writable("Applying path fields")?;
     let session_id: SessionId = request.session_id.parse().map_err(|_| {
         CommandError::InvalidArgument(format!("Invalid session ID: {}", request.session_id))
     })?;
@@ -582,8 +585,9 @@ pub struct SchemaPromoteResponse {
 #[tauri::command]
 pub async fn casp_schema_promote(
     request: SchemaPromoteRequest,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> CommandResult<SchemaPromoteResponse> {
+    state.ensure_writable("Promoting schemas")?;
     let session_id: SessionId = request.session_id.parse().map_err(|_| {
         CommandError::InvalidArgument(format!("Invalid session ID: {}", request.session_id))
     })?;
@@ -633,8 +637,9 @@ pub struct SchemaResolveAmbiguityResponse {
 #[tauri::command]
 pub async fn casp_schema_resolve_ambiguity(
     request: SchemaResolveAmbiguityRequest,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> CommandResult<SchemaResolveAmbiguityResponse> {
+    state.ensure_writable("Resolving schema ambiguities")?;
     let session_id: SessionId = request.session_id.parse().map_err(|_| {
         CommandError::InvalidArgument(format!("Invalid session ID: {}", request.session_id))
     })?;
@@ -851,8 +856,9 @@ pub struct PatchApplyResponse {
 #[tauri::command]
 pub async fn casp_patch_apply(
     request: PatchApplyRequest,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> CommandResult<PatchApplyResponse> {
+    state.ensure_writable("Applying patches")?;
     let session_id: SessionId = request.session_id.parse().map_err(|_| {
         CommandError::InvalidArgument(format!("Invalid session ID: {}", request.session_id))
     })?;
@@ -972,8 +978,9 @@ pub struct PublishExecuteResponse {
 #[tauri::command]
 pub async fn casp_publish_execute(
     request: PublishExecuteRequest,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> CommandResult<PublishExecuteResponse> {
+    state.ensure_writable("Publishing")?;
     let session_id: SessionId = request.session_id.parse().map_err(|_| {
         CommandError::InvalidArgument(format!("Invalid session ID: {}", request.session_id))
     })?;
@@ -1088,8 +1095,9 @@ pub struct RunExecuteResponse {
 #[tauri::command]
 pub async fn casp_run_execute(
     request: RunExecuteRequest,
-    _state: State<'_, AppState>,
+    state: State<'_, AppState>,
 ) -> CommandResult<RunExecuteResponse> {
+    state.ensure_writable("Running jobs")?;
     let session_id: SessionId = request.session_id.parse().map_err(|_| {
         CommandError::InvalidArgument(format!("Invalid session ID: {}", request.session_id))
     })?;
@@ -2,16 +2,38 @@
 //!
 //! These commands manage background jobs (backtest, run, etc.).
 //!
+//! Live logs: workers stream log output of running jobs to the Sentinel, which
+//! appends it to `cf_job_logs`. `job_log_follow` tails that table and emits a
+//! `job-log-chunk` event per chunk until the job finishes.
+//!
+//! Exports: `export_artifact` copies one output to a file of the user's
+//! choosing, verified against the job's receipt (see
+//! `casparian_sentinel::export`).
+//!
 //! Tape instrumentation (WS7-05):
 //! - Records job operations with job_id for correlation
 //! - Input directories are hashed for privacy
 
 use crate::state::{AppState, CommandError, CommandResult};
-use casparian_protocol::{JobId, ProcessingStatus};
-use casparian_sentinel::JobQueue;
+use casparian_db::DbConnection;
+use casparian_protocol::{JobId, JobResultSummary, PipelineRun, ProcessingStatus};
+use casparian_sentinel::db::StateStore;
+use casparian_sentinel::{
+    ExportFormat, ExportOptions, Job, JobHistoryQuery, JobHistorySort, JobLogChunk, JobQueue,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
 use tauri::State;
 
+/// Tauri event emitted for each new chunk of a followed job's log.
+pub const JOB_LOG_CHUNK_EVENT: &str = "job-log-chunk";
+/// How often `job_log_follow` polls for new chunks.
+const LOG_FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Maximum chunks returned by one read.
+const LOG_READ_LIMIT: i64 = 500;
+
 /// Job item for list view.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,6 +49,8 @@ pub struct JobItem {
     pub finished_at: Option<String>,
     pub error_message: Option<String>,
     pub progress: Option<JobProgress>,
+    /// Artifacts (kind, URI, rows), counters and warnings of a concluded job
+    pub result: Option<JobResultSummary>,
 }
 
 /// Job progress info.
@@ -39,28 +63,106 @@ pub struct JobProgress {
     pub message: Option<String>,
 }
 
+/// Request for a page of job history.
+///
+/// Dates are RFC 3339; `created_after` is inclusive and `created_before`
+/// exclusive.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobHistoryRequest {
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    pub plugin_name: Option<String>,
+    /// Only jobs of plugins deployed into this namespace
+    pub namespace: Option<String>,
+    pub tag: Option<String>,
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    /// created_at (default), finished_at, plugin_name, or status
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub ascending: bool,
+    pub cursor: Option<String>,
+    #[serde(default = "default_history_limit")]
+    pub limit: usize,
+}
+
+fn default_history_limit() -> usize {
+    100
+}
+
+/// One page of job history.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobHistoryResponse {
+    pub jobs: Vec<JobItem>,
+    /// Pass back as `cursor` to fetch the next page; null on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// A chunk of a job's log.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobLogChunkItem {
+    pub job_id: String,
+    /// Byte offset of the chunk in the worker's log file.
+    pub offset: i64,
+    pub data: String,
+}
+
+impl JobLogChunkItem {
+    fn new(job_id: i64, chunk: JobLogChunk) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            offset: chunk.offset,
+            data: chunk.chunk,
+        }
+    }
+}
+
 /// Job cancel response.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JobCancelResponse {
     pub success: bool,
     pub status: String,
+    pub message: String,
 }
 
 /// List all jobs.
+///
+/// With `namespace`, only jobs of plugins deployed into that namespace are
+/// listed; the filter reads the state store directly.
 #[tauri::command]
 pub async fn job_list(
     status: Option<String>,
+    namespace: Option<String>,
     limit: Option<usize>,
     state: State<'_, AppState>,
 ) -> CommandResult<Vec<JobItem>> {
     let status_filter = status.as_deref().and_then(|s| parse_processing_status(s));
     let limit = limit.unwrap_or(100);
 
+    if let Some(namespace) = namespace {
+        let query = JobHistoryQuery {
+            statuses: status_filter.into_iter().collect(),
+            namespace: Some(namespace),
+            limit,
+            ..Default::default()
+        };
+        let conn = state
+            .open_readonly_connection()
+            .map_err(|e| CommandError::Database(e.to_string()))?;
+        let page = JobQueue::new(conn)
+            .query_job_history(&query)
+            .map_err(|e| CommandError::Database(e.to_string()))?;
+        return Ok(page.jobs.into_iter().map(history_item).collect());
+    }
+
     let jobs = if let Some(client) = state.try_control_client() {
         client
             .list_jobs(status_filter, Some(limit as i64), Some(0))
-            .map_err(|e| CommandError::Internal(format!("Control API error: {}", e)))?
+            .map_err(CommandError::control)?
             .into_iter()
             .map(|job| JobItem {
                 id: job.id.as_u64().to_string(),
@@ -74,6 +176,7 @@ pub async fn job_list(
                 finished_at: None,
                 error_message: job.error_message,
                 progress: None,
+                result: job.result,
             })
             .collect()
     } else {
@@ -100,6 +203,7 @@ pub async fn job_list(
                 finished_at: None,
                 error_message: job.error_message,
                 progress: None,
+                result: job.result,
             })
             .collect()
     };
@@ -107,6 +211,65 @@ pub async fn job_list(
     Ok(jobs)
 }
 
+/// Page through job history with filters and server-side sort.
+///
+/// Reads the state store directly (read-only) so the Jobs screen can scroll
+/// through hundreds of thousands of rows one page at a time.
+#[tauri::command]
+pub async fn job_history(
+    request: JobHistoryRequest,
+    state: State<'_, AppState>,
+) -> CommandResult<JobHistoryResponse> {
+    let statuses = request
+        .statuses
+        .iter()
+        .map(|raw| {
+            parse_processing_status(raw)
+                .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown status '{}'", raw)))
+        })
+        .collect::<CommandResult<Vec<_>>>()?;
+    let sort = match request.sort.as_deref() {
+        Some(raw) => raw
+            .parse::<JobHistorySort>()
+            .map_err(CommandError::InvalidArgument)?,
+        None => JobHistorySort::default(),
+    };
+    let created_after = request
+        .created_after
+        .as_deref()
+        .map(parse_rfc3339_millis)
+        .transpose()?;
+    let created_before = request
+        .created_before
+        .as_deref()
+        .map(parse_rfc3339_millis)
+        .transpose()?;
+    let query = JobHistoryQuery {
+        statuses,
+        plugin_name: request.plugin_name,
+        namespace: request.namespace,
+        tag: request.tag,
+        created_after,
+        created_before,
+        sort,
+        ascending: request.ascending,
+        cursor: request.cursor,
+        limit: request.limit,
+    };
+
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let page = JobQueue::new(conn)
+        .query_job_history(&query)
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+
+    Ok(JobHistoryResponse {
+        jobs: page.jobs.into_iter().map(history_item).collect(),
+        next_cursor: page.next_cursor,
+    })
+}
+
 /// Get job status by ID.
 #[tauri::command]
 pub async fn job_status(job_id: String, state: State<'_, AppState>) -> CommandResult<JobItem> {
@@ -117,7 +280,7 @@ pub async fn job_status(job_id: String, state: State<'_, AppState>) -> CommandRe
     if let Some(client) = state.try_control_client() {
         let job = client
             .get_job(JobId::new(id))
-            .map_err(|e| CommandError::Internal(format!("Control API error: {}", e)))?
+            .map_err(CommandError::control)?
             .ok_or_else(|| CommandError::NotFound(format!("Job {} not found", job_id)))?;
 
         return Ok(JobItem {
@@ -132,6 +295,7 @@ pub async fn job_status(job_id: String, state: State<'_, AppState>) -> CommandRe
             finished_at: None,
             error_message: job.error_message,
             progress: None,
+            result: job.result,
         });
     }
 
@@ -159,17 +323,144 @@ pub async fn job_status(job_id: String, state: State<'_, AppState>) -> CommandRe
         finished_at: None,
         error_message: job.error_message,
         progress: None,
+        result: job.result,
     })
 }
 
-/// Cancel a running job.
+/// Get a pipeline run: its job DAG, per-stage timing and failures.
+#[tauri::command]
+pub async fn get_pipeline_run(
+    run_id: String,
+    state: State<'_, AppState>,
+) -> CommandResult<PipelineRun> {
+    let run = if let Some(client) = state.try_control_client() {
+        client
+            .get_pipeline_run(&run_id)
+            .map_err(CommandError::control)?
+    } else {
+        let conn = state
+            .open_readonly_connection()
+            .map_err(|e| CommandError::Database(e.to_string()))?;
+        JobQueue::new(conn)
+            .get_pipeline_run(&run_id)
+            .map_err(|e| CommandError::Database(e.to_string()))?
+    };
+    run.ok_or_else(|| CommandError::NotFound(format!("Pipeline run {} not found", run_id)))
+}
+
+/// Read the log recorded so far for a job, from `from_offset` on.
+#[tauri::command]
+pub async fn job_log_read(
+    job_id: String,
+    from_offset: Option<i64>,
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<JobLogChunkItem>> {
+    let id = parse_log_job_id(&job_id)?;
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    if !has_job_logs(&conn)? {
+        return Ok(Vec::new());
+    }
+    let chunks = JobQueue::new(conn)
+        .read_job_log(id, from_offset.unwrap_or(0), LOG_READ_LIMIT)
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    Ok(chunks
+        .into_iter()
+        .map(|chunk| JobLogChunkItem::new(id, chunk))
+        .collect())
+}
+
+/// Tail a job's log live.
 ///
-/// WS4-04: Uses Control API when sentinel is running, falls back to direct DB.
+/// Emits a `job-log-chunk` event to the calling window for every chunk at or
+/// after `from_offset`, and stops once the job has finished and its log is
+/// drained, or the window is closed.
+#[tauri::command]
+pub async fn job_log_follow(
+    job_id: String,
+    from_offset: Option<i64>,
+    window: tauri::Window,
+    state: State<'_, AppState>,
+) -> CommandResult<()> {
+    let id = parse_log_job_id(&job_id)?;
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    if !has_job_logs(&conn)? {
+        return Err(CommandError::NotFound(
+            "No job logs recorded yet (start the Sentinel)".to_string(),
+        ));
+    }
+    drop(conn);
+
+    let db_path = state.db_path.clone();
+    let from_offset = from_offset.unwrap_or(0);
+    std::thread::spawn(move || {
+        if let Err(e) = follow_job_log(&window, &db_path, id, from_offset) {
+            tracing::warn!("Stopped following log for job {}: {}", id, e);
+        }
+    });
+    Ok(())
+}
+
+fn follow_job_log(
+    window: &tauri::Window,
+    db_path: &str,
+    job_id: i64,
+    mut from_offset: i64,
+) -> anyhow::Result<()> {
+    // DbConnection is not Send, so the follower opens its own.
+    let queue = JobQueue::new(DbConnection::open_sqlite_readonly(std::path::Path::new(
+        db_path,
+    ))?);
+    let job = JobId::try_from(job_id)?;
+    loop {
+        let chunks = queue.read_job_log(job_id, from_offset, LOG_READ_LIMIT)?;
+        if chunks.is_empty() {
+            // The worker drains its log before CONCLUDE, so a finished job has
+            // nothing left to stream.
+            let finished = match queue.get_job(job)? {
+                Some(job) => job.status.is_terminal(),
+                None => true,
+            };
+            if finished {
+                return Ok(());
+            }
+            std::thread::sleep(LOG_FOLLOW_POLL_INTERVAL);
+            continue;
+        }
+        for chunk in chunks {
+            // Offsets are byte positions; anything after this chunk starts later.
+            from_offset = chunk.offset + 1;
+            window.emit(JOB_LOG_CHUNK_EVENT, JobLogChunkItem::new(job_id, chunk))?;
+        }
+    }
+}
+
+fn parse_log_job_id(job_id: &str) -> CommandResult<i64> {
+    job_id
+        .parse::<u64>()
+        .ok()
+        .and_then(|id| i64::try_from(id).ok())
+        .ok_or_else(|| CommandError::InvalidArgument("Invalid job ID".to_string()))
+}
+
+fn has_job_logs(conn: &DbConnection) -> CommandResult<bool> {
+    conn.table_exists("cf_job_logs")
+        .map_err(|e| CommandError::Database(e.to_string()))
+}
+
+/// Cancel a queued or running job.
+///
+/// WS4-04: Requires the Control API. Running jobs are marked cancelled and the
+/// owning worker is sent ABORT; it rolls back any staged sink output.
 #[tauri::command]
 pub async fn job_cancel(
     job_id: String,
     state: State<'_, AppState>,
 ) -> CommandResult<JobCancelResponse> {
+    state.ensure_writable("Cancelling jobs")?;
     // Record tape event
     let tape_ids = {
         let tape = state.tape().read().ok();
@@ -198,7 +489,7 @@ pub async fn job_cancel(
                         );
                     }
                 }
-                CommandError::Internal(format!("Control API error: {}", e))
+                CommandError::control(e)
             })?
     } else {
         if let Some((event_id, correlation_id)) = &tape_ids {
@@ -237,9 +528,112 @@ pub async fn job_cancel(
     Ok(JobCancelResponse {
         success: cancelled,
         status: status.to_string(),
+        message,
     })
 }
 
+/// Request to export one job output.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactExportRequest {
+    pub job_id: String,
+    pub output_name: String,
+    /// Destination file; must not exist
+    pub dest: String,
+    /// "original" (default) or "csv" (Parquet outputs only)
+    pub format: Option<String>,
+}
+
+/// A completed, verified artifact export.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactExportResult {
+    pub dest: String,
+    pub format: String,
+    pub rows: Option<u64>,
+    pub bytes: u64,
+    pub source_hash: String,
+    pub dest_hash: String,
+    /// False when the job's receipt had no file hash to check against
+    pub receipt_hash_checked: bool,
+}
+
+/// Export a job output to a local file after verifying it against the job's
+/// receipt (file hash, Parquet row count). The export is recorded in the
+/// audit log.
+#[tauri::command]
+pub async fn export_artifact(
+    request: ArtifactExportRequest,
+    state: State<'_, AppState>,
+) -> CommandResult<ArtifactExportResult> {
+    let job_id: i64 = request
+        .job_id
+        .parse()
+        .map_err(|_| CommandError::InvalidArgument("Invalid job ID".to_string()))?;
+    let format = match request.format.as_deref() {
+        Some(format) => format
+            .parse::<ExportFormat>()
+            .map_err(CommandError::InvalidArgument)?,
+        None => ExportFormat::default(),
+    };
+
+    let state_store =
+        StateStore::open(&state.db_url()).map_err(|e| CommandError::Database(e.to_string()))?;
+    let options = ExportOptions {
+        format,
+        actor: None,
+    };
+    let report = casparian_sentinel::export_artifact(
+        &state_store,
+        job_id,
+        &request.output_name,
+        Path::new(&request.dest),
+        &options,
+    )?;
+
+    Ok(ArtifactExportResult {
+        dest: report.dest.to_string_lossy().into_owned(),
+        format: report.format.as_str().to_string(),
+        rows: report.rows,
+        bytes: report.bytes,
+        source_hash: report.source_hash,
+        dest_hash: report.dest_hash,
+        receipt_hash_checked: report.receipt_hash_checked,
+    })
+}
+
+fn history_item(job: Job) -> JobItem {
+    JobItem {
+        id: job.id.as_u64().to_string(),
+        job_type: "run".to_string(),
+        status: status_to_string(job.status),
+        plugin_name: job.plugin_name,
+        plugin_version: job.parser_version,
+        input_dir: "-".to_string(),
+        created_at: job
+            .created_at
+            .map(millis_to_rfc3339)
+            .unwrap_or_else(|| "-".to_string()),
+        started_at: None,
+        finished_at: None,
+        error_message: job.error_message,
+        progress: None,
+        result: job.result,
+    }
+}
+
+fn parse_rfc3339_millis(raw: &str) -> CommandResult<i64> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|ts| ts.timestamp_millis())
+        .map_err(|e| CommandError::InvalidArgument(format!("Invalid date '{}': {}", raw, e)))
+}
+
+fn millis_to_rfc3339(millis: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(millis)
+        .map(|ts| ts.to_rfc3339())
+        .unwrap_or_else(|| "-".to_string())
+}
+
 fn parse_processing_status(raw: &str) -> Option<ProcessingStatus> {
     match raw.to_lowercase().as_str() {
         "queued" => Some(ProcessingStatus::Queued),
@@ -0,0 +1,139 @@
+//! Lineage graph commands.
+//!
+//! The sentinel records a source file → job → artifact hop on every CONCLUDE.
+//! These commands read those hops (read-only) and shape them into a graph the
+//! Deck can render.
+
+use crate::state::{AppState, CommandError, CommandResult};
+use casparian_sentinel::{LineageGraph, LineageNode, LineageStorage};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Graph node for the lineage view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineageNodeItem {
+    pub id: String,
+    /// "source", "job", or "artifact".
+    pub kind: String,
+    pub label: String,
+    pub path: Option<String>,
+    pub hash: Option<String>,
+    pub job_id: Option<String>,
+    pub plugin_name: Option<String>,
+    pub parser_version: Option<String>,
+    pub uri: Option<String>,
+    pub artifact_kind: Option<String>,
+    pub rows: Option<i64>,
+}
+
+impl From<LineageNode> for LineageNodeItem {
+    fn from(node: LineageNode) -> Self {
+        let empty = |id: String, kind: &str, label: String| Self {
+            id,
+            kind: kind.to_string(),
+            label,
+            path: None,
+            hash: None,
+            job_id: None,
+            plugin_name: None,
+            parser_version: None,
+            uri: None,
+            artifact_kind: None,
+            rows: None,
+        };
+        match node {
+            LineageNode::Source { id, path, hash } => {
+                let label = path
+                    .clone()
+                    .or_else(|| hash.clone())
+                    .unwrap_or_else(|| id.clone());
+                Self {
+                    path,
+                    hash,
+                    ..empty(id, "source", label)
+                }
+            }
+            LineageNode::Job {
+                id,
+                job_id,
+                plugin_name,
+                parser_version,
+            } => Self {
+                job_id: Some(job_id.to_string()),
+                plugin_name: Some(plugin_name.clone()),
+                parser_version,
+                ..empty(id, "job", format!("{} #{}", plugin_name, job_id))
+            },
+            LineageNode::Artifact {
+                id,
+                uri,
+                name,
+                artifact_kind,
+                rows,
+            } => Self {
+                uri: Some(uri),
+                artifact_kind: Some(artifact_kind),
+                rows,
+                ..empty(id, "artifact", name)
+            },
+        }
+    }
+}
+
+/// Directed edge (upstream → downstream).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineageEdgeItem {
+    pub from: String,
+    pub to: String,
+}
+
+/// Lineage graph response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineageGraphResponse {
+    pub nodes: Vec<LineageNodeItem>,
+    pub edges: Vec<LineageEdgeItem>,
+}
+
+impl From<LineageGraph> for LineageGraphResponse {
+    fn from(graph: LineageGraph) -> Self {
+        Self {
+            nodes: graph.nodes.into_iter().map(Into::into).collect(),
+            edges: graph
+                .edges
+                .into_iter()
+                .map(|edge| LineageEdgeItem {
+                    from: edge.from,
+                    to: edge.to,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Build a lineage graph upstream of an artifact URI or downstream of a
+/// source content hash. Exactly one of the two must be given.
+#[tauri::command]
+pub async fn lineage_graph(
+    artifact_uri: Option<String>,
+    source_hash: Option<String>,
+    state: State<'_, AppState>,
+) -> CommandResult<LineageGraphResponse> {
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let storage = LineageStorage::new(conn);
+    let records = match (artifact_uri.as_deref(), source_hash.as_deref()) {
+        (Some(uri), None) => storage.lineage_for_artifact(uri),
+        (None, Some(hash)) => storage.downstream_of(hash),
+        _ => {
+            return Err(CommandError::InvalidArgument(
+                "Provide exactly one of artifactUri or sourceHash".to_string(),
+            ))
+        }
+    }
+    .map_err(|e| CommandError::Database(e.to_string()))?;
+    Ok(LineageGraph::from_records(&records).into())
+}
@@ -3,8 +3,15 @@
 //! Each module provides commands for a specific feature area.
 
 pub mod approvals;
+pub mod audit;
+pub mod datasets;
+pub mod dead_letter;
 pub mod intent;
 pub mod jobs;
+pub mod lineage;
+pub mod plugins;
 pub mod query;
+pub mod schedules;
+pub mod schema_amendments;
 pub mod sessions;
 pub mod stats;
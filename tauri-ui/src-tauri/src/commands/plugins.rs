@@ -0,0 +1,261 @@
+//! Plugin registry commands.
+//!
+//! Views over `cf_plugin_manifest` for reviewing what a new plugin version
+//! changes before it is approved, rollback to an earlier version, and the
+//! subscriptions that chain one plugin's outputs into another plugin.
+
+use crate::state::{AppState, CommandError, CommandResult};
+use casparian::publish::{self, ListChange, PluginVersionDiff, SourceDiffLine, ValueChange};
+use casparian_sentinel::db::queue::TopicSubscription;
+use casparian_sentinel::{JobQueue, PluginRollbackInfo, SubscriptionInfo};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// One line of the source diff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceDiffLineItem {
+    /// "unchanged", "added", or "removed".
+    pub kind: String,
+    pub from_line: Option<usize>,
+    pub to_line: Option<usize>,
+    pub text: String,
+}
+
+impl From<SourceDiffLine> for SourceDiffLineItem {
+    fn from(line: SourceDiffLine) -> Self {
+        Self {
+            kind: line.kind.as_str().to_string(),
+            from_line: line.from_line,
+            to_line: line.to_line,
+            text: line.text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueChangeItem {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub changed: bool,
+}
+
+impl From<ValueChange> for ValueChangeItem {
+    fn from(change: ValueChange) -> Self {
+        Self {
+            from: change.from,
+            to: change.to,
+            changed: change.changed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListChangeItem {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: Vec<String>,
+}
+
+impl From<ListChange> for ListChangeItem {
+    fn from(change: ListChange) -> Self {
+        Self {
+            added: change.added,
+            removed: change.removed,
+            unchanged: change.unchanged,
+        }
+    }
+}
+
+/// Plugin version diff response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginVersionDiffResponse {
+    pub plugin_name: String,
+    pub from_version: String,
+    pub to_version: String,
+    pub source_changed: bool,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub source_diff: Vec<SourceDiffLineItem>,
+    pub env_hash: ValueChangeItem,
+    pub topics: ListChangeItem,
+    pub handler_methods: ListChangeItem,
+}
+
+impl From<PluginVersionDiff> for PluginVersionDiffResponse {
+    fn from(diff: PluginVersionDiff) -> Self {
+        Self {
+            plugin_name: diff.plugin_name,
+            from_version: diff.from_version,
+            to_version: diff.to_version,
+            source_changed: diff.source_changed,
+            lines_added: diff.lines_added,
+            lines_removed: diff.lines_removed,
+            source_diff: diff.source_diff.into_iter().map(Into::into).collect(),
+            env_hash: diff.env_hash.into(),
+            topics: diff.topics.into(),
+            handler_methods: diff.handler_methods.into(),
+        }
+    }
+}
+
+/// Diff two published versions of a plugin (`v1` -> `v2`): source lines,
+/// env_hash, detected topics, and handler methods.
+#[tauri::command]
+pub async fn diff_plugin_versions(
+    plugin_name: String,
+    v1: String,
+    v2: String,
+    state: State<'_, AppState>,
+) -> CommandResult<PluginVersionDiffResponse> {
+    if plugin_name.trim().is_empty() || v1.trim().is_empty() || v2.trim().is_empty() {
+        return Err(CommandError::InvalidArgument(
+            "pluginName, v1, and v2 are required".to_string(),
+        ));
+    }
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let diff = publish::diff_plugin_versions(&conn, &plugin_name, &v1, &v2)?;
+    Ok(diff.into())
+}
+
+/// Plugin rollback response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRollbackResponse {
+    pub plugin_name: String,
+    pub from_version: Option<String>,
+    pub to_version: String,
+    pub workers_notified: usize,
+}
+
+impl From<PluginRollbackInfo> for PluginRollbackResponse {
+    fn from(info: PluginRollbackInfo) -> Self {
+        Self {
+            plugin_name: info.plugin_name,
+            from_version: info.from_version,
+            to_version: info.to_version,
+            workers_notified: info.workers_notified,
+        }
+    }
+}
+
+/// Re-activate a previously active plugin version. Goes through the Sentinel
+/// so workers are notified and the rollback is audited.
+#[tauri::command]
+pub async fn rollback_plugin(
+    plugin_name: String,
+    target_version: String,
+    state: State<'_, AppState>,
+) -> CommandResult<PluginRollbackResponse> {
+    state.ensure_writable("Rolling back plugins")?;
+    if plugin_name.trim().is_empty() || target_version.trim().is_empty() {
+        return Err(CommandError::InvalidArgument(
+            "pluginName and targetVersion are required".to_string(),
+        ));
+    }
+    let client = state.try_control_client().ok_or_else(|| {
+        CommandError::Internal("Sentinel must be running to roll back plugins".to_string())
+    })?;
+    let actor = std::env::var("USER").ok();
+    let info = client
+        .rollback_plugin(&plugin_name, &target_version, actor.as_deref())
+        .map_err(CommandError::control)?;
+    Ok(info.into())
+}
+
+/// Subscription of a plugin to another plugin's output topic (one edge of
+/// the topology view).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginSubscriptionItem {
+    pub id: i64,
+    /// Subscribing (downstream) plugin
+    pub plugin_name: String,
+    /// `producer_plugin:output_name`
+    pub topic_name: String,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+impl From<TopicSubscription> for PluginSubscriptionItem {
+    fn from(subscription: TopicSubscription) -> Self {
+        Self {
+            id: subscription.id,
+            plugin_name: subscription.plugin_name,
+            topic_name: subscription.topic_name,
+            enabled: subscription.is_active,
+            created_at: subscription.created_at,
+        }
+    }
+}
+
+impl From<SubscriptionInfo> for PluginSubscriptionItem {
+    fn from(info: SubscriptionInfo) -> Self {
+        Self {
+            id: info.id,
+            plugin_name: info.plugin_name,
+            topic_name: info.topic_name,
+            enabled: info.enabled,
+            created_at: info.created_at,
+        }
+    }
+}
+
+/// List plugin subscriptions, enabled or not.
+#[tauri::command]
+pub async fn list_plugin_subscriptions(
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<PluginSubscriptionItem>> {
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let subscriptions = JobQueue::new(conn)
+        .list_subscriptions()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    Ok(subscriptions.into_iter().map(Into::into).collect())
+}
+
+/// Subscribe a plugin to an output topic (`producer_plugin:output_name`).
+/// Goes through the Sentinel, which rejects subscriptions that form a cycle.
+#[tauri::command]
+pub async fn subscribe_plugin(
+    plugin_name: String,
+    topic_name: String,
+    state: State<'_, AppState>,
+) -> CommandResult<PluginSubscriptionItem> {
+    state.ensure_writable("Subscribing plugins")?;
+    if plugin_name.trim().is_empty() || topic_name.trim().is_empty() {
+        return Err(CommandError::InvalidArgument(
+            "pluginName and topicName are required".to_string(),
+        ));
+    }
+    let client = state.try_control_client().ok_or_else(|| {
+        CommandError::Internal("Sentinel must be running to change subscriptions".to_string())
+    })?;
+    let info = client
+        .subscribe_plugin(&plugin_name, &topic_name)
+        .map_err(CommandError::control)?;
+    Ok(info.into())
+}
+
+/// Enable or disable one subscription edge without deleting it.
+#[tauri::command]
+pub async fn set_plugin_subscription_enabled(
+    subscription_id: i64,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> CommandResult<PluginSubscriptionItem> {
+    state.ensure_writable("Changing subscriptions")?;
+    let client = state.try_control_client().ok_or_else(|| {
+        CommandError::Internal("Sentinel must be running to change subscriptions".to_string())
+    })?;
+    let info = client
+        .set_subscription_enabled(subscription_id, enabled)
+        .map_err(CommandError::control)?;
+    Ok(info.into())
+}
@@ -55,13 +55,12 @@ pub async fn query_execute(
     // Record tape event before execution
     let tape_ids = {
         let tape = state.tape().read().ok();
+        // The SQL is hashed by the tape's redaction policy
         tape.as_ref().and_then(|t| {
-            // Hash the SQL query for privacy - don't record raw SQL
-            let sql_hash = t.redact(&request.sql);
             t.emit_command(
                 "QueryExecute",
                 serde_json::json!({
-                    "sql_hash": sql_hash,
+                    "sql": request.sql,
                     "limit": request.limit,
                 }),
             )
@@ -0,0 +1,197 @@
+//! Cron schedule commands.
+//!
+//! Schedules rescan a source or open a processing window for the files a
+//! tagging rule matched. Changes go through the Sentinel, which evaluates
+//! the schedules in its local time.
+
+use crate::state::{AppState, CommandError, CommandResult};
+use casparian_protocol::{ScheduleCatchUp, ScheduleTarget};
+use casparian_sentinel::db::queue::Schedule;
+use casparian_sentinel::{JobQueue, ScheduleInfo};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// A schedule as shown in the Deck.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleItem {
+    pub id: i64,
+    pub name: String,
+    pub cron: String,
+    /// "rescan_source" or "rule_window"
+    pub target_kind: String,
+    /// Source ID or tagging rule ID
+    pub target_id: String,
+    /// Rule windows only
+    pub window_minutes: Option<u32>,
+    /// "skip" or "run_once"
+    pub catch_up: String,
+    pub enabled: bool,
+    pub next_fire_at: Option<i64>,
+    pub last_fired_at: Option<i64>,
+    pub window_open_until: Option<i64>,
+    pub created_at: i64,
+}
+
+impl ScheduleItem {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        id: i64,
+        name: String,
+        cron: String,
+        target: ScheduleTarget,
+        catch_up: ScheduleCatchUp,
+        enabled: bool,
+        next_fire_at: Option<i64>,
+        last_fired_at: Option<i64>,
+        window_open_until: Option<i64>,
+        created_at: i64,
+    ) -> Self {
+        let window_minutes = match &target {
+            ScheduleTarget::RuleWindow { window_minutes, .. } => Some(*window_minutes),
+            ScheduleTarget::RescanSource { .. } => None,
+        };
+        Self {
+            id,
+            name,
+            cron,
+            target_kind: target.kind().to_string(),
+            target_id: target.target_id().to_string(),
+            window_minutes,
+            catch_up: catch_up.as_str().to_string(),
+            enabled,
+            next_fire_at,
+            last_fired_at,
+            window_open_until,
+            created_at,
+        }
+    }
+}
+
+impl From<Schedule> for ScheduleItem {
+    fn from(schedule: Schedule) -> Self {
+        Self::new(
+            schedule.id,
+            schedule.name,
+            schedule.cron,
+            schedule.target,
+            schedule.catch_up,
+            schedule.is_active,
+            schedule.next_fire_at,
+            schedule.last_fired_at,
+            schedule.window_open_until,
+            schedule.created_at,
+        )
+    }
+}
+
+impl From<ScheduleInfo> for ScheduleItem {
+    fn from(info: ScheduleInfo) -> Self {
+        Self::new(
+            info.id,
+            info.name,
+            info.cron,
+            info.target,
+            info.catch_up,
+            info.enabled,
+            info.next_fire_at,
+            info.last_fired_at,
+            info.window_open_until,
+            info.created_at,
+        )
+    }
+}
+
+/// New schedule. `windowMinutes` is required for rule windows.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateScheduleRequest {
+    pub name: String,
+    pub cron: String,
+    pub target_kind: String,
+    pub target_id: String,
+    pub window_minutes: Option<u32>,
+    pub catch_up: Option<String>,
+}
+
+/// List schedules, enabled or not.
+#[tauri::command]
+pub async fn schedule_list(state: State<'_, AppState>) -> CommandResult<Vec<ScheduleItem>> {
+    let conn = state
+        .open_readonly_connection()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let schedules = JobQueue::new(conn)
+        .list_schedules()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    Ok(schedules.into_iter().map(Into::into).collect())
+}
+
+/// Create a schedule (requires Sentinel).
+#[tauri::command]
+pub async fn schedule_create(
+    request: CreateScheduleRequest,
+    state: State<'_, AppState>,
+) -> CommandResult<ScheduleItem> {
+    state.ensure_writable("Creating schedules")?;
+    let target = match request.target_kind.as_str() {
+        "rescan_source" => ScheduleTarget::RescanSource {
+            source_id: request.target_id,
+        },
+        "rule_window" => ScheduleTarget::RuleWindow {
+            rule_id: request.target_id,
+            window_minutes: request.window_minutes.ok_or_else(|| {
+                CommandError::InvalidArgument(
+                    "windowMinutes is required for rule windows".to_string(),
+                )
+            })?,
+        },
+        other => {
+            return Err(CommandError::InvalidArgument(format!(
+                "Unknown target kind '{}': expected rescan_source or rule_window",
+                other
+            )))
+        }
+    };
+    let catch_up = match request.catch_up.as_deref() {
+        Some(value) => value
+            .parse::<ScheduleCatchUp>()
+            .map_err(CommandError::InvalidArgument)?,
+        None => ScheduleCatchUp::default(),
+    };
+    let client = state.try_control_client().ok_or_else(|| {
+        CommandError::Internal("Sentinel must be running to change schedules".to_string())
+    })?;
+    let info = client
+        .create_schedule(&request.name, &request.cron, target, catch_up)
+        .map_err(CommandError::control)?;
+    Ok(info.into())
+}
+
+/// Enable or disable a schedule (requires Sentinel).
+#[tauri::command]
+pub async fn schedule_set_enabled(
+    schedule_id: i64,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> CommandResult<ScheduleItem> {
+    state.ensure_writable("Changing schedules")?;
+    let client = state.try_control_client().ok_or_else(|| {
+        CommandError::Internal("Sentinel must be running to change schedules".to_string())
+    })?;
+    let info = client
+        .set_schedule_enabled(schedule_id, enabled)
+        .map_err(CommandError::control)?;
+    Ok(info.into())
+}
+
+/// Delete a schedule (requires Sentinel). Returns false if it did not exist.
+#[tauri::command]
+pub async fn schedule_delete(schedule_id: i64, state: State<'_, AppState>) -> CommandResult<bool> {
+    state.ensure_writable("Deleting schedules")?;
+    let client = state.try_control_client().ok_or_else(|| {
+        CommandError::Internal("Sentinel must be running to change schedules".to_string())
+    })?;
+    client
+        .delete_schedule(schedule_id)
+        .map_err(CommandError::control)
+}
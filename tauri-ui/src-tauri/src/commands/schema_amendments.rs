@@ -0,0 +1,483 @@
+//! Schema contract amendment commands.
+//!
+//! Review queue for `SchemaAmendmentProposal`s: list pending proposals,
+//! preview the `SchemaChange` diff with the sample values that triggered it,
+//! and approve or reject. Approval bumps the contract version, records the
+//! `AmendmentResult`, and queues backtests of every plugin version bound to
+//! the contract (requires Sentinel).
+//!
+//! Tape instrumentation:
+//! - Records amendment decisions with amendment_id
+
+use crate::state::{AppState, CommandError, CommandResult};
+use casparian_protocol::HttpJobType;
+use casparian_schema::amendment::{self, AmendmentAction, AmendmentError};
+use casparian_schema::approval::derive_scope_id;
+use casparian_schema::{
+    AmendmentId, LockedColumn, SampleValue, SchemaAmendmentProposal, SchemaChange, SchemaContract,
+    SchemaStorage,
+};
+use casparian_sentinel::db::audit;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Pending amendment for list view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaAmendmentItem {
+    pub id: String,
+    pub contract_id: String,
+    pub schema_name: String,
+    pub contract_version: Option<u32>,
+    pub reason: String,
+    pub change_count: usize,
+    pub affected_files: usize,
+    pub created_at: String,
+    pub status: String,
+}
+
+/// Column of the current or proposed schema.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaColumnItem {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub format: Option<String>,
+}
+
+impl From<&LockedColumn> for SchemaColumnItem {
+    fn from(column: &LockedColumn) -> Self {
+        Self {
+            name: column.name.clone(),
+            data_type: column.data_type.to_string(),
+            nullable: column.nullable,
+            format: column.format.clone(),
+        }
+    }
+}
+
+/// Sample value that triggered the proposal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleValueItem {
+    pub file_path: String,
+    pub row: Option<usize>,
+    pub column: String,
+    pub value: String,
+    pub issue: String,
+}
+
+impl From<&SampleValue> for SampleValueItem {
+    fn from(sample: &SampleValue) -> Self {
+        Self {
+            file_path: sample.file_path.clone(),
+            row: sample.row,
+            column: sample.column.clone(),
+            value: sample.value.clone(),
+            issue: sample.issue.clone(),
+        }
+    }
+}
+
+/// One change of the diff, with the samples for its column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaChangeItem {
+    /// "add_column", "change_type", ... (the `SchemaChange` variant).
+    pub kind: String,
+    pub column: Option<String>,
+    pub description: String,
+    pub samples: Vec<SampleValueItem>,
+}
+
+/// Full preview of one amendment proposal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaAmendmentPreview {
+    pub amendment: SchemaAmendmentItem,
+    pub current_columns: Vec<SchemaColumnItem>,
+    pub proposed_columns: Vec<SchemaColumnItem>,
+    pub changes: Vec<SchemaChangeItem>,
+    /// Samples whose column no change touches.
+    pub other_samples: Vec<SampleValueItem>,
+    pub system_notes: Option<String>,
+}
+
+/// Amendment decision request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaAmendmentDecision {
+    pub amendment_id: String,
+    pub decision: String, // "approve" or "reject"
+    pub reason: Option<String>,
+}
+
+/// Amendment decision response.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaAmendmentDecisionResponse {
+    pub status: String,
+    /// Version of the amended contract (approvals only).
+    pub contract_version: Option<u32>,
+    /// API job IDs of the backtests queued against the new contract.
+    pub backtest_job_ids: Vec<String>,
+    /// Why no backtests were queued, if none were.
+    pub backtest_note: Option<String>,
+}
+
+/// List pending schema amendment proposals, oldest first.
+#[tauri::command]
+pub async fn schema_amendment_list(
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<SchemaAmendmentItem>> {
+    let storage = state
+        .open_schema_storage()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let proposals = storage
+        .list_pending_amendments()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+
+    Ok(proposals
+        .iter()
+        .map(|proposal| amendment_item(&storage, proposal))
+        .collect())
+}
+
+/// Preview one amendment: current vs proposed columns and the change list,
+/// each change with the sample values seen for its column.
+#[tauri::command]
+pub async fn schema_amendment_preview(
+    amendment_id: String,
+    state: State<'_, AppState>,
+) -> CommandResult<SchemaAmendmentPreview> {
+    let amendment_id = parse_amendment_id(&amendment_id)?;
+    let storage = state
+        .open_schema_storage()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let proposal = storage
+        .get_amendment(&amendment_id)
+        .map_err(|e| CommandError::Database(e.to_string()))?
+        .ok_or_else(|| CommandError::NotFound(format!("Amendment {}", amendment_id)))?;
+
+    let changes: Vec<SchemaChangeItem> = proposal
+        .changes
+        .iter()
+        .map(|change| {
+            let column = change.column_name();
+            SchemaChangeItem {
+                kind: change_kind(change).to_string(),
+                column: column.map(str::to_string),
+                description: change.to_string(),
+                samples: proposal
+                    .sample_values
+                    .iter()
+                    .filter(|sample| Some(sample.column.as_str()) == column)
+                    .map(Into::into)
+                    .collect(),
+            }
+        })
+        .collect();
+    let other_samples = proposal
+        .sample_values
+        .iter()
+        .filter(|sample| {
+            !proposal
+                .changes
+                .iter()
+                .any(|change| change.column_name() == Some(sample.column.as_str()))
+        })
+        .map(Into::into)
+        .collect();
+
+    Ok(SchemaAmendmentPreview {
+        amendment: amendment_item(&storage, &proposal),
+        current_columns: proposal
+            .current_schema
+            .columns
+            .iter()
+            .map(Into::into)
+            .collect(),
+        proposed_columns: proposal
+            .proposed_schema
+            .columns
+            .iter()
+            .map(Into::into)
+            .collect(),
+        changes,
+        other_samples,
+        system_notes: proposal.system_notes.clone(),
+    })
+}
+
+/// Approve or reject an amendment. Approval saves the next contract version
+/// and queues backtests of the plugins bound to it.
+#[tauri::command]
+pub async fn schema_amendment_decide(
+    decision: SchemaAmendmentDecision,
+    state: State<'_, AppState>,
+) -> CommandResult<SchemaAmendmentDecisionResponse> {
+    state.ensure_writable("Deciding schema amendments")?;
+    let tape_ids = {
+        let tape = state.tape().read().ok();
+        tape.as_ref().and_then(|t| {
+            t.emit_command(
+                "SchemaAmendmentDecide",
+                serde_json::json!({
+                    "amendment_id": decision.amendment_id,
+                    "decision": decision.decision,
+                    "has_reason": decision.reason.is_some(),
+                }),
+            )
+        })
+    };
+
+    let result = decide(&decision, &state);
+    match (&result, tape_ids) {
+        (Ok(response), Some((event_id, correlation_id))) => {
+            if let Ok(tape) = state.tape().read() {
+                tape.emit_success(
+                    &correlation_id,
+                    &event_id,
+                    serde_json::json!({
+                        "status": "success",
+                        "amendment_id": decision.amendment_id,
+                        "decision": decision.decision,
+                        "backtests": response.backtest_job_ids.len(),
+                    }),
+                );
+            }
+        }
+        (Err(err), Some((event_id, correlation_id))) => {
+            if let Ok(tape) = state.tape().read() {
+                tape.emit_error(
+                    &correlation_id,
+                    &event_id,
+                    &err.to_string(),
+                    serde_json::json!({"status": "failed"}),
+                );
+            }
+        }
+        _ => {}
+    }
+    result
+}
+
+fn decide(
+    decision: &SchemaAmendmentDecision,
+    state: &AppState,
+) -> CommandResult<SchemaAmendmentDecisionResponse> {
+    let amendment_id = parse_amendment_id(&decision.amendment_id)?;
+    let action = match decision.decision.as_str() {
+        "approve" => AmendmentAction::ApproveAsProposed,
+        "reject" => AmendmentAction::Reject {
+            reason: decision.reason.clone().unwrap_or_default(),
+        },
+        _ => {
+            return Err(CommandError::InvalidArgument(
+                "Decision must be 'approve' or 'reject'".to_string(),
+            ))
+        }
+    };
+
+    let storage = state
+        .open_schema_storage()
+        .map_err(|e| CommandError::Database(e.to_string()))?;
+    let proposal = storage
+        .get_amendment(&amendment_id)
+        .map_err(|e| CommandError::Database(e.to_string()))?
+        .ok_or_else(|| CommandError::NotFound(format!("Amendment {}", amendment_id)))?;
+
+    let actor = audit::default_actor();
+    let result = amendment::process_amendment(&storage, &amendment_id, action, actor.as_str())
+        .map_err(|e| match e {
+            AmendmentError::AmendmentNotFound(_) => CommandError::NotFound(e.to_string()),
+            AmendmentError::AlreadyProcessed(_) => CommandError::InvalidArgument(e.to_string()),
+            other => CommandError::Internal(other.to_string()),
+        })?;
+
+    state.audit_log().record(
+        audit::entity::SCHEMA_AMENDMENT,
+        amendment_id.as_str(),
+        result.status.as_str(),
+        Some(actor.as_str()),
+        audit::snapshot(&proposal.current_schema),
+        result
+            .contract
+            .as_ref()
+            .and_then(|contract| audit::snapshot(&contract.schemas)),
+    );
+
+    let (backtest_job_ids, backtest_note) = match &result.contract {
+        Some(contract) => queue_backtests(state, &proposal, contract),
+        None => (Vec::new(), None),
+    };
+
+    Ok(SchemaAmendmentDecisionResponse {
+        status: result.status.as_str().to_string(),
+        contract_version: result.contract.as_ref().map(|contract| contract.version),
+        backtest_job_ids,
+        backtest_note,
+    })
+}
+
+/// Queue a backtest API job for every plugin version whose output is bound
+/// to `contract`, over the directory holding the files that triggered the
+/// amendment.
+fn queue_backtests(
+    state: &AppState,
+    proposal: &SchemaAmendmentProposal,
+    contract: &SchemaContract,
+) -> (Vec<String>, Option<String>) {
+    let plugins = match bound_plugin_versions(state, contract) {
+        Ok(plugins) if plugins.is_empty() => {
+            return (
+                Vec::new(),
+                Some("No plugin is bound to this contract".into()),
+            )
+        }
+        Ok(plugins) => plugins,
+        Err(e) => return (Vec::new(), Some(format!("Failed to find plugins: {}", e))),
+    };
+    let Some(input_dir) = sample_input_dir(&proposal.sample_values) else {
+        return (
+            Vec::new(),
+            Some("Proposal has no sample files to backtest against".into()),
+        );
+    };
+    let Some(client) = state.try_control_client() else {
+        return (
+            Vec::new(),
+            Some("Sentinel must be running to queue backtests".into()),
+        );
+    };
+
+    let spec_json = serde_json::json!({
+        "amendment_id": proposal.amendment_id.as_str(),
+        "contract_id": contract.contract_id.as_str(),
+        "contract_version": contract.version,
+    })
+    .to_string();
+    let input_dir = input_dir.to_string_lossy();
+
+    let mut job_ids = Vec::new();
+    let mut failures = Vec::new();
+    for (plugin_name, version) in plugins {
+        match client.create_api_job(
+            HttpJobType::Backtest,
+            &plugin_name,
+            Some(&version),
+            &input_dir,
+            None,
+            None,
+            Some(&spec_json),
+        ) {
+            Ok(job_id) => job_ids.push(job_id.to_string()),
+            Err(e) => failures.push(format!("{}@{}: {}", plugin_name, version, e)),
+        }
+    }
+    let note = (!failures.is_empty()).then(|| failures.join("; "));
+    (job_ids, note)
+}
+
+/// `(plugin_name, version)` pairs whose scope for the contract's output is
+/// the contract's scope.
+fn bound_plugin_versions(
+    state: &AppState,
+    contract: &SchemaContract,
+) -> anyhow::Result<Vec<(String, String)>> {
+    let conn = state.open_readonly_connection()?;
+    if !conn.table_exists("cf_plugin_manifest")? {
+        return Ok(Vec::new());
+    }
+    let rows = conn.query_all(
+        "SELECT DISTINCT plugin_name, version FROM cf_plugin_manifest",
+        &[],
+    )?;
+
+    let mut plugins = Vec::new();
+    for row in &rows {
+        let plugin_name: String = row.get_by_name("plugin_name")?;
+        let version: String = row.get_by_name("version")?;
+        let bound = contract.schemas.iter().any(|schema| {
+            derive_scope_id(&plugin_name, &version, &schema.name) == contract.scope_id
+        });
+        if bound {
+            plugins.push((plugin_name, version));
+        }
+    }
+    Ok(plugins)
+}
+
+/// Deepest directory containing every sample file.
+fn sample_input_dir(samples: &[SampleValue]) -> Option<PathBuf> {
+    let mut dirs = samples
+        .iter()
+        .filter_map(|sample| Path::new(&sample.file_path).parent());
+    let mut common = dirs.next()?.to_path_buf();
+    for dir in dirs {
+        while !dir.starts_with(&common) {
+            if !common.pop() {
+                return None;
+            }
+        }
+    }
+    (!common.as_os_str().is_empty()).then_some(common)
+}
+
+fn amendment_item(
+    storage: &SchemaStorage,
+    proposal: &SchemaAmendmentProposal,
+) -> SchemaAmendmentItem {
+    let contract_version = storage
+        .get_contract(&proposal.contract_id)
+        .ok()
+        .flatten()
+        .map(|contract| contract.version);
+    SchemaAmendmentItem {
+        id: proposal.amendment_id.to_string(),
+        contract_id: proposal.contract_id.to_string(),
+        schema_name: proposal.current_schema.name.clone(),
+        contract_version,
+        reason: proposal.reason.to_string(),
+        change_count: proposal.changes.len(),
+        affected_files: proposal.affected_files,
+        created_at: proposal.created_at.to_string(),
+        status: proposal.status.as_str().to_string(),
+    }
+}
+
+fn change_kind(change: &SchemaChange) -> &'static str {
+    match change {
+        SchemaChange::AddColumn { .. } => "add_column",
+        SchemaChange::RemoveColumn { .. } => "remove_column",
+        SchemaChange::ChangeType { .. } => "change_type",
+        SchemaChange::ChangeNullability { .. } => "change_nullability",
+        SchemaChange::AddDefaultValue { .. } => "add_default_value",
+        SchemaChange::RemoveDefaultValue { .. } => "remove_default_value",
+        SchemaChange::ChangeFormat { .. } => "change_format",
+        SchemaChange::RenameColumn { .. } => "rename_column",
+        SchemaChange::ReorderColumns { .. } => "reorder_columns",
+    }
+}
+
+fn parse_amendment_id(raw: &str) -> CommandResult<AmendmentId> {
+    AmendmentId::parse(raw)
+        .map_err(|_| CommandError::InvalidArgument(format!("Invalid amendment ID: {}", raw)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_input_dir_is_common_parent() {
+        let samples = vec![
+            SampleValue::new("/data/in/a/1.csv", "id", "x", "bad"),
+            SampleValue::new("/data/in/b/2.csv", "id", "y", "bad"),
+        ];
+        assert_eq!(sample_input_dir(&samples), Some(PathBuf::from("/data/in")));
+        assert_eq!(sample_input_dir(&[]), None);
+    }
+}
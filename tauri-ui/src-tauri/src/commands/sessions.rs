@@ -150,16 +150,16 @@ pub async fn session_create(
     request: CreateSessionRequest,
     state: State<'_, AppState>,
 ) -> CommandResult<CreateSessionResponse> {
-    // Record tape event - hash input_dir for privacy
+    state.ensure_writable("Creating sessions")?;
+    // Record tape event - input_dir is hashed by the tape's redaction policy
     let tape_ids = {
         let tape = state.tape().read().ok();
         tape.as_ref().and_then(|t| {
-            let input_dir_hash = request.input_dir.as_ref().map(|d| t.redact(d));
             t.emit_command(
                 "SessionCreate",
                 serde_json::json!({
                     "intent": request.intent,
-                    "input_dir_hash": input_dir_hash,
+                    "input_dir": request.input_dir,
                 }),
             )
         })
@@ -295,6 +295,7 @@ pub async fn session_advance(
     request: AdvanceSessionRequest,
     state: State<'_, AppState>,
 ) -> CommandResult<AdvanceSessionResponse> {
+    state.ensure_writable("Advancing sessions")?;
     // Record tape event
     let tape_ids = {
         let tape = state.tape().read().ok();
@@ -374,6 +375,7 @@ pub async fn session_cancel(
     session_id: String,
     state: State<'_, AppState>,
 ) -> CommandResult<AdvanceSessionResponse> {
+    state.ensure_writable("Cancelling sessions")?;
     let storage = state
         .open_session_storage()
         .map_err(|e| CommandError::Database(e.to_string()))?;
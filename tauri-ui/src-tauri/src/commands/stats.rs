@@ -1,9 +1,14 @@
 //! Dashboard statistics commands.
 //!
-//! These commands provide aggregate statistics for the home dashboard.
+//! These commands provide aggregate statistics for the home dashboard, and
+//! the per-plugin / per-tag breakdown, worker health and connection
+//! diagnostics used by the topology view.
 
 use crate::state::{AppState, CommandError, CommandResult};
+use casparian_protocol::endpoint::{diagnose_endpoint, EndpointDiagnosis};
+use casparian_protocol::metrics::{BreakdownEntry, PluginMetricsReport, LATENCY_BUCKETS_MS};
 use casparian_protocol::{metrics, HttpJobStatus};
+use casparian_sentinel::control::{SystemPulse, WorkerHealthInfo, WorkerHealthReport};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -142,6 +147,214 @@ pub async fn dashboard_stats(state: State<'_, AppState>) -> CommandResult<Dashbo
     })
 }
 
+/// Job metrics for one plugin or tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsBreakdownItem {
+    pub key: String,
+    pub total: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub retried: u64,
+    pub rejected: u64,
+    pub aborted: u64,
+    pub rows_written: u64,
+    pub failure_rate: f64,
+    pub latency_mean_ms: Option<f64>,
+    /// Non-cumulative counts per `latency_bucket_bounds_ms` bucket, overflow last.
+    pub latency_buckets: Vec<u64>,
+}
+
+impl From<BreakdownEntry> for MetricsBreakdownItem {
+    fn from(entry: BreakdownEntry) -> Self {
+        let jobs = entry.jobs;
+        Self {
+            key: entry.key,
+            total: jobs.total(),
+            completed: jobs.completed,
+            failed: jobs.failed,
+            retried: jobs.retried,
+            rejected: jobs.rejected,
+            aborted: jobs.aborted,
+            rows_written: jobs.rows_written,
+            failure_rate: entry.failure_rate,
+            latency_mean_ms: jobs.latency.mean_ms(),
+            latency_buckets: jobs.latency.buckets.to_vec(),
+        }
+    }
+}
+
+/// Per-plugin, per-tag and per-namespace job metrics since the sentinel started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginMetricsResponse {
+    pub plugins: Vec<MetricsBreakdownItem>,
+    pub tags: Vec<MetricsBreakdownItem>,
+    pub namespaces: Vec<MetricsBreakdownItem>,
+    pub latency_bucket_bounds_ms: Vec<u64>,
+}
+
+impl From<PluginMetricsReport> for PluginMetricsResponse {
+    fn from(report: PluginMetricsReport) -> Self {
+        Self {
+            plugins: report.plugins.into_iter().map(Into::into).collect(),
+            tags: report.tags.into_iter().map(Into::into).collect(),
+            namespaces: report.namespaces.into_iter().map(Into::into).collect(),
+            latency_bucket_bounds_ms: LATENCY_BUCKETS_MS.to_vec(),
+        }
+    }
+}
+
+/// Get per-plugin, per-tag and per-namespace job counts, failure rates, rows,
+/// and latency.
+///
+/// The breakdown lives in the sentinel's memory, so this requires the Control API.
+#[tauri::command]
+pub async fn get_plugin_metrics(
+    state: State<'_, AppState>,
+) -> CommandResult<PluginMetricsResponse> {
+    let client = state.try_control_client().ok_or_else(|| {
+        CommandError::Internal("Sentinel must be running to read plugin metrics".to_string())
+    })?;
+    let report = client.get_plugin_metrics().map_err(CommandError::control)?;
+    Ok(report.into())
+}
+
+/// Per-state worker counts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemPulseResponse {
+    pub healthy: u64,
+    pub degraded: u64,
+    pub unreachable: u64,
+    /// Evicted since the sentinel started
+    pub evicted: u64,
+    pub flapping: u64,
+    /// Local worker pool (all 0 when the Sentinel runs without one)
+    pub pool_size: u64,
+    pub pool_target: u64,
+    pub pool_max: u64,
+    pub pool_restarts: u64,
+}
+
+impl From<SystemPulse> for SystemPulseResponse {
+    fn from(pulse: SystemPulse) -> Self {
+        Self {
+            healthy: pulse.workers_healthy,
+            degraded: pulse.workers_degraded,
+            unreachable: pulse.workers_unreachable,
+            evicted: pulse.workers_evicted,
+            flapping: pulse.workers_flapping,
+            pool_size: pulse.pool_size,
+            pool_target: pulse.pool_target,
+            pool_max: pulse.pool_max,
+            pool_restarts: pulse.pool_restarts,
+        }
+    }
+}
+
+/// Health of one connected worker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerHealthItem {
+    pub worker_id: String,
+    /// HEALTHY, DEGRADED, UNREACHABLE
+    pub health: String,
+    pub flapping: bool,
+    pub status: String,
+    pub seconds_since_seen: f64,
+    pub seconds_in_state: f64,
+    pub current_job_id: Option<String>,
+}
+
+impl From<WorkerHealthInfo> for WorkerHealthItem {
+    fn from(info: WorkerHealthInfo) -> Self {
+        Self {
+            worker_id: info.worker_id,
+            health: info.health.as_str().to_string(),
+            flapping: info.flapping,
+            status: info.status.as_str().to_string(),
+            seconds_since_seen: info.seconds_since_seen,
+            seconds_in_state: info.seconds_in_state,
+            current_job_id: info.current_job_id.map(|id| id.to_string()),
+        }
+    }
+}
+
+/// Worker health for the topology view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerHealthResponse {
+    pub pulse: SystemPulseResponse,
+    pub workers: Vec<WorkerHealthItem>,
+}
+
+impl From<WorkerHealthReport> for WorkerHealthResponse {
+    fn from(report: WorkerHealthReport) -> Self {
+        Self {
+            pulse: report.pulse.into(),
+            workers: report.workers.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Get the health state of each connected worker and per-state counts.
+///
+/// Worker health lives in the sentinel's memory, so this requires the Control API.
+#[tauri::command]
+pub async fn get_worker_health(state: State<'_, AppState>) -> CommandResult<WorkerHealthResponse> {
+    let client = state.try_control_client().ok_or_else(|| {
+        CommandError::Internal("Sentinel must be running to read worker health".to_string())
+    })?;
+    let report = client.get_worker_health().map_err(CommandError::control)?;
+    Ok(report.into())
+}
+
+/// Probe of one Sentinel address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointStatus {
+    pub addr: String,
+    /// None when the transport cannot be probed
+    pub reachable: Option<bool>,
+    pub detail: String,
+}
+
+impl From<EndpointDiagnosis> for EndpointStatus {
+    fn from(diagnosis: EndpointDiagnosis) -> Self {
+        Self {
+            addr: diagnosis.addr,
+            reachable: diagnosis.reachable,
+            detail: diagnosis.detail,
+        }
+    }
+}
+
+/// Why the Deck or workers cannot reach the Sentinel, if they cannot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionDiagnostics {
+    /// Control API address the Deck uses
+    pub control: EndpointStatus,
+    /// Address workers connect to
+    pub workers: EndpointStatus,
+    /// The Control API answered a ping
+    pub control_api_ok: bool,
+}
+
+/// Probe the Control API and worker addresses, explaining missing or stale
+/// sockets and refused ports. Works without a running Sentinel.
+#[tauri::command]
+pub async fn get_connection_diagnostics(
+    state: State<'_, AppState>,
+) -> CommandResult<ConnectionDiagnostics> {
+    Ok(ConnectionDiagnostics {
+        control: diagnose_endpoint(&state.control_addr()).into(),
+        workers: diagnose_endpoint(&state.sentinel_addr()).into(),
+        control_api_ok: state.try_control_client().is_some(),
+    })
+}
+
 fn sum_quarantine_rows(completed: &[casparian_protocol::Job]) -> u64 {
     completed
         .iter()
@@ -81,14 +81,48 @@ fn main() {
             commands::approvals::approval_list,
             commands::approvals::approval_decide,
             commands::approvals::approval_stats,
+            // Audit log commands
+            commands::audit::audit_list,
             // Query commands
             commands::query::query_execute,
             // Job commands
             commands::jobs::job_list,
+            commands::jobs::job_history,
             commands::jobs::job_status,
+            commands::jobs::get_pipeline_run,
             commands::jobs::job_cancel,
+            commands::jobs::job_log_read,
+            commands::jobs::job_log_follow,
+            commands::jobs::export_artifact,
+            // Dead-letter queue commands
+            commands::dead_letter::dlq_list,
+            commands::dead_letter::dlq_requeue,
+            commands::dead_letter::dlq_purge,
+            // Lineage commands
+            commands::lineage::lineage_graph,
+            // Dataset catalog commands
+            commands::datasets::dataset_list,
+            commands::datasets::dataset_describe,
+            // Plugin commands
+            commands::plugins::diff_plugin_versions,
+            commands::plugins::rollback_plugin,
+            commands::plugins::list_plugin_subscriptions,
+            commands::plugins::subscribe_plugin,
+            commands::plugins::set_plugin_subscription_enabled,
+            // Schedule commands
+            commands::schedules::schedule_list,
+            commands::schedules::schedule_create,
+            commands::schedules::schedule_set_enabled,
+            commands::schedules::schedule_delete,
+            // Schema amendment commands
+            commands::schema_amendments::schema_amendment_list,
+            commands::schema_amendments::schema_amendment_preview,
+            commands::schema_amendments::schema_amendment_decide,
             // Stats commands
             commands::stats::dashboard_stats,
+            commands::stats::get_plugin_metrics,
+            commands::stats::get_worker_health,
+            commands::stats::get_connection_diagnostics,
             // Intent pipeline commands - Selection
             commands::intent::casp_select_propose,
             commands::intent::casp_select_approve,
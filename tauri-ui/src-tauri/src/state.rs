@@ -5,7 +5,9 @@
 
 use anyhow::{Context, Result};
 use casparian_db::DbConnection;
-use casparian_sentinel::{ApiStorage, ControlClient};
+use casparian_schema::SchemaStorage;
+use casparian_sentinel::db::AuditLog;
+use casparian_sentinel::{ApiStorage, ControlClient, ReadOnlyMode};
 
 use crate::session_storage::SessionStorage;
 use crate::tape::{create_disabled_tape, SharedTapeState};
@@ -13,6 +15,8 @@ use std::time::Duration;
 
 /// Default Control API address when sentinel is running.
 const DEFAULT_CONTROL_ADDR: &str = casparian_protocol::defaults::DEFAULT_CONTROL_ADDR;
+/// Default worker address of the sentinel.
+const DEFAULT_SENTINEL_BIND_ADDR: &str = casparian_protocol::defaults::DEFAULT_SENTINEL_BIND_ADDR;
 
 /// Application state shared across Tauri commands.
 ///
@@ -23,6 +27,9 @@ pub struct AppState {
     pub db_path: String,
     /// Tape recording state (shared across commands).
     tape: SharedTapeState,
+    /// Audit mode (`CASPARIAN_READ_ONLY=1`): mutating commands fail with
+    /// `CommandError::ReadOnlyMode`.
+    read_only: bool,
 }
 
 impl AppState {
@@ -30,7 +37,29 @@ impl AppState {
     pub fn new() -> Result<Self> {
         let db_path = Self::default_db_path()?;
         let tape = create_disabled_tape();
-        Ok(Self { db_path, tape })
+        let read_only = std::env::var("CASPARIAN_READ_ONLY").is_ok_and(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        });
+        Ok(Self {
+            db_path,
+            tape,
+            read_only,
+        })
+    }
+
+    /// Refuse `action` in read-only mode. Called first by every mutating
+    /// command, whether it writes directly or through the Sentinel.
+    pub fn ensure_writable(&self, action: &str) -> CommandResult<()> {
+        if self.read_only {
+            return Err(CommandError::ReadOnlyMode(format!(
+                "{} is disabled while the Deck is read-only",
+                action
+            )));
+        }
+        Ok(())
     }
 
     /// Get the default database path.
@@ -59,12 +88,26 @@ impl AppState {
     /// Creates a new connection - caller is responsible for cleanup.
     pub fn open_api_storage(&self) -> Result<ApiStorage> {
         let storage = ApiStorage::open(&self.db_url()).context("Failed to open API storage")?;
-        storage
-            .init_schema()
-            .context("Failed to initialize API schema")?;
+        if !self.read_only {
+            storage
+                .init_schema()
+                .context("Failed to initialize API schema")?;
+        }
         Ok(storage)
     }
 
+    /// Open schema contract storage (contracts and amendment proposals).
+    pub fn open_schema_storage(&self) -> Result<SchemaStorage> {
+        let conn = DbConnection::open_sqlite(std::path::Path::new(&self.db_path))
+            .context("Failed to open schema storage")?;
+        SchemaStorage::new(conn).map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Audit log shared with the Sentinel and CLI (next to the database).
+    pub fn audit_log(&self) -> AuditLog {
+        AuditLog::for_sqlite(std::path::Path::new(&self.db_path))
+    }
+
     /// Open a read-only connection for query operations.
     pub fn open_readonly_connection(&self) -> Result<DbConnection> {
         DbConnection::open_sqlite_readonly(std::path::Path::new(&self.db_path))
@@ -73,6 +116,12 @@ impl AppState {
 
     /// Open a read-write connection for mutation operations (dev-only).
     pub fn open_rw_connection(&self) -> Result<DbConnection> {
+        if self.read_only {
+            return Err(ReadOnlyMode {
+                message: "direct DB writes are disabled".to_string(),
+            }
+            .into());
+        }
         if std::env::var("CASPARIAN_DEV_ALLOW_DIRECT_DB_WRITE")
             .ok()
             .as_deref()
@@ -92,9 +141,11 @@ impl AppState {
     pub fn open_session_storage(&self) -> Result<SessionStorage> {
         let storage =
             SessionStorage::open(&self.db_url()).context("Failed to open session storage")?;
-        storage
-            .init_schema()
-            .context("Failed to initialize session schema")?;
+        if !self.read_only {
+            storage
+                .init_schema()
+                .context("Failed to initialize session schema")?;
+        }
         Ok(storage)
     }
 
@@ -103,13 +154,23 @@ impl AppState {
         &self.tape
     }
 
+    /// Control API address (`CASPARIAN_CONTROL_ADDR`).
+    pub fn control_addr(&self) -> String {
+        std::env::var("CASPARIAN_CONTROL_ADDR").unwrap_or_else(|_| DEFAULT_CONTROL_ADDR.into())
+    }
+
+    /// Address workers connect to (`CASPARIAN_SENTINEL_ADDR`).
+    pub fn sentinel_addr(&self) -> String {
+        std::env::var("CASPARIAN_SENTINEL_ADDR")
+            .unwrap_or_else(|_| DEFAULT_SENTINEL_BIND_ADDR.into())
+    }
+
     /// Attempt to connect to the control API (sentinel mutation authority).
     pub fn try_control_client(&self) -> Option<ControlClient> {
         if std::env::var("CASPARIAN_CONTROL_DISABLED").is_ok() {
             return None;
         }
-        let addr =
-            std::env::var("CASPARIAN_CONTROL_ADDR").unwrap_or_else(|_| DEFAULT_CONTROL_ADDR.into());
+        let addr = self.control_addr();
         let timeout = Duration::from_millis(500);
         let client = ControlClient::connect_with_timeout(&addr, timeout).ok()?;
         match client.ping() {
@@ -132,11 +193,26 @@ pub enum CommandError {
     InvalidArgument(String),
     #[error("Internal error: {0}")]
     Internal(String),
+    #[error("Read-only mode: {0}")]
+    ReadOnlyMode(String),
 }
 
 impl From<anyhow::Error> for CommandError {
     fn from(err: anyhow::Error) -> Self {
-        CommandError::Internal(err.to_string())
+        match err.downcast_ref::<ReadOnlyMode>() {
+            Some(read_only) => CommandError::ReadOnlyMode(read_only.message.clone()),
+            None => CommandError::Internal(err.to_string()),
+        }
+    }
+}
+
+impl CommandError {
+    /// Map a Control API failure, keeping a Sentinel read-only refusal typed.
+    pub fn control(err: anyhow::Error) -> Self {
+        match err.downcast_ref::<ReadOnlyMode>() {
+            Some(read_only) => CommandError::ReadOnlyMode(read_only.message.clone()),
+            None => CommandError::Internal(format!("Control API error: {}", err)),
+        }
     }
 }
 
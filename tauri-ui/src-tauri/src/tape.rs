@@ -5,12 +5,13 @@
 //!
 //! # Privacy
 //!
-//! Sensitive data is automatically redacted:
+//! Sensitive data is automatically redacted by the writer's
+//! `RedactionPolicy` (see `redaction_policy`):
 //! - SQL queries are hashed (not stored in plaintext)
 //! - File paths are hashed
 //! - Query results are NOT recorded (only row counts)
 
-use casparian_tape::{EventName, TapeWriter};
+use casparian_tape::{EventName, RedactionMode, RedactionPolicy, TapeWriter, TapeWriterConfig};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use tracing::{debug, warn};
@@ -33,7 +34,8 @@ impl TapeState {
     ///
     /// The tape file will be created at the specified path.
     pub fn enabled(tape_path: &Path) -> Result<Self, casparian_tape::TapeError> {
-        let writer = TapeWriter::new(tape_path)?;
+        let config = TapeWriterConfig::default().with_redaction(redaction_policy());
+        let writer = TapeWriter::with_config(tape_path, config)?;
         Ok(Self {
             writer: Some(writer),
         })
@@ -44,16 +46,6 @@ impl TapeState {
         self.writer.is_some()
     }
 
-    /// Hash a string for redaction.
-    ///
-    /// Returns a 16-character hex hash. If tape is disabled, returns a placeholder.
-    pub fn redact(&self, s: &str) -> String {
-        match &self.writer {
-            Some(writer) => writer.redact_string(s),
-            None => "[no-tape]".to_string(),
-        }
-    }
-
     /// Emit a UICommand event.
     ///
     /// Returns the event_id and correlation_id for linking with the response.
@@ -132,6 +124,14 @@ pub type SharedTapeState = Arc<RwLock<TapeState>>;
 static_assertions::assert_impl_all!(TapeState: Send, Sync);
 static_assertions::assert_impl_all!(SharedTapeState: Send, Sync);
 
+/// Redaction applied to every command payload: SQL and input directories
+/// are hashed wherever they appear.
+pub fn redaction_policy() -> RedactionPolicy {
+    RedactionPolicy::new()
+        .with_rule("**.sql", RedactionMode::Hash)
+        .with_rule("**.input_dir", RedactionMode::Hash)
+}
+
 /// Create a shared tape state that is disabled (no recording).
 pub fn create_disabled_tape() -> SharedTapeState {
     Arc::new(RwLock::new(TapeState::disabled()))
@@ -221,7 +221,6 @@ mod tests {
     fn test_tape_state_disabled() {
         let state = TapeState::disabled();
         assert!(!state.is_enabled());
-        assert_eq!(state.redact("sensitive"), "[no-tape]");
 
         // emit_command returns None when disabled
         let result = state.emit_command("Test", serde_json::json!({}));
@@ -236,18 +235,21 @@ mod tests {
         let state = TapeState::enabled(&tape_path).unwrap();
         assert!(state.is_enabled());
 
-        // Redaction produces 16-char hash
-        let hash = state.redact("sensitive data");
+        // Sensitive fields are hashed by the writer's policy
+        state
+            .emit_command(
+                "QueryExecute",
+                serde_json::json!({"sql": "SELECT secret FROM t", "limit": 10}),
+            )
+            .unwrap();
+        let content = std::fs::read_to_string(&tape_path).unwrap();
+        assert!(!content.contains("SELECT secret"));
+        let command: casparian_tape::EnvelopeV1 =
+            serde_json::from_str(content.lines().nth(1).unwrap()).unwrap();
+        let hash = command.payload["sql"].as_str().unwrap();
         assert_eq!(hash.len(), 16);
         assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
-
-        // Same value produces same hash
-        let hash2 = state.redact("sensitive data");
-        assert_eq!(hash, hash2);
-
-        // Different value produces different hash
-        let hash3 = state.redact("other data");
-        assert_ne!(hash, hash3);
+        assert_eq!(command.payload["limit"], 10);
     }
 
     #[test]
@@ -5,6 +5,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/tauri'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type {
   SessionSummary,
   SessionStatus,
@@ -14,11 +15,37 @@ import type {
   ApprovalStats,
   ApprovalDecision,
   ApprovalDecisionResponse,
+  AuditListRequest,
+  AuditItem,
   QueryRequest,
   QueryResult,
   JobItem,
+  JobHistoryRequest,
+  JobHistoryResponse,
+  JobLogChunk,
   JobCancelResponse,
+  ArtifactExportRequest,
+  ArtifactExportResult,
+  PipelineRun,
+  DeadLetterItem,
+  DeadLetterRequeueResponse,
+  DeadLetterPurgeResponse,
   DashboardStats,
+  PluginMetrics,
+  WorkerHealth,
+  ConnectionDiagnostics,
+  LineageGraph,
+  DatasetItem,
+  DatasetDetail,
+  PluginVersionDiff,
+  PluginRollback,
+  PluginSubscription,
+  ScheduleItem,
+  CreateScheduleRequest,
+  SchemaAmendmentItem,
+  SchemaAmendmentPreview,
+  SchemaAmendmentDecision,
+  SchemaAmendmentDecisionResponse,
 } from './types'
 
 // =============================================================================
@@ -75,6 +102,17 @@ export async function approvalStats(): Promise<ApprovalStats> {
   return invoke<ApprovalStats>('approval_stats')
 }
 
+// =============================================================================
+// Audit Commands
+// =============================================================================
+
+/**
+ * List control-plane audit entries (most recent last).
+ */
+export async function auditList(request: AuditListRequest = {}): Promise<AuditItem[]> {
+  return invoke<AuditItem[]>('audit_list', { request })
+}
+
 // =============================================================================
 // Query Commands
 // =============================================================================
@@ -91,13 +129,23 @@ export async function queryExecute(request: QueryRequest): Promise<QueryResult>
 // =============================================================================
 
 /**
- * List all jobs, optionally filtered by status.
+ * List all jobs, optionally filtered by status and plugin namespace.
  */
 export async function jobList(
   status?: string,
-  limit?: number
+  limit?: number,
+  namespace?: string
 ): Promise<JobItem[]> {
-  return invoke<JobItem[]>('job_list', { status, limit })
+  return invoke<JobItem[]>('job_list', { status, namespace, limit })
+}
+
+/**
+ * Page through job history with filters and server-side sort.
+ */
+export async function jobHistory(
+  request: JobHistoryRequest
+): Promise<JobHistoryResponse> {
+  return invoke<JobHistoryResponse>('job_history', { request })
 }
 
 /**
@@ -107,6 +155,13 @@ export async function jobStatus(jobId: string): Promise<JobItem> {
   return invoke<JobItem>('job_status', { jobId })
 }
 
+/**
+ * Get a pipeline run: its job DAG, per-stage timing and failures.
+ */
+export async function getPipelineRun(runId: string): Promise<PipelineRun> {
+  return invoke<PipelineRun>('get_pipeline_run', { runId })
+}
+
 /**
  * Cancel a running job.
  */
@@ -114,6 +169,234 @@ export async function jobCancel(jobId: string): Promise<JobCancelResponse> {
   return invoke<JobCancelResponse>('job_cancel', { jobId })
 }
 
+/**
+ * Read the log recorded so far for a job.
+ */
+export async function jobLogRead(
+  jobId: string,
+  fromOffset?: number
+): Promise<JobLogChunk[]> {
+  return invoke<JobLogChunk[]>('job_log_read', { jobId, fromOffset })
+}
+
+/**
+ * Tail a running job's log. Chunks arrive as `job-log-chunk` events
+ * (see onJobLogChunk) until the job finishes.
+ */
+export async function jobLogFollow(
+  jobId: string,
+  fromOffset?: number
+): Promise<void> {
+  return invoke<void>('job_log_follow', { jobId, fromOffset })
+}
+
+/**
+ * Subscribe to live log chunks emitted by jobLogFollow.
+ */
+export async function onJobLogChunk(
+  handler: (chunk: JobLogChunk) => void
+): Promise<UnlistenFn> {
+  return listen<JobLogChunk>('job-log-chunk', (event) => handler(event.payload))
+}
+
+/**
+ * Export a job output to a local file, verified against the job's receipt.
+ */
+export async function exportArtifact(
+  request: ArtifactExportRequest
+): Promise<ArtifactExportResult> {
+  return invoke<ArtifactExportResult>('export_artifact', { request })
+}
+
+// =============================================================================
+// Dead-Letter Queue Commands
+// =============================================================================
+
+/**
+ * List dead-letter entries, optionally filtered by plugin.
+ */
+export async function dlqList(
+  pluginName?: string,
+  limit?: number
+): Promise<DeadLetterItem[]> {
+  return invoke<DeadLetterItem[]>('dlq_list', { pluginName, limit })
+}
+
+/**
+ * Requeue a dead-letter entry as a new job.
+ */
+export async function dlqRequeue(deadLetterId: number): Promise<DeadLetterRequeueResponse> {
+  return invoke<DeadLetterRequeueResponse>('dlq_requeue', { deadLetterId })
+}
+
+/**
+ * Purge one dead-letter entry, or all entries matching the filters.
+ */
+export async function dlqPurge(options: {
+  deadLetterId?: number
+  movedBefore?: string
+  pluginName?: string
+}): Promise<DeadLetterPurgeResponse> {
+  return invoke<DeadLetterPurgeResponse>('dlq_purge', options)
+}
+
+// =============================================================================
+// Lineage Commands
+// =============================================================================
+
+/**
+ * Lineage graph upstream of an artifact URI, or downstream of a source hash.
+ */
+export async function lineageGraph(
+  query: { artifactUri: string } | { sourceHash: string }
+): Promise<LineageGraph> {
+  return invoke<LineageGraph>('lineage_graph', query)
+}
+
+// =============================================================================
+// Dataset Catalog Commands
+// =============================================================================
+
+/**
+ * List committed datasets, most recently updated first. `query` matches
+ * dataset, plugin, table, sink and column names.
+ */
+export async function datasetList(options?: {
+  query?: string
+  pluginName?: string
+  limit?: number
+}): Promise<DatasetItem[]> {
+  return invoke<DatasetItem[]>('dataset_list', options ?? {})
+}
+
+/**
+ * Describe one dataset: schema, row count, partitions, sink and source root.
+ */
+export async function datasetDescribe(name: string): Promise<DatasetDetail> {
+  return invoke<DatasetDetail>('dataset_describe', { name })
+}
+
+// =============================================================================
+// Plugin Commands
+// =============================================================================
+
+/**
+ * Diff two published versions of a plugin (v1 -> v2).
+ */
+export async function diffPluginVersions(
+  pluginName: string,
+  v1: string,
+  v2: string
+): Promise<PluginVersionDiff> {
+  return invoke<PluginVersionDiff>('diff_plugin_versions', { pluginName, v1, v2 })
+}
+
+/**
+ * Re-activate a previously active plugin version (requires Sentinel).
+ */
+export async function rollbackPlugin(
+  pluginName: string,
+  targetVersion: string
+): Promise<PluginRollback> {
+  return invoke<PluginRollback>('rollback_plugin', { pluginName, targetVersion })
+}
+
+/**
+ * List plugin subscriptions to output topics (topology edges).
+ */
+export async function listPluginSubscriptions(): Promise<PluginSubscription[]> {
+  return invoke<PluginSubscription[]>('list_plugin_subscriptions')
+}
+
+/**
+ * Subscribe a plugin to an output topic, "producer_plugin:output_name"
+ * (requires Sentinel).
+ */
+export async function subscribePlugin(
+  pluginName: string,
+  topicName: string
+): Promise<PluginSubscription> {
+  return invoke<PluginSubscription>('subscribe_plugin', { pluginName, topicName })
+}
+
+/**
+ * Enable or disable one subscription edge (requires Sentinel).
+ */
+export async function setPluginSubscriptionEnabled(
+  subscriptionId: number,
+  enabled: boolean
+): Promise<PluginSubscription> {
+  return invoke<PluginSubscription>('set_plugin_subscription_enabled', {
+    subscriptionId,
+    enabled,
+  })
+}
+
+// =============================================================================
+// Schedule Commands
+// =============================================================================
+
+/**
+ * List cron schedules (source rescans and rule windows).
+ */
+export async function scheduleList(): Promise<ScheduleItem[]> {
+  return invoke<ScheduleItem[]>('schedule_list')
+}
+
+/**
+ * Create a schedule (requires Sentinel).
+ */
+export async function scheduleCreate(request: CreateScheduleRequest): Promise<ScheduleItem> {
+  return invoke<ScheduleItem>('schedule_create', { request })
+}
+
+/**
+ * Enable or disable a schedule (requires Sentinel).
+ */
+export async function scheduleSetEnabled(
+  scheduleId: number,
+  enabled: boolean
+): Promise<ScheduleItem> {
+  return invoke<ScheduleItem>('schedule_set_enabled', { scheduleId, enabled })
+}
+
+/**
+ * Delete a schedule (requires Sentinel).
+ */
+export async function scheduleDelete(scheduleId: number): Promise<boolean> {
+  return invoke<boolean>('schedule_delete', { scheduleId })
+}
+
+// =============================================================================
+// Schema Amendment Commands
+// =============================================================================
+
+/**
+ * List pending schema amendment proposals.
+ */
+export async function schemaAmendmentList(): Promise<SchemaAmendmentItem[]> {
+  return invoke<SchemaAmendmentItem[]>('schema_amendment_list')
+}
+
+/**
+ * Preview an amendment's schema diff with the sample values behind it.
+ */
+export async function schemaAmendmentPreview(
+  amendmentId: string
+): Promise<SchemaAmendmentPreview> {
+  return invoke<SchemaAmendmentPreview>('schema_amendment_preview', { amendmentId })
+}
+
+/**
+ * Approve or reject an amendment. Approval bumps the contract version and
+ * queues plugin backtests (requires Sentinel).
+ */
+export async function schemaAmendmentDecide(
+  decision: SchemaAmendmentDecision
+): Promise<SchemaAmendmentDecisionResponse> {
+  return invoke<SchemaAmendmentDecisionResponse>('schema_amendment_decide', { decision })
+}
+
 // =============================================================================
 // Dashboard Commands
 // =============================================================================
@@ -125,6 +408,24 @@ export async function dashboardStats(): Promise<DashboardStats> {
   return invoke<DashboardStats>('dashboard_stats')
 }
 
+/**
+ * Get per-plugin and per-tag job metrics (requires a running sentinel).
+ */
+export async function getPluginMetrics(): Promise<PluginMetrics> {
+  return invoke<PluginMetrics>('get_plugin_metrics')
+}
+
+/**
+ * Get per-worker health and per-state worker counts (requires a running sentinel).
+ */
+export async function getWorkerHealth(): Promise<WorkerHealth> {
+  return invoke<WorkerHealth>('get_worker_health')
+}
+
+export async function getConnectionDiagnostics(): Promise<ConnectionDiagnostics> {
+  return invoke<ConnectionDiagnostics>('get_connection_diagnostics')
+}
+
 // =============================================================================
 // Intent Pipeline Commands - Selection
 // =============================================================================
@@ -219,6 +219,13 @@ export function replayJobList(context: ReplayContext): JobItem[] {
             message: null,
           }
         : null,
+      // Tapes record output names but not sink URIs.
+      result: summary.outputs.length
+        ? {
+            artifacts: summary.outputs.map((name) => ({ kind: 'OUTPUT' as const, name })),
+            counters: summary.rows !== null ? { rows: summary.rows } : {},
+          }
+        : null,
     })
   }
 
@@ -81,6 +81,33 @@ export interface ApprovalDecisionResponse {
   status: string
 }
 
+// =============================================================================
+// Audit Types
+// =============================================================================
+
+export interface AuditListRequest {
+  /** routing_rule | plugin | approval | config */
+  entityKind?: string
+  entityId?: string
+  actor?: string
+  /** RFC 3339, inclusive */
+  since?: string
+  /** RFC 3339, exclusive */
+  until?: string
+  limit?: number
+}
+
+export interface AuditItem {
+  seq: number
+  timestamp: string
+  entityKind: string
+  entityId: string
+  action: string
+  actor: string
+  before: Record<string, unknown> | null
+  after: Record<string, unknown> | null
+}
+
 // =============================================================================
 // Query Types
 // =============================================================================
@@ -113,6 +140,70 @@ export interface JobItem {
   finishedAt: string | null
   errorMessage: string | null
   progress: JobProgress | null
+  /** Structured result of a concluded job; null while running or for old rows */
+  result: JobResultSummary | null
+}
+
+export type ArtifactKind = 'OUTPUT' | 'QUARANTINE' | 'LOG' | 'OTHER'
+
+export interface ResultArtifact {
+  kind: ArtifactKind
+  name: string
+  uri?: string
+  rows?: number
+}
+
+export interface JobResultSummary {
+  artifacts: ResultArtifact[]
+  /** Receipt metrics, e.g. rows, quarantine_rows, rows.<output> */
+  counters: Record<string, number>
+  warnings?: string[]
+}
+
+/** Jobs chained from one source file, as returned by `get_pipeline_run` */
+export interface PipelineRun {
+  run_id: string
+  /** queued | running | noop | failed | completed */
+  status: string
+  /** Unix millis */
+  started_at: number | null
+  completed_at: number | null
+  jobs: PipelineRunJob[]
+  stages: PipelineRunStage[]
+}
+
+export interface PipelineRunJob {
+  job_id: number
+  plugin_name: string
+  file_id: number
+  /** Job whose output this job reads (null for a root job) */
+  upstream_job_id: number | null
+  depth: number
+  status: string
+  queued_at: number
+  started_at: number | null
+  finished_at: number | null
+  error_message: string | null
+}
+
+export interface PipelineRunStage {
+  depth: number
+  plugin_name: string
+  jobs: number
+  active: number
+  completed: number
+  failed: number
+  started_at: number | null
+  /** Set once every job of the stage is terminal */
+  finished_at: number | null
+  duration_ms: number | null
+  failures?: PipelineRunFailure[]
+}
+
+export interface PipelineRunFailure {
+  job_id: number
+  file_id: number
+  error_message: string | null
 }
 
 export interface JobProgress {
@@ -125,6 +216,299 @@ export interface JobProgress {
 export interface JobCancelResponse {
   success: boolean
   status: string
+  message: string
+}
+
+export interface JobLogChunk {
+  jobId: string
+  /** Byte offset of the chunk in the worker's log file */
+  offset: number
+  data: string
+}
+
+export type ArtifactExportFormat = 'original' | 'csv'
+
+export interface ArtifactExportRequest {
+  jobId: string
+  outputName: string
+  /** Destination file; must not exist */
+  dest: string
+  /** Defaults to 'original'; 'csv' converts Parquet outputs */
+  format?: ArtifactExportFormat
+}
+
+export interface ArtifactExportResult {
+  dest: string
+  format: ArtifactExportFormat
+  rows: number | null
+  bytes: number
+  sourceHash: string
+  destHash: string
+  /** False when the job's receipt had no file hash to check against */
+  receiptHashChecked: boolean
+}
+
+export type JobHistorySort = 'created_at' | 'finished_at' | 'plugin_name' | 'status'
+
+export interface JobHistoryRequest {
+  statuses?: string[]
+  pluginName?: string
+  /** Only jobs of plugins deployed into this namespace */
+  namespace?: string
+  tag?: string
+  /** RFC 3339, inclusive */
+  createdAfter?: string
+  /** RFC 3339, exclusive */
+  createdBefore?: string
+  sort?: JobHistorySort
+  ascending?: boolean
+  /** `nextCursor` from the previous page */
+  cursor?: string
+  limit?: number
+}
+
+export interface JobHistoryResponse {
+  jobs: JobItem[]
+  nextCursor: string | null
+}
+
+// =============================================================================
+// Dead-Letter Queue Types
+// =============================================================================
+
+export interface DeadLetterItem {
+  id: number
+  originalJobId: string
+  pluginName: string
+  inputFile: string | null
+  errorMessage: string | null
+  reason: string | null
+  retryCount: number
+  movedAt: string
+  parserVersion: string | null
+  pipelineRunId: string | null
+  lastWorker: string | null
+  lastClaimTime: string | null
+}
+
+export interface DeadLetterRequeueResponse {
+  success: boolean
+  message: string
+  newJobId: string | null
+}
+
+export interface DeadLetterPurgeResponse {
+  purged: number
+}
+
+// =============================================================================
+// Lineage Types
+// =============================================================================
+
+export type LineageNodeKind = 'source' | 'job' | 'artifact'
+
+export interface LineageNode {
+  id: string
+  kind: LineageNodeKind
+  label: string
+  path: string | null
+  hash: string | null
+  jobId: string | null
+  pluginName: string | null
+  parserVersion: string | null
+  uri: string | null
+  artifactKind: string | null
+  rows: number | null
+}
+
+export interface LineageEdge {
+  from: string
+  to: string
+}
+
+export interface LineageGraph {
+  nodes: LineageNode[]
+  edges: LineageEdge[]
+}
+
+// =============================================================================
+// Dataset Catalog Types
+// =============================================================================
+
+export interface DatasetItem {
+  name: string
+  pluginName: string
+  sinkUri: string
+  tableName: string | null
+  rowCount: number
+  partitionColumns: string[]
+  lastUpdated: string
+}
+
+export interface DatasetColumn {
+  name: string
+  dataType: string
+  nullable: boolean
+}
+
+export interface DatasetDetail extends DatasetItem {
+  sinkMode: string
+  columns: DatasetColumn[]
+  schemaHash: string | null
+  lineageRoot: string | null
+  jobCount: number
+  lastJobId: string
+  createdAt: string
+}
+
+// =============================================================================
+// Plugin Types
+// =============================================================================
+
+export type SourceDiffLineKind = 'unchanged' | 'added' | 'removed'
+
+export interface SourceDiffLine {
+  kind: SourceDiffLineKind
+  fromLine: number | null
+  toLine: number | null
+  text: string
+}
+
+export interface ValueChange {
+  from: string | null
+  to: string | null
+  changed: boolean
+}
+
+export interface ListChange {
+  added: string[]
+  removed: string[]
+  unchanged: string[]
+}
+
+export interface PluginVersionDiff {
+  pluginName: string
+  fromVersion: string
+  toVersion: string
+  sourceChanged: boolean
+  linesAdded: number
+  linesRemoved: number
+  sourceDiff: SourceDiffLine[]
+  envHash: ValueChange
+  topics: ListChange
+  handlerMethods: ListChange
+}
+
+export interface PluginRollback {
+  pluginName: string
+  fromVersion: string | null
+  toVersion: string
+  workersNotified: number
+}
+
+export interface PluginSubscription {
+  id: number
+  /** Subscribing (downstream) plugin */
+  pluginName: string
+  /** producer_plugin:output_name */
+  topicName: string
+  enabled: boolean
+  createdAt: number
+}
+
+// =============================================================================
+// Schedule Types
+// =============================================================================
+
+export type ScheduleTargetKind = 'rescan_source' | 'rule_window'
+
+export type ScheduleCatchUp = 'skip' | 'run_once'
+
+export interface ScheduleItem {
+  id: number
+  name: string
+  /** Five-field cron expression, in the Sentinel's local time */
+  cron: string
+  targetKind: ScheduleTargetKind
+  /** Source ID or tagging rule ID */
+  targetId: string
+  /** Rule windows only */
+  windowMinutes: number | null
+  catchUp: ScheduleCatchUp
+  enabled: boolean
+  nextFireAt: number | null
+  lastFiredAt: number | null
+  windowOpenUntil: number | null
+  createdAt: number
+}
+
+export interface CreateScheduleRequest {
+  name: string
+  cron: string
+  targetKind: ScheduleTargetKind
+  targetId: string
+  windowMinutes?: number
+  catchUp?: ScheduleCatchUp
+}
+
+// =============================================================================
+// Schema Amendment Types
+// =============================================================================
+
+export interface SchemaAmendmentItem {
+  id: string
+  contractId: string
+  schemaName: string
+  contractVersion: number | null
+  reason: string
+  changeCount: number
+  affectedFiles: number
+  createdAt: string
+  status: string
+}
+
+export interface SchemaColumnItem {
+  name: string
+  dataType: string
+  nullable: boolean
+  format: string | null
+}
+
+export interface SampleValueItem {
+  filePath: string
+  row: number | null
+  column: string
+  value: string
+  issue: string
+}
+
+export interface SchemaChangeItem {
+  kind: string
+  column: string | null
+  description: string
+  samples: SampleValueItem[]
+}
+
+export interface SchemaAmendmentPreview {
+  amendment: SchemaAmendmentItem
+  currentColumns: SchemaColumnItem[]
+  proposedColumns: SchemaColumnItem[]
+  changes: SchemaChangeItem[]
+  otherSamples: SampleValueItem[]
+  systemNotes: string | null
+}
+
+export interface SchemaAmendmentDecision {
+  amendmentId: string
+  decision: 'approve' | 'reject'
+  reason?: string
+}
+
+export interface SchemaAmendmentDecisionResponse {
+  status: string
+  contractVersion: number | null
+  backtestJobIds: string[]
+  backtestNote: string | null
 }
 
 // =============================================================================
@@ -151,6 +535,72 @@ export interface ActiveRun {
   progress: number
 }
 
+export interface MetricsBreakdownItem {
+  key: string
+  total: number
+  completed: number
+  failed: number
+  retried: number
+  rejected: number
+  aborted: number
+  rowsWritten: number
+  failureRate: number
+  latencyMeanMs: number | null
+  /** Non-cumulative counts per latencyBucketBoundsMs bucket, overflow last. */
+  latencyBuckets: number[]
+}
+
+export interface PluginMetrics {
+  plugins: MetricsBreakdownItem[]
+  tags: MetricsBreakdownItem[]
+  namespaces: MetricsBreakdownItem[]
+  latencyBucketBoundsMs: number[]
+}
+
+export type WorkerHealthState = 'HEALTHY' | 'DEGRADED' | 'UNREACHABLE'
+
+export interface SystemPulse {
+  healthy: number
+  degraded: number
+  unreachable: number
+  /** Evicted since the sentinel started */
+  evicted: number
+  flapping: number
+  /** Local worker pool (all 0 without one) */
+  poolSize: number
+  poolTarget: number
+  poolMax: number
+  poolRestarts: number
+}
+
+export interface WorkerHealthItem {
+  workerId: string
+  health: WorkerHealthState
+  flapping: boolean
+  status: string
+  secondsSinceSeen: number
+  secondsInState: number
+  currentJobId: string | null
+}
+
+export interface WorkerHealth {
+  pulse: SystemPulse
+  workers: WorkerHealthItem[]
+}
+
+export interface EndpointStatus {
+  addr: string
+  /** null when the transport cannot be probed */
+  reachable: boolean | null
+  detail: string
+}
+
+export interface ConnectionDiagnostics {
+  control: EndpointStatus
+  workers: EndpointStatus
+  controlApiOk: boolean
+}
+
 // =============================================================================
 // Intent Pipeline Types (for future use)
 // =============================================================================